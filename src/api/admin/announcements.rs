@@ -0,0 +1,46 @@
+//! Announcement banner settings
+
+use axum::{extract::State, Json};
+use serde::Serialize;
+
+use crate::api::middleware::{ApiError, AppState, AuthenticatedUser};
+use crate::services::announcement::{self, Announcement, AnnouncementError};
+
+/// Every stored announcement, including scheduled and expired ones
+#[derive(Debug, Serialize)]
+pub struct AnnouncementsResponse {
+    pub announcements: Vec<Announcement>,
+}
+
+/// GET /api/v1/admin/announcements - List announcement banners
+///
+/// Requires admin authentication.
+pub async fn get_announcements(
+    State(state): State<AppState>,
+    _user: AuthenticatedUser,
+) -> Json<AnnouncementsResponse> {
+    Json(AnnouncementsResponse {
+        announcements: announcement::load(&state.settings_service).await,
+    })
+}
+
+/// PUT /api/v1/admin/announcements - Replace the announcement banners
+///
+/// Body: `[{"id": "maintenance", "message": "Down for maintenance at 22:00",
+/// "style": "warning", "starts_at": null, "ends_at": "2026-01-01T00:00:00Z",
+/// "dismissible": true}]`. Missing ids are generated.
+/// Requires admin authentication.
+pub async fn update_announcements(
+    State(state): State<AppState>,
+    user: AuthenticatedUser,
+    Json(input): Json<Vec<Announcement>>,
+) -> Result<Json<AnnouncementsResponse>, ApiError> {
+    let announcements = announcement::save(&state.settings_service, input)
+        .await
+        .map_err(|e| match e {
+            AnnouncementError::Validation(msg) => ApiError::validation_error(msg),
+            AnnouncementError::Internal(msg) => ApiError::internal_error(msg),
+        })?;
+    tracing::info!(user_id = user.0.id, "Announcements changed");
+    Ok(Json(AnnouncementsResponse { announcements }))
+}
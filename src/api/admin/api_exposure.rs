@@ -0,0 +1,71 @@
+//! Public endpoint exposure settings
+
+use axum::{extract::State, Json};
+use serde::Serialize;
+
+use crate::api::middleware::{ApiError, AppState, AuthenticatedUser};
+use crate::services::api_exposure::{
+    ApiExposure, ApiExposureError, UpdateApiExposureInput, ENDPOINT_GROUPS,
+};
+
+/// One row of the exposure matrix
+#[derive(Debug, Serialize)]
+pub struct EndpointGroupStatus {
+    pub id: &'static str,
+    pub description: &'static str,
+    pub enabled: bool,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ApiExposureResponse {
+    pub read_only: bool,
+    pub endpoints: Vec<EndpointGroupStatus>,
+}
+
+impl From<&ApiExposure> for ApiExposureResponse {
+    fn from(exposure: &ApiExposure) -> Self {
+        Self {
+            read_only: exposure.read_only,
+            endpoints: ENDPOINT_GROUPS
+                .iter()
+                .map(|group| EndpointGroupStatus {
+                    id: group.id,
+                    description: group.description,
+                    enabled: exposure.endpoints.get(group.id).copied().unwrap_or(true),
+                })
+                .collect(),
+        }
+    }
+}
+
+/// GET /api/v1/admin/api-exposure - Get which public endpoints are exposed
+///
+/// Requires admin authentication.
+pub async fn get_api_exposure(
+    State(state): State<AppState>,
+    _user: AuthenticatedUser,
+) -> Json<ApiExposureResponse> {
+    Json(ApiExposureResponse::from(
+        state.api_exposure.current().await.as_ref(),
+    ))
+}
+
+/// PUT /api/v1/admin/api-exposure - Switch public endpoint groups and read-only mode
+///
+/// Body: `{"read_only": true, "endpoints": {"search": false}}`; omitted
+/// groups keep their setting. Requires admin authentication.
+pub async fn update_api_exposure(
+    State(state): State<AppState>,
+    _user: AuthenticatedUser,
+    Json(input): Json<UpdateApiExposureInput>,
+) -> Result<Json<ApiExposureResponse>, ApiError> {
+    let exposure = state
+        .api_exposure
+        .update(input)
+        .await
+        .map_err(|e| match e {
+            ApiExposureError::Validation(msg) => ApiError::validation_error(msg),
+            ApiExposureError::Internal(msg) => ApiError::internal_error(msg),
+        })?;
+    Ok(Json(ApiExposureResponse::from(exposure.as_ref())))
+}
@@ -1,49 +1,101 @@
 //! Admin backup & restore API endpoints
+//!
+//! Backups are stored in `backup.path`; see [`crate::services::backup`].
 
 use axum::{
-    extract::{Multipart, State},
+    body::{Body, Bytes},
+    extract::{Multipart, Path, State},
     http::{header, StatusCode},
-    response::IntoResponse,
+    response::{IntoResponse, Response},
     Json,
 };
 use serde_json::json;
+use tokio::io::AsyncReadExt;
 
 use crate::api::middleware::AppState;
 use crate::services::backup;
 
-/// GET /api/v1/admin/backup — download full backup as ZIP
+/// GET /api/v1/admin/backup — create a backup and download it as ZIP
+///
+/// The archive is also kept in the backup directory like any other backup.
 pub async fn download_backup(State(state): State<AppState>) -> impl IntoResponse {
-    let upload_dir = state.upload_config.path.clone();
-    match backup::create_backup(&state.pool, &upload_dir).await {
-        Ok(zip_bytes) => {
-            let filename = format!(
-                "noteva-backup-{}.zip",
-                chrono::Utc::now().format("%Y%m%d-%H%M%S")
-            );
-            (
-                StatusCode::OK,
-                [
-                    (header::CONTENT_TYPE, "application/zip".to_string()),
-                    (
-                        header::CONTENT_DISPOSITION,
-                        format!("attachment; filename=\"{}\"", filename),
-                    ),
-                ],
-                zip_bytes,
+    match state.backup_service.create().await {
+        Ok(archive) => send_archive(&state, &archive.name).await,
+        Err(e) => {
+            tracing::error!(error = %e, "backup failed");
+            error_response(
+                StatusCode::INTERNAL_SERVER_ERROR,
+                format!("Backup failed: {}", e),
             )
-                .into_response()
         }
+    }
+}
+
+/// GET /api/v1/admin/backup/archives — list stored backups, newest first
+pub async fn list_archives(State(state): State<AppState>) -> impl IntoResponse {
+    match state.backup_service.list() {
+        Ok(archives) => Json(json!({ "archives": archives })).into_response(),
+        Err(e) => {
+            tracing::error!(error = %e, "failed to list backups");
+            error_response(
+                StatusCode::INTERNAL_SERVER_ERROR,
+                format!("Failed to list backups: {}", e),
+            )
+        }
+    }
+}
+
+/// POST /api/v1/admin/backup/archives — create a stored backup
+pub async fn create_archive(State(state): State<AppState>) -> impl IntoResponse {
+    match state.backup_service.create().await {
+        Ok(archive) => (StatusCode::CREATED, Json(json!(archive))).into_response(),
         Err(e) => {
             tracing::error!(error = %e, "backup failed");
-            (
+            error_response(
                 StatusCode::INTERNAL_SERVER_ERROR,
-                Json(json!({ "error": { "message": format!("Backup failed: {}", e) } })),
+                format!("Backup failed: {}", e),
             )
-                .into_response()
         }
     }
 }
 
+/// GET /api/v1/admin/backup/archives/{name} — download a stored backup
+pub async fn download_archive(
+    State(state): State<AppState>,
+    Path(name): Path<String>,
+) -> impl IntoResponse {
+    send_archive(&state, &name).await
+}
+
+/// DELETE /api/v1/admin/backup/archives/{name} — delete a stored backup
+pub async fn delete_archive(
+    State(state): State<AppState>,
+    Path(name): Path<String>,
+) -> impl IntoResponse {
+    match state.backup_service.delete(&name) {
+        Ok(true) => StatusCode::NO_CONTENT.into_response(),
+        Ok(false) => error_response(StatusCode::NOT_FOUND, "Backup not found"),
+        Err(e) => {
+            tracing::error!(error = %e, "failed to delete backup");
+            error_response(
+                StatusCode::INTERNAL_SERVER_ERROR,
+                format!("Failed to delete backup: {}", e),
+            )
+        }
+    }
+}
+
+/// POST /api/v1/admin/backup/archives/{name}/restore — restore a stored backup
+pub async fn restore_archive(
+    State(state): State<AppState>,
+    Path(name): Path<String>,
+) -> impl IntoResponse {
+    if state.backup_service.archive_path(&name).is_none() {
+        return error_response(StatusCode::NOT_FOUND, "Backup not found");
+    }
+    restore_response(state.backup_service.restore_archive(&name).await)
+}
+
 /// POST /api/v1/admin/backup/restore — upload ZIP to restore
 pub async fn restore_backup_endpoint(
     State(state): State<AppState>,
@@ -63,10 +115,10 @@ pub async fn restore_backup_endpoint(
                 }
                 Err(e) => {
                     tracing::error!(error = %e, "restore: failed to read file");
-                    return (
+                    return error_response(
                         StatusCode::BAD_REQUEST,
-                        Json(json!({ "error": { "message": format!("Failed to read file: {}", e) } })),
-                    ).into_response();
+                        format!("Failed to read file: {}", e),
+                    );
                 }
             }
         }
@@ -76,11 +128,7 @@ pub async fn restore_backup_endpoint(
         Some(d) => d,
         None => {
             tracing::error!("restore: no file uploaded");
-            return (
-                StatusCode::BAD_REQUEST,
-                Json(json!({ "error": { "message": "No file uploaded" } })),
-            )
-                .into_response();
+            return error_response(StatusCode::BAD_REQUEST, "No file uploaded");
         }
     };
 
@@ -88,8 +136,11 @@ pub async fn restore_backup_endpoint(
         size = zip_data.len(),
         "restore: starting restore process..."
     );
-    let upload_dir = state.upload_config.path.clone();
-    match backup::restore_backup(&state.pool, &upload_dir, &zip_data).await {
+    restore_response(state.backup_service.restore(&zip_data).await)
+}
+
+fn restore_response(result: anyhow::Result<backup::BackupManifest>) -> Response {
+    match result {
         Ok(manifest) => {
             tracing::info!("restore: completed successfully!");
             (
@@ -109,15 +160,70 @@ pub async fn restore_backup_endpoint(
         }
         Err(e) => {
             tracing::error!(error = %e, "restore failed");
-            (
+            error_response(
                 StatusCode::INTERNAL_SERVER_ERROR,
-                Json(json!({ "error": { "message": format!("Restore failed: {}", e) } })),
+                format!("Restore failed: {}", e),
             )
-                .into_response()
         }
     }
 }
 
+/// Stream a stored archive from disk
+async fn send_archive(state: &AppState, name: &str) -> Response {
+    let Some(path) = state.backup_service.archive_path(name) else {
+        return error_response(StatusCode::NOT_FOUND, "Backup not found");
+    };
+    let file = match tokio::fs::File::open(&path).await {
+        Ok(file) => file,
+        Err(e) => {
+            tracing::error!(error = %e, "failed to open backup");
+            return error_response(
+                StatusCode::INTERNAL_SERVER_ERROR,
+                format!("Failed to open backup: {}", e),
+            );
+        }
+    };
+    let size = file.metadata().await.map(|m| m.len()).ok();
+    let stream = futures::stream::unfold(file, |mut file| async move {
+        let mut buf = vec![0u8; 64 * 1024];
+        match file.read(&mut buf).await {
+            Ok(0) => None,
+            Ok(n) => {
+                buf.truncate(n);
+                Some((Ok::<_, std::io::Error>(Bytes::from(buf)), file))
+            }
+            Err(e) => Some((Err(e), file)),
+        }
+    });
+
+    let mut response = (
+        StatusCode::OK,
+        [
+            (header::CONTENT_TYPE, "application/zip".to_string()),
+            (
+                header::CONTENT_DISPOSITION,
+                format!("attachment; filename=\"{}\"", name),
+            ),
+        ],
+        Body::from_stream(stream),
+    )
+        .into_response();
+    if let Some(size) = size {
+        response
+            .headers_mut()
+            .insert(header::CONTENT_LENGTH, header::HeaderValue::from(size));
+    }
+    response
+}
+
+fn error_response(status: StatusCode, message: impl Into<String>) -> Response {
+    (
+        status,
+        Json(json!({ "error": { "message": message.into() } })),
+    )
+        .into_response()
+}
+
 /// GET /api/v1/admin/backup/export-markdown — download all articles as Markdown ZIP
 pub async fn export_markdown_endpoint(State(state): State<AppState>) -> impl IntoResponse {
     match backup::export_markdown(&state.pool).await {
@@ -150,6 +256,38 @@ pub async fn export_markdown_endpoint(State(state): State<AppState>) -> impl Int
     }
 }
 
+/// GET /api/v1/admin/export — download all content as a portable ZIP bundle
+pub async fn export_bundle_endpoint(State(state): State<AppState>) -> impl IntoResponse {
+    match backup::export_bundle(&state.pool).await {
+        Ok(zip_bytes) => {
+            let filename = format!(
+                "noteva-export-{}.zip",
+                chrono::Utc::now().format("%Y%m%d-%H%M%S")
+            );
+            (
+                StatusCode::OK,
+                [
+                    (header::CONTENT_TYPE, "application/zip".to_string()),
+                    (
+                        header::CONTENT_DISPOSITION,
+                        format!("attachment; filename=\"{}\"", filename),
+                    ),
+                ],
+                zip_bytes,
+            )
+                .into_response()
+        }
+        Err(e) => {
+            tracing::error!(error = %e, "content export failed");
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(json!({ "error": { "message": format!("Export failed: {}", e) } })),
+            )
+                .into_response()
+        }
+    }
+}
+
 /// POST /api/v1/admin/backup/import — import articles from Markdown ZIP or WordPress XML
 pub async fn import_articles_endpoint(
     State(state): State<AppState>,
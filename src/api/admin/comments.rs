@@ -1,14 +1,23 @@
 //! Comment management endpoints
 
 use axum::{
+    body::{Body, Bytes},
     extract::{Path, Query, State},
-    http::StatusCode,
+    http::{header, HeaderMap, StatusCode},
+    response::{IntoResponse, Response},
     Json,
 };
+use chrono::{DateTime, NaiveDate, Utc};
 use serde::{Deserialize, Serialize};
+use std::sync::Arc;
 
-use crate::api::common::{default_page_i64, default_per_page};
-use crate::api::middleware::{ApiError, AppState, AuthenticatedUser};
+use crate::api::common::{csv_line, default_page_i64, default_per_page};
+use crate::api::middleware::{check_write_preconditions, ApiError, AppState, AuthenticatedUser};
+use crate::models::{CommentExportFilter, CommentExportRecord, CommentSearchFilter};
+use crate::services::CommentService;
+
+/// Number of comments fetched per database round-trip while exporting
+const EXPORT_BATCH_SIZE: i64 = 500;
 
 /// Query params for comments list
 #[derive(Debug, Deserialize)]
@@ -125,12 +134,127 @@ pub async fn list_pending_comments(
     }))
 }
 
+/// Query params for comment search
+#[derive(Debug, Deserialize)]
+pub struct CommentSearchQuery {
+    #[serde(default = "default_page_i64")]
+    pub page: i64,
+    #[serde(default = "default_per_page")]
+    pub per_page: i64,
+    /// Keyword matched against content and nickname
+    pub q: Option<String>,
+    pub email: Option<String>,
+    pub ip: Option<String>,
+    pub status: Option<String>,
+}
+
+/// A comment search hit, including the fields used to spot related spam
+#[derive(Debug, Serialize)]
+pub struct AdminCommentSearchResult {
+    pub id: i64,
+    pub article_id: i64,
+    pub content: String,
+    pub status: String,
+    pub nickname: Option<String>,
+    pub email: Option<String>,
+    pub ip_address: Option<String>,
+    pub user_agent: Option<String>,
+    pub created_at: String,
+}
+
+/// Response for comment search
+#[derive(Debug, Serialize)]
+pub struct AdminCommentSearchResponse {
+    pub comments: Vec<AdminCommentSearchResult>,
+    pub total: i64,
+    pub page: i64,
+    pub per_page: i64,
+    pub total_pages: i64,
+}
+
+/// GET /api/v1/admin/comments/search - Search comments by keyword, email, IP and status
+pub async fn search_comments(
+    State(state): State<AppState>,
+    _user: AuthenticatedUser,
+    Query(query): Query<CommentSearchQuery>,
+) -> Result<Json<AdminCommentSearchResponse>, ApiError> {
+    let page = query.page.max(1);
+    let per_page = query.per_page.clamp(1, 100);
+    let non_empty = |value: Option<String>| {
+        value
+            .map(|v| v.trim().to_string())
+            .filter(|v| !v.is_empty())
+    };
+
+    let filter = CommentSearchFilter {
+        keyword: non_empty(query.q),
+        email: non_empty(query.email),
+        ip_address: non_empty(query.ip),
+        status: non_empty(query.status)
+            .map(|s| s.parse())
+            .transpose()
+            .map_err(ApiError::validation_error)?,
+    };
+
+    let (comments, total) = state
+        .comment_service
+        .search(&filter, page, per_page)
+        .await
+        .map_err(|e| ApiError::internal_error(e.to_string()))?;
+
+    let total_pages = (total as f64 / per_page as f64).ceil() as i64;
+
+    let comments = comments
+        .into_iter()
+        .map(|c| AdminCommentSearchResult {
+            id: c.id,
+            article_id: c.article_id,
+            content: c.content,
+            status: c.status.to_string(),
+            nickname: c.nickname,
+            email: c.email,
+            ip_address: c.ip_address,
+            user_agent: c.user_agent,
+            created_at: c.created_at.to_rfc3339(),
+        })
+        .collect();
+
+    Ok(Json(AdminCommentSearchResponse {
+        comments,
+        total,
+        page,
+        per_page,
+        total_pages,
+    }))
+}
+
+/// Evaluate `If-Match`/`If-Unmodified-Since` against the stored comment
+async fn ensure_comment_unchanged(
+    state: &AppState,
+    id: i64,
+    headers: &HeaderMap,
+) -> Result<(), ApiError> {
+    if !headers.contains_key(header::IF_MATCH) && !headers.contains_key(header::IF_UNMODIFIED_SINCE)
+    {
+        return Ok(());
+    }
+    let comment = state
+        .comment_service
+        .get_by_id(id)
+        .await
+        .map_err(|e| ApiError::internal_error(e.to_string()))?
+        .ok_or_else(|| ApiError::not_found("Comment not found"))?;
+    check_write_preconditions(headers, comment.id, comment.updated_at)
+}
+
 /// POST /api/v1/admin/comments/:id/approve - Approve a comment
 pub async fn approve_comment(
     State(state): State<AppState>,
     _user: AuthenticatedUser,
     Path(id): Path<i64>,
+    headers: HeaderMap,
 ) -> Result<StatusCode, ApiError> {
+    ensure_comment_unchanged(&state, id, &headers).await?;
     let success = state
         .comment_service
         .approve(id)
@@ -149,7 +273,9 @@ pub async fn reject_comment(
     State(state): State<AppState>,
     _user: AuthenticatedUser,
     Path(id): Path<i64>,
+    headers: HeaderMap,
 ) -> Result<StatusCode, ApiError> {
+    ensure_comment_unchanged(&state, id, &headers).await?;
     let success = state
         .comment_service
         .reject(id)
@@ -162,3 +288,215 @@ pub async fn reject_comment(
         Err(ApiError::not_found("Comment not found"))
     }
 }
+
+/// Query params for comment export
+#[derive(Debug, Deserialize)]
+pub struct CommentExportQuery {
+    /// "csv" (default) or "json"
+    pub format: Option<String>,
+    pub status: Option<String>,
+    pub article_id: Option<i64>,
+    /// Start date (`YYYY-MM-DD` or RFC 3339), inclusive
+    pub from: Option<String>,
+    /// End date (`YYYY-MM-DD` or RFC 3339); a plain date includes the whole day
+    pub to: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ExportFormat {
+    Csv,
+    Json,
+}
+
+/// GET /api/v1/admin/comments/export - Stream filtered comments as CSV or JSON
+pub async fn export_comments(
+    State(state): State<AppState>,
+    _user: AuthenticatedUser,
+    Query(query): Query<CommentExportQuery>,
+) -> Result<Response, ApiError> {
+    let format = match query.format.as_deref().unwrap_or("csv") {
+        "csv" => ExportFormat::Csv,
+        "json" => ExportFormat::Json,
+        other => {
+            return Err(ApiError::validation_error(format!(
+                "Unsupported export format: {}",
+                other
+            )))
+        }
+    };
+
+    let filter = CommentExportFilter {
+        status: query
+            .status
+            .as_deref()
+            .filter(|s| !s.is_empty())
+            .map(str::parse)
+            .transpose()
+            .map_err(ApiError::validation_error)?,
+        article_id: query.article_id,
+        from: query
+            .from
+            .as_deref()
+            .map(|v| parse_export_date(v, false))
+            .transpose()?,
+        to: query
+            .to
+            .as_deref()
+            .map(|v| parse_export_date(v, true))
+            .transpose()?,
+    };
+
+    let (content_type, extension) = match format {
+        ExportFormat::Csv => ("text/csv; charset=utf-8", "csv"),
+        ExportFormat::Json => ("application/json", "json"),
+    };
+    let filename = format!(
+        "comments-{}.{}",
+        Utc::now().format("%Y%m%d-%H%M%S"),
+        extension
+    );
+
+    let cursor = ExportCursor {
+        service: state.comment_service.clone(),
+        filter,
+        format,
+        after_id: 0,
+        written: 0,
+        started: false,
+        finished: false,
+    };
+    let stream = futures::stream::unfold(cursor, |mut cursor| async move {
+        if cursor.finished {
+            return None;
+        }
+        let chunk = cursor.next_chunk().await;
+        Some((chunk, cursor))
+    });
+
+    Ok((
+        [
+            (header::CONTENT_TYPE, content_type.to_string()),
+            (
+                header::CONTENT_DISPOSITION,
+                format!("attachment; filename=\"{}\"", filename),
+            ),
+        ],
+        Body::from_stream(stream),
+    )
+        .into_response())
+}
+
+/// Streaming state for [`export_comments`]
+struct ExportCursor {
+    service: Arc<CommentService>,
+    filter: CommentExportFilter,
+    format: ExportFormat,
+    after_id: i64,
+    written: usize,
+    started: bool,
+    finished: bool,
+}
+
+impl ExportCursor {
+    /// Produce the next body chunk: one batch of records, plus the document
+    /// header on the first call and the footer once the data runs out.
+    async fn next_chunk(&mut self) -> Result<Bytes, std::io::Error> {
+        let mut chunk = String::new();
+        if !self.started {
+            self.started = true;
+            match self.format {
+                ExportFormat::Csv => chunk.push_str(CSV_HEADER),
+                ExportFormat::Json => chunk.push('['),
+            }
+        }
+
+        let batch = self
+            .service
+            .list_for_export(&self.filter, self.after_id, EXPORT_BATCH_SIZE)
+            .await
+            .map_err(|e| {
+                self.finished = true;
+                tracing::error!(error = %e, "comment export failed");
+                std::io::Error::other(e.to_string())
+            })?;
+
+        for record in &batch {
+            match self.format {
+                ExportFormat::Csv => chunk.push_str(&csv_row(record)),
+                ExportFormat::Json => {
+                    if self.written > 0 {
+                        chunk.push(',');
+                    }
+                    let json = serde_json::to_string(record)
+                        .map_err(|e| std::io::Error::other(e.to_string()))?;
+                    chunk.push_str(&json);
+                }
+            }
+            self.written += 1;
+        }
+
+        match batch.last() {
+            Some(last) if batch.len() as i64 == EXPORT_BATCH_SIZE => self.after_id = last.id,
+            _ => {
+                self.finished = true;
+                if self.format == ExportFormat::Json {
+                    chunk.push(']');
+                }
+            }
+        }
+
+        Ok(Bytes::from(chunk))
+    }
+}
+
+const CSV_HEADER: &str =
+    "id,article_id,article_slug,parent_id,user_id,nickname,email,content,status,ip_address,created_at\r\n";
+
+/// Format one record as a CSV line (RFC 4180)
+fn csv_row(record: &CommentExportRecord) -> String {
+    let fields = [
+        record.id.to_string(),
+        record.article_id.to_string(),
+        record.article_slug.clone().unwrap_or_default(),
+        record.parent_id.map(|v| v.to_string()).unwrap_or_default(),
+        record.user_id.map(|v| v.to_string()).unwrap_or_default(),
+        record.nickname.clone().unwrap_or_default(),
+        record.email.clone().unwrap_or_default(),
+        record.content.clone(),
+        record.status.to_string(),
+        record.ip_address.clone().unwrap_or_default(),
+        record.created_at.to_rfc3339(),
+    ];
+    csv_line(&fields)
+}
+
+/// Parse an export date bound; a plain date used as the upper bound is
+/// moved to the start of the next day so the whole day is included.
+fn parse_export_date(value: &str, end_of_range: bool) -> Result<DateTime<Utc>, ApiError> {
+    if let Ok(dt) = DateTime::parse_from_rfc3339(value) {
+        return Ok(dt.with_timezone(&Utc));
+    }
+    let date = NaiveDate::parse_from_str(value, "%Y-%m-%d")
+        .map_err(|_| ApiError::validation_error(format!("Invalid date: {}", value)))?;
+    let date = if end_of_range {
+        date.succ_opt()
+            .ok_or_else(|| ApiError::validation_error(format!("Invalid date: {}", value)))?
+    } else {
+        date
+    };
+    Ok(date.and_hms_opt(0, 0, 0).unwrap_or_default().and_utc())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn plain_end_date_covers_whole_day() {
+        let from = parse_export_date("2024-02-28", false).unwrap();
+        let to = parse_export_date("2024-02-28", true).unwrap();
+        assert_eq!(from.to_rfc3339(), "2024-02-28T00:00:00+00:00");
+        assert_eq!(to.to_rfc3339(), "2024-02-29T00:00:00+00:00");
+        assert!(parse_export_date("yesterday", false).is_err());
+    }
+}
@@ -0,0 +1,72 @@
+//! Near-duplicate article report endpoints
+//!
+//! See [`crate::services::duplicates`] for how articles are compared.
+
+use axum::{extract::State, http::StatusCode, Json};
+use serde::{Deserialize, Serialize};
+
+use crate::api::middleware::{ApiError, AppState, AuthenticatedUser};
+use crate::services::duplicates::{DuplicateReport, DEFAULT_MIN_SIMILARITY, MIN_SIMILARITY_FLOOR};
+
+/// Request body for starting a scan
+#[derive(Debug, Default, Deserialize)]
+pub struct ScanRequest {
+    /// Similarity a pair needs to be reported, 0.5 to 1
+    pub min_similarity: Option<f64>,
+}
+
+/// Response for a started scan
+#[derive(Debug, Serialize)]
+pub struct ScanStartedResponse {
+    /// Background job to follow under `/api/v1/admin/jobs/{id}`
+    pub job_id: u64,
+}
+
+/// GET /api/v1/admin/duplicates - Latest near-duplicate report
+///
+/// `null` until a scan has finished since startup.
+/// Requires admin authentication.
+pub async fn get_report(
+    State(state): State<AppState>,
+    _user: AuthenticatedUser,
+) -> Json<Option<DuplicateReport>> {
+    Json(state.duplicates.report())
+}
+
+/// POST /api/v1/admin/duplicates/scan - Compare all articles in the background
+///
+/// Body (optional): `{"min_similarity": 0.8}`.
+/// Requires admin authentication.
+pub async fn start_scan(
+    State(state): State<AppState>,
+    _user: AuthenticatedUser,
+    body: Option<Json<ScanRequest>>,
+) -> Result<(StatusCode, Json<ScanStartedResponse>), ApiError> {
+    let min_similarity = body
+        .and_then(|Json(body)| body.min_similarity)
+        .unwrap_or(DEFAULT_MIN_SIMILARITY);
+    if !(MIN_SIMILARITY_FLOOR..=1.0).contains(&min_similarity) {
+        return Err(ApiError::validation_error(format!(
+            "min_similarity must be between {} and 1",
+            MIN_SIMILARITY_FLOOR
+        )));
+    }
+    let duplicates = state.duplicates.clone();
+    let job_id = state.jobs.submit(
+        "duplicate_scan",
+        format!("similarity >= {}", min_similarity),
+        move || {
+            let duplicates = duplicates.clone();
+            async move {
+                let report = duplicates.scan(min_similarity).await?;
+                tracing::info!(
+                    articles = report.articles_scanned,
+                    pairs = report.pairs.len(),
+                    "duplicate scan finished"
+                );
+                Ok(())
+            }
+        },
+    );
+    Ok((StatusCode::ACCEPTED, Json(ScanStartedResponse { job_id })))
+}
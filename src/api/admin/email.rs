@@ -0,0 +1,179 @@
+//! Email diagnostics and suppression list endpoints
+
+use axum::{
+    extract::{Path, Query, State},
+    http::StatusCode,
+    Json,
+};
+use serde::{Deserialize, Serialize};
+
+use crate::api::common::{default_page_i64, default_per_page};
+use crate::api::middleware::{ApiError, AppState, AuthenticatedUser};
+use crate::db::repositories::{EmailSuppressionRepository, SqlxEmailSuppressionRepository};
+use crate::models::{EmailSuppression, SuppressionReason};
+use crate::services::email::{EmailDiagnostics, SendFailure};
+
+fn default_send() -> bool {
+    true
+}
+
+/// Request body for the test email endpoint
+#[derive(Debug, Default, Deserialize)]
+pub struct TestEmailRequest {
+    /// Recipient; defaults to the signed-in admin's address
+    pub to: Option<String>,
+    /// DKIM selector to check; defaults to the `dkim_selector` setting
+    pub dkim_selector: Option<String>,
+    /// Set to false to only run the diagnostics
+    #[serde(default = "default_send")]
+    pub send: bool,
+}
+
+/// Response for the test email endpoint
+#[derive(Debug, Serialize)]
+pub struct TestEmailResponse {
+    pub sent: bool,
+    pub to: Option<String>,
+    pub send_error: Option<String>,
+    #[serde(flatten)]
+    pub diagnostics: EmailDiagnostics,
+    /// Recent delivery failures, newest first
+    pub recent_failures: Vec<SendFailure>,
+}
+
+/// POST /api/v1/admin/email/test - Send a test email and report deliverability
+///
+/// Reports the SMTP connection, SPF/DMARC/DKIM records of the sending
+/// domain and recent delivery failures. Requires admin authentication.
+pub async fn send_test_email(
+    State(state): State<AppState>,
+    user: AuthenticatedUser,
+    body: Option<Json<TestEmailRequest>>,
+) -> Result<Json<TestEmailResponse>, ApiError> {
+    let Json(body) = body.unwrap_or_default();
+    let to = body
+        .to
+        .map(|to| to.trim().to_string())
+        .filter(|to| !to.is_empty())
+        .unwrap_or_else(|| user.0.email.clone());
+    if !to.contains('@') {
+        return Err(ApiError::validation_error("Invalid recipient address"));
+    }
+
+    let email = &state.email_service;
+    let diagnostics = email.diagnose(body.dkim_selector.as_deref()).await;
+
+    let (sent, send_error) = if body.send {
+        match email.send_test_email(&to).await {
+            Ok(()) => (true, None),
+            Err(e) => (false, Some(e.to_string())),
+        }
+    } else {
+        (false, None)
+    };
+
+    Ok(Json(TestEmailResponse {
+        sent,
+        to: body.send.then_some(to),
+        send_error,
+        diagnostics,
+        recent_failures: email.recent_failures(),
+    }))
+}
+
+/// Query parameters for the suppression list
+#[derive(Debug, Deserialize)]
+pub struct SuppressionListQuery {
+    #[serde(default = "default_page_i64")]
+    pub page: i64,
+    #[serde(default = "default_per_page")]
+    pub per_page: i64,
+}
+
+/// Response for the suppression list
+#[derive(Debug, Serialize)]
+pub struct SuppressionListResponse {
+    pub suppressions: Vec<EmailSuppression>,
+    pub total: i64,
+    pub page: i64,
+    pub per_page: i64,
+}
+
+/// Request body for manually suppressing an address
+#[derive(Debug, Deserialize)]
+pub struct AddSuppressionRequest {
+    pub email: String,
+    pub detail: Option<String>,
+}
+
+/// GET /api/v1/admin/email/suppressions - List suppressed addresses
+///
+/// Requires admin authentication.
+pub async fn list_suppressions(
+    State(state): State<AppState>,
+    _user: AuthenticatedUser,
+    Query(query): Query<SuppressionListQuery>,
+) -> Result<Json<SuppressionListResponse>, ApiError> {
+    let page = query.page.max(1);
+    let per_page = query.per_page.clamp(1, 100);
+    let repo = SqlxEmailSuppressionRepository::new(state.pool.clone());
+    let (suppressions, total) = repo
+        .list(page, per_page)
+        .await
+        .map_err(|e| ApiError::internal_error(e.to_string()))?;
+
+    Ok(Json(SuppressionListResponse {
+        suppressions,
+        total,
+        page,
+        per_page,
+    }))
+}
+
+/// POST /api/v1/admin/email/suppressions - Suppress an address manually
+///
+/// Requires admin authentication.
+pub async fn add_suppression(
+    State(state): State<AppState>,
+    _user: AuthenticatedUser,
+    Json(body): Json<AddSuppressionRequest>,
+) -> Result<(StatusCode, Json<EmailSuppression>), ApiError> {
+    let email = body.email.trim();
+    if !email.contains('@') {
+        return Err(ApiError::validation_error("Invalid email address"));
+    }
+    let detail = body
+        .detail
+        .as_deref()
+        .map(str::trim)
+        .filter(|d| !d.is_empty());
+
+    let repo = SqlxEmailSuppressionRepository::new(state.pool.clone());
+    let entry = repo
+        .upsert(email, SuppressionReason::Manual, detail)
+        .await
+        .map_err(|e| ApiError::internal_error(e.to_string()))?;
+
+    Ok((StatusCode::CREATED, Json(entry)))
+}
+
+/// DELETE /api/v1/admin/email/suppressions/{email} - Allow sending to an address again
+///
+/// Requires admin authentication.
+pub async fn remove_suppression(
+    State(state): State<AppState>,
+    _user: AuthenticatedUser,
+    Path(email): Path<String>,
+) -> Result<StatusCode, ApiError> {
+    let repo = SqlxEmailSuppressionRepository::new(state.pool.clone());
+    let removed = repo
+        .delete(&email)
+        .await
+        .map_err(|e| ApiError::internal_error(e.to_string()))?;
+
+    if removed {
+        Ok(StatusCode::NO_CONTENT)
+    } else {
+        Err(ApiError::not_found("Address is not suppressed"))
+    }
+}
@@ -0,0 +1,78 @@
+//! Live admin event stream
+//!
+//! `GET /admin/events` is a Server-Sent Events stream of
+//! [`crate::services::AdminEvents`] (`comment`, `job`, `plugin_log`) plus a
+//! `stats` snapshot of the request counters every few seconds.
+
+use std::convert::Infallible;
+use std::sync::Arc;
+use std::time::Duration;
+
+use axum::{
+    extract::State,
+    response::sse::{Event, KeepAlive, Sse},
+};
+use futures::stream::{self, Stream};
+use serde_json::json;
+use tokio::sync::broadcast::{self, error::RecvError};
+use tokio::time::{interval, Interval, MissedTickBehavior};
+
+use crate::api::middleware::{AppState, AuthenticatedUser, RequestStats};
+use crate::services::AdminEvent;
+
+/// How often a `stats` event is sent
+const STATS_INTERVAL: Duration = Duration::from_secs(5);
+
+struct StreamState {
+    events: broadcast::Receiver<AdminEvent>,
+    ticker: Interval,
+    stats: Arc<RequestStats>,
+}
+
+fn stats_event(stats: &RequestStats) -> Event {
+    Event::default().event("stats").data(
+        json!({
+            "total_requests": stats.total_requests(),
+            "avg_response_time_ms": stats.avg_response_time_us() / 1000.0,
+            "in_flight": stats.in_flight(),
+            "uptime_seconds": stats.uptime_seconds(),
+        })
+        .to_string(),
+    )
+}
+
+/// Stream admin events until the client disconnects
+pub async fn stream_events(
+    State(state): State<AppState>,
+    _user: AuthenticatedUser,
+) -> Sse<impl Stream<Item = Result<Event, Infallible>>> {
+    let mut ticker = interval(STATS_INTERVAL);
+    ticker.set_missed_tick_behavior(MissedTickBehavior::Delay);
+    let initial = StreamState {
+        events: state.admin_events.subscribe(),
+        ticker,
+        stats: state.request_stats.clone(),
+    };
+
+    let stream = stream::unfold(initial, |mut s| async move {
+        loop {
+            tokio::select! {
+                received = s.events.recv() => match received {
+                    Ok(event) => {
+                        let sse = Event::default().event(event.kind).data(event.data.to_string());
+                        return Some((Ok(sse), s));
+                    }
+                    // Slow client: skip what it missed
+                    Err(RecvError::Lagged(_)) => continue,
+                    Err(RecvError::Closed) => return None,
+                },
+                _ = s.ticker.tick() => {
+                    let sse = stats_event(&s.stats);
+                    return Some((Ok(sse), s));
+                }
+            }
+        }
+    });
+
+    Sse::new(stream).keep_alive(KeepAlive::default())
+}
@@ -0,0 +1,225 @@
+//! Admin import API endpoints
+
+use axum::{
+    extract::{Multipart, Query, State},
+    http::StatusCode,
+    response::IntoResponse,
+    Json,
+};
+use serde::Deserialize;
+use serde_json::json;
+
+use crate::api::middleware::{AppState, AuthenticatedUser};
+use crate::db::repositories::{SqlxArticleRepository, SqlxCommentRepository};
+use crate::services::import::{self, MarkdownImporter, WordpressImporter};
+
+/// Query params for the WordPress import
+#[derive(Debug, Default, Deserialize)]
+pub struct WordpressImportQuery {
+    /// Keep media links pointing at the old site instead of downloading them
+    #[serde(default)]
+    pub skip_media: bool,
+}
+
+/// Read the `file` field of a multipart upload
+async fn read_upload(multipart: &mut Multipart) -> Result<Vec<u8>, axum::response::Response> {
+    let mut file_data: Option<Vec<u8>> = None;
+    while let Ok(Some(field)) = multipart.next_field().await {
+        if field.name() == Some("file") {
+            match field.bytes().await {
+                Ok(bytes) => file_data = Some(bytes.to_vec()),
+                Err(e) => {
+                    return Err((
+                        StatusCode::BAD_REQUEST,
+                        Json(json!({ "error": { "message": format!("Failed to read file: {}", e) } })),
+                    )
+                        .into_response());
+                }
+            }
+        }
+    }
+    file_data.ok_or_else(|| {
+        (
+            StatusCode::BAD_REQUEST,
+            Json(json!({ "error": { "message": "No file uploaded" } })),
+        )
+            .into_response()
+    })
+}
+
+/// POST /api/v1/admin/import/comments — import comments from a Disqus or WordPress export
+pub async fn import_comments_endpoint(
+    State(state): State<AppState>,
+    mut multipart: Multipart,
+) -> impl IntoResponse {
+    let file_data = match read_upload(&mut multipart).await {
+        Ok(data) => data,
+        Err(response) => return response,
+    };
+
+    let article_repo = SqlxArticleRepository::new(state.pool.clone());
+    let comment_repo = SqlxCommentRepository::new(state.pool.clone());
+    match import::import_comments(&article_repo, &comment_repo, &file_data).await {
+        Ok(result) => {
+            for (id, slug) in &result.articles {
+                let _ = state
+                    .article_service
+                    .invalidate_article_cache(*id, slug)
+                    .await;
+            }
+            state.comment_service.invalidate_cache().await;
+
+            tracing::info!(
+                format = ?result.format,
+                imported = result.imported,
+                skipped = result.skipped,
+                "comment import completed"
+            );
+            (
+                StatusCode::OK,
+                Json(json!({
+                    "status": "ok",
+                    "format": result.format,
+                    "imported": result.imported,
+                    "skipped": result.skipped,
+                    "errors": result.errors,
+                })),
+            )
+                .into_response()
+        }
+        Err(e) => {
+            tracing::error!(error = %e, "comment import failed");
+            (
+                StatusCode::BAD_REQUEST,
+                Json(json!({ "error": { "message": format!("Import failed: {}", e) } })),
+            )
+                .into_response()
+        }
+    }
+}
+
+/// POST /api/v1/admin/import/wordpress — import a WordPress WXR export
+///
+/// Creates authors, categories, tags, posts and pages, keeping their slugs so
+/// old URLs still resolve, and downloads attached media unless `skip_media`.
+pub async fn import_wordpress_endpoint(
+    State(state): State<AppState>,
+    user: AuthenticatedUser,
+    Query(query): Query<WordpressImportQuery>,
+    mut multipart: Multipart,
+) -> impl IntoResponse {
+    let file_data = match read_upload(&mut multipart).await {
+        Ok(data) => data,
+        Err(response) => return response,
+    };
+
+    let article_repo = SqlxArticleRepository::new(state.pool.clone());
+    let importer = WordpressImporter {
+        articles: &state.article_service,
+        article_repo: &article_repo,
+        categories: &state.category_service,
+        tags: &state.tag_service,
+        pages: &state.page_service,
+        users: &state.user_service,
+        actor_id: user.0.id,
+        uploads: &state.upload_config.path,
+        max_file_size: state.upload_config.max_file_size,
+        download_media: !query.skip_media,
+    };
+    match importer.import(&file_data).await {
+        Ok(result) => {
+            tracing::info!(
+                user_id = user.0.id,
+                posts = result.posts,
+                pages = result.pages,
+                media = result.media,
+                skipped = result.skipped,
+                "WordPress import completed"
+            );
+            (
+                StatusCode::OK,
+                Json(json!({
+                    "status": "ok",
+                    "authors": result.authors,
+                    "categories": result.categories,
+                    "tags": result.tags,
+                    "posts": result.posts,
+                    "pages": result.pages,
+                    "media": result.media,
+                    "skipped": result.skipped,
+                    "errors": result.errors,
+                })),
+            )
+                .into_response()
+        }
+        Err(e) => {
+            tracing::error!(error = %e, "WordPress import failed");
+            (
+                StatusCode::BAD_REQUEST,
+                Json(json!({ "error": { "message": format!("Import failed: {:#}", e) } })),
+            )
+                .into_response()
+        }
+    }
+}
+
+/// POST /api/v1/admin/import/markdown — import Hugo / Jekyll Markdown or a Ghost export
+///
+/// Accepts a ZIP of Markdown files with front matter, a single Markdown file
+/// or a Ghost JSON export. Imported posts are owned by the calling admin.
+pub async fn import_markdown_endpoint(
+    State(state): State<AppState>,
+    user: AuthenticatedUser,
+    mut multipart: Multipart,
+) -> impl IntoResponse {
+    let file_data = match read_upload(&mut multipart).await {
+        Ok(data) => data,
+        Err(response) => return response,
+    };
+
+    let article_repo = SqlxArticleRepository::new(state.pool.clone());
+    let importer = MarkdownImporter {
+        articles: &state.article_service,
+        article_repo: &article_repo,
+        categories: &state.category_service,
+        tags: &state.tag_service,
+        pages: &state.page_service,
+        author_id: user.0.id,
+    };
+    match importer.import(&file_data).await {
+        Ok(result) => {
+            tracing::info!(
+                user_id = user.0.id,
+                format = ?result.format,
+                posts = result.posts,
+                pages = result.pages,
+                drafts = result.drafts,
+                skipped = result.skipped,
+                "Markdown import completed"
+            );
+            (
+                StatusCode::OK,
+                Json(json!({
+                    "status": "ok",
+                    "format": result.format,
+                    "categories": result.categories,
+                    "tags": result.tags,
+                    "posts": result.posts,
+                    "pages": result.pages,
+                    "drafts": result.drafts,
+                    "skipped": result.skipped,
+                    "errors": result.errors,
+                })),
+            )
+                .into_response()
+        }
+        Err(e) => {
+            tracing::error!(error = %e, "Markdown import failed");
+            (
+                StatusCode::BAD_REQUEST,
+                Json(json!({ "error": { "message": format!("Import failed: {:#}", e) } })),
+            )
+                .into_response()
+        }
+    }
+}
@@ -0,0 +1,162 @@
+//! Background job and schedule endpoints
+//!
+//! See [`crate::services::jobs`] for what is tracked in memory, and
+//! [`crate::services::job_queue`] for the persistent queue under `/jobs/queue`.
+
+use axum::{
+    extract::{Path, Query, State},
+    http::StatusCode,
+    Json,
+};
+use serde::Deserialize;
+
+use crate::api::middleware::{ApiError, AppState, AuthenticatedUser};
+use crate::models::{QueuedJob, QueuedJobStatus};
+use crate::services::job_queue::DEFAULT_LIST_LIMIT;
+use crate::services::{
+    JobError, JobInfo, JobQueueError, JobQueueOverview, JobStatus, ScheduleInfo,
+};
+
+fn map_error(e: JobError) -> ApiError {
+    match e {
+        JobError::NotFound => ApiError::not_found("Job not found"),
+        JobError::InvalidState(msg) => ApiError::validation_error(msg),
+    }
+}
+
+fn map_queue_error(e: JobQueueError) -> ApiError {
+    match e {
+        JobQueueError::NotFound => ApiError::not_found("Job not found"),
+        JobQueueError::InvalidState(msg) => ApiError::validation_error(msg),
+        JobQueueError::Internal(e) => ApiError::internal_error(e.to_string()),
+    }
+}
+
+/// Query parameters for listing jobs
+#[derive(Debug, Deserialize)]
+pub struct JobsQuery {
+    /// Only jobs with this status
+    pub status: Option<JobStatus>,
+}
+
+/// GET /api/v1/admin/jobs - List background jobs, newest first
+///
+/// Requires admin authentication.
+pub async fn list_jobs(
+    State(state): State<AppState>,
+    _user: AuthenticatedUser,
+    Query(query): Query<JobsQuery>,
+) -> Json<Vec<JobInfo>> {
+    Json(state.jobs.jobs(query.status))
+}
+
+/// GET /api/v1/admin/jobs/{id} - Get a background job
+///
+/// Requires admin authentication.
+pub async fn get_job(
+    State(state): State<AppState>,
+    _user: AuthenticatedUser,
+    Path(id): Path<u64>,
+) -> Result<Json<JobInfo>, ApiError> {
+    state
+        .jobs
+        .job(id)
+        .map(Json)
+        .ok_or_else(|| map_error(JobError::NotFound))
+}
+
+/// POST /api/v1/admin/jobs/{id}/retry - Run a failed or cancelled job again
+///
+/// Requires admin authentication.
+pub async fn retry_job(
+    State(state): State<AppState>,
+    _user: AuthenticatedUser,
+    Path(id): Path<u64>,
+) -> Result<Json<JobInfo>, ApiError> {
+    let job = state.jobs.retry(id).map_err(map_error)?;
+    Ok(Json(job))
+}
+
+/// POST /api/v1/admin/jobs/{id}/cancel - Stop a queued or running job
+///
+/// Requires admin authentication.
+pub async fn cancel_job(
+    State(state): State<AppState>,
+    _user: AuthenticatedUser,
+    Path(id): Path<u64>,
+) -> Result<Json<JobInfo>, ApiError> {
+    let job = state.jobs.cancel(id).map_err(map_error)?;
+    Ok(Json(job))
+}
+
+/// GET /api/v1/admin/schedules - List periodic tasks with their last run
+///
+/// Requires admin authentication.
+pub async fn list_schedules(
+    State(state): State<AppState>,
+    _user: AuthenticatedUser,
+) -> Json<Vec<ScheduleInfo>> {
+    Json(state.jobs.schedules())
+}
+
+/// Query parameters for listing queued jobs
+#[derive(Debug, Deserialize)]
+pub struct QueueQuery {
+    /// Only jobs with this status
+    pub status: Option<QueuedJobStatus>,
+    /// Number of jobs listed (default 100, at most 500)
+    pub limit: Option<i64>,
+}
+
+/// GET /api/v1/admin/jobs/queue - Job counts per status and the newest queued jobs
+///
+/// Requires admin authentication.
+pub async fn list_queued_jobs(
+    State(state): State<AppState>,
+    _user: AuthenticatedUser,
+    Query(query): Query<QueueQuery>,
+) -> Result<Json<JobQueueOverview>, ApiError> {
+    let limit = query.limit.unwrap_or(DEFAULT_LIST_LIMIT).clamp(1, 500);
+    let overview = state
+        .job_queue
+        .overview(query.status, limit)
+        .await
+        .map_err(map_queue_error)?;
+    Ok(Json(overview))
+}
+
+/// GET /api/v1/admin/jobs/queue/{id} - Get a queued job
+///
+/// Requires admin authentication.
+pub async fn get_queued_job(
+    State(state): State<AppState>,
+    _user: AuthenticatedUser,
+    Path(id): Path<i64>,
+) -> Result<Json<QueuedJob>, ApiError> {
+    let job = state.job_queue.job(id).await.map_err(map_queue_error)?;
+    Ok(Json(job))
+}
+
+/// POST /api/v1/admin/jobs/queue/{id}/retry - Give a dead job new attempts
+///
+/// Requires admin authentication.
+pub async fn retry_queued_job(
+    State(state): State<AppState>,
+    _user: AuthenticatedUser,
+    Path(id): Path<i64>,
+) -> Result<Json<QueuedJob>, ApiError> {
+    let job = state.job_queue.retry(id).await.map_err(map_queue_error)?;
+    Ok(Json(job))
+}
+
+/// DELETE /api/v1/admin/jobs/queue/{id} - Delete a job that is not running
+///
+/// Requires admin authentication.
+pub async fn delete_queued_job(
+    State(state): State<AppState>,
+    _user: AuthenticatedUser,
+    Path(id): Path<i64>,
+) -> Result<StatusCode, ApiError> {
+    state.job_queue.delete(id).await.map_err(map_queue_error)?;
+    Ok(StatusCode::NO_CONTENT)
+}
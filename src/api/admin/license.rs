@@ -0,0 +1,41 @@
+//! Site default license settings
+
+use axum::{extract::State, Json};
+
+use crate::api::middleware::{ApiError, AppState, AuthenticatedUser};
+use crate::models::ContentLicense;
+use crate::services::license::{self, LicenseError};
+
+/// GET /api/v1/admin/license - Get the license articles use by default
+///
+/// Requires admin authentication.
+pub async fn get_default_license(
+    State(state): State<AppState>,
+    _user: AuthenticatedUser,
+) -> Json<ContentLicense> {
+    Json(license::load(&state.settings_service).await)
+}
+
+/// PUT /api/v1/admin/license - Change the default license
+///
+/// Body: `{"kind": "cc-by-sa"}`, or `{"kind": "custom", "name": "...", "url": "..."}`.
+/// Articles with a license of their own keep it.
+/// Requires admin authentication.
+pub async fn update_default_license(
+    State(state): State<AppState>,
+    user: AuthenticatedUser,
+    Json(input): Json<ContentLicense>,
+) -> Result<Json<ContentLicense>, ApiError> {
+    let license = license::save(&state.settings_service, input)
+        .await
+        .map_err(|e| match e {
+            LicenseError::Validation(msg) => ApiError::validation_error(msg),
+            LicenseError::Internal(msg) => ApiError::internal_error(msg),
+        })?;
+    tracing::info!(
+        user_id = user.0.id,
+        kind = license.kind.as_str(),
+        "default license changed"
+    );
+    Ok(Json(license))
+}
@@ -0,0 +1,71 @@
+//! Internal link suggestion endpoint
+//!
+//! See [`crate::services::link_suggestions`] for how articles are matched.
+
+use axum::{extract::State, Json};
+use serde::Deserialize;
+
+use crate::api::middleware::{ApiError, AppState, AuthenticatedUser};
+use crate::services::link_suggestions::{Draft, LinkSuggestion, DEFAULT_LIMIT, MAX_LIMIT};
+
+/// Longest draft accepted, in bytes
+const MAX_CONTENT_BYTES: usize = 512 * 1024;
+
+/// Request body for suggesting links
+#[derive(Debug, Deserialize)]
+pub struct LinkSuggestionRequest {
+    pub content: String,
+    #[serde(default)]
+    pub title: String,
+    /// Tag names
+    #[serde(default)]
+    pub tags: Vec<String>,
+    /// The article being edited, never suggested; 0 or missing for a new
+    /// article
+    #[serde(default)]
+    pub article_id: i64,
+    /// Suggestions to return, 1 to 50
+    pub limit: Option<usize>,
+}
+
+/// POST /api/v1/admin/link-suggestions - Published articles a draft could link to
+///
+/// Requires admin authentication.
+pub async fn suggest_links(
+    State(state): State<AppState>,
+    _user: AuthenticatedUser,
+    Json(req): Json<LinkSuggestionRequest>,
+) -> Result<Json<Vec<LinkSuggestion>>, ApiError> {
+    if req.content.len() > MAX_CONTENT_BYTES {
+        return Err(ApiError::validation_error(format!(
+            "Content is larger than {} KB",
+            MAX_CONTENT_BYTES / 1024
+        )));
+    }
+    let limit = req.limit.unwrap_or(DEFAULT_LIMIT);
+    if !(1..=MAX_LIMIT).contains(&limit) {
+        return Err(ApiError::validation_error(format!(
+            "limit must be between 1 and {}",
+            MAX_LIMIT
+        )));
+    }
+    let permalink_structure = state
+        .settings_service
+        .get(crate::services::settings::keys::PERMALINK_STRUCTURE)
+        .await
+        .ok()
+        .flatten()
+        .unwrap_or_else(|| "/posts/{slug}".to_string());
+    let draft = Draft {
+        title: req.title,
+        content: req.content,
+        tags: req.tags,
+        article_id: req.article_id,
+    };
+    let suggestions = state
+        .link_suggestions
+        .suggest(&draft, &permalink_structure, limit)
+        .await
+        .map_err(|e| ApiError::internal_error(e.to_string()))?;
+    Ok(Json(suggestions))
+}
@@ -0,0 +1,88 @@
+//! Maintenance mode endpoints
+
+use std::net::SocketAddr;
+
+use axum::{
+    extract::{ConnectInfo, State},
+    http::HeaderMap,
+    Json,
+};
+use chrono::Utc;
+use serde::Serialize;
+
+use crate::api::middleware::{extract_client_ip, ApiError, AppState, AuthenticatedUser};
+use crate::services::maintenance::{
+    MaintenanceError, MaintenanceState, MaintenanceWindow, UpdateMaintenanceInput,
+};
+
+/// Maintenance settings plus the caller's address, for adding it to the allowlist
+#[derive(Debug, Serialize)]
+pub struct MaintenanceResponse {
+    #[serde(flatten)]
+    pub state: MaintenanceState,
+    /// Whether public requests are blocked right now, by hand or by a window
+    pub in_effect: bool,
+    /// The scheduled window in progress
+    pub active_window: Option<MaintenanceWindow>,
+    pub client_ip: String,
+}
+
+impl MaintenanceResponse {
+    fn new(state: MaintenanceState, client_ip: String) -> Self {
+        let now = Utc::now();
+        Self {
+            in_effect: state.in_effect(now),
+            active_window: state.active_window(now).cloned(),
+            state,
+            client_ip,
+        }
+    }
+}
+
+fn map_error(e: MaintenanceError) -> ApiError {
+    match e {
+        MaintenanceError::Validation(msg) => ApiError::validation_error(msg),
+        MaintenanceError::Internal(msg) => ApiError::internal_error(msg),
+    }
+}
+
+/// GET /api/v1/admin/maintenance - Get maintenance mode settings
+///
+/// Requires admin authentication.
+pub async fn get_maintenance(
+    State(state): State<AppState>,
+    _user: AuthenticatedUser,
+    ConnectInfo(addr): ConnectInfo<SocketAddr>,
+    headers: HeaderMap,
+) -> Json<MaintenanceResponse> {
+    Json(MaintenanceResponse::new(
+        state.maintenance.state().await,
+        extract_client_ip(&headers, addr),
+    ))
+}
+
+/// PUT /api/v1/admin/maintenance - Turn maintenance mode on or off and
+/// schedule maintenance windows
+///
+/// Body: `{"enabled": false, "windows": [{"starts_at": "2026-01-01T22:00:00Z",
+/// "ends_at": "2026-01-01T23:00:00Z", "message": "", "announce_hours": 24}]}`;
+/// omitted fields are kept.
+/// Requires admin authentication.
+pub async fn update_maintenance(
+    State(state): State<AppState>,
+    user: AuthenticatedUser,
+    ConnectInfo(addr): ConnectInfo<SocketAddr>,
+    headers: HeaderMap,
+    Json(input): Json<UpdateMaintenanceInput>,
+) -> Result<Json<MaintenanceResponse>, ApiError> {
+    let updated = state.maintenance.update(input).await.map_err(map_error)?;
+    tracing::info!(
+        user_id = user.0.id,
+        enabled = updated.enabled,
+        "maintenance mode changed"
+    );
+    Ok(Json(MaintenanceResponse::new(
+        updated,
+        extract_client_ip(&headers, addr),
+    )))
+}
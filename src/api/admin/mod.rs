@@ -12,20 +12,40 @@
 //! - 6.1: Theme switching
 
 mod ai;
+mod announcements;
+mod api_exposure;
 mod backup;
 mod comments;
 mod dashboard;
+mod duplicates;
+mod email;
+mod events;
 mod files;
+mod import;
+mod jobs;
+mod license;
+mod link_suggestions;
+mod maintenance;
+mod newsletter;
+mod preview;
+mod publish_checklist;
 mod reload;
+mod robots;
 mod security;
 mod settings;
+mod spellcheck;
+mod stats;
 mod taxonomy;
 mod themes;
+mod traffic;
 mod update;
+mod users;
+mod webhooks;
 
 pub use comments::{
-    approve_comment, list_comments, list_pending_comments, reject_comment, AdminCommentResponse,
-    AdminCommentsResponse, CommentsQuery,
+    approve_comment, export_comments, list_comments, list_pending_comments, reject_comment,
+    search_comments, AdminCommentResponse, AdminCommentSearchResponse, AdminCommentsResponse,
+    CommentExportQuery, CommentSearchQuery, CommentsQuery,
 };
 pub use security::{LoginLogEntry, LoginLogsQuery, LoginLogsResponse};
 pub use update::APP_VERSION;
@@ -43,8 +63,19 @@ pub fn router() -> Router<AppState> {
         .route("/dashboard", get(dashboard::get_dashboard))
         // Built-in About profile
         .nest("/about", crate::api::about::admin_router())
+        // Live events (SSE)
+        .route("/events", get(events::stream_events))
         // System stats
         .route("/stats", get(dashboard::get_system_stats))
+        .route("/stats/export/traffic", get(stats::export_traffic))
+        .route("/stats/export/top-content", get(stats::export_top_content))
+        .route("/stats/export/comments", get(stats::export_comments))
+        // Built-in analytics
+        .route("/stats/traffic", get(traffic::get_traffic))
+        .route("/stats/traffic/pages", get(traffic::top_pages))
+        .route("/stats/traffic/posts", get(traffic::top_posts))
+        .route("/stats/traffic/referrers", get(traffic::top_referrers))
+        .route("/stats/traffic/countries", get(traffic::top_countries))
         // Update check
         .route("/update-check", get(update::check_update))
         .route("/update-perform", post(update::perform_update))
@@ -52,9 +83,13 @@ pub fn router() -> Router<AppState> {
         .route("/categories", post(taxonomy::create_category))
         .route("/categories/{id}", put(taxonomy::update_category))
         .route("/categories/{id}", delete(taxonomy::delete_category))
+        .route("/categories/order", put(taxonomy::reorder_categories))
+        .route("/categories/{id}/move", put(taxonomy::move_category))
         // Tag management
         .route("/tags", post(taxonomy::create_tag))
+        .route("/tags/{id}", put(taxonomy::rename_tag))
         .route("/tags/{id}", delete(taxonomy::delete_tag))
+        .route("/tags/{id}/merge", post(taxonomy::merge_tags))
         // Theme management
         .route("/themes", get(themes::list_themes))
         .route("/themes/switch", post(themes::switch_theme))
@@ -70,18 +105,136 @@ pub fn router() -> Router<AppState> {
         // Comment management
         .route("/comments", get(list_comments))
         .route("/comments/pending", get(list_pending_comments))
+        .route("/comments/search", get(search_comments))
+        .route("/comments/export", get(export_comments))
         .route("/comments/{id}/approve", post(approve_comment))
         .route("/comments/{id}/reject", post(reject_comment))
+        // Email diagnostics
+        .route("/email/test", post(email::send_test_email))
+        .route(
+            "/email/suppressions",
+            get(email::list_suppressions).post(email::add_suppression),
+        )
+        .route(
+            "/email/suppressions/{email}",
+            delete(email::remove_suppression),
+        )
+        // Editor preview with plugin content filters
+        .route("/preview", post(preview::preview))
+        // Draft spell check
+        .route(
+            "/spellcheck",
+            get(spellcheck::list_languages).post(spellcheck::check_draft),
+        )
+        // Internal link suggestions for drafts
+        .route("/link-suggestions", post(link_suggestions::suggest_links))
+        // Newsletter subscribers and sending
+        .route("/newsletter/subscribers", get(newsletter::list_subscribers))
+        .route(
+            "/newsletter/subscribers/{id}",
+            delete(newsletter::delete_subscriber),
+        )
+        .route(
+            "/newsletter/articles/{id}",
+            get(newsletter::get_article_issue),
+        )
+        .route(
+            "/newsletter/articles/{id}/send",
+            post(newsletter::send_article),
+        )
+        // Public endpoint exposure
+        .route(
+            "/api-exposure",
+            get(api_exposure::get_api_exposure).put(api_exposure::update_api_exposure),
+        )
+        // Maintenance mode
+        .route(
+            "/maintenance",
+            get(maintenance::get_maintenance).put(maintenance::update_maintenance),
+        )
+        // Announcement banners
+        .route(
+            "/announcements",
+            get(announcements::get_announcements).put(announcements::update_announcements),
+        )
+        // Near-duplicate articles
+        .route("/duplicates", get(duplicates::get_report))
+        .route("/duplicates/scan", post(duplicates::start_scan))
+        // Requirements for publishing articles
+        .route(
+            "/publish-checklist",
+            get(publish_checklist::get_publish_checklist)
+                .put(publish_checklist::update_publish_checklist),
+        )
+        // License of articles without their own
+        .route(
+            "/license",
+            get(license::get_default_license).put(license::update_default_license),
+        )
+        // robots.txt rules
+        .route(
+            "/robots",
+            get(robots::get_robots).put(robots::update_robots),
+        )
+        // Inbound webhooks for plugin integrations
+        .route(
+            "/webhooks/inbound",
+            get(webhooks::list_inbound).post(webhooks::create_inbound),
+        )
+        .route("/webhooks/inbound/{id}", delete(webhooks::delete_inbound))
+        // Background jobs and periodic tasks
+        .route("/jobs", get(jobs::list_jobs))
+        .route("/jobs/{id}", get(jobs::get_job))
+        .route("/jobs/{id}/retry", post(jobs::retry_job))
+        .route("/jobs/{id}/cancel", post(jobs::cancel_job))
+        .route("/jobs/queue", get(jobs::list_queued_jobs))
+        .route(
+            "/jobs/queue/{id}",
+            get(jobs::get_queued_job).delete(jobs::delete_queued_job),
+        )
+        .route("/jobs/queue/{id}/retry", post(jobs::retry_queued_job))
+        .route("/schedules", get(jobs::list_schedules))
         // Login logs (security)
         .route("/login-logs", get(security::list_login_logs))
+        .route("/ip-reputation", get(security::get_ip_reputation))
+        .route(
+            "/ip-reputation/overrides",
+            put(security::update_ip_overrides),
+        )
+        .route("/ip-reputation/{ip}", delete(security::reset_ip_reputation))
+        // User access management
+        .route("/users/provision", post(users::provision_users))
+        .route("/users/{id}/role", put(users::update_role))
+        .route(
+            "/users/{id}/force-password-reset",
+            post(users::force_password_reset),
+        )
+        .route("/users/{id}/sessions", delete(users::revoke_sessions))
         // Backup & Restore
         .route("/backup", get(backup::download_backup))
+        .route(
+            "/backup/archives",
+            get(backup::list_archives).post(backup::create_archive),
+        )
+        .route(
+            "/backup/archives/{name}",
+            get(backup::download_archive).delete(backup::delete_archive),
+        )
+        .route(
+            "/backup/archives/{name}/restore",
+            post(backup::restore_archive),
+        )
         .route("/backup/restore", post(backup::restore_backup_endpoint))
         .route(
             "/backup/export-markdown",
             get(backup::export_markdown_endpoint),
         )
         .route("/backup/import", post(backup::import_articles_endpoint))
+        .route("/export", get(backup::export_bundle_endpoint))
+        // Content import from other platforms
+        .route("/import/comments", post(import::import_comments_endpoint))
+        .route("/import/wordpress", post(import::import_wordpress_endpoint))
+        .route("/import/markdown", post(import::import_markdown_endpoint))
         // File management
         .route("/files", get(files::list_files))
         .route("/files/stats", get(files::get_storage_stats))
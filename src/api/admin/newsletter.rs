@@ -0,0 +1,152 @@
+//! Newsletter subscriber and sending endpoints
+
+use axum::{
+    extract::{Path, Query, State},
+    http::StatusCode,
+    Json,
+};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+use crate::api::common::{default_page_i64, default_per_page};
+use crate::api::middleware::{ApiError, AppState, AuthenticatedUser};
+use crate::models::{NewsletterIssue, Subscriber, SubscriberStatus};
+use crate::services::NewsletterError;
+
+/// Query parameters for the subscriber list
+#[derive(Debug, Deserialize)]
+pub struct SubscriberListQuery {
+    /// `pending`, `active` or `unsubscribed`; all when missing
+    pub status: Option<String>,
+    #[serde(default = "default_page_i64")]
+    pub page: i64,
+    #[serde(default = "default_per_page")]
+    pub per_page: i64,
+}
+
+/// Response for the subscriber list
+#[derive(Debug, Serialize)]
+pub struct SubscriberListResponse {
+    pub subscribers: Vec<Subscriber>,
+    pub total: i64,
+    pub page: i64,
+    pub per_page: i64,
+    /// Number of subscribers per status
+    pub counts: BTreeMap<SubscriberStatus, i64>,
+}
+
+/// Request body for sending an article
+#[derive(Debug, Default, Deserialize)]
+pub struct SendArticleRequest {
+    /// Send again although the article was sent before
+    #[serde(default)]
+    pub resend: bool,
+}
+
+/// GET /api/v1/admin/newsletter/subscribers - List subscribers
+///
+/// Requires admin authentication.
+pub async fn list_subscribers(
+    State(state): State<AppState>,
+    _user: AuthenticatedUser,
+    Query(query): Query<SubscriberListQuery>,
+) -> Result<Json<SubscriberListResponse>, ApiError> {
+    let status = query
+        .status
+        .as_deref()
+        .filter(|s| !s.is_empty())
+        .map(str::parse::<SubscriberStatus>)
+        .transpose()
+        .map_err(ApiError::validation_error)?;
+    let page = query.page.max(1);
+    let per_page = query.per_page.clamp(1, 100);
+
+    let service = &state.newsletter_service;
+    let (subscribers, total) = service
+        .list(status, page, per_page)
+        .await
+        .map_err(|e| ApiError::internal_error(e.to_string()))?;
+    let counts = service
+        .counts()
+        .await
+        .map_err(|e| ApiError::internal_error(e.to_string()))?
+        .into_iter()
+        .collect();
+
+    Ok(Json(SubscriberListResponse {
+        subscribers,
+        total,
+        page,
+        per_page,
+        counts,
+    }))
+}
+
+/// DELETE /api/v1/admin/newsletter/subscribers/{id} - Remove a subscriber
+///
+/// Requires admin authentication.
+pub async fn delete_subscriber(
+    State(state): State<AppState>,
+    _user: AuthenticatedUser,
+    Path(id): Path<i64>,
+) -> Result<StatusCode, ApiError> {
+    let removed = state
+        .newsletter_service
+        .delete(id)
+        .await
+        .map_err(|e| ApiError::internal_error(e.to_string()))?;
+
+    if removed {
+        Ok(StatusCode::NO_CONTENT)
+    } else {
+        Err(ApiError::not_found("Subscriber not found"))
+    }
+}
+
+/// GET /api/v1/admin/newsletter/articles/{id} - When an article was sent
+///
+/// Returns `null` for articles that were never sent. Requires admin
+/// authentication.
+pub async fn get_article_issue(
+    State(state): State<AppState>,
+    _user: AuthenticatedUser,
+    Path(id): Path<i64>,
+) -> Result<Json<Option<NewsletterIssue>>, ApiError> {
+    let issue = state
+        .newsletter_service
+        .issue(id)
+        .await
+        .map_err(|e| ApiError::internal_error(e.to_string()))?;
+    Ok(Json(issue))
+}
+
+/// POST /api/v1/admin/newsletter/articles/{id}/send - Send an article to all subscribers
+///
+/// Queues one email per active subscriber. Articles that were sent before
+/// answer 409 unless `resend` is set. Requires admin authentication.
+pub async fn send_article(
+    State(state): State<AppState>,
+    _user: AuthenticatedUser,
+    Path(id): Path<i64>,
+    body: Option<Json<SendArticleRequest>>,
+) -> Result<(StatusCode, Json<NewsletterIssue>), ApiError> {
+    let Json(body) = body.unwrap_or_default();
+    let issue = state
+        .newsletter_service
+        .send_article(id, body.resend)
+        .await
+        .map_err(|e| match e {
+            NewsletterError::ArticleNotFound => ApiError::not_found(e.to_string()),
+            NewsletterError::NotPublished
+            | NewsletterError::NotPublic
+            | NewsletterError::SiteUrlMissing => ApiError::validation_error(e.to_string()),
+            NewsletterError::AlreadySent(sent_at) => ApiError::with_details(
+                "CONFLICT",
+                e.to_string(),
+                serde_json::json!({ "sent_at": sent_at }),
+            ),
+            e => ApiError::internal_error(e.to_string()),
+        })?;
+
+    Ok((StatusCode::ACCEPTED, Json(issue)))
+}
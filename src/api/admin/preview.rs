@@ -0,0 +1,55 @@
+//! Live editor preview
+//!
+//! Unlike `POST /site/render`, the preview also runs the
+//! `article_content_filter` hook, so plugin content filters show up the way
+//! they will in the published article.
+
+use axum::{extract::State, Json};
+use serde::{Deserialize, Serialize};
+
+use crate::api::middleware::{AppState, AuthenticatedUser};
+use crate::models::InputFormat;
+use crate::services::markdown::TocEntry;
+
+/// Request body for a preview
+#[derive(Debug, Deserialize)]
+pub struct PreviewRequest {
+    pub content: String,
+    /// Article input format; Markdown when missing or unknown
+    #[serde(default)]
+    pub input_format: Option<String>,
+    /// The article being edited, passed to hooks and shortcodes; 0 or
+    /// missing for a new article
+    #[serde(default)]
+    pub article_id: i64,
+}
+
+/// Rendered preview
+#[derive(Debug, Serialize)]
+pub struct PreviewResponse {
+    pub html: String,
+    pub toc: Vec<TocEntry>,
+}
+
+/// POST /api/v1/admin/preview - Render a draft as it would be published
+///
+/// Requires admin authentication.
+pub async fn preview(
+    State(state): State<AppState>,
+    _user: AuthenticatedUser,
+    Json(req): Json<PreviewRequest>,
+) -> Json<PreviewResponse> {
+    let input_format = req
+        .input_format
+        .as_deref()
+        .and_then(InputFormat::parse)
+        .unwrap_or_default();
+    let html = state
+        .article_service
+        .render_filtered(input_format, &req.content, req.article_id);
+    // Published articles take their ToC from the unfiltered source as well
+    let toc = state
+        .article_service
+        .extract_source_toc(input_format, &req.content);
+    Json(PreviewResponse { html, toc })
+}
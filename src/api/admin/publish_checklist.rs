@@ -0,0 +1,36 @@
+//! Publish checklist settings
+
+use axum::{extract::State, Json};
+
+use crate::api::middleware::{ApiError, AppState, AuthenticatedUser};
+use crate::services::publish_checklist::{self, PublishChecklist, PublishChecklistError};
+
+/// GET /api/v1/admin/publish-checklist - Get the publish checklist
+///
+/// Requires admin authentication.
+pub async fn get_publish_checklist(
+    State(state): State<AppState>,
+    _user: AuthenticatedUser,
+) -> Json<PublishChecklist> {
+    Json(publish_checklist::load(&state.settings_service).await)
+}
+
+/// PUT /api/v1/admin/publish-checklist - Replace the publish checklist
+///
+/// Body: `{"mode": "block", "items": ["thumbnail", "category", "excerpt", "seo"]}`;
+/// `mode` is `off`, `warn` or `block`.
+/// Requires admin authentication.
+pub async fn update_publish_checklist(
+    State(state): State<AppState>,
+    user: AuthenticatedUser,
+    Json(input): Json<PublishChecklist>,
+) -> Result<Json<PublishChecklist>, ApiError> {
+    let config = publish_checklist::save(&state.settings_service, input)
+        .await
+        .map_err(|e| match e {
+            PublishChecklistError::Validation(msg) => ApiError::validation_error(msg),
+            PublishChecklistError::Internal(msg) => ApiError::internal_error(msg),
+        })?;
+    tracing::info!(user_id = user.0.id, "publish checklist changed");
+    Ok(Json(config))
+}
@@ -0,0 +1,62 @@
+//! robots.txt settings
+
+use axum::{extract::State, Json};
+use serde::Serialize;
+
+use crate::api::middleware::{ApiError, AppState, AuthenticatedUser};
+use crate::services::robots::{self, AiCrawler, RobotsConfig, RobotsError, AI_CRAWLERS};
+use crate::services::settings::keys;
+
+/// The rules, the crawlers that can be blocked and the resulting file
+#[derive(Debug, Serialize)]
+pub struct RobotsResponse {
+    pub config: RobotsConfig,
+    pub ai_crawlers: &'static [AiCrawler],
+    pub preview: String,
+}
+
+async fn respond(state: &AppState, config: RobotsConfig) -> Json<RobotsResponse> {
+    let site_url = state
+        .settings_service
+        .get(keys::SITE_URL)
+        .await
+        .ok()
+        .flatten()
+        .unwrap_or_default();
+    Json(RobotsResponse {
+        preview: config.render(&site_url),
+        config,
+        ai_crawlers: AI_CRAWLERS,
+    })
+}
+
+/// GET /api/v1/admin/robots - Get the robots.txt rules
+///
+/// Requires admin authentication.
+pub async fn get_robots(
+    State(state): State<AppState>,
+    _user: AuthenticatedUser,
+) -> Json<RobotsResponse> {
+    let config = robots::load(&state.settings_service).await;
+    respond(&state, config).await
+}
+
+/// PUT /api/v1/admin/robots - Replace the robots.txt rules
+///
+/// Body: `{"rules": [{"user_agent": "*", "disallow": ["/drafts/"]}],
+/// "blocked_ai_crawlers": ["GPTBot"], "include_sitemap": true, "extra": ""}`.
+/// Requires admin authentication.
+pub async fn update_robots(
+    State(state): State<AppState>,
+    user: AuthenticatedUser,
+    Json(input): Json<RobotsConfig>,
+) -> Result<Json<RobotsResponse>, ApiError> {
+    let config = robots::save(&state.settings_service, input)
+        .await
+        .map_err(|e| match e {
+            RobotsError::Validation(msg) => ApiError::validation_error(msg),
+            RobotsError::Internal(msg) => ApiError::internal_error(msg),
+        })?;
+    tracing::info!(user_id = user.0.id, "robots.txt rules changed");
+    Ok(respond(&state, config).await)
+}
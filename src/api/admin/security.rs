@@ -1,13 +1,16 @@
 //! Login logs (security) endpoints
 
 use axum::{
-    extract::{Query, State},
+    extract::{Path, Query, State},
+    http::StatusCode,
     Json,
 };
 use serde::{Deserialize, Serialize};
+use std::net::IpAddr;
 
 use crate::api::common::{default_page_i64, default_per_page};
 use crate::api::middleware::{ApiError, AppState, AuthenticatedUser};
+use crate::services::ip_reputation::{keys, parse_ip_list, IpReputation};
 
 /// Login log entry
 #[derive(Debug, Serialize)]
@@ -337,3 +340,106 @@ pub async fn list_login_logs(
         failed_count,
     }))
 }
+
+/// Response for the IP reputation overview
+#[derive(Debug, Serialize)]
+pub struct IpReputationResponse {
+    pub entries: Vec<IpReputation>,
+    pub allowlist: Vec<IpAddr>,
+    pub blocklist: Vec<IpAddr>,
+}
+
+/// Request body for updating the admin override lists
+#[derive(Debug, Deserialize)]
+pub struct UpdateIpOverridesRequest {
+    pub allowlist: Vec<String>,
+    pub blocklist: Vec<String>,
+}
+
+/// GET /api/v1/admin/ip-reputation - Tracked addresses and override lists
+pub async fn get_ip_reputation(
+    State(state): State<AppState>,
+    _user: AuthenticatedUser,
+) -> Result<Json<IpReputationResponse>, ApiError> {
+    Ok(Json(IpReputationResponse {
+        entries: state.ip_reputation.list().await,
+        allowlist: read_ip_list(&state, keys::IP_ALLOWLIST).await?,
+        blocklist: read_ip_list(&state, keys::IP_BLOCKLIST).await?,
+    }))
+}
+
+/// PUT /api/v1/admin/ip-reputation/overrides - Replace the allow/block lists
+pub async fn update_ip_overrides(
+    State(state): State<AppState>,
+    _user: AuthenticatedUser,
+    Json(body): Json<UpdateIpOverridesRequest>,
+) -> Result<Json<IpReputationResponse>, ApiError> {
+    let allowlist = parse_ip_entries(&body.allowlist)?;
+    let blocklist = parse_ip_entries(&body.blocklist)?;
+    if let Some(ip) = allowlist.iter().find(|ip| blocklist.contains(ip)) {
+        return Err(ApiError::validation_error(format!(
+            "{} cannot be on both the allowlist and the blocklist",
+            ip
+        )));
+    }
+
+    for (key, list) in [
+        (keys::IP_ALLOWLIST, &allowlist),
+        (keys::IP_BLOCKLIST, &blocklist),
+    ] {
+        let value = list
+            .iter()
+            .map(IpAddr::to_string)
+            .collect::<Vec<_>>()
+            .join("\n");
+        state
+            .settings_service
+            .set(key, &value)
+            .await
+            .map_err(|e| ApiError::internal_error(e.to_string()))?;
+    }
+
+    Ok(Json(IpReputationResponse {
+        entries: state.ip_reputation.list().await,
+        allowlist,
+        blocklist,
+    }))
+}
+
+/// DELETE /api/v1/admin/ip-reputation/{ip} - Forget the score of an address
+pub async fn reset_ip_reputation(
+    State(state): State<AppState>,
+    _user: AuthenticatedUser,
+    Path(ip): Path<String>,
+) -> Result<StatusCode, ApiError> {
+    let ip: IpAddr = ip
+        .parse()
+        .map_err(|_| ApiError::validation_error("Invalid IP address"))?;
+    if state.ip_reputation.reset(ip).await {
+        Ok(StatusCode::NO_CONTENT)
+    } else {
+        Err(ApiError::not_found("IP address is not tracked"))
+    }
+}
+
+async fn read_ip_list(state: &AppState, key: &str) -> Result<Vec<IpAddr>, ApiError> {
+    let value = state
+        .settings_service
+        .get(key)
+        .await
+        .map_err(|e| ApiError::internal_error(e.to_string()))?;
+    Ok(value.as_deref().map(parse_ip_list).unwrap_or_default())
+}
+
+fn parse_ip_entries(entries: &[String]) -> Result<Vec<IpAddr>, ApiError> {
+    let mut ips = Vec::with_capacity(entries.len());
+    for entry in entries.iter().map(|e| e.trim()).filter(|e| !e.is_empty()) {
+        let ip: IpAddr = entry
+            .parse()
+            .map_err(|_| ApiError::validation_error(format!("Invalid IP address: {}", entry)))?;
+        if !ips.contains(&ip) {
+            ips.push(ip);
+        }
+    }
+    Ok(ips)
+}
@@ -4,6 +4,7 @@ use axum::{extract::State, Json};
 use serde::Serialize;
 
 use crate::api::middleware::{ApiError, AppState, AuthenticatedUser};
+use crate::services::settings::keys;
 
 /// Request for updating site settings (supports dynamic fields)
 pub type SiteSettingsRequest = std::collections::HashMap<String, String>;
@@ -88,6 +89,13 @@ pub async fn update_settings(
             .map_err(|e| ApiError::internal_error(e.to_string()))?;
     }
 
+    if let Some(locale) = body.get(keys::DEFAULT_LOCALE) {
+        match state.theme_engine.write() {
+            Ok(mut engine) => engine.set_locale(Some(locale)),
+            Err(e) => tracing::warn!("Failed to update theme locale: {}", e),
+        }
+    }
+
     // Return updated settings
     let settings = state
         .settings_service
@@ -0,0 +1,63 @@
+//! Draft spell check endpoints
+//!
+//! See [`crate::services::spellcheck`] for the checks and offsets.
+
+use axum::{extract::State, Json};
+use serde::{Deserialize, Serialize};
+
+use crate::api::middleware::{ApiError, AppState, AuthenticatedUser};
+use crate::services::spellcheck::SpellcheckReport;
+
+/// Longest draft accepted, in bytes
+const MAX_CONTENT_BYTES: usize = 512 * 1024;
+
+/// Request body for checking a draft
+#[derive(Debug, Deserialize)]
+pub struct SpellcheckRequest {
+    pub content: String,
+    /// ISO 639-3 code; detected when missing
+    pub language: Option<String>,
+}
+
+/// Response for the dictionary list
+#[derive(Debug, Serialize)]
+pub struct SpellcheckLanguagesResponse {
+    pub languages: Vec<&'static str>,
+}
+
+/// GET /api/v1/admin/spellcheck - Languages with a dictionary
+///
+/// Requires admin authentication.
+pub async fn list_languages(
+    State(state): State<AppState>,
+    _user: AuthenticatedUser,
+) -> Json<SpellcheckLanguagesResponse> {
+    Json(SpellcheckLanguagesResponse {
+        languages: state.spellcheck.languages(),
+    })
+}
+
+/// POST /api/v1/admin/spellcheck - Check a draft
+///
+/// Requires admin authentication.
+pub async fn check_draft(
+    State(state): State<AppState>,
+    _user: AuthenticatedUser,
+    Json(req): Json<SpellcheckRequest>,
+) -> Result<Json<SpellcheckReport>, ApiError> {
+    if req.content.len() > MAX_CONTENT_BYTES {
+        return Err(ApiError::validation_error(format!(
+            "Content is larger than {} KB",
+            MAX_CONTENT_BYTES / 1024
+        )));
+    }
+    let spellcheck = state.spellcheck.clone();
+    // Suggestions are CPU-bound, keep them off the async workers
+    let report = tokio::task::spawn_blocking(move || {
+        spellcheck.check(&req.content, req.language.as_deref())
+    })
+    .await
+    .map_err(|e| ApiError::internal_error(e.to_string()))?
+    .map_err(|e| ApiError::validation_error(e.to_string()))?;
+    Ok(Json(report))
+}
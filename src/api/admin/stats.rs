@@ -0,0 +1,269 @@
+//! Statistics CSV export endpoints
+//!
+//! All exports take `from` and `to` as `YYYY-MM-DD` (UTC, both inclusive)
+//! and default to the last 30 days. Bodies are streamed in batches so large
+//! ranges do not build the whole file in memory.
+
+use axum::{
+    body::{Body, Bytes},
+    extract::{Query, State},
+    http::header,
+    response::{IntoResponse, Response},
+};
+use chrono::{Duration, NaiveDate, Utc};
+use serde::Deserialize;
+use std::sync::Arc;
+
+use crate::api::common::csv_line;
+use crate::api::middleware::{ApiError, AppState, AuthenticatedUser};
+use crate::services::stats::{StatsService, MAX_RANGE_DAYS};
+
+/// Days per batch for the daily series
+const DAYS_PER_BATCH: i64 = 92;
+/// Articles per batch for the top content ranking
+const ARTICLES_PER_BATCH: i64 = 500;
+const DEFAULT_RANGE_DAYS: i64 = 30;
+
+/// Query params for statistics exports
+#[derive(Debug, Deserialize)]
+pub struct StatsExportQuery {
+    /// First day, inclusive
+    pub from: Option<String>,
+    /// Last day, inclusive
+    pub to: Option<String>,
+    /// Top content only: maximum number of articles (default: all)
+    pub limit: Option<i64>,
+}
+
+#[derive(Debug, Clone, Copy)]
+enum Report {
+    Traffic,
+    Comments,
+    TopContent { limit: i64 },
+}
+
+impl Report {
+    fn name(self) -> &'static str {
+        match self {
+            Report::Traffic => "traffic",
+            Report::Comments => "comments",
+            Report::TopContent { .. } => "top-content",
+        }
+    }
+
+    fn header(self) -> &'static str {
+        match self {
+            Report::Traffic => "date,views,articles_viewed\r\n",
+            Report::Comments => "date,approved,pending,spam,total\r\n",
+            Report::TopContent { .. } => {
+                "rank,article_id,slug,title,views,total_views,comments\r\n"
+            }
+        }
+    }
+}
+
+/// GET /api/v1/admin/stats/export/traffic - Daily article views as CSV
+pub async fn export_traffic(
+    State(state): State<AppState>,
+    _user: AuthenticatedUser,
+    Query(query): Query<StatsExportQuery>,
+) -> Result<Response, ApiError> {
+    export(state, &query, Report::Traffic)
+}
+
+/// GET /api/v1/admin/stats/export/comments - Daily comment counts as CSV
+pub async fn export_comments(
+    State(state): State<AppState>,
+    _user: AuthenticatedUser,
+    Query(query): Query<StatsExportQuery>,
+) -> Result<Response, ApiError> {
+    export(state, &query, Report::Comments)
+}
+
+/// GET /api/v1/admin/stats/export/top-content - Articles ranked by views as CSV
+pub async fn export_top_content(
+    State(state): State<AppState>,
+    _user: AuthenticatedUser,
+    Query(query): Query<StatsExportQuery>,
+) -> Result<Response, ApiError> {
+    let limit = match query.limit {
+        Some(limit) if limit < 1 => {
+            return Err(ApiError::validation_error("limit must be positive"))
+        }
+        Some(limit) => limit,
+        None => i64::MAX,
+    };
+    export(state, &query, Report::TopContent { limit })
+}
+
+fn export(state: AppState, query: &StatsExportQuery, report: Report) -> Result<Response, ApiError> {
+    let (from, to) = parse_range(query.from.as_deref(), query.to.as_deref())?;
+    let filename = format!(
+        "{}-{}-{}.csv",
+        report.name(),
+        from.format("%Y%m%d"),
+        (to - Duration::days(1)).format("%Y%m%d")
+    );
+
+    let cursor = StatsCursor {
+        service: state.stats_service.clone(),
+        report,
+        from,
+        to,
+        next_day: from,
+        written: 0,
+        started: false,
+        finished: false,
+    };
+    let stream = futures::stream::unfold(cursor, |mut cursor| async move {
+        if cursor.finished {
+            return None;
+        }
+        let chunk = cursor.next_chunk().await;
+        Some((chunk, cursor))
+    });
+
+    Ok((
+        [
+            (header::CONTENT_TYPE, "text/csv; charset=utf-8".to_string()),
+            (
+                header::CONTENT_DISPOSITION,
+                format!("attachment; filename=\"{}\"", filename),
+            ),
+        ],
+        Body::from_stream(stream),
+    )
+        .into_response())
+}
+
+/// Resolve inclusive `from` and `to` query values to a `[from, to)` day
+/// range, the last 30 days by default
+pub(super) fn parse_range(
+    from: Option<&str>,
+    to: Option<&str>,
+) -> Result<(NaiveDate, NaiveDate), ApiError> {
+    let parse = |value: &str| {
+        NaiveDate::parse_from_str(value.trim(), "%Y-%m-%d")
+            .map_err(|_| ApiError::validation_error(format!("Invalid date: {}", value)))
+    };
+    let last = match to.filter(|v| !v.trim().is_empty()) {
+        Some(value) => parse(value)?,
+        None => Utc::now().date_naive(),
+    };
+    let first = match from.filter(|v| !v.trim().is_empty()) {
+        Some(value) => parse(value)?,
+        None => last - Duration::days(DEFAULT_RANGE_DAYS - 1),
+    };
+    if first > last {
+        return Err(ApiError::validation_error("from must not be after to"));
+    }
+    if (last - first).num_days() >= MAX_RANGE_DAYS {
+        return Err(ApiError::validation_error(format!(
+            "Range is limited to {} days",
+            MAX_RANGE_DAYS
+        )));
+    }
+    Ok((first, last + Duration::days(1)))
+}
+
+/// Streaming state for the statistics exports
+struct StatsCursor {
+    service: Arc<StatsService>,
+    report: Report,
+    from: NaiveDate,
+    to: NaiveDate,
+    /// Daily series: first day of the next batch
+    next_day: NaiveDate,
+    /// Top content: articles written so far
+    written: i64,
+    started: bool,
+    finished: bool,
+}
+
+impl StatsCursor {
+    /// Produce the next body chunk: the header on the first call, then one
+    /// batch of rows.
+    async fn next_chunk(&mut self) -> Result<Bytes, std::io::Error> {
+        let mut chunk = String::new();
+        if !self.started {
+            self.started = true;
+            chunk.push_str(self.report.header());
+        }
+        let result = self.fill(&mut chunk).await;
+        if let Err(e) = result {
+            self.finished = true;
+            tracing::error!(report = self.report.name(), error = %e, "stats export failed");
+            return Err(std::io::Error::other(e.to_string()));
+        }
+        Ok(Bytes::from(chunk))
+    }
+
+    async fn fill(&mut self, chunk: &mut String) -> anyhow::Result<()> {
+        match self.report {
+            Report::Traffic | Report::Comments => {
+                let end = (self.next_day + Duration::days(DAYS_PER_BATCH)).min(self.to);
+                if matches!(self.report, Report::Traffic) {
+                    for day in self.service.traffic(self.next_day, end).await? {
+                        chunk.push_str(&csv_line(&[
+                            day.day,
+                            day.views.to_string(),
+                            day.articles.to_string(),
+                        ]));
+                    }
+                } else {
+                    for day in self.service.comments(self.next_day, end).await? {
+                        chunk.push_str(&csv_line(&[
+                            day.day,
+                            day.approved.to_string(),
+                            day.pending.to_string(),
+                            day.spam.to_string(),
+                            (day.approved + day.pending + day.spam).to_string(),
+                        ]));
+                    }
+                }
+                self.next_day = end;
+                self.finished = end >= self.to;
+            }
+            Report::TopContent { limit } => {
+                let batch_size = ARTICLES_PER_BATCH.min(limit - self.written);
+                let batch = self
+                    .service
+                    .top_content(self.from, self.to, self.written, batch_size)
+                    .await?;
+                for article in &batch {
+                    self.written += 1;
+                    chunk.push_str(&csv_line(&[
+                        self.written.to_string(),
+                        article.article_id.to_string(),
+                        article.slug.clone(),
+                        article.title.clone(),
+                        article.views.to_string(),
+                        article.total_views.to_string(),
+                        article.comments.to_string(),
+                    ]));
+                }
+                self.finished = (batch.len() as i64) < batch_size || self.written >= limit;
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn range_includes_both_days() {
+        let (from, to) = parse_range(Some("2024-02-28"), Some("2024-03-01")).unwrap();
+        assert_eq!(from, NaiveDate::from_ymd_opt(2024, 2, 28).unwrap());
+        assert_eq!(to, NaiveDate::from_ymd_opt(2024, 3, 2).unwrap());
+
+        let (from, to) = parse_range(None, None).unwrap();
+        assert_eq!((to - from).num_days(), DEFAULT_RANGE_DAYS);
+
+        assert!(parse_range(Some("2024-03-02"), Some("2024-03-01")).is_err());
+        assert!(parse_range(Some("2000-01-01"), Some("2024-03-01")).is_err());
+        assert!(parse_range(Some("03/01/2024"), None).is_err());
+    }
+}
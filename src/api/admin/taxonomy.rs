@@ -1,14 +1,17 @@
 //! Category and tag management endpoints
 
 use axum::{
-    extract::{Path, State},
+    extract::{Path, Query, State},
     http::StatusCode,
     Json,
 };
 use serde::{Deserialize, Serialize};
 
 use crate::api::middleware::{ApiError, AppState, AuthenticatedUser};
+use crate::models::{CategoryAccess, CategoryTree};
+use crate::plugin::hook_names;
 use crate::services::category::CategoryServiceError;
+use crate::services::tag::TagServiceError;
 use serde_json::json;
 
 /// Request for creating/updating a category
@@ -20,6 +23,9 @@ pub struct CategoryRequest {
     pub description: Option<String>,
     #[serde(default)]
     pub parent_id: Option<Option<i64>>,
+    /// `public`, `members`, `editors` or `admins`; unchanged on update
+    /// when missing
+    pub access: Option<CategoryAccess>,
 }
 
 fn map_category_error(error: CategoryServiceError) -> ApiError {
@@ -40,11 +46,56 @@ fn map_category_error(error: CategoryServiceError) -> ApiError {
         }
         CategoryServiceError::CannotDeleteDefault
         | CategoryServiceError::ValidationError(_)
-        | CategoryServiceError::CircularReference => ApiError::validation_error(error.to_string()),
+        | CategoryServiceError::CircularReference
+        | CategoryServiceError::TooDeep(_) => ApiError::validation_error(error.to_string()),
         CategoryServiceError::InternalError(_) => ApiError::internal_error(error.to_string()),
     }
 }
 
+/// Request for moving a category under a new parent
+#[derive(Debug, Deserialize)]
+pub struct MoveCategoryRequest {
+    /// New parent, `null` for the top level
+    pub parent_id: Option<i64>,
+    /// Index among the new siblings; appended when missing
+    pub position: Option<usize>,
+}
+
+/// Request for reordering the children of one parent
+#[derive(Debug, Deserialize)]
+pub struct ReorderCategoriesRequest {
+    /// Parent whose children are reordered, `null` for the top level
+    pub parent_id: Option<i64>,
+    /// Every child of the parent, in the new order
+    pub category_ids: Vec<i64>,
+}
+
+/// What happens to a deleted category's articles
+#[derive(Debug, Default, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DeleteArticlesAction {
+    /// Reassign them to `move_to`, or the default category
+    #[default]
+    Move,
+    /// Delete them along with the category
+    Delete,
+}
+
+/// Query params for deleting a category
+#[derive(Debug, Deserialize)]
+pub struct DeleteCategoryQuery {
+    #[serde(default)]
+    pub articles: DeleteArticlesAction,
+    /// Target category when moving articles
+    pub move_to: Option<i64>,
+}
+
+/// Response carrying the recomputed category tree
+#[derive(Debug, Serialize)]
+pub struct CategoryTreeResponse {
+    pub categories: Vec<CategoryTree>,
+}
+
 /// Response for a category
 #[derive(Debug, Serialize)]
 pub struct CategoryResponse {
@@ -53,6 +104,7 @@ pub struct CategoryResponse {
     pub name: String,
     pub description: Option<String>,
     pub parent_id: Option<i64>,
+    pub access: CategoryAccess,
     pub created_at: String,
 }
 
@@ -64,6 +116,7 @@ impl From<crate::models::Category> for CategoryResponse {
             name: cat.name,
             description: cat.description,
             parent_id: cat.parent_id,
+            access: cat.access,
             created_at: cat.created_at.to_rfc3339(),
         }
     }
@@ -73,6 +126,27 @@ impl From<crate::models::Category> for CategoryResponse {
 #[derive(Debug, Deserialize)]
 pub struct TagRequest {
     pub name: String,
+    /// Generated from the name when missing; only used when renaming
+    #[serde(default)]
+    pub slug: Option<String>,
+}
+
+/// Request for merging tags into the one in the path
+#[derive(Debug, Deserialize)]
+pub struct MergeTagsRequest {
+    /// Tags to fold into the target and delete
+    pub tag_ids: Vec<i64>,
+}
+
+fn map_tag_error(error: TagServiceError) -> ApiError {
+    match error {
+        TagServiceError::DuplicateName(_) | TagServiceError::DuplicateSlug(_) => {
+            ApiError::with_details("CONFLICT", error.to_string(), json!({}))
+        }
+        TagServiceError::NotFound(message) => ApiError::not_found(message),
+        TagServiceError::ValidationError(message) => ApiError::validation_error(message),
+        TagServiceError::InternalError(_) => ApiError::internal_error(error.to_string()),
+    }
 }
 
 /// Response for a tag
@@ -117,6 +191,10 @@ pub async fn create_category(
     } else {
         input
     };
+    let input = match body.access {
+        Some(access) => input.with_access(access),
+        None => input,
+    };
 
     let category = state
         .category_service
@@ -154,6 +232,9 @@ pub async fn update_category(
     if let Some(parent_id) = body.parent_id {
         input = input.with_parent(parent_id);
     }
+    if let Some(access) = body.access {
+        input = input.with_access(access);
+    }
 
     let category = state
         .category_service
@@ -161,22 +242,113 @@ pub async fn update_category(
         .await
         .map_err(map_category_error)?;
 
+    // Cached public listings may still hold (or lack) the category's articles
+    if body.access.is_some() {
+        state
+            .article_service
+            .invalidate_list_cache()
+            .await
+            .map_err(|e| ApiError::internal_error(e.to_string()))?;
+    }
+
     Ok(Json(category.into()))
 }
 
+/// PUT /api/v1/admin/categories/:id/move - Move category
+///
+/// Reparents the category and places it at `position` among its new
+/// siblings. Rejects cycles and trees deeper than the configured limit.
+///
+/// Requires admin authentication.
+pub async fn move_category(
+    State(state): State<AppState>,
+    _user: AuthenticatedUser,
+    Path(id): Path<i64>,
+    Json(body): Json<MoveCategoryRequest>,
+) -> Result<Json<CategoryTreeResponse>, ApiError> {
+    let categories = state
+        .category_service
+        .move_to(id, body.parent_id, body.position)
+        .await
+        .map_err(map_category_error)?;
+
+    // Articles of a category now inherit a different parent's listings
+    state
+        .article_service
+        .invalidate_list_cache()
+        .await
+        .map_err(|e| ApiError::internal_error(e.to_string()))?;
+
+    // Hook: category_after_move
+    state.hook_manager.trigger(
+        "category_after_move",
+        json!({ "id": id, "parent_id": body.parent_id, "position": body.position }),
+    );
+
+    Ok(Json(CategoryTreeResponse { categories }))
+}
+
+/// PUT /api/v1/admin/categories/order - Reorder sibling categories
+///
+/// Requires admin authentication.
+pub async fn reorder_categories(
+    State(state): State<AppState>,
+    _user: AuthenticatedUser,
+    Json(body): Json<ReorderCategoriesRequest>,
+) -> Result<Json<CategoryTreeResponse>, ApiError> {
+    let categories = state
+        .category_service
+        .reorder(body.parent_id, &body.category_ids)
+        .await
+        .map_err(map_category_error)?;
+
+    Ok(Json(CategoryTreeResponse { categories }))
+}
+
 /// DELETE /api/v1/admin/categories/:id - Delete category
 ///
+/// `?articles=move` (the default) reassigns the category's articles to
+/// `move_to` or the default category; `?articles=delete` deletes them and
+/// may not be combined with `move_to`.
+///
 /// Requires admin authentication.
 pub async fn delete_category(
     State(state): State<AppState>,
     _user: AuthenticatedUser,
     Path(id): Path<i64>,
+    Query(query): Query<DeleteCategoryQuery>,
 ) -> Result<StatusCode, ApiError> {
-    state
+    let category = state
         .category_service
-        .delete(id)
+        .get_by_id(id)
         .await
-        .map_err(map_category_error)?;
+        .map_err(map_category_error)?
+        .ok_or_else(|| ApiError::not_found(format!("Category with ID {} not found", id)))?;
+    if category.is_default() {
+        return Err(map_category_error(
+            CategoryServiceError::CannotDeleteDefault,
+        ));
+    }
+    if matches!(query.articles, DeleteArticlesAction::Delete) && query.move_to.is_some() {
+        return Err(ApiError::validation_error(
+            "move_to cannot be combined with articles=delete",
+        ));
+    }
+
+    match query.articles {
+        DeleteArticlesAction::Move => state
+            .category_service
+            .delete_into(id, query.move_to)
+            .await
+            .map_err(map_category_error)?,
+        DeleteArticlesAction::Delete => delete_category_articles(&state, id).await?,
+    }
+
+    state
+        .article_service
+        .invalidate_list_cache()
+        .await
+        .map_err(|e| ApiError::internal_error(e.to_string()))?;
 
     // Hook: category_after_delete
     state
@@ -186,6 +358,69 @@ pub async fn delete_category(
     Ok(StatusCode::NO_CONTENT)
 }
 
+/// Delete a category and the articles filed directly under it
+///
+/// The category service removes both in one transaction. Attachment rows
+/// go with the articles, so their files are looked up beforehand and
+/// deleted afterwards along with the rest of what the articles owned.
+/// Only after-delete hooks fire, once the transaction has committed, so
+/// plugins never hear about a deletion that was rolled back.
+async fn delete_category_articles(state: &AppState, id: i64) -> Result<(), ApiError> {
+    let mut doomed = Vec::new();
+    for article_id in state
+        .category_service
+        .article_ids(id)
+        .await
+        .map_err(map_category_error)?
+    {
+        let Some(article) = state
+            .article_service
+            .get_by_id(article_id)
+            .await
+            .map_err(|e| ApiError::internal_error(e.to_string()))?
+        else {
+            continue;
+        };
+        let attachments = state
+            .attachment_service
+            .list(article_id)
+            .await
+            .unwrap_or_else(|e| {
+                tracing::warn!(
+                    "Failed to list attachments of article {}: {}",
+                    article_id,
+                    e
+                );
+                Vec::new()
+            });
+        doomed.push((article, attachments));
+    }
+
+    let deleted = state
+        .category_service
+        .delete_with_articles(id)
+        .await
+        .map_err(map_category_error)?;
+
+    for article_id in deleted {
+        if let Some((article, attachments)) = doomed.iter().find(|(a, _)| a.id == article_id) {
+            state.attachment_service.remove_files(attachments).await;
+            state
+                .article_service
+                .invalidate_article_cache(article.id, &article.slug)
+                .await
+                .map_err(|e| ApiError::internal_error(e.to_string()))?;
+            state.hook_manager.trigger(
+                hook_names::ARTICLE_AFTER_DELETE,
+                json!({ "id": article.id, "title": article.title, "slug": article.slug }),
+            );
+        }
+        crate::api::articles::forget_article(state, article_id).await;
+    }
+
+    Ok(())
+}
+
 /// POST /api/v1/admin/tags - Create tag
 ///
 /// Requires admin authentication.
@@ -209,6 +444,69 @@ pub async fn create_tag(
     Ok((StatusCode::CREATED, Json(tag.into())))
 }
 
+/// PUT /api/v1/admin/tags/:id - Rename tag
+///
+/// Requires admin authentication. The old slug keeps resolving to the
+/// tag, so existing tag links redirect to the new one.
+pub async fn rename_tag(
+    State(state): State<AppState>,
+    _user: AuthenticatedUser,
+    Path(id): Path<i64>,
+    Json(body): Json<TagRequest>,
+) -> Result<Json<TagResponse>, ApiError> {
+    let tag = state
+        .tag_service
+        .rename(id, &body.name, body.slug.as_deref())
+        .await
+        .map_err(map_tag_error)?;
+
+    // Cached article lists embed the tag's name and slug
+    state
+        .article_service
+        .invalidate_list_cache()
+        .await
+        .map_err(|e| ApiError::internal_error(e.to_string()))?;
+
+    // Hook: tag_after_update
+    state.hook_manager.trigger(
+        "tag_after_update",
+        json!({ "id": tag.id, "name": tag.name, "slug": tag.slug }),
+    );
+
+    Ok(Json(tag.into()))
+}
+
+/// POST /api/v1/admin/tags/:id/merge - Merge tags into this one
+///
+/// Requires admin authentication. Articles with any of `tag_ids` get this
+/// tag instead and the merged tags are deleted; their slugs redirect here.
+pub async fn merge_tags(
+    State(state): State<AppState>,
+    _user: AuthenticatedUser,
+    Path(id): Path<i64>,
+    Json(body): Json<MergeTagsRequest>,
+) -> Result<Json<TagResponse>, ApiError> {
+    let tag = state
+        .tag_service
+        .merge(id, &body.tag_ids)
+        .await
+        .map_err(map_tag_error)?;
+
+    state
+        .article_service
+        .invalidate_list_cache()
+        .await
+        .map_err(|e| ApiError::internal_error(e.to_string()))?;
+
+    // Hook: tag_after_merge
+    state.hook_manager.trigger(
+        "tag_after_merge",
+        json!({ "id": tag.id, "merged_ids": body.tag_ids }),
+    );
+
+    Ok(Json(tag.into()))
+}
+
 /// DELETE /api/v1/admin/tags/:id - Delete tag
 ///
 /// Requires admin authentication.
@@ -0,0 +1,150 @@
+//! Traffic statistics from the built-in analytics
+//!
+//! All endpoints take `from` and `to` as `YYYY-MM-DD` (UTC, both inclusive)
+//! and default to the last 30 days. Counts are written every
+//! `analytics.flush_secs`, so the current minute may be missing. Visitors
+//! are unique per day; over a range they are the sum of the daily counts.
+
+use axum::{
+    extract::{Query, State},
+    Json,
+};
+use chrono::{Duration, NaiveDate};
+use serde::{Deserialize, Serialize};
+
+use super::stats::parse_range;
+use crate::api::middleware::{ApiError, AppState, AuthenticatedUser};
+use crate::db::repositories::{DailyVisits, RankedTraffic, TopContent, TrafficDimension};
+
+const DEFAULT_LIMIT: i64 = 10;
+const MAX_LIMIT: i64 = 100;
+
+/// Query params for traffic statistics
+#[derive(Debug, Deserialize)]
+pub struct TrafficQuery {
+    /// First day, inclusive
+    pub from: Option<String>,
+    /// Last day, inclusive
+    pub to: Option<String>,
+    /// Rankings only: number of entries (default 10, at most 100)
+    pub limit: Option<i64>,
+}
+
+impl TrafficQuery {
+    fn range(&self) -> Result<(NaiveDate, NaiveDate), ApiError> {
+        parse_range(self.from.as_deref(), self.to.as_deref())
+    }
+
+    fn limit(&self) -> Result<i64, ApiError> {
+        match self.limit {
+            Some(limit) if !(1..=MAX_LIMIT).contains(&limit) => Err(ApiError::validation_error(
+                format!("limit must be between 1 and {}", MAX_LIMIT),
+            )),
+            Some(limit) => Ok(limit),
+            None => Ok(DEFAULT_LIMIT),
+        }
+    }
+}
+
+/// Page views over time
+#[derive(Debug, Serialize)]
+pub struct TrafficOverview {
+    pub from: NaiveDate,
+    pub to: NaiveDate,
+    pub views: i64,
+    pub visitors: i64,
+    pub days: Vec<DailyVisits>,
+}
+
+/// A ranking within the requested range
+#[derive(Debug, Serialize)]
+pub struct TrafficRanking<T> {
+    pub from: NaiveDate,
+    pub to: NaiveDate,
+    pub items: Vec<T>,
+}
+
+/// GET /api/v1/admin/stats/traffic - Daily page views and visitors
+pub async fn get_traffic(
+    State(state): State<AppState>,
+    _user: AuthenticatedUser,
+    Query(query): Query<TrafficQuery>,
+) -> Result<Json<TrafficOverview>, ApiError> {
+    let (from, to) = query.range()?;
+    let days = state
+        .analytics
+        .visits(from, to)
+        .await
+        .map_err(|e| ApiError::internal_error(e.to_string()))?;
+    Ok(Json(TrafficOverview {
+        from,
+        to: to - Duration::days(1),
+        views: days.iter().map(|d| d.views).sum(),
+        visitors: days.iter().map(|d| d.visitors).sum(),
+        days,
+    }))
+}
+
+/// GET /api/v1/admin/stats/traffic/pages - Most viewed pages
+pub async fn top_pages(
+    State(state): State<AppState>,
+    _user: AuthenticatedUser,
+    Query(query): Query<TrafficQuery>,
+) -> Result<Json<TrafficRanking<RankedTraffic>>, ApiError> {
+    ranking(state, &query, TrafficDimension::Page).await
+}
+
+/// GET /api/v1/admin/stats/traffic/referrers - Top referring hosts
+pub async fn top_referrers(
+    State(state): State<AppState>,
+    _user: AuthenticatedUser,
+    Query(query): Query<TrafficQuery>,
+) -> Result<Json<TrafficRanking<RankedTraffic>>, ApiError> {
+    ranking(state, &query, TrafficDimension::Referrer).await
+}
+
+/// GET /api/v1/admin/stats/traffic/countries - Top visitor countries
+pub async fn top_countries(
+    State(state): State<AppState>,
+    _user: AuthenticatedUser,
+    Query(query): Query<TrafficQuery>,
+) -> Result<Json<TrafficRanking<RankedTraffic>>, ApiError> {
+    ranking(state, &query, TrafficDimension::Country).await
+}
+
+/// GET /api/v1/admin/stats/traffic/posts - Articles ranked by views
+pub async fn top_posts(
+    State(state): State<AppState>,
+    _user: AuthenticatedUser,
+    Query(query): Query<TrafficQuery>,
+) -> Result<Json<TrafficRanking<TopContent>>, ApiError> {
+    let (from, to) = query.range()?;
+    let items = state
+        .stats_service
+        .top_content(from, to, 0, query.limit()?)
+        .await
+        .map_err(|e| ApiError::internal_error(e.to_string()))?;
+    Ok(Json(TrafficRanking {
+        from,
+        to: to - Duration::days(1),
+        items,
+    }))
+}
+
+async fn ranking(
+    state: AppState,
+    query: &TrafficQuery,
+    dimension: TrafficDimension,
+) -> Result<Json<TrafficRanking<RankedTraffic>>, ApiError> {
+    let (from, to) = query.range()?;
+    let items = state
+        .analytics
+        .top(dimension, from, to, query.limit()?)
+        .await
+        .map_err(|e| ApiError::internal_error(e.to_string()))?;
+    Ok(Json(TrafficRanking {
+        from,
+        to: to - Duration::days(1),
+        items,
+    }))
+}
@@ -0,0 +1,209 @@
+//! User access management endpoints
+//!
+//! Changing a user's role or forcing a password reset revokes all of the
+//! user's sessions in the same transaction.
+//!
+//! `POST /api/v1/admin/users/provision` lets identity-management tooling
+//! create, update and deactivate users in bulk, keyed by email.
+
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+    Json,
+};
+use serde::{Deserialize, Serialize};
+use std::str::FromStr;
+
+use crate::api::auth::UserResponse;
+use crate::api::middleware::{ApiError, AppState, AuthenticatedUser};
+use crate::models::UserRole;
+use crate::services::user::{ProvisionOutcome, ProvisionUserInput, UserServiceError};
+
+/// Most records accepted in one provisioning request
+const MAX_PROVISION_RECORDS: usize = 500;
+
+/// Request body for changing a user's role
+#[derive(Debug, Deserialize)]
+pub struct UpdateRoleRequest {
+    pub role: String,
+}
+
+/// One user record in a provisioning request
+#[derive(Debug, Deserialize)]
+pub struct ProvisionUserRecord {
+    pub email: String,
+    /// Username for new users (defaults to the local part of the email)
+    #[serde(default)]
+    pub username: Option<String>,
+    #[serde(default)]
+    pub display_name: Option<String>,
+    /// Role to apply; existing users keep theirs and new users become
+    /// authors when omitted
+    #[serde(default)]
+    pub role: Option<String>,
+    /// `false` deactivates (bans and signs out) the user
+    #[serde(default = "default_active")]
+    pub active: bool,
+}
+
+fn default_active() -> bool {
+    true
+}
+
+/// Request body for bulk provisioning
+#[derive(Debug, Deserialize)]
+pub struct ProvisionUsersRequest {
+    pub users: Vec<ProvisionUserRecord>,
+}
+
+/// Result for one provisioning record
+#[derive(Debug, Serialize)]
+pub struct ProvisionResult {
+    pub email: String,
+    /// created, updated, deactivated, unchanged or error
+    pub status: &'static str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub user: Option<UserResponse>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+/// Response for bulk provisioning
+#[derive(Debug, Serialize)]
+pub struct ProvisionUsersResponse {
+    pub results: Vec<ProvisionResult>,
+}
+
+fn map_user_error(e: UserServiceError) -> ApiError {
+    match e {
+        UserServiceError::UserNotFound => ApiError::not_found("User not found"),
+        UserServiceError::ValidationError(msg) => ApiError::validation_error(msg),
+        e => ApiError::internal_error(e.to_string()),
+    }
+}
+
+/// PUT /api/v1/admin/users/{id}/role - Change a user's role
+///
+/// All sessions of the user are revoked.
+pub async fn update_role(
+    State(state): State<AppState>,
+    user: AuthenticatedUser,
+    Path(id): Path<i64>,
+    Json(body): Json<UpdateRoleRequest>,
+) -> Result<Json<UserResponse>, ApiError> {
+    let role = UserRole::from_str(&body.role)
+        .map_err(|_| ApiError::validation_error(format!("Invalid role: {}", body.role)))?;
+
+    let updated = state
+        .user_service
+        .change_role(user.0.id, id, role)
+        .await
+        .map_err(map_user_error)?;
+
+    Ok(Json(updated.into()))
+}
+
+/// POST /api/v1/admin/users/{id}/force-password-reset - Require a new password
+///
+/// All sessions of the user are revoked; after logging in again the user
+/// must change their password before using any other endpoint.
+pub async fn force_password_reset(
+    State(state): State<AppState>,
+    user: AuthenticatedUser,
+    Path(id): Path<i64>,
+) -> Result<Json<UserResponse>, ApiError> {
+    if user.0.id == id {
+        return Err(ApiError::validation_error(
+            "Use the change password endpoint for your own account",
+        ));
+    }
+
+    let updated = state
+        .user_service
+        .force_password_reset(id)
+        .await
+        .map_err(map_user_error)?;
+
+    Ok(Json(updated.into()))
+}
+
+/// DELETE /api/v1/admin/users/{id}/sessions - Sign a user out everywhere
+pub async fn revoke_sessions(
+    State(state): State<AppState>,
+    _user: AuthenticatedUser,
+    Path(id): Path<i64>,
+) -> Result<StatusCode, ApiError> {
+    state
+        .user_service
+        .revoke_sessions(id)
+        .await
+        .map_err(map_user_error)?;
+
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// POST /api/v1/admin/users/provision - Create, update or deactivate users
+///
+/// Records are matched by email and applied one by one, so re-sending a
+/// batch is safe. A failing record does not stop the others; its result
+/// carries the error.
+pub async fn provision_users(
+    State(state): State<AppState>,
+    user: AuthenticatedUser,
+    Json(body): Json<ProvisionUsersRequest>,
+) -> Result<Json<ProvisionUsersResponse>, ApiError> {
+    if body.users.len() > MAX_PROVISION_RECORDS {
+        return Err(ApiError::validation_error(format!(
+            "At most {} users can be provisioned per request",
+            MAX_PROVISION_RECORDS
+        )));
+    }
+
+    let mut results = Vec::with_capacity(body.users.len());
+    for record in body.users {
+        let email = record.email.trim().to_string();
+        let role = match record.role.as_deref().map(UserRole::from_str).transpose() {
+            Ok(role) => role,
+            Err(_) => {
+                results.push(ProvisionResult {
+                    email,
+                    status: "error",
+                    user: None,
+                    error: Some(format!("Invalid role: {}", record.role.unwrap_or_default())),
+                });
+                continue;
+            }
+        };
+        let input = ProvisionUserInput {
+            email: email.clone(),
+            username: record.username,
+            display_name: record.display_name,
+            role,
+            active: record.active,
+        };
+
+        let result = match state.user_service.provision_user(user.0.id, input).await {
+            Ok((outcome, provisioned)) => ProvisionResult {
+                email,
+                status: match outcome {
+                    ProvisionOutcome::Created => "created",
+                    ProvisionOutcome::Updated => "updated",
+                    ProvisionOutcome::Deactivated => "deactivated",
+                    ProvisionOutcome::Unchanged => "unchanged",
+                },
+                user: provisioned.map(Into::into),
+                error: None,
+            },
+            Err(UserServiceError::ValidationError(msg)) => ProvisionResult {
+                email,
+                status: "error",
+                user: None,
+                error: Some(msg),
+            },
+            Err(e) => return Err(map_user_error(e)),
+        };
+        results.push(result);
+    }
+
+    Ok(Json(ProvisionUsersResponse { results }))
+}
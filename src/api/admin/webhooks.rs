@@ -0,0 +1,59 @@
+//! Inbound webhook management endpoints
+
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+    Json,
+};
+
+use crate::api::middleware::{ApiError, AppState, AuthenticatedUser};
+use crate::models::{CreateInboundWebhookInput, InboundWebhook};
+use crate::services::InboundWebhookError;
+
+fn map_error(e: InboundWebhookError) -> ApiError {
+    match e {
+        InboundWebhookError::NotFound => ApiError::not_found("Webhook not found"),
+        InboundWebhookError::Validation(msg) => ApiError::validation_error(msg),
+        other => ApiError::internal_error(other.to_string()),
+    }
+}
+
+/// GET /api/v1/admin/webhooks/inbound - List inbound webhooks
+///
+/// Requires admin authentication.
+pub async fn list_inbound(
+    State(state): State<AppState>,
+    _user: AuthenticatedUser,
+) -> Result<Json<Vec<InboundWebhook>>, ApiError> {
+    let webhooks = state.inbound_webhooks.list().await.map_err(map_error)?;
+    Ok(Json(webhooks))
+}
+
+/// POST /api/v1/admin/webhooks/inbound - Create an inbound webhook
+///
+/// The response contains the token for `/api/v1/hooks/in/{token}`.
+/// Requires admin authentication.
+pub async fn create_inbound(
+    State(state): State<AppState>,
+    _user: AuthenticatedUser,
+    Json(input): Json<CreateInboundWebhookInput>,
+) -> Result<(StatusCode, Json<InboundWebhook>), ApiError> {
+    let webhook = state
+        .inbound_webhooks
+        .create(input)
+        .await
+        .map_err(map_error)?;
+    Ok((StatusCode::CREATED, Json(webhook)))
+}
+
+/// DELETE /api/v1/admin/webhooks/inbound/{id} - Delete an inbound webhook
+///
+/// Requires admin authentication.
+pub async fn delete_inbound(
+    State(state): State<AppState>,
+    _user: AuthenticatedUser,
+    Path(id): Path<i64>,
+) -> Result<StatusCode, ApiError> {
+    state.inbound_webhooks.delete(id).await.map_err(map_error)?;
+    Ok(StatusCode::NO_CONTENT)
+}
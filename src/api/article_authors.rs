@@ -0,0 +1,86 @@
+//! Article author credit endpoints.
+//!
+//! - GET /api/v1/admin/articles/:id/authors - An article's credits
+//! - PUT /api/v1/admin/articles/:id/authors - Replace them
+//!
+//! Readers get the credits as `authors` on article responses, and article
+//! lists filter on them with `?author=` and `?author_role=`.
+
+use axum::{
+    extract::{Path, State},
+    response::IntoResponse,
+    Json,
+};
+use serde::Serialize;
+
+use crate::api::middleware::{ApiError, AppState};
+use crate::models::{ArticleAuthor, ArticleAuthorsInput};
+use crate::services::ArticleAuthorError;
+
+#[derive(Debug, Serialize)]
+struct AuthorsResponse {
+    authors: Vec<ArticleAuthor>,
+}
+
+fn map_author_error(e: ArticleAuthorError) -> ApiError {
+    match e {
+        ArticleAuthorError::Validation(_) => ApiError::validation_error(e.to_string()),
+        ArticleAuthorError::Internal(e) => ApiError::internal_error(e.to_string()),
+    }
+}
+
+async fn ensure_article(state: &AppState, id: i64) -> Result<(), ApiError> {
+    state
+        .article_service
+        .get_by_id(id)
+        .await
+        .map_err(|e| ApiError::internal_error(e.to_string()))?
+        .ok_or_else(|| ApiError::not_found(format!("Article not found: {}", id)))?;
+    Ok(())
+}
+
+/// GET /api/v1/admin/articles/{id}/authors - List an article's credits
+pub async fn list_authors(
+    State(state): State<AppState>,
+    Path(id): Path<i64>,
+) -> Result<impl IntoResponse, ApiError> {
+    ensure_article(&state, id).await?;
+    let authors = state
+        .article_author_service
+        .list(id)
+        .await
+        .map_err(map_author_error)?;
+    Ok(Json(AuthorsResponse { authors }))
+}
+
+/// PUT /api/v1/admin/articles/{id}/authors - Replace an article's credits
+///
+/// Body: `{"authors": [{"user_id": 1, "role": "author"}, {"user_id": 4,
+/// "role": "translator"}]}`, in display order. Roles are `author`, `editor`
+/// and `translator`; the first `author` becomes the article's `author_id`.
+pub async fn set_authors(
+    State(state): State<AppState>,
+    Path(id): Path<i64>,
+    Json(input): Json<ArticleAuthorsInput>,
+) -> Result<impl IntoResponse, ApiError> {
+    ensure_article(&state, id).await?;
+    let authors = state
+        .article_author_service
+        .set(id, input)
+        .await
+        .map_err(map_author_error)?;
+    Ok(Json(AuthorsResponse { authors }))
+}
+
+/// Credits of an article; empty on failure so a broken list never breaks
+/// the article itself
+pub(crate) async fn for_article(state: &AppState, article_id: i64) -> Vec<ArticleAuthor> {
+    state
+        .article_author_service
+        .list(article_id)
+        .await
+        .unwrap_or_else(|e| {
+            tracing::warn!("Failed to load authors of article {}: {}", article_id, e);
+            Vec::new()
+        })
+}
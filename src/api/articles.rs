@@ -1,7 +1,8 @@
 //! Article API endpoints
 //!
 //! Handles HTTP requests for article management:
-//! - GET /api/v1/articles - List articles with pagination
+//! - GET /api/v1/articles - List articles with pagination, filtering and sorting
+//! - GET /api/v1/articles/popular - Most viewed articles in a time window
 //! - GET /api/v1/articles/:slug - Get article by slug
 //! - POST /api/v1/articles - Create new article
 //! - PUT /api/v1/articles/:id - Update article
@@ -14,17 +15,30 @@
 //! - 1.4: Article deletion
 
 use axum::{
-    extract::{Path, Query, State},
-    http::StatusCode,
+    extract::{Extension, Path, Query, State},
+    http::{HeaderMap, StatusCode},
+    response::{IntoResponse, Response},
     routing::{delete, get, post, put},
     Json, Router,
 };
 use serde::{Deserialize, Deserializer};
+use std::collections::HashSet;
 
-use crate::api::common::{default_page, default_page_size};
-use crate::api::middleware::{ApiError, AppState, AuthenticatedUser};
-use crate::api::responses::{ArticleResponse, PaginatedArticlesResponse};
-use crate::models::{ArticleSortBy, ArticleStatus, ListParams};
+use crate::api::common::{default_page, default_page_size, parse_cursor, parse_date_bound};
+use crate::api::middleware::{
+    check_write_preconditions, conditional_json, version_headers, ApiError, AppState,
+    AuthenticatedUser,
+};
+use crate::api::reader_groups::{ensure_readable, viewer_reader};
+use crate::api::responses::{ArticleLink, ArticleResponse, PaginatedArticlesResponse};
+use crate::models::{
+    Article, ArticleFilter, ArticleListScope, ArticleReader, ArticleSlug, ArticleSortBy,
+    ArticleStatus, AuthorRole, CategoryAccess, ContentLicense, InputFormat, ListParams,
+    PagedResult, PopularWindow, SortDirection,
+};
+use crate::services::featured::MAX_FEATURED;
+use crate::services::license;
+use crate::services::publish_checklist::{self, ChecklistItem, ChecklistMode};
 
 /// Query parameters for listing articles
 #[derive(Debug, Deserialize)]
@@ -44,8 +58,106 @@ pub struct ListArticlesQuery {
     pub category: Option<String>,
     /// Filter by tag (ID or slug)
     pub tag: Option<String>,
-    /// Sort order: "views", "comments", "latest" (default)
+    /// Filter by author (ID or username), co-authors included
+    pub author: Option<String>,
+    /// Only credits with this role: `author`, `editor` or `translator`
+    pub author_role: Option<String>,
+    /// Only articles dated on or after this date (RFC 3339 or YYYY-MM-DD)
+    pub from: Option<String>,
+    /// Only articles dated before this time; a bare YYYY-MM-DD includes that day
+    pub to: Option<String>,
+    /// Sort order: "views", "comments", "title", "updated", "latest" (default)
     pub sort: Option<String>,
+    /// Sort direction: "asc" or "desc" (default depends on the sort field)
+    pub order: Option<String>,
+    /// Only articles in this language (e.g. `en`); articles without a
+    /// language are in the site language
+    pub lang: Option<String>,
+    /// Opaque cursor for keyset pagination; an empty value requests the
+    /// first page. When present, `page` is ignored and articles are ordered
+    /// newest first.
+    pub cursor: Option<String>,
+    /// Comma-separated article fields to return, e.g. `slug,title,thumbnail`.
+    /// `id` is always included; without `content` or `content_html` the
+    /// article bodies are not loaded.
+    pub fields: Option<String>,
+}
+
+/// Article fields that `?fields=` may select on list endpoints
+const LIST_FIELDS: &[&str] = &[
+    "id",
+    "slug",
+    "title",
+    "content",
+    "content_html",
+    "author_id",
+    "authors",
+    "custom_fields",
+    "category_id",
+    "status",
+    "published_at",
+    "created_at",
+    "updated_at",
+    "view_count",
+    "like_count",
+    "favorite_count",
+    "comment_count",
+    "word_count",
+    "char_count",
+    "reading_time",
+    "summary",
+    "excerpt",
+    "thumbnail",
+    "is_pinned",
+    "pin_order",
+    "category",
+    "tags",
+    "meta",
+    "scheduled_at",
+    "input_format",
+    "seo",
+    "license",
+];
+
+/// Fields that need the article body
+const CONTENT_FIELDS: &[&str] = &["content", "content_html"];
+
+/// Field selection parsed from `?fields=`; `None` selects every field
+struct FieldSelection(Option<HashSet<&'static str>>);
+
+impl FieldSelection {
+    fn parse(value: Option<&str>) -> Result<Self, ApiError> {
+        let Some(value) = value.map(str::trim).filter(|v| !v.is_empty()) else {
+            return Ok(Self(None));
+        };
+        let mut fields = HashSet::from(["id"]);
+        for name in value.split(',').map(str::trim).filter(|n| !n.is_empty()) {
+            let field = LIST_FIELDS
+                .iter()
+                .find(|f| **f == name)
+                .ok_or_else(|| ApiError::validation_error(format!("Unknown field: {}", name)))?;
+            fields.insert(field);
+        }
+        Ok(Self(Some(fields)))
+    }
+
+    fn includes(&self, field: &str) -> bool {
+        self.0.as_ref().is_none_or(|fields| fields.contains(field))
+    }
+
+    fn needs_content(&self) -> bool {
+        CONTENT_FIELDS.iter().any(|f| self.includes(f))
+    }
+
+    /// Serialize an article, keeping only the selected fields
+    fn apply(&self, article: ArticleResponse) -> Result<serde_json::Value, ApiError> {
+        let mut value =
+            serde_json::to_value(article).map_err(|e| ApiError::internal_error(e.to_string()))?;
+        if let (Some(fields), Some(object)) = (&self.0, value.as_object_mut()) {
+            object.retain(|key, _| fields.contains(key.as_str()));
+        }
+        Ok(value)
+    }
 }
 
 /// Query parameters for resolving article path
@@ -71,6 +183,18 @@ pub struct CreateArticleRequest {
     pub tag_ids: Option<Vec<i64>>,
     #[serde(default)]
     pub scheduled_at: Option<String>,
+    /// `markdown` (default), `bbcode`, `rst`, `html` or `blocks`
+    #[serde(default)]
+    pub input_format: Option<String>,
+    /// Excerpt for lists; generated from the content when empty
+    #[serde(default)]
+    pub excerpt: Option<String>,
+    /// License of the article; the site default applies when omitted
+    #[serde(default)]
+    pub license: Option<ContentLicense>,
+    /// Take a slug an earlier article used (admins only)
+    #[serde(default)]
+    pub reclaim_slug: bool,
 }
 
 /// Request body for updating an article
@@ -91,6 +215,34 @@ pub struct UpdateArticleRequest {
     pub pin_order: Option<i32>,
     #[serde(default)]
     pub scheduled_at: Option<Option<String>>,
+    #[serde(default)]
+    pub input_format: Option<String>,
+    /// Title for search engines and link previews, `null` to clear
+    #[serde(default, deserialize_with = "deserialize_nullable_string_patch")]
+    pub meta_title: Option<Option<String>>,
+    #[serde(default, deserialize_with = "deserialize_nullable_string_patch")]
+    pub meta_description: Option<Option<String>>,
+    #[serde(default, deserialize_with = "deserialize_nullable_string_patch")]
+    pub canonical_url: Option<Option<String>>,
+    pub noindex: Option<bool>,
+    /// Excerpt for lists, `null` or empty to generate one from the content
+    #[serde(default, deserialize_with = "deserialize_nullable_string_patch")]
+    pub excerpt: Option<Option<String>>,
+    /// License of the article, `null` to use the site default
+    #[serde(default, deserialize_with = "deserialize_patch")]
+    pub license: Option<Option<ContentLicense>>,
+    /// Take a slug another article used before (admins only)
+    #[serde(default)]
+    pub reclaim_slug: bool,
+}
+
+/// Tell a `null` field (`Some(None)`) apart from a missing one (`None`)
+fn deserialize_patch<'de, D, T>(deserializer: D) -> Result<Option<Option<T>>, D::Error>
+where
+    D: Deserializer<'de>,
+    T: Deserialize<'de>,
+{
+    Option::<T>::deserialize(deserializer).map(Some)
 }
 
 fn deserialize_nullable_string_patch<'de, D>(
@@ -110,9 +262,7 @@ where
                 Ok(Some(Some(value.to_string())))
             }
         }
-        _ => Err(serde::de::Error::custom(
-            "thumbnail must be a string or null",
-        )),
+        _ => Err(serde::de::Error::custom("expected a string or null")),
     }
 }
 
@@ -140,6 +290,64 @@ fn parse_article_status_input(status: Option<&str>) -> Result<Option<ArticleStat
     }
 }
 
+fn parse_input_format(format: Option<&str>) -> Result<Option<InputFormat>, ApiError> {
+    match format {
+        None | Some("") => Ok(None),
+        Some(value) => InputFormat::parse(value)
+            .map(Some)
+            .ok_or_else(|| ApiError::validation_error(format!("Invalid input format: {}", value))),
+    }
+}
+
+/// Only admins may take over a slug from an earlier article
+fn check_reclaim_slug(user: &AuthenticatedUser, reclaim: bool) -> Result<bool, ApiError> {
+    if reclaim && !user.0.is_admin() {
+        return Err(ApiError::forbidden(
+            "Only admins can reclaim a slug used by another article",
+        ));
+    }
+    Ok(reclaim)
+}
+
+fn reserved_slug_error(slug: String) -> ApiError {
+    ApiError::with_details(
+        "CONFLICT",
+        format!(
+            "Article slug was used by another article: {}; set reclaim_slug to take it over",
+            slug
+        ),
+        serde_json::json!({"field": "slug", "value": slug, "reserved": true}),
+    )
+}
+
+/// Check the publish checklist for an article about to be published or
+/// scheduled; fails in `block` mode and returns the missing items otherwise
+async fn check_publish_checklist(
+    state: &AppState,
+    article: &crate::models::Article,
+) -> Result<Vec<ChecklistItem>, ApiError> {
+    let checklist = publish_checklist::load(&state.settings_service).await;
+    if checklist.mode == ChecklistMode::Off {
+        return Ok(Vec::new());
+    }
+    let default_category_id = state
+        .category_service
+        .get_default()
+        .await
+        .map_err(|e| ApiError::internal_error(e.to_string()))?
+        .map(|c| c.id);
+    let unmet = checklist.unmet(article, default_category_id);
+    if checklist.mode == ChecklistMode::Block && !unmet.is_empty() {
+        let names: Vec<&str> = unmet.iter().map(ChecklistItem::as_str).collect();
+        return Err(ApiError::with_details(
+            "VALIDATION_ERROR",
+            format!("Publish checklist not met: {}", names.join(", ")),
+            serde_json::json!({ "unmet": unmet }),
+        ));
+    }
+    Ok(unmet)
+}
+
 fn empty_articles_response(params: &ListParams) -> Json<PaginatedArticlesResponse> {
     Json(PaginatedArticlesResponse {
         articles: Vec::new(),
@@ -147,6 +355,7 @@ fn empty_articles_response(params: &ListParams) -> Json<PaginatedArticlesRespons
         page: params.page,
         page_size: params.per_page,
         total_pages: 0,
+        next_cursor: None,
     })
 }
 
@@ -168,6 +377,7 @@ pub use create_article as create_article_handler;
 pub use delete_article as delete_article_handler;
 pub use get_article as get_article_handler;
 pub use get_article_by_id as get_article_by_id_handler;
+pub use list_article_slugs as list_article_slugs_handler;
 pub use list_articles as list_articles_handler;
 pub use list_articles_admin as list_articles_admin_handler;
 pub use resolve_article as resolve_article_handler;
@@ -186,27 +396,49 @@ pub fn router() -> Router<AppState> {
 /// GET /api/v1/articles - List articles with pagination
 ///
 /// Satisfies requirement 1.2: Article listing with pagination
+///
+/// Responses carry an ETag; a matching `If-None-Match` gets 304 Not Modified.
 pub async fn list_articles(
     State(state): State<AppState>,
+    user: Option<Extension<AuthenticatedUser>>,
+    headers: HeaderMap,
     Query(query): Query<ListArticlesQuery>,
-) -> Result<Json<PaginatedArticlesResponse>, ApiError> {
-    list_articles_inner(state, query, true).await
+) -> Result<Response, ApiError> {
+    let clearance = viewer_clearance(user.as_ref());
+    let reader = viewer_reader(user.as_ref());
+    let Json(response) = list_articles_inner(state, query, true, clearance, reader).await?;
+    Ok(conditional_json(&headers, &response))
 }
 
 /// GET /api/v1/admin/articles - List articles for admin management.
 pub async fn list_articles_admin(
-    _user: AuthenticatedUser,
+    user: AuthenticatedUser,
     State(state): State<AppState>,
     Query(query): Query<ListArticlesQuery>,
 ) -> Result<Json<PaginatedArticlesResponse>, ApiError> {
-    list_articles_inner(state, query, false).await
+    let clearance = CategoryAccess::clearance(Some(&user.0));
+    let reader = ArticleReader::of(Some(&user.0));
+    list_articles_inner(state, query, false, clearance, reader).await
 }
 
-async fn list_articles_inner(
+/// Highest category access level the (optionally signed-in) reader may see
+pub(crate) fn viewer_clearance(user: Option<&Extension<AuthenticatedUser>>) -> CategoryAccess {
+    CategoryAccess::clearance(user.map(|Extension(user)| &user.0))
+}
+
+/// Listings of published articles leave out categories above `clearance`
+/// and articles whose audience does not admit `reader`. The dedicated
+/// published queries only know anonymous visitors, so signed-in readers go
+/// through the filtered query. Cursor listings apply the same clearance and
+/// audience; keyword listings always show what anonymous visitors see.
+pub(crate) async fn list_articles_inner(
     state: AppState,
     query: ListArticlesQuery,
     public_only: bool,
+    clearance: CategoryAccess,
+    reader: Option<ArticleReader>,
 ) -> Result<Json<PaginatedArticlesResponse>, ApiError> {
+    let fields = FieldSelection::parse(query.fields.as_deref())?;
     let params = ListParams::new(query.page, query.page_size);
 
     let status_filter = if public_only {
@@ -266,14 +498,171 @@ async fn list_articles_inner(
         None
     };
 
+    // Resolve author: try as ID first, then as username
+    let author_id = if let Some(author) = query
+        .author
+        .as_deref()
+        .map(str::trim)
+        .filter(|s| !s.is_empty())
+    {
+        if let Ok(id) = author.parse::<i64>() {
+            Some(id)
+        } else {
+            match state
+                .user_repo
+                .get_by_username(author)
+                .await
+                .map_err(|e| ApiError::internal_error(e.to_string()))?
+            {
+                Some(user) => Some(user.id),
+                None => return Ok(empty_articles_response(&params)),
+            }
+        }
+    } else {
+        None
+    };
+
+    let non_empty = |value: &Option<String>| {
+        value
+            .as_deref()
+            .map(str::trim)
+            .filter(|s| !s.is_empty())
+            .map(str::to_string)
+    };
+    let author_role = non_empty(&query.author_role)
+        .map(|role| {
+            AuthorRole::parse(&role)
+                .ok_or_else(|| ApiError::validation_error(format!("Invalid author role: {}", role)))
+        })
+        .transpose()?;
+    let date_from = non_empty(&query.from)
+        .map(|v| parse_date_bound(&v, false))
+        .transpose()?;
+    let date_to = non_empty(&query.to)
+        .map(|v| parse_date_bound(&v, true))
+        .transpose()?;
+
+    let lang = crate::api::translations::lang_filter(&state, query.lang.as_deref()).await?;
+
     // Parse sort order from query string
     let sort_by = ArticleSortBy::from_str(query.sort.as_deref().unwrap_or("date"));
+    let order = non_empty(&query.order);
+    let direction = match order.as_deref() {
+        Some(value) => SortDirection::parse(value).ok_or_else(|| {
+            ApiError::validation_error(format!("Invalid sort direction: {}", value))
+        })?,
+        None => sort_by.default_direction(),
+    };
 
-    let result = if let Some(ref keyword) = query.keyword {
+    // Filters the dedicated listing queries cannot express go through
+    // the generic filtered query, as do listings that skip article bodies
+    let skip_content = !fields.needs_content();
+    let use_filtered_query = author_id.is_some()
+        || author_role.is_some()
+        || date_from.is_some()
+        || date_to.is_some()
+        || lang.is_some()
+        || order.is_some()
+        || (category_id.is_some() && tag_id.is_some())
+        || ((skip_content || (filter_published && clearance > CategoryAccess::Public))
+            && query.cursor.is_none()
+            && query.keyword.is_none());
+
+    let mut next_cursor = None;
+    let result = if use_filtered_query {
+        if query.cursor.is_some() || query.keyword.is_some() {
+            return Err(ApiError::validation_error(
+                "Author, date range, language and sort direction filters cannot be combined with cursor or keyword search",
+            ));
+        }
+        let mut category_ids = match category_id {
+            Some(cat_id) if filter_published => state
+                .category_service
+                .get_all_descendants(cat_id)
+                .await
+                .map_err(|e| ApiError::internal_error(e.to_string()))?,
+            _ => Vec::new(),
+        };
+        if let Some(cat_id) = category_id.filter(|id| !category_ids.contains(id)) {
+            category_ids.push(cat_id);
+        }
+        let filter = ArticleFilter {
+            status: status_filter,
+            author_id,
+            author_role,
+            category_ids,
+            tag_id,
+            date_from,
+            date_to,
+            lang,
+            clearance: Some(clearance),
+            reader,
+        };
+        let mut filtered = params
+            .clone()
+            .with_filter(filter)
+            .with_sort(sort_by, direction);
+        if skip_content {
+            filtered = filtered.without_content();
+        }
+        state
+            .article_service
+            .list_filtered(&filtered, reader.as_ref())
+            .await
+            .map_err(|e| ApiError::internal_error(e.to_string()))?
+    } else if let Some(ref cursor) = query.cursor {
+        if query.keyword.is_some() {
+            return Err(ApiError::validation_error(
+                "Cursor pagination is not supported for keyword search",
+            ));
+        }
+        if sort_by != ArticleSortBy::Date {
+            return Err(ApiError::validation_error(
+                "Cursor pagination only supports the default sort order",
+            ));
+        }
+        let cursor = parse_cursor(cursor)?;
+        let scope = if let Some(cat_id) = category_id {
+            if filter_published {
+                let category_ids = state
+                    .category_service
+                    .get_all_descendants(cat_id)
+                    .await
+                    .map_err(|e| ApiError::internal_error(e.to_string()))?;
+                ArticleListScope::PublishedInCategories(category_ids)
+            } else {
+                ArticleListScope::Category(cat_id)
+            }
+        } else if let Some(t_id) = tag_id {
+            if filter_published {
+                ArticleListScope::PublishedWithTag(t_id)
+            } else {
+                ArticleListScope::Tag(t_id)
+            }
+        } else if let Some(status) = status_filter {
+            ArticleListScope::Status(status)
+        } else {
+            ArticleListScope::All
+        };
+
+        let page = state
+            .article_service
+            .list_by_cursor(
+                &scope,
+                cursor.as_ref(),
+                params.limit(),
+                clearance,
+                reader.as_ref(),
+            )
+            .await
+            .map_err(|e| ApiError::internal_error(e.to_string()))?;
+        next_cursor = page.next_cursor;
+        PagedResult::new(page.items, page.total, &ListParams::new(1, params.per_page))
+    } else if let Some(ref keyword) = query.keyword {
         // Search by keyword
         state
             .article_service
-            .search(keyword, &params, filter_published, sort_by)
+            .search(keyword, &params, filter_published, sort_by, reader.as_ref())
             .await
             .map_err(|e| ApiError::internal_error(e.to_string()))?
     } else if let Some(cat_id) = category_id {
@@ -292,7 +681,7 @@ async fn list_articles_inner(
         } else {
             state
                 .article_service
-                .list_by_category(cat_id, &params, sort_by)
+                .list_by_category(cat_id, &params, sort_by, reader.as_ref())
                 .await
                 .map_err(|e| ApiError::internal_error(e.to_string()))?
         }
@@ -307,7 +696,7 @@ async fn list_articles_inner(
         } else {
             state
                 .article_service
-                .list_by_tag(t_id, &params, sort_by)
+                .list_by_tag(t_id, &params, sort_by, reader.as_ref())
                 .await
                 .map_err(|e| ApiError::internal_error(e.to_string()))?
         }
@@ -320,13 +709,13 @@ async fn list_articles_inner(
     } else if let Some(status) = status_filter {
         state
             .article_service
-            .list_by_status(status, &params, sort_by)
+            .list_by_status(status, &params, sort_by, reader.as_ref())
             .await
             .map_err(|e| ApiError::internal_error(e.to_string()))?
     } else {
         state
             .article_service
-            .list(&params, sort_by)
+            .list(&params, sort_by, reader.as_ref())
             .await
             .map_err(|e| ApiError::internal_error(e.to_string()))?
     };
@@ -336,28 +725,7 @@ async fn list_articles_inner(
     let per_page = result.per_page;
     let total_pages = result.total_pages();
 
-    // Batch-fetch tags for all articles (1 query instead of N)
-    let article_ids: Vec<i64> = result.items.iter().map(|a| a.id).collect();
-    let tags_map = state
-        .tag_service
-        .get_by_article_ids(&article_ids)
-        .await
-        .unwrap_or_default();
-
-    // Build responses with category + tags
-    let mut articles: Vec<ArticleResponse> = Vec::new();
-    for article in result.items {
-        let category = state
-            .category_service
-            .get_by_id(article.category_id)
-            .await
-            .ok()
-            .flatten();
-        let tags = tags_map.get(&article.id).cloned().unwrap_or_default();
-
-        let response: ArticleResponse = article.into();
-        articles.push(response.with_category(category).with_tags(tags));
-    }
+    let articles = list_items(&state, result.items, &fields).await?;
 
     // Hook: article_list_filter — allow plugins to modify article list
     state.hook_manager.trigger(
@@ -375,9 +743,86 @@ async fn list_articles_inner(
         page,
         page_size: per_page,
         total_pages,
+        next_cursor,
     }))
 }
 
+/// Serialize a page of articles for a list response, with the category,
+/// tags, credits and custom fields the field selection asks for
+async fn list_items(
+    state: &AppState,
+    items: Vec<Article>,
+    fields: &FieldSelection,
+) -> Result<Vec<serde_json::Value>, ApiError> {
+    // Batch-fetch tags for all articles (1 query instead of N)
+    let tags_map = if fields.includes("tags") {
+        let article_ids: Vec<i64> = items.iter().map(|a| a.id).collect();
+        state
+            .tag_service
+            .get_by_article_ids(&article_ids)
+            .await
+            .unwrap_or_default()
+    } else {
+        Default::default()
+    };
+
+    let authors_map = if fields.includes("authors") {
+        let article_ids: Vec<i64> = items.iter().map(|a| a.id).collect();
+        state
+            .article_author_service
+            .by_articles(&article_ids)
+            .await
+            .unwrap_or_default()
+    } else {
+        Default::default()
+    };
+
+    let mut custom_fields_map = if fields.includes("custom_fields") {
+        let article_ids: Vec<i64> = items.iter().map(|a| a.id).collect();
+        state
+            .custom_field_service
+            .maps(crate::models::ContentKind::Article, &article_ids)
+            .await
+            .unwrap_or_default()
+    } else {
+        Default::default()
+    };
+
+    let default_license = license::load(&state.settings_service).await;
+
+    // Build responses with category, tags, credits and custom fields
+    let mut articles = Vec::new();
+    for article in items {
+        let category = if fields.includes("category") {
+            state
+                .category_service
+                .get_by_id(article.category_id)
+                .await
+                .ok()
+                .flatten()
+        } else {
+            None
+        };
+        let tags = tags_map.get(&article.id).cloned().unwrap_or_default();
+        let authors = authors_map.get(&article.id).cloned().unwrap_or_default();
+        let custom_fields = custom_fields_map.remove(&article.id).unwrap_or_default();
+
+        let response: ArticleResponse = article.into();
+        articles.push(
+            fields.apply(
+                response
+                    .with_category(category)
+                    .with_tags(tags)
+                    .with_authors(authors)
+                    .with_custom_fields(custom_fields)
+                    .with_site_license(&default_license),
+            )?,
+        );
+    }
+
+    Ok(articles)
+}
+
 /// Archive entry for monthly aggregation
 #[derive(Debug, serde::Serialize)]
 pub struct ArchiveEntry {
@@ -410,6 +855,271 @@ pub async fn get_archives(
     Ok(Json(archives))
 }
 
+/// Months of a year with published articles
+#[derive(Debug, PartialEq, serde::Serialize)]
+pub struct ArchiveYear {
+    pub year: i32,
+    /// Articles published that year
+    pub count: i64,
+    /// Newest first
+    pub months: Vec<ArchiveMonth>,
+}
+
+/// A month with published articles
+#[derive(Debug, PartialEq, serde::Serialize)]
+pub struct ArchiveMonth {
+    /// 1 to 12
+    pub month: u32,
+    pub count: i64,
+}
+
+/// Year/month archive tree
+#[derive(Debug, serde::Serialize)]
+pub struct ArchiveTreeResponse {
+    /// Newest first
+    pub years: Vec<ArchiveYear>,
+    /// Published articles in all years
+    pub total: i64,
+}
+
+/// Group `YYYY-MM` month counts, newest first, into years
+fn archive_tree(monthly: Vec<(String, i64)>) -> Vec<ArchiveYear> {
+    let mut years: Vec<ArchiveYear> = Vec::new();
+    for (key, count) in monthly {
+        let Some((year, month)) = key
+            .split_once('-')
+            .and_then(|(y, m)| Some((y.parse::<i32>().ok()?, m.parse::<u32>().ok()?)))
+        else {
+            continue;
+        };
+        match years.last_mut() {
+            Some(last) if last.year == year => {
+                last.count += count;
+                last.months.push(ArchiveMonth { month, count });
+            }
+            _ => years.push(ArchiveYear {
+                year,
+                count,
+                months: vec![ArchiveMonth { month, count }],
+            }),
+        }
+    }
+    years
+}
+
+/// GET /api/v1/archives - Published article counts by year and month
+pub async fn get_archive_tree(
+    State(state): State<AppState>,
+) -> Result<Json<ArchiveTreeResponse>, ApiError> {
+    let monthly = state
+        .article_service
+        .get_archives_monthly()
+        .await
+        .map_err(|e| ApiError::internal_error(e.to_string()))?;
+    let years = archive_tree(monthly);
+    let total = years.iter().map(|y| y.count).sum();
+    Ok(Json(ArchiveTreeResponse { years, total }))
+}
+
+/// Query parameters for the articles of an archive month
+#[derive(Debug, Deserialize)]
+pub struct ArchiveMonthQuery {
+    #[serde(default = "default_page")]
+    pub page: u32,
+    #[serde(default = "default_page_size")]
+    pub page_size: u32,
+    /// Comma-separated article fields to return, as on `/articles`
+    pub fields: Option<String>,
+}
+
+/// GET /api/v1/archives/:year/:month - Published articles of a month
+///
+/// Oldest first, the order they were written in, and paginated like
+/// `/articles`; pinning does not apply. A month without articles returns an
+/// empty page.
+pub async fn list_archive_month(
+    State(state): State<AppState>,
+    Path((year, month)): Path<(i32, u32)>,
+    Query(query): Query<ArchiveMonthQuery>,
+) -> Result<Json<PaginatedArticlesResponse>, ApiError> {
+    let start = chrono::NaiveDate::from_ymd_opt(year, month, 1)
+        .filter(|_| (1..=9999).contains(&year))
+        .ok_or_else(|| ApiError::validation_error("Invalid year or month"))?;
+    let end = start
+        .checked_add_months(chrono::Months::new(1))
+        .ok_or_else(|| ApiError::validation_error("Invalid year or month"))?;
+    let fields = FieldSelection::parse(query.fields.as_deref())?;
+
+    let filter = ArticleFilter {
+        status: Some(ArticleStatus::Published),
+        date_from: Some(start.and_hms_opt(0, 0, 0).unwrap_or_default().and_utc()),
+        date_to: Some(end.and_hms_opt(0, 0, 0).unwrap_or_default().and_utc()),
+        ..Default::default()
+    };
+    let mut params = ListParams::new(query.page, query.page_size)
+        .with_filter(filter)
+        .with_sort(ArticleSortBy::Date, SortDirection::Asc);
+    if !fields.needs_content() {
+        params = params.without_content();
+    }
+    let result = state
+        .article_service
+        .list_filtered(&params, None)
+        .await
+        .map_err(|e| ApiError::internal_error(e.to_string()))?;
+
+    let total = result.total;
+    let page = result.page;
+    let page_size = result.per_page;
+    let total_pages = result.total_pages();
+    let articles = list_items(&state, result.items, &fields).await?;
+    Ok(Json(PaginatedArticlesResponse {
+        articles,
+        total,
+        page,
+        page_size,
+        total_pages,
+        next_cursor: None,
+    }))
+}
+
+/// Query parameters for popular articles
+#[derive(Debug, Deserialize)]
+pub struct PopularArticlesQuery {
+    /// `24h`, `7d`, `30d` (up to 365 days) or `all`; defaults to `7d`
+    pub window: Option<String>,
+    /// Number of articles (default 10, at most 50)
+    pub limit: Option<i64>,
+}
+
+/// An article ranked by views
+#[derive(Debug, serde::Serialize)]
+pub struct PopularArticle {
+    #[serde(flatten)]
+    pub link: ArticleLink,
+    pub published_at: Option<String>,
+    /// Views within the window
+    pub views: i64,
+}
+
+/// Popular articles of a window
+#[derive(Debug, serde::Serialize)]
+pub struct PopularArticlesResponse {
+    /// Normalized window, e.g. `7d` or `all`
+    pub window: String,
+    pub articles: Vec<PopularArticle>,
+}
+
+/// GET /api/v1/articles/popular - Most viewed published articles
+///
+/// Windowed rankings sum the daily view counts recorded by
+/// `POST /view/{article_id}`; `all` ranks by lifetime views. Results are
+/// cached for a few minutes.
+pub async fn get_popular_articles(
+    State(state): State<AppState>,
+    Query(query): Query<PopularArticlesQuery>,
+) -> Result<Json<PopularArticlesResponse>, ApiError> {
+    let window = match query.window.as_deref() {
+        Some(window) => PopularWindow::parse(window).ok_or_else(|| {
+            ApiError::validation_error(format!(
+                "window must be like 24h, 7d or 30d (at most {} days) or all",
+                PopularWindow::MAX_DAYS
+            ))
+        })?,
+        None => PopularWindow::Days(7),
+    };
+    let limit = query.limit.unwrap_or(10).clamp(1, 50);
+
+    let popular = state
+        .article_service
+        .list_popular(window, limit)
+        .await
+        .map_err(|e| ApiError::internal_error(e.to_string()))?;
+
+    let articles = popular
+        .into_iter()
+        .map(|(article, views)| PopularArticle {
+            published_at: article.published_at.map(|dt| dt.to_rfc3339()),
+            link: ArticleLink {
+                id: article.id,
+                slug: article.slug,
+                title: article.title,
+                thumbnail: article.thumbnail,
+            },
+            views,
+        })
+        .collect();
+
+    Ok(Json(PopularArticlesResponse {
+        window: window.cache_key(),
+        articles,
+    }))
+}
+
+/// Query parameters for the featured list
+#[derive(Debug, Deserialize)]
+pub struct FeaturedArticlesQuery {
+    /// Number of articles (default 10, at most 50)
+    pub limit: Option<usize>,
+}
+
+/// Featured articles, in curated order
+#[derive(Debug, serde::Serialize)]
+pub struct FeaturedArticlesResponse {
+    pub articles: Vec<ArticleResponse>,
+}
+
+/// GET /api/v1/articles/featured - Hand-picked articles for hero sections
+///
+/// Published articles on the featured list managed under
+/// `/api/v1/admin/featured`, in its order; expired entries are skipped.
+pub async fn get_featured_articles(
+    State(state): State<AppState>,
+    Query(query): Query<FeaturedArticlesQuery>,
+) -> Result<Json<FeaturedArticlesResponse>, ApiError> {
+    let limit = query.limit.unwrap_or(10).clamp(1, MAX_FEATURED);
+    let ids = state
+        .featured_service
+        .active_ids(limit)
+        .await
+        .map_err(|e| ApiError::internal_error(e.to_string()))?;
+
+    let tags_map = state
+        .tag_service
+        .get_by_article_ids(&ids)
+        .await
+        .unwrap_or_default();
+    let default_license = license::load(&state.settings_service).await;
+
+    let mut articles = Vec::new();
+    for id in ids {
+        let Some(article) = state
+            .article_service
+            .get_by_id(id)
+            .await
+            .map_err(|e| ApiError::internal_error(e.to_string()))?
+        else {
+            continue;
+        };
+        let category = state
+            .category_service
+            .get_by_id(article.category_id)
+            .await
+            .ok()
+            .flatten();
+        let tags = tags_map.get(&article.id).cloned().unwrap_or_default();
+        let response: ArticleResponse = article.into();
+        articles.push(
+            response
+                .with_category(category)
+                .with_tags(tags)
+                .with_site_license(&default_license),
+        );
+    }
+
+    Ok(Json(FeaturedArticlesResponse { articles }))
+}
+
 /// GET /api/v1/articles/:slug - Get article by slug or ID
 ///
 /// Resolves articles based on the current permalink_structure setting:
@@ -422,10 +1132,14 @@ pub async fn get_archives(
 /// Triggers hooks:
 /// - `article_before_display`: Before returning article data (can modify/filter)
 /// - `article_view`: After article is viewed (for statistics, logging)
+///
+/// Responses carry an ETag; a matching `If-None-Match` gets 304 Not Modified.
 pub async fn get_article(
     State(state): State<AppState>,
+    user: Option<Extension<AuthenticatedUser>>,
+    headers: HeaderMap,
     Path(identifier): Path<String>,
-) -> Result<Json<ArticleResponse>, ApiError> {
+) -> Result<Response, ApiError> {
     // Read permalink structure setting
     let permalink_structure = state
         .settings_service
@@ -451,13 +1165,22 @@ pub async fn get_article(
                 .ok_or_else(|| ApiError::not_found(format!("Article not found: {}", identifier)))?;
             (art, false)
         } else {
-            // Non-numeric identifier in ID mode: try slug, redirect to canonical ID URL
-            let art = state
+            // Non-numeric identifier in ID mode: try slug (current or previous),
+            // redirect to canonical ID URL
+            let art = match state
                 .article_service
                 .get_by_slug(&identifier)
                 .await
                 .map_err(|e| ApiError::internal_error(e.to_string()))?
-                .ok_or_else(|| ApiError::not_found(format!("Article not found: {}", identifier)))?;
+            {
+                Some(art) => Some(art),
+                None => state
+                    .article_service
+                    .get_by_previous_slug(&identifier)
+                    .await
+                    .map_err(|e| ApiError::internal_error(e.to_string()))?,
+            }
+            .ok_or_else(|| ApiError::not_found(format!("Article not found: {}", identifier)))?;
             (art, true)
         }
     } else {
@@ -469,8 +1192,20 @@ pub async fn get_article(
             .await
             .map_err(|e| ApiError::internal_error(e.to_string()))?;
 
+        let by_previous_slug = match by_slug {
+            Some(_) => None,
+            None => state
+                .article_service
+                .get_by_previous_slug(&identifier)
+                .await
+                .map_err(|e| ApiError::internal_error(e.to_string()))?,
+        };
+
         if let Some(art) = by_slug {
             (art, false)
+        } else if let Some(art) = by_previous_slug {
+            // Old slug: redirect to the article's current URL
+            (art, true)
         } else if is_numeric {
             // Slug not found, identifier is numeric: try by ID, redirect to canonical slug URL
             let id = identifier.parse::<i64>().unwrap();
@@ -504,6 +1239,24 @@ pub async fn get_article(
         .await
         .ok()
         .flatten();
+    // Articles in a private category are not found for readers without
+    // clearance
+    if category
+        .as_ref()
+        .is_some_and(|c| !c.readable_by(viewer_clearance(user.as_ref())))
+    {
+        return Err(ApiError::not_found(format!(
+            "Article not found: {}",
+            identifier
+        )));
+    }
+    ensure_readable(
+        &state,
+        article.id,
+        viewer_reader(user.as_ref()).as_ref(),
+        || ApiError::not_found(format!("Article not found: {}", identifier)),
+    )
+    .await?;
     let tags = state
         .tag_service
         .get_by_article_id(article.id)
@@ -514,18 +1267,46 @@ pub async fn get_article(
     let article_slug = article.slug.clone();
     let article_category_id = article.category_id;
     let article_published_at = article.published_at;
+    let input_format = article.input_format;
 
     let mut response: ArticleResponse = article.into();
     response = response.with_category(category).with_tags(tags.clone());
 
-    // Re-render HTML from raw markdown to ensure heading IDs match TOC
-    let toc = state.article_service.extract_toc(&response.content);
-    response.content_html = state.article_service.render_markdown_with_shortcodes(
+    // Re-render HTML from the source to ensure heading IDs match TOC
+    let toc = state
+        .article_service
+        .extract_source_toc(input_format, &response.content);
+    response.content_html = state.article_service.render_source(
+        input_format,
         &response.content,
         Some(article_id),
         None,
     );
-    response = response.with_toc(toc);
+    // Fill in `[poll]`, `[faq]` and `[attachments]` placeholders
+    response.content_html = state
+        .poll_service
+        .render_embeds(&response.content_html)
+        .await;
+    response.content_html = state
+        .faq_service
+        .render_embeds(&response.content_html)
+        .await;
+    let attachments = crate::api::attachments::for_article(&state, article_id).await;
+    response.content_html =
+        crate::services::attachment::fill_embeds(&response.content_html, &attachments);
+    let authors = crate::api::article_authors::for_article(&state, article_id).await;
+    let custom_fields = crate::api::custom_fields::for_content(
+        &state,
+        crate::models::ContentKind::Article,
+        article_id,
+    )
+    .await;
+    response = response
+        .with_toc(toc)
+        .with_attachments(attachments)
+        .with_authors(authors)
+        .with_custom_fields(custom_fields)
+        .with_site_license(&license::load(&state.settings_service).await);
 
     // Generate canonical URL if redirect is needed
     if needs_redirect {
@@ -540,7 +1321,6 @@ pub async fn get_article(
 
     // Fetch prev/next articles via targeted SQL queries (2 queries instead of loading all)
     {
-        use crate::api::responses::ArticleLink;
         if let Some(pub_at) = article_published_at {
             if let Ok((prev, next)) = state.article_service.get_adjacent(article_id, pub_at).await {
                 let prev_link = prev.map(|a| ArticleLink {
@@ -578,6 +1358,17 @@ pub async fn get_article(
         }
     }
 
+    // Language variants for hreflang links and language switchers
+    response = response.with_translations(
+        crate::api::translations::alternates(
+            &state,
+            crate::models::ContentKind::Article,
+            article_id,
+        )
+        .await,
+    );
+    response = response.with_series(crate::api::series::navigation(&state, article_id).await);
+
     // Trigger article_before_display hook (can modify article data)
     let hook_data = serde_json::json!({
         "article": &response,
@@ -608,7 +1399,23 @@ pub async fn get_article(
         .hook_manager
         .trigger(crate::plugin::hook_names::ARTICLE_VIEW, view_data);
 
-    Ok(Json(response))
+    Ok(conditional_json(&headers, &response))
+}
+
+/// GET /api/v1/admin/articles/:id/slugs - Every slug the article has had
+///
+/// Old slugs redirect to the article and stay reserved after it is deleted.
+pub async fn list_article_slugs(
+    State(state): State<AppState>,
+    _user: AuthenticatedUser,
+    Path(id): Path<i64>,
+) -> Result<Json<Vec<ArticleSlug>>, ApiError> {
+    let slugs = state
+        .article_service
+        .list_slug_history(id)
+        .await
+        .map_err(|e| ApiError::internal_error(e.to_string()))?;
+    Ok(Json(slugs))
 }
 
 /// GET /api/v1/admin/articles/:id - Get article by ID (admin only)
@@ -619,13 +1426,14 @@ pub async fn get_article_by_id(
     State(state): State<AppState>,
     _user: AuthenticatedUser,
     Path(id): Path<i64>,
-) -> Result<Json<ArticleResponse>, ApiError> {
+) -> Result<impl IntoResponse, ApiError> {
     let article = state
         .article_service
         .get_by_id(id)
         .await
         .map_err(|e| ApiError::internal_error(e.to_string()))?
         .ok_or_else(|| ApiError::not_found(format!("Article not found: {}", id)))?;
+    let validators = version_headers(article.id, article.updated_at);
 
     // Fetch category and tags
     let category = state
@@ -640,20 +1448,47 @@ pub async fn get_article_by_id(
         .await
         .unwrap_or_default();
 
+    let input_format = article.input_format;
     let response: ArticleResponse = article.into();
     let response = response.with_category(category).with_tags(tags);
 
-    // Re-render HTML from raw markdown to ensure heading IDs match TOC
-    let toc = state.article_service.extract_toc(&response.content);
+    // Re-render HTML from the source to ensure heading IDs match TOC
+    let toc = state
+        .article_service
+        .extract_source_toc(input_format, &response.content);
     let mut response = response;
-    response.content_html = state.article_service.render_markdown_with_shortcodes(
+    response.content_html = state.article_service.render_source(
+        input_format,
         &response.content,
         Some(response.id),
         None,
     );
-    let response = response.with_toc(toc);
-
-    Ok(Json(response))
+    response.content_html = state
+        .poll_service
+        .render_embeds(&response.content_html)
+        .await;
+    response.content_html = state
+        .faq_service
+        .render_embeds(&response.content_html)
+        .await;
+    let attachments = crate::api::attachments::for_article(&state, response.id).await;
+    response.content_html =
+        crate::services::attachment::fill_embeds(&response.content_html, &attachments);
+    let authors = crate::api::article_authors::for_article(&state, response.id).await;
+    let custom_fields = crate::api::custom_fields::for_content(
+        &state,
+        crate::models::ContentKind::Article,
+        response.id,
+    )
+    .await;
+    let response = response
+        .with_toc(toc)
+        .with_attachments(attachments)
+        .with_authors(authors)
+        .with_custom_fields(custom_fields)
+        .with_site_license(&license::load(&state.settings_service).await);
+
+    Ok((validators, Json(response)))
 }
 
 /// POST /api/v1/articles - Create new article
@@ -686,6 +1521,33 @@ pub async fn create_article(
         .map(parse_scheduled_at)
         .transpose()?;
 
+    let input_format = parse_input_format(body.input_format.as_deref())?.unwrap_or_default();
+    let excerpt = body
+        .excerpt
+        .as_deref()
+        .map(str::trim)
+        .filter(|v| !v.is_empty())
+        .map(str::to_string);
+
+    let unmet_checklist = if status == Some(ArticleStatus::Published) || scheduled_at.is_some() {
+        let mut candidate = crate::models::Article::new(
+            body.slug.clone(),
+            body.title.clone(),
+            body.content.clone(),
+            String::new(),
+            user.0.id,
+            category_id,
+            ArticleStatus::Published,
+        );
+        if let Some(summary) = &body.summary {
+            candidate.meta = serde_json::json!({ "summary": summary });
+        }
+        candidate.excerpt = excerpt.clone();
+        check_publish_checklist(&state, &candidate).await?
+    } else {
+        Vec::new()
+    };
+
     let input = crate::models::CreateArticleInput {
         title: body.title,
         content: body.content,
@@ -695,6 +1557,11 @@ pub async fn create_article(
         category_id,
         status,
         scheduled_at,
+        input_format,
+        excerpt,
+        license: body.license,
+        stats: None,
+        reclaim_slug: check_reclaim_slug(&user, body.reclaim_slug)?,
     };
 
     let article = state
@@ -712,6 +1579,9 @@ pub async fn create_article(
                     serde_json::json!({"field": "slug", "value": slug}),
                 )
             }
+            crate::services::article::ArticleServiceError::ReservedSlug(slug) => {
+                reserved_slug_error(slug)
+            }
             _ => ApiError::internal_error(e.to_string()),
         })?;
 
@@ -730,19 +1600,31 @@ pub async fn create_article(
         .map_err(|e| ApiError::internal_error(e.to_string()))?
         .unwrap_or(article);
 
-    Ok((StatusCode::CREATED, Json(article.into())))
+    Ok((
+        StatusCode::CREATED,
+        Json(
+            ArticleResponse::from(article)
+                .with_site_license(&license::load(&state.settings_service).await)
+                .with_unmet_checklist(unmet_checklist),
+        ),
+    ))
 }
 
 /// PUT /api/v1/articles/:id - Update article
 ///
 /// Requires authentication and permission to edit.
 /// Satisfies requirement 1.3: Article update
+///
+/// `If-Match` (with the ETag from reading the article) or
+/// `If-Unmodified-Since` make the update fail with 412 when the article was
+/// changed in the meantime.
 pub async fn update_article(
     State(state): State<AppState>,
     user: AuthenticatedUser,
     Path(id): Path<i64>,
+    headers: HeaderMap,
     Json(body): Json<UpdateArticleRequest>,
-) -> Result<Json<ArticleResponse>, ApiError> {
+) -> Result<impl IntoResponse, ApiError> {
     // Check if article exists and user can edit
     let existing = state
         .article_service
@@ -756,6 +1638,7 @@ pub async fn update_article(
             "You don't have permission to edit this article",
         ));
     }
+    check_write_preconditions(&headers, existing.id, existing.updated_at)?;
 
     let status = parse_article_status_input(body.status.as_deref())?;
 
@@ -766,6 +1649,36 @@ pub async fn update_article(
         None => None,
     };
 
+    let publishing =
+        status == Some(ArticleStatus::Published) && existing.status != ArticleStatus::Published;
+    let unmet_checklist = if publishing || matches!(scheduled_at, Some(Some(_))) {
+        let mut candidate = existing.clone();
+        if let Some(thumbnail) = &body.thumbnail {
+            candidate.thumbnail = thumbnail.clone();
+        }
+        if let Some(category_id) = body.category_id {
+            candidate.category_id = category_id;
+        }
+        if let Some(meta_title) = &body.meta_title {
+            candidate.meta_title = meta_title.clone();
+        }
+        if let Some(meta_description) = &body.meta_description {
+            candidate.meta_description = meta_description.clone();
+        }
+        if let Some(excerpt) = &body.excerpt {
+            candidate.excerpt = excerpt.clone();
+        }
+        if let Some(summary) = &body.summary {
+            if !candidate.meta.is_object() {
+                candidate.meta = serde_json::json!({});
+            }
+            candidate.meta["summary"] = serde_json::Value::from(summary.as_str());
+        }
+        check_publish_checklist(&state, &candidate).await?
+    } else {
+        Vec::new()
+    };
+
     let input = crate::models::UpdateArticleInput {
         title: body.title,
         content: body.content,
@@ -777,6 +1690,15 @@ pub async fn update_article(
         is_pinned: body.is_pinned,
         pin_order: body.pin_order,
         scheduled_at,
+        input_format: parse_input_format(body.input_format.as_deref())?,
+        meta_title: body.meta_title,
+        meta_description: body.meta_description,
+        canonical_url: body.canonical_url,
+        noindex: body.noindex,
+        excerpt: body.excerpt,
+        license: body.license,
+        stats: None,
+        reclaim_slug: check_reclaim_slug(&user, body.reclaim_slug)?,
     };
 
     let article = state
@@ -797,6 +1719,9 @@ pub async fn update_article(
                     serde_json::json!({"field": "slug", "value": slug}),
                 )
             }
+            crate::services::article::ArticleServiceError::ReservedSlug(slug) => {
+                reserved_slug_error(slug)
+            }
             _ => ApiError::internal_error(e.to_string()),
         })?;
 
@@ -815,7 +1740,14 @@ pub async fn update_article(
         .map_err(|e| ApiError::internal_error(e.to_string()))?
         .unwrap_or(article);
 
-    Ok(Json(article.into()))
+    Ok((
+        version_headers(article.id, article.updated_at),
+        Json(
+            ArticleResponse::from(article)
+                .with_site_license(&license::load(&state.settings_service).await)
+                .with_unmet_checklist(unmet_checklist),
+        ),
+    ))
 }
 
 /// DELETE /api/v1/articles/:id - Delete article
@@ -841,13 +1773,55 @@ pub async fn delete_article(
         ));
     }
 
+    remove_article(&state, id).await?;
+
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// Delete an article together with its attachments, translations, series
+/// membership, authors, audience and custom fields
+pub(crate) async fn remove_article(state: &AppState, id: i64) -> Result<(), ApiError> {
+    // Before the article goes, so the files are found and deleted too
+    if let Err(e) = state.attachment_service.remove_article(id).await {
+        tracing::warn!("Failed to remove attachments of article {}: {}", id, e);
+    }
     state
         .article_service
         .delete(id)
         .await
         .map_err(|e| ApiError::internal_error(e.to_string()))?;
+    forget_article(state, id).await;
 
-    Ok(StatusCode::NO_CONTENT)
+    Ok(())
+}
+
+/// Drop what a deleted article left behind in other services
+///
+/// Failures are logged; the article itself is already gone.
+pub(crate) async fn forget_article(state: &AppState, id: i64) {
+    if let Err(e) = state
+        .translation_service
+        .remove(crate::models::ContentKind::Article, id)
+        .await
+    {
+        tracing::warn!("Failed to remove translation of article {}: {}", id, e);
+    }
+    if let Err(e) = state.series_service.remove_article(id).await {
+        tracing::warn!("Failed to remove article {} from its series: {}", id, e);
+    }
+    if let Err(e) = state.article_author_service.remove_article(id).await {
+        tracing::warn!("Failed to remove authors of article {}: {}", id, e);
+    }
+    if let Err(e) = state.reader_group_service.remove_article(id).await {
+        tracing::warn!("Failed to remove audience of article {}: {}", id, e);
+    }
+    if let Err(e) = state
+        .custom_field_service
+        .remove_content(crate::models::ContentKind::Article, id)
+        .await
+    {
+        tracing::warn!("Failed to remove custom fields of article {}: {}", id, e);
+    }
 }
 
 /// Response for resolve endpoint
@@ -871,6 +1845,7 @@ pub struct ResolveArticleResponse {
 /// If the requested path doesn't match the canonical URL, `should_redirect` will be true.
 pub async fn resolve_article(
     State(state): State<AppState>,
+    user: Option<Extension<AuthenticatedUser>>,
     Query(query): Query<ResolveArticleQuery>,
 ) -> Result<Json<ResolveArticleResponse>, ApiError> {
     let path = query.path.trim_start_matches('/');
@@ -884,12 +1859,20 @@ pub async fn resolve_article(
             .await
             .map_err(|e| ApiError::internal_error(e.to_string()))?
     } else {
-        // Try as slug
-        state
+        // Try as slug, then as a slug the article had before
+        match state
             .article_service
             .get_by_slug(path)
             .await
             .map_err(|e| ApiError::internal_error(e.to_string()))?
+        {
+            Some(article) => Some(article),
+            None => state
+                .article_service
+                .get_by_previous_slug(path)
+                .await
+                .map_err(|e| ApiError::internal_error(e.to_string()))?,
+        }
     };
 
     let article = article.ok_or_else(|| ApiError::not_found("Article not found"))?;
@@ -927,24 +1910,73 @@ pub async fn resolve_article(
         .await
         .ok()
         .flatten();
+    if category
+        .as_ref()
+        .is_some_and(|c| !c.readable_by(viewer_clearance(user.as_ref())))
+    {
+        return Err(ApiError::not_found("Article not found"));
+    }
+    ensure_readable(
+        &state,
+        article.id,
+        viewer_reader(user.as_ref()).as_ref(),
+        || ApiError::not_found("Article not found"),
+    )
+    .await?;
     let tags = state
         .tag_service
         .get_by_article_id(article.id)
         .await
         .unwrap_or_default();
 
+    let input_format = article.input_format;
     let response: ArticleResponse = article.into();
     let response = response.with_category(category).with_tags(tags);
 
-    // Re-render HTML from raw markdown to ensure heading IDs match TOC
-    let toc = state.article_service.extract_toc(&response.content);
+    // Re-render HTML from the source to ensure heading IDs match TOC
+    let toc = state
+        .article_service
+        .extract_source_toc(input_format, &response.content);
     let mut response = response;
-    response.content_html = state.article_service.render_markdown_with_shortcodes(
+    response.content_html = state.article_service.render_source(
+        input_format,
         &response.content,
         Some(response.id),
         None,
     );
-    let response = response.with_toc(toc);
+    response.content_html = state
+        .poll_service
+        .render_embeds(&response.content_html)
+        .await;
+    response.content_html = state
+        .faq_service
+        .render_embeds(&response.content_html)
+        .await;
+    let attachments = crate::api::attachments::for_article(&state, response.id).await;
+    response.content_html =
+        crate::services::attachment::fill_embeds(&response.content_html, &attachments);
+    let translations = crate::api::translations::alternates(
+        &state,
+        crate::models::ContentKind::Article,
+        response.id,
+    )
+    .await;
+    let series = crate::api::series::navigation(&state, response.id).await;
+    let authors = crate::api::article_authors::for_article(&state, response.id).await;
+    let custom_fields = crate::api::custom_fields::for_content(
+        &state,
+        crate::models::ContentKind::Article,
+        response.id,
+    )
+    .await;
+    let response = response
+        .with_toc(toc)
+        .with_translations(translations)
+        .with_series(series)
+        .with_attachments(attachments)
+        .with_authors(authors)
+        .with_custom_fields(custom_fields)
+        .with_site_license(&license::load(&state.settings_service).await);
 
     Ok(Json(ResolveArticleResponse {
         article: response,
@@ -974,7 +2006,7 @@ where
 
 #[cfg(test)]
 mod tests {
-    use super::UpdateArticleRequest;
+    use super::{archive_tree, ArchiveMonth, FieldSelection, UpdateArticleRequest};
 
     #[test]
     fn update_article_request_distinguishes_thumbnail_patch_states() {
@@ -991,4 +2023,58 @@ mod tests {
             serde_json::from_str(r#"{"thumbnail":"/uploads/cover.png"}"#).unwrap();
         assert_eq!(set.thumbnail, Some(Some("/uploads/cover.png".to_string())));
     }
+
+    #[test]
+    fn update_article_request_accepts_seo_patches() {
+        let body: UpdateArticleRequest = serde_json::from_str(
+            r#"{"meta_title":" Better title ","meta_description":null,"noindex":true}"#,
+        )
+        .unwrap();
+        assert_eq!(body.meta_title, Some(Some("Better title".to_string())));
+        assert_eq!(body.meta_description, Some(None));
+        assert_eq!(body.canonical_url, None);
+        assert_eq!(body.noindex, Some(true));
+
+        assert!(serde_json::from_str::<UpdateArticleRequest>(r#"{"canonical_url":1}"#).is_err());
+    }
+
+    #[test]
+    fn field_selection_keeps_requested_fields() {
+        let all = FieldSelection::parse(None).unwrap();
+        assert!(all.needs_content());
+        assert!(all.includes("tags"));
+
+        let light = FieldSelection::parse(Some("slug, title,thumbnail")).unwrap();
+        assert!(!light.needs_content());
+        assert!(light.includes("id"));
+        assert!(!light.includes("tags"));
+
+        // Counts are stored, so they come without the body
+        let with_reading_time = FieldSelection::parse(Some("title,reading_time")).unwrap();
+        assert!(!with_reading_time.needs_content());
+        let with_html = FieldSelection::parse(Some("title,content_html")).unwrap();
+        assert!(with_html.needs_content());
+
+        assert!(FieldSelection::parse(Some("title,password_hash")).is_err());
+    }
+
+    #[test]
+    fn archive_months_group_into_years() {
+        let years = archive_tree(vec![
+            ("2025-02".to_string(), 3),
+            ("2025-01".to_string(), 1),
+            ("2024-12".to_string(), 2),
+            ("bad".to_string(), 9),
+        ]);
+        assert_eq!(years.len(), 2);
+        assert_eq!((years[0].year, years[0].count), (2025, 4));
+        assert_eq!(
+            years[0].months,
+            vec![
+                ArchiveMonth { month: 2, count: 3 },
+                ArchiveMonth { month: 1, count: 1 }
+            ]
+        );
+        assert_eq!((years[1].year, years[1].count), (2024, 2));
+    }
 }
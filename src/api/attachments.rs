@@ -0,0 +1,388 @@
+//! Article attachment API endpoints.
+//!
+//! - GET /api/v1/admin/articles/:id/attachments - An article's attachments
+//! - POST /api/v1/admin/articles/:id/attachments - Upload an attachment
+//!   (multipart field `file`)
+//! - PUT /api/v1/admin/articles/:id/attachments - Reorder attachments
+//! - PUT /api/v1/admin/articles/:id/attachments/:attachment_id - Change who
+//!   may download it
+//! - DELETE /api/v1/admin/articles/:id/attachments/:attachment_id - Remove one
+//! - GET /api/v1/admin/articles/:id/attachments/:attachment_id/downloads -
+//!   Download stats and captured addresses
+//! - GET /api/v1/admin/attachments/top - Most downloaded attachments
+//! - GET|POST /api/v1/attachments/:id/download - Download a file, counting it
+//!
+//! Readers get the list as `attachments` on the article response, and the
+//! `[attachments]` shortcode renders it as a download list in the content.
+
+use axum::{
+    body::Body,
+    extract::{Multipart, Path, Query, State},
+    http::{header, HeaderValue, StatusCode},
+    response::{IntoResponse, Response},
+    routing::get,
+    Extension, Form, Json, Router,
+};
+use serde::{Deserialize, Serialize};
+
+use crate::api::middleware::{ApiError, AppState, AuthenticatedUser};
+use crate::models::{
+    ArticleAttachment, ArticleStatus, AttachmentAccess, AttachmentOrderInput, AttachmentUpdateInput,
+};
+use crate::services::AttachmentError;
+
+/// Days of daily downloads returned by default
+const DEFAULT_STATS_DAYS: u32 = 30;
+const MAX_STATS_DAYS: u32 = 365;
+
+/// Build the public download router; needs `optional_auth` for login gates
+pub fn public_router() -> Router<AppState> {
+    Router::new().route(
+        "/{id}/download",
+        get(download_attachment).post(download_attachment_with_email),
+    )
+}
+
+#[derive(Debug, Serialize)]
+struct AttachmentsResponse {
+    attachments: Vec<ArticleAttachment>,
+}
+
+#[derive(Debug, Serialize)]
+struct AttachmentResponse {
+    attachment: ArticleAttachment,
+}
+
+fn map_attachment_error(e: AttachmentError) -> ApiError {
+    match e {
+        AttachmentError::NotFound(_) => ApiError::not_found(e.to_string()),
+        AttachmentError::Validation(_) => ApiError::validation_error(e.to_string()),
+        AttachmentError::Gated(access) => ApiError::with_details(
+            if access == AttachmentAccess::Login {
+                "UNAUTHORIZED"
+            } else {
+                "FORBIDDEN"
+            },
+            e.to_string(),
+            serde_json::json!({ "access": access }),
+        ),
+        AttachmentError::Internal(e) => ApiError::internal_error(e.to_string()),
+    }
+}
+
+async fn ensure_article(state: &AppState, id: i64) -> Result<(), ApiError> {
+    state
+        .article_service
+        .get_by_id(id)
+        .await
+        .map_err(|e| ApiError::internal_error(e.to_string()))?
+        .ok_or_else(|| ApiError::not_found(format!("Article not found: {}", id)))?;
+    Ok(())
+}
+
+/// GET /api/v1/admin/articles/{id}/attachments - List an article's attachments
+pub async fn list_attachments(
+    State(state): State<AppState>,
+    Path(id): Path<i64>,
+) -> Result<impl IntoResponse, ApiError> {
+    ensure_article(&state, id).await?;
+    let attachments = state
+        .attachment_service
+        .list(id)
+        .await
+        .map_err(map_attachment_error)?;
+    Ok(Json(AttachmentsResponse { attachments }))
+}
+
+/// POST /api/v1/admin/articles/{id}/attachments - Attach a file
+///
+/// Accepts multipart/form-data with a single file field named "file". The
+/// extension must be in `upload.attachment_extensions`. An optional `access`
+/// field (`public`, `login` or `email`) gates downloads.
+pub async fn upload_attachment(
+    State(state): State<AppState>,
+    Path(id): Path<i64>,
+    mut multipart: Multipart,
+) -> Result<impl IntoResponse, ApiError> {
+    ensure_article(&state, id).await?;
+
+    let mut access = AttachmentAccess::Public;
+    let mut file = None;
+    while let Some(field) = multipart
+        .next_field()
+        .await
+        .map_err(|e| ApiError::internal_error(format!("Failed to read multipart: {}", e)))?
+    {
+        match field.name() {
+            Some("access") => {
+                let value = field.text().await.map_err(|e| {
+                    ApiError::internal_error(format!("Failed to read field: {}", e))
+                })?;
+                access = AttachmentAccess::parse(value.trim()).ok_or_else(|| {
+                    ApiError::validation_error("access must be public, login or email")
+                })?;
+            }
+            Some("file") if file.is_none() => {
+                let filename = field.file_name().unwrap_or("").to_string();
+                let content_type = field
+                    .content_type()
+                    .map(|s| s.to_string())
+                    .unwrap_or_else(|| "application/octet-stream".to_string());
+                crate::api::upload::validate_safe_upload_name(&filename, &content_type)?;
+
+                let data = field
+                    .bytes()
+                    .await
+                    .map_err(|e| ApiError::internal_error(format!("Failed to read file: {}", e)))?;
+                file = Some((filename, content_type, data));
+            }
+            _ => {}
+        }
+    }
+
+    let Some((filename, content_type, data)) = file else {
+        return Err(ApiError::validation_error("No file provided"));
+    };
+    let attachment = state
+        .attachment_service
+        .add(id, &filename, &content_type, access, &data)
+        .await
+        .map_err(map_attachment_error)?;
+    Ok((StatusCode::CREATED, Json(AttachmentResponse { attachment })))
+}
+
+/// PUT /api/v1/admin/articles/{id}/attachments - Reorder attachments
+///
+/// Body: `{"attachment_ids": [3, 1, 2]}`, listing every attachment once.
+pub async fn reorder_attachments(
+    State(state): State<AppState>,
+    Path(id): Path<i64>,
+    Json(input): Json<AttachmentOrderInput>,
+) -> Result<impl IntoResponse, ApiError> {
+    ensure_article(&state, id).await?;
+    let attachments = state
+        .attachment_service
+        .reorder(id, &input.attachment_ids)
+        .await
+        .map_err(map_attachment_error)?;
+    Ok(Json(AttachmentsResponse { attachments }))
+}
+
+/// PUT /api/v1/admin/articles/{id}/attachments/{attachment_id} - Change who
+/// may download an attachment
+///
+/// Body: `{"access": "email"}`; one of `public`, `login` and `email`.
+pub async fn update_attachment(
+    State(state): State<AppState>,
+    Path((id, attachment_id)): Path<(i64, i64)>,
+    Json(input): Json<AttachmentUpdateInput>,
+) -> Result<impl IntoResponse, ApiError> {
+    let attachment = state
+        .attachment_service
+        .set_access(id, attachment_id, input.access)
+        .await
+        .map_err(map_attachment_error)?;
+    Ok(Json(AttachmentResponse { attachment }))
+}
+
+#[derive(Debug, Deserialize)]
+pub struct DownloadStatsQuery {
+    /// Days of daily counts, today included (default 30, at most 365)
+    days: Option<u32>,
+}
+
+/// GET /api/v1/admin/articles/{id}/attachments/{attachment_id}/downloads -
+/// Daily downloads, signed-in downloads and the addresses left for an
+/// attachment
+pub async fn attachment_downloads(
+    State(state): State<AppState>,
+    Path((id, attachment_id)): Path<(i64, i64)>,
+    Query(query): Query<DownloadStatsQuery>,
+) -> Result<impl IntoResponse, ApiError> {
+    let days = query
+        .days
+        .unwrap_or(DEFAULT_STATS_DAYS)
+        .clamp(1, MAX_STATS_DAYS);
+    let stats = state
+        .attachment_service
+        .download_stats(id, attachment_id, days)
+        .await
+        .map_err(map_attachment_error)?;
+    Ok(Json(stats))
+}
+
+#[derive(Debug, Deserialize)]
+pub struct TopAttachmentsQuery {
+    limit: Option<i64>,
+}
+
+/// GET /api/v1/admin/attachments/top - Most downloaded attachments across
+/// the site (`?limit=`, default 10, at most 100)
+pub async fn top_attachments(
+    State(state): State<AppState>,
+    Query(query): Query<TopAttachmentsQuery>,
+) -> Result<impl IntoResponse, ApiError> {
+    let attachments = state
+        .attachment_service
+        .top(query.limit.unwrap_or(10))
+        .await
+        .map_err(map_attachment_error)?;
+    Ok(Json(AttachmentsResponse { attachments }))
+}
+
+/// DELETE /api/v1/admin/articles/{id}/attachments/{attachment_id} - Remove an
+/// attachment and its file
+pub async fn delete_attachment(
+    State(state): State<AppState>,
+    Path((id, attachment_id)): Path<(i64, i64)>,
+) -> Result<impl IntoResponse, ApiError> {
+    state
+        .attachment_service
+        .delete(id, attachment_id)
+        .await
+        .map_err(map_attachment_error)?;
+    Ok(StatusCode::NO_CONTENT)
+}
+
+#[derive(Debug, Deserialize)]
+pub struct DownloadForm {
+    #[serde(default)]
+    email: Option<String>,
+}
+
+/// GET /api/v1/attachments/{id}/download - Download an attachment
+///
+/// Login-gated files need a session; email-gated ones answer 403 with
+/// `details.access = "email"` unless the visitor is signed in.
+async fn download_attachment(
+    State(state): State<AppState>,
+    user: Option<Extension<AuthenticatedUser>>,
+    Path(id): Path<i64>,
+) -> Result<Response, ApiError> {
+    serve_download(&state, user.map(|Extension(u)| u), id, None).await
+}
+
+/// POST /api/v1/attachments/{id}/download - Download an email-gated
+/// attachment
+///
+/// Form body: `email=reader@example.com`. The address is logged with the
+/// download and listed in the attachment's stats.
+async fn download_attachment_with_email(
+    State(state): State<AppState>,
+    user: Option<Extension<AuthenticatedUser>>,
+    Path(id): Path<i64>,
+    Form(form): Form<DownloadForm>,
+) -> Result<Response, ApiError> {
+    serve_download(
+        &state,
+        user.map(|Extension(u)| u),
+        id,
+        form.email.as_deref(),
+    )
+    .await
+}
+
+async fn serve_download(
+    state: &AppState,
+    user: Option<AuthenticatedUser>,
+    id: i64,
+    email: Option<&str>,
+) -> Result<Response, ApiError> {
+    let attachment = state
+        .attachment_service
+        .get(id)
+        .await
+        .map_err(map_attachment_error)?;
+    let article = state
+        .article_service
+        .get_by_id(attachment.article_id)
+        .await
+        .map_err(|e| ApiError::internal_error(e.to_string()))?
+        .ok_or_else(|| ApiError::not_found("Attachment not found"))?;
+    let user = user.map(|AuthenticatedUser(user)| user);
+    let visible = article.status == ArticleStatus::Published
+        || user.as_ref().is_some_and(|u| u.can_edit(article.author_id));
+    if !visible {
+        return Err(ApiError::not_found("Attachment not found"));
+    }
+
+    let path = state
+        .attachment_service
+        .download(&attachment, user.as_ref().map(|u| u.id), email)
+        .await
+        .map_err(map_attachment_error)?;
+    let contents = tokio::fs::read(&path)
+        .await
+        .map_err(|e| ApiError::internal_error(format!("Failed to read attachment: {}", e)))?;
+
+    let mut response = Response::new(Body::from(contents));
+    let headers = response.headers_mut();
+    headers.insert(
+        header::CONTENT_TYPE,
+        HeaderValue::from_str(&attachment.content_type)
+            .unwrap_or_else(|_| HeaderValue::from_static("application/octet-stream")),
+    );
+    if let Ok(value) = HeaderValue::from_str(&content_disposition(&attachment.filename)) {
+        headers.insert(header::CONTENT_DISPOSITION, value);
+    }
+    headers.insert(
+        header::X_CONTENT_TYPE_OPTIONS,
+        HeaderValue::from_static("nosniff"),
+    );
+    headers.insert(header::CACHE_CONTROL, HeaderValue::from_static("no-store"));
+    Ok(response)
+}
+
+/// `Content-Disposition` offering the original file name: an ASCII fallback
+/// plus the UTF-8 name for clients that understand `filename*`
+fn content_disposition(filename: &str) -> String {
+    let fallback: String = filename
+        .chars()
+        .map(|c| {
+            if (c.is_ascii_graphic() || c == ' ') && c != '"' && c != '\\' {
+                c
+            } else {
+                '_'
+            }
+        })
+        .collect();
+    format!(
+        "attachment; filename=\"{}\"; filename*=UTF-8''{}",
+        fallback,
+        urlencoding::encode(filename)
+    )
+}
+
+/// Attachments of an article; empty on failure so a broken list never
+/// breaks the article itself
+pub(crate) async fn for_article(state: &AppState, article_id: i64) -> Vec<ArticleAttachment> {
+    state
+        .attachment_service
+        .list(article_id)
+        .await
+        .unwrap_or_else(|e| {
+            tracing::warn!(
+                "Failed to load attachments of article {}: {}",
+                article_id,
+                e
+            );
+            Vec::new()
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::content_disposition;
+
+    #[test]
+    fn disposition_keeps_the_original_name() {
+        assert_eq!(
+            content_disposition("report.pdf"),
+            "attachment; filename=\"report.pdf\"; filename*=UTF-8''report.pdf"
+        );
+        assert_eq!(
+            content_disposition("报告 \"v2\".pdf"),
+            "attachment; filename=\"__ _v2_.pdf\"; filename*=UTF-8''%E6%8A%A5%E5%91%8A%20%22v2%22.pdf"
+        );
+    }
+}
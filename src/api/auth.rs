@@ -5,6 +5,9 @@
 //! - POST /api/v1/auth/login - User login
 //! - POST /api/v1/auth/logout - User logout
 //! - GET /api/v1/auth/me - Get current user
+//! - GET/PUT /api/v1/auth/preferences - Admin UI preferences of the current user
+//!
+//! Passkey login lives in `passkeys.rs`.
 //!
 //! Satisfies requirements:
 //! - 4.1: First user becomes admin
@@ -12,11 +15,14 @@
 //! - 4.3: User login
 
 use crate::api::middleware::{
-    extract_client_ip, should_set_secure_cookie, ApiError, AppState, AuthenticatedUser,
+    ensure_ip_not_blocked, extract_client_ip, should_set_secure_cookie, ApiError, AppState,
+    AuthenticatedUser,
 };
 use crate::config::DatabaseDriver;
 use crate::db::DynDatabasePool;
+use crate::models::{UserPreferences, MAX_PREFERENCES_BYTES};
 use crate::services::user::{LoginInput, RegisterInput, UserServiceError};
+use crate::services::AbuseSignal;
 use axum::{
     extract::{ConnectInfo, State},
     http::{header, HeaderMap, HeaderValue, StatusCode},
@@ -34,6 +40,7 @@ pub struct RegisterRequest {
     pub username: String,
     pub email: String,
     pub password: String,
+    pub captcha_token: Option<String>,
 }
 
 /// Request body for user login
@@ -61,6 +68,7 @@ pub struct UserResponse {
     pub display_name: Option<String>,
     pub avatar: Option<String>,
     pub totp_enabled: bool,
+    pub password_reset_required: bool,
     pub created_at: String,
 }
 
@@ -75,6 +83,7 @@ impl From<crate::models::User> for UserResponse {
             display_name: user.display_name,
             avatar: user.avatar,
             totp_enabled: user.totp_enabled,
+            password_reset_required: user.password_reset_required,
             created_at: user.created_at.to_rfc3339(),
         }
     }
@@ -87,6 +96,7 @@ pub fn protected_router() -> Router<AppState> {
         .route("/me", get(get_current_user))
         .route("/profile", put(update_profile))
         .route("/password", put(change_password))
+        .route("/preferences", get(get_preferences).put(update_preferences))
 }
 
 /// Build public auth routes (no auth required)
@@ -143,8 +153,13 @@ async fn register(
         ));
     }
 
+    let client_ip = extract_client_ip(&headers, addr);
+    ensure_ip_not_blocked(&state, &client_ip).await?;
+
     let password = body.password.clone();
-    let input = RegisterInput::new(body.username, body.email, body.password);
+    let ip_addr = Some(client_ip);
+    let input = RegisterInput::new(body.username, body.email, body.password)
+        .with_captcha(body.captcha_token, ip_addr.clone());
 
     let user = state
         .user_service
@@ -160,7 +175,6 @@ async fn register(
 
     // Create session for the new user
     let login_input = LoginInput::new(&user.username, &password);
-    let ip_addr = Some(extract_client_ip(&headers, addr));
     let ua = headers
         .get(header::USER_AGENT)
         .and_then(|h| h.to_str().ok())
@@ -233,6 +247,10 @@ async fn login(
     // Check IP rate limit (10 requests per minute)
     if let Some(ip) = ip_address.as_ref().and_then(|s| s.parse().ok()) {
         if state.rate_limiter.is_ip_limited(ip).await {
+            state
+                .ip_reputation
+                .record(ip, AbuseSignal::LoginRateLimited)
+                .await;
             log_login_attempt(
                 &state.pool,
                 &body.username_or_email,
@@ -257,6 +275,12 @@ async fn login(
         .check_username_limit(&body.username_or_email)
         .await
     {
+        if let Some(ip) = ip_address.as_deref() {
+            state
+                .ip_reputation
+                .record_str(ip, AbuseSignal::LoginRateLimited)
+                .await;
+        }
         log_login_attempt(
             &state.pool,
             &body.username_or_email,
@@ -286,6 +310,7 @@ async fn login(
             let ua = user_agent.clone();
             let pool = state.pool.clone();
             let limiter = state.rate_limiter.clone();
+            let reputation = state.ip_reputation.clone();
 
             // Determine error type before moving
             let is_banned = matches!(&e, UserServiceError::AuthenticationError(msg) if msg.contains("banned") || msg.contains("封禁"));
@@ -293,6 +318,11 @@ async fn login(
 
             tokio::spawn(async move {
                 limiter.record_failed_attempt(&username).await;
+                if is_auth_error && !is_banned {
+                    if let Some(ip) = ip.as_deref() {
+                        reputation.record_str(ip, AbuseSignal::FailedLogin).await;
+                    }
+                }
                 let reason = if is_banned {
                     "User banned"
                 } else if is_auth_error {
@@ -316,6 +346,27 @@ async fn login(
             }
         })?;
 
+    // Admins with a passkey may be required to use it
+    let password_allowed = state
+        .webauthn_service
+        .password_login_allowed(&user)
+        .await
+        .map_err(|e| ApiError::internal_error(e.to_string()))?;
+    if !password_allowed {
+        log_login_attempt(
+            &state.pool,
+            &body.username_or_email,
+            ip_address.as_deref(),
+            user_agent.as_deref(),
+            false,
+            Some("Password login disabled, passkey required"),
+        )
+        .await;
+        return Err(ApiError::forbidden(
+            "Password sign-in is disabled for this account. Please sign in with a passkey.",
+        ));
+    }
+
     // Check if 2FA is enabled — return challenge instead of full login
     if user.totp_enabled {
         let challenge_token = crate::api::middleware::generate_csrf_token();
@@ -515,6 +566,53 @@ async fn update_profile(
     Ok(Json(updated.into()))
 }
 
+/// GET /api/v1/auth/preferences - Get current user's admin UI preferences
+///
+/// Returns defaults when nothing has been stored yet.
+async fn get_preferences(
+    State(state): State<AppState>,
+    user: AuthenticatedUser,
+) -> Result<Json<UserPreferences>, ApiError> {
+    let stored = state
+        .preferences_repo
+        .get(user.0.id)
+        .await
+        .map_err(|e| ApiError::internal_error(e.to_string()))?;
+
+    // A document that no longer matches the schema falls back to defaults
+    let prefs = stored
+        .and_then(|data| serde_json::from_str(&data).ok())
+        .unwrap_or_default();
+    Ok(Json(prefs))
+}
+
+/// PUT /api/v1/auth/preferences - Replace current user's admin UI preferences
+async fn update_preferences(
+    State(state): State<AppState>,
+    user: AuthenticatedUser,
+    Json(body): Json<serde_json::Value>,
+) -> Result<Json<UserPreferences>, ApiError> {
+    let prefs: UserPreferences = serde_json::from_value(body)
+        .map_err(|e| ApiError::validation_error(format!("Invalid preferences: {}", e)))?;
+    prefs.validate().map_err(ApiError::validation_error)?;
+
+    let data =
+        serde_json::to_string(&prefs).map_err(|e| ApiError::internal_error(e.to_string()))?;
+    if data.len() > MAX_PREFERENCES_BYTES {
+        return Err(ApiError::validation_error(format!(
+            "Preferences must not exceed {} bytes",
+            MAX_PREFERENCES_BYTES
+        )));
+    }
+
+    state
+        .preferences_repo
+        .set(user.0.id, &data)
+        .await
+        .map_err(|e| ApiError::internal_error(e.to_string()))?;
+    Ok(Json(prefs))
+}
+
 /// Request body for changing password
 #[derive(Debug, Deserialize)]
 pub struct ChangePasswordRequest {
@@ -528,37 +626,15 @@ async fn change_password(
     user: AuthenticatedUser,
     Json(body): Json<ChangePasswordRequest>,
 ) -> Result<impl IntoResponse, ApiError> {
-    use crate::services::password::{hash_password, verify_password};
-
-    // Validate new password
-    if body.new_password.len() < 8 {
-        return Err(ApiError::validation_error(
-            "Password must be at least 8 characters",
-        ));
-    }
-
-    // Verify current password
-    let is_valid = verify_password(&body.current_password, &user.0.password_hash)
-        .map_err(|e| ApiError::internal_error(e.to_string()))?;
-
-    if !is_valid {
-        return Err(ApiError::validation_error("Current password is incorrect"));
-    }
-
-    // Hash new password
-    let new_hash =
-        hash_password(&body.new_password).map_err(|e| ApiError::internal_error(e.to_string()))?;
-
-    // Update user
     let user_id = user.0.id;
-    let mut updated_user = user.0;
-    updated_user.password_hash = new_hash;
-
     state
         .user_service
-        .update_user(updated_user)
+        .change_password(user.0, &body.current_password, &body.new_password)
         .await
-        .map_err(|e| ApiError::internal_error(e.to_string()))?;
+        .map_err(|e| match e {
+            UserServiceError::ValidationError(msg) => ApiError::validation_error(msg),
+            e => ApiError::internal_error(e.to_string()),
+        })?;
 
     // Hook: user_password_change
     state.hook_manager.trigger(
@@ -574,7 +650,7 @@ async fn change_password(
 // ============================================================================
 
 /// Log login attempt to database for security auditing
-async fn log_login_attempt(
+pub(crate) async fn log_login_attempt(
     pool: &DynDatabasePool,
     username: &str,
     ip_address: Option<&str>,
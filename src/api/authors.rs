@@ -0,0 +1,84 @@
+//! Public author archives
+//!
+//! - GET /api/v1/authors - Authors of published articles with their counts
+//! - GET /api/v1/authors/:username - Profile and published articles of an author
+//!
+//! Only the public view of a user is shown. Articles count for every user
+//! credited on them, as with the `?author=` article filter.
+
+use axum::{
+    extract::{Path, Query, State},
+    routing::get,
+    Extension, Json, Router,
+};
+use serde::Serialize;
+
+use crate::api::articles::{list_articles_inner, viewer_clearance, ListArticlesQuery};
+use crate::api::middleware::{ApiError, AppState, AuthenticatedUser};
+use crate::api::reader_groups::viewer_reader;
+use crate::api::responses::PaginatedArticlesResponse;
+use crate::models::{PublicUser, PublishedAuthor};
+
+/// Build the public authors router
+pub fn router() -> Router<AppState> {
+    Router::new()
+        .route("/", get(list_authors))
+        .route("/{username}", get(get_author))
+}
+
+/// Authors with published articles
+#[derive(Debug, Serialize)]
+pub struct AuthorsResponse {
+    pub authors: Vec<PublishedAuthor>,
+}
+
+/// An author and a page of their published articles
+#[derive(Debug, Serialize)]
+pub struct AuthorProfileResponse {
+    pub author: PublicUser,
+    #[serde(flatten)]
+    pub articles: PaginatedArticlesResponse,
+}
+
+/// GET /api/v1/authors - Authors of public articles, most articles first
+async fn list_authors(State(state): State<AppState>) -> Result<Json<AuthorsResponse>, ApiError> {
+    let authors = state
+        .article_author_service
+        .published_authors()
+        .await
+        .map_err(|e| ApiError::internal_error(e.to_string()))?;
+    Ok(Json(AuthorsResponse { authors }))
+}
+
+/// GET /api/v1/authors/:username - Public profile of an author
+///
+/// Takes the pagination, sort and field parameters of the article list.
+/// Users without published articles the reader may see are not found.
+async fn get_author(
+    State(state): State<AppState>,
+    user: Option<Extension<AuthenticatedUser>>,
+    Path(username): Path<String>,
+    Query(mut query): Query<ListArticlesQuery>,
+) -> Result<Json<AuthorProfileResponse>, ApiError> {
+    let author = state
+        .user_repo
+        .get_by_username(&username)
+        .await
+        .map_err(|e| ApiError::internal_error(e.to_string()))?
+        .filter(|u| u.is_active())
+        .ok_or_else(|| ApiError::not_found("Author not found"))?;
+
+    query.author = Some(author.id.to_string());
+    query.author_role = None;
+    let clearance = viewer_clearance(user.as_ref());
+    let reader = viewer_reader(user.as_ref());
+    let Json(articles) = list_articles_inner(state, query, true, clearance, reader).await?;
+    if articles.total == 0 {
+        return Err(ApiError::not_found("Author not found"));
+    }
+
+    Ok(Json(AuthorProfileResponse {
+        author: PublicUser::from(&author),
+        articles,
+    }))
+}
@@ -228,6 +228,7 @@ pub async fn verify_pow(
     }))
 }
 
+/// Verify a built-in proof-of-work or Cap token for a guest comment.
 pub async fn verify_comment_token(
     state: &AppState,
     token: Option<&str>,
@@ -237,6 +238,14 @@ pub async fn verify_comment_token(
     if !config.enabled {
         return Ok(());
     }
+    // Turnstile and hCaptcha tokens are verified by `CommentService::create`;
+    // checking them here as well would consume the single-use token twice.
+    if matches!(
+        config.provider.as_str(),
+        PROVIDER_TURNSTILE | PROVIDER_HCAPTCHA
+    ) {
+        return Ok(());
+    }
 
     let token = token
         .map(str::trim)
@@ -269,35 +278,7 @@ pub async fn verify_comment_token(
         return verify_cap_token(&cap_base_url, &config.site_key, &secret, token).await;
     }
 
-    let verify_url = match config.provider.as_str() {
-        PROVIDER_TURNSTILE => "https://challenges.cloudflare.com/turnstile/v0/siteverify",
-        PROVIDER_HCAPTCHA => "https://hcaptcha.com/siteverify",
-        _ => return Ok(()),
-    };
-
-    let client = reqwest::Client::new();
-    let mut form = vec![("secret", secret.as_str()), ("response", token)];
-    if let Some(ip) = remote_ip.filter(|value| !value.trim().is_empty()) {
-        form.push(("remoteip", ip));
-    }
-
-    let response = client
-        .post(verify_url)
-        .form(&form)
-        .send()
-        .await
-        .map_err(|e| ApiError::internal_error(format!("Captcha verification failed: {}", e)))?;
-
-    let result = response
-        .json::<CaptchaVerifyResponse>()
-        .await
-        .map_err(|e| ApiError::internal_error(format!("Invalid captcha response: {}", e)))?;
-
-    if result.success {
-        Ok(())
-    } else {
-        Err(ApiError::validation_error("Captcha verification failed"))
-    }
+    Ok(())
 }
 
 async fn verify_cap_token(
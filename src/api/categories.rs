@@ -4,20 +4,27 @@
 //! - GET /api/v1/categories - Get flat category list
 //! - GET /api/v1/categories/:slug/articles - Get articles in category
 //!
+//! Private categories (see [`CategoryAccess`]) are left out of the list and
+//! not found for readers without clearance.
+//!
 //! Satisfies requirements:
 //! - 2.3: Category article listing
 
 use axum::{
-    extract::{Path, Query, State},
+    extract::{Extension, Path, Query, State},
     routing::get,
     Json, Router,
 };
 use serde::{Deserialize, Serialize};
 
-use crate::api::common::{default_page, default_page_size};
-use crate::api::middleware::{ApiError, AppState};
+use crate::api::common::{default_page, default_page_size, parse_cursor};
+use crate::api::middleware::{ApiError, AppState, AuthenticatedUser};
+use crate::api::reader_groups::viewer_reader;
 use crate::api::responses::{ArticleSummary, PaginatedArticleSummaryResponse};
-use crate::models::{ArticleSortBy, ListParams};
+use crate::models::{
+    ArticleFilter, ArticleListScope, ArticleSortBy, ArticleStatus, CategoryAccess, ListParams,
+    PagedResult,
+};
 
 /// Query parameters for listing articles
 #[derive(Debug, Deserialize)]
@@ -26,6 +33,8 @@ pub struct ListArticlesQuery {
     pub page: u32,
     #[serde(default = "default_page_size")]
     pub page_size: u32,
+    /// Opaque cursor for keyset pagination (empty for the first page)
+    pub cursor: Option<String>,
 }
 
 /// Response for category list
@@ -41,6 +50,7 @@ pub struct CategoryNodeResponse {
     pub slug: String,
     pub name: String,
     pub description: Option<String>,
+    pub access: CategoryAccess,
 }
 
 /// Build the categories router
@@ -53,7 +63,9 @@ pub fn router() -> Router<AppState> {
 /// GET /api/v1/categories - Get flat category list
 async fn get_category_list(
     State(state): State<AppState>,
+    user: Option<Extension<AuthenticatedUser>>,
 ) -> Result<Json<CategoryTreeResponse>, ApiError> {
+    let clearance = CategoryAccess::clearance(user.as_ref().map(|Extension(u)| &u.0));
     let all = state
         .category_service
         .list()
@@ -62,11 +74,13 @@ async fn get_category_list(
 
     let categories: Vec<CategoryNodeResponse> = all
         .into_iter()
+        .filter(|c| c.readable_by(clearance))
         .map(|c| CategoryNodeResponse {
             id: c.id,
             slug: c.slug,
             name: c.name,
             description: c.description,
+            access: c.access,
         })
         .collect();
 
@@ -78,14 +92,18 @@ async fn get_category_list(
 /// Satisfies requirement 2.3: Category article listing
 async fn get_category_articles(
     State(state): State<AppState>,
+    user: Option<Extension<AuthenticatedUser>>,
     Path(slug): Path<String>,
     Query(query): Query<ListArticlesQuery>,
 ) -> Result<Json<PaginatedArticleSummaryResponse>, ApiError> {
+    let clearance = CategoryAccess::clearance(user.as_ref().map(|Extension(u)| &u.0));
+    let reader = viewer_reader(user.as_ref());
     let category = state
         .category_service
         .get_by_slug(&slug)
         .await
         .map_err(|e| ApiError::internal_error(e.to_string()))?
+        .filter(|c| c.readable_by(clearance))
         .ok_or_else(|| ApiError::not_found(format!("Category not found: {}", slug)))?;
 
     let params = ListParams::new(query.page, query.page_size);
@@ -95,11 +113,44 @@ async fn get_category_articles(
         .await
         .map_err(|e| ApiError::internal_error(e.to_string()))?;
 
-    let result = state
-        .article_service
-        .list_published_by_category_ids(&category_ids, &params, ArticleSortBy::default())
-        .await
-        .map_err(|e| ApiError::internal_error(e.to_string()))?;
+    let mut next_cursor = None;
+    let result = if let Some(ref cursor) = query.cursor {
+        let cursor = parse_cursor(cursor)?;
+        let page = state
+            .article_service
+            .list_by_cursor(
+                &ArticleListScope::PublishedInCategories(category_ids),
+                cursor.as_ref(),
+                params.limit(),
+                clearance,
+                reader.as_ref(),
+            )
+            .await
+            .map_err(|e| ApiError::internal_error(e.to_string()))?;
+        next_cursor = page.next_cursor;
+        PagedResult::new(page.items, page.total, &ListParams::new(1, params.per_page))
+    } else if clearance > CategoryAccess::Public {
+        // The dedicated published query only knows anonymous visitors;
+        // signed-in readers may also be in restricted articles' audiences
+        let filter = ArticleFilter {
+            status: Some(ArticleStatus::Published),
+            category_ids,
+            clearance: Some(clearance),
+            reader,
+            ..Default::default()
+        };
+        state
+            .article_service
+            .list_filtered(&params.with_filter(filter), reader.as_ref())
+            .await
+            .map_err(|e| ApiError::internal_error(e.to_string()))?
+    } else {
+        state
+            .article_service
+            .list_published_by_category_ids(&category_ids, &params, ArticleSortBy::default())
+            .await
+            .map_err(|e| ApiError::internal_error(e.to_string()))?
+    };
 
     let total = result.total;
     let page = result.page;
@@ -113,5 +164,6 @@ async fn get_category_articles(
         page,
         page_size: per_page,
         total_pages,
+        next_cursor,
     }))
 }
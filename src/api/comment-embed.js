@@ -0,0 +1,215 @@
+/**
+ * Noteva comment widget
+ * Embeds the comment thread of a Noteva article on an external site:
+ *
+ *   <div id="noteva-comments" data-article="article-slug"></div>
+ *   <script src="https://blog.example.com/embed/comments.js" async></script>
+ *
+ * The embedding site's origin must be listed in the blog's
+ * `comment_embed_origins` setting.
+ */
+(function () {
+  'use strict';
+
+  const script = document.currentScript;
+  const BASE = script ? new URL(script.src).origin : '';
+  const API = BASE + '/api/v1/embed/comments/';
+
+  const STYLE = `
+.noteva-embed { font: inherit; color: inherit; }
+.noteva-embed-list { list-style: none; margin: 0; padding: 0; }
+.noteva-embed-list .noteva-embed-list { margin-left: 2em; }
+.noteva-embed-comment { display: flex; gap: .75em; margin: 1em 0; }
+.noteva-embed-comment img { width: 40px; height: 40px; border-radius: 50%; flex: none; }
+.noteva-embed-meta { font-size: .85em; opacity: .7; }
+.noteva-embed-body { white-space: pre-wrap; word-break: break-word; margin: .25em 0; }
+.noteva-embed-reply { background: none; border: 0; padding: 0; color: inherit; opacity: .7; cursor: pointer; font-size: .85em; }
+.noteva-embed-form { display: grid; gap: .5em; margin-top: 1.5em; }
+.noteva-embed-form input, .noteva-embed-form textarea { font: inherit; padding: .5em; box-sizing: border-box; width: 100%; }
+.noteva-embed-form textarea { min-height: 6em; }
+.noteva-embed-notice { font-size: .9em; opacity: .8; }
+`;
+
+  function el(tag, className, text) {
+    const node = document.createElement(tag);
+    if (className) node.className = className;
+    if (text !== undefined && text !== null) node.textContent = text;
+    return node;
+  }
+
+  async function request(url, options) {
+    const response = await fetch(url, Object.assign({ credentials: 'omit' }, options));
+    let data = null;
+    try {
+      data = await response.json();
+    } catch (_) {
+      // Non-JSON error page
+    }
+    if (!response.ok) {
+      const message = data && data.error && data.error.message;
+      throw new Error(message || 'Request failed (' + response.status + ')');
+    }
+    return data;
+  }
+
+  function renderComment(comment, onReply) {
+    const item = el('li');
+    const wrap = el('div', 'noteva-embed-comment');
+    if (comment.avatar_url) {
+      const avatar = el('img');
+      avatar.src = new URL(comment.avatar_url, BASE || location.href).href;
+      avatar.alt = '';
+      avatar.loading = 'lazy';
+      wrap.appendChild(avatar);
+    }
+    const main = el('div');
+    const date = new Date(comment.created_at);
+    main.appendChild(
+      el('div', 'noteva-embed-meta', (comment.nickname || 'Anonymous') + ' · ' + date.toLocaleString())
+    );
+    main.appendChild(el('div', 'noteva-embed-body', comment.content));
+    const reply = el('button', 'noteva-embed-reply', 'Reply');
+    reply.type = 'button';
+    reply.addEventListener('click', function () {
+      onReply(comment);
+    });
+    main.appendChild(reply);
+    wrap.appendChild(main);
+    item.appendChild(wrap);
+
+    if (comment.replies && comment.replies.length) {
+      const replies = el('ul', 'noteva-embed-list');
+      comment.replies.forEach(function (child) {
+        replies.appendChild(renderComment(child, onReply));
+      });
+      item.appendChild(replies);
+    }
+    return item;
+  }
+
+  function renderForm(container, slug, onPosted) {
+    const form = el('form', 'noteva-embed-form');
+    const replyNotice = el('div', 'noteva-embed-notice');
+    replyNotice.hidden = true;
+    const nickname = el('input');
+    nickname.name = 'nickname';
+    nickname.placeholder = 'Name';
+    nickname.required = true;
+    nickname.maxLength = 100;
+    const email = el('input');
+    email.name = 'email';
+    email.type = 'email';
+    email.placeholder = 'Email (optional, not shown)';
+    const content = el('textarea');
+    content.name = 'content';
+    content.placeholder = 'Write a comment';
+    content.required = true;
+    const submit = el('button', null, 'Post comment');
+    submit.type = 'submit';
+    const status = el('div', 'noteva-embed-notice');
+    let parentId = null;
+
+    form.append(replyNotice, nickname, email, content, submit, status);
+    form.addEventListener('submit', async function (event) {
+      event.preventDefault();
+      submit.disabled = true;
+      status.textContent = '';
+      try {
+        const result = await request(API + encodeURIComponent(slug), {
+          method: 'POST',
+          headers: { 'Content-Type': 'application/json' },
+          body: JSON.stringify({
+            parent_id: parentId,
+            nickname: nickname.value,
+            email: email.value || null,
+            content: content.value,
+          }),
+        });
+        content.value = '';
+        setParent(null);
+        status.textContent =
+          result.comment.status === 'approved'
+            ? 'Comment posted.'
+            : 'Thanks! Your comment is awaiting moderation.';
+        onPosted();
+      } catch (error) {
+        status.textContent = error.message;
+      } finally {
+        submit.disabled = false;
+      }
+    });
+
+    function setParent(comment) {
+      parentId = comment ? comment.id : null;
+      replyNotice.hidden = !comment;
+      replyNotice.textContent = comment ? 'Replying to ' + (comment.nickname || 'Anonymous') + ' ' : '';
+      if (comment) {
+        const cancel = el('button', 'noteva-embed-reply', 'Cancel');
+        cancel.type = 'button';
+        cancel.addEventListener('click', function () {
+          setParent(null);
+        });
+        replyNotice.appendChild(cancel);
+        content.focus();
+      }
+    }
+
+    container.appendChild(form);
+    return setParent;
+  }
+
+  async function mount(container) {
+    const slug = container.getAttribute('data-article');
+    if (!slug) return;
+    container.classList.add('noteva-embed');
+    const list = el('ul', 'noteva-embed-list');
+    container.appendChild(list);
+    let setParent = function () {};
+    // Reply buttons look the form up when clicked; it is created after the first load
+    const onReply = function (comment) {
+      setParent(comment);
+    };
+
+    async function load() {
+      try {
+        const thread = await request(API + encodeURIComponent(slug));
+        list.replaceChildren();
+        thread.comments.forEach(function (comment) {
+          list.appendChild(renderComment(comment, onReply));
+        });
+        if (!thread.comments.length) {
+          list.appendChild(el('li', 'noteva-embed-notice', 'No comments yet.'));
+        }
+        return thread;
+      } catch (error) {
+        list.replaceChildren(el('li', 'noteva-embed-notice', error.message));
+        return null;
+      }
+    }
+
+    const thread = await load();
+    if (!thread) return;
+    if (thread.require_login) {
+      const link = el('a', 'noteva-embed-notice', 'Log in on the blog to comment.');
+      link.href = BASE + '/';
+      container.appendChild(link);
+      return;
+    }
+    setParent = renderForm(container, slug, load);
+  }
+
+  function init() {
+    if (!document.getElementById('noteva-embed-style')) {
+      const style = el('style', null, STYLE);
+      style.id = 'noteva-embed-style';
+      document.head.appendChild(style);
+    }
+    document.querySelectorAll('#noteva-comments, [data-noteva-comments]').forEach(mount);
+  }
+
+  if (document.readyState === 'loading') {
+    document.addEventListener('DOMContentLoaded', init);
+  } else {
+    init();
+  }
+})();
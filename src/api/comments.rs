@@ -8,12 +8,12 @@ use axum::{
 use serde::{Deserialize, Serialize};
 use std::net::SocketAddr;
 
-use crate::api::middleware::{extract_client_ip, ApiError, AppState};
+use crate::api::middleware::{ensure_ip_not_blocked, extract_client_ip, ApiError, AppState};
 use crate::models::{
-    Article, ArticleStatus, Comment, CommentStatus, CommentWithMeta, CreateCommentInput,
-    LikeTargetType,
+    Article, ArticleStatus, Comment, CommentCounts, CommentPreview, CommentStatus, CommentWithMeta,
+    CreateCommentInput, LikeTargetType,
 };
-use crate::services::generate_fingerprint;
+use crate::services::{generate_fingerprint, AbuseSignal, CaptchaError};
 
 // ============================================================================
 // Response Types
@@ -22,6 +22,42 @@ use crate::services::generate_fingerprint;
 #[derive(Debug, Serialize)]
 pub struct CommentsResponse {
     pub comments: Vec<CommentWithMeta>,
+    /// Article comment lists only
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub meta: Option<CommentsMeta>,
+}
+
+/// Pagination and counts of an article's comments
+#[derive(Debug, Serialize)]
+pub struct CommentsMeta {
+    pub page: u32,
+    /// `None` when all threads are returned
+    pub per_page: Option<u32>,
+    pub total_pages: u32,
+    /// Top-level comments; pages are made of threads
+    pub threads: usize,
+    #[serde(flatten)]
+    pub counts: CommentCounts,
+}
+
+/// First threads of an article and a token for loading the rest
+#[derive(Debug, Serialize)]
+pub struct LazyCommentsResponse {
+    pub comments: Vec<CommentPreview>,
+    /// Whether the article has more threads than returned
+    pub has_more: bool,
+    /// Pass to `/comments/{article_id}/lazy/{token}` for the full threads
+    pub token: String,
+    pub expires_at: chrono::DateTime<chrono::Utc>,
+    #[serde(flatten)]
+    pub counts: CommentCounts,
+}
+
+#[derive(Debug, Serialize)]
+pub struct CommentCountResponse {
+    pub article_id: i64,
+    #[serde(flatten)]
+    pub counts: CommentCounts,
 }
 
 #[derive(Debug, Serialize)]
@@ -67,6 +103,20 @@ pub struct CheckLikeQuery {
     pub target_id: i64,
 }
 
+/// Query params for an article's comments; everything in one page unless
+/// `page` or `per_page` is given
+#[derive(Debug, Deserialize)]
+pub struct ArticleCommentsQuery {
+    pub page: Option<u32>,
+    pub per_page: Option<u32>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct LazyCommentsQuery {
+    /// Threads in the first batch (default 5, at most 100)
+    pub limit: Option<i64>,
+}
+
 #[derive(Debug, Deserialize)]
 pub struct RecentCommentsQuery {
     pub limit: Option<i64>,
@@ -76,17 +126,180 @@ pub struct RecentCommentsQuery {
 // Handlers
 // ============================================================================
 
+/// Default and largest number of threads per page
+const DEFAULT_COMMENTS_PER_PAGE: u32 = 20;
+const MAX_COMMENTS_PER_PAGE: u32 = 100;
+
 /// Get comments for an article
+///
+/// Pages are made of top-level comments with all their replies.
 pub async fn get_comments(
     State(state): State<AppState>,
     ConnectInfo(addr): ConnectInfo<SocketAddr>,
     headers: HeaderMap,
     Path(article_id): Path<i64>,
+    Query(query): Query<ArticleCommentsQuery>,
 ) -> Result<Json<CommentsResponse>, ApiError> {
     ensure_published_article(&state, article_id).await?;
 
     let client_ip = extract_client_ip(&headers, addr);
-    let fingerprint = extract_fingerprint(&client_ip, &headers);
+    let comments = load_comments(&state, article_id, &client_ip, &headers).await?;
+    let counts = state
+        .comment_service
+        .count_by_article(article_id)
+        .await
+        .map_err(|e| ApiError::internal_error(e.to_string()))?;
+
+    let threads = comments.len();
+    let (comments, meta) = if query.page.is_none() && query.per_page.is_none() {
+        let meta = CommentsMeta {
+            page: 1,
+            per_page: None,
+            total_pages: 1,
+            threads,
+            counts,
+        };
+        (comments, meta)
+    } else {
+        let page = query.page.unwrap_or(1).max(1);
+        let per_page = query
+            .per_page
+            .unwrap_or(DEFAULT_COMMENTS_PER_PAGE)
+            .clamp(1, MAX_COMMENTS_PER_PAGE);
+        let comments = comments
+            .into_iter()
+            .skip((page as usize - 1).saturating_mul(per_page as usize))
+            .take(per_page as usize)
+            .collect();
+        let meta = CommentsMeta {
+            page,
+            per_page: Some(per_page),
+            total_pages: (threads as u32).div_ceil(per_page),
+            threads,
+            counts,
+        };
+        (comments, meta)
+    };
+
+    Ok(Json(CommentsResponse {
+        comments,
+        meta: Some(meta),
+    }))
+}
+
+/// Get the comment counts of an article, e.g. for "N comments" links
+pub async fn get_comment_count(
+    State(state): State<AppState>,
+    Path(article_id): Path<i64>,
+) -> Result<Json<CommentCountResponse>, ApiError> {
+    ensure_published_article(&state, article_id).await?;
+
+    let counts = state
+        .comment_service
+        .count_by_article(article_id)
+        .await
+        .map_err(|e| ApiError::internal_error(e.to_string()))?;
+
+    Ok(Json(CommentCountResponse { article_id, counts }))
+}
+
+/// Get the first threads of an article for lazy loading
+///
+/// Top-level comments come without replies or likes, which keeps the query
+/// cheap for pages that may never show the thread. The returned token loads
+/// the full threads at `/comments/{article_id}/lazy/{token}`.
+pub async fn get_lazy_comments(
+    State(state): State<AppState>,
+    Path(article_id): Path<i64>,
+    Query(query): Query<LazyCommentsQuery>,
+) -> Result<Json<LazyCommentsResponse>, ApiError> {
+    ensure_published_article(&state, article_id).await?;
+
+    let limit = query.limit.unwrap_or(5).clamp(1, 100);
+    let mut comments = state
+        .comment_service
+        .list_thread_previews(article_id, limit + 1)
+        .await
+        .map_err(|e| ApiError::internal_error(e.to_string()))?;
+    let has_more = comments.len() as i64 > limit;
+    comments.truncate(limit as usize);
+
+    // Plugins filtering displayed comments see the previews as well
+    let hook_data = serde_json::json!({
+        "article_id": article_id,
+        "comments": &comments,
+        "count": comments.len()
+    });
+    let modified = state
+        .hook_manager
+        .trigger(crate::plugin::hook_names::COMMENT_BEFORE_DISPLAY, hook_data);
+    if let Some(modified_comments) = modified.get("comments") {
+        if let Ok(filtered) = serde_json::from_value(modified_comments.clone()) {
+            comments = filtered;
+        }
+    }
+
+    let counts = state
+        .comment_service
+        .count_by_article(article_id)
+        .await
+        .map_err(|e| ApiError::internal_error(e.to_string()))?;
+    let token = state
+        .comment_service
+        .issue_load_token(article_id)
+        .await
+        .map_err(|e| ApiError::internal_error(e.to_string()))?;
+
+    Ok(Json(LazyCommentsResponse {
+        comments,
+        has_more,
+        token,
+        expires_at: chrono::Utc::now()
+            + chrono::Duration::seconds(crate::services::comment::LOAD_TOKEN_TTL_SECS as i64),
+        counts,
+    }))
+}
+
+/// Load the full threads of an article with a token from
+/// [`get_lazy_comments`]
+///
+/// Returns every thread with replies and likes, replacing the previews.
+pub async fn get_lazy_comments_rest(
+    State(state): State<AppState>,
+    ConnectInfo(addr): ConnectInfo<SocketAddr>,
+    headers: HeaderMap,
+    Path((article_id, token)): Path<(i64, String)>,
+) -> Result<Json<CommentsResponse>, ApiError> {
+    if !state
+        .comment_service
+        .check_load_token(&token, article_id)
+        .await
+    {
+        return Err(ApiError::validation_error(
+            "Comment token is invalid or has expired",
+        ));
+    }
+    get_comments(
+        State(state),
+        ConnectInfo(addr),
+        headers,
+        Path(article_id),
+        Query(ArticleCommentsQuery {
+            page: None,
+            per_page: None,
+        }),
+    )
+    .await
+}
+
+/// Approved comments of an article, after the `comment_before_display` hook
+pub(crate) async fn load_comments(
+    state: &AppState,
+    article_id: i64,
+    client_ip: &str,
+    headers: &HeaderMap,
+) -> Result<Vec<CommentWithMeta>, ApiError> {
+    let fingerprint = extract_fingerprint(client_ip, headers);
 
     let comments = state
         .comment_service
@@ -107,11 +320,11 @@ pub async fn get_comments(
     // Use modified comments if hook returned them
     if let Some(modified_comments) = modified.get("comments") {
         if let Ok(comments) = serde_json::from_value(modified_comments.clone()) {
-            return Ok(Json(CommentsResponse { comments }));
+            return Ok(comments);
         }
     }
 
-    Ok(Json(CommentsResponse { comments }))
+    Ok(comments)
 }
 
 /// Get recent comments across all articles
@@ -127,7 +340,10 @@ pub async fn get_recent_comments(
         .await
         .map_err(|e| ApiError::internal_error(e.to_string()))?;
 
-    Ok(Json(CommentsResponse { comments }))
+    Ok(Json(CommentsResponse {
+        comments,
+        meta: None,
+    }))
 }
 
 /// Create a comment
@@ -151,6 +367,22 @@ pub async fn create_comment(
         return Err(ApiError::unauthorized("Login required to comment"));
     }
 
+    let client_ip = extract_client_ip(&headers, addr);
+    let comment = submit_comment(&state, user_id, client_ip, &headers, req).await?;
+
+    Ok((StatusCode::CREATED, Json(CommentResponse { comment })))
+}
+
+/// Validate and store a comment on a published article
+///
+/// Shared by the site's comment API and the cross-origin embed widget.
+pub(crate) async fn submit_comment(
+    state: &AppState,
+    user_id: Option<i64>,
+    client_ip: String,
+    headers: &HeaderMap,
+    req: CreateCommentRequest,
+) -> Result<Comment, ApiError> {
     // For guest comments, require nickname
     if user_id.is_none()
         && req
@@ -168,16 +400,28 @@ pub async fn create_comment(
         return Err(ApiError::validation_error("Content is required"));
     }
 
-    ensure_published_article(&state, req.article_id).await?;
-    ensure_parent_comment(&state, req.article_id, req.parent_id).await?;
+    ensure_published_article(state, req.article_id).await?;
+    ensure_parent_comment(state, req.article_id, req.parent_id).await?;
+    ensure_ip_not_blocked(state, &client_ip).await?;
 
-    let client_ip = extract_client_ip(&headers, addr);
-    crate::api::captcha::verify_comment_token(
-        &state,
-        req.captcha_token.as_deref(),
-        Some(&client_ip),
-    )
-    .await?;
+    // Logged-in users are trusted and skip the captcha
+    if user_id.is_none() {
+        if let Err(e) = crate::api::captcha::verify_comment_token(
+            state,
+            req.captcha_token.as_deref(),
+            Some(&client_ip),
+        )
+        .await
+        {
+            if e.error.code == "VALIDATION_ERROR" {
+                state
+                    .ip_reputation
+                    .record_str(&client_ip, AbuseSignal::CaptchaFailed)
+                    .await;
+            }
+            return Err(e);
+        }
+    }
 
     let ip = Some(client_ip);
     let ua = headers
@@ -193,13 +437,14 @@ pub async fn create_comment(
         content: req.content,
     };
 
-    let comment = state
+    state
         .comment_service
-        .create(input, user_id, ip, ua)
+        .create(input, user_id, ip, ua, req.captcha_token.as_deref())
         .await
-        .map_err(|e| ApiError::internal_error(e.to_string()))?;
-
-    Ok((StatusCode::CREATED, Json(CommentResponse { comment })))
+        .map_err(|e| match e.downcast_ref::<CaptchaError>() {
+            Some(CaptchaError::Internal(_)) | None => ApiError::internal_error(e.to_string()),
+            Some(captcha_error) => ApiError::validation_error(captcha_error.to_string()),
+        })
 }
 
 /// Check if user has liked an article or comment
@@ -244,9 +489,11 @@ pub async fn like(
     let target_type = parse_target_type(&req.target_type)?;
     ensure_public_like_target(&state, &target_type, req.target_id).await?;
 
+    let client_ip = extract_client_ip(&headers, addr);
+    ensure_ip_not_blocked(&state, &client_ip).await?;
+
     let user_id = get_user_id_from_headers(&state, &headers).await;
     let fingerprint = if user_id.is_none() {
-        let client_ip = extract_client_ip(&headers, addr);
         extract_fingerprint(&client_ip, &headers)
     } else {
         None
@@ -307,24 +554,25 @@ pub async fn like(
     }))
 }
 
-/// Increment view count for an article
+/// Count a view of an article
+///
+/// Repeated views of the same reader within `views.dedup_secs` and bots are
+/// ignored. Counts are written in batches, so `view_count` lags behind by up
+/// to `views.flush_secs`.
 pub async fn increment_view(
     State(state): State<AppState>,
+    ConnectInfo(addr): ConnectInfo<SocketAddr>,
+    headers: HeaderMap,
     Path(article_id): Path<i64>,
 ) -> Result<StatusCode, ApiError> {
-    let article = ensure_published_article(&state, article_id).await?;
-
-    state
-        .comment_service
-        .increment_view(article_id)
-        .await
-        .map_err(|e| ApiError::internal_error(e.to_string()))?;
+    ensure_published_article(&state, article_id).await?;
 
-    // Clear article cache so next request gets fresh view count
-    let _ = state
-        .article_service
-        .invalidate_article_cache(article.id, &article.slug)
-        .await;
+    let client_ip = extract_client_ip(&headers, addr);
+    let user_agent = headers
+        .get("user-agent")
+        .and_then(|v| v.to_str().ok())
+        .unwrap_or("");
+    state.views.record(article_id, &client_ip, user_agent).await;
 
     Ok(StatusCode::OK)
 }
@@ -360,7 +608,10 @@ fn parse_target_type(s: &str) -> Result<LikeTargetType, ApiError> {
     }
 }
 
-async fn ensure_published_article(state: &AppState, article_id: i64) -> Result<Article, ApiError> {
+pub(crate) async fn ensure_published_article(
+    state: &AppState,
+    article_id: i64,
+) -> Result<Article, ApiError> {
     let article = state
         .article_service
         .get_by_id(article_id)
@@ -440,7 +691,7 @@ fn extract_fingerprint(ip: &str, headers: &HeaderMap) -> Option<String> {
 }
 
 /// Get user ID from session cookie
-async fn get_user_id_from_headers(state: &AppState, headers: &HeaderMap) -> Option<i64> {
+pub(crate) async fn get_user_id_from_headers(state: &AppState, headers: &HeaderMap) -> Option<i64> {
     let cookie = headers.get("cookie")?.to_str().ok()?;
     let session_id = cookie.split(';').find_map(|c| {
         let c = c.trim();
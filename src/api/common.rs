@@ -2,8 +2,12 @@
 //!
 //! This module contains shared utilities used across multiple API endpoints.
 
+use chrono::{DateTime, NaiveDate, Utc};
 use serde::Deserialize;
 
+use crate::api::middleware::ApiError;
+use crate::models::ArticleCursor;
+
 // ============================================================================
 // Pagination Defaults
 // ============================================================================
@@ -28,6 +32,68 @@ pub fn default_per_page() -> i64 {
     20
 }
 
+/// Parse a `?cursor=` value. An empty value starts cursor pagination from
+/// the newest item.
+pub fn parse_cursor(cursor: &str) -> Result<Option<ArticleCursor>, ApiError> {
+    if cursor.trim().is_empty() {
+        return Ok(None);
+    }
+    ArticleCursor::decode(cursor)
+        .map(Some)
+        .ok_or_else(|| ApiError::validation_error("Invalid cursor"))
+}
+
+/// Parse a date range bound given as an RFC 3339 timestamp or `YYYY-MM-DD`.
+///
+/// Upper bounds are exclusive, so a bare date used as `upper` covers that
+/// whole day.
+pub fn parse_date_bound(value: &str, upper: bool) -> Result<DateTime<Utc>, ApiError> {
+    let value = value.trim();
+    if let Ok(time) = DateTime::parse_from_rfc3339(value) {
+        return Ok(time.with_timezone(&Utc));
+    }
+    let date = NaiveDate::parse_from_str(value, "%Y-%m-%d")
+        .map_err(|_| ApiError::validation_error(format!("Invalid date: {}", value)))?;
+    let date = if upper {
+        date.succ_opt().unwrap_or(date)
+    } else {
+        date
+    };
+    Ok(date.and_hms_opt(0, 0, 0).unwrap_or_default().and_utc())
+}
+
+// ============================================================================
+// CSV Exports
+// ============================================================================
+
+/// Quote a CSV field when needed.
+///
+/// Values starting with a formula character are prefixed with `'` so
+/// spreadsheet applications do not evaluate user-supplied text.
+pub fn csv_field(value: &str) -> String {
+    let value = if value.starts_with(['=', '+', '-', '@']) {
+        format!("'{}", value)
+    } else {
+        value.to_string()
+    };
+    if value.contains([',', '"', '\r', '\n']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value
+    }
+}
+
+/// Format one CSV line (RFC 4180), including the CRLF terminator
+pub fn csv_line(fields: &[String]) -> String {
+    let mut line = fields
+        .iter()
+        .map(|f| csv_field(f))
+        .collect::<Vec<_>>()
+        .join(",");
+    line.push_str("\r\n");
+    line
+}
+
 // ============================================================================
 // Pagination Query Types
 // ============================================================================
@@ -49,3 +115,21 @@ pub struct AdminPaginationQuery {
     #[serde(default = "default_per_page")]
     pub per_page: i64,
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn csv_fields_are_quoted_and_neutralized() {
+        assert_eq!(csv_field("plain"), "plain");
+        assert_eq!(csv_field("a,b"), "\"a,b\"");
+        assert_eq!(csv_field("say \"hi\""), "\"say \"\"hi\"\"\"");
+        assert_eq!(csv_field("line\nbreak"), "\"line\nbreak\"");
+        assert_eq!(csv_field("=SUM(A1)"), "'=SUM(A1)");
+        assert_eq!(
+            csv_line(&["1".to_string(), "a,b".to_string()]),
+            "1,\"a,b\"\r\n"
+        );
+    }
+}
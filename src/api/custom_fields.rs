@@ -0,0 +1,179 @@
+//! Custom field endpoints for articles and pages.
+//!
+//! - GET /api/v1/admin/articles/:id/fields - An article's fields with types
+//! - PUT /api/v1/admin/articles/:id/fields - Replace them
+//! - PUT /api/v1/admin/articles/:id/fields/:key - Set one field
+//! - DELETE /api/v1/admin/articles/:id/fields/:key - Remove one field
+//!
+//! The same routes exist under /api/v1/admin/pages/:id/fields. Readers get
+//! the fields as a `custom_fields` map on article and page responses, and
+//! themes read them with the `custom_field` function.
+
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+    response::IntoResponse,
+    routing::{get, put},
+    Json, Router,
+};
+use serde::Serialize;
+
+use crate::api::middleware::{ApiError, AppState};
+use crate::models::{
+    ContentKind, CustomField, CustomFieldMap, CustomFieldValueInput, CustomFieldsInput,
+};
+use crate::services::CustomFieldError;
+
+/// Build the custom field router of one kind of content, nested under its
+/// `/{id}/fields` path
+pub fn router(kind: ContentKind) -> Router<AppState> {
+    Router::new()
+        .route(
+            "/",
+            get(move |state: State<AppState>, path: Path<i64>| list_fields(state, kind, path)).put(
+                move |state: State<AppState>, path: Path<i64>, body: Json<CustomFieldsInput>| {
+                    replace_fields(state, kind, path, body)
+                },
+            ),
+        )
+        .route(
+            "/{key}",
+            put(
+                move |state: State<AppState>,
+                      path: Path<(i64, String)>,
+                      body: Json<CustomFieldValueInput>| {
+                    set_field(state, kind, path, body)
+                },
+            )
+            .delete(move |state: State<AppState>, path: Path<(i64, String)>| {
+                delete_field(state, kind, path)
+            }),
+        )
+}
+
+#[derive(Debug, Serialize)]
+struct FieldsResponse {
+    fields: Vec<CustomField>,
+}
+
+fn map_field_error(e: CustomFieldError) -> ApiError {
+    match e {
+        CustomFieldError::NotFound => ApiError::not_found(e.to_string()),
+        CustomFieldError::Validation(_) => ApiError::validation_error(e.to_string()),
+        CustomFieldError::Internal(e) => ApiError::internal_error(e.to_string()),
+    }
+}
+
+async fn ensure_content(state: &AppState, kind: ContentKind, id: i64) -> Result<(), ApiError> {
+    let exists = match kind {
+        ContentKind::Article => state
+            .article_service
+            .get_by_id(id)
+            .await
+            .map_err(|e| ApiError::internal_error(e.to_string()))?
+            .is_some(),
+        ContentKind::Page => state
+            .page_service
+            .get_by_id(id)
+            .await
+            .map_err(|e| ApiError::internal_error(e.to_string()))?
+            .is_some(),
+    };
+    if exists {
+        Ok(())
+    } else {
+        Err(ApiError::not_found(format!(
+            "{} not found: {}",
+            match kind {
+                ContentKind::Article => "Article",
+                ContentKind::Page => "Page",
+            },
+            id
+        )))
+    }
+}
+
+/// GET .../{id}/fields - List the fields with their types
+async fn list_fields(
+    State(state): State<AppState>,
+    kind: ContentKind,
+    Path(id): Path<i64>,
+) -> Result<impl IntoResponse, ApiError> {
+    ensure_content(&state, kind, id).await?;
+    let fields = state
+        .custom_field_service
+        .list(kind, id)
+        .await
+        .map_err(map_field_error)?;
+    Ok(Json(FieldsResponse { fields }))
+}
+
+/// PUT .../{id}/fields - Replace all fields
+///
+/// Body: `{"fields": [{"key": "rating", "type": "number", "value": 4.5},
+/// {"key": "mood", "value": "calm"}]}`. Types are `text`, `number`,
+/// `boolean`, `date` and `json`; without one it follows the JSON value.
+async fn replace_fields(
+    State(state): State<AppState>,
+    kind: ContentKind,
+    Path(id): Path<i64>,
+    Json(input): Json<CustomFieldsInput>,
+) -> Result<impl IntoResponse, ApiError> {
+    ensure_content(&state, kind, id).await?;
+    let fields = state
+        .custom_field_service
+        .replace(kind, id, input)
+        .await
+        .map_err(map_field_error)?;
+    Ok(Json(FieldsResponse { fields }))
+}
+
+/// PUT .../{id}/fields/{key} - Set one field
+///
+/// Body: `{"type": "date", "value": "2026-03-01"}`.
+async fn set_field(
+    State(state): State<AppState>,
+    kind: ContentKind,
+    Path((id, key)): Path<(i64, String)>,
+    Json(input): Json<CustomFieldValueInput>,
+) -> Result<impl IntoResponse, ApiError> {
+    ensure_content(&state, kind, id).await?;
+    let fields = state
+        .custom_field_service
+        .set(kind, id, &key, input)
+        .await
+        .map_err(map_field_error)?;
+    Ok(Json(FieldsResponse { fields }))
+}
+
+/// DELETE .../{id}/fields/{key} - Remove one field
+async fn delete_field(
+    State(state): State<AppState>,
+    kind: ContentKind,
+    Path((id, key)): Path<(i64, String)>,
+) -> Result<impl IntoResponse, ApiError> {
+    state
+        .custom_field_service
+        .delete(kind, id, &key)
+        .await
+        .map_err(map_field_error)?;
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// Fields of an article or page; empty on failure so broken fields never
+/// break the content itself
+pub(crate) async fn for_content(state: &AppState, kind: ContentKind, id: i64) -> CustomFieldMap {
+    state
+        .custom_field_service
+        .map(kind, id)
+        .await
+        .unwrap_or_else(|e| {
+            tracing::warn!(
+                "Failed to load custom fields of {} {}: {}",
+                kind.as_str(),
+                id,
+                e
+            );
+            CustomFieldMap::new()
+        })
+}
@@ -0,0 +1,222 @@
+//! Documentation API endpoints.
+//!
+//! - GET /api/v1/admin/docs/versions - All versions
+//! - POST /api/v1/admin/docs/versions - Create a version, optionally copying one
+//! - GET/PUT/DELETE /api/v1/admin/docs/versions/:id - Manage a version
+//! - GET /api/v1/admin/docs/versions/:id/pages - A version's pages
+//! - POST /api/v1/admin/docs/pages - Create a page
+//! - GET/PUT/DELETE /api/v1/admin/docs/pages/:id - Manage a page
+//! - GET /api/v1/docs - Published versions
+//! - GET /api/v1/docs/:version - First page of a version
+//! - GET /api/v1/docs/:version/:slug - A page with sidebar, version switcher
+//!   and canonical path
+//!
+//! `latest` may be used in place of a version slug.
+
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+    response::IntoResponse,
+    routing::{get, post},
+    Json, Router,
+};
+use serde::Serialize;
+
+use crate::api::middleware::{ApiError, AppState};
+use crate::models::{DocPage, DocPageInput, DocVersion, DocVersionInput};
+use crate::services::DocError;
+
+/// Build the docs management router (requires admin)
+pub fn router() -> Router<AppState> {
+    Router::new()
+        .route("/versions", get(list_versions).post(create_version))
+        .route(
+            "/versions/{id}",
+            get(get_version).put(update_version).delete(delete_version),
+        )
+        .route("/versions/{id}/pages", get(list_pages))
+        .route("/pages", post(create_page))
+        .route(
+            "/pages/{id}",
+            get(get_page).put(update_page).delete(delete_page),
+        )
+}
+
+/// Build the public docs router
+pub fn public_router() -> Router<AppState> {
+    Router::new()
+        .route("/", get(list_versions))
+        .route("/{version}", get(view_version))
+        .route("/{version}/{slug}", get(view_page))
+}
+
+#[derive(Debug, Serialize)]
+struct VersionsResponse {
+    versions: Vec<DocVersion>,
+}
+
+#[derive(Debug, Serialize)]
+struct VersionResponse {
+    version: DocVersion,
+}
+
+#[derive(Debug, Serialize)]
+struct PagesResponse {
+    pages: Vec<DocPage>,
+}
+
+#[derive(Debug, Serialize)]
+struct PageResponse {
+    page: DocPage,
+}
+
+fn map_doc_error(e: DocError) -> ApiError {
+    match e {
+        DocError::NotFound(_) => ApiError::not_found(e.to_string()),
+        DocError::Validation(_) => ApiError::validation_error(e.to_string()),
+        DocError::Internal(e) => ApiError::internal_error(e.to_string()),
+    }
+}
+
+async fn list_versions(State(state): State<AppState>) -> Result<impl IntoResponse, ApiError> {
+    let versions = state
+        .doc_service
+        .list_versions()
+        .await
+        .map_err(map_doc_error)?;
+    Ok(Json(VersionsResponse { versions }))
+}
+
+async fn get_version(
+    State(state): State<AppState>,
+    Path(id): Path<i64>,
+) -> Result<impl IntoResponse, ApiError> {
+    let version = state
+        .doc_service
+        .get_version(id)
+        .await
+        .map_err(map_doc_error)?;
+    Ok(Json(VersionResponse { version }))
+}
+
+async fn create_version(
+    State(state): State<AppState>,
+    Json(input): Json<DocVersionInput>,
+) -> Result<impl IntoResponse, ApiError> {
+    let version = state
+        .doc_service
+        .create_version(input)
+        .await
+        .map_err(map_doc_error)?;
+    Ok((StatusCode::CREATED, Json(VersionResponse { version })))
+}
+
+async fn update_version(
+    State(state): State<AppState>,
+    Path(id): Path<i64>,
+    Json(input): Json<DocVersionInput>,
+) -> Result<impl IntoResponse, ApiError> {
+    let version = state
+        .doc_service
+        .update_version(id, input)
+        .await
+        .map_err(map_doc_error)?;
+    Ok(Json(VersionResponse { version }))
+}
+
+async fn delete_version(
+    State(state): State<AppState>,
+    Path(id): Path<i64>,
+) -> Result<impl IntoResponse, ApiError> {
+    state
+        .doc_service
+        .delete_version(id)
+        .await
+        .map_err(map_doc_error)?;
+    Ok(StatusCode::NO_CONTENT)
+}
+
+async fn list_pages(
+    State(state): State<AppState>,
+    Path(id): Path<i64>,
+) -> Result<impl IntoResponse, ApiError> {
+    let pages = state
+        .doc_service
+        .list_pages(id)
+        .await
+        .map_err(map_doc_error)?;
+    Ok(Json(PagesResponse { pages }))
+}
+
+async fn get_page(
+    State(state): State<AppState>,
+    Path(id): Path<i64>,
+) -> Result<impl IntoResponse, ApiError> {
+    let page = state
+        .doc_service
+        .get_page(id)
+        .await
+        .map_err(map_doc_error)?;
+    Ok(Json(PageResponse { page }))
+}
+
+async fn create_page(
+    State(state): State<AppState>,
+    Json(input): Json<DocPageInput>,
+) -> Result<impl IntoResponse, ApiError> {
+    let page = state
+        .doc_service
+        .create_page(input)
+        .await
+        .map_err(map_doc_error)?;
+    Ok((StatusCode::CREATED, Json(PageResponse { page })))
+}
+
+async fn update_page(
+    State(state): State<AppState>,
+    Path(id): Path<i64>,
+    Json(input): Json<DocPageInput>,
+) -> Result<impl IntoResponse, ApiError> {
+    let page = state
+        .doc_service
+        .update_page(id, input)
+        .await
+        .map_err(map_doc_error)?;
+    Ok(Json(PageResponse { page }))
+}
+
+async fn delete_page(
+    State(state): State<AppState>,
+    Path(id): Path<i64>,
+) -> Result<impl IntoResponse, ApiError> {
+    state
+        .doc_service
+        .delete_page(id)
+        .await
+        .map_err(map_doc_error)?;
+    Ok(StatusCode::NO_CONTENT)
+}
+
+async fn view_version(
+    State(state): State<AppState>,
+    Path(version): Path<String>,
+) -> Result<impl IntoResponse, ApiError> {
+    let view = state
+        .doc_service
+        .view(&version, None)
+        .await
+        .map_err(map_doc_error)?;
+    Ok(Json(view))
+}
+
+async fn view_page(
+    State(state): State<AppState>,
+    Path((version, slug)): Path<(String, String)>,
+) -> Result<impl IntoResponse, ApiError> {
+    let view = state
+        .doc_service
+        .view(&version, Some(&slug))
+        .await
+        .map_err(map_doc_error)?;
+    Ok(Json(view))
+}
@@ -0,0 +1,92 @@
+//! Bounce and complaint webhooks from email providers
+//!
+//! POST /api/v1/email/webhook/{provider}?token=... where provider is one of
+//! `postmark`, `sendgrid`, `mailgun` or `ses`. The token must match the
+//! `email_webhook_secret` setting; the endpoint is disabled while that
+//! setting is empty.
+
+use axum::{
+    extract::{Path, Query, State},
+    http::StatusCode,
+};
+use serde::Deserialize;
+
+use crate::api::middleware::{ApiError, AppState};
+use crate::services::email::suppression;
+
+/// Query parameters for provider webhooks
+#[derive(Debug, Deserialize)]
+pub struct WebhookQuery {
+    pub token: Option<String>,
+}
+
+/// Compare secrets without leaking the matching prefix length through timing
+fn secrets_match(expected: &str, given: &str) -> bool {
+    expected.len() == given.len()
+        && expected
+            .bytes()
+            .zip(given.bytes())
+            .fold(0u8, |diff, (a, b)| diff | (a ^ b))
+            == 0
+}
+
+/// POST /api/v1/email/webhook/{provider} - Record bounces and complaints
+///
+/// The body is read as text since SNS posts JSON as `text/plain`.
+pub async fn receive(
+    State(state): State<AppState>,
+    Path(provider): Path<String>,
+    Query(query): Query<WebhookQuery>,
+    body: String,
+) -> Result<StatusCode, ApiError> {
+    let secret = state
+        .settings_service
+        .get("email_webhook_secret")
+        .await
+        .ok()
+        .flatten()
+        .unwrap_or_default();
+    if secret.is_empty() {
+        return Err(ApiError::not_found("Email webhooks are not enabled"));
+    }
+    if !secrets_match(&secret, query.token.as_deref().unwrap_or_default()) {
+        return Err(ApiError::unauthorized("Invalid webhook token"));
+    }
+    if !suppression::PROVIDERS.contains(&provider.as_str()) {
+        return Err(ApiError::not_found("Unknown email provider"));
+    }
+
+    let payload: serde_json::Value = serde_json::from_str(&body)
+        .map_err(|e| ApiError::validation_error(format!("Invalid webhook body: {}", e)))?;
+
+    // SNS must be told the subscription is wanted before it sends events
+    if payload.get("Type").and_then(|t| t.as_str()) == Some("SubscriptionConfirmation") {
+        tracing::info!(
+            subscribe_url = payload
+                .get("SubscribeURL")
+                .and_then(|u| u.as_str())
+                .unwrap_or_default(),
+            "SNS subscription confirmation received; open the URL to confirm"
+        );
+        return Ok(StatusCode::OK);
+    }
+
+    let events = suppression::parse_webhook(&provider, &payload)
+        .map_err(|e| ApiError::validation_error(e.to_string()))?;
+    state.email_service.apply_suppression_events(&events).await;
+
+    Ok(StatusCode::OK)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn secrets_must_match_exactly() {
+        assert!(secrets_match("s3cret", "s3cret"));
+        assert!(!secrets_match("s3cret", "s3creT"));
+        assert!(!secrets_match("s3cret", "s3cre"));
+        assert!(!secrets_match("s3cret", ""));
+    }
+}
@@ -0,0 +1,177 @@
+//! Comment embedding for external sites
+//!
+//! `/embed/comments.js` renders the comment thread of a published article on
+//! another site:
+//!
+//! ```html
+//! <div id="noteva-comments" data-article="article-slug"></div>
+//! <script src="https://blog.example.com/embed/comments.js" async></script>
+//! ```
+//!
+//! The widget talks to the cross-origin endpoints below, which only answer
+//! sites listed in the `comment_embed_origins` setting. Embedded comments are
+//! always posted as guests (no session cookie crosses sites) and go through
+//! the usual moderation and captcha rules; the widget does not render a
+//! captcha, so sites with one enabled should rely on moderation instead.
+
+use axum::{
+    extract::{ConnectInfo, Path, State},
+    http::{header, HeaderMap, StatusCode},
+    Json,
+};
+use serde::{Deserialize, Serialize};
+use std::net::SocketAddr;
+
+use crate::api::comments::{load_comments, submit_comment, CreateCommentRequest};
+use crate::api::middleware::{extract_client_ip, ApiError, AppState};
+use crate::models::{Article, ArticleStatus, Comment, CommentWithMeta};
+use crate::services::comment::{normalize_origin, parse_embed_origins, COMMENT_EMBED_ORIGINS_KEY};
+
+/// Path prefix of the cross-origin embed API
+pub const EMBED_API_PREFIX: &str = "/api/v1/embed/";
+
+/// Article summary shown above an embedded thread
+#[derive(Debug, Serialize)]
+pub struct EmbedArticle {
+    pub id: i64,
+    pub slug: String,
+    pub title: String,
+}
+
+#[derive(Debug, Serialize)]
+pub struct EmbedThreadResponse {
+    pub article: EmbedArticle,
+    pub comments: Vec<CommentWithMeta>,
+    pub require_login: bool,
+}
+
+#[derive(Debug, Serialize)]
+pub struct EmbedCommentResponse {
+    pub comment: Comment,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct EmbedCommentRequest {
+    pub parent_id: Option<i64>,
+    pub nickname: Option<String>,
+    pub email: Option<String>,
+    pub content: String,
+    pub captcha_token: Option<String>,
+}
+
+/// Whether `origin` may embed comments
+pub async fn origin_allowed(state: &AppState, origin: &str) -> bool {
+    let Ok(settings) = state.settings_service.get_all_settings().await else {
+        return false;
+    };
+    let origin = normalize_origin(origin);
+    settings
+        .get(COMMENT_EMBED_ORIGINS_KEY)
+        .map(|value| parse_embed_origins(value).contains(&origin))
+        .unwrap_or(false)
+}
+
+/// Refuse requests from sites that are not allowlisted
+///
+/// Browsers always send `Origin` on cross-origin fetches; requests without
+/// one only come from the blog itself or non-browser clients.
+async fn ensure_embed_origin(state: &AppState, headers: &HeaderMap) -> Result<(), ApiError> {
+    let Some(origin) = headers.get(header::ORIGIN).and_then(|v| v.to_str().ok()) else {
+        return Ok(());
+    };
+    if origin_allowed(state, origin).await {
+        Ok(())
+    } else {
+        Err(ApiError::forbidden(
+            "This site is not allowed to embed comments",
+        ))
+    }
+}
+
+async fn published_article(state: &AppState, slug: &str) -> Result<Article, ApiError> {
+    state
+        .article_service
+        .get_by_slug(slug)
+        .await
+        .map_err(|e| ApiError::internal_error(e.to_string()))?
+        .filter(|article| article.status == ArticleStatus::Published)
+        .ok_or_else(|| ApiError::not_found("Article not found"))
+}
+
+/// GET /api/v1/embed/comments/{slug} - Comment thread of a published article
+pub async fn get_thread(
+    State(state): State<AppState>,
+    ConnectInfo(addr): ConnectInfo<SocketAddr>,
+    headers: HeaderMap,
+    Path(slug): Path<String>,
+) -> Result<Json<EmbedThreadResponse>, ApiError> {
+    ensure_embed_origin(&state, &headers).await?;
+    let article = published_article(&state, &slug).await?;
+
+    let client_ip = extract_client_ip(&headers, addr);
+    let comments = load_comments(&state, article.id, &client_ip, &headers).await?;
+    let require_login = state
+        .comment_service
+        .check_require_login()
+        .await
+        .unwrap_or(false);
+
+    Ok(Json(EmbedThreadResponse {
+        article: EmbedArticle {
+            id: article.id,
+            slug: article.slug,
+            title: article.title,
+        },
+        comments,
+        require_login,
+    }))
+}
+
+/// POST /api/v1/embed/comments/{slug} - Post a guest comment from an embedding site
+pub async fn create_comment(
+    State(state): State<AppState>,
+    ConnectInfo(addr): ConnectInfo<SocketAddr>,
+    headers: HeaderMap,
+    Path(slug): Path<String>,
+    Json(req): Json<EmbedCommentRequest>,
+) -> Result<(StatusCode, Json<EmbedCommentResponse>), ApiError> {
+    ensure_embed_origin(&state, &headers).await?;
+    if state
+        .comment_service
+        .check_require_login()
+        .await
+        .unwrap_or(false)
+    {
+        return Err(ApiError::unauthorized("Login required to comment"));
+    }
+    let article = published_article(&state, &slug).await?;
+
+    let client_ip = extract_client_ip(&headers, addr);
+    let request = CreateCommentRequest {
+        article_id: article.id,
+        parent_id: req.parent_id,
+        nickname: req.nickname,
+        email: req.email,
+        content: req.content,
+        captcha_token: req.captcha_token,
+    };
+    let comment = submit_comment(&state, None, client_ip, &headers, request).await?;
+
+    Ok((StatusCode::CREATED, Json(EmbedCommentResponse { comment })))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn embed_origins_are_normalized() {
+        let origins = parse_embed_origins("https://Docs.Example.com/, http://localhost:8080\n\n");
+        assert_eq!(
+            origins,
+            vec!["https://docs.example.com", "http://localhost:8080"]
+        );
+        assert!(origins.contains(&normalize_origin("https://docs.example.com")));
+        assert!(!origins.contains(&normalize_origin("https://evil.example.com")));
+    }
+}
@@ -0,0 +1,170 @@
+//! Event API endpoints.
+//!
+//! - GET /api/v1/admin/events - All events
+//! - POST /api/v1/admin/events - Create an event
+//! - GET/PUT/DELETE /api/v1/admin/events/:id - Manage an event
+//! - GET /api/v1/events/upcoming - Next occurrences, repeats expanded
+//! - GET /events.ics - iCalendar feed to subscribe to
+
+use axum::{
+    body::Body,
+    extract::{Path, Query, State},
+    http::{header, StatusCode},
+    response::{IntoResponse, Response},
+    routing::get,
+    Json, Router,
+};
+use chrono::Utc;
+use serde::{Deserialize, Serialize};
+
+use crate::api::middleware::{ApiError, AppState};
+use crate::models::{Event, EventInput, EventOccurrence};
+use crate::services::settings::keys;
+
+const DEFAULT_UPCOMING_LIMIT: usize = 20;
+const MAX_UPCOMING_LIMIT: usize = 100;
+
+/// Build the event management router (requires admin)
+pub fn router() -> Router<AppState> {
+    Router::new()
+        .route("/", get(list_events).post(create_event))
+        .route(
+            "/{id}",
+            get(get_event).put(update_event).delete(delete_event),
+        )
+}
+
+/// Build the public event router
+pub fn public_router() -> Router<AppState> {
+    Router::new().route("/upcoming", get(upcoming_events))
+}
+
+#[derive(Debug, Serialize)]
+struct EventsResponse {
+    events: Vec<Event>,
+}
+
+#[derive(Debug, Serialize)]
+struct EventResponse {
+    event: Event,
+}
+
+#[derive(Debug, Deserialize)]
+struct UpcomingQuery {
+    limit: Option<usize>,
+}
+
+#[derive(Debug, Serialize)]
+struct UpcomingResponse {
+    occurrences: Vec<EventOccurrence>,
+}
+
+async fn list_events(State(state): State<AppState>) -> Result<impl IntoResponse, ApiError> {
+    let events = state
+        .event_service
+        .list()
+        .await
+        .map_err(|e| ApiError::internal_error(e.to_string()))?;
+    Ok(Json(EventsResponse { events }))
+}
+
+async fn get_event(
+    State(state): State<AppState>,
+    Path(id): Path<i64>,
+) -> Result<impl IntoResponse, ApiError> {
+    let event = state
+        .event_service
+        .get_by_id(id)
+        .await
+        .map_err(|e| ApiError::internal_error(e.to_string()))?;
+    match event {
+        Some(event) => Ok(Json(EventResponse { event })),
+        None => Err(ApiError::not_found("Event not found")),
+    }
+}
+
+async fn create_event(
+    State(state): State<AppState>,
+    Json(input): Json<EventInput>,
+) -> Result<impl IntoResponse, ApiError> {
+    let event = state
+        .event_service
+        .create(input)
+        .await
+        .map_err(|e| ApiError::validation_error(e.to_string()))?;
+    Ok((StatusCode::CREATED, Json(EventResponse { event })))
+}
+
+async fn update_event(
+    State(state): State<AppState>,
+    Path(id): Path<i64>,
+    Json(input): Json<EventInput>,
+) -> Result<impl IntoResponse, ApiError> {
+    let event = state
+        .event_service
+        .update(id, input)
+        .await
+        .map_err(|e| ApiError::validation_error(e.to_string()))?;
+    Ok(Json(EventResponse { event }))
+}
+
+async fn delete_event(
+    State(state): State<AppState>,
+    Path(id): Path<i64>,
+) -> Result<impl IntoResponse, ApiError> {
+    let deleted = state
+        .event_service
+        .delete(id)
+        .await
+        .map_err(|e| ApiError::internal_error(e.to_string()))?;
+    if !deleted {
+        return Err(ApiError::not_found("Event not found"));
+    }
+    Ok(StatusCode::NO_CONTENT)
+}
+
+async fn upcoming_events(
+    State(state): State<AppState>,
+    Query(query): Query<UpcomingQuery>,
+) -> Result<impl IntoResponse, ApiError> {
+    let limit = query
+        .limit
+        .unwrap_or(DEFAULT_UPCOMING_LIMIT)
+        .clamp(1, MAX_UPCOMING_LIMIT);
+    let occurrences = state
+        .event_service
+        .upcoming(Utc::now(), limit)
+        .await
+        .map_err(|e| ApiError::internal_error(e.to_string()))?;
+    Ok(Json(UpcomingResponse { occurrences }))
+}
+
+/// GET /events.ics
+pub async fn events_ics(State(state): State<AppState>) -> Response {
+    let settings = &state.settings_service;
+    let site_name = settings
+        .get(keys::SITE_NAME)
+        .await
+        .ok()
+        .flatten()
+        .unwrap_or_else(|| "Noteva".to_string());
+    let site_url = settings
+        .get(keys::SITE_URL)
+        .await
+        .ok()
+        .flatten()
+        .unwrap_or_default();
+
+    match state.event_service.ical_feed(&site_name, &site_url).await {
+        Ok(ical) => Response::builder()
+            .status(StatusCode::OK)
+            .header(header::CONTENT_TYPE, "text/calendar; charset=utf-8")
+            .header(header::CACHE_CONTROL, "public, max-age=1800")
+            .body(Body::from(ical))
+            .unwrap(),
+        Err(e) => {
+            tracing::error!("Failed to build events feed: {}", e);
+            StatusCode::INTERNAL_SERVER_ERROR.into_response()
+        }
+    }
+}
@@ -0,0 +1,249 @@
+//! FAQ API endpoints.
+//!
+//! - GET /api/v1/admin/faq/topics - All topics
+//! - POST /api/v1/admin/faq/topics - Create a topic
+//! - GET/PUT/DELETE /api/v1/admin/faq/topics/:id - Manage a topic
+//! - GET /api/v1/admin/faq/topics/:id/items - A topic's questions
+//! - POST /api/v1/admin/faq/items - Add a question
+//! - GET/PUT/DELETE /api/v1/admin/faq/items/:id - Manage a question
+//! - GET /api/v1/faq - All topics with their questions
+//! - GET /api/v1/faq/:slug - One topic with its questions
+//!
+//! Public responses carry `json_ld`, a schema.org `FAQPage` object for
+//! themes to print in a `<script type="application/ld+json">`.
+
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+    response::IntoResponse,
+    routing::{get, post},
+    Json, Router,
+};
+use serde::Serialize;
+use serde_json::Value;
+
+use crate::api::middleware::{ApiError, AppState};
+use crate::models::{FaqItem, FaqItemInput, FaqTopic, FaqTopicInput, FaqTopicWithItems};
+use crate::services::FaqError;
+use crate::theme::structured_data;
+
+/// Build the FAQ management router (requires admin)
+pub fn router() -> Router<AppState> {
+    Router::new()
+        .route("/topics", get(list_topics).post(create_topic))
+        .route(
+            "/topics/{id}",
+            get(get_topic).put(update_topic).delete(delete_topic),
+        )
+        .route("/topics/{id}/items", get(list_items))
+        .route("/items", post(create_item))
+        .route(
+            "/items/{id}",
+            get(get_item).put(update_item).delete(delete_item),
+        )
+}
+
+/// Build the public FAQ router
+pub fn public_router() -> Router<AppState> {
+    Router::new()
+        .route("/", get(public_topics))
+        .route("/{slug}", get(public_topic))
+}
+
+/// Standalone `FAQPage` JSON-LD for every question of the topics
+fn json_ld(topics: &[FaqTopicWithItems]) -> Value {
+    let questions: Vec<(&str, &str)> = topics
+        .iter()
+        .flat_map(|t| &t.items)
+        .map(|item| (item.question.as_str(), item.answer_html.as_str()))
+        .collect();
+    let mut page = structured_data::faq_page(&questions);
+    page["@context"] = Value::from("https://schema.org");
+    page
+}
+
+#[derive(Debug, Serialize)]
+struct TopicsResponse {
+    topics: Vec<FaqTopic>,
+}
+
+#[derive(Debug, Serialize)]
+struct TopicResponse {
+    topic: FaqTopic,
+}
+
+#[derive(Debug, Serialize)]
+struct ItemsResponse {
+    items: Vec<FaqItem>,
+}
+
+#[derive(Debug, Serialize)]
+struct ItemResponse {
+    item: FaqItem,
+}
+
+#[derive(Debug, Serialize)]
+struct PublicTopicsResponse {
+    topics: Vec<FaqTopicWithItems>,
+    json_ld: Value,
+}
+
+#[derive(Debug, Serialize)]
+struct PublicTopicResponse {
+    topic: FaqTopicWithItems,
+    json_ld: Value,
+}
+
+fn map_faq_error(e: FaqError) -> ApiError {
+    match e {
+        FaqError::NotFound(_) => ApiError::not_found(e.to_string()),
+        FaqError::Validation(_) => ApiError::validation_error(e.to_string()),
+        FaqError::Internal(e) => ApiError::internal_error(e.to_string()),
+    }
+}
+
+async fn list_topics(State(state): State<AppState>) -> Result<impl IntoResponse, ApiError> {
+    let topics = state
+        .faq_service
+        .list_topics()
+        .await
+        .map_err(map_faq_error)?;
+    Ok(Json(TopicsResponse { topics }))
+}
+
+async fn get_topic(
+    State(state): State<AppState>,
+    Path(id): Path<i64>,
+) -> Result<impl IntoResponse, ApiError> {
+    let topic = state
+        .faq_service
+        .get_topic(id)
+        .await
+        .map_err(map_faq_error)?;
+    Ok(Json(TopicResponse { topic }))
+}
+
+async fn create_topic(
+    State(state): State<AppState>,
+    Json(input): Json<FaqTopicInput>,
+) -> Result<impl IntoResponse, ApiError> {
+    let topic = state
+        .faq_service
+        .create_topic(input)
+        .await
+        .map_err(map_faq_error)?;
+    Ok((StatusCode::CREATED, Json(TopicResponse { topic })))
+}
+
+async fn update_topic(
+    State(state): State<AppState>,
+    Path(id): Path<i64>,
+    Json(input): Json<FaqTopicInput>,
+) -> Result<impl IntoResponse, ApiError> {
+    let topic = state
+        .faq_service
+        .update_topic(id, input)
+        .await
+        .map_err(map_faq_error)?;
+    Ok(Json(TopicResponse { topic }))
+}
+
+async fn delete_topic(
+    State(state): State<AppState>,
+    Path(id): Path<i64>,
+) -> Result<impl IntoResponse, ApiError> {
+    state
+        .faq_service
+        .delete_topic(id)
+        .await
+        .map_err(map_faq_error)?;
+    Ok(StatusCode::NO_CONTENT)
+}
+
+async fn list_items(
+    State(state): State<AppState>,
+    Path(id): Path<i64>,
+) -> Result<impl IntoResponse, ApiError> {
+    let items = state
+        .faq_service
+        .list_items(id)
+        .await
+        .map_err(map_faq_error)?;
+    Ok(Json(ItemsResponse { items }))
+}
+
+async fn get_item(
+    State(state): State<AppState>,
+    Path(id): Path<i64>,
+) -> Result<impl IntoResponse, ApiError> {
+    let item = state
+        .faq_service
+        .get_item(id)
+        .await
+        .map_err(map_faq_error)?;
+    Ok(Json(ItemResponse { item }))
+}
+
+async fn create_item(
+    State(state): State<AppState>,
+    Json(input): Json<FaqItemInput>,
+) -> Result<impl IntoResponse, ApiError> {
+    let item = state
+        .faq_service
+        .create_item(input)
+        .await
+        .map_err(map_faq_error)?;
+    Ok((StatusCode::CREATED, Json(ItemResponse { item })))
+}
+
+async fn update_item(
+    State(state): State<AppState>,
+    Path(id): Path<i64>,
+    Json(input): Json<FaqItemInput>,
+) -> Result<impl IntoResponse, ApiError> {
+    let item = state
+        .faq_service
+        .update_item(id, input)
+        .await
+        .map_err(map_faq_error)?;
+    Ok(Json(ItemResponse { item }))
+}
+
+async fn delete_item(
+    State(state): State<AppState>,
+    Path(id): Path<i64>,
+) -> Result<impl IntoResponse, ApiError> {
+    state
+        .faq_service
+        .delete_item(id)
+        .await
+        .map_err(map_faq_error)?;
+    Ok(StatusCode::NO_CONTENT)
+}
+
+async fn public_topics(State(state): State<AppState>) -> Result<impl IntoResponse, ApiError> {
+    let topics = state
+        .faq_service
+        .list_with_items()
+        .await
+        .map_err(map_faq_error)?;
+    Ok(Json(PublicTopicsResponse {
+        json_ld: json_ld(&topics),
+        topics,
+    }))
+}
+
+async fn public_topic(
+    State(state): State<AppState>,
+    Path(slug): Path<String>,
+) -> Result<impl IntoResponse, ApiError> {
+    let topic = state
+        .faq_service
+        .get_with_items(&slug)
+        .await
+        .map_err(map_faq_error)?;
+    Ok(Json(PublicTopicResponse {
+        json_ld: json_ld(std::slice::from_ref(&topic)),
+        topic,
+    }))
+}
@@ -0,0 +1,273 @@
+//! Reader favorites ("read later")
+//!
+//! - GET /api/v1/favorites - Saved articles, most recently saved first
+//! - GET /api/v1/favorites/settings - Whether the list is public
+//! - PUT /api/v1/favorites/settings - Make the list public or private
+//! - GET /api/v1/favorites/:article_id - Whether an article is saved
+//! - PUT /api/v1/favorites/:article_id - Save an article
+//! - DELETE /api/v1/favorites/:article_id - Remove an article
+//! - GET /api/v1/users/:username/favorites - Public list of a user
+//!
+//! Saving is idempotent. Every article carries `favorite_count`; lists are
+//! private until the owner makes them public.
+
+use axum::{
+    extract::{Path, Query, State},
+    routing::get,
+    Json, Router,
+};
+use serde::{Deserialize, Serialize};
+
+use crate::api::middleware::{ApiError, AppState, AuthenticatedUser};
+use crate::models::{ArticleStatus, FavoriteArticle, ListParams, PagedResult};
+
+/// Build the favorites router (requires authentication)
+pub fn router() -> Router<AppState> {
+    Router::new()
+        .route("/", get(list_favorites))
+        .route("/settings", get(get_settings).put(update_settings))
+        .route(
+            "/{article_id}",
+            get(get_favorite).put(add_favorite).delete(remove_favorite),
+        )
+}
+
+/// Build the public favorites router, nested under `/users`
+pub fn public_router() -> Router<AppState> {
+    Router::new().route("/{username}/favorites", get(list_public_favorites))
+}
+
+/// Query params for listing favorites
+#[derive(Debug, Deserialize)]
+pub struct ListFavoritesQuery {
+    #[serde(default = "default_page")]
+    pub page: u32,
+    #[serde(default = "default_page_size")]
+    pub page_size: u32,
+}
+
+fn default_page() -> u32 {
+    1
+}
+
+fn default_page_size() -> u32 {
+    10
+}
+
+/// A page of saved articles
+#[derive(Debug, Serialize)]
+pub struct FavoritesResponse {
+    pub favorites: Vec<FavoriteArticle>,
+    pub total: i64,
+    pub page: u32,
+    pub page_size: u32,
+    pub total_pages: u32,
+}
+
+/// Owner of a public favorites list
+#[derive(Debug, Serialize)]
+pub struct FavoritesOwner {
+    pub username: String,
+    pub display_name: Option<String>,
+    pub avatar: Option<String>,
+}
+
+/// A user's public favorites
+#[derive(Debug, Serialize)]
+pub struct PublicFavoritesResponse {
+    pub user: FavoritesOwner,
+    #[serde(flatten)]
+    pub list: FavoritesResponse,
+}
+
+/// Favorite state of one article for the current user
+#[derive(Debug, Serialize)]
+pub struct FavoriteStatus {
+    pub favorited: bool,
+    pub favorite_count: i64,
+}
+
+/// Visibility of the favorites list
+#[derive(Debug, Serialize, Deserialize)]
+pub struct FavoriteSettings {
+    pub public: bool,
+}
+
+async fn favorites_page(
+    state: &AppState,
+    user_id: i64,
+    query: &ListFavoritesQuery,
+) -> Result<FavoritesResponse, ApiError> {
+    let params = ListParams::new(query.page, query.page_size);
+    let favorites = state
+        .favorite_repo
+        .list(user_id, params.offset(), params.limit())
+        .await
+        .map_err(|e| ApiError::internal_error(e.to_string()))?;
+    let total = state
+        .favorite_repo
+        .count(user_id)
+        .await
+        .map_err(|e| ApiError::internal_error(e.to_string()))?;
+    let result = PagedResult::new(favorites, total, &params);
+    let total_pages = result.total_pages();
+    Ok(FavoritesResponse {
+        favorites: result.items,
+        total: result.total,
+        page: result.page,
+        page_size: result.per_page,
+        total_pages,
+    })
+}
+
+/// GET /api/v1/favorites - Saved articles of the current user
+async fn list_favorites(
+    State(state): State<AppState>,
+    user: AuthenticatedUser,
+    Query(query): Query<ListFavoritesQuery>,
+) -> Result<Json<FavoritesResponse>, ApiError> {
+    Ok(Json(favorites_page(&state, user.0.id, &query).await?))
+}
+
+/// GET /api/v1/users/:username/favorites - Public favorites of a user
+async fn list_public_favorites(
+    State(state): State<AppState>,
+    Path(username): Path<String>,
+    Query(query): Query<ListFavoritesQuery>,
+) -> Result<Json<PublicFavoritesResponse>, ApiError> {
+    let user = state
+        .user_repo
+        .get_by_username(&username)
+        .await
+        .map_err(|e| ApiError::internal_error(e.to_string()))?
+        .filter(|u| u.is_active())
+        .ok_or_else(|| ApiError::not_found("User not found"))?;
+    let public = state
+        .favorite_repo
+        .is_public(user.id)
+        .await
+        .map_err(|e| ApiError::internal_error(e.to_string()))?;
+    // A private list looks the same as a missing user
+    if !public {
+        return Err(ApiError::not_found("User not found"));
+    }
+
+    let list = favorites_page(&state, user.id, &query).await?;
+    Ok(Json(PublicFavoritesResponse {
+        user: FavoritesOwner {
+            username: user.username,
+            display_name: user.display_name,
+            avatar: user.avatar,
+        },
+        list,
+    }))
+}
+
+/// GET /api/v1/favorites/:article_id - Whether the user saved an article
+async fn get_favorite(
+    State(state): State<AppState>,
+    user: AuthenticatedUser,
+    Path(article_id): Path<i64>,
+) -> Result<Json<FavoriteStatus>, ApiError> {
+    let favorited = state
+        .favorite_repo
+        .contains(user.0.id, article_id)
+        .await
+        .map_err(|e| ApiError::internal_error(e.to_string()))?;
+    favorite_status(&state, article_id, favorited).await
+}
+
+/// PUT /api/v1/favorites/:article_id - Save an article
+async fn add_favorite(
+    State(state): State<AppState>,
+    user: AuthenticatedUser,
+    Path(article_id): Path<i64>,
+) -> Result<Json<FavoriteStatus>, ApiError> {
+    let article = published_article(&state, article_id).await?;
+    let added = state
+        .favorite_repo
+        .add(user.0.id, article_id)
+        .await
+        .map_err(|e| ApiError::internal_error(e.to_string()))?;
+    if added {
+        let _ = state
+            .article_service
+            .invalidate_article_cache(article_id, &article.slug)
+            .await;
+    }
+    favorite_status(&state, article_id, true).await
+}
+
+/// DELETE /api/v1/favorites/:article_id - Remove an article
+async fn remove_favorite(
+    State(state): State<AppState>,
+    user: AuthenticatedUser,
+    Path(article_id): Path<i64>,
+) -> Result<Json<FavoriteStatus>, ApiError> {
+    let removed = state
+        .favorite_repo
+        .remove(user.0.id, article_id)
+        .await
+        .map_err(|e| ApiError::internal_error(e.to_string()))?;
+    if removed {
+        if let Ok(Some(article)) = state.article_service.get_by_id(article_id).await {
+            let _ = state
+                .article_service
+                .invalidate_article_cache(article_id, &article.slug)
+                .await;
+        }
+    }
+    favorite_status(&state, article_id, false).await
+}
+
+/// GET /api/v1/favorites/settings - Whether the list is public
+async fn get_settings(
+    State(state): State<AppState>,
+    user: AuthenticatedUser,
+) -> Result<Json<FavoriteSettings>, ApiError> {
+    let public = state
+        .favorite_repo
+        .is_public(user.0.id)
+        .await
+        .map_err(|e| ApiError::internal_error(e.to_string()))?;
+    Ok(Json(FavoriteSettings { public }))
+}
+
+/// PUT /api/v1/favorites/settings - Make the list public or private
+async fn update_settings(
+    State(state): State<AppState>,
+    user: AuthenticatedUser,
+    Json(body): Json<FavoriteSettings>,
+) -> Result<Json<FavoriteSettings>, ApiError> {
+    state
+        .favorite_repo
+        .set_public(user.0.id, body.public)
+        .await
+        .map_err(|e| ApiError::internal_error(e.to_string()))?;
+    Ok(Json(body))
+}
+
+async fn published_article(
+    state: &AppState,
+    article_id: i64,
+) -> Result<crate::models::Article, ApiError> {
+    state
+        .article_service
+        .get_by_id(article_id)
+        .await
+        .map_err(|e| ApiError::internal_error(e.to_string()))?
+        .filter(|a| a.status == ArticleStatus::Published)
+        .ok_or_else(|| ApiError::not_found("Article not found"))
+}
+
+async fn favorite_status(
+    state: &AppState,
+    article_id: i64,
+    favorited: bool,
+) -> Result<Json<FavoriteStatus>, ApiError> {
+    let article = published_article(state, article_id).await?;
+    Ok(Json(FavoriteStatus {
+        favorited,
+        favorite_count: article.favorite_count,
+    }))
+}
@@ -0,0 +1,98 @@
+//! Featured article API endpoints.
+//!
+//! - GET /api/v1/admin/featured - The featured list, expired entries included
+//! - PUT /api/v1/admin/featured - Replace the list, in order
+//! - DELETE /api/v1/admin/featured/:article_id - Take an article off the list
+//!
+//! Themes read the live list from `GET /api/v1/articles/featured`.
+
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+    response::IntoResponse,
+    routing::{delete, get},
+    Json, Router,
+};
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+
+use crate::api::middleware::{ApiError, AppState};
+use crate::models::{FeaturedArticle, FeaturedInput};
+use crate::services::FeaturedError;
+
+/// Build the featured list management router (requires admin)
+pub fn router() -> Router<AppState> {
+    Router::new()
+        .route("/", get(list_featured).put(set_featured))
+        .route("/{article_id}", delete(remove_featured))
+}
+
+#[derive(Debug, Serialize)]
+struct FeaturedEntry {
+    #[serde(flatten)]
+    entry: FeaturedArticle,
+    /// Past its expiry, no longer shown to visitors
+    expired: bool,
+}
+
+#[derive(Debug, Serialize)]
+struct FeaturedListResponse {
+    items: Vec<FeaturedEntry>,
+}
+
+impl FeaturedListResponse {
+    fn new(items: Vec<FeaturedArticle>, now: DateTime<Utc>) -> Self {
+        Self {
+            items: items
+                .into_iter()
+                .map(|entry| FeaturedEntry {
+                    expired: entry.is_expired(now),
+                    entry,
+                })
+                .collect(),
+        }
+    }
+}
+
+fn map_featured_error(e: FeaturedError) -> ApiError {
+    match e {
+        FeaturedError::NotFound(_) => ApiError::not_found(e.to_string()),
+        FeaturedError::Validation(_) => ApiError::validation_error(e.to_string()),
+        FeaturedError::Internal(e) => ApiError::internal_error(e.to_string()),
+    }
+}
+
+async fn list_featured(State(state): State<AppState>) -> Result<impl IntoResponse, ApiError> {
+    let items = state
+        .featured_service
+        .list()
+        .await
+        .map_err(map_featured_error)?;
+    Ok(Json(FeaturedListResponse::new(items, Utc::now())))
+}
+
+/// Body: `{"items": [{"article_id": 3, "expires_at": "2025-01-31T00:00:00Z"}, {"article_id": 1}]}`,
+/// in display order
+async fn set_featured(
+    State(state): State<AppState>,
+    Json(input): Json<FeaturedInput>,
+) -> Result<impl IntoResponse, ApiError> {
+    let items = state
+        .featured_service
+        .set(input)
+        .await
+        .map_err(map_featured_error)?;
+    Ok(Json(FeaturedListResponse::new(items, Utc::now())))
+}
+
+async fn remove_featured(
+    State(state): State<AppState>,
+    Path(article_id): Path<i64>,
+) -> Result<impl IntoResponse, ApiError> {
+    state
+        .featured_service
+        .remove(article_id)
+        .await
+        .map_err(map_featured_error)?;
+    Ok(StatusCode::NO_CONTENT)
+}
@@ -0,0 +1,85 @@
+//! GitHub "publish on push" webhook
+//!
+//! POST /api/v1/integrations/github/push receives push events from the
+//! content repository configured in settings and publishes the changed
+//! Markdown files in the background.
+
+use axum::{
+    body::Bytes,
+    extract::State,
+    http::{HeaderMap, StatusCode},
+    Json,
+};
+use serde_json::{json, Value};
+use std::sync::Arc;
+
+use crate::api::middleware::{ApiError, AppState};
+use crate::services::github_publish::PushEvent;
+use crate::services::GithubPublishError;
+
+fn map_error(e: GithubPublishError) -> ApiError {
+    match e {
+        GithubPublishError::NotConfigured => {
+            ApiError::not_found("GitHub publishing is not configured")
+        }
+        GithubPublishError::InvalidSignature => ApiError::unauthorized("Invalid webhook signature"),
+        GithubPublishError::InvalidRequest(msg) => ApiError::validation_error(msg),
+        GithubPublishError::Internal(e) => ApiError::internal_error(e.to_string()),
+    }
+}
+
+/// POST /api/v1/integrations/github/push - Receive a GitHub webhook delivery
+pub async fn receive(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    body: Bytes,
+) -> Result<(StatusCode, Json<Value>), ApiError> {
+    let service = state.github_publish.clone();
+    service
+        .verify_signature(
+            headers
+                .get("x-hub-signature-256")
+                .and_then(|v| v.to_str().ok()),
+            &body,
+        )
+        .await
+        .map_err(map_error)?;
+
+    let event_type = headers
+        .get("x-github-event")
+        .and_then(|v| v.to_str().ok())
+        .unwrap_or_default();
+    if event_type != "push" {
+        // `ping` is sent when the webhook is created; other events are ignored
+        return Ok((StatusCode::OK, Json(json!({ "ignored": event_type }))));
+    }
+
+    let event: PushEvent = serde_json::from_slice(&body)
+        .map_err(|e| map_error(GithubPublishError::InvalidRequest(e.to_string())))?;
+    let Some(files) = service.files_to_publish(&event).await.map_err(map_error)? else {
+        return Ok((
+            StatusCode::OK,
+            Json(json!({ "ignored": "repository or branch not configured for publishing" })),
+        ));
+    };
+
+    let response = json!({ "files": &files });
+    if !files.is_empty() {
+        let commit = event.after.clone();
+        let (event, files) = (Arc::new(event), Arc::new(files));
+        state.jobs.submit("github_publish", commit, move || {
+            let (service, event, files) = (service.clone(), event.clone(), files.clone());
+            async move {
+                let report = service.publish(&event, &files).await;
+                if report.errors.is_empty() {
+                    tracing::info!(commit = %event.after, ?report, "GitHub push published");
+                    Ok(())
+                } else {
+                    tracing::warn!(commit = %event.after, ?report, "GitHub push published with errors");
+                    anyhow::bail!(report.errors.join("; "))
+                }
+            }
+        });
+    }
+    Ok((StatusCode::ACCEPTED, Json(response)))
+}
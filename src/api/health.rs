@@ -0,0 +1,189 @@
+//! Liveness and readiness probes
+//!
+//! `GET /healthz` only says the process is serving requests. `GET /readyz`
+//! checks the database, cache, migrations and active theme and answers
+//! `503` when any of them fails, so orchestrators can hold traffic back.
+
+use axum::{extract::State, http::StatusCode, Json};
+use serde::Serialize;
+use std::future::Future;
+use std::time::{Duration, Instant};
+
+use crate::api::middleware::AppState;
+use crate::db::migrations;
+
+/// Upper bound for each readiness check
+const CHECK_TIMEOUT: Duration = Duration::from_secs(3);
+
+/// Response for GET /healthz
+#[derive(Debug, Serialize)]
+pub struct HealthResponse {
+    pub status: &'static str,
+    pub version: &'static str,
+    pub uptime_seconds: u64,
+}
+
+/// Outcome of a single readiness check
+#[derive(Debug, Serialize)]
+pub struct CheckResult {
+    pub ok: bool,
+    pub latency_ms: u64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub detail: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+/// Response for GET /readyz
+#[derive(Debug, Serialize)]
+pub struct ReadinessResponse {
+    pub status: &'static str,
+    pub database: CheckResult,
+    pub cache: CheckResult,
+    pub migrations: CheckResult,
+    pub theme: CheckResult,
+}
+
+impl ReadinessResponse {
+    pub(crate) fn new(
+        database: CheckResult,
+        cache: CheckResult,
+        migrations: CheckResult,
+        theme: CheckResult,
+    ) -> Self {
+        let ready = [&database, &cache, &migrations, &theme]
+            .iter()
+            .all(|check| check.ok);
+        Self {
+            status: if ready { "ready" } else { "not_ready" },
+            database,
+            cache,
+            migrations,
+            theme,
+        }
+    }
+
+    pub(crate) fn is_ready(&self) -> bool {
+        self.status == "ready"
+    }
+
+    /// Checks in display order
+    pub(crate) fn components(&self) -> [(&'static str, &CheckResult); 4] {
+        [
+            ("database", &self.database),
+            ("cache", &self.cache),
+            ("migrations", &self.migrations),
+            ("theme", &self.theme),
+        ]
+    }
+
+    fn status_code(&self) -> StatusCode {
+        if self.is_ready() {
+            StatusCode::OK
+        } else {
+            StatusCode::SERVICE_UNAVAILABLE
+        }
+    }
+}
+
+/// Run a check with a timeout; `Ok` carries an optional detail message
+async fn run_check<F>(check: F) -> CheckResult
+where
+    F: Future<Output = anyhow::Result<Option<String>>>,
+{
+    let started = Instant::now();
+    let result = tokio::time::timeout(CHECK_TIMEOUT, check).await;
+    let latency_ms = started.elapsed().as_millis() as u64;
+    match result {
+        Ok(Ok(detail)) => CheckResult {
+            ok: true,
+            latency_ms,
+            detail,
+            error: None,
+        },
+        Ok(Err(e)) => CheckResult {
+            ok: false,
+            latency_ms,
+            detail: None,
+            error: Some(e.to_string()),
+        },
+        Err(_) => CheckResult {
+            ok: false,
+            latency_ms,
+            detail: None,
+            error: Some(format!("timed out after {}s", CHECK_TIMEOUT.as_secs())),
+        },
+    }
+}
+
+/// GET /healthz - The process is up
+pub async fn healthz(State(state): State<AppState>) -> Json<HealthResponse> {
+    Json(HealthResponse {
+        status: "ok",
+        version: env!("CARGO_PKG_VERSION"),
+        uptime_seconds: state.request_stats.uptime_seconds(),
+    })
+}
+
+/// GET /readyz - Dependencies are reachable and the schema is current
+pub async fn readyz(State(state): State<AppState>) -> (StatusCode, Json<ReadinessResponse>) {
+    let response = check_components(&state).await;
+    (response.status_code(), Json(response))
+}
+
+/// Run every readiness check; shared with the public status page
+pub(crate) async fn check_components(state: &AppState) -> ReadinessResponse {
+    let (database, cache, migrations) = tokio::join!(
+        run_check(async {
+            state.pool.ping().await?;
+            Ok(None)
+        }),
+        run_check(async {
+            state.cache.ping().await?;
+            Ok(None)
+        }),
+        run_check(async {
+            match migrations::pending_count(&state.pool).await? {
+                0 => Ok(Some(format!("{} applied", migrations::total_migrations()))),
+                pending => anyhow::bail!("{} migrations pending", pending),
+            }
+        }),
+    );
+    let theme = run_check(async {
+        let engine = state
+            .theme_engine
+            .read()
+            .map_err(|_| anyhow::anyhow!("theme engine lock poisoned"))?;
+        let name = engine.get_current_theme();
+        if !engine.theme_exists(name) {
+            anyhow::bail!("active theme '{}' is not installed", name);
+        }
+        if engine.tera().get_template_names().next().is_none() {
+            anyhow::bail!("active theme '{}' has no templates loaded", name);
+        }
+        Ok(Some(name.to_string()))
+    })
+    .await;
+
+    ReadinessResponse::new(database, cache, migrations, theme)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn failing_check_makes_service_unavailable() {
+        let ok = || run_check(async { Ok(None) });
+        let failed = run_check(async { anyhow::bail!("connection refused") }).await;
+        assert!(!failed.ok);
+        assert_eq!(failed.error.as_deref(), Some("connection refused"));
+
+        let ready = ReadinessResponse::new(ok().await, ok().await, ok().await, ok().await);
+        assert_eq!(ready.status_code(), StatusCode::OK);
+
+        let not_ready = ReadinessResponse::new(ok().await, failed, ok().await, ok().await);
+        assert_eq!(not_ready.status, "not_ready");
+        assert_eq!(not_ready.status_code(), StatusCode::SERVICE_UNAVAILABLE);
+    }
+}
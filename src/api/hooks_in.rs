@@ -0,0 +1,38 @@
+//! Inbound webhook receiver
+//!
+//! POST /api/v1/hooks/in/{token} accepts any body, checks the endpoint's
+//! signature and queues the event for plugins (`external_webhook` hook).
+
+use axum::{
+    body::Bytes,
+    extract::{Path, State},
+    http::{HeaderMap, StatusCode},
+};
+
+use crate::api::middleware::{ApiError, AppState};
+use crate::services::InboundWebhookError;
+
+/// POST /api/v1/hooks/in/{token} - Receive an event from a third party
+pub async fn receive(
+    State(state): State<AppState>,
+    Path(token): Path<String>,
+    headers: HeaderMap,
+    body: Bytes,
+) -> Result<StatusCode, ApiError> {
+    state
+        .inbound_webhooks
+        .receive(&token, &headers, &body)
+        .await
+        .map_err(|e| match e {
+            InboundWebhookError::NotFound => ApiError::not_found("Webhook not found"),
+            InboundWebhookError::InvalidSignature => {
+                ApiError::unauthorized("Invalid webhook signature")
+            }
+            InboundWebhookError::QueueFull => {
+                ApiError::new("SERVICE_UNAVAILABLE", "Webhook queue is full, retry later")
+            }
+            other => ApiError::internal_error(other.to_string()),
+        })?;
+
+    Ok(StatusCode::ACCEPTED)
+}
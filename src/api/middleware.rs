@@ -9,7 +9,7 @@
 //! - 5.4: Permission control for admin access
 
 use axum::{
-    extract::{Request, State},
+    extract::{ConnectInfo, OriginalUri, Request, State},
     http::{header, HeaderMap, StatusCode},
     middleware::Next,
     response::{IntoResponse, Response},
@@ -36,6 +36,8 @@ pub struct RequestStats {
     total_requests: AtomicU64,
     /// Total response time in microseconds (for calculating average)
     total_response_time_us: AtomicU64,
+    /// Requests currently being handled
+    in_flight: AtomicU64,
     /// Application start time
     start_time: Instant,
 }
@@ -46,6 +48,7 @@ impl RequestStats {
         Self {
             total_requests: AtomicU64::new(0),
             total_response_time_us: AtomicU64::new(0),
+            in_flight: AtomicU64::new(0),
             start_time: Instant::now(),
         }
     }
@@ -76,6 +79,27 @@ impl RequestStats {
     pub fn uptime_seconds(&self) -> u64 {
         self.start_time.elapsed().as_secs()
     }
+
+    /// Number of requests currently being handled
+    pub fn in_flight(&self) -> u64 {
+        self.in_flight.load(Ordering::Relaxed)
+    }
+
+    /// Count a request as in flight until the guard is dropped, which also
+    /// covers requests abandoned by the client
+    pub fn track_in_flight(&self) -> InFlightGuard<'_> {
+        self.in_flight.fetch_add(1, Ordering::Relaxed);
+        InFlightGuard(&self.in_flight)
+    }
+}
+
+/// Guard returned by [`RequestStats::track_in_flight`]
+pub struct InFlightGuard<'a>(&'a AtomicU64);
+
+impl Drop for InFlightGuard<'_> {
+    fn drop(&mut self) {
+        self.0.fetch_sub(1, Ordering::Relaxed);
+    }
 }
 
 impl Default for RequestStats {
@@ -101,8 +125,12 @@ pub type TwoFactorChallengeStore =
 #[derive(Clone)]
 pub struct AppState {
     pub pool: crate::db::DynDatabasePool,
+    pub cache: Arc<crate::cache::Cache>,
     pub user_service: Arc<UserService>,
     pub user_repo: Arc<dyn crate::db::repositories::UserRepository>,
+    pub preferences_repo: Arc<dyn crate::db::repositories::UserPreferencesRepository>,
+    pub reading_progress_repo: Arc<dyn crate::db::repositories::ReadingProgressRepository>,
+    pub favorite_repo: Arc<dyn crate::db::repositories::FavoriteRepository>,
     pub article_service: Arc<crate::services::article::ArticleService>,
     pub category_service: Arc<crate::services::category::CategoryService>,
     pub tag_service: Arc<crate::services::tag::TagService>,
@@ -110,15 +138,59 @@ pub struct AppState {
     pub comment_service: Arc<crate::services::comment::CommentService>,
     pub about_service: Arc<crate::services::about::AboutService>,
     pub friend_link_service: Arc<crate::services::friend_link::FriendLinkService>,
+    pub redirect_service: Arc<crate::services::redirect::RedirectService>,
+    pub poll_service: Arc<crate::services::PollService>,
+    pub event_service: Arc<crate::services::EventService>,
+    pub doc_service: Arc<crate::services::DocService>,
+    pub faq_service: Arc<crate::services::FaqService>,
+    pub series_service: Arc<crate::services::SeriesService>,
+    pub featured_service: Arc<crate::services::FeaturedService>,
+    pub attachment_service: Arc<crate::services::AttachmentService>,
+    pub article_author_service: Arc<crate::services::ArticleAuthorService>,
+    pub custom_field_service: Arc<crate::services::CustomFieldService>,
+    pub reader_group_service: Arc<crate::services::ReaderGroupService>,
+    pub translation_service: Arc<crate::services::TranslationService>,
+    pub webmention_service: Arc<crate::services::webmention::WebmentionService>,
+    pub newsletter_service: Arc<crate::services::NewsletterService>,
+    pub web_push_service: Arc<crate::services::WebPushService>,
+    /// Hunspell dictionaries for draft spell checking
+    pub spellcheck: Arc<crate::services::SpellcheckService>,
+    /// Latest near-duplicate article report
+    pub duplicates: Arc<crate::services::DuplicateService>,
+    pub link_suggestions: Arc<crate::services::LinkSuggestionService>,
+    pub inbound_webhooks: Arc<crate::services::InboundWebhookService>,
+    pub github_publish: Arc<crate::services::GithubPublishService>,
+    pub maintenance: Arc<crate::services::MaintenanceService>,
+    pub api_exposure: Arc<crate::services::ApiExposureService>,
+    pub webauthn_service: Arc<crate::services::webauthn::WebauthnService>,
+    /// SAML single sign-on, when enabled in config
+    #[cfg(feature = "saml")]
+    pub saml_service: Option<Arc<crate::services::saml::SamlService>>,
     pub theme_engine: Arc<std::sync::RwLock<crate::theme::ThemeEngine>>,
     pub upload_config: Arc<crate::config::UploadConfig>,
+    /// The server terminates TLS itself (`server.tls`)
+    pub native_tls: bool,
+    pub status_page: crate::config::StatusPageConfig,
     pub page_service: Arc<crate::services::page::PageService>,
     pub nav_service: Arc<crate::services::nav_item::NavItemService>,
+    pub sync_service: Arc<crate::services::SyncService>,
+    pub stats_service: Arc<crate::services::StatsService>,
+    /// Page view analytics of the public site
+    pub analytics: Arc<crate::services::AnalyticsService>,
+    pub views: Arc<crate::services::ViewCounter>,
+    pub backup_service: Arc<crate::services::backup::BackupService>,
+    pub jobs: Arc<crate::services::JobMonitor>,
+    pub job_queue: Arc<crate::services::JobQueue>,
     pub plugin_manager: Arc<tokio::sync::RwLock<PluginManager>>,
     pub hook_manager: Arc<HookManager>,
     pub shortcode_manager: Arc<ShortcodeManager>,
     pub request_stats: Arc<RequestStats>,
+    /// Live events streamed at `/admin/events`
+    pub admin_events: Arc<crate::services::AdminEvents>,
     pub rate_limiter: Arc<crate::services::LoginRateLimiter>,
+    pub api_rate_limiter: Arc<crate::services::ApiRateLimiter>,
+    pub email_service: Arc<crate::services::EmailService>,
+    pub ip_reputation: Arc<crate::services::IpReputationStore>,
     pub captcha_pow_store: Arc<crate::services::captcha_pow::CaptchaPowStore>,
     pub wasm_runtime: Arc<tokio::sync::RwLock<crate::plugin::PluginRuntime>>,
     pub wasm_registry: Arc<tokio::sync::RwLock<crate::plugin::wasm_bridge::WasmPluginRegistry>>,
@@ -187,6 +259,10 @@ impl ApiError {
     pub fn internal_error(message: impl Into<String>) -> Self {
         Self::new("INTERNAL_ERROR", message)
     }
+
+    pub fn precondition_failed(message: impl Into<String>) -> Self {
+        Self::new("PRECONDITION_FAILED", message)
+    }
 }
 
 impl IntoResponse for ApiError {
@@ -197,8 +273,11 @@ impl IntoResponse for ApiError {
             "NOT_FOUND" => StatusCode::NOT_FOUND,
             "VALIDATION_ERROR" => StatusCode::BAD_REQUEST,
             "CONFLICT" => StatusCode::CONFLICT,
+            "PRECONDITION_FAILED" => StatusCode::PRECONDITION_FAILED,
             "USER_BANNED" => StatusCode::FORBIDDEN,
+            "PASSWORD_RESET_REQUIRED" => StatusCode::FORBIDDEN,
             "RATE_LIMIT" => StatusCode::TOO_MANY_REQUESTS,
+            "SERVICE_UNAVAILABLE" => StatusCode::SERVICE_UNAVAILABLE,
             _ => StatusCode::INTERNAL_SERVER_ERROR,
         };
 
@@ -217,6 +296,16 @@ pub fn extract_client_ip(headers: &HeaderMap, peer_addr: SocketAddr) -> String {
     peer_ip.to_string()
 }
 
+/// Refuse requests from addresses blocked by the shared IP reputation store.
+pub async fn ensure_ip_not_blocked(state: &AppState, client_ip: &str) -> Result<(), ApiError> {
+    if state.ip_reputation.is_blocked(client_ip).await {
+        return Err(ApiError::forbidden(
+            "Requests from your network are temporarily blocked",
+        ));
+    }
+    Ok(())
+}
+
 /// Decide whether auth cookies should include the Secure flag.
 ///
 /// Prefer the configured site URL because it is controlled by the admin. Only
@@ -226,6 +315,9 @@ pub async fn should_set_secure_cookie(
     headers: &HeaderMap,
     peer_addr: Option<SocketAddr>,
 ) -> bool {
+    if state.native_tls {
+        return true;
+    }
     if let Ok(Some(site_url)) = state.settings_service.get("site_url").await {
         if site_url.trim().to_ascii_lowercase().starts_with("https://") {
             return true;
@@ -398,10 +490,32 @@ pub async fn require_auth(
         .map_err(|e| ApiError::internal_error(format!("Session validation failed: {}", e)))?
         .ok_or_else(|| ApiError::unauthorized("Invalid or expired session"))?;
 
+    if user.password_reset_required && !is_password_reset_exempt(&request) {
+        return Err(ApiError::new(
+            "PASSWORD_RESET_REQUIRED",
+            "You must change your password before continuing",
+        ));
+    }
+
     request.extensions_mut().insert(AuthenticatedUser(user));
     Ok(next.run(request).await)
 }
 
+/// Endpoints a user with a pending admin-enforced password reset may still use
+const PASSWORD_RESET_EXEMPT_PATHS: &[&str] = &["/auth/password", "/auth/me", "/auth/logout"];
+
+fn is_password_reset_exempt(request: &Request) -> bool {
+    // Nested routers see a stripped path; match against the original URI
+    let path = request
+        .extensions()
+        .get::<OriginalUri>()
+        .map(|uri| uri.path())
+        .unwrap_or_else(|| request.uri().path());
+    PASSWORD_RESET_EXEMPT_PATHS
+        .iter()
+        .any(|exempt| path.ends_with(exempt))
+}
+
 /// Optional authentication middleware
 pub async fn optional_auth(
     State(state): State<AppState>,
@@ -515,6 +629,7 @@ pub async fn request_stats_middleware(
     next: Next,
 ) -> Response {
     let start = Instant::now();
+    let _in_flight = state.request_stats.track_in_flight();
 
     // Process the request
     let response = next.run(request).await;
@@ -526,6 +641,163 @@ pub async fn request_stats_middleware(
     response
 }
 
+/// Path prefixes that are never counted as page views
+const NON_PAGE_PREFIXES: &[&str] = &[
+    "/api/",
+    "/manage",
+    "/uploads/",
+    "/themes/",
+    "/_next/",
+    "/embed/",
+];
+
+/// Page view analytics middleware
+///
+/// Counts successful HTML responses to `GET` requests of the public site.
+/// Prefetches are skipped, and so are visitors sending `DNT: 1` or
+/// `Sec-GPC: 1` unless `analytics.respect_dnt` is off.
+pub async fn analytics_middleware(
+    State(state): State<AppState>,
+    request: Request,
+    next: Next,
+) -> Response {
+    let config = state.analytics.config();
+    let path = request.uri().path();
+    let headers = request.headers();
+    let header_is = |name: &str, value: &str| {
+        headers
+            .get(name)
+            .and_then(|v| v.to_str().ok())
+            .is_some_and(|v| v.trim().eq_ignore_ascii_case(value))
+    };
+    let counted = config.enabled
+        && request.method() == axum::http::Method::GET
+        && path != "/status"
+        && !NON_PAGE_PREFIXES.iter().any(|p| path.starts_with(p))
+        && !header_is("sec-purpose", "prefetch")
+        && !header_is("purpose", "prefetch")
+        && !(config.respect_dnt && (header_is("dnt", "1") || header_is("sec-gpc", "1")));
+    if !counted {
+        return next.run(request).await;
+    }
+
+    let header_value = |name: &str| {
+        headers
+            .get(name)
+            .and_then(|v| v.to_str().ok())
+            .map(str::to_string)
+    };
+    let path = path.to_string();
+    let host = header_value("host");
+    let referrer = header_value("referer");
+    let user_agent = header_value("user-agent").unwrap_or_default();
+    let country = config.country_header.as_deref().and_then(header_value);
+    let peer = request
+        .extensions()
+        .get::<ConnectInfo<SocketAddr>>()
+        .map(|info| info.0)
+        .unwrap_or_else(|| SocketAddr::from(([0, 0, 0, 0], 0)));
+    let client_ip = extract_client_ip(headers, peer);
+
+    let response = next.run(request).await;
+    let is_html = response
+        .headers()
+        .get(header::CONTENT_TYPE)
+        .and_then(|v| v.to_str().ok())
+        .is_some_and(|ct| ct.starts_with("text/html"));
+    if response.status() == StatusCode::OK && is_html {
+        state.analytics.record(&crate::services::PageHit {
+            path: &path,
+            host: host.as_deref(),
+            referrer: referrer.as_deref(),
+            client_ip: &client_ip,
+            user_agent: &user_agent,
+            country: country.as_deref(),
+        });
+    }
+    response
+}
+
+/// Client key for rate limiting: the session token (hashed) once
+/// authentication has verified it, otherwise the client IP
+fn rate_limit_client(request: &Request) -> String {
+    if request.extensions().get::<AuthenticatedUser>().is_some() {
+        if let Some(token) = extract_session_token(request) {
+            let digest = ring::digest::digest(&ring::digest::SHA256, token.as_bytes());
+            return format!(
+                "token:{}",
+                data_encoding::HEXLOWER.encode(&digest.as_ref()[..16])
+            );
+        }
+    }
+    let peer = request
+        .extensions()
+        .get::<ConnectInfo<SocketAddr>>()
+        .map(|info| info.0)
+        .unwrap_or_else(|| SocketAddr::from(([0, 0, 0, 0], 0)));
+    format!("ip:{}", extract_client_ip(request.headers(), peer))
+}
+
+async fn enforce_rate_limit(
+    state: &AppState,
+    class: crate::services::RateLimitClass,
+    request: Request,
+    next: Next,
+) -> Response {
+    let client = rate_limit_client(&request);
+    match state.api_rate_limiter.check(class, &client).await {
+        crate::services::RateDecision::Allowed => next.run(request).await,
+        crate::services::RateDecision::Limited { retry_after_secs } => {
+            let mut response =
+                ApiError::new("RATE_LIMIT", "Too many requests, please slow down").into_response();
+            response
+                .headers_mut()
+                .insert(header::RETRY_AFTER, retry_after_secs.into());
+            response
+        }
+    }
+}
+
+/// Rate limit for the public API
+pub async fn rate_limit_api(
+    State(state): State<AppState>,
+    request: Request,
+    next: Next,
+) -> Response {
+    enforce_rate_limit(&state, crate::services::RateLimitClass::Api, request, next).await
+}
+
+/// Rate limit for comment submissions
+pub async fn rate_limit_comments(
+    State(state): State<AppState>,
+    request: Request,
+    next: Next,
+) -> Response {
+    enforce_rate_limit(
+        &state,
+        crate::services::RateLimitClass::Comments,
+        request,
+        next,
+    )
+    .await
+}
+
+/// Rate limit for uploads; runs after authentication so signed-in users
+/// are limited per session
+pub async fn rate_limit_uploads(
+    State(state): State<AppState>,
+    request: Request,
+    next: Next,
+) -> Response {
+    enforce_rate_limit(
+        &state,
+        crate::services::RateLimitClass::Uploads,
+        request,
+        next,
+    )
+    .await
+}
+
 /// Extract authenticated user from request extensions
 pub fn get_authenticated_user(request: &Request) -> Option<&User> {
     request
@@ -560,18 +832,22 @@ pub async fn demo_guard(request: Request, next: Next) -> Result<Response, ApiErr
     // Whitelist: endpoints that should work in demo mode
     // Principle: allow read-like and interactive features, block data mutation
     let whitelisted = [
-        "/api/v1/auth/login",    // Login
-        "/api/v1/auth/logout",   // Logout
-        "/api/v1/auth/register", // Register (let users try the flow)
-        "/api/v1/auth/2fa",      // 2FA verify (part of login flow)
-        "/api/v1/captcha/",      // Captcha challenge/verify for public comments
-        "/api/v1/comments",      // Post comments (demo interaction)
-        "/api/v1/like",          // Like/unlike (demo interaction)
-        "/api/v1/view/",         // View count increment (not real data)
-        "/api/v1/site/render",   // Markdown preview
-        "/api/v1/cache/",        // Frontend cache read/write
-        "/api/v1/plugins/proxy", // Plugin proxy (for plugin demos)
-        "/api/v1/plugins/",      // Plugin API routes (read-like)
+        "/api/v1/auth/login",             // Login
+        "/api/v1/auth/logout",            // Logout
+        "/api/v1/auth/register",          // Register (let users try the flow)
+        "/api/v1/auth/2fa",               // 2FA verify (part of login flow)
+        "/api/v1/captcha/",               // Captcha challenge/verify for public comments
+        "/api/v1/comments",               // Post comments (demo interaction)
+        "/api/v1/like",                   // Like/unlike (demo interaction)
+        "/api/v1/view/",                  // View count increment (not real data)
+        "/api/v1/site/render",            // Markdown preview
+        "/api/v1/site/convert",           // Markdown/blocks conversion
+        "/api/v1/admin/preview",          // Editor preview
+        "/api/v1/admin/spellcheck",       // Draft spell check (read-only)
+        "/api/v1/admin/link-suggestions", // Draft link suggestions (read-only)
+        "/api/v1/cache/",                 // Frontend cache read/write
+        "/api/v1/plugins/proxy",          // Plugin proxy (for plugin demos)
+        "/api/v1/plugins/",               // Plugin API routes (read-like)
     ];
 
     // Check if path is whitelisted
@@ -606,6 +882,190 @@ pub fn is_demo_mode() -> bool {
     cfg!(feature = "demo")
 }
 
+// ============================================================================
+// Maintenance Mode
+// ============================================================================
+
+/// Paths that keep working in maintenance mode: the admin panel and its API,
+/// login, health probes and the assets the maintenance page itself needs
+const MAINTENANCE_EXEMPT_PREFIXES: &[&str] = &[
+    "/manage",
+    "/api/v1/admin",
+    "/api/v1/auth/",
+    "/healthz",
+    "/readyz",
+    "/api/v1/status",
+    "/themes/",
+    "/_next/",
+    "/noteva-sdk.",
+];
+
+fn is_maintenance_exempt(path: &str) -> bool {
+    if MAINTENANCE_EXEMPT_PREFIXES
+        .iter()
+        .any(|prefix| path.starts_with(prefix))
+    {
+        return true;
+    }
+    // Root-level files such as /favicon.ico and /logo.png
+    let file = path.trim_start_matches('/');
+    file.contains('.') && !file.contains('/')
+}
+
+/// Maintenance mode middleware
+///
+/// While maintenance mode is on, public API routes return a JSON 503 and
+/// pages return the theme's `maintenance.html` (or a built-in page).
+/// Allowlisted addresses and logged-in admins are let through.
+pub async fn maintenance_guard(
+    State(state): State<AppState>,
+    request: Request,
+    next: Next,
+) -> Response {
+    let path = request.uri().path().to_string();
+    if is_maintenance_exempt(&path) {
+        return next.run(request).await;
+    }
+
+    let client_ip = request
+        .extensions()
+        .get::<ConnectInfo<SocketAddr>>()
+        .and_then(|ConnectInfo(addr)| extract_client_ip(request.headers(), *addr).parse().ok());
+    let Some(message) = state.maintenance.blocks(client_ip).await else {
+        return next.run(request).await;
+    };
+
+    if let Some(token) = extract_session_token(&request) {
+        if let Ok(Some(user)) = state.user_service.validate_session(&token).await {
+            if user.role == UserRole::Admin {
+                return next.run(request).await;
+            }
+        }
+    }
+
+    let retry_after = (header::RETRY_AFTER, "300");
+    if path.starts_with("/api/") {
+        let message = if message.is_empty() {
+            "The site is down for maintenance".to_string()
+        } else {
+            message
+        };
+        let mut response = ApiError::new("SERVICE_UNAVAILABLE", message).into_response();
+        response.headers_mut().insert(
+            retry_after.0,
+            header::HeaderValue::from_static(retry_after.1),
+        );
+        return response;
+    }
+
+    let html = render_maintenance_page(&state, &path, &message).await;
+    (
+        StatusCode::SERVICE_UNAVAILABLE,
+        [retry_after, (header::CACHE_CONTROL, "no-store")],
+        axum::response::Html(html),
+    )
+        .into_response()
+}
+
+/// The active theme's `maintenance.html`, or the built-in page
+async fn render_maintenance_page(state: &AppState, path: &str, message: &str) -> String {
+    let site = state
+        .settings_service
+        .get_site_settings()
+        .await
+        .unwrap_or_default();
+
+    if let Ok(engine) = state.theme_engine.read() {
+        if engine
+            .tera()
+            .get_template_names()
+            .any(|name| name == "maintenance.html")
+        {
+            let mut context = tera::Context::new();
+            context.insert("message", message);
+            let vars = crate::theme::StandardTemplateVars::new(
+                &site.site_name,
+                &site.site_description,
+                path,
+            );
+            match engine.render_with_standard_vars("maintenance.html", &context, &vars) {
+                Ok(html) => return html,
+                Err(e) => tracing::warn!("Failed to render maintenance.html: {}", e),
+            }
+        }
+    }
+
+    builtin_maintenance_page(&site.site_name, message)
+}
+
+fn builtin_maintenance_page(site_name: &str, message: &str) -> String {
+    let escape = |s: &str| {
+        s.replace('&', "&amp;")
+            .replace('<', "&lt;")
+            .replace('>', "&gt;")
+            .replace('"', "&quot;")
+    };
+    let message = if message.is_empty() {
+        "We are performing scheduled maintenance and will be back shortly."
+    } else {
+        message
+    };
+    format!(
+        r#"<!DOCTYPE html>
+<html>
+<head>
+    <meta charset="UTF-8">
+    <meta name="viewport" content="width=device-width, initial-scale=1.0">
+    <meta name="robots" content="noindex">
+    <title>{site} - Maintenance</title>
+    <style>
+        body {{
+            font-family: -apple-system, BlinkMacSystemFont, 'Segoe UI', Roboto, sans-serif;
+            max-width: 600px;
+            margin: 15vh auto;
+            padding: 20px;
+            color: #333;
+            text-align: center;
+        }}
+        h1 {{ font-weight: 500; }}
+        p {{ color: #666; line-height: 1.6; white-space: pre-line; }}
+    </style>
+</head>
+<body>
+    <h1>{site}</h1>
+    <p>{message}</p>
+</body>
+</html>"#,
+        site = escape(site_name),
+        message = escape(message),
+    )
+}
+
+// ============================================================================
+// Public Endpoint Exposure
+// ============================================================================
+
+/// Refuse public endpoints switched off in settings, and visitor writes
+/// while the public API is read-only
+pub async fn api_exposure_guard(
+    State(state): State<AppState>,
+    request: Request,
+    next: Next,
+) -> Result<Response, ApiError> {
+    use crate::services::api_exposure::Refusal;
+
+    let exposure = state.api_exposure.current().await;
+    match exposure.check(
+        request.method(),
+        request.uri().path(),
+        request.uri().query(),
+    ) {
+        None => Ok(next.run(request).await),
+        Some(Refusal::Disabled) => Err(ApiError::not_found("Not found")),
+        Some(Refusal::ReadOnly) => Err(ApiError::forbidden("The public API is read-only")),
+    }
+}
+
 // ============================================================================
 // CSRF Protection (Double Submit Cookie)
 // ============================================================================
@@ -658,11 +1118,20 @@ pub async fn csrf_protection(request: Request, next: Next) -> Result<Response, A
         "/api/v1/auth/login",
         "/api/v1/auth/register",
         "/api/v1/auth/has-admin",
-        "/api/v1/captcha/",      // public captcha challenge/verify
-        "/api/v1/comments",      // public comment posting (uses its own auth)
-        "/api/v1/like",          // public like
-        "/api/v1/view/",         // public view count
-        "/api/v1/plugins/proxy", // plugin proxy
+        "/api/v1/auth/passkeys/login/",
+        "/api/v1/auth/saml/",               // IdP posts the response cross-site
+        "/api/v1/captcha/",                 // public captcha challenge/verify
+        "/api/v1/comments",                 // public comment posting (uses its own auth)
+        "/api/v1/like",                     // public like
+        "/api/v1/view/",                    // public view count
+        "/api/v1/plugins/proxy",            // plugin proxy
+        "/webmention",                      // cross-site Webmention notifications
+        "/api/v1/newsletter/",              // newsletter signup and one-click unsubscribe
+        "/api/v1/attachments/",             // email-gated download forms
+        "/api/v1/push/",                    // Web Push subscriptions from service workers
+        "/api/v1/hooks/in/",                // third-party webhooks (token/signature auth)
+        "/api/v1/integrations/github/push", // GitHub webhook (signature auth)
+        "/api/v1/embed/",                   // comment widget on allowlisted external sites
     ];
 
     for exempt in &csrf_exempt {
@@ -746,9 +1215,11 @@ pub fn generate_weak_etag(content: &[u8]) -> String {
 pub fn etag_matches(request_etag: Option<&str>, response_etag: &str) -> bool {
     match request_etag {
         Some(etag) => {
-            let normalized_request = etag.trim_start_matches("W/");
+            // If-None-Match may list several tags or be `*`
             let normalized_response = response_etag.trim_start_matches("W/");
-            normalized_request == normalized_response
+            etag.split(',').map(str::trim).any(|candidate| {
+                candidate == "*" || candidate.trim_start_matches("W/") == normalized_response
+            })
         }
         None => false,
     }
@@ -840,20 +1311,109 @@ impl<T: Serialize> IntoResponse for CachedResponse<T> {
 
 /// Check If-None-Match header
 pub fn check_if_none_match(request: &Request, etag: &str) -> Option<Response> {
-    if let Some(if_none_match) = request.headers().get(header::IF_NONE_MATCH) {
-        if let Ok(if_none_match_str) = if_none_match.to_str() {
-            if etag_matches(Some(if_none_match_str), etag) {
-                return Some(
-                    Response::builder()
-                        .status(StatusCode::NOT_MODIFIED)
-                        .header(header::ETAG, etag)
-                        .body(axum::body::Body::empty())
-                        .unwrap(),
-                );
-            }
-        }
+    not_modified(request.headers(), etag)
+}
+
+/// 304 Not Modified response when the request headers carry a matching
+/// If-None-Match
+pub fn not_modified(headers: &HeaderMap, etag: &str) -> Option<Response> {
+    let if_none_match = headers.get(header::IF_NONE_MATCH)?.to_str().ok()?;
+    etag_matches(Some(if_none_match), etag).then(|| {
+        Response::builder()
+            .status(StatusCode::NOT_MODIFIED)
+            .header(header::ETAG, etag)
+            .body(axum::body::Body::empty())
+            .unwrap()
+    })
+}
+
+/// JSON response with a strong ETag over the serialized body, or 304 Not
+/// Modified when the client already has it
+///
+/// The body may depend on who is signed in, so both responses are marked
+/// private and vary on the credentials; shared caches never store them.
+pub fn conditional_json<T: Serialize>(headers: &HeaderMap, data: &T) -> Response {
+    let json = match serde_json::to_vec(data) {
+        Ok(json) => json,
+        Err(e) => return ApiError::internal_error(e.to_string()).into_response(),
+    };
+    let etag = generate_etag(&json);
+    let mut response = not_modified(headers, &etag).unwrap_or_else(|| {
+        Response::builder()
+            .status(StatusCode::OK)
+            .header(header::CONTENT_TYPE, "application/json")
+            .header(header::ETAG, etag)
+            .body(axum::body::Body::from(json))
+            .unwrap()
+    });
+    let headers = response.headers_mut();
+    headers.insert(
+        header::CACHE_CONTROL,
+        header::HeaderValue::from_str(&cache_control_private(0)).unwrap(),
+    );
+    headers.insert(
+        header::VARY,
+        header::HeaderValue::from_static("Authorization, Cookie"),
+    );
+    response
+}
+
+/// Strong validator of a stored resource, changing with every update
+pub fn version_etag(id: i64, updated_at: chrono::DateTime<chrono::Utc>) -> String {
+    format!("\"{}-{}\"", id, updated_at.timestamp_micros())
+}
+
+/// `ETag` and `Last-Modified` headers of a stored resource
+pub fn version_headers(
+    id: i64,
+    updated_at: chrono::DateTime<chrono::Utc>,
+) -> [(header::HeaderName, String); 2] {
+    [
+        (header::ETAG, version_etag(id, updated_at)),
+        (
+            header::LAST_MODIFIED,
+            updated_at.format("%a, %d %b %Y %H:%M:%S GMT").to_string(),
+        ),
+    ]
+}
+
+/// Evaluate `If-Match` and `If-Unmodified-Since` before changing a resource
+///
+/// `If-Match` takes precedence and only matches strong validators from
+/// [`version_etag`]; `If-Unmodified-Since` has second resolution and is
+/// ignored when it cannot be parsed (RFC 9110, section 13.2.2).
+pub fn check_write_preconditions(
+    headers: &HeaderMap,
+    id: i64,
+    updated_at: chrono::DateTime<chrono::Utc>,
+) -> Result<(), ApiError> {
+    if let Some(if_match) = headers.get(header::IF_MATCH) {
+        let current = version_etag(id, updated_at);
+        let matches = if_match.to_str().is_ok_and(|value| {
+            value
+                .split(',')
+                .map(str::trim)
+                .any(|tag| tag == "*" || tag == current)
+        });
+        return if matches {
+            Ok(())
+        } else {
+            Err(ApiError::precondition_failed(
+                "The resource was changed since it was loaded",
+            ))
+        };
+    }
+
+    let since = headers
+        .get(header::IF_UNMODIFIED_SINCE)
+        .and_then(|v| v.to_str().ok())
+        .and_then(|v| chrono::DateTime::parse_from_rfc2822(v).ok());
+    match since {
+        Some(since) if updated_at.timestamp() > since.timestamp() => Err(
+            ApiError::precondition_failed("The resource was changed since it was loaded"),
+        ),
+        _ => Ok(()),
     }
-    None
 }
 
 // ============================================================================
@@ -881,6 +1441,22 @@ mod tests {
             .unwrap()
     }
 
+    #[test]
+    fn maintenance_keeps_admin_and_assets_reachable() {
+        assert!(is_maintenance_exempt("/manage/articles"));
+        assert!(is_maintenance_exempt("/api/v1/admin/maintenance"));
+        assert!(is_maintenance_exempt("/api/v1/auth/login"));
+        assert!(is_maintenance_exempt("/favicon.ico"));
+        assert!(!is_maintenance_exempt("/"));
+        assert!(!is_maintenance_exempt("/posts/hello"));
+        assert!(!is_maintenance_exempt("/api/v1/articles"));
+        assert!(!is_maintenance_exempt("/uploads/a.png"));
+
+        let html = builtin_maintenance_page("<Blog>", "");
+        assert!(html.contains("&lt;Blog&gt;"));
+        assert!(html.contains("scheduled maintenance"));
+    }
+
     #[test]
     fn test_extract_session_token_from_bearer() {
         let request = create_request_with_auth("test-token-123");
@@ -1006,7 +1582,7 @@ mod property_tests {
                 password_hash: "hash".to_string(), role,
                 status: crate::models::UserStatus::Active,
                 display_name: None, avatar: None,
-                totp_secret: None, totp_enabled: false,
+                totp_secret: None, totp_enabled: false, password_reset_required: false,
                 created_at: chrono::Utc::now(), updated_at: chrono::Utc::now(),
             };
             prop_assert!(!user.is_admin());
@@ -1019,7 +1595,7 @@ mod property_tests {
                 password_hash: "hash".to_string(), role: UserRole::Admin,
                 status: crate::models::UserStatus::Active,
                 display_name: None, avatar: None,
-                totp_secret: None, totp_enabled: false,
+                totp_secret: None, totp_enabled: false, password_reset_required: false,
                 created_at: chrono::Utc::now(), updated_at: chrono::Utc::now(),
             };
             prop_assert!(user.is_admin());
@@ -1032,7 +1608,7 @@ mod property_tests {
                 password_hash: "hash".to_string(), role,
                 status: crate::models::UserStatus::Active,
                 display_name: None, avatar: None,
-                totp_secret: None, totp_enabled: false,
+                totp_secret: None, totp_enabled: false, password_reset_required: false,
                 created_at: chrono::Utc::now(), updated_at: chrono::Utc::now(),
             };
             let expected = matches!(role, UserRole::Admin | UserRole::Editor);
@@ -1046,7 +1622,7 @@ mod property_tests {
                 password_hash: "hash".to_string(), role: UserRole::Author,
                 status: crate::models::UserStatus::Active,
                 display_name: None, avatar: None,
-                totp_secret: None, totp_enabled: false,
+                totp_secret: None, totp_enabled: false, password_reset_required: false,
                 created_at: chrono::Utc::now(), updated_at: chrono::Utc::now(),
             };
             prop_assert_eq!(user.can_edit(content_author_id), user_id == content_author_id);
@@ -1060,7 +1636,7 @@ mod property_tests {
                 password_hash: "hash".to_string(), role,
                 status: crate::models::UserStatus::Active,
                 display_name: None, avatar: None,
-                totp_secret: None, totp_enabled: false,
+                totp_secret: None, totp_enabled: false, password_reset_required: false,
                 created_at: chrono::Utc::now(), updated_at: chrono::Utc::now(),
             };
             prop_assert!(user.can_edit(content_author_id));
@@ -1101,6 +1677,39 @@ mod cache_header_tests {
         assert!(etag_matches(Some("\"12345\""), "W/\"12345\""));
     }
 
+    #[test]
+    fn test_etag_matches_list_and_wildcard() {
+        assert!(etag_matches(Some("\"1\", W/\"12345\""), "\"12345\""));
+        assert!(!etag_matches(Some("\"1\", \"2\""), "\"12345\""));
+        assert!(etag_matches(Some("*"), "\"12345\""));
+    }
+
+    #[test]
+    fn test_conditional_json_returns_not_modified() {
+        let data = serde_json::json!({ "id": 1, "title": "Hello" });
+        let response = conditional_json(&HeaderMap::new(), &data);
+        assert_eq!(response.status(), StatusCode::OK);
+        let etag = response.headers()[header::ETAG].clone();
+        assert_eq!(
+            response.headers()[header::CACHE_CONTROL],
+            "private, max-age=0"
+        );
+        assert_eq!(response.headers()[header::VARY], "Authorization, Cookie");
+
+        let mut headers = HeaderMap::new();
+        headers.insert(header::IF_NONE_MATCH, etag.clone());
+        let response = conditional_json(&headers, &data);
+        assert_eq!(response.status(), StatusCode::NOT_MODIFIED);
+        assert_eq!(response.headers()[header::ETAG], etag);
+        assert_eq!(response.headers()[header::VARY], "Authorization, Cookie");
+
+        let changed = serde_json::json!({ "id": 1, "title": "Hello, world" });
+        assert_eq!(
+            conditional_json(&headers, &changed).status(),
+            StatusCode::OK
+        );
+    }
+
     #[test]
     fn test_etag_matches_none() {
         assert!(!etag_matches(None, "\"12345\""));
@@ -1148,6 +1757,39 @@ mod cache_header_tests {
         assert_eq!(config.static_max_age, 31536000);
         assert_eq!(config.api_max_age, 300);
     }
+
+    #[test]
+    fn test_write_preconditions() {
+        use chrono::TimeZone;
+        let updated_at = chrono::Utc.with_ymd_and_hms(2024, 5, 1, 12, 0, 30).unwrap();
+        let check = |name: header::HeaderName, value: &str| {
+            let mut headers = HeaderMap::new();
+            headers.insert(name, value.parse().unwrap());
+            check_write_preconditions(&headers, 7, updated_at).is_ok()
+        };
+
+        assert!(check_write_preconditions(&HeaderMap::new(), 7, updated_at).is_ok());
+        let etag = version_etag(7, updated_at);
+        assert!(check(header::IF_MATCH, &etag));
+        assert!(check(header::IF_MATCH, &format!("\"x\", {}", etag)));
+        assert!(check(header::IF_MATCH, "*"));
+        assert!(!check(header::IF_MATCH, &format!("W/{}", etag)));
+        assert!(!check(header::IF_MATCH, &version_etag(8, updated_at)));
+
+        assert!(check(
+            header::IF_UNMODIFIED_SINCE,
+            "Wed, 01 May 2024 12:00:30 GMT"
+        ));
+        assert!(check(
+            header::IF_UNMODIFIED_SINCE,
+            "Thu, 02 May 2024 00:00:00 GMT"
+        ));
+        assert!(!check(
+            header::IF_UNMODIFIED_SINCE,
+            "Wed, 01 May 2024 12:00:29 GMT"
+        ));
+        assert!(check(header::IF_UNMODIFIED_SINCE, "yesterday"));
+    }
 }
 
 #[cfg(test)]
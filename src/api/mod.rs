@@ -18,55 +18,99 @@
 pub mod about;
 pub mod admin;
 mod archive;
+pub mod article_authors;
 pub mod articles;
+pub mod attachments;
 pub mod auth;
+pub mod authors;
 pub mod cache;
 pub mod captcha;
 pub mod categories;
 pub mod comments;
 pub mod common;
+pub mod custom_fields;
+pub mod docs;
+pub mod email_webhook;
+pub mod embed;
+pub mod events;
+pub mod faq;
+pub mod favorites;
+pub mod featured;
 pub mod friend_links;
+mod github_push;
 mod github_update;
+pub mod health;
+pub mod hooks_in;
 pub mod middleware;
 pub mod nav;
+pub mod newsletter;
 pub mod pages;
+pub mod passkeys;
 pub mod plugin_install;
 pub mod plugins;
+pub mod polls;
 pub mod proxy;
+pub mod push;
+pub mod reader_groups;
+pub mod reading_progress;
+pub mod redirects;
+pub mod releases;
 pub mod responses;
+#[cfg(feature = "saml")]
+pub mod saml;
 pub mod seo;
+pub mod series;
 pub mod site;
 pub mod static_files;
+pub mod status;
+pub mod sync;
 pub mod tags;
 pub mod theme;
 pub mod theme_install;
+pub mod translations;
 pub mod two_factor;
 pub mod upload;
+pub mod webmention;
 
 use axum::{
     extract::DefaultBodyLimit,
-    http::{header, HeaderName, HeaderValue, Method},
+    http::{header, Extensions, HeaderMap, HeaderName, HeaderValue, Method},
     middleware as axum_middleware, Router,
 };
-use tower_http::cors::CorsLayer;
+use tower_http::compression::{predicate::SizeAbove, CompressionLayer, Predicate};
+use tower_http::cors::{AllowOrigin, CorsLayer};
+use tower_http::trace::TraceLayer;
 
 pub use middleware::{
     add_api_cache_headers, add_static_cache_headers, cache_control_api, cache_control_no_cache,
-    cache_control_private, cache_control_static, check_if_none_match, etag_matches, generate_etag,
-    generate_weak_etag, ApiError, AppState, CacheConfig, CachedResponse, RequestStats,
+    cache_control_private, cache_control_static, check_if_none_match, conditional_json,
+    etag_matches, generate_etag, generate_weak_etag, ApiError, AppState, CacheConfig,
+    CachedResponse, RequestStats,
 };
 
 /// Build the main API router
 pub fn build_api_router(state: AppState) -> Router<AppState> {
     // Read body limits from config (add 1MB headroom for multipart overhead)
     let image_body_limit = (state.upload_config.max_file_size as usize).saturating_add(1024 * 1024);
-    let admin_body_limit =
-        (state.upload_config.max_plugin_file_size as usize).saturating_add(1024 * 1024);
+    let admin_body_limit = (state
+        .upload_config
+        .max_plugin_file_size
+        .max(state.upload_config.max_attachment_size) as usize)
+        .saturating_add(1024 * 1024);
 
     // Admin routes (need admin role)
     let admin_routes = Router::new()
         .nest("/admin", admin::router())
         .nest("/admin/friend-links", friend_links::router())
+        .nest("/admin/redirects", redirects::router())
+        .nest("/admin/polls", polls::router())
+        .nest("/admin/events", events::router())
+        .nest("/admin/docs", docs::router())
+        .nest("/admin/faq", faq::router())
+        .nest("/admin/series", series::router())
+        .nest("/admin/featured", featured::router())
+        .nest("/admin/reader-groups", reader_groups::router())
+        .nest("/admin/translations", translations::router())
         .nest("/admin/pages", pages::router())
         .nest("/admin/nav", nav::router())
         .nest("/admin/plugins", plugins::router())
@@ -146,6 +190,41 @@ pub fn build_api_router(state: AppState) -> Router<AppState> {
             "/admin/articles/{id}",
             axum::routing::delete(articles::delete_article_handler),
         )
+        .route(
+            "/admin/articles/{id}/slugs",
+            axum::routing::get(articles::list_article_slugs_handler),
+        )
+        .route(
+            "/admin/articles/{id}/authors",
+            axum::routing::get(article_authors::list_authors).put(article_authors::set_authors),
+        )
+        .route(
+            "/admin/articles/{id}/audience",
+            axum::routing::get(reader_groups::get_audience).put(reader_groups::set_audience),
+        )
+        .route(
+            "/admin/articles/{id}/attachments",
+            axum::routing::get(attachments::list_attachments)
+                .post(attachments::upload_attachment)
+                .put(attachments::reorder_attachments),
+        )
+        .route(
+            "/admin/articles/{id}/attachments/{attachment_id}",
+            axum::routing::put(attachments::update_attachment)
+                .delete(attachments::delete_attachment),
+        )
+        .route(
+            "/admin/articles/{id}/attachments/{attachment_id}/downloads",
+            axum::routing::get(attachments::attachment_downloads),
+        )
+        .route(
+            "/admin/attachments/top",
+            axum::routing::get(attachments::top_attachments),
+        )
+        .nest(
+            "/admin/articles/{id}/fields",
+            custom_fields::router(crate::models::ContentKind::Article),
+        )
         // Admin comment operations
         .route(
             "/admin/comments/{id}",
@@ -162,14 +241,22 @@ pub fn build_api_router(state: AppState) -> Router<AppState> {
     let protected_routes = Router::new()
         .nest("/auth", auth::protected_router())
         .nest("/auth/2fa", two_factor::router())
+        .nest("/auth/passkeys", passkeys::router())
         .nest(
             "/upload",
-            upload::router().layer(DefaultBodyLimit::max(image_body_limit)),
+            upload::router()
+                .layer(DefaultBodyLimit::max(image_body_limit))
+                .route_layer(axum_middleware::from_fn_with_state(
+                    state.clone(),
+                    middleware::rate_limit_uploads,
+                )),
         )
         .route(
             "/articles",
             axum::routing::post(articles::create_article_handler),
         )
+        .nest("/reading-progress", reading_progress::router())
+        .nest("/favorites", favorites::router())
         .nest(
             "/cache",
             Router::new()
@@ -180,13 +267,18 @@ pub fn build_api_router(state: AppState) -> Router<AppState> {
             "/site/render",
             axum::routing::post(site::render_content_handler),
         )
+        .route(
+            "/site/convert",
+            axum::routing::post(site::convert_content_handler),
+        )
         .route_layer(axum_middleware::from_fn_with_state(
             state.clone(),
             middleware::require_auth,
         ));
 
-    // Public routes
-    Router::new()
+    // Public routes that show signed-in readers their private categories
+    // and the restricted articles they are in the audience of
+    let reader_routes = Router::new()
         .route(
             "/articles",
             axum::routing::get(articles::list_articles_handler),
@@ -195,20 +287,58 @@ pub fn build_api_router(state: AppState) -> Router<AppState> {
             "/articles/resolve",
             axum::routing::get(articles::resolve_article_handler),
         )
+        .route(
+            "/articles/{slug}",
+            axum::routing::get(articles::get_article_handler),
+        )
+        .nest("/categories", categories::router())
+        .nest("/authors", authors::router())
+        .route_layer(axum_middleware::from_fn_with_state(
+            state.clone(),
+            middleware::optional_auth,
+        ));
+
+    // Public routes
+    let router = Router::new()
+        .merge(reader_routes)
         .route(
             "/articles/archives",
             axum::routing::get(articles::get_archives),
         )
         .route(
-            "/articles/{slug}",
-            axum::routing::get(articles::get_article_handler),
+            "/articles/popular",
+            axum::routing::get(articles::get_popular_articles),
+        )
+        .route(
+            "/articles/featured",
+            axum::routing::get(articles::get_featured_articles),
+        )
+        .route("/archives", axum::routing::get(articles::get_archive_tree))
+        .route(
+            "/archives/{year}/{month}",
+            axum::routing::get(articles::list_archive_month),
         )
-        .nest("/categories", categories::router())
         .nest("/tags", tags::router())
         .nest("/auth", auth::public_router())
         .nest("/auth/2fa", two_factor::public_router())
+        .nest("/auth/passkeys", passkeys::public_router())
         .nest("/site", site::router())
         .nest("/about", about::public_router())
+        .nest("/users", favorites::public_router())
+        .nest("/polls", polls::public_router())
+        .nest("/events", events::public_router())
+        .nest("/releases", releases::public_router())
+        .nest("/docs", docs::public_router())
+        .nest("/faq", faq::public_router())
+        .nest("/series", series::public_router())
+        .nest("/translations", translations::public_router())
+        .nest(
+            "/attachments",
+            attachments::public_router().route_layer(axum_middleware::from_fn_with_state(
+                state.clone(),
+                middleware::optional_auth,
+            )),
+        )
         .route("/captcha/config", axum::routing::get(captcha::get_config))
         .route(
             "/captcha/challenge",
@@ -233,6 +363,26 @@ pub fn build_api_router(state: AppState) -> Router<AppState> {
         .nest("/page", pages::slug_router())
         .nest("/friend-links", friend_links::public_router())
         .nest("/nav", nav::public_router())
+        // Newsletter subscribe, confirm and unsubscribe
+        .nest("/newsletter", newsletter::router())
+        // Web Push subscriptions for new-article notifications
+        .nest("/push", push::router())
+        // Changes since a checkpoint, for offline clients
+        .route("/sync", axum::routing::get(sync::get_changes))
+        // Uptime and component health for visitors
+        .route("/status", axum::routing::get(status::get_status))
+        // Inbound webhooks for plugin integrations
+        .route("/hooks/in/{token}", axum::routing::post(hooks_in::receive))
+        // Publish Markdown articles from a GitHub content repository
+        .route(
+            "/integrations/github/push",
+            axum::routing::post(github_push::receive),
+        )
+        // Email provider bounce/complaint webhooks
+        .route(
+            "/email/webhook/{provider}",
+            axum::routing::post(email_webhook::receive),
+        )
         // Plugin assets (public)
         .route(
             "/plugins/assets/plugins.js",
@@ -266,52 +416,141 @@ pub fn build_api_router(state: AppState) -> Router<AppState> {
             "/comments/{article_id}",
             axum::routing::get(comments::get_comments),
         )
-        .route("/comments", axum::routing::post(comments::create_comment))
+        .route(
+            "/comments/{article_id}/count",
+            axum::routing::get(comments::get_comment_count),
+        )
+        .route(
+            "/comments/{article_id}/lazy",
+            axum::routing::get(comments::get_lazy_comments),
+        )
+        .route(
+            "/comments/{article_id}/lazy/{token}",
+            axum::routing::get(comments::get_lazy_comments_rest),
+        )
+        .route(
+            "/comments",
+            axum::routing::post(comments::create_comment).layer(
+                axum_middleware::from_fn_with_state(state.clone(), middleware::rate_limit_comments),
+            ),
+        )
+        // Cross-origin comment widget for allowlisted external sites
+        .route(
+            "/embed/comments/{slug}",
+            axum::routing::get(embed::get_thread),
+        )
+        .route(
+            "/embed/comments/{slug}",
+            axum::routing::post(embed::create_comment).layer(axum_middleware::from_fn_with_state(
+                state.clone(),
+                middleware::rate_limit_comments,
+            )),
+        )
         .route("/like", axum::routing::post(comments::like))
         .route("/like/check", axum::routing::get(comments::check_like))
         .route(
             "/view/{article_id}",
             axum::routing::post(comments::increment_view),
         )
+        // Applies to the public routes above only
+        .route_layer(axum_middleware::from_fn_with_state(
+            state.clone(),
+            middleware::rate_limit_api,
+        ))
         .merge(admin_routes)
-        .merge(protected_routes)
+        .merge(protected_routes);
+
+    // SAML single sign-on (public)
+    #[cfg(feature = "saml")]
+    let router = router.nest("/auth/saml", saml::router());
+
+    router
 }
 
 /// Build the complete router with middleware
-pub fn build_router(state: AppState, cors_origin: &str) -> Router {
-    // CORS configuration - 支持 cookie 认证
-    let cors_origin_header = if cors_origin.trim() == "*" {
-        tracing::warn!("cors_origin='*' is invalid with credentials; using http://localhost:3000");
-        HeaderValue::from_static("http://localhost:3000")
-    } else {
-        cors_origin.parse::<HeaderValue>().unwrap_or_else(|_| {
-            tracing::warn!(
-                cors_origin,
-                "invalid cors_origin in config; using http://localhost:3000"
-            );
-            HeaderValue::from_static("http://localhost:3000")
-        })
-    };
+pub fn build_router(
+    state: AppState,
+    cors_policy: crate::config::CorsPolicy,
+    compression: &crate::config::CompressionConfig,
+) -> Router {
+    // CORS - cookie authentication, so origins are mirrored rather than `*`.
+    // Configured origins may call every endpoint; sites allowlisted for
+    // comment embedding may only call the embed API
+    let embed_state = state.clone();
+    let allow_origin = AllowOrigin::async_predicate(
+        move |origin: HeaderValue, parts: &axum::http::request::Parts| {
+            let is_embed = parts.uri.path().starts_with(embed::EMBED_API_PREFIX);
+            let allowed = origin
+                .to_str()
+                .map(|o| cors_policy.allows(o))
+                .unwrap_or(false);
+            let state = embed_state.clone();
+            async move {
+                if allowed {
+                    return true;
+                }
+                match origin.to_str() {
+                    Ok(origin) if is_embed => embed::origin_allowed(&state, origin).await,
+                    _ => false,
+                }
+            }
+        },
+    );
 
     let cors = CorsLayer::new()
-        .allow_origin(cors_origin_header)
+        .allow_origin(allow_origin)
         .allow_methods([Method::GET, Method::POST, Method::PUT, Method::DELETE])
         .allow_headers([
             header::CONTENT_TYPE,
             header::AUTHORIZATION,
             header::COOKIE,
+            header::IF_MATCH,
+            header::IF_UNMODIFIED_SINCE,
             HeaderName::from_static("x-csrf-token"),
         ])
+        // Validators for conditional updates
+        .expose_headers([header::ETAG, header::LAST_MODIFIED])
         .allow_credentials(true);
 
-    Router::new()
+    // Response compression for API JSON, theme HTML and static text assets
+    let compression_config = compression.clone();
+    let compression = CompressionLayer::new()
+        .br(compression.uses("br"))
+        .zstd(compression.uses("zstd"))
+        .gzip(compression.uses("gzip"))
+        .deflate(compression.uses("deflate"))
+        .compress_when(SizeAbove::new(compression.min_size).and(
+            move |_status, _version, headers: &HeaderMap, _extensions: &Extensions| {
+                headers
+                    .get(header::CONTENT_TYPE)
+                    .and_then(|v| v.to_str().ok())
+                    .is_none_or(|ct| !compression_config.excludes(ct))
+            },
+        ));
+
+    let mut router = Router::new();
+    if state.status_page.html {
+        router = router.route("/status", axum::routing::get(status::status_page));
+    }
+
+    router
         .nest("/api/v1", build_api_router(state.clone()))
+        // Liveness/readiness probes for container orchestrators
+        .route("/healthz", axum::routing::get(health::healthz))
+        .route("/readyz", axum::routing::get(health::readyz))
         // SEO endpoints (top-level, before static file fallback)
         .route("/sitemap.xml", axum::routing::get(seo::sitemap_xml))
         .route("/robots.txt", axum::routing::get(seo::robots_txt))
         .route("/feed.xml", axum::routing::get(seo::feed_xml))
         .route("/rss.xml", axum::routing::get(seo::feed_xml))
         .route("/feed", axum::routing::get(seo::feed_xml))
+        .route("/events.ics", axum::routing::get(events::events_ics))
+        .route(
+            "/releases.json",
+            axum::routing::get(releases::releases_json),
+        )
+        // Webmention receiving endpoint (W3C spec, form-encoded POST)
+        .route("/webmention", axum::routing::post(webmention::receive))
         // Static file serving (for production)
         .fallback(static_files::serve_static)
         .layer(cors)
@@ -319,10 +558,37 @@ pub fn build_router(state: AppState, cors_origin: &str) -> Router {
         .layer(axum_middleware::from_fn(middleware::csrf_protection))
         // Demo mode guard (blocks write operations when compiled with --features demo)
         .layer(axum_middleware::from_fn(middleware::demo_guard))
+        // Maintenance mode (public routes return 503 while enabled)
+        .layer(axum_middleware::from_fn_with_state(
+            state.clone(),
+            middleware::maintenance_guard,
+        ))
+        // Public endpoints switched off in settings, read-only public API
+        .layer(axum_middleware::from_fn_with_state(
+            state.clone(),
+            middleware::api_exposure_guard,
+        ))
+        // Compression wraps the guards so their pages are compressed too
+        .layer(compression)
+        // Page view analytics (sees the final status and content type)
+        .layer(axum_middleware::from_fn_with_state(
+            state.clone(),
+            middleware::analytics_middleware,
+        ))
         // Request stats middleware (outermost layer, runs for all requests)
         .layer(axum_middleware::from_fn_with_state(
             state.clone(),
             middleware::request_stats_middleware,
         ))
+        // Request span, exported when OpenTelemetry is enabled
+        .layer(
+            TraceLayer::new_for_http()
+                .make_span_with(crate::telemetry::http_request_span)
+                .on_response(
+                    |response: &axum::response::Response, _latency, span: &tracing::Span| {
+                        crate::telemetry::record_response_status(response.status(), span)
+                    },
+                ),
+        )
         .with_state(state)
 }
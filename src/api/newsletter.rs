@@ -0,0 +1,130 @@
+//! Public newsletter endpoints
+//!
+//! - POST /api/v1/newsletter/subscribe - Start a subscription (JSON `email`)
+//! - GET /api/v1/newsletter/confirm?token= - Confirmation link from the email
+//! - GET|POST /api/v1/newsletter/unsubscribe?token= - Unsubscribe link and
+//!   RFC 8058 one-click unsubscribe from mail clients
+//!
+//! The links answer with a small HTML page since they are opened from email.
+
+use axum::{
+    extract::{Query, State},
+    http::StatusCode,
+    response::Html,
+    routing::{get, post},
+    Json, Router,
+};
+use serde::Deserialize;
+
+use crate::api::middleware::{ApiError, AppState};
+use crate::services::NewsletterError;
+
+/// Build the public newsletter router
+pub fn router() -> Router<AppState> {
+    Router::new()
+        .route("/subscribe", post(subscribe))
+        .route("/confirm", get(confirm))
+        .route("/unsubscribe", get(unsubscribe).post(unsubscribe))
+}
+
+/// Request body for subscribing
+#[derive(Debug, Deserialize)]
+pub struct SubscribeRequest {
+    pub email: String,
+}
+
+/// Token of a confirmation or unsubscribe link
+#[derive(Debug, Deserialize)]
+pub struct TokenQuery {
+    #[serde(default)]
+    pub token: String,
+}
+
+/// POST /api/v1/newsletter/subscribe - Subscribe an address
+///
+/// Answers `202 Accepted` whether or not the address was already
+/// subscribed; a confirmation email is queued when needed.
+pub async fn subscribe(
+    State(state): State<AppState>,
+    Json(body): Json<SubscribeRequest>,
+) -> Result<StatusCode, ApiError> {
+    state
+        .newsletter_service
+        .subscribe(&body.email)
+        .await
+        .map_err(|e| match e {
+            NewsletterError::Disabled => ApiError::not_found("Newsletter is not enabled"),
+            NewsletterError::InvalidEmail => ApiError::validation_error(e.to_string()),
+            e => ApiError::internal_error(e.to_string()),
+        })?;
+    Ok(StatusCode::ACCEPTED)
+}
+
+/// GET /api/v1/newsletter/confirm - Confirm a subscription
+pub async fn confirm(
+    State(state): State<AppState>,
+    Query(query): Query<TokenQuery>,
+) -> (StatusCode, Html<String>) {
+    let result = state.newsletter_service.confirm(&query.token).await;
+    link_page(
+        &state,
+        result.map(|_| "订阅已确认，新文章发布后会发送到你的邮箱。"),
+    )
+    .await
+}
+
+/// GET|POST /api/v1/newsletter/unsubscribe - Unsubscribe
+pub async fn unsubscribe(
+    State(state): State<AppState>,
+    Query(query): Query<TokenQuery>,
+) -> (StatusCode, Html<String>) {
+    let result = state.newsletter_service.unsubscribe(&query.token).await;
+    link_page(
+        &state,
+        result.map(|_| "已退订，你将不会再收到新文章的邮件。"),
+    )
+    .await
+}
+
+/// Page shown after following a link from a newsletter email
+async fn link_page(
+    state: &AppState,
+    result: Result<&str, NewsletterError>,
+) -> (StatusCode, Html<String>) {
+    let (status, message) = match result {
+        Ok(message) => (StatusCode::OK, message),
+        Err(NewsletterError::InvalidToken) => (StatusCode::NOT_FOUND, "链接无效或已过期。"),
+        Err(e) => {
+            tracing::warn!(error = %e, "newsletter link failed");
+            (StatusCode::INTERNAL_SERVER_ERROR, "处理失败，请稍后再试。")
+        }
+    };
+    let site_name = state
+        .settings_service
+        .get_site_settings()
+        .await
+        .map(|site| site.site_name)
+        .unwrap_or_default();
+    let site_name = site_name
+        .replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;");
+    let html = format!(
+        r#"<!DOCTYPE html>
+<html>
+<head>
+<meta charset="utf-8">
+<meta name="viewport" content="width=device-width, initial-scale=1">
+<meta name="robots" content="noindex">
+<title>{site_name}</title>
+</head>
+<body style="margin:0;padding:64px 16px;font-family:-apple-system,BlinkMacSystemFont,'Segoe UI',Roboto,'PingFang SC','Microsoft YaHei',sans-serif;color:#18181b;text-align:center;">
+<h1 style="font-size:20px;margin:0 0 16px;">{site_name}</h1>
+<p style="margin:0 0 24px;">{message}</p>
+<p><a href="/" style="color:#2563eb;">返回首页</a></p>
+</body>
+</html>"#
+    );
+    (status, Html(html))
+}
@@ -340,6 +340,8 @@
       name: category.name || category.title || '',
       description: category.description || '',
       parentId: firstValue(category.parentId, category.parent_id, null),
+      // 阅读权限：public / members / editors / admins
+      access: category.access || 'public',
       articleCount: asNumber(firstValue(category.articleCount, category.article_count), 0),
       createdAt: firstValue(category.createdAt, category.created_at, ''),
       updatedAt: firstValue(category.updatedAt, category.updated_at, ''),
@@ -376,6 +378,51 @@
     };
   };
 
+  // 语言版本（含当前文章/页面），可用于 hreflang 和语言切换
+  const normalizeTranslations = (list) => asArray(list).map(entry => ({
+    lang: entry.lang || '',
+    id: asNumber(entry.id),
+    slug: entry.slug || '',
+    title: entry.title || '',
+    url: entry.url || '',
+  }));
+
+  // 文章所属系列及上一篇/下一篇；不在系列中时为 null
+  const normalizeArticleSeries = (series) => series ? {
+    id: asNumber(series.id),
+    slug: series.slug || '',
+    title: series.title || '',
+    index: asNumber(series.index, 0),
+    total: asNumber(series.total, 0),
+    prev: normalizeArticleLink(series.prev),
+    next: normalizeArticleLink(series.next),
+  } : null;
+
+  // 文章署名（作者、编辑、译者），按署名顺序
+  const normalizeArticleAuthors = (list) => asArray(list).map(item => ({
+    userId: asNumber(firstValue(item.userId, item.user_id)),
+    username: item.username || '',
+    displayName: firstValue(item.displayName, item.display_name, item.username, ''),
+    avatar: item.avatar || '',
+    role: item.role || 'author',
+  }));
+
+  // 文章附件，按后台排列顺序；access 为 public / login / email
+  const normalizeAttachments = (list) => asArray(list).map(item => ({
+    id: asNumber(item.id),
+    filename: item.filename || '',
+    url: item.url || '',
+    contentType: firstValue(item.contentType, item.content_type, ''),
+    size: asNumber(item.size, 0),
+    access: item.access || 'public',
+    downloadCount: asNumber(firstValue(item.downloadCount, item.download_count), 0),
+  }));
+
+  // 自定义字段：键到值的映射，值已按字段类型解析（数字、布尔、JSON 等）
+  const normalizeCustomFields = (fields) => (
+    fields && typeof fields === 'object' && !Array.isArray(fields) ? { ...fields } : {}
+  );
+
   const normalizeArticle = (article) => {
     if (!article) return null;
     const content = firstValue(article.content, '');
@@ -387,7 +434,8 @@
       extractThumbnail(html || content)
     );
     const metaSummary = article.meta && typeof article.meta === 'object' ? article.meta.summary : undefined;
-    const excerpt = firstValue(article.summary, article.excerpt, metaSummary, stripMarkup(content || html).slice(0, 200));
+    // 后端的 excerpt 已按 作者摘要 → summary → 正文开头 生成
+    const excerpt = firstValue(article.excerpt, article.summary, metaSummary, stripMarkup(content || html).slice(0, 200));
     return {
       id: asNumber(article.id),
       slug: article.slug || String(article.id || ''),
@@ -402,6 +450,7 @@
       categoryId: firstValue(article.categoryId, article.category_id, null),
       status: article.status || '',
       author: normalizeSimpleUser(article.author),
+      authors: normalizeArticleAuthors(article.authors),
       category: normalizeCategory(article.category),
       tags: asArray(article.tags).map(normalizeTag).filter(Boolean),
       createdAt: firstValue(article.createdAt, article.created_at, ''),
@@ -410,8 +459,10 @@
       scheduledAt: firstValue(article.scheduledAt, article.scheduled_at, null),
       viewCount: asNumber(firstValue(article.viewCount, article.view_count), 0),
       likeCount: asNumber(firstValue(article.likeCount, article.like_count), 0),
+      favoriteCount: asNumber(firstValue(article.favoriteCount, article.favorite_count), 0),
       commentCount: asNumber(firstValue(article.commentCount, article.comment_count), 0),
       wordCount: asNumber(firstValue(article.wordCount, article.word_count), 0),
+      charCount: asNumber(firstValue(article.charCount, article.char_count), 0),
       readingTime: asNumber(firstValue(article.readingTime, article.reading_time), 0),
       isPinned: asBoolean(firstValue(article.isPinned, article.is_pinned), false),
       pinOrder: asNumber(firstValue(article.pinOrder, article.pin_order), 0),
@@ -421,6 +472,34 @@
       toc: asArray(article.toc),
       meta: firstValue(article.meta, null),
       canonicalUrl: firstValue(article.canonicalUrl, article.canonical_url, null),
+      seo: normalizeArticleSeo(article.seo),
+      translations: normalizeTranslations(article.translations),
+      series: normalizeArticleSeries(article.series),
+      attachments: normalizeAttachments(article.attachments),
+      customFields: normalizeCustomFields(firstValue(article.customFields, article.custom_fields)),
+      license: normalizeLicense(article.license),
+    };
+  };
+
+  // 文章的许可协议；inherited 为 true 时表示沿用站点默认协议
+  const normalizeLicense = (license) => {
+    if (!license || typeof license !== 'object') return null;
+    return {
+      kind: license.kind || 'all-rights-reserved',
+      name: license.name || '',
+      url: firstValue(license.url, null),
+      inherited: asBoolean(license.inherited, false),
+    };
+  };
+
+  // 作者设置的 SEO 覆盖项；未设置的字段为 null，主题应回退到标题、摘要和固定链接
+  const normalizeArticleSeo = (seo = {}) => {
+    const value = seo && typeof seo === 'object' ? seo : {};
+    return {
+      metaTitle: firstValue(value.metaTitle, value.meta_title, null),
+      metaDescription: firstValue(value.metaDescription, value.meta_description, null),
+      canonicalUrl: firstValue(value.canonicalUrl, value.canonical_url, null),
+      noindex: asBoolean(value.noindex, false),
     };
   };
 
@@ -451,6 +530,7 @@
       source: page.source || '',
       createdAt: firstValue(page.createdAt, page.created_at, ''),
       updatedAt: firstValue(page.updatedAt, page.updated_at, ''),
+      customFields: normalizeCustomFields(firstValue(page.customFields, page.custom_fields)),
     };
   };
 
@@ -551,6 +631,15 @@
     likeCount: asNumber(firstValue(result.likeCount, result.like_count), 0),
   });
 
+  const normalizeAnnouncement = (item = {}) => ({
+    id: String(item.id || ''),
+    message: item.message || '',
+    style: item.style || 'info',
+    startsAt: firstValue(item.startsAt, item.starts_at, null),
+    endsAt: firstValue(item.endsAt, item.ends_at, null),
+    dismissible: asBoolean(item.dismissible, true),
+  });
+
   const normalizeSiteInfo = (data = {}) => ({
     version: data.version || '',
     name: firstValue(data.name, data.site_name, 'Noteva'),
@@ -570,6 +659,7 @@
     showComments: asBoolean(firstValue(data.showComments, data.show_comments), true),
     friendLinksNavEnabled: asBoolean(firstValue(data.friendLinksNavEnabled, data.friend_links_nav_enabled), true),
     aboutNavEnabled: asBoolean(firstValue(data.aboutNavEnabled, data.about_nav_enabled), false),
+    announcements: asArray(data.announcements).map(normalizeAnnouncement),
     stats: {
       totalArticles: asNumber(firstValue(data.stats?.totalArticles, data.stats?.total_articles), 0),
       totalCategories: asNumber(firstValue(data.stats?.totalCategories, data.stats?.total_categories), 0),
@@ -660,6 +750,22 @@
       this._nav = null;
       return this.getInfo();
     },
+
+    // 当前生效的公告，已被访客关闭的不再返回
+    async announcements() {
+      const info = await this.getInfo();
+      const dismissed = asArray(storage.get('dismissed_announcements', []));
+      return info.announcements.filter(item => !(item.dismissible && dismissed.includes(item.id)));
+    },
+
+    dismissAnnouncement(id) {
+      const dismissed = asArray(storage.get('dismissed_announcements', []));
+      if (!dismissed.includes(id)) {
+        // 只保留最近的记录，避免无限增长
+        storage.set('dismissed_announcements', [...dismissed, id].slice(-50));
+      }
+      events.emit('announcement:dismiss', { id });
+    },
   };
 
   const urls = {
@@ -759,6 +865,9 @@
       if (params.tag) queryParams.tag = params.tag;
       if (params.keyword) queryParams.keyword = params.keyword;
       if (params.sort) queryParams.sort = params.sort;
+      if (params.lang) queryParams.lang = params.lang;
+      if (params.author) queryParams.author = params.author;
+      if (params.authorRole) queryParams.author_role = params.authorRole;
 
       return normalizeArticleList(await api.get('/articles', queryParams));
     },
@@ -795,11 +904,57 @@
       return asArray(article?.related).slice(0, params.limit || 5);
     },
 
+    // 热门文章：window 为 24h / 7d / 30d / all，默认 7d
+    async popular(params = {}) {
+      const result = await api.get('/articles/popular', {
+        window: params.window,
+        limit: params.limit,
+      });
+      return asArray(result.articles).map(article => ({
+        id: article.id,
+        slug: article.slug,
+        title: article.title,
+        thumbnail: article.thumbnail || null,
+        publishedAt: article.published_at || null,
+        views: asNumber(article.views, 0),
+      }));
+    },
+
+    // 精选文章：后台手动挑选并排序，已过期的不会返回
+    async featured(params = {}) {
+      const result = await api.get('/articles/featured', { limit: params.limit });
+      return asArray(result.articles).map(normalizeArticle).filter(Boolean);
+    },
+
     async archives() {
       const result = await api.get('/articles/archives');
       return asArray(result).map(normalizeArchiveEntry);
     },
 
+    // 按年、月分组的归档树，新的在前
+    async archiveTree() {
+      const result = await api.get('/archives');
+      return {
+        total: asNumber(result.total, 0),
+        years: asArray(result.years).map(year => ({
+          year: asNumber(year.year, 0),
+          count: asNumber(year.count, 0),
+          months: asArray(year.months).map(month => ({
+            month: asNumber(month.month, 0),
+            count: asNumber(month.count, 0),
+          })),
+        })),
+      };
+    },
+
+    // 某年某月已发布的文章，按时间从早到晚排列
+    async archiveMonth(year, month, params = {}) {
+      return normalizeArticleList(await api.get(`/archives/${year}/${month}`, {
+        page: params.page || 1,
+        page_size: params.pageSize || 10,
+      }));
+    },
+
     /**
      * 增加文章浏览计数
      * @param {number} articleId - 文章 ID
@@ -860,14 +1015,19 @@
   // 页面 API
   // ============================================
   const pages = {
-    async list() {
-      const result = await api.get('/pages');
+    // params.lang 只返回该语言的页面
+    async list(params = {}) {
+      const result = await api.get('/pages', { lang: params.lang });
       return asArray(result.pages).map(normalizePage).filter(Boolean);
     },
 
     async get(slug) {
       const result = await api.get(`/page/${slug}`);
       const customPage = normalizePage(result.page || result);
+      if (customPage) {
+        customPage.translations = normalizeTranslations(result.translations);
+        customPage.customFields = normalizeCustomFields(result.custom_fields);
+      }
       page.set({
         type: 'page',
         articleId: null,
@@ -932,13 +1092,71 @@
   // 评论 API
   // ============================================
   const comments = {
-    async list(articleId) {
-      const result = await api.get(`/comments/${articleId}`);
+    async list(articleId, params = {}) {
+      const result = await api.get(`/comments/${articleId}`, {
+        page: params.page,
+        per_page: params.perPage,
+      });
       const commentList = asArray(result.comments || result).map(normalizeComment).filter(Boolean);
       // 触发评论显示前钩子
       return hooks.trigger('comment_before_display', commentList);
     },
 
+    // 分页读取（按顶层评论分页），附带总数
+    async paginate(articleId, params = {}) {
+      const result = await api.get(`/comments/${articleId}`, {
+        page: params.page || 1,
+        per_page: params.perPage || 20,
+      });
+      const commentList = asArray(result.comments).map(normalizeComment).filter(Boolean);
+      const meta = result.meta || {};
+      return {
+        comments: hooks.trigger('comment_before_display', commentList),
+        page: asNumber(meta.page, 1),
+        perPage: asNumber(meta.per_page, commentList.length),
+        totalPages: asNumber(meta.total_pages, 1),
+        threads: asNumber(meta.threads, commentList.length),
+        total: asNumber(meta.total, 0),
+        approved: asNumber(meta.approved, 0),
+        pending: asNumber(meta.pending, 0),
+      };
+    },
+
+    // 评论数（不含垃圾评论），无需加载评论列表
+    async count(articleId) {
+      const result = await api.get(`/comments/${articleId}/count`);
+      return {
+        total: asNumber(result.total, 0),
+        approved: asNumber(result.approved, 0),
+        pending: asNumber(result.pending, 0),
+      };
+    },
+
+    // 懒加载：先取前几条顶层评论（不含回复）和一个短期令牌，
+    // 需要时再用 loadRest 取完整评论
+    async lazy(articleId, params = {}) {
+      const result = await api.get(`/comments/${articleId}/lazy`, {
+        limit: params.limit,
+      });
+      const commentList = asArray(result.comments).map(normalizeComment).filter(Boolean);
+      return {
+        comments: hooks.trigger('comment_before_display', commentList),
+        hasMore: Boolean(result.has_more),
+        token: result.token,
+        expiresAt: result.expires_at,
+        total: asNumber(result.total, 0),
+        approved: asNumber(result.approved, 0),
+        pending: asNumber(result.pending, 0),
+      };
+    },
+
+    // 用 lazy 返回的令牌读取完整评论，替换预览
+    async loadRest(articleId, token) {
+      const result = await api.get(`/comments/${articleId}/lazy/${encodeURIComponent(token)}`);
+      const commentList = asArray(result.comments).map(normalizeComment).filter(Boolean);
+      return hooks.trigger('comment_before_display', commentList);
+    },
+
     async create(data) {
       // 触发评论创建前钩子
       const processedData = hooks.trigger('comment_before_create', data);
@@ -2021,6 +2239,394 @@
     },
   };
 
+  // ============================================
+  // 阅读进度 API（需登录）
+  // ============================================
+  const normalizeReadingProgress = (entry) => entry ? {
+    articleId: entry.article_id,
+    slug: entry.slug,
+    title: entry.title,
+    position: asNumber(entry.position, 0),
+    updatedAt: entry.updated_at,
+  } : null;
+
+  const readingProgress = {
+    // 最近阅读的文章，最新的在前
+    async list(params = {}) {
+      const result = await api.get('/reading-progress', { limit: params.limit });
+      return asArray(result).map(normalizeReadingProgress).filter(Boolean);
+    },
+
+    // 某篇文章的阅读位置，没有记录时返回 null
+    async get(articleId) {
+      try {
+        return normalizeReadingProgress(await api.get(`/reading-progress/${articleId}`));
+      } catch (error) {
+        if (error?.status === 404) return null;
+        throw error;
+      }
+    },
+
+    // position 为 0 到 1 之间的滚动比例
+    async save(articleId, position) {
+      const clamped = Math.min(1, Math.max(0, asNumber(position, 0)));
+      return normalizeReadingProgress(
+        await api.put(`/reading-progress/${articleId}`, { position: clamped })
+      );
+    },
+
+    async remove(articleId) {
+      await api.delete(`/reading-progress/${articleId}`);
+    },
+  };
+
+  // ============================================
+  // 收藏 API（需登录，公开列表除外）
+  // ============================================
+  const normalizeFavorite = (entry) => entry ? {
+    articleId: entry.article_id,
+    slug: entry.slug,
+    title: entry.title,
+    thumbnail: firstValue(entry.thumbnail, null),
+    publishedAt: firstValue(entry.published_at, null),
+    favoriteCount: asNumber(entry.favorite_count, 0),
+    favoritedAt: entry.favorited_at,
+  } : null;
+
+  const normalizeFavoriteList = (result = {}) => {
+    const page = asNumber(result.page, 1);
+    const pageSize = asNumber(result.page_size, 10);
+    const total = asNumber(result.total, 0);
+    return {
+      favorites: asArray(result.favorites).map(normalizeFavorite).filter(Boolean),
+      total,
+      page,
+      pageSize,
+      totalPages: asNumber(result.total_pages, 0),
+      hasMore: page * pageSize < total,
+    };
+  };
+
+  const normalizeFavoriteStatus = (result = {}) => ({
+    favorited: asBoolean(result.favorited, false),
+    favoriteCount: asNumber(result.favorite_count, 0),
+  });
+
+  const favorites = {
+    // 当前用户的收藏，最近收藏的在前
+    async list(params = {}) {
+      const result = await api.get('/favorites', {
+        page: params.page || 1,
+        page_size: params.pageSize || 10,
+      });
+      return normalizeFavoriteList(result);
+    },
+
+    async check(articleId) {
+      return normalizeFavoriteStatus(await api.get(`/favorites/${articleId}`));
+    },
+
+    // 重复收藏不会报错
+    async add(articleId) {
+      return normalizeFavoriteStatus(await api.put(`/favorites/${articleId}`));
+    },
+
+    async remove(articleId) {
+      return normalizeFavoriteStatus(await api.delete(`/favorites/${articleId}`));
+    },
+
+    // 收藏列表是否公开
+    async settings() {
+      const result = await api.get('/favorites/settings');
+      return { public: asBoolean(result?.public, false) };
+    },
+
+    async setPublic(isPublic) {
+      const result = await api.put('/favorites/settings', { public: !!isPublic });
+      return { public: asBoolean(result?.public, false) };
+    },
+
+    // 其他用户公开的收藏，未公开时返回 null
+    async ofUser(username, params = {}) {
+      try {
+        const result = await api.get(`/users/${encodeURIComponent(username)}/favorites`, {
+          page: params.page || 1,
+          page_size: params.pageSize || 10,
+        });
+        return {
+          user: {
+            username: result.user?.username || username,
+            displayName: firstValue(result.user?.display_name, null),
+            avatar: firstValue(result.user?.avatar, null),
+          },
+          ...normalizeFavoriteList(result),
+        };
+      } catch (error) {
+        if (error?.status === 404) return null;
+        throw error;
+      }
+    },
+  };
+
+  // ============================================
+  // 作者 API
+  // ============================================
+  const normalizePublicUser = (user) => user ? {
+    id: asNumber(user.id),
+    username: user.username || '',
+    displayName: firstValue(user.display_name, null),
+    avatar: firstValue(user.avatar, null),
+    createdAt: firstValue(user.created_at, null),
+  } : null;
+
+  const authors = {
+    // 有已发布文章的作者，文章多的在前
+    async list() {
+      const result = await api.get('/authors');
+      return asArray(result.authors).map(author => ({
+        ...normalizePublicUser(author),
+        articleCount: asNumber(author.article_count, 0),
+      }));
+    },
+
+    // 作者资料及其文章，作者不存在或没有文章时返回 null
+    async get(username, params = {}) {
+      const queryParams = {
+        page: params.page || 1,
+        page_size: params.pageSize || 10,
+      };
+      if (params.sort) queryParams.sort = params.sort;
+      try {
+        const result = await api.get(`/authors/${encodeURIComponent(username)}`, queryParams);
+        return {
+          author: normalizePublicUser(result.author),
+          ...normalizeArticleList(result),
+        };
+      } catch (error) {
+        if (error?.status === 404) return null;
+        throw error;
+      }
+    },
+  };
+
+  // ============================================
+  // 投票 API（[poll id=N] 短代码）
+  // ============================================
+  const normalizePoll = (poll) => poll ? {
+    id: poll.id,
+    question: poll.question || '',
+    multiple: asBoolean(poll.multiple, false),
+    closesAt: firstValue(poll.closes_at, null),
+    options: asArray(poll.options).map(option => ({
+      id: option.id,
+      label: option.label || '',
+      votes: asNumber(option.votes, 0),
+    })),
+    totalVoters: asNumber(poll.total_voters, 0),
+  } : null;
+
+  const normalizePollView = (result = {}) => ({
+    poll: normalizePoll(result.poll),
+    voted: asArray(result.voted).map(id => asNumber(id, 0)),
+    html: result.html || '',
+  });
+
+  const polls = {
+    // 当前访客看到的投票，voted 为已选选项
+    async get(pollId) {
+      return normalizePollView(await api.get(`/polls/${pollId}`));
+    },
+
+    // 每位访客只能投一次，重复投票返回已有结果
+    async vote(pollId, optionIds) {
+      const ids = asArray(optionIds).map(id => asNumber(id, 0));
+      return normalizePollView(await api.post(`/polls/${pollId}/vote`, { option_ids: ids }));
+    },
+  };
+
+  // ============================================
+  // 活动日程 API（Noteva.events 是事件总线，这里叫 calendar）
+  // ============================================
+  const normalizeEventOccurrence = (entry) => entry ? {
+    eventId: entry.event_id,
+    title: entry.title || '',
+    description: entry.description || '',
+    location: firstValue(entry.location, null),
+    url: firstValue(entry.url, null),
+    startsAt: entry.starts_at,
+    endsAt: entry.ends_at,
+    allDay: asBoolean(entry.all_day, false),
+    recurring: asBoolean(entry.recurring, false),
+  } : null;
+
+  const calendar = {
+    // 即将开始和正在进行的活动，重复活动按每次发生展开
+    async upcoming(params = {}) {
+      const result = await api.get('/events/upcoming', { limit: params.limit || 20 });
+      return asArray(result?.occurrences).map(normalizeEventOccurrence).filter(Boolean);
+    },
+
+    // iCal 订阅地址，可用于“添加到日历”链接
+    feedUrl() {
+      return '/events.ics';
+    },
+  };
+
+  // ============================================
+  // 版本发布 API
+  // ============================================
+  const normalizeRelease = (entry) => entry ? {
+    version: entry.version || '',
+    prerelease: asBoolean(entry.prerelease, false),
+    articleId: entry.article_id,
+    title: entry.title || '',
+    slug: entry.slug || '',
+    url: entry.url || '',
+    publishedAt: entry.published_at,
+  } : null;
+
+  const releases = {
+    // 带发布标签的文章，按次版本号分组，版本从新到旧
+    async list() {
+      const result = await api.get('/releases');
+      return asArray(result?.groups).map(group => ({
+        series: group.series || '',
+        latest: group.latest || '',
+        releases: asArray(group.releases).map(normalizeRelease).filter(Boolean),
+      }));
+    },
+
+    // 机器可读的版本列表地址
+    feedUrl() {
+      return '/releases.json';
+    },
+  };
+
+  // ============================================
+  // 文档 API（按版本组织的文档树）
+  // ============================================
+  const normalizeDocVersion = (entry) => entry ? {
+    id: entry.id,
+    slug: entry.slug || '',
+    title: entry.title || '',
+    isLatest: asBoolean(entry.is_latest, false),
+  } : null;
+
+  const normalizeDocNode = (node) => node ? {
+    id: node.id,
+    slug: node.slug || '',
+    title: node.title || '',
+    url: node.url || '',
+    children: asArray(node.children).map(normalizeDocNode).filter(Boolean),
+  } : null;
+
+  const normalizeDocLink = (link) => link ? {
+    slug: link.slug || '',
+    title: link.title || '',
+    url: link.url || '',
+  } : null;
+
+  const normalizeDocView = (view) => view ? {
+    version: normalizeDocVersion(view.version),
+    versions: asArray(view.versions).map(entry => ({
+      slug: entry.slug || '',
+      title: entry.title || '',
+      isLatest: asBoolean(entry.is_latest, false),
+      current: asBoolean(entry.current, false),
+      url: entry.url || '',
+    })),
+    sidebar: asArray(view.sidebar).map(normalizeDocNode).filter(Boolean),
+    page: view.page ? {
+      id: view.page.id,
+      parentId: firstValue(view.page.parent_id, null),
+      slug: view.page.slug || '',
+      title: view.page.title || '',
+      html: view.page.content_html || '',
+      updatedAt: view.page.updated_at,
+    } : null,
+    prev: normalizeDocLink(view.prev),
+    next: normalizeDocLink(view.next),
+    canonical: view.canonical || '',
+  } : null;
+
+  const docs = {
+    // 所有文档版本，按后台排序
+    async versions() {
+      const result = await api.get('/docs');
+      return asArray(result?.versions).map(normalizeDocVersion).filter(Boolean);
+    },
+
+    // 文档页及其侧边栏、上一页/下一页、版本切换和 canonical 地址
+    // version 可以是 'latest'；不传 slug 时返回该版本的第一页
+    async get(version = 'latest', slug = '') {
+      const path = slug
+        ? `/docs/${encodeURIComponent(version)}/${encodeURIComponent(slug)}`
+        : `/docs/${encodeURIComponent(version)}`;
+      return normalizeDocView(await api.get(path));
+    },
+  };
+
+  // ============================================
+  // 常见问题 API
+  // ============================================
+  const normalizeFaqTopic = (topic) => topic ? {
+    id: topic.id,
+    slug: topic.slug || '',
+    title: topic.title || '',
+    description: topic.description || '',
+    items: asArray(topic.items).map(item => ({
+      id: item.id,
+      question: item.question || '',
+      answer: item.answer_html || '',
+    })),
+  } : null;
+
+  const faq = {
+    // 所有主题及其问题；jsonLd 为 schema.org FAQPage，可直接输出到
+    // <script type="application/ld+json">
+    async list() {
+      const result = await api.get('/faq');
+      return {
+        topics: asArray(result?.topics).map(normalizeFaqTopic).filter(Boolean),
+        jsonLd: result?.json_ld || null,
+      };
+    },
+
+    // 单个主题
+    async get(slug) {
+      const result = await api.get(`/faq/${encodeURIComponent(slug)}`);
+      return {
+        topic: normalizeFaqTopic(result?.topic),
+        jsonLd: result?.json_ld || null,
+      };
+    },
+  };
+
+  // ============================================
+  // 文章系列 API
+  // ============================================
+  const normalizeSeries = (series) => series ? {
+    id: asNumber(series.id),
+    slug: series.slug || '',
+    title: series.title || '',
+    description: series.description || '',
+    articles: asArray(series.articles).map(normalizeArticleLink).filter(Boolean),
+  } : null;
+
+  const series = {
+    // 所有系列（不含文章）
+    async list() {
+      const result = await api.get('/series');
+      return asArray(result?.series).map(normalizeSeries).filter(Boolean);
+    },
+
+    // 单个系列及其已发布文章，按阅读顺序排列
+    async get(slug) {
+      const result = await api.get(`/series/${encodeURIComponent(slug)}`);
+      return normalizeSeries(result?.series);
+    },
+  };
+
   const publicUser = {
     isLoggedIn: () => user.isLoggedIn(),
     getCurrent: () => user.getCurrent(),
@@ -2694,16 +3300,22 @@
      * @param {string} [siteUrl] - 站点 URL
      */
     setArticleMeta(article, siteName, siteUrl) {
-      const title = `${article.title} - ${siteName}`;
-      const desc = (article.excerpt || '').substring(0, 200);
-      const url = siteUrl ? `${siteUrl.replace(/\/$/, '')}${urls.article(article)}` : '';
+      const overrides = normalizeArticleSeo(article.seo);
+      const shareTitle = overrides.metaTitle || article.title;
+      const title = overrides.metaTitle || `${article.title} - ${siteName}`;
+      const desc = (overrides.metaDescription || article.excerpt || '').substring(0, 200);
+      const base = siteUrl ? siteUrl.replace(/\/$/, '') : '';
+      let url = base ? `${base}${urls.article(article)}` : '';
+      if (overrides.canonicalUrl) {
+        url = overrides.canonicalUrl.startsWith('/') ? `${base}${overrides.canonicalUrl}` : overrides.canonicalUrl;
+      }
       const image = article.thumbnail || article.coverImage || '';
 
       this.set({
         title,
-        meta: { description: desc },
+        meta: { description: desc, robots: overrides.noindex ? 'noindex' : 'all' },
         og: {
-          title: article.title,
+          title: shareTitle,
           description: desc,
           type: 'article',
           site_name: siteName,
@@ -2712,7 +3324,7 @@
         },
         twitter: {
           card: image ? 'summary_large_image' : 'summary',
-          title: article.title,
+          title: shareTitle,
           description: desc,
           ...(image ? { image } : {}),
         },
@@ -3316,6 +3928,42 @@
       });
     });
 
+    // 投票：服务端渲染的是不区分访客的表单，这里换成当前访客的视图
+    const mountPoll = (el, pollId, html) => {
+      const wrapper = document.createElement('div');
+      wrapper.innerHTML = html;
+      const next = wrapper.firstElementChild;
+      if (!next) {
+        el.remove();
+        return;
+      }
+      next.dataset.notevaBound = '1';
+      el.replaceWith(next);
+      if (next.tagName !== 'FORM') return;
+      next.addEventListener('submit', async event => {
+        event.preventDefault();
+        const ids = Array.from(next.querySelectorAll('input:checked')).map(input => input.value);
+        if (!ids.length) return;
+        const button = next.querySelector('.noteva-poll-submit');
+        if (button) button.disabled = true;
+        try {
+          const view = await polls.vote(pollId, ids);
+          mountPoll(next, pollId, view.html);
+        } catch (e) {
+          if (button) button.disabled = false;
+          console.warn('[Noteva] Poll vote failed:', e);
+        }
+      });
+    };
+
+    document.querySelectorAll('[data-noteva-poll]:not([data-noteva-bound])').forEach(el => {
+      const pollId = el.dataset.notevaPoll;
+      el.dataset.notevaBound = '1';
+      polls.get(pollId)
+        .then(view => mountPoll(el, pollId, view.html))
+        .catch(e => console.warn('[Noteva] Poll load failed:', e));
+    });
+
     const locale = i18n.getLocale ? i18n.getLocale() : undefined;
     const formatDate = (value, timezone) => {
       if (!value) return '';
@@ -3793,6 +4441,15 @@
     comments,
     captcha,
     user: publicUser,
+    readingProgress,
+    favorites,
+    authors,
+    polls,
+    calendar,
+    releases,
+    docs,
+    faq,
+    series,
     interactions,
     search,
 
@@ -1,16 +1,21 @@
 //! Pages API endpoints
 
 use axum::{
-    extract::{Path, State},
-    http::StatusCode,
+    extract::{Path, Query, State},
+    http::{HeaderMap, StatusCode},
     response::IntoResponse,
     routing::{delete, get, post, put},
     Json, Router,
 };
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
 
-use crate::api::middleware::{ApiError, AppState};
-use crate::models::{CreatePageInput, Page, UpdatePageInput};
+use crate::api::middleware::{
+    check_write_preconditions, conditional_json, version_headers, ApiError, AppState,
+};
+use crate::models::{
+    ContentAlternate, ContentKind, CreatePageInput, CustomFieldMap, Page, UpdatePageInput,
+};
+use crate::services::translation::matches_lang;
 
 pub fn router() -> Router<AppState> {
     Router::new()
@@ -19,6 +24,10 @@ pub fn router() -> Router<AppState> {
         .route("/{id}", get(get_page))
         .route("/{id}", put(update_page))
         .route("/{id}", delete(delete_page))
+        .nest(
+            "/{id}/fields",
+            crate::api::custom_fields::router(ContentKind::Page),
+        )
 }
 
 pub fn public_router() -> Router<AppState> {
@@ -37,26 +46,71 @@ struct PagesResponse {
 #[derive(Serialize)]
 struct PageResponse {
     page: Page,
+    /// Published language variants, this page included, for `hreflang`
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    translations: Vec<ContentAlternate>,
+    /// Typed key/value fields set by the author, by key
+    #[serde(skip_serializing_if = "CustomFieldMap::is_empty")]
+    custom_fields: CustomFieldMap,
+}
+
+#[derive(Debug, Deserialize)]
+struct ListPagesQuery {
+    /// Only pages in this language; pages without one are in the site language
+    lang: Option<String>,
+}
+
+/// Keep the pages in the requested language, if any
+async fn filter_by_lang(
+    state: &AppState,
+    pages: Vec<Page>,
+    lang: Option<&str>,
+) -> Result<Vec<Page>, ApiError> {
+    let Some(filter) = crate::api::translations::lang_filter(state, lang).await? else {
+        return Ok(pages);
+    };
+    let languages = state
+        .translation_service
+        .languages(ContentKind::Page)
+        .await
+        .map_err(|e| ApiError::internal_error(e.to_string()))?;
+    Ok(pages
+        .into_iter()
+        .filter(|p| {
+            matches_lang(
+                languages.get(&p.id).map(String::as_str),
+                &filter.lang,
+                &filter.site_lang,
+            )
+        })
+        .collect())
 }
 
-async fn list_pages(State(state): State<AppState>) -> Result<impl IntoResponse, ApiError> {
+async fn list_pages(
+    State(state): State<AppState>,
+    Query(query): Query<ListPagesQuery>,
+) -> Result<impl IntoResponse, ApiError> {
     let pages = state
         .page_service
         .list()
         .await
         .map_err(|e| ApiError::internal_error(e.to_string()))?;
+    let pages = filter_by_lang(&state, pages, query.lang.as_deref()).await?;
     Ok(Json(PagesResponse { pages }))
 }
 
 async fn list_published_pages(
     State(state): State<AppState>,
+    headers: HeaderMap,
+    Query(query): Query<ListPagesQuery>,
 ) -> Result<impl IntoResponse, ApiError> {
     let pages = state
         .page_service
         .list_published()
         .await
         .map_err(|e| ApiError::internal_error(e.to_string()))?;
-    Ok(Json(PagesResponse { pages }))
+    let pages = filter_by_lang(&state, pages, query.lang.as_deref()).await?;
+    Ok(conditional_json(&headers, &PagesResponse { pages }))
 }
 
 async fn get_page(
@@ -69,13 +123,25 @@ async fn get_page(
         .await
         .map_err(|e| ApiError::internal_error(e.to_string()))?;
     match page {
-        Some(p) => Ok(Json(PageResponse { page: p })),
+        Some(p) => {
+            let custom_fields =
+                crate::api::custom_fields::for_content(&state, ContentKind::Page, p.id).await;
+            Ok((
+                version_headers(p.id, p.updated_at),
+                Json(PageResponse {
+                    page: p,
+                    translations: Vec::new(),
+                    custom_fields,
+                }),
+            ))
+        }
         None => Err(ApiError::not_found("Page not found")),
     }
 }
 
 async fn get_page_by_slug(
     State(state): State<AppState>,
+    headers: HeaderMap,
     Path(slug): Path<String>,
 ) -> Result<impl IntoResponse, ApiError> {
     let page = state
@@ -84,7 +150,20 @@ async fn get_page_by_slug(
         .await
         .map_err(|e| ApiError::internal_error(e.to_string()))?;
     match page {
-        Some(p) => Ok(Json(PageResponse { page: p })),
+        Some(p) => {
+            let translations =
+                crate::api::translations::alternates(&state, ContentKind::Page, p.id).await;
+            let custom_fields =
+                crate::api::custom_fields::for_content(&state, ContentKind::Page, p.id).await;
+            Ok(conditional_json(
+                &headers,
+                &PageResponse {
+                    page: p,
+                    translations,
+                    custom_fields,
+                },
+            ))
+        }
         None => Err(ApiError::not_found("Page not found")),
     }
 }
@@ -98,20 +177,44 @@ async fn create_page(
         .create(input.slug, input.title, input.content, input.status)
         .await
         .map_err(|e| ApiError::validation_error(e.to_string()))?;
-    Ok((StatusCode::CREATED, Json(PageResponse { page })))
+    Ok((
+        StatusCode::CREATED,
+        Json(PageResponse {
+            page,
+            translations: Vec::new(),
+            custom_fields: CustomFieldMap::new(),
+        }),
+    ))
 }
 
+/// Honors `If-Match` and `If-Unmodified-Since` like article updates
 async fn update_page(
     State(state): State<AppState>,
     Path(id): Path<i64>,
+    headers: HeaderMap,
     Json(input): Json<UpdatePageInput>,
 ) -> Result<impl IntoResponse, ApiError> {
+    let existing = state
+        .page_service
+        .get_by_id(id)
+        .await
+        .map_err(|e| ApiError::internal_error(e.to_string()))?
+        .ok_or_else(|| ApiError::not_found("Page not found"))?;
+    check_write_preconditions(&headers, existing.id, existing.updated_at)?;
+
     let page = state
         .page_service
         .update(id, input.slug, input.title, input.content, input.status)
         .await
         .map_err(|e| ApiError::validation_error(e.to_string()))?;
-    Ok(Json(PageResponse { page }))
+    Ok((
+        version_headers(page.id, page.updated_at),
+        Json(PageResponse {
+            page,
+            translations: Vec::new(),
+            custom_fields: CustomFieldMap::new(),
+        }),
+    ))
 }
 
 async fn delete_page(
@@ -123,5 +226,19 @@ async fn delete_page(
         .delete(id)
         .await
         .map_err(|e| ApiError::internal_error(e.to_string()))?;
+    if let Err(e) = state
+        .translation_service
+        .remove(ContentKind::Page, id)
+        .await
+    {
+        tracing::warn!("Failed to remove translation of page {}: {}", id, e);
+    }
+    if let Err(e) = state
+        .custom_field_service
+        .remove_content(ContentKind::Page, id)
+        .await
+    {
+        tracing::warn!("Failed to remove custom fields of page {}: {}", id, e);
+    }
     Ok(StatusCode::NO_CONTENT)
 }
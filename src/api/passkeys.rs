@@ -0,0 +1,269 @@
+//! Passkey (WebAuthn) API endpoints
+//!
+//! Credential management (requires auth, admins only):
+//! - GET    /api/v1/auth/passkeys - List the current user's passkeys
+//! - POST   /api/v1/auth/passkeys/register/start - Get creation options
+//! - POST   /api/v1/auth/passkeys/register/finish - Store a new passkey
+//! - PUT    /api/v1/auth/passkeys/{id} - Rename a passkey
+//! - DELETE /api/v1/auth/passkeys/{id} - Delete a passkey
+//!
+//! Login (public):
+//! - POST /api/v1/auth/passkeys/login/start - Get request options
+//! - POST /api/v1/auth/passkeys/login/finish - Verify assertion, start session
+
+use crate::api::auth::{log_login_attempt, AuthResponse};
+use crate::api::middleware::{
+    ensure_ip_not_blocked, extract_client_ip, should_set_secure_cookie, ApiError, AppState,
+    AuthenticatedUser,
+};
+use crate::models::WebauthnCredential;
+use crate::services::webauthn::{AuthenticationCredential, RegistrationCredential};
+use crate::services::{AbuseSignal, WebauthnError};
+use axum::{
+    extract::{ConnectInfo, Path, State},
+    http::{header, HeaderMap, HeaderValue, StatusCode},
+    response::{IntoResponse, Response},
+    routing::{get, post, put},
+    Json, Router,
+};
+use serde::Deserialize;
+use std::net::SocketAddr;
+
+/// Build the passkey management router (requires auth)
+pub fn router() -> Router<AppState> {
+    Router::new()
+        .route("/", get(list_passkeys))
+        .route("/register/start", post(start_registration))
+        .route("/register/finish", post(finish_registration))
+        .route("/{id}", put(rename_passkey).delete(delete_passkey))
+}
+
+/// Build the public passkey login router
+pub fn public_router() -> Router<AppState> {
+    Router::new()
+        .route("/login/start", post(start_login))
+        .route("/login/finish", post(finish_login))
+}
+
+/// Request body for finishing a registration
+#[derive(Debug, Deserialize)]
+pub struct FinishRegistrationRequest {
+    pub name: String,
+    pub credential: RegistrationCredential,
+}
+
+/// Request body for renaming a passkey
+#[derive(Debug, Deserialize)]
+pub struct RenamePasskeyRequest {
+    pub name: String,
+}
+
+/// Request body for starting a passkey login
+#[derive(Debug, Default, Deserialize)]
+pub struct StartLoginRequest {
+    /// Optional username; omit to use discoverable credentials
+    #[serde(default)]
+    pub username: Option<String>,
+}
+
+fn map_webauthn_error(e: WebauthnError) -> ApiError {
+    match e {
+        WebauthnError::Disabled => ApiError::not_found("Passkeys are not enabled"),
+        WebauthnError::NotConfigured(_) | WebauthnError::Forbidden => {
+            ApiError::forbidden(e.to_string())
+        }
+        WebauthnError::InvalidChallenge | WebauthnError::VerificationFailed(_) => {
+            ApiError::unauthorized(e.to_string())
+        }
+        WebauthnError::TooManyChallenges => ApiError::new("RATE_LIMIT", e.to_string()),
+        WebauthnError::ValidationError(msg) => ApiError::validation_error(msg),
+        WebauthnError::NotFound => ApiError::not_found("Passkey not found"),
+        WebauthnError::Internal(e) => ApiError::internal_error(e.to_string()),
+    }
+}
+
+/// GET /api/v1/auth/passkeys - List the current user's passkeys
+async fn list_passkeys(
+    State(state): State<AppState>,
+    user: AuthenticatedUser,
+) -> Result<Json<Vec<WebauthnCredential>>, ApiError> {
+    let passkeys = state
+        .webauthn_service
+        .list(user.0.id)
+        .await
+        .map_err(map_webauthn_error)?;
+    Ok(Json(passkeys))
+}
+
+/// POST /api/v1/auth/passkeys/register/start - Get credential creation options
+async fn start_registration(
+    State(state): State<AppState>,
+    user: AuthenticatedUser,
+) -> Result<Json<serde_json::Value>, ApiError> {
+    let options = state
+        .webauthn_service
+        .start_registration(&user.0)
+        .await
+        .map_err(map_webauthn_error)?;
+    Ok(Json(options))
+}
+
+/// POST /api/v1/auth/passkeys/register/finish - Verify and store a new passkey
+async fn finish_registration(
+    State(state): State<AppState>,
+    user: AuthenticatedUser,
+    Json(body): Json<FinishRegistrationRequest>,
+) -> Result<(StatusCode, Json<WebauthnCredential>), ApiError> {
+    let passkey = state
+        .webauthn_service
+        .finish_registration(&user.0, &body.name, &body.credential)
+        .await
+        .map_err(map_webauthn_error)?;
+    Ok((StatusCode::CREATED, Json(passkey)))
+}
+
+/// PUT /api/v1/auth/passkeys/{id} - Rename a passkey
+async fn rename_passkey(
+    State(state): State<AppState>,
+    user: AuthenticatedUser,
+    Path(id): Path<i64>,
+    Json(body): Json<RenamePasskeyRequest>,
+) -> Result<StatusCode, ApiError> {
+    state
+        .webauthn_service
+        .rename(user.0.id, id, &body.name)
+        .await
+        .map_err(map_webauthn_error)?;
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// DELETE /api/v1/auth/passkeys/{id} - Delete a passkey
+async fn delete_passkey(
+    State(state): State<AppState>,
+    user: AuthenticatedUser,
+    Path(id): Path<i64>,
+) -> Result<StatusCode, ApiError> {
+    state
+        .webauthn_service
+        .delete(user.0.id, id)
+        .await
+        .map_err(map_webauthn_error)?;
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// POST /api/v1/auth/passkeys/login/start - Get credential request options
+async fn start_login(
+    State(state): State<AppState>,
+    ConnectInfo(addr): ConnectInfo<SocketAddr>,
+    headers: HeaderMap,
+    body: Option<Json<StartLoginRequest>>,
+) -> Result<Json<serde_json::Value>, ApiError> {
+    ensure_ip_not_blocked(&state, &extract_client_ip(&headers, addr)).await?;
+    let body = body.map(|Json(b)| b).unwrap_or_default();
+
+    let options = state
+        .webauthn_service
+        .start_authentication(body.username.as_deref())
+        .await
+        .map_err(map_webauthn_error)?;
+    Ok(Json(options))
+}
+
+/// POST /api/v1/auth/passkeys/login/finish - Verify the assertion and sign in
+///
+/// Passkeys replace both the password and the TOTP code, so no 2FA challenge
+/// is issued.
+async fn finish_login(
+    State(state): State<AppState>,
+    ConnectInfo(addr): ConnectInfo<SocketAddr>,
+    headers: HeaderMap,
+    Json(credential): Json<AuthenticationCredential>,
+) -> Result<Response, ApiError> {
+    let ip_address = extract_client_ip(&headers, addr);
+    ensure_ip_not_blocked(&state, &ip_address).await?;
+    let user_agent = headers
+        .get(header::USER_AGENT)
+        .and_then(|h| h.to_str().ok())
+        .map(String::from);
+
+    let user = match state
+        .webauthn_service
+        .finish_authentication(&credential)
+        .await
+    {
+        Ok(user) => user,
+        Err(e) => {
+            if matches!(e, WebauthnError::VerificationFailed(_)) {
+                state
+                    .ip_reputation
+                    .record_str(&ip_address, AbuseSignal::FailedLogin)
+                    .await;
+            }
+            log_login_attempt(
+                &state.pool,
+                "(passkey)",
+                Some(&ip_address),
+                user_agent.as_deref(),
+                false,
+                Some("Passkey verification failed"),
+            )
+            .await;
+            return Err(map_webauthn_error(e));
+        }
+    };
+
+    log_login_attempt(
+        &state.pool,
+        &user.username,
+        Some(&ip_address),
+        user_agent.as_deref(),
+        true,
+        Some("Passkey"),
+    )
+    .await;
+
+    let session = state
+        .user_service
+        .create_login_session(&user, Some(ip_address), user_agent)
+        .await
+        .map_err(|e| ApiError::internal_error(e.to_string()))?;
+
+    let is_secure = should_set_secure_cookie(&state, &headers, Some(addr)).await;
+    let secure_flag = if is_secure { "; Secure" } else { "" };
+    let csrf_token = crate::api::middleware::generate_csrf_token();
+
+    // Same cookies as a password login
+    let session_cookie = format!(
+        "session={}; Path=/; HttpOnly; SameSite=Lax; Max-Age={}{}",
+        session.id,
+        7 * 24 * 60 * 60,
+        secure_flag,
+    );
+    let csrf_cookie = format!(
+        "csrf_token={}; Path=/; SameSite=Lax; Max-Age={}{}",
+        csrf_token,
+        7 * 24 * 60 * 60,
+        secure_flag,
+    );
+
+    let mut response_headers = HeaderMap::new();
+    response_headers.insert(
+        header::SET_COOKIE,
+        HeaderValue::from_str(&session_cookie)
+            .map_err(|_| ApiError::internal_error("Failed to build session cookie"))?,
+    );
+    response_headers.append(
+        header::SET_COOKIE,
+        HeaderValue::from_str(&csrf_cookie)
+            .map_err(|_| ApiError::internal_error("Failed to build CSRF cookie"))?,
+    );
+
+    Ok((
+        response_headers,
+        Json(AuthResponse {
+            user: user.into(),
+            token: session.id,
+        }),
+    )
+        .into_response())
+}
@@ -0,0 +1,185 @@
+//! Poll API endpoints.
+//!
+//! - GET /api/v1/admin/polls - All polls with their counts
+//! - POST /api/v1/admin/polls - Create a poll
+//! - GET/PUT/DELETE /api/v1/admin/polls/:id - Manage a poll
+//! - GET /api/v1/polls/:id - The poll as the current reader sees it
+//! - POST /api/v1/polls/:id/vote - Vote, once per reader
+//!
+//! Readers are told apart by account, or by IP and user agent when logged
+//! out. Public responses carry `html`, the form or results to show.
+
+use axum::{
+    extract::{ConnectInfo, Path, State},
+    http::{HeaderMap, StatusCode},
+    response::IntoResponse,
+    routing::{get, post},
+    Json, Router,
+};
+use serde::{Deserialize, Serialize};
+use std::net::SocketAddr;
+
+use crate::api::comments::get_user_id_from_headers;
+use crate::api::middleware::{ensure_ip_not_blocked, extract_client_ip, ApiError, AppState};
+use crate::models::{CreatePollInput, Poll, UpdatePollInput};
+use crate::services::{generate_fingerprint, PollError, PollView};
+
+/// Build the poll management router (requires admin)
+pub fn router() -> Router<AppState> {
+    Router::new()
+        .route("/", get(list_polls).post(create_poll))
+        .route("/{id}", get(get_poll).put(update_poll).delete(delete_poll))
+}
+
+/// Build the public poll router
+pub fn public_router() -> Router<AppState> {
+    Router::new()
+        .route("/{id}", get(view_poll))
+        .route("/{id}/vote", post(vote))
+}
+
+#[derive(Debug, Serialize)]
+struct PollsResponse {
+    polls: Vec<Poll>,
+}
+
+#[derive(Debug, Serialize)]
+struct PollResponse {
+    poll: Poll,
+}
+
+#[derive(Debug, Serialize)]
+struct PollViewResponse {
+    poll: Poll,
+    /// Options the reader picked, empty until they vote
+    voted: Vec<i64>,
+    html: String,
+}
+
+impl From<PollView> for PollViewResponse {
+    fn from(view: PollView) -> Self {
+        let html = view.html();
+        Self {
+            poll: view.poll,
+            voted: view.voted,
+            html,
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct VoteRequest {
+    option_ids: Vec<i64>,
+}
+
+fn map_poll_error(e: PollError) -> ApiError {
+    match e {
+        PollError::NotFound => ApiError::not_found(e.to_string()),
+        PollError::Closed | PollError::Validation(_) => ApiError::validation_error(e.to_string()),
+        PollError::Internal(e) => ApiError::internal_error(e.to_string()),
+    }
+}
+
+async fn list_polls(State(state): State<AppState>) -> Result<impl IntoResponse, ApiError> {
+    let polls = state.poll_service.list().await.map_err(map_poll_error)?;
+    Ok(Json(PollsResponse { polls }))
+}
+
+async fn get_poll(
+    State(state): State<AppState>,
+    Path(id): Path<i64>,
+) -> Result<impl IntoResponse, ApiError> {
+    let poll = state
+        .poll_service
+        .get_by_id(id)
+        .await
+        .map_err(map_poll_error)?;
+    Ok(Json(PollResponse { poll }))
+}
+
+async fn create_poll(
+    State(state): State<AppState>,
+    Json(input): Json<CreatePollInput>,
+) -> Result<impl IntoResponse, ApiError> {
+    let poll = state
+        .poll_service
+        .create(input)
+        .await
+        .map_err(map_poll_error)?;
+    Ok((StatusCode::CREATED, Json(PollResponse { poll })))
+}
+
+async fn update_poll(
+    State(state): State<AppState>,
+    Path(id): Path<i64>,
+    Json(input): Json<UpdatePollInput>,
+) -> Result<impl IntoResponse, ApiError> {
+    let poll = state
+        .poll_service
+        .update(id, input)
+        .await
+        .map_err(map_poll_error)?;
+    Ok(Json(PollResponse { poll }))
+}
+
+async fn delete_poll(
+    State(state): State<AppState>,
+    Path(id): Path<i64>,
+) -> Result<impl IntoResponse, ApiError> {
+    let deleted = state
+        .poll_service
+        .delete(id)
+        .await
+        .map_err(map_poll_error)?;
+    if !deleted {
+        return Err(ApiError::not_found("Poll not found"));
+    }
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// Key a reader votes under: their account, else IP and user agent
+async fn voter_key(state: &AppState, headers: &HeaderMap, client_ip: &str) -> String {
+    if let Some(user_id) = get_user_id_from_headers(state, headers).await {
+        return format!("user:{}", user_id);
+    }
+    let ua = headers
+        .get("user-agent")
+        .and_then(|v| v.to_str().ok())
+        .unwrap_or("");
+    format!("fp:{}", generate_fingerprint(client_ip, ua))
+}
+
+async fn view_poll(
+    State(state): State<AppState>,
+    ConnectInfo(addr): ConnectInfo<SocketAddr>,
+    headers: HeaderMap,
+    Path(id): Path<i64>,
+) -> Result<impl IntoResponse, ApiError> {
+    let client_ip = extract_client_ip(&headers, addr);
+    let key = voter_key(&state, &headers, &client_ip).await;
+    let view = state
+        .poll_service
+        .view(id, &key)
+        .await
+        .map_err(map_poll_error)?;
+    Ok(Json(PollViewResponse::from(view)))
+}
+
+async fn vote(
+    State(state): State<AppState>,
+    ConnectInfo(addr): ConnectInfo<SocketAddr>,
+    headers: HeaderMap,
+    Path(id): Path<i64>,
+    Json(req): Json<VoteRequest>,
+) -> Result<impl IntoResponse, ApiError> {
+    let client_ip = extract_client_ip(&headers, addr);
+    ensure_ip_not_blocked(&state, &client_ip).await?;
+
+    let key = voter_key(&state, &headers, &client_ip).await;
+    let view = state
+        .poll_service
+        .vote(id, &key, &req.option_ids)
+        .await
+        .map_err(map_poll_error)?;
+    Ok(Json(PollViewResponse::from(view)))
+}
@@ -0,0 +1,101 @@
+//! Public Web Push endpoints
+//!
+//! - GET /api/v1/push/public-key - VAPID key for `PushManager.subscribe()`
+//! - POST /api/v1/push/subscribe - Store a `PushSubscription.toJSON()`
+//! - POST /api/v1/push/unsubscribe - Remove a subscription by endpoint
+//!
+//! All answer 404 while Web Push is disabled, except unsubscribing.
+
+use axum::{
+    extract::State,
+    http::StatusCode,
+    routing::{get, post},
+    Json, Router,
+};
+use serde::{Deserialize, Serialize};
+
+use crate::api::middleware::{ApiError, AppState};
+use crate::services::WebPushError;
+
+/// Build the public Web Push router
+pub fn router() -> Router<AppState> {
+    Router::new()
+        .route("/public-key", get(public_key))
+        .route("/subscribe", post(subscribe))
+        .route("/unsubscribe", post(unsubscribe))
+}
+
+/// Response with the VAPID public key
+#[derive(Debug, Serialize)]
+pub struct PublicKeyResponse {
+    /// Uncompressed P-256 public key, base64url
+    pub public_key: String,
+}
+
+/// Keys of a push subscription
+#[derive(Debug, Deserialize)]
+pub struct SubscriptionKeys {
+    pub p256dh: String,
+    pub auth: String,
+}
+
+/// Request body for subscribing, as produced by `PushSubscription.toJSON()`
+#[derive(Debug, Deserialize)]
+pub struct SubscribeRequest {
+    pub endpoint: String,
+    pub keys: SubscriptionKeys,
+}
+
+/// Request body for unsubscribing
+#[derive(Debug, Deserialize)]
+pub struct UnsubscribeRequest {
+    pub endpoint: String,
+}
+
+fn map_error(e: WebPushError) -> ApiError {
+    match e {
+        WebPushError::Disabled => ApiError::not_found("Web Push is not enabled"),
+        WebPushError::InvalidSubscription(_) => ApiError::validation_error(e.to_string()),
+        e => ApiError::internal_error(e.to_string()),
+    }
+}
+
+/// GET /api/v1/push/public-key - VAPID application server key
+pub async fn public_key(
+    State(state): State<AppState>,
+) -> Result<Json<PublicKeyResponse>, ApiError> {
+    let public_key = state
+        .web_push_service
+        .public_key()
+        .await
+        .map_err(map_error)?;
+    Ok(Json(PublicKeyResponse { public_key }))
+}
+
+/// POST /api/v1/push/subscribe - Register a browser for notifications
+pub async fn subscribe(
+    State(state): State<AppState>,
+    Json(body): Json<SubscribeRequest>,
+) -> Result<StatusCode, ApiError> {
+    state
+        .web_push_service
+        .subscribe(&body.endpoint, &body.keys.p256dh, &body.keys.auth)
+        .await
+        .map_err(map_error)?;
+    Ok(StatusCode::CREATED)
+}
+
+/// POST /api/v1/push/unsubscribe - Stop notifications for a browser
+///
+/// Answers `204 No Content` whether or not the endpoint was registered.
+pub async fn unsubscribe(
+    State(state): State<AppState>,
+    Json(body): Json<UnsubscribeRequest>,
+) -> Result<StatusCode, ApiError> {
+    state
+        .web_push_service
+        .unsubscribe(&body.endpoint)
+        .await
+        .map_err(|e| ApiError::internal_error(e.to_string()))?;
+    Ok(StatusCode::NO_CONTENT)
+}
@@ -0,0 +1,239 @@
+//! Reader group and article audience endpoints.
+//!
+//! - GET /api/v1/admin/reader-groups - All groups
+//! - POST /api/v1/admin/reader-groups - Create a group
+//! - GET/PUT/DELETE /api/v1/admin/reader-groups/:id - Manage a group
+//! - GET/PUT /api/v1/admin/reader-groups/:id/members - A group's members
+//! - GET/PUT /api/v1/admin/articles/:id/audience - Who may read an article
+//!
+//! Articles with an audience are left out of every public listing, feed
+//! and search for readers it does not admit. Fetching one directly answers
+//! 404, or 403 when the `restricted_article_status` setting is `403`.
+
+use axum::{
+    extract::{Extension, Path, State},
+    http::StatusCode,
+    response::IntoResponse,
+    routing::get,
+    Json, Router,
+};
+use serde::Serialize;
+
+use crate::api::middleware::{ApiError, AppState, AuthenticatedUser};
+use crate::models::{
+    ArticleAudience, ArticleReader, PublicUser, ReaderGroup, ReaderGroupInput,
+    ReaderGroupMembersInput,
+};
+use crate::services::reader_group::forbid_restricted;
+use crate::services::ReaderGroupError;
+
+/// Build the reader group management router (requires admin)
+pub fn router() -> Router<AppState> {
+    Router::new()
+        .route("/", get(list_groups).post(create_group))
+        .route(
+            "/{id}",
+            get(get_group).put(update_group).delete(delete_group),
+        )
+        .route("/{id}/members", get(list_members).put(set_members))
+}
+
+#[derive(Debug, Serialize)]
+struct GroupsResponse {
+    groups: Vec<ReaderGroup>,
+}
+
+#[derive(Debug, Serialize)]
+struct GroupResponse {
+    group: ReaderGroup,
+}
+
+#[derive(Debug, Serialize)]
+struct MembersResponse {
+    members: Vec<PublicUser>,
+}
+
+#[derive(Debug, Serialize)]
+struct AudienceResponse {
+    audience: ArticleAudience,
+}
+
+fn map_group_error(e: ReaderGroupError) -> ApiError {
+    match e {
+        ReaderGroupError::NotFound(_) => ApiError::not_found(e.to_string()),
+        ReaderGroupError::Validation(_) => ApiError::validation_error(e.to_string()),
+        ReaderGroupError::Internal(e) => ApiError::internal_error(e.to_string()),
+    }
+}
+
+/// The reader behind an optional signed-in user
+pub(crate) fn viewer_reader(user: Option<&Extension<AuthenticatedUser>>) -> Option<ArticleReader> {
+    ArticleReader::of(user.map(|Extension(user)| &user.0))
+}
+
+/// Fail unless `reader` may read the article, with 404 or 403 as the
+/// `restricted_article_status` setting says
+pub(crate) async fn ensure_readable(
+    state: &AppState,
+    article_id: i64,
+    reader: Option<&ArticleReader>,
+    not_found: impl FnOnce() -> ApiError,
+) -> Result<(), ApiError> {
+    let readable = state
+        .reader_group_service
+        .can_read(article_id, reader)
+        .await
+        .map_err(map_group_error)?;
+    if readable {
+        Ok(())
+    } else if forbid_restricted(&state.settings_service).await {
+        Err(ApiError::forbidden(
+            "You don't have permission to read this article",
+        ))
+    } else {
+        Err(not_found())
+    }
+}
+
+async fn list_groups(State(state): State<AppState>) -> Result<impl IntoResponse, ApiError> {
+    let groups = state
+        .reader_group_service
+        .list_groups()
+        .await
+        .map_err(map_group_error)?;
+    Ok(Json(GroupsResponse { groups }))
+}
+
+async fn get_group(
+    State(state): State<AppState>,
+    Path(id): Path<i64>,
+) -> Result<impl IntoResponse, ApiError> {
+    let group = state
+        .reader_group_service
+        .get_group(id)
+        .await
+        .map_err(map_group_error)?;
+    Ok(Json(GroupResponse { group }))
+}
+
+async fn create_group(
+    State(state): State<AppState>,
+    Json(input): Json<ReaderGroupInput>,
+) -> Result<impl IntoResponse, ApiError> {
+    let group = state
+        .reader_group_service
+        .create_group(input)
+        .await
+        .map_err(map_group_error)?;
+    Ok((StatusCode::CREATED, Json(GroupResponse { group })))
+}
+
+async fn update_group(
+    State(state): State<AppState>,
+    Path(id): Path<i64>,
+    Json(input): Json<ReaderGroupInput>,
+) -> Result<impl IntoResponse, ApiError> {
+    let group = state
+        .reader_group_service
+        .update_group(id, input)
+        .await
+        .map_err(map_group_error)?;
+    Ok(Json(GroupResponse { group }))
+}
+
+/// DELETE /api/v1/admin/reader-groups/{id} - Delete a group
+///
+/// Articles shown to the group stay restricted and become admins only.
+async fn delete_group(
+    State(state): State<AppState>,
+    Path(id): Path<i64>,
+) -> Result<impl IntoResponse, ApiError> {
+    state
+        .reader_group_service
+        .delete_group(id)
+        .await
+        .map_err(map_group_error)?;
+    invalidate_lists(&state).await?;
+    Ok(StatusCode::NO_CONTENT)
+}
+
+async fn list_members(
+    State(state): State<AppState>,
+    Path(id): Path<i64>,
+) -> Result<impl IntoResponse, ApiError> {
+    let members = state
+        .reader_group_service
+        .list_members(id)
+        .await
+        .map_err(map_group_error)?;
+    Ok(Json(MembersResponse { members }))
+}
+
+/// PUT /api/v1/admin/reader-groups/{id}/members - Replace a group's members
+///
+/// Body: `{"user_ids": [2, 5]}`.
+async fn set_members(
+    State(state): State<AppState>,
+    Path(id): Path<i64>,
+    Json(input): Json<ReaderGroupMembersInput>,
+) -> Result<impl IntoResponse, ApiError> {
+    let members = state
+        .reader_group_service
+        .set_members(id, input)
+        .await
+        .map_err(map_group_error)?;
+    Ok(Json(MembersResponse { members }))
+}
+
+async fn ensure_article(state: &AppState, id: i64) -> Result<(), ApiError> {
+    state
+        .article_service
+        .get_by_id(id)
+        .await
+        .map_err(|e| ApiError::internal_error(e.to_string()))?
+        .ok_or_else(|| ApiError::not_found(format!("Article not found: {}", id)))?;
+    Ok(())
+}
+
+/// GET /api/v1/admin/articles/{id}/audience - Who may read an article
+pub async fn get_audience(
+    State(state): State<AppState>,
+    Path(id): Path<i64>,
+) -> Result<impl IntoResponse, ApiError> {
+    ensure_article(&state, id).await?;
+    let audience = state
+        .reader_group_service
+        .audience(id)
+        .await
+        .map_err(map_group_error)?;
+    Ok(Json(AudienceResponse { audience }))
+}
+
+/// PUT /api/v1/admin/articles/{id}/audience - Limit who may read an article
+///
+/// Body: `{"roles": ["editor"], "group_ids": [3]}`. Readers with one of the
+/// roles (or a higher one) or in one of the groups are admitted; admins
+/// always are. Empty lists open the article to everyone again.
+pub async fn set_audience(
+    State(state): State<AppState>,
+    Path(id): Path<i64>,
+    Json(input): Json<ArticleAudience>,
+) -> Result<impl IntoResponse, ApiError> {
+    ensure_article(&state, id).await?;
+    let audience = state
+        .reader_group_service
+        .set_audience(id, input)
+        .await
+        .map_err(map_group_error)?;
+    invalidate_lists(&state).await?;
+    Ok(Json(AudienceResponse { audience }))
+}
+
+/// Cached public listings may still hold (or lack) the affected articles
+async fn invalidate_lists(state: &AppState) -> Result<(), ApiError> {
+    state
+        .article_service
+        .invalidate_list_cache()
+        .await
+        .map_err(|e| ApiError::internal_error(e.to_string()))
+}
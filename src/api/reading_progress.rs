@@ -0,0 +1,164 @@
+//! Reading progress of the logged-in user
+//!
+//! - GET /api/v1/reading-progress - Articles in progress, most recent first
+//! - GET /api/v1/reading-progress/:article_id - Position in one article
+//! - PUT /api/v1/reading-progress/:article_id - Save the position
+//! - DELETE /api/v1/reading-progress/:article_id - Forget the position
+//!
+//! Positions are scroll fractions from 0.0 to 1.0, so they survive layout
+//! changes between devices. Only published articles the user may read are
+//! tracked: not those in categories above their clearance, nor those whose
+//! audience leaves them out.
+
+use axum::{
+    extract::{Path, Query, State},
+    http::StatusCode,
+    routing::get,
+    Json, Router,
+};
+use serde::Deserialize;
+
+use crate::api::middleware::{ApiError, AppState, AuthenticatedUser};
+use crate::api::reader_groups::ensure_readable;
+use crate::models::{ArticleReader, ArticleStatus, CategoryAccess, ReadingProgress};
+
+const DEFAULT_LIMIT: i64 = 10;
+const MAX_LIMIT: i64 = 100;
+
+/// Build the reading progress router (requires authentication)
+pub fn router() -> Router<AppState> {
+    Router::new().route("/", get(list_progress)).route(
+        "/{article_id}",
+        get(get_progress).put(save_progress).delete(delete_progress),
+    )
+}
+
+/// Query params for listing progress
+#[derive(Debug, Deserialize)]
+pub struct ListProgressQuery {
+    /// Number of articles (default 10, at most 100)
+    pub limit: Option<i64>,
+}
+
+/// Request body for saving progress
+#[derive(Debug, Deserialize)]
+pub struct SaveProgressRequest {
+    /// Scroll position as a fraction of the article, from 0.0 to 1.0
+    pub position: f64,
+}
+
+/// GET /api/v1/reading-progress - Articles the user has started reading
+async fn list_progress(
+    State(state): State<AppState>,
+    user: AuthenticatedUser,
+    Query(query): Query<ListProgressQuery>,
+) -> Result<Json<Vec<ReadingProgress>>, ApiError> {
+    let limit = query.limit.unwrap_or(DEFAULT_LIMIT).clamp(1, MAX_LIMIT);
+    let progress = state
+        .reading_progress_repo
+        .list(
+            &reader_of(&user),
+            CategoryAccess::clearance(Some(&user.0)),
+            limit,
+        )
+        .await
+        .map_err(|e| ApiError::internal_error(e.to_string()))?;
+    Ok(Json(progress))
+}
+
+/// GET /api/v1/reading-progress/:article_id - Position in one article
+async fn get_progress(
+    State(state): State<AppState>,
+    user: AuthenticatedUser,
+    Path(article_id): Path<i64>,
+) -> Result<Json<ReadingProgress>, ApiError> {
+    let progress = state
+        .reading_progress_repo
+        .get(
+            &reader_of(&user),
+            CategoryAccess::clearance(Some(&user.0)),
+            article_id,
+        )
+        .await
+        .map_err(|e| ApiError::internal_error(e.to_string()))?
+        .ok_or_else(|| ApiError::not_found("No reading progress for this article"))?;
+    Ok(Json(progress))
+}
+
+/// PUT /api/v1/reading-progress/:article_id - Save the position in an article
+async fn save_progress(
+    State(state): State<AppState>,
+    user: AuthenticatedUser,
+    Path(article_id): Path<i64>,
+    Json(body): Json<SaveProgressRequest>,
+) -> Result<Json<ReadingProgress>, ApiError> {
+    if !ReadingProgress::is_valid_position(body.position) {
+        return Err(ApiError::validation_error(
+            "position must be between 0.0 and 1.0",
+        ));
+    }
+
+    let article = state
+        .article_service
+        .get_by_id(article_id)
+        .await
+        .map_err(|e| ApiError::internal_error(e.to_string()))?;
+    let Some(article) = article.filter(|a| a.status == ArticleStatus::Published) else {
+        return Err(ApiError::not_found("Article not found"));
+    };
+    // Articles in a private category are not found for readers without
+    // clearance
+    let category = state
+        .category_service
+        .get_by_id(article.category_id)
+        .await
+        .map_err(|e| ApiError::internal_error(e.to_string()))?;
+    if category.is_some_and(|c| !c.readable_by(CategoryAccess::clearance(Some(&user.0)))) {
+        return Err(ApiError::not_found("Article not found"));
+    }
+    let reader = reader_of(&user);
+    ensure_readable(&state, article_id, Some(&reader), || {
+        ApiError::not_found("Article not found")
+    })
+    .await?;
+
+    let saved = state
+        .reading_progress_repo
+        .save(
+            &reader,
+            CategoryAccess::clearance(Some(&user.0)),
+            article_id,
+            body.position,
+        )
+        .await
+        .map_err(|e| ApiError::internal_error(e.to_string()))?;
+    if !saved {
+        return Err(ApiError::not_found("Article not found"));
+    }
+    get_progress(State(state), user, Path(article_id)).await
+}
+
+/// DELETE /api/v1/reading-progress/:article_id - Forget the position
+async fn delete_progress(
+    State(state): State<AppState>,
+    user: AuthenticatedUser,
+    Path(article_id): Path<i64>,
+) -> Result<StatusCode, ApiError> {
+    let deleted = state
+        .reading_progress_repo
+        .delete(user.0.id, article_id)
+        .await
+        .map_err(|e| ApiError::internal_error(e.to_string()))?;
+    if !deleted {
+        return Err(ApiError::not_found("No reading progress for this article"));
+    }
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// The signed-in user as an article reader
+fn reader_of(user: &AuthenticatedUser) -> ArticleReader {
+    ArticleReader {
+        user_id: user.0.id,
+        role: user.0.role,
+    }
+}
@@ -0,0 +1,101 @@
+//! Redirect management API endpoints.
+//!
+//! Old slugs of articles redirect on their own; these are for everything
+//! else, such as links from a previous blog engine.
+
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+    response::IntoResponse,
+    routing::get,
+    Json, Router,
+};
+use serde::Serialize;
+
+use crate::api::middleware::{ApiError, AppState};
+use crate::models::{CreateRedirectInput, Redirect, UpdateRedirectInput};
+
+pub fn router() -> Router<AppState> {
+    Router::new()
+        .route("/", get(list_redirects).post(create_redirect))
+        .route(
+            "/{id}",
+            get(get_redirect)
+                .put(update_redirect)
+                .delete(delete_redirect),
+        )
+}
+
+#[derive(Debug, Serialize)]
+struct RedirectsResponse {
+    redirects: Vec<Redirect>,
+}
+
+#[derive(Debug, Serialize)]
+struct RedirectResponse {
+    redirect: Redirect,
+}
+
+async fn list_redirects(State(state): State<AppState>) -> Result<impl IntoResponse, ApiError> {
+    let redirects = state
+        .redirect_service
+        .list()
+        .await
+        .map_err(|e| ApiError::internal_error(e.to_string()))?;
+    Ok(Json(RedirectsResponse { redirects }))
+}
+
+async fn get_redirect(
+    State(state): State<AppState>,
+    Path(id): Path<i64>,
+) -> Result<impl IntoResponse, ApiError> {
+    let redirect = state
+        .redirect_service
+        .get_by_id(id)
+        .await
+        .map_err(|e| ApiError::internal_error(e.to_string()))?;
+    match redirect {
+        Some(redirect) => Ok(Json(RedirectResponse { redirect })),
+        None => Err(ApiError::not_found("Redirect not found")),
+    }
+}
+
+async fn create_redirect(
+    State(state): State<AppState>,
+    Json(input): Json<CreateRedirectInput>,
+) -> Result<impl IntoResponse, ApiError> {
+    let redirect = state
+        .redirect_service
+        .create(input)
+        .await
+        .map_err(|e| ApiError::validation_error(e.to_string()))?;
+    Ok((StatusCode::CREATED, Json(RedirectResponse { redirect })))
+}
+
+async fn update_redirect(
+    State(state): State<AppState>,
+    Path(id): Path<i64>,
+    Json(input): Json<UpdateRedirectInput>,
+) -> Result<impl IntoResponse, ApiError> {
+    let redirect = state
+        .redirect_service
+        .update(id, input)
+        .await
+        .map_err(|e| ApiError::validation_error(e.to_string()))?;
+    Ok(Json(RedirectResponse { redirect }))
+}
+
+async fn delete_redirect(
+    State(state): State<AppState>,
+    Path(id): Path<i64>,
+) -> Result<impl IntoResponse, ApiError> {
+    let deleted = state
+        .redirect_service
+        .delete(id)
+        .await
+        .map_err(|e| ApiError::internal_error(e.to_string()))?;
+    if !deleted {
+        return Err(ApiError::not_found("Redirect not found"));
+    }
+    Ok(StatusCode::NO_CONTENT)
+}
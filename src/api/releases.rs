@@ -0,0 +1,69 @@
+//! Release notes API endpoints.
+//!
+//! - GET /api/v1/releases - Release notes grouped by minor series
+//! - GET /releases.json - Flat, machine-readable release list
+
+use axum::{
+    body::Body,
+    extract::State,
+    http::{header, StatusCode},
+    response::{IntoResponse, Response},
+    routing::get,
+    Json, Router,
+};
+use serde::Serialize;
+
+use crate::api::middleware::{ApiError, AppState};
+use crate::services::release::{self, ReleaseGroup};
+use crate::services::settings::keys;
+
+/// Build the public release notes router
+pub fn public_router() -> Router<AppState> {
+    Router::new().route("/", get(list_releases))
+}
+
+#[derive(Debug, Serialize)]
+struct ReleasesResponse {
+    groups: Vec<ReleaseGroup>,
+}
+
+async fn site_setting(state: &AppState, key: &str) -> Option<String> {
+    state.settings_service.get(key).await.ok().flatten()
+}
+
+async fn list_releases(State(state): State<AppState>) -> Result<impl IntoResponse, ApiError> {
+    let site_url = site_setting(&state, keys::SITE_URL)
+        .await
+        .unwrap_or_default();
+    let releases = release::list_releases(&state.pool, &state.settings_service, &site_url)
+        .await
+        .map_err(|e| ApiError::internal_error(e.to_string()))?;
+    Ok(Json(ReleasesResponse {
+        groups: release::group_releases(releases),
+    }))
+}
+
+/// GET /releases.json
+pub async fn releases_json(State(state): State<AppState>) -> Response {
+    let site_name = site_setting(&state, keys::SITE_NAME)
+        .await
+        .unwrap_or_else(|| "Noteva".to_string());
+    let site_url = site_setting(&state, keys::SITE_URL)
+        .await
+        .unwrap_or_default();
+
+    match release::list_releases(&state.pool, &state.settings_service, &site_url).await {
+        Ok(releases) => Response::builder()
+            .status(StatusCode::OK)
+            .header(header::CONTENT_TYPE, "application/json; charset=utf-8")
+            .header(header::CACHE_CONTROL, "public, max-age=1800")
+            .body(Body::from(
+                release::releases_document(&site_name, &site_url, &releases).to_string(),
+            ))
+            .unwrap(),
+        Err(e) => {
+            tracing::error!("Failed to list releases: {}", e);
+            StatusCode::INTERNAL_SERVER_ERROR.into_response()
+        }
+    }
+}
@@ -28,8 +28,12 @@ pub struct ArticleResponse {
     pub updated_at: String,
     pub view_count: i64,
     pub like_count: i64,
+    pub favorite_count: i64,
     pub comment_count: i64,
     pub word_count: u64,
+    /// Characters other than whitespace
+    pub char_count: u64,
+    /// Estimated minutes to read
     pub reading_time: u32,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub summary: Option<String>,
@@ -55,9 +59,42 @@ pub struct ArticleResponse {
     pub related: Option<Vec<ArticleLink>>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub scheduled_at: Option<String>,
+    /// Syntax of `content`: `markdown`, `bbcode`, `rst`, `html` or `blocks`
+    #[serde(default)]
+    pub input_format: String,
+    /// Search engine overrides set by the author
+    #[serde(default)]
+    pub seo: ArticleSeoFields,
     /// Canonical URL based on permalink setting (present when URL mismatch detected)
     #[serde(skip_serializing_if = "Option::is_none")]
     pub canonical_url: Option<String>,
+    /// Published language variants, this article included, for `hreflang`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub translations: Option<Vec<crate::models::ContentAlternate>>,
+    /// The series this article belongs to, with its previous and next part
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub series: Option<crate::models::SeriesNavigation>,
+    /// Everyone credited on the article with their role; the first
+    /// `author` is `author_id`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub authors: Option<Vec<crate::models::ArticleAuthor>>,
+    /// Files attached to the article, in display order
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub attachments: Option<Vec<crate::models::ArticleAttachment>>,
+    /// Typed key/value fields set by the author, by key
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub custom_fields: Option<crate::models::CustomFieldMap>,
+    /// License the article may be reused under
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub license: Option<crate::models::LicenseInfo>,
+    /// Publish checklist items the article was published without, in
+    /// `warn` mode
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub unmet_checklist: Option<Vec<crate::services::publish_checklist::ChecklistItem>>,
+    /// The content, excerpt and summary of someone else's draft are
+    /// withheld from an author listing it
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub redacted: bool,
 }
 
 /// Simplified article response for list views
@@ -99,18 +136,32 @@ pub struct TagInfo {
     pub name: String,
 }
 
+/// SEO overrides embedded in article response; unset fields fall back to
+/// the title, excerpt and permalink
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+pub struct ArticleSeoFields {
+    pub meta_title: Option<String>,
+    pub meta_description: Option<String>,
+    pub canonical_url: Option<String>,
+    pub noindex: bool,
+}
+
 // ============================================================================
 // Pagination Response Types
 // ============================================================================
 
-/// Paginated article list response (full articles)
+/// Paginated article list response
 #[derive(Debug, Serialize)]
 pub struct PaginatedArticlesResponse {
-    pub articles: Vec<ArticleResponse>,
+    /// Serialized [`ArticleResponse`]s, trimmed to the `?fields=` selection
+    pub articles: Vec<serde_json::Value>,
     pub total: i64,
     pub page: u32,
     pub page_size: u32,
     pub total_pages: u32,
+    /// Cursor for the next page when `?cursor=` pagination is used
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub next_cursor: Option<String>,
 }
 
 /// Paginated article summary list response
@@ -121,37 +172,18 @@ pub struct PaginatedArticleSummaryResponse {
     pub page: u32,
     pub page_size: u32,
     pub total_pages: u32,
+    /// Cursor for the next page when `?cursor=` pagination is used
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub next_cursor: Option<String>,
 }
 
 // ============================================================================
 // Conversions
 // ============================================================================
 
-/// Count words in text (handles CJK characters as individual words)
-fn count_words(text: &str) -> u64 {
-    let mut count: u64 = 0;
-    let mut in_latin_word = false;
-    for ch in text.chars() {
-        if ch.is_ascii_alphanumeric() {
-            if !in_latin_word {
-                count += 1;
-                in_latin_word = true;
-            }
-        } else if ch > '\u{2E7F}' {
-            // CJK characters count as individual words
-            count += 1;
-            in_latin_word = false;
-        } else {
-            in_latin_word = false;
-        }
-    }
-    count
-}
-
 impl From<crate::models::Article> for ArticleResponse {
     fn from(article: crate::models::Article) -> Self {
-        let wc = count_words(&article.content);
-        let reading_min = (wc as f64 / 275.0).ceil() as u32; // ~275 wpm avg
+        let stats = article.stats;
         let summary = article
             .meta
             .get("summary")
@@ -159,6 +191,7 @@ impl From<crate::models::Article> for ArticleResponse {
             .map(str::trim)
             .filter(|value| !value.is_empty())
             .map(ToString::to_string);
+        let excerpt = Some(article.excerpt_text()).filter(|e| !e.is_empty());
         Self {
             id: article.id,
             slug: article.slug,
@@ -173,11 +206,13 @@ impl From<crate::models::Article> for ArticleResponse {
             updated_at: article.updated_at.to_rfc3339(),
             view_count: article.view_count,
             like_count: article.like_count,
+            favorite_count: article.favorite_count,
             comment_count: article.comment_count,
-            word_count: wc,
-            reading_time: reading_min.max(1),
-            summary: summary.clone(),
-            excerpt: summary,
+            word_count: stats.word_count.max(0) as u64,
+            char_count: stats.char_count.max(0) as u64,
+            reading_time: stats.reading_time.max(0) as u32,
+            summary,
+            excerpt,
             thumbnail: article.thumbnail,
             is_pinned: article.is_pinned,
             pin_order: article.pin_order,
@@ -193,14 +228,28 @@ impl From<crate::models::Article> for ArticleResponse {
             next: None,
             related: None,
             scheduled_at: article.scheduled_at.map(|dt| dt.to_rfc3339()),
+            input_format: article.input_format.to_string(),
+            seo: ArticleSeoFields {
+                meta_title: article.meta_title,
+                meta_description: article.meta_description,
+                canonical_url: article.canonical_url,
+                noindex: article.noindex,
+            },
             canonical_url: None,
+            translations: None,
+            series: None,
+            authors: None,
+            attachments: None,
+            custom_fields: None,
+            license: article.license.map(|license| license.info(false)),
+            unmet_checklist: None,
+            redacted: article.redacted,
         }
     }
 }
 
 impl From<crate::models::Article> for ArticleSummary {
     fn from(article: crate::models::Article) -> Self {
-        let wc = count_words(&article.content);
         Self {
             id: article.id,
             slug: article.slug,
@@ -208,7 +257,7 @@ impl From<crate::models::Article> for ArticleSummary {
             status: article.status.to_string(),
             published_at: article.published_at.map(|dt| dt.to_rfc3339()),
             created_at: article.created_at.to_rfc3339(),
-            word_count: wc,
+            word_count: article.stats.word_count.max(0) as u64,
         }
     }
 }
@@ -266,4 +315,61 @@ impl ArticleResponse {
         self.canonical_url = Some(url);
         self
     }
+
+    /// Add language variants
+    pub fn with_translations(mut self, translations: Vec<crate::models::ContentAlternate>) -> Self {
+        if !translations.is_empty() {
+            self.translations = Some(translations);
+        }
+        self
+    }
+
+    /// Add series navigation
+    pub fn with_series(mut self, series: Option<crate::models::SeriesNavigation>) -> Self {
+        self.series = series;
+        self
+    }
+
+    /// Add author credits
+    pub fn with_authors(mut self, authors: Vec<crate::models::ArticleAuthor>) -> Self {
+        if !authors.is_empty() {
+            self.authors = Some(authors);
+        }
+        self
+    }
+
+    /// Add attached files
+    pub fn with_attachments(mut self, attachments: Vec<crate::models::ArticleAttachment>) -> Self {
+        if !attachments.is_empty() {
+            self.attachments = Some(attachments);
+        }
+        self
+    }
+
+    /// Add custom fields
+    pub fn with_custom_fields(mut self, fields: crate::models::CustomFieldMap) -> Self {
+        if !fields.is_empty() {
+            self.custom_fields = Some(fields);
+        }
+        self
+    }
+
+    /// Fill in the site default license when the article has none
+    pub fn with_site_license(mut self, default: &crate::models::ContentLicense) -> Self {
+        if self.license.is_none() {
+            self.license = Some(default.info(true));
+        }
+        self
+    }
+
+    /// Report publish checklist items the article misses
+    pub fn with_unmet_checklist(
+        mut self,
+        unmet: Vec<crate::services::publish_checklist::ChecklistItem>,
+    ) -> Self {
+        if !unmet.is_empty() {
+            self.unmet_checklist = Some(unmet);
+        }
+        self
+    }
 }
@@ -0,0 +1,203 @@
+//! SAML single sign-on endpoints (`saml` feature)
+//!
+//! - GET  /api/v1/auth/saml/login - Redirect to the identity provider
+//! - POST /api/v1/auth/saml/acs - Assertion consumer service, starts a session
+//! - GET  /api/v1/auth/saml/metadata - Service provider metadata
+
+use crate::api::auth::log_login_attempt;
+use crate::api::middleware::{
+    ensure_ip_not_blocked, extract_client_ip, should_set_secure_cookie, ApiError, AppState,
+};
+use crate::services::{AbuseSignal, SamlError, SamlService};
+use axum::{
+    extract::{ConnectInfo, Query, State},
+    http::{header, HeaderMap, HeaderValue, StatusCode},
+    response::{IntoResponse, Response},
+    routing::{get, post},
+    Form, Router,
+};
+use serde::Deserialize;
+use std::net::SocketAddr;
+use std::sync::Arc;
+
+/// Build the public SAML router
+pub fn router() -> Router<AppState> {
+    Router::new()
+        .route("/login", get(login))
+        .route("/acs", post(assertion_consumer))
+        .route("/metadata", get(metadata))
+}
+
+/// Query parameters for starting a sign-in
+#[derive(Debug, Deserialize)]
+pub struct LoginQuery {
+    /// Local path to return to after signing in
+    pub redirect: Option<String>,
+}
+
+/// Form posted by the identity provider
+#[derive(Debug, Deserialize)]
+pub struct AcsForm {
+    #[serde(rename = "SAMLResponse")]
+    pub saml_response: String,
+    #[serde(rename = "RelayState")]
+    pub relay_state: Option<String>,
+}
+
+fn saml_service(state: &AppState) -> Result<&Arc<SamlService>, ApiError> {
+    state
+        .saml_service
+        .as_ref()
+        .ok_or_else(|| ApiError::not_found("SAML single sign-on is not enabled"))
+}
+
+/// Only same-site paths are accepted as redirect targets
+fn local_path(path: Option<&str>) -> Option<&str> {
+    path.filter(|p| p.starts_with('/') && !p.starts_with("//") && !p.contains('\\'))
+}
+
+fn map_saml_error(e: SamlError) -> ApiError {
+    match e {
+        SamlError::InvalidResponse(_) => ApiError::unauthorized(e.to_string()),
+        SamlError::Forbidden(msg) => ApiError::forbidden(msg),
+        SamlError::Internal(e) => ApiError::internal_error(e.to_string()),
+    }
+}
+
+fn redirect(location: &str) -> Result<HeaderValue, ApiError> {
+    HeaderValue::from_str(location).map_err(|_| ApiError::internal_error("Invalid redirect URL"))
+}
+
+/// GET /api/v1/auth/saml/login - Redirect the browser to the IdP
+async fn login(
+    State(state): State<AppState>,
+    Query(query): Query<LoginQuery>,
+) -> Result<Response, ApiError> {
+    let service = saml_service(&state)?;
+    let url = service
+        .login_url(local_path(query.redirect.as_deref()))
+        .await
+        .map_err(map_saml_error)?;
+    Ok((StatusCode::SEE_OTHER, [(header::LOCATION, redirect(&url)?)]).into_response())
+}
+
+/// POST /api/v1/auth/saml/acs - Verify the IdP response and sign in
+async fn assertion_consumer(
+    State(state): State<AppState>,
+    ConnectInfo(addr): ConnectInfo<SocketAddr>,
+    headers: HeaderMap,
+    Form(form): Form<AcsForm>,
+) -> Result<Response, ApiError> {
+    let service = saml_service(&state)?;
+    let ip_address = extract_client_ip(&headers, addr);
+    ensure_ip_not_blocked(&state, &ip_address).await?;
+    let user_agent = headers
+        .get(header::USER_AGENT)
+        .and_then(|h| h.to_str().ok())
+        .map(String::from);
+
+    let result = match service.process_response(&form.saml_response).await {
+        Ok(assertion) => service.sign_in(&assertion).await,
+        Err(e) => Err(e),
+    };
+    let user = match result {
+        Ok(user) => user,
+        Err(e) => {
+            if matches!(e, SamlError::InvalidResponse(_)) {
+                state
+                    .ip_reputation
+                    .record_str(&ip_address, AbuseSignal::FailedLogin)
+                    .await;
+            }
+            tracing::warn!(error = %e, "SAML sign-in failed");
+            log_login_attempt(
+                &state.pool,
+                "(saml)",
+                Some(&ip_address),
+                user_agent.as_deref(),
+                false,
+                Some("SAML sign-in failed"),
+            )
+            .await;
+            return Err(map_saml_error(e));
+        }
+    };
+
+    log_login_attempt(
+        &state.pool,
+        &user.username,
+        Some(&ip_address),
+        user_agent.as_deref(),
+        true,
+        Some("SAML"),
+    )
+    .await;
+
+    // The IdP vouches for the second factor, so no 2FA challenge is issued
+    let session = state
+        .user_service
+        .create_login_session(&user, Some(ip_address), user_agent)
+        .await
+        .map_err(|e| ApiError::internal_error(e.to_string()))?;
+
+    let is_secure = should_set_secure_cookie(&state, &headers, Some(addr)).await;
+    let secure_flag = if is_secure { "; Secure" } else { "" };
+    let csrf_token = crate::api::middleware::generate_csrf_token();
+
+    // Same cookies as a password login
+    let session_cookie = format!(
+        "session={}; Path=/; HttpOnly; SameSite=Lax; Max-Age={}{}",
+        session.id,
+        7 * 24 * 60 * 60,
+        secure_flag,
+    );
+    let csrf_cookie = format!(
+        "csrf_token={}; Path=/; SameSite=Lax; Max-Age={}{}",
+        csrf_token,
+        7 * 24 * 60 * 60,
+        secure_flag,
+    );
+
+    let target = local_path(form.relay_state.as_deref()).unwrap_or(service.login_redirect());
+    let mut response_headers = HeaderMap::new();
+    response_headers.insert(header::LOCATION, redirect(target)?);
+    response_headers.insert(
+        header::SET_COOKIE,
+        HeaderValue::from_str(&session_cookie)
+            .map_err(|_| ApiError::internal_error("Failed to build session cookie"))?,
+    );
+    response_headers.append(
+        header::SET_COOKIE,
+        HeaderValue::from_str(&csrf_cookie)
+            .map_err(|_| ApiError::internal_error("Failed to build CSRF cookie"))?,
+    );
+
+    Ok((StatusCode::SEE_OTHER, response_headers).into_response())
+}
+
+/// GET /api/v1/auth/saml/metadata - Service provider metadata XML
+async fn metadata(State(state): State<AppState>) -> Result<Response, ApiError> {
+    let service = saml_service(&state)?;
+    Ok((
+        [(header::CONTENT_TYPE, "application/samlmetadata+xml")],
+        service.metadata_xml(),
+    )
+        .into_response())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn only_local_paths_are_redirect_targets() {
+        assert_eq!(
+            local_path(Some("/manage/articles")),
+            Some("/manage/articles")
+        );
+        assert_eq!(local_path(Some("//evil.example.com")), None);
+        assert_eq!(local_path(Some("/\\evil.example.com")), None);
+        assert_eq!(local_path(Some("https://evil.example.com")), None);
+        assert_eq!(local_path(None), None);
+    }
+}
@@ -1,6 +1,8 @@
 //! SEO endpoints: sitemap.xml, robots.txt, RSS feed
 //!
-//! All endpoints are public and cacheable.
+//! All endpoints are public and cacheable. The bodies are built by
+//! [`robots_body`], [`build_sitemap`] and [`build_feed`], which the static
+//! site export reuses.
 
 use axum::{
     body::Body,
@@ -16,12 +18,15 @@ use crate::db::repositories::{
     SqlxArticleRepository, SqlxCategoryRepository, SqlxPageRepository, SqlxSettingsRepository,
     SqlxTagRepository, TagRepository,
 };
+use crate::db::DynDatabasePool;
 use crate::models::{ArticleSortBy, ArticleStatus};
+use crate::plugin::HookManager;
+use crate::services::robots;
+use crate::services::settings::SettingsService;
 
 /// Helper: get site_url from settings, fallback to empty string
-async fn get_site_url(state: &AppState) -> String {
-    state
-        .settings_service
+async fn get_site_url(settings: &SettingsService) -> String {
+    settings
         .get("site_url")
         .await
         .ok()
@@ -30,8 +35,8 @@ async fn get_site_url(state: &AppState) -> String {
 }
 
 /// Helper: get site_name from settings
-async fn get_site_name(state: &AppState) -> String {
-    let repo = SqlxSettingsRepository::new(state.pool.clone());
+async fn get_site_name(pool: &DynDatabasePool) -> String {
+    let repo = SqlxSettingsRepository::new(pool.clone());
     repo.get("site_name")
         .await
         .ok()
@@ -41,8 +46,8 @@ async fn get_site_name(state: &AppState) -> String {
 }
 
 /// Helper: get site_description from settings
-async fn get_site_description(state: &AppState) -> String {
-    let repo = SqlxSettingsRepository::new(state.pool.clone());
+async fn get_site_description(pool: &DynDatabasePool) -> String {
+    let repo = SqlxSettingsRepository::new(pool.clone());
     repo.get("site_description")
         .await
         .ok()
@@ -52,9 +57,8 @@ async fn get_site_description(state: &AppState) -> String {
 }
 
 /// Helper: build article URL based on permalink_structure setting
-async fn build_article_url(base: &str, id: i64, slug: &str, state: &AppState) -> String {
-    let permalink_structure = state
-        .settings_service
+async fn build_article_url(base: &str, id: i64, slug: &str, settings: &SettingsService) -> String {
+    let permalink_structure = settings
         .get(crate::services::settings::keys::PERMALINK_STRUCTURE)
         .await
         .ok()
@@ -93,16 +97,8 @@ fn rfc2822_datetime(dt: &DateTime<Utc>) -> String {
 // ============================================================================
 
 pub async fn robots_txt(State(state): State<AppState>) -> Response {
-    let site_url = get_site_url(&state).await;
-
-    let body = if site_url.is_empty() {
-        "User-agent: *\nAllow: /\nDisallow: /manage/\nDisallow: /api/\n".to_string()
-    } else {
-        format!(
-            "User-agent: *\nAllow: /\nDisallow: /manage/\nDisallow: /api/\n\nSitemap: {}/sitemap.xml\n",
-            site_url.trim_end_matches('/')
-        )
-    };
+    let settings = &state.settings_service;
+    let body = robots_body(settings, &get_site_url(settings).await).await;
 
     Response::builder()
         .status(StatusCode::OK)
@@ -112,18 +108,50 @@ pub async fn robots_txt(State(state): State<AppState>) -> Response {
         .unwrap()
 }
 
+/// robots.txt from the `robots_config` setting, linking the sitemap when
+/// `site_url` is known
+pub async fn robots_body(settings: &SettingsService, site_url: &str) -> String {
+    robots::load(settings).await.render(site_url)
+}
+
 // ============================================================================
 // GET /sitemap.xml
 // ============================================================================
 
 pub async fn sitemap_xml(State(state): State<AppState>) -> Response {
-    let site_url = get_site_url(&state).await;
-    if site_url.is_empty() {
+    let site_url = get_site_url(&state.settings_service).await;
+    let Some(xml) = build_sitemap(
+        &state.pool,
+        &state.settings_service,
+        &state.hook_manager,
+        &site_url,
+    )
+    .await
+    else {
         return Response::builder()
             .status(StatusCode::SERVICE_UNAVAILABLE)
             .header(header::CONTENT_TYPE, "text/plain")
             .body(Body::from("site_url not configured"))
             .unwrap();
+    };
+
+    Response::builder()
+        .status(StatusCode::OK)
+        .header(header::CONTENT_TYPE, "application/xml; charset=utf-8")
+        .header(header::CACHE_CONTROL, "public, max-age=3600")
+        .body(Body::from(xml))
+        .unwrap()
+}
+
+/// Sitemap of all public URLs under `site_url`; `None` when it is empty
+pub async fn build_sitemap(
+    pool: &DynDatabasePool,
+    settings: &SettingsService,
+    hooks: &HookManager,
+    site_url: &str,
+) -> Option<String> {
+    if site_url.is_empty() {
+        return None;
     }
     let base = site_url.trim_end_matches('/');
 
@@ -140,7 +168,7 @@ pub async fn sitemap_xml(State(state): State<AppState>) -> Response {
     ));
 
     // Published articles (up to 5000)
-    let article_repo = SqlxArticleRepository::new(state.pool.clone());
+    let article_repo = SqlxArticleRepository::new(pool.clone());
     if let Ok(articles) = article_repo
         .list_published(0, 5000, ArticleSortBy::default())
         .await
@@ -149,7 +177,7 @@ pub async fn sitemap_xml(State(state): State<AppState>) -> Response {
             if article.status != ArticleStatus::Published {
                 continue;
             }
-            let url = build_article_url(base, article.id, &article.slug, &state).await;
+            let url = build_article_url(base, article.id, &article.slug, settings).await;
             let lastmod = w3c_datetime(&article.updated_at);
             xml.push_str(&format!(
                 "  <url>\n    <loc>{}</loc>\n    <lastmod>{}</lastmod>\n    <changefreq>weekly</changefreq>\n    <priority>0.8</priority>\n  </url>\n",
@@ -159,7 +187,7 @@ pub async fn sitemap_xml(State(state): State<AppState>) -> Response {
     }
 
     // Published pages
-    let page_repo = SqlxPageRepository::new(state.pool.clone());
+    let page_repo = SqlxPageRepository::new(pool.clone());
     if let Ok(pages) = page_repo.list_published().await {
         for page in &pages {
             let url = format!("{}/{}", base, page.slug);
@@ -172,7 +200,7 @@ pub async fn sitemap_xml(State(state): State<AppState>) -> Response {
     }
 
     // Categories
-    let cat_repo = SqlxCategoryRepository::new(state.pool.clone());
+    let cat_repo = SqlxCategoryRepository::new(pool.clone());
     if let Ok(categories) = cat_repo.list().await {
         for cat in &categories {
             let url = format!("{}/categories/{}", base, cat.slug);
@@ -184,7 +212,7 @@ pub async fn sitemap_xml(State(state): State<AppState>) -> Response {
     }
 
     // Tags
-    let tag_repo = SqlxTagRepository::new(state.pool.clone());
+    let tag_repo = SqlxTagRepository::new(pool.clone());
     if let Ok(tags) = tag_repo.list().await {
         for tag in &tags {
             let url = format!("{}/tags/{}", base, tag.slug);
@@ -198,19 +226,11 @@ pub async fn sitemap_xml(State(state): State<AppState>) -> Response {
     xml.push_str("</urlset>\n");
 
     // Hook: sitemap_filter — allow plugins to modify sitemap XML
-    let hook_result = state
-        .hook_manager
-        .trigger("sitemap_filter", serde_json::json!({ "xml": xml }));
+    let hook_result = hooks.trigger("sitemap_filter", serde_json::json!({ "xml": xml }));
     if let Some(modified) = hook_result.get("xml").and_then(|v| v.as_str()) {
         xml = modified.to_string();
     }
-
-    Response::builder()
-        .status(StatusCode::OK)
-        .header(header::CONTENT_TYPE, "application/xml; charset=utf-8")
-        .header(header::CACHE_CONTROL, "public, max-age=3600")
-        .body(Body::from(xml))
-        .unwrap()
+    Some(xml)
 }
 
 // ============================================================================
@@ -218,19 +238,38 @@ pub async fn sitemap_xml(State(state): State<AppState>) -> Response {
 // ============================================================================
 
 pub async fn feed_xml(State(state): State<AppState>) -> Response {
-    let site_url = get_site_url(&state).await;
-    let base = if site_url.is_empty() {
-        ""
-    } else {
-        site_url.trim_end_matches('/')
-    };
-    let site_name = get_site_name(&state).await;
-    let site_desc = get_site_description(&state).await;
+    let site_url = get_site_url(&state.settings_service).await;
+    let xml = build_feed(
+        &state.pool,
+        &state.settings_service,
+        &state.hook_manager,
+        &site_url,
+    )
+    .await;
+
+    Response::builder()
+        .status(StatusCode::OK)
+        .header(header::CONTENT_TYPE, "application/rss+xml; charset=utf-8")
+        .header(header::CACHE_CONTROL, "public, max-age=1800")
+        .body(Body::from(xml))
+        .unwrap()
+}
+
+/// RSS 2.0 feed of the latest 50 published articles
+pub async fn build_feed(
+    pool: &DynDatabasePool,
+    settings: &SettingsService,
+    hooks: &HookManager,
+    site_url: &str,
+) -> String {
+    let base = site_url.trim_end_matches('/');
+    let site_name = get_site_name(pool).await;
+    let site_desc = get_site_description(pool).await;
 
     let mut xml = String::with_capacity(16384);
     xml.push_str(r#"<?xml version="1.0" encoding="UTF-8"?>"#);
     xml.push('\n');
-    xml.push_str(r#"<rss version="2.0" xmlns:atom="http://www.w3.org/2005/Atom" xmlns:dc="http://purl.org/dc/elements/1.1/" xmlns:content="http://purl.org/rss/1.0/modules/content/">"#);
+    xml.push_str(r#"<rss version="2.0" xmlns:atom="http://www.w3.org/2005/Atom" xmlns:dc="http://purl.org/dc/elements/1.1/" xmlns:content="http://purl.org/rss/1.0/modules/content/" xmlns:creativeCommons="http://backend.userland.com/creativeCommonsRssModule">"#);
     xml.push('\n');
     xml.push_str("<channel>\n");
     xml.push_str(&format!("  <title>{}</title>\n", xml_escape(&site_name)));
@@ -249,17 +288,21 @@ pub async fn feed_xml(State(state): State<AppState>) -> Response {
         "  <generator>Noteva {}</generator>\n",
         env!("CARGO_PKG_VERSION")
     ));
-    let lang = state
-        .settings_service
+    let lang = settings
         .get("site_language")
         .await
         .ok()
         .flatten()
         .unwrap_or_else(|| "zh-CN".to_string());
     xml.push_str(&format!("  <language>{}</language>\n", xml_escape(&lang)));
+    let default_license = crate::services::license::load(settings).await;
+    xml.push_str(&format!(
+        "  <copyright>{}</copyright>\n",
+        xml_escape(default_license.name())
+    ));
 
     // Latest 50 published articles
-    let article_repo = SqlxArticleRepository::new(state.pool.clone());
+    let article_repo = SqlxArticleRepository::new(pool.clone());
     if let Ok(articles) = article_repo
         .list_published(0, 50, ArticleSortBy::default())
         .await
@@ -278,18 +321,9 @@ pub async fn feed_xml(State(state): State<AppState>) -> Response {
                 continue;
             }
             let pub_date = article.published_at.unwrap_or(article.created_at);
-            let url = build_article_url(base, article.id, &article.slug, &state).await;
-
-            // Generate excerpt: strip markdown, limit 300 chars
-            let excerpt: String = article
-                .content
-                .replace('#', "")
-                .replace('*', "")
-                .replace('`', "")
-                .replace('\n', " ")
-                .chars()
-                .take(300)
-                .collect();
+            let url = build_article_url(base, article.id, &article.slug, settings).await;
+
+            let excerpt = article.excerpt_text();
 
             xml.push_str("  <item>\n");
             xml.push_str(&format!(
@@ -313,6 +347,22 @@ pub async fn feed_xml(State(state): State<AppState>) -> Response {
             xml.push_str("    <content:encoded><![CDATA[");
             xml.push_str(&article.content_html);
             xml.push_str("]]></content:encoded>\n");
+            let license = article.license.as_ref().unwrap_or(&default_license);
+            xml.push_str(&format!(
+                "    <dc:rights>{}</dc:rights>\n",
+                xml_escape(license.name())
+            ));
+            if let Some(license_url) = license.url() {
+                let license_url = if license_url.starts_with('/') {
+                    format!("{}{}", base, license_url)
+                } else {
+                    license_url.to_string()
+                };
+                xml.push_str(&format!(
+                    "    <creativeCommons:license>{}</creativeCommons:license>\n",
+                    xml_escape(&license_url)
+                ));
+            }
             if let Some(ref thumb) = article.thumbnail {
                 if !thumb.is_empty() {
                     let img_url = if thumb.starts_with("http") {
@@ -333,17 +383,9 @@ pub async fn feed_xml(State(state): State<AppState>) -> Response {
     xml.push_str("</channel>\n</rss>\n");
 
     // Hook: feed_filter — allow plugins to modify RSS XML
-    let hook_result = state
-        .hook_manager
-        .trigger("feed_filter", serde_json::json!({ "xml": xml }));
+    let hook_result = hooks.trigger("feed_filter", serde_json::json!({ "xml": xml }));
     if let Some(modified) = hook_result.get("xml").and_then(|v| v.as_str()) {
         xml = modified.to_string();
     }
-
-    Response::builder()
-        .status(StatusCode::OK)
-        .header(header::CONTENT_TYPE, "application/rss+xml; charset=utf-8")
-        .header(header::CACHE_CONTROL, "public, max-age=1800")
-        .body(Body::from(xml))
-        .unwrap()
+    xml
 }
@@ -0,0 +1,182 @@
+//! Article series API endpoints.
+//!
+//! - GET /api/v1/admin/series - All series
+//! - POST /api/v1/admin/series - Create a series
+//! - GET/PUT/DELETE /api/v1/admin/series/:id - Manage a series
+//! - GET/PUT /api/v1/admin/series/:id/articles - Articles of a series, in order
+//! - GET /api/v1/series - All series
+//! - GET /api/v1/series/:slug - One series with its published articles
+//!
+//! Article responses carry `series` with the previous and next article of
+//! the series, for themes to render series navigation.
+
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+    response::IntoResponse,
+    routing::get,
+    Json, Router,
+};
+use serde::Serialize;
+
+use crate::api::middleware::{ApiError, AppState};
+use crate::models::{Series, SeriesArticle, SeriesArticlesInput, SeriesInput, SeriesWithArticles};
+use crate::services::SeriesError;
+
+/// Build the series management router (requires admin)
+pub fn router() -> Router<AppState> {
+    Router::new()
+        .route("/", get(list_series).post(create_series))
+        .route(
+            "/{id}",
+            get(get_series).put(update_series).delete(delete_series),
+        )
+        .route("/{id}/articles", get(list_articles).put(set_articles))
+}
+
+/// Build the public series router
+pub fn public_router() -> Router<AppState> {
+    Router::new()
+        .route("/", get(list_series))
+        .route("/{slug}", get(public_series))
+}
+
+#[derive(Debug, Serialize)]
+struct SeriesListResponse {
+    series: Vec<Series>,
+}
+
+#[derive(Debug, Serialize)]
+struct SeriesResponse {
+    series: Series,
+}
+
+#[derive(Debug, Serialize)]
+struct ArticlesResponse {
+    articles: Vec<SeriesArticle>,
+}
+
+#[derive(Debug, Serialize)]
+struct PublicSeriesResponse {
+    series: SeriesWithArticles,
+}
+
+fn map_series_error(e: SeriesError) -> ApiError {
+    match e {
+        SeriesError::NotFound(_) => ApiError::not_found(e.to_string()),
+        SeriesError::Validation(_) => ApiError::validation_error(e.to_string()),
+        SeriesError::Internal(e) => ApiError::internal_error(e.to_string()),
+    }
+}
+
+async fn list_series(State(state): State<AppState>) -> Result<impl IntoResponse, ApiError> {
+    let series = state
+        .series_service
+        .list()
+        .await
+        .map_err(map_series_error)?;
+    Ok(Json(SeriesListResponse { series }))
+}
+
+async fn get_series(
+    State(state): State<AppState>,
+    Path(id): Path<i64>,
+) -> Result<impl IntoResponse, ApiError> {
+    let series = state
+        .series_service
+        .get(id)
+        .await
+        .map_err(map_series_error)?;
+    Ok(Json(SeriesResponse { series }))
+}
+
+async fn create_series(
+    State(state): State<AppState>,
+    Json(input): Json<SeriesInput>,
+) -> Result<impl IntoResponse, ApiError> {
+    let series = state
+        .series_service
+        .create(input)
+        .await
+        .map_err(map_series_error)?;
+    Ok((StatusCode::CREATED, Json(SeriesResponse { series })))
+}
+
+async fn update_series(
+    State(state): State<AppState>,
+    Path(id): Path<i64>,
+    Json(input): Json<SeriesInput>,
+) -> Result<impl IntoResponse, ApiError> {
+    let series = state
+        .series_service
+        .update(id, input)
+        .await
+        .map_err(map_series_error)?;
+    Ok(Json(SeriesResponse { series }))
+}
+
+async fn delete_series(
+    State(state): State<AppState>,
+    Path(id): Path<i64>,
+) -> Result<impl IntoResponse, ApiError> {
+    state
+        .series_service
+        .delete(id)
+        .await
+        .map_err(map_series_error)?;
+    Ok(StatusCode::NO_CONTENT)
+}
+
+async fn list_articles(
+    State(state): State<AppState>,
+    Path(id): Path<i64>,
+) -> Result<impl IntoResponse, ApiError> {
+    let articles = state
+        .series_service
+        .articles(id)
+        .await
+        .map_err(map_series_error)?;
+    Ok(Json(ArticlesResponse { articles }))
+}
+
+/// Body: `{"article_ids": [3, 1, 2]}`, in reading order
+async fn set_articles(
+    State(state): State<AppState>,
+    Path(id): Path<i64>,
+    Json(input): Json<SeriesArticlesInput>,
+) -> Result<impl IntoResponse, ApiError> {
+    let articles = state
+        .series_service
+        .set_articles(id, input)
+        .await
+        .map_err(map_series_error)?;
+    Ok(Json(ArticlesResponse { articles }))
+}
+
+async fn public_series(
+    State(state): State<AppState>,
+    Path(slug): Path<String>,
+) -> Result<impl IntoResponse, ApiError> {
+    let series = state
+        .series_service
+        .get_with_articles(&slug)
+        .await
+        .map_err(map_series_error)?;
+    Ok(Json(PublicSeriesResponse { series }))
+}
+
+/// Series navigation of an article; `None` on failure so a broken series
+/// never breaks the article itself
+pub(crate) async fn navigation(
+    state: &AppState,
+    article_id: i64,
+) -> Option<crate::models::SeriesNavigation> {
+    state
+        .series_service
+        .navigation(article_id)
+        .await
+        .unwrap_or_else(|e| {
+            tracing::warn!("Failed to load series of article {}: {}", article_id, e);
+            None
+        })
+}
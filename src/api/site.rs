@@ -6,7 +6,11 @@
 use axum::{extract::State, routing::get, Json, Router};
 use serde::{Deserialize, Serialize};
 
-use crate::api::middleware::{AppState, AuthenticatedUser};
+use crate::api::middleware::{ApiError, AppState, AuthenticatedUser};
+use crate::api::static_files::{admin_frontend, AdminFrontend};
+use crate::models::InputFormat;
+use crate::services::announcement::{self, Announcement};
+use crate::services::markup::blocks;
 
 /// Response for public site info
 #[derive(Debug, Serialize)]
@@ -35,6 +39,8 @@ pub struct SiteInfoResponse {
     pub friend_links_nav_enabled: bool,
     /// Whether the built-in about page appears in theme navigation.
     pub about_nav_enabled: bool,
+    /// Announcement banners currently inside their start/end window
+    pub announcements: Vec<Announcement>,
     pub stats: SiteStats,
 }
 
@@ -51,6 +57,9 @@ pub struct SiteStats {
 #[derive(Debug, Deserialize)]
 pub struct RenderRequest {
     pub content: String,
+    /// Article input format; Markdown when missing or unknown
+    #[serde(default)]
+    pub input_format: Option<String>,
 }
 
 /// Response for rendered content
@@ -59,13 +68,58 @@ pub struct RenderResponse {
     pub html: String,
 }
 
+/// Request for converting content between input formats
+#[derive(Debug, Deserialize)]
+pub struct ConvertRequest {
+    pub content: String,
+    /// `markdown` or `blocks`
+    pub from: String,
+    /// `markdown` or `blocks`
+    pub to: String,
+}
+
+/// Response for converted content
+#[derive(Debug, Serialize)]
+pub struct ConvertResponse {
+    pub content: String,
+}
+
+/// Response for the embedded admin frontend
+#[derive(Debug, Serialize)]
+pub struct FrontendInfoResponse {
+    pub api_version: &'static str,
+    #[serde(flatten)]
+    pub frontend: &'static AdminFrontend,
+    /// Whether the frontend was built from the same release as the server;
+    /// null when the build recorded no version
+    pub compatible: Option<bool>,
+}
+
 /// Build the public site router
 pub fn router() -> Router<AppState> {
-    Router::new().route("/info", get(get_site_info))
+    Router::new()
+        .route("/info", get(get_site_info))
+        .route("/frontend", get(get_frontend_info))
 }
 
+pub use convert_content as convert_content_handler;
 pub use render_content as render_content_handler;
 
+/// GET /api/v1/site/frontend - Version, build hash and subresource integrity
+/// values of the embedded admin frontend
+async fn get_frontend_info() -> Json<FrontendInfoResponse> {
+    let api_version = env!("CARGO_PKG_VERSION");
+    let frontend = admin_frontend();
+    Json(FrontendInfoResponse {
+        api_version,
+        frontend,
+        compatible: frontend
+            .version
+            .as_deref()
+            .map(|version| version == api_version),
+    })
+}
+
 /// GET /api/v1/site/info - Get public site information
 ///
 /// No authentication required.
@@ -188,6 +242,7 @@ async fn get_site_info(State(state): State<AppState>) -> Json<SiteInfoResponse>
             .flatten(),
     );
     let about_nav_enabled = state.about_service.is_nav_enabled().await;
+    let announcements = announcement::active(&state.settings_service).await;
 
     Json(SiteInfoResponse {
         version: env!("CARGO_PKG_VERSION").to_string(),
@@ -208,6 +263,7 @@ async fn get_site_info(State(state): State<AppState>) -> Json<SiteInfoResponse>
         show_comments,
         friend_links_nav_enabled,
         about_nav_enabled,
+        announcements,
         stats: SiteStats {
             total_articles,
             total_categories,
@@ -217,7 +273,7 @@ async fn get_site_info(State(state): State<AppState>) -> Json<SiteInfoResponse>
     })
 }
 
-/// POST /api/v1/site/render - Render markdown content with shortcode processing
+/// POST /api/v1/site/render - Render article content with shortcode processing
 ///
 /// Used by admin preview to show how content will look with shortcodes processed.
 pub async fn render_content(
@@ -225,9 +281,44 @@ pub async fn render_content(
     _user: AuthenticatedUser,
     Json(req): Json<RenderRequest>,
 ) -> Json<RenderResponse> {
+    let input_format = req
+        .input_format
+        .as_deref()
+        .and_then(crate::models::InputFormat::parse)
+        .unwrap_or_default();
     // Use article service to render with shortcode processing
     let html = state
         .article_service
-        .render_markdown_with_shortcodes(&req.content, None, None);
+        .render_source(input_format, &req.content, None, None);
     Json(RenderResponse { html })
 }
+
+/// POST /api/v1/site/convert - Convert content between Markdown and blocks
+///
+/// Lets block editors import Markdown articles and export block documents.
+pub async fn convert_content(
+    _user: AuthenticatedUser,
+    Json(req): Json<ConvertRequest>,
+) -> Result<Json<ConvertResponse>, ApiError> {
+    let format = |name: &str| {
+        InputFormat::parse(name)
+            .filter(|f| matches!(f, InputFormat::Markdown | InputFormat::Blocks))
+            .ok_or_else(|| {
+                ApiError::validation_error(format!(
+                    "Cannot convert '{}', expected markdown or blocks",
+                    name
+                ))
+            })
+    };
+    let content = match (format(&req.from)?, format(&req.to)?) {
+        (InputFormat::Markdown, InputFormat::Blocks) => {
+            serde_json::to_string(&blocks::from_markdown(&req.content))
+                .map_err(|e| ApiError::internal_error(e.to_string()))?
+        }
+        (InputFormat::Blocks, InputFormat::Markdown) => blocks::parse(&req.content)
+            .map(|b| blocks::to_markdown(&b))
+            .map_err(|e| ApiError::validation_error(format!("Invalid blocks source: {}", e)))?,
+        _ => req.content,
+    };
+    Ok(Json(ConvertResponse { content }))
+}
@@ -3,16 +3,25 @@
 use axum::{
     body::Body,
     extract::State,
-    http::{header, HeaderName, HeaderValue, StatusCode, Uri},
+    http::{header, HeaderMap, HeaderName, HeaderValue, StatusCode, Uri},
     response::Response,
 };
-use rust_embed::RustEmbed;
+use data_encoding::{BASE64, HEXLOWER};
+use once_cell::sync::Lazy;
+use rust_embed::{EmbeddedFile, RustEmbed};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::BTreeMap;
 use std::path::{Component, Path, PathBuf};
 use tokio::fs;
 use urlencoding;
 
 use crate::api::middleware::AppState;
 use crate::db::repositories::{SettingsRepository, SqlxSettingsRepository};
+use crate::models::{normalize_lang, ContentAlternate, ContentKind};
+use crate::services::translation::{DEFAULT_SITE_LANGUAGE, SITE_LANGUAGE_KEY};
+use crate::theme::social_meta::{self, SiteSocial, SocialPage};
+use crate::theme::structured_data::{self, ArticleData, StructuredData};
 
 /// Embedded admin files (management interface)
 #[derive(RustEmbed)]
@@ -26,8 +35,71 @@ struct AdminAssets;
 #[include = "*"]
 struct DefaultThemeAssets;
 
+/// Written into `web/dist/` by the admin's Vite build
+const BUILD_INFO_FILE: &str = "build-info.json";
+
+/// Sent with every embedded admin file so a stale tab can tell it is outdated
+const FRONTEND_BUILD_HEADER: &str = "x-noteva-frontend-build";
+
+/// Contents of `build-info.json`
+#[derive(Debug, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct BuildInfo {
+    version: Option<String>,
+    built_at: Option<String>,
+}
+
+/// Identity of the embedded admin frontend
+#[derive(Debug, Clone, Serialize)]
+pub struct AdminFrontend {
+    /// `version` of `web/package.json` when the frontend was built
+    pub version: Option<String>,
+    pub built_at: Option<String>,
+    /// Hash over every embedded file; changes with any rebuild
+    pub build: String,
+    /// Subresource integrity values of the scripts and stylesheets, by URL
+    pub integrity: BTreeMap<String, String>,
+}
+
+static ADMIN_FRONTEND: Lazy<AdminFrontend> = Lazy::new(|| {
+    let mut paths: Vec<_> = AdminAssets::iter().collect();
+    paths.sort();
+    let mut hasher = Sha256::new();
+    let mut integrity = BTreeMap::new();
+    for path in &paths {
+        let Some(file) = AdminAssets::get(path) else {
+            continue;
+        };
+        let hash = file.metadata.sha256_hash();
+        hasher.update(path.as_bytes());
+        hasher.update([0]);
+        hasher.update(hash);
+        if path.ends_with(".js") || path.ends_with(".css") {
+            integrity.insert(
+                format!("/manage/{}", path),
+                format!("sha256-{}", BASE64.encode(&hash)),
+            );
+        }
+    }
+    let info: BuildInfo = AdminAssets::get(BUILD_INFO_FILE)
+        .and_then(|file| serde_json::from_slice(&file.data).ok())
+        .unwrap_or_default();
+
+    AdminFrontend {
+        version: info.version,
+        built_at: info.built_at,
+        build: HEXLOWER.encode(&hasher.finalize()[..8]),
+        integrity,
+    }
+});
+
+/// The admin frontend compiled into this binary
+pub fn admin_frontend() -> &'static AdminFrontend {
+    &ADMIN_FRONTEND
+}
+
 /// Serve static files based on path
-pub async fn serve_static(State(state): State<AppState>, uri: Uri) -> Response {
+pub async fn serve_static(State(state): State<AppState>, headers: HeaderMap, uri: Uri) -> Response {
     let path = uri.path();
     // URL decode the path to handle encoded characters like %5B%5D -> []
     let decoded_path = urlencoding::decode(path).unwrap_or_else(|_| path.into());
@@ -43,6 +115,11 @@ pub async fn serve_static(State(state): State<AppState>, uri: Uri) -> Response {
         return serve_theme_static(path, &state).await;
     }
 
+    // /embed/comments.js -> comment widget for external sites
+    if path == "/embed/comments.js" {
+        return build_response("comments.js", include_bytes!("comment-embed.js"));
+    }
+
     // /noteva-sdk.js and /noteva-sdk.css -> serve SDK files
     if path == "/noteva-sdk.js" || path == "/noteva-sdk.css" {
         return serve_sdk_file(path).await;
@@ -54,14 +131,14 @@ pub async fn serve_static(State(state): State<AppState>, uri: Uri) -> Response {
         let asset_path = path.trim_start_matches('/');
         if asset_path.contains('.') && !asset_path.contains('/') {
             if let Some(content) = AdminAssets::get(asset_path) {
-                return build_response(asset_path, &content.data);
+                return admin_response(asset_path, &content, &headers);
             }
         }
     }
 
     // /manage/* -> admin assets
     if path.starts_with("/manage") {
-        return serve_admin(path, &state).await;
+        return serve_admin(path, &state, &headers).await;
     }
 
     // /_next/* -> theme assets (Next.js based themes)
@@ -94,10 +171,95 @@ pub async fn serve_static(State(state): State<AppState>, uri: Uri) -> Response {
         return not_found();
     }
 
+    // Manual redirects and old article and tag slugs win over the theme's 404 page
+    if let Some(response) = redirect_response(path, uri.query(), &state).await {
+        return response;
+    }
+
     // Everything else -> theme assets
     serve_theme(path, &state).await
 }
 
+/// Redirect for a path the theme would otherwise render, if any
+async fn redirect_response(path: &str, query: Option<&str>, state: &AppState) -> Option<Response> {
+    let (status, target) = match state.redirect_service.find(path).await {
+        Ok(Some(redirect)) => (redirect.status_code, redirect.target),
+        _ => match previous_slug_target(path, state).await {
+            Some(target) => (301, target),
+            None => (301, previous_tag_target(path, state).await?),
+        },
+    };
+    let mut location = encode_location(&target);
+    if let Some(query) = query.filter(|q| !q.is_empty() && !target.contains('?')) {
+        location.push('?');
+        location.push_str(query);
+    }
+    Response::builder()
+        .status(StatusCode::from_u16(status).unwrap_or(StatusCode::MOVED_PERMANENTLY))
+        .header(header::LOCATION, location)
+        .body(Body::empty())
+        .ok()
+}
+
+/// Current URL of the article whose old slug is in `path`
+async fn previous_slug_target(path: &str, state: &AppState) -> Option<String> {
+    use crate::services::settings::{
+        generate_article_url, keys::PERMALINK_STRUCTURE, match_article_slug,
+    };
+
+    let structure = state
+        .settings_service
+        .get(PERMALINK_STRUCTURE)
+        .await
+        .ok()
+        .flatten()
+        .unwrap_or_else(|| "/posts/{slug}".to_string());
+    let slug = match_article_slug(&structure, path)?;
+    let article = state
+        .article_service
+        .get_by_previous_slug(slug)
+        .await
+        .ok()
+        .flatten()
+        .filter(|a| a.status == crate::models::ArticleStatus::Published)?;
+    Some(generate_article_url(
+        &structure,
+        article.id,
+        &article.slug,
+        article.published_at.as_ref(),
+    ))
+}
+
+/// Current URL of the tag whose former slug is in `path` (`/tags/{slug}/...`)
+async fn previous_tag_target(path: &str, state: &AppState) -> Option<String> {
+    let rest = path.strip_prefix("/tags/")?;
+    let slug = rest.split('/').next().filter(|s| !s.is_empty())?;
+    // A tag created with the slug since takes precedence
+    if let Ok(Some(_)) = state.tag_service.get_by_slug(slug).await {
+        return None;
+    }
+    let tag = state
+        .tag_service
+        .get_by_previous_slug(slug)
+        .await
+        .ok()
+        .flatten()?;
+    Some(format!("/tags/{}{}", tag.slug, &rest[slug.len()..]))
+}
+
+/// Percent-encode the bytes a Location header cannot carry as-is
+fn encode_location(location: &str) -> String {
+    let mut encoded = String::with_capacity(location.len());
+    for byte in location.bytes() {
+        if byte.is_ascii_graphic() {
+            encoded.push(byte as char);
+        } else {
+            encoded.push_str(&format!("%{:02X}", byte));
+        }
+    }
+    encoded
+}
+
 /// Serve theme static files (preview images, etc.) from disk
 /// Path format: /themes/{theme_name}/{file}
 async fn serve_theme_static(path: &str, state: &AppState) -> Response {
@@ -182,6 +344,10 @@ async fn serve_uploads(path: &str) -> Response {
     if !canonical.starts_with(&uploads_dir) {
         return not_found();
     }
+    // Attachments are only served through their counted, gated download URL
+    if canonical.starts_with(uploads_dir.join(crate::models::ATTACHMENTS_DIR)) {
+        return not_found();
+    }
 
     match fs::read(&canonical).await {
         Ok(contents) => {
@@ -237,7 +403,7 @@ async fn serve_sdk_file(path: &str) -> Response {
 }
 
 /// Serve admin files
-async fn serve_admin(path: &str, state: &AppState) -> Response {
+async fn serve_admin(path: &str, state: &AppState, headers: &HeaderMap) -> Response {
     let asset_path = path.trim_start_matches('/');
     let normalized_path = asset_path.trim_end_matches('/');
 
@@ -278,14 +444,14 @@ async fn serve_admin(path: &str, state: &AppState) -> Response {
     // Try exact file match (static assets like JS, CSS, images)
     if !relative_path.is_empty() {
         if let Some(content) = AdminAssets::get(relative_path) {
-            return build_response(relative_path, &content.data);
+            return admin_response(relative_path, &content, headers);
         }
     }
 
     // SPA fallback: serve index.html for all /manage/* routes
     // React Router handles client-side routing
     if let Some(content) = AdminAssets::get("index.html") {
-        return build_response("index.html", &content.data);
+        return admin_response("index.html", &content, headers);
     }
 
     not_found()
@@ -467,6 +633,15 @@ async fn inject_seo_into_html(
             "permalink_structure",
             "about_nav_enabled",
             "friend_links_nav_enabled",
+            crate::services::webmention::WEBMENTION_ENABLED_KEY,
+            structured_data::PUBLISHER_TYPE_KEY,
+            structured_data::PUBLISHER_NAME_KEY,
+            structured_data::PUBLISHER_LOGO_KEY,
+            structured_data::ARTICLE_TYPE_KEY,
+            social_meta::DEFAULT_IMAGE_KEY,
+            social_meta::TWITTER_SITE_KEY,
+            SITE_LANGUAGE_KEY,
+            crate::services::license::DEFAULT_LICENSE_KEY,
         ])
         .await
         .unwrap_or_default();
@@ -511,6 +686,12 @@ async fn inject_seo_into_html(
         })
         .unwrap_or(false);
     let is_id_mode = permalink_structure.contains("{id}");
+    let webmention_enabled = settings
+        .get(crate::services::webmention::WEBMENTION_ENABLED_KEY)
+        .is_some_and(|value| value == "true");
+    let default_license = crate::services::license::from_settings(&settings);
+
+    let announcements = crate::services::announcement::active(&state.settings_service).await;
 
     // Build config JSON
     let config_json = serde_json::json!({
@@ -520,7 +701,8 @@ async fn inject_seo_into_html(
         "site_logo": site_logo,
         "site_footer": site_footer,
         "about_nav_enabled": about_nav_enabled,
-        "friend_links_nav_enabled": friend_links_nav_enabled
+        "friend_links_nav_enabled": friend_links_nav_enabled,
+        "announcements": announcements
     });
     let nav_items = crate::api::nav::visible_nav_tree_with_hooks(state)
         .await
@@ -529,6 +711,8 @@ async fn inject_seo_into_html(
     let version = env!("CARGO_PKG_VERSION");
 
     let base_url = site_url.trim_end_matches('/');
+    let schema = StructuredData::from_settings(&settings, base_url);
+    let social = SiteSocial::from_settings(&settings, base_url);
 
     // Try to fetch article data for SEO if this is a post page
     let article_seo = if asset_path.starts_with("posts/") && !asset_path.contains('.') {
@@ -565,11 +749,34 @@ async fn inject_seo_into_html(
         None
     };
 
+    // Language variants of the article or page
+    let alternates = if let Some(seo) = &article_seo {
+        crate::api::translations::alternates(state, ContentKind::Article, seo.id).await
+    } else if let Some(seo) = &page_seo {
+        crate::api::translations::alternates(state, ContentKind::Page, seo.id).await
+    } else {
+        Vec::new()
+    };
+    let site_lang = settings
+        .get(SITE_LANGUAGE_KEY)
+        .map(String::as_str)
+        .and_then(normalize_lang)
+        .unwrap_or_else(|| DEFAULT_SITE_LANGUAGE.to_string());
+    let hreflang = hreflang_links(base_url, &alternates, &site_lang);
+
     // Build meta tags for SEO
     let (title_tag, meta_tags, body_content) = if let Some(ref seo) = article_seo {
-        let title = format!("{} - {}", seo.title, site_name);
-        let description = seo.excerpt.replace('"', "&quot;");
-        let canonical_url = if base_url.is_empty() {
+        let title = seo
+            .meta
+            .title
+            .clone()
+            .unwrap_or_else(|| format!("{} - {}", seo.title, site_name));
+        let share_title = seo.meta.title.as_deref().unwrap_or(&seo.title);
+        let summary = seo.meta.description.as_deref().unwrap_or(&seo.excerpt);
+        let description = summary.replace('"', "&quot;");
+        let canonical_url = if let Some(url) = seo.meta.absolute_canonical_url(base_url) {
+            url
+        } else if base_url.is_empty() {
             String::new()
         } else {
             let identifier = if is_id_mode {
@@ -579,17 +786,8 @@ async fn inject_seo_into_html(
             };
             format!("{}/posts/{}", base_url, identifier)
         };
-        let og_image = seo.thumbnail.as_deref().unwrap_or("").to_string();
-        let og_image_full = if og_image.is_empty() {
-            String::new()
-        } else if og_image.starts_with("http") {
-            og_image.clone()
-        } else if !base_url.is_empty() {
-            format!("{}{}", base_url, og_image)
-        } else {
-            og_image.clone()
-        };
-
+        let license = seo.license.as_ref().unwrap_or(&default_license);
+        let license_url = license.url().map(|url| schema.absolute_url(url));
         let mut meta = format!(
             r#"<title>{}</title>
 <meta name="description" content="{}">
@@ -598,73 +796,64 @@ async fn inject_seo_into_html(
             html_escape(&description),
             html_escape(&canonical_url),
         );
-        // Open Graph
-        meta.push_str(&format!(
-            r#"
-<meta property="og:title" content="{}">
-<meta property="og:description" content="{}">
-<meta property="og:type" content="article">
-<meta property="og:site_name" content="{}">"#,
-            html_escape(&seo.title),
-            html_escape(&description),
-            html_escape(&site_name),
-        ));
-        if !canonical_url.is_empty() {
-            meta.push_str(&format!(
-                "\n<meta property=\"og:url\" content=\"{}\">",
-                html_escape(&canonical_url)
-            ));
+        if seo.meta.noindex {
+            meta.push_str("\n<meta name=\"robots\" content=\"noindex\">");
         }
-        if !og_image_full.is_empty() {
+        if let Some(url) = &license_url {
             meta.push_str(&format!(
-                "\n<meta property=\"og:image\" content=\"{}\">",
-                html_escape(&og_image_full)
-            ));
-        }
-        meta.push_str(&format!(
-            r#"
-<meta property="article:published_time" content="{}">
-<meta property="article:modified_time" content="{}">"#,
-            seo.published_at, seo.updated_at,
-        ));
-        // Twitter Card
-        meta.push_str(&format!(
-            r#"
-<meta name="twitter:card" content="{}">
-<meta name="twitter:title" content="{}">
-<meta name="twitter:description" content="{}">"#,
-            if og_image_full.is_empty() {
-                "summary"
-            } else {
-                "summary_large_image"
-            },
-            html_escape(&seo.title),
-            html_escape(&description),
-        ));
-        if !og_image_full.is_empty() {
-            meta.push_str(&format!(
-                "\n<meta name=\"twitter:image\" content=\"{}\">",
-                html_escape(&og_image_full)
+                "\n<link rel=\"license\" href=\"{}\">",
+                html_escape(url)
             ));
         }
+        // Open Graph and Twitter Card
+        let published_time = seo.published_at.map(|d| d.to_rfc3339());
+        let modified_time = seo.updated_at.to_rfc3339();
+        meta.push('\n');
+        meta.push_str(&social.meta_tags(&SocialPage {
+            article: true,
+            title: share_title,
+            description: summary,
+            url: &canonical_url,
+            image: seo.thumbnail.as_deref(),
+            author: seo.author.as_deref(),
+            published_time: published_time.as_deref(),
+            modified_time: Some(&modified_time),
+        }));
         // JSON-LD structured data
-        let json_ld_image = if og_image_full.is_empty() {
-            "null".to_string()
-        } else {
-            format!("\"{}\"", og_image_full.replace('"', "\\\""))
-        };
-        meta.push_str(&format!(
-            r#"
-<script type="application/ld+json">
-{{"@context":"https://schema.org","@type":"BlogPosting","headline":"{}","description":"{}","datePublished":"{}","dateModified":"{}","image":{},"url":"{}","author":{{"@type":"Organization","name":"{}"}}}}</script>"#,
-            seo.title.replace('"', "\\\""),
-            seo.excerpt.replace('"', "\\\"").chars().take(160).collect::<String>(),
-            seo.published_at,
-            seo.updated_at,
-            json_ld_image,
-            canonical_url.replace('"', "\\\""),
-            site_name.replace('"', "\\\""),
-        ));
+        let mut graph = vec![schema.article(&ArticleData {
+            headline: share_title,
+            description: &summary.chars().take(160).collect::<String>(),
+            url: &canonical_url,
+            image: seo.thumbnail.as_deref(),
+            published_at: seo.published_at,
+            updated_at: Some(seo.updated_at),
+            author: seo.author.as_deref(),
+            section: seo.category.as_ref().map(|(name, _)| name.as_str()),
+            keywords: &seo.tags,
+            license: license_url.as_deref(),
+        })];
+        if !base_url.is_empty() {
+            let mut trail = Vec::new();
+            if let Some((name, slug)) = &seo.category {
+                trail.push((
+                    name.as_str(),
+                    format!("{}/categories?c={}", base_url, urlencoding::encode(slug)),
+                ));
+            }
+            trail.push((share_title, canonical_url.clone()));
+            graph.push(schema.breadcrumbs(&trail));
+        }
+        if !seo.faq.is_empty() {
+            let questions: Vec<(&str, &str)> = seo
+                .faq
+                .iter()
+                .map(|(q, a)| (q.as_str(), a.as_str()))
+                .collect();
+            graph.push(structured_data::faq_page(&questions));
+        }
+        meta.push('\n');
+        meta.push_str(&structured_data::script_tag(graph));
+        meta.push_str(&hreflang);
         // RSS feed discovery
         if !base_url.is_empty() {
             meta.push_str(&format!(
@@ -672,11 +861,27 @@ async fn inject_seo_into_html(
                 html_escape(&site_name), base_url
             ));
         }
+        // Webmention endpoint discovery
+        if webmention_enabled && !base_url.is_empty() {
+            meta.push_str(&format!(
+                "\n<link rel=\"webmention\" href=\"{}/webmention\">",
+                base_url
+            ));
+        }
         // Inject article content into <div id="root"> for crawlers
+        let license_notice = match &license_url {
+            Some(url) => format!(
+                r#"<a rel="license" href="{}">{}</a>"#,
+                html_escape(url),
+                html_escape(license.name())
+            ),
+            None => html_escape(license.name()),
+        };
         let body = format!(
-            r#"<article style="display:none" data-noteva-seo="true"><h1>{}</h1><div>{}</div></article>"#,
+            r#"<article style="display:none" data-noteva-seo="true"><h1>{}</h1><div>{}</div><footer>{}</footer></article>"#,
             html_escape(&seo.title),
             seo.content_html,
+            license_notice,
         );
         (Some(title), meta, body)
     } else if let Some(ref seo) = page_seo {
@@ -691,33 +896,26 @@ async fn inject_seo_into_html(
         let mut meta = format!(
             r#"<title>{}</title>
 <meta name="description" content="{}">
-<link rel="canonical" href="{}">
-<meta property="og:title" content="{}">
-<meta property="og:description" content="{}">
-<meta property="og:type" content="website">
-<meta property="og:site_name" content="{}">"#,
+<link rel="canonical" href="{}">"#,
             html_escape(&title),
             html_escape(&description),
             html_escape(&canonical_url),
-            html_escape(&seo.title),
-            html_escape(&description),
-            html_escape(&site_name),
         );
-        if !canonical_url.is_empty() {
-            meta.push_str(&format!(
-                "\n<meta property=\"og:url\" content=\"{}\">",
-                html_escape(&canonical_url)
-            ));
-        }
-        meta.push_str(&format!(
-            r#"
-<meta name="twitter:card" content="summary">
-<meta name="twitter:title" content="{}">
-<meta name="twitter:description" content="{}">"#,
-            html_escape(&seo.title),
-            html_escape(&description),
-        ));
+        meta.push('\n');
+        meta.push_str(&social.meta_tags(&SocialPage {
+            title: &seo.title,
+            description: &seo.excerpt,
+            url: &canonical_url,
+            ..Default::default()
+        }));
+        meta.push_str(&hreflang);
         if !base_url.is_empty() {
+            let graph = vec![
+                schema.web_page(&seo.title, &seo.excerpt, &canonical_url),
+                schema.breadcrumbs(&[(seo.title.as_str(), canonical_url.clone())]),
+            ];
+            meta.push('\n');
+            meta.push_str(&structured_data::script_tag(graph));
             meta.push_str(&format!(
                 "\n<link rel=\"alternate\" type=\"application/rss+xml\" title=\"{}\" href=\"{}/feed.xml\">",
                 html_escape(&site_name), base_url
@@ -755,34 +953,18 @@ async fn inject_seo_into_html(
                 "\n<link rel=\"canonical\" href=\"{}\">",
                 html_escape(&canonical_url)
             ));
-            meta.push_str(&format!(
-                r#"
-<meta property="og:title" content="{}">
-<meta property="og:description" content="{}">
-<meta property="og:type" content="website">
-<meta property="og:url" content="{}">
-<meta property="og:site_name" content="{}">
-<meta name="twitter:card" content="summary">
-<meta name="twitter:title" content="{}">
-<meta name="twitter:description" content="{}">"#,
-                html_escape(&home_title),
-                html_escape(&site_description),
-                html_escape(&canonical_url),
-                html_escape(&site_name),
-                html_escape(&home_title),
-                html_escape(&site_description),
-            ));
         }
+        meta.push('\n');
+        meta.push_str(&social.meta_tags(&SocialPage {
+            title: &home_title,
+            description: &site_description,
+            url: &canonical_url,
+            ..Default::default()
+        }));
         // JSON-LD for website
         if !base_url.is_empty() {
-            meta.push_str(&format!(
-                r#"
-<script type="application/ld+json">
-{{"@context":"https://schema.org","@type":"WebSite","name":"{}","description":"{}","url":"{}"}}</script>"#,
-                site_name.replace('"', "\\\""),
-                site_description.replace('"', "\\\""),
-                base_url.replace('"', "\\\""),
-            ));
+            meta.push('\n');
+            meta.push_str(&structured_data::script_tag(vec![schema.website()]));
         }
         if !base_url.is_empty() {
             meta.push_str(&format!(
@@ -858,14 +1040,25 @@ struct ArticleSeo {
     title: String,
     excerpt: String,
     content_html: String,
-    published_at: String,
-    updated_at: String,
+    published_at: Option<chrono::DateTime<chrono::Utc>>,
+    updated_at: chrono::DateTime<chrono::Utc>,
     thumbnail: Option<String>,
     slug: String,
+    meta: crate::theme::SeoMeta,
+    /// Display name of the author
+    author: Option<String>,
+    /// Category name and slug
+    category: Option<(String, String)>,
+    tags: Vec<String>,
+    /// Question and answer HTML of embedded FAQ topics
+    faq: Vec<(String, String)>,
+    /// The article's own license; `None` uses the site default
+    license: Option<crate::models::ContentLicense>,
 }
 
 /// Page SEO data
 struct PageSeo {
+    id: i64,
     title: String,
     excerpt: String,
     content_html: String,
@@ -878,7 +1071,12 @@ async fn fetch_article_seo(
     pool: &crate::db::DynDatabasePool,
     _site_name: &str,
 ) -> Option<ArticleSeo> {
-    use crate::db::repositories::{ArticleRepository, SqlxArticleRepository};
+    use crate::db::repositories::{
+        ArticleRepository, AttachmentRepository, CategoryRepository, ReaderGroupRepository,
+        SqlxArticleRepository, SqlxAttachmentRepository, SqlxCategoryRepository,
+        SqlxReaderGroupRepository, SqlxTagRepository, SqlxUserRepository, TagRepository,
+        UserRepository,
+    };
 
     let repo = SqlxArticleRepository::new(pool.clone());
 
@@ -898,33 +1096,80 @@ async fn fetch_article_seo(
         return None;
     }
 
-    // Generate excerpt from content (strip markdown, limit to 200 chars)
-    let excerpt = article
-        .content
-        .replace('#', "")
-        .replace('*', "")
-        .replace('`', "")
-        .replace('\n', " ")
-        .chars()
-        .take(200)
-        .collect::<String>();
+    let excerpt = article.excerpt_text();
 
-    let published_at = article
-        .published_at
-        .map(|d| d.to_rfc3339())
+    let author = SqlxUserRepository::new(pool.clone())
+        .get_by_id(article.author_id)
+        .await
+        .ok()
+        .flatten()
+        .map(|user| user.get_display_name().to_string());
+    let category = SqlxCategoryRepository::new(pool.clone())
+        .get_by_id(article.category_id)
+        .await
+        .ok()
+        .flatten();
+    // Crawlers are anonymous; private categories and restricted articles
+    // stay out of the HTML
+    if category
+        .as_ref()
+        .is_some_and(|c| !c.readable_by(crate::models::CategoryAccess::Public))
+    {
+        return None;
+    }
+    let audience = SqlxReaderGroupRepository::new(pool.clone())
+        .get_audience(article.id)
+        .await
+        .ok()?;
+    if audience.is_restricted() {
+        return None;
+    }
+    let category = category.map(|category| (category.name, category.slug));
+    let tags = SqlxTagRepository::new(pool.clone())
+        .get_by_article_id(article.id)
+        .await
+        .unwrap_or_default()
+        .into_iter()
+        .map(|tag| tag.name)
+        .collect();
+
+    // Crawlers get polls, FAQ topics and attachments filled in, not empty
+    // placeholders
+    let polls = crate::services::PollService::new(
+        crate::db::repositories::SqlxPollRepository::boxed(pool.clone()),
+    );
+    let content_html = polls.render_embeds(&article.content_html).await;
+    let faqs = crate::services::FaqService::new(crate::db::repositories::SqlxFaqRepository::boxed(
+        pool.clone(),
+    ));
+    let topics = faqs.embedded_topics(&content_html).await;
+    let content_html = crate::services::faq::fill_embeds(&content_html, &topics);
+    let attachments = SqlxAttachmentRepository::new(pool.clone())
+        .list(article.id)
+        .await
         .unwrap_or_default();
-
-    let updated_at = article.updated_at.to_rfc3339();
+    let content_html = crate::services::attachment::fill_embeds(&content_html, &attachments);
+    let faq = topics
+        .into_iter()
+        .flat_map(|t| t.items)
+        .map(|item| (item.question, item.answer_html))
+        .collect();
 
     Some(ArticleSeo {
+        meta: crate::theme::SeoMeta::from_article(&article),
         id: article.id,
         title: article.title,
         excerpt,
-        content_html: article.content_html,
-        published_at,
-        updated_at,
+        content_html,
+        published_at: article.published_at,
+        updated_at: article.updated_at,
         thumbnail: article.thumbnail,
         slug: article.slug,
+        author,
+        category,
+        tags,
+        faq,
+        license: article.license,
     })
 }
 
@@ -950,6 +1195,7 @@ async fn fetch_page_seo(slug: &str, pool: &crate::db::DynDatabasePool) -> Option
         .collect::<String>();
 
     Some(PageSeo {
+        id: page.id,
         title: page.title,
         excerpt,
         content_html: page.content_html,
@@ -957,6 +1203,32 @@ async fn fetch_page_seo(slug: &str, pool: &crate::db::DynDatabasePool) -> Option
     })
 }
 
+/// `<link rel="alternate" hreflang>` for each language variant, plus
+/// `x-default` pointing at the site-language one. Needs absolute URLs, so
+/// nothing without `site_url`.
+fn hreflang_links(base_url: &str, alternates: &[ContentAlternate], site_lang: &str) -> String {
+    if base_url.is_empty() {
+        return String::new();
+    }
+    let mut links = String::new();
+    for alternate in alternates {
+        links.push_str(&format!(
+            "\n<link rel=\"alternate\" hreflang=\"{}\" href=\"{}{}\">",
+            html_escape(&alternate.lang),
+            base_url,
+            html_escape(&alternate.url)
+        ));
+    }
+    if let Some(default) = alternates.iter().find(|a| a.lang == site_lang) {
+        links.push_str(&format!(
+            "\n<link rel=\"alternate\" hreflang=\"x-default\" href=\"{}{}\">",
+            base_url,
+            html_escape(&default.url)
+        ));
+    }
+    links
+}
+
 /// Simple HTML escaping for injected content
 fn html_escape(s: &str) -> String {
     s.replace('&', "&amp;")
@@ -977,23 +1249,60 @@ fn json_for_script<T: serde::Serialize>(value: &T) -> String {
 
 /// Build HTTP response with proper headers
 fn build_response(path: &str, data: &[u8]) -> Response {
-    let content_type = get_content_type(path);
-    let cache_control = if is_immutable(path) {
-        "public, max-age=31536000, immutable"
-    } else if content_type.starts_with("text/html") {
-        "no-cache"
-    } else {
-        "public, max-age=3600"
-    };
-
     Response::builder()
         .status(StatusCode::OK)
-        .header(header::CONTENT_TYPE, content_type)
-        .header(header::CACHE_CONTROL, cache_control)
+        .header(header::CONTENT_TYPE, get_content_type(path))
+        .header(header::CACHE_CONTROL, cache_control(path))
         .body(Body::from(data.to_vec()))
         .unwrap()
 }
 
+/// Serve an embedded admin file with an ETag of its content hash, answering
+/// a matching `If-None-Match` with 304
+fn admin_response(path: &str, file: &EmbeddedFile, headers: &HeaderMap) -> Response {
+    let etag = format!("\"{}\"", HEXLOWER.encode(&file.metadata.sha256_hash()));
+    let mut response = if etag_matches(headers, &etag) {
+        Response::builder()
+            .status(StatusCode::NOT_MODIFIED)
+            .header(header::CACHE_CONTROL, cache_control(path))
+            .body(Body::empty())
+            .unwrap()
+    } else {
+        build_response(path, &file.data)
+    };
+
+    let response_headers = response.headers_mut();
+    if let Ok(value) = HeaderValue::from_str(&etag) {
+        response_headers.insert(header::ETAG, value);
+    }
+    if let Ok(value) = HeaderValue::from_str(&admin_frontend().build) {
+        response_headers.insert(HeaderName::from_static(FRONTEND_BUILD_HEADER), value);
+    }
+    response
+}
+
+/// Whether `If-None-Match` lists `etag`
+fn etag_matches(headers: &HeaderMap, etag: &str) -> bool {
+    headers
+        .get_all(header::IF_NONE_MATCH)
+        .iter()
+        .filter_map(|value| value.to_str().ok())
+        .flat_map(|value| value.split(','))
+        .map(|tag| tag.trim())
+        .any(|tag| tag == "*" || tag.trim_start_matches("W/") == etag)
+}
+
+/// `Cache-Control` for a served file
+fn cache_control(path: &str) -> &'static str {
+    if is_immutable(path) {
+        "public, max-age=31536000, immutable"
+    } else if get_content_type(path).starts_with("text/html") {
+        "no-cache"
+    } else {
+        "public, max-age=3600"
+    }
+}
+
 /// 404 response
 fn not_found() -> Response {
     Response::builder()
@@ -1030,6 +1339,73 @@ fn get_content_type(path: &str) -> &'static str {
 fn is_immutable(path: &str) -> bool {
     // Next.js: /_next/static/xxx.js
     // Vite: /assets/index-xxx.js
+    // Embedded paths come without the leading slash
+    let path = format!("/{}", path.trim_start_matches('/'));
     (path.contains("/_next/static/") || path.contains("/assets/"))
         && (path.ends_with(".js") || path.ends_with(".css"))
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn hreflang_links_include_x_default_for_the_site_language() {
+        let alternate = |lang: &str, url: &str| ContentAlternate {
+            lang: lang.to_string(),
+            id: 1,
+            slug: String::new(),
+            title: String::new(),
+            url: url.to_string(),
+        };
+        let alternates = [
+            alternate("en", "/posts/hello"),
+            alternate("de", "/posts/hallo"),
+        ];
+
+        let links = hreflang_links("https://example.com", &alternates, "en");
+        assert!(links.contains(
+            r#"<link rel="alternate" hreflang="de" href="https://example.com/posts/hallo">"#
+        ));
+        assert!(links.contains(
+            r#"<link rel="alternate" hreflang="x-default" href="https://example.com/posts/hello">"#
+        ));
+
+        assert!(!hreflang_links("https://example.com", &alternates, "fr").contains("x-default"));
+        assert!(hreflang_links("", &alternates, "en").is_empty());
+    }
+
+    #[test]
+    fn etag_matches_if_none_match_lists() {
+        let etag = "\"abc\"";
+        let mut headers = HeaderMap::new();
+        assert!(!etag_matches(&headers, etag));
+
+        headers.insert(
+            header::IF_NONE_MATCH,
+            HeaderValue::from_static("\"old\", W/\"abc\""),
+        );
+        assert!(etag_matches(&headers, etag));
+        assert!(!etag_matches(&headers, "\"new\""));
+
+        headers.insert(header::IF_NONE_MATCH, HeaderValue::from_static("*"));
+        assert!(etag_matches(&headers, "\"new\""));
+    }
+
+    #[test]
+    fn hashed_assets_are_cached_for_good() {
+        assert_eq!(
+            cache_control("assets/index-3f2a.js"),
+            "public, max-age=31536000, immutable"
+        );
+        assert_eq!(cache_control("index.html"), "no-cache");
+        assert_eq!(cache_control("favicon.ico"), "public, max-age=3600");
+    }
+
+    #[test]
+    fn redirect_locations_are_ascii() {
+        assert_eq!(encode_location("/posts/new-slug"), "/posts/new-slug");
+        assert_eq!(encode_location("/posts/你好"), "/posts/%E4%BD%A0%E5%A5%BD");
+        assert_eq!(encode_location("/a b"), "/a%20b");
+    }
+}
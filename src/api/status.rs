@@ -0,0 +1,320 @@
+//! Public status page
+//!
+//! `GET /api/v1/status` summarizes uptime and component health for visitors,
+//! and `GET /status` renders the same as a page when `status_page.html` is
+//! on. Unlike `/readyz` it always answers `200`; the outcome is in the body.
+//! With `status_page.show_details` off, the version, latencies and error
+//! messages are left out. Scheduled maintenance windows are listed, and the
+//! status reads "maintenance" while maintenance mode is on.
+
+use axum::{
+    extract::State,
+    http::header,
+    response::{Html, IntoResponse, Response},
+    Json,
+};
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+
+use crate::api::health::{self, CheckResult, ReadinessResponse};
+use crate::api::middleware::{ApiError, AppState};
+use crate::services::maintenance::MaintenanceState;
+
+/// Response for GET /api/v1/status
+#[derive(Debug, Serialize)]
+pub struct StatusResponse {
+    /// "operational", "degraded" or "maintenance"
+    pub status: &'static str,
+    pub started_at: DateTime<Utc>,
+    pub uptime_seconds: u64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub version: Option<&'static str>,
+    pub components: Vec<ComponentStatus>,
+    /// Upcoming, current and recently finished maintenance windows
+    pub maintenance: Vec<MaintenanceWindowStatus>,
+}
+
+/// A scheduled maintenance window
+#[derive(Debug, Serialize)]
+pub struct MaintenanceWindowStatus {
+    pub starts_at: DateTime<Utc>,
+    pub ends_at: DateTime<Utc>,
+    #[serde(skip_serializing_if = "String::is_empty")]
+    pub message: String,
+    /// "scheduled", "in_progress" or "completed"
+    pub status: &'static str,
+}
+
+/// Health of one component
+#[derive(Debug, Serialize)]
+pub struct ComponentStatus {
+    pub name: &'static str,
+    /// "ok" or "down"
+    pub status: &'static str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub latency_ms: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub detail: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+impl StatusResponse {
+    fn new(checks: &ReadinessResponse, uptime_seconds: u64, details: bool) -> Self {
+        let component = |name, check: &CheckResult| ComponentStatus {
+            name,
+            status: if check.ok { "ok" } else { "down" },
+            latency_ms: details.then_some(check.latency_ms),
+            detail: check.detail.clone().filter(|_| details),
+            error: check.error.clone().filter(|_| details),
+        };
+        Self {
+            status: if checks.is_ready() {
+                "operational"
+            } else {
+                "degraded"
+            },
+            started_at: Utc::now() - chrono::Duration::seconds(uptime_seconds as i64),
+            uptime_seconds,
+            version: details.then_some(env!("CARGO_PKG_VERSION")),
+            components: checks
+                .components()
+                .into_iter()
+                .map(|(name, check)| component(name, check))
+                .collect(),
+            maintenance: Vec::new(),
+        }
+    }
+
+    /// Add the maintenance windows, reporting "maintenance" while it is on
+    fn with_maintenance(mut self, maintenance: &MaintenanceState, now: DateTime<Utc>) -> Self {
+        if maintenance.in_effect(now) {
+            self.status = "maintenance";
+        }
+        self.maintenance = maintenance
+            .windows
+            .iter()
+            .map(|w| MaintenanceWindowStatus {
+                starts_at: w.starts_at,
+                ends_at: w.ends_at,
+                message: w.message.clone(),
+                status: w.phase(now),
+            })
+            .collect();
+        self
+    }
+}
+
+async fn current_status(state: &AppState) -> StatusResponse {
+    let checks = health::check_components(state).await;
+    StatusResponse::new(
+        &checks,
+        state.request_stats.uptime_seconds(),
+        state.status_page.show_details,
+    )
+    .with_maintenance(&state.maintenance.state().await, Utc::now())
+}
+
+/// GET /api/v1/status - Uptime and component health
+pub async fn get_status(State(state): State<AppState>) -> Result<Response, ApiError> {
+    if !state.status_page.enabled {
+        return Err(ApiError::not_found("Not found"));
+    }
+    let status = current_status(&state).await;
+    Ok(([(header::CACHE_CONTROL, "no-cache")], Json(status)).into_response())
+}
+
+/// GET /status - The active theme's `status.html`, or the built-in page
+pub async fn status_page(State(state): State<AppState>) -> Result<Response, ApiError> {
+    if !state.status_page.enabled {
+        return Err(ApiError::not_found("Not found"));
+    }
+    let status = current_status(&state).await;
+    let site = state
+        .settings_service
+        .get_site_settings()
+        .await
+        .unwrap_or_default();
+
+    if let Ok(engine) = state.theme_engine.read() {
+        if engine
+            .tera()
+            .get_template_names()
+            .any(|name| name == "status.html")
+        {
+            let mut context = tera::Context::new();
+            context.insert("status", &status);
+            let vars = crate::theme::StandardTemplateVars::new(
+                &site.site_name,
+                &site.site_description,
+                "/status",
+            );
+            match engine.render_with_standard_vars("status.html", &context, &vars) {
+                Ok(html) => {
+                    return Ok(([(header::CACHE_CONTROL, "no-cache")], Html(html)).into_response())
+                }
+                Err(e) => tracing::warn!("Failed to render status.html: {}", e),
+            }
+        }
+    }
+
+    let html = builtin_status_page(&site.site_name, &status);
+    Ok(([(header::CACHE_CONTROL, "no-cache")], Html(html)).into_response())
+}
+
+fn builtin_status_page(site_name: &str, status: &StatusResponse) -> String {
+    let escape = |s: &str| {
+        s.replace('&', "&amp;")
+            .replace('<', "&lt;")
+            .replace('>', "&gt;")
+            .replace('"', "&quot;")
+    };
+    let rows: String = status
+        .components
+        .iter()
+        .map(|c| {
+            let note = c
+                .error
+                .as_deref()
+                .or(c.detail.as_deref())
+                .map(|n| format!(" <small>{}</small>", escape(n)))
+                .unwrap_or_default();
+            format!(
+                "<li class=\"{status}\"><span>{name}</span><span>{status}{note}</span></li>",
+                status = c.status,
+                name = c.name,
+                note = note,
+            )
+        })
+        .collect();
+    let version = status
+        .version
+        .map(|v| format!(" &middot; Noteva {}", v))
+        .unwrap_or_default();
+    let headline = match status.status {
+        "operational" => "All systems operational",
+        "maintenance" => "Down for maintenance",
+        _ => "Some systems are degraded",
+    };
+    let maintenance: String = status
+        .maintenance
+        .iter()
+        .filter(|w| w.status != "completed")
+        .map(|w| {
+            let message = if w.message.is_empty() {
+                String::new()
+            } else {
+                format!(" &middot; {}", escape(&w.message))
+            };
+            format!(
+                "\n    <p>Maintenance {} &ndash; {}{}</p>",
+                w.starts_at.format("%Y-%m-%d %H:%M"),
+                w.ends_at.format("%Y-%m-%d %H:%M UTC"),
+                message
+            )
+        })
+        .collect();
+    format!(
+        r#"<!DOCTYPE html>
+<html>
+<head>
+    <meta charset="UTF-8">
+    <meta name="viewport" content="width=device-width, initial-scale=1.0">
+    <meta name="robots" content="noindex">
+    <title>{site} - Status</title>
+    <style>
+        body {{
+            font-family: -apple-system, BlinkMacSystemFont, 'Segoe UI', Roboto, sans-serif;
+            max-width: 600px;
+            margin: 10vh auto;
+            padding: 20px;
+            color: #333;
+        }}
+        h1 {{ font-weight: 500; }}
+        ul {{ list-style: none; padding: 0; }}
+        li {{ display: flex; justify-content: space-between; padding: 10px 0; border-bottom: 1px solid #eee; }}
+        .ok span:last-child {{ color: #1a7f37; }}
+        .down span:last-child {{ color: #cf222e; }}
+        small, p {{ color: #666; }}
+    </style>
+</head>
+<body>
+    <h1>{headline}</h1>{maintenance}
+    <ul>{rows}</ul>
+    <p>{site} &middot; up since {started}{version}</p>
+</body>
+</html>"#,
+        site = escape(site_name),
+        headline = headline,
+        maintenance = maintenance,
+        rows = rows,
+        started = status.started_at.format("%Y-%m-%d %H:%M UTC"),
+        version = version,
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::services::maintenance::MaintenanceWindow;
+
+    fn check(ok: bool) -> CheckResult {
+        CheckResult {
+            ok,
+            latency_ms: 4,
+            detail: ok.then(|| "default".to_string()),
+            error: (!ok).then(|| "connection refused".to_string()),
+        }
+    }
+
+    #[test]
+    fn details_are_hidden_unless_enabled() {
+        let checks = ReadinessResponse::new(check(true), check(false), check(true), check(true));
+
+        let summary = StatusResponse::new(&checks, 90, false);
+        assert_eq!(summary.status, "degraded");
+        assert!(summary.version.is_none());
+        assert_eq!(summary.components[1].name, "cache");
+        assert_eq!(summary.components[1].status, "down");
+        assert!(summary.components[1].error.is_none());
+        assert!(summary.components[0].latency_ms.is_none());
+
+        let detailed = StatusResponse::new(&checks, 90, true);
+        assert!(detailed.version.is_some());
+        assert_eq!(
+            detailed.components[1].error.as_deref(),
+            Some("connection refused")
+        );
+
+        let html = builtin_status_page("<Blog>", &summary);
+        assert!(html.contains("&lt;Blog&gt;"));
+        assert!(!html.contains("connection refused"));
+    }
+
+    #[test]
+    fn maintenance_windows_are_listed() {
+        let checks = ReadinessResponse::new(check(true), check(true), check(true), check(true));
+        let start = Utc::now() + chrono::Duration::hours(2);
+        let maintenance = MaintenanceState {
+            windows: vec![MaintenanceWindow {
+                starts_at: start,
+                ends_at: start + chrono::Duration::hours(1),
+                message: "<Upgrade>".to_string(),
+                announce_hours: 24,
+            }],
+            ..Default::default()
+        };
+
+        let before =
+            StatusResponse::new(&checks, 90, false).with_maintenance(&maintenance, Utc::now());
+        assert_eq!(before.status, "operational");
+        assert_eq!(before.maintenance[0].status, "scheduled");
+        let html = builtin_status_page("Blog", &before);
+        assert!(html.contains("&lt;Upgrade&gt;"));
+
+        let during = StatusResponse::new(&checks, 90, false).with_maintenance(&maintenance, start);
+        assert_eq!(during.status, "maintenance");
+        assert_eq!(during.maintenance[0].status, "in_progress");
+        assert!(builtin_status_page("Blog", &during).contains("Down for maintenance"));
+    }
+}
@@ -0,0 +1,65 @@
+//! Delta sync API for offline clients.
+
+use axum::{
+    extract::{Query, State},
+    Json,
+};
+use chrono::{DateTime, Utc};
+use serde::Deserialize;
+
+use crate::api::middleware::{ApiError, AppState};
+use crate::services::sync::SyncResult;
+
+#[derive(Debug, Deserialize)]
+pub struct SyncQuery {
+    /// Checkpoint from the previous sync, RFC 3339 or unix seconds
+    pub since: Option<String>,
+}
+
+/// GET /api/v1/sync?since=
+pub async fn get_changes(
+    State(state): State<AppState>,
+    Query(query): Query<SyncQuery>,
+) -> Result<Json<SyncResult>, ApiError> {
+    let since = query
+        .since
+        .as_deref()
+        .map(str::trim)
+        .filter(|s| !s.is_empty())
+        .map(|s| {
+            parse_since(s).ok_or_else(|| {
+                ApiError::validation_error("since must be an RFC 3339 timestamp or unix seconds")
+            })
+        })
+        .transpose()?;
+
+    let result = state
+        .sync_service
+        .changes_since(since)
+        .await
+        .map_err(|e| ApiError::internal_error(e.to_string()))?;
+    Ok(Json(result))
+}
+
+fn parse_since(value: &str) -> Option<DateTime<Utc>> {
+    if let Ok(secs) = value.parse::<i64>() {
+        return DateTime::from_timestamp(secs, 0);
+    }
+    DateTime::parse_from_rfc3339(value)
+        .ok()
+        .map(|t| t.with_timezone(&Utc))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_rfc3339_and_unix_seconds() {
+        let expected = DateTime::from_timestamp(1714550400, 0).unwrap();
+        assert_eq!(parse_since("1714550400"), Some(expected));
+        assert_eq!(parse_since("2024-05-01T08:00:00+00:00"), Some(expected));
+        assert_eq!(parse_since("2024-05-01T10:00:00+02:00"), Some(expected));
+        assert_eq!(parse_since("yesterday"), None);
+    }
+}
@@ -15,10 +15,10 @@ use axum::{
 };
 use serde::{Deserialize, Serialize};
 
-use crate::api::common::{default_page, default_page_size};
+use crate::api::common::{default_page, default_page_size, parse_cursor};
 use crate::api::middleware::{ApiError, AppState};
 use crate::api::responses::{ArticleSummary, PaginatedArticleSummaryResponse};
-use crate::models::{ArticleSortBy, ListParams};
+use crate::models::{ArticleListScope, ArticleSortBy, CategoryAccess, ListParams, PagedResult};
 
 /// Query parameters for tag list
 #[derive(Debug, Deserialize)]
@@ -42,6 +42,8 @@ pub struct ListArticlesQuery {
     pub page: u32,
     #[serde(default = "default_page_size")]
     pub page_size: u32,
+    /// Opaque cursor for keyset pagination (empty for the first page)
+    pub cursor: Option<String>,
 }
 
 /// Response for tag list
@@ -127,21 +129,47 @@ async fn get_tag_articles(
     Path(slug): Path<String>,
     Query(query): Query<ListArticlesQuery>,
 ) -> Result<Json<PaginatedArticleSummaryResponse>, ApiError> {
-    // Get tag by slug
-    let tag = state
+    // Get tag by slug, or by a slug it had before a rename or merge
+    let tag = match state
         .tag_service
         .get_by_slug(&slug)
         .await
         .map_err(|e| ApiError::internal_error(e.to_string()))?
-        .ok_or_else(|| ApiError::not_found(format!("Tag not found: {}", slug)))?;
+    {
+        Some(tag) => Some(tag),
+        None => state
+            .tag_service
+            .get_by_previous_slug(&slug)
+            .await
+            .map_err(|e| ApiError::internal_error(e.to_string()))?,
+    }
+    .ok_or_else(|| ApiError::not_found(format!("Tag not found: {}", slug)))?;
 
     let params = ListParams::new(query.page, query.page_size);
 
-    let result = state
-        .article_service
-        .list_published_by_tag(tag.id, &params, ArticleSortBy::default())
-        .await
-        .map_err(|e| ApiError::internal_error(e.to_string()))?;
+    let mut next_cursor = None;
+    let result = if let Some(ref cursor) = query.cursor {
+        let cursor = parse_cursor(cursor)?;
+        let page = state
+            .article_service
+            .list_by_cursor(
+                &ArticleListScope::PublishedWithTag(tag.id),
+                cursor.as_ref(),
+                params.limit(),
+                CategoryAccess::Public,
+                None,
+            )
+            .await
+            .map_err(|e| ApiError::internal_error(e.to_string()))?;
+        next_cursor = page.next_cursor;
+        PagedResult::new(page.items, page.total, &ListParams::new(1, params.per_page))
+    } else {
+        state
+            .article_service
+            .list_published_by_tag(tag.id, &params, ArticleSortBy::default())
+            .await
+            .map_err(|e| ApiError::internal_error(e.to_string()))?
+    };
 
     let total = result.total;
     let page = result.page;
@@ -155,5 +183,6 @@ async fn get_tag_articles(
         page,
         page_size: per_page,
         total_pages,
+        next_cursor,
     }))
 }
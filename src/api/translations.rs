@@ -0,0 +1,184 @@
+//! Content translation API endpoints.
+//!
+//! - GET /api/v1/admin/translations/:kind/:id - Language and variants
+//! - PUT /api/v1/admin/translations/:kind/:id - Set the language, optionally
+//!   joining another article's or page's group with `translation_of`
+//! - DELETE /api/v1/admin/translations/:kind/:id - Drop the language
+//! - GET /api/v1/translations/:kind/:id - Published variants for `hreflang`
+//!
+//! `kind` is `articles` or `pages`.
+
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+    response::IntoResponse,
+    routing::get,
+    Json, Router,
+};
+use serde::Serialize;
+
+use crate::api::middleware::{ApiError, AppState};
+use crate::models::{
+    normalize_lang, ContentAlternate, ContentKind, ContentTranslation, LangFilter, TranslationInput,
+};
+use crate::services::translation::{DEFAULT_SITE_LANGUAGE, SITE_LANGUAGE_KEY};
+use crate::services::TranslationError;
+
+/// Build the translation management router (requires admin)
+pub fn router() -> Router<AppState> {
+    Router::new().route(
+        "/{kind}/{id}",
+        get(get_translation)
+            .put(set_translation)
+            .delete(delete_translation),
+    )
+}
+
+/// Build the public translations router
+pub fn public_router() -> Router<AppState> {
+    Router::new().route("/{kind}/{id}", get(list_alternates))
+}
+
+/// Language of content that was never given one
+pub(crate) async fn site_language(state: &AppState) -> String {
+    state
+        .settings_service
+        .get(SITE_LANGUAGE_KEY)
+        .await
+        .ok()
+        .flatten()
+        .and_then(|lang| normalize_lang(&lang))
+        .unwrap_or_else(|| DEFAULT_SITE_LANGUAGE.to_string())
+}
+
+/// Parse a `lang` query parameter into a listing filter
+pub(crate) async fn lang_filter(
+    state: &AppState,
+    lang: Option<&str>,
+) -> Result<Option<LangFilter>, ApiError> {
+    let Some(lang) = lang.map(str::trim).filter(|s| !s.is_empty()) else {
+        return Ok(None);
+    };
+    let lang = normalize_lang(lang)
+        .ok_or_else(|| ApiError::validation_error(format!("Invalid language tag: {}", lang)))?;
+    Ok(Some(LangFilter {
+        lang,
+        site_lang: site_language(state).await,
+    }))
+}
+
+/// Published variants of an article or page; empty on failure so a broken
+/// group never breaks the page itself
+pub(crate) async fn alternates(
+    state: &AppState,
+    kind: ContentKind,
+    id: i64,
+) -> Vec<ContentAlternate> {
+    let permalink_structure = state
+        .settings_service
+        .get(crate::services::settings::keys::PERMALINK_STRUCTURE)
+        .await
+        .ok()
+        .flatten()
+        .unwrap_or_else(|| "/posts/{slug}".to_string());
+    state
+        .translation_service
+        .alternates(kind, id, &permalink_structure)
+        .await
+        .unwrap_or_else(|e| {
+            tracing::warn!(
+                "Failed to load translations of {} {}: {}",
+                kind.as_str(),
+                id,
+                e
+            );
+            Vec::new()
+        })
+}
+
+#[derive(Debug, Serialize)]
+struct TranslationResponse {
+    translation: Option<ContentTranslation>,
+    variants: Vec<ContentTranslation>,
+}
+
+#[derive(Debug, Serialize)]
+struct VariantsResponse {
+    variants: Vec<ContentTranslation>,
+}
+
+#[derive(Debug, Serialize)]
+struct AlternatesResponse {
+    translations: Vec<ContentAlternate>,
+}
+
+fn map_translation_error(e: TranslationError) -> ApiError {
+    match e {
+        TranslationError::NotFound(_) => ApiError::not_found(e.to_string()),
+        TranslationError::Validation(_) => ApiError::validation_error(e.to_string()),
+        TranslationError::Internal(e) => ApiError::internal_error(e.to_string()),
+    }
+}
+
+fn parse_kind(kind: &str) -> Result<ContentKind, ApiError> {
+    ContentKind::parse(kind)
+        .ok_or_else(|| ApiError::not_found(format!("Unknown content type: {}", kind)))
+}
+
+async fn get_translation(
+    State(state): State<AppState>,
+    Path((kind, id)): Path<(String, i64)>,
+) -> Result<impl IntoResponse, ApiError> {
+    let kind = parse_kind(&kind)?;
+    let translation = state
+        .translation_service
+        .get(kind, id)
+        .await
+        .map_err(map_translation_error)?;
+    let variants = state
+        .translation_service
+        .variants(kind, id)
+        .await
+        .map_err(map_translation_error)?;
+    Ok(Json(TranslationResponse {
+        translation,
+        variants,
+    }))
+}
+
+async fn set_translation(
+    State(state): State<AppState>,
+    Path((kind, id)): Path<(String, i64)>,
+    Json(input): Json<TranslationInput>,
+) -> Result<impl IntoResponse, ApiError> {
+    let kind = parse_kind(&kind)?;
+    let variants = state
+        .translation_service
+        .set(kind, id, input)
+        .await
+        .map_err(map_translation_error)?;
+    Ok(Json(VariantsResponse { variants }))
+}
+
+async fn delete_translation(
+    State(state): State<AppState>,
+    Path((kind, id)): Path<(String, i64)>,
+) -> Result<impl IntoResponse, ApiError> {
+    let kind = parse_kind(&kind)?;
+    state
+        .translation_service
+        .remove(kind, id)
+        .await
+        .map_err(map_translation_error)?;
+    Ok(StatusCode::NO_CONTENT)
+}
+
+async fn list_alternates(
+    State(state): State<AppState>,
+    Path((kind, id)): Path<(String, i64)>,
+) -> Result<impl IntoResponse, ApiError> {
+    let kind = parse_kind(&kind)?;
+    Ok(Json(AlternatesResponse {
+        translations: alternates(&state, kind, id).await,
+    }))
+}
@@ -522,7 +522,10 @@ fn get_extension(filename: &str, content_type: &str) -> String {
     }
 }
 
-fn validate_safe_upload_name(filename: &str, content_type: &str) -> Result<(), ApiError> {
+pub(crate) fn validate_safe_upload_name(
+    filename: &str,
+    content_type: &str,
+) -> Result<(), ApiError> {
     let ext = filename
         .rsplit('.')
         .next()
@@ -0,0 +1,48 @@
+//! Webmention receiving endpoint
+//!
+//! POST /webmention with form fields `source` and `target`.
+//! Requests are validated synchronously; fetching and verifying the source
+//! is queued as a background job and the endpoint answers `202 Accepted`.
+
+use axum::{extract::State, http::StatusCode, Form};
+use serde::Deserialize;
+
+use crate::api::middleware::{ApiError, AppState};
+use crate::services::webmention::{self, VerifyJob};
+use crate::services::WebmentionError;
+
+/// Webmention form body
+#[derive(Debug, Deserialize)]
+pub struct WebmentionRequest {
+    pub source: String,
+    pub target: String,
+}
+
+/// POST /webmention - Receive a Webmention
+pub async fn receive(
+    State(state): State<AppState>,
+    Form(request): Form<WebmentionRequest>,
+) -> Result<StatusCode, ApiError> {
+    let article = state
+        .webmention_service
+        .accept(&request.source, &request.target)
+        .await
+        .map_err(|e| match e {
+            WebmentionError::Disabled => ApiError::not_found("Webmention is not enabled"),
+            WebmentionError::InvalidRequest(msg) => ApiError::validation_error(msg),
+            WebmentionError::Internal(e) => ApiError::internal_error(e.to_string()),
+        })?;
+
+    let job = VerifyJob {
+        source: request.source,
+        target: request.target,
+        article_id: article.id,
+    };
+    state
+        .job_queue
+        .enqueue(webmention::VERIFY_JOB, &job)
+        .await
+        .map_err(|e| ApiError::internal_error(e.to_string()))?;
+
+    Ok(StatusCode::ACCEPTED)
+}
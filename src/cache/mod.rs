@@ -27,6 +27,7 @@ use async_trait::async_trait;
 use serde::{de::DeserializeOwned, Serialize};
 use std::sync::Arc;
 use std::time::Duration;
+use tracing::Instrument;
 
 use crate::config::{CacheConfig, CacheDriver};
 use crate::plugin::HookManager;
@@ -84,11 +85,15 @@ pub enum Cache {
 #[async_trait]
 impl CacheLayer for Cache {
     async fn get<T: DeserializeOwned + Send>(&self, key: &str) -> Result<Option<T>> {
-        match self {
-            Cache::Memory(cache) => cache.get(key).await,
-            #[cfg(feature = "redis-cache")]
-            Cache::Redis(cache) => cache.get(key).await,
+        async {
+            match self {
+                Cache::Memory(cache) => cache.get(key).await,
+                #[cfg(feature = "redis-cache")]
+                Cache::Redis(cache) => cache.get(key).await,
+            }
         }
+        .instrument(tracing::info_span!("cache.get", cache.key = key))
+        .await
     }
 
     async fn set<T: Serialize + Send + Sync>(
@@ -97,34 +102,75 @@ impl CacheLayer for Cache {
         value: &T,
         ttl: Duration,
     ) -> Result<()> {
-        match self {
-            Cache::Memory(cache) => cache.set(key, value, ttl).await,
-            #[cfg(feature = "redis-cache")]
-            Cache::Redis(cache) => cache.set(key, value, ttl).await,
+        async {
+            match self {
+                Cache::Memory(cache) => cache.set(key, value, ttl).await,
+                #[cfg(feature = "redis-cache")]
+                Cache::Redis(cache) => cache.set(key, value, ttl).await,
+            }
         }
+        .instrument(tracing::info_span!("cache.set", cache.key = key))
+        .await
     }
 
     async fn delete(&self, key: &str) -> Result<()> {
-        match self {
-            Cache::Memory(cache) => cache.delete(key).await,
-            #[cfg(feature = "redis-cache")]
-            Cache::Redis(cache) => cache.delete(key).await,
+        async {
+            match self {
+                Cache::Memory(cache) => cache.delete(key).await,
+                #[cfg(feature = "redis-cache")]
+                Cache::Redis(cache) => cache.delete(key).await,
+            }
         }
+        .instrument(tracing::info_span!("cache.delete", cache.key = key))
+        .await
     }
 
     async fn delete_pattern(&self, pattern: &str) -> Result<()> {
-        match self {
-            Cache::Memory(cache) => cache.delete_pattern(pattern).await,
-            #[cfg(feature = "redis-cache")]
-            Cache::Redis(cache) => cache.delete_pattern(pattern).await,
+        async {
+            match self {
+                Cache::Memory(cache) => cache.delete_pattern(pattern).await,
+                #[cfg(feature = "redis-cache")]
+                Cache::Redis(cache) => cache.delete_pattern(pattern).await,
+            }
         }
+        .instrument(tracing::info_span!(
+            "cache.delete_pattern",
+            cache.key = pattern
+        ))
+        .await
     }
 
     async fn clear(&self) -> Result<()> {
+        async {
+            match self {
+                Cache::Memory(cache) => cache.clear().await,
+                #[cfg(feature = "redis-cache")]
+                Cache::Redis(cache) => cache.clear().await,
+            }
+        }
+        .instrument(tracing::info_span!("cache.clear"))
+        .await
+    }
+}
+
+impl Cache {
+    /// Number of entries held in memory; `None` for Redis
+    pub fn entry_count(&self) -> Option<u64> {
         match self {
-            Cache::Memory(cache) => cache.clear().await,
+            Cache::Memory(cache) => Some(cache.entry_count()),
             #[cfg(feature = "redis-cache")]
-            Cache::Redis(cache) => cache.clear().await,
+            Cache::Redis(_) => None,
+        }
+    }
+
+    /// Round-trip a probe key to check the backend is reachable
+    pub async fn ping(&self) -> Result<()> {
+        const PROBE_KEY: &str = "noteva:health:ping";
+        let token = chrono::Utc::now().timestamp_nanos_opt().unwrap_or_default();
+        self.set(PROBE_KEY, &token, Duration::from_secs(10)).await?;
+        match self.get::<i64>(PROBE_KEY).await? {
+            Some(value) if value == token => Ok(()),
+            _ => anyhow::bail!("cache did not return the probe value"),
         }
     }
 }
@@ -371,4 +417,10 @@ mod tests {
         // Clean up
         cache.delete("factory_test_key").await.unwrap();
     }
+
+    #[tokio::test]
+    async fn test_memory_cache_ping() {
+        let cache = create_cache(&CacheConfig::default()).await.unwrap();
+        cache.ping().await.unwrap();
+    }
 }
@@ -0,0 +1,402 @@
+//! Command line interface
+//!
+//! ```text
+//! noteva [--config config.yml] [serve]
+//! noteva user create <username> --email <email> [--admin]
+//! noteva user passwd <username>
+//! noteva migrate
+//! noteva config check
+//! noteva export --out ./public [--base-url https://cdn.example.com] [--comments-embed comments.html]
+//! noteva install-service [--name noteva] [--user noteva] [--output /etc/systemd/system/noteva.service]
+//! ```
+//!
+//! Running without a subcommand starts the server. The user commands ask for
+//! the password on the terminal, or read one line from stdin when it is not a
+//! terminal, so they can be scripted.
+//!
+//! `--workdir` changes directory before anything else, so relative paths in
+//! the configuration resolve against it; installed services pass it on
+//! Windows, where services start in the system directory.
+
+use anyhow::{bail, Context, Result};
+use clap::{Parser, Subcommand};
+use std::io::{BufRead, IsTerminal};
+use std::path::PathBuf;
+use std::sync::Arc;
+
+use crate::config::{AuthBackend, Config};
+use crate::daemon::ServiceSpec;
+use crate::db::{
+    self,
+    repositories::{
+        SettingsRepository, SqlxSessionRepository, SqlxSettingsRepository, SqlxUserRepository,
+    },
+};
+use crate::export::{self, ExportOptions};
+use crate::models::UserRole;
+use crate::plugin::{hook_registry::HookRegistry, HookManager};
+use crate::services::user::{RegisterInput, UserService};
+use crate::theme::ThemeEngine;
+
+/// Noteva - A lightweight modern blog system
+#[derive(Debug, Parser)]
+#[command(name = "noteva", version)]
+pub struct Cli {
+    /// Configuration file
+    #[arg(short, long, global = true, default_value = "config.yml")]
+    pub config: PathBuf,
+
+    /// Directory to run in; relative paths resolve against it
+    #[arg(short = 'C', long, global = true)]
+    pub workdir: Option<PathBuf>,
+
+    #[command(subcommand)]
+    pub command: Option<Command>,
+}
+
+#[derive(Debug, Subcommand)]
+pub enum Command {
+    /// Start the web server (default)
+    Serve,
+    /// Manage user accounts
+    User {
+        #[command(subcommand)]
+        command: UserCommand,
+    },
+    /// Apply pending database migrations
+    Migrate,
+    /// Inspect the configuration
+    Config {
+        #[command(subcommand)]
+        command: ConfigCommand,
+    },
+    /// Render the published site to static files
+    Export {
+        /// Output directory
+        #[arg(long, default_value = "public")]
+        out: PathBuf,
+        /// Public URL of the exported site (defaults to the site_url setting)
+        #[arg(long)]
+        base_url: Option<String>,
+        /// HTML file placed under every article, e.g. an external comment widget
+        #[arg(long)]
+        comments_embed: Option<PathBuf>,
+    },
+    /// Install as a system service: a systemd unit, or a Windows service
+    InstallService {
+        /// Service name
+        #[arg(long, default_value = "noteva")]
+        name: String,
+        /// Account the service runs as (systemd)
+        #[arg(long)]
+        user: Option<String>,
+        /// Write the systemd unit here instead of printing it
+        #[arg(long)]
+        output: Option<PathBuf>,
+    },
+    /// Run under the Windows Service Control Manager
+    #[command(hide = true)]
+    RunService {
+        #[arg(long, default_value = "noteva")]
+        name: String,
+    },
+}
+
+#[derive(Debug, Subcommand)]
+pub enum UserCommand {
+    /// Create a user account
+    Create {
+        username: String,
+        #[arg(long)]
+        email: String,
+        /// Give the account the admin role instead of author
+        #[arg(long)]
+        admin: bool,
+    },
+    /// Set a user's password and sign them out everywhere
+    Passwd { username: String },
+}
+
+#[derive(Debug, Subcommand)]
+pub enum ConfigCommand {
+    /// Load and validate the configuration without starting the server
+    Check,
+}
+
+/// Run an administrative command (anything but `serve`)
+pub async fn run(command: Command, config_path: &std::path::Path, config: &Config) -> Result<()> {
+    match command {
+        Command::Serve => bail!("serve is handled by the server entry point"),
+        Command::User { command } => run_user(command, config).await,
+        Command::Migrate => migrate(config).await,
+        Command::Config {
+            command: ConfigCommand::Check,
+        } => check_config(config_path, config),
+        Command::Export {
+            out,
+            base_url,
+            comments_embed,
+        } => export(config, out, base_url, comments_embed).await,
+        Command::InstallService { name, user, output } => {
+            install_service(config_path, &name, user, output)
+        }
+        Command::RunService { .. } => {
+            bail!("run-service is only used by the Windows service manager")
+        }
+    }
+}
+
+async fn run_user(command: UserCommand, config: &Config) -> Result<()> {
+    let pool = db::create_pool(&config.database).await?;
+    db::migrations::run_migrations(&pool).await?;
+    let users = UserService::new(
+        SqlxUserRepository::boxed(pool.clone()),
+        Arc::new(SqlxSessionRepository::new(pool.clone())),
+    )
+    .with_settings(Arc::new(SqlxSettingsRepository::new(pool)));
+
+    match command {
+        UserCommand::Create {
+            username,
+            email,
+            admin,
+        } => {
+            let password = read_new_password()?;
+            let role = if admin {
+                UserRole::Admin
+            } else {
+                UserRole::Author
+            };
+            let user = users
+                .create_user(RegisterInput::new(username, email, password), role)
+                .await?;
+            println!("Created {} '{}' (id {})", user.role, user.username, user.id);
+        }
+        UserCommand::Passwd { username } => {
+            let user = users
+                .get_by_username(&username)
+                .await?
+                .with_context(|| format!("No user named '{}'", username))?;
+            let password = read_new_password()?;
+            users.reset_password(user.id, &password).await?;
+            println!(
+                "Password changed for '{}'; existing sessions were signed out",
+                username
+            );
+        }
+    }
+    Ok(())
+}
+
+/// Prompt twice on a terminal, otherwise take the first line of stdin
+fn read_new_password() -> Result<String> {
+    if !std::io::stdin().is_terminal() {
+        let mut line = String::new();
+        std::io::stdin()
+            .lock()
+            .read_line(&mut line)
+            .context("Failed to read password from stdin")?;
+        return Ok(line.trim_end_matches(['\r', '\n']).to_string());
+    }
+    let password = rpassword::prompt_password("Password: ")?;
+    let confirm = rpassword::prompt_password("Repeat password: ")?;
+    if password != confirm {
+        bail!("Passwords do not match");
+    }
+    Ok(password)
+}
+
+async fn migrate(config: &Config) -> Result<()> {
+    let pool = db::create_pool(&config.database).await?;
+    let applied = db::migrations::run_migrations(&pool).await?;
+    if applied == 0 {
+        println!("Database is up to date");
+    } else {
+        println!("Applied {} migration(s)", applied);
+    }
+    Ok(())
+}
+
+async fn export(
+    config: &Config,
+    out: PathBuf,
+    base_url: Option<String>,
+    comments_embed: Option<PathBuf>,
+) -> Result<()> {
+    let comments_html = comments_embed
+        .map(|path| {
+            std::fs::read_to_string(&path)
+                .with_context(|| format!("Failed to read {}", path.display()))
+        })
+        .transpose()?;
+
+    let pool = db::create_pool(&config.database).await?;
+    db::migrations::run_migrations(&pool).await?;
+
+    let active_theme = SqlxSettingsRepository::new(pool.clone())
+        .get("active_theme")
+        .await?
+        .map(|s| s.value)
+        .unwrap_or_else(|| config.theme.active.clone());
+    let mut theme = ThemeEngine::new(&config.theme.path, "default")?;
+    if active_theme != "default" && theme.set_theme_with_fallback(&active_theme).used_fallback {
+        eprintln!("Theme '{}' is not available, using default", active_theme);
+    }
+    let hooks = HookManager::new(HookRegistry::load_embedded());
+
+    let options = ExportOptions {
+        out,
+        base_url,
+        comments_html,
+        uploads: config.upload.path.clone(),
+    };
+    let report = export::export_site(&pool, &mut theme, &hooks, &options).await?;
+    println!(
+        "Exported {} articles, {} pages, {} categories and {} tags ({} files) to {}",
+        report.articles,
+        report.pages,
+        report.categories,
+        report.tags,
+        report.files,
+        options.out.display()
+    );
+    for skipped in &report.skipped {
+        println!("Skipped {}: slug is not usable as a file name", skipped);
+    }
+    Ok(())
+}
+
+/// Write or print a systemd unit, or register a Windows service, that runs
+/// the server with this configuration from the current directory
+fn install_service(
+    config_path: &std::path::Path,
+    name: &str,
+    user: Option<String>,
+    output: Option<PathBuf>,
+) -> Result<()> {
+    if !config_path.exists() {
+        eprintln!(
+            "{} not found; the service will use defaults and environment overrides",
+            config_path.display()
+        );
+    }
+    let spec = ServiceSpec::current(name, config_path, user)?;
+
+    #[cfg(windows)]
+    {
+        if output.is_some() {
+            bail!("--output only applies to systemd units");
+        }
+        crate::daemon::windows::install(&spec)?;
+        println!(
+            "Installed service '{}' running from {}; start it with `sc start {}`",
+            spec.name,
+            spec.workdir.display(),
+            spec.name
+        );
+    }
+
+    #[cfg(not(windows))]
+    match output {
+        Some(path) => {
+            std::fs::write(&path, spec.systemd_unit())
+                .with_context(|| format!("Failed to write {}", path.display()))?;
+            println!(
+                "Wrote {}; enable it with `systemctl daemon-reload && systemctl enable --now {}`",
+                path.display(),
+                spec.name
+            );
+        }
+        None => print!("{}", spec.systemd_unit()),
+    }
+    Ok(())
+}
+
+/// Run the startup checks that need no database
+fn check_config(config_path: &std::path::Path, config: &Config) -> Result<()> {
+    if !config_path.exists() {
+        println!(
+            "{} not found; using defaults and environment overrides",
+            config_path.display()
+        );
+    }
+    config.server.cors_policy()?;
+    config.server.compression.validate()?;
+    if let Some(tls) = &config.server.tls {
+        crate::tls::setup(tls)?;
+    }
+    if config.auth.backend == AuthBackend::Ldap {
+        crate::services::LdapAuthenticator::new(config.auth.ldap.clone())?;
+    }
+    println!("Configuration OK");
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_subcommands() {
+        let cli = Cli::parse_from(["noteva"]);
+        assert!(cli.command.is_none());
+        assert_eq!(cli.config, PathBuf::from("config.yml"));
+
+        let cli = Cli::parse_from([
+            "noteva",
+            "user",
+            "create",
+            "alice",
+            "--email",
+            "a@example.com",
+            "--admin",
+            "-c",
+            "/etc/noteva.yml",
+        ]);
+        assert_eq!(cli.config, PathBuf::from("/etc/noteva.yml"));
+        assert!(matches!(
+            cli.command,
+            Some(Command::User {
+                command: UserCommand::Create { admin: true, .. }
+            })
+        ));
+
+        assert!(Cli::try_parse_from(["noteva", "user", "create", "alice"]).is_err());
+        assert!(matches!(
+            Cli::parse_from(["noteva", "config", "check"]).command,
+            Some(Command::Config {
+                command: ConfigCommand::Check
+            })
+        ));
+
+        let Some(Command::Export { out, base_url, .. }) =
+            Cli::parse_from(["noteva", "export", "--out", "dist"]).command
+        else {
+            panic!("expected export command");
+        };
+        assert_eq!(out, PathBuf::from("dist"));
+        assert!(base_url.is_none());
+
+        let cli = Cli::parse_from([
+            "noteva",
+            "install-service",
+            "--user",
+            "noteva",
+            "--workdir",
+            "/srv/noteva",
+        ]);
+        assert_eq!(cli.workdir, Some(PathBuf::from("/srv/noteva")));
+        let Some(Command::InstallService { name, user, output }) = cli.command else {
+            panic!("expected install-service command");
+        };
+        assert_eq!(name, "noteva");
+        assert_eq!(user.as_deref(), Some("noteva"));
+        assert!(output.is_none());
+    }
+
+    #[test]
+    fn command_definitions_are_consistent() {
+        use clap::CommandFactory;
+        Cli::command().debug_assert();
+    }
+}
@@ -0,0 +1,60 @@
+//! Built-in page view analytics configuration
+//!
+//! ```yaml
+//! analytics:
+//!   enabled: true
+//!   flush_secs: 60                  # how often counts are written out
+//!   respect_dnt: true               # skip visitors sending DNT or Sec-GPC
+//!   country_header: "CF-IPCountry"  # country set by a CDN in front
+//!   geoip_db: "GeoLite2-Country.mmdb"  # requires a build with `--features geoip`
+//! ```
+//!
+//! Only daily aggregates are stored. Visitors are told apart by a hash of
+//! their IP and user agent with a salt that changes every day and never
+//! leaves memory.
+
+use serde::{Deserialize, Serialize};
+
+/// Analytics settings under `analytics`
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AnalyticsConfig {
+    /// Record page views of the public site
+    #[serde(default = "default_enabled")]
+    pub enabled: bool,
+    /// Seconds between writes of the buffered counts
+    #[serde(default = "default_flush_secs")]
+    pub flush_secs: u64,
+    /// Do not count visitors asking not to be tracked
+    #[serde(default = "default_respect_dnt")]
+    pub respect_dnt: bool,
+    /// Request header holding the visitor's ISO country code
+    #[serde(default)]
+    pub country_header: Option<String>,
+    /// MaxMind country database, used when the header is missing
+    #[serde(default)]
+    pub geoip_db: Option<String>,
+}
+
+impl Default for AnalyticsConfig {
+    fn default() -> Self {
+        Self {
+            enabled: default_enabled(),
+            flush_secs: default_flush_secs(),
+            respect_dnt: default_respect_dnt(),
+            country_header: None,
+            geoip_db: None,
+        }
+    }
+}
+
+fn default_enabled() -> bool {
+    true
+}
+
+fn default_flush_secs() -> u64 {
+    60
+}
+
+fn default_respect_dnt() -> bool {
+    true
+}
@@ -0,0 +1,43 @@
+//! Backup configuration
+//!
+//! ```yaml
+//! backup:
+//!   path: "data/backups"
+//!   interval_hours: 24   # 0 disables scheduled backups
+//!   keep: 7              # newest archives kept; older ones are deleted
+//! ```
+
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+
+/// Backup archive settings under `backup`
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BackupConfig {
+    /// Directory holding the archives
+    #[serde(default = "default_path")]
+    pub path: PathBuf,
+    /// Hours between scheduled backups; 0 disables them
+    #[serde(default)]
+    pub interval_hours: u64,
+    /// Number of archives to keep; 0 keeps all
+    #[serde(default = "default_keep")]
+    pub keep: usize,
+}
+
+impl Default for BackupConfig {
+    fn default() -> Self {
+        Self {
+            path: default_path(),
+            interval_hours: 0,
+            keep: default_keep(),
+        }
+    }
+}
+
+fn default_path() -> PathBuf {
+    PathBuf::from("data/backups")
+}
+
+fn default_keep() -> usize {
+    7
+}
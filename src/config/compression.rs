@@ -0,0 +1,158 @@
+//! Response compression configuration
+//!
+//! ```yaml
+//! server:
+//!   compression:
+//!     enabled: true
+//!     algorithms: [br, zstd, gzip, deflate]
+//!     min_size: 1024
+//!     exclude_content_types: ["image/png", "video/", "application/zip"]
+//! ```
+//!
+//! The encoding is negotiated from `Accept-Encoding`. Responses smaller than
+//! `min_size` bytes and content types starting with an excluded prefix are
+//! sent as-is. Server-sent events are never compressed. Brotli (`br`) is
+//! compressed at a moderate quality so dynamic responses stay fast.
+
+use serde::{Deserialize, Serialize};
+
+use super::ConfigError;
+
+/// Encodings this build can produce
+const SUPPORTED_ALGORITHMS: &[&str] = &["br", "zstd", "gzip", "deflate"];
+
+/// Response compression settings under `server.compression`
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CompressionConfig {
+    /// Compress responses at all
+    #[serde(default = "default_enabled")]
+    pub enabled: bool,
+    /// Encodings offered to clients
+    #[serde(default = "default_algorithms")]
+    pub algorithms: Vec<String>,
+    /// Responses with a known size below this many bytes are not compressed
+    #[serde(default = "default_min_size")]
+    pub min_size: u16,
+    /// Content type prefixes that are never compressed
+    #[serde(default = "default_exclude_content_types")]
+    pub exclude_content_types: Vec<String>,
+}
+
+impl Default for CompressionConfig {
+    fn default() -> Self {
+        Self {
+            enabled: default_enabled(),
+            algorithms: default_algorithms(),
+            min_size: default_min_size(),
+            exclude_content_types: default_exclude_content_types(),
+        }
+    }
+}
+
+fn default_enabled() -> bool {
+    true
+}
+
+fn default_algorithms() -> Vec<String> {
+    SUPPORTED_ALGORITHMS.iter().map(|s| s.to_string()).collect()
+}
+
+fn default_min_size() -> u16 {
+    1024
+}
+
+/// Formats that are already compressed
+fn default_exclude_content_types() -> Vec<String> {
+    [
+        "image/png",
+        "image/jpeg",
+        "image/gif",
+        "image/webp",
+        "image/avif",
+        "video/",
+        "audio/",
+        "font/woff",
+        "application/zip",
+        "application/gzip",
+        "application/x-gzip",
+        "application/pdf",
+    ]
+    .iter()
+    .map(|s| s.to_string())
+    .collect()
+}
+
+impl CompressionConfig {
+    /// Reject encodings this build cannot produce
+    pub fn validate(&self) -> Result<(), ConfigError> {
+        for algorithm in &self.algorithms {
+            if !SUPPORTED_ALGORITHMS.contains(&algorithm.to_ascii_lowercase().as_str()) {
+                return Err(ConfigError::ValidationError(format!(
+                    "server.compression.algorithms: '{}' is not supported (available: {})",
+                    algorithm,
+                    SUPPORTED_ALGORITHMS.join(", ")
+                )));
+            }
+        }
+        Ok(())
+    }
+
+    /// Whether `algorithm` is offered to clients
+    pub fn uses(&self, algorithm: &str) -> bool {
+        self.enabled
+            && self
+                .algorithms
+                .iter()
+                .any(|a| a.eq_ignore_ascii_case(algorithm))
+    }
+
+    /// Whether a response with this `Content-Type` is sent uncompressed
+    pub fn excludes(&self, content_type: &str) -> bool {
+        let content_type = content_type.trim().to_ascii_lowercase();
+        content_type.starts_with("text/event-stream")
+            || self
+                .exclude_content_types
+                .iter()
+                .map(|prefix| prefix.trim())
+                .filter(|prefix| !prefix.is_empty())
+                .any(|prefix| content_type.starts_with(&prefix.to_ascii_lowercase()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn excludes_configured_prefixes_and_event_streams() {
+        let config = CompressionConfig::default();
+        assert!(config.excludes("image/png"));
+        assert!(config.excludes("Video/MP4"));
+        assert!(config.excludes("text/event-stream"));
+        assert!(!config.excludes("image/svg+xml"));
+        assert!(!config.excludes("application/json"));
+        assert!(!config.excludes("text/html; charset=utf-8"));
+
+        let custom: CompressionConfig =
+            serde_yaml::from_str("exclude_content_types: [\"application/json\"]").unwrap();
+        assert!(custom.excludes("application/json"));
+        assert!(!custom.excludes("image/png"));
+        assert_eq!(custom.min_size, 1024);
+    }
+
+    #[test]
+    fn validates_algorithms() {
+        assert!(CompressionConfig::default().validate().is_ok());
+        let config: CompressionConfig =
+            serde_yaml::from_str("algorithms: [GZIP, br, Zstd]").unwrap();
+        assert!(config.validate().is_ok());
+        assert!(config.uses("zstd"));
+        let config: CompressionConfig = serde_yaml::from_str("algorithms: [gzip, lz4]").unwrap();
+        assert!(config.validate().is_err());
+        let config: CompressionConfig = serde_yaml::from_str("algorithms: [gzip]").unwrap();
+        assert!(config.uses("gzip"));
+        assert!(!config.uses("deflate"));
+        let disabled: CompressionConfig = serde_yaml::from_str("enabled: false").unwrap();
+        assert!(!disabled.uses("gzip"));
+    }
+}
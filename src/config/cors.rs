@@ -0,0 +1,226 @@
+//! CORS origin configuration
+//!
+//! `server.cors_origin` takes one origin, a comma-separated string or a list.
+//! Entries are exact origins (`https://example.com`) or wildcard subdomain
+//! patterns (`https://*.example.com`, which does not match the bare domain).
+//! `server.cors_permissive: true` mirrors any origin and is meant for local
+//! development only.
+
+use serde::{Deserialize, Serialize};
+
+use super::ConfigError;
+
+/// Configured CORS origins, as written in the config file
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(from = "OneOrMany")]
+pub struct CorsOrigins(pub Vec<String>);
+
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum OneOrMany {
+    One(String),
+    Many(Vec<String>),
+}
+
+impl From<OneOrMany> for CorsOrigins {
+    fn from(value: OneOrMany) -> Self {
+        match value {
+            OneOrMany::One(s) => Self::from(s.as_str()),
+            OneOrMany::Many(list) => Self(list),
+        }
+    }
+}
+
+impl From<&str> for CorsOrigins {
+    /// Parse a comma-separated list
+    fn from(value: &str) -> Self {
+        Self(
+            value
+                .split(',')
+                .map(str::trim)
+                .filter(|s| !s.is_empty())
+                .map(str::to_string)
+                .collect(),
+        )
+    }
+}
+
+/// A validated origin rule
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum OriginRule {
+    Exact(String),
+    /// Scheme and the domain suffix after `*.`, plus an optional `:port`
+    Subdomain {
+        scheme: String,
+        suffix: String,
+        port: Option<String>,
+    },
+}
+
+/// Origins allowed to make credentialed cross-origin requests
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct CorsPolicy {
+    permissive: bool,
+    rules: Vec<OriginRule>,
+}
+
+impl CorsPolicy {
+    /// Validate configured origins
+    pub fn new(origins: &CorsOrigins, permissive: bool) -> Result<Self, ConfigError> {
+        let mut rules = Vec::new();
+        for entry in &origins.0 {
+            let entry = entry.trim();
+            if entry == "*" {
+                // Kept for configs written for older versions, where it was
+                // ignored; a bare wildcard is invalid with credentials
+                tracing::warn!(
+                    "cors_origin '*' is ignored; list origins or set cors_permissive for development"
+                );
+                continue;
+            }
+            rules.push(parse_rule(entry).map_err(|reason| {
+                ConfigError::ValidationError(format!(
+                    "server.cors_origin entry '{}': {}",
+                    entry, reason
+                ))
+            })?);
+        }
+        Ok(Self { permissive, rules })
+    }
+
+    /// Whether every origin is mirrored
+    pub fn is_permissive(&self) -> bool {
+        self.permissive
+    }
+
+    /// Whether a request `Origin` header value is allowed
+    pub fn allows(&self, origin: &str) -> bool {
+        if self.permissive {
+            return true;
+        }
+        let origin = origin.to_ascii_lowercase();
+        self.rules.iter().any(|rule| match rule {
+            OriginRule::Exact(allowed) => *allowed == origin,
+            OriginRule::Subdomain {
+                scheme,
+                suffix,
+                port,
+            } => {
+                let Some(rest) = origin
+                    .strip_prefix(scheme.as_str())
+                    .and_then(|r| r.strip_prefix("://"))
+                else {
+                    return false;
+                };
+                let (host, origin_port) = match rest.rsplit_once(':') {
+                    Some((host, p)) if p.bytes().all(|b| b.is_ascii_digit()) => (host, Some(p)),
+                    _ => (rest, None),
+                };
+                origin_port == port.as_deref()
+                    && host
+                        .strip_suffix(suffix.as_str())
+                        .and_then(|sub| sub.strip_suffix('.'))
+                        .is_some_and(|sub| !sub.is_empty())
+            }
+        })
+    }
+}
+
+fn parse_rule(entry: &str) -> Result<OriginRule, &'static str> {
+    let entry = entry.trim_end_matches('/').to_ascii_lowercase();
+    let (scheme, rest) = entry
+        .split_once("://")
+        .ok_or("expected scheme://host[:port]")?;
+    if scheme != "http" && scheme != "https" {
+        return Err("scheme must be http or https");
+    }
+    if rest.is_empty() || rest.contains(['/', '?', '#', '@', ' ']) {
+        return Err("origins have no path, query or credentials");
+    }
+
+    let Some(domain) = rest.strip_prefix("*.") else {
+        if rest.contains('*') {
+            return Err("'*' is only allowed as the first label, as in https://*.example.com");
+        }
+        return Ok(OriginRule::Exact(entry.clone()));
+    };
+    let (suffix, port) = match domain.split_once(':') {
+        Some((suffix, port)) => {
+            port.parse::<u16>().map_err(|_| "invalid port")?;
+            (suffix, Some(port.to_string()))
+        }
+        None => (domain, None),
+    };
+    if suffix.is_empty() || suffix.contains('*') || !suffix.contains('.') {
+        return Err("wildcards need a domain with at least two labels, as in *.example.com");
+    }
+    Ok(OriginRule::Subdomain {
+        scheme: scheme.to_string(),
+        suffix: suffix.to_string(),
+        port,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn policy(origins: &[&str]) -> CorsPolicy {
+        CorsPolicy::new(
+            &CorsOrigins(origins.iter().map(|s| s.to_string()).collect()),
+            false,
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn matches_exact_and_wildcard_origins() {
+        let policy = policy(&[
+            "https://blog.example.com/",
+            "https://*.example.org",
+            "http://*.local.test:3000",
+        ]);
+        assert!(policy.allows("https://blog.example.com"));
+        assert!(policy.allows("HTTPS://Blog.Example.com"));
+        assert!(!policy.allows("http://blog.example.com"));
+
+        assert!(policy.allows("https://a.example.org"));
+        assert!(policy.allows("https://a.b.example.org"));
+        assert!(!policy.allows("https://example.org"));
+        assert!(!policy.allows("https://evilexample.org"));
+        assert!(!policy.allows("https://a.example.org:8443"));
+        assert!(!policy.allows("http://a.example.org"));
+
+        assert!(policy.allows("http://app.local.test:3000"));
+        assert!(!policy.allows("http://app.local.test"));
+    }
+
+    #[test]
+    fn rejects_invalid_entries_and_ignores_bare_wildcard() {
+        for bad in [
+            "example.com",
+            "ftp://example.com",
+            "https://example.com/path",
+            "https://a.*.example.com",
+            "https://*.com",
+            "https://*.example.com:http",
+        ] {
+            assert!(
+                CorsPolicy::new(&CorsOrigins(vec![bad.to_string()]), false).is_err(),
+                "{bad}"
+            );
+        }
+        let policy = policy(&["*"]);
+        assert!(!policy.allows("https://example.com"));
+        let permissive = CorsPolicy::new(&CorsOrigins(vec![]), true).unwrap();
+        assert!(permissive.allows("https://anything.test"));
+    }
+
+    #[test]
+    fn deserializes_string_or_list() {
+        let one: CorsOrigins = serde_yaml::from_str("\"https://a.com, https://b.com\"").unwrap();
+        assert_eq!(one.0, vec!["https://a.com", "https://b.com"]);
+        let many: CorsOrigins = serde_yaml::from_str("[https://a.com, https://*.b.com]").unwrap();
+        assert_eq!(many.0, vec!["https://a.com", "https://*.b.com"]);
+    }
+}
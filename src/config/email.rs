@@ -0,0 +1,100 @@
+//! Outgoing email configuration
+//!
+//! ```yaml
+//! email:
+//!   smtp:
+//!     host: smtp.example.com
+//!     port: 587
+//!     tls: starttls        # starttls | tls (implicit, usually port 465) | none
+//!     username: blog@example.com
+//!     password: secret     # or NOTEVA_EMAIL_SMTP_PASSWORD
+//!     from: blog@example.com
+//!     from_name: My Blog
+//!     timeout_secs: 30
+//! ```
+//!
+//! Without `smtp` the SMTP settings saved in the admin panel are used; a
+//! server configured here takes precedence over them. Verification codes,
+//! monitor alerts and test emails are all sent through this server.
+
+use serde::{Deserialize, Serialize};
+
+/// How the SMTP connection is encrypted
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SmtpTls {
+    /// Plain connection upgraded with STARTTLS (required)
+    #[default]
+    Starttls,
+    /// TLS from the start (SMTPS)
+    Tls,
+    /// No encryption; only for a relay on localhost or a private network
+    None,
+}
+
+impl SmtpTls {
+    /// Parse the `smtp_tls` setting value
+    pub fn parse(s: &str) -> Option<Self> {
+        match s.trim().to_lowercase().as_str() {
+            "starttls" => Some(Self::Starttls),
+            "tls" | "ssl" | "smtps" => Some(Self::Tls),
+            "none" | "off" => Some(Self::None),
+            _ => None,
+        }
+    }
+
+    /// Encryption usually expected on `port`
+    pub fn for_port(port: u16) -> Self {
+        match port {
+            465 => Self::Tls,
+            _ => Self::Starttls,
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Starttls => "starttls",
+            Self::Tls => "tls",
+            Self::None => "none",
+        }
+    }
+}
+
+/// Email settings under `email`
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct EmailConfig {
+    /// SMTP server; the admin panel settings are used when missing
+    #[serde(default)]
+    pub smtp: Option<SmtpConfig>,
+}
+
+/// SMTP server used for all outgoing mail
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SmtpConfig {
+    pub host: String,
+    #[serde(default = "default_port")]
+    pub port: u16,
+    #[serde(default)]
+    pub tls: SmtpTls,
+    /// Leave out for servers that accept mail without authentication
+    #[serde(default)]
+    pub username: Option<String>,
+    #[serde(default)]
+    pub password: Option<String>,
+    /// Sender address
+    pub from: String,
+    /// Sender display name; defaults to "Noteva"
+    #[serde(default)]
+    pub from_name: Option<String>,
+    /// Connection and command timeout in seconds
+    #[serde(default = "default_timeout_secs")]
+    pub timeout_secs: u64,
+}
+
+fn default_port() -> u16 {
+    587
+}
+
+fn default_timeout_secs() -> u64 {
+    30
+}
@@ -0,0 +1,73 @@
+//! Persistent job queue configuration
+//!
+//! ```yaml
+//! job_queue:
+//!   workers: 2             # 0 leaves queued jobs unprocessed
+//!   max_attempts: 5        # then the job is dead until retried by hand
+//!   retry_base_secs: 30    # doubled after every failed attempt
+//!   retry_max_secs: 3600
+//!   timeout_secs: 300      # an attempt running longer fails
+//!   keep_days: 7           # succeeded jobs are deleted after this; dead ones are kept
+//! ```
+
+use serde::{Deserialize, Serialize};
+
+/// Job queue settings under `job_queue`
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct JobQueueConfig {
+    /// Worker tasks claiming jobs
+    #[serde(default = "default_workers")]
+    pub workers: usize,
+    /// Attempts before a job is moved to the dead letter status
+    #[serde(default = "default_max_attempts")]
+    pub max_attempts: u32,
+    /// Delay before the first retry, in seconds
+    #[serde(default = "default_retry_base_secs")]
+    pub retry_base_secs: u64,
+    /// Longest delay between retries, in seconds
+    #[serde(default = "default_retry_max_secs")]
+    pub retry_max_secs: u64,
+    /// Time limit of one attempt, in seconds
+    #[serde(default = "default_timeout_secs")]
+    pub timeout_secs: u64,
+    /// Days succeeded jobs are kept for inspection
+    #[serde(default = "default_keep_days")]
+    pub keep_days: u64,
+}
+
+impl Default for JobQueueConfig {
+    fn default() -> Self {
+        Self {
+            workers: default_workers(),
+            max_attempts: default_max_attempts(),
+            retry_base_secs: default_retry_base_secs(),
+            retry_max_secs: default_retry_max_secs(),
+            timeout_secs: default_timeout_secs(),
+            keep_days: default_keep_days(),
+        }
+    }
+}
+
+fn default_workers() -> usize {
+    2
+}
+
+fn default_max_attempts() -> u32 {
+    5
+}
+
+fn default_retry_base_secs() -> u64 {
+    30
+}
+
+fn default_retry_max_secs() -> u64 {
+    3600
+}
+
+fn default_timeout_secs() -> u64 {
+    300
+}
+
+fn default_keep_days() -> u64 {
+    7
+}
@@ -0,0 +1,70 @@
+//! Log output configuration
+//!
+//! ```yaml
+//! logging:
+//!   stdout: true
+//!   file: "data/logs/noteva.log"   # unset: no log file
+//!   rotation: daily                # never | hourly | daily
+//!   max_size_mb: 50                # also rotate at this size; 0 = no limit
+//!   keep: 7                        # rotated files kept; 0 keeps all
+//! ```
+//!
+//! Rotated files are renamed to `noteva.log.<timestamp>` next to the
+//! active file.
+
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+
+/// When the log file is rotated regardless of its size
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum LogRotation {
+    Never,
+    Hourly,
+    #[default]
+    Daily,
+}
+
+/// Log settings under `logging`
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LoggingConfig {
+    /// Write logs to stdout
+    #[serde(default = "default_stdout")]
+    pub stdout: bool,
+    /// Also write logs (without colors) to this file
+    #[serde(default)]
+    pub file: Option<PathBuf>,
+    /// Time-based rotation of the log file
+    #[serde(default)]
+    pub rotation: LogRotation,
+    /// Size in MiB at which the log file is rotated early; 0 disables it
+    #[serde(default = "default_max_size_mb")]
+    pub max_size_mb: u64,
+    /// Number of rotated files to keep; 0 keeps all
+    #[serde(default = "default_keep")]
+    pub keep: usize,
+}
+
+impl Default for LoggingConfig {
+    fn default() -> Self {
+        Self {
+            stdout: default_stdout(),
+            file: None,
+            rotation: LogRotation::default(),
+            max_size_mb: default_max_size_mb(),
+            keep: default_keep(),
+        }
+    }
+}
+
+fn default_stdout() -> bool {
+    true
+}
+
+fn default_max_size_mb() -> u64 {
+    50
+}
+
+fn default_keep() -> usize {
+    7
+}
@@ -0,0 +1,133 @@
+//! Markdown engine configuration
+//!
+//! ```yaml
+//! markdown:
+//!   engine: pulldown     # pulldown (pulldown-cmark) | comrak
+//!   hard_breaks: true    # a single newline becomes <br>
+//!   pulldown:
+//!     tables: true
+//!     strikethrough: true
+//!     tasklists: true
+//!     footnotes: true
+//!     smart_punctuation: true
+//!   comrak:
+//!     tables: true
+//!     strikethrough: true
+//!     tasklists: true
+//!     footnotes: true
+//!     smart_punctuation: true
+//!     autolink: true     # GFM bare URL links
+//!     superscript: false # ^text^
+//!     description_lists: false
+//! ```
+//!
+//! Only the options of the selected engine apply. Turning every extension
+//! off and `hard_breaks` to false gives strict CommonMark; comrak with
+//! `autolink` on covers GitHub Flavored Markdown. Shortcodes, math, image
+//! grids and sanitizing work the same with either engine.
+
+use serde::{Deserialize, Serialize};
+
+/// Library that turns Markdown into HTML
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum MarkdownEngineKind {
+    #[default]
+    Pulldown,
+    Comrak,
+}
+
+/// Markdown settings under `markdown`
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MarkdownConfig {
+    #[serde(default)]
+    pub engine: MarkdownEngineKind,
+    /// Render soft line breaks as `<br>` instead of a space
+    #[serde(default = "default_true")]
+    pub hard_breaks: bool,
+    #[serde(default)]
+    pub pulldown: PulldownOptions,
+    #[serde(default)]
+    pub comrak: ComrakOptions,
+}
+
+impl Default for MarkdownConfig {
+    fn default() -> Self {
+        Self {
+            engine: MarkdownEngineKind::default(),
+            hard_breaks: true,
+            pulldown: PulldownOptions::default(),
+            comrak: ComrakOptions::default(),
+        }
+    }
+}
+
+/// Extensions of the pulldown-cmark engine
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PulldownOptions {
+    #[serde(default = "default_true")]
+    pub tables: bool,
+    #[serde(default = "default_true")]
+    pub strikethrough: bool,
+    #[serde(default = "default_true")]
+    pub tasklists: bool,
+    #[serde(default = "default_true")]
+    pub footnotes: bool,
+    /// Curly quotes, dashes and ellipses
+    #[serde(default = "default_true")]
+    pub smart_punctuation: bool,
+}
+
+impl Default for PulldownOptions {
+    fn default() -> Self {
+        Self {
+            tables: true,
+            strikethrough: true,
+            tasklists: true,
+            footnotes: true,
+            smart_punctuation: true,
+        }
+    }
+}
+
+/// Extensions of the comrak engine
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ComrakOptions {
+    #[serde(default = "default_true")]
+    pub tables: bool,
+    #[serde(default = "default_true")]
+    pub strikethrough: bool,
+    #[serde(default = "default_true")]
+    pub tasklists: bool,
+    #[serde(default = "default_true")]
+    pub footnotes: bool,
+    /// Curly quotes, dashes and ellipses
+    #[serde(default = "default_true")]
+    pub smart_punctuation: bool,
+    /// Link bare URLs and email addresses
+    #[serde(default = "default_true")]
+    pub autolink: bool,
+    #[serde(default)]
+    pub superscript: bool,
+    #[serde(default)]
+    pub description_lists: bool,
+}
+
+impl Default for ComrakOptions {
+    fn default() -> Self {
+        Self {
+            tables: true,
+            strikethrough: true,
+            tasklists: true,
+            footnotes: true,
+            smart_punctuation: true,
+            autolink: true,
+            superscript: false,
+            description_lists: false,
+        }
+    }
+}
+
+fn default_true() -> bool {
+    true
+}
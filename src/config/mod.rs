@@ -8,8 +8,35 @@
 //! Missing optional values are filled with sensible defaults.
 
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
 use std::path::PathBuf;
 
+mod analytics;
+mod backup;
+mod compression;
+mod cors;
+mod email;
+mod job_queue;
+mod logging;
+mod markdown;
+mod monitor;
+mod spellcheck;
+mod status_page;
+mod views;
+
+pub use analytics::AnalyticsConfig;
+pub use backup::BackupConfig;
+pub use compression::CompressionConfig;
+pub use cors::{CorsOrigins, CorsPolicy};
+pub use email::{EmailConfig, SmtpConfig, SmtpTls};
+pub use job_queue::JobQueueConfig;
+pub use logging::{LogRotation, LoggingConfig};
+pub use markdown::{ComrakOptions, MarkdownConfig, MarkdownEngineKind, PulldownOptions};
+pub use monitor::MonitorConfig;
+pub use spellcheck::SpellcheckConfig;
+pub use status_page::StatusPageConfig;
+pub use views::ViewsConfig;
+
 /// Main configuration structure
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Config {
@@ -28,6 +55,48 @@ pub struct Config {
     /// Upload configuration
     #[serde(default)]
     pub upload: UploadConfig,
+    /// Authentication backend configuration
+    #[serde(default)]
+    pub auth: AuthConfig,
+    /// SAML single sign-on configuration (requires the `saml` feature)
+    #[serde(default)]
+    pub saml: SamlConfig,
+    /// API rate limiting configuration
+    #[serde(default)]
+    pub rate_limit: RateLimitConfig,
+    /// Log output and file rotation
+    #[serde(default)]
+    pub logging: LoggingConfig,
+    /// OpenTelemetry trace export (requires the `otel` feature)
+    #[serde(default)]
+    pub telemetry: TelemetryConfig,
+    /// Backup archives and schedule
+    #[serde(default)]
+    pub backup: BackupConfig,
+    /// Public status page
+    #[serde(default)]
+    pub status_page: StatusPageConfig,
+    /// Resource self-monitoring
+    #[serde(default)]
+    pub monitor: MonitorConfig,
+    /// Persistent background job queue
+    #[serde(default)]
+    pub job_queue: JobQueueConfig,
+    /// Markdown engine and its extensions
+    #[serde(default)]
+    pub markdown: MarkdownConfig,
+    /// Outgoing email (SMTP)
+    #[serde(default)]
+    pub email: EmailConfig,
+    /// Hunspell dictionaries for draft spell checking
+    #[serde(default)]
+    pub spellcheck: SpellcheckConfig,
+    /// Built-in page view analytics
+    #[serde(default)]
+    pub analytics: AnalyticsConfig,
+    /// Article view counting (`POST /view/{article_id}`)
+    #[serde(default)]
+    pub views: ViewsConfig,
 }
 
 impl Default for Config {
@@ -38,6 +107,20 @@ impl Default for Config {
             cache: CacheConfig::default(),
             theme: ThemeConfig::default(),
             upload: UploadConfig::default(),
+            auth: AuthConfig::default(),
+            saml: SamlConfig::default(),
+            rate_limit: RateLimitConfig::default(),
+            logging: LoggingConfig::default(),
+            telemetry: TelemetryConfig::default(),
+            backup: BackupConfig::default(),
+            status_page: StatusPageConfig::default(),
+            monitor: MonitorConfig::default(),
+            job_queue: JobQueueConfig::default(),
+            markdown: MarkdownConfig::default(),
+            email: EmailConfig::default(),
+            spellcheck: SpellcheckConfig::default(),
+            analytics: AnalyticsConfig::default(),
+            views: ViewsConfig::default(),
         }
     }
 }
@@ -51,9 +134,20 @@ pub struct ServerConfig {
     /// Port to listen on
     #[serde(default = "default_port")]
     pub port: u16,
-    /// CORS allowed origin (for cookie-based auth)
-    #[serde(default = "default_cors_origin")]
-    pub cors_origin: String,
+    /// CORS allowed origins (for cookie-based auth): one origin, a
+    /// comma-separated string or a list; `https://*.example.com` matches
+    /// subdomains
+    #[serde(default = "default_cors_origin", alias = "cors_origins")]
+    pub cors_origin: CorsOrigins,
+    /// Mirror any request origin (local development only)
+    #[serde(default)]
+    pub cors_permissive: bool,
+    /// Response compression
+    #[serde(default)]
+    pub compression: CompressionConfig,
+    /// Terminate TLS in-process instead of behind a reverse proxy
+    #[serde(default)]
+    pub tls: Option<TlsConfig>,
 }
 
 impl Default for ServerConfig {
@@ -62,10 +156,69 @@ impl Default for ServerConfig {
             host: default_host(),
             port: default_port(),
             cors_origin: default_cors_origin(),
+            cors_permissive: false,
+            compression: CompressionConfig::default(),
+            tls: None,
         }
     }
 }
 
+impl ServerConfig {
+    /// Validate the CORS settings
+    pub fn cors_policy(&self) -> Result<CorsPolicy, ConfigError> {
+        CorsPolicy::new(&self.cors_origin, self.cors_permissive)
+    }
+}
+
+/// Native HTTPS settings under `server.tls`
+///
+/// When set, `server.port` serves HTTPS, either with a certificate from
+/// `cert_path`/`key_path` (read once at startup; restart after renewing it)
+/// or with certificates obtained and renewed automatically through `acme`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TlsConfig {
+    /// PEM certificate chain, leaf certificate first
+    #[serde(default)]
+    pub cert_path: Option<PathBuf>,
+    /// PEM private key (PKCS#8, PKCS#1 or SEC1)
+    #[serde(default)]
+    pub key_path: Option<PathBuf>,
+    /// Also listen for plain HTTP on this port and redirect it to HTTPS
+    /// (defaults to 80 with `acme`, which answers challenges there)
+    #[serde(default)]
+    pub redirect_http_port: Option<u16>,
+    /// Obtain certificates from an ACME CA such as Let's Encrypt
+    #[serde(default)]
+    pub acme: Option<AcmeConfig>,
+}
+
+/// Automatic certificates under `server.tls.acme`
+///
+/// Domains are validated with HTTP-01 challenges, so port 80 of every
+/// domain must reach the plain HTTP listener.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AcmeConfig {
+    /// Domains on the certificate; the first is its common name
+    pub domains: Vec<String>,
+    /// Contact address for expiry notices from the CA
+    #[serde(default)]
+    pub email: Option<String>,
+    /// ACME directory of the CA
+    #[serde(default = "default_acme_directory_url")]
+    pub directory_url: String,
+    /// Where the account key, certificate and key are kept
+    #[serde(default = "default_acme_state_dir")]
+    pub state_dir: PathBuf,
+}
+
+fn default_acme_directory_url() -> String {
+    "https://acme-v02.api.letsencrypt.org/directory".to_string()
+}
+
+fn default_acme_state_dir() -> PathBuf {
+    PathBuf::from("data/acme")
+}
+
 fn default_host() -> String {
     "0.0.0.0".to_string()
 }
@@ -74,8 +227,8 @@ fn default_port() -> u16 {
     8080
 }
 
-fn default_cors_origin() -> String {
-    "http://localhost:3000".to_string()
+fn default_cors_origin() -> CorsOrigins {
+    CorsOrigins::from("http://localhost:3000")
 }
 
 /// Database configuration
@@ -98,6 +251,27 @@ impl Default for DatabaseConfig {
     }
 }
 
+impl DatabaseConfig {
+    /// Directory holding the SQLite database file; `None` for MySQL and
+    /// in-memory databases
+    pub fn data_dir(&self) -> Option<PathBuf> {
+        if self.driver != DatabaseDriver::Sqlite || self.url.contains(":memory:") {
+            return None;
+        }
+        let path = self
+            .url
+            .trim_start_matches("sqlite:")
+            .trim_start_matches("//");
+        let path = path.split('?').next().unwrap_or(path);
+        let dir = std::path::Path::new(path).parent()?;
+        Some(if dir.as_os_str().is_empty() {
+            PathBuf::from(".")
+        } else {
+            dir.to_path_buf()
+        })
+    }
+}
+
 fn default_database_url() -> String {
     "data/noteva.db".to_string()
 }
@@ -195,6 +369,12 @@ pub struct UploadConfig {
     /// Allowed image MIME types
     #[serde(default = "default_allowed_types")]
     pub allowed_types: Vec<String>,
+    /// File extensions accepted as article attachments
+    #[serde(default = "default_attachment_extensions")]
+    pub attachment_extensions: Vec<String>,
+    /// Maximum attachment size in bytes (default: 50MB)
+    #[serde(default = "default_max_attachment_size")]
+    pub max_attachment_size: u64,
 }
 
 impl Default for UploadConfig {
@@ -204,6 +384,8 @@ impl Default for UploadConfig {
             max_file_size: default_max_file_size(),
             max_plugin_file_size: default_max_plugin_file_size(),
             allowed_types: default_allowed_types(),
+            attachment_extensions: default_attachment_extensions(),
+            max_attachment_size: default_max_attachment_size(),
         }
     }
 }
@@ -230,6 +412,20 @@ fn default_allowed_types() -> Vec<String> {
     ]
 }
 
+fn default_attachment_extensions() -> Vec<String> {
+    [
+        "pdf", "zip", "7z", "tar", "gz", "txt", "md", "csv", "epub", "doc", "docx", "xls", "xlsx",
+        "ppt", "pptx", "odt", "ods", "odp", "mp3", "mp4",
+    ]
+    .iter()
+    .map(|ext| ext.to_string())
+    .collect()
+}
+
+fn default_max_attachment_size() -> u64 {
+    50 * 1024 * 1024 // 50MB
+}
+
 impl UploadConfig {
     /// Check if a MIME type is allowed
     pub fn is_type_allowed(&self, mime_type: &str) -> bool {
@@ -252,6 +448,300 @@ impl UploadConfig {
     }
 }
 
+/// Authentication backend configuration
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct AuthConfig {
+    /// Where passwords are checked (local or ldap)
+    #[serde(default)]
+    pub backend: AuthBackend,
+    /// LDAP / Active Directory settings, used with `backend: ldap`
+    #[serde(default)]
+    pub ldap: LdapConfig,
+}
+
+/// Password authentication backend
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum AuthBackend {
+    /// Local accounts with argon2 password hashes (default)
+    #[default]
+    Local,
+    /// LDAP or Active Directory; users are provisioned on first login
+    Ldap,
+}
+
+/// LDAP / Active Directory configuration
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LdapConfig {
+    /// Server URL (`ldap://` or `ldaps://`)
+    #[serde(default)]
+    pub url: String,
+    /// Upgrade `ldap://` connections with StartTLS
+    #[serde(default)]
+    pub starttls: bool,
+    /// Skip TLS certificate verification (testing only)
+    #[serde(default)]
+    pub tls_insecure: bool,
+    /// DN of the service account used to search for users (anonymous if unset)
+    #[serde(default)]
+    pub bind_dn: Option<String>,
+    /// Password of the service account
+    #[serde(default)]
+    pub bind_password: Option<String>,
+    /// Base DN for user searches
+    #[serde(default)]
+    pub base_dn: String,
+    /// Search filter; `{username}` is replaced with the escaped login name.
+    /// Use `(sAMAccountName={username})` for Active Directory.
+    #[serde(default = "default_ldap_user_filter")]
+    pub user_filter: String,
+    /// Attribute holding the username (defaults to the login name as typed)
+    #[serde(default)]
+    pub username_attribute: Option<String>,
+    /// Attribute holding the email address
+    #[serde(default = "default_ldap_email_attribute")]
+    pub email_attribute: String,
+    /// Attribute holding the display name
+    #[serde(default = "default_ldap_display_name_attribute")]
+    pub display_name_attribute: String,
+    /// Attribute listing the user's groups
+    #[serde(default = "default_ldap_group_attribute")]
+    pub group_attribute: String,
+    /// Group (full DN or its CN) to role (`admin`, `editor` or `author`);
+    /// the highest matching role wins
+    #[serde(default)]
+    pub role_mapping: BTreeMap<String, String>,
+    /// Role for users in no mapped group; such users are refused when unset
+    #[serde(default)]
+    pub default_role: Option<String>,
+    /// Let local accounts sign in when the directory rejects them or is down
+    #[serde(default = "default_true")]
+    pub allow_local_fallback: bool,
+    /// Adopt an unbound local account with the same email on its first
+    /// directory login; adopted accounts keep their local role. Without it,
+    /// such logins are refused until an administrator links the account.
+    #[serde(default)]
+    pub link_by_email: bool,
+    /// Connection and operation timeout in seconds
+    #[serde(default = "default_ldap_timeout")]
+    pub timeout_seconds: u64,
+}
+
+impl Default for LdapConfig {
+    fn default() -> Self {
+        Self {
+            url: String::new(),
+            starttls: false,
+            tls_insecure: false,
+            bind_dn: None,
+            bind_password: None,
+            base_dn: String::new(),
+            user_filter: default_ldap_user_filter(),
+            username_attribute: None,
+            email_attribute: default_ldap_email_attribute(),
+            display_name_attribute: default_ldap_display_name_attribute(),
+            group_attribute: default_ldap_group_attribute(),
+            role_mapping: BTreeMap::new(),
+            default_role: None,
+            allow_local_fallback: true,
+            link_by_email: false,
+            timeout_seconds: default_ldap_timeout(),
+        }
+    }
+}
+
+fn default_ldap_user_filter() -> String {
+    "(uid={username})".to_string()
+}
+
+fn default_ldap_email_attribute() -> String {
+    "mail".to_string()
+}
+
+fn default_ldap_display_name_attribute() -> String {
+    "displayName".to_string()
+}
+
+fn default_ldap_group_attribute() -> String {
+    "memberOf".to_string()
+}
+
+fn default_true() -> bool {
+    true
+}
+
+fn default_ldap_timeout() -> u64 {
+    10
+}
+
+/// SAML service provider configuration
+///
+/// Only used when the binary is built with the `saml` feature.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct SamlConfig {
+    /// Enable SAML sign-in
+    #[serde(default)]
+    pub enabled: bool,
+    /// Entity ID of this service provider
+    #[serde(default)]
+    pub sp_entity_id: String,
+    /// Assertion consumer service URL (`https://<site>/api/v1/auth/saml/acs`)
+    #[serde(default)]
+    pub acs_url: String,
+    /// Entity ID of the identity provider
+    #[serde(default)]
+    pub idp_entity_id: String,
+    /// Single sign-on URL of the identity provider (HTTP-Redirect binding)
+    #[serde(default)]
+    pub idp_sso_url: String,
+    /// PEM-encoded signing certificate of the identity provider
+    #[serde(default)]
+    pub idp_certificate: String,
+    /// Attribute holding the username (defaults to the NameID)
+    #[serde(default)]
+    pub username_attribute: Option<String>,
+    /// Attribute holding the email address (defaults to `email`)
+    #[serde(default)]
+    pub email_attribute: Option<String>,
+    /// Attribute holding the display name
+    #[serde(default)]
+    pub display_name_attribute: Option<String>,
+    /// Attribute whose values are mapped to roles, e.g. group membership
+    #[serde(default)]
+    pub role_attribute: Option<String>,
+    /// Attribute value to role (`admin`, `editor` or `author`); the highest
+    /// matching role wins
+    #[serde(default)]
+    pub role_mapping: BTreeMap<String, String>,
+    /// Role for users without a mapped value; such users are refused when unset
+    #[serde(default)]
+    pub default_role: Option<String>,
+    /// Accept unsolicited (IdP-initiated) responses
+    #[serde(default)]
+    pub allow_idp_initiated: bool,
+    /// Adopt an existing local account with the asserted email address on
+    /// its first SAML sign-in. Only enable when the IdP verifies email
+    /// addresses; adopted accounts keep their local role.
+    #[serde(default)]
+    pub link_by_email: bool,
+    /// Where to send the browser after signing in (defaults to `/manage`)
+    #[serde(default)]
+    pub login_redirect: Option<String>,
+}
+
+/// API rate limiting configuration
+///
+/// Each class of request has its own token bucket per client: per session
+/// token for signed-in users, otherwise per IP address.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RateLimitConfig {
+    /// Enable rate limiting
+    #[serde(default = "default_true")]
+    pub enabled: bool,
+    /// Where counters are kept (memory or redis)
+    #[serde(default)]
+    pub driver: CacheDriver,
+    /// Redis connection URL (defaults to `cache.redis_url`)
+    #[serde(default)]
+    pub redis_url: Option<String>,
+    /// Public API requests
+    #[serde(default = "default_api_rate_limit")]
+    pub api: RateLimitRule,
+    /// Comment submissions
+    #[serde(default = "default_comment_rate_limit")]
+    pub comments: RateLimitRule,
+    /// File uploads
+    #[serde(default = "default_upload_rate_limit")]
+    pub uploads: RateLimitRule,
+}
+
+impl Default for RateLimitConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            driver: CacheDriver::default(),
+            redis_url: None,
+            api: default_api_rate_limit(),
+            comments: default_comment_rate_limit(),
+            uploads: default_upload_rate_limit(),
+        }
+    }
+}
+
+/// Token bucket limits for one class of requests
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RateLimitRule {
+    /// Sustained rate in requests per minute; 0 disables the limit
+    pub per_minute: u32,
+    /// Requests allowed in a burst (bucket size)
+    pub burst: u32,
+}
+
+fn default_api_rate_limit() -> RateLimitRule {
+    RateLimitRule {
+        per_minute: 300,
+        burst: 60,
+    }
+}
+
+fn default_comment_rate_limit() -> RateLimitRule {
+    RateLimitRule {
+        per_minute: 5,
+        burst: 3,
+    }
+}
+
+fn default_upload_rate_limit() -> RateLimitRule {
+    RateLimitRule {
+        per_minute: 30,
+        burst: 10,
+    }
+}
+
+/// OpenTelemetry tracing configuration
+///
+/// Only used when the binary is built with the `otel` feature. Spans are
+/// exported over OTLP/HTTP (protobuf).
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TelemetryConfig {
+    /// Export traces
+    #[serde(default)]
+    pub enabled: bool,
+    /// OTLP/HTTP traces endpoint
+    #[serde(default = "default_otlp_endpoint")]
+    pub endpoint: String,
+    /// `service.name` reported with every span
+    #[serde(default = "default_service_name")]
+    pub service_name: String,
+    /// Fraction of new traces to sample (0.0 - 1.0); traces started by an
+    /// upstream `traceparent` follow the caller's decision
+    #[serde(default = "default_sample_ratio")]
+    pub sample_ratio: f64,
+}
+
+impl Default for TelemetryConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            endpoint: default_otlp_endpoint(),
+            service_name: default_service_name(),
+            sample_ratio: default_sample_ratio(),
+        }
+    }
+}
+
+fn default_otlp_endpoint() -> String {
+    "http://localhost:4318/v1/traces".to_string()
+}
+
+fn default_service_name() -> String {
+    "noteva".to_string()
+}
+
+fn default_sample_ratio() -> f64 {
+    1.0
+}
+
 /// Error type for configuration parsing
 #[derive(Debug, thiserror::Error)]
 pub enum ConfigError {
@@ -307,6 +797,11 @@ impl Config {
     /// Environment variables follow the pattern:
     /// - NOTEVA_SERVER_HOST
     /// - NOTEVA_SERVER_PORT
+    /// - NOTEVA_SERVER_CORS_ORIGIN (comma separated)
+    /// - NOTEVA_SERVER_CORS_PERMISSIVE
+    /// - NOTEVA_SERVER_COMPRESSION_ENABLED
+    /// - NOTEVA_SERVER_TLS_CERT_PATH / NOTEVA_SERVER_TLS_KEY_PATH (both required)
+    /// - NOTEVA_SERVER_TLS_REDIRECT_HTTP_PORT
     /// - NOTEVA_DATABASE_DRIVER
     /// - NOTEVA_DATABASE_URL
     /// - NOTEVA_CACHE_DRIVER
@@ -314,6 +809,16 @@ impl Config {
     /// - NOTEVA_CACHE_TTL_SECONDS
     /// - NOTEVA_THEME_ACTIVE
     /// - NOTEVA_THEME_PATH
+    /// - NOTEVA_AUTH_BACKEND
+    /// - NOTEVA_AUTH_LDAP_BIND_PASSWORD
+    /// - NOTEVA_RATE_LIMIT_ENABLED
+    /// - NOTEVA_RATE_LIMIT_DRIVER
+    /// - NOTEVA_RATE_LIMIT_REDIS_URL
+    /// - NOTEVA_LOG_FILE
+    /// - NOTEVA_TELEMETRY_ENABLED
+    /// - NOTEVA_TELEMETRY_ENDPOINT
+    /// - NOTEVA_BACKUP_INTERVAL_HOURS
+    /// - NOTEVA_EMAIL_SMTP_PASSWORD (when `email.smtp` is configured)
     ///
     /// Satisfies requirement:
     /// - 11.5: THE Noteva_System SHALL 支持通过环境变量覆盖配置�?
@@ -339,7 +844,30 @@ impl Config {
             }
         }
         if let Ok(cors_origin) = std::env::var("NOTEVA_SERVER_CORS_ORIGIN") {
-            self.server.cors_origin = cors_origin;
+            self.server.cors_origin = CorsOrigins::from(cors_origin.as_str());
+        }
+        if let Ok(permissive) = std::env::var("NOTEVA_SERVER_CORS_PERMISSIVE") {
+            self.server.cors_permissive = permissive.eq_ignore_ascii_case("true");
+        }
+        if let Ok(enabled) = std::env::var("NOTEVA_SERVER_COMPRESSION_ENABLED") {
+            self.server.compression.enabled = enabled.eq_ignore_ascii_case("true");
+        }
+        if let (Ok(cert_path), Ok(key_path)) = (
+            std::env::var("NOTEVA_SERVER_TLS_CERT_PATH"),
+            std::env::var("NOTEVA_SERVER_TLS_KEY_PATH"),
+        ) {
+            let redirect_http_port = self.server.tls.as_ref().and_then(|t| t.redirect_http_port);
+            self.server.tls = Some(TlsConfig {
+                cert_path: Some(PathBuf::from(cert_path)),
+                key_path: Some(PathBuf::from(key_path)),
+                redirect_http_port,
+                acme: None,
+            });
+        }
+        if let Ok(port) = std::env::var("NOTEVA_SERVER_TLS_REDIRECT_HTTP_PORT") {
+            if let (Some(tls), Ok(port)) = (self.server.tls.as_mut(), port.parse::<u16>()) {
+                tls.redirect_http_port = Some(port);
+            }
         }
 
         // Database configuration
@@ -378,6 +906,64 @@ impl Config {
         if let Ok(path) = std::env::var("NOTEVA_THEME_PATH") {
             self.theme.path = PathBuf::from(path);
         }
+
+        // Authentication configuration
+        if let Ok(backend) = std::env::var("NOTEVA_AUTH_BACKEND") {
+            match backend.to_lowercase().as_str() {
+                "local" => self.auth.backend = AuthBackend::Local,
+                "ldap" => self.auth.backend = AuthBackend::Ldap,
+                _ => {} // Ignore invalid values
+            }
+        }
+        if let Ok(password) = std::env::var("NOTEVA_AUTH_LDAP_BIND_PASSWORD") {
+            self.auth.ldap.bind_password = Some(password);
+        }
+
+        // Rate limiting configuration
+        if let Ok(enabled) = std::env::var("NOTEVA_RATE_LIMIT_ENABLED") {
+            if let Ok(enabled) = enabled.parse::<bool>() {
+                self.rate_limit.enabled = enabled;
+            }
+        }
+        if let Ok(driver) = std::env::var("NOTEVA_RATE_LIMIT_DRIVER") {
+            match driver.to_lowercase().as_str() {
+                "memory" => self.rate_limit.driver = CacheDriver::Memory,
+                "redis" => self.rate_limit.driver = CacheDriver::Redis,
+                _ => {} // Ignore invalid values
+            }
+        }
+        if let Ok(redis_url) = std::env::var("NOTEVA_RATE_LIMIT_REDIS_URL") {
+            self.rate_limit.redis_url = Some(redis_url);
+        }
+
+        // Logging configuration
+        if let Ok(file) = std::env::var("NOTEVA_LOG_FILE") {
+            self.logging.file = Some(PathBuf::from(file));
+        }
+
+        // Telemetry configuration
+        if let Ok(enabled) = std::env::var("NOTEVA_TELEMETRY_ENABLED") {
+            if let Ok(enabled) = enabled.parse::<bool>() {
+                self.telemetry.enabled = enabled;
+            }
+        }
+        if let Ok(endpoint) = std::env::var("NOTEVA_TELEMETRY_ENDPOINT") {
+            self.telemetry.endpoint = endpoint;
+        }
+
+        // Backup configuration
+        if let Ok(hours) = std::env::var("NOTEVA_BACKUP_INTERVAL_HOURS") {
+            if let Ok(hours) = hours.parse::<u64>() {
+                self.backup.interval_hours = hours;
+            }
+        }
+
+        // Email configuration
+        if let Ok(password) = std::env::var("NOTEVA_EMAIL_SMTP_PASSWORD") {
+            if let Some(smtp) = self.email.smtp.as_mut() {
+                smtp.password = Some(password);
+            }
+        }
     }
 }
 
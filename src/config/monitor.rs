@@ -0,0 +1,73 @@
+//! Resource self-monitoring configuration
+//!
+//! ```yaml
+//! monitor:
+//!   interval_secs: 60           # 0 disables the monitor
+//!   max_rss_mb: 1024            # resident memory of the process
+//!   max_connections: 512        # in-flight requests plus database connections
+//!   max_cache_entries: 0        # entries in the in-memory cache
+//!   min_disk_free_mb: 1024      # on the database and upload volumes
+//!   notify_emails: ["ops@example.com"]
+//!   webhook_url: "https://hooks.example.com/noteva"
+//! ```
+//!
+//! A limit of 0 turns that check off. Crossed limits are always logged as
+//! warnings; emails and the webhook are only sent when configured.
+
+use serde::{Deserialize, Serialize};
+
+/// Self-monitoring settings under `monitor`
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MonitorConfig {
+    /// Seconds between checks; 0 disables the monitor
+    #[serde(default = "default_interval_secs")]
+    pub interval_secs: u64,
+    /// Resident memory limit in MiB
+    #[serde(default = "default_max_rss_mb")]
+    pub max_rss_mb: u64,
+    /// Limit on requests being handled plus open database connections
+    #[serde(default = "default_max_connections")]
+    pub max_connections: u64,
+    /// Limit on in-memory cache entries (not checked for Redis)
+    #[serde(default)]
+    pub max_cache_entries: u64,
+    /// Free space in MiB required on the database and upload volumes
+    #[serde(default = "default_min_disk_free_mb")]
+    pub min_disk_free_mb: u64,
+    /// Addresses emailed when a limit is crossed or recovers
+    #[serde(default)]
+    pub notify_emails: Vec<String>,
+    /// URL receiving a JSON POST when a limit is crossed or recovers
+    #[serde(default)]
+    pub webhook_url: Option<String>,
+}
+
+impl Default for MonitorConfig {
+    fn default() -> Self {
+        Self {
+            interval_secs: default_interval_secs(),
+            max_rss_mb: default_max_rss_mb(),
+            max_connections: default_max_connections(),
+            max_cache_entries: 0,
+            min_disk_free_mb: default_min_disk_free_mb(),
+            notify_emails: Vec::new(),
+            webhook_url: None,
+        }
+    }
+}
+
+fn default_interval_secs() -> u64 {
+    60
+}
+
+fn default_max_rss_mb() -> u64 {
+    1024
+}
+
+fn default_max_connections() -> u64 {
+    512
+}
+
+fn default_min_disk_free_mb() -> u64 {
+    1024
+}
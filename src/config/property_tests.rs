@@ -94,7 +94,10 @@ fn valid_server_config_strategy() -> impl Strategy<Value = ServerConfig> {
     (valid_host_strategy(), valid_port_strategy()).prop_map(|(host, port)| ServerConfig {
         host,
         port,
-        cors_origin: "http://localhost:3000".to_string(),
+        cors_origin: CorsOrigins::from("http://localhost:3000"),
+        cors_permissive: false,
+        compression: CompressionConfig::default(),
+        tls: None,
     })
 }
 
@@ -141,6 +144,13 @@ fn valid_config_strategy() -> impl Strategy<Value = Config> {
             cache,
             theme,
             upload: UploadConfig::default(),
+            auth: AuthConfig::default(),
+            saml: SamlConfig::default(),
+            rate_limit: RateLimitConfig::default(),
+            telemetry: TelemetryConfig::default(),
+            backup: BackupConfig::default(),
+            status_page: StatusPageConfig::default(),
+            ..Config::default()
         })
 }
 
@@ -562,11 +572,18 @@ proptest! {
         theme_name in valid_theme_name_strategy(),
     ) {
         let config = Config {
-            server: ServerConfig { host: host.clone(), port, cors_origin: "http://localhost:3000".to_string() },
+            server: ServerConfig { host: host.clone(), port, cors_origin: CorsOrigins::from("http://localhost:3000"), cors_permissive: false, compression: CompressionConfig::default(), tls: None },
             database: DatabaseConfig { driver: db_driver, url: db_url.clone() },
             cache: CacheConfig { driver: cache_driver, redis_url: None, ttl_seconds: ttl },
             theme: ThemeConfig { active: theme_name.clone(), path: PathBuf::from("themes") },
             upload: UploadConfig::default(),
+            auth: AuthConfig::default(),
+            saml: SamlConfig::default(),
+            rate_limit: RateLimitConfig::default(),
+            telemetry: TelemetryConfig::default(),
+            backup: BackupConfig::default(),
+            status_page: StatusPageConfig::default(),
+            ..Config::default()
         };
 
         // Serialize and deserialize
@@ -0,0 +1,39 @@
+//! Draft spell checking configuration
+//!
+//! ```yaml
+//! spellcheck:
+//!   dictionaries:                  # detected language -> Hunspell .aff/.dic pair
+//!     eng: "dictionaries/en_US"    # loads en_US.aff and en_US.dic
+//!     deu: "dictionaries/de_DE"
+//!   max_suggestions: 5
+//! ```
+//!
+//! Languages are ISO 639-3 codes as returned by language detection. Drafts in
+//! a language without a dictionary only get the grammar checks.
+
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+/// Spell check settings under `spellcheck`
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SpellcheckConfig {
+    /// Dictionary path without extension, by language
+    #[serde(default)]
+    pub dictionaries: BTreeMap<String, String>,
+    /// Suggestions returned per misspelled word
+    #[serde(default = "default_max_suggestions")]
+    pub max_suggestions: usize,
+}
+
+impl Default for SpellcheckConfig {
+    fn default() -> Self {
+        Self {
+            dictionaries: BTreeMap::new(),
+            max_suggestions: default_max_suggestions(),
+        }
+    }
+}
+
+fn default_max_suggestions() -> usize {
+    5
+}
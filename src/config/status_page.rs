@@ -0,0 +1,39 @@
+//! Public status page configuration
+//!
+//! ```yaml
+//! status_page:
+//!   enabled: true         # GET /api/v1/status
+//!   html: false           # also serve GET /status as a page
+//!   show_details: false   # version, latencies and errors per component
+//! ```
+
+use serde::{Deserialize, Serialize};
+
+/// Status page settings under `status_page`
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct StatusPageConfig {
+    /// Serve the status JSON; disabled answers 404
+    #[serde(default = "default_enabled")]
+    pub enabled: bool,
+    /// Serve `/status` as an HTML page (the theme's `status.html` or a built-in page)
+    #[serde(default)]
+    pub html: bool,
+    /// Include the version and per-component latency and errors; otherwise
+    /// only the overall status, uptime and component states are shown
+    #[serde(default)]
+    pub show_details: bool,
+}
+
+impl Default for StatusPageConfig {
+    fn default() -> Self {
+        Self {
+            enabled: default_enabled(),
+            html: false,
+            show_details: false,
+        }
+    }
+}
+
+fn default_enabled() -> bool {
+    true
+}
@@ -110,6 +110,179 @@ fn test_load_malformed_yaml_returns_error() {
     assert!(result.is_err());
 }
 
+#[test]
+fn test_load_ldap_auth_config_fills_defaults() {
+    let mut file = NamedTempFile::new().unwrap();
+    write!(
+        file,
+        "auth:\n  backend: ldap\n  ldap:\n    url: \"ldaps://ad.example.com\"\n    base_dn: \"dc=example,dc=com\"\n    role_mapping:\n      blog-admins: admin\n"
+    )
+    .unwrap();
+
+    let config = Config::load(file.path()).unwrap();
+
+    assert_eq!(config.auth.backend, AuthBackend::Ldap);
+    assert_eq!(config.auth.ldap.url, "ldaps://ad.example.com");
+    assert_eq!(config.auth.ldap.user_filter, "(uid={username})");
+    assert_eq!(config.auth.ldap.group_attribute, "memberOf");
+    assert_eq!(config.auth.ldap.role_mapping["blog-admins"], "admin");
+    assert!(config.auth.ldap.allow_local_fallback);
+    assert_eq!(Config::default().auth.backend, AuthBackend::Local);
+}
+
+#[test]
+fn test_load_rate_limit_config_keeps_unset_rules() {
+    let mut file = NamedTempFile::new().unwrap();
+    write!(
+        file,
+        "rate_limit:\n  driver: redis\n  comments:\n    per_minute: 2\n    burst: 1\n"
+    )
+    .unwrap();
+
+    let config = Config::load(file.path()).unwrap();
+
+    assert!(config.rate_limit.enabled);
+    assert_eq!(config.rate_limit.driver, CacheDriver::Redis);
+    assert_eq!(
+        config.rate_limit.comments,
+        RateLimitRule {
+            per_minute: 2,
+            burst: 1
+        }
+    );
+    assert_eq!(config.rate_limit.api, RateLimitConfig::default().api);
+}
+
+#[test]
+fn test_load_telemetry_config_defaults() {
+    let mut file = NamedTempFile::new().unwrap();
+    write!(file, "telemetry:\n  enabled: true\n  sample_ratio: 0.25\n").unwrap();
+
+    let config = Config::load(file.path()).unwrap();
+
+    assert!(config.telemetry.enabled);
+    assert_eq!(config.telemetry.sample_ratio, 0.25);
+    assert_eq!(config.telemetry.endpoint, "http://localhost:4318/v1/traces");
+    assert_eq!(config.telemetry.service_name, "noteva");
+    assert!(!Config::default().telemetry.enabled);
+}
+
+#[test]
+fn test_load_logging_config() {
+    let mut file = NamedTempFile::new().unwrap();
+    write!(
+        file,
+        "logging:\n  file: \"data/logs/noteva.log\"\n  rotation: hourly\n  keep: 3\n"
+    )
+    .unwrap();
+
+    let config = Config::load(file.path()).unwrap();
+
+    assert!(config.logging.stdout);
+    assert_eq!(
+        config.logging.file,
+        Some(PathBuf::from("data/logs/noteva.log"))
+    );
+    assert_eq!(config.logging.rotation, LogRotation::Hourly);
+    assert_eq!(config.logging.max_size_mb, 50);
+    assert_eq!(config.logging.keep, 3);
+    assert!(Config::default().logging.file.is_none());
+
+    assert!(serde_yaml::from_str::<LoggingConfig>("rotation: weekly").is_err());
+}
+
+#[test]
+fn test_load_monitor_config() {
+    let mut file = NamedTempFile::new().unwrap();
+    write!(
+        file,
+        "monitor:\n  max_rss_mb: 256\n  notify_emails: [\"ops@example.com\"]\n"
+    )
+    .unwrap();
+
+    let config = Config::load(file.path()).unwrap();
+
+    assert_eq!(config.monitor.interval_secs, 60);
+    assert_eq!(config.monitor.max_rss_mb, 256);
+    assert_eq!(config.monitor.max_cache_entries, 0);
+    assert_eq!(config.monitor.notify_emails, ["ops@example.com"]);
+    assert!(config.monitor.webhook_url.is_none());
+}
+
+#[test]
+fn test_load_job_queue_config() {
+    let mut file = NamedTempFile::new().unwrap();
+    write!(file, "job_queue:\n  workers: 4\n  max_attempts: 3\n").unwrap();
+
+    let config = Config::load(file.path()).unwrap();
+
+    assert_eq!(config.job_queue.workers, 4);
+    assert_eq!(config.job_queue.max_attempts, 3);
+    assert_eq!(config.job_queue.retry_base_secs, 30);
+    assert_eq!(config.job_queue.keep_days, 7);
+}
+
+#[test]
+fn test_load_markdown_config() {
+    let mut file = NamedTempFile::new().unwrap();
+    write!(
+        file,
+        "markdown:\n  engine: comrak\n  hard_breaks: false\n  comrak:\n    autolink: false\n"
+    )
+    .unwrap();
+
+    let config = Config::load(file.path()).unwrap();
+
+    assert_eq!(config.markdown.engine, MarkdownEngineKind::Comrak);
+    assert!(!config.markdown.hard_breaks);
+    assert!(!config.markdown.comrak.autolink);
+    assert!(config.markdown.comrak.tables);
+    assert_eq!(config.markdown.pulldown, PulldownOptions::default());
+}
+
+#[test]
+fn test_load_email_config() {
+    let mut file = NamedTempFile::new().unwrap();
+    write!(
+        file,
+        "email:\n  smtp:\n    host: smtp.example.com\n    port: 465\n    tls: tls\n    from: blog@example.com\n"
+    )
+    .unwrap();
+
+    let config = Config::load(file.path()).unwrap();
+
+    let smtp = config.email.smtp.unwrap();
+    assert_eq!(smtp.host, "smtp.example.com");
+    assert_eq!(smtp.port, 465);
+    assert_eq!(smtp.tls, SmtpTls::Tls);
+    assert_eq!(smtp.username, None);
+    assert_eq!(smtp.timeout_secs, 30);
+    assert!(Config::default().email.smtp.is_none());
+}
+
+#[test]
+fn test_database_data_dir() {
+    let sqlite = |url: &str| DatabaseConfig {
+        driver: DatabaseDriver::Sqlite,
+        url: url.to_string(),
+    };
+    assert_eq!(
+        sqlite("data/noteva.db").data_dir(),
+        Some(PathBuf::from("data"))
+    );
+    assert_eq!(
+        sqlite("sqlite:///var/lib/noteva/blog.db?mode=rwc").data_dir(),
+        Some(PathBuf::from("/var/lib/noteva"))
+    );
+    assert_eq!(sqlite("blog.db").data_dir(), Some(PathBuf::from(".")));
+    assert_eq!(sqlite("sqlite::memory:").data_dir(), None);
+    let mysql = DatabaseConfig {
+        driver: DatabaseDriver::Mysql,
+        url: "mysql://localhost/noteva".to_string(),
+    };
+    assert_eq!(mysql.data_dir(), None);
+}
+
 #[test]
 fn test_env_override_server_config() {
     let _guard = lock_env();
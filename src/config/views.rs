@@ -0,0 +1,43 @@
+//! Article view counting configuration
+//!
+//! ```yaml
+//! views:
+//!   dedup_secs: 1800   # a reader counts once per article in this window
+//!   flush_secs: 30     # how often view increments are written out
+//!   count_bots: false  # count crawlers and other bots as well
+//! ```
+
+use serde::{Deserialize, Serialize};
+
+/// View counting settings under `views`
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ViewsConfig {
+    /// Seconds during which repeated views of the same reader are ignored;
+    /// 0 counts every request
+    #[serde(default = "default_dedup_secs")]
+    pub dedup_secs: u64,
+    /// Seconds between writes of the buffered increments
+    #[serde(default = "default_flush_secs")]
+    pub flush_secs: u64,
+    /// Count requests whose user agent looks like a bot
+    #[serde(default)]
+    pub count_bots: bool,
+}
+
+impl Default for ViewsConfig {
+    fn default() -> Self {
+        Self {
+            dedup_secs: default_dedup_secs(),
+            flush_secs: default_flush_secs(),
+            count_bots: false,
+        }
+    }
+}
+
+fn default_dedup_secs() -> u64 {
+    1800
+}
+
+fn default_flush_secs() -> u64 {
+    30
+}
@@ -0,0 +1,143 @@
+//! Running as a system service
+//!
+//! `noteva install-service` writes a systemd unit on Linux and registers a
+//! service with the Service Control Manager on Windows. Both start the server
+//! from the directory `install-service` was run in, so relative paths in the
+//! configuration (`data/`, `uploads/`, `themes/`) keep resolving the same way.
+//!
+//! systemd stops the server with SIGTERM. Windows services get no signal;
+//! the service control handler calls [`request_stop`] instead, which the
+//! server's shutdown future waits on through [`stop_requested`].
+
+use once_cell::sync::Lazy;
+use std::path::{Path, PathBuf};
+use tokio::sync::watch;
+
+#[cfg(windows)]
+pub mod windows;
+
+static STOP: Lazy<watch::Sender<bool>> = Lazy::new(|| watch::channel(false).0);
+
+/// Ask the running server to shut down gracefully
+pub fn request_stop() {
+    STOP.send_replace(true);
+}
+
+/// Resolves once [`request_stop`] has been called
+pub async fn stop_requested() {
+    let mut stop = STOP.subscribe();
+    // The sender lives in a static, so this never fails
+    let _ = stop.wait_for(|stop| *stop).await;
+}
+
+/// How the installed service starts the server
+#[derive(Debug, Clone)]
+pub struct ServiceSpec {
+    pub name: String,
+    /// Absolute path of the `noteva` binary
+    pub exe: PathBuf,
+    /// Absolute path of the configuration file
+    pub config: PathBuf,
+    /// Directory the server runs in
+    pub workdir: PathBuf,
+    /// Account to run as; root when unset (systemd only)
+    pub user: Option<String>,
+}
+
+impl ServiceSpec {
+    /// Describe a service running this binary from the current directory
+    pub fn current(name: &str, config: &Path, user: Option<String>) -> std::io::Result<Self> {
+        Ok(Self {
+            name: name.to_string(),
+            exe: std::env::current_exe()?,
+            config: std::path::absolute(config)?,
+            workdir: std::env::current_dir()?,
+            user,
+        })
+    }
+
+    /// Contents of `/etc/systemd/system/<name>.service`
+    pub fn systemd_unit(&self) -> String {
+        let mut unit = String::from(
+            "[Unit]\n\
+             Description=Noteva Blog System\n\
+             After=network-online.target\n\
+             Wants=network-online.target\n\
+             \n\
+             [Service]\n\
+             Type=simple\n",
+        );
+        if let Some(user) = &self.user {
+            unit.push_str(&format!("User={}\n", user));
+        }
+        unit.push_str(&format!(
+            "WorkingDirectory={}\n\
+             ExecStart={} --config {} serve\n\
+             Restart=on-failure\n\
+             RestartSec=5\n\
+             KillSignal=SIGTERM\n\
+             TimeoutStopSec=30\n\
+             \n\
+             [Install]\n\
+             WantedBy=multi-user.target\n",
+            self.workdir.display().to_string().replace('%', "%%"),
+            systemd_quote(&self.exe),
+            systemd_quote(&self.config),
+        ));
+        unit
+    }
+}
+
+/// Quote a path for `ExecStart=`
+fn systemd_quote(path: &Path) -> String {
+    let path = path.display().to_string().replace('%', "%%");
+    if path
+        .chars()
+        .any(|c| c.is_whitespace() || matches!(c, '"' | '\'' | '\\' | ';' | '$'))
+    {
+        let escaped = path
+            .replace('\\', "\\\\")
+            .replace('"', "\\\"")
+            .replace('$', "$$");
+        format!("\"{}\"", escaped)
+    } else {
+        path
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn systemd_unit_runs_from_the_install_directory() {
+        let spec = ServiceSpec {
+            name: "noteva".to_string(),
+            exe: PathBuf::from("/opt/noteva/noteva"),
+            config: PathBuf::from("/srv/my blog/config.yml"),
+            workdir: PathBuf::from("/srv/my blog"),
+            user: Some("noteva".to_string()),
+        };
+        let unit = spec.systemd_unit();
+        assert!(unit.contains("User=noteva\n"));
+        assert!(unit.contains("WorkingDirectory=/srv/my blog\n"));
+        assert!(unit
+            .contains("ExecStart=/opt/noteva/noteva --config \"/srv/my blog/config.yml\" serve\n"));
+        assert!(unit.ends_with("WantedBy=multi-user.target\n"));
+
+        assert_eq!(systemd_quote(Path::new("/a/100%")), "/a/100%%");
+    }
+
+    #[tokio::test]
+    async fn stop_request_reaches_waiters() {
+        let waiter = tokio::spawn(stop_requested());
+        tokio::task::yield_now().await;
+        request_stop();
+        tokio::time::timeout(std::time::Duration::from_secs(1), waiter)
+            .await
+            .unwrap()
+            .unwrap();
+        // Later waiters see the request too
+        stop_requested().await;
+    }
+}
@@ -0,0 +1,171 @@
+//! Windows service registration and Service Control Manager glue
+//!
+//! The service runs `noteva --workdir <dir> --config <file> run-service`.
+//! [`start`] connects that process to the SCM: the server then runs on the
+//! regular tokio runtime while the SCM's dispatcher thread reports its state
+//! and turns Stop and Shutdown controls into [`request_stop`].
+
+use anyhow::{anyhow, Context, Result};
+use once_cell::sync::OnceCell;
+use std::ffi::OsString;
+use std::sync::mpsc;
+use std::sync::Mutex;
+use std::time::Duration;
+use windows_service::service::{
+    ServiceAccess, ServiceControl, ServiceControlAccept, ServiceErrorControl, ServiceExitCode,
+    ServiceInfo, ServiceStartType, ServiceState, ServiceStatus, ServiceType,
+};
+use windows_service::service_control_handler::{self, ServiceControlHandlerResult};
+use windows_service::service_manager::{ServiceManager, ServiceManagerAccess};
+use windows_service::{define_windows_service, service_dispatcher};
+
+use super::{request_stop, ServiceSpec};
+
+/// Name passed to [`start`], read by the dispatcher thread
+static SERVICE_NAME: OnceCell<String> = OnceCell::new();
+/// Channels between [`start`] / [`RunningService`] and the dispatcher thread
+static CHANNELS: OnceCell<Channels> = OnceCell::new();
+
+struct Channels {
+    /// Reports whether the service registered with the SCM
+    started: mpsc::Sender<Result<()>>,
+    /// Receives the server's exit code once it has stopped
+    stopped: Mutex<Option<mpsc::Receiver<u32>>>,
+}
+
+/// Register the service with the SCM so it starts at boot
+pub fn install(spec: &ServiceSpec) -> Result<()> {
+    let manager = ServiceManager::local_computer(
+        None::<&str>,
+        ServiceManagerAccess::CONNECT | ServiceManagerAccess::CREATE_SERVICE,
+    )
+    .context("Failed to connect to the Service Control Manager (run as Administrator)")?;
+    let info = ServiceInfo {
+        name: OsString::from(&spec.name),
+        display_name: OsString::from("Noteva Blog System"),
+        service_type: ServiceType::OWN_PROCESS,
+        start_type: ServiceStartType::AutoStart,
+        error_control: ServiceErrorControl::Normal,
+        executable_path: spec.exe.clone(),
+        launch_arguments: vec![
+            OsString::from("--workdir"),
+            spec.workdir.clone().into_os_string(),
+            OsString::from("--config"),
+            spec.config.clone().into_os_string(),
+            OsString::from("run-service"),
+            OsString::from("--name"),
+            OsString::from(&spec.name),
+        ],
+        dependencies: Vec::new(),
+        account_name: None,
+        account_password: None,
+    };
+    let service = manager
+        .create_service(&info, ServiceAccess::CHANGE_CONFIG)
+        .with_context(|| format!("Failed to create service '{}'", spec.name))?;
+    service.set_description("Noteva blog server")?;
+    Ok(())
+}
+
+/// Handle for reporting the server's exit to the SCM
+pub struct RunningService {
+    exit: mpsc::Sender<u32>,
+    dispatcher: std::thread::JoinHandle<()>,
+}
+
+impl RunningService {
+    /// Report that the server has stopped, as exit code 1 if it failed
+    ///
+    /// Waits for the SCM to be told, so call it right before exiting.
+    pub fn stopped<T>(self, result: &Result<T>) {
+        let code = if result.is_ok() { 0 } else { 1 };
+        let _ = self.exit.send(code);
+        let _ = self.dispatcher.join();
+    }
+}
+
+/// Connect this process to the SCM; fails when not started as a service
+pub async fn start(name: &str) -> Result<RunningService> {
+    let (started_tx, started_rx) = mpsc::channel();
+    let (exit_tx, exit_rx) = mpsc::channel();
+    SERVICE_NAME
+        .set(name.to_string())
+        .map_err(|_| anyhow!("Service already started"))?;
+    let _ = CHANNELS.set(Channels {
+        started: started_tx.clone(),
+        stopped: Mutex::new(Some(exit_rx)),
+    });
+
+    // Blocks until the service has stopped
+    let dispatcher = std::thread::spawn(move || {
+        if let Err(e) = service_dispatcher::start(name_or_default(), ffi_service_main) {
+            let _ = started_tx.send(Err(anyhow!(e).context(
+                "Not running under the Service Control Manager; use `noteva serve` instead",
+            )));
+        }
+    });
+
+    tokio::task::spawn_blocking(move || started_rx.recv())
+        .await?
+        .context("Service dispatcher exited")??;
+    Ok(RunningService {
+        exit: exit_tx,
+        dispatcher,
+    })
+}
+
+fn name_or_default() -> &'static str {
+    SERVICE_NAME.get().map(String::as_str).unwrap_or("noteva")
+}
+
+define_windows_service!(ffi_service_main, service_main);
+
+fn service_main(_arguments: Vec<OsString>) {
+    let Some(channels) = CHANNELS.get() else {
+        return;
+    };
+    let started = channels.started.clone();
+    let Some(stopped) = channels.stopped.lock().unwrap().take() else {
+        return;
+    };
+    if let Err(e) = run_service(&started, stopped) {
+        let _ = started.send(Err(e));
+    }
+}
+
+fn run_service(started: &mpsc::Sender<Result<()>>, stopped: mpsc::Receiver<u32>) -> Result<()> {
+    let status_handle =
+        service_control_handler::register(name_or_default(), |control| match control {
+            ServiceControl::Stop | ServiceControl::Shutdown => {
+                request_stop();
+                ServiceControlHandlerResult::NoError
+            }
+            ServiceControl::Interrogate => ServiceControlHandlerResult::NoError,
+            _ => ServiceControlHandlerResult::NotImplemented,
+        })?;
+    let status = |state, controls_accepted, exit_code| ServiceStatus {
+        service_type: ServiceType::OWN_PROCESS,
+        current_state: state,
+        controls_accepted,
+        exit_code: ServiceExitCode::Win32(exit_code),
+        checkpoint: 0,
+        wait_hint: Duration::default(),
+        process_id: None,
+    };
+
+    status_handle.set_service_status(status(
+        ServiceState::Running,
+        ServiceControlAccept::STOP | ServiceControlAccept::SHUTDOWN,
+        0,
+    ))?;
+    let _ = started.send(Ok(()));
+
+    // Stop was requested or the server exited on its own
+    let code = stopped.recv().unwrap_or(1);
+    status_handle.set_service_status(status(
+        ServiceState::Stopped,
+        ServiceControlAccept::empty(),
+        code,
+    ))?;
+    Ok(())
+}
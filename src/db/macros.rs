@@ -8,7 +8,8 @@
 ///
 /// This macro replaces the repetitive `match self.pool.driver()` pattern used
 /// in every trait impl method. It handles extracting the typed pool reference
-/// and calling the corresponding backend-specific function.
+/// and calling the corresponding backend-specific function. Each call runs
+/// in a `db.query` span named after the function.
 ///
 /// # Examples
 ///
@@ -34,25 +35,53 @@ macro_rules! dispatch {
     ($self:expr, $fn_base:ident $(,)?) => {
         match $self.pool.driver() {
             crate::config::DatabaseDriver::Sqlite => {
-                paste::paste! { [<$fn_base _sqlite>]($self.pool.as_sqlite_or_err()?).await }
+                tracing::Instrument::instrument(
+                    paste::paste! { [<$fn_base _sqlite>]($self.pool.as_sqlite_or_err()?) },
+                    db_query_span!("sqlite", $fn_base),
+                )
+                .await
             }
             crate::config::DatabaseDriver::Mysql => {
-                paste::paste! { [<$fn_base _mysql>]($self.pool.as_mysql_or_err()?).await }
+                tracing::Instrument::instrument(
+                    paste::paste! { [<$fn_base _mysql>]($self.pool.as_mysql_or_err()?) },
+                    db_query_span!("mysql", $fn_base),
+                )
+                .await
             }
         }
     };
     ($self:expr, $fn_base:ident, $($arg:expr),+ $(,)?) => {
         match $self.pool.driver() {
             crate::config::DatabaseDriver::Sqlite => {
-                paste::paste! { [<$fn_base _sqlite>]($self.pool.as_sqlite_or_err()?, $($arg),+).await }
+                tracing::Instrument::instrument(
+                    paste::paste! { [<$fn_base _sqlite>]($self.pool.as_sqlite_or_err()?, $($arg),+) },
+                    db_query_span!("sqlite", $fn_base),
+                )
+                .await
             }
             crate::config::DatabaseDriver::Mysql => {
-                paste::paste! { [<$fn_base _mysql>]($self.pool.as_mysql_or_err()?, $($arg),+).await }
+                tracing::Instrument::instrument(
+                    paste::paste! { [<$fn_base _mysql>]($self.pool.as_mysql_or_err()?, $($arg),+) },
+                    db_query_span!("mysql", $fn_base),
+                )
+                .await
             }
         }
     };
 }
 
+/// Span around a repository query dispatched by `dispatch!`
+macro_rules! db_query_span {
+    ($system:literal, $fn_base:ident) => {
+        tracing::info_span!(
+            "db.query",
+            otel.name = concat!("db ", stringify!($fn_base)),
+            db.system = $system,
+            db.operation = stringify!($fn_base),
+        )
+    };
+}
+
 /// Generate both SQLite and MySQL variants of a database function.
 ///
 /// This macro takes a single function definition with a generic pool placeholder
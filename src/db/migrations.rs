@@ -806,6 +806,1205 @@ pub const MIGRATIONS: &[Migration] = &[
             CREATE INDEX idx_friend_links_sort ON friend_links(category, sort_order);
         "#,
     },
+    // Migration 31: Per-user admin UI preferences
+    Migration {
+        version: 31,
+        name: "create_user_preferences",
+        up_sqlite: r#"
+            CREATE TABLE IF NOT EXISTS user_preferences (
+                user_id INTEGER PRIMARY KEY,
+                data TEXT NOT NULL,
+                updated_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+                FOREIGN KEY (user_id) REFERENCES users(id) ON DELETE CASCADE
+            );
+        "#,
+        up_mysql: r#"
+            CREATE TABLE IF NOT EXISTS user_preferences (
+                user_id BIGINT PRIMARY KEY,
+                data TEXT NOT NULL,
+                updated_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP ON UPDATE CURRENT_TIMESTAMP,
+                FOREIGN KEY (user_id) REFERENCES users(id) ON DELETE CASCADE
+            );
+        "#,
+    },
+    // Migration 32: Comment types (regular comments and webmentions)
+    Migration {
+        version: 32,
+        name: "add_comment_type",
+        up_sqlite: r#"
+            ALTER TABLE comments ADD COLUMN comment_type VARCHAR(20) NOT NULL DEFAULT 'comment';
+            ALTER TABLE comments ADD COLUMN source_url VARCHAR(500);
+            CREATE INDEX IF NOT EXISTS idx_comments_source_url ON comments(source_url);
+        "#,
+        up_mysql: r#"
+            ALTER TABLE comments ADD COLUMN comment_type VARCHAR(20) NOT NULL DEFAULT 'comment';
+            ALTER TABLE comments ADD COLUMN source_url VARCHAR(500);
+            CREATE INDEX idx_comments_source_url ON comments(source_url);
+        "#,
+    },
+    // Migration 33: Comment search for moderators (FTS5 / FULLTEXT, IP lookups)
+    Migration {
+        version: 33,
+        name: "add_comment_fulltext_search",
+        up_sqlite: r#"
+            DROP TRIGGER IF EXISTS comments_fts_insert;
+            DROP TRIGGER IF EXISTS comments_fts_update;
+            DROP TRIGGER IF EXISTS comments_fts_delete;
+            DROP TABLE IF EXISTS comments_fts;
+
+            CREATE VIRTUAL TABLE comments_fts USING fts5(
+                content,
+                nickname,
+                content='comments',
+                content_rowid='id'
+            );
+
+            INSERT INTO comments_fts(rowid, content, nickname)
+                SELECT id, content, nickname FROM comments;
+
+            CREATE TRIGGER comments_fts_insert AFTER INSERT ON comments BEGIN
+                INSERT INTO comments_fts(rowid, content, nickname) VALUES (new.id, new.content, new.nickname);
+            END;
+
+            CREATE TRIGGER comments_fts_update AFTER UPDATE OF content, nickname ON comments BEGIN
+                INSERT INTO comments_fts(comments_fts, rowid, content, nickname) VALUES ('delete', old.id, old.content, old.nickname);
+                INSERT INTO comments_fts(rowid, content, nickname) VALUES (new.id, new.content, new.nickname);
+            END;
+
+            CREATE TRIGGER comments_fts_delete AFTER DELETE ON comments BEGIN
+                INSERT INTO comments_fts(comments_fts, rowid, content, nickname) VALUES ('delete', old.id, old.content, old.nickname);
+            END;
+
+            CREATE INDEX IF NOT EXISTS idx_comments_ip_address ON comments(ip_address);
+        "#,
+        up_mysql: r#"
+            ALTER TABLE comments ADD FULLTEXT INDEX ft_comments_content_nickname (content, nickname) WITH PARSER ngram;
+            CREATE INDEX idx_comments_ip_address ON comments(ip_address);
+        "#,
+    },
+    // Migration 34: Add admin-enforced password reset flag to users
+    Migration {
+        version: 34,
+        name: "add_user_password_reset_required",
+        up_sqlite: r#"
+            ALTER TABLE users ADD COLUMN password_reset_required INTEGER NOT NULL DEFAULT 0;
+        "#,
+        up_mysql: r#"
+            ALTER TABLE users ADD COLUMN password_reset_required TINYINT NOT NULL DEFAULT 0;
+        "#,
+    },
+    // Migration 35: Index for keyset (cursor) pagination of articles
+    Migration {
+        version: 35,
+        name: "add_article_cursor_index",
+        up_sqlite: r#"
+            CREATE INDEX IF NOT EXISTS idx_articles_created_at_id ON articles(created_at, id);
+        "#,
+        up_mysql: r#"
+            CREATE INDEX idx_articles_created_at_id ON articles(created_at, id);
+        "#,
+    },
+    // Migration 36: Passkey (WebAuthn) credentials
+    Migration {
+        version: 36,
+        name: "create_webauthn_credentials",
+        up_sqlite: r#"
+            CREATE TABLE IF NOT EXISTS webauthn_credentials (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                user_id INTEGER NOT NULL,
+                credential_id VARCHAR(512) NOT NULL UNIQUE,
+                public_key TEXT NOT NULL,
+                sign_count INTEGER NOT NULL DEFAULT 0,
+                name VARCHAR(100) NOT NULL,
+                created_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP,
+                last_used_at DATETIME,
+                FOREIGN KEY (user_id) REFERENCES users(id) ON DELETE CASCADE
+            );
+            CREATE INDEX IF NOT EXISTS idx_webauthn_credentials_user_id ON webauthn_credentials(user_id);
+        "#,
+        up_mysql: r#"
+            CREATE TABLE IF NOT EXISTS webauthn_credentials (
+                id BIGINT PRIMARY KEY AUTO_INCREMENT,
+                user_id BIGINT NOT NULL,
+                credential_id VARCHAR(512) NOT NULL UNIQUE,
+                public_key TEXT NOT NULL,
+                sign_count BIGINT NOT NULL DEFAULT 0,
+                name VARCHAR(100) NOT NULL,
+                created_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP,
+                last_used_at DATETIME,
+                FOREIGN KEY (user_id) REFERENCES users(id) ON DELETE CASCADE
+            );
+            CREATE INDEX idx_webauthn_credentials_user_id ON webauthn_credentials(user_id);
+        "#,
+    },
+    // Migration 37: Outbound email suppression list
+    Migration {
+        version: 37,
+        name: "create_email_suppressions",
+        up_sqlite: r#"
+            CREATE TABLE IF NOT EXISTS email_suppressions (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                email VARCHAR(255) NOT NULL UNIQUE,
+                reason VARCHAR(20) NOT NULL,
+                detail TEXT,
+                created_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP
+            );
+        "#,
+        up_mysql: r#"
+            CREATE TABLE IF NOT EXISTS email_suppressions (
+                id BIGINT PRIMARY KEY AUTO_INCREMENT,
+                email VARCHAR(255) NOT NULL UNIQUE,
+                reason VARCHAR(20) NOT NULL,
+                detail TEXT,
+                created_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP
+            );
+        "#,
+    },
+    // Migration 38: Inbound webhook endpoints for plugin integrations
+    Migration {
+        version: 38,
+        name: "create_inbound_webhooks",
+        up_sqlite: r#"
+            CREATE TABLE IF NOT EXISTS inbound_webhooks (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                name VARCHAR(100) NOT NULL,
+                token VARCHAR(64) NOT NULL UNIQUE,
+                signature VARCHAR(20) NOT NULL DEFAULT 'none',
+                secret TEXT,
+                last_received_at DATETIME,
+                created_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP
+            );
+        "#,
+        up_mysql: r#"
+            CREATE TABLE IF NOT EXISTS inbound_webhooks (
+                id BIGINT PRIMARY KEY AUTO_INCREMENT,
+                name VARCHAR(100) NOT NULL,
+                token VARCHAR(64) NOT NULL UNIQUE,
+                signature VARCHAR(20) NOT NULL DEFAULT 'none',
+                secret TEXT,
+                last_received_at DATETIME,
+                created_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP
+            );
+        "#,
+    },
+    // Migration 39: Markdown files published from GitHub
+    Migration {
+        version: 39,
+        name: "create_github_synced_files",
+        up_sqlite: r#"
+            CREATE TABLE IF NOT EXISTS github_synced_files (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                path VARCHAR(255) NOT NULL UNIQUE,
+                article_id INTEGER NOT NULL,
+                commit_sha VARCHAR(40) NOT NULL,
+                synced_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP,
+                FOREIGN KEY (article_id) REFERENCES articles(id) ON DELETE CASCADE
+            );
+        "#,
+        up_mysql: r#"
+            CREATE TABLE IF NOT EXISTS github_synced_files (
+                id BIGINT PRIMARY KEY AUTO_INCREMENT,
+                path VARCHAR(255) NOT NULL UNIQUE,
+                article_id BIGINT NOT NULL,
+                commit_sha VARCHAR(40) NOT NULL,
+                synced_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP,
+                FOREIGN KEY (article_id) REFERENCES articles(id) ON DELETE CASCADE
+            );
+        "#,
+    },
+    // Migration 40: Deleted articles and pages, for delta sync
+    Migration {
+        version: 40,
+        name: "create_sync_tombstones",
+        up_sqlite: r#"
+            CREATE TABLE IF NOT EXISTS sync_tombstones (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                entity_type VARCHAR(20) NOT NULL,
+                entity_id INTEGER NOT NULL,
+                slug VARCHAR(255) NOT NULL,
+                status VARCHAR(20) NOT NULL,
+                deleted_at DATETIME NOT NULL
+            );
+            CREATE INDEX IF NOT EXISTS idx_sync_tombstones_deleted_at ON sync_tombstones(deleted_at);
+        "#,
+        up_mysql: r#"
+            CREATE TABLE IF NOT EXISTS sync_tombstones (
+                id BIGINT PRIMARY KEY AUTO_INCREMENT,
+                entity_type VARCHAR(20) NOT NULL,
+                entity_id BIGINT NOT NULL,
+                slug VARCHAR(255) NOT NULL,
+                status VARCHAR(20) NOT NULL,
+                deleted_at DATETIME NOT NULL
+            );
+            CREATE INDEX idx_sync_tombstones_deleted_at ON sync_tombstones(deleted_at);
+        "#,
+    },
+    // Migration 41: Article views per UTC day, for stats exports
+    Migration {
+        version: 41,
+        name: "create_article_views_daily",
+        up_sqlite: r#"
+            CREATE TABLE IF NOT EXISTS article_views_daily (
+                article_id INTEGER NOT NULL,
+                day VARCHAR(10) NOT NULL,
+                views INTEGER NOT NULL DEFAULT 0,
+                PRIMARY KEY (article_id, day),
+                FOREIGN KEY (article_id) REFERENCES articles(id) ON DELETE CASCADE
+            );
+            CREATE INDEX IF NOT EXISTS idx_article_views_daily_day ON article_views_daily(day);
+        "#,
+        up_mysql: r#"
+            CREATE TABLE IF NOT EXISTS article_views_daily (
+                article_id BIGINT NOT NULL,
+                day VARCHAR(10) NOT NULL,
+                views BIGINT NOT NULL DEFAULT 0,
+                PRIMARY KEY (article_id, day),
+                FOREIGN KEY (article_id) REFERENCES articles(id) ON DELETE CASCADE
+            );
+            CREATE INDEX idx_article_views_daily_day ON article_views_daily(day);
+        "#,
+    },
+    // Migration 42: Persistent background job queue
+    Migration {
+        version: 42,
+        name: "create_job_queue",
+        up_sqlite: r#"
+            CREATE TABLE IF NOT EXISTS job_queue (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                kind VARCHAR(100) NOT NULL,
+                payload TEXT NOT NULL,
+                status VARCHAR(20) NOT NULL DEFAULT 'pending',
+                attempts INTEGER NOT NULL DEFAULT 0,
+                max_attempts INTEGER NOT NULL,
+                run_at DATETIME NOT NULL,
+                locked_at DATETIME,
+                last_error TEXT,
+                created_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP,
+                finished_at DATETIME
+            );
+            CREATE INDEX IF NOT EXISTS idx_job_queue_status_run_at ON job_queue(status, run_at);
+        "#,
+        up_mysql: r#"
+            CREATE TABLE IF NOT EXISTS job_queue (
+                id BIGINT PRIMARY KEY AUTO_INCREMENT,
+                kind VARCHAR(100) NOT NULL,
+                payload LONGTEXT NOT NULL,
+                status VARCHAR(20) NOT NULL DEFAULT 'pending',
+                attempts INT NOT NULL DEFAULT 0,
+                max_attempts INT NOT NULL,
+                run_at DATETIME NOT NULL,
+                locked_at DATETIME,
+                last_error TEXT,
+                created_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP,
+                finished_at DATETIME
+            );
+            CREATE INDEX idx_job_queue_status_run_at ON job_queue(status, run_at);
+        "#,
+    },
+    Migration {
+        version: 43,
+        name: "add_article_input_format",
+        up_sqlite: r#"
+            ALTER TABLE articles ADD COLUMN input_format VARCHAR(16) NOT NULL DEFAULT 'markdown';
+        "#,
+        up_mysql: r#"
+            ALTER TABLE articles ADD COLUMN input_format VARCHAR(16) NOT NULL DEFAULT 'markdown';
+        "#,
+    },
+    // Migration 44: Newsletter subscribers and sent issues
+    Migration {
+        version: 44,
+        name: "create_subscribers",
+        up_sqlite: r#"
+            CREATE TABLE IF NOT EXISTS subscribers (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                email VARCHAR(255) NOT NULL UNIQUE,
+                status VARCHAR(20) NOT NULL DEFAULT 'pending',
+                token VARCHAR(64) NOT NULL UNIQUE,
+                created_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP,
+                confirmed_at DATETIME,
+                unsubscribed_at DATETIME
+            );
+            CREATE INDEX IF NOT EXISTS idx_subscribers_status ON subscribers(status);
+            CREATE TABLE IF NOT EXISTS newsletter_issues (
+                article_id INTEGER PRIMARY KEY,
+                recipients INTEGER NOT NULL DEFAULT 0,
+                sent_at DATETIME NOT NULL,
+                FOREIGN KEY (article_id) REFERENCES articles(id) ON DELETE CASCADE
+            );
+        "#,
+        up_mysql: r#"
+            CREATE TABLE IF NOT EXISTS subscribers (
+                id BIGINT PRIMARY KEY AUTO_INCREMENT,
+                email VARCHAR(255) NOT NULL UNIQUE,
+                status VARCHAR(20) NOT NULL DEFAULT 'pending',
+                token VARCHAR(64) NOT NULL UNIQUE,
+                created_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP,
+                confirmed_at DATETIME,
+                unsubscribed_at DATETIME
+            );
+            CREATE INDEX idx_subscribers_status ON subscribers(status);
+            CREATE TABLE IF NOT EXISTS newsletter_issues (
+                article_id BIGINT PRIMARY KEY,
+                recipients BIGINT NOT NULL DEFAULT 0,
+                sent_at DATETIME NOT NULL,
+                FOREIGN KEY (article_id) REFERENCES articles(id) ON DELETE CASCADE
+            );
+        "#,
+    },
+    // Migration 45: Web Push subscriptions and notified articles
+    Migration {
+        version: 45,
+        name: "create_push_subscriptions",
+        up_sqlite: r#"
+            CREATE TABLE IF NOT EXISTS push_subscriptions (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                endpoint VARCHAR(512) NOT NULL UNIQUE,
+                p256dh VARCHAR(128) NOT NULL,
+                auth VARCHAR(64) NOT NULL,
+                created_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP
+            );
+            CREATE TABLE IF NOT EXISTS push_notifications (
+                article_id INTEGER PRIMARY KEY,
+                sent_at DATETIME NOT NULL,
+                FOREIGN KEY (article_id) REFERENCES articles(id) ON DELETE CASCADE
+            );
+        "#,
+        up_mysql: r#"
+            CREATE TABLE IF NOT EXISTS push_subscriptions (
+                id BIGINT PRIMARY KEY AUTO_INCREMENT,
+                endpoint VARCHAR(512) NOT NULL UNIQUE,
+                p256dh VARCHAR(128) NOT NULL,
+                auth VARCHAR(64) NOT NULL,
+                created_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP
+            );
+            CREATE TABLE IF NOT EXISTS push_notifications (
+                article_id BIGINT PRIMARY KEY,
+                sent_at DATETIME NOT NULL,
+                FOREIGN KEY (article_id) REFERENCES articles(id) ON DELETE CASCADE
+            );
+        "#,
+    },
+    Migration {
+        version: 46,
+        name: "create_article_slugs",
+        // No foreign key: slugs of deleted articles stay reserved
+        up_sqlite: r#"
+            CREATE TABLE IF NOT EXISTS article_slugs (
+                slug VARCHAR(255) PRIMARY KEY,
+                article_id INTEGER NOT NULL,
+                created_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP
+            );
+            CREATE INDEX IF NOT EXISTS idx_article_slugs_article ON article_slugs(article_id);
+            INSERT OR IGNORE INTO article_slugs (slug, article_id, created_at)
+                SELECT slug, id, created_at FROM articles;
+        "#,
+        up_mysql: r#"
+            CREATE TABLE IF NOT EXISTS article_slugs (
+                slug VARCHAR(255) PRIMARY KEY,
+                article_id BIGINT NOT NULL,
+                created_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP
+            );
+            CREATE INDEX idx_article_slugs_article ON article_slugs(article_id);
+            INSERT IGNORE INTO article_slugs (slug, article_id, created_at)
+                SELECT slug, id, created_at FROM articles;
+        "#,
+    },
+    // Migration 47: Daily page view analytics
+    Migration {
+        version: 47,
+        name: "create_analytics_daily",
+        up_sqlite: r#"
+            CREATE TABLE IF NOT EXISTS analytics_daily (
+                day VARCHAR(10) PRIMARY KEY,
+                views INTEGER NOT NULL DEFAULT 0,
+                visitors INTEGER NOT NULL DEFAULT 0
+            );
+            CREATE TABLE IF NOT EXISTS analytics_pages_daily (
+                day VARCHAR(10) NOT NULL,
+                path VARCHAR(255) NOT NULL,
+                views INTEGER NOT NULL DEFAULT 0,
+                visitors INTEGER NOT NULL DEFAULT 0,
+                PRIMARY KEY (day, path)
+            );
+            CREATE TABLE IF NOT EXISTS analytics_referrers_daily (
+                day VARCHAR(10) NOT NULL,
+                referrer VARCHAR(255) NOT NULL,
+                views INTEGER NOT NULL DEFAULT 0,
+                visitors INTEGER NOT NULL DEFAULT 0,
+                PRIMARY KEY (day, referrer)
+            );
+            CREATE TABLE IF NOT EXISTS analytics_countries_daily (
+                day VARCHAR(10) NOT NULL,
+                country VARCHAR(2) NOT NULL,
+                views INTEGER NOT NULL DEFAULT 0,
+                visitors INTEGER NOT NULL DEFAULT 0,
+                PRIMARY KEY (day, country)
+            );
+        "#,
+        up_mysql: r#"
+            CREATE TABLE IF NOT EXISTS analytics_daily (
+                day VARCHAR(10) PRIMARY KEY,
+                views BIGINT NOT NULL DEFAULT 0,
+                visitors BIGINT NOT NULL DEFAULT 0
+            );
+            CREATE TABLE IF NOT EXISTS analytics_pages_daily (
+                day VARCHAR(10) NOT NULL,
+                path VARCHAR(255) NOT NULL,
+                views BIGINT NOT NULL DEFAULT 0,
+                visitors BIGINT NOT NULL DEFAULT 0,
+                PRIMARY KEY (day, path)
+            );
+            CREATE TABLE IF NOT EXISTS analytics_referrers_daily (
+                day VARCHAR(10) NOT NULL,
+                referrer VARCHAR(255) NOT NULL,
+                views BIGINT NOT NULL DEFAULT 0,
+                visitors BIGINT NOT NULL DEFAULT 0,
+                PRIMARY KEY (day, referrer)
+            );
+            CREATE TABLE IF NOT EXISTS analytics_countries_daily (
+                day VARCHAR(10) NOT NULL,
+                country VARCHAR(2) NOT NULL,
+                views BIGINT NOT NULL DEFAULT 0,
+                visitors BIGINT NOT NULL DEFAULT 0,
+                PRIMARY KEY (day, country)
+            );
+        "#,
+    },
+    // Migration 48: Reading progress of logged-in readers
+    Migration {
+        version: 48,
+        name: "create_reading_progress",
+        up_sqlite: r#"
+            CREATE TABLE IF NOT EXISTS reading_progress (
+                user_id INTEGER NOT NULL,
+                article_id INTEGER NOT NULL,
+                position REAL NOT NULL DEFAULT 0,
+                updated_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+                PRIMARY KEY (user_id, article_id),
+                FOREIGN KEY (user_id) REFERENCES users(id) ON DELETE CASCADE,
+                FOREIGN KEY (article_id) REFERENCES articles(id) ON DELETE CASCADE
+            );
+            CREATE INDEX IF NOT EXISTS idx_reading_progress_updated ON reading_progress(user_id, updated_at);
+        "#,
+        up_mysql: r#"
+            CREATE TABLE IF NOT EXISTS reading_progress (
+                user_id BIGINT NOT NULL,
+                article_id BIGINT NOT NULL,
+                position DOUBLE NOT NULL DEFAULT 0,
+                updated_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+                PRIMARY KEY (user_id, article_id),
+                FOREIGN KEY (user_id) REFERENCES users(id) ON DELETE CASCADE,
+                FOREIGN KEY (article_id) REFERENCES articles(id) ON DELETE CASCADE
+            );
+            CREATE INDEX idx_reading_progress_updated ON reading_progress(user_id, updated_at);
+        "#,
+    },
+    // Migration 49: Reader favorites ("read later") with a per-article count
+    Migration {
+        version: 49,
+        name: "create_favorites",
+        up_sqlite: r#"
+            ALTER TABLE articles ADD COLUMN favorite_count INTEGER NOT NULL DEFAULT 0;
+            CREATE TABLE IF NOT EXISTS favorites (
+                user_id INTEGER NOT NULL,
+                article_id INTEGER NOT NULL,
+                created_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+                PRIMARY KEY (user_id, article_id),
+                FOREIGN KEY (user_id) REFERENCES users(id) ON DELETE CASCADE,
+                FOREIGN KEY (article_id) REFERENCES articles(id) ON DELETE CASCADE
+            );
+            CREATE INDEX IF NOT EXISTS idx_favorites_article ON favorites(article_id);
+            CREATE TABLE IF NOT EXISTS favorite_settings (
+                user_id INTEGER PRIMARY KEY,
+                is_public BOOLEAN NOT NULL DEFAULT 0,
+                FOREIGN KEY (user_id) REFERENCES users(id) ON DELETE CASCADE
+            );
+        "#,
+        up_mysql: r#"
+            ALTER TABLE articles ADD COLUMN favorite_count INT NOT NULL DEFAULT 0;
+            CREATE TABLE IF NOT EXISTS favorites (
+                user_id BIGINT NOT NULL,
+                article_id BIGINT NOT NULL,
+                created_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+                PRIMARY KEY (user_id, article_id),
+                FOREIGN KEY (user_id) REFERENCES users(id) ON DELETE CASCADE,
+                FOREIGN KEY (article_id) REFERENCES articles(id) ON DELETE CASCADE
+            );
+            CREATE INDEX idx_favorites_article ON favorites(article_id);
+            CREATE TABLE IF NOT EXISTS favorite_settings (
+                user_id BIGINT PRIMARY KEY,
+                is_public BOOLEAN NOT NULL DEFAULT FALSE,
+                FOREIGN KEY (user_id) REFERENCES users(id) ON DELETE CASCADE
+            );
+        "#,
+    },
+    // Migration 50: Manual redirects checked before the theme's 404
+    Migration {
+        version: 50,
+        name: "create_redirects",
+        up_sqlite: r#"
+            CREATE TABLE IF NOT EXISTS redirects (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                source VARCHAR(500) NOT NULL UNIQUE,
+                target VARCHAR(1000) NOT NULL,
+                status_code INTEGER NOT NULL DEFAULT 301,
+                created_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+                updated_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP
+            );
+        "#,
+        up_mysql: r#"
+            CREATE TABLE IF NOT EXISTS redirects (
+                id BIGINT PRIMARY KEY AUTO_INCREMENT,
+                source VARCHAR(500) NOT NULL UNIQUE,
+                target VARCHAR(1000) NOT NULL,
+                status_code INT NOT NULL DEFAULT 301,
+                created_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+                updated_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP ON UPDATE CURRENT_TIMESTAMP
+            );
+        "#,
+    },
+    // Migration 51: Per-article SEO overrides
+    Migration {
+        version: 51,
+        name: "add_article_seo_fields",
+        up_sqlite: r#"
+            ALTER TABLE articles ADD COLUMN meta_title VARCHAR(200);
+            ALTER TABLE articles ADD COLUMN meta_description VARCHAR(500);
+            ALTER TABLE articles ADD COLUMN canonical_url VARCHAR(1000);
+            ALTER TABLE articles ADD COLUMN noindex BOOLEAN NOT NULL DEFAULT 0;
+        "#,
+        up_mysql: r#"
+            ALTER TABLE articles ADD COLUMN meta_title VARCHAR(200);
+            ALTER TABLE articles ADD COLUMN meta_description VARCHAR(500);
+            ALTER TABLE articles ADD COLUMN canonical_url VARCHAR(1000);
+            ALTER TABLE articles ADD COLUMN noindex BOOLEAN NOT NULL DEFAULT FALSE;
+        "#,
+    },
+    // Migration 52: Polls embedded in articles with [poll id=N]
+    Migration {
+        version: 52,
+        name: "create_polls",
+        up_sqlite: r#"
+            CREATE TABLE IF NOT EXISTS polls (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                question VARCHAR(500) NOT NULL,
+                multiple BOOLEAN NOT NULL DEFAULT 0,
+                closes_at TIMESTAMP,
+                created_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+                updated_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP
+            );
+            CREATE TABLE IF NOT EXISTS poll_options (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                poll_id INTEGER NOT NULL,
+                label VARCHAR(200) NOT NULL,
+                sort_order INTEGER NOT NULL DEFAULT 0,
+                FOREIGN KEY (poll_id) REFERENCES polls(id) ON DELETE CASCADE
+            );
+            CREATE INDEX IF NOT EXISTS idx_poll_options_poll ON poll_options(poll_id);
+            CREATE TABLE IF NOT EXISTS poll_voters (
+                poll_id INTEGER NOT NULL,
+                voter_key VARCHAR(128) NOT NULL,
+                created_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+                PRIMARY KEY (poll_id, voter_key),
+                FOREIGN KEY (poll_id) REFERENCES polls(id) ON DELETE CASCADE
+            );
+            CREATE TABLE IF NOT EXISTS poll_votes (
+                poll_id INTEGER NOT NULL,
+                option_id INTEGER NOT NULL,
+                voter_key VARCHAR(128) NOT NULL,
+                PRIMARY KEY (poll_id, voter_key, option_id),
+                FOREIGN KEY (poll_id) REFERENCES polls(id) ON DELETE CASCADE,
+                FOREIGN KEY (option_id) REFERENCES poll_options(id) ON DELETE CASCADE
+            );
+        "#,
+        up_mysql: r#"
+            CREATE TABLE IF NOT EXISTS polls (
+                id BIGINT PRIMARY KEY AUTO_INCREMENT,
+                question VARCHAR(500) NOT NULL,
+                multiple BOOLEAN NOT NULL DEFAULT FALSE,
+                closes_at TIMESTAMP NULL,
+                created_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+                updated_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP ON UPDATE CURRENT_TIMESTAMP
+            );
+            CREATE TABLE IF NOT EXISTS poll_options (
+                id BIGINT PRIMARY KEY AUTO_INCREMENT,
+                poll_id BIGINT NOT NULL,
+                label VARCHAR(200) NOT NULL,
+                sort_order INT NOT NULL DEFAULT 0,
+                FOREIGN KEY (poll_id) REFERENCES polls(id) ON DELETE CASCADE
+            );
+            CREATE INDEX idx_poll_options_poll ON poll_options(poll_id);
+            CREATE TABLE IF NOT EXISTS poll_voters (
+                poll_id BIGINT NOT NULL,
+                voter_key VARCHAR(128) NOT NULL,
+                created_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+                PRIMARY KEY (poll_id, voter_key),
+                FOREIGN KEY (poll_id) REFERENCES polls(id) ON DELETE CASCADE
+            );
+            CREATE TABLE IF NOT EXISTS poll_votes (
+                poll_id BIGINT NOT NULL,
+                option_id BIGINT NOT NULL,
+                voter_key VARCHAR(128) NOT NULL,
+                PRIMARY KEY (poll_id, voter_key, option_id),
+                FOREIGN KEY (poll_id) REFERENCES polls(id) ON DELETE CASCADE,
+                FOREIGN KEY (option_id) REFERENCES poll_options(id) ON DELETE CASCADE
+            );
+        "#,
+    },
+    // Migration 53: Calendar events, published as a list and an iCal feed
+    Migration {
+        version: 53,
+        name: "create_events",
+        up_sqlite: r#"
+            CREATE TABLE IF NOT EXISTS events (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                title VARCHAR(200) NOT NULL,
+                description TEXT NOT NULL DEFAULT '',
+                location VARCHAR(300),
+                url VARCHAR(1000),
+                starts_at TIMESTAMP NOT NULL,
+                ends_at TIMESTAMP,
+                all_day BOOLEAN NOT NULL DEFAULT 0,
+                repeat_frequency VARCHAR(10),
+                repeat_interval INTEGER NOT NULL DEFAULT 1,
+                repeat_until TIMESTAMP,
+                created_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+                updated_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP
+            );
+            CREATE INDEX IF NOT EXISTS idx_events_starts_at ON events(starts_at);
+        "#,
+        up_mysql: r#"
+            CREATE TABLE IF NOT EXISTS events (
+                id BIGINT PRIMARY KEY AUTO_INCREMENT,
+                title VARCHAR(200) NOT NULL,
+                description TEXT NOT NULL,
+                location VARCHAR(300) NULL,
+                url VARCHAR(1000) NULL,
+                starts_at TIMESTAMP NOT NULL,
+                ends_at TIMESTAMP NULL,
+                all_day BOOLEAN NOT NULL DEFAULT FALSE,
+                repeat_frequency VARCHAR(10) NULL,
+                repeat_interval INT NOT NULL DEFAULT 1,
+                repeat_until TIMESTAMP NULL,
+                created_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+                updated_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP ON UPDATE CURRENT_TIMESTAMP
+            );
+            CREATE INDEX idx_events_starts_at ON events(starts_at);
+        "#,
+    },
+    // Migration 54: Versioned documentation trees
+    Migration {
+        version: 54,
+        name: "create_docs",
+        up_sqlite: r#"
+            CREATE TABLE IF NOT EXISTS doc_versions (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                slug VARCHAR(50) NOT NULL UNIQUE,
+                title VARCHAR(100) NOT NULL,
+                position INTEGER NOT NULL DEFAULT 0,
+                is_latest BOOLEAN NOT NULL DEFAULT 0,
+                created_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+                updated_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP
+            );
+            CREATE TABLE IF NOT EXISTS doc_pages (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                version_id INTEGER NOT NULL,
+                parent_id INTEGER,
+                slug VARCHAR(100) NOT NULL,
+                title VARCHAR(200) NOT NULL,
+                content TEXT NOT NULL DEFAULT '',
+                content_html TEXT NOT NULL DEFAULT '',
+                position INTEGER NOT NULL DEFAULT 0,
+                created_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+                updated_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+                UNIQUE (version_id, slug),
+                FOREIGN KEY (version_id) REFERENCES doc_versions(id) ON DELETE CASCADE,
+                FOREIGN KEY (parent_id) REFERENCES doc_pages(id) ON DELETE SET NULL
+            );
+            CREATE INDEX IF NOT EXISTS idx_doc_pages_slug ON doc_pages(slug);
+        "#,
+        up_mysql: r#"
+            CREATE TABLE IF NOT EXISTS doc_versions (
+                id BIGINT PRIMARY KEY AUTO_INCREMENT,
+                slug VARCHAR(50) NOT NULL UNIQUE,
+                title VARCHAR(100) NOT NULL,
+                position INT NOT NULL DEFAULT 0,
+                is_latest BOOLEAN NOT NULL DEFAULT FALSE,
+                created_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+                updated_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP ON UPDATE CURRENT_TIMESTAMP
+            );
+            CREATE TABLE IF NOT EXISTS doc_pages (
+                id BIGINT PRIMARY KEY AUTO_INCREMENT,
+                version_id BIGINT NOT NULL,
+                parent_id BIGINT NULL,
+                slug VARCHAR(100) NOT NULL,
+                title VARCHAR(200) NOT NULL,
+                content LONGTEXT NOT NULL,
+                content_html LONGTEXT NOT NULL,
+                position INT NOT NULL DEFAULT 0,
+                created_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+                updated_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP ON UPDATE CURRENT_TIMESTAMP,
+                UNIQUE KEY uk_doc_pages_version_slug (version_id, slug),
+                FOREIGN KEY (version_id) REFERENCES doc_versions(id) ON DELETE CASCADE,
+                FOREIGN KEY (parent_id) REFERENCES doc_pages(id) ON DELETE SET NULL
+            );
+            CREATE INDEX idx_doc_pages_slug ON doc_pages(slug);
+        "#,
+    },
+    // Migration 55: FAQ topics and their questions
+    Migration {
+        version: 55,
+        name: "create_faqs",
+        up_sqlite: r#"
+            CREATE TABLE IF NOT EXISTS faq_topics (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                slug VARCHAR(100) NOT NULL UNIQUE,
+                title VARCHAR(200) NOT NULL,
+                description TEXT NOT NULL DEFAULT '',
+                position INTEGER NOT NULL DEFAULT 0,
+                created_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+                updated_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP
+            );
+            CREATE TABLE IF NOT EXISTS faq_items (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                topic_id INTEGER NOT NULL,
+                question VARCHAR(500) NOT NULL,
+                answer TEXT NOT NULL DEFAULT '',
+                answer_html TEXT NOT NULL DEFAULT '',
+                position INTEGER NOT NULL DEFAULT 0,
+                created_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+                updated_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+                FOREIGN KEY (topic_id) REFERENCES faq_topics(id) ON DELETE CASCADE
+            );
+            CREATE INDEX IF NOT EXISTS idx_faq_items_topic ON faq_items(topic_id, position);
+        "#,
+        up_mysql: r#"
+            CREATE TABLE IF NOT EXISTS faq_topics (
+                id BIGINT PRIMARY KEY AUTO_INCREMENT,
+                slug VARCHAR(100) NOT NULL UNIQUE,
+                title VARCHAR(200) NOT NULL,
+                description TEXT NOT NULL,
+                position INT NOT NULL DEFAULT 0,
+                created_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+                updated_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP ON UPDATE CURRENT_TIMESTAMP
+            );
+            CREATE TABLE IF NOT EXISTS faq_items (
+                id BIGINT PRIMARY KEY AUTO_INCREMENT,
+                topic_id BIGINT NOT NULL,
+                question VARCHAR(500) NOT NULL,
+                answer TEXT NOT NULL,
+                answer_html TEXT NOT NULL,
+                position INT NOT NULL DEFAULT 0,
+                created_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+                updated_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP ON UPDATE CURRENT_TIMESTAMP,
+                FOREIGN KEY (topic_id) REFERENCES faq_topics(id) ON DELETE CASCADE
+            );
+            CREATE INDEX idx_faq_items_topic ON faq_items(topic_id, position);
+        "#,
+    },
+    // Migration 56: Language variants of articles and pages
+    Migration {
+        version: 56,
+        name: "create_content_translations",
+        up_sqlite: r#"
+            CREATE TABLE IF NOT EXISTS content_translations (
+                content_type VARCHAR(20) NOT NULL,
+                content_id INTEGER NOT NULL,
+                lang VARCHAR(35) NOT NULL,
+                translation_group VARCHAR(64) NOT NULL,
+                created_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+                PRIMARY KEY (content_type, content_id),
+                UNIQUE (content_type, translation_group, lang)
+            );
+            CREATE INDEX IF NOT EXISTS idx_content_translations_lang ON content_translations(content_type, lang);
+        "#,
+        up_mysql: r#"
+            CREATE TABLE IF NOT EXISTS content_translations (
+                content_type VARCHAR(20) NOT NULL,
+                content_id BIGINT NOT NULL,
+                lang VARCHAR(35) NOT NULL,
+                translation_group VARCHAR(64) NOT NULL,
+                created_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+                PRIMARY KEY (content_type, content_id),
+                UNIQUE KEY uq_content_translations_group (content_type, translation_group, lang)
+            );
+            CREATE INDEX idx_content_translations_lang ON content_translations(content_type, lang);
+        "#,
+    },    // Migration 57: Article series and the order of their articles
+    Migration {
+        version: 57,
+        name: "create_series",
+        up_sqlite: r#"
+            CREATE TABLE IF NOT EXISTS series (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                slug VARCHAR(100) NOT NULL UNIQUE,
+                title VARCHAR(200) NOT NULL,
+                description TEXT NOT NULL DEFAULT '',
+                created_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+                updated_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP
+            );
+            CREATE TABLE IF NOT EXISTS series_articles (
+                series_id INTEGER NOT NULL,
+                article_id INTEGER NOT NULL UNIQUE,
+                position INTEGER NOT NULL DEFAULT 0,
+                PRIMARY KEY (series_id, article_id),
+                FOREIGN KEY (series_id) REFERENCES series(id) ON DELETE CASCADE,
+                FOREIGN KEY (article_id) REFERENCES articles(id) ON DELETE CASCADE
+            );
+            CREATE INDEX IF NOT EXISTS idx_series_articles_position ON series_articles(series_id, position);
+        "#,
+        up_mysql: r#"
+            CREATE TABLE IF NOT EXISTS series (
+                id BIGINT PRIMARY KEY AUTO_INCREMENT,
+                slug VARCHAR(100) NOT NULL UNIQUE,
+                title VARCHAR(200) NOT NULL,
+                description TEXT NOT NULL,
+                created_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+                updated_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP ON UPDATE CURRENT_TIMESTAMP
+            );
+            CREATE TABLE IF NOT EXISTS series_articles (
+                series_id BIGINT NOT NULL,
+                article_id BIGINT NOT NULL UNIQUE,
+                position INT NOT NULL DEFAULT 0,
+                PRIMARY KEY (series_id, article_id),
+                FOREIGN KEY (series_id) REFERENCES series(id) ON DELETE CASCADE,
+                FOREIGN KEY (article_id) REFERENCES articles(id) ON DELETE CASCADE
+            );
+            CREATE INDEX idx_series_articles_position ON series_articles(series_id, position);
+        "#,
+    },
+    // Migration 58: Files attached to articles
+    Migration {
+        version: 58,
+        name: "create_article_attachments",
+        up_sqlite: r#"
+            CREATE TABLE IF NOT EXISTS article_attachments (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                article_id INTEGER NOT NULL,
+                filename VARCHAR(255) NOT NULL,
+                stored_name VARCHAR(100) NOT NULL,
+                content_type VARCHAR(100) NOT NULL,
+                size INTEGER NOT NULL,
+                position INTEGER NOT NULL DEFAULT 0,
+                created_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+                FOREIGN KEY (article_id) REFERENCES articles(id) ON DELETE CASCADE
+            );
+            CREATE INDEX IF NOT EXISTS idx_article_attachments_article ON article_attachments(article_id, position);
+        "#,
+        up_mysql: r#"
+            CREATE TABLE IF NOT EXISTS article_attachments (
+                id BIGINT PRIMARY KEY AUTO_INCREMENT,
+                article_id BIGINT NOT NULL,
+                filename VARCHAR(255) NOT NULL,
+                stored_name VARCHAR(100) NOT NULL,
+                content_type VARCHAR(100) NOT NULL,
+                size BIGINT NOT NULL,
+                position INT NOT NULL DEFAULT 0,
+                created_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+                FOREIGN KEY (article_id) REFERENCES articles(id) ON DELETE CASCADE
+            );
+            CREATE INDEX idx_article_attachments_article ON article_attachments(article_id, position);
+        "#,
+    },
+    // Migration 59: Co-authors with roles; existing articles keep their
+    // author as the only one
+    Migration {
+        version: 59,
+        name: "create_article_authors",
+        up_sqlite: r#"
+            CREATE TABLE IF NOT EXISTS article_authors (
+                article_id INTEGER NOT NULL,
+                user_id INTEGER NOT NULL,
+                role VARCHAR(20) NOT NULL DEFAULT 'author',
+                position INTEGER NOT NULL DEFAULT 0,
+                PRIMARY KEY (article_id, user_id),
+                FOREIGN KEY (article_id) REFERENCES articles(id) ON DELETE CASCADE,
+                FOREIGN KEY (user_id) REFERENCES users(id) ON DELETE CASCADE
+            );
+            CREATE INDEX IF NOT EXISTS idx_article_authors_user ON article_authors(user_id, role);
+            INSERT OR IGNORE INTO article_authors (article_id, user_id, role, position)
+                SELECT id, author_id, 'author', 0 FROM articles;
+        "#,
+        up_mysql: r#"
+            CREATE TABLE IF NOT EXISTS article_authors (
+                article_id BIGINT NOT NULL,
+                user_id BIGINT NOT NULL,
+                role VARCHAR(20) NOT NULL DEFAULT 'author',
+                position INT NOT NULL DEFAULT 0,
+                PRIMARY KEY (article_id, user_id),
+                FOREIGN KEY (article_id) REFERENCES articles(id) ON DELETE CASCADE,
+                FOREIGN KEY (user_id) REFERENCES users(id) ON DELETE CASCADE
+            );
+            CREATE INDEX idx_article_authors_user ON article_authors(user_id, role);
+            INSERT IGNORE INTO article_authors (article_id, user_id, role, position)
+                SELECT id, author_id, 'author', 0 FROM articles;
+        "#,
+    },
+    // Migration 60: Typed key/value custom fields on articles and pages
+    Migration {
+        version: 60,
+        name: "create_custom_fields",
+        up_sqlite: r#"
+            CREATE TABLE IF NOT EXISTS custom_fields (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                content_type VARCHAR(20) NOT NULL,
+                content_id INTEGER NOT NULL,
+                field_key VARCHAR(64) NOT NULL,
+                field_type VARCHAR(20) NOT NULL DEFAULT 'text',
+                value TEXT NOT NULL,
+                updated_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+                UNIQUE (content_type, content_id, field_key)
+            );
+            CREATE INDEX IF NOT EXISTS idx_custom_fields_key ON custom_fields(content_type, field_key);
+        "#,
+        up_mysql: r#"
+            CREATE TABLE IF NOT EXISTS custom_fields (
+                id BIGINT PRIMARY KEY AUTO_INCREMENT,
+                content_type VARCHAR(20) NOT NULL,
+                content_id BIGINT NOT NULL,
+                field_key VARCHAR(64) NOT NULL,
+                field_type VARCHAR(20) NOT NULL DEFAULT 'text',
+                value TEXT NOT NULL,
+                updated_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+                UNIQUE KEY uk_custom_fields_content (content_type, content_id, field_key)
+            );
+            CREATE INDEX idx_custom_fields_key ON custom_fields(content_type, field_key);
+        "#,
+    },
+    // Migration 61: Attachment download counts, access gates and download log
+    Migration {
+        version: 61,
+        name: "add_attachment_downloads",
+        up_sqlite: r#"
+            ALTER TABLE article_attachments ADD COLUMN access VARCHAR(20) NOT NULL DEFAULT 'public';
+            ALTER TABLE article_attachments ADD COLUMN download_count INTEGER NOT NULL DEFAULT 0;
+            CREATE TABLE IF NOT EXISTS attachment_downloads (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                attachment_id INTEGER NOT NULL,
+                user_id INTEGER,
+                email VARCHAR(255),
+                created_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+                FOREIGN KEY (attachment_id) REFERENCES article_attachments(id) ON DELETE CASCADE
+            );
+            CREATE INDEX IF NOT EXISTS idx_attachment_downloads_attachment ON attachment_downloads(attachment_id, created_at);
+        "#,
+        up_mysql: r#"
+            ALTER TABLE article_attachments ADD COLUMN access VARCHAR(20) NOT NULL DEFAULT 'public';
+            ALTER TABLE article_attachments ADD COLUMN download_count BIGINT NOT NULL DEFAULT 0;
+            CREATE TABLE IF NOT EXISTS attachment_downloads (
+                id BIGINT PRIMARY KEY AUTO_INCREMENT,
+                attachment_id BIGINT NOT NULL,
+                user_id BIGINT,
+                email VARCHAR(255),
+                created_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+                FOREIGN KEY (attachment_id) REFERENCES article_attachments(id) ON DELETE CASCADE
+            );
+            CREATE INDEX idx_attachment_downloads_attachment ON attachment_downloads(attachment_id, created_at);
+        "#,
+    },
+    // Migration 62: Author-written article excerpts
+    Migration {
+        version: 62,
+        name: "add_article_excerpt",
+        up_sqlite: r#"
+            ALTER TABLE articles ADD COLUMN excerpt TEXT;
+        "#,
+        up_mysql: r#"
+            ALTER TABLE articles ADD COLUMN excerpt TEXT;
+        "#,
+    },
+    // Migration 63: Per-article license, as JSON; NULL uses the site default
+    Migration {
+        version: 63,
+        name: "add_article_license",
+        up_sqlite: r#"
+            ALTER TABLE articles ADD COLUMN license VARCHAR(1000);
+        "#,
+        up_mysql: r#"
+            ALTER TABLE articles ADD COLUMN license VARCHAR(1000);
+        "#,
+    },
+    // Migration 64: Word count and reading time, measured on save; NULL
+    // until existing articles are measured at startup
+    Migration {
+        version: 64,
+        name: "add_article_content_stats",
+        up_sqlite: r#"
+            ALTER TABLE articles ADD COLUMN word_count INTEGER;
+            ALTER TABLE articles ADD COLUMN char_count INTEGER;
+            ALTER TABLE articles ADD COLUMN reading_time INTEGER;
+        "#,
+        up_mysql: r#"
+            ALTER TABLE articles ADD COLUMN word_count BIGINT;
+            ALTER TABLE articles ADD COLUMN char_count BIGINT;
+            ALTER TABLE articles ADD COLUMN reading_time BIGINT;
+        "#,
+    },
+    // Migration 65: Hand-picked featured articles, in order, with optional
+    // expiry
+    Migration {
+        version: 65,
+        name: "create_featured_articles",
+        up_sqlite: r#"
+            CREATE TABLE IF NOT EXISTS featured_articles (
+                article_id INTEGER PRIMARY KEY,
+                position INTEGER NOT NULL DEFAULT 0,
+                expires_at TIMESTAMP,
+                created_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+                FOREIGN KEY (article_id) REFERENCES articles(id) ON DELETE CASCADE
+            );
+            CREATE INDEX IF NOT EXISTS idx_featured_articles_position ON featured_articles(position);
+        "#,
+        up_mysql: r#"
+            CREATE TABLE IF NOT EXISTS featured_articles (
+                article_id BIGINT PRIMARY KEY,
+                position INT NOT NULL DEFAULT 0,
+                expires_at TIMESTAMP NULL,
+                created_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+                FOREIGN KEY (article_id) REFERENCES articles(id) ON DELETE CASCADE
+            );
+            CREATE INDEX idx_featured_articles_position ON featured_articles(position);
+        "#,
+    },
+    // Migration 66: Category access level; articles in non-public
+    // categories are hidden from readers without clearance
+    Migration {
+        version: 66,
+        name: "add_category_access",
+        up_sqlite: r#"
+            ALTER TABLE categories ADD COLUMN access VARCHAR(20) NOT NULL DEFAULT 'public';
+        "#,
+        up_mysql: r#"
+            ALTER TABLE categories ADD COLUMN access VARCHAR(20) NOT NULL DEFAULT 'public';
+        "#,
+    },
+    // Migration 67: Reader groups and article audiences; an article with
+    // audience rows is only shown to the readers they admit
+    Migration {
+        version: 67,
+        name: "create_reader_groups",
+        up_sqlite: r#"
+            CREATE TABLE IF NOT EXISTS reader_groups (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                name VARCHAR(100) NOT NULL,
+                slug VARCHAR(100) NOT NULL UNIQUE,
+                description TEXT,
+                created_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP
+            );
+            CREATE TABLE IF NOT EXISTS reader_group_members (
+                group_id INTEGER NOT NULL,
+                user_id INTEGER NOT NULL,
+                PRIMARY KEY (group_id, user_id),
+                FOREIGN KEY (group_id) REFERENCES reader_groups(id) ON DELETE CASCADE,
+                FOREIGN KEY (user_id) REFERENCES users(id) ON DELETE CASCADE
+            );
+            CREATE INDEX IF NOT EXISTS idx_reader_group_members_user ON reader_group_members(user_id);
+            CREATE TABLE IF NOT EXISTS article_audiences (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                article_id INTEGER NOT NULL,
+                role VARCHAR(20),
+                group_id INTEGER,
+                FOREIGN KEY (article_id) REFERENCES articles(id) ON DELETE CASCADE,
+                FOREIGN KEY (group_id) REFERENCES reader_groups(id) ON DELETE CASCADE
+            );
+            CREATE INDEX IF NOT EXISTS idx_article_audiences_article ON article_audiences(article_id);
+        "#,
+        up_mysql: r#"
+            CREATE TABLE IF NOT EXISTS reader_groups (
+                id BIGINT PRIMARY KEY AUTO_INCREMENT,
+                name VARCHAR(100) NOT NULL,
+                slug VARCHAR(100) NOT NULL UNIQUE,
+                description TEXT,
+                created_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP
+            );
+            CREATE TABLE IF NOT EXISTS reader_group_members (
+                group_id BIGINT NOT NULL,
+                user_id BIGINT NOT NULL,
+                PRIMARY KEY (group_id, user_id),
+                FOREIGN KEY (group_id) REFERENCES reader_groups(id) ON DELETE CASCADE,
+                FOREIGN KEY (user_id) REFERENCES users(id) ON DELETE CASCADE
+            );
+            CREATE INDEX idx_reader_group_members_user ON reader_group_members(user_id);
+            CREATE TABLE IF NOT EXISTS article_audiences (
+                id BIGINT PRIMARY KEY AUTO_INCREMENT,
+                article_id BIGINT NOT NULL,
+                role VARCHAR(20),
+                group_id BIGINT,
+                FOREIGN KEY (article_id) REFERENCES articles(id) ON DELETE CASCADE,
+                FOREIGN KEY (group_id) REFERENCES reader_groups(id) ON DELETE CASCADE
+            );
+            CREATE INDEX idx_article_audiences_article ON article_audiences(article_id);
+        "#,
+    },
+    // Migration 68: Former slugs of renamed and merged tags, so old tag
+    // URLs keep resolving to the tag that replaced them
+    Migration {
+        version: 68,
+        name: "create_tag_slugs",
+        up_sqlite: r#"
+            CREATE TABLE IF NOT EXISTS tag_slugs (
+                slug VARCHAR(100) PRIMARY KEY,
+                tag_id INTEGER NOT NULL,
+                created_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP,
+                FOREIGN KEY (tag_id) REFERENCES tags(id) ON DELETE CASCADE
+            );
+            CREATE INDEX IF NOT EXISTS idx_tag_slugs_tag ON tag_slugs(tag_id);
+        "#,
+        up_mysql: r#"
+            CREATE TABLE IF NOT EXISTS tag_slugs (
+                slug VARCHAR(100) PRIMARY KEY,
+                tag_id BIGINT NOT NULL,
+                created_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP,
+                FOREIGN KEY (tag_id) REFERENCES tags(id) ON DELETE CASCADE
+            );
+            CREATE INDEX idx_tag_slugs_tag ON tag_slugs(tag_id);
+        "#,
+    },
+    // Migration 69: Bindings between SAML / LDAP accounts and local users
+    Migration {
+        version: 69,
+        name: "create_external_identities",
+        up_sqlite: r#"
+            CREATE TABLE IF NOT EXISTS external_identities (
+                provider VARCHAR(20) NOT NULL,
+                issuer VARCHAR(255) NOT NULL,
+                subject VARCHAR(255) NOT NULL,
+                user_id INTEGER NOT NULL,
+                manages_role BOOLEAN NOT NULL DEFAULT 0,
+                created_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP,
+                PRIMARY KEY (provider, issuer, subject),
+                FOREIGN KEY (user_id) REFERENCES users(id) ON DELETE CASCADE
+            );
+            CREATE INDEX IF NOT EXISTS idx_external_identities_user ON external_identities(user_id);
+        "#,
+        up_mysql: r#"
+            CREATE TABLE IF NOT EXISTS external_identities (
+                provider VARCHAR(20) NOT NULL,
+                issuer VARCHAR(255) NOT NULL,
+                subject VARCHAR(255) NOT NULL,
+                user_id BIGINT NOT NULL,
+                manages_role BOOLEAN NOT NULL DEFAULT FALSE,
+                created_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP,
+                PRIMARY KEY (provider, issuer, subject),
+                FOREIGN KEY (user_id) REFERENCES users(id) ON DELETE CASCADE
+            );
+            CREATE INDEX idx_external_identities_user ON external_identities(user_id);
+        "#,
+    },
+    // Migration 70: Passkeys are stored as webauthn-rs credentials (JSON).
+    // Ones saved earlier as bare COSE keys cannot be loaded and are dropped;
+    // their owners sign in with their password and register again.
+    Migration {
+        version: 70,
+        name: "drop_cose_only_webauthn_credentials",
+        up_sqlite: r#"
+            DELETE FROM webauthn_credentials WHERE public_key NOT LIKE '{%';
+        "#,
+        up_mysql: r#"
+            DELETE FROM webauthn_credentials WHERE public_key NOT LIKE '{%';
+        "#,
+    },
 ];
 
 /// Run all pending migrations
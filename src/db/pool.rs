@@ -41,6 +41,15 @@ pub trait DatabasePool: Send + Sync {
     /// Get the underlying MySQL pool if this is a MySQL connection
     fn as_mysql(&self) -> Option<&MySqlPool>;
 
+    /// Number of connections currently open, idle or in use
+    fn connections(&self) -> u32 {
+        match (self.as_sqlite(), self.as_mysql()) {
+            (Some(pool), _) => pool.size(),
+            (_, Some(pool)) => pool.size(),
+            _ => 0,
+        }
+    }
+
     /// Get the underlying SQLite pool, or return an error if this is not a SQLite connection
     fn as_sqlite_or_err(&self) -> Result<&SqlitePool> {
         self.as_sqlite()
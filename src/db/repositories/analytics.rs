@@ -0,0 +1,361 @@
+//! Repository for the built-in page view analytics
+//!
+//! Counts are aggregated per UTC day (`YYYY-MM-DD`) before they reach the
+//! database; visitors are unique per day and key, so they add up across
+//! flushes but not across days. Ranges include `from` and exclude `to`.
+
+use anyhow::{Context, Result};
+use async_trait::async_trait;
+use serde::Serialize;
+use sqlx::{MySqlPool, Row, SqlitePool};
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use crate::db::DynDatabasePool;
+
+/// Page views and unique visitors
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize)]
+pub struct VisitCounts {
+    pub views: i64,
+    pub visitors: i64,
+}
+
+impl VisitCounts {
+    pub fn add(&mut self, other: VisitCounts) {
+        self.views += other.views;
+        self.visitors += other.visitors;
+    }
+}
+
+/// Counts collected for one day, written in one transaction
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct DayCounts {
+    pub total: VisitCounts,
+    pub pages: HashMap<String, VisitCounts>,
+    /// By referring host
+    pub referrers: HashMap<String, VisitCounts>,
+    /// By ISO 3166-1 alpha-2 code
+    pub countries: HashMap<String, VisitCounts>,
+}
+
+impl DayCounts {
+    pub fn merge(&mut self, other: DayCounts) {
+        self.total.add(other.total);
+        for (mine, theirs) in [
+            (&mut self.pages, other.pages),
+            (&mut self.referrers, other.referrers),
+            (&mut self.countries, other.countries),
+        ] {
+            for (key, counts) in theirs {
+                mine.entry(key).or_default().add(counts);
+            }
+        }
+    }
+}
+
+/// Site-wide views on one day
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct DailyVisits {
+    pub day: String,
+    pub views: i64,
+    pub visitors: i64,
+}
+
+/// What a ranking is broken down by
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TrafficDimension {
+    Page,
+    Referrer,
+    Country,
+}
+
+impl TrafficDimension {
+    fn table(self) -> &'static str {
+        match self {
+            TrafficDimension::Page => "analytics_pages_daily",
+            TrafficDimension::Referrer => "analytics_referrers_daily",
+            TrafficDimension::Country => "analytics_countries_daily",
+        }
+    }
+
+    fn column(self) -> &'static str {
+        match self {
+            TrafficDimension::Page => "path",
+            TrafficDimension::Referrer => "referrer",
+            TrafficDimension::Country => "country",
+        }
+    }
+}
+
+/// A page, referrer or country ranked by views within a range
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct RankedTraffic {
+    pub key: String,
+    pub views: i64,
+    /// Sum of the daily unique visitors
+    pub visitors: i64,
+}
+
+/// Repository trait for analytics aggregates
+#[async_trait]
+pub trait AnalyticsRepository: Send + Sync {
+    /// Add the counts collected for `day`
+    async fn add_counts(&self, day: &str, counts: &DayCounts) -> Result<()>;
+
+    /// Days in `[from, to)` with at least one view, oldest first
+    async fn visits_by_day(&self, from: &str, to: &str) -> Result<Vec<DailyVisits>>;
+
+    /// Top `limit` keys of `dimension` in `[from, to)`, most viewed first
+    async fn top(
+        &self,
+        dimension: TrafficDimension,
+        from: &str,
+        to: &str,
+        limit: i64,
+    ) -> Result<Vec<RankedTraffic>>;
+}
+
+/// SQLx-based analytics repository
+pub struct SqlxAnalyticsRepository {
+    pool: DynDatabasePool,
+}
+
+impl SqlxAnalyticsRepository {
+    pub fn new(pool: DynDatabasePool) -> Self {
+        Self { pool }
+    }
+
+    /// Create a boxed repository for use with dependency injection
+    pub fn boxed(pool: DynDatabasePool) -> Arc<dyn AnalyticsRepository> {
+        Arc::new(Self::new(pool))
+    }
+}
+
+#[async_trait]
+impl AnalyticsRepository for SqlxAnalyticsRepository {
+    async fn add_counts(&self, day: &str, counts: &DayCounts) -> Result<()> {
+        dispatch!(self, add_counts, day, counts)
+    }
+
+    async fn visits_by_day(&self, from: &str, to: &str) -> Result<Vec<DailyVisits>> {
+        dispatch!(self, visits_by_day, from, to)
+    }
+
+    async fn top(
+        &self,
+        dimension: TrafficDimension,
+        from: &str,
+        to: &str,
+        limit: i64,
+    ) -> Result<Vec<RankedTraffic>> {
+        dispatch!(self, top, dimension, from, to, limit)
+    }
+}
+
+// ============================================================================
+// Shared implementations (identical SQL)
+// ============================================================================
+
+impl_dual_fn! {
+    async fn visits_by_day(pool, from: &str, to: &str) -> Result<Vec<DailyVisits>> {
+        let rows = sqlx::query(
+            "SELECT day, views, visitors FROM analytics_daily \
+             WHERE day >= ? AND day < ? AND views > 0 ORDER BY day",
+        )
+        .bind(from)
+        .bind(to)
+        .fetch_all(pool)
+        .await
+        .context("Failed to load daily visits")?;
+        Ok(rows
+            .iter()
+            .map(|row| DailyVisits {
+                day: row.get("day"),
+                views: row.get("views"),
+                visitors: row.get("visitors"),
+            })
+            .collect())
+    }
+}
+
+impl_dual_fn! {
+    async fn top(pool, dimension: TrafficDimension, from: &str, to: &str, limit: i64) -> Result<Vec<RankedTraffic>> {
+        let sql = format!(
+            "SELECT {column} AS name, CAST(SUM(views) AS SIGNED) AS views, \
+             CAST(SUM(visitors) AS SIGNED) AS visitors FROM {table} \
+             WHERE day >= ? AND day < ? GROUP BY {column} ORDER BY views DESC, name LIMIT ?",
+            column = dimension.column(),
+            table = dimension.table(),
+        );
+        let rows = sqlx::query(&sql)
+            .bind(from)
+            .bind(to)
+            .bind(limit)
+            .fetch_all(pool)
+            .await
+            .context("Failed to load traffic ranking")?;
+        Ok(rows
+            .iter()
+            .map(|row| RankedTraffic {
+                key: row.get("name"),
+                views: row.get("views"),
+                visitors: row.get("visitors"),
+            })
+            .collect())
+    }
+}
+
+// ============================================================================
+// Driver-specific implementations (upsert syntax differs)
+// ============================================================================
+
+const DIMENSIONS: [TrafficDimension; 3] = [
+    TrafficDimension::Page,
+    TrafficDimension::Referrer,
+    TrafficDimension::Country,
+];
+
+fn rows_of(counts: &DayCounts, dimension: TrafficDimension) -> &HashMap<String, VisitCounts> {
+    match dimension {
+        TrafficDimension::Page => &counts.pages,
+        TrafficDimension::Referrer => &counts.referrers,
+        TrafficDimension::Country => &counts.countries,
+    }
+}
+
+async fn add_counts_sqlite(pool: &SqlitePool, day: &str, counts: &DayCounts) -> Result<()> {
+    const ADD: &str = "DO UPDATE SET views = views + excluded.views, \
+                       visitors = visitors + excluded.visitors";
+    let mut tx = pool.begin().await.context("Failed to start transaction")?;
+    sqlx::query(&format!(
+        "INSERT INTO analytics_daily (day, views, visitors) VALUES (?, ?, ?) \
+         ON CONFLICT(day) {ADD}"
+    ))
+    .bind(day)
+    .bind(counts.total.views)
+    .bind(counts.total.visitors)
+    .execute(&mut *tx)
+    .await
+    .context("Failed to record daily visits")?;
+    for dimension in DIMENSIONS {
+        let sql = format!(
+            "INSERT INTO {table} (day, {column}, views, visitors) VALUES (?, ?, ?, ?) \
+             ON CONFLICT(day, {column}) {ADD}",
+            table = dimension.table(),
+            column = dimension.column(),
+        );
+        for (key, c) in rows_of(counts, dimension) {
+            sqlx::query(&sql)
+                .bind(day)
+                .bind(key)
+                .bind(c.views)
+                .bind(c.visitors)
+                .execute(&mut *tx)
+                .await
+                .with_context(|| format!("Failed to record {}", dimension.column()))?;
+        }
+    }
+    tx.commit().await.context("Failed to commit transaction")?;
+    Ok(())
+}
+
+async fn add_counts_mysql(pool: &MySqlPool, day: &str, counts: &DayCounts) -> Result<()> {
+    const ADD: &str = "ON DUPLICATE KEY UPDATE views = views + VALUES(views), \
+                       visitors = visitors + VALUES(visitors)";
+    let mut tx = pool.begin().await.context("Failed to start transaction")?;
+    sqlx::query(&format!(
+        "INSERT INTO analytics_daily (day, views, visitors) VALUES (?, ?, ?) {ADD}"
+    ))
+    .bind(day)
+    .bind(counts.total.views)
+    .bind(counts.total.visitors)
+    .execute(&mut *tx)
+    .await
+    .context("Failed to record daily visits")?;
+    for dimension in DIMENSIONS {
+        let sql = format!(
+            "INSERT INTO {table} (day, {column}, views, visitors) VALUES (?, ?, ?, ?) {ADD}",
+            table = dimension.table(),
+            column = dimension.column(),
+        );
+        for (key, c) in rows_of(counts, dimension) {
+            sqlx::query(&sql)
+                .bind(day)
+                .bind(key)
+                .bind(c.views)
+                .bind(c.visitors)
+                .execute(&mut *tx)
+                .await
+                .with_context(|| format!("Failed to record {}", dimension.column()))?;
+        }
+    }
+    tx.commit().await.context("Failed to commit transaction")?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::{create_test_pool, migrations};
+
+    fn counts(views: i64, visitors: i64) -> VisitCounts {
+        VisitCounts { views, visitors }
+    }
+
+    #[tokio::test]
+    async fn test_counts_add_up_across_flushes() {
+        let pool = create_test_pool().await.unwrap();
+        migrations::run_migrations(&pool).await.unwrap();
+        let repo = SqlxAnalyticsRepository::new(pool);
+
+        let mut first = DayCounts {
+            total: counts(3, 2),
+            ..Default::default()
+        };
+        first.pages.insert("/".to_string(), counts(2, 2));
+        first.pages.insert("/posts/a".to_string(), counts(1, 1));
+        first
+            .referrers
+            .insert("news.example.com".to_string(), counts(1, 1));
+        first.countries.insert("DE".to_string(), counts(3, 2));
+        repo.add_counts("2024-05-01", &first).await.unwrap();
+
+        let mut second = DayCounts {
+            total: counts(4, 1),
+            ..Default::default()
+        };
+        second.pages.insert("/posts/a".to_string(), counts(4, 1));
+        repo.add_counts("2024-05-01", &second).await.unwrap();
+        repo.add_counts("2024-05-03", &second).await.unwrap();
+
+        let days = repo
+            .visits_by_day("2024-05-01", "2024-05-03")
+            .await
+            .unwrap();
+        assert_eq!(
+            days,
+            vec![DailyVisits {
+                day: "2024-05-01".to_string(),
+                views: 7,
+                visitors: 3
+            }]
+        );
+
+        let pages = repo
+            .top(TrafficDimension::Page, "2024-05-01", "2024-06-01", 10)
+            .await
+            .unwrap();
+        let ranked: Vec<(&str, i64, i64)> = pages
+            .iter()
+            .map(|p| (p.key.as_str(), p.views, p.visitors))
+            .collect();
+        assert_eq!(ranked, vec![("/posts/a", 9, 3), ("/", 2, 2)]);
+
+        let countries = repo
+            .top(TrafficDimension::Country, "2024-05-01", "2024-06-01", 1)
+            .await
+            .unwrap();
+        assert_eq!(countries.len(), 1);
+        assert_eq!(countries[0].key, "DE");
+    }
+}
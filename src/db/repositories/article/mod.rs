@@ -8,9 +8,13 @@
 //!
 //! Satisfies requirements:
 //! - 1.1: WHEN 鐢ㄦ埛鎻愪氦鏂版枃绔?THEN Article_Manager SHALL 鍒涘缓鏂囩珷璁板綍骞剁敓鎴愬敮涓€鏍囪瘑绗?//! - 1.2: WHEN 鐢ㄦ埛璇锋眰鏂囩珷鍒楄〃 THEN Article_Manager SHALL 杩斿洖鍒嗛〉鐨勬枃绔犲垪琛紝鏀寔鎸夋椂闂存帓搴?
+use crate::db::repositories::category::{public_article_sql, readable_category_sql};
+use crate::db::repositories::reader_group::readable_audience_sql;
 use crate::db::DynDatabasePool;
 use crate::models::{
-    Article, ArticleSortBy, ArticleStatus, CreateArticleInput, UpdateArticleInput,
+    Article, ArticleCursor, ArticleFilter, ArticleListScope, ArticleReader, ArticleSlug,
+    ArticleSortBy, ArticleStatus, AuthorRole, CategoryAccess, ContentLicense, ContentStats,
+    CreateArticleInput, InputFormat, ListParams, SortDirection, UpdateArticleInput,
 };
 use anyhow::{Context, Result};
 use async_trait::async_trait;
@@ -113,6 +117,24 @@ pub trait ArticleRepository: Send + Sync {
         sort_by: ArticleSortBy,
     ) -> Result<Vec<Article>>;
 
+    /// List articles in `scope` after a keyset cursor, ordered by
+    /// `created_at DESC, id DESC`. Without a cursor the first page is returned.
+    async fn list_after_cursor(
+        &self,
+        scope: &ArticleListScope,
+        cursor: Option<&ArticleCursor>,
+        limit: i64,
+        clearance: CategoryAccess,
+        reader: Option<&ArticleReader>,
+    ) -> Result<Vec<Article>>;
+
+    /// List a page of articles matching `params.filter`, ordered by
+    /// `params.sort_by` / `params.direction`
+    async fn list_filtered(&self, params: &ListParams) -> Result<Vec<Article>>;
+
+    /// Count articles matching `filter`
+    async fn count_filtered(&self, filter: &ArticleFilter) -> Result<i64>;
+
     /// Count articles by status
     async fn count_by_status(&self, status: ArticleStatus) -> Result<i64>;
 
@@ -128,6 +150,19 @@ pub trait ArticleRepository: Send + Sync {
     /// Check if a slug exists for a different article (for updates)
     async fn exists_by_slug_excluding(&self, slug: &str, exclude_id: i64) -> Result<bool>;
 
+    /// Whether an article is published where anonymous visitors can read it
+    async fn is_public(&self, id: i64) -> Result<bool>;
+
+    /// Add a slug to an article's history, taking it over from the article
+    /// that had it before
+    async fn record_slug(&self, article_id: i64, slug: &str) -> Result<()>;
+
+    /// The article that has or last had `slug`, which may be deleted
+    async fn get_slug_owner(&self, slug: &str) -> Result<Option<i64>>;
+
+    /// Every slug an article has had, oldest first
+    async fn list_slugs(&self, article_id: i64) -> Result<Vec<ArticleSlug>>;
+
     /// Search articles by keyword in title and content
     async fn search(
         &self,
@@ -152,9 +187,23 @@ pub trait ArticleRepository: Send + Sync {
     /// Replace article meta JSON with a complete object.
     async fn replace_meta(&self, article_id: i64, meta: &serde_json::Value) -> Result<()>;
 
+    /// Backdate an imported article. `published_at` is only changed when given.
+    async fn set_imported_dates(
+        &self,
+        article_id: i64,
+        created_at: chrono::DateTime<Utc>,
+        published_at: Option<chrono::DateTime<Utc>>,
+    ) -> Result<()>;
+
     /// List draft articles whose scheduled_at has passed (for auto-publishing)
     async fn list_scheduled_due(&self) -> Result<Vec<Article>>;
 
+    /// Articles saved before content stats were measured, as `(id, content_html)`
+    async fn list_unmeasured(&self, limit: i64) -> Result<Vec<(i64, String)>>;
+
+    /// Store measured content stats without touching `updated_at`
+    async fn set_stats(&self, article_id: i64, stats: &ContentStats) -> Result<()>;
+
     /// Get adjacent (prev/next) published articles relative to a given published_at time.
     /// Returns (prev_article, next_article) where prev is newer and next is older.
     async fn get_adjacent(
@@ -174,6 +223,10 @@ pub trait ArticleRepository: Send + Sync {
         category_id: i64,
         limit: i64,
     ) -> Result<Vec<Article>>;
+
+    /// Published articles with the most views since `since` (`YYYY-MM-DD`),
+    /// or by lifetime views when `None`. Each comes with its views in the window.
+    async fn list_popular(&self, since: Option<&str>, limit: i64) -> Result<Vec<(Article, i64)>>;
 }
 
 /// SQLx-based article repository implementation
@@ -198,7 +251,9 @@ impl SqlxArticleRepository {
 #[async_trait]
 impl ArticleRepository for SqlxArticleRepository {
     async fn create(&self, input: &CreateArticleInput) -> Result<Article> {
-        dispatch!(self, create_article, input)
+        let article = dispatch!(self, create_article, input)?;
+        self.record_slug(article.id, &article.slug).await?;
+        Ok(article)
     }
 
     async fn get_by_id(&self, id: i64) -> Result<Option<Article>> {
@@ -218,7 +273,11 @@ impl ArticleRepository for SqlxArticleRepository {
     }
 
     async fn update(&self, id: i64, input: &UpdateArticleInput) -> Result<Article> {
-        dispatch!(self, update_article, id, input)
+        let article = dispatch!(self, update_article, id, input)?;
+        if input.slug.is_some() {
+            self.record_slug(id, &article.slug).await?;
+        }
+        Ok(article)
     }
 
     async fn delete(&self, id: i64) -> Result<()> {
@@ -324,6 +383,36 @@ impl ArticleRepository for SqlxArticleRepository {
         )
     }
 
+    async fn list_after_cursor(
+        &self,
+        scope: &ArticleListScope,
+        cursor: Option<&ArticleCursor>,
+        limit: i64,
+        clearance: CategoryAccess,
+        reader: Option<&ArticleReader>,
+    ) -> Result<Vec<Article>> {
+        if matches!(scope, ArticleListScope::PublishedInCategories(ids) if ids.is_empty()) {
+            return Ok(Vec::new());
+        }
+        dispatch!(
+            self,
+            list_articles_after_cursor,
+            scope,
+            cursor,
+            limit,
+            clearance,
+            reader
+        )
+    }
+
+    async fn list_filtered(&self, params: &ListParams) -> Result<Vec<Article>> {
+        dispatch!(self, list_articles_filtered, params)
+    }
+
+    async fn count_filtered(&self, filter: &ArticleFilter) -> Result<i64> {
+        dispatch!(self, count_articles_filtered, filter)
+    }
+
     async fn count_by_status(&self, status: ArticleStatus) -> Result<i64> {
         dispatch!(self, count_articles_by_status, status)
     }
@@ -344,6 +433,22 @@ impl ArticleRepository for SqlxArticleRepository {
         dispatch!(self, exists_by_slug_excluding, exclude_id, slug)
     }
 
+    async fn is_public(&self, id: i64) -> Result<bool> {
+        dispatch!(self, is_public_article, id)
+    }
+
+    async fn record_slug(&self, article_id: i64, slug: &str) -> Result<()> {
+        dispatch!(self, record_article_slug, article_id, slug)
+    }
+
+    async fn get_slug_owner(&self, slug: &str) -> Result<Option<i64>> {
+        dispatch!(self, get_article_slug_owner, slug)
+    }
+
+    async fn list_slugs(&self, article_id: i64) -> Result<Vec<ArticleSlug>> {
+        dispatch!(self, list_article_slugs, article_id)
+    }
+
     async fn search(
         &self,
         keyword: &str,
@@ -394,11 +499,34 @@ impl ArticleRepository for SqlxArticleRepository {
         dispatch!(self, update_article_meta, article_id, &meta.to_string())
     }
 
+    async fn set_imported_dates(
+        &self,
+        article_id: i64,
+        created_at: chrono::DateTime<Utc>,
+        published_at: Option<chrono::DateTime<Utc>>,
+    ) -> Result<()> {
+        dispatch!(
+            self,
+            set_article_dates,
+            article_id,
+            &created_at,
+            published_at.as_ref()
+        )
+    }
+
     async fn list_scheduled_due(&self) -> Result<Vec<Article>> {
         let now = Utc::now();
         dispatch!(self, list_scheduled_due_articles, &now)
     }
 
+    async fn list_unmeasured(&self, limit: i64) -> Result<Vec<(i64, String)>> {
+        dispatch!(self, list_unmeasured_articles, limit)
+    }
+
+    async fn set_stats(&self, article_id: i64, stats: &ContentStats) -> Result<()> {
+        dispatch!(self, set_article_stats, article_id, stats)
+    }
+
     async fn get_adjacent(
         &self,
         article_id: i64,
@@ -421,12 +549,249 @@ impl ArticleRepository for SqlxArticleRepository {
     ) -> Result<Vec<Article>> {
         dispatch!(self, get_related_articles, article_id, category_id, limit)
     }
+
+    async fn list_popular(&self, since: Option<&str>, limit: i64) -> Result<Vec<(Article, i64)>> {
+        dispatch!(self, list_popular_articles, since, limit)
+    }
 }
 
 // ============================================================================
 // Shared implementations (identical SQL across SQLite and MySQL)
 // ============================================================================
 
+/// Bind value for dynamically built list queries
+pub(super) enum QueryBind {
+    Int(i64),
+    Text(&'static str),
+    OwnedText(String),
+    Time(chrono::DateTime<Utc>),
+}
+
+/// Build the SQL and bind values for a keyset page of `scope`
+///
+/// Published scopes leave out articles in categories above `clearance` and
+/// articles whose audience does not admit `reader`, as `filter_conditions`
+/// does for page listings.
+pub(super) fn cursor_list_query(
+    scope: &ArticleListScope,
+    cursor: Option<&ArticleCursor>,
+    limit: i64,
+    clearance: CategoryAccess,
+    reader: Option<&ArticleReader>,
+) -> (String, Vec<QueryBind>) {
+    let published = format!(
+        "a.status = 'published' AND {} AND {}",
+        readable_category_sql("a.category_id", clearance),
+        readable_audience_sql("a.id", reader)
+    );
+    let mut joins = "";
+    let mut conditions = Vec::new();
+    let mut binds = Vec::new();
+
+    match scope {
+        ArticleListScope::All => {}
+        ArticleListScope::Status(ArticleStatus::Published) => {
+            conditions.push(published);
+        }
+        ArticleListScope::Status(status) => {
+            conditions.push("a.status = ?".to_string());
+            binds.push(QueryBind::Text(status.as_str()));
+        }
+        ArticleListScope::Category(category_id) => {
+            conditions.push("a.category_id = ?".to_string());
+            binds.push(QueryBind::Int(*category_id));
+        }
+        ArticleListScope::Tag(tag_id) => {
+            joins = " INNER JOIN article_tags at ON a.id = at.article_id";
+            conditions.push("at.tag_id = ?".to_string());
+            binds.push(QueryBind::Int(*tag_id));
+        }
+        ArticleListScope::PublishedInCategories(category_ids) => {
+            let placeholders = vec!["?"; category_ids.len()].join(", ");
+            conditions.push(format!(
+                "{} AND a.category_id IN ({})",
+                published, placeholders
+            ));
+            binds.extend(category_ids.iter().map(|id| QueryBind::Int(*id)));
+        }
+        ArticleListScope::PublishedWithTag(tag_id) => {
+            joins = " INNER JOIN article_tags at ON a.id = at.article_id";
+            conditions.push(format!("at.tag_id = ? AND {}", published));
+            binds.push(QueryBind::Int(*tag_id));
+        }
+    }
+
+    if let Some(cursor) = cursor {
+        conditions.push("(a.created_at < ? OR (a.created_at = ? AND a.id < ?))".to_string());
+        binds.push(QueryBind::Time(cursor.created_at));
+        binds.push(QueryBind::Time(cursor.created_at));
+        binds.push(QueryBind::Int(cursor.id));
+    }
+
+    let where_sql = if conditions.is_empty() {
+        String::new()
+    } else {
+        format!(" WHERE {}", conditions.join(" AND "))
+    };
+    binds.push(QueryBind::Int(limit));
+
+    let sql = format!(
+        "SELECT a.id, a.slug, a.title, a.content, a.content_html, a.author_id, a.category_id, a.status, a.published_at, a.created_at, a.updated_at, a.view_count, a.like_count, a.favorite_count, a.comment_count, a.meta_title, a.meta_description, a.canonical_url, a.noindex, a.excerpt, a.license, a.word_count, a.char_count, a.reading_time, a.thumbnail, a.is_pinned, a.pin_order, a.meta, a.scheduled_at, a.input_format \
+         FROM articles a{}{} ORDER BY a.created_at DESC, a.id DESC LIMIT ?",
+        joins, where_sql
+    );
+    (sql, binds)
+}
+
+/// Date used for range filters: publication date, else creation date
+const ARTICLE_DATE_SQL: &str = "COALESCE(a.published_at, a.created_at)";
+
+/// Build the WHERE clause and bind values for `filter`
+fn filter_conditions(filter: &ArticleFilter) -> (String, Vec<QueryBind>) {
+    let mut conditions = Vec::new();
+    let mut binds = Vec::new();
+
+    if let Some(status) = filter.status {
+        conditions.push("a.status = ?".to_string());
+        binds.push(QueryBind::Text(status.as_str()));
+        if status == ArticleStatus::Published {
+            conditions.push(readable_category_sql(
+                "a.category_id",
+                filter.clearance.unwrap_or_default(),
+            ));
+            conditions.push(readable_audience_sql("a.id", filter.reader.as_ref()));
+        }
+    }
+    // The primary author counts as credited with the `author` role even
+    // when the article has no rows in `article_authors`
+    match (filter.author_id, filter.author_role) {
+        (Some(author_id), None | Some(AuthorRole::Author)) => {
+            let role_sql = if filter.author_role.is_some() {
+                " AND aa.role = 'author'"
+            } else {
+                ""
+            };
+            conditions.push(format!(
+                "(a.author_id = ? OR EXISTS (SELECT 1 FROM article_authors aa WHERE aa.article_id = a.id AND aa.user_id = ?{}))",
+                role_sql
+            ));
+            binds.push(QueryBind::Int(author_id));
+            binds.push(QueryBind::Int(author_id));
+        }
+        (Some(author_id), Some(role)) => {
+            conditions.push(
+                "EXISTS (SELECT 1 FROM article_authors aa WHERE aa.article_id = a.id AND aa.user_id = ? AND aa.role = ?)"
+                    .to_string(),
+            );
+            binds.push(QueryBind::Int(author_id));
+            binds.push(QueryBind::Text(role.as_str()));
+        }
+        (None, Some(role)) if role != AuthorRole::Author => {
+            conditions.push(
+                "EXISTS (SELECT 1 FROM article_authors aa WHERE aa.article_id = a.id AND aa.role = ?)"
+                    .to_string(),
+            );
+            binds.push(QueryBind::Text(role.as_str()));
+        }
+        _ => {}
+    }
+    if !filter.category_ids.is_empty() {
+        let placeholders = vec!["?"; filter.category_ids.len()].join(", ");
+        conditions.push(format!("a.category_id IN ({})", placeholders));
+        binds.extend(filter.category_ids.iter().map(|id| QueryBind::Int(*id)));
+    }
+    if let Some(tag_id) = filter.tag_id {
+        conditions.push(
+            "EXISTS (SELECT 1 FROM article_tags at WHERE at.article_id = a.id AND at.tag_id = ?)"
+                .to_string(),
+        );
+        binds.push(QueryBind::Int(tag_id));
+    }
+    if let Some(lang) = &filter.lang {
+        // Articles without a language are in the site language
+        conditions.push(
+            "COALESCE((SELECT ct.lang FROM content_translations ct WHERE ct.content_type = 'article' AND ct.content_id = a.id), ?) = ?"
+                .to_string(),
+        );
+        binds.push(QueryBind::OwnedText(lang.site_lang.clone()));
+        binds.push(QueryBind::OwnedText(lang.lang.clone()));
+    }
+    if let Some(from) = filter.date_from {
+        conditions.push(format!("{} >= ?", ARTICLE_DATE_SQL));
+        binds.push(QueryBind::Time(from));
+    }
+    if let Some(to) = filter.date_to {
+        conditions.push(format!("{} < ?", ARTICLE_DATE_SQL));
+        binds.push(QueryBind::Time(to));
+    }
+
+    let where_sql = if conditions.is_empty() {
+        String::new()
+    } else {
+        format!(" WHERE {}", conditions.join(" AND "))
+    };
+    (where_sql, binds)
+}
+
+/// Build the SQL and bind values for a filtered, sorted page of articles.
+///
+/// Pinned articles come first when listing published articles in the
+/// default order, as on the other published listings. With
+/// `skip_content` set the article bodies are not read.
+pub(super) fn filtered_list_query(params: &ListParams) -> (String, Vec<QueryBind>) {
+    let (where_sql, mut binds) = filter_conditions(&params.filter);
+    let direction = params.direction.as_sql();
+    let pinned_first = params.filter.status == Some(ArticleStatus::Published)
+        && params.sort_by == ArticleSortBy::Date
+        && params.direction == SortDirection::Desc;
+    let sort_column = match params.sort_by {
+        ArticleSortBy::Date => ARTICLE_DATE_SQL.to_string(),
+        other => format!("a.{}", other.column()),
+    };
+
+    binds.push(QueryBind::Int(params.limit()));
+    binds.push(QueryBind::Int(params.offset()));
+    // Bodies are replaced so rows still map to `Article`; the start of the
+    // HTML is kept for generated excerpts
+    let content_columns = if params.skip_content {
+        "'' AS content, SUBSTR(a.content_html, 1, 4000) AS content_html"
+    } else {
+        "a.content, a.content_html"
+    };
+    let sql = format!(
+        "SELECT a.id, a.slug, a.title, {}, a.author_id, a.category_id, a.status, a.published_at, a.created_at, a.updated_at, a.view_count, a.like_count, a.favorite_count, a.comment_count, a.meta_title, a.meta_description, a.canonical_url, a.noindex, a.excerpt, a.license, a.word_count, a.char_count, a.reading_time, a.thumbnail, a.is_pinned, a.pin_order, a.meta, a.scheduled_at, a.input_format \
+         FROM articles a{} ORDER BY {}{} {}, a.id {} LIMIT ? OFFSET ?",
+        content_columns,
+        where_sql,
+        if pinned_first { "a.is_pinned DESC, a.pin_order ASC, " } else { "" },
+        sort_column,
+        direction,
+        direction
+    );
+    (sql, binds)
+}
+
+impl_dual_fn! {
+    pub(super) async fn count_articles_filtered(pool, filter: &ArticleFilter) -> Result<i64> {
+        let (where_sql, binds) = filter_conditions(filter);
+        let sql = format!("SELECT COUNT(*) as count FROM articles a{}", where_sql);
+        let mut query = sqlx::query(&sql);
+        for bind in binds {
+            query = match bind {
+                QueryBind::Int(value) => query.bind(value),
+                QueryBind::Text(value) => query.bind(value),
+                QueryBind::OwnedText(value) => query.bind(value),
+                QueryBind::Time(value) => query.bind(value),
+            };
+        }
+        let row = query
+            .fetch_one(pool)
+            .await
+            .context("Failed to count filtered articles")?;
+        Ok(row.get("count"))
+    }
+}
+
 impl_row_mapper! {
     pub(super) fn row_to_article(row) -> Result<Article> {
         let status_str: String = row.get("status");
@@ -447,6 +812,7 @@ impl_row_mapper! {
             updated_at: row.get("updated_at"),
             view_count: row.try_get("view_count").unwrap_or(0),
             like_count: row.try_get("like_count").unwrap_or(0),
+            favorite_count: row.try_get("favorite_count").unwrap_or(0),
             comment_count: row.try_get("comment_count").unwrap_or(0),
             thumbnail: row.try_get("thumbnail").ok().flatten(),
             is_pinned: row.try_get("is_pinned").unwrap_or(false),
@@ -456,12 +822,52 @@ impl_row_mapper! {
                 .and_then(|s| serde_json::from_str(&s).ok())
                 .unwrap_or(serde_json::json!({})),
             scheduled_at: row.try_get("scheduled_at").ok().flatten(),
+            input_format: row.try_get::<String, _>("input_format")
+                .ok()
+                .and_then(|s| InputFormat::parse(&s))
+                .unwrap_or_default(),
+            meta_title: row.try_get("meta_title").ok().flatten(),
+            meta_description: row.try_get("meta_description").ok().flatten(),
+            canonical_url: row.try_get("canonical_url").ok().flatten(),
+            noindex: row.try_get("noindex").unwrap_or(false),
+            excerpt: row.try_get("excerpt").ok().flatten(),
+            license: row.try_get::<Option<String>, _>("license")
+                .ok()
+                .flatten()
+                .and_then(|s| serde_json::from_str(&s).ok()),
+            stats: ContentStats {
+                word_count: row.try_get::<Option<i64>, _>("word_count")
+                    .ok()
+                    .flatten()
+                    .unwrap_or(0),
+                char_count: row.try_get::<Option<i64>, _>("char_count")
+                    .ok()
+                    .flatten()
+                    .unwrap_or(0),
+                reading_time: row.try_get::<Option<i64>, _>("reading_time")
+                    .ok()
+                    .flatten()
+                    .unwrap_or(0),
+            },
+            redacted: false,
         })
     }
 }
 
+/// Stored form of an article license
+pub(super) fn license_json(license: Option<&ContentLicense>) -> Option<String> {
+    license.and_then(|license| serde_json::to_string(license).ok())
+}
+
 impl_dual_fn! {
     pub(super) async fn delete_article(pool, id: i64) -> Result<()> {
+        // Leave a tombstone for delta sync clients
+        sqlx::query("INSERT INTO sync_tombstones (entity_type, entity_id, slug, status, deleted_at) SELECT 'article', id, slug, status, ? FROM articles WHERE id = ?")
+            .bind(Utc::now())
+            .bind(id)
+            .execute(pool)
+            .await
+            .context("Failed to record article tombstone")?;
         sqlx::query("DELETE FROM articles WHERE id = ?")
             .bind(id)
             .execute(pool)
@@ -471,6 +877,66 @@ impl_dual_fn! {
     }
 }
 
+impl_dual_fn! {
+    pub(super) async fn record_article_slug(pool, article_id: i64, slug: &str) -> Result<()> {
+        let updated = sqlx::query("UPDATE article_slugs SET article_id = ?, created_at = ? WHERE slug = ? AND article_id <> ?")
+            .bind(article_id)
+            .bind(Utc::now())
+            .bind(slug)
+            .bind(article_id)
+            .execute(pool)
+            .await
+            .context("Failed to update article slug history")?;
+        if updated.rows_affected() > 0 {
+            return Ok(());
+        }
+        let exists = sqlx::query("SELECT slug FROM article_slugs WHERE slug = ?")
+            .bind(slug)
+            .fetch_optional(pool)
+            .await
+            .context("Failed to check article slug history")?;
+        if exists.is_none() {
+            sqlx::query("INSERT INTO article_slugs (slug, article_id, created_at) VALUES (?, ?, ?)")
+                .bind(slug)
+                .bind(article_id)
+                .bind(Utc::now())
+                .execute(pool)
+                .await
+                .context("Failed to record article slug")?;
+        }
+        Ok(())
+    }
+}
+
+impl_dual_fn! {
+    pub(super) async fn get_article_slug_owner(pool, slug: &str) -> Result<Option<i64>> {
+        let row = sqlx::query("SELECT article_id FROM article_slugs WHERE slug = ?")
+            .bind(slug)
+            .fetch_optional(pool)
+            .await
+            .context("Failed to look up article slug history")?;
+        Ok(row.map(|r| r.get("article_id")))
+    }
+}
+
+impl_dual_fn! {
+    pub(super) async fn list_article_slugs(pool, article_id: i64) -> Result<Vec<ArticleSlug>> {
+        let rows = sqlx::query("SELECT slug, article_id, created_at FROM article_slugs WHERE article_id = ? ORDER BY created_at, slug")
+            .bind(article_id)
+            .fetch_all(pool)
+            .await
+            .context("Failed to list article slugs")?;
+        Ok(rows
+            .iter()
+            .map(|r| ArticleSlug {
+                slug: r.get("slug"),
+                article_id: r.get("article_id"),
+                created_at: r.get("created_at"),
+            })
+            .collect())
+    }
+}
+
 impl_dual_fn! {
     pub(super) async fn count_articles(pool) -> Result<i64> {
         let row = sqlx::query("SELECT COUNT(*) as count FROM articles")
@@ -483,7 +949,8 @@ impl_dual_fn! {
 
 impl_dual_fn! {
     pub(super) async fn count_published(pool) -> Result<i64> {
-        let row = sqlx::query("SELECT COUNT(*) as count FROM articles WHERE status = 'published'")
+        let published = public_article_sql("");
+        let row = sqlx::query(&format!("SELECT COUNT(*) as count FROM articles WHERE {published}"))
             .fetch_one(pool)
             .await
             .context("Failed to count published articles")?;
@@ -524,7 +991,8 @@ impl_dual_fn! {
             .collect::<Vec<_>>()
             .join(", ");
         let query = format!(
-            "SELECT COUNT(*) as count FROM articles WHERE status = 'published' AND category_id IN ({})",
+            "SELECT COUNT(*) as count FROM articles WHERE {} AND category_id IN ({})",
+            public_article_sql(""),
             placeholders
         );
         let mut query = sqlx::query(&query);
@@ -553,11 +1021,12 @@ impl_dual_fn! {
 
 impl_dual_fn! {
     pub(super) async fn count_published_by_tag(pool, tag_id: i64) -> Result<i64> {
-        let row = sqlx::query(
+        let row = sqlx::query(&format!(
             "SELECT COUNT(*) as count \
              FROM article_tags at INNER JOIN articles a ON a.id = at.article_id \
-             WHERE at.tag_id = ? AND a.status = 'published'",
-        )
+             WHERE at.tag_id = ? AND {}",
+            public_article_sql("a.")
+        ))
         .bind(tag_id)
         .fetch_one(pool)
         .await
@@ -591,6 +1060,21 @@ impl_dual_fn! {
     }
 }
 
+impl_dual_fn! {
+    pub(super) async fn is_public_article(pool, id: i64) -> Result<bool> {
+        let row = sqlx::query(&format!(
+            "SELECT COUNT(*) as count FROM articles WHERE id = ? AND {}",
+            public_article_sql("")
+        ))
+        .bind(id)
+        .fetch_one(pool)
+        .await
+        .context("Failed to check article visibility")?;
+        let count: i64 = row.get("count");
+        Ok(count > 0)
+    }
+}
+
 impl_dual_fn! {
     pub(super) async fn update_article_meta(pool, article_id: i64, meta_str: &str) -> Result<()> {
         sqlx::query("UPDATE articles SET meta = ?, updated_at = ? WHERE id = ?")
@@ -604,37 +1088,102 @@ impl_dual_fn! {
     }
 }
 
+impl_dual_fn! {
+    pub(super) async fn list_unmeasured_articles(pool, limit: i64) -> Result<Vec<(i64, String)>> {
+        let rows = sqlx::query(
+            "SELECT id, content_html FROM articles WHERE word_count IS NULL ORDER BY id LIMIT ?",
+        )
+        .bind(limit)
+        .fetch_all(pool)
+        .await
+        .context("Failed to list unmeasured articles")?;
+        Ok(rows
+            .iter()
+            .map(|r| (r.get("id"), r.get("content_html")))
+            .collect())
+    }
+}
+
+impl_dual_fn! {
+    pub(super) async fn set_article_stats(pool, article_id: i64, stats: &ContentStats) -> Result<()> {
+        sqlx::query("UPDATE articles SET word_count = ?, char_count = ?, reading_time = ? WHERE id = ?")
+            .bind(stats.word_count)
+            .bind(stats.char_count)
+            .bind(stats.reading_time)
+            .bind(article_id)
+            .execute(pool)
+            .await
+            .context("Failed to update article stats")?;
+        Ok(())
+    }
+}
+
+impl_dual_fn! {
+    pub(super) async fn set_article_dates(
+        pool,
+        article_id: i64,
+        created_at: &chrono::DateTime<Utc>,
+        published_at: Option<&chrono::DateTime<Utc>>
+    ) -> Result<()> {
+        sqlx::query(
+            "UPDATE articles SET created_at = ?, published_at = COALESCE(?, published_at) WHERE id = ?",
+        )
+        .bind(created_at)
+        .bind(published_at)
+        .bind(article_id)
+        .execute(pool)
+        .await
+        .context("Failed to update article dates")?;
+        Ok(())
+    }
+}
+
 /// SQL for prev/next queries (same for both DBs)
-const PREV_ARTICLE_SQL: &str = r#"
-    SELECT id, slug, title, content, content_html, author_id, category_id, status, published_at, created_at, updated_at, view_count, like_count, comment_count, thumbnail, is_pinned, pin_order, meta
+fn prev_article_sql() -> String {
+    let published = public_article_sql("");
+    format!(
+        r#"
+    SELECT id, slug, title, content, content_html, author_id, category_id, status, published_at, created_at, updated_at, view_count, like_count, favorite_count, comment_count, meta_title, meta_description, canonical_url, noindex, excerpt, license, word_count, char_count, reading_time, thumbnail, is_pinned, pin_order, meta
     FROM articles
-    WHERE status = 'published' AND published_at > ? AND id != ?
+    WHERE {published} AND published_at > ? AND id != ?
     ORDER BY published_at ASC
     LIMIT 1
-"#;
+"#
+    )
+}
 
-const NEXT_ARTICLE_SQL: &str = r#"
-    SELECT id, slug, title, content, content_html, author_id, category_id, status, published_at, created_at, updated_at, view_count, like_count, comment_count, thumbnail, is_pinned, pin_order, meta
+fn next_article_sql() -> String {
+    let published = public_article_sql("");
+    format!(
+        r#"
+    SELECT id, slug, title, content, content_html, author_id, category_id, status, published_at, created_at, updated_at, view_count, like_count, favorite_count, comment_count, meta_title, meta_description, canonical_url, noindex, excerpt, license, word_count, char_count, reading_time, thumbnail, is_pinned, pin_order, meta
     FROM articles
-    WHERE status = 'published' AND published_at < ? AND id != ?
+    WHERE {published} AND published_at < ? AND id != ?
     ORDER BY published_at DESC
     LIMIT 1
-"#;
+"#
+    )
+}
 
-const RELATED_ARTICLES_SQL: &str = r#"
-    SELECT id, slug, title, content, content_html, author_id, category_id, status, published_at, created_at, updated_at, view_count, like_count, comment_count, thumbnail, is_pinned, pin_order, meta
+fn related_articles_sql() -> String {
+    let published = public_article_sql("");
+    format!(
+        r#"
+    SELECT id, slug, title, content, content_html, author_id, category_id, status, published_at, created_at, updated_at, view_count, like_count, favorite_count, comment_count, meta_title, meta_description, canonical_url, noindex, excerpt, license, word_count, char_count, reading_time, thumbnail, is_pinned, pin_order, meta
     FROM articles
-    WHERE status = 'published' AND category_id = ? AND id != ?
+    WHERE {published} AND category_id = ? AND id != ?
     ORDER BY published_at DESC
     LIMIT ?
-"#;
+"#
+    )
+}
 
 pub(super) async fn get_prev_article_sqlite(
     pool: &SqlitePool,
     article_id: i64,
     published_at: &chrono::DateTime<Utc>,
 ) -> Result<Option<Article>> {
-    let row = sqlx::query(PREV_ARTICLE_SQL)
+    let row = sqlx::query(&prev_article_sql())
         .bind(published_at)
         .bind(article_id)
         .fetch_optional(pool)
@@ -651,7 +1200,7 @@ pub(super) async fn get_prev_article_mysql(
     article_id: i64,
     published_at: &chrono::DateTime<Utc>,
 ) -> Result<Option<Article>> {
-    let row = sqlx::query(PREV_ARTICLE_SQL)
+    let row = sqlx::query(&prev_article_sql())
         .bind(published_at)
         .bind(article_id)
         .fetch_optional(pool)
@@ -668,7 +1217,7 @@ pub(super) async fn get_next_article_sqlite(
     article_id: i64,
     published_at: &chrono::DateTime<Utc>,
 ) -> Result<Option<Article>> {
-    let row = sqlx::query(NEXT_ARTICLE_SQL)
+    let row = sqlx::query(&next_article_sql())
         .bind(published_at)
         .bind(article_id)
         .fetch_optional(pool)
@@ -685,7 +1234,7 @@ pub(super) async fn get_next_article_mysql(
     article_id: i64,
     published_at: &chrono::DateTime<Utc>,
 ) -> Result<Option<Article>> {
-    let row = sqlx::query(NEXT_ARTICLE_SQL)
+    let row = sqlx::query(&next_article_sql())
         .bind(published_at)
         .bind(article_id)
         .fetch_optional(pool)
@@ -699,15 +1248,16 @@ pub(super) async fn get_next_article_mysql(
 
 /// SQLite: monthly archive counts via strftime
 pub(super) async fn get_archives_monthly_sqlite(pool: &SqlitePool) -> Result<Vec<(String, i64)>> {
-    let rows = sqlx::query(
+    let published = public_article_sql("");
+    let rows = sqlx::query(&format!(
         r#"
         SELECT strftime('%Y-%m', published_at) as month, COUNT(*) as count
         FROM articles
-        WHERE status = 'published' AND published_at IS NOT NULL
+        WHERE {published} AND published_at IS NOT NULL
         GROUP BY month
         ORDER BY month DESC
-        "#,
-    )
+        "#
+    ))
     .fetch_all(pool)
     .await
     .context("Failed to get monthly archives")?;
@@ -723,15 +1273,16 @@ pub(super) async fn get_archives_monthly_sqlite(pool: &SqlitePool) -> Result<Vec
 
 /// MySQL: monthly archive counts via DATE_FORMAT
 pub(super) async fn get_archives_monthly_mysql(pool: &MySqlPool) -> Result<Vec<(String, i64)>> {
-    let rows = sqlx::query(
+    let published = public_article_sql("");
+    let rows = sqlx::query(&format!(
         r#"
         SELECT DATE_FORMAT(published_at, '%Y-%m') as month, COUNT(*) as count
         FROM articles
-        WHERE status = 'published' AND published_at IS NOT NULL
+        WHERE {published} AND published_at IS NOT NULL
         GROUP BY month
         ORDER BY month DESC
-        "#,
-    )
+        "#
+    ))
     .fetch_all(pool)
     .await
     .context("Failed to get monthly archives")?;
@@ -751,7 +1302,7 @@ pub(super) async fn get_related_articles_sqlite(
     category_id: i64,
     limit: i64,
 ) -> Result<Vec<Article>> {
-    let rows = sqlx::query(RELATED_ARTICLES_SQL)
+    let rows = sqlx::query(&related_articles_sql())
         .bind(category_id)
         .bind(article_id)
         .bind(limit)
@@ -767,7 +1318,7 @@ pub(super) async fn get_related_articles_mysql(
     category_id: i64,
     limit: i64,
 ) -> Result<Vec<Article>> {
-    let rows = sqlx::query(RELATED_ARTICLES_SQL)
+    let rows = sqlx::query(&related_articles_sql())
         .bind(category_id)
         .bind(article_id)
         .bind(limit)
@@ -776,3 +1327,77 @@ pub(super) async fn get_related_articles_mysql(
         .context("Failed to get related articles")?;
     rows.iter().map(row_to_article_mysql).collect()
 }
+
+fn popular_all_time_sql() -> String {
+    let published = public_article_sql("");
+    format!(
+        r#"
+    SELECT id, slug, title, content, content_html, author_id, category_id, status, published_at, created_at, updated_at, view_count, like_count, favorite_count, comment_count, meta_title, meta_description, canonical_url, noindex, excerpt, license, word_count, char_count, reading_time, thumbnail, is_pinned, pin_order, meta, view_count AS window_views
+    FROM articles
+    WHERE {published} AND view_count > 0
+    ORDER BY view_count DESC, published_at DESC
+    LIMIT ?
+"#
+    )
+}
+
+fn popular_since_sql() -> String {
+    let published = public_article_sql("a.");
+    format!(
+        r#"
+    SELECT a.id, a.slug, a.title, a.content, a.content_html, a.author_id, a.category_id, a.status, a.published_at, a.created_at, a.updated_at, a.view_count, a.like_count, a.favorite_count, a.comment_count, a.meta_title, a.meta_description, a.canonical_url, a.noindex, a.excerpt, a.license, a.word_count, a.char_count, a.reading_time, a.thumbnail, a.is_pinned, a.pin_order, a.meta, v.views AS window_views
+    FROM (SELECT article_id, CAST(SUM(views) AS SIGNED) AS views FROM article_views_daily
+          WHERE day >= ? GROUP BY article_id) v
+    JOIN articles a ON a.id = v.article_id
+    WHERE {published}
+    ORDER BY v.views DESC, a.published_at DESC
+    LIMIT ?
+"#
+    )
+}
+
+pub(super) async fn list_popular_articles_sqlite(
+    pool: &SqlitePool,
+    since: Option<&str>,
+    limit: i64,
+) -> Result<Vec<(Article, i64)>> {
+    let sql = match since {
+        Some(_) => popular_since_sql(),
+        None => popular_all_time_sql(),
+    };
+    let query = match since {
+        Some(since) => sqlx::query(&sql).bind(since),
+        None => sqlx::query(&sql),
+    };
+    let rows = query
+        .bind(limit)
+        .fetch_all(pool)
+        .await
+        .context("Failed to get popular articles")?;
+    rows.iter()
+        .map(|row| Ok((row_to_article_sqlite(row)?, row.get("window_views"))))
+        .collect()
+}
+
+pub(super) async fn list_popular_articles_mysql(
+    pool: &MySqlPool,
+    since: Option<&str>,
+    limit: i64,
+) -> Result<Vec<(Article, i64)>> {
+    let sql = match since {
+        Some(_) => popular_since_sql(),
+        None => popular_all_time_sql(),
+    };
+    let query = match since {
+        Some(since) => sqlx::query(&sql).bind(since),
+        None => sqlx::query(&sql),
+    };
+    let rows = query
+        .bind(limit)
+        .fetch_all(pool)
+        .await
+        .context("Failed to get popular articles")?;
+    rows.iter()
+        .map(|row| Ok((row_to_article_mysql(row)?, row.get("window_views"))))
+        .collect()
+}
@@ -26,8 +26,8 @@ pub(super) async fn create_article_sqlite(
 
     let result = sqlx::query(
         r#"
-        INSERT INTO articles (slug, title, content, content_html, author_id, category_id, status, published_at, created_at, updated_at, thumbnail, is_pinned, pin_order, scheduled_at)
-        VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
+        INSERT INTO articles (slug, title, content, content_html, author_id, category_id, status, published_at, created_at, updated_at, thumbnail, is_pinned, pin_order, scheduled_at, input_format, excerpt, license, word_count, char_count, reading_time)
+        VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
         "#,
     )
     .bind(&input.slug)
@@ -44,6 +44,12 @@ pub(super) async fn create_article_sqlite(
     .bind(false)
     .bind(0)
     .bind(scheduled_at)
+    .bind(input.input_format.as_str())
+    .bind(&input.excerpt)
+    .bind(license_json(input.license.as_ref()))
+    .bind(input.stats.map(|stats| stats.word_count))
+    .bind(input.stats.map(|stats| stats.char_count))
+    .bind(input.stats.map(|stats| stats.reading_time))
     .execute(pool)
     .await
     .context("Failed to create article")?;
@@ -64,12 +70,22 @@ pub(super) async fn create_article_sqlite(
         updated_at: now,
         view_count: 0,
         like_count: 0,
+        favorite_count: 0,
         comment_count: 0,
         thumbnail: None,
         is_pinned: false,
         pin_order: 0,
         meta: serde_json::json!({}),
         scheduled_at,
+        input_format: input.input_format,
+        meta_title: None,
+        meta_description: None,
+        canonical_url: None,
+        noindex: false,
+        excerpt: input.excerpt.clone(),
+        license: input.license.clone(),
+        stats: input.stats.unwrap_or_default(),
+        redacted: false,
     })
 }
 
@@ -79,7 +95,7 @@ pub(super) async fn get_article_by_id_sqlite(
 ) -> Result<Option<Article>> {
     let row = sqlx::query(
         r#"
-        SELECT id, slug, title, content, content_html, author_id, category_id, status, published_at, created_at, updated_at, view_count, like_count, comment_count, thumbnail, is_pinned, pin_order, meta, scheduled_at
+        SELECT id, slug, title, content, content_html, author_id, category_id, status, published_at, created_at, updated_at, view_count, like_count, favorite_count, comment_count, meta_title, meta_description, canonical_url, noindex, excerpt, license, word_count, char_count, reading_time, thumbnail, is_pinned, pin_order, meta, scheduled_at, input_format
         FROM articles
         WHERE id = ?
         "#,
@@ -101,7 +117,7 @@ pub(super) async fn get_article_by_slug_sqlite(
 ) -> Result<Option<Article>> {
     let row = sqlx::query(
         r#"
-        SELECT id, slug, title, content, content_html, author_id, category_id, status, published_at, created_at, updated_at, view_count, like_count, comment_count, thumbnail, is_pinned, pin_order, meta, scheduled_at
+        SELECT id, slug, title, content, content_html, author_id, category_id, status, published_at, created_at, updated_at, view_count, like_count, favorite_count, comment_count, meta_title, meta_description, canonical_url, noindex, excerpt, license, word_count, char_count, reading_time, thumbnail, is_pinned, pin_order, meta, scheduled_at, input_format
         FROM articles
         WHERE slug = ?
         "#,
@@ -124,7 +140,7 @@ pub(super) async fn list_articles_sqlite(
     sort_by: ArticleSortBy,
 ) -> Result<Vec<Article>> {
     let query = format!(
-        "SELECT id, slug, title, content, content_html, author_id, category_id, status, published_at, created_at, updated_at, view_count, like_count, comment_count, thumbnail, is_pinned, pin_order, meta, scheduled_at \
+        "SELECT id, slug, title, content, content_html, author_id, category_id, status, published_at, created_at, updated_at, view_count, like_count, favorite_count, comment_count, meta_title, meta_description, canonical_url, noindex, excerpt, license, word_count, char_count, reading_time, thumbnail, is_pinned, pin_order, meta, scheduled_at, input_format \
          FROM articles ORDER BY {} LIMIT ? OFFSET ?",
         sort_by.order_by_sql()
     );
@@ -164,6 +180,28 @@ pub(super) async fn update_article_sqlite(
     let new_is_pinned = input.is_pinned.unwrap_or(existing.is_pinned);
     let new_pin_order = input.pin_order.unwrap_or(existing.pin_order);
     let mut new_scheduled_at = input.scheduled_at.clone().unwrap_or(existing.scheduled_at);
+    let new_input_format = input.input_format.unwrap_or(existing.input_format);
+    let new_meta_title = input
+        .meta_title
+        .clone()
+        .unwrap_or_else(|| existing.meta_title.clone());
+    let new_meta_description = input
+        .meta_description
+        .clone()
+        .unwrap_or_else(|| existing.meta_description.clone());
+    let new_canonical_url = input
+        .canonical_url
+        .clone()
+        .unwrap_or_else(|| existing.canonical_url.clone());
+    let new_noindex = input.noindex.unwrap_or(existing.noindex);
+    let new_excerpt = input
+        .excerpt
+        .clone()
+        .unwrap_or_else(|| existing.excerpt.clone());
+    let new_license = input
+        .license
+        .clone()
+        .unwrap_or_else(|| existing.license.clone());
 
     let new_published_at =
         if new_status == ArticleStatus::Published && existing.status != ArticleStatus::Published {
@@ -180,7 +218,7 @@ pub(super) async fn update_article_sqlite(
     sqlx::query(
         r#"
         UPDATE articles
-        SET slug = ?, title = ?, content = ?, content_html = ?, category_id = ?, status = ?, published_at = ?, updated_at = ?, thumbnail = ?, is_pinned = ?, pin_order = ?, scheduled_at = ?
+        SET slug = ?, title = ?, content = ?, content_html = ?, category_id = ?, status = ?, published_at = ?, updated_at = ?, thumbnail = ?, is_pinned = ?, pin_order = ?, scheduled_at = ?, input_format = ?, meta_title = ?, meta_description = ?, canonical_url = ?, noindex = ?, excerpt = ?, license = ?, word_count = COALESCE(?, word_count), char_count = COALESCE(?, char_count), reading_time = COALESCE(?, reading_time)
         WHERE id = ?
         "#,
     )
@@ -196,6 +234,16 @@ pub(super) async fn update_article_sqlite(
     .bind(new_is_pinned)
     .bind(new_pin_order)
     .bind(new_scheduled_at)
+    .bind(new_input_format.as_str())
+    .bind(&new_meta_title)
+    .bind(&new_meta_description)
+    .bind(&new_canonical_url)
+    .bind(new_noindex)
+    .bind(&new_excerpt)
+    .bind(license_json(new_license.as_ref()))
+    .bind(input.stats.map(|stats| stats.word_count))
+    .bind(input.stats.map(|stats| stats.char_count))
+    .bind(input.stats.map(|stats| stats.reading_time))
     .bind(id)
     .execute(pool)
     .await
@@ -214,7 +262,7 @@ pub(super) async fn list_articles_by_category_sqlite(
     sort_by: ArticleSortBy,
 ) -> Result<Vec<Article>> {
     let query = format!(
-        "SELECT id, slug, title, content, content_html, author_id, category_id, status, published_at, created_at, updated_at, view_count, like_count, comment_count, thumbnail, is_pinned, pin_order, meta, scheduled_at \
+        "SELECT id, slug, title, content, content_html, author_id, category_id, status, published_at, created_at, updated_at, view_count, like_count, favorite_count, comment_count, meta_title, meta_description, canonical_url, noindex, excerpt, license, word_count, char_count, reading_time, thumbnail, is_pinned, pin_order, meta, scheduled_at, input_format \
          FROM articles WHERE category_id = ? ORDER BY is_pinned DESC, pin_order ASC, {} LIMIT ? OFFSET ?",
         sort_by.order_by_sql()
     );
@@ -237,7 +285,7 @@ pub(super) async fn list_articles_by_tag_sqlite(
     sort_by: ArticleSortBy,
 ) -> Result<Vec<Article>> {
     let query = format!(
-        "SELECT a.id, a.slug, a.title, a.content, a.content_html, a.author_id, a.category_id, a.status, a.published_at, a.created_at, a.updated_at, a.view_count, a.like_count, a.comment_count, a.thumbnail, a.is_pinned, a.pin_order, a.meta, a.scheduled_at \
+        "SELECT a.id, a.slug, a.title, a.content, a.content_html, a.author_id, a.category_id, a.status, a.published_at, a.created_at, a.updated_at, a.view_count, a.like_count, a.favorite_count, a.comment_count, a.meta_title, a.meta_description, a.canonical_url, a.noindex, a.excerpt, a.license, a.word_count, a.char_count, a.reading_time, a.thumbnail, a.is_pinned, a.pin_order, a.meta, a.scheduled_at, a.input_format \
          FROM articles a INNER JOIN article_tags at ON a.id = at.article_id \
          WHERE at.tag_id = ? ORDER BY a.is_pinned DESC, a.pin_order ASC, {} LIMIT ? OFFSET ?",
         sort_by.order_by_sql()
@@ -259,9 +307,10 @@ pub(super) async fn list_published_articles_sqlite(
     limit: i64,
     sort_by: ArticleSortBy,
 ) -> Result<Vec<Article>> {
+    let published = public_article_sql("");
     let query = format!(
-        "SELECT id, slug, title, content, content_html, author_id, category_id, status, published_at, created_at, updated_at, view_count, like_count, comment_count, thumbnail, is_pinned, pin_order, meta, scheduled_at \
-         FROM articles WHERE status = 'published' ORDER BY is_pinned DESC, pin_order ASC, {} LIMIT ? OFFSET ?",
+        "SELECT id, slug, title, content, content_html, author_id, category_id, status, published_at, created_at, updated_at, view_count, like_count, favorite_count, comment_count, meta_title, meta_description, canonical_url, noindex, excerpt, license, word_count, char_count, reading_time, thumbnail, is_pinned, pin_order, meta, scheduled_at, input_format \
+         FROM articles WHERE {published} ORDER BY is_pinned DESC, pin_order ASC, {} LIMIT ? OFFSET ?",
         sort_by.order_by_sql()
     );
     let rows = sqlx::query(&query)
@@ -281,6 +330,7 @@ pub(super) async fn list_published_articles_by_category_ids_sqlite(
     limit: i64,
     sort_by: ArticleSortBy,
 ) -> Result<Vec<Article>> {
+    let published = public_article_sql("");
     if category_ids.is_empty() {
         return Ok(Vec::new());
     }
@@ -290,8 +340,8 @@ pub(super) async fn list_published_articles_by_category_ids_sqlite(
         .collect::<Vec<_>>()
         .join(", ");
     let query = format!(
-        "SELECT id, slug, title, content, content_html, author_id, category_id, status, published_at, created_at, updated_at, view_count, like_count, comment_count, thumbnail, is_pinned, pin_order, meta, scheduled_at \
-         FROM articles WHERE status = 'published' AND category_id IN ({}) ORDER BY is_pinned DESC, pin_order ASC, {} LIMIT ? OFFSET ?",
+        "SELECT id, slug, title, content, content_html, author_id, category_id, status, published_at, created_at, updated_at, view_count, like_count, favorite_count, comment_count, meta_title, meta_description, canonical_url, noindex, excerpt, license, word_count, char_count, reading_time, thumbnail, is_pinned, pin_order, meta, scheduled_at, input_format \
+         FROM articles WHERE {published} AND category_id IN ({}) ORDER BY is_pinned DESC, pin_order ASC, {} LIMIT ? OFFSET ?",
         placeholders,
         sort_by.order_by_sql()
     );
@@ -316,10 +366,11 @@ pub(super) async fn list_published_articles_by_tag_sqlite(
     limit: i64,
     sort_by: ArticleSortBy,
 ) -> Result<Vec<Article>> {
+    let published = public_article_sql("a.");
     let query = format!(
-        "SELECT a.id, a.slug, a.title, a.content, a.content_html, a.author_id, a.category_id, a.status, a.published_at, a.created_at, a.updated_at, a.view_count, a.like_count, a.comment_count, a.thumbnail, a.is_pinned, a.pin_order, a.meta, a.scheduled_at \
+        "SELECT a.id, a.slug, a.title, a.content, a.content_html, a.author_id, a.category_id, a.status, a.published_at, a.created_at, a.updated_at, a.view_count, a.like_count, a.favorite_count, a.comment_count, a.meta_title, a.meta_description, a.canonical_url, a.noindex, a.excerpt, a.license, a.word_count, a.char_count, a.reading_time, a.thumbnail, a.is_pinned, a.pin_order, a.meta, a.scheduled_at, a.input_format \
          FROM articles a INNER JOIN article_tags at ON a.id = at.article_id \
-         WHERE at.tag_id = ? AND a.status = 'published' ORDER BY a.is_pinned DESC, a.pin_order ASC, {} LIMIT ? OFFSET ?",
+         WHERE at.tag_id = ? AND {published} ORDER BY a.is_pinned DESC, a.pin_order ASC, {} LIMIT ? OFFSET ?",
         sort_by.order_by_sql()
     );
     let rows = sqlx::query(&query)
@@ -333,6 +384,54 @@ pub(super) async fn list_published_articles_by_tag_sqlite(
     rows.iter().map(row_to_article_sqlite).collect()
 }
 
+pub(super) async fn list_articles_filtered_sqlite(
+    pool: &SqlitePool,
+    params: &ListParams,
+) -> Result<Vec<Article>> {
+    let (sql, binds) = filtered_list_query(params);
+    let mut query = sqlx::query(&sql);
+    for bind in binds {
+        query = match bind {
+            QueryBind::Int(value) => query.bind(value),
+            QueryBind::Text(value) => query.bind(value),
+            QueryBind::OwnedText(value) => query.bind(value),
+            QueryBind::Time(value) => query.bind(value),
+        };
+    }
+    let rows = query
+        .fetch_all(pool)
+        .await
+        .context("Failed to list filtered articles")?;
+
+    rows.iter().map(row_to_article_sqlite).collect()
+}
+
+pub(super) async fn list_articles_after_cursor_sqlite(
+    pool: &SqlitePool,
+    scope: &ArticleListScope,
+    cursor: Option<&ArticleCursor>,
+    limit: i64,
+    clearance: CategoryAccess,
+    reader: Option<&ArticleReader>,
+) -> Result<Vec<Article>> {
+    let (sql, binds) = cursor_list_query(scope, cursor, limit, clearance, reader);
+    let mut query = sqlx::query(&sql);
+    for bind in binds {
+        query = match bind {
+            QueryBind::Int(value) => query.bind(value),
+            QueryBind::Text(value) => query.bind(value),
+            QueryBind::OwnedText(value) => query.bind(value),
+            QueryBind::Time(value) => query.bind(value),
+        };
+    }
+    let rows = query
+        .fetch_all(pool)
+        .await
+        .context("Failed to list articles after cursor")?;
+
+    rows.iter().map(row_to_article_sqlite).collect()
+}
+
 pub(super) async fn list_articles_by_status_sqlite(
     pool: &SqlitePool,
     status: ArticleStatus,
@@ -346,7 +445,7 @@ pub(super) async fn list_articles_by_status_sqlite(
         ""
     };
     let query = format!(
-        "SELECT id, slug, title, content, content_html, author_id, category_id, status, published_at, created_at, updated_at, view_count, like_count, comment_count, thumbnail, is_pinned, pin_order, meta, scheduled_at \
+        "SELECT id, slug, title, content, content_html, author_id, category_id, status, published_at, created_at, updated_at, view_count, like_count, favorite_count, comment_count, meta_title, meta_description, canonical_url, noindex, excerpt, license, word_count, char_count, reading_time, thumbnail, is_pinned, pin_order, meta, scheduled_at, input_format \
          FROM articles WHERE status = ? ORDER BY {}{} LIMIT ? OFFSET ?",
         order_prefix,
         sort_by.order_by_sql()
@@ -373,20 +472,21 @@ pub(super) async fn search_articles_sqlite(
     // FTS5 requires at least 2 characters; fallback to LIKE for very short queries
     let use_fts = keyword.chars().count() >= 2;
     let order = sort_by.order_by_sql();
+    let published = public_article_sql("a.");
 
     let rows = if use_fts {
         // FTS5 search 鈥?much faster than LIKE for large datasets
         let fts_query = format!("\"{}\"", keyword.replace('"', "\"\""));
         let query = if published_only {
             format!(
-                "SELECT a.id, a.slug, a.title, a.content, a.content_html, a.author_id, a.category_id, a.status, a.published_at, a.created_at, a.updated_at, a.view_count, a.like_count, a.comment_count, a.thumbnail, a.is_pinned, a.pin_order, a.meta, a.scheduled_at \
+                "SELECT a.id, a.slug, a.title, a.content, a.content_html, a.author_id, a.category_id, a.status, a.published_at, a.created_at, a.updated_at, a.view_count, a.like_count, a.favorite_count, a.comment_count, a.meta_title, a.meta_description, a.canonical_url, a.noindex, a.excerpt, a.license, a.word_count, a.char_count, a.reading_time, a.thumbnail, a.is_pinned, a.pin_order, a.meta, a.scheduled_at, a.input_format \
                  FROM articles a INNER JOIN articles_fts fts ON a.id = fts.rowid \
-                 WHERE fts.articles_fts MATCH ? AND a.status = 'published' \
+                 WHERE fts.articles_fts MATCH ? AND {published} \
                  ORDER BY a.is_pinned DESC, a.pin_order ASC, {} LIMIT ? OFFSET ?", order
             )
         } else {
             format!(
-                "SELECT a.id, a.slug, a.title, a.content, a.content_html, a.author_id, a.category_id, a.status, a.published_at, a.created_at, a.updated_at, a.view_count, a.like_count, a.comment_count, a.thumbnail, a.is_pinned, a.pin_order, a.meta, a.scheduled_at \
+                "SELECT a.id, a.slug, a.title, a.content, a.content_html, a.author_id, a.category_id, a.status, a.published_at, a.created_at, a.updated_at, a.view_count, a.like_count, a.favorite_count, a.comment_count, a.meta_title, a.meta_description, a.canonical_url, a.noindex, a.excerpt, a.license, a.word_count, a.char_count, a.reading_time, a.thumbnail, a.is_pinned, a.pin_order, a.meta, a.scheduled_at, a.input_format \
                  FROM articles a INNER JOIN articles_fts fts ON a.id = fts.rowid \
                  WHERE fts.articles_fts MATCH ? \
                  ORDER BY {} LIMIT ? OFFSET ?", order
@@ -404,13 +504,13 @@ pub(super) async fn search_articles_sqlite(
         let search_pattern = format!("%{}%", keyword);
         let query = if published_only {
             format!(
-                "SELECT id, slug, title, content, content_html, author_id, category_id, status, published_at, created_at, updated_at, view_count, like_count, comment_count, thumbnail, is_pinned, pin_order, meta, scheduled_at \
-                 FROM articles WHERE status = 'published' AND (title LIKE ? OR content LIKE ?) \
+                "SELECT id, slug, title, content, content_html, author_id, category_id, status, published_at, created_at, updated_at, view_count, like_count, favorite_count, comment_count, meta_title, meta_description, canonical_url, noindex, excerpt, license, word_count, char_count, reading_time, thumbnail, is_pinned, pin_order, meta, scheduled_at, input_format \
+                 FROM articles a WHERE {published} AND (title LIKE ? OR content LIKE ?) \
                  ORDER BY is_pinned DESC, pin_order ASC, {} LIMIT ? OFFSET ?", order
             )
         } else {
             format!(
-                "SELECT id, slug, title, content, content_html, author_id, category_id, status, published_at, created_at, updated_at, view_count, like_count, comment_count, thumbnail, is_pinned, pin_order, meta, scheduled_at \
+                "SELECT id, slug, title, content, content_html, author_id, category_id, status, published_at, created_at, updated_at, view_count, like_count, favorite_count, comment_count, meta_title, meta_description, canonical_url, noindex, excerpt, license, word_count, char_count, reading_time, thumbnail, is_pinned, pin_order, meta, scheduled_at, input_format \
                  FROM articles WHERE title LIKE ? OR content LIKE ? \
                  ORDER BY {} LIMIT ? OFFSET ?", order
             )
@@ -434,19 +534,23 @@ pub(super) async fn count_search_sqlite(
     published_only: bool,
 ) -> Result<i64> {
     let use_fts = keyword.chars().count() >= 2;
+    let published = public_article_sql("a.");
 
     let row = if use_fts {
         let fts_query = format!("\"{}\"", keyword.replace('"', "\"\""));
         let query = if published_only {
-            "SELECT COUNT(*) as count \
-             FROM articles a INNER JOIN articles_fts fts ON a.id = fts.rowid \
-             WHERE fts.articles_fts MATCH ? AND a.status = 'published'"
+            format!(
+                "SELECT COUNT(*) as count \
+                 FROM articles a INNER JOIN articles_fts fts ON a.id = fts.rowid \
+                 WHERE fts.articles_fts MATCH ? AND {published}"
+            )
         } else {
             "SELECT COUNT(*) as count \
              FROM articles a INNER JOIN articles_fts fts ON a.id = fts.rowid \
              WHERE fts.articles_fts MATCH ?"
+                .to_string()
         };
-        sqlx::query(query)
+        sqlx::query(&query)
             .bind(&fts_query)
             .fetch_one(pool)
             .await
@@ -454,11 +558,12 @@ pub(super) async fn count_search_sqlite(
     } else {
         let search_pattern = format!("%{}%", keyword);
         let query = if published_only {
-            "SELECT COUNT(*) as count FROM articles WHERE status = 'published' AND (title LIKE ? OR content LIKE ?)"
+            format!("SELECT COUNT(*) as count FROM articles a WHERE {published} AND (title LIKE ? OR content LIKE ?)")
         } else {
             "SELECT COUNT(*) as count FROM articles WHERE title LIKE ? OR content LIKE ?"
+                .to_string()
         };
-        sqlx::query(query)
+        sqlx::query(&query)
             .bind(&search_pattern)
             .bind(&search_pattern)
             .fetch_one(pool)
@@ -475,7 +580,7 @@ pub(super) async fn list_scheduled_due_articles_sqlite(
 ) -> Result<Vec<Article>> {
     let rows = sqlx::query(
         r#"
-        SELECT id, slug, title, content, content_html, author_id, category_id, status, published_at, created_at, updated_at, view_count, like_count, comment_count, thumbnail, is_pinned, pin_order, meta, scheduled_at
+        SELECT id, slug, title, content, content_html, author_id, category_id, status, published_at, created_at, updated_at, view_count, like_count, favorite_count, comment_count, meta_title, meta_description, canonical_url, noindex, excerpt, license, word_count, char_count, reading_time, thumbnail, is_pinned, pin_order, meta, scheduled_at, input_format
         FROM articles
         WHERE status = 'draft' AND scheduled_at IS NOT NULL AND scheduled_at <= ?
         "#,
@@ -1,7 +1,12 @@
 use super::*;
+use crate::db::repositories::reader_group::{ReaderGroupRepository, SqlxReaderGroupRepository};
+use crate::db::repositories::sync::{SqlxSyncRepository, SyncRepository};
 use crate::db::repositories::tag::{SqlxTagRepository, TagRepository};
 use crate::db::{create_test_pool, migrations};
-use crate::models::{ArticleSortBy, ListParams, PagedResult, Tag};
+use crate::models::{
+    ArticleAudience, ArticleFilter, ArticleReader, ArticleSortBy, AuthorRole, CategoryAccess, LangFilter,
+    ListParams, PagedResult, SortDirection, Tag, UserRole,
+};
 
 async fn setup_test_repo() -> (DynDatabasePool, SqlxArticleRepository) {
     let pool = create_test_pool()
@@ -55,6 +60,11 @@ fn create_test_input(
         category_id,
         status: None,
         scheduled_at: None,
+        input_format: InputFormat::Markdown,
+        excerpt: None,
+        license: None,
+        stats: None,
+        reclaim_slug: false,
     }
 }
 
@@ -75,6 +85,97 @@ async fn test_create_article() {
     assert!(created.published_at.is_none());
 }
 
+#[tokio::test]
+async fn test_unmeasured_articles_get_stats_once() {
+    let (pool, repo) = setup_test_repo().await;
+    let sqlite_pool = pool.as_sqlite().unwrap();
+    let user_id = create_test_user(sqlite_pool).await;
+    let category_id = create_test_category(sqlite_pool, "test-cat").await;
+
+    let old = repo
+        .create(&create_test_input("old", "Old", user_id, category_id))
+        .await
+        .unwrap();
+    let stats = ContentStats {
+        word_count: 3,
+        char_count: 15,
+        reading_time: 1,
+    };
+    let mut input = create_test_input("new", "New", user_id, category_id);
+    input.stats = Some(stats);
+    let new = repo.create(&input).await.unwrap();
+    assert_eq!(new.stats, stats);
+
+    let unmeasured = repo.list_unmeasured(10).await.unwrap();
+    assert_eq!(
+        unmeasured,
+        vec![(old.id, "<p>Content for Old</p>".to_string())]
+    );
+
+    repo.set_stats(old.id, &stats).await.unwrap();
+    assert!(repo.list_unmeasured(10).await.unwrap().is_empty());
+
+    // Updates without new stats keep the stored ones
+    let updated = repo
+        .update(
+            old.id,
+            &UpdateArticleInput::new().with_title("Renamed".to_string()),
+        )
+        .await
+        .unwrap();
+    assert_eq!(updated.stats, stats);
+}
+
+#[tokio::test]
+async fn test_slug_history_survives_rename_and_delete() {
+    let (pool, repo) = setup_test_repo().await;
+    let sqlite_pool = pool.as_sqlite().unwrap();
+    let user_id = create_test_user(sqlite_pool).await;
+    let category_id = create_test_category(sqlite_pool, "test-cat").await;
+
+    let first = repo
+        .create(&create_test_input("first", "First", user_id, category_id))
+        .await
+        .unwrap();
+    repo.update(
+        first.id,
+        &UpdateArticleInput::new().with_slug("renamed".to_string()),
+    )
+    .await
+    .unwrap();
+    let slugs: Vec<_> = repo
+        .list_slugs(first.id)
+        .await
+        .unwrap()
+        .into_iter()
+        .map(|s| s.slug)
+        .collect();
+    assert_eq!(slugs.len(), 2);
+    assert_eq!(repo.get_slug_owner("first").await.unwrap(), Some(first.id));
+
+    repo.delete(first.id).await.unwrap();
+    assert_eq!(
+        repo.get_slug_owner("renamed").await.unwrap(),
+        Some(first.id)
+    );
+
+    // Taking the slug over moves it to the new article
+    let second = repo
+        .create(&create_test_input(
+            "renamed",
+            "Second",
+            user_id,
+            category_id,
+        ))
+        .await
+        .unwrap();
+    assert_eq!(
+        repo.get_slug_owner("renamed").await.unwrap(),
+        Some(second.id)
+    );
+    assert_eq!(repo.list_slugs(first.id).await.unwrap().len(), 1);
+}
+
 #[tokio::test]
 async fn test_create_published_article() {
     let (pool, repo) = setup_test_repo().await;
@@ -342,6 +443,17 @@ async fn test_delete_article() {
         .await
         .expect("Failed to get article");
     assert!(found.is_none());
+
+    // Deletion is remembered for delta sync
+    let sync = SqlxSyncRepository::new(pool.clone());
+    let tombstones = sync
+        .tombstones_since(chrono::DateTime::<chrono::Utc>::UNIX_EPOCH)
+        .await
+        .expect("Failed to list tombstones");
+    assert_eq!(tombstones.len(), 1);
+    assert_eq!(tombstones[0].entity_type, "article");
+    assert_eq!(tombstones[0].entity_id, created.id);
+    assert_eq!(tombstones[0].slug, "to-delete");
 }
 
 #[tokio::test]
@@ -720,6 +832,304 @@ async fn test_count_by_tag() {
     assert_eq!(count, 3);
 }
 
+#[tokio::test]
+async fn test_list_after_cursor_is_stable_across_inserts() {
+    let (pool, repo) = setup_test_repo().await;
+    let sqlite_pool = pool.as_sqlite().unwrap();
+    let user_id = create_test_user(sqlite_pool).await;
+    let category_id = create_test_category(sqlite_pool, "cursor-cat").await;
+
+    let mut ids = Vec::new();
+    for i in 0..5 {
+        let input = create_test_input(&format!("cursor-{}", i), "Cursor", user_id, category_id);
+        ids.push(repo.create(&input).await.unwrap().id);
+    }
+    // Identical timestamps must be ordered by id
+    sqlx::query("UPDATE articles SET created_at = (SELECT MIN(created_at) FROM articles)")
+        .execute(sqlite_pool)
+        .await
+        .unwrap();
+
+    let first = repo
+        .list_after_cursor(&ArticleListScope::All, None, 2, CategoryAccess::Public, None)
+        .await
+        .unwrap();
+    assert_eq!(
+        first.iter().map(|a| a.id).collect::<Vec<_>>(),
+        vec![ids[4], ids[3]]
+    );
+
+    // A new article must not shift the following pages
+    let input = create_test_input("cursor-new", "Cursor", user_id, category_id);
+    repo.create(&input).await.unwrap();
+
+    let cursor = ArticleCursor::decode(&ArticleCursor::from_article(&first[1]).encode()).unwrap();
+    let rest = repo
+        .list_after_cursor(
+            &ArticleListScope::Category(category_id),
+            Some(&cursor),
+            10,
+            CategoryAccess::Public,
+            None,
+        )
+        .await
+        .unwrap();
+    assert_eq!(
+        rest.iter().map(|a| a.id).collect::<Vec<_>>(),
+        vec![ids[2], ids[1], ids[0]]
+    );
+
+    let published = repo
+        .list_after_cursor(
+            &ArticleListScope::Status(ArticleStatus::Published),
+            None,
+            10,
+            CategoryAccess::Public,
+            None,
+        )
+        .await
+        .unwrap();
+    assert!(published.is_empty());
+}
+
+#[tokio::test]
+async fn test_list_filtered_applies_filters_in_sql() {
+    let (pool, repo) = setup_test_repo().await;
+    let sqlite_pool = pool.as_sqlite().unwrap();
+    let author_a = create_test_user(sqlite_pool).await;
+    let author_b = sqlx::query(
+        "INSERT INTO users (username, email, password_hash, role) VALUES ('other', 'other@example.com', 'x', 'author')",
+    )
+    .execute(sqlite_pool)
+    .await
+    .unwrap()
+    .last_insert_rowid();
+    let cat_a = create_test_category(sqlite_pool, "filter-a").await;
+    let cat_b = create_test_category(sqlite_pool, "filter-b").await;
+
+    let specs = [
+        ("beta", author_a, cat_a, "2024-01-10T00:00:00Z"),
+        ("alpha", author_a, cat_b, "2024-02-10T00:00:00Z"),
+        ("gamma", author_b, cat_a, "2024-03-10T00:00:00Z"),
+    ];
+    let mut ids = Vec::new();
+    for (title, author, category, date) in specs {
+        let mut input = create_test_input(title, title, author, category);
+        input.status = Some(ArticleStatus::Published);
+        let article = repo.create(&input).await.unwrap();
+        sqlx::query("UPDATE articles SET published_at = ? WHERE id = ?")
+            .bind(
+                chrono::DateTime::parse_from_rfc3339(date)
+                    .unwrap()
+                    .with_timezone(&Utc),
+            )
+            .bind(article.id)
+            .execute(sqlite_pool)
+            .await
+            .unwrap();
+        ids.push(article.id);
+    }
+    let tag_id = sqlx::query("INSERT INTO tags (slug, name) VALUES ('t', 'T')")
+        .execute(sqlite_pool)
+        .await
+        .unwrap()
+        .last_insert_rowid();
+    for id in [ids[0], ids[2]] {
+        sqlx::query("INSERT INTO article_tags (article_id, tag_id) VALUES (?, ?)")
+            .bind(id)
+            .bind(tag_id)
+            .execute(sqlite_pool)
+            .await
+            .unwrap();
+    }
+
+    let titles = |articles: Vec<Article>| articles.into_iter().map(|a| a.title).collect::<Vec<_>>();
+
+    // Author + category
+    let filter = ArticleFilter {
+        author_id: Some(author_a),
+        category_ids: vec![cat_a],
+        ..Default::default()
+    };
+    let params = ListParams::new(1, 10).with_filter(filter.clone());
+    assert_eq!(
+        titles(repo.list_filtered(&params).await.unwrap()),
+        vec!["beta"]
+    );
+    assert_eq!(repo.count_filtered(&filter).await.unwrap(), 1);
+
+    // Tag + date range, oldest first
+    let filter = ArticleFilter {
+        status: Some(ArticleStatus::Published),
+        tag_id: Some(tag_id),
+        date_from: Some(
+            chrono::DateTime::parse_from_rfc3339("2024-01-01T00:00:00Z")
+                .unwrap()
+                .with_timezone(&Utc),
+        ),
+        date_to: Some(
+            chrono::DateTime::parse_from_rfc3339("2024-04-01T00:00:00Z")
+                .unwrap()
+                .with_timezone(&Utc),
+        ),
+        ..Default::default()
+    };
+    let params = ListParams::new(1, 10)
+        .with_filter(filter.clone())
+        .with_sort(ArticleSortBy::Date, SortDirection::Asc);
+    assert_eq!(
+        titles(repo.list_filtered(&params).await.unwrap()),
+        vec!["beta", "gamma"]
+    );
+    assert_eq!(repo.count_filtered(&filter).await.unwrap(), 2);
+
+    // Sort by title with pagination
+    let params = ListParams::new(2, 2).with_sort(ArticleSortBy::Title, SortDirection::Asc);
+    assert_eq!(
+        titles(repo.list_filtered(&params).await.unwrap()),
+        vec!["gamma"]
+    );
+    assert_eq!(repo.count_filtered(&params.filter).await.unwrap(), 3);
+}
+
+#[tokio::test]
+async fn test_list_filtered_by_author_includes_co_authors() {
+    let (pool, repo) = setup_test_repo().await;
+    let sqlite_pool = pool.as_sqlite().unwrap();
+    let writer = create_test_user(sqlite_pool).await;
+    let translator = sqlx::query(
+        "INSERT INTO users (username, email, password_hash, role) VALUES ('tr', 'tr@example.com', 'x', 'author')",
+    )
+    .execute(sqlite_pool)
+    .await
+    .unwrap()
+    .last_insert_rowid();
+    let category_id = create_test_category(sqlite_pool, "credits").await;
+    repo.create(&create_test_input("solo", "Solo", writer, category_id))
+        .await
+        .unwrap();
+    let shared = repo
+        .create(&create_test_input("shared", "Shared", writer, category_id))
+        .await
+        .unwrap();
+    sqlx::query(
+        "INSERT INTO article_authors (article_id, user_id, role, position) VALUES (?, ?, 'author', 0), (?, ?, 'translator', 1)",
+    )
+    .bind(shared.id)
+    .bind(writer)
+    .bind(shared.id)
+    .bind(translator)
+    .execute(sqlite_pool)
+    .await
+    .unwrap();
+
+    let slugs = |filter: ArticleFilter| {
+        let repo = &repo;
+        async move {
+            let params = ListParams::new(1, 10)
+                .with_filter(filter)
+                .with_sort(ArticleSortBy::Title, SortDirection::Asc);
+            repo.list_filtered(&params)
+                .await
+                .unwrap()
+                .into_iter()
+                .map(|a| a.slug)
+                .collect::<Vec<_>>()
+        }
+    };
+    // The primary author matches articles without credit rows too
+    let by_writer = ArticleFilter {
+        author_id: Some(writer),
+        author_role: Some(AuthorRole::Author),
+        ..Default::default()
+    };
+    assert_eq!(slugs(by_writer).await, ["shared", "solo"]);
+    let by_translator = ArticleFilter {
+        author_id: Some(translator),
+        ..Default::default()
+    };
+    assert_eq!(slugs(by_translator).await, ["shared"]);
+    let as_author = ArticleFilter {
+        author_id: Some(translator),
+        author_role: Some(AuthorRole::Author),
+        ..Default::default()
+    };
+    assert!(slugs(as_author).await.is_empty());
+    let translated = ArticleFilter {
+        author_role: Some(AuthorRole::Translator),
+        ..Default::default()
+    };
+    assert_eq!(slugs(translated).await, ["shared"]);
+}
+
+#[tokio::test]
+async fn test_list_filtered_without_content_skips_bodies() {
+    let (pool, repo) = setup_test_repo().await;
+    let sqlite_pool = pool.as_sqlite().unwrap();
+    let user_id = create_test_user(sqlite_pool).await;
+    let category_id = create_test_category(sqlite_pool, "light").await;
+    let article = repo
+        .create(&create_test_input("light", "Light", user_id, category_id))
+        .await
+        .unwrap();
+    assert!(!article.content.is_empty());
+
+    let params = ListParams::new(1, 10).without_content();
+    let articles = repo.list_filtered(&params).await.unwrap();
+    assert_eq!(articles.len(), 1);
+    assert_eq!(articles[0].id, article.id);
+    assert_eq!(articles[0].title, "Light");
+    assert!(articles[0].content.is_empty());
+    // Only the start of the HTML is read, enough for an excerpt
+    assert_eq!(articles[0].excerpt_text(), "Content for Light");
+}
+
+#[tokio::test]
+async fn test_list_filtered_by_language_counts_untagged_as_site_language() {
+    let (pool, repo) = setup_test_repo().await;
+    let sqlite_pool = pool.as_sqlite().unwrap();
+    let user_id = create_test_user(sqlite_pool).await;
+    let category_id = create_test_category(sqlite_pool, "lang").await;
+    let mut ids = Vec::new();
+    for slug in ["plain", "english", "german"] {
+        let article = repo
+            .create(&create_test_input(slug, slug, user_id, category_id))
+            .await
+            .unwrap();
+        ids.push(article.id);
+    }
+    for (id, lang) in [(ids[1], "en"), (ids[2], "de")] {
+        sqlx::query(
+            "INSERT INTO content_translations (content_type, content_id, lang, translation_group) VALUES ('article', ?, ?, 'g')",
+        )
+        .bind(id)
+        .bind(lang)
+        .execute(sqlite_pool)
+        .await
+        .unwrap();
+    }
+
+    let in_lang = |lang: &str| ArticleFilter {
+        lang: Some(LangFilter {
+            lang: lang.to_string(),
+            site_lang: "en".to_string(),
+        }),
+        ..Default::default()
+    };
+    let slugs = |articles: Vec<Article>| {
+        let mut slugs: Vec<String> = articles.into_iter().map(|a| a.slug).collect();
+        slugs.sort();
+        slugs
+    };
+    let params = ListParams::new(1, 10).with_filter(in_lang("en"));
+    assert_eq!(
+        slugs(repo.list_filtered(&params).await.unwrap()),
+        vec!["english", "plain"]
+    );
+    assert_eq!(repo.count_filtered(&in_lang("de")).await.unwrap(), 1);
+    assert_eq!(repo.count_filtered(&in_lang("fr")).await.unwrap(), 0);
+}
+
 #[tokio::test]
 async fn test_exists_by_slug() {
     let (pool, repo) = setup_test_repo().await;
@@ -848,3 +1258,226 @@ async fn test_paged_result() {
     assert!(!result.has_next());
     assert!(result.has_prev());
 }
+
+#[tokio::test]
+async fn test_list_popular_counts_views_in_window() {
+    let (pool, repo) = setup_test_repo().await;
+    let sqlite_pool = pool.as_sqlite().unwrap();
+    let user_id = create_test_user(sqlite_pool).await;
+    let category_id = create_test_category(sqlite_pool, "test-cat").await;
+
+    let mut ids = Vec::new();
+    for slug in ["old-hit", "new-hit", "draft"] {
+        let mut input = create_test_input(slug, slug, user_id, category_id);
+        if slug != "draft" {
+            input.status = Some(ArticleStatus::Published);
+        }
+        ids.push(repo.create(&input).await.unwrap().id);
+    }
+    for (id, day, views) in [
+        (ids[0], "2024-04-01", 50),
+        (ids[0], "2024-05-02", 1),
+        (ids[1], "2024-05-01", 3),
+        (ids[1], "2024-05-03", 4),
+        (ids[2], "2024-05-03", 99),
+    ] {
+        sqlx::query("INSERT INTO article_views_daily (article_id, day, views) VALUES (?, ?, ?)")
+            .bind(id)
+            .bind(day)
+            .bind(views)
+            .execute(sqlite_pool)
+            .await
+            .unwrap();
+    }
+    sqlx::query("UPDATE articles SET view_count = 51 WHERE id = ?")
+        .bind(ids[0])
+        .execute(sqlite_pool)
+        .await
+        .unwrap();
+
+    let recent = repo.list_popular(Some("2024-05-01"), 10).await.unwrap();
+    let ranked: Vec<(&str, i64)> = recent
+        .iter()
+        .map(|(a, views)| (a.slug.as_str(), *views))
+        .collect();
+    assert_eq!(ranked, vec![("new-hit", 7), ("old-hit", 1)]);
+
+    let all_time = repo.list_popular(None, 1).await.unwrap();
+    assert_eq!(all_time.len(), 1);
+    assert_eq!(all_time[0].0.slug, "old-hit");
+    assert_eq!(all_time[0].1, 51);
+}
+
+#[tokio::test]
+async fn test_private_categories_hidden_from_public_listings() {
+    let (pool, repo) = setup_test_repo().await;
+    let sqlite_pool = pool.as_sqlite().unwrap();
+    let user_id = create_test_user(sqlite_pool).await;
+    let open_id = create_test_category(sqlite_pool, "open").await;
+    let members_id = create_test_category(sqlite_pool, "members-only").await;
+    sqlx::query("UPDATE categories SET access = 'members' WHERE id = ?")
+        .bind(members_id)
+        .execute(sqlite_pool)
+        .await
+        .unwrap();
+    for (slug, category_id) in [("open-post", open_id), ("secret-post", members_id)] {
+        let mut input = create_test_input(slug, slug, user_id, category_id);
+        input.status = Some(ArticleStatus::Published);
+        repo.create(&input).await.unwrap();
+    }
+    let slugs = |articles: Vec<Article>| articles.into_iter().map(|a| a.slug).collect::<Vec<_>>();
+
+    let published = repo
+        .list_published(0, 10, ArticleSortBy::default())
+        .await
+        .unwrap();
+    assert_eq!(slugs(published), vec!["open-post"]);
+    assert_eq!(repo.count_published().await.unwrap(), 1);
+    assert!(repo
+        .list_published_by_category_ids(&[members_id], 0, 10, ArticleSortBy::default())
+        .await
+        .unwrap()
+        .is_empty());
+    assert_eq!(repo.count_search("post", true).await.unwrap(), 1);
+    assert_eq!(repo.count_search("post", false).await.unwrap(), 2);
+    let cursor_page = repo
+        .list_after_cursor(
+            &ArticleListScope::Status(ArticleStatus::Published),
+            None,
+            10,
+            CategoryAccess::Public,
+            None,
+        )
+        .await
+        .unwrap();
+    assert_eq!(slugs(cursor_page), vec!["open-post"]);
+
+    // Readers cleared for the category see it through the filtered query
+    let filter = |clearance| ArticleFilter {
+        status: Some(ArticleStatus::Published),
+        clearance,
+        ..Default::default()
+    };
+    assert_eq!(repo.count_filtered(&filter(None)).await.unwrap(), 1);
+    assert_eq!(
+        repo.count_filtered(&filter(Some(CategoryAccess::Members)))
+            .await
+            .unwrap(),
+        2
+    );
+    let cleared_cursor_page = repo
+        .list_after_cursor(
+            &ArticleListScope::Status(ArticleStatus::Published),
+            None,
+            10,
+            CategoryAccess::Members,
+            None,
+        )
+        .await
+        .unwrap();
+    assert_eq!(slugs(cleared_cursor_page).len(), 2);
+
+    // Unknown access values are admins only
+    sqlx::query("UPDATE categories SET access = 'unknown' WHERE id = ?")
+        .bind(members_id)
+        .execute(sqlite_pool)
+        .await
+        .unwrap();
+    assert_eq!(
+        repo.count_filtered(&filter(Some(CategoryAccess::Editors)))
+            .await
+            .unwrap(),
+        1
+    );
+    assert_eq!(
+        repo.count_filtered(&filter(Some(CategoryAccess::Admins)))
+            .await
+            .unwrap(),
+        2
+    );
+}
+
+#[tokio::test]
+async fn test_private_category_articles_are_not_public() {
+    let (pool, repo) = setup_test_repo().await;
+    let sqlite_pool = pool.as_sqlite().unwrap();
+    let user_id = create_test_user(sqlite_pool).await;
+    let open_id = create_test_category(sqlite_pool, "open").await;
+    let members_id = create_test_category(sqlite_pool, "members-only").await;
+    sqlx::query("UPDATE categories SET access = 'members' WHERE id = ?")
+        .bind(members_id)
+        .execute(sqlite_pool)
+        .await
+        .unwrap();
+    let mut ids = Vec::new();
+    for (slug, category_id) in [("open-post", open_id), ("secret-post", members_id)] {
+        let mut input = create_test_input(slug, slug, user_id, category_id);
+        input.status = Some(ArticleStatus::Published);
+        ids.push(repo.create(&input).await.unwrap().id);
+    }
+    let draft = repo
+        .create(&create_test_input("draft-post", "Draft", user_id, open_id))
+        .await
+        .unwrap();
+
+    assert!(repo.is_public(ids[0]).await.unwrap());
+    assert!(!repo.is_public(ids[1]).await.unwrap());
+    assert!(!repo.is_public(draft.id).await.unwrap());
+
+    // Delta sync flags the private article so clients drop it
+    let changed = SqlxSyncRepository::new(pool.clone())
+        .articles_changed_since(chrono::DateTime::<chrono::Utc>::UNIX_EPOCH)
+        .await
+        .unwrap();
+    let public = |slug: &str| changed.iter().find(|a| a.slug == slug).unwrap().public;
+    assert!(public("open-post"));
+    assert!(!public("secret-post"));
+    // Status is reported separately
+    assert!(public("draft-post"));
+}
+
+#[tokio::test]
+async fn test_articles_with_an_audience_are_not_public() {
+    let (pool, repo) = setup_test_repo().await;
+    let sqlite_pool = pool.as_sqlite().unwrap();
+    let user_id = create_test_user(sqlite_pool).await;
+    let category_id = create_test_category(sqlite_pool, "open").await;
+    let mut input = create_test_input("members-only", "Members only", user_id, category_id);
+    input.status = Some(ArticleStatus::Published);
+    let article = repo.create(&input).await.unwrap();
+    assert!(repo.is_public(article.id).await.unwrap());
+
+    SqlxReaderGroupRepository::new(pool.clone())
+        .set_audience(
+            article.id,
+            &ArticleAudience {
+                roles: vec![UserRole::Author],
+                group_ids: Vec::new(),
+            },
+        )
+        .await
+        .unwrap();
+    assert!(!repo.is_public(article.id).await.unwrap());
+    let changed = SqlxSyncRepository::new(pool.clone())
+        .articles_changed_since(chrono::DateTime::<chrono::Utc>::UNIX_EPOCH)
+        .await
+        .unwrap();
+    assert!(!changed[0].public);
+
+    // Cursor listings show it only to readers in the audience
+    let scope = ArticleListScope::Status(ArticleStatus::Published);
+    let anonymous = repo
+        .list_after_cursor(&scope, None, 10, CategoryAccess::Public, None)
+        .await
+        .unwrap();
+    assert!(anonymous.is_empty());
+    let reader = ArticleReader {
+        user_id,
+        role: UserRole::Author,
+    };
+    let signed_in = repo
+        .list_after_cursor(&scope, None, 10, CategoryAccess::Public, Some(&reader))
+        .await
+        .unwrap();
+    assert_eq!(signed_in.len(), 1);
+}
@@ -0,0 +1,369 @@
+//! Article author credits repository.
+
+use crate::db::repositories::category::public_article_sql;
+use crate::db::DynDatabasePool;
+use crate::models::{ArticleAuthor, ArticleAuthorInput, AuthorRole, PublicUser, PublishedAuthor};
+use anyhow::{Context, Result};
+use async_trait::async_trait;
+use sqlx::Row;
+use std::sync::Arc;
+
+#[async_trait]
+pub trait ArticleAuthorRepository: Send + Sync {
+    /// Credits of the given articles as `(article_id, author)` pairs, in
+    /// order; an article without credits yields its primary author
+    async fn list_for_articles(&self, article_ids: &[i64]) -> Result<Vec<(i64, ArticleAuthor)>>;
+    /// Replace the credits of an article and make `primary_id` its
+    /// `author_id`
+    async fn set(
+        &self,
+        article_id: i64,
+        primary_id: i64,
+        authors: &[ArticleAuthorInput],
+    ) -> Result<()>;
+    /// Drop all credits of an article
+    async fn remove_article(&self, article_id: i64) -> Result<()>;
+    /// Which of the given user ids exist
+    async fn existing_user_ids(&self, user_ids: &[i64]) -> Result<Vec<i64>>;
+    /// Active users credited on public published articles, most articles
+    /// first
+    async fn list_published_authors(&self) -> Result<Vec<PublishedAuthor>>;
+}
+
+pub struct SqlxArticleAuthorRepository {
+    pool: DynDatabasePool,
+}
+
+impl SqlxArticleAuthorRepository {
+    pub fn new(pool: DynDatabasePool) -> Self {
+        Self { pool }
+    }
+
+    pub fn boxed(pool: DynDatabasePool) -> Arc<dyn ArticleAuthorRepository> {
+        Arc::new(Self::new(pool))
+    }
+}
+
+#[async_trait]
+impl ArticleAuthorRepository for SqlxArticleAuthorRepository {
+    async fn list_for_articles(&self, article_ids: &[i64]) -> Result<Vec<(i64, ArticleAuthor)>> {
+        if article_ids.is_empty() {
+            return Ok(Vec::new());
+        }
+        dispatch!(self, list_for_articles, article_ids)
+    }
+
+    async fn set(
+        &self,
+        article_id: i64,
+        primary_id: i64,
+        authors: &[ArticleAuthorInput],
+    ) -> Result<()> {
+        dispatch!(self, set, article_id, primary_id, authors)
+    }
+
+    async fn remove_article(&self, article_id: i64) -> Result<()> {
+        dispatch!(self, remove_article, article_id)
+    }
+
+    async fn existing_user_ids(&self, user_ids: &[i64]) -> Result<Vec<i64>> {
+        if user_ids.is_empty() {
+            return Ok(Vec::new());
+        }
+        dispatch!(self, existing_user_ids, user_ids)
+    }
+
+    async fn list_published_authors(&self) -> Result<Vec<PublishedAuthor>> {
+        dispatch!(self, list_published_authors)
+    }
+}
+
+impl_dual_fn! {
+    async fn list_for_articles(pool, article_ids: &[i64]) -> Result<Vec<(i64, ArticleAuthor)>> {
+        let placeholders = vec!["?"; article_ids.len()].join(", ");
+        let sql = format!(
+            "SELECT aa.article_id, aa.user_id, u.username, u.display_name, u.avatar, aa.role, aa.position \
+             FROM article_authors aa JOIN users u ON u.id = aa.user_id WHERE aa.article_id IN ({0}) \
+             UNION ALL \
+             SELECT a.id, u.id, u.username, u.display_name, u.avatar, 'author', 0 \
+             FROM articles a JOIN users u ON u.id = a.author_id WHERE a.id IN ({0}) \
+             AND NOT EXISTS (SELECT 1 FROM article_authors aa WHERE aa.article_id = a.id) \
+             ORDER BY 1, 7",
+            placeholders
+        );
+        let mut query = sqlx::query(&sql);
+        for _ in 0..2 {
+            for id in article_ids {
+                query = query.bind(id);
+            }
+        }
+        let rows = query
+            .fetch_all(pool)
+            .await
+            .context("Failed to list article authors")?;
+        Ok(rows.iter().map(row_to_author).collect())
+    }
+}
+
+impl_dual_fn! {
+    async fn set(pool, article_id: i64, primary_id: i64, authors: &[ArticleAuthorInput]) -> Result<()> {
+        let mut tx = pool.begin().await.context("Failed to start transaction")?;
+        sqlx::query("DELETE FROM article_authors WHERE article_id = ?")
+            .bind(article_id)
+            .execute(&mut *tx)
+            .await
+            .context("Failed to clear article authors")?;
+        for (position, author) in authors.iter().enumerate() {
+            sqlx::query("INSERT INTO article_authors (article_id, user_id, role, position) VALUES (?, ?, ?, ?)")
+                .bind(article_id)
+                .bind(author.user_id)
+                .bind(author.role.as_str())
+                .bind(position as i32)
+                .execute(&mut *tx)
+                .await
+                .context("Failed to add article author")?;
+        }
+        sqlx::query("UPDATE articles SET author_id = ? WHERE id = ?")
+            .bind(primary_id)
+            .bind(article_id)
+            .execute(&mut *tx)
+            .await
+            .context("Failed to update primary author")?;
+        tx.commit().await.context("Failed to commit transaction")?;
+        Ok(())
+    }
+}
+
+impl_dual_fn! {
+    async fn remove_article(pool, article_id: i64) -> Result<()> {
+        sqlx::query("DELETE FROM article_authors WHERE article_id = ?")
+            .bind(article_id)
+            .execute(pool)
+            .await
+            .context("Failed to remove article authors")?;
+        Ok(())
+    }
+}
+
+impl_dual_fn! {
+    async fn existing_user_ids(pool, user_ids: &[i64]) -> Result<Vec<i64>> {
+        let placeholders = vec!["?"; user_ids.len()].join(", ");
+        let sql = format!("SELECT id FROM users WHERE id IN ({})", placeholders);
+        let mut query = sqlx::query_scalar(&sql);
+        for id in user_ids {
+            query = query.bind(id);
+        }
+        let ids: Vec<i64> = query
+            .fetch_all(pool)
+            .await
+            .context("Failed to look up users")?;
+        Ok(ids)
+    }
+}
+
+impl_dual_fn! {
+    async fn list_published_authors(pool) -> Result<Vec<PublishedAuthor>> {
+        // Counts every credit, like the `?author=` article filter
+        let sql = format!(
+            "SELECT u.id, u.username, u.display_name, u.avatar, u.created_at, COUNT(*) AS article_count \
+             FROM users u JOIN articles a ON (a.author_id = u.id \
+             OR EXISTS (SELECT 1 FROM article_authors aa WHERE aa.article_id = a.id AND aa.user_id = u.id)) \
+             WHERE u.status = 'active' AND {} \
+             GROUP BY u.id, u.username, u.display_name, u.avatar, u.created_at \
+             ORDER BY article_count DESC, u.username",
+            public_article_sql("a.")
+        );
+        let rows = sqlx::query(&sql)
+            .fetch_all(pool)
+            .await
+            .context("Failed to list published authors")?;
+        Ok(rows
+            .iter()
+            .map(|row| PublishedAuthor {
+                user: PublicUser {
+                    id: row.get("id"),
+                    username: row.get("username"),
+                    display_name: row.get("display_name"),
+                    avatar: row.get("avatar"),
+                    created_at: row.get("created_at"),
+                },
+                article_count: row.get("article_count"),
+            })
+            .collect())
+    }
+}
+
+/// `position` is read as `i64`: its type in the union differs by database
+fn row_to_author<'r, R>(row: &'r R) -> (i64, ArticleAuthor)
+where
+    R: sqlx::Row,
+    usize: sqlx::ColumnIndex<R>,
+    String: sqlx::Decode<'r, R::Database> + sqlx::Type<R::Database>,
+    Option<String>: sqlx::Decode<'r, R::Database> + sqlx::Type<R::Database>,
+    i64: sqlx::Decode<'r, R::Database> + sqlx::Type<R::Database>,
+{
+    let role: String = row.get(5);
+    let position: i64 = row.get(6);
+    let author = ArticleAuthor {
+        user_id: row.get(1),
+        username: row.get(2),
+        display_name: row.get(3),
+        avatar: row.get(4),
+        role: AuthorRole::parse(&role).unwrap_or_default(),
+        position: position as i32,
+    };
+    (row.get(0), author)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::{create_test_pool, migrations};
+
+    #[tokio::test]
+    async fn credits_fall_back_to_the_primary_author() {
+        let pool = create_test_pool()
+            .await
+            .expect("Failed to create test pool");
+        migrations::run_migrations(&pool)
+            .await
+            .expect("Failed to run migrations");
+        let sqlite = pool.as_sqlite().unwrap();
+        let mut users = Vec::new();
+        for name in ["ada", "bob", "cy"] {
+            let id = sqlx::query(
+                "INSERT INTO users (username, email, password_hash, role) VALUES (?, ?, 'x', 'author')",
+            )
+            .bind(name)
+            .bind(format!("{}@example.com", name))
+            .execute(sqlite)
+            .await
+            .unwrap()
+            .last_insert_rowid();
+            users.push(id);
+        }
+        let article_id = sqlx::query(
+            "INSERT INTO articles (slug, title, content, content_html, author_id, category_id) VALUES ('a', 'A', '', '', ?, 1)",
+        )
+        .bind(users[0])
+        .execute(sqlite)
+        .await
+        .unwrap()
+        .last_insert_rowid();
+        let repo = SqlxArticleAuthorRepository::new(pool.clone());
+
+        let credits = repo.list_for_articles(&[article_id]).await.unwrap();
+        assert_eq!(credits.len(), 1);
+        assert_eq!(credits[0].0, article_id);
+        assert_eq!(credits[0].1.username, "ada");
+        assert_eq!(credits[0].1.role, AuthorRole::Author);
+
+        let input = |user_id: i64, role: AuthorRole| ArticleAuthorInput { user_id, role };
+        repo.set(
+            article_id,
+            users[1],
+            &[
+                input(users[1], AuthorRole::Author),
+                input(users[2], AuthorRole::Translator),
+            ],
+        )
+        .await
+        .unwrap();
+        let credits: Vec<(String, AuthorRole)> = repo
+            .list_for_articles(&[article_id])
+            .await
+            .unwrap()
+            .into_iter()
+            .map(|(_, a)| (a.username, a.role))
+            .collect();
+        assert_eq!(
+            credits,
+            [
+                ("bob".to_string(), AuthorRole::Author),
+                ("cy".to_string(), AuthorRole::Translator)
+            ]
+        );
+        let author_id: i64 = sqlx::query_scalar("SELECT author_id FROM articles WHERE id = ?")
+            .bind(article_id)
+            .fetch_one(sqlite)
+            .await
+            .unwrap();
+        assert_eq!(author_id, users[1]);
+
+        assert_eq!(
+            repo.existing_user_ids(&[users[2], 999]).await.unwrap(),
+            [users[2]]
+        );
+        repo.remove_article(article_id).await.unwrap();
+        assert_eq!(
+            repo.list_for_articles(&[article_id]).await.unwrap().len(),
+            1
+        );
+    }
+
+    #[tokio::test]
+    async fn published_authors_count_every_credit() {
+        let pool = create_test_pool()
+            .await
+            .expect("Failed to create test pool");
+        migrations::run_migrations(&pool)
+            .await
+            .expect("Failed to run migrations");
+        let sqlite = pool.as_sqlite().unwrap();
+        let mut users = Vec::new();
+        for (name, status) in [("ada", "active"), ("bob", "active"), ("cy", "banned")] {
+            let id = sqlx::query(
+                "INSERT INTO users (username, email, password_hash, role, status) VALUES (?, ?, 'x', 'author', ?)",
+            )
+            .bind(name)
+            .bind(format!("{}@example.com", name))
+            .bind(status)
+            .execute(sqlite)
+            .await
+            .unwrap()
+            .last_insert_rowid();
+            users.push(id);
+        }
+        let mut articles = Vec::new();
+        for (slug, author, status) in [
+            ("one", users[0], "published"),
+            ("two", users[0], "published"),
+            ("draft", users[1], "draft"),
+            ("banned", users[2], "published"),
+        ] {
+            let id = sqlx::query(
+                "INSERT INTO articles (slug, title, content, content_html, author_id, category_id, status) VALUES (?, ?, '', '', ?, 1, ?)",
+            )
+            .bind(slug)
+            .bind(slug)
+            .bind(author)
+            .bind(status)
+            .execute(sqlite)
+            .await
+            .unwrap()
+            .last_insert_rowid();
+            articles.push(id);
+        }
+        let repo = SqlxArticleAuthorRepository::new(pool.clone());
+        let input = |user_id: i64, role: AuthorRole| ArticleAuthorInput { user_id, role };
+        repo.set(
+            articles[1],
+            users[0],
+            &[
+                input(users[0], AuthorRole::Author),
+                input(users[1], AuthorRole::Translator),
+            ],
+        )
+        .await
+        .unwrap();
+
+        let authors: Vec<(String, i64)> = repo
+            .list_published_authors()
+            .await
+            .unwrap()
+            .into_iter()
+            .map(|a| (a.user.username, a.article_count))
+            .collect();
+        assert_eq!(authors, [("ada".to_string(), 2), ("bob".to_string(), 1)]);
+    }
+}
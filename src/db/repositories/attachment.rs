@@ -0,0 +1,546 @@
+//! Article attachment repository.
+
+use crate::db::DynDatabasePool;
+use crate::models::{
+    attachment_url, ArticleAttachment, AttachmentAccess, DailyDownloads, DownloadLead,
+};
+use anyhow::{Context, Result};
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use sqlx::{MySqlPool, Row, SqlitePool};
+use std::sync::Arc;
+
+const COLUMNS: &str = "id, article_id, filename, stored_name, content_type, size, position, \
+     access, download_count, created_at";
+
+#[async_trait]
+pub trait AttachmentRepository: Send + Sync {
+    /// Attachments of an article by position
+    async fn list(&self, article_id: i64) -> Result<Vec<ArticleAttachment>>;
+    async fn get(&self, id: i64) -> Result<Option<ArticleAttachment>>;
+    /// Add an attachment after the article's existing ones
+    async fn create(&self, attachment: &ArticleAttachment) -> Result<ArticleAttachment>;
+    async fn delete(&self, id: i64) -> Result<bool>;
+    /// Renumber an article's attachments in the given order
+    async fn set_order(&self, article_id: i64, attachment_ids: &[i64]) -> Result<()>;
+    /// Delete all attachments of an article
+    async fn delete_for_article(&self, article_id: i64) -> Result<()>;
+    async fn set_access(&self, id: i64, access: AttachmentAccess) -> Result<bool>;
+    /// Count a download and log who made it
+    async fn record_download(
+        &self,
+        id: i64,
+        user_id: Option<i64>,
+        email: Option<&str>,
+    ) -> Result<()>;
+    /// Downloads per day since `since` (`YYYY-MM-DD`), oldest first
+    async fn daily_downloads(&self, id: i64, since: &str) -> Result<Vec<DailyDownloads>>;
+    /// Downloads by signed-in users since `since`
+    async fn signed_in_downloads(&self, id: i64, since: &str) -> Result<i64>;
+    /// Addresses left for an attachment, newest first
+    async fn leads(&self, id: i64, limit: i64) -> Result<Vec<DownloadLead>>;
+    /// Most downloaded attachments across the site
+    async fn top(&self, limit: i64) -> Result<Vec<ArticleAttachment>>;
+}
+
+pub struct SqlxAttachmentRepository {
+    pool: DynDatabasePool,
+}
+
+impl SqlxAttachmentRepository {
+    pub fn new(pool: DynDatabasePool) -> Self {
+        Self { pool }
+    }
+
+    pub fn boxed(pool: DynDatabasePool) -> Arc<dyn AttachmentRepository> {
+        Arc::new(Self::new(pool))
+    }
+}
+
+#[async_trait]
+impl AttachmentRepository for SqlxAttachmentRepository {
+    async fn list(&self, article_id: i64) -> Result<Vec<ArticleAttachment>> {
+        dispatch!(self, list, article_id)
+    }
+
+    async fn get(&self, id: i64) -> Result<Option<ArticleAttachment>> {
+        dispatch!(self, get, id)
+    }
+
+    async fn create(&self, attachment: &ArticleAttachment) -> Result<ArticleAttachment> {
+        dispatch!(self, create, attachment)
+    }
+
+    async fn delete(&self, id: i64) -> Result<bool> {
+        dispatch!(self, delete, id)
+    }
+
+    async fn set_order(&self, article_id: i64, attachment_ids: &[i64]) -> Result<()> {
+        dispatch!(self, set_order, article_id, attachment_ids)
+    }
+
+    async fn delete_for_article(&self, article_id: i64) -> Result<()> {
+        dispatch!(self, delete_for_article, article_id)
+    }
+
+    async fn set_access(&self, id: i64, access: AttachmentAccess) -> Result<bool> {
+        dispatch!(self, set_access, id, access.as_str())
+    }
+
+    async fn record_download(
+        &self,
+        id: i64,
+        user_id: Option<i64>,
+        email: Option<&str>,
+    ) -> Result<()> {
+        dispatch!(self, record_download, id, user_id, email)
+    }
+
+    async fn daily_downloads(&self, id: i64, since: &str) -> Result<Vec<DailyDownloads>> {
+        dispatch!(self, daily_downloads, id, since)
+    }
+
+    async fn signed_in_downloads(&self, id: i64, since: &str) -> Result<i64> {
+        dispatch!(self, signed_in_downloads, id, since)
+    }
+
+    async fn leads(&self, id: i64, limit: i64) -> Result<Vec<DownloadLead>> {
+        dispatch!(self, leads, id, limit)
+    }
+
+    async fn top(&self, limit: i64) -> Result<Vec<ArticleAttachment>> {
+        dispatch!(self, top, limit)
+    }
+}
+
+impl_dual_fn! {
+    async fn list(pool, article_id: i64) -> Result<Vec<ArticleAttachment>> {
+        let rows = sqlx::query(&format!(
+            "SELECT {} FROM article_attachments WHERE article_id = ? ORDER BY position, id",
+            COLUMNS
+        ))
+        .bind(article_id)
+        .fetch_all(pool)
+        .await
+        .context("Failed to list attachments")?;
+        Ok(rows.iter().map(row_to_attachment).collect())
+    }
+}
+
+impl_dual_fn! {
+    async fn get(pool, id: i64) -> Result<Option<ArticleAttachment>> {
+        let row = sqlx::query(&format!("SELECT {} FROM article_attachments WHERE id = ?", COLUMNS))
+            .bind(id)
+            .fetch_optional(pool)
+            .await
+            .context("Failed to get attachment")?;
+        Ok(row.map(|r| row_to_attachment(&r)))
+    }
+}
+
+impl_dual_fn! {
+    async fn delete(pool, id: i64) -> Result<bool> {
+        let result = sqlx::query("DELETE FROM article_attachments WHERE id = ?")
+            .bind(id)
+            .execute(pool)
+            .await
+            .context("Failed to delete attachment")?;
+        Ok(result.rows_affected() > 0)
+    }
+}
+
+impl_dual_fn! {
+    async fn set_order(pool, article_id: i64, attachment_ids: &[i64]) -> Result<()> {
+        let mut tx = pool.begin().await.context("Failed to start transaction")?;
+        for (position, id) in attachment_ids.iter().enumerate() {
+            sqlx::query("UPDATE article_attachments SET position = ? WHERE id = ? AND article_id = ?")
+                .bind(position as i32)
+                .bind(id)
+                .bind(article_id)
+                .execute(&mut *tx)
+                .await
+                .context("Failed to reorder attachments")?;
+        }
+        tx.commit().await.context("Failed to commit transaction")?;
+        Ok(())
+    }
+}
+
+impl_dual_fn! {
+    async fn delete_for_article(pool, article_id: i64) -> Result<()> {
+        sqlx::query("DELETE FROM article_attachments WHERE article_id = ?")
+            .bind(article_id)
+            .execute(pool)
+            .await
+            .context("Failed to delete article attachments")?;
+        Ok(())
+    }
+}
+
+impl_dual_fn! {
+    async fn set_access(pool, id: i64, access: &str) -> Result<bool> {
+        let result = sqlx::query("UPDATE article_attachments SET access = ? WHERE id = ?")
+            .bind(access)
+            .bind(id)
+            .execute(pool)
+            .await
+            .context("Failed to update attachment access")?;
+        Ok(result.rows_affected() > 0)
+    }
+}
+
+impl_dual_fn! {
+    async fn record_download(pool, id: i64, user_id: Option<i64>, email: Option<&str>) -> Result<()> {
+        let mut tx = pool.begin().await.context("Failed to start transaction")?;
+        sqlx::query("INSERT INTO attachment_downloads (attachment_id, user_id, email, created_at) VALUES (?, ?, ?, ?)")
+            .bind(id)
+            .bind(user_id)
+            .bind(email)
+            .bind(Utc::now())
+            .execute(&mut *tx)
+            .await
+            .context("Failed to log download")?;
+        sqlx::query("UPDATE article_attachments SET download_count = download_count + 1 WHERE id = ?")
+            .bind(id)
+            .execute(&mut *tx)
+            .await
+            .context("Failed to count download")?;
+        tx.commit().await.context("Failed to commit transaction")?;
+        Ok(())
+    }
+}
+
+impl_dual_fn! {
+    async fn signed_in_downloads(pool, id: i64, since: &str) -> Result<i64> {
+        sqlx::query_scalar(
+            "SELECT COUNT(*) FROM attachment_downloads WHERE attachment_id = ? AND created_at >= ? AND user_id IS NOT NULL",
+        )
+        .bind(id)
+        .bind(since)
+        .fetch_one(pool)
+        .await
+        .context("Failed to count signed-in downloads")
+    }
+}
+
+impl_dual_fn! {
+    async fn leads(pool, id: i64, limit: i64) -> Result<Vec<DownloadLead>> {
+        let rows = sqlx::query(
+            "SELECT email, MAX(created_at) AS last_at FROM attachment_downloads \
+             WHERE attachment_id = ? AND email IS NOT NULL GROUP BY email ORDER BY last_at DESC LIMIT ?",
+        )
+        .bind(id)
+        .bind(limit)
+        .fetch_all(pool)
+        .await
+        .context("Failed to list download leads")?;
+        Ok(rows
+            .iter()
+            .map(|row| DownloadLead {
+                email: row.get("email"),
+                created_at: row.get("last_at"),
+            })
+            .collect())
+    }
+}
+
+impl_dual_fn! {
+    async fn top(pool, limit: i64) -> Result<Vec<ArticleAttachment>> {
+        let rows = sqlx::query(&format!(
+            "SELECT {} FROM article_attachments WHERE download_count > 0 ORDER BY download_count DESC, id LIMIT ?",
+            COLUMNS
+        ))
+        .bind(limit)
+        .fetch_all(pool)
+        .await
+        .context("Failed to list top attachments")?;
+        Ok(rows.iter().map(row_to_attachment).collect())
+    }
+}
+
+/// Comparing `created_at` with a bare `YYYY-MM-DD` day works on both drivers
+const DOWNLOADS_BY_DAY: &str = "SELECT {day} AS day, COUNT(*) AS downloads \
+     FROM attachment_downloads WHERE attachment_id = ? AND created_at >= ? GROUP BY day ORDER BY day";
+
+async fn daily_downloads_sqlite(
+    pool: &SqlitePool,
+    id: i64,
+    since: &str,
+) -> Result<Vec<DailyDownloads>> {
+    let sql = DOWNLOADS_BY_DAY.replace("{day}", "substr(created_at, 1, 10)");
+    let rows = sqlx::query(&sql)
+        .bind(id)
+        .bind(since)
+        .fetch_all(pool)
+        .await
+        .context("Failed to load daily downloads")?;
+    Ok(rows
+        .iter()
+        .map(|row| DailyDownloads {
+            day: row.get("day"),
+            count: row.get("downloads"),
+        })
+        .collect())
+}
+
+async fn daily_downloads_mysql(
+    pool: &MySqlPool,
+    id: i64,
+    since: &str,
+) -> Result<Vec<DailyDownloads>> {
+    let sql = DOWNLOADS_BY_DAY.replace("{day}", "DATE_FORMAT(created_at, '%Y-%m-%d')");
+    let rows = sqlx::query(&sql)
+        .bind(id)
+        .bind(since)
+        .fetch_all(pool)
+        .await
+        .context("Failed to load daily downloads")?;
+    Ok(rows
+        .iter()
+        .map(|row| DailyDownloads {
+            day: row.get("day"),
+            count: row.get("downloads"),
+        })
+        .collect())
+}
+
+fn row_to_attachment<'r, R>(row: &'r R) -> ArticleAttachment
+where
+    R: sqlx::Row,
+    &'r str: sqlx::ColumnIndex<R>,
+    String: sqlx::Decode<'r, R::Database> + sqlx::Type<R::Database>,
+    i64: sqlx::Decode<'r, R::Database> + sqlx::Type<R::Database>,
+    i32: sqlx::Decode<'r, R::Database> + sqlx::Type<R::Database>,
+    DateTime<Utc>: sqlx::Decode<'r, R::Database> + sqlx::Type<R::Database>,
+{
+    let id: i64 = row.get("id");
+    let access: String = row.get("access");
+    ArticleAttachment {
+        id,
+        article_id: row.get("article_id"),
+        filename: row.get("filename"),
+        stored_name: row.get("stored_name"),
+        url: attachment_url(id),
+        content_type: row.get("content_type"),
+        size: row.get("size"),
+        position: row.get("position"),
+        access: AttachmentAccess::parse(&access).unwrap_or_default(),
+        download_count: row.get("download_count"),
+        created_at: row.get("created_at"),
+    }
+}
+
+async fn create_sqlite(
+    pool: &SqlitePool,
+    attachment: &ArticleAttachment,
+) -> Result<ArticleAttachment> {
+    let now = Utc::now();
+    let position: i32 = sqlx::query_scalar(
+        "SELECT COALESCE(MAX(position) + 1, 0) FROM article_attachments WHERE article_id = ?",
+    )
+    .bind(attachment.article_id)
+    .fetch_one(pool)
+    .await
+    .context("Failed to get next attachment position")?;
+    let result = sqlx::query(
+        "INSERT INTO article_attachments (article_id, filename, stored_name, content_type, size, position, access, created_at) VALUES (?, ?, ?, ?, ?, ?, ?, ?)",
+    )
+    .bind(attachment.article_id)
+    .bind(&attachment.filename)
+    .bind(&attachment.stored_name)
+    .bind(&attachment.content_type)
+    .bind(attachment.size)
+    .bind(position)
+    .bind(attachment.access.as_str())
+    .bind(now)
+    .execute(pool)
+    .await
+    .context("Failed to create attachment")?;
+
+    let id = result.last_insert_rowid();
+    Ok(ArticleAttachment {
+        id,
+        url: attachment_url(id),
+        position,
+        download_count: 0,
+        created_at: now,
+        ..attachment.clone()
+    })
+}
+
+async fn create_mysql(
+    pool: &MySqlPool,
+    attachment: &ArticleAttachment,
+) -> Result<ArticleAttachment> {
+    let now = Utc::now();
+    let position: i32 = sqlx::query_scalar(
+        "SELECT CAST(COALESCE(MAX(position) + 1, 0) AS SIGNED) FROM article_attachments WHERE article_id = ?",
+    )
+    .bind(attachment.article_id)
+    .fetch_one(pool)
+    .await
+    .map(|p: i64| p as i32)
+    .context("Failed to get next attachment position")?;
+    let result = sqlx::query(
+        "INSERT INTO article_attachments (article_id, filename, stored_name, content_type, size, position, access, created_at) VALUES (?, ?, ?, ?, ?, ?, ?, ?)",
+    )
+    .bind(attachment.article_id)
+    .bind(&attachment.filename)
+    .bind(&attachment.stored_name)
+    .bind(&attachment.content_type)
+    .bind(attachment.size)
+    .bind(position)
+    .bind(attachment.access.as_str())
+    .bind(now)
+    .execute(pool)
+    .await
+    .context("Failed to create attachment")?;
+
+    let id = result.last_insert_id() as i64;
+    Ok(ArticleAttachment {
+        id,
+        url: attachment_url(id),
+        position,
+        download_count: 0,
+        created_at: now,
+        ..attachment.clone()
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::{create_test_pool, migrations};
+
+    fn attachment(article_id: i64, filename: &str) -> ArticleAttachment {
+        ArticleAttachment {
+            id: 0,
+            article_id,
+            filename: filename.to_string(),
+            stored_name: format!("{}.bin", filename),
+            url: String::new(),
+            content_type: "application/octet-stream".to_string(),
+            size: 10,
+            position: 0,
+            access: AttachmentAccess::Public,
+            download_count: 0,
+            created_at: Utc::now(),
+        }
+    }
+
+    #[tokio::test]
+    async fn attachments_are_appended_and_reordered() {
+        let pool = create_test_pool()
+            .await
+            .expect("Failed to create test pool");
+        migrations::run_migrations(&pool)
+            .await
+            .expect("Failed to run migrations");
+        let sqlite = pool.as_sqlite().unwrap();
+        let user_id = sqlx::query(
+            "INSERT INTO users (username, email, password_hash, role) VALUES ('u', 'u@example.com', 'x', 'admin')",
+        )
+        .execute(sqlite)
+        .await
+        .unwrap()
+        .last_insert_rowid();
+        let article_id = sqlx::query(
+            "INSERT INTO articles (slug, title, content, content_html, author_id, category_id) VALUES ('a', 'A', '', '', ?, 1)",
+        )
+        .bind(user_id)
+        .execute(sqlite)
+        .await
+        .unwrap()
+        .last_insert_rowid();
+        let repo = SqlxAttachmentRepository::new(pool.clone());
+
+        let a = repo.create(&attachment(article_id, "a.pdf")).await.unwrap();
+        let b = repo.create(&attachment(article_id, "b.zip")).await.unwrap();
+        assert_eq!((a.position, b.position), (0, 1));
+
+        let names = |list: Vec<ArticleAttachment>| -> Vec<String> {
+            list.into_iter().map(|a| a.filename).collect()
+        };
+        repo.set_order(article_id, &[b.id, a.id]).await.unwrap();
+        assert_eq!(
+            names(repo.list(article_id).await.unwrap()),
+            ["b.zip", "a.pdf"]
+        );
+
+        let stored = repo.get(a.id).await.unwrap().unwrap();
+        assert_eq!(stored.url, format!("/api/v1/attachments/{}/download", a.id));
+
+        assert!(repo.delete(b.id).await.unwrap());
+        assert!(!repo.delete(b.id).await.unwrap());
+        repo.delete_for_article(article_id).await.unwrap();
+        assert!(repo.list(article_id).await.unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn downloads_are_counted_and_logged() {
+        let pool = create_test_pool()
+            .await
+            .expect("Failed to create test pool");
+        migrations::run_migrations(&pool)
+            .await
+            .expect("Failed to run migrations");
+        let sqlite = pool.as_sqlite().unwrap();
+        let user_id = sqlx::query(
+            "INSERT INTO users (username, email, password_hash, role) VALUES ('u', 'u@example.com', 'x', 'admin')",
+        )
+        .execute(sqlite)
+        .await
+        .unwrap()
+        .last_insert_rowid();
+        let article_id = sqlx::query(
+            "INSERT INTO articles (slug, title, content, content_html, author_id, category_id) VALUES ('a', 'A', '', '', ?, 1)",
+        )
+        .bind(user_id)
+        .execute(sqlite)
+        .await
+        .unwrap()
+        .last_insert_rowid();
+        let repo = SqlxAttachmentRepository::new(pool.clone());
+        let a = repo.create(&attachment(article_id, "a.pdf")).await.unwrap();
+        let b = repo.create(&attachment(article_id, "b.zip")).await.unwrap();
+
+        assert!(repo
+            .set_access(a.id, AttachmentAccess::Email)
+            .await
+            .unwrap());
+        repo.record_download(a.id, None, Some("x@example.com"))
+            .await
+            .unwrap();
+        repo.record_download(a.id, None, Some("y@example.com"))
+            .await
+            .unwrap();
+        repo.record_download(a.id, Some(user_id), Some("x@example.com"))
+            .await
+            .unwrap();
+        repo.record_download(b.id, None, None).await.unwrap();
+
+        let stored = repo.get(a.id).await.unwrap().unwrap();
+        assert_eq!(stored.access, AttachmentAccess::Email);
+        assert_eq!(stored.download_count, 3);
+
+        let since = (Utc::now() - chrono::Duration::days(1))
+            .format("%Y-%m-%d")
+            .to_string();
+        let daily = repo.daily_downloads(a.id, &since).await.unwrap();
+        assert_eq!(daily.iter().map(|d| d.count).sum::<i64>(), 3);
+        assert_eq!(repo.signed_in_downloads(a.id, &since).await.unwrap(), 1);
+
+        let mut leads: Vec<String> = repo
+            .leads(a.id, 10)
+            .await
+            .unwrap()
+            .into_iter()
+            .map(|l| l.email)
+            .collect();
+        leads.sort();
+        assert_eq!(leads, ["x@example.com", "y@example.com"]);
+
+        let top: Vec<i64> = repo.top(10).await.unwrap().iter().map(|a| a.id).collect();
+        assert_eq!(top, [a.id, b.id]);
+    }
+}
@@ -10,8 +10,9 @@
 //! - 2.1: WHEN 用户创建分类 THEN Category_Service SHALL 创建分类记录并支持设置父分类
 //! - 2.3: WHEN 用户请求某分类下的文章 THEN Category_Service SHALL 返回该分类及其子分类下的所有文章
 
+use crate::db::repositories::reader_group::readable_audience_sql;
 use crate::db::DynDatabasePool;
-use crate::models::{Category, CategoryTree};
+use crate::models::{Category, CategoryAccess, CategoryTree};
 use anyhow::{Context, Result};
 use async_trait::async_trait;
 use chrono::Utc;
@@ -53,6 +54,27 @@ pub trait CategoryRepository: Send + Sync {
     /// Delete a category
     async fn delete(&self, id: i64) -> Result<()>;
 
+    /// Delete a category along with what hangs off it, in one transaction
+    ///
+    /// Its direct children move up to `parent_id`. Its articles move to
+    /// `move_articles_to`, or are deleted (leaving sync tombstones) when it
+    /// is `None`.
+    ///
+    /// # Returns
+    /// IDs of the deleted articles
+    async fn delete_cascading(
+        &self,
+        id: i64,
+        parent_id: Option<i64>,
+        move_articles_to: Option<i64>,
+    ) -> Result<Vec<i64>>;
+
+    /// Place the given categories under `parent_id` in the given order
+    ///
+    /// Each category's `sort_order` becomes its index in `ids`. All rows are
+    /// updated in a single transaction.
+    async fn set_order(&self, parent_id: Option<i64>, ids: &[i64]) -> Result<()>;
+
     /// Check if a category name already exists
     async fn exists_by_name(&self, name: &str) -> Result<bool>;
 
@@ -125,6 +147,25 @@ impl CategoryRepository for SqlxCategoryRepository {
         dispatch!(self, delete_category, id)
     }
 
+    async fn delete_cascading(
+        &self,
+        id: i64,
+        parent_id: Option<i64>,
+        move_articles_to: Option<i64>,
+    ) -> Result<Vec<i64>> {
+        dispatch!(
+            self,
+            delete_category_cascading,
+            id,
+            parent_id,
+            move_articles_to
+        )
+    }
+
+    async fn set_order(&self, parent_id: Option<i64>, ids: &[i64]) -> Result<()> {
+        dispatch!(self, set_category_order, parent_id, ids)
+    }
+
     async fn exists_by_name(&self, name: &str) -> Result<bool> {
         dispatch!(self, exists_by_name, name)
     }
@@ -138,6 +179,51 @@ impl CategoryRepository for SqlxCategoryRepository {
     }
 }
 
+// ============================================================================
+// Access control
+// ============================================================================
+
+/// SQL condition on an article's category column (`column`, e.g.
+/// `a.category_id`) that leaves out categories a reader with `clearance`
+/// may not read.
+///
+/// Every query listing published articles to readers goes through this,
+/// so a private category's articles never reach a listing, feed, search
+/// or sitemap that did not ask for them. Unknown access values count as
+/// admins only.
+pub fn readable_category_sql(column: &str, clearance: CategoryAccess) -> String {
+    if clearance == CategoryAccess::Admins {
+        return "1 = 1".to_string();
+    }
+    let levels = clearance
+        .readable()
+        .map(|level| format!("'{}'", level.as_str()))
+        .collect::<Vec<_>>()
+        .join(", ");
+    format!(
+        "{} NOT IN (SELECT id FROM categories WHERE access NOT IN ({}))",
+        column, levels
+    )
+}
+
+/// Published and readable by anonymous visitors: the condition public
+/// listings, feeds and searches filter articles on. Articles with an
+/// audience are left out too. `alias` is the articles table alias with its
+/// dot, or empty.
+pub fn public_article_sql(alias: &str) -> String {
+    format!(
+        "{}status = 'published' AND {} AND {}",
+        alias,
+        readable_category_sql(&format!("{}category_id", alias), CategoryAccess::Public),
+        readable_audience_sql(&format!("{}id", alias), None)
+    )
+}
+
+/// Stored access value; unknown values are the most restrictive level
+fn parse_access(value: String) -> CategoryAccess {
+    value.parse().unwrap_or(CategoryAccess::Admins)
+}
+
 // ============================================================================
 // Tree building helper
 // ============================================================================
@@ -161,7 +247,7 @@ fn build_category_tree(categories: Vec<Category>) -> Vec<CategoryTree> {
         children.sort_by(|a, b| {
             let cat_a = category_map.get(a).unwrap();
             let cat_b = category_map.get(b).unwrap();
-            cat_a.sort_order.cmp(&cat_b.sort_order)
+            cat_a.sort_order.cmp(&cat_b.sort_order).then(a.cmp(b))
         });
     }
 
@@ -188,6 +274,68 @@ fn build_category_tree(categories: Vec<Category>) -> Vec<CategoryTree> {
     build_subtree(None, &category_map, &children_map)
 }
 
+// ============================================================================
+// Shared implementations
+// ============================================================================
+
+impl_dual_fn! {
+    async fn delete_category_cascading(
+        pool,
+        id: i64,
+        parent_id: Option<i64>,
+        move_articles_to: Option<i64>
+    ) -> Result<Vec<i64>> {
+        let mut tx = pool.begin().await.context("Failed to start transaction")?;
+
+        let article_ids: Vec<i64> = match move_articles_to {
+            Some(target_id) => {
+                sqlx::query("UPDATE articles SET category_id = ? WHERE category_id = ?")
+                    .bind(target_id)
+                    .bind(id)
+                    .execute(&mut *tx)
+                    .await
+                    .context("Failed to move category articles")?;
+                Vec::new()
+            }
+            None => {
+                let article_ids = sqlx::query_scalar("SELECT id FROM articles WHERE category_id = ?")
+                    .bind(id)
+                    .fetch_all(&mut *tx)
+                    .await
+                    .context("Failed to list category articles")?;
+                // Leave tombstones for delta sync clients
+                sqlx::query("INSERT INTO sync_tombstones (entity_type, entity_id, slug, status, deleted_at) SELECT 'article', id, slug, status, ? FROM articles WHERE category_id = ?")
+                    .bind(Utc::now())
+                    .bind(id)
+                    .execute(&mut *tx)
+                    .await
+                    .context("Failed to record article tombstones")?;
+                sqlx::query("DELETE FROM articles WHERE category_id = ?")
+                    .bind(id)
+                    .execute(&mut *tx)
+                    .await
+                    .context("Failed to delete category articles")?;
+                article_ids
+            }
+        };
+
+        sqlx::query("UPDATE categories SET parent_id = ? WHERE parent_id = ?")
+            .bind(parent_id)
+            .bind(id)
+            .execute(&mut *tx)
+            .await
+            .context("Failed to reparent child categories")?;
+        sqlx::query("DELETE FROM categories WHERE id = ?")
+            .bind(id)
+            .execute(&mut *tx)
+            .await
+            .context("Failed to delete category")?;
+
+        tx.commit().await.context("Failed to commit transaction")?;
+        Ok(article_ids)
+    }
+}
+
 // ============================================================================
 // SQLite implementations
 // ============================================================================
@@ -197,8 +345,8 @@ async fn create_category_sqlite(pool: &SqlitePool, category: &Category) -> Resul
 
     let result = sqlx::query(
         r#"
-        INSERT INTO categories (slug, name, description, parent_id, sort_order, created_at)
-        VALUES (?, ?, ?, ?, ?, ?)
+        INSERT INTO categories (slug, name, description, parent_id, sort_order, access, created_at)
+        VALUES (?, ?, ?, ?, ?, ?, ?)
         "#,
     )
     .bind(&category.slug)
@@ -206,6 +354,7 @@ async fn create_category_sqlite(pool: &SqlitePool, category: &Category) -> Resul
     .bind(&category.description)
     .bind(category.parent_id)
     .bind(category.sort_order)
+    .bind(category.access.as_str())
     .bind(now)
     .execute(pool)
     .await
@@ -220,6 +369,7 @@ async fn create_category_sqlite(pool: &SqlitePool, category: &Category) -> Resul
         description: category.description.clone(),
         parent_id: category.parent_id,
         sort_order: category.sort_order,
+        access: category.access,
         created_at: now,
     })
 }
@@ -227,7 +377,7 @@ async fn create_category_sqlite(pool: &SqlitePool, category: &Category) -> Resul
 async fn get_category_by_id_sqlite(pool: &SqlitePool, id: i64) -> Result<Option<Category>> {
     let row = sqlx::query(
         r#"
-        SELECT id, slug, name, description, parent_id, sort_order, created_at
+        SELECT id, slug, name, description, parent_id, sort_order, access, created_at
         FROM categories
         WHERE id = ?
         "#,
@@ -246,7 +396,7 @@ async fn get_category_by_id_sqlite(pool: &SqlitePool, id: i64) -> Result<Option<
 async fn get_category_by_slug_sqlite(pool: &SqlitePool, slug: &str) -> Result<Option<Category>> {
     let row = sqlx::query(
         r#"
-        SELECT id, slug, name, description, parent_id, sort_order, created_at
+        SELECT id, slug, name, description, parent_id, sort_order, access, created_at
         FROM categories
         WHERE slug = ?
         "#,
@@ -265,7 +415,7 @@ async fn get_category_by_slug_sqlite(pool: &SqlitePool, slug: &str) -> Result<Op
 async fn get_category_by_name_sqlite(pool: &SqlitePool, name: &str) -> Result<Option<Category>> {
     let row = sqlx::query(
         r#"
-        SELECT id, slug, name, description, parent_id, sort_order, created_at
+        SELECT id, slug, name, description, parent_id, sort_order, access, created_at
         FROM categories
         WHERE name = ?
         "#,
@@ -284,7 +434,7 @@ async fn get_category_by_name_sqlite(pool: &SqlitePool, name: &str) -> Result<Op
 async fn list_categories_sqlite(pool: &SqlitePool) -> Result<Vec<Category>> {
     let rows = sqlx::query(
         r#"
-        SELECT id, slug, name, description, parent_id, sort_order, created_at
+        SELECT id, slug, name, description, parent_id, sort_order, access, created_at
         FROM categories
         ORDER BY sort_order, name
         "#,
@@ -304,7 +454,7 @@ async fn list_categories_sqlite(pool: &SqlitePool) -> Result<Vec<Category>> {
 async fn get_children_sqlite(pool: &SqlitePool, parent_id: i64) -> Result<Vec<Category>> {
     let rows = sqlx::query(
         r#"
-        SELECT id, slug, name, description, parent_id, sort_order, created_at
+        SELECT id, slug, name, description, parent_id, sort_order, access, created_at
         FROM categories
         WHERE parent_id = ?
         ORDER BY sort_order, name
@@ -356,7 +506,7 @@ async fn update_category_sqlite(pool: &SqlitePool, category: &Category) -> Resul
     sqlx::query(
         r#"
         UPDATE categories
-        SET slug = ?, name = ?, description = ?, parent_id = ?, sort_order = ?
+        SET slug = ?, name = ?, description = ?, parent_id = ?, sort_order = ?, access = ?
         WHERE id = ?
         "#,
     )
@@ -365,6 +515,7 @@ async fn update_category_sqlite(pool: &SqlitePool, category: &Category) -> Resul
     .bind(&category.description)
     .bind(category.parent_id)
     .bind(category.sort_order)
+    .bind(category.access.as_str())
     .bind(category.id)
     .execute(pool)
     .await
@@ -386,6 +537,27 @@ async fn delete_category_sqlite(pool: &SqlitePool, id: i64) -> Result<()> {
     Ok(())
 }
 
+async fn set_category_order_sqlite(
+    pool: &SqlitePool,
+    parent_id: Option<i64>,
+    ids: &[i64],
+) -> Result<()> {
+    let mut tx = pool.begin().await.context("Failed to start transaction")?;
+
+    for (position, id) in ids.iter().enumerate() {
+        sqlx::query("UPDATE categories SET parent_id = ?, sort_order = ? WHERE id = ?")
+            .bind(parent_id)
+            .bind(position as i32)
+            .bind(id)
+            .execute(&mut *tx)
+            .await
+            .context("Failed to update category order")?;
+    }
+
+    tx.commit().await.context("Failed to commit transaction")?;
+    Ok(())
+}
+
 async fn exists_by_name_sqlite(pool: &SqlitePool, name: &str) -> Result<bool> {
     let row = sqlx::query("SELECT COUNT(*) as count FROM categories WHERE name = ?")
         .bind(name)
@@ -416,6 +588,7 @@ fn row_to_category_sqlite(row: &sqlx::sqlite::SqliteRow) -> Result<Category> {
         description: row.get("description"),
         parent_id: row.get("parent_id"),
         sort_order: row.get("sort_order"),
+        access: parse_access(row.get("access")),
         created_at: row.get("created_at"),
     })
 }
@@ -429,8 +602,8 @@ async fn create_category_mysql(pool: &MySqlPool, category: &Category) -> Result<
 
     let result = sqlx::query(
         r#"
-        INSERT INTO categories (slug, name, description, parent_id, sort_order, created_at)
-        VALUES (?, ?, ?, ?, ?, ?)
+        INSERT INTO categories (slug, name, description, parent_id, sort_order, access, created_at)
+        VALUES (?, ?, ?, ?, ?, ?, ?)
         "#,
     )
     .bind(&category.slug)
@@ -438,6 +611,7 @@ async fn create_category_mysql(pool: &MySqlPool, category: &Category) -> Result<
     .bind(&category.description)
     .bind(category.parent_id)
     .bind(category.sort_order)
+    .bind(category.access.as_str())
     .bind(now)
     .execute(pool)
     .await
@@ -452,6 +626,7 @@ async fn create_category_mysql(pool: &MySqlPool, category: &Category) -> Result<
         description: category.description.clone(),
         parent_id: category.parent_id,
         sort_order: category.sort_order,
+        access: category.access,
         created_at: now,
     })
 }
@@ -459,7 +634,7 @@ async fn create_category_mysql(pool: &MySqlPool, category: &Category) -> Result<
 async fn get_category_by_id_mysql(pool: &MySqlPool, id: i64) -> Result<Option<Category>> {
     let row = sqlx::query(
         r#"
-        SELECT id, slug, name, description, parent_id, sort_order, created_at
+        SELECT id, slug, name, description, parent_id, sort_order, access, created_at
         FROM categories
         WHERE id = ?
         "#,
@@ -478,7 +653,7 @@ async fn get_category_by_id_mysql(pool: &MySqlPool, id: i64) -> Result<Option<Ca
 async fn get_category_by_slug_mysql(pool: &MySqlPool, slug: &str) -> Result<Option<Category>> {
     let row = sqlx::query(
         r#"
-        SELECT id, slug, name, description, parent_id, sort_order, created_at
+        SELECT id, slug, name, description, parent_id, sort_order, access, created_at
         FROM categories
         WHERE slug = ?
         "#,
@@ -497,7 +672,7 @@ async fn get_category_by_slug_mysql(pool: &MySqlPool, slug: &str) -> Result<Opti
 async fn get_category_by_name_mysql(pool: &MySqlPool, name: &str) -> Result<Option<Category>> {
     let row = sqlx::query(
         r#"
-        SELECT id, slug, name, description, parent_id, sort_order, created_at
+        SELECT id, slug, name, description, parent_id, sort_order, access, created_at
         FROM categories
         WHERE name = ?
         "#,
@@ -516,7 +691,7 @@ async fn get_category_by_name_mysql(pool: &MySqlPool, name: &str) -> Result<Opti
 async fn list_categories_mysql(pool: &MySqlPool) -> Result<Vec<Category>> {
     let rows = sqlx::query(
         r#"
-        SELECT id, slug, name, description, parent_id, sort_order, created_at
+        SELECT id, slug, name, description, parent_id, sort_order, access, created_at
         FROM categories
         ORDER BY sort_order, name
         "#,
@@ -536,7 +711,7 @@ async fn list_categories_mysql(pool: &MySqlPool) -> Result<Vec<Category>> {
 async fn get_children_mysql(pool: &MySqlPool, parent_id: i64) -> Result<Vec<Category>> {
     let rows = sqlx::query(
         r#"
-        SELECT id, slug, name, description, parent_id, sort_order, created_at
+        SELECT id, slug, name, description, parent_id, sort_order, access, created_at
         FROM categories
         WHERE parent_id = ?
         ORDER BY sort_order, name
@@ -588,7 +763,7 @@ async fn update_category_mysql(pool: &MySqlPool, category: &Category) -> Result<
     sqlx::query(
         r#"
         UPDATE categories
-        SET slug = ?, name = ?, description = ?, parent_id = ?, sort_order = ?
+        SET slug = ?, name = ?, description = ?, parent_id = ?, sort_order = ?, access = ?
         WHERE id = ?
         "#,
     )
@@ -597,6 +772,7 @@ async fn update_category_mysql(pool: &MySqlPool, category: &Category) -> Result<
     .bind(&category.description)
     .bind(category.parent_id)
     .bind(category.sort_order)
+    .bind(category.access.as_str())
     .bind(category.id)
     .execute(pool)
     .await
@@ -618,6 +794,27 @@ async fn delete_category_mysql(pool: &MySqlPool, id: i64) -> Result<()> {
     Ok(())
 }
 
+async fn set_category_order_mysql(
+    pool: &MySqlPool,
+    parent_id: Option<i64>,
+    ids: &[i64],
+) -> Result<()> {
+    let mut tx = pool.begin().await.context("Failed to start transaction")?;
+
+    for (position, id) in ids.iter().enumerate() {
+        sqlx::query("UPDATE categories SET parent_id = ?, sort_order = ? WHERE id = ?")
+            .bind(parent_id)
+            .bind(position as i32)
+            .bind(id)
+            .execute(&mut *tx)
+            .await
+            .context("Failed to update category order")?;
+    }
+
+    tx.commit().await.context("Failed to commit transaction")?;
+    Ok(())
+}
+
 async fn exists_by_name_mysql(pool: &MySqlPool, name: &str) -> Result<bool> {
     let row = sqlx::query("SELECT COUNT(*) as count FROM categories WHERE name = ?")
         .bind(name)
@@ -648,6 +845,7 @@ fn row_to_category_mysql(row: &sqlx::mysql::MySqlRow) -> Result<Category> {
         description: row.get("description"),
         parent_id: row.get("parent_id"),
         sort_order: row.get("sort_order"),
+        access: parse_access(row.get("access")),
         created_at: row.get("created_at"),
     })
 }
@@ -962,6 +1160,41 @@ mod tests {
         assert!(found.is_none());
     }
 
+    #[tokio::test]
+    async fn test_set_order() {
+        let (_pool, repo) = setup_test_repo().await;
+
+        let root = repo
+            .create(&create_test_category("root", "Root", None))
+            .await
+            .expect("Failed to create root");
+        let first = repo
+            .create(&create_test_category("first", "First", None))
+            .await
+            .expect("Failed to create first");
+        let second = repo
+            .create(&create_test_category("second", "Second", Some(root.id)))
+            .await
+            .expect("Failed to create second");
+
+        repo.set_order(Some(root.id), &[first.id, second.id])
+            .await
+            .expect("Failed to set order");
+
+        let tree = repo.list_tree().await.expect("Failed to list tree");
+        let root_tree = tree
+            .iter()
+            .find(|t| t.category.id == root.id)
+            .expect("Root not found");
+        let slugs: Vec<&str> = root_tree
+            .children
+            .iter()
+            .map(|t| t.category.slug.as_str())
+            .collect();
+        assert_eq!(slugs, vec!["first", "second"]);
+        assert_eq!(root_tree.children[1].category.sort_order, 1);
+    }
+
     #[tokio::test]
     async fn test_exists_by_name() {
         let (_pool, repo) = setup_test_repo().await;
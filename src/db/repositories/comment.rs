@@ -1,12 +1,17 @@
 //! Comment repository
 
-use anyhow::Result;
+use anyhow::{Context, Result};
 use async_trait::async_trait;
-use chrono::Utc;
+use chrono::{DateTime, Utc};
 use sqlx::{MySqlPool, Row, SqlitePool};
 
+use crate::db::repositories::category::public_article_sql;
 use crate::db::DynDatabasePool;
-use crate::models::{Comment, CommentStatus, CommentWithMeta, CreateCommentInput, LikeTargetType};
+use crate::models::{
+    Comment, CommentCounts, CommentExportFilter, CommentExportRecord, CommentPreview,
+    CommentSearchFilter, CommentStatus, CommentType, CommentWithMeta, CreateCommentInput,
+    LikeTargetType,
+};
 
 /// Comment repository trait
 #[async_trait]
@@ -87,6 +92,10 @@ pub trait CommentRepository: Send + Sync {
     /// Increment article view count
     async fn increment_view(&self, article_id: i64) -> Result<()>;
 
+    /// Add batched `(article_id, views)` increments counted on `day` in one
+    /// transaction; deleted articles are skipped
+    async fn add_views(&self, day: &str, views: &[(i64, i64)]) -> Result<()>;
+
     /// Get recent approved comments across all articles
     async fn list_recent(&self, limit: i64) -> Result<Vec<CommentWithMeta>>;
 
@@ -95,6 +104,67 @@ pub trait CommentRepository: Send + Sync {
 
     /// Count pending comments.
     async fn count_pending(&self) -> Result<i64>;
+
+    /// Count the approved and pending comments of an article
+    async fn count_by_article(&self, article_id: i64) -> Result<CommentCounts>;
+
+    /// Oldest approved top-level comments of an article, without the like
+    /// lookups of [`CommentRepository::get_by_article`]
+    async fn list_thread_previews(
+        &self,
+        article_id: i64,
+        limit: i64,
+    ) -> Result<Vec<CommentPreview>>;
+
+    /// Insert an imported comment, keeping its original timestamp.
+    ///
+    /// Does not touch the article comment count; call
+    /// [`CommentRepository::sync_comment_count`] once the import is done.
+    async fn create_imported(
+        &self,
+        input: CreateCommentInput,
+        status: CommentStatus,
+        created_at: DateTime<Utc>,
+    ) -> Result<i64>;
+
+    /// Recalculate the approved comment count of an article.
+    async fn sync_comment_count(&self, article_id: i64) -> Result<()>;
+
+    /// Fetch a batch of comments for export, ordered by ID.
+    ///
+    /// Returns up to `limit` comments with an ID greater than `after_id`,
+    /// so callers can walk the whole result set without OFFSET scans.
+    async fn list_for_export(
+        &self,
+        filter: &CommentExportFilter,
+        after_id: i64,
+        limit: i64,
+    ) -> Result<Vec<CommentExportRecord>>;
+
+    /// Store a verified Webmention, replacing an earlier one from the same source.
+    ///
+    /// Returns the comment ID.
+    async fn upsert_webmention(
+        &self,
+        article_id: i64,
+        source_url: &str,
+        author: Option<String>,
+        content: &str,
+        status: CommentStatus,
+    ) -> Result<i64>;
+
+    /// Remove the Webmention from `source_url`, e.g. after the source dropped its link.
+    async fn delete_webmention(&self, article_id: i64, source_url: &str) -> Result<bool>;
+
+    /// Search comments for moderation, newest first.
+    ///
+    /// Returns the requested page and the total number of matches.
+    async fn search(
+        &self,
+        filter: &CommentSearchFilter,
+        page: i64,
+        per_page: i64,
+    ) -> Result<(Vec<Comment>, i64)>;
 }
 
 /// Comment repository implementation
@@ -206,6 +276,10 @@ impl CommentRepository for SqlxCommentRepository {
         dispatch!(self, increment_view, article_id)
     }
 
+    async fn add_views(&self, day: &str, views: &[(i64, i64)]) -> Result<()> {
+        dispatch!(self, add_views, day, views)
+    }
+
     async fn list_recent(&self, limit: i64) -> Result<Vec<CommentWithMeta>> {
         dispatch!(self, list_recent, limit)
     }
@@ -217,6 +291,336 @@ impl CommentRepository for SqlxCommentRepository {
     async fn count_pending(&self) -> Result<i64> {
         dispatch!(self, count_pending)
     }
+
+    async fn count_by_article(&self, article_id: i64) -> Result<CommentCounts> {
+        dispatch!(self, count_by_article, article_id)
+    }
+
+    async fn list_thread_previews(
+        &self,
+        article_id: i64,
+        limit: i64,
+    ) -> Result<Vec<CommentPreview>> {
+        dispatch!(self, list_thread_previews, article_id, limit)
+    }
+
+    async fn create_imported(
+        &self,
+        input: CreateCommentInput,
+        status: CommentStatus,
+        created_at: DateTime<Utc>,
+    ) -> Result<i64> {
+        dispatch!(self, create_imported, input, status, created_at)
+    }
+
+    async fn sync_comment_count(&self, article_id: i64) -> Result<()> {
+        dispatch!(self, sync_article_comment_count, article_id)
+    }
+
+    async fn list_for_export(
+        &self,
+        filter: &CommentExportFilter,
+        after_id: i64,
+        limit: i64,
+    ) -> Result<Vec<CommentExportRecord>> {
+        dispatch!(self, list_for_export, filter, after_id, limit)
+    }
+
+    async fn upsert_webmention(
+        &self,
+        article_id: i64,
+        source_url: &str,
+        author: Option<String>,
+        content: &str,
+        status: CommentStatus,
+    ) -> Result<i64> {
+        dispatch!(
+            self,
+            upsert_webmention,
+            article_id,
+            source_url,
+            author,
+            content,
+            status
+        )
+    }
+
+    async fn delete_webmention(&self, article_id: i64, source_url: &str) -> Result<bool> {
+        dispatch!(self, delete_webmention, article_id, source_url)
+    }
+
+    async fn search(
+        &self,
+        filter: &CommentSearchFilter,
+        page: i64,
+        per_page: i64,
+    ) -> Result<(Vec<Comment>, i64)> {
+        dispatch!(self, search, filter, page, per_page)
+    }
+}
+
+/// Build the WHERE clause for an export query; binds follow the order of
+/// `status`, `article_id`, `from`, `to`.
+fn export_where_clause(filter: &CommentExportFilter) -> String {
+    let mut clause = String::from("c.id > ?");
+    if filter.status.is_some() {
+        clause.push_str(" AND c.status = ?");
+    }
+    if filter.article_id.is_some() {
+        clause.push_str(" AND c.article_id = ?");
+    }
+    if filter.from.is_some() {
+        clause.push_str(" AND c.created_at >= ?");
+    }
+    if filter.to.is_some() {
+        clause.push_str(" AND c.created_at < ?");
+    }
+    clause
+}
+
+impl_dual_fn! {
+    async fn list_for_export(
+        pool,
+        filter: &CommentExportFilter,
+        after_id: i64,
+        limit: i64
+    ) -> Result<Vec<CommentExportRecord>> {
+        let sql = format!(
+            r#"SELECT c.id, c.article_id, a.slug AS article_slug, c.parent_id, c.user_id,
+                      c.nickname, c.email, c.content, c.status, c.ip_address, c.created_at
+               FROM comments c
+               LEFT JOIN articles a ON c.article_id = a.id
+               WHERE {}
+               ORDER BY c.id
+               LIMIT ?"#,
+            export_where_clause(filter)
+        );
+        let mut query = sqlx::query(&sql).bind(after_id);
+        if let Some(status) = &filter.status {
+            query = query.bind(status.to_string());
+        }
+        if let Some(article_id) = filter.article_id {
+            query = query.bind(article_id);
+        }
+        if let Some(from) = filter.from {
+            query = query.bind(from);
+        }
+        if let Some(to) = filter.to {
+            query = query.bind(to);
+        }
+        let rows = query
+            .bind(limit)
+            .fetch_all(pool)
+            .await
+            .context("Failed to list comments for export")?;
+
+        Ok(rows
+            .iter()
+            .map(|row| {
+                let status: String = row.get("status");
+                CommentExportRecord {
+                    id: row.get("id"),
+                    article_id: row.get("article_id"),
+                    article_slug: row.get("article_slug"),
+                    parent_id: row.get("parent_id"),
+                    user_id: row.get("user_id"),
+                    nickname: row.get("nickname"),
+                    email: row.get("email"),
+                    content: row.get("content"),
+                    status: status.parse().unwrap_or_default(),
+                    ip_address: row.get("ip_address"),
+                    created_at: row.get("created_at"),
+                }
+            })
+            .collect())
+    }
+}
+
+impl_dual_fn! {
+    async fn count_by_article(pool, article_id: i64) -> Result<CommentCounts> {
+        let row = sqlx::query(
+            "SELECT COUNT(CASE WHEN status = 'approved' THEN 1 END) AS approved, \
+             COUNT(CASE WHEN status = 'pending' THEN 1 END) AS pending \
+             FROM comments WHERE article_id = ?",
+        )
+        .bind(article_id)
+        .fetch_one(pool)
+        .await
+        .context("Failed to count article comments")?;
+        let approved: i64 = row.get("approved");
+        let pending: i64 = row.get("pending");
+        Ok(CommentCounts {
+            total: approved + pending,
+            approved,
+            pending,
+        })
+    }
+}
+
+impl_dual_fn! {
+    async fn list_thread_previews(pool, article_id: i64, limit: i64) -> Result<Vec<CommentPreview>> {
+        let rows = sqlx::query(
+            r#"SELECT c.id, c.user_id, c.nickname, c.email, c.content, c.comment_type, c.created_at,
+                      u.username, u.role AS user_role, u.avatar AS user_avatar,
+                      u.display_name AS user_display_name, a.author_id,
+                      (SELECT COUNT(*) FROM comments r
+                       WHERE r.parent_id = c.id AND r.status = 'approved') AS reply_count
+               FROM comments c
+               JOIN articles a ON a.id = c.article_id
+               LEFT JOIN users u ON c.user_id = u.id
+               WHERE c.article_id = ? AND c.status = 'approved' AND c.parent_id IS NULL
+               ORDER BY c.created_at ASC, c.id ASC
+               LIMIT ?"#,
+        )
+        .bind(article_id)
+        .bind(limit)
+        .fetch_all(pool)
+        .await
+        .context("Failed to list comment threads")?;
+
+        Ok(rows
+            .iter()
+            .map(|row| {
+                let email: Option<String> = row.get("email");
+                let user_id: Option<i64> = row.get("user_id");
+                let author_id: Option<i64> = row.get("author_id");
+                let user_role: Option<String> = row.try_get("user_role").ok().flatten();
+                let username: Option<String> = row.try_get("username").ok().flatten();
+                let user_display_name: Option<String> =
+                    row.try_get("user_display_name").ok().flatten();
+                let user_avatar: Option<String> = row.try_get("user_avatar").ok().flatten();
+                CommentPreview {
+                    id: row.get("id"),
+                    nickname: user_display_name.or(username).or(row.get("nickname")),
+                    content: row.get("content"),
+                    comment_type: row
+                        .get::<String, _>("comment_type")
+                        .parse()
+                        .unwrap_or_default(),
+                    created_at: row.get("created_at"),
+                    avatar_url: user_avatar
+                        .filter(|a| !a.is_empty())
+                        .unwrap_or_else(|| CommentWithMeta::gravatar_url(&email)),
+                    is_author: user_id.is_some()
+                        && (user_id == author_id || user_role.as_deref() == Some("admin")),
+                    reply_count: row.get("reply_count"),
+                }
+            })
+            .collect())
+    }
+}
+
+/// A driver-specific keyword condition and its bound value
+type KeywordMatch = Option<(&'static str, String)>;
+
+/// Keywords shorter than this fall back to `LIKE`, matching article search
+const MIN_FULLTEXT_KEYWORD_CHARS: usize = 2;
+
+/// Build the WHERE clause for a comment search; binds follow the order of
+/// keyword, `email`, `ip_address`, `status`.
+fn search_where_clause(filter: &CommentSearchFilter, keyword: &KeywordMatch) -> String {
+    let mut conditions = Vec::new();
+    if let Some((condition, _)) = keyword {
+        conditions.push(*condition);
+    }
+    if filter.email.is_some() {
+        conditions.push("LOWER(c.email) = LOWER(?)");
+    }
+    if filter.ip_address.is_some() {
+        conditions.push("c.ip_address = ?");
+    }
+    if filter.status.is_some() {
+        conditions.push("c.status = ?");
+    }
+    if conditions.is_empty() {
+        "1 = 1".to_string()
+    } else {
+        conditions.join(" AND ")
+    }
+}
+
+impl_dual_fn! {
+    async fn search_matching(
+        pool,
+        filter: &CommentSearchFilter,
+        keyword: KeywordMatch,
+        page: i64,
+        per_page: i64
+    ) -> Result<(Vec<Comment>, i64)> {
+        let where_clause = search_where_clause(filter, &keyword);
+        let count_sql = format!("SELECT COUNT(*) FROM comments c WHERE {}", where_clause);
+        let list_sql = format!(
+            "SELECT c.* FROM comments c WHERE {} ORDER BY c.created_at DESC, c.id DESC LIMIT ? OFFSET ?",
+            where_clause
+        );
+
+        let mut count_query = sqlx::query_scalar::<_, i64>(&count_sql);
+        let mut list_query = sqlx::query(&list_sql);
+        let binds = keyword
+            .map(|(_, value)| value)
+            .into_iter()
+            .chain(filter.email.clone())
+            .chain(filter.ip_address.clone())
+            .chain(filter.status.as_ref().map(|s| s.to_string()));
+        for value in binds {
+            count_query = count_query.bind(value.clone());
+            list_query = list_query.bind(value);
+        }
+
+        let total = count_query
+            .fetch_one(pool)
+            .await
+            .context("Failed to count comment search results")?;
+        let rows = list_query
+            .bind(per_page)
+            .bind((page - 1) * per_page)
+            .fetch_all(pool)
+            .await
+            .context("Failed to search comments")?;
+
+        let comments = rows
+            .iter()
+            .map(|r| Comment {
+                id: r.get("id"),
+                article_id: r.get("article_id"),
+                user_id: r.get("user_id"),
+                parent_id: r.get("parent_id"),
+                nickname: r.get("nickname"),
+                email: r.get("email"),
+                content: r.get("content"),
+                status: r.get::<String, _>("status").parse().unwrap_or_default(),
+                comment_type: r
+                    .get::<String, _>("comment_type")
+                    .parse()
+                    .unwrap_or_default(),
+                source_url: r.get("source_url"),
+                ip_address: r.get("ip_address"),
+                user_agent: r.get("user_agent"),
+                created_at: r.get("created_at"),
+                updated_at: r.get("updated_at"),
+            })
+            .collect();
+
+        Ok((comments, total))
+    }
+}
+
+/// Keyword condition for a search; `None` when no keyword was given.
+fn keyword_match(
+    filter: &CommentSearchFilter,
+    fulltext_condition: &'static str,
+    fulltext_value: impl FnOnce(&str) -> String,
+) -> KeywordMatch {
+    let keyword = filter
+        .keyword
+        .as_deref()
+        .map(str::trim)
+        .filter(|k| !k.is_empty())?;
+    if keyword.chars().count() >= MIN_FULLTEXT_KEYWORD_CHARS {
+        Some((fulltext_condition, fulltext_value(keyword)))
+    } else {
+        Some(("c.content LIKE ?", format!("%{}%", keyword)))
+    }
 }
 
 // SQLite implementations
@@ -266,6 +670,8 @@ async fn create_sqlite(
         email: input.email,
         content: input.content,
         status,
+        comment_type: CommentType::Comment,
+        source_url: None,
         ip_address: ip,
         user_agent: ua,
         created_at: now,
@@ -288,6 +694,11 @@ async fn get_by_id_sqlite(pool: &SqlitePool, id: i64) -> Result<Option<Comment>>
         email: r.get("email"),
         content: r.get("content"),
         status: r.get::<String, _>("status").parse().unwrap_or_default(),
+        comment_type: r
+            .get::<String, _>("comment_type")
+            .parse()
+            .unwrap_or_default(),
+        source_url: r.get("source_url"),
         ip_address: r.get("ip_address"),
         user_agent: r.get("user_agent"),
         created_at: r.get("created_at"),
@@ -362,6 +773,11 @@ async fn get_by_article_sqlite(
             email: email.clone(),
             content: row.get("content"),
             status: row.get::<String, _>("status").parse().unwrap_or_default(),
+            comment_type: row
+                .get::<String, _>("comment_type")
+                .parse()
+                .unwrap_or_default(),
+            source_url: row.get("source_url"),
             created_at: row.get("created_at"),
             avatar_url,
             like_count,
@@ -451,6 +867,11 @@ async fn list_pending_sqlite(
                 email: email.clone(),
                 content: row.get("content"),
                 status: row.get::<String, _>("status").parse().unwrap_or_default(),
+                comment_type: row
+                    .get::<String, _>("comment_type")
+                    .parse()
+                    .unwrap_or_default(),
+                source_url: row.get("source_url"),
                 created_at: row.get("created_at"),
                 avatar_url: CommentWithMeta::gravatar_url(&email),
                 like_count: 0,
@@ -532,6 +953,11 @@ async fn list_all_sqlite(
                 email: email.clone(),
                 content: row.get("content"),
                 status: row.get::<String, _>("status").parse().unwrap_or_default(),
+                comment_type: row
+                    .get::<String, _>("comment_type")
+                    .parse()
+                    .unwrap_or_default(),
+                source_url: row.get("source_url"),
                 created_at: row.get("created_at"),
                 avatar_url: CommentWithMeta::gravatar_url(&email),
                 like_count: 0,
@@ -546,15 +972,16 @@ async fn list_all_sqlite(
 }
 
 async fn list_recent_sqlite(pool: &SqlitePool, limit: i64) -> Result<Vec<CommentWithMeta>> {
-    let rows = sqlx::query(
+    let rows = sqlx::query(&format!(
         r#"SELECT c.*, u.username, a.title as article_title, a.slug as article_slug
            FROM comments c
            LEFT JOIN users u ON c.user_id = u.id
            INNER JOIN articles a ON c.article_id = a.id
-           WHERE c.status = 'approved' AND a.status = 'published'
+           WHERE c.status = 'approved' AND {}
            ORDER BY c.created_at DESC
            LIMIT ?"#,
-    )
+        public_article_sql("a.")
+    ))
     .bind(limit)
     .fetch_all(pool)
     .await?;
@@ -577,6 +1004,11 @@ async fn list_recent_sqlite(pool: &SqlitePool, limit: i64) -> Result<Vec<Comment
                 email: email.clone(),
                 content: row.get("content"),
                 status: row.get::<String, _>("status").parse().unwrap_or_default(),
+                comment_type: row
+                    .get::<String, _>("comment_type")
+                    .parse()
+                    .unwrap_or_default(),
+                source_url: row.get("source_url"),
                 created_at: row.get("created_at"),
                 avatar_url: CommentWithMeta::gravatar_url(&email),
                 like_count: 0,
@@ -604,6 +1036,117 @@ async fn count_pending_sqlite(pool: &SqlitePool) -> Result<i64> {
         .map_err(Into::into)
 }
 
+async fn create_imported_sqlite(
+    pool: &SqlitePool,
+    input: CreateCommentInput,
+    status: CommentStatus,
+    created_at: DateTime<Utc>,
+) -> Result<i64> {
+    let result = sqlx::query(
+        r#"INSERT INTO comments (article_id, parent_id, nickname, email, content, status, created_at, updated_at)
+           VALUES (?, ?, ?, ?, ?, ?, ?, ?)"#,
+    )
+    .bind(input.article_id)
+    .bind(input.parent_id)
+    .bind(&input.nickname)
+    .bind(&input.email)
+    .bind(&input.content)
+    .bind(status.to_string())
+    .bind(created_at)
+    .bind(created_at)
+    .execute(pool)
+    .await?;
+
+    Ok(result.last_insert_rowid())
+}
+
+async fn upsert_webmention_sqlite(
+    pool: &SqlitePool,
+    article_id: i64,
+    source_url: &str,
+    author: Option<String>,
+    content: &str,
+    status: CommentStatus,
+) -> Result<i64> {
+    let now = Utc::now();
+    let existing: Option<i64> = sqlx::query_scalar(
+        "SELECT id FROM comments WHERE article_id = ? AND source_url = ? AND comment_type = 'webmention'",
+    )
+    .bind(article_id)
+    .bind(source_url)
+    .fetch_optional(pool)
+    .await?;
+
+    let id = match existing {
+        Some(id) => {
+            // Re-sent mentions refresh the content but keep the moderation decision
+            sqlx::query(
+                "UPDATE comments SET nickname = ?, content = ?, updated_at = ? WHERE id = ?",
+            )
+            .bind(&author)
+            .bind(content)
+            .bind(now)
+            .bind(id)
+            .execute(pool)
+            .await?;
+            id
+        }
+        None => {
+            let result = sqlx::query(
+                r#"INSERT INTO comments (article_id, nickname, content, status, comment_type, source_url, created_at, updated_at)
+                   VALUES (?, ?, ?, ?, 'webmention', ?, ?, ?)"#,
+            )
+            .bind(article_id)
+            .bind(&author)
+            .bind(content)
+            .bind(status.to_string())
+            .bind(source_url)
+            .bind(now)
+            .bind(now)
+            .execute(pool)
+            .await?;
+            result.last_insert_rowid()
+        }
+    };
+
+    sync_article_comment_count_sqlite(pool, article_id).await?;
+    Ok(id)
+}
+
+async fn delete_webmention_sqlite(
+    pool: &SqlitePool,
+    article_id: i64,
+    source_url: &str,
+) -> Result<bool> {
+    let result = sqlx::query(
+        "DELETE FROM comments WHERE article_id = ? AND source_url = ? AND comment_type = 'webmention'",
+    )
+    .bind(article_id)
+    .bind(source_url)
+    .execute(pool)
+    .await?;
+
+    if result.rows_affected() > 0 {
+        sync_article_comment_count_sqlite(pool, article_id).await?;
+        return Ok(true);
+    }
+    Ok(false)
+}
+
+async fn search_sqlite(
+    pool: &SqlitePool,
+    filter: &CommentSearchFilter,
+    page: i64,
+    per_page: i64,
+) -> Result<(Vec<Comment>, i64)> {
+    let keyword = keyword_match(
+        filter,
+        "c.id IN (SELECT rowid FROM comments_fts WHERE comments_fts MATCH ?)",
+        |k| format!("\"{}\"", k.replace('"', "\"\"")),
+    );
+    search_matching_sqlite(pool, filter, keyword, page, per_page).await
+}
+
 async fn sync_article_comment_count_sqlite(pool: &SqlitePool, article_id: i64) -> Result<()> {
     sqlx::query(
         r#"UPDATE articles
@@ -803,6 +1346,39 @@ async fn increment_view_sqlite(pool: &SqlitePool, article_id: i64) -> Result<()>
     if result.rows_affected() == 0 {
         anyhow::bail!("Article not found: {}", article_id);
     }
+    sqlx::query(
+        "INSERT INTO article_views_daily (article_id, day, views) VALUES (?, ?, 1) ON CONFLICT(article_id, day) DO UPDATE SET views = views + 1",
+    )
+    .bind(article_id)
+    .bind(Utc::now().format("%Y-%m-%d").to_string())
+    .execute(pool)
+    .await?;
+    Ok(())
+}
+
+async fn add_views_sqlite(pool: &SqlitePool, day: &str, views: &[(i64, i64)]) -> Result<()> {
+    let mut tx = pool.begin().await.context("Failed to start transaction")?;
+    for &(article_id, count) in views {
+        let result = sqlx::query("UPDATE articles SET view_count = view_count + ? WHERE id = ?")
+            .bind(count)
+            .bind(article_id)
+            .execute(&mut *tx)
+            .await
+            .context("Failed to add article views")?;
+        if result.rows_affected() == 0 {
+            continue;
+        }
+        sqlx::query(
+            "INSERT INTO article_views_daily (article_id, day, views) VALUES (?, ?, ?) ON CONFLICT(article_id, day) DO UPDATE SET views = views + excluded.views",
+        )
+        .bind(article_id)
+        .bind(day)
+        .bind(count)
+        .execute(&mut *tx)
+        .await
+        .context("Failed to add daily article views")?;
+    }
+    tx.commit().await.context("Failed to commit transaction")?;
     Ok(())
 }
 
@@ -875,6 +1451,8 @@ async fn create_mysql(
         email: input.email,
         content: input.content,
         status,
+        comment_type: CommentType::Comment,
+        source_url: None,
         ip_address: ip,
         user_agent: ua,
         created_at: now,
@@ -897,6 +1475,11 @@ async fn get_by_id_mysql(pool: &MySqlPool, id: i64) -> Result<Option<Comment>> {
         email: r.get("email"),
         content: r.get("content"),
         status: r.get::<String, _>("status").parse().unwrap_or_default(),
+        comment_type: r
+            .get::<String, _>("comment_type")
+            .parse()
+            .unwrap_or_default(),
+        source_url: r.get("source_url"),
         ip_address: r.get("ip_address"),
         user_agent: r.get("user_agent"),
         created_at: r.get("created_at"),
@@ -965,6 +1548,11 @@ async fn get_by_article_mysql(
             email: email.clone(),
             content: row.get("content"),
             status: row.get::<String, _>("status").parse().unwrap_or_default(),
+            comment_type: row
+                .get::<String, _>("comment_type")
+                .parse()
+                .unwrap_or_default(),
+            source_url: row.get("source_url"),
             created_at: row.get("created_at"),
             avatar_url: CommentWithMeta::gravatar_url(&email),
             like_count,
@@ -1028,6 +1616,11 @@ async fn list_pending_mysql(
                 email: email.clone(),
                 content: row.get("content"),
                 status: row.get::<String, _>("status").parse().unwrap_or_default(),
+                comment_type: row
+                    .get::<String, _>("comment_type")
+                    .parse()
+                    .unwrap_or_default(),
+                source_url: row.get("source_url"),
                 created_at: row.get("created_at"),
                 avatar_url: CommentWithMeta::gravatar_url(&email),
                 like_count: 0,
@@ -1108,6 +1701,11 @@ async fn list_all_mysql(
                 email: email.clone(),
                 content: row.get("content"),
                 status: row.get::<String, _>("status").parse().unwrap_or_default(),
+                comment_type: row
+                    .get::<String, _>("comment_type")
+                    .parse()
+                    .unwrap_or_default(),
+                source_url: row.get("source_url"),
                 created_at: row.get("created_at"),
                 avatar_url: CommentWithMeta::gravatar_url(&email),
                 like_count: 0,
@@ -1122,15 +1720,16 @@ async fn list_all_mysql(
 }
 
 async fn list_recent_mysql(pool: &MySqlPool, limit: i64) -> Result<Vec<CommentWithMeta>> {
-    let rows = sqlx::query(
+    let rows = sqlx::query(&format!(
         r#"SELECT c.*, u.username, a.title as article_title, a.slug as article_slug
            FROM comments c
            LEFT JOIN users u ON c.user_id = u.id
            INNER JOIN articles a ON c.article_id = a.id
-           WHERE c.status = 'approved' AND a.status = 'published'
+           WHERE c.status = 'approved' AND {}
            ORDER BY c.created_at DESC
            LIMIT ?"#,
-    )
+        public_article_sql("a.")
+    ))
     .bind(limit)
     .fetch_all(pool)
     .await?;
@@ -1153,6 +1752,11 @@ async fn list_recent_mysql(pool: &MySqlPool, limit: i64) -> Result<Vec<CommentWi
                 email: email.clone(),
                 content: row.get("content"),
                 status: row.get::<String, _>("status").parse().unwrap_or_default(),
+                comment_type: row
+                    .get::<String, _>("comment_type")
+                    .parse()
+                    .unwrap_or_default(),
+                source_url: row.get("source_url"),
                 created_at: row.get("created_at"),
                 avatar_url: CommentWithMeta::gravatar_url(&email),
                 like_count: 0,
@@ -1180,6 +1784,117 @@ async fn count_pending_mysql(pool: &MySqlPool) -> Result<i64> {
         .map_err(Into::into)
 }
 
+async fn create_imported_mysql(
+    pool: &MySqlPool,
+    input: CreateCommentInput,
+    status: CommentStatus,
+    created_at: DateTime<Utc>,
+) -> Result<i64> {
+    let result = sqlx::query(
+        r#"INSERT INTO comments (article_id, parent_id, nickname, email, content, status, created_at, updated_at)
+           VALUES (?, ?, ?, ?, ?, ?, ?, ?)"#,
+    )
+    .bind(input.article_id)
+    .bind(input.parent_id)
+    .bind(&input.nickname)
+    .bind(&input.email)
+    .bind(&input.content)
+    .bind(status.to_string())
+    .bind(created_at)
+    .bind(created_at)
+    .execute(pool)
+    .await?;
+
+    Ok(result.last_insert_id() as i64)
+}
+
+async fn upsert_webmention_mysql(
+    pool: &MySqlPool,
+    article_id: i64,
+    source_url: &str,
+    author: Option<String>,
+    content: &str,
+    status: CommentStatus,
+) -> Result<i64> {
+    let now = Utc::now();
+    let existing: Option<i64> = sqlx::query_scalar(
+        "SELECT id FROM comments WHERE article_id = ? AND source_url = ? AND comment_type = 'webmention'",
+    )
+    .bind(article_id)
+    .bind(source_url)
+    .fetch_optional(pool)
+    .await?;
+
+    let id = match existing {
+        Some(id) => {
+            // Re-sent mentions refresh the content but keep the moderation decision
+            sqlx::query(
+                "UPDATE comments SET nickname = ?, content = ?, updated_at = ? WHERE id = ?",
+            )
+            .bind(&author)
+            .bind(content)
+            .bind(now)
+            .bind(id)
+            .execute(pool)
+            .await?;
+            id
+        }
+        None => {
+            let result = sqlx::query(
+                r#"INSERT INTO comments (article_id, nickname, content, status, comment_type, source_url, created_at, updated_at)
+                   VALUES (?, ?, ?, ?, 'webmention', ?, ?, ?)"#,
+            )
+            .bind(article_id)
+            .bind(&author)
+            .bind(content)
+            .bind(status.to_string())
+            .bind(source_url)
+            .bind(now)
+            .bind(now)
+            .execute(pool)
+            .await?;
+            result.last_insert_id() as i64
+        }
+    };
+
+    sync_article_comment_count_mysql(pool, article_id).await?;
+    Ok(id)
+}
+
+async fn delete_webmention_mysql(
+    pool: &MySqlPool,
+    article_id: i64,
+    source_url: &str,
+) -> Result<bool> {
+    let result = sqlx::query(
+        "DELETE FROM comments WHERE article_id = ? AND source_url = ? AND comment_type = 'webmention'",
+    )
+    .bind(article_id)
+    .bind(source_url)
+    .execute(pool)
+    .await?;
+
+    if result.rows_affected() > 0 {
+        sync_article_comment_count_mysql(pool, article_id).await?;
+        return Ok(true);
+    }
+    Ok(false)
+}
+
+async fn search_mysql(
+    pool: &MySqlPool,
+    filter: &CommentSearchFilter,
+    page: i64,
+    per_page: i64,
+) -> Result<(Vec<Comment>, i64)> {
+    let keyword = keyword_match(
+        filter,
+        "MATCH(c.content, c.nickname) AGAINST(? IN BOOLEAN MODE)",
+        |k| format!("\"{}\"", k.replace('"', "")),
+    );
+    search_matching_mysql(pool, filter, keyword, page, per_page).await
+}
+
 async fn sync_article_comment_count_mysql(pool: &MySqlPool, article_id: i64) -> Result<()> {
     sqlx::query(
         r#"UPDATE articles
@@ -1377,6 +2092,39 @@ async fn increment_view_mysql(pool: &MySqlPool, article_id: i64) -> Result<()> {
     if result.rows_affected() == 0 {
         anyhow::bail!("Article not found: {}", article_id);
     }
+    sqlx::query(
+        "INSERT INTO article_views_daily (article_id, day, views) VALUES (?, ?, 1) ON DUPLICATE KEY UPDATE views = views + 1",
+    )
+    .bind(article_id)
+    .bind(Utc::now().format("%Y-%m-%d").to_string())
+    .execute(pool)
+    .await?;
+    Ok(())
+}
+
+async fn add_views_mysql(pool: &MySqlPool, day: &str, views: &[(i64, i64)]) -> Result<()> {
+    let mut tx = pool.begin().await.context("Failed to start transaction")?;
+    for &(article_id, count) in views {
+        let result = sqlx::query("UPDATE articles SET view_count = view_count + ? WHERE id = ?")
+            .bind(count)
+            .bind(article_id)
+            .execute(&mut *tx)
+            .await
+            .context("Failed to add article views")?;
+        if result.rows_affected() == 0 {
+            continue;
+        }
+        sqlx::query(
+            "INSERT INTO article_views_daily (article_id, day, views) VALUES (?, ?, ?) ON DUPLICATE KEY UPDATE views = views + VALUES(views)",
+        )
+        .bind(article_id)
+        .bind(day)
+        .bind(count)
+        .execute(&mut *tx)
+        .await
+        .context("Failed to add daily article views")?;
+    }
+    tx.commit().await.context("Failed to commit transaction")?;
     Ok(())
 }
 
@@ -1401,3 +2149,306 @@ async fn like_target_exists_mysql(
     };
     Ok(count > 0)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::{create_test_pool, migrations};
+    use chrono::TimeZone;
+
+    async fn setup() -> (SqlxCommentRepository, i64) {
+        let pool = create_test_pool()
+            .await
+            .expect("Failed to create test pool");
+        migrations::run_migrations(&pool)
+            .await
+            .expect("Failed to run migrations");
+        let sqlite = pool.as_sqlite().unwrap();
+        let user_id = sqlx::query(
+            "INSERT INTO users (username, email, password_hash, role) VALUES ('u', 'u@example.com', 'x', 'admin')",
+        )
+        .execute(sqlite)
+        .await
+        .unwrap()
+        .last_insert_rowid();
+        let article_id = sqlx::query(
+            "INSERT INTO articles (slug, title, content, content_html, author_id, category_id, status) VALUES ('post', 'Post', 'c', 'c', ?, 1, 'published')",
+        )
+        .bind(user_id)
+        .execute(sqlite)
+        .await
+        .unwrap()
+        .last_insert_rowid();
+        (SqlxCommentRepository::new(pool), article_id)
+    }
+
+    fn input(article_id: i64, parent_id: Option<i64>, content: &str) -> CreateCommentInput {
+        CreateCommentInput {
+            article_id,
+            parent_id,
+            nickname: Some("Alice".to_string()),
+            email: None,
+            content: content.to_string(),
+        }
+    }
+
+    #[tokio::test]
+    async fn imported_comments_keep_timestamps_and_update_count() {
+        let (repo, article_id) = setup().await;
+        let created_at = Utc.with_ymd_and_hms(2015, 3, 1, 10, 0, 0).unwrap();
+
+        let parent = repo
+            .create_imported(
+                input(article_id, None, "first"),
+                CommentStatus::Approved,
+                created_at,
+            )
+            .await
+            .unwrap();
+        repo.create_imported(
+            input(article_id, Some(parent), "spam"),
+            CommentStatus::Spam,
+            created_at,
+        )
+        .await
+        .unwrap();
+        repo.sync_comment_count(article_id).await.unwrap();
+
+        let stored = repo.get_by_id(parent).await.unwrap().unwrap();
+        assert_eq!(stored.created_at, created_at);
+        let count: i64 = sqlx::query_scalar("SELECT comment_count FROM articles WHERE id = ?")
+            .bind(article_id)
+            .fetch_one(repo.pool.as_sqlite().unwrap())
+            .await
+            .unwrap();
+        assert_eq!(count, 1);
+    }
+
+    #[tokio::test]
+    async fn article_counts_skip_spam() {
+        let (repo, article_id) = setup().await;
+        for status in [
+            CommentStatus::Approved,
+            CommentStatus::Approved,
+            CommentStatus::Pending,
+            CommentStatus::Spam,
+        ] {
+            repo.create_imported(input(article_id, None, "hi"), status, Utc::now())
+                .await
+                .unwrap();
+        }
+
+        let counts = repo.count_by_article(article_id).await.unwrap();
+        assert_eq!(
+            counts,
+            CommentCounts {
+                total: 3,
+                approved: 2,
+                pending: 1
+            }
+        );
+        assert_eq!(
+            repo.count_by_article(article_id + 1).await.unwrap(),
+            CommentCounts::default()
+        );
+    }
+
+    #[tokio::test]
+    async fn batched_views_skip_missing_articles() {
+        let (repo, article_id) = setup().await;
+        repo.add_views("2024-05-01", &[(article_id, 3), (article_id + 1, 5)])
+            .await
+            .unwrap();
+        repo.add_views("2024-05-01", &[(article_id, 2)])
+            .await
+            .unwrap();
+
+        let sqlite = repo.pool.as_sqlite().unwrap();
+        let total: i64 = sqlx::query_scalar("SELECT view_count FROM articles WHERE id = ?")
+            .bind(article_id)
+            .fetch_one(sqlite)
+            .await
+            .unwrap();
+        assert_eq!(total, 5);
+        let daily: Vec<(i64, i64)> =
+            sqlx::query_as("SELECT article_id, views FROM article_views_daily WHERE day = ?")
+                .bind("2024-05-01")
+                .fetch_all(sqlite)
+                .await
+                .unwrap();
+        assert_eq!(daily, vec![(article_id, 5)]);
+    }
+
+    #[tokio::test]
+    async fn thread_previews_list_top_level_comments() {
+        let (repo, article_id) = setup().await;
+        let at = |minute| Utc.with_ymd_and_hms(2024, 1, 1, 12, minute, 0).unwrap();
+        let first = repo
+            .create_imported(
+                input(article_id, None, "first"),
+                CommentStatus::Approved,
+                at(0),
+            )
+            .await
+            .unwrap();
+        for (parent, status, minute) in [
+            (Some(first), CommentStatus::Approved, 1),
+            (Some(first), CommentStatus::Pending, 2),
+            (None, CommentStatus::Approved, 3),
+            (None, CommentStatus::Spam, 4),
+            (None, CommentStatus::Approved, 5),
+        ] {
+            repo.create_imported(input(article_id, parent, "hi"), status, at(minute))
+                .await
+                .unwrap();
+        }
+
+        let previews = repo.list_thread_previews(article_id, 2).await.unwrap();
+        assert_eq!(previews.len(), 2);
+        assert_eq!(previews[0].id, first);
+        assert_eq!(previews[0].reply_count, 1);
+        assert_eq!(previews[0].nickname.as_deref(), Some("Alice"));
+        assert_eq!(previews[1].created_at, at(3));
+        assert_eq!(
+            repo.list_thread_previews(article_id, 10)
+                .await
+                .unwrap()
+                .len(),
+            3
+        );
+    }
+
+    #[tokio::test]
+    async fn export_applies_filters_and_keyset_pagination() {
+        let (repo, article_id) = setup().await;
+        for (day, status) in [
+            (1, CommentStatus::Approved),
+            (2, CommentStatus::Pending),
+            (3, CommentStatus::Approved),
+        ] {
+            repo.create_imported(
+                input(article_id, None, &format!("day {}", day)),
+                status,
+                Utc.with_ymd_and_hms(2024, 1, day, 12, 0, 0).unwrap(),
+            )
+            .await
+            .unwrap();
+        }
+
+        let all = repo
+            .list_for_export(&CommentExportFilter::default(), 0, 2)
+            .await
+            .unwrap();
+        assert_eq!(all.len(), 2);
+        assert_eq!(all[0].article_slug.as_deref(), Some("post"));
+        let rest = repo
+            .list_for_export(&CommentExportFilter::default(), all[1].id, 2)
+            .await
+            .unwrap();
+        assert_eq!(rest.len(), 1);
+
+        let filter = CommentExportFilter {
+            status: Some(CommentStatus::Approved),
+            from: Some(Utc.with_ymd_and_hms(2024, 1, 2, 0, 0, 0).unwrap()),
+            ..Default::default()
+        };
+        let filtered = repo.list_for_export(&filter, 0, 10).await.unwrap();
+        assert_eq!(filtered.len(), 1);
+        assert_eq!(filtered[0].content, "day 3");
+    }
+
+    #[tokio::test]
+    async fn webmentions_are_upserted_per_source() {
+        let (repo, article_id) = setup().await;
+        let source = "https://other.example.com/reply";
+
+        let id = repo
+            .upsert_webmention(
+                article_id,
+                source,
+                Some("other.example.com".to_string()),
+                "First title",
+                CommentStatus::Pending,
+            )
+            .await
+            .unwrap();
+        repo.update_status(id, CommentStatus::Approved)
+            .await
+            .unwrap();
+        let again = repo
+            .upsert_webmention(
+                article_id,
+                source,
+                None,
+                "New title",
+                CommentStatus::Pending,
+            )
+            .await
+            .unwrap();
+        assert_eq!(again, id);
+
+        let stored = repo.get_by_id(id).await.unwrap().unwrap();
+        assert_eq!(stored.comment_type, CommentType::Webmention);
+        assert_eq!(stored.source_url.as_deref(), Some(source));
+        assert_eq!(stored.content, "New title");
+        assert_eq!(stored.status, CommentStatus::Approved);
+
+        assert!(repo.delete_webmention(article_id, source).await.unwrap());
+        assert!(repo.get_by_id(id).await.unwrap().is_none());
+        assert!(!repo.delete_webmention(article_id, source).await.unwrap());
+    }
+
+    #[tokio::test]
+    async fn search_combines_keyword_email_ip_and_status() {
+        let (repo, article_id) = setup().await;
+        let mut spam = input(article_id, None, "Buy cheap watches now");
+        spam.email = Some("Spammer@example.com".to_string());
+        for (input, ip, status) in [
+            (spam, "203.0.113.9", CommentStatus::Pending),
+            (
+                input(article_id, None, "cheap trick"),
+                "203.0.113.9",
+                CommentStatus::Approved,
+            ),
+            (
+                input(article_id, None, "Great post"),
+                "198.51.100.1",
+                CommentStatus::Approved,
+            ),
+        ] {
+            repo.create_with_status(input, None, Some(ip.to_string()), None, status)
+                .await
+                .unwrap();
+        }
+
+        let search = |filter: CommentSearchFilter| {
+            let repo = &repo;
+            async move { repo.search(&filter, 1, 10).await.unwrap() }
+        };
+
+        let (hits, total) = search(CommentSearchFilter {
+            keyword: Some("cheap".to_string()),
+            ..Default::default()
+        })
+        .await;
+        assert_eq!((hits.len(), total), (2, 2));
+
+        let (hits, _) = search(CommentSearchFilter {
+            email: Some("spammer@EXAMPLE.com".to_string()),
+            ..Default::default()
+        })
+        .await;
+        assert_eq!(hits[0].content, "Buy cheap watches now");
+
+        let (_, total) = search(CommentSearchFilter {
+            ip_address: Some("203.0.113.9".to_string()),
+            status: Some(CommentStatus::Approved),
+            ..Default::default()
+        })
+        .await;
+        assert_eq!(total, 1);
+
+        let (_, total) = search(CommentSearchFilter::default()).await;
+        assert_eq!(total, 3);
+    }
+}
@@ -0,0 +1,280 @@
+//! Custom fields repository.
+//!
+//! Values arrive already encoded by their type; rows are decoded back into
+//! JSON values on the way out.
+
+use crate::db::DynDatabasePool;
+use crate::models::{ContentKind, CustomField, CustomFieldType};
+use anyhow::{Context, Result};
+use async_trait::async_trait;
+use sqlx::{MySqlPool, SqlitePool};
+use std::sync::Arc;
+
+/// A field ready to store: key, type and the value encoded as text
+pub type StoredField = (String, CustomFieldType, String);
+
+#[async_trait]
+pub trait CustomFieldRepository: Send + Sync {
+    /// Fields of the given articles or pages as `(content_id, field)`
+    /// pairs, ordered by key
+    async fn list_for(&self, kind: ContentKind, ids: &[i64]) -> Result<Vec<(i64, CustomField)>>;
+    /// Replace all fields of an article or page
+    async fn replace(&self, kind: ContentKind, id: i64, fields: &[StoredField]) -> Result<()>;
+    /// Add or overwrite one field
+    async fn upsert(&self, kind: ContentKind, id: i64, field: &StoredField) -> Result<()>;
+    /// Remove one field; returns whether it existed
+    async fn delete(&self, kind: ContentKind, id: i64, key: &str) -> Result<bool>;
+    /// Drop all fields of a deleted article or page
+    async fn remove_content(&self, kind: ContentKind, id: i64) -> Result<()>;
+}
+
+pub struct SqlxCustomFieldRepository {
+    pool: DynDatabasePool,
+}
+
+impl SqlxCustomFieldRepository {
+    pub fn new(pool: DynDatabasePool) -> Self {
+        Self { pool }
+    }
+
+    pub fn boxed(pool: DynDatabasePool) -> Arc<dyn CustomFieldRepository> {
+        Arc::new(Self::new(pool))
+    }
+}
+
+#[async_trait]
+impl CustomFieldRepository for SqlxCustomFieldRepository {
+    async fn list_for(&self, kind: ContentKind, ids: &[i64]) -> Result<Vec<(i64, CustomField)>> {
+        if ids.is_empty() {
+            return Ok(Vec::new());
+        }
+        dispatch!(self, list_for, kind.as_str(), ids)
+    }
+
+    async fn replace(&self, kind: ContentKind, id: i64, fields: &[StoredField]) -> Result<()> {
+        dispatch!(self, replace, kind.as_str(), id, fields)
+    }
+
+    async fn upsert(&self, kind: ContentKind, id: i64, field: &StoredField) -> Result<()> {
+        dispatch!(self, upsert, kind.as_str(), id, field)
+    }
+
+    async fn delete(&self, kind: ContentKind, id: i64, key: &str) -> Result<bool> {
+        dispatch!(self, delete, kind.as_str(), id, key)
+    }
+
+    async fn remove_content(&self, kind: ContentKind, id: i64) -> Result<()> {
+        dispatch!(self, remove_content, kind.as_str(), id)
+    }
+}
+
+// ============================================================================
+// Shared implementations (identical SQL)
+// ============================================================================
+
+impl_dual_fn! {
+    async fn list_for(pool, kind: &str, ids: &[i64]) -> Result<Vec<(i64, CustomField)>> {
+        let placeholders = vec!["?"; ids.len()].join(", ");
+        let sql = format!(
+            "SELECT content_id, field_key, field_type, value FROM custom_fields \
+             WHERE content_type = ? AND content_id IN ({}) ORDER BY content_id, field_key",
+            placeholders
+        );
+        let mut query = sqlx::query(&sql).bind(kind);
+        for id in ids {
+            query = query.bind(id);
+        }
+        let rows = query
+            .fetch_all(pool)
+            .await
+            .context("Failed to list custom fields")?;
+        Ok(rows.iter().map(row_to_field).collect())
+    }
+}
+
+impl_dual_fn! {
+    async fn replace(pool, kind: &str, id: i64, fields: &[StoredField]) -> Result<()> {
+        let mut tx = pool.begin().await.context("Failed to start transaction")?;
+        sqlx::query("DELETE FROM custom_fields WHERE content_type = ? AND content_id = ?")
+            .bind(kind)
+            .bind(id)
+            .execute(&mut *tx)
+            .await
+            .context("Failed to clear custom fields")?;
+        for (key, field_type, value) in fields {
+            sqlx::query(
+                "INSERT INTO custom_fields (content_type, content_id, field_key, field_type, value) VALUES (?, ?, ?, ?, ?)",
+            )
+            .bind(kind)
+            .bind(id)
+            .bind(key)
+            .bind(field_type.as_str())
+            .bind(value)
+            .execute(&mut *tx)
+            .await
+            .context("Failed to add custom field")?;
+        }
+        tx.commit().await.context("Failed to commit transaction")?;
+        Ok(())
+    }
+}
+
+impl_dual_fn! {
+    async fn delete(pool, kind: &str, id: i64, key: &str) -> Result<bool> {
+        let result = sqlx::query(
+            "DELETE FROM custom_fields WHERE content_type = ? AND content_id = ? AND field_key = ?",
+        )
+        .bind(kind)
+        .bind(id)
+        .bind(key)
+        .execute(pool)
+        .await
+        .context("Failed to delete custom field")?;
+        Ok(result.rows_affected() > 0)
+    }
+}
+
+impl_dual_fn! {
+    async fn remove_content(pool, kind: &str, id: i64) -> Result<()> {
+        sqlx::query("DELETE FROM custom_fields WHERE content_type = ? AND content_id = ?")
+            .bind(kind)
+            .bind(id)
+            .execute(pool)
+            .await
+            .context("Failed to remove custom fields")?;
+        Ok(())
+    }
+}
+
+// ============================================================================
+// Dialect-specific upserts
+// ============================================================================
+
+async fn upsert_sqlite(pool: &SqlitePool, kind: &str, id: i64, field: &StoredField) -> Result<()> {
+    let (key, field_type, value) = field;
+    sqlx::query(
+        r#"INSERT INTO custom_fields (content_type, content_id, field_key, field_type, value, updated_at)
+           VALUES (?, ?, ?, ?, ?, CURRENT_TIMESTAMP)
+           ON CONFLICT(content_type, content_id, field_key) DO UPDATE SET
+               field_type = excluded.field_type,
+               value = excluded.value,
+               updated_at = CURRENT_TIMESTAMP"#,
+    )
+    .bind(kind)
+    .bind(id)
+    .bind(key)
+    .bind(field_type.as_str())
+    .bind(value)
+    .execute(pool)
+    .await
+    .context("Failed to save custom field")?;
+    Ok(())
+}
+
+async fn upsert_mysql(pool: &MySqlPool, kind: &str, id: i64, field: &StoredField) -> Result<()> {
+    let (key, field_type, value) = field;
+    sqlx::query(
+        r#"INSERT INTO custom_fields (content_type, content_id, field_key, field_type, value)
+           VALUES (?, ?, ?, ?, ?)
+           ON DUPLICATE KEY UPDATE field_type = VALUES(field_type), value = VALUES(value),
+               updated_at = CURRENT_TIMESTAMP"#,
+    )
+    .bind(kind)
+    .bind(id)
+    .bind(key)
+    .bind(field_type.as_str())
+    .bind(value)
+    .execute(pool)
+    .await
+    .context("Failed to save custom field")?;
+    Ok(())
+}
+
+fn row_to_field<'r, R>(row: &'r R) -> (i64, CustomField)
+where
+    R: sqlx::Row,
+    usize: sqlx::ColumnIndex<R>,
+    String: sqlx::Decode<'r, R::Database> + sqlx::Type<R::Database>,
+    i64: sqlx::Decode<'r, R::Database> + sqlx::Type<R::Database>,
+{
+    let field_type = CustomFieldType::parse(&row.get::<String, _>(2)).unwrap_or_default();
+    let value: String = row.get(3);
+    let field = CustomField {
+        key: row.get(1),
+        field_type: Some(field_type),
+        value: field_type.decode(&value),
+    };
+    (row.get(0), field)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::{create_test_pool, migrations};
+    use serde_json::json;
+
+    fn stored(key: &str, field_type: CustomFieldType, value: &str) -> StoredField {
+        (key.to_string(), field_type, value.to_string())
+    }
+
+    #[tokio::test]
+    async fn fields_are_scoped_by_content_and_typed() {
+        let pool = create_test_pool()
+            .await
+            .expect("Failed to create test pool");
+        migrations::run_migrations(&pool)
+            .await
+            .expect("Failed to run migrations");
+        let repo = SqlxCustomFieldRepository::new(pool);
+
+        repo.replace(
+            ContentKind::Article,
+            1,
+            &[
+                stored("rating", CustomFieldType::Number, "4.5"),
+                stored("mood", CustomFieldType::Text, "calm"),
+            ],
+        )
+        .await
+        .unwrap();
+        repo.upsert(
+            ContentKind::Page,
+            1,
+            &stored("hero", CustomFieldType::Boolean, "true"),
+        )
+        .await
+        .unwrap();
+        repo.upsert(
+            ContentKind::Article,
+            1,
+            &stored("mood", CustomFieldType::Json, r#"["calm","warm"]"#),
+        )
+        .await
+        .unwrap();
+
+        let fields = repo.list_for(ContentKind::Article, &[1, 2]).await.unwrap();
+        let values: Vec<(&str, &serde_json::Value)> = fields
+            .iter()
+            .map(|(_, f)| (f.key.as_str(), &f.value))
+            .collect();
+        assert_eq!(
+            values,
+            [("mood", &json!(["calm", "warm"])), ("rating", &json!(4.5))]
+        );
+        let page = repo.list_for(ContentKind::Page, &[1]).await.unwrap();
+        assert_eq!(page[0].1.value, json!(true));
+
+        assert!(repo.delete(ContentKind::Article, 1, "mood").await.unwrap());
+        assert!(!repo.delete(ContentKind::Article, 1, "mood").await.unwrap());
+        repo.remove_content(ContentKind::Article, 1).await.unwrap();
+        assert!(repo
+            .list_for(ContentKind::Article, &[1])
+            .await
+            .unwrap()
+            .is_empty());
+        assert_eq!(
+            repo.list_for(ContentKind::Page, &[1]).await.unwrap().len(),
+            1
+        );
+    }
+}
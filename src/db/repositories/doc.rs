@@ -0,0 +1,548 @@
+//! Documentation repository.
+
+use crate::db::DynDatabasePool;
+use crate::models::{DocPage, DocVersion};
+use anyhow::{Context, Result};
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use sqlx::{MySqlPool, SqlitePool};
+use std::sync::Arc;
+
+const VERSION_COLUMNS: &str = "id, slug, title, position, is_latest, created_at, updated_at";
+const PAGE_COLUMNS: &str =
+    "id, version_id, parent_id, slug, title, content, content_html, position, created_at, updated_at";
+
+#[async_trait]
+pub trait DocRepository: Send + Sync {
+    /// All versions, in switcher order
+    async fn list_versions(&self) -> Result<Vec<DocVersion>>;
+    async fn get_version(&self, id: i64) -> Result<Option<DocVersion>>;
+    async fn get_version_by_slug(&self, slug: &str) -> Result<Option<DocVersion>>;
+    /// Insert a version; a latest version unmarks the previous one
+    async fn create_version(&self, version: &DocVersion) -> Result<DocVersion>;
+    /// Save all fields of a version; a latest version unmarks the others
+    async fn update_version(&self, version: &DocVersion) -> Result<DocVersion>;
+    /// Delete a version with all its pages
+    async fn delete_version(&self, id: i64) -> Result<bool>;
+
+    /// Pages of a version, by position
+    async fn list_pages(&self, version_id: i64) -> Result<Vec<DocPage>>;
+    async fn get_page(&self, id: i64) -> Result<Option<DocPage>>;
+    async fn get_page_by_slug(&self, version_id: i64, slug: &str) -> Result<Option<DocPage>>;
+    /// Ids of the versions that have a page with this slug
+    async fn versions_with_page(&self, slug: &str) -> Result<Vec<i64>>;
+    async fn create_page(&self, page: &DocPage) -> Result<DocPage>;
+    async fn update_page(&self, page: &DocPage) -> Result<DocPage>;
+    /// Delete a page; its children move up to its parent
+    async fn delete_page(&self, id: i64) -> Result<bool>;
+}
+
+pub struct SqlxDocRepository {
+    pool: DynDatabasePool,
+}
+
+impl SqlxDocRepository {
+    pub fn new(pool: DynDatabasePool) -> Self {
+        Self { pool }
+    }
+
+    pub fn boxed(pool: DynDatabasePool) -> Arc<dyn DocRepository> {
+        Arc::new(Self::new(pool))
+    }
+}
+
+#[async_trait]
+impl DocRepository for SqlxDocRepository {
+    async fn list_versions(&self) -> Result<Vec<DocVersion>> {
+        dispatch!(self, list_versions)
+    }
+
+    async fn get_version(&self, id: i64) -> Result<Option<DocVersion>> {
+        dispatch!(self, get_version, id)
+    }
+
+    async fn get_version_by_slug(&self, slug: &str) -> Result<Option<DocVersion>> {
+        dispatch!(self, get_version_by_slug, slug)
+    }
+
+    async fn create_version(&self, version: &DocVersion) -> Result<DocVersion> {
+        dispatch!(self, create_version, version)
+    }
+
+    async fn update_version(&self, version: &DocVersion) -> Result<DocVersion> {
+        dispatch!(self, update_version, version)
+    }
+
+    async fn delete_version(&self, id: i64) -> Result<bool> {
+        dispatch!(self, delete_version, id)
+    }
+
+    async fn list_pages(&self, version_id: i64) -> Result<Vec<DocPage>> {
+        dispatch!(self, list_pages, version_id)
+    }
+
+    async fn get_page(&self, id: i64) -> Result<Option<DocPage>> {
+        dispatch!(self, get_page, id)
+    }
+
+    async fn get_page_by_slug(&self, version_id: i64, slug: &str) -> Result<Option<DocPage>> {
+        dispatch!(self, get_page_by_slug, version_id, slug)
+    }
+
+    async fn versions_with_page(&self, slug: &str) -> Result<Vec<i64>> {
+        dispatch!(self, versions_with_page, slug)
+    }
+
+    async fn create_page(&self, page: &DocPage) -> Result<DocPage> {
+        dispatch!(self, create_page, page)
+    }
+
+    async fn update_page(&self, page: &DocPage) -> Result<DocPage> {
+        dispatch!(self, update_page, page)
+    }
+
+    async fn delete_page(&self, id: i64) -> Result<bool> {
+        dispatch!(self, delete_page, id)
+    }
+}
+
+impl_dual_fn! {
+    async fn list_versions(pool) -> Result<Vec<DocVersion>> {
+        let rows = sqlx::query(&format!(
+            "SELECT {} FROM doc_versions ORDER BY position, id",
+            VERSION_COLUMNS
+        ))
+        .fetch_all(pool)
+        .await
+        .context("Failed to list doc versions")?;
+        Ok(rows.iter().map(row_to_version).collect())
+    }
+}
+
+impl_dual_fn! {
+    async fn get_version(pool, id: i64) -> Result<Option<DocVersion>> {
+        let row = sqlx::query(&format!("SELECT {} FROM doc_versions WHERE id = ?", VERSION_COLUMNS))
+            .bind(id)
+            .fetch_optional(pool)
+            .await
+            .context("Failed to get doc version")?;
+        Ok(row.map(|r| row_to_version(&r)))
+    }
+}
+
+impl_dual_fn! {
+    async fn get_version_by_slug(pool, slug: &str) -> Result<Option<DocVersion>> {
+        let row = sqlx::query(&format!("SELECT {} FROM doc_versions WHERE slug = ?", VERSION_COLUMNS))
+            .bind(slug)
+            .fetch_optional(pool)
+            .await
+            .context("Failed to get doc version")?;
+        Ok(row.map(|r| row_to_version(&r)))
+    }
+}
+
+impl_dual_fn! {
+    async fn update_version(pool, version: &DocVersion) -> Result<DocVersion> {
+        let now = Utc::now();
+        let mut tx = pool.begin().await.context("Failed to start transaction")?;
+        if version.is_latest {
+            sqlx::query("UPDATE doc_versions SET is_latest = ? WHERE id <> ?")
+                .bind(false)
+                .bind(version.id)
+                .execute(&mut *tx)
+                .await
+                .context("Failed to unmark latest doc version")?;
+        }
+        sqlx::query(
+            "UPDATE doc_versions SET slug = ?, title = ?, position = ?, is_latest = ?, updated_at = ? WHERE id = ?",
+        )
+        .bind(&version.slug)
+        .bind(&version.title)
+        .bind(version.position)
+        .bind(version.is_latest)
+        .bind(now)
+        .bind(version.id)
+        .execute(&mut *tx)
+        .await
+        .context("Failed to update doc version")?;
+        tx.commit().await.context("Failed to commit transaction")?;
+        Ok(DocVersion {
+            updated_at: now,
+            ..version.clone()
+        })
+    }
+}
+
+impl_dual_fn! {
+    async fn delete_version(pool, id: i64) -> Result<bool> {
+        let mut tx = pool.begin().await.context("Failed to start transaction")?;
+        // Detach pages first so deleting them never trips the parent key
+        sqlx::query("UPDATE doc_pages SET parent_id = NULL WHERE version_id = ?")
+            .bind(id)
+            .execute(&mut *tx)
+            .await
+            .context("Failed to detach doc pages")?;
+        sqlx::query("DELETE FROM doc_pages WHERE version_id = ?")
+            .bind(id)
+            .execute(&mut *tx)
+            .await
+            .context("Failed to delete doc pages")?;
+        let result = sqlx::query("DELETE FROM doc_versions WHERE id = ?")
+            .bind(id)
+            .execute(&mut *tx)
+            .await
+            .context("Failed to delete doc version")?;
+        tx.commit().await.context("Failed to commit transaction")?;
+        Ok(result.rows_affected() > 0)
+    }
+}
+
+impl_dual_fn! {
+    async fn list_pages(pool, version_id: i64) -> Result<Vec<DocPage>> {
+        let rows = sqlx::query(&format!(
+            "SELECT {} FROM doc_pages WHERE version_id = ? ORDER BY position, id",
+            PAGE_COLUMNS
+        ))
+        .bind(version_id)
+        .fetch_all(pool)
+        .await
+        .context("Failed to list doc pages")?;
+        Ok(rows.iter().map(row_to_page).collect())
+    }
+}
+
+impl_dual_fn! {
+    async fn get_page(pool, id: i64) -> Result<Option<DocPage>> {
+        let row = sqlx::query(&format!("SELECT {} FROM doc_pages WHERE id = ?", PAGE_COLUMNS))
+            .bind(id)
+            .fetch_optional(pool)
+            .await
+            .context("Failed to get doc page")?;
+        Ok(row.map(|r| row_to_page(&r)))
+    }
+}
+
+impl_dual_fn! {
+    async fn get_page_by_slug(pool, version_id: i64, slug: &str) -> Result<Option<DocPage>> {
+        let row = sqlx::query(&format!(
+            "SELECT {} FROM doc_pages WHERE version_id = ? AND slug = ?",
+            PAGE_COLUMNS
+        ))
+        .bind(version_id)
+        .bind(slug)
+        .fetch_optional(pool)
+        .await
+        .context("Failed to get doc page")?;
+        Ok(row.map(|r| row_to_page(&r)))
+    }
+}
+
+impl_dual_fn! {
+    async fn versions_with_page(pool, slug: &str) -> Result<Vec<i64>> {
+        let ids: Vec<(i64,)> = sqlx::query_as("SELECT version_id FROM doc_pages WHERE slug = ?")
+            .bind(slug)
+            .fetch_all(pool)
+            .await
+            .context("Failed to find doc page versions")?;
+        Ok(ids.into_iter().map(|(id,)| id).collect())
+    }
+}
+
+impl_dual_fn! {
+    async fn update_page(pool, page: &DocPage) -> Result<DocPage> {
+        let now = Utc::now();
+        sqlx::query(
+            "UPDATE doc_pages SET parent_id = ?, slug = ?, title = ?, content = ?, content_html = ?, \
+             position = ?, updated_at = ? WHERE id = ?",
+        )
+        .bind(page.parent_id)
+        .bind(&page.slug)
+        .bind(&page.title)
+        .bind(&page.content)
+        .bind(&page.content_html)
+        .bind(page.position)
+        .bind(now)
+        .bind(page.id)
+        .execute(pool)
+        .await
+        .context("Failed to update doc page")?;
+        Ok(DocPage {
+            updated_at: now,
+            ..page.clone()
+        })
+    }
+}
+
+impl_dual_fn! {
+    async fn delete_page(pool, id: i64) -> Result<bool> {
+        let mut tx = pool.begin().await.context("Failed to start transaction")?;
+        let parent: Option<(Option<i64>,)> =
+            sqlx::query_as("SELECT parent_id FROM doc_pages WHERE id = ?")
+                .bind(id)
+                .fetch_optional(&mut *tx)
+                .await
+                .context("Failed to get doc page")?;
+        let Some((parent_id,)) = parent else {
+            return Ok(false);
+        };
+        sqlx::query("UPDATE doc_pages SET parent_id = ? WHERE parent_id = ?")
+            .bind(parent_id)
+            .bind(id)
+            .execute(&mut *tx)
+            .await
+            .context("Failed to move doc page children")?;
+        sqlx::query("DELETE FROM doc_pages WHERE id = ?")
+            .bind(id)
+            .execute(&mut *tx)
+            .await
+            .context("Failed to delete doc page")?;
+        tx.commit().await.context("Failed to commit transaction")?;
+        Ok(true)
+    }
+}
+
+fn row_to_version<'r, R>(row: &'r R) -> DocVersion
+where
+    R: sqlx::Row,
+    &'r str: sqlx::ColumnIndex<R>,
+    String: sqlx::Decode<'r, R::Database> + sqlx::Type<R::Database>,
+    i64: sqlx::Decode<'r, R::Database> + sqlx::Type<R::Database>,
+    i32: sqlx::Decode<'r, R::Database> + sqlx::Type<R::Database>,
+    bool: sqlx::Decode<'r, R::Database> + sqlx::Type<R::Database>,
+    DateTime<Utc>: sqlx::Decode<'r, R::Database> + sqlx::Type<R::Database>,
+{
+    DocVersion {
+        id: row.get("id"),
+        slug: row.get("slug"),
+        title: row.get("title"),
+        position: row.get("position"),
+        is_latest: row.get("is_latest"),
+        created_at: row.get("created_at"),
+        updated_at: row.get("updated_at"),
+    }
+}
+
+fn row_to_page<'r, R>(row: &'r R) -> DocPage
+where
+    R: sqlx::Row,
+    &'r str: sqlx::ColumnIndex<R>,
+    String: sqlx::Decode<'r, R::Database> + sqlx::Type<R::Database>,
+    i64: sqlx::Decode<'r, R::Database> + sqlx::Type<R::Database>,
+    Option<i64>: sqlx::Decode<'r, R::Database> + sqlx::Type<R::Database>,
+    i32: sqlx::Decode<'r, R::Database> + sqlx::Type<R::Database>,
+    DateTime<Utc>: sqlx::Decode<'r, R::Database> + sqlx::Type<R::Database>,
+{
+    DocPage {
+        id: row.get("id"),
+        version_id: row.get("version_id"),
+        parent_id: row.get("parent_id"),
+        slug: row.get("slug"),
+        title: row.get("title"),
+        content: row.get("content"),
+        content_html: row.get("content_html"),
+        position: row.get("position"),
+        created_at: row.get("created_at"),
+        updated_at: row.get("updated_at"),
+    }
+}
+
+async fn create_version_sqlite(pool: &SqlitePool, version: &DocVersion) -> Result<DocVersion> {
+    let now = Utc::now();
+    let mut tx = pool.begin().await.context("Failed to start transaction")?;
+    if version.is_latest {
+        sqlx::query("UPDATE doc_versions SET is_latest = ?")
+            .bind(false)
+            .execute(&mut *tx)
+            .await
+            .context("Failed to unmark latest doc version")?;
+    }
+    let id = sqlx::query(
+        "INSERT INTO doc_versions (slug, title, position, is_latest, created_at, updated_at) \
+         VALUES (?, ?, ?, ?, ?, ?)",
+    )
+    .bind(&version.slug)
+    .bind(&version.title)
+    .bind(version.position)
+    .bind(version.is_latest)
+    .bind(now)
+    .bind(now)
+    .execute(&mut *tx)
+    .await
+    .context("Failed to create doc version")?
+    .last_insert_rowid();
+    tx.commit().await.context("Failed to commit transaction")?;
+
+    Ok(DocVersion {
+        id,
+        created_at: now,
+        updated_at: now,
+        ..version.clone()
+    })
+}
+
+async fn create_page_sqlite(pool: &SqlitePool, page: &DocPage) -> Result<DocPage> {
+    let now = Utc::now();
+    let result = sqlx::query(
+        "INSERT INTO doc_pages (version_id, parent_id, slug, title, content, content_html, position, created_at, updated_at) \
+         VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?)",
+    )
+    .bind(page.version_id)
+    .bind(page.parent_id)
+    .bind(&page.slug)
+    .bind(&page.title)
+    .bind(&page.content)
+    .bind(&page.content_html)
+    .bind(page.position)
+    .bind(now)
+    .bind(now)
+    .execute(pool)
+    .await
+    .context("Failed to create doc page")?;
+
+    Ok(DocPage {
+        id: result.last_insert_rowid(),
+        created_at: now,
+        updated_at: now,
+        ..page.clone()
+    })
+}
+
+async fn create_version_mysql(pool: &MySqlPool, version: &DocVersion) -> Result<DocVersion> {
+    let now = Utc::now();
+    let mut tx = pool.begin().await.context("Failed to start transaction")?;
+    if version.is_latest {
+        sqlx::query("UPDATE doc_versions SET is_latest = ?")
+            .bind(false)
+            .execute(&mut *tx)
+            .await
+            .context("Failed to unmark latest doc version")?;
+    }
+    let id = sqlx::query(
+        "INSERT INTO doc_versions (slug, title, position, is_latest, created_at, updated_at) \
+         VALUES (?, ?, ?, ?, ?, ?)",
+    )
+    .bind(&version.slug)
+    .bind(&version.title)
+    .bind(version.position)
+    .bind(version.is_latest)
+    .bind(now)
+    .bind(now)
+    .execute(&mut *tx)
+    .await
+    .context("Failed to create doc version")?
+    .last_insert_id() as i64;
+    tx.commit().await.context("Failed to commit transaction")?;
+
+    Ok(DocVersion {
+        id,
+        created_at: now,
+        updated_at: now,
+        ..version.clone()
+    })
+}
+
+async fn create_page_mysql(pool: &MySqlPool, page: &DocPage) -> Result<DocPage> {
+    let now = Utc::now();
+    let result = sqlx::query(
+        "INSERT INTO doc_pages (version_id, parent_id, slug, title, content, content_html, position, created_at, updated_at) \
+         VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?)",
+    )
+    .bind(page.version_id)
+    .bind(page.parent_id)
+    .bind(&page.slug)
+    .bind(&page.title)
+    .bind(&page.content)
+    .bind(&page.content_html)
+    .bind(page.position)
+    .bind(now)
+    .bind(now)
+    .execute(pool)
+    .await
+    .context("Failed to create doc page")?;
+
+    Ok(DocPage {
+        id: result.last_insert_id() as i64,
+        created_at: now,
+        updated_at: now,
+        ..page.clone()
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::{create_test_pool, migrations};
+
+    fn version(slug: &str, is_latest: bool) -> DocVersion {
+        let now = Utc::now();
+        DocVersion {
+            id: 0,
+            slug: slug.to_string(),
+            title: slug.to_string(),
+            position: 0,
+            is_latest,
+            created_at: now,
+            updated_at: now,
+        }
+    }
+
+    fn page(version_id: i64, parent_id: Option<i64>, slug: &str) -> DocPage {
+        let now = Utc::now();
+        DocPage {
+            id: 0,
+            version_id,
+            parent_id,
+            slug: slug.to_string(),
+            title: slug.to_string(),
+            content: String::new(),
+            content_html: String::new(),
+            position: 0,
+            created_at: now,
+            updated_at: now,
+        }
+    }
+
+    #[tokio::test]
+    async fn versions_and_page_trees_round_trip() {
+        let pool = create_test_pool()
+            .await
+            .expect("Failed to create test pool");
+        migrations::run_migrations(&pool)
+            .await
+            .expect("Failed to run migrations");
+        let repo = SqlxDocRepository::new(pool);
+
+        let v1 = repo.create_version(&version("v1", true)).await.unwrap();
+        let v2 = repo.create_version(&version("v2", true)).await.unwrap();
+        let latest: Vec<bool> = repo
+            .list_versions()
+            .await
+            .unwrap()
+            .iter()
+            .map(|v| v.is_latest)
+            .collect();
+        assert_eq!(latest, [false, true]);
+
+        let guide = repo.create_page(&page(v1.id, None, "guide")).await.unwrap();
+        let install = repo
+            .create_page(&page(v1.id, Some(guide.id), "install"))
+            .await
+            .unwrap();
+        repo.create_page(&page(v2.id, None, "install"))
+            .await
+            .unwrap();
+        assert!(repo.create_page(&page(v1.id, None, "guide")).await.is_err());
+
+        let mut versions = repo.versions_with_page("install").await.unwrap();
+        versions.sort();
+        assert_eq!(versions, [v1.id, v2.id]);
+
+        assert!(repo.delete_page(guide.id).await.unwrap());
+        let moved = repo.get_page(install.id).await.unwrap().unwrap();
+        assert_eq!(moved.parent_id, None);
+
+        assert!(repo.delete_version(v1.id).await.unwrap());
+        assert!(repo.get_page(install.id).await.unwrap().is_none());
+        assert_eq!(repo.versions_with_page("install").await.unwrap(), [v2.id]);
+    }
+}
@@ -0,0 +1,225 @@
+//! Email suppression list repository
+
+use anyhow::{Context, Result};
+use async_trait::async_trait;
+use chrono::Utc;
+use sqlx::Row;
+use std::sync::Arc;
+
+use crate::db::DynDatabasePool;
+use crate::models::{normalize_email, EmailSuppression, SuppressionReason};
+
+/// Repository trait for suppressed email addresses
+#[async_trait]
+pub trait EmailSuppressionRepository: Send + Sync {
+    /// Look up an address (case-insensitive)
+    async fn get(&self, email: &str) -> Result<Option<EmailSuppression>>;
+
+    /// Suppress an address, replacing the reason of an existing entry
+    async fn upsert(
+        &self,
+        email: &str,
+        reason: SuppressionReason,
+        detail: Option<&str>,
+    ) -> Result<EmailSuppression>;
+
+    /// Remove an address. Returns whether it was suppressed.
+    async fn delete(&self, email: &str) -> Result<bool>;
+
+    /// List entries, newest first, with the total count
+    async fn list(&self, page: i64, per_page: i64) -> Result<(Vec<EmailSuppression>, i64)>;
+}
+
+/// SQLx-based email suppression repository
+pub struct SqlxEmailSuppressionRepository {
+    pool: DynDatabasePool,
+}
+
+impl SqlxEmailSuppressionRepository {
+    pub fn new(pool: DynDatabasePool) -> Self {
+        Self { pool }
+    }
+
+    /// Create a boxed repository for use with dependency injection
+    pub fn boxed(pool: DynDatabasePool) -> Arc<dyn EmailSuppressionRepository> {
+        Arc::new(Self::new(pool))
+    }
+}
+
+#[async_trait]
+impl EmailSuppressionRepository for SqlxEmailSuppressionRepository {
+    async fn get(&self, email: &str) -> Result<Option<EmailSuppression>> {
+        let email = normalize_email(email);
+        dispatch!(self, get_suppression, &email)
+    }
+
+    async fn upsert(
+        &self,
+        email: &str,
+        reason: SuppressionReason,
+        detail: Option<&str>,
+    ) -> Result<EmailSuppression> {
+        let email = normalize_email(email);
+        dispatch!(self, upsert_suppression, &email, reason, detail)
+    }
+
+    async fn delete(&self, email: &str) -> Result<bool> {
+        let email = normalize_email(email);
+        dispatch!(self, delete_suppression, &email)
+    }
+
+    async fn list(&self, page: i64, per_page: i64) -> Result<(Vec<EmailSuppression>, i64)> {
+        dispatch!(self, list_suppressions, page, per_page)
+    }
+}
+
+// ============================================================================
+// Shared implementations (identical SQL)
+// ============================================================================
+
+const SELECT_COLUMNS: &str = "SELECT id, email, reason, detail, created_at FROM email_suppressions";
+
+impl_dual_fn! {
+    async fn get_suppression(pool, email: &str) -> Result<Option<EmailSuppression>> {
+        let row = sqlx::query(&format!("{} WHERE email = ?", SELECT_COLUMNS))
+            .bind(email)
+            .fetch_optional(pool)
+            .await
+            .context("Failed to get email suppression")?;
+        row.as_ref().map(row_to_suppression).transpose()
+    }
+}
+
+impl_dual_fn! {
+    async fn upsert_suppression(pool, email: &str, reason: SuppressionReason, detail: Option<&str>) -> Result<EmailSuppression> {
+        let updated = sqlx::query("UPDATE email_suppressions SET reason = ?, detail = ? WHERE email = ?")
+            .bind(reason.as_str())
+            .bind(detail)
+            .bind(email)
+            .execute(pool)
+            .await
+            .context("Failed to update email suppression")?;
+        // MySQL reports 0 affected rows when the values are unchanged, so
+        // check for the row before inserting
+        if updated.rows_affected() == 0 {
+            let existing = sqlx::query("SELECT id FROM email_suppressions WHERE email = ?")
+                .bind(email)
+                .fetch_optional(pool)
+                .await
+                .context("Failed to check email suppression")?;
+            if existing.is_none() {
+                sqlx::query("INSERT INTO email_suppressions (email, reason, detail, created_at) VALUES (?, ?, ?, ?)")
+                    .bind(email)
+                    .bind(reason.as_str())
+                    .bind(detail)
+                    .bind(Utc::now())
+                    .execute(pool)
+                    .await
+                    .context("Failed to add email suppression")?;
+            }
+        }
+
+        let row = sqlx::query(&format!("{} WHERE email = ?", SELECT_COLUMNS))
+            .bind(email)
+            .fetch_one(pool)
+            .await
+            .context("Failed to load email suppression")?;
+        row_to_suppression(&row)
+    }
+}
+
+impl_dual_fn! {
+    async fn delete_suppression(pool, email: &str) -> Result<bool> {
+        let result = sqlx::query("DELETE FROM email_suppressions WHERE email = ?")
+            .bind(email)
+            .execute(pool)
+            .await
+            .context("Failed to delete email suppression")?;
+        Ok(result.rows_affected() > 0)
+    }
+}
+
+impl_dual_fn! {
+    async fn list_suppressions(pool, page: i64, per_page: i64) -> Result<(Vec<EmailSuppression>, i64)> {
+        let offset = (page.max(1) - 1) * per_page;
+        let rows = sqlx::query(&format!("{} ORDER BY created_at DESC, id DESC LIMIT ? OFFSET ?", SELECT_COLUMNS))
+            .bind(per_page)
+            .bind(offset)
+            .fetch_all(pool)
+            .await
+            .context("Failed to list email suppressions")?;
+        let total: i64 = sqlx::query("SELECT COUNT(*) as count FROM email_suppressions")
+            .fetch_one(pool)
+            .await
+            .context("Failed to count email suppressions")?
+            .get("count");
+        let entries = rows.iter().map(row_to_suppression).collect::<Result<Vec<_>>>()?;
+        Ok((entries, total))
+    }
+}
+
+/// Map a row to a suppression entry (same column types on SQLite and MySQL)
+fn row_to_suppression<'r, R>(row: &'r R) -> Result<EmailSuppression>
+where
+    R: sqlx::Row,
+    &'r str: sqlx::ColumnIndex<R>,
+    String: sqlx::Decode<'r, R::Database> + sqlx::Type<R::Database>,
+    i64: sqlx::Decode<'r, R::Database> + sqlx::Type<R::Database>,
+    chrono::DateTime<Utc>: sqlx::Decode<'r, R::Database> + sqlx::Type<R::Database>,
+{
+    let reason: String = row.get("reason");
+    Ok(EmailSuppression {
+        id: row.get("id"),
+        email: row.get("email"),
+        reason: reason.parse().map_err(anyhow::Error::msg)?,
+        detail: row.get("detail"),
+        created_at: row.get("created_at"),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::{create_test_pool, migrations};
+
+    #[tokio::test]
+    async fn suppressions_are_case_insensitive_and_replaceable() {
+        let pool = create_test_pool()
+            .await
+            .expect("Failed to create test pool");
+        migrations::run_migrations(&pool)
+            .await
+            .expect("Failed to run migrations");
+        let repo = SqlxEmailSuppressionRepository::new(pool);
+
+        let entry = repo
+            .upsert(
+                "Reader@Example.com ",
+                SuppressionReason::HardBounce,
+                Some("550 5.1.1 User unknown"),
+            )
+            .await
+            .unwrap();
+        assert_eq!(entry.email, "reader@example.com");
+        assert!(repo.get("READER@example.com").await.unwrap().is_some());
+
+        let replaced = repo
+            .upsert("reader@example.com", SuppressionReason::Complaint, None)
+            .await
+            .unwrap();
+        assert_eq!(replaced.id, entry.id);
+        assert_eq!(replaced.reason, SuppressionReason::Complaint);
+        assert_eq!(replaced.detail, None);
+
+        repo.upsert("other@example.com", SuppressionReason::Manual, None)
+            .await
+            .unwrap();
+        let (entries, total) = repo.list(1, 1).await.unwrap();
+        assert_eq!(total, 2);
+        assert_eq!(entries.len(), 1);
+
+        assert!(repo.delete("Reader@example.com").await.unwrap());
+        assert!(!repo.delete("reader@example.com").await.unwrap());
+        assert!(repo.get("reader@example.com").await.unwrap().is_none());
+    }
+}
@@ -0,0 +1,353 @@
+//! Event repository.
+
+use crate::db::DynDatabasePool;
+use crate::models::{Event, EventRepeat, RepeatFrequency};
+use anyhow::{Context, Result};
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use sqlx::{MySqlPool, SqlitePool};
+use std::sync::Arc;
+
+const EVENT_COLUMNS: &str = "id, title, description, location, url, starts_at, ends_at, all_day, \
+     repeat_frequency, repeat_interval, repeat_until, created_at, updated_at";
+
+#[async_trait]
+pub trait EventRepository: Send + Sync {
+    async fn create(&self, event: &Event) -> Result<Event>;
+    async fn get_by_id(&self, id: i64) -> Result<Option<Event>>;
+    /// All events, by first start
+    async fn list(&self) -> Result<Vec<Event>>;
+    /// Events that may still have an occurrence ending after `since`
+    async fn list_active(&self, since: DateTime<Utc>) -> Result<Vec<Event>>;
+    async fn update(&self, event: &Event) -> Result<Event>;
+    async fn delete(&self, id: i64) -> Result<bool>;
+}
+
+pub struct SqlxEventRepository {
+    pool: DynDatabasePool,
+}
+
+impl SqlxEventRepository {
+    pub fn new(pool: DynDatabasePool) -> Self {
+        Self { pool }
+    }
+
+    pub fn boxed(pool: DynDatabasePool) -> Arc<dyn EventRepository> {
+        Arc::new(Self::new(pool))
+    }
+}
+
+#[async_trait]
+impl EventRepository for SqlxEventRepository {
+    async fn create(&self, event: &Event) -> Result<Event> {
+        dispatch!(self, create, event)
+    }
+
+    async fn get_by_id(&self, id: i64) -> Result<Option<Event>> {
+        dispatch!(self, get_by_id, id)
+    }
+
+    async fn list(&self) -> Result<Vec<Event>> {
+        dispatch!(self, list)
+    }
+
+    async fn list_active(&self, since: DateTime<Utc>) -> Result<Vec<Event>> {
+        dispatch!(self, list_active, since)
+    }
+
+    async fn update(&self, event: &Event) -> Result<Event> {
+        dispatch!(self, update, event)
+    }
+
+    async fn delete(&self, id: i64) -> Result<bool> {
+        dispatch!(self, delete, id)
+    }
+}
+
+impl_dual_fn! {
+    async fn get_by_id(pool, id: i64) -> Result<Option<Event>> {
+        let row = sqlx::query(&format!("SELECT {} FROM events WHERE id = ?", EVENT_COLUMNS))
+            .bind(id)
+            .fetch_optional(pool)
+            .await
+            .context("Failed to get event")?;
+        row.map(|r| row_to_event(&r)).transpose()
+    }
+}
+
+impl_dual_fn! {
+    async fn list(pool) -> Result<Vec<Event>> {
+        let rows = sqlx::query(&format!(
+            "SELECT {} FROM events ORDER BY starts_at, id",
+            EVENT_COLUMNS
+        ))
+        .fetch_all(pool)
+        .await
+        .context("Failed to list events")?;
+        rows.iter().map(row_to_event).collect()
+    }
+}
+
+impl_dual_fn! {
+    async fn list_active(pool, since: DateTime<Utc>) -> Result<Vec<Event>> {
+        // All-day events end a day after their stored last day, so callers
+        // pass a `since` a day early; occurrences are filtered exactly later
+        let rows = sqlx::query(&format!(
+            "SELECT {} FROM events \
+             WHERE starts_at >= ? OR ends_at >= ? \
+                OR (repeat_frequency IS NOT NULL AND (repeat_until IS NULL OR repeat_until >= ?)) \
+             ORDER BY starts_at, id",
+            EVENT_COLUMNS
+        ))
+        .bind(since)
+        .bind(since)
+        .bind(since)
+        .fetch_all(pool)
+        .await
+        .context("Failed to list active events")?;
+        rows.iter().map(row_to_event).collect()
+    }
+}
+
+impl_dual_fn! {
+    async fn delete(pool, id: i64) -> Result<bool> {
+        let result = sqlx::query("DELETE FROM events WHERE id = ?")
+            .bind(id)
+            .execute(pool)
+            .await
+            .context("Failed to delete event")?;
+        Ok(result.rows_affected() > 0)
+    }
+}
+
+fn row_to_event<'r, R>(row: &'r R) -> Result<Event>
+where
+    R: sqlx::Row,
+    &'r str: sqlx::ColumnIndex<R>,
+    String: sqlx::Decode<'r, R::Database> + sqlx::Type<R::Database>,
+    Option<String>: sqlx::Decode<'r, R::Database> + sqlx::Type<R::Database>,
+    i64: sqlx::Decode<'r, R::Database> + sqlx::Type<R::Database>,
+    i32: sqlx::Decode<'r, R::Database> + sqlx::Type<R::Database>,
+    bool: sqlx::Decode<'r, R::Database> + sqlx::Type<R::Database>,
+    DateTime<Utc>: sqlx::Decode<'r, R::Database> + sqlx::Type<R::Database>,
+    Option<DateTime<Utc>>: sqlx::Decode<'r, R::Database> + sqlx::Type<R::Database>,
+{
+    let frequency: Option<String> = row.get("repeat_frequency");
+    let repeat = match frequency {
+        Some(frequency) => {
+            let interval: i32 = row.get("repeat_interval");
+            Some(EventRepeat {
+                frequency: RepeatFrequency::parse(&frequency)
+                    .with_context(|| format!("Invalid repeat frequency: {}", frequency))?,
+                interval: u32::try_from(interval).context("Invalid repeat interval")?,
+                until: row.get("repeat_until"),
+            })
+        }
+        None => None,
+    };
+    Ok(Event {
+        id: row.get("id"),
+        title: row.get("title"),
+        description: row.get("description"),
+        location: row.get("location"),
+        url: row.get("url"),
+        starts_at: row.get("starts_at"),
+        ends_at: row.get("ends_at"),
+        all_day: row.get("all_day"),
+        repeat,
+        created_at: row.get("created_at"),
+        updated_at: row.get("updated_at"),
+    })
+}
+
+async fn create_sqlite(pool: &SqlitePool, event: &Event) -> Result<Event> {
+    let now = Utc::now();
+    let repeat = event.repeat.as_ref();
+    let result = sqlx::query(
+        "INSERT INTO events (title, description, location, url, starts_at, ends_at, all_day, repeat_frequency, repeat_interval, repeat_until, created_at, updated_at) \
+         VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)",
+    )
+    .bind(&event.title)
+    .bind(&event.description)
+    .bind(&event.location)
+    .bind(&event.url)
+    .bind(event.starts_at)
+    .bind(event.ends_at)
+    .bind(event.all_day)
+    .bind(repeat.map(|r| r.frequency.as_str()))
+    .bind(repeat.map_or(1, |r| r.interval as i32))
+    .bind(repeat.and_then(|r| r.until))
+    .bind(now)
+    .bind(now)
+    .execute(pool)
+    .await
+    .context("Failed to create event")?;
+
+    Ok(Event {
+        id: result.last_insert_rowid(),
+        created_at: now,
+        updated_at: now,
+        ..event.clone()
+    })
+}
+
+async fn update_sqlite(pool: &SqlitePool, event: &Event) -> Result<Event> {
+    let repeat = event.repeat.as_ref();
+    sqlx::query(
+        "UPDATE events SET title = ?, description = ?, location = ?, url = ?, starts_at = ?, ends_at = ?, all_day = ?, \
+         repeat_frequency = ?, repeat_interval = ?, repeat_until = ?, updated_at = ? WHERE id = ?",
+    )
+    .bind(&event.title)
+    .bind(&event.description)
+    .bind(&event.location)
+    .bind(&event.url)
+    .bind(event.starts_at)
+    .bind(event.ends_at)
+    .bind(event.all_day)
+    .bind(repeat.map(|r| r.frequency.as_str()))
+    .bind(repeat.map_or(1, |r| r.interval as i32))
+    .bind(repeat.and_then(|r| r.until))
+    .bind(Utc::now())
+    .bind(event.id)
+    .execute(pool)
+    .await
+    .context("Failed to update event")?;
+    get_by_id_sqlite(pool, event.id)
+        .await?
+        .ok_or_else(|| anyhow::anyhow!("Event not found after update"))
+}
+
+async fn create_mysql(pool: &MySqlPool, event: &Event) -> Result<Event> {
+    let now = Utc::now();
+    let repeat = event.repeat.as_ref();
+    let result = sqlx::query(
+        "INSERT INTO events (title, description, location, url, starts_at, ends_at, all_day, repeat_frequency, repeat_interval, repeat_until, created_at, updated_at) \
+         VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)",
+    )
+    .bind(&event.title)
+    .bind(&event.description)
+    .bind(&event.location)
+    .bind(&event.url)
+    .bind(event.starts_at)
+    .bind(event.ends_at)
+    .bind(event.all_day)
+    .bind(repeat.map(|r| r.frequency.as_str()))
+    .bind(repeat.map_or(1, |r| r.interval as i32))
+    .bind(repeat.and_then(|r| r.until))
+    .bind(now)
+    .bind(now)
+    .execute(pool)
+    .await
+    .context("Failed to create event")?;
+
+    Ok(Event {
+        id: result.last_insert_id() as i64,
+        created_at: now,
+        updated_at: now,
+        ..event.clone()
+    })
+}
+
+async fn update_mysql(pool: &MySqlPool, event: &Event) -> Result<Event> {
+    let repeat = event.repeat.as_ref();
+    sqlx::query(
+        "UPDATE events SET title = ?, description = ?, location = ?, url = ?, starts_at = ?, ends_at = ?, all_day = ?, \
+         repeat_frequency = ?, repeat_interval = ?, repeat_until = ?, updated_at = ? WHERE id = ?",
+    )
+    .bind(&event.title)
+    .bind(&event.description)
+    .bind(&event.location)
+    .bind(&event.url)
+    .bind(event.starts_at)
+    .bind(event.ends_at)
+    .bind(event.all_day)
+    .bind(repeat.map(|r| r.frequency.as_str()))
+    .bind(repeat.map_or(1, |r| r.interval as i32))
+    .bind(repeat.and_then(|r| r.until))
+    .bind(Utc::now())
+    .bind(event.id)
+    .execute(pool)
+    .await
+    .context("Failed to update event")?;
+    get_by_id_mysql(pool, event.id)
+        .await?
+        .ok_or_else(|| anyhow::anyhow!("Event not found after update"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::{create_test_pool, migrations};
+    use chrono::Duration;
+
+    fn event(title: &str, starts_at: DateTime<Utc>, repeat: Option<EventRepeat>) -> Event {
+        Event {
+            id: 0,
+            title: title.to_string(),
+            description: String::new(),
+            location: Some("Library".to_string()),
+            url: None,
+            starts_at,
+            ends_at: None,
+            all_day: false,
+            repeat,
+            created_at: starts_at,
+            updated_at: starts_at,
+        }
+    }
+
+    #[tokio::test]
+    async fn events_round_trip_and_past_ones_are_inactive() {
+        let pool = create_test_pool()
+            .await
+            .expect("Failed to create test pool");
+        migrations::run_migrations(&pool)
+            .await
+            .expect("Failed to run migrations");
+        let repo = SqlxEventRepository::new(pool);
+        let now = Utc::now();
+
+        let past = repo
+            .create(&event("Launch", now - Duration::days(30), None))
+            .await
+            .unwrap();
+        let weekly = repo
+            .create(&event(
+                "Office hours",
+                now - Duration::days(60),
+                Some(EventRepeat {
+                    frequency: RepeatFrequency::Weekly,
+                    interval: 1,
+                    until: None,
+                }),
+            ))
+            .await
+            .unwrap();
+        let next = repo
+            .create(&event("Meetup", now + Duration::days(3), None))
+            .await
+            .unwrap();
+
+        let mut changed = next.clone();
+        changed.location = None;
+        let updated = repo.update(&changed).await.unwrap();
+        assert_eq!(updated.location, None);
+        assert_eq!(
+            repo.get_by_id(weekly.id).await.unwrap().unwrap().repeat,
+            weekly.repeat
+        );
+
+        let active: Vec<i64> = repo
+            .list_active(now)
+            .await
+            .unwrap()
+            .iter()
+            .map(|e| e.id)
+            .collect();
+        assert_eq!(active, [weekly.id, next.id]);
+        assert_eq!(repo.list().await.unwrap().len(), 3);
+
+        assert!(repo.delete(past.id).await.unwrap());
+        assert!(repo.get_by_id(past.id).await.unwrap().is_none());
+    }
+}
@@ -0,0 +1,197 @@
+//! External identity repository
+//!
+//! Bindings between SAML / LDAP accounts and local users.
+
+use anyhow::{Context, Result};
+use async_trait::async_trait;
+use sqlx::Row;
+use std::sync::Arc;
+
+use crate::db::DynDatabasePool;
+use crate::models::ExternalIdentity;
+
+/// Repository trait for external identity bindings
+#[async_trait]
+pub trait ExternalIdentityRepository: Send + Sync {
+    /// Look up the binding of a provider account
+    async fn get(
+        &self,
+        provider: &str,
+        issuer: &str,
+        subject: &str,
+    ) -> Result<Option<ExternalIdentity>>;
+
+    /// Whether a user already has a binding at a provider
+    async fn exists_for_user(&self, provider: &str, user_id: i64) -> Result<bool>;
+
+    /// Store a new binding
+    async fn create(&self, identity: &ExternalIdentity) -> Result<()>;
+}
+
+/// SQLx-based external identity repository
+pub struct SqlxExternalIdentityRepository {
+    pool: DynDatabasePool,
+}
+
+impl SqlxExternalIdentityRepository {
+    pub fn new(pool: DynDatabasePool) -> Self {
+        Self { pool }
+    }
+
+    /// Create a boxed repository for use with dependency injection
+    pub fn boxed(pool: DynDatabasePool) -> Arc<dyn ExternalIdentityRepository> {
+        Arc::new(Self::new(pool))
+    }
+}
+
+#[async_trait]
+impl ExternalIdentityRepository for SqlxExternalIdentityRepository {
+    async fn get(
+        &self,
+        provider: &str,
+        issuer: &str,
+        subject: &str,
+    ) -> Result<Option<ExternalIdentity>> {
+        dispatch!(self, get_external_identity, provider, issuer, subject)
+    }
+
+    async fn exists_for_user(&self, provider: &str, user_id: i64) -> Result<bool> {
+        dispatch!(self, external_identity_exists_for_user, provider, user_id)
+    }
+
+    async fn create(&self, identity: &ExternalIdentity) -> Result<()> {
+        dispatch!(self, create_external_identity, identity)
+    }
+}
+
+// ============================================================================
+// Shared implementations (identical SQL)
+// ============================================================================
+
+impl_dual_fn! {
+    async fn get_external_identity(pool, provider: &str, issuer: &str, subject: &str) -> Result<Option<ExternalIdentity>> {
+        let row = sqlx::query(
+            "SELECT provider, issuer, subject, user_id, manages_role, created_at FROM external_identities WHERE provider = ? AND issuer = ? AND subject = ?",
+        )
+        .bind(provider)
+        .bind(issuer)
+        .bind(subject)
+        .fetch_optional(pool)
+        .await
+        .context("Failed to get external identity")?;
+        row.as_ref().map(row_to_identity).transpose()
+    }
+}
+
+impl_dual_fn! {
+    async fn external_identity_exists_for_user(pool, provider: &str, user_id: i64) -> Result<bool> {
+        let row = sqlx::query(
+            "SELECT COUNT(*) as count FROM external_identities WHERE provider = ? AND user_id = ?",
+        )
+        .bind(provider)
+        .bind(user_id)
+        .fetch_one(pool)
+        .await
+        .context("Failed to check external identities")?;
+        Ok(row.get::<i64, _>("count") > 0)
+    }
+}
+
+impl_dual_fn! {
+    async fn create_external_identity(pool, identity: &ExternalIdentity) -> Result<()> {
+        sqlx::query(
+            "INSERT INTO external_identities (provider, issuer, subject, user_id, manages_role, created_at) VALUES (?, ?, ?, ?, ?, ?)",
+        )
+        .bind(&identity.provider)
+        .bind(&identity.issuer)
+        .bind(&identity.subject)
+        .bind(identity.user_id)
+        .bind(identity.manages_role)
+        .bind(identity.created_at)
+        .execute(pool)
+        .await
+        .context("Failed to store external identity")?;
+        Ok(())
+    }
+}
+
+/// Map a row to an identity (same column types on SQLite and MySQL)
+fn row_to_identity<'r, R>(row: &'r R) -> Result<ExternalIdentity>
+where
+    R: sqlx::Row,
+    &'r str: sqlx::ColumnIndex<R>,
+    String: sqlx::Decode<'r, R::Database> + sqlx::Type<R::Database>,
+    i64: sqlx::Decode<'r, R::Database> + sqlx::Type<R::Database>,
+    bool: sqlx::Decode<'r, R::Database> + sqlx::Type<R::Database>,
+    chrono::DateTime<chrono::Utc>: sqlx::Decode<'r, R::Database> + sqlx::Type<R::Database>,
+{
+    Ok(ExternalIdentity {
+        provider: row.get("provider"),
+        issuer: row.get("issuer"),
+        subject: row.get("subject"),
+        user_id: row.get("user_id"),
+        manages_role: row.get("manages_role"),
+        created_at: row.get("created_at"),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::{create_test_pool, migrations};
+    use crate::models::{PROVIDER_LDAP, PROVIDER_SAML};
+
+    #[tokio::test]
+    async fn identities_are_keyed_by_provider_issuer_and_subject() {
+        let pool = create_test_pool()
+            .await
+            .expect("Failed to create test pool");
+        migrations::run_migrations(&pool)
+            .await
+            .expect("Failed to run migrations");
+        sqlx::query(
+            "INSERT INTO users (username, email, password_hash, role) VALUES ('alice', 'alice@example.com', 'x', 'author')",
+        )
+        .execute(pool.as_sqlite().unwrap())
+        .await
+        .unwrap();
+        let repo = SqlxExternalIdentityRepository::new(pool);
+
+        repo.create(&ExternalIdentity::new(
+            PROVIDER_SAML,
+            "https://idp.example.com",
+            "alice",
+            1,
+            true,
+        ))
+        .await
+        .unwrap();
+
+        let found = repo
+            .get(PROVIDER_SAML, "https://idp.example.com", "alice")
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(found.user_id, 1);
+        assert!(found.manages_role);
+        assert!(repo
+            .get(PROVIDER_SAML, "https://other.example.com", "alice")
+            .await
+            .unwrap()
+            .is_none());
+        assert!(repo.exists_for_user(PROVIDER_SAML, 1).await.unwrap());
+        assert!(!repo.exists_for_user(PROVIDER_LDAP, 1).await.unwrap());
+
+        // The same provider account cannot be bound twice
+        assert!(repo
+            .create(&ExternalIdentity::new(
+                PROVIDER_SAML,
+                "https://idp.example.com",
+                "alice",
+                1,
+                false,
+            ))
+            .await
+            .is_err());
+    }
+}
@@ -0,0 +1,459 @@
+//! FAQ repository.
+
+use crate::db::DynDatabasePool;
+use crate::models::{FaqItem, FaqTopic};
+use anyhow::{Context, Result};
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use sqlx::{MySqlPool, SqlitePool};
+use std::sync::Arc;
+
+const TOPIC_COLUMNS: &str = "id, slug, title, description, position, created_at, updated_at";
+const ITEM_COLUMNS: &str =
+    "id, topic_id, question, answer, answer_html, position, created_at, updated_at";
+
+#[async_trait]
+pub trait FaqRepository: Send + Sync {
+    /// All topics, by position
+    async fn list_topics(&self) -> Result<Vec<FaqTopic>>;
+    async fn get_topic(&self, id: i64) -> Result<Option<FaqTopic>>;
+    async fn get_topic_by_slug(&self, slug: &str) -> Result<Option<FaqTopic>>;
+    async fn create_topic(&self, topic: &FaqTopic) -> Result<FaqTopic>;
+    async fn update_topic(&self, topic: &FaqTopic) -> Result<FaqTopic>;
+    /// Delete a topic with its questions
+    async fn delete_topic(&self, id: i64) -> Result<bool>;
+
+    /// Questions of the given topics, by topic then position
+    async fn list_items(&self, topic_ids: &[i64]) -> Result<Vec<FaqItem>>;
+    async fn get_item(&self, id: i64) -> Result<Option<FaqItem>>;
+    async fn create_item(&self, item: &FaqItem) -> Result<FaqItem>;
+    async fn update_item(&self, item: &FaqItem) -> Result<FaqItem>;
+    async fn delete_item(&self, id: i64) -> Result<bool>;
+}
+
+pub struct SqlxFaqRepository {
+    pool: DynDatabasePool,
+}
+
+impl SqlxFaqRepository {
+    pub fn new(pool: DynDatabasePool) -> Self {
+        Self { pool }
+    }
+
+    pub fn boxed(pool: DynDatabasePool) -> Arc<dyn FaqRepository> {
+        Arc::new(Self::new(pool))
+    }
+}
+
+#[async_trait]
+impl FaqRepository for SqlxFaqRepository {
+    async fn list_topics(&self) -> Result<Vec<FaqTopic>> {
+        dispatch!(self, list_topics)
+    }
+
+    async fn get_topic(&self, id: i64) -> Result<Option<FaqTopic>> {
+        dispatch!(self, get_topic, id)
+    }
+
+    async fn get_topic_by_slug(&self, slug: &str) -> Result<Option<FaqTopic>> {
+        dispatch!(self, get_topic_by_slug, slug)
+    }
+
+    async fn create_topic(&self, topic: &FaqTopic) -> Result<FaqTopic> {
+        dispatch!(self, create_topic, topic)
+    }
+
+    async fn update_topic(&self, topic: &FaqTopic) -> Result<FaqTopic> {
+        dispatch!(self, update_topic, topic)
+    }
+
+    async fn delete_topic(&self, id: i64) -> Result<bool> {
+        dispatch!(self, delete_topic, id)
+    }
+
+    async fn list_items(&self, topic_ids: &[i64]) -> Result<Vec<FaqItem>> {
+        if topic_ids.is_empty() {
+            return Ok(Vec::new());
+        }
+        dispatch!(self, list_items, topic_ids)
+    }
+
+    async fn get_item(&self, id: i64) -> Result<Option<FaqItem>> {
+        dispatch!(self, get_item, id)
+    }
+
+    async fn create_item(&self, item: &FaqItem) -> Result<FaqItem> {
+        dispatch!(self, create_item, item)
+    }
+
+    async fn update_item(&self, item: &FaqItem) -> Result<FaqItem> {
+        dispatch!(self, update_item, item)
+    }
+
+    async fn delete_item(&self, id: i64) -> Result<bool> {
+        dispatch!(self, delete_item, id)
+    }
+}
+
+impl_dual_fn! {
+    async fn list_topics(pool) -> Result<Vec<FaqTopic>> {
+        let rows = sqlx::query(&format!(
+            "SELECT {} FROM faq_topics ORDER BY position, id",
+            TOPIC_COLUMNS
+        ))
+        .fetch_all(pool)
+        .await
+        .context("Failed to list FAQ topics")?;
+        Ok(rows.iter().map(row_to_topic).collect())
+    }
+}
+
+impl_dual_fn! {
+    async fn get_topic(pool, id: i64) -> Result<Option<FaqTopic>> {
+        let row = sqlx::query(&format!("SELECT {} FROM faq_topics WHERE id = ?", TOPIC_COLUMNS))
+            .bind(id)
+            .fetch_optional(pool)
+            .await
+            .context("Failed to get FAQ topic")?;
+        Ok(row.map(|r| row_to_topic(&r)))
+    }
+}
+
+impl_dual_fn! {
+    async fn get_topic_by_slug(pool, slug: &str) -> Result<Option<FaqTopic>> {
+        let row = sqlx::query(&format!("SELECT {} FROM faq_topics WHERE slug = ?", TOPIC_COLUMNS))
+            .bind(slug)
+            .fetch_optional(pool)
+            .await
+            .context("Failed to get FAQ topic")?;
+        Ok(row.map(|r| row_to_topic(&r)))
+    }
+}
+
+impl_dual_fn! {
+    async fn update_topic(pool, topic: &FaqTopic) -> Result<FaqTopic> {
+        let now = Utc::now();
+        sqlx::query(
+            "UPDATE faq_topics SET slug = ?, title = ?, description = ?, position = ?, updated_at = ? WHERE id = ?",
+        )
+        .bind(&topic.slug)
+        .bind(&topic.title)
+        .bind(&topic.description)
+        .bind(topic.position)
+        .bind(now)
+        .bind(topic.id)
+        .execute(pool)
+        .await
+        .context("Failed to update FAQ topic")?;
+        Ok(FaqTopic {
+            updated_at: now,
+            ..topic.clone()
+        })
+    }
+}
+
+impl_dual_fn! {
+    async fn delete_topic(pool, id: i64) -> Result<bool> {
+        let mut tx = pool.begin().await.context("Failed to start transaction")?;
+        sqlx::query("DELETE FROM faq_items WHERE topic_id = ?")
+            .bind(id)
+            .execute(&mut *tx)
+            .await
+            .context("Failed to delete FAQ questions")?;
+        let result = sqlx::query("DELETE FROM faq_topics WHERE id = ?")
+            .bind(id)
+            .execute(&mut *tx)
+            .await
+            .context("Failed to delete FAQ topic")?;
+        tx.commit().await.context("Failed to commit transaction")?;
+        Ok(result.rows_affected() > 0)
+    }
+}
+
+impl_dual_fn! {
+    async fn list_items(pool, topic_ids: &[i64]) -> Result<Vec<FaqItem>> {
+        let placeholders = vec!["?"; topic_ids.len()].join(", ");
+        let sql = format!(
+            "SELECT {} FROM faq_items WHERE topic_id IN ({}) ORDER BY topic_id, position, id",
+            ITEM_COLUMNS, placeholders
+        );
+        let mut query = sqlx::query(&sql);
+        for id in topic_ids {
+            query = query.bind(id);
+        }
+        let rows = query
+            .fetch_all(pool)
+            .await
+            .context("Failed to list FAQ questions")?;
+        Ok(rows.iter().map(row_to_item).collect())
+    }
+}
+
+impl_dual_fn! {
+    async fn get_item(pool, id: i64) -> Result<Option<FaqItem>> {
+        let row = sqlx::query(&format!("SELECT {} FROM faq_items WHERE id = ?", ITEM_COLUMNS))
+            .bind(id)
+            .fetch_optional(pool)
+            .await
+            .context("Failed to get FAQ question")?;
+        Ok(row.map(|r| row_to_item(&r)))
+    }
+}
+
+impl_dual_fn! {
+    async fn update_item(pool, item: &FaqItem) -> Result<FaqItem> {
+        let now = Utc::now();
+        sqlx::query(
+            "UPDATE faq_items SET topic_id = ?, question = ?, answer = ?, answer_html = ?, position = ?, updated_at = ? WHERE id = ?",
+        )
+        .bind(item.topic_id)
+        .bind(&item.question)
+        .bind(&item.answer)
+        .bind(&item.answer_html)
+        .bind(item.position)
+        .bind(now)
+        .bind(item.id)
+        .execute(pool)
+        .await
+        .context("Failed to update FAQ question")?;
+        Ok(FaqItem {
+            updated_at: now,
+            ..item.clone()
+        })
+    }
+}
+
+impl_dual_fn! {
+    async fn delete_item(pool, id: i64) -> Result<bool> {
+        let result = sqlx::query("DELETE FROM faq_items WHERE id = ?")
+            .bind(id)
+            .execute(pool)
+            .await
+            .context("Failed to delete FAQ question")?;
+        Ok(result.rows_affected() > 0)
+    }
+}
+
+fn row_to_topic<'r, R>(row: &'r R) -> FaqTopic
+where
+    R: sqlx::Row,
+    &'r str: sqlx::ColumnIndex<R>,
+    String: sqlx::Decode<'r, R::Database> + sqlx::Type<R::Database>,
+    i64: sqlx::Decode<'r, R::Database> + sqlx::Type<R::Database>,
+    i32: sqlx::Decode<'r, R::Database> + sqlx::Type<R::Database>,
+    DateTime<Utc>: sqlx::Decode<'r, R::Database> + sqlx::Type<R::Database>,
+{
+    FaqTopic {
+        id: row.get("id"),
+        slug: row.get("slug"),
+        title: row.get("title"),
+        description: row.get("description"),
+        position: row.get("position"),
+        created_at: row.get("created_at"),
+        updated_at: row.get("updated_at"),
+    }
+}
+
+fn row_to_item<'r, R>(row: &'r R) -> FaqItem
+where
+    R: sqlx::Row,
+    &'r str: sqlx::ColumnIndex<R>,
+    String: sqlx::Decode<'r, R::Database> + sqlx::Type<R::Database>,
+    i64: sqlx::Decode<'r, R::Database> + sqlx::Type<R::Database>,
+    i32: sqlx::Decode<'r, R::Database> + sqlx::Type<R::Database>,
+    DateTime<Utc>: sqlx::Decode<'r, R::Database> + sqlx::Type<R::Database>,
+{
+    FaqItem {
+        id: row.get("id"),
+        topic_id: row.get("topic_id"),
+        question: row.get("question"),
+        answer: row.get("answer"),
+        answer_html: row.get("answer_html"),
+        position: row.get("position"),
+        created_at: row.get("created_at"),
+        updated_at: row.get("updated_at"),
+    }
+}
+
+async fn create_topic_sqlite(pool: &SqlitePool, topic: &FaqTopic) -> Result<FaqTopic> {
+    let now = Utc::now();
+    let result = sqlx::query(
+        "INSERT INTO faq_topics (slug, title, description, position, created_at, updated_at) \
+         VALUES (?, ?, ?, ?, ?, ?)",
+    )
+    .bind(&topic.slug)
+    .bind(&topic.title)
+    .bind(&topic.description)
+    .bind(topic.position)
+    .bind(now)
+    .bind(now)
+    .execute(pool)
+    .await
+    .context("Failed to create FAQ topic")?;
+
+    Ok(FaqTopic {
+        id: result.last_insert_rowid(),
+        created_at: now,
+        updated_at: now,
+        ..topic.clone()
+    })
+}
+
+async fn create_item_sqlite(pool: &SqlitePool, item: &FaqItem) -> Result<FaqItem> {
+    let now = Utc::now();
+    let result = sqlx::query(
+        "INSERT INTO faq_items (topic_id, question, answer, answer_html, position, created_at, updated_at) \
+         VALUES (?, ?, ?, ?, ?, ?, ?)",
+    )
+    .bind(item.topic_id)
+    .bind(&item.question)
+    .bind(&item.answer)
+    .bind(&item.answer_html)
+    .bind(item.position)
+    .bind(now)
+    .bind(now)
+    .execute(pool)
+    .await
+    .context("Failed to create FAQ question")?;
+
+    Ok(FaqItem {
+        id: result.last_insert_rowid(),
+        created_at: now,
+        updated_at: now,
+        ..item.clone()
+    })
+}
+
+async fn create_topic_mysql(pool: &MySqlPool, topic: &FaqTopic) -> Result<FaqTopic> {
+    let now = Utc::now();
+    let result = sqlx::query(
+        "INSERT INTO faq_topics (slug, title, description, position, created_at, updated_at) \
+         VALUES (?, ?, ?, ?, ?, ?)",
+    )
+    .bind(&topic.slug)
+    .bind(&topic.title)
+    .bind(&topic.description)
+    .bind(topic.position)
+    .bind(now)
+    .bind(now)
+    .execute(pool)
+    .await
+    .context("Failed to create FAQ topic")?;
+
+    Ok(FaqTopic {
+        id: result.last_insert_id() as i64,
+        created_at: now,
+        updated_at: now,
+        ..topic.clone()
+    })
+}
+
+async fn create_item_mysql(pool: &MySqlPool, item: &FaqItem) -> Result<FaqItem> {
+    let now = Utc::now();
+    let result = sqlx::query(
+        "INSERT INTO faq_items (topic_id, question, answer, answer_html, position, created_at, updated_at) \
+         VALUES (?, ?, ?, ?, ?, ?, ?)",
+    )
+    .bind(item.topic_id)
+    .bind(&item.question)
+    .bind(&item.answer)
+    .bind(&item.answer_html)
+    .bind(item.position)
+    .bind(now)
+    .bind(now)
+    .execute(pool)
+    .await
+    .context("Failed to create FAQ question")?;
+
+    Ok(FaqItem {
+        id: result.last_insert_id() as i64,
+        created_at: now,
+        updated_at: now,
+        ..item.clone()
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::{create_test_pool, migrations};
+
+    fn topic(slug: &str, position: i32) -> FaqTopic {
+        let now = Utc::now();
+        FaqTopic {
+            id: 0,
+            slug: slug.to_string(),
+            title: slug.to_string(),
+            description: String::new(),
+            position,
+            created_at: now,
+            updated_at: now,
+        }
+    }
+
+    fn item(topic_id: i64, question: &str, position: i32) -> FaqItem {
+        let now = Utc::now();
+        FaqItem {
+            id: 0,
+            topic_id,
+            question: question.to_string(),
+            answer: "Yes.".to_string(),
+            answer_html: "<p>Yes.</p>".to_string(),
+            position,
+            created_at: now,
+            updated_at: now,
+        }
+    }
+
+    #[tokio::test]
+    async fn topics_keep_their_questions_in_order() {
+        let pool = create_test_pool()
+            .await
+            .expect("Failed to create test pool");
+        migrations::run_migrations(&pool)
+            .await
+            .expect("Failed to run migrations");
+        let repo = SqlxFaqRepository::new(pool);
+
+        let billing = repo.create_topic(&topic("billing", 1)).await.unwrap();
+        let accounts = repo.create_topic(&topic("accounts", 0)).await.unwrap();
+        let slugs: Vec<String> = repo
+            .list_topics()
+            .await
+            .unwrap()
+            .into_iter()
+            .map(|t| t.slug)
+            .collect();
+        assert_eq!(slugs, ["accounts", "billing"]);
+
+        repo.create_item(&item(billing.id, "Refunds?", 1))
+            .await
+            .unwrap();
+        repo.create_item(&item(billing.id, "Invoices?", 0))
+            .await
+            .unwrap();
+        let reset = repo
+            .create_item(&item(accounts.id, "Reset password?", 0))
+            .await
+            .unwrap();
+        let questions: Vec<String> = repo
+            .list_items(&[billing.id])
+            .await
+            .unwrap()
+            .into_iter()
+            .map(|i| i.question)
+            .collect();
+        assert_eq!(questions, ["Invoices?", "Refunds?"]);
+        assert_eq!(
+            repo.list_items(&[accounts.id, billing.id])
+                .await
+                .unwrap()
+                .len(),
+            3
+        );
+
+        assert!(repo.delete_topic(accounts.id).await.unwrap());
+        assert!(repo.get_item(reset.id).await.unwrap().is_none());
+        assert!(repo.get_topic_by_slug("accounts").await.unwrap().is_none());
+    }
+}
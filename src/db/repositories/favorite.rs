@@ -0,0 +1,332 @@
+//! Reader favorites repository
+//!
+//! Adding and removing a favorite keeps `articles.favorite_count` in step in
+//! the same transaction. Favorites of drafts stay stored but are not listed.
+
+use anyhow::{Context, Result};
+use async_trait::async_trait;
+use chrono::Utc;
+use sqlx::{MySqlPool, Row, SqlitePool};
+use std::sync::Arc;
+
+use crate::db::repositories::category::public_article_sql;
+use crate::db::DynDatabasePool;
+use crate::models::FavoriteArticle;
+
+/// Repository trait for reader favorites
+#[async_trait]
+pub trait FavoriteRepository: Send + Sync {
+    /// Save an article to a user's favorites; false when already saved
+    async fn add(&self, user_id: i64, article_id: i64) -> Result<bool>;
+
+    /// Remove an article from a user's favorites; false when not saved
+    async fn remove(&self, user_id: i64, article_id: i64) -> Result<bool>;
+
+    /// Whether the user saved the article
+    async fn contains(&self, user_id: i64, article_id: i64) -> Result<bool>;
+
+    /// Published favorites of a user, most recently saved first
+    async fn list(&self, user_id: i64, offset: i64, limit: i64) -> Result<Vec<FavoriteArticle>>;
+
+    /// Number of published favorites of a user
+    async fn count(&self, user_id: i64) -> Result<i64>;
+
+    /// Whether the user shows their favorites publicly
+    async fn is_public(&self, user_id: i64) -> Result<bool>;
+
+    /// Show or hide the user's favorites publicly
+    async fn set_public(&self, user_id: i64, public: bool) -> Result<()>;
+}
+
+/// SQLx-based favorites repository
+pub struct SqlxFavoriteRepository {
+    pool: DynDatabasePool,
+}
+
+impl SqlxFavoriteRepository {
+    pub fn new(pool: DynDatabasePool) -> Self {
+        Self { pool }
+    }
+
+    /// Create a boxed repository for use with dependency injection
+    pub fn boxed(pool: DynDatabasePool) -> Arc<dyn FavoriteRepository> {
+        Arc::new(Self::new(pool))
+    }
+}
+
+#[async_trait]
+impl FavoriteRepository for SqlxFavoriteRepository {
+    async fn add(&self, user_id: i64, article_id: i64) -> Result<bool> {
+        dispatch!(self, add, user_id, article_id)
+    }
+
+    async fn remove(&self, user_id: i64, article_id: i64) -> Result<bool> {
+        dispatch!(self, remove, user_id, article_id)
+    }
+
+    async fn contains(&self, user_id: i64, article_id: i64) -> Result<bool> {
+        dispatch!(self, contains, user_id, article_id)
+    }
+
+    async fn list(&self, user_id: i64, offset: i64, limit: i64) -> Result<Vec<FavoriteArticle>> {
+        dispatch!(self, list, user_id, offset, limit)
+    }
+
+    async fn count(&self, user_id: i64) -> Result<i64> {
+        dispatch!(self, count, user_id)
+    }
+
+    async fn is_public(&self, user_id: i64) -> Result<bool> {
+        dispatch!(self, is_public, user_id)
+    }
+
+    async fn set_public(&self, user_id: i64, public: bool) -> Result<()> {
+        dispatch!(self, set_public, user_id, public)
+    }
+}
+
+// ============================================================================
+// Shared implementations (identical SQL)
+// ============================================================================
+
+impl_dual_fn! {
+    async fn remove(pool, user_id: i64, article_id: i64) -> Result<bool> {
+        let mut tx = pool.begin().await.context("Failed to start transaction")?;
+        let removed = sqlx::query("DELETE FROM favorites WHERE user_id = ? AND article_id = ?")
+            .bind(user_id)
+            .bind(article_id)
+            .execute(&mut *tx)
+            .await
+            .context("Failed to remove favorite")?
+            .rows_affected()
+            > 0;
+        if removed {
+            sqlx::query(
+                "UPDATE articles SET favorite_count = favorite_count - 1 \
+                 WHERE id = ? AND favorite_count > 0",
+            )
+            .bind(article_id)
+            .execute(&mut *tx)
+            .await
+            .context("Failed to update favorite count")?;
+        }
+        tx.commit().await.context("Failed to commit transaction")?;
+        Ok(removed)
+    }
+}
+
+impl_dual_fn! {
+    async fn contains(pool, user_id: i64, article_id: i64) -> Result<bool> {
+        let row = sqlx::query("SELECT 1 FROM favorites WHERE user_id = ? AND article_id = ?")
+            .bind(user_id)
+            .bind(article_id)
+            .fetch_optional(pool)
+            .await
+            .context("Failed to check favorite")?;
+        Ok(row.is_some())
+    }
+}
+
+impl_dual_fn! {
+    async fn list(pool, user_id: i64, offset: i64, limit: i64) -> Result<Vec<FavoriteArticle>> {
+        let rows = sqlx::query(&format!(
+            "SELECT a.id, a.slug, a.title, a.thumbnail, a.published_at, a.favorite_count, \
+             f.created_at AS favorited_at \
+             FROM favorites f JOIN articles a ON a.id = f.article_id \
+             WHERE f.user_id = ? AND {} \
+             ORDER BY f.created_at DESC, a.id DESC LIMIT ? OFFSET ?",
+            public_article_sql("a.")
+        ))
+        .bind(user_id)
+        .bind(limit)
+        .bind(offset)
+        .fetch_all(pool)
+        .await
+        .context("Failed to list favorites")?;
+        Ok(rows
+            .iter()
+            .map(|row| FavoriteArticle {
+                article_id: row.get("id"),
+                slug: row.get("slug"),
+                title: row.get("title"),
+                thumbnail: row.get("thumbnail"),
+                published_at: row.get("published_at"),
+                favorite_count: row.get("favorite_count"),
+                favorited_at: row.get("favorited_at"),
+            })
+            .collect())
+    }
+}
+
+impl_dual_fn! {
+    async fn count(pool, user_id: i64) -> Result<i64> {
+        sqlx::query_scalar(&format!(
+            "SELECT COUNT(*) FROM favorites f JOIN articles a ON a.id = f.article_id \
+             WHERE f.user_id = ? AND {}",
+            public_article_sql("a.")
+        ))
+        .bind(user_id)
+        .fetch_one(pool)
+        .await
+        .context("Failed to count favorites")
+    }
+}
+
+impl_dual_fn! {
+    async fn is_public(pool, user_id: i64) -> Result<bool> {
+        let public: Option<bool> =
+            sqlx::query_scalar("SELECT is_public FROM favorite_settings WHERE user_id = ?")
+                .bind(user_id)
+                .fetch_optional(pool)
+                .await
+                .context("Failed to get favorite settings")?;
+        Ok(public.unwrap_or(false))
+    }
+}
+
+// ============================================================================
+// Dialect-specific inserts
+// ============================================================================
+
+async fn add_sqlite(pool: &SqlitePool, user_id: i64, article_id: i64) -> Result<bool> {
+    let mut tx = pool.begin().await.context("Failed to start transaction")?;
+    let added = sqlx::query(
+        "INSERT OR IGNORE INTO favorites (user_id, article_id, created_at) VALUES (?, ?, ?)",
+    )
+    .bind(user_id)
+    .bind(article_id)
+    .bind(Utc::now())
+    .execute(&mut *tx)
+    .await
+    .context("Failed to add favorite")?
+    .rows_affected()
+        > 0;
+    if added {
+        sqlx::query("UPDATE articles SET favorite_count = favorite_count + 1 WHERE id = ?")
+            .bind(article_id)
+            .execute(&mut *tx)
+            .await
+            .context("Failed to update favorite count")?;
+    }
+    tx.commit().await.context("Failed to commit transaction")?;
+    Ok(added)
+}
+
+async fn add_mysql(pool: &MySqlPool, user_id: i64, article_id: i64) -> Result<bool> {
+    let mut tx = pool.begin().await.context("Failed to start transaction")?;
+    let added = sqlx::query(
+        "INSERT IGNORE INTO favorites (user_id, article_id, created_at) VALUES (?, ?, ?)",
+    )
+    .bind(user_id)
+    .bind(article_id)
+    .bind(Utc::now())
+    .execute(&mut *tx)
+    .await
+    .context("Failed to add favorite")?
+    .rows_affected()
+        > 0;
+    if added {
+        sqlx::query("UPDATE articles SET favorite_count = favorite_count + 1 WHERE id = ?")
+            .bind(article_id)
+            .execute(&mut *tx)
+            .await
+            .context("Failed to update favorite count")?;
+    }
+    tx.commit().await.context("Failed to commit transaction")?;
+    Ok(added)
+}
+
+async fn set_public_sqlite(pool: &SqlitePool, user_id: i64, public: bool) -> Result<()> {
+    sqlx::query(
+        "INSERT INTO favorite_settings (user_id, is_public) VALUES (?, ?) \
+         ON CONFLICT(user_id) DO UPDATE SET is_public = excluded.is_public",
+    )
+    .bind(user_id)
+    .bind(public)
+    .execute(pool)
+    .await
+    .context("Failed to save favorite settings")?;
+    Ok(())
+}
+
+async fn set_public_mysql(pool: &MySqlPool, user_id: i64, public: bool) -> Result<()> {
+    sqlx::query(
+        "INSERT INTO favorite_settings (user_id, is_public) VALUES (?, ?) \
+         ON DUPLICATE KEY UPDATE is_public = VALUES(is_public)",
+    )
+    .bind(user_id)
+    .bind(public)
+    .execute(pool)
+    .await
+    .context("Failed to save favorite settings")?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::{create_test_pool, migrations};
+
+    #[tokio::test]
+    async fn favorites_keep_article_counts() {
+        let pool = create_test_pool()
+            .await
+            .expect("Failed to create test pool");
+        migrations::run_migrations(&pool)
+            .await
+            .expect("Failed to run migrations");
+        let sqlite = pool.as_sqlite().unwrap();
+        for (id, name) in [(1, "alice"), (2, "bob")] {
+            sqlx::query("INSERT INTO users (id, username, email, password_hash, role) VALUES (?, ?, ?, 'x', 'author')")
+                .bind(id)
+                .bind(name)
+                .bind(format!("{}@example.com", name))
+                .execute(sqlite)
+                .await
+                .unwrap();
+        }
+        for (slug, status) in [("a", "published"), ("b", "published"), ("c", "draft")] {
+            sqlx::query(
+                "INSERT INTO articles (slug, title, content, content_html, author_id, category_id, status) \
+                 VALUES (?, ?, '', '', 1, 1, ?)",
+            )
+            .bind(slug)
+            .bind(slug.to_uppercase())
+            .bind(status)
+            .execute(sqlite)
+            .await
+            .unwrap();
+        }
+        let repo = SqlxFavoriteRepository::new(pool.clone());
+
+        assert!(repo.add(1, 1).await.unwrap());
+        assert!(!repo.add(1, 1).await.unwrap());
+        assert!(repo.add(2, 1).await.unwrap());
+        assert!(repo.add(1, 2).await.unwrap());
+        assert!(repo.add(1, 3).await.unwrap());
+        assert!(repo.contains(1, 2).await.unwrap());
+        assert!(!repo.contains(2, 2).await.unwrap());
+
+        let listed = repo.list(1, 0, 10).await.unwrap();
+        let slugs: Vec<(&str, i64)> = listed
+            .iter()
+            .map(|f| (f.slug.as_str(), f.favorite_count))
+            .collect();
+        assert_eq!(slugs, vec![("b", 1), ("a", 2)]);
+        assert_eq!(repo.count(1).await.unwrap(), 2);
+
+        assert!(repo.remove(2, 1).await.unwrap());
+        assert!(!repo.remove(2, 1).await.unwrap());
+        let count: i64 = sqlx::query_scalar("SELECT favorite_count FROM articles WHERE id = 1")
+            .fetch_one(sqlite)
+            .await
+            .unwrap();
+        assert_eq!(count, 1);
+
+        assert!(!repo.is_public(1).await.unwrap());
+        repo.set_public(1, true).await.unwrap();
+        assert!(repo.is_public(1).await.unwrap());
+        repo.set_public(1, false).await.unwrap();
+        assert!(!repo.is_public(1).await.unwrap());
+    }
+}
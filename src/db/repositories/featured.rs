@@ -0,0 +1,263 @@
+//! Featured article repository.
+
+use crate::db::repositories::category::public_article_sql;
+use crate::db::DynDatabasePool;
+use crate::models::{FeaturedArticle, FeaturedItemInput};
+use anyhow::{Context, Result};
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use std::collections::HashMap;
+use std::sync::Arc;
+
+const FEATURED_COLUMNS: &str =
+    "f.article_id, a.slug, a.title, a.status, f.position, f.expires_at, f.created_at";
+
+#[async_trait]
+pub trait FeaturedRepository: Send + Sync {
+    /// The whole list by position, drafts and expired entries included
+    async fn list(&self) -> Result<Vec<FeaturedArticle>>;
+    /// Replace the list, in order; entries already on it keep their
+    /// `created_at`
+    async fn set(&self, items: &[FeaturedItemInput]) -> Result<()>;
+    /// Take an article off the list
+    async fn remove(&self, article_id: i64) -> Result<bool>;
+    /// Published articles on the list that have not expired at `now`, by
+    /// position
+    async fn active_article_ids(&self, now: DateTime<Utc>, limit: i64) -> Result<Vec<i64>>;
+    /// Which of the given article ids exist
+    async fn existing_article_ids(&self, article_ids: &[i64]) -> Result<Vec<i64>>;
+}
+
+pub struct SqlxFeaturedRepository {
+    pool: DynDatabasePool,
+}
+
+impl SqlxFeaturedRepository {
+    pub fn new(pool: DynDatabasePool) -> Self {
+        Self { pool }
+    }
+
+    pub fn boxed(pool: DynDatabasePool) -> Arc<dyn FeaturedRepository> {
+        Arc::new(Self::new(pool))
+    }
+}
+
+#[async_trait]
+impl FeaturedRepository for SqlxFeaturedRepository {
+    async fn list(&self) -> Result<Vec<FeaturedArticle>> {
+        dispatch!(self, list)
+    }
+
+    async fn set(&self, items: &[FeaturedItemInput]) -> Result<()> {
+        dispatch!(self, set, items)
+    }
+
+    async fn remove(&self, article_id: i64) -> Result<bool> {
+        dispatch!(self, remove, article_id)
+    }
+
+    async fn active_article_ids(&self, now: DateTime<Utc>, limit: i64) -> Result<Vec<i64>> {
+        dispatch!(self, active_article_ids, now, limit)
+    }
+
+    async fn existing_article_ids(&self, article_ids: &[i64]) -> Result<Vec<i64>> {
+        if article_ids.is_empty() {
+            return Ok(Vec::new());
+        }
+        dispatch!(self, existing_article_ids, article_ids)
+    }
+}
+
+impl_dual_fn! {
+    async fn list(pool) -> Result<Vec<FeaturedArticle>> {
+        let rows = sqlx::query(&format!(
+            "SELECT {} FROM featured_articles f JOIN articles a ON a.id = f.article_id \
+             ORDER BY f.position, f.article_id",
+            FEATURED_COLUMNS
+        ))
+        .fetch_all(pool)
+        .await
+        .context("Failed to list featured articles")?;
+        Ok(rows.iter().map(row_to_featured).collect())
+    }
+}
+
+impl_dual_fn! {
+    async fn set(pool, items: &[FeaturedItemInput]) -> Result<()> {
+        let mut tx = pool.begin().await.context("Failed to start transaction")?;
+        let rows: Vec<(i64, DateTime<Utc>)> =
+            sqlx::query_as("SELECT article_id, created_at FROM featured_articles")
+                .fetch_all(&mut *tx)
+                .await
+                .context("Failed to read featured articles")?;
+        let created: HashMap<i64, DateTime<Utc>> = rows.into_iter().collect();
+        sqlx::query("DELETE FROM featured_articles")
+            .execute(&mut *tx)
+            .await
+            .context("Failed to clear featured articles")?;
+        let now = Utc::now();
+        for (position, item) in items.iter().enumerate() {
+            sqlx::query(
+                "INSERT INTO featured_articles (article_id, position, expires_at, created_at) VALUES (?, ?, ?, ?)",
+            )
+            .bind(item.article_id)
+            .bind(position as i32)
+            .bind(item.expires_at)
+            .bind(created.get(&item.article_id).copied().unwrap_or(now))
+            .execute(&mut *tx)
+            .await
+            .context("Failed to add featured article")?;
+        }
+        tx.commit().await.context("Failed to commit transaction")?;
+        Ok(())
+    }
+}
+
+impl_dual_fn! {
+    async fn remove(pool, article_id: i64) -> Result<bool> {
+        let result = sqlx::query("DELETE FROM featured_articles WHERE article_id = ?")
+            .bind(article_id)
+            .execute(pool)
+            .await
+            .context("Failed to remove featured article")?;
+        Ok(result.rows_affected() > 0)
+    }
+}
+
+impl_dual_fn! {
+    async fn active_article_ids(pool, now: DateTime<Utc>, limit: i64) -> Result<Vec<i64>> {
+        let ids: Vec<i64> = sqlx::query_scalar(&format!(
+            "SELECT f.article_id FROM featured_articles f JOIN articles a ON a.id = f.article_id \
+             WHERE {} AND (f.expires_at IS NULL OR f.expires_at > ?) \
+             ORDER BY f.position, f.article_id LIMIT ?",
+            public_article_sql("a.")
+        ))
+        .bind(now)
+        .bind(limit)
+        .fetch_all(pool)
+        .await
+        .context("Failed to list featured articles")?;
+        Ok(ids)
+    }
+}
+
+impl_dual_fn! {
+    async fn existing_article_ids(pool, article_ids: &[i64]) -> Result<Vec<i64>> {
+        let placeholders = vec!["?"; article_ids.len()].join(", ");
+        let sql = format!("SELECT id FROM articles WHERE id IN ({})", placeholders);
+        let mut query = sqlx::query_scalar(&sql);
+        for id in article_ids {
+            query = query.bind(id);
+        }
+        let ids: Vec<i64> = query
+            .fetch_all(pool)
+            .await
+            .context("Failed to look up articles")?;
+        Ok(ids)
+    }
+}
+
+fn row_to_featured<'r, R>(row: &'r R) -> FeaturedArticle
+where
+    R: sqlx::Row,
+    &'r str: sqlx::ColumnIndex<R>,
+    String: sqlx::Decode<'r, R::Database> + sqlx::Type<R::Database>,
+    i64: sqlx::Decode<'r, R::Database> + sqlx::Type<R::Database>,
+    i32: sqlx::Decode<'r, R::Database> + sqlx::Type<R::Database>,
+    DateTime<Utc>: sqlx::Decode<'r, R::Database> + sqlx::Type<R::Database>,
+{
+    FeaturedArticle {
+        article_id: row.get("article_id"),
+        slug: row.get("slug"),
+        title: row.get("title"),
+        status: row.get("status"),
+        position: row.get("position"),
+        expires_at: row.get("expires_at"),
+        created_at: row.get("created_at"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::{create_test_pool, migrations};
+    use chrono::Duration;
+
+    fn item(article_id: i64, expires_at: Option<DateTime<Utc>>) -> FeaturedItemInput {
+        FeaturedItemInput {
+            article_id,
+            expires_at,
+        }
+    }
+
+    #[tokio::test]
+    async fn active_list_skips_drafts_and_expired_entries() {
+        let pool = create_test_pool()
+            .await
+            .expect("Failed to create test pool");
+        migrations::run_migrations(&pool)
+            .await
+            .expect("Failed to run migrations");
+        let sqlite = pool.as_sqlite().unwrap();
+        let user_id = sqlx::query(
+            "INSERT INTO users (username, email, password_hash, role) VALUES ('u', 'u@example.com', 'x', 'admin')",
+        )
+        .execute(sqlite)
+        .await
+        .unwrap()
+        .last_insert_rowid();
+        let mut ids = Vec::new();
+        for (slug, status) in [
+            ("a", "published"),
+            ("b", "draft"),
+            ("c", "published"),
+            ("d", "published"),
+        ] {
+            let id = sqlx::query(
+                "INSERT INTO articles (slug, title, content, content_html, author_id, category_id, status) VALUES (?, ?, '', '', ?, 1, ?)",
+            )
+            .bind(slug)
+            .bind(slug.to_uppercase())
+            .bind(user_id)
+            .bind(status)
+            .execute(sqlite)
+            .await
+            .unwrap()
+            .last_insert_rowid();
+            ids.push(id);
+        }
+        let repo = SqlxFeaturedRepository::new(pool);
+        let now = Utc::now();
+
+        repo.set(&[
+            item(ids[2], None),
+            item(ids[1], None),
+            item(ids[3], Some(now - Duration::hours(1))),
+            item(ids[0], Some(now + Duration::hours(1))),
+        ])
+        .await
+        .unwrap();
+        let list = repo.list().await.unwrap();
+        let slugs: Vec<&str> = list.iter().map(|f| f.slug.as_str()).collect();
+        assert_eq!(slugs, vec!["c", "b", "d", "a"]);
+        assert!(list[2].is_expired(now));
+        assert_eq!(
+            repo.active_article_ids(now, 10).await.unwrap(),
+            vec![ids[2], ids[0]]
+        );
+        assert_eq!(repo.active_article_ids(now, 1).await.unwrap(), vec![ids[2]]);
+
+        let created = list[0].created_at;
+        repo.set(&[item(ids[0], None), item(ids[2], None)])
+            .await
+            .unwrap();
+        let list = repo.list().await.unwrap();
+        assert_eq!(list.len(), 2);
+        assert_eq!(list[1].article_id, ids[2]);
+        assert_eq!(list[1].created_at, created);
+
+        assert!(repo.remove(ids[0]).await.unwrap());
+        assert!(!repo.remove(ids[0]).await.unwrap());
+        assert_eq!(repo.list().await.unwrap().len(), 1);
+    }
+}
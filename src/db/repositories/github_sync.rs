@@ -0,0 +1,150 @@
+//! Repository for Markdown files published from GitHub
+
+use anyhow::{Context, Result};
+use async_trait::async_trait;
+use chrono::Utc;
+use std::sync::Arc;
+
+use crate::db::DynDatabasePool;
+
+/// A repository file and the article it was published as
+#[derive(Debug, Clone)]
+pub struct SyncedFile {
+    pub path: String,
+    pub article_id: i64,
+    /// Commit the article content was last taken from
+    pub commit_sha: String,
+}
+
+/// Repository trait for GitHub publish state
+#[async_trait]
+pub trait GithubSyncRepository: Send + Sync {
+    /// Look up a file by its repository path
+    async fn get(&self, path: &str) -> Result<Option<SyncedFile>>;
+
+    /// Record that a file was published at a commit
+    async fn upsert(&self, path: &str, article_id: i64, commit_sha: &str) -> Result<()>;
+}
+
+/// SQLx-based GitHub publish state repository
+pub struct SqlxGithubSyncRepository {
+    pool: DynDatabasePool,
+}
+
+impl SqlxGithubSyncRepository {
+    pub fn new(pool: DynDatabasePool) -> Self {
+        Self { pool }
+    }
+
+    /// Create a boxed repository for use with dependency injection
+    pub fn boxed(pool: DynDatabasePool) -> Arc<dyn GithubSyncRepository> {
+        Arc::new(Self::new(pool))
+    }
+}
+
+#[async_trait]
+impl GithubSyncRepository for SqlxGithubSyncRepository {
+    async fn get(&self, path: &str) -> Result<Option<SyncedFile>> {
+        dispatch!(self, get_synced_file, path)
+    }
+
+    async fn upsert(&self, path: &str, article_id: i64, commit_sha: &str) -> Result<()> {
+        dispatch!(self, upsert_synced_file, path, article_id, commit_sha)
+    }
+}
+
+// ============================================================================
+// Shared implementations (identical SQL)
+// ============================================================================
+
+impl_dual_fn! {
+    async fn get_synced_file(pool, path: &str) -> Result<Option<SyncedFile>> {
+        let row = sqlx::query("SELECT path, article_id, commit_sha FROM github_synced_files WHERE path = ?")
+            .bind(path)
+            .fetch_optional(pool)
+            .await
+            .context("Failed to get synced file")?;
+        Ok(row.map(|row| {
+            use sqlx::Row;
+            SyncedFile {
+                path: row.get("path"),
+                article_id: row.get("article_id"),
+                commit_sha: row.get("commit_sha"),
+            }
+        }))
+    }
+}
+
+impl_dual_fn! {
+    async fn upsert_synced_file(pool, path: &str, article_id: i64, commit_sha: &str) -> Result<()> {
+        let updated = sqlx::query("UPDATE github_synced_files SET article_id = ?, commit_sha = ?, synced_at = ? WHERE path = ?")
+            .bind(article_id)
+            .bind(commit_sha)
+            .bind(Utc::now())
+            .bind(path)
+            .execute(pool)
+            .await
+            .context("Failed to update synced file")?;
+        // MySQL reports 0 affected rows when the values are unchanged, so
+        // check for the row before inserting
+        let exists = updated.rows_affected() > 0
+            || sqlx::query("SELECT id FROM github_synced_files WHERE path = ?")
+                .bind(path)
+                .fetch_optional(pool)
+                .await
+                .context("Failed to check synced file")?
+                .is_some();
+        if !exists {
+            sqlx::query("INSERT INTO github_synced_files (path, article_id, commit_sha, synced_at) VALUES (?, ?, ?, ?)")
+                .bind(path)
+                .bind(article_id)
+                .bind(commit_sha)
+                .bind(Utc::now())
+                .execute(pool)
+                .await
+                .context("Failed to record synced file")?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::{create_test_pool, migrations};
+
+    #[tokio::test]
+    async fn synced_files_track_latest_commit() {
+        let pool = create_test_pool()
+            .await
+            .expect("Failed to create test pool");
+        migrations::run_migrations(&pool)
+            .await
+            .expect("Failed to run migrations");
+        let sqlite = pool.as_sqlite().unwrap();
+        let user_id = sqlx::query(
+            "INSERT INTO users (username, email, password_hash, role) VALUES ('u', 'u@example.com', 'x', 'admin')",
+        )
+        .execute(sqlite)
+        .await
+        .unwrap()
+        .last_insert_rowid();
+        let article_id = sqlx::query(
+            "INSERT INTO articles (slug, title, content, content_html, author_id, category_id, status) VALUES ('a', 'A', '', '', ?, 1, 'draft')",
+        )
+        .bind(user_id)
+        .execute(sqlite)
+        .await
+        .unwrap()
+        .last_insert_rowid();
+        let repo = SqlxGithubSyncRepository::new(pool);
+
+        assert!(repo.get("posts/a.md").await.unwrap().is_none());
+        repo.upsert("posts/a.md", article_id, "aaa").await.unwrap();
+        repo.upsert("posts/a.md", article_id, "bbb").await.unwrap();
+
+        let file = repo.get("posts/a.md").await.unwrap().unwrap();
+        assert_eq!(file.article_id, article_id);
+        assert_eq!(file.commit_sha, "bbb");
+    }
+}
@@ -0,0 +1,211 @@
+//! Inbound webhook repository
+
+use anyhow::{Context, Result};
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use std::sync::Arc;
+
+use crate::db::DynDatabasePool;
+use crate::models::{InboundWebhook, SignatureScheme};
+
+/// Repository trait for inbound webhook endpoints
+#[async_trait]
+pub trait InboundWebhookRepository: Send + Sync {
+    /// Create an endpoint with an already generated token
+    async fn create(
+        &self,
+        name: &str,
+        token: &str,
+        signature: SignatureScheme,
+        secret: Option<&str>,
+    ) -> Result<InboundWebhook>;
+
+    /// List all endpoints, newest first
+    async fn list(&self) -> Result<Vec<InboundWebhook>>;
+
+    /// Look up an endpoint by its URL token
+    async fn get_by_token(&self, token: &str) -> Result<Option<InboundWebhook>>;
+
+    /// Delete an endpoint. Returns whether it existed.
+    async fn delete(&self, id: i64) -> Result<bool>;
+
+    /// Record that a request was accepted
+    async fn touch(&self, id: i64, at: DateTime<Utc>) -> Result<()>;
+}
+
+/// SQLx-based inbound webhook repository
+pub struct SqlxInboundWebhookRepository {
+    pool: DynDatabasePool,
+}
+
+impl SqlxInboundWebhookRepository {
+    pub fn new(pool: DynDatabasePool) -> Self {
+        Self { pool }
+    }
+
+    /// Create a boxed repository for use with dependency injection
+    pub fn boxed(pool: DynDatabasePool) -> Arc<dyn InboundWebhookRepository> {
+        Arc::new(Self::new(pool))
+    }
+}
+
+#[async_trait]
+impl InboundWebhookRepository for SqlxInboundWebhookRepository {
+    async fn create(
+        &self,
+        name: &str,
+        token: &str,
+        signature: SignatureScheme,
+        secret: Option<&str>,
+    ) -> Result<InboundWebhook> {
+        dispatch!(self, create_webhook, name, token, signature, secret)
+    }
+
+    async fn list(&self) -> Result<Vec<InboundWebhook>> {
+        dispatch!(self, list_webhooks)
+    }
+
+    async fn get_by_token(&self, token: &str) -> Result<Option<InboundWebhook>> {
+        dispatch!(self, get_webhook_by_token, token)
+    }
+
+    async fn delete(&self, id: i64) -> Result<bool> {
+        dispatch!(self, delete_webhook, id)
+    }
+
+    async fn touch(&self, id: i64, at: DateTime<Utc>) -> Result<()> {
+        dispatch!(self, touch_webhook, id, at)
+    }
+}
+
+// ============================================================================
+// Shared implementations (identical SQL)
+// ============================================================================
+
+const SELECT_COLUMNS: &str =
+    "SELECT id, name, token, signature, secret, last_received_at, created_at FROM inbound_webhooks";
+
+impl_dual_fn! {
+    async fn create_webhook(pool, name: &str, token: &str, signature: SignatureScheme, secret: Option<&str>) -> Result<InboundWebhook> {
+        sqlx::query("INSERT INTO inbound_webhooks (name, token, signature, secret, created_at) VALUES (?, ?, ?, ?, ?)")
+            .bind(name)
+            .bind(token)
+            .bind(signature.as_str())
+            .bind(secret)
+            .bind(Utc::now())
+            .execute(pool)
+            .await
+            .context("Failed to create inbound webhook")?;
+
+        let row = sqlx::query(&format!("{} WHERE token = ?", SELECT_COLUMNS))
+            .bind(token)
+            .fetch_one(pool)
+            .await
+            .context("Failed to load inbound webhook")?;
+        row_to_webhook(&row)
+    }
+}
+
+impl_dual_fn! {
+    async fn list_webhooks(pool) -> Result<Vec<InboundWebhook>> {
+        let rows = sqlx::query(&format!("{} ORDER BY id DESC", SELECT_COLUMNS))
+            .fetch_all(pool)
+            .await
+            .context("Failed to list inbound webhooks")?;
+        rows.iter().map(row_to_webhook).collect()
+    }
+}
+
+impl_dual_fn! {
+    async fn get_webhook_by_token(pool, token: &str) -> Result<Option<InboundWebhook>> {
+        let row = sqlx::query(&format!("{} WHERE token = ?", SELECT_COLUMNS))
+            .bind(token)
+            .fetch_optional(pool)
+            .await
+            .context("Failed to get inbound webhook")?;
+        row.as_ref().map(row_to_webhook).transpose()
+    }
+}
+
+impl_dual_fn! {
+    async fn delete_webhook(pool, id: i64) -> Result<bool> {
+        let result = sqlx::query("DELETE FROM inbound_webhooks WHERE id = ?")
+            .bind(id)
+            .execute(pool)
+            .await
+            .context("Failed to delete inbound webhook")?;
+        Ok(result.rows_affected() > 0)
+    }
+}
+
+impl_dual_fn! {
+    async fn touch_webhook(pool, id: i64, at: DateTime<Utc>) -> Result<()> {
+        sqlx::query("UPDATE inbound_webhooks SET last_received_at = ? WHERE id = ?")
+            .bind(at)
+            .bind(id)
+            .execute(pool)
+            .await
+            .context("Failed to update inbound webhook")?;
+        Ok(())
+    }
+}
+
+/// Map a row to an inbound webhook (same column types on SQLite and MySQL)
+fn row_to_webhook<'r, R>(row: &'r R) -> Result<InboundWebhook>
+where
+    R: sqlx::Row,
+    &'r str: sqlx::ColumnIndex<R>,
+    String: sqlx::Decode<'r, R::Database> + sqlx::Type<R::Database>,
+    Option<String>: sqlx::Decode<'r, R::Database> + sqlx::Type<R::Database>,
+    i64: sqlx::Decode<'r, R::Database> + sqlx::Type<R::Database>,
+    DateTime<Utc>: sqlx::Decode<'r, R::Database> + sqlx::Type<R::Database>,
+    Option<DateTime<Utc>>: sqlx::Decode<'r, R::Database> + sqlx::Type<R::Database>,
+{
+    let signature: String = row.get("signature");
+    Ok(InboundWebhook {
+        id: row.get("id"),
+        name: row.get("name"),
+        token: row.get("token"),
+        signature: signature.parse().map_err(anyhow::Error::msg)?,
+        secret: row.get("secret"),
+        last_received_at: row.get("last_received_at"),
+        created_at: row.get("created_at"),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::{create_test_pool, migrations};
+
+    #[tokio::test]
+    async fn webhooks_are_found_by_token() {
+        let pool = create_test_pool()
+            .await
+            .expect("Failed to create test pool");
+        migrations::run_migrations(&pool)
+            .await
+            .expect("Failed to run migrations");
+        let repo = SqlxInboundWebhookRepository::new(pool);
+
+        let hook = repo
+            .create("github", "abc123", SignatureScheme::Github, Some("s3cret"))
+            .await
+            .unwrap();
+        assert_eq!(hook.signature, SignatureScheme::Github);
+        assert!(hook.last_received_at.is_none());
+
+        let found = repo.get_by_token("abc123").await.unwrap().unwrap();
+        assert_eq!(found.id, hook.id);
+        assert_eq!(found.secret.as_deref(), Some("s3cret"));
+        assert!(repo.get_by_token("other").await.unwrap().is_none());
+
+        repo.touch(hook.id, Utc::now()).await.unwrap();
+        let listed = repo.list().await.unwrap();
+        assert_eq!(listed.len(), 1);
+        assert!(listed[0].last_received_at.is_some());
+
+        assert!(repo.delete(hook.id).await.unwrap());
+        assert!(!repo.delete(hook.id).await.unwrap());
+    }
+}
@@ -0,0 +1,466 @@
+//! Persistent job queue repository
+
+use anyhow::{Context, Result};
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use sqlx::{MySqlPool, Row, SqlitePool};
+use std::sync::Arc;
+
+use crate::db::DynDatabasePool;
+use crate::models::{QueuedJob, QueuedJobStatus};
+
+/// Repository trait for the `job_queue` table
+#[async_trait]
+pub trait JobQueueRepository: Send + Sync {
+    /// Add a pending job
+    async fn enqueue(
+        &self,
+        kind: &str,
+        payload: &serde_json::Value,
+        max_attempts: u32,
+        run_at: DateTime<Utc>,
+    ) -> Result<QueuedJob>;
+
+    /// Get a job by id
+    async fn get(&self, id: i64) -> Result<Option<QueuedJob>>;
+
+    /// Jobs, newest first, optionally only those with `status`
+    async fn list(&self, status: Option<QueuedJobStatus>, limit: i64) -> Result<Vec<QueuedJob>>;
+
+    /// Number of jobs per status
+    async fn counts(&self) -> Result<Vec<(QueuedJobStatus, i64)>>;
+
+    /// Mark the oldest due pending job running and return it
+    async fn claim(&self, now: DateTime<Utc>) -> Result<Option<QueuedJob>>;
+
+    /// Record a successful attempt
+    async fn complete(&self, id: i64, at: DateTime<Utc>) -> Result<()>;
+
+    /// Record a failed attempt: pending again at `retry_at`, or dead when `None`
+    async fn fail(
+        &self,
+        id: i64,
+        error: &str,
+        retry_at: Option<DateTime<Utc>>,
+        at: DateTime<Utc>,
+    ) -> Result<()>;
+
+    /// Make a dead job pending again with fresh attempts. Returns whether it was dead.
+    async fn retry(&self, id: i64, at: DateTime<Utc>) -> Result<bool>;
+
+    /// Delete a job that is not running. Returns whether it was deleted.
+    async fn delete(&self, id: i64) -> Result<bool>;
+
+    /// Put running jobs claimed before `locked_before` back to pending
+    async fn release_stale(&self, locked_before: DateTime<Utc>) -> Result<u64>;
+
+    /// Delete succeeded jobs that finished before `before`
+    async fn prune_succeeded(&self, before: DateTime<Utc>) -> Result<u64>;
+}
+
+/// SQLx-based job queue repository
+pub struct SqlxJobQueueRepository {
+    pool: DynDatabasePool,
+}
+
+impl SqlxJobQueueRepository {
+    pub fn new(pool: DynDatabasePool) -> Self {
+        Self { pool }
+    }
+
+    /// Create a boxed repository for use with dependency injection
+    pub fn boxed(pool: DynDatabasePool) -> Arc<dyn JobQueueRepository> {
+        Arc::new(Self::new(pool))
+    }
+}
+
+#[async_trait]
+impl JobQueueRepository for SqlxJobQueueRepository {
+    async fn enqueue(
+        &self,
+        kind: &str,
+        payload: &serde_json::Value,
+        max_attempts: u32,
+        run_at: DateTime<Utc>,
+    ) -> Result<QueuedJob> {
+        let payload = payload.to_string();
+        let id = dispatch!(self, insert_job, kind, &payload, max_attempts, run_at)?;
+        self.get(id)
+            .await?
+            .context("Queued job disappeared after insert")
+    }
+
+    async fn get(&self, id: i64) -> Result<Option<QueuedJob>> {
+        dispatch!(self, get_job, id)
+    }
+
+    async fn list(&self, status: Option<QueuedJobStatus>, limit: i64) -> Result<Vec<QueuedJob>> {
+        dispatch!(self, list_jobs, status, limit)
+    }
+
+    async fn counts(&self) -> Result<Vec<(QueuedJobStatus, i64)>> {
+        dispatch!(self, count_jobs)
+    }
+
+    async fn claim(&self, now: DateTime<Utc>) -> Result<Option<QueuedJob>> {
+        dispatch!(self, claim_job, now)
+    }
+
+    async fn complete(&self, id: i64, at: DateTime<Utc>) -> Result<()> {
+        dispatch!(self, complete_job, id, at)
+    }
+
+    async fn fail(
+        &self,
+        id: i64,
+        error: &str,
+        retry_at: Option<DateTime<Utc>>,
+        at: DateTime<Utc>,
+    ) -> Result<()> {
+        dispatch!(self, fail_job, id, error, retry_at, at)
+    }
+
+    async fn retry(&self, id: i64, at: DateTime<Utc>) -> Result<bool> {
+        dispatch!(self, retry_job, id, at)
+    }
+
+    async fn delete(&self, id: i64) -> Result<bool> {
+        dispatch!(self, delete_job, id)
+    }
+
+    async fn release_stale(&self, locked_before: DateTime<Utc>) -> Result<u64> {
+        dispatch!(self, release_stale_jobs, locked_before)
+    }
+
+    async fn prune_succeeded(&self, before: DateTime<Utc>) -> Result<u64> {
+        dispatch!(self, prune_succeeded_jobs, before)
+    }
+}
+
+// ============================================================================
+// Backend-specific implementations (insert id)
+// ============================================================================
+
+const INSERT_JOB: &str = "INSERT INTO job_queue (kind, payload, status, attempts, max_attempts, run_at, created_at) VALUES (?, ?, 'pending', 0, ?, ?, ?)";
+
+async fn insert_job_sqlite(
+    pool: &SqlitePool,
+    kind: &str,
+    payload: &str,
+    max_attempts: u32,
+    run_at: DateTime<Utc>,
+) -> Result<i64> {
+    let result = sqlx::query(INSERT_JOB)
+        .bind(kind)
+        .bind(payload)
+        .bind(max_attempts as i32)
+        .bind(run_at)
+        .bind(Utc::now())
+        .execute(pool)
+        .await
+        .context("Failed to enqueue job")?;
+    Ok(result.last_insert_rowid())
+}
+
+async fn insert_job_mysql(
+    pool: &MySqlPool,
+    kind: &str,
+    payload: &str,
+    max_attempts: u32,
+    run_at: DateTime<Utc>,
+) -> Result<i64> {
+    let result = sqlx::query(INSERT_JOB)
+        .bind(kind)
+        .bind(payload)
+        .bind(max_attempts as i32)
+        .bind(run_at)
+        .bind(Utc::now())
+        .execute(pool)
+        .await
+        .context("Failed to enqueue job")?;
+    Ok(result.last_insert_id() as i64)
+}
+
+// ============================================================================
+// Shared implementations (identical SQL)
+// ============================================================================
+
+const SELECT_COLUMNS: &str = "SELECT id, kind, payload, status, attempts, max_attempts, run_at, locked_at, last_error, created_at, finished_at FROM job_queue";
+
+/// Claims lost to another worker before giving up until the next poll
+const CLAIM_TRIES: usize = 3;
+
+impl_dual_fn! {
+    async fn get_job(pool, id: i64) -> Result<Option<QueuedJob>> {
+        let row = sqlx::query(&format!("{} WHERE id = ?", SELECT_COLUMNS))
+            .bind(id)
+            .fetch_optional(pool)
+            .await
+            .context("Failed to get queued job")?;
+        row.as_ref().map(row_to_job).transpose()
+    }
+}
+
+impl_dual_fn! {
+    async fn list_jobs(pool, status: Option<QueuedJobStatus>, limit: i64) -> Result<Vec<QueuedJob>> {
+        let rows = match status {
+            Some(status) => {
+                sqlx::query(&format!("{} WHERE status = ? ORDER BY id DESC LIMIT ?", SELECT_COLUMNS))
+                    .bind(status.as_str())
+                    .bind(limit)
+                    .fetch_all(pool)
+                    .await
+            }
+            None => {
+                sqlx::query(&format!("{} ORDER BY id DESC LIMIT ?", SELECT_COLUMNS))
+                    .bind(limit)
+                    .fetch_all(pool)
+                    .await
+            }
+        }
+        .context("Failed to list queued jobs")?;
+        rows.iter().map(row_to_job).collect()
+    }
+}
+
+impl_dual_fn! {
+    async fn count_jobs(pool) -> Result<Vec<(QueuedJobStatus, i64)>> {
+        let rows = sqlx::query("SELECT status, COUNT(*) AS count FROM job_queue GROUP BY status")
+            .fetch_all(pool)
+            .await
+            .context("Failed to count queued jobs")?;
+        rows.iter()
+            .map(|row| -> Result<(QueuedJobStatus, i64)> {
+                let status: String = row.get("status");
+                Ok((status.parse().map_err(anyhow::Error::msg)?, row.get("count")))
+            })
+            .collect()
+    }
+}
+
+impl_dual_fn! {
+    async fn claim_job(pool, now: DateTime<Utc>) -> Result<Option<QueuedJob>> {
+        for _ in 0..CLAIM_TRIES {
+            let next = sqlx::query("SELECT id FROM job_queue WHERE status = 'pending' AND run_at <= ? ORDER BY run_at, id LIMIT 1")
+                .bind(now)
+                .fetch_optional(pool)
+                .await
+                .context("Failed to find a due job")?;
+            let Some(next) = next else {
+                return Ok(None);
+            };
+            let id: i64 = next.get("id");
+            // Another worker may claim the same job between the two queries
+            let claimed = sqlx::query("UPDATE job_queue SET status = 'running', attempts = attempts + 1, locked_at = ? WHERE id = ? AND status = 'pending'")
+                .bind(now)
+                .bind(id)
+                .execute(pool)
+                .await
+                .context("Failed to claim job")?;
+            if claimed.rows_affected() == 1 {
+                let row = sqlx::query(&format!("{} WHERE id = ?", SELECT_COLUMNS))
+                    .bind(id)
+                    .fetch_one(pool)
+                    .await
+                    .context("Failed to load claimed job")?;
+                return row_to_job(&row).map(Some);
+            }
+        }
+        Ok(None)
+    }
+}
+
+impl_dual_fn! {
+    async fn complete_job(pool, id: i64, at: DateTime<Utc>) -> Result<()> {
+        sqlx::query("UPDATE job_queue SET status = 'succeeded', locked_at = NULL, finished_at = ? WHERE id = ?")
+            .bind(at)
+            .bind(id)
+            .execute(pool)
+            .await
+            .context("Failed to complete job")?;
+        Ok(())
+    }
+}
+
+impl_dual_fn! {
+    async fn fail_job(pool, id: i64, error: &str, retry_at: Option<DateTime<Utc>>, at: DateTime<Utc>) -> Result<()> {
+        match retry_at {
+            Some(retry_at) => {
+                sqlx::query("UPDATE job_queue SET status = 'pending', locked_at = NULL, last_error = ?, run_at = ? WHERE id = ?")
+                    .bind(error)
+                    .bind(retry_at)
+                    .bind(id)
+                    .execute(pool)
+                    .await
+            }
+            None => {
+                sqlx::query("UPDATE job_queue SET status = 'dead', locked_at = NULL, last_error = ?, finished_at = ? WHERE id = ?")
+                    .bind(error)
+                    .bind(at)
+                    .bind(id)
+                    .execute(pool)
+                    .await
+            }
+        }
+        .context("Failed to record job failure")?;
+        Ok(())
+    }
+}
+
+impl_dual_fn! {
+    async fn retry_job(pool, id: i64, at: DateTime<Utc>) -> Result<bool> {
+        let result = sqlx::query("UPDATE job_queue SET status = 'pending', attempts = 0, run_at = ?, finished_at = NULL WHERE id = ? AND status = 'dead'")
+            .bind(at)
+            .bind(id)
+            .execute(pool)
+            .await
+            .context("Failed to retry job")?;
+        Ok(result.rows_affected() > 0)
+    }
+}
+
+impl_dual_fn! {
+    async fn delete_job(pool, id: i64) -> Result<bool> {
+        let result = sqlx::query("DELETE FROM job_queue WHERE id = ? AND status <> 'running'")
+            .bind(id)
+            .execute(pool)
+            .await
+            .context("Failed to delete job")?;
+        Ok(result.rows_affected() > 0)
+    }
+}
+
+impl_dual_fn! {
+    async fn release_stale_jobs(pool, locked_before: DateTime<Utc>) -> Result<u64> {
+        let result = sqlx::query("UPDATE job_queue SET status = 'pending', locked_at = NULL WHERE status = 'running' AND locked_at < ?")
+            .bind(locked_before)
+            .execute(pool)
+            .await
+            .context("Failed to release stale jobs")?;
+        Ok(result.rows_affected())
+    }
+}
+
+impl_dual_fn! {
+    async fn prune_succeeded_jobs(pool, before: DateTime<Utc>) -> Result<u64> {
+        let result = sqlx::query("DELETE FROM job_queue WHERE status = 'succeeded' AND finished_at < ?")
+            .bind(before)
+            .execute(pool)
+            .await
+            .context("Failed to prune succeeded jobs")?;
+        Ok(result.rows_affected())
+    }
+}
+
+/// Map a row to a queued job (same column types on SQLite and MySQL)
+fn row_to_job<'r, R>(row: &'r R) -> Result<QueuedJob>
+where
+    R: sqlx::Row,
+    &'r str: sqlx::ColumnIndex<R>,
+    String: sqlx::Decode<'r, R::Database> + sqlx::Type<R::Database>,
+    Option<String>: sqlx::Decode<'r, R::Database> + sqlx::Type<R::Database>,
+    i32: sqlx::Decode<'r, R::Database> + sqlx::Type<R::Database>,
+    i64: sqlx::Decode<'r, R::Database> + sqlx::Type<R::Database>,
+    DateTime<Utc>: sqlx::Decode<'r, R::Database> + sqlx::Type<R::Database>,
+    Option<DateTime<Utc>>: sqlx::Decode<'r, R::Database> + sqlx::Type<R::Database>,
+{
+    let status: String = row.get("status");
+    let payload: String = row.get("payload");
+    let attempts: i32 = row.get("attempts");
+    let max_attempts: i32 = row.get("max_attempts");
+    Ok(QueuedJob {
+        id: row.get("id"),
+        kind: row.get("kind"),
+        payload: serde_json::from_str(&payload).context("Invalid job payload")?,
+        status: status.parse().map_err(anyhow::Error::msg)?,
+        attempts: attempts.max(0) as u32,
+        max_attempts: max_attempts.max(0) as u32,
+        run_at: row.get("run_at"),
+        locked_at: row.get("locked_at"),
+        last_error: row.get("last_error"),
+        created_at: row.get("created_at"),
+        finished_at: row.get("finished_at"),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::{create_test_pool, migrations};
+    use chrono::Duration;
+
+    #[tokio::test]
+    async fn jobs_are_claimed_when_due_and_dead_lettered() {
+        let pool = create_test_pool()
+            .await
+            .expect("Failed to create test pool");
+        migrations::run_migrations(&pool)
+            .await
+            .expect("Failed to run migrations");
+        let repo = SqlxJobQueueRepository::new(pool);
+        let now = Utc::now();
+
+        let job = repo
+            .enqueue("test", &serde_json::json!({ "n": 1 }), 2, now)
+            .await
+            .unwrap();
+        assert_eq!(job.status, QueuedJobStatus::Pending);
+        assert_eq!(job.payload["n"], 1);
+        repo.enqueue(
+            "later",
+            &serde_json::json!(null),
+            2,
+            now + Duration::hours(1),
+        )
+        .await
+        .unwrap();
+
+        let claimed = repo.claim(now).await.unwrap().unwrap();
+        assert_eq!(claimed.id, job.id);
+        assert_eq!(claimed.status, QueuedJobStatus::Running);
+        assert_eq!(claimed.attempts, 1);
+        assert!(repo.claim(now).await.unwrap().is_none());
+        assert!(!repo.delete(job.id).await.unwrap());
+
+        repo.fail(job.id, "boom", Some(now), now).await.unwrap();
+        let claimed = repo.claim(now).await.unwrap().unwrap();
+        assert_eq!(claimed.attempts, 2);
+        assert_eq!(claimed.last_error.as_deref(), Some("boom"));
+
+        repo.fail(job.id, "boom again", None, now).await.unwrap();
+        let dead = repo.get(job.id).await.unwrap().unwrap();
+        assert_eq!(dead.status, QueuedJobStatus::Dead);
+        assert_eq!(
+            repo.list(Some(QueuedJobStatus::Dead), 10)
+                .await
+                .unwrap()
+                .len(),
+            1
+        );
+        let counts = repo.counts().await.unwrap();
+        assert!(counts.contains(&(QueuedJobStatus::Dead, 1)));
+        assert!(counts.contains(&(QueuedJobStatus::Pending, 1)));
+
+        assert!(repo.retry(job.id, now).await.unwrap());
+        assert!(!repo.retry(job.id, now).await.unwrap());
+        let claimed = repo.claim(now).await.unwrap().unwrap();
+        assert_eq!(claimed.attempts, 1);
+        assert_eq!(
+            repo.release_stale(now + Duration::seconds(1))
+                .await
+                .unwrap(),
+            1
+        );
+
+        let claimed = repo.claim(now).await.unwrap().unwrap();
+        repo.complete(claimed.id, now).await.unwrap();
+        assert_eq!(
+            repo.prune_succeeded(now + Duration::seconds(1))
+                .await
+                .unwrap(),
+            1
+        );
+        assert!(repo.get(job.id).await.unwrap().is_none());
+        assert_eq!(repo.list(None, 10).await.unwrap().len(), 1);
+    }
+}
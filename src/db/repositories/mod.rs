@@ -3,28 +3,83 @@
 //! Repository pattern implementations for database access.
 //! Each repository handles CRUD operations for a specific entity.
 
+pub mod analytics;
 pub mod article;
+pub mod article_author;
+pub mod attachment;
 pub mod category;
 pub mod comment;
+pub mod custom_field;
+pub mod doc;
+pub mod email_suppression;
+pub mod event;
+pub mod external_identity;
+pub mod faq;
+pub mod favorite;
+pub mod featured;
 pub mod friend_link;
+pub mod github_sync;
+pub mod inbound_webhook;
+pub mod job_queue;
 pub mod nav_item;
 pub mod page;
 pub mod plugin_data;
 pub mod plugin_state;
+pub mod poll;
+pub mod push_subscription;
+pub mod reader_group;
+pub mod reading_progress;
+pub mod redirect;
+pub mod series;
 pub mod session;
 pub mod settings;
+pub mod stats;
+pub mod subscriber;
+pub mod sync;
 pub mod tag;
+pub mod translation;
 pub mod user;
+pub mod user_preferences;
+pub mod webauthn_credential;
 
+pub use analytics::{
+    AnalyticsRepository, DailyVisits, DayCounts, RankedTraffic, SqlxAnalyticsRepository,
+    TrafficDimension, VisitCounts,
+};
 pub use article::{ArticleRepository, SqlxArticleRepository};
+pub use article_author::{ArticleAuthorRepository, SqlxArticleAuthorRepository};
+pub use attachment::{AttachmentRepository, SqlxAttachmentRepository};
 pub use category::{CategoryRepository, SqlxCategoryRepository};
 pub use comment::{CommentRepository, SqlxCommentRepository};
+pub use custom_field::{CustomFieldRepository, SqlxCustomFieldRepository};
+pub use doc::{DocRepository, SqlxDocRepository};
+pub use email_suppression::{EmailSuppressionRepository, SqlxEmailSuppressionRepository};
+pub use event::{EventRepository, SqlxEventRepository};
+pub use external_identity::{ExternalIdentityRepository, SqlxExternalIdentityRepository};
+pub use faq::{FaqRepository, SqlxFaqRepository};
+pub use favorite::{FavoriteRepository, SqlxFavoriteRepository};
+pub use featured::{FeaturedRepository, SqlxFeaturedRepository};
 pub use friend_link::{FriendLinkRepository, SqlxFriendLinkRepository};
+pub use github_sync::{GithubSyncRepository, SqlxGithubSyncRepository, SyncedFile};
+pub use inbound_webhook::{InboundWebhookRepository, SqlxInboundWebhookRepository};
+pub use job_queue::{JobQueueRepository, SqlxJobQueueRepository};
 pub use nav_item::{NavItemRepository, SqlxNavItemRepository};
 pub use page::{PageRepository, SqlxPageRepository};
 pub use plugin_data::{PluginData, PluginDataRepository, SqlxPluginDataRepository};
 pub use plugin_state::{PluginState, PluginStateRepository, SqlxPluginStateRepository};
+pub use poll::{PollRepository, SqlxPollRepository};
+pub use push_subscription::{PushSubscriptionRepository, SqlxPushSubscriptionRepository};
+pub use reader_group::{ReaderGroupRepository, SqlxReaderGroupRepository};
+pub use reading_progress::{ReadingProgressRepository, SqlxReadingProgressRepository};
+pub use redirect::{RedirectRepository, SqlxRedirectRepository};
+pub use series::{SeriesRepository, SqlxSeriesRepository};
 pub use session::{SessionRepository, SqlxSessionRepository};
 pub use settings::{Setting, SettingsRepository, SqlxSettingsRepository};
+pub use stats::{DailyComments, DailyTraffic, SqlxStatsRepository, StatsRepository, TopContent};
+pub use subscriber::{SqlxSubscriberRepository, SubscriberRepository};
+pub use sync::{SqlxSyncRepository, SyncArticle, SyncPage, SyncRepository, Tombstone};
 pub use tag::{SqlxTagRepository, TagRepository};
+pub use translation::{SqlxTranslationRepository, TranslationRepository};
 pub use user::{SqlxUserRepository, UserRepository};
+pub use user_preferences::{SqlxUserPreferencesRepository, UserPreferencesRepository};
+pub use webauthn_credential::{SqlxWebauthnCredentialRepository, WebauthnCredentialRepository};
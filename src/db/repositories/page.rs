@@ -75,6 +75,8 @@ impl PageRepository for SqlxPageRepository {
 
 impl_dual_fn! {
     async fn delete(pool, id: i64) -> Result<()> {
+        // Leave a tombstone for delta sync clients
+        sqlx::query("INSERT INTO sync_tombstones (entity_type, entity_id, slug, status, deleted_at) SELECT 'page', id, slug, status, ? FROM pages WHERE id = ?").bind(Utc::now()).bind(id).execute(pool).await.context("Failed to record page tombstone")?;
         sqlx::query("DELETE FROM pages WHERE id = ?").bind(id).execute(pool).await.context("Failed to delete page")?;
         Ok(())
     }
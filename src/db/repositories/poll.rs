@@ -0,0 +1,401 @@
+//! Poll repository.
+//!
+//! Vote counts are aggregated from `poll_votes` when a poll is loaded.
+//! `poll_voters` holds one row per reader and poll, so a second vote from
+//! the same reader is ignored even when both requests race.
+
+use anyhow::{Context, Result};
+use async_trait::async_trait;
+use chrono::Utc;
+use sqlx::{MySqlPool, Row, SqlitePool};
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use crate::db::DynDatabasePool;
+use crate::models::{CreatePollInput, Poll, PollOption};
+
+#[async_trait]
+pub trait PollRepository: Send + Sync {
+    async fn create(&self, input: &CreatePollInput) -> Result<Poll>;
+    async fn get_by_id(&self, id: i64) -> Result<Option<Poll>>;
+    /// Every poll, newest first
+    async fn list(&self) -> Result<Vec<Poll>>;
+    /// Save the question, `multiple` and `closes_at` of a poll
+    async fn update(&self, poll: &Poll) -> Result<Poll>;
+    async fn delete(&self, id: i64) -> Result<bool>;
+    /// Record a reader's choice; false when they already voted
+    async fn vote(&self, poll_id: i64, voter_key: &str, option_ids: &[i64]) -> Result<bool>;
+    /// Options the reader picked, empty when they have not voted
+    async fn voted_options(&self, poll_id: i64, voter_key: &str) -> Result<Vec<i64>>;
+}
+
+pub struct SqlxPollRepository {
+    pool: DynDatabasePool,
+}
+
+impl SqlxPollRepository {
+    pub fn new(pool: DynDatabasePool) -> Self {
+        Self { pool }
+    }
+
+    pub fn boxed(pool: DynDatabasePool) -> Arc<dyn PollRepository> {
+        Arc::new(Self::new(pool))
+    }
+}
+
+#[async_trait]
+impl PollRepository for SqlxPollRepository {
+    async fn create(&self, input: &CreatePollInput) -> Result<Poll> {
+        dispatch!(self, create, input)
+    }
+
+    async fn get_by_id(&self, id: i64) -> Result<Option<Poll>> {
+        let polls: Vec<Poll> = dispatch!(self, load, Some(id))?;
+        Ok(polls.into_iter().next())
+    }
+
+    async fn list(&self) -> Result<Vec<Poll>> {
+        dispatch!(self, load, None)
+    }
+
+    async fn update(&self, poll: &Poll) -> Result<Poll> {
+        dispatch!(self, update, poll)
+    }
+
+    async fn delete(&self, id: i64) -> Result<bool> {
+        dispatch!(self, delete, id)
+    }
+
+    async fn vote(&self, poll_id: i64, voter_key: &str, option_ids: &[i64]) -> Result<bool> {
+        dispatch!(self, vote, poll_id, voter_key, option_ids)
+    }
+
+    async fn voted_options(&self, poll_id: i64, voter_key: &str) -> Result<Vec<i64>> {
+        dispatch!(self, voted_options, poll_id, voter_key)
+    }
+}
+
+// ============================================================================
+// Shared implementations (identical SQL)
+// ============================================================================
+
+impl_dual_fn! {
+    /// Polls with their options and counts; all polls when `id` is `None`
+    async fn load(pool, id: Option<i64>) -> Result<Vec<Poll>> {
+        let rows = sqlx::query(
+            "SELECT id, question, multiple, closes_at, created_at, updated_at FROM polls \
+             WHERE (? IS NULL OR id = ?) ORDER BY created_at DESC, id DESC",
+        )
+        .bind(id)
+        .bind(id)
+        .fetch_all(pool)
+        .await
+        .context("Failed to load polls")?;
+
+        let option_rows = sqlx::query(
+            "SELECT o.poll_id, o.id, o.label, COUNT(v.option_id) AS votes \
+             FROM poll_options o LEFT JOIN poll_votes v ON v.option_id = o.id \
+             WHERE (? IS NULL OR o.poll_id = ?) \
+             GROUP BY o.poll_id, o.id, o.label, o.sort_order \
+             ORDER BY o.sort_order, o.id",
+        )
+        .bind(id)
+        .bind(id)
+        .fetch_all(pool)
+        .await
+        .context("Failed to load poll options")?;
+        let mut options: HashMap<i64, Vec<PollOption>> = HashMap::new();
+        for row in &option_rows {
+            options
+                .entry(row.get("poll_id"))
+                .or_default()
+                .push(PollOption {
+                    id: row.get("id"),
+                    label: row.get("label"),
+                    votes: row.get("votes"),
+                });
+        }
+
+        let voter_rows = sqlx::query(
+            "SELECT poll_id, COUNT(*) AS voters FROM poll_voters \
+             WHERE (? IS NULL OR poll_id = ?) GROUP BY poll_id",
+        )
+        .bind(id)
+        .bind(id)
+        .fetch_all(pool)
+        .await
+        .context("Failed to count poll voters")?;
+        let voters: HashMap<i64, i64> = voter_rows
+            .iter()
+            .map(|row| (row.get("poll_id"), row.get("voters")))
+            .collect();
+
+        Ok(rows
+            .iter()
+            .map(|row| {
+                let id: i64 = row.get("id");
+                Poll {
+                    id,
+                    question: row.get("question"),
+                    multiple: row.get("multiple"),
+                    closes_at: row.get("closes_at"),
+                    options: options.remove(&id).unwrap_or_default(),
+                    total_voters: voters.get(&id).copied().unwrap_or(0),
+                    created_at: row.get("created_at"),
+                    updated_at: row.get("updated_at"),
+                }
+            })
+            .collect())
+    }
+}
+
+impl_dual_fn! {
+    async fn delete(pool, id: i64) -> Result<bool> {
+        let result = sqlx::query("DELETE FROM polls WHERE id = ?")
+            .bind(id)
+            .execute(pool)
+            .await
+            .context("Failed to delete poll")?;
+        Ok(result.rows_affected() > 0)
+    }
+}
+
+impl_dual_fn! {
+    async fn voted_options(pool, poll_id: i64, voter_key: &str) -> Result<Vec<i64>> {
+        sqlx::query_scalar(
+            "SELECT option_id FROM poll_votes WHERE poll_id = ? AND voter_key = ? ORDER BY option_id",
+        )
+        .bind(poll_id)
+        .bind(voter_key)
+        .fetch_all(pool)
+        .await
+        .context("Failed to get poll vote")
+    }
+}
+
+// ============================================================================
+// Dialect-specific implementations
+// ============================================================================
+
+async fn create_sqlite(pool: &SqlitePool, input: &CreatePollInput) -> Result<Poll> {
+    let now = Utc::now();
+    let mut tx = pool.begin().await.context("Failed to start transaction")?;
+    let poll_id = sqlx::query(
+        "INSERT INTO polls (question, multiple, closes_at, created_at, updated_at) VALUES (?, ?, ?, ?, ?)",
+    )
+    .bind(&input.question)
+    .bind(input.multiple)
+    .bind(input.closes_at)
+    .bind(now)
+    .bind(now)
+    .execute(&mut *tx)
+    .await
+    .context("Failed to create poll")?
+    .last_insert_rowid();
+    for (sort_order, label) in input.options.iter().enumerate() {
+        sqlx::query("INSERT INTO poll_options (poll_id, label, sort_order) VALUES (?, ?, ?)")
+            .bind(poll_id)
+            .bind(label)
+            .bind(sort_order as i32)
+            .execute(&mut *tx)
+            .await
+            .context("Failed to create poll option")?;
+    }
+    tx.commit().await.context("Failed to commit transaction")?;
+
+    load_sqlite(pool, Some(poll_id))
+        .await?
+        .pop()
+        .ok_or_else(|| anyhow::anyhow!("Poll not found after create"))
+}
+
+async fn update_sqlite(pool: &SqlitePool, poll: &Poll) -> Result<Poll> {
+    sqlx::query(
+        "UPDATE polls SET question = ?, multiple = ?, closes_at = ?, updated_at = ? WHERE id = ?",
+    )
+    .bind(&poll.question)
+    .bind(poll.multiple)
+    .bind(poll.closes_at)
+    .bind(Utc::now())
+    .bind(poll.id)
+    .execute(pool)
+    .await
+    .context("Failed to update poll")?;
+    load_sqlite(pool, Some(poll.id))
+        .await?
+        .pop()
+        .ok_or_else(|| anyhow::anyhow!("Poll not found after update"))
+}
+
+async fn vote_sqlite(
+    pool: &SqlitePool,
+    poll_id: i64,
+    voter_key: &str,
+    option_ids: &[i64],
+) -> Result<bool> {
+    let mut tx = pool.begin().await.context("Failed to start transaction")?;
+    let first_vote = sqlx::query(
+        "INSERT OR IGNORE INTO poll_voters (poll_id, voter_key, created_at) VALUES (?, ?, ?)",
+    )
+    .bind(poll_id)
+    .bind(voter_key)
+    .bind(Utc::now())
+    .execute(&mut *tx)
+    .await
+    .context("Failed to record poll voter")?
+    .rows_affected()
+        > 0;
+    if first_vote {
+        for option_id in option_ids {
+            sqlx::query("INSERT INTO poll_votes (poll_id, option_id, voter_key) VALUES (?, ?, ?)")
+                .bind(poll_id)
+                .bind(option_id)
+                .bind(voter_key)
+                .execute(&mut *tx)
+                .await
+                .context("Failed to record poll vote")?;
+        }
+    }
+    tx.commit().await.context("Failed to commit transaction")?;
+    Ok(first_vote)
+}
+
+async fn create_mysql(pool: &MySqlPool, input: &CreatePollInput) -> Result<Poll> {
+    let now = Utc::now();
+    let mut tx = pool.begin().await.context("Failed to start transaction")?;
+    let poll_id = sqlx::query(
+        "INSERT INTO polls (question, multiple, closes_at, created_at, updated_at) VALUES (?, ?, ?, ?, ?)",
+    )
+    .bind(&input.question)
+    .bind(input.multiple)
+    .bind(input.closes_at)
+    .bind(now)
+    .bind(now)
+    .execute(&mut *tx)
+    .await
+    .context("Failed to create poll")?
+    .last_insert_id() as i64;
+    for (sort_order, label) in input.options.iter().enumerate() {
+        sqlx::query("INSERT INTO poll_options (poll_id, label, sort_order) VALUES (?, ?, ?)")
+            .bind(poll_id)
+            .bind(label)
+            .bind(sort_order as i32)
+            .execute(&mut *tx)
+            .await
+            .context("Failed to create poll option")?;
+    }
+    tx.commit().await.context("Failed to commit transaction")?;
+
+    load_mysql(pool, Some(poll_id))
+        .await?
+        .pop()
+        .ok_or_else(|| anyhow::anyhow!("Poll not found after create"))
+}
+
+async fn update_mysql(pool: &MySqlPool, poll: &Poll) -> Result<Poll> {
+    sqlx::query(
+        "UPDATE polls SET question = ?, multiple = ?, closes_at = ?, updated_at = ? WHERE id = ?",
+    )
+    .bind(&poll.question)
+    .bind(poll.multiple)
+    .bind(poll.closes_at)
+    .bind(Utc::now())
+    .bind(poll.id)
+    .execute(pool)
+    .await
+    .context("Failed to update poll")?;
+    load_mysql(pool, Some(poll.id))
+        .await?
+        .pop()
+        .ok_or_else(|| anyhow::anyhow!("Poll not found after update"))
+}
+
+async fn vote_mysql(
+    pool: &MySqlPool,
+    poll_id: i64,
+    voter_key: &str,
+    option_ids: &[i64],
+) -> Result<bool> {
+    let mut tx = pool.begin().await.context("Failed to start transaction")?;
+    let first_vote = sqlx::query(
+        "INSERT IGNORE INTO poll_voters (poll_id, voter_key, created_at) VALUES (?, ?, ?)",
+    )
+    .bind(poll_id)
+    .bind(voter_key)
+    .bind(Utc::now())
+    .execute(&mut *tx)
+    .await
+    .context("Failed to record poll voter")?
+    .rows_affected()
+        > 0;
+    if first_vote {
+        for option_id in option_ids {
+            sqlx::query("INSERT INTO poll_votes (poll_id, option_id, voter_key) VALUES (?, ?, ?)")
+                .bind(poll_id)
+                .bind(option_id)
+                .bind(voter_key)
+                .execute(&mut *tx)
+                .await
+                .context("Failed to record poll vote")?;
+        }
+    }
+    tx.commit().await.context("Failed to commit transaction")?;
+    Ok(first_vote)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::{create_test_pool, migrations};
+
+    #[tokio::test]
+    async fn votes_are_counted_once_per_voter() {
+        let pool = create_test_pool()
+            .await
+            .expect("Failed to create test pool");
+        migrations::run_migrations(&pool)
+            .await
+            .expect("Failed to run migrations");
+        let repo = SqlxPollRepository::new(pool);
+
+        let poll = repo
+            .create(&CreatePollInput {
+                question: "Tabs or spaces?".to_string(),
+                options: vec!["Tabs".to_string(), "Spaces".to_string()],
+                multiple: false,
+                closes_at: None,
+            })
+            .await
+            .unwrap();
+        let labels: Vec<&str> = poll.options.iter().map(|o| o.label.as_str()).collect();
+        assert_eq!(labels, ["Tabs", "Spaces"]);
+        let (tabs, spaces) = (poll.options[0].id, poll.options[1].id);
+
+        assert!(repo.vote(poll.id, "user:1", &[spaces]).await.unwrap());
+        assert!(repo.vote(poll.id, "fp:abc", &[spaces]).await.unwrap());
+        assert!(!repo.vote(poll.id, "user:1", &[tabs]).await.unwrap());
+        assert_eq!(
+            repo.voted_options(poll.id, "user:1").await.unwrap(),
+            [spaces]
+        );
+        assert!(repo
+            .voted_options(poll.id, "user:2")
+            .await
+            .unwrap()
+            .is_empty());
+
+        let poll = repo.get_by_id(poll.id).await.unwrap().unwrap();
+        assert_eq!(poll.total_voters, 2);
+        assert_eq!(poll.options[0].votes, 0);
+        assert_eq!(poll.options[1].votes, 2);
+        assert_eq!(repo.list().await.unwrap(), vec![poll.clone()]);
+
+        assert!(repo.delete(poll.id).await.unwrap());
+        assert!(repo.get_by_id(poll.id).await.unwrap().is_none());
+        assert!(repo
+            .voted_options(poll.id, "user:1")
+            .await
+            .unwrap()
+            .is_empty());
+    }
+}
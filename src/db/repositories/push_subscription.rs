@@ -0,0 +1,204 @@
+//! Web Push subscription repository
+
+use anyhow::{Context, Result};
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use sqlx::Row;
+use std::sync::Arc;
+
+use crate::db::DynDatabasePool;
+use crate::models::PushSubscription;
+
+/// Repository trait for push subscriptions and notified articles
+#[async_trait]
+pub trait PushSubscriptionRepository: Send + Sync {
+    /// Store a subscription, replacing the keys of a known endpoint
+    async fn upsert(&self, endpoint: &str, p256dh: &str, auth: &str) -> Result<PushSubscription>;
+
+    /// Remove a subscription. Returns whether it existed.
+    async fn delete_by_endpoint(&self, endpoint: &str) -> Result<bool>;
+
+    /// All subscriptions, oldest first
+    async fn list(&self) -> Result<Vec<PushSubscription>>;
+
+    async fn count(&self) -> Result<i64>;
+
+    /// Record that subscribers were notified of an article. Returns false
+    /// when they already were.
+    async fn mark_notified(&self, article_id: i64, at: DateTime<Utc>) -> Result<bool>;
+}
+
+/// SQLx-based push subscription repository
+pub struct SqlxPushSubscriptionRepository {
+    pool: DynDatabasePool,
+}
+
+impl SqlxPushSubscriptionRepository {
+    pub fn new(pool: DynDatabasePool) -> Self {
+        Self { pool }
+    }
+
+    /// Create a boxed repository for use with dependency injection
+    pub fn boxed(pool: DynDatabasePool) -> Arc<dyn PushSubscriptionRepository> {
+        Arc::new(Self::new(pool))
+    }
+}
+
+#[async_trait]
+impl PushSubscriptionRepository for SqlxPushSubscriptionRepository {
+    async fn upsert(&self, endpoint: &str, p256dh: &str, auth: &str) -> Result<PushSubscription> {
+        dispatch!(self, upsert_push_subscription, endpoint, p256dh, auth)
+    }
+
+    async fn delete_by_endpoint(&self, endpoint: &str) -> Result<bool> {
+        dispatch!(self, delete_push_subscription, endpoint)
+    }
+
+    async fn list(&self) -> Result<Vec<PushSubscription>> {
+        dispatch!(self, list_push_subscriptions)
+    }
+
+    async fn count(&self) -> Result<i64> {
+        dispatch!(self, count_push_subscriptions)
+    }
+
+    async fn mark_notified(&self, article_id: i64, at: DateTime<Utc>) -> Result<bool> {
+        dispatch!(self, mark_push_notified, article_id, at)
+    }
+}
+
+// ============================================================================
+// Shared implementations (identical SQL)
+// ============================================================================
+
+const SELECT_COLUMNS: &str =
+    "SELECT id, endpoint, p256dh, auth, created_at FROM push_subscriptions";
+
+impl_dual_fn! {
+    async fn upsert_push_subscription(pool, endpoint: &str, p256dh: &str, auth: &str) -> Result<PushSubscription> {
+        let updated = sqlx::query("UPDATE push_subscriptions SET p256dh = ?, auth = ? WHERE endpoint = ?")
+            .bind(p256dh)
+            .bind(auth)
+            .bind(endpoint)
+            .execute(pool)
+            .await
+            .context("Failed to update push subscription")?;
+        if updated.rows_affected() == 0 {
+            sqlx::query("INSERT INTO push_subscriptions (endpoint, p256dh, auth, created_at) VALUES (?, ?, ?, ?)")
+                .bind(endpoint)
+                .bind(p256dh)
+                .bind(auth)
+                .bind(Utc::now())
+                .execute(pool)
+                .await
+                .context("Failed to add push subscription")?;
+        }
+        let row = sqlx::query(&format!("{} WHERE endpoint = ?", SELECT_COLUMNS))
+            .bind(endpoint)
+            .fetch_one(pool)
+            .await
+            .context("Failed to load push subscription")?;
+        Ok(row_to_subscription(&row))
+    }
+}
+
+impl_dual_fn! {
+    async fn delete_push_subscription(pool, endpoint: &str) -> Result<bool> {
+        let result = sqlx::query("DELETE FROM push_subscriptions WHERE endpoint = ?")
+            .bind(endpoint)
+            .execute(pool)
+            .await
+            .context("Failed to delete push subscription")?;
+        Ok(result.rows_affected() > 0)
+    }
+}
+
+impl_dual_fn! {
+    async fn list_push_subscriptions(pool) -> Result<Vec<PushSubscription>> {
+        let rows = sqlx::query(&format!("{} ORDER BY id", SELECT_COLUMNS))
+            .fetch_all(pool)
+            .await
+            .context("Failed to list push subscriptions")?;
+        Ok(rows.iter().map(row_to_subscription).collect())
+    }
+}
+
+impl_dual_fn! {
+    async fn count_push_subscriptions(pool) -> Result<i64> {
+        let row = sqlx::query("SELECT COUNT(*) AS count FROM push_subscriptions")
+            .fetch_one(pool)
+            .await
+            .context("Failed to count push subscriptions")?;
+        Ok(row.get("count"))
+    }
+}
+
+impl_dual_fn! {
+    async fn mark_push_notified(pool, article_id: i64, at: DateTime<Utc>) -> Result<bool> {
+        let exists = sqlx::query("SELECT article_id FROM push_notifications WHERE article_id = ?")
+            .bind(article_id)
+            .fetch_optional(pool)
+            .await
+            .context("Failed to check push notification")?;
+        if exists.is_some() {
+            return Ok(false);
+        }
+        // A concurrent insert for the same article fails on the primary key
+        let inserted = sqlx::query("INSERT INTO push_notifications (article_id, sent_at) VALUES (?, ?)")
+            .bind(article_id)
+            .bind(at)
+            .execute(pool)
+            .await;
+        Ok(inserted.is_ok())
+    }
+}
+
+/// Map a row to a subscription (same column types on SQLite and MySQL)
+fn row_to_subscription<'r, R>(row: &'r R) -> PushSubscription
+where
+    R: sqlx::Row,
+    &'r str: sqlx::ColumnIndex<R>,
+    String: sqlx::Decode<'r, R::Database> + sqlx::Type<R::Database>,
+    i64: sqlx::Decode<'r, R::Database> + sqlx::Type<R::Database>,
+    DateTime<Utc>: sqlx::Decode<'r, R::Database> + sqlx::Type<R::Database>,
+{
+    PushSubscription {
+        id: row.get("id"),
+        endpoint: row.get("endpoint"),
+        p256dh: row.get("p256dh"),
+        auth: row.get("auth"),
+        created_at: row.get("created_at"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::{create_test_pool, migrations};
+
+    #[tokio::test]
+    async fn subscriptions_are_keyed_by_endpoint() {
+        let pool = create_test_pool()
+            .await
+            .expect("Failed to create test pool");
+        migrations::run_migrations(&pool)
+            .await
+            .expect("Failed to run migrations");
+        let repo = SqlxPushSubscriptionRepository::new(pool);
+
+        let endpoint = "https://push.example.com/send/abc";
+        let first = repo.upsert(endpoint, "key-1", "auth-1").await.unwrap();
+        let second = repo.upsert(endpoint, "key-2", "auth-2").await.unwrap();
+        assert_eq!(first.id, second.id);
+        assert_eq!(second.p256dh, "key-2");
+        repo.upsert("https://push.example.com/send/def", "key", "auth")
+            .await
+            .unwrap();
+        assert_eq!(repo.count().await.unwrap(), 2);
+        assert_eq!(repo.list().await.unwrap()[0].endpoint, endpoint);
+
+        assert!(repo.delete_by_endpoint(endpoint).await.unwrap());
+        assert!(!repo.delete_by_endpoint(endpoint).await.unwrap());
+        assert_eq!(repo.count().await.unwrap(), 1);
+    }
+}
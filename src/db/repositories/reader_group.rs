@@ -0,0 +1,559 @@
+//! Reader group and article audience repository.
+
+use crate::db::DynDatabasePool;
+use crate::models::{ArticleAudience, ArticleReader, PublicUser, ReaderGroup, UserRole};
+use anyhow::{Context, Result};
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use sqlx::{MySqlPool, Row, SqlitePool};
+use std::sync::Arc;
+
+const GROUP_COLUMNS: &str = "g.id, g.name, g.slug, g.description, g.created_at, \
+     (SELECT COUNT(*) FROM reader_group_members m WHERE m.group_id = g.id) AS member_count";
+
+#[async_trait]
+pub trait ReaderGroupRepository: Send + Sync {
+    /// All groups, by name
+    async fn list_groups(&self) -> Result<Vec<ReaderGroup>>;
+    async fn get_group(&self, id: i64) -> Result<Option<ReaderGroup>>;
+    async fn get_group_by_slug(&self, slug: &str) -> Result<Option<ReaderGroup>>;
+    async fn create_group(&self, group: &ReaderGroup) -> Result<ReaderGroup>;
+    async fn update_group(&self, group: &ReaderGroup) -> Result<()>;
+    /// Delete a group with its memberships; audiences that named it admit
+    /// admins instead
+    async fn delete_group(&self, id: i64) -> Result<bool>;
+
+    /// Members of a group, by username
+    async fn list_members(&self, group_id: i64) -> Result<Vec<PublicUser>>;
+    /// Replace the members of a group
+    async fn set_members(&self, group_id: i64, user_ids: &[i64]) -> Result<()>;
+    /// Groups the user belongs to
+    async fn group_ids_of_user(&self, user_id: i64) -> Result<Vec<i64>>;
+    /// Which of the given user ids exist
+    async fn existing_user_ids(&self, user_ids: &[i64]) -> Result<Vec<i64>>;
+    /// Which of the given group ids exist
+    async fn existing_group_ids(&self, group_ids: &[i64]) -> Result<Vec<i64>>;
+
+    /// Who may read an article; empty when it is open to everyone
+    async fn get_audience(&self, article_id: i64) -> Result<ArticleAudience>;
+    /// Replace the audience of an article
+    async fn set_audience(&self, article_id: i64, audience: &ArticleAudience) -> Result<()>;
+    /// Drop the audience of a deleted article
+    async fn remove_article(&self, article_id: i64) -> Result<()>;
+}
+
+pub struct SqlxReaderGroupRepository {
+    pool: DynDatabasePool,
+}
+
+impl SqlxReaderGroupRepository {
+    pub fn new(pool: DynDatabasePool) -> Self {
+        Self { pool }
+    }
+
+    pub fn boxed(pool: DynDatabasePool) -> Arc<dyn ReaderGroupRepository> {
+        Arc::new(Self::new(pool))
+    }
+}
+
+#[async_trait]
+impl ReaderGroupRepository for SqlxReaderGroupRepository {
+    async fn list_groups(&self) -> Result<Vec<ReaderGroup>> {
+        dispatch!(self, list_groups)
+    }
+
+    async fn get_group(&self, id: i64) -> Result<Option<ReaderGroup>> {
+        dispatch!(self, get_group, id)
+    }
+
+    async fn get_group_by_slug(&self, slug: &str) -> Result<Option<ReaderGroup>> {
+        dispatch!(self, get_group_by_slug, slug)
+    }
+
+    async fn create_group(&self, group: &ReaderGroup) -> Result<ReaderGroup> {
+        dispatch!(self, create_group, group)
+    }
+
+    async fn update_group(&self, group: &ReaderGroup) -> Result<()> {
+        dispatch!(self, update_group, group)
+    }
+
+    async fn delete_group(&self, id: i64) -> Result<bool> {
+        dispatch!(self, delete_group, id)
+    }
+
+    async fn list_members(&self, group_id: i64) -> Result<Vec<PublicUser>> {
+        dispatch!(self, list_members, group_id)
+    }
+
+    async fn set_members(&self, group_id: i64, user_ids: &[i64]) -> Result<()> {
+        dispatch!(self, set_members, group_id, user_ids)
+    }
+
+    async fn group_ids_of_user(&self, user_id: i64) -> Result<Vec<i64>> {
+        dispatch!(self, group_ids_of_user, user_id)
+    }
+
+    async fn existing_user_ids(&self, user_ids: &[i64]) -> Result<Vec<i64>> {
+        if user_ids.is_empty() {
+            return Ok(Vec::new());
+        }
+        dispatch!(self, existing_user_ids, user_ids)
+    }
+
+    async fn existing_group_ids(&self, group_ids: &[i64]) -> Result<Vec<i64>> {
+        if group_ids.is_empty() {
+            return Ok(Vec::new());
+        }
+        dispatch!(self, existing_group_ids, group_ids)
+    }
+
+    async fn get_audience(&self, article_id: i64) -> Result<ArticleAudience> {
+        dispatch!(self, get_audience, article_id)
+    }
+
+    async fn set_audience(&self, article_id: i64, audience: &ArticleAudience) -> Result<()> {
+        dispatch!(self, set_audience, article_id, audience)
+    }
+
+    async fn remove_article(&self, article_id: i64) -> Result<()> {
+        dispatch!(self, remove_article, article_id)
+    }
+}
+
+// ============================================================================
+// Access control
+// ============================================================================
+
+/// SQL condition on an article id column (`column`, e.g. `a.id`) that
+/// leaves out articles whose audience does not admit `reader`; `None` is
+/// an anonymous visitor, who only sees articles without an audience.
+///
+/// Like [`readable_category_sql`](super::category::readable_category_sql)
+/// the reader is written into the SQL, so callers have nothing to bind.
+pub fn readable_audience_sql(column: &str, reader: Option<&ArticleReader>) -> String {
+    let open = format!("{} NOT IN (SELECT article_id FROM article_audiences)", column);
+    let Some(reader) = reader else {
+        return open;
+    };
+    if reader.role == UserRole::Admin {
+        return "1 = 1".to_string();
+    }
+    let roles = reader
+        .admitted_roles()
+        .map(|role| format!("'{}'", role))
+        .collect::<Vec<_>>()
+        .join(", ");
+    format!(
+        "({} OR {} IN (SELECT article_id FROM article_audiences WHERE role IN ({}) \
+         OR group_id IN (SELECT group_id FROM reader_group_members WHERE user_id = {})))",
+        open, column, roles, reader.user_id
+    )
+}
+
+impl_dual_fn! {
+    async fn list_groups(pool) -> Result<Vec<ReaderGroup>> {
+        let rows = sqlx::query(&format!(
+            "SELECT {} FROM reader_groups g ORDER BY g.name, g.id",
+            GROUP_COLUMNS
+        ))
+        .fetch_all(pool)
+        .await
+        .context("Failed to list reader groups")?;
+        Ok(rows.iter().map(row_to_group).collect())
+    }
+}
+
+impl_dual_fn! {
+    async fn get_group(pool, id: i64) -> Result<Option<ReaderGroup>> {
+        let row = sqlx::query(&format!(
+            "SELECT {} FROM reader_groups g WHERE g.id = ?",
+            GROUP_COLUMNS
+        ))
+        .bind(id)
+        .fetch_optional(pool)
+        .await
+        .context("Failed to get reader group")?;
+        Ok(row.map(|r| row_to_group(&r)))
+    }
+}
+
+impl_dual_fn! {
+    async fn get_group_by_slug(pool, slug: &str) -> Result<Option<ReaderGroup>> {
+        let row = sqlx::query(&format!(
+            "SELECT {} FROM reader_groups g WHERE g.slug = ?",
+            GROUP_COLUMNS
+        ))
+        .bind(slug)
+        .fetch_optional(pool)
+        .await
+        .context("Failed to get reader group")?;
+        Ok(row.map(|r| row_to_group(&r)))
+    }
+}
+
+impl_dual_fn! {
+    async fn update_group(pool, group: &ReaderGroup) -> Result<()> {
+        sqlx::query("UPDATE reader_groups SET name = ?, slug = ?, description = ? WHERE id = ?")
+            .bind(&group.name)
+            .bind(&group.slug)
+            .bind(&group.description)
+            .bind(group.id)
+            .execute(pool)
+            .await
+            .context("Failed to update reader group")?;
+        Ok(())
+    }
+}
+
+impl_dual_fn! {
+    async fn delete_group(pool, id: i64) -> Result<bool> {
+        let mut tx = pool.begin().await.context("Failed to start transaction")?;
+        sqlx::query("DELETE FROM reader_group_members WHERE group_id = ?")
+            .bind(id)
+            .execute(&mut *tx)
+            .await
+            .context("Failed to delete reader group members")?;
+        // Articles shown to the group stay restricted, to admins
+        sqlx::query(
+            "UPDATE article_audiences SET group_id = NULL, role = 'admin' WHERE group_id = ?",
+        )
+        .bind(id)
+        .execute(&mut *tx)
+        .await
+        .context("Failed to update reader group audiences")?;
+        let result = sqlx::query("DELETE FROM reader_groups WHERE id = ?")
+            .bind(id)
+            .execute(&mut *tx)
+            .await
+            .context("Failed to delete reader group")?;
+        tx.commit().await.context("Failed to commit transaction")?;
+        Ok(result.rows_affected() > 0)
+    }
+}
+
+impl_dual_fn! {
+    async fn list_members(pool, group_id: i64) -> Result<Vec<PublicUser>> {
+        let rows = sqlx::query(
+            "SELECT u.id, u.username, u.display_name, u.avatar, u.created_at \
+             FROM reader_group_members m JOIN users u ON u.id = m.user_id \
+             WHERE m.group_id = ? ORDER BY u.username",
+        )
+        .bind(group_id)
+        .fetch_all(pool)
+        .await
+        .context("Failed to list reader group members")?;
+        Ok(rows
+            .iter()
+            .map(|row| PublicUser {
+                id: row.get("id"),
+                username: row.get("username"),
+                display_name: row.get("display_name"),
+                avatar: row.get("avatar"),
+                created_at: row.get("created_at"),
+            })
+            .collect())
+    }
+}
+
+impl_dual_fn! {
+    async fn set_members(pool, group_id: i64, user_ids: &[i64]) -> Result<()> {
+        let mut tx = pool.begin().await.context("Failed to start transaction")?;
+        sqlx::query("DELETE FROM reader_group_members WHERE group_id = ?")
+            .bind(group_id)
+            .execute(&mut *tx)
+            .await
+            .context("Failed to clear reader group members")?;
+        for user_id in user_ids {
+            sqlx::query("INSERT INTO reader_group_members (group_id, user_id) VALUES (?, ?)")
+                .bind(group_id)
+                .bind(user_id)
+                .execute(&mut *tx)
+                .await
+                .context("Failed to add reader group member")?;
+        }
+        tx.commit().await.context("Failed to commit transaction")?;
+        Ok(())
+    }
+}
+
+impl_dual_fn! {
+    async fn group_ids_of_user(pool, user_id: i64) -> Result<Vec<i64>> {
+        let ids: Vec<i64> =
+            sqlx::query_scalar("SELECT group_id FROM reader_group_members WHERE user_id = ?")
+                .bind(user_id)
+                .fetch_all(pool)
+                .await
+                .context("Failed to list reader groups of user")?;
+        Ok(ids)
+    }
+}
+
+impl_dual_fn! {
+    async fn existing_user_ids(pool, user_ids: &[i64]) -> Result<Vec<i64>> {
+        let placeholders = vec!["?"; user_ids.len()].join(", ");
+        let sql = format!("SELECT id FROM users WHERE id IN ({})", placeholders);
+        let mut query = sqlx::query_scalar(&sql);
+        for id in user_ids {
+            query = query.bind(id);
+        }
+        let ids: Vec<i64> = query
+            .fetch_all(pool)
+            .await
+            .context("Failed to look up users")?;
+        Ok(ids)
+    }
+}
+
+impl_dual_fn! {
+    async fn existing_group_ids(pool, group_ids: &[i64]) -> Result<Vec<i64>> {
+        let placeholders = vec!["?"; group_ids.len()].join(", ");
+        let sql = format!("SELECT id FROM reader_groups WHERE id IN ({})", placeholders);
+        let mut query = sqlx::query_scalar(&sql);
+        for id in group_ids {
+            query = query.bind(id);
+        }
+        let ids: Vec<i64> = query
+            .fetch_all(pool)
+            .await
+            .context("Failed to look up reader groups")?;
+        Ok(ids)
+    }
+}
+
+impl_dual_fn! {
+    async fn get_audience(pool, article_id: i64) -> Result<ArticleAudience> {
+        let rows = sqlx::query(
+            "SELECT role, group_id FROM article_audiences WHERE article_id = ? ORDER BY id",
+        )
+        .bind(article_id)
+        .fetch_all(pool)
+        .await
+        .context("Failed to get article audience")?;
+        let mut audience = ArticleAudience::default();
+        for row in &rows {
+            let role: Option<String> = row.get("role");
+            // Unknown roles only admit admins, who are always admitted
+            if let Some(role) = role {
+                let role = role.parse().unwrap_or(UserRole::Admin);
+                if !audience.roles.contains(&role) {
+                    audience.roles.push(role);
+                }
+            }
+            if let Some(group_id) = row.get::<Option<i64>, _>("group_id") {
+                audience.group_ids.push(group_id);
+            }
+        }
+        Ok(audience)
+    }
+}
+
+impl_dual_fn! {
+    async fn set_audience(pool, article_id: i64, audience: &ArticleAudience) -> Result<()> {
+        let mut tx = pool.begin().await.context("Failed to start transaction")?;
+        sqlx::query("DELETE FROM article_audiences WHERE article_id = ?")
+            .bind(article_id)
+            .execute(&mut *tx)
+            .await
+            .context("Failed to clear article audience")?;
+        for role in &audience.roles {
+            sqlx::query("INSERT INTO article_audiences (article_id, role) VALUES (?, ?)")
+                .bind(article_id)
+                .bind(role.to_string())
+                .execute(&mut *tx)
+                .await
+                .context("Failed to add article audience role")?;
+        }
+        for group_id in &audience.group_ids {
+            sqlx::query("INSERT INTO article_audiences (article_id, group_id) VALUES (?, ?)")
+                .bind(article_id)
+                .bind(group_id)
+                .execute(&mut *tx)
+                .await
+                .context("Failed to add article audience group")?;
+        }
+        tx.commit().await.context("Failed to commit transaction")?;
+        Ok(())
+    }
+}
+
+impl_dual_fn! {
+    async fn remove_article(pool, article_id: i64) -> Result<()> {
+        sqlx::query("DELETE FROM article_audiences WHERE article_id = ?")
+            .bind(article_id)
+            .execute(pool)
+            .await
+            .context("Failed to remove article audience")?;
+        Ok(())
+    }
+}
+
+fn row_to_group<'r, R>(row: &'r R) -> ReaderGroup
+where
+    R: sqlx::Row,
+    &'r str: sqlx::ColumnIndex<R>,
+    String: sqlx::Decode<'r, R::Database> + sqlx::Type<R::Database>,
+    Option<String>: sqlx::Decode<'r, R::Database> + sqlx::Type<R::Database>,
+    i64: sqlx::Decode<'r, R::Database> + sqlx::Type<R::Database>,
+    DateTime<Utc>: sqlx::Decode<'r, R::Database> + sqlx::Type<R::Database>,
+{
+    ReaderGroup {
+        id: row.get("id"),
+        name: row.get("name"),
+        slug: row.get("slug"),
+        description: row.get("description"),
+        member_count: row.get("member_count"),
+        created_at: row.get("created_at"),
+    }
+}
+
+async fn create_group_sqlite(pool: &SqlitePool, group: &ReaderGroup) -> Result<ReaderGroup> {
+    let now = Utc::now();
+    let result = sqlx::query(
+        "INSERT INTO reader_groups (name, slug, description, created_at) VALUES (?, ?, ?, ?)",
+    )
+    .bind(&group.name)
+    .bind(&group.slug)
+    .bind(&group.description)
+    .bind(now)
+    .execute(pool)
+    .await
+    .context("Failed to create reader group")?;
+
+    Ok(ReaderGroup {
+        id: result.last_insert_rowid(),
+        member_count: 0,
+        created_at: now,
+        ..group.clone()
+    })
+}
+
+async fn create_group_mysql(pool: &MySqlPool, group: &ReaderGroup) -> Result<ReaderGroup> {
+    let now = Utc::now();
+    let result = sqlx::query(
+        "INSERT INTO reader_groups (name, slug, description, created_at) VALUES (?, ?, ?, ?)",
+    )
+    .bind(&group.name)
+    .bind(&group.slug)
+    .bind(&group.description)
+    .bind(now)
+    .execute(pool)
+    .await
+    .context("Failed to create reader group")?;
+
+    Ok(ReaderGroup {
+        id: result.last_insert_id() as i64,
+        member_count: 0,
+        created_at: now,
+        ..group.clone()
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::{create_test_pool, migrations};
+
+    fn group(name: &str) -> ReaderGroup {
+        ReaderGroup {
+            id: 0,
+            name: name.to_string(),
+            slug: name.to_lowercase(),
+            description: None,
+            member_count: 0,
+            created_at: Utc::now(),
+        }
+    }
+
+    #[tokio::test]
+    async fn audiences_admit_roles_and_group_members() {
+        let pool = create_test_pool()
+            .await
+            .expect("Failed to create test pool");
+        migrations::run_migrations(&pool)
+            .await
+            .expect("Failed to run migrations");
+        let sqlite = pool.as_sqlite().unwrap();
+        let mut users = Vec::new();
+        for (name, role) in [("ada", "author"), ("bob", "author"), ("cy", "editor")] {
+            let id = sqlx::query(
+                "INSERT INTO users (username, email, password_hash, role) VALUES (?, ?, 'x', ?)",
+            )
+            .bind(name)
+            .bind(format!("{}@example.com", name))
+            .bind(role)
+            .execute(sqlite)
+            .await
+            .unwrap()
+            .last_insert_rowid();
+            users.push(id);
+        }
+        let mut articles = Vec::new();
+        for slug in ["open", "staff", "beta"] {
+            let id = sqlx::query(
+                "INSERT INTO articles (slug, title, content, content_html, author_id, category_id) VALUES (?, ?, '', '', ?, 1)",
+            )
+            .bind(slug)
+            .bind(slug)
+            .bind(users[0])
+            .execute(sqlite)
+            .await
+            .unwrap()
+            .last_insert_rowid();
+            articles.push(id);
+        }
+        let repo = SqlxReaderGroupRepository::new(pool.clone());
+
+        let beta = repo.create_group(&group("Beta")).await.unwrap();
+        repo.set_members(beta.id, &[users[0]]).await.unwrap();
+        assert_eq!(repo.get_group(beta.id).await.unwrap().unwrap().member_count, 1);
+        assert_eq!(repo.group_ids_of_user(users[0]).await.unwrap(), [beta.id]);
+
+        let staff = ArticleAudience {
+            roles: vec![UserRole::Editor],
+            group_ids: Vec::new(),
+        };
+        let beta_only = ArticleAudience {
+            roles: Vec::new(),
+            group_ids: vec![beta.id],
+        };
+        repo.set_audience(articles[1], &staff).await.unwrap();
+        repo.set_audience(articles[2], &beta_only).await.unwrap();
+        assert_eq!(repo.get_audience(articles[1]).await.unwrap(), staff);
+        assert!(!repo.get_audience(articles[0]).await.unwrap().is_restricted());
+
+        let visible = |reader: Option<ArticleReader>| {
+            let sql = format!(
+                "SELECT slug FROM articles a WHERE {} ORDER BY a.id",
+                readable_audience_sql("a.id", reader.as_ref())
+            );
+            async move {
+                sqlx::query_scalar::<_, String>(&sql)
+                    .fetch_all(sqlite)
+                    .await
+                    .unwrap()
+            }
+        };
+        let reader = |user_id: i64, role: UserRole| Some(ArticleReader { user_id, role });
+        assert_eq!(visible(None).await, ["open"]);
+        assert_eq!(
+            visible(reader(users[0], UserRole::Author)).await,
+            ["open", "beta"]
+        );
+        assert_eq!(visible(reader(users[1], UserRole::Author)).await, ["open"]);
+        assert_eq!(
+            visible(reader(users[2], UserRole::Editor)).await,
+            ["open", "staff"]
+        );
+        assert_eq!(visible(reader(99, UserRole::Admin)).await.len(), 3);
+
+        // Articles shown to a deleted group are left to admins
+        assert!(repo.delete_group(beta.id).await.unwrap());
+        assert_eq!(
+            repo.get_audience(articles[2]).await.unwrap().roles,
+            [UserRole::Admin]
+        );
+        assert_eq!(visible(reader(users[0], UserRole::Author)).await, ["open"]);
+        assert!(repo.group_ids_of_user(users[0]).await.unwrap().is_empty());
+    }
+}
@@ -0,0 +1,435 @@
+//! Reading progress repository
+//!
+//! One row per user and article; saving again moves the position and bumps
+//! `updated_at`. Progress is only saved in, and only listed for, published
+//! articles the reader may read: not in categories above their clearance,
+//! nor in articles whose audience leaves them out.
+
+use anyhow::{Context, Result};
+use async_trait::async_trait;
+use chrono::Utc;
+use sqlx::{MySqlPool, Row, SqlitePool};
+use std::sync::Arc;
+
+use crate::db::repositories::category::readable_category_sql;
+use crate::db::repositories::reader_group::readable_audience_sql;
+use crate::db::DynDatabasePool;
+use crate::models::{ArticleReader, CategoryAccess, ReadingProgress};
+
+/// Repository trait for reading progress
+#[async_trait]
+pub trait ReadingProgressRepository: Send + Sync {
+    /// Progress of a user in published articles they may read, most
+    /// recently read first
+    async fn list(
+        &self,
+        reader: &ArticleReader,
+        clearance: CategoryAccess,
+        limit: i64,
+    ) -> Result<Vec<ReadingProgress>>;
+
+    /// Progress of a user in one published article they may read
+    async fn get(
+        &self,
+        reader: &ArticleReader,
+        clearance: CategoryAccess,
+        article_id: i64,
+    ) -> Result<Option<ReadingProgress>>;
+
+    /// Store the position of a user in a published article they may read;
+    /// false when the article is not one
+    async fn save(
+        &self,
+        reader: &ArticleReader,
+        clearance: CategoryAccess,
+        article_id: i64,
+        position: f64,
+    ) -> Result<bool>;
+
+    /// Forget the progress in an article; false when none was stored
+    async fn delete(&self, user_id: i64, article_id: i64) -> Result<bool>;
+}
+
+/// SQLx-based reading progress repository
+pub struct SqlxReadingProgressRepository {
+    pool: DynDatabasePool,
+}
+
+impl SqlxReadingProgressRepository {
+    pub fn new(pool: DynDatabasePool) -> Self {
+        Self { pool }
+    }
+
+    /// Create a boxed repository for use with dependency injection
+    pub fn boxed(pool: DynDatabasePool) -> Arc<dyn ReadingProgressRepository> {
+        Arc::new(Self::new(pool))
+    }
+}
+
+#[async_trait]
+impl ReadingProgressRepository for SqlxReadingProgressRepository {
+    async fn list(
+        &self,
+        reader: &ArticleReader,
+        clearance: CategoryAccess,
+        limit: i64,
+    ) -> Result<Vec<ReadingProgress>> {
+        dispatch!(self, list, reader, clearance, limit)
+    }
+
+    async fn get(
+        &self,
+        reader: &ArticleReader,
+        clearance: CategoryAccess,
+        article_id: i64,
+    ) -> Result<Option<ReadingProgress>> {
+        dispatch!(self, get, reader, clearance, article_id)
+    }
+
+    async fn save(
+        &self,
+        reader: &ArticleReader,
+        clearance: CategoryAccess,
+        article_id: i64,
+        position: f64,
+    ) -> Result<bool> {
+        dispatch!(self, save, reader, clearance, article_id, position)
+    }
+
+    async fn delete(&self, user_id: i64, article_id: i64) -> Result<bool> {
+        dispatch!(self, delete, user_id, article_id)
+    }
+}
+
+/// Condition on articles `a` that `reader` may keep progress in
+fn readable_sql(reader: &ArticleReader, clearance: CategoryAccess) -> String {
+    format!(
+        "a.status = 'published' AND {} AND {}",
+        readable_category_sql("a.category_id", clearance),
+        readable_audience_sql("a.id", Some(reader))
+    )
+}
+
+/// Progress rows of one user (bound first) in articles they may read
+fn select_progress(reader: &ArticleReader, clearance: CategoryAccess) -> String {
+    format!(
+        "SELECT p.article_id, a.slug, a.title, p.position, p.updated_at \
+         FROM reading_progress p JOIN articles a ON a.id = p.article_id \
+         WHERE p.user_id = ? AND {}",
+        readable_sql(reader, clearance)
+    )
+}
+
+impl_row_mapper! {
+    fn row_to_progress(row) -> Result<ReadingProgress> {
+        Ok(ReadingProgress {
+            article_id: row.get("article_id"),
+            slug: row.get("slug"),
+            title: row.get("title"),
+            position: row.get("position"),
+            updated_at: row.get("updated_at"),
+        })
+    }
+}
+
+// ============================================================================
+// Driver-specific implementations (row types and upsert syntax differ)
+// ============================================================================
+
+async fn list_sqlite(
+    pool: &SqlitePool,
+    reader: &ArticleReader,
+    clearance: CategoryAccess,
+    limit: i64,
+) -> Result<Vec<ReadingProgress>> {
+    let rows = sqlx::query(&format!(
+        "{} ORDER BY p.updated_at DESC LIMIT ?",
+        select_progress(reader, clearance)
+    ))
+    .bind(reader.user_id)
+    .bind(limit)
+    .fetch_all(pool)
+    .await
+    .context("Failed to list reading progress")?;
+    rows.iter().map(row_to_progress_sqlite).collect()
+}
+
+async fn list_mysql(
+    pool: &MySqlPool,
+    reader: &ArticleReader,
+    clearance: CategoryAccess,
+    limit: i64,
+) -> Result<Vec<ReadingProgress>> {
+    let rows = sqlx::query(&format!(
+        "{} ORDER BY p.updated_at DESC LIMIT ?",
+        select_progress(reader, clearance)
+    ))
+    .bind(reader.user_id)
+    .bind(limit)
+    .fetch_all(pool)
+    .await
+    .context("Failed to list reading progress")?;
+    rows.iter().map(row_to_progress_mysql).collect()
+}
+
+async fn get_sqlite(
+    pool: &SqlitePool,
+    reader: &ArticleReader,
+    clearance: CategoryAccess,
+    article_id: i64,
+) -> Result<Option<ReadingProgress>> {
+    let row = sqlx::query(&format!(
+        "{} AND p.article_id = ?",
+        select_progress(reader, clearance)
+    ))
+    .bind(reader.user_id)
+    .bind(article_id)
+    .fetch_optional(pool)
+    .await
+    .context("Failed to get reading progress")?;
+    row.as_ref().map(row_to_progress_sqlite).transpose()
+}
+
+async fn get_mysql(
+    pool: &MySqlPool,
+    reader: &ArticleReader,
+    clearance: CategoryAccess,
+    article_id: i64,
+) -> Result<Option<ReadingProgress>> {
+    let row = sqlx::query(&format!(
+        "{} AND p.article_id = ?",
+        select_progress(reader, clearance)
+    ))
+    .bind(reader.user_id)
+    .bind(article_id)
+    .fetch_optional(pool)
+    .await
+    .context("Failed to get reading progress")?;
+    row.as_ref().map(row_to_progress_mysql).transpose()
+}
+
+async fn save_sqlite(
+    pool: &SqlitePool,
+    reader: &ArticleReader,
+    clearance: CategoryAccess,
+    article_id: i64,
+    position: f64,
+) -> Result<bool> {
+    let result = sqlx::query(&format!(
+        r#"INSERT INTO reading_progress (user_id, article_id, position, updated_at)
+           SELECT ?, a.id, ?, ? FROM articles a WHERE a.id = ? AND {}
+           ON CONFLICT(user_id, article_id) DO UPDATE SET
+               position = excluded.position,
+               updated_at = excluded.updated_at"#,
+        readable_sql(reader, clearance)
+    ))
+    .bind(reader.user_id)
+    .bind(position)
+    .bind(Utc::now())
+    .bind(article_id)
+    .execute(pool)
+    .await
+    .context("Failed to save reading progress")?;
+    Ok(result.rows_affected() > 0)
+}
+
+async fn save_mysql(
+    pool: &MySqlPool,
+    reader: &ArticleReader,
+    clearance: CategoryAccess,
+    article_id: i64,
+    position: f64,
+) -> Result<bool> {
+    let result = sqlx::query(&format!(
+        r#"INSERT INTO reading_progress (user_id, article_id, position, updated_at)
+           SELECT ?, a.id, ?, ? FROM articles a WHERE a.id = ? AND {}
+           ON DUPLICATE KEY UPDATE
+               position = VALUES(position),
+               updated_at = VALUES(updated_at)"#,
+        readable_sql(reader, clearance)
+    ))
+    .bind(reader.user_id)
+    .bind(position)
+    .bind(Utc::now())
+    .bind(article_id)
+    .execute(pool)
+    .await
+    .context("Failed to save reading progress")?;
+    Ok(result.rows_affected() > 0)
+}
+
+// ============================================================================
+// Shared implementations (identical SQL)
+// ============================================================================
+
+impl_dual_fn! {
+    async fn delete(pool, user_id: i64, article_id: i64) -> Result<bool> {
+        let result = sqlx::query("DELETE FROM reading_progress WHERE user_id = ? AND article_id = ?")
+            .bind(user_id)
+            .bind(article_id)
+            .execute(pool)
+            .await
+            .context("Failed to delete reading progress")?;
+        Ok(result.rows_affected() > 0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::{create_test_pool, migrations};
+    use crate::models::UserRole;
+
+    /// Reader 1, an author, and a repository over a fresh database
+    async fn setup() -> (SqlitePool, SqlxReadingProgressRepository) {
+        let pool = create_test_pool()
+            .await
+            .expect("Failed to create test pool");
+        migrations::run_migrations(&pool)
+            .await
+            .expect("Failed to run migrations");
+        let sqlite = pool.as_sqlite().unwrap().clone();
+        sqlx::query("INSERT INTO users (id, username, email, password_hash, role) VALUES (1, 'reader', 'r@example.com', 'x', 'author')")
+            .execute(&sqlite)
+            .await
+            .unwrap();
+        (sqlite, SqlxReadingProgressRepository::new(pool))
+    }
+
+    const READER: ArticleReader = ArticleReader {
+        user_id: 1,
+        role: UserRole::Author,
+    };
+
+    async fn insert_article(pool: &SqlitePool, slug: &str, status: &str) -> i64 {
+        sqlx::query(
+            "INSERT INTO articles (slug, title, content, content_html, author_id, category_id, status) \
+             VALUES (?, ?, '', '', 1, 1, ?)",
+        )
+        .bind(slug)
+        .bind(slug.to_uppercase())
+        .bind(status)
+        .execute(pool)
+        .await
+        .unwrap()
+        .last_insert_rowid()
+    }
+
+    #[tokio::test]
+    async fn progress_is_upserted_and_listed_newest_first() {
+        let (sqlite, repo) = setup().await;
+        // Category 1 is the default category created by the migrations
+        let first = insert_article(&sqlite, "first", "published").await;
+        let second = insert_article(&sqlite, "second", "published").await;
+        let draft = insert_article(&sqlite, "draft", "draft").await;
+        let clearance = CategoryAccess::Members;
+
+        assert!(repo.save(&READER, clearance, first, 0.2).await.unwrap());
+        assert!(repo.save(&READER, clearance, second, 0.5).await.unwrap());
+        assert!(!repo.save(&READER, clearance, draft, 0.9).await.unwrap());
+        assert!(repo.save(&READER, clearance, first, 0.4).await.unwrap());
+
+        let listed = repo.list(&READER, clearance, 10).await.unwrap();
+        let positions: Vec<(&str, f64)> = listed
+            .iter()
+            .map(|p| (p.slug.as_str(), p.position))
+            .collect();
+        assert_eq!(positions, vec![("first", 0.4), ("second", 0.5)]);
+        let saved = repo.get(&READER, clearance, second).await.unwrap();
+        assert_eq!(saved.unwrap().title, "SECOND");
+        assert!(repo.get(&READER, clearance, draft).await.unwrap().is_none());
+
+        assert!(repo.delete(1, first).await.unwrap());
+        assert!(!repo.delete(1, first).await.unwrap());
+        assert_eq!(repo.list(&READER, clearance, 10).await.unwrap().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn progress_in_private_categories_needs_clearance() {
+        let (sqlite, repo) = setup().await;
+        let private = sqlx::query(
+            "INSERT INTO categories (slug, name, access) VALUES ('staff', 'Staff', 'editors')",
+        )
+        .execute(&sqlite)
+        .await
+        .unwrap()
+        .last_insert_rowid();
+        let article = insert_article(&sqlite, "internal", "published").await;
+        sqlx::query("UPDATE articles SET category_id = ? WHERE id = ?")
+            .bind(private)
+            .bind(article)
+            .execute(&sqlite)
+            .await
+            .unwrap();
+
+        assert!(!repo
+            .save(&READER, CategoryAccess::Members, article, 0.3)
+            .await
+            .unwrap());
+        assert!(repo
+            .save(&READER, CategoryAccess::Editors, article, 0.3)
+            .await
+            .unwrap());
+
+        // Stored rows stay hidden from readers without clearance
+        assert!(repo
+            .list(&READER, CategoryAccess::Members, 10)
+            .await
+            .unwrap()
+            .is_empty());
+        assert!(repo
+            .get(&READER, CategoryAccess::Members, article)
+            .await
+            .unwrap()
+            .is_none());
+        assert_eq!(
+            repo.list(&READER, CategoryAccess::Editors, 10)
+                .await
+                .unwrap()
+                .len(),
+            1
+        );
+    }
+
+    #[tokio::test]
+    async fn non_members_cannot_keep_progress_in_restricted_articles() {
+        let (sqlite, repo) = setup().await;
+        let article = insert_article(&sqlite, "insiders", "published").await;
+        let group =
+            sqlx::query("INSERT INTO reader_groups (name, slug) VALUES ('Insiders', 'insiders')")
+                .execute(&sqlite)
+                .await
+                .unwrap()
+                .last_insert_rowid();
+        sqlx::query("INSERT INTO article_audiences (article_id, group_id) VALUES (?, ?)")
+            .bind(article)
+            .bind(group)
+            .execute(&sqlite)
+            .await
+            .unwrap();
+        let clearance = CategoryAccess::Members;
+
+        assert!(!repo.save(&READER, clearance, article, 0.3).await.unwrap());
+        assert!(repo.list(&READER, clearance, 10).await.unwrap().is_empty());
+
+        sqlx::query("INSERT INTO reader_group_members (group_id, user_id) VALUES (?, 1)")
+            .bind(group)
+            .execute(&sqlite)
+            .await
+            .unwrap();
+        assert!(repo.save(&READER, clearance, article, 0.3).await.unwrap());
+        assert_eq!(repo.list(&READER, clearance, 10).await.unwrap().len(), 1);
+
+        // Leaving the group hides the bookmark again
+        sqlx::query("DELETE FROM reader_group_members WHERE user_id = 1")
+            .execute(&sqlite)
+            .await
+            .unwrap();
+        assert!(repo.list(&READER, clearance, 10).await.unwrap().is_empty());
+        assert!(repo
+            .get(&READER, clearance, article)
+            .await
+            .unwrap()
+            .is_none());
+    }
+}
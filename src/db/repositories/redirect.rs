@@ -0,0 +1,228 @@
+//! Redirect repository.
+
+use crate::db::DynDatabasePool;
+use crate::models::Redirect;
+use anyhow::{Context, Result};
+use async_trait::async_trait;
+use chrono::Utc;
+use sqlx::{MySqlPool, SqlitePool};
+use std::sync::Arc;
+
+#[async_trait]
+pub trait RedirectRepository: Send + Sync {
+    async fn create(&self, redirect: &Redirect) -> Result<Redirect>;
+    async fn get_by_id(&self, id: i64) -> Result<Option<Redirect>>;
+    async fn list(&self) -> Result<Vec<Redirect>>;
+    async fn update(&self, redirect: &Redirect) -> Result<Redirect>;
+    async fn delete(&self, id: i64) -> Result<bool>;
+}
+
+pub struct SqlxRedirectRepository {
+    pool: DynDatabasePool,
+}
+
+impl SqlxRedirectRepository {
+    pub fn new(pool: DynDatabasePool) -> Self {
+        Self { pool }
+    }
+
+    pub fn boxed(pool: DynDatabasePool) -> Arc<dyn RedirectRepository> {
+        Arc::new(Self::new(pool))
+    }
+}
+
+#[async_trait]
+impl RedirectRepository for SqlxRedirectRepository {
+    async fn create(&self, redirect: &Redirect) -> Result<Redirect> {
+        dispatch!(self, create, redirect)
+    }
+
+    async fn get_by_id(&self, id: i64) -> Result<Option<Redirect>> {
+        dispatch!(self, get_by_id, id)
+    }
+
+    async fn list(&self) -> Result<Vec<Redirect>> {
+        dispatch!(self, list)
+    }
+
+    async fn update(&self, redirect: &Redirect) -> Result<Redirect> {
+        dispatch!(self, update, redirect)
+    }
+
+    async fn delete(&self, id: i64) -> Result<bool> {
+        dispatch!(self, delete, id)
+    }
+}
+
+impl_dual_fn! {
+    async fn get_by_id(pool, id: i64) -> Result<Option<Redirect>> {
+        let row = sqlx::query(
+            "SELECT id, source, target, status_code, created_at, updated_at FROM redirects WHERE id = ?"
+        )
+        .bind(id)
+        .fetch_optional(pool)
+        .await
+        .context("Failed to get redirect")?;
+        row.map(|r| row_to_redirect(&r)).transpose()
+    }
+}
+
+impl_dual_fn! {
+    async fn list(pool) -> Result<Vec<Redirect>> {
+        let rows = sqlx::query(
+            "SELECT id, source, target, status_code, created_at, updated_at FROM redirects ORDER BY source"
+        )
+        .fetch_all(pool)
+        .await
+        .context("Failed to list redirects")?;
+        rows.iter().map(row_to_redirect).collect()
+    }
+}
+
+impl_dual_fn! {
+    async fn delete(pool, id: i64) -> Result<bool> {
+        let result = sqlx::query("DELETE FROM redirects WHERE id = ?")
+            .bind(id)
+            .execute(pool)
+            .await
+            .context("Failed to delete redirect")?;
+        Ok(result.rows_affected() > 0)
+    }
+}
+
+fn row_to_redirect<'r, R>(row: &'r R) -> Result<Redirect>
+where
+    R: sqlx::Row,
+    &'r str: sqlx::ColumnIndex<R>,
+    String: sqlx::Decode<'r, R::Database> + sqlx::Type<R::Database>,
+    i64: sqlx::Decode<'r, R::Database> + sqlx::Type<R::Database>,
+    i32: sqlx::Decode<'r, R::Database> + sqlx::Type<R::Database>,
+    chrono::DateTime<Utc>: sqlx::Decode<'r, R::Database> + sqlx::Type<R::Database>,
+{
+    let status_code: i32 = row.get("status_code");
+    Ok(Redirect {
+        id: row.get("id"),
+        source: row.get("source"),
+        target: row.get("target"),
+        status_code: u16::try_from(status_code).context("Invalid redirect status code")?,
+        created_at: row.get("created_at"),
+        updated_at: row.get("updated_at"),
+    })
+}
+
+async fn create_sqlite(pool: &SqlitePool, redirect: &Redirect) -> Result<Redirect> {
+    let now = Utc::now();
+    let result = sqlx::query(
+        "INSERT INTO redirects (source, target, status_code, created_at, updated_at) VALUES (?, ?, ?, ?, ?)"
+    )
+    .bind(&redirect.source)
+    .bind(&redirect.target)
+    .bind(redirect.status_code as i32)
+    .bind(now)
+    .bind(now)
+    .execute(pool)
+    .await
+    .context("Failed to create redirect")?;
+
+    Ok(Redirect {
+        id: result.last_insert_rowid(),
+        created_at: now,
+        updated_at: now,
+        ..redirect.clone()
+    })
+}
+
+async fn update_sqlite(pool: &SqlitePool, redirect: &Redirect) -> Result<Redirect> {
+    sqlx::query(
+        "UPDATE redirects SET source = ?, target = ?, status_code = ?, updated_at = ? WHERE id = ?",
+    )
+    .bind(&redirect.source)
+    .bind(&redirect.target)
+    .bind(redirect.status_code as i32)
+    .bind(Utc::now())
+    .bind(redirect.id)
+    .execute(pool)
+    .await
+    .context("Failed to update redirect")?;
+    get_by_id_sqlite(pool, redirect.id)
+        .await?
+        .ok_or_else(|| anyhow::anyhow!("Redirect not found after update"))
+}
+
+async fn create_mysql(pool: &MySqlPool, redirect: &Redirect) -> Result<Redirect> {
+    let now = Utc::now();
+    let result = sqlx::query(
+        "INSERT INTO redirects (source, target, status_code, created_at, updated_at) VALUES (?, ?, ?, ?, ?)"
+    )
+    .bind(&redirect.source)
+    .bind(&redirect.target)
+    .bind(redirect.status_code as i32)
+    .bind(now)
+    .bind(now)
+    .execute(pool)
+    .await
+    .context("Failed to create redirect")?;
+
+    Ok(Redirect {
+        id: result.last_insert_id() as i64,
+        created_at: now,
+        updated_at: now,
+        ..redirect.clone()
+    })
+}
+
+async fn update_mysql(pool: &MySqlPool, redirect: &Redirect) -> Result<Redirect> {
+    sqlx::query(
+        "UPDATE redirects SET source = ?, target = ?, status_code = ?, updated_at = ? WHERE id = ?",
+    )
+    .bind(&redirect.source)
+    .bind(&redirect.target)
+    .bind(redirect.status_code as i32)
+    .bind(Utc::now())
+    .bind(redirect.id)
+    .execute(pool)
+    .await
+    .context("Failed to update redirect")?;
+    get_by_id_mysql(pool, redirect.id)
+        .await?
+        .ok_or_else(|| anyhow::anyhow!("Redirect not found after update"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::{create_test_pool, migrations};
+
+    #[tokio::test]
+    async fn redirects_round_trip_and_sources_are_unique() {
+        let pool = create_test_pool()
+            .await
+            .expect("Failed to create test pool");
+        migrations::run_migrations(&pool)
+            .await
+            .expect("Failed to run migrations");
+        let repo = SqlxRedirectRepository::new(pool);
+
+        let mut redirect = Redirect::new("/old-feed".to_string(), "/rss.xml".to_string());
+        redirect.status_code = 308;
+        let created = repo.create(&redirect).await.unwrap();
+        assert!(repo
+            .create(&Redirect::new(
+                "/old-feed".to_string(),
+                "https://example.com/".to_string()
+            ))
+            .await
+            .is_err());
+
+        let mut changed = created.clone();
+        changed.target = "/feed.xml".to_string();
+        let updated = repo.update(&changed).await.unwrap();
+        assert_eq!(updated.target, "/feed.xml");
+        assert_eq!(updated.status_code, 308);
+        assert_eq!(repo.list().await.unwrap(), vec![updated]);
+
+        assert!(repo.delete(created.id).await.unwrap());
+        assert!(!repo.delete(created.id).await.unwrap());
+        assert!(repo.get_by_id(created.id).await.unwrap().is_none());
+    }
+}
@@ -0,0 +1,439 @@
+//! Article series repository.
+
+use crate::db::repositories::category::public_article_sql;
+use crate::db::DynDatabasePool;
+use crate::models::{Series, SeriesArticle};
+use anyhow::{Context, Result};
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use sqlx::{MySqlPool, SqlitePool};
+use std::sync::Arc;
+
+const SERIES_COLUMNS: &str = "id, slug, title, description, created_at, updated_at";
+const ARTICLE_COLUMNS: &str =
+    "a.id, a.slug, a.title, a.thumbnail, a.status, a.published_at, sa.position";
+
+#[async_trait]
+pub trait SeriesRepository: Send + Sync {
+    /// All series, newest first
+    async fn list(&self) -> Result<Vec<Series>>;
+    async fn get(&self, id: i64) -> Result<Option<Series>>;
+    async fn get_by_slug(&self, slug: &str) -> Result<Option<Series>>;
+    async fn create(&self, series: &Series) -> Result<Series>;
+    async fn update(&self, series: &Series) -> Result<Series>;
+    /// Delete a series; its articles are kept
+    async fn delete(&self, id: i64) -> Result<bool>;
+
+    /// Articles of a series by position, optionally only published ones
+    async fn list_articles(
+        &self,
+        series_id: i64,
+        published_only: bool,
+    ) -> Result<Vec<SeriesArticle>>;
+    /// Replace the articles of a series, in order; articles listed here
+    /// leave any other series
+    async fn set_articles(&self, series_id: i64, article_ids: &[i64]) -> Result<()>;
+    /// The series an article belongs to
+    async fn series_of_article(&self, article_id: i64) -> Result<Option<i64>>;
+    /// Take an article out of its series
+    async fn remove_article(&self, article_id: i64) -> Result<()>;
+    /// Which of the given article ids exist
+    async fn existing_article_ids(&self, article_ids: &[i64]) -> Result<Vec<i64>>;
+}
+
+pub struct SqlxSeriesRepository {
+    pool: DynDatabasePool,
+}
+
+impl SqlxSeriesRepository {
+    pub fn new(pool: DynDatabasePool) -> Self {
+        Self { pool }
+    }
+
+    pub fn boxed(pool: DynDatabasePool) -> Arc<dyn SeriesRepository> {
+        Arc::new(Self::new(pool))
+    }
+}
+
+#[async_trait]
+impl SeriesRepository for SqlxSeriesRepository {
+    async fn list(&self) -> Result<Vec<Series>> {
+        dispatch!(self, list)
+    }
+
+    async fn get(&self, id: i64) -> Result<Option<Series>> {
+        dispatch!(self, get, id)
+    }
+
+    async fn get_by_slug(&self, slug: &str) -> Result<Option<Series>> {
+        dispatch!(self, get_by_slug, slug)
+    }
+
+    async fn create(&self, series: &Series) -> Result<Series> {
+        dispatch!(self, create, series)
+    }
+
+    async fn update(&self, series: &Series) -> Result<Series> {
+        dispatch!(self, update, series)
+    }
+
+    async fn delete(&self, id: i64) -> Result<bool> {
+        dispatch!(self, delete, id)
+    }
+
+    async fn list_articles(
+        &self,
+        series_id: i64,
+        published_only: bool,
+    ) -> Result<Vec<SeriesArticle>> {
+        dispatch!(self, list_articles, series_id, published_only)
+    }
+
+    async fn set_articles(&self, series_id: i64, article_ids: &[i64]) -> Result<()> {
+        dispatch!(self, set_articles, series_id, article_ids)
+    }
+
+    async fn series_of_article(&self, article_id: i64) -> Result<Option<i64>> {
+        dispatch!(self, series_of_article, article_id)
+    }
+
+    async fn remove_article(&self, article_id: i64) -> Result<()> {
+        dispatch!(self, remove_article, article_id)
+    }
+
+    async fn existing_article_ids(&self, article_ids: &[i64]) -> Result<Vec<i64>> {
+        if article_ids.is_empty() {
+            return Ok(Vec::new());
+        }
+        dispatch!(self, existing_article_ids, article_ids)
+    }
+}
+
+impl_dual_fn! {
+    async fn list(pool) -> Result<Vec<Series>> {
+        let rows = sqlx::query(&format!(
+            "SELECT {} FROM series ORDER BY created_at DESC, id DESC",
+            SERIES_COLUMNS
+        ))
+        .fetch_all(pool)
+        .await
+        .context("Failed to list series")?;
+        Ok(rows.iter().map(row_to_series).collect())
+    }
+}
+
+impl_dual_fn! {
+    async fn get(pool, id: i64) -> Result<Option<Series>> {
+        let row = sqlx::query(&format!("SELECT {} FROM series WHERE id = ?", SERIES_COLUMNS))
+            .bind(id)
+            .fetch_optional(pool)
+            .await
+            .context("Failed to get series")?;
+        Ok(row.map(|r| row_to_series(&r)))
+    }
+}
+
+impl_dual_fn! {
+    async fn get_by_slug(pool, slug: &str) -> Result<Option<Series>> {
+        let row = sqlx::query(&format!("SELECT {} FROM series WHERE slug = ?", SERIES_COLUMNS))
+            .bind(slug)
+            .fetch_optional(pool)
+            .await
+            .context("Failed to get series")?;
+        Ok(row.map(|r| row_to_series(&r)))
+    }
+}
+
+impl_dual_fn! {
+    async fn update(pool, series: &Series) -> Result<Series> {
+        let now = Utc::now();
+        sqlx::query("UPDATE series SET slug = ?, title = ?, description = ?, updated_at = ? WHERE id = ?")
+            .bind(&series.slug)
+            .bind(&series.title)
+            .bind(&series.description)
+            .bind(now)
+            .bind(series.id)
+            .execute(pool)
+            .await
+            .context("Failed to update series")?;
+        Ok(Series {
+            updated_at: now,
+            ..series.clone()
+        })
+    }
+}
+
+impl_dual_fn! {
+    async fn delete(pool, id: i64) -> Result<bool> {
+        let mut tx = pool.begin().await.context("Failed to start transaction")?;
+        sqlx::query("DELETE FROM series_articles WHERE series_id = ?")
+            .bind(id)
+            .execute(&mut *tx)
+            .await
+            .context("Failed to clear series articles")?;
+        let result = sqlx::query("DELETE FROM series WHERE id = ?")
+            .bind(id)
+            .execute(&mut *tx)
+            .await
+            .context("Failed to delete series")?;
+        tx.commit().await.context("Failed to commit transaction")?;
+        Ok(result.rows_affected() > 0)
+    }
+}
+
+impl_dual_fn! {
+    async fn list_articles(pool, series_id: i64, published_only: bool) -> Result<Vec<SeriesArticle>> {
+        let filter = if published_only {
+            format!(" AND {}", public_article_sql("a."))
+        } else {
+            String::new()
+        };
+        let rows = sqlx::query(&format!(
+            "SELECT {} FROM series_articles sa JOIN articles a ON a.id = sa.article_id \
+             WHERE sa.series_id = ?{} ORDER BY sa.position, a.id",
+            ARTICLE_COLUMNS, filter
+        ))
+        .bind(series_id)
+        .fetch_all(pool)
+        .await
+        .context("Failed to list series articles")?;
+        Ok(rows.iter().map(row_to_article).collect())
+    }
+}
+
+impl_dual_fn! {
+    async fn set_articles(pool, series_id: i64, article_ids: &[i64]) -> Result<()> {
+        let mut tx = pool.begin().await.context("Failed to start transaction")?;
+        sqlx::query("DELETE FROM series_articles WHERE series_id = ?")
+            .bind(series_id)
+            .execute(&mut *tx)
+            .await
+            .context("Failed to clear series articles")?;
+        for (position, article_id) in article_ids.iter().enumerate() {
+            sqlx::query("DELETE FROM series_articles WHERE article_id = ?")
+                .bind(article_id)
+                .execute(&mut *tx)
+                .await
+                .context("Failed to move article between series")?;
+            sqlx::query("INSERT INTO series_articles (series_id, article_id, position) VALUES (?, ?, ?)")
+                .bind(series_id)
+                .bind(article_id)
+                .bind(position as i32)
+                .execute(&mut *tx)
+                .await
+                .context("Failed to add article to series")?;
+        }
+        tx.commit().await.context("Failed to commit transaction")?;
+        Ok(())
+    }
+}
+
+impl_dual_fn! {
+    async fn series_of_article(pool, article_id: i64) -> Result<Option<i64>> {
+        let series_id: Option<i64> =
+            sqlx::query_scalar("SELECT series_id FROM series_articles WHERE article_id = ?")
+                .bind(article_id)
+                .fetch_optional(pool)
+                .await
+                .context("Failed to get series of article")?;
+        Ok(series_id)
+    }
+}
+
+impl_dual_fn! {
+    async fn remove_article(pool, article_id: i64) -> Result<()> {
+        sqlx::query("DELETE FROM series_articles WHERE article_id = ?")
+            .bind(article_id)
+            .execute(pool)
+            .await
+            .context("Failed to remove article from series")?;
+        Ok(())
+    }
+}
+
+impl_dual_fn! {
+    async fn existing_article_ids(pool, article_ids: &[i64]) -> Result<Vec<i64>> {
+        let placeholders = vec!["?"; article_ids.len()].join(", ");
+        let sql = format!("SELECT id FROM articles WHERE id IN ({})", placeholders);
+        let mut query = sqlx::query_scalar(&sql);
+        for id in article_ids {
+            query = query.bind(id);
+        }
+        let ids: Vec<i64> = query
+            .fetch_all(pool)
+            .await
+            .context("Failed to look up articles")?;
+        Ok(ids)
+    }
+}
+
+fn row_to_series<'r, R>(row: &'r R) -> Series
+where
+    R: sqlx::Row,
+    &'r str: sqlx::ColumnIndex<R>,
+    String: sqlx::Decode<'r, R::Database> + sqlx::Type<R::Database>,
+    i64: sqlx::Decode<'r, R::Database> + sqlx::Type<R::Database>,
+    DateTime<Utc>: sqlx::Decode<'r, R::Database> + sqlx::Type<R::Database>,
+{
+    Series {
+        id: row.get("id"),
+        slug: row.get("slug"),
+        title: row.get("title"),
+        description: row.get("description"),
+        created_at: row.get("created_at"),
+        updated_at: row.get("updated_at"),
+    }
+}
+
+fn row_to_article<'r, R>(row: &'r R) -> SeriesArticle
+where
+    R: sqlx::Row,
+    &'r str: sqlx::ColumnIndex<R>,
+    String: sqlx::Decode<'r, R::Database> + sqlx::Type<R::Database>,
+    i64: sqlx::Decode<'r, R::Database> + sqlx::Type<R::Database>,
+    i32: sqlx::Decode<'r, R::Database> + sqlx::Type<R::Database>,
+    DateTime<Utc>: sqlx::Decode<'r, R::Database> + sqlx::Type<R::Database>,
+{
+    SeriesArticle {
+        id: row.get("id"),
+        slug: row.get("slug"),
+        title: row.get("title"),
+        thumbnail: row.get("thumbnail"),
+        status: row.get("status"),
+        published_at: row.get("published_at"),
+        position: row.get("position"),
+    }
+}
+
+async fn create_sqlite(pool: &SqlitePool, series: &Series) -> Result<Series> {
+    let now = Utc::now();
+    let result = sqlx::query(
+        "INSERT INTO series (slug, title, description, created_at, updated_at) VALUES (?, ?, ?, ?, ?)",
+    )
+    .bind(&series.slug)
+    .bind(&series.title)
+    .bind(&series.description)
+    .bind(now)
+    .bind(now)
+    .execute(pool)
+    .await
+    .context("Failed to create series")?;
+
+    Ok(Series {
+        id: result.last_insert_rowid(),
+        created_at: now,
+        updated_at: now,
+        ..series.clone()
+    })
+}
+
+async fn create_mysql(pool: &MySqlPool, series: &Series) -> Result<Series> {
+    let now = Utc::now();
+    let result = sqlx::query(
+        "INSERT INTO series (slug, title, description, created_at, updated_at) VALUES (?, ?, ?, ?, ?)",
+    )
+    .bind(&series.slug)
+    .bind(&series.title)
+    .bind(&series.description)
+    .bind(now)
+    .bind(now)
+    .execute(pool)
+    .await
+    .context("Failed to create series")?;
+
+    Ok(Series {
+        id: result.last_insert_id() as i64,
+        created_at: now,
+        updated_at: now,
+        ..series.clone()
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::{create_test_pool, migrations};
+
+    fn series(slug: &str) -> Series {
+        let now = Utc::now();
+        Series {
+            id: 0,
+            slug: slug.to_string(),
+            title: slug.to_string(),
+            description: String::new(),
+            created_at: now,
+            updated_at: now,
+        }
+    }
+
+    #[tokio::test]
+    async fn articles_keep_their_order_and_belong_to_one_series() {
+        let pool = create_test_pool()
+            .await
+            .expect("Failed to create test pool");
+        migrations::run_migrations(&pool)
+            .await
+            .expect("Failed to run migrations");
+        let sqlite = pool.as_sqlite().unwrap();
+        let user_id = sqlx::query(
+            "INSERT INTO users (username, email, password_hash, role) VALUES ('u', 'u@example.com', 'x', 'admin')",
+        )
+        .execute(sqlite)
+        .await
+        .unwrap()
+        .last_insert_rowid();
+        let mut ids = Vec::new();
+        for (slug, status) in [("a", "published"), ("b", "draft"), ("c", "published")] {
+            let id = sqlx::query(
+                "INSERT INTO articles (slug, title, content, content_html, author_id, category_id, status) VALUES (?, ?, '', '', ?, 1, ?)",
+            )
+            .bind(slug)
+            .bind(slug.to_uppercase())
+            .bind(user_id)
+            .bind(status)
+            .execute(sqlite)
+            .await
+            .unwrap()
+            .last_insert_rowid();
+            ids.push(id);
+        }
+        let repo = SqlxSeriesRepository::new(pool);
+
+        let rust = repo.create(&series("rust")).await.unwrap();
+        let go = repo.create(&series("go")).await.unwrap();
+        repo.set_articles(rust.id, &[ids[2], ids[1], ids[0]])
+            .await
+            .unwrap();
+        let slugs = |articles: Vec<SeriesArticle>| -> Vec<String> {
+            articles.into_iter().map(|a| a.slug).collect()
+        };
+        assert_eq!(
+            slugs(repo.list_articles(rust.id, false).await.unwrap()),
+            ["c", "b", "a"]
+        );
+        assert_eq!(
+            slugs(repo.list_articles(rust.id, true).await.unwrap()),
+            ["c", "a"]
+        );
+
+        // Adding an article to another series moves it
+        repo.set_articles(go.id, &[ids[0]]).await.unwrap();
+        assert_eq!(repo.series_of_article(ids[0]).await.unwrap(), Some(go.id));
+        assert_eq!(
+            slugs(repo.list_articles(rust.id, false).await.unwrap()),
+            ["c", "b"]
+        );
+
+        repo.remove_article(ids[2]).await.unwrap();
+        assert_eq!(repo.series_of_article(ids[2]).await.unwrap(), None);
+        assert_eq!(
+            repo.existing_article_ids(&[ids[0], 999]).await.unwrap(),
+            [ids[0]]
+        );
+
+        assert!(repo.delete(go.id).await.unwrap());
+        assert_eq!(repo.series_of_article(ids[0]).await.unwrap(), None);
+        assert!(repo.get_by_slug("go").await.unwrap().is_none());
+        assert_eq!(repo.list().await.unwrap().len(), 1);
+    }
+}
@@ -0,0 +1,330 @@
+//! Repository for traffic and engagement statistics
+//!
+//! Days are `YYYY-MM-DD` strings in UTC; ranges include `from` and exclude
+//! `to`.
+
+use anyhow::{Context, Result};
+use async_trait::async_trait;
+use serde::Serialize;
+use sqlx::{MySqlPool, Row, SqlitePool};
+use std::sync::Arc;
+
+use crate::db::DynDatabasePool;
+
+/// Article views on one day
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct DailyTraffic {
+    pub day: String,
+    pub views: i64,
+    /// Number of distinct articles viewed
+    pub articles: i64,
+}
+
+/// Comments created on one day, by status
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct DailyComments {
+    pub day: String,
+    pub approved: i64,
+    pub pending: i64,
+    pub spam: i64,
+}
+
+/// An article ranked by views within a range
+#[derive(Debug, Clone, Serialize)]
+pub struct TopContent {
+    pub article_id: i64,
+    pub slug: String,
+    pub title: String,
+    /// Views within the range
+    pub views: i64,
+    /// All-time views
+    pub total_views: i64,
+    /// Approved comments created within the range
+    pub comments: i64,
+}
+
+/// Repository trait for statistics queries
+#[async_trait]
+pub trait StatsRepository: Send + Sync {
+    /// Days in `[from, to)` with at least one view, oldest first
+    async fn traffic_by_day(&self, from: &str, to: &str) -> Result<Vec<DailyTraffic>>;
+
+    /// Days in `[from, to)` with at least one comment, oldest first
+    async fn comments_by_day(&self, from: &str, to: &str) -> Result<Vec<DailyComments>>;
+
+    /// Articles viewed in `[from, to)`, most viewed first
+    async fn top_content(
+        &self,
+        from: &str,
+        to: &str,
+        offset: i64,
+        limit: i64,
+    ) -> Result<Vec<TopContent>>;
+}
+
+/// SQLx-based statistics repository
+pub struct SqlxStatsRepository {
+    pool: DynDatabasePool,
+}
+
+impl SqlxStatsRepository {
+    pub fn new(pool: DynDatabasePool) -> Self {
+        Self { pool }
+    }
+
+    /// Create a boxed repository for use with dependency injection
+    pub fn boxed(pool: DynDatabasePool) -> Arc<dyn StatsRepository> {
+        Arc::new(Self::new(pool))
+    }
+}
+
+#[async_trait]
+impl StatsRepository for SqlxStatsRepository {
+    async fn traffic_by_day(&self, from: &str, to: &str) -> Result<Vec<DailyTraffic>> {
+        dispatch!(self, traffic_by_day, from, to)
+    }
+
+    async fn comments_by_day(&self, from: &str, to: &str) -> Result<Vec<DailyComments>> {
+        dispatch!(self, comments_by_day, from, to)
+    }
+
+    async fn top_content(
+        &self,
+        from: &str,
+        to: &str,
+        offset: i64,
+        limit: i64,
+    ) -> Result<Vec<TopContent>> {
+        dispatch!(self, top_content, from, to, offset, limit)
+    }
+}
+
+// ============================================================================
+// Shared implementations (identical SQL)
+// ============================================================================
+
+impl_dual_fn! {
+    async fn traffic_by_day(pool, from: &str, to: &str) -> Result<Vec<DailyTraffic>> {
+        let rows = sqlx::query(
+            "SELECT day, CAST(SUM(views) AS SIGNED) AS views, COUNT(*) AS articles \
+             FROM article_views_daily WHERE day >= ? AND day < ? GROUP BY day ORDER BY day",
+        )
+        .bind(from)
+        .bind(to)
+        .fetch_all(pool)
+        .await
+        .context("Failed to load daily traffic")?;
+        Ok(rows
+            .iter()
+            .map(|row| DailyTraffic {
+                day: row.get("day"),
+                views: row.get("views"),
+                articles: row.get("articles"),
+            })
+            .collect())
+    }
+}
+
+impl_dual_fn! {
+    async fn top_content(pool, from: &str, to: &str, offset: i64, limit: i64) -> Result<Vec<TopContent>> {
+        let rows = sqlx::query(
+            "SELECT a.id, a.slug, a.title, a.view_count, v.views, \
+             (SELECT COUNT(*) FROM comments c WHERE c.article_id = a.id AND c.status = 'approved' \
+              AND c.created_at >= ? AND c.created_at < ?) AS comments \
+             FROM (SELECT article_id, CAST(SUM(views) AS SIGNED) AS views FROM article_views_daily \
+                   WHERE day >= ? AND day < ? GROUP BY article_id) v \
+             JOIN articles a ON a.id = v.article_id \
+             ORDER BY v.views DESC, a.id LIMIT ? OFFSET ?",
+        )
+        .bind(from)
+        .bind(to)
+        .bind(from)
+        .bind(to)
+        .bind(limit)
+        .bind(offset)
+        .fetch_all(pool)
+        .await
+        .context("Failed to load top content")?;
+        Ok(rows
+            .iter()
+            .map(|row| TopContent {
+                article_id: row.get("id"),
+                slug: row.get("slug"),
+                title: row.get("title"),
+                views: row.get("views"),
+                total_views: row.get("view_count"),
+                comments: row.get("comments"),
+            })
+            .collect())
+    }
+}
+
+// ============================================================================
+// Driver-specific implementations (date formatting differs)
+// ============================================================================
+
+/// Comparing `created_at` with a bare `YYYY-MM-DD` day works on both drivers
+const COMMENTS_BY_DAY: &str = "SELECT {day} AS day, \
+     COUNT(CASE WHEN status = 'approved' THEN 1 END) AS approved, \
+     COUNT(CASE WHEN status = 'pending' THEN 1 END) AS pending, \
+     COUNT(CASE WHEN status = 'spam' THEN 1 END) AS spam \
+     FROM comments WHERE created_at >= ? AND created_at < ? GROUP BY day ORDER BY day";
+
+async fn comments_by_day_sqlite(
+    pool: &SqlitePool,
+    from: &str,
+    to: &str,
+) -> Result<Vec<DailyComments>> {
+    let sql = COMMENTS_BY_DAY.replace("{day}", "substr(created_at, 1, 10)");
+    let rows = sqlx::query(&sql)
+        .bind(from)
+        .bind(to)
+        .fetch_all(pool)
+        .await
+        .context("Failed to load daily comments")?;
+    Ok(rows
+        .iter()
+        .map(|row| DailyComments {
+            day: row.get("day"),
+            approved: row.get("approved"),
+            pending: row.get("pending"),
+            spam: row.get("spam"),
+        })
+        .collect())
+}
+
+async fn comments_by_day_mysql(
+    pool: &MySqlPool,
+    from: &str,
+    to: &str,
+) -> Result<Vec<DailyComments>> {
+    let sql = COMMENTS_BY_DAY.replace("{day}", "DATE_FORMAT(created_at, '%Y-%m-%d')");
+    let rows = sqlx::query(&sql)
+        .bind(from)
+        .bind(to)
+        .fetch_all(pool)
+        .await
+        .context("Failed to load daily comments")?;
+    Ok(rows
+        .iter()
+        .map(|row| DailyComments {
+            day: row.get("day"),
+            approved: row.get("approved"),
+            pending: row.get("pending"),
+            spam: row.get("spam"),
+        })
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::{create_test_pool, migrations};
+
+    #[tokio::test]
+    async fn test_daily_stats() {
+        let pool = create_test_pool().await.unwrap();
+        migrations::run_migrations(&pool).await.unwrap();
+        let sqlite = pool.as_sqlite().unwrap();
+        sqlx::query("INSERT INTO users (username, email, password_hash, role) VALUES ('u', 'u@example.com', 'x', 'admin')")
+            .execute(sqlite)
+            .await
+            .unwrap();
+        sqlx::query("INSERT INTO categories (slug, name, sort_order) VALUES ('c', 'C', 0)")
+            .execute(sqlite)
+            .await
+            .unwrap();
+        for slug in ["a", "b"] {
+            sqlx::query("INSERT INTO articles (slug, title, content, content_html, author_id, category_id, status) VALUES (?, ?, '', '', 1, 1, 'published')")
+                .bind(slug)
+                .bind(slug.to_uppercase())
+                .execute(sqlite)
+                .await
+                .unwrap();
+        }
+        for (article, day, views) in [
+            (1, "2024-05-01", 3),
+            (2, "2024-05-01", 1),
+            (2, "2024-05-02", 5),
+            (1, "2024-06-01", 9),
+        ] {
+            sqlx::query(
+                "INSERT INTO article_views_daily (article_id, day, views) VALUES (?, ?, ?)",
+            )
+            .bind(article)
+            .bind(day)
+            .bind(views)
+            .execute(sqlite)
+            .await
+            .unwrap();
+        }
+        for (status, at) in [
+            ("approved", "2024-05-01 10:00:00"),
+            ("approved", "2024-05-02 23:59:59"),
+            ("spam", "2024-05-02 08:00:00"),
+            ("approved", "2024-06-01 08:00:00"),
+        ] {
+            sqlx::query("INSERT INTO comments (article_id, content, status, created_at) VALUES (2, 'hi', ?, ?)")
+                .bind(status)
+                .bind(at)
+                .execute(sqlite)
+                .await
+                .unwrap();
+        }
+
+        let repo = SqlxStatsRepository::new(pool.clone());
+        let traffic = repo
+            .traffic_by_day("2024-05-01", "2024-06-01")
+            .await
+            .unwrap();
+        assert_eq!(
+            traffic,
+            vec![
+                DailyTraffic {
+                    day: "2024-05-01".to_string(),
+                    views: 4,
+                    articles: 2
+                },
+                DailyTraffic {
+                    day: "2024-05-02".to_string(),
+                    views: 5,
+                    articles: 1
+                },
+            ]
+        );
+
+        let top = repo
+            .top_content("2024-05-01", "2024-06-01", 0, 10)
+            .await
+            .unwrap();
+        let ranked: Vec<(&str, i64, i64)> = top
+            .iter()
+            .map(|t| (t.slug.as_str(), t.views, t.comments))
+            .collect();
+        assert_eq!(ranked, vec![("b", 6, 2), ("a", 3, 0)]);
+
+        let comments = repo
+            .comments_by_day("2024-05-01", "2024-06-01")
+            .await
+            .unwrap();
+        assert_eq!(comments.len(), 2);
+        assert_eq!(comments[1].day, "2024-05-02");
+        assert_eq!((comments[1].approved, comments[1].spam), (1, 1));
+
+        // Recorded views land on today's row
+        use crate::db::repositories::{CommentRepository, SqlxCommentRepository};
+        let comment_repo = SqlxCommentRepository::new(pool.clone());
+        comment_repo.increment_view(1).await.unwrap();
+        comment_repo.increment_view(1).await.unwrap();
+        let today = chrono::Utc::now().date_naive();
+        let traffic = repo
+            .traffic_by_day(
+                &today.format("%Y-%m-%d").to_string(),
+                &today.succ_opt().unwrap().format("%Y-%m-%d").to_string(),
+            )
+            .await
+            .unwrap();
+        assert_eq!(traffic.len(), 1);
+        assert_eq!((traffic[0].views, traffic[0].articles), (2, 1));
+    }
+}
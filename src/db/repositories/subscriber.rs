@@ -0,0 +1,444 @@
+//! Newsletter subscriber repository
+
+use anyhow::{Context, Result};
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use sqlx::Row;
+use std::sync::Arc;
+
+use crate::db::DynDatabasePool;
+use crate::models::{normalize_email, NewsletterIssue, Subscriber, SubscriberStatus};
+
+/// Repository trait for newsletter subscribers and sent issues
+#[async_trait]
+pub trait SubscriberRepository: Send + Sync {
+    async fn get(&self, id: i64) -> Result<Option<Subscriber>>;
+
+    /// Look up an address (case-insensitive)
+    async fn get_by_email(&self, email: &str) -> Result<Option<Subscriber>>;
+
+    /// Look up the secret of a confirmation or unsubscribe link
+    async fn get_by_token(&self, token: &str) -> Result<Option<Subscriber>>;
+
+    /// Add a pending subscriber
+    async fn create(&self, email: &str, token: &str) -> Result<Subscriber>;
+
+    /// Make an unsubscribed address pending again with a new token
+    async fn resubscribe(&self, id: i64, token: &str) -> Result<()>;
+
+    /// Change the status, stamping `confirmed_at` or `unsubscribed_at`.
+    /// Returns whether the subscriber exists.
+    async fn set_status(
+        &self,
+        id: i64,
+        status: SubscriberStatus,
+        at: DateTime<Utc>,
+    ) -> Result<bool>;
+
+    /// Remove a subscriber. Returns whether it existed.
+    async fn delete(&self, id: i64) -> Result<bool>;
+
+    /// List subscribers, newest first, with the total count
+    async fn list(
+        &self,
+        status: Option<SubscriberStatus>,
+        page: i64,
+        per_page: i64,
+    ) -> Result<(Vec<Subscriber>, i64)>;
+
+    /// Ids of subscribers receiving newsletters
+    async fn active_ids(&self) -> Result<Vec<i64>>;
+
+    /// Number of subscribers per status
+    async fn counts(&self) -> Result<Vec<(SubscriberStatus, i64)>>;
+
+    /// When an article was sent as a newsletter, if it was
+    async fn get_issue(&self, article_id: i64) -> Result<Option<NewsletterIssue>>;
+
+    /// Record that an article was sent, replacing an earlier record
+    async fn record_issue(
+        &self,
+        article_id: i64,
+        recipients: i64,
+        at: DateTime<Utc>,
+    ) -> Result<NewsletterIssue>;
+}
+
+/// SQLx-based subscriber repository
+pub struct SqlxSubscriberRepository {
+    pool: DynDatabasePool,
+}
+
+impl SqlxSubscriberRepository {
+    pub fn new(pool: DynDatabasePool) -> Self {
+        Self { pool }
+    }
+
+    /// Create a boxed repository for use with dependency injection
+    pub fn boxed(pool: DynDatabasePool) -> Arc<dyn SubscriberRepository> {
+        Arc::new(Self::new(pool))
+    }
+}
+
+#[async_trait]
+impl SubscriberRepository for SqlxSubscriberRepository {
+    async fn get(&self, id: i64) -> Result<Option<Subscriber>> {
+        dispatch!(self, get_subscriber, id)
+    }
+
+    async fn get_by_email(&self, email: &str) -> Result<Option<Subscriber>> {
+        let email = normalize_email(email);
+        dispatch!(self, get_subscriber_by_email, &email)
+    }
+
+    async fn get_by_token(&self, token: &str) -> Result<Option<Subscriber>> {
+        dispatch!(self, get_subscriber_by_token, token)
+    }
+
+    async fn create(&self, email: &str, token: &str) -> Result<Subscriber> {
+        let email = normalize_email(email);
+        dispatch!(self, create_subscriber, &email, token)
+    }
+
+    async fn resubscribe(&self, id: i64, token: &str) -> Result<()> {
+        dispatch!(self, resubscribe_subscriber, id, token)
+    }
+
+    async fn set_status(
+        &self,
+        id: i64,
+        status: SubscriberStatus,
+        at: DateTime<Utc>,
+    ) -> Result<bool> {
+        dispatch!(self, set_subscriber_status, id, status, at)
+    }
+
+    async fn delete(&self, id: i64) -> Result<bool> {
+        dispatch!(self, delete_subscriber, id)
+    }
+
+    async fn list(
+        &self,
+        status: Option<SubscriberStatus>,
+        page: i64,
+        per_page: i64,
+    ) -> Result<(Vec<Subscriber>, i64)> {
+        dispatch!(self, list_subscribers, status, page, per_page)
+    }
+
+    async fn active_ids(&self) -> Result<Vec<i64>> {
+        dispatch!(self, active_subscriber_ids)
+    }
+
+    async fn counts(&self) -> Result<Vec<(SubscriberStatus, i64)>> {
+        dispatch!(self, count_subscribers)
+    }
+
+    async fn get_issue(&self, article_id: i64) -> Result<Option<NewsletterIssue>> {
+        dispatch!(self, get_newsletter_issue, article_id)
+    }
+
+    async fn record_issue(
+        &self,
+        article_id: i64,
+        recipients: i64,
+        at: DateTime<Utc>,
+    ) -> Result<NewsletterIssue> {
+        dispatch!(self, record_newsletter_issue, article_id, recipients, at)
+    }
+}
+
+// ============================================================================
+// Shared implementations (identical SQL)
+// ============================================================================
+
+const SELECT_COLUMNS: &str =
+    "SELECT id, email, status, token, created_at, confirmed_at, unsubscribed_at FROM subscribers";
+
+impl_dual_fn! {
+    async fn get_subscriber(pool, id: i64) -> Result<Option<Subscriber>> {
+        let row = sqlx::query(&format!("{} WHERE id = ?", SELECT_COLUMNS))
+            .bind(id)
+            .fetch_optional(pool)
+            .await
+            .context("Failed to get subscriber")?;
+        row.as_ref().map(row_to_subscriber).transpose()
+    }
+}
+
+impl_dual_fn! {
+    async fn get_subscriber_by_email(pool, email: &str) -> Result<Option<Subscriber>> {
+        let row = sqlx::query(&format!("{} WHERE email = ?", SELECT_COLUMNS))
+            .bind(email)
+            .fetch_optional(pool)
+            .await
+            .context("Failed to get subscriber")?;
+        row.as_ref().map(row_to_subscriber).transpose()
+    }
+}
+
+impl_dual_fn! {
+    async fn get_subscriber_by_token(pool, token: &str) -> Result<Option<Subscriber>> {
+        let row = sqlx::query(&format!("{} WHERE token = ?", SELECT_COLUMNS))
+            .bind(token)
+            .fetch_optional(pool)
+            .await
+            .context("Failed to get subscriber")?;
+        row.as_ref().map(row_to_subscriber).transpose()
+    }
+}
+
+impl_dual_fn! {
+    async fn create_subscriber(pool, email: &str, token: &str) -> Result<Subscriber> {
+        sqlx::query("INSERT INTO subscribers (email, status, token, created_at) VALUES (?, ?, ?, ?)")
+            .bind(email)
+            .bind(SubscriberStatus::Pending.as_str())
+            .bind(token)
+            .bind(Utc::now())
+            .execute(pool)
+            .await
+            .context("Failed to add subscriber")?;
+        let row = sqlx::query(&format!("{} WHERE email = ?", SELECT_COLUMNS))
+            .bind(email)
+            .fetch_one(pool)
+            .await
+            .context("Failed to load subscriber")?;
+        row_to_subscriber(&row)
+    }
+}
+
+impl_dual_fn! {
+    async fn resubscribe_subscriber(pool, id: i64, token: &str) -> Result<()> {
+        sqlx::query("UPDATE subscribers SET status = ?, token = ?, confirmed_at = NULL, unsubscribed_at = NULL WHERE id = ?")
+            .bind(SubscriberStatus::Pending.as_str())
+            .bind(token)
+            .bind(id)
+            .execute(pool)
+            .await
+            .context("Failed to resubscribe")?;
+        Ok(())
+    }
+}
+
+impl_dual_fn! {
+    async fn set_subscriber_status(pool, id: i64, status: SubscriberStatus, at: DateTime<Utc>) -> Result<bool> {
+        // Pending keeps its signup time; the other states record when they were reached
+        let query = match status {
+            SubscriberStatus::Pending => sqlx::query("UPDATE subscribers SET status = ? WHERE id = ?")
+                .bind(status.as_str()),
+            SubscriberStatus::Active => sqlx::query("UPDATE subscribers SET status = ?, confirmed_at = ? WHERE id = ?")
+                .bind(status.as_str())
+                .bind(at),
+            SubscriberStatus::Unsubscribed => sqlx::query("UPDATE subscribers SET status = ?, unsubscribed_at = ? WHERE id = ?")
+                .bind(status.as_str())
+                .bind(at),
+        };
+        let result = query
+            .bind(id)
+            .execute(pool)
+            .await
+            .context("Failed to update subscriber")?;
+        Ok(result.rows_affected() > 0)
+    }
+}
+
+impl_dual_fn! {
+    async fn delete_subscriber(pool, id: i64) -> Result<bool> {
+        let result = sqlx::query("DELETE FROM subscribers WHERE id = ?")
+            .bind(id)
+            .execute(pool)
+            .await
+            .context("Failed to delete subscriber")?;
+        Ok(result.rows_affected() > 0)
+    }
+}
+
+impl_dual_fn! {
+    async fn list_subscribers(pool, status: Option<SubscriberStatus>, page: i64, per_page: i64) -> Result<(Vec<Subscriber>, i64)> {
+        let offset = (page.max(1) - 1) * per_page;
+        let (rows, total) = match status {
+            Some(status) => {
+                let rows = sqlx::query(&format!("{} WHERE status = ? ORDER BY id DESC LIMIT ? OFFSET ?", SELECT_COLUMNS))
+                    .bind(status.as_str())
+                    .bind(per_page)
+                    .bind(offset)
+                    .fetch_all(pool)
+                    .await
+                    .context("Failed to list subscribers")?;
+                let total: i64 = sqlx::query("SELECT COUNT(*) as count FROM subscribers WHERE status = ?")
+                    .bind(status.as_str())
+                    .fetch_one(pool)
+                    .await
+                    .context("Failed to count subscribers")?
+                    .get("count");
+                (rows, total)
+            }
+            None => {
+                let rows = sqlx::query(&format!("{} ORDER BY id DESC LIMIT ? OFFSET ?", SELECT_COLUMNS))
+                    .bind(per_page)
+                    .bind(offset)
+                    .fetch_all(pool)
+                    .await
+                    .context("Failed to list subscribers")?;
+                let total: i64 = sqlx::query("SELECT COUNT(*) as count FROM subscribers")
+                    .fetch_one(pool)
+                    .await
+                    .context("Failed to count subscribers")?
+                    .get("count");
+                (rows, total)
+            }
+        };
+        let subscribers = rows.iter().map(row_to_subscriber).collect::<Result<Vec<_>>>()?;
+        Ok((subscribers, total))
+    }
+}
+
+impl_dual_fn! {
+    async fn active_subscriber_ids(pool) -> Result<Vec<i64>> {
+        let rows = sqlx::query("SELECT id FROM subscribers WHERE status = ? ORDER BY id")
+            .bind(SubscriberStatus::Active.as_str())
+            .fetch_all(pool)
+            .await
+            .context("Failed to list active subscribers")?;
+        Ok(rows.iter().map(|row| row.get("id")).collect())
+    }
+}
+
+impl_dual_fn! {
+    async fn count_subscribers(pool) -> Result<Vec<(SubscriberStatus, i64)>> {
+        let rows = sqlx::query("SELECT status, COUNT(*) AS count FROM subscribers GROUP BY status")
+            .fetch_all(pool)
+            .await
+            .context("Failed to count subscribers")?;
+        rows.iter()
+            .map(|row| -> Result<(SubscriberStatus, i64)> {
+                let status: String = row.get("status");
+                Ok((status.parse().map_err(anyhow::Error::msg)?, row.get("count")))
+            })
+            .collect()
+    }
+}
+
+impl_dual_fn! {
+    async fn get_newsletter_issue(pool, article_id: i64) -> Result<Option<NewsletterIssue>> {
+        let row = sqlx::query("SELECT article_id, recipients, sent_at FROM newsletter_issues WHERE article_id = ?")
+            .bind(article_id)
+            .fetch_optional(pool)
+            .await
+            .context("Failed to get newsletter issue")?;
+        Ok(row.map(|row| NewsletterIssue {
+            article_id: row.get("article_id"),
+            recipients: row.get("recipients"),
+            sent_at: row.get("sent_at"),
+        }))
+    }
+}
+
+impl_dual_fn! {
+    async fn record_newsletter_issue(pool, article_id: i64, recipients: i64, at: DateTime<Utc>) -> Result<NewsletterIssue> {
+        let updated = sqlx::query("UPDATE newsletter_issues SET recipients = ?, sent_at = ? WHERE article_id = ?")
+            .bind(recipients)
+            .bind(at)
+            .bind(article_id)
+            .execute(pool)
+            .await
+            .context("Failed to update newsletter issue")?;
+        if updated.rows_affected() == 0 {
+            sqlx::query("INSERT INTO newsletter_issues (article_id, recipients, sent_at) VALUES (?, ?, ?)")
+                .bind(article_id)
+                .bind(recipients)
+                .bind(at)
+                .execute(pool)
+                .await
+                .context("Failed to record newsletter issue")?;
+        }
+        Ok(NewsletterIssue {
+            article_id,
+            recipients,
+            sent_at: at,
+        })
+    }
+}
+
+/// Map a row to a subscriber (same column types on SQLite and MySQL)
+fn row_to_subscriber<'r, R>(row: &'r R) -> Result<Subscriber>
+where
+    R: sqlx::Row,
+    &'r str: sqlx::ColumnIndex<R>,
+    String: sqlx::Decode<'r, R::Database> + sqlx::Type<R::Database>,
+    i64: sqlx::Decode<'r, R::Database> + sqlx::Type<R::Database>,
+    DateTime<Utc>: sqlx::Decode<'r, R::Database> + sqlx::Type<R::Database>,
+    Option<DateTime<Utc>>: sqlx::Decode<'r, R::Database> + sqlx::Type<R::Database>,
+{
+    let status: String = row.get("status");
+    Ok(Subscriber {
+        id: row.get("id"),
+        email: row.get("email"),
+        status: status.parse().map_err(anyhow::Error::msg)?,
+        token: row.get("token"),
+        created_at: row.get("created_at"),
+        confirmed_at: row.get("confirmed_at"),
+        unsubscribed_at: row.get("unsubscribed_at"),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::{create_test_pool, migrations};
+
+    #[tokio::test]
+    async fn subscribers_move_through_opt_in() {
+        let pool = create_test_pool()
+            .await
+            .expect("Failed to create test pool");
+        migrations::run_migrations(&pool)
+            .await
+            .expect("Failed to run migrations");
+        let repo = SqlxSubscriberRepository::new(pool);
+
+        let subscriber = repo.create("Reader@Example.com ", "token-1").await.unwrap();
+        assert_eq!(subscriber.email, "reader@example.com");
+        assert_eq!(subscriber.status, SubscriberStatus::Pending);
+        assert!(repo.create("reader@example.com", "token-2").await.is_err());
+        assert!(repo.active_ids().await.unwrap().is_empty());
+
+        let now = Utc::now();
+        assert!(repo
+            .set_status(subscriber.id, SubscriberStatus::Active, now)
+            .await
+            .unwrap());
+        let confirmed = repo.get_by_token("token-1").await.unwrap().unwrap();
+        assert_eq!(confirmed.status, SubscriberStatus::Active);
+        assert!(confirmed.confirmed_at.is_some());
+        assert_eq!(repo.active_ids().await.unwrap(), [subscriber.id]);
+
+        repo.set_status(subscriber.id, SubscriberStatus::Unsubscribed, now)
+            .await
+            .unwrap();
+        repo.resubscribe(subscriber.id, "token-3").await.unwrap();
+        let again = repo
+            .get_by_email("READER@example.com")
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(again.status, SubscriberStatus::Pending);
+        assert_eq!(again.token, "token-3");
+        assert!(again.unsubscribed_at.is_none());
+
+        repo.create("other@example.com", "token-4").await.unwrap();
+        let (pending, total) = repo
+            .list(Some(SubscriberStatus::Pending), 1, 1)
+            .await
+            .unwrap();
+        assert_eq!((pending.len(), total), (1, 2));
+        assert!(repo
+            .counts()
+            .await
+            .unwrap()
+            .contains(&(SubscriberStatus::Pending, 2)));
+
+        assert!(repo.delete(subscriber.id).await.unwrap());
+        assert!(repo.get(subscriber.id).await.unwrap().is_none());
+    }
+}
@@ -0,0 +1,200 @@
+//! Repository for delta sync queries
+//!
+//! Reads article and page summaries changed after a checkpoint, and the
+//! tombstones left behind by deletions.
+
+use anyhow::{Context, Result};
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use std::sync::Arc;
+
+use crate::db::repositories::category::readable_category_sql;
+use crate::db::repositories::reader_group::readable_audience_sql;
+use crate::db::DynDatabasePool;
+use crate::models::CategoryAccess;
+
+/// Summary of a changed article
+#[derive(Debug, Clone, Serialize)]
+pub struct SyncArticle {
+    pub id: i64,
+    pub slug: String,
+    pub title: String,
+    pub category_id: i64,
+    pub thumbnail: Option<String>,
+    #[serde(skip)]
+    pub status: String,
+    /// Readable by anonymous visitors: not in a private category and not
+    /// restricted to an audience
+    #[serde(skip)]
+    pub public: bool,
+    pub published_at: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+/// Summary of a changed page
+#[derive(Debug, Clone, Serialize)]
+pub struct SyncPage {
+    pub id: i64,
+    pub slug: String,
+    pub title: String,
+    #[serde(skip)]
+    pub status: String,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+/// A deleted article or page
+#[derive(Debug, Clone)]
+pub struct Tombstone {
+    /// "article" or "page"
+    pub entity_type: String,
+    pub entity_id: i64,
+    pub slug: String,
+    /// Status at the time of deletion
+    pub status: String,
+    pub deleted_at: DateTime<Utc>,
+}
+
+/// Repository trait for delta sync
+#[async_trait]
+pub trait SyncRepository: Send + Sync {
+    /// Articles updated after `since`, oldest change first, flagged by
+    /// whether anonymous visitors may read them
+    async fn articles_changed_since(&self, since: DateTime<Utc>) -> Result<Vec<SyncArticle>>;
+
+    /// Pages updated after `since`, oldest change first
+    async fn pages_changed_since(&self, since: DateTime<Utc>) -> Result<Vec<SyncPage>>;
+
+    /// Deletions after `since`, oldest first
+    async fn tombstones_since(&self, since: DateTime<Utc>) -> Result<Vec<Tombstone>>;
+
+    /// Forget deletions older than `before`
+    async fn prune_tombstones(&self, before: DateTime<Utc>) -> Result<u64>;
+}
+
+/// SQLx-based delta sync repository
+pub struct SqlxSyncRepository {
+    pool: DynDatabasePool,
+}
+
+impl SqlxSyncRepository {
+    pub fn new(pool: DynDatabasePool) -> Self {
+        Self { pool }
+    }
+
+    /// Create a boxed repository for use with dependency injection
+    pub fn boxed(pool: DynDatabasePool) -> Arc<dyn SyncRepository> {
+        Arc::new(Self::new(pool))
+    }
+}
+
+#[async_trait]
+impl SyncRepository for SqlxSyncRepository {
+    async fn articles_changed_since(&self, since: DateTime<Utc>) -> Result<Vec<SyncArticle>> {
+        dispatch!(self, articles_changed_since, since)
+    }
+
+    async fn pages_changed_since(&self, since: DateTime<Utc>) -> Result<Vec<SyncPage>> {
+        dispatch!(self, pages_changed_since, since)
+    }
+
+    async fn tombstones_since(&self, since: DateTime<Utc>) -> Result<Vec<Tombstone>> {
+        dispatch!(self, tombstones_since, since)
+    }
+
+    async fn prune_tombstones(&self, before: DateTime<Utc>) -> Result<u64> {
+        dispatch!(self, prune_tombstones, before)
+    }
+}
+
+// ============================================================================
+// Shared implementations (identical SQL)
+// ============================================================================
+
+impl_dual_fn! {
+    async fn articles_changed_since(pool, since: DateTime<Utc>) -> Result<Vec<SyncArticle>> {
+        let rows = sqlx::query(&format!(
+            "SELECT id, slug, title, category_id, thumbnail, status, \
+             CASE WHEN {} AND {} THEN 1 ELSE 0 END AS public, \
+             published_at, created_at, updated_at FROM articles WHERE updated_at > ? ORDER BY updated_at",
+            readable_category_sql("category_id", CategoryAccess::Public),
+            readable_audience_sql("id", None)
+        ))
+        .bind(since)
+        .fetch_all(pool)
+        .await
+        .context("Failed to list changed articles")?;
+        use sqlx::Row;
+        Ok(rows
+            .iter()
+            .map(|row| SyncArticle {
+                id: row.get("id"),
+                slug: row.get("slug"),
+                title: row.get("title"),
+                category_id: row.get("category_id"),
+                thumbnail: row.try_get("thumbnail").ok().flatten(),
+                status: row.get("status"),
+                public: row.get::<i64, _>("public") != 0,
+                published_at: row.try_get("published_at").ok().flatten(),
+                created_at: row.get("created_at"),
+                updated_at: row.get("updated_at"),
+            })
+            .collect())
+    }
+}
+
+impl_dual_fn! {
+    async fn pages_changed_since(pool, since: DateTime<Utc>) -> Result<Vec<SyncPage>> {
+        let rows = sqlx::query("SELECT id, slug, title, status, created_at, updated_at FROM pages WHERE updated_at > ? ORDER BY updated_at")
+            .bind(since)
+            .fetch_all(pool)
+            .await
+            .context("Failed to list changed pages")?;
+        use sqlx::Row;
+        Ok(rows
+            .iter()
+            .map(|row| SyncPage {
+                id: row.get("id"),
+                slug: row.get("slug"),
+                title: row.get("title"),
+                status: row.get("status"),
+                created_at: row.get("created_at"),
+                updated_at: row.get("updated_at"),
+            })
+            .collect())
+    }
+}
+
+impl_dual_fn! {
+    async fn tombstones_since(pool, since: DateTime<Utc>) -> Result<Vec<Tombstone>> {
+        let rows = sqlx::query("SELECT entity_type, entity_id, slug, status, deleted_at FROM sync_tombstones WHERE deleted_at > ? ORDER BY deleted_at")
+            .bind(since)
+            .fetch_all(pool)
+            .await
+            .context("Failed to list tombstones")?;
+        use sqlx::Row;
+        Ok(rows
+            .iter()
+            .map(|row| Tombstone {
+                entity_type: row.get("entity_type"),
+                entity_id: row.get("entity_id"),
+                slug: row.get("slug"),
+                status: row.get("status"),
+                deleted_at: row.get("deleted_at"),
+            })
+            .collect())
+    }
+}
+
+impl_dual_fn! {
+    async fn prune_tombstones(pool, before: DateTime<Utc>) -> Result<u64> {
+        let result = sqlx::query("DELETE FROM sync_tombstones WHERE deleted_at < ?")
+            .bind(before)
+            .execute(pool)
+            .await
+            .context("Failed to prune tombstones")?;
+        Ok(result.rows_affected())
+    }
+}
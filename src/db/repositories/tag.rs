@@ -10,6 +10,7 @@
 //! - 3.1: WHEN 用户为文章添加标签 THEN Tag_Service SHALL 创建或复用已有标签并建立关联
 //! - 3.4: THE Tag_Service SHALL 提供标签云功能，按使用频率排序
 
+use crate::db::repositories::category::public_article_sql;
 use crate::db::DynDatabasePool;
 use crate::models::{Tag, TagWithCount};
 use anyhow::{Context, Result};
@@ -60,6 +61,16 @@ pub trait TagRepository: Send + Sync {
 
     /// Remove all tags from an article (for re-tagging)
     async fn remove_all_by_article(&self, article_id: i64) -> Result<()>;
+
+    /// Change a tag's name and slug, keeping the old slug pointing at it
+    async fn rename(&self, id: i64, name: &str, slug: &str) -> Result<()>;
+
+    /// Move the articles and slugs of `source_ids` to `target_id` and
+    /// delete the source tags, all in one transaction
+    async fn merge(&self, target_id: i64, source_ids: &[i64]) -> Result<()>;
+
+    /// Get the tag a former slug now points to
+    async fn get_by_previous_slug(&self, slug: &str) -> Result<Option<Tag>>;
 }
 
 /// SQLx-based tag repository implementation
@@ -136,6 +147,24 @@ impl TagRepository for SqlxTagRepository {
     async fn remove_all_by_article(&self, article_id: i64) -> Result<()> {
         dispatch!(self, remove_all_tags_by_article, article_id)
     }
+
+    async fn rename(&self, id: i64, name: &str, slug: &str) -> Result<()> {
+        dispatch!(self, rename_tag, id, name, slug)
+    }
+
+    async fn merge(&self, target_id: i64, source_ids: &[i64]) -> Result<()> {
+        if source_ids.is_empty() {
+            return Ok(());
+        }
+        dispatch!(self, merge_tags, target_id, source_ids)
+    }
+
+    async fn get_by_previous_slug(&self, slug: &str) -> Result<Option<Tag>> {
+        match dispatch!(self, get_tag_slug_owner, slug)? {
+            Some(id) => self.get_by_id(id).await,
+            None => Ok(None),
+        }
+    }
 }
 
 // ============================================================================
@@ -248,17 +277,18 @@ async fn list_tags_sqlite(pool: &SqlitePool) -> Result<Vec<Tag>> {
 /// Get tags with article counts for tag cloud functionality (SQLite)
 /// Returns tags sorted by article count in descending order
 async fn get_tags_with_counts_sqlite(pool: &SqlitePool, limit: usize) -> Result<Vec<TagWithCount>> {
-    let rows = sqlx::query(
+    let rows = sqlx::query(&format!(
         r#"
         SELECT t.id, t.slug, t.name, t.created_at, COUNT(a.id) as article_count
         FROM tags t
         LEFT JOIN article_tags at ON t.id = at.tag_id
-        LEFT JOIN articles a ON a.id = at.article_id AND a.status = 'published'
+        LEFT JOIN articles a ON a.id = at.article_id AND {}
         GROUP BY t.id, t.slug, t.name, t.created_at
         ORDER BY article_count DESC, t.name ASC
         LIMIT ?
         "#,
-    )
+        public_article_sql("a.")
+    ))
     .bind(limit as i64)
     .fetch_all(pool)
     .await
@@ -440,17 +470,18 @@ async fn list_tags_mysql(pool: &MySqlPool) -> Result<Vec<Tag>> {
 /// Get tags with article counts for tag cloud functionality (MySQL)
 /// Returns tags sorted by article count in descending order
 async fn get_tags_with_counts_mysql(pool: &MySqlPool, limit: usize) -> Result<Vec<TagWithCount>> {
-    let rows = sqlx::query(
+    let rows = sqlx::query(&format!(
         r#"
         SELECT t.id, t.slug, t.name, t.created_at, COUNT(a.id) as article_count
         FROM tags t
         LEFT JOIN article_tags at ON t.id = at.tag_id
-        LEFT JOIN articles a ON a.id = at.article_id AND a.status = 'published'
+        LEFT JOIN articles a ON a.id = at.article_id AND {}
         GROUP BY t.id, t.slug, t.name, t.created_at
         ORDER BY article_count DESC, t.name ASC
         LIMIT ?
         "#,
-    )
+        public_article_sql("a.")
+    ))
     .bind(limit as i64)
     .fetch_all(pool)
     .await
@@ -648,6 +679,139 @@ impl_dual_fn! {
     }
 }
 
+impl_dual_fn! {
+    async fn rename_tag(pool, id: i64, name: &str, slug: &str) -> Result<()> {
+        let mut tx = pool.begin().await.context("Failed to start transaction")?;
+        let old_slug: String = sqlx::query_scalar("SELECT slug FROM tags WHERE id = ?")
+            .bind(id)
+            .fetch_optional(&mut *tx)
+            .await
+            .context("Failed to get tag")?
+            .ok_or_else(|| anyhow::anyhow!("Tag with ID {} not found", id))?;
+        if old_slug != slug {
+            // The new slug may be a former one, of this tag or another
+            sqlx::query("DELETE FROM tag_slugs WHERE slug = ? OR slug = ?")
+                .bind(slug)
+                .bind(&old_slug)
+                .execute(&mut *tx)
+                .await
+                .context("Failed to update tag slug history")?;
+            sqlx::query("INSERT INTO tag_slugs (slug, tag_id, created_at) VALUES (?, ?, ?)")
+                .bind(&old_slug)
+                .bind(id)
+                .bind(Utc::now())
+                .execute(&mut *tx)
+                .await
+                .context("Failed to record tag slug")?;
+        }
+        sqlx::query("UPDATE tags SET name = ?, slug = ? WHERE id = ?")
+            .bind(name)
+            .bind(slug)
+            .bind(id)
+            .execute(&mut *tx)
+            .await
+            .context("Failed to rename tag")?;
+        tx.commit().await.context("Failed to commit transaction")?;
+        Ok(())
+    }
+}
+
+impl_dual_fn! {
+    async fn merge_tags(pool, target_id: i64, source_ids: &[i64]) -> Result<()> {
+        let placeholders = vec!["?"; source_ids.len()].join(", ");
+        let mut tx = pool.begin().await.context("Failed to start transaction")?;
+
+        // Tag the source tags' articles with the target, once each
+        let sql = format!(
+            "INSERT INTO article_tags (article_id, tag_id) \
+             SELECT DISTINCT at.article_id, ? FROM article_tags at \
+             WHERE at.tag_id IN ({}) AND NOT EXISTS \
+             (SELECT 1 FROM article_tags t WHERE t.article_id = at.article_id AND t.tag_id = ?)",
+            placeholders
+        );
+        let mut query = sqlx::query(&sql).bind(target_id);
+        for id in source_ids {
+            query = query.bind(id);
+        }
+        query
+            .bind(target_id)
+            .execute(&mut *tx)
+            .await
+            .context("Failed to move articles to merged tag")?;
+
+        let sql = format!("DELETE FROM article_tags WHERE tag_id IN ({})", placeholders);
+        let mut query = sqlx::query(&sql);
+        for id in source_ids {
+            query = query.bind(id);
+        }
+        query
+            .execute(&mut *tx)
+            .await
+            .context("Failed to remove merged tags from articles")?;
+
+        // Old URLs of the source tags lead to the target
+        let sql = format!(
+            "DELETE FROM tag_slugs WHERE slug IN (SELECT slug FROM tags WHERE id IN ({}))",
+            placeholders
+        );
+        let mut query = sqlx::query(&sql);
+        for id in source_ids {
+            query = query.bind(id);
+        }
+        query
+            .execute(&mut *tx)
+            .await
+            .context("Failed to update tag slug history")?;
+
+        let sql = format!("UPDATE tag_slugs SET tag_id = ? WHERE tag_id IN ({})", placeholders);
+        let mut query = sqlx::query(&sql).bind(target_id);
+        for id in source_ids {
+            query = query.bind(id);
+        }
+        query
+            .execute(&mut *tx)
+            .await
+            .context("Failed to move tag slug history")?;
+
+        let sql = format!(
+            "INSERT INTO tag_slugs (slug, tag_id, created_at) SELECT slug, ?, ? FROM tags WHERE id IN ({})",
+            placeholders
+        );
+        let mut query = sqlx::query(&sql).bind(target_id).bind(Utc::now());
+        for id in source_ids {
+            query = query.bind(id);
+        }
+        query
+            .execute(&mut *tx)
+            .await
+            .context("Failed to record merged tag slugs")?;
+
+        let sql = format!("DELETE FROM tags WHERE id IN ({})", placeholders);
+        let mut query = sqlx::query(&sql);
+        for id in source_ids {
+            query = query.bind(id);
+        }
+        query
+            .execute(&mut *tx)
+            .await
+            .context("Failed to delete merged tags")?;
+
+        tx.commit().await.context("Failed to commit transaction")?;
+        Ok(())
+    }
+}
+
+impl_dual_fn! {
+    async fn get_tag_slug_owner(pool, slug: &str) -> Result<Option<i64>> {
+        let id = sqlx::query_scalar("SELECT tag_id FROM tag_slugs WHERE slug = ?")
+            .bind(slug)
+            .fetch_optional(pool)
+            .await
+            .context("Failed to look up tag slug history")?;
+        Ok(id)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -1141,4 +1305,62 @@ mod tests {
         let count: i64 = row.get("count");
         assert_eq!(count, 0);
     }
+
+    #[tokio::test]
+    async fn test_rename_keeps_old_slug() {
+        let (_pool, repo) = setup_test_repo().await;
+        let tag = repo
+            .create(&create_test_tag("js", "JS"))
+            .await
+            .expect("Failed to create tag");
+
+        repo.rename(tag.id, "JavaScript", "javascript")
+            .await
+            .expect("Failed to rename tag");
+
+        let renamed = repo.get_by_id(tag.id).await.unwrap().unwrap();
+        assert_eq!(renamed.name, "JavaScript");
+        assert_eq!(renamed.slug, "javascript");
+        let previous = repo.get_by_previous_slug("js").await.unwrap();
+        assert_eq!(previous.map(|t| t.id), Some(tag.id));
+
+        // Renaming back reclaims the old slug
+        repo.rename(tag.id, "JS", "js").await.unwrap();
+        assert!(repo.get_by_previous_slug("js").await.unwrap().is_none());
+        let previous = repo.get_by_previous_slug("javascript").await.unwrap();
+        assert_eq!(previous.map(|t| t.id), Some(tag.id));
+    }
+
+    #[tokio::test]
+    async fn test_merge_moves_articles_and_slugs() {
+        let (pool, repo) = setup_test_repo().await;
+        let sqlite_pool = pool.as_sqlite().unwrap();
+        let user_id = create_test_user(sqlite_pool).await;
+        let both = create_test_article(sqlite_pool, user_id, "both").await;
+        let source_only = create_test_article(sqlite_pool, user_id, "source-only").await;
+
+        let target = repo.create(&create_test_tag("rust", "Rust")).await.unwrap();
+        let source = repo
+            .create(&create_test_tag("rustlang", "Rustlang"))
+            .await
+            .unwrap();
+        repo.rename(source.id, "Rust lang", "rust-lang").await.unwrap();
+        repo.add_to_article(target.id, both).await.unwrap();
+        repo.add_to_article(source.id, both).await.unwrap();
+        repo.add_to_article(source.id, source_only).await.unwrap();
+
+        repo.merge(target.id, &[source.id])
+            .await
+            .expect("Failed to merge tags");
+
+        assert!(repo.get_by_id(source.id).await.unwrap().is_none());
+        for article_id in [both, source_only] {
+            let tags = repo.get_by_article_id(article_id).await.unwrap();
+            assert_eq!(tags.iter().map(|t| t.id).collect::<Vec<_>>(), vec![target.id]);
+        }
+        for slug in ["rustlang", "rust-lang"] {
+            let previous = repo.get_by_previous_slug(slug).await.unwrap();
+            assert_eq!(previous.map(|t| t.id), Some(target.id));
+        }
+    }
 }
@@ -0,0 +1,220 @@
+//! Content translation repository.
+
+use crate::db::DynDatabasePool;
+use crate::models::{ContentKind, ContentTranslation};
+use anyhow::{Context, Result};
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use std::sync::Arc;
+
+const COLUMNS: &str = "content_id, lang, translation_group, created_at";
+
+#[async_trait]
+pub trait TranslationRepository: Send + Sync {
+    async fn get(&self, kind: ContentKind, content_id: i64) -> Result<Option<ContentTranslation>>;
+    /// Variants in a group, by language
+    async fn list_group(&self, kind: ContentKind, group: &str) -> Result<Vec<ContentTranslation>>;
+    /// Every article or page that has a language
+    async fn list(&self, kind: ContentKind) -> Result<Vec<ContentTranslation>>;
+    /// Set the language and group, replacing any earlier ones
+    async fn set(&self, translation: &ContentTranslation) -> Result<ContentTranslation>;
+    async fn delete(&self, kind: ContentKind, content_id: i64) -> Result<bool>;
+}
+
+pub struct SqlxTranslationRepository {
+    pool: DynDatabasePool,
+}
+
+impl SqlxTranslationRepository {
+    pub fn new(pool: DynDatabasePool) -> Self {
+        Self { pool }
+    }
+
+    pub fn boxed(pool: DynDatabasePool) -> Arc<dyn TranslationRepository> {
+        Arc::new(Self::new(pool))
+    }
+}
+
+#[async_trait]
+impl TranslationRepository for SqlxTranslationRepository {
+    async fn get(&self, kind: ContentKind, content_id: i64) -> Result<Option<ContentTranslation>> {
+        dispatch!(self, get_translation, kind, content_id)
+    }
+
+    async fn list_group(&self, kind: ContentKind, group: &str) -> Result<Vec<ContentTranslation>> {
+        dispatch!(self, list_group, kind, group)
+    }
+
+    async fn list(&self, kind: ContentKind) -> Result<Vec<ContentTranslation>> {
+        dispatch!(self, list_translations, kind)
+    }
+
+    async fn set(&self, translation: &ContentTranslation) -> Result<ContentTranslation> {
+        dispatch!(self, set_translation, translation)
+    }
+
+    async fn delete(&self, kind: ContentKind, content_id: i64) -> Result<bool> {
+        dispatch!(self, delete_translation, kind, content_id)
+    }
+}
+
+impl_dual_fn! {
+    async fn get_translation(pool, kind: ContentKind, content_id: i64) -> Result<Option<ContentTranslation>> {
+        let row = sqlx::query(&format!(
+            "SELECT {} FROM content_translations WHERE content_type = ? AND content_id = ?",
+            COLUMNS
+        ))
+        .bind(kind.as_str())
+        .bind(content_id)
+        .fetch_optional(pool)
+        .await
+        .context("Failed to get translation")?;
+        Ok(row.map(|r| row_to_translation(kind, &r)))
+    }
+}
+
+impl_dual_fn! {
+    async fn list_group(pool, kind: ContentKind, group: &str) -> Result<Vec<ContentTranslation>> {
+        let rows = sqlx::query(&format!(
+            "SELECT {} FROM content_translations WHERE content_type = ? AND translation_group = ? ORDER BY lang",
+            COLUMNS
+        ))
+        .bind(kind.as_str())
+        .bind(group)
+        .fetch_all(pool)
+        .await
+        .context("Failed to list translation group")?;
+        Ok(rows.iter().map(|r| row_to_translation(kind, r)).collect())
+    }
+}
+
+impl_dual_fn! {
+    async fn list_translations(pool, kind: ContentKind) -> Result<Vec<ContentTranslation>> {
+        let rows = sqlx::query(&format!(
+            "SELECT {} FROM content_translations WHERE content_type = ? ORDER BY content_id",
+            COLUMNS
+        ))
+        .bind(kind.as_str())
+        .fetch_all(pool)
+        .await
+        .context("Failed to list translations")?;
+        Ok(rows.iter().map(|r| row_to_translation(kind, r)).collect())
+    }
+}
+
+impl_dual_fn! {
+    async fn set_translation(pool, translation: &ContentTranslation) -> Result<ContentTranslation> {
+        let now = Utc::now();
+        let mut tx = pool.begin().await.context("Failed to start transaction")?;
+        sqlx::query("DELETE FROM content_translations WHERE content_type = ? AND content_id = ?")
+            .bind(translation.content_type.as_str())
+            .bind(translation.content_id)
+            .execute(&mut *tx)
+            .await
+            .context("Failed to replace translation")?;
+        sqlx::query(
+            "INSERT INTO content_translations (content_type, content_id, lang, translation_group, created_at) \
+             VALUES (?, ?, ?, ?, ?)",
+        )
+        .bind(translation.content_type.as_str())
+        .bind(translation.content_id)
+        .bind(&translation.lang)
+        .bind(&translation.translation_group)
+        .bind(now)
+        .execute(&mut *tx)
+        .await
+        .context("Failed to save translation")?;
+        tx.commit().await.context("Failed to commit transaction")?;
+        Ok(ContentTranslation {
+            created_at: now,
+            ..translation.clone()
+        })
+    }
+}
+
+impl_dual_fn! {
+    async fn delete_translation(pool, kind: ContentKind, content_id: i64) -> Result<bool> {
+        let result =
+            sqlx::query("DELETE FROM content_translations WHERE content_type = ? AND content_id = ?")
+                .bind(kind.as_str())
+                .bind(content_id)
+                .execute(pool)
+                .await
+                .context("Failed to delete translation")?;
+        Ok(result.rows_affected() > 0)
+    }
+}
+
+fn row_to_translation<'r, R>(kind: ContentKind, row: &'r R) -> ContentTranslation
+where
+    R: sqlx::Row,
+    &'r str: sqlx::ColumnIndex<R>,
+    String: sqlx::Decode<'r, R::Database> + sqlx::Type<R::Database>,
+    i64: sqlx::Decode<'r, R::Database> + sqlx::Type<R::Database>,
+    DateTime<Utc>: sqlx::Decode<'r, R::Database> + sqlx::Type<R::Database>,
+{
+    ContentTranslation {
+        content_type: kind,
+        content_id: row.get("content_id"),
+        lang: row.get("lang"),
+        translation_group: row.get("translation_group"),
+        created_at: row.get("created_at"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::{create_test_pool, migrations};
+
+    fn translation(kind: ContentKind, id: i64, lang: &str, group: &str) -> ContentTranslation {
+        ContentTranslation {
+            content_type: kind,
+            content_id: id,
+            lang: lang.to_string(),
+            translation_group: group.to_string(),
+            created_at: Utc::now(),
+        }
+    }
+
+    #[tokio::test]
+    async fn groups_are_scoped_by_content_kind() {
+        let pool = create_test_pool().await.unwrap();
+        migrations::run_migrations(&pool).await.unwrap();
+        let repo = SqlxTranslationRepository::new(pool);
+
+        repo.set(&translation(ContentKind::Article, 1, "en", "g1"))
+            .await
+            .unwrap();
+        repo.set(&translation(ContentKind::Article, 2, "de", "g1"))
+            .await
+            .unwrap();
+        repo.set(&translation(ContentKind::Page, 1, "fr", "g1"))
+            .await
+            .unwrap();
+
+        let group = repo.list_group(ContentKind::Article, "g1").await.unwrap();
+        let langs: Vec<&str> = group.iter().map(|t| t.lang.as_str()).collect();
+        assert_eq!(langs, ["de", "en"]);
+
+        // A second language in the same group is rejected by the database
+        assert!(repo
+            .set(&translation(ContentKind::Article, 3, "en", "g1"))
+            .await
+            .is_err());
+
+        // Setting again replaces the row
+        repo.set(&translation(ContentKind::Article, 2, "fr", "g2"))
+            .await
+            .unwrap();
+        let moved = repo.get(ContentKind::Article, 2).await.unwrap().unwrap();
+        assert_eq!(
+            (moved.lang.as_str(), moved.translation_group.as_str()),
+            ("fr", "g2")
+        );
+
+        assert!(repo.delete(ContentKind::Page, 1).await.unwrap());
+        assert!(repo.get(ContentKind::Page, 1).await.unwrap().is_none());
+        assert_eq!(repo.list(ContentKind::Article).await.unwrap().len(), 2);
+    }
+}
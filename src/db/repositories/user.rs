@@ -44,6 +44,15 @@ pub trait UserRepository: Send + Sync {
 
     /// List all users with pagination
     async fn list(&self, page: i64, per_page: i64) -> Result<(Vec<User>, i64)>;
+
+    /// Delete all sessions of a user, optionally changing their role and/or
+    /// requiring a password change, in a single transaction
+    async fn revoke_access(
+        &self,
+        id: i64,
+        role: Option<UserRole>,
+        require_password_reset: bool,
+    ) -> Result<()>;
 }
 
 /// SQLx-based user repository implementation
@@ -98,6 +107,15 @@ impl UserRepository for SqlxUserRepository {
     async fn list(&self, page: i64, per_page: i64) -> Result<(Vec<User>, i64)> {
         dispatch!(self, list_users, page, per_page)
     }
+
+    async fn revoke_access(
+        &self,
+        id: i64,
+        role: Option<UserRole>,
+        require_password_reset: bool,
+    ) -> Result<()> {
+        dispatch!(self, revoke_user_access, id, role, require_password_reset)
+    }
 }
 
 impl_dual_fn! {
@@ -112,6 +130,41 @@ impl_dual_fn! {
     }
 }
 
+impl_dual_fn! {
+    async fn revoke_user_access(pool, id: i64, role: Option<UserRole>, require_password_reset: bool) -> Result<()> {
+        let mut tx = pool.begin().await.context("Failed to start transaction")?;
+        let now = Utc::now();
+
+        if let Some(role) = role {
+            sqlx::query("UPDATE users SET role = ?, updated_at = ? WHERE id = ?")
+                .bind(role.to_string())
+                .bind(now)
+                .bind(id)
+                .execute(&mut *tx)
+                .await
+                .context("Failed to update user role")?;
+        }
+
+        if require_password_reset {
+            sqlx::query("UPDATE users SET password_reset_required = 1, updated_at = ? WHERE id = ?")
+                .bind(now)
+                .bind(id)
+                .execute(&mut *tx)
+                .await
+                .context("Failed to flag password reset")?;
+        }
+
+        sqlx::query("DELETE FROM sessions WHERE user_id = ?")
+            .bind(id)
+            .execute(&mut *tx)
+            .await
+            .context("Failed to revoke sessions")?;
+
+        tx.commit().await.context("Failed to commit transaction")?;
+        Ok(())
+    }
+}
+
 impl_dual_fn! {
     async fn count_users(pool) -> Result<i64> {
         let row = sqlx::query("SELECT COUNT(*) as count FROM users")
@@ -139,6 +192,7 @@ impl_row_mapper! {
             .unwrap_or(UserStatus::Active);
 
         let totp_enabled_raw: Option<i32> = row.try_get("totp_enabled").ok();
+        let password_reset_raw: Option<i32> = row.try_get("password_reset_required").ok();
 
         Ok(User {
             id: row.get("id"),
@@ -151,6 +205,7 @@ impl_row_mapper! {
             avatar: row.try_get("avatar").ok(),
             totp_secret: row.try_get("totp_secret").ok().flatten(),
             totp_enabled: totp_enabled_raw.unwrap_or(0) != 0,
+            password_reset_required: password_reset_raw.unwrap_or(0) != 0,
             created_at: row.get("created_at"),
             updated_at: row.get("updated_at"),
         })
@@ -164,7 +219,7 @@ impl_row_mapper! {
 async fn get_user_by_id_sqlite(pool: &SqlitePool, id: i64) -> Result<Option<User>> {
     let row = sqlx::query(
         r#"
-        SELECT id, username, email, password_hash, role, status, display_name, avatar, totp_secret, totp_enabled, created_at, updated_at
+        SELECT id, username, email, password_hash, role, status, display_name, avatar, totp_secret, totp_enabled, password_reset_required, created_at, updated_at
         FROM users
         WHERE id = ?
         "#,
@@ -183,7 +238,7 @@ async fn get_user_by_id_sqlite(pool: &SqlitePool, id: i64) -> Result<Option<User
 async fn get_user_by_username_sqlite(pool: &SqlitePool, username: &str) -> Result<Option<User>> {
     let row = sqlx::query(
         r#"
-        SELECT id, username, email, password_hash, role, status, display_name, avatar, totp_secret, totp_enabled, created_at, updated_at
+        SELECT id, username, email, password_hash, role, status, display_name, avatar, totp_secret, totp_enabled, password_reset_required, created_at, updated_at
         FROM users
         WHERE username = ?
         "#,
@@ -202,7 +257,7 @@ async fn get_user_by_username_sqlite(pool: &SqlitePool, username: &str) -> Resul
 async fn get_user_by_email_sqlite(pool: &SqlitePool, email: &str) -> Result<Option<User>> {
     let row = sqlx::query(
         r#"
-        SELECT id, username, email, password_hash, role, status, display_name, avatar, totp_secret, totp_enabled, created_at, updated_at
+        SELECT id, username, email, password_hash, role, status, display_name, avatar, totp_secret, totp_enabled, password_reset_required, created_at, updated_at
         FROM users
         WHERE email = ?
         "#,
@@ -255,6 +310,7 @@ async fn create_user_sqlite(pool: &SqlitePool, user: &User) -> Result<User> {
         avatar: user.avatar.clone(),
         totp_secret: None,
         totp_enabled: false,
+        password_reset_required: false,
         created_at: now,
         updated_at: now,
     })
@@ -268,7 +324,7 @@ async fn update_user_sqlite(pool: &SqlitePool, user: &User) -> Result<User> {
     sqlx::query(
         r#"
         UPDATE users
-        SET username = ?, email = ?, password_hash = ?, role = ?, status = ?, display_name = ?, avatar = ?, totp_secret = ?, totp_enabled = ?, updated_at = ?
+        SET username = ?, email = ?, password_hash = ?, role = ?, status = ?, display_name = ?, avatar = ?, totp_secret = ?, totp_enabled = ?, password_reset_required = ?, updated_at = ?
         WHERE id = ?
         "#,
     )
@@ -281,6 +337,7 @@ async fn update_user_sqlite(pool: &SqlitePool, user: &User) -> Result<User> {
     .bind(&user.avatar)
     .bind(&user.totp_secret)
     .bind(if user.totp_enabled { 1 } else { 0 })
+    .bind(if user.password_reset_required { 1 } else { 0 })
     .bind(now)
     .bind(user.id)
     .execute(pool)
@@ -301,7 +358,7 @@ async fn list_users_sqlite(
 
     let rows = sqlx::query(
         r#"
-        SELECT id, username, email, password_hash, role, status, display_name, avatar, totp_secret, totp_enabled, created_at, updated_at
+        SELECT id, username, email, password_hash, role, status, display_name, avatar, totp_secret, totp_enabled, password_reset_required, created_at, updated_at
         FROM users
         ORDER BY id ASC
         LIMIT ? OFFSET ?
@@ -330,7 +387,7 @@ async fn list_users_sqlite(
 async fn get_user_by_id_mysql(pool: &MySqlPool, id: i64) -> Result<Option<User>> {
     let row = sqlx::query(
         r#"
-        SELECT id, username, email, password_hash, role, status, display_name, avatar, totp_secret, totp_enabled, created_at, updated_at
+        SELECT id, username, email, password_hash, role, status, display_name, avatar, totp_secret, totp_enabled, password_reset_required, created_at, updated_at
         FROM users
         WHERE id = ?
         "#,
@@ -349,7 +406,7 @@ async fn get_user_by_id_mysql(pool: &MySqlPool, id: i64) -> Result<Option<User>>
 async fn get_user_by_username_mysql(pool: &MySqlPool, username: &str) -> Result<Option<User>> {
     let row = sqlx::query(
         r#"
-        SELECT id, username, email, password_hash, role, status, display_name, avatar, totp_secret, totp_enabled, created_at, updated_at
+        SELECT id, username, email, password_hash, role, status, display_name, avatar, totp_secret, totp_enabled, password_reset_required, created_at, updated_at
         FROM users
         WHERE username = ?
         "#,
@@ -368,7 +425,7 @@ async fn get_user_by_username_mysql(pool: &MySqlPool, username: &str) -> Result<
 async fn get_user_by_email_mysql(pool: &MySqlPool, email: &str) -> Result<Option<User>> {
     let row = sqlx::query(
         r#"
-        SELECT id, username, email, password_hash, role, status, display_name, avatar, totp_secret, totp_enabled, created_at, updated_at
+        SELECT id, username, email, password_hash, role, status, display_name, avatar, totp_secret, totp_enabled, password_reset_required, created_at, updated_at
         FROM users
         WHERE email = ?
         "#,
@@ -421,6 +478,7 @@ async fn create_user_mysql(pool: &MySqlPool, user: &User) -> Result<User> {
         avatar: user.avatar.clone(),
         totp_secret: None,
         totp_enabled: false,
+        password_reset_required: false,
         created_at: now,
         updated_at: now,
     })
@@ -434,7 +492,7 @@ async fn update_user_mysql(pool: &MySqlPool, user: &User) -> Result<User> {
     sqlx::query(
         r#"
         UPDATE users
-        SET username = ?, email = ?, password_hash = ?, role = ?, status = ?, display_name = ?, avatar = ?, totp_secret = ?, totp_enabled = ?, updated_at = ?
+        SET username = ?, email = ?, password_hash = ?, role = ?, status = ?, display_name = ?, avatar = ?, totp_secret = ?, totp_enabled = ?, password_reset_required = ?, updated_at = ?
         WHERE id = ?
         "#,
     )
@@ -447,6 +505,7 @@ async fn update_user_mysql(pool: &MySqlPool, user: &User) -> Result<User> {
     .bind(&user.avatar)
     .bind(&user.totp_secret)
     .bind(if user.totp_enabled { 1 } else { 0 })
+    .bind(if user.password_reset_required { 1 } else { 0 })
     .bind(now)
     .bind(user.id)
     .execute(pool)
@@ -463,7 +522,7 @@ async fn list_users_mysql(pool: &MySqlPool, page: i64, per_page: i64) -> Result<
 
     let rows = sqlx::query(
         r#"
-        SELECT id, username, email, password_hash, role, status, display_name, avatar, totp_secret, totp_enabled, created_at, updated_at
+        SELECT id, username, email, password_hash, role, status, display_name, avatar, totp_secret, totp_enabled, password_reset_required, created_at, updated_at
         FROM users
         ORDER BY id ASC
         LIMIT ? OFFSET ?
@@ -0,0 +1,95 @@
+//! User preferences repository
+//!
+//! Stores each user's admin UI preferences as a single JSON document.
+
+use anyhow::{Context, Result};
+use async_trait::async_trait;
+use sqlx::{MySqlPool, SqlitePool};
+use std::sync::Arc;
+
+use crate::db::DynDatabasePool;
+
+/// Repository trait for user preferences
+#[async_trait]
+pub trait UserPreferencesRepository: Send + Sync {
+    /// Get the raw preferences JSON of a user
+    async fn get(&self, user_id: i64) -> Result<Option<String>>;
+
+    /// Replace the preferences JSON of a user
+    async fn set(&self, user_id: i64, data: &str) -> Result<()>;
+}
+
+/// SQLx-based user preferences repository
+pub struct SqlxUserPreferencesRepository {
+    pool: DynDatabasePool,
+}
+
+impl SqlxUserPreferencesRepository {
+    pub fn new(pool: DynDatabasePool) -> Self {
+        Self { pool }
+    }
+
+    /// Create a boxed repository for use with dependency injection
+    pub fn boxed(pool: DynDatabasePool) -> Arc<dyn UserPreferencesRepository> {
+        Arc::new(Self::new(pool))
+    }
+}
+
+#[async_trait]
+impl UserPreferencesRepository for SqlxUserPreferencesRepository {
+    async fn get(&self, user_id: i64) -> Result<Option<String>> {
+        dispatch!(self, get, user_id)
+    }
+
+    async fn set(&self, user_id: i64, data: &str) -> Result<()> {
+        dispatch!(self, set, user_id, data)
+    }
+}
+
+// ============================================================================
+// Shared implementations (identical SQL)
+// ============================================================================
+
+impl_dual_fn! {
+    async fn get(pool, user_id: i64) -> Result<Option<String>> {
+        sqlx::query_scalar("SELECT data FROM user_preferences WHERE user_id = ?")
+            .bind(user_id)
+            .fetch_optional(pool)
+            .await
+            .context("Failed to get user preferences")
+    }
+}
+
+// ============================================================================
+// Dialect-specific upserts
+// ============================================================================
+
+async fn set_sqlite(pool: &SqlitePool, user_id: i64, data: &str) -> Result<()> {
+    sqlx::query(
+        r#"INSERT INTO user_preferences (user_id, data, updated_at)
+           VALUES (?, ?, CURRENT_TIMESTAMP)
+           ON CONFLICT(user_id) DO UPDATE SET
+               data = excluded.data,
+               updated_at = CURRENT_TIMESTAMP"#,
+    )
+    .bind(user_id)
+    .bind(data)
+    .execute(pool)
+    .await
+    .context("Failed to save user preferences")?;
+    Ok(())
+}
+
+async fn set_mysql(pool: &MySqlPool, user_id: i64, data: &str) -> Result<()> {
+    sqlx::query(
+        r#"INSERT INTO user_preferences (user_id, data)
+           VALUES (?, ?)
+           ON DUPLICATE KEY UPDATE data = VALUES(data)"#,
+    )
+    .bind(user_id)
+    .bind(data)
+    .execute(pool)
+    .await
+    .context("Failed to save user preferences")?;
+    Ok(())
+}
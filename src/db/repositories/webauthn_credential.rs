@@ -0,0 +1,278 @@
+//! Passkey (WebAuthn) credential repository
+
+use anyhow::{Context, Result};
+use async_trait::async_trait;
+use chrono::Utc;
+use sqlx::Row;
+use std::sync::Arc;
+
+use crate::db::DynDatabasePool;
+use crate::models::{CreateWebauthnCredentialInput, WebauthnCredential};
+
+/// Repository trait for passkey credentials
+#[async_trait]
+pub trait WebauthnCredentialRepository: Send + Sync {
+    /// Store a newly registered credential
+    async fn create(&self, input: &CreateWebauthnCredentialInput) -> Result<WebauthnCredential>;
+
+    /// Look up a credential by its credential ID (base64url)
+    async fn get_by_credential_id(&self, credential_id: &str)
+        -> Result<Option<WebauthnCredential>>;
+
+    /// List the credentials of a user, oldest first
+    async fn list_by_user(&self, user_id: i64) -> Result<Vec<WebauthnCredential>>;
+
+    /// Count the credentials of a user
+    async fn count_by_user(&self, user_id: i64) -> Result<i64>;
+
+    /// Record a successful authentication along with the updated passkey state
+    async fn update_usage(&self, id: i64, public_key: &str, sign_count: i64) -> Result<()>;
+
+    /// Rename a credential owned by `user_id`. Returns whether it existed.
+    async fn rename(&self, user_id: i64, id: i64, name: &str) -> Result<bool>;
+
+    /// Delete a credential owned by `user_id`. Returns whether it existed.
+    async fn delete(&self, user_id: i64, id: i64) -> Result<bool>;
+}
+
+/// SQLx-based passkey credential repository
+pub struct SqlxWebauthnCredentialRepository {
+    pool: DynDatabasePool,
+}
+
+impl SqlxWebauthnCredentialRepository {
+    pub fn new(pool: DynDatabasePool) -> Self {
+        Self { pool }
+    }
+
+    /// Create a boxed repository for use with dependency injection
+    pub fn boxed(pool: DynDatabasePool) -> Arc<dyn WebauthnCredentialRepository> {
+        Arc::new(Self::new(pool))
+    }
+}
+
+#[async_trait]
+impl WebauthnCredentialRepository for SqlxWebauthnCredentialRepository {
+    async fn create(&self, input: &CreateWebauthnCredentialInput) -> Result<WebauthnCredential> {
+        dispatch!(self, create_credential, input)
+    }
+
+    async fn get_by_credential_id(
+        &self,
+        credential_id: &str,
+    ) -> Result<Option<WebauthnCredential>> {
+        dispatch!(self, get_credential_by_credential_id, credential_id)
+    }
+
+    async fn list_by_user(&self, user_id: i64) -> Result<Vec<WebauthnCredential>> {
+        dispatch!(self, list_credentials_by_user, user_id)
+    }
+
+    async fn count_by_user(&self, user_id: i64) -> Result<i64> {
+        dispatch!(self, count_credentials_by_user, user_id)
+    }
+
+    async fn update_usage(&self, id: i64, public_key: &str, sign_count: i64) -> Result<()> {
+        dispatch!(self, update_credential_usage, id, public_key, sign_count)
+    }
+
+    async fn rename(&self, user_id: i64, id: i64, name: &str) -> Result<bool> {
+        dispatch!(self, rename_credential, user_id, id, name)
+    }
+
+    async fn delete(&self, user_id: i64, id: i64) -> Result<bool> {
+        dispatch!(self, delete_credential, user_id, id)
+    }
+}
+
+// ============================================================================
+// Shared implementations (identical SQL)
+// ============================================================================
+
+const SELECT_COLUMNS: &str =
+    "SELECT id, user_id, credential_id, public_key, sign_count, name, created_at, last_used_at FROM webauthn_credentials";
+
+impl_dual_fn! {
+    async fn create_credential(pool, input: &CreateWebauthnCredentialInput) -> Result<WebauthnCredential> {
+        sqlx::query(
+            "INSERT INTO webauthn_credentials (user_id, credential_id, public_key, sign_count, name, created_at) VALUES (?, ?, ?, ?, ?, ?)",
+        )
+        .bind(input.user_id)
+        .bind(&input.credential_id)
+        .bind(&input.public_key)
+        .bind(input.sign_count)
+        .bind(&input.name)
+        .bind(Utc::now())
+        .execute(pool)
+        .await
+        .context("Failed to store passkey")?;
+
+        let row = sqlx::query(&format!("{} WHERE credential_id = ?", SELECT_COLUMNS))
+            .bind(&input.credential_id)
+            .fetch_one(pool)
+            .await
+            .context("Failed to load stored passkey")?;
+        row_to_credential(&row)
+    }
+}
+
+impl_dual_fn! {
+    async fn get_credential_by_credential_id(pool, credential_id: &str) -> Result<Option<WebauthnCredential>> {
+        let row = sqlx::query(&format!("{} WHERE credential_id = ?", SELECT_COLUMNS))
+            .bind(credential_id)
+            .fetch_optional(pool)
+            .await
+            .context("Failed to get passkey")?;
+        row.as_ref().map(row_to_credential).transpose()
+    }
+}
+
+impl_dual_fn! {
+    async fn list_credentials_by_user(pool, user_id: i64) -> Result<Vec<WebauthnCredential>> {
+        let rows = sqlx::query(&format!("{} WHERE user_id = ? ORDER BY id ASC", SELECT_COLUMNS))
+            .bind(user_id)
+            .fetch_all(pool)
+            .await
+            .context("Failed to list passkeys")?;
+        rows.iter().map(row_to_credential).collect()
+    }
+}
+
+impl_dual_fn! {
+    async fn count_credentials_by_user(pool, user_id: i64) -> Result<i64> {
+        let row = sqlx::query("SELECT COUNT(*) as count FROM webauthn_credentials WHERE user_id = ?")
+            .bind(user_id)
+            .fetch_one(pool)
+            .await
+            .context("Failed to count passkeys")?;
+        Ok(row.get("count"))
+    }
+}
+
+impl_dual_fn! {
+    async fn update_credential_usage(pool, id: i64, public_key: &str, sign_count: i64) -> Result<()> {
+        sqlx::query(
+            "UPDATE webauthn_credentials SET public_key = ?, sign_count = ?, last_used_at = ? WHERE id = ?",
+        )
+        .bind(public_key)
+        .bind(sign_count)
+        .bind(Utc::now())
+        .bind(id)
+        .execute(pool)
+        .await
+        .context("Failed to update passkey usage")?;
+        Ok(())
+    }
+}
+
+impl_dual_fn! {
+    async fn rename_credential(pool, user_id: i64, id: i64, name: &str) -> Result<bool> {
+        let result = sqlx::query("UPDATE webauthn_credentials SET name = ? WHERE id = ? AND user_id = ?")
+            .bind(name)
+            .bind(id)
+            .bind(user_id)
+            .execute(pool)
+            .await
+            .context("Failed to rename passkey")?;
+        if result.rows_affected() > 0 {
+            return Ok(true);
+        }
+        // MySQL reports 0 affected rows when the name is unchanged
+        let exists = sqlx::query("SELECT id FROM webauthn_credentials WHERE id = ? AND user_id = ?")
+            .bind(id)
+            .bind(user_id)
+            .fetch_optional(pool)
+            .await
+            .context("Failed to check passkey")?;
+        Ok(exists.is_some())
+    }
+}
+
+impl_dual_fn! {
+    async fn delete_credential(pool, user_id: i64, id: i64) -> Result<bool> {
+        let result = sqlx::query("DELETE FROM webauthn_credentials WHERE id = ? AND user_id = ?")
+            .bind(id)
+            .bind(user_id)
+            .execute(pool)
+            .await
+            .context("Failed to delete passkey")?;
+        Ok(result.rows_affected() > 0)
+    }
+}
+
+/// Map a row to a credential (same column types on SQLite and MySQL)
+fn row_to_credential<'r, R>(row: &'r R) -> Result<WebauthnCredential>
+where
+    R: sqlx::Row,
+    &'r str: sqlx::ColumnIndex<R>,
+    String: sqlx::Decode<'r, R::Database> + sqlx::Type<R::Database>,
+    i64: sqlx::Decode<'r, R::Database> + sqlx::Type<R::Database>,
+    chrono::DateTime<Utc>: sqlx::Decode<'r, R::Database> + sqlx::Type<R::Database>,
+{
+    Ok(WebauthnCredential {
+        id: row.get("id"),
+        user_id: row.get("user_id"),
+        credential_id: row.get("credential_id"),
+        public_key: row.get("public_key"),
+        sign_count: row.get("sign_count"),
+        name: row.get("name"),
+        created_at: row.get("created_at"),
+        last_used_at: row.get("last_used_at"),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::{create_test_pool, migrations};
+
+    #[tokio::test]
+    async fn credentials_are_scoped_to_their_owner() {
+        let pool = create_test_pool()
+            .await
+            .expect("Failed to create test pool");
+        migrations::run_migrations(&pool)
+            .await
+            .expect("Failed to run migrations");
+        for name in ["alice", "bob"] {
+            sqlx::query(
+                "INSERT INTO users (username, email, password_hash, role) VALUES (?, ?, 'x', 'admin')",
+            )
+            .bind(name)
+            .bind(format!("{}@example.com", name))
+            .execute(pool.as_sqlite().unwrap())
+            .await
+            .unwrap();
+        }
+        let repo = SqlxWebauthnCredentialRepository::new(pool);
+
+        let created = repo
+            .create(&CreateWebauthnCredentialInput {
+                user_id: 1,
+                credential_id: "cred-1".to_string(),
+                public_key: "key".to_string(),
+                sign_count: 3,
+                name: "Laptop".to_string(),
+            })
+            .await
+            .unwrap();
+        assert_eq!(created.user_id, 1);
+        assert!(created.last_used_at.is_none());
+        assert_eq!(repo.count_by_user(1).await.unwrap(), 1);
+
+        // Other users cannot rename or delete the credential
+        assert!(!repo.rename(2, created.id, "Mine").await.unwrap());
+        assert!(!repo.delete(2, created.id).await.unwrap());
+
+        repo.update_usage(created.id, "key-2", 7).await.unwrap();
+        let stored = repo.get_by_credential_id("cred-1").await.unwrap().unwrap();
+        assert_eq!(stored.public_key, "key-2");
+        assert_eq!(stored.sign_count, 7);
+        assert!(stored.last_used_at.is_some());
+
+        assert!(repo.rename(1, created.id, "Phone").await.unwrap());
+        assert_eq!(repo.list_by_user(1).await.unwrap()[0].name, "Phone");
+        assert!(repo.delete(1, created.id).await.unwrap());
+        assert!(repo.list_by_user(1).await.unwrap().is_empty());
+    }
+}
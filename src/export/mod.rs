@@ -0,0 +1,782 @@
+//! Static site export
+//!
+//! `noteva export --out ./public` renders the published site to plain files
+//! that any static host or CDN can serve:
+//!
+//! ```text
+//! index.html, page/{n}/index.html         article list
+//! posts/{slug}/index.html                 articles ({id} with ID permalinks)
+//! {slug}/index.html                       pages
+//! categories/{slug}/[page/{n}/]index.html
+//! tags/{slug}/[page/{n}/]index.html
+//! feed.xml, events.ics, releases.json, sitemap.xml, robots.txt
+//! uploads/...                             copied from `upload.path`
+//! ```
+//!
+//! HTML is rendered by the [`ThemeEngine`] from the active theme's
+//! `export/base.html`, `export/article.html`, `export/page.html` and
+//! `export/list.html` templates. Built-in versions fill in whatever the
+//! theme does not ship. The theme's other non-HTML `dist/` files are copied
+//! as assets. There is no comment form; an embed snippet for an external
+//! comment service can be placed under every article instead.
+
+use anyhow::{Context, Result};
+use chrono::{DateTime, Datelike, Utc};
+use serde::Serialize;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use tera::Context as TeraContext;
+
+use crate::api::seo;
+use crate::db::repositories::{
+    ArticleRepository, CategoryRepository, CustomFieldRepository, PageRepository,
+    SettingsRepository, SqlxArticleRepository, SqlxCategoryRepository, SqlxCustomFieldRepository,
+    SqlxEventRepository, SqlxPageRepository, SqlxSettingsRepository, SqlxTagRepository,
+    TagRepository,
+};
+use crate::db::DynDatabasePool;
+use crate::models::{
+    Article, ArticleSortBy, Category, ContentKind, ContentLicense, ContentStats, CustomField,
+    CustomFieldMap, LicenseInfo, Page, Tag,
+};
+use crate::plugin::HookManager;
+use crate::services::settings::{keys, SettingsService};
+use crate::services::{license, release, EventService};
+use crate::theme::social_meta::{self, SiteSocial};
+use crate::theme::structured_data::{self, ArticleData, StructuredData};
+use crate::theme::ThemeEngine;
+
+/// Articles per list page when `posts_per_page` is not set
+const DEFAULT_PER_PAGE: usize = 10;
+
+/// Built-in templates, used when the theme does not provide them
+const BUILTIN_TEMPLATES: &[(&str, &str)] = &[
+    ("export/base.html", include_str!("templates/base.html")),
+    (
+        "export/article.html",
+        include_str!("templates/article.html"),
+    ),
+    ("export/page.html", include_str!("templates/page.html")),
+    ("export/list.html", include_str!("templates/list.html")),
+];
+
+/// Export settings
+#[derive(Debug, Clone)]
+pub struct ExportOptions {
+    /// Output directory, created if missing
+    pub out: PathBuf,
+    /// Public URL of the exported site; defaults to the `site_url` setting
+    pub base_url: Option<String>,
+    /// HTML placed under every article, e.g. an external comment widget
+    pub comments_html: Option<String>,
+    /// Uploads directory to copy
+    pub uploads: PathBuf,
+}
+
+/// What an export wrote
+#[derive(Debug, Default, Serialize)]
+pub struct ExportReport {
+    pub articles: usize,
+    pub pages: usize,
+    pub categories: usize,
+    pub tags: usize,
+    /// Files written in total, including assets and list pages
+    pub files: usize,
+    /// Entries skipped because their slug cannot be a directory name
+    pub skipped: Vec<String>,
+}
+
+/// Site-wide template variables
+#[derive(Debug, Serialize)]
+struct SiteVars {
+    name: String,
+    description: String,
+    subtitle: String,
+    url: String,
+    footer: String,
+    language: String,
+    custom_css: String,
+    /// Share image when a page has none
+    image: Option<String>,
+    /// Twitter handle for `twitter:site`
+    twitter: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+struct LinkVars {
+    name: String,
+    url: String,
+}
+
+#[derive(Debug, Serialize)]
+struct PageLinkVars {
+    title: String,
+    url: String,
+}
+
+#[derive(Debug, Serialize)]
+struct NavVars {
+    categories: Vec<LinkVars>,
+    pages: Vec<PageLinkVars>,
+}
+
+/// An article as seen by the templates
+#[derive(Debug, Clone, Serialize)]
+struct ArticleVars {
+    id: i64,
+    slug: String,
+    title: String,
+    url: String,
+    excerpt: String,
+    content_html: String,
+    thumbnail: Option<String>,
+    published_at: DateTime<Utc>,
+    updated_at: DateTime<Utc>,
+    category: Option<LinkVars>,
+    tags: Vec<LinkVars>,
+    /// Read with `custom_field(item=article, key=...)`
+    custom_fields: CustomFieldMap,
+    license: LicenseInfo,
+    /// `word_count`, `char_count` and `reading_time` in minutes
+    #[serde(flatten)]
+    stats: ContentStats,
+    #[serde(skip)]
+    category_id: i64,
+    #[serde(skip)]
+    tag_ids: Vec<i64>,
+}
+
+/// A page as seen by the templates
+#[derive(Debug, Serialize)]
+struct PageVars<'a> {
+    #[serde(flatten)]
+    page: &'a Page,
+    custom_fields: CustomFieldMap,
+}
+
+#[derive(Debug, Serialize)]
+struct ListVars<'a> {
+    title: String,
+    description: String,
+    articles: &'a [ArticleVars],
+    current_page: usize,
+    total_pages: usize,
+    prev_url: Option<String>,
+    next_url: Option<String>,
+}
+
+/// Render the published site into `options.out`
+pub async fn export_site(
+    pool: &DynDatabasePool,
+    theme: &mut ThemeEngine,
+    hooks: &HookManager,
+    options: &ExportOptions,
+) -> Result<ExportReport> {
+    let settings = SettingsService::from_sqlx(SqlxSettingsRepository::new(pool.clone()));
+    let values = SqlxSettingsRepository::new(pool.clone())
+        .get_many(&[
+            keys::SITE_NAME,
+            keys::SITE_DESCRIPTION,
+            keys::SITE_SUBTITLE,
+            keys::SITE_FOOTER,
+            keys::SITE_URL,
+            keys::SITE_LOGO,
+            keys::POSTS_PER_PAGE,
+            keys::PERMALINK_STRUCTURE,
+            keys::DEFAULT_LOCALE,
+            "site_language",
+            "custom_css",
+            structured_data::PUBLISHER_TYPE_KEY,
+            structured_data::PUBLISHER_NAME_KEY,
+            structured_data::PUBLISHER_LOGO_KEY,
+            structured_data::ARTICLE_TYPE_KEY,
+            social_meta::DEFAULT_IMAGE_KEY,
+            social_meta::TWITTER_SITE_KEY,
+            license::DEFAULT_LICENSE_KEY,
+        ])
+        .await?;
+    let setting = |key: &str| values.get(key).cloned().unwrap_or_default();
+
+    let site_url = options
+        .base_url
+        .clone()
+        .unwrap_or_else(|| setting(keys::SITE_URL))
+        .trim_end_matches('/')
+        .to_string();
+    let social = SiteSocial::from_settings(&values, &site_url);
+    let site = SiteVars {
+        name: values
+            .get(keys::SITE_NAME)
+            .cloned()
+            .unwrap_or_else(|| "Noteva".to_string()),
+        description: setting(keys::SITE_DESCRIPTION),
+        subtitle: setting(keys::SITE_SUBTITLE),
+        url: site_url.clone(),
+        footer: setting(keys::SITE_FOOTER),
+        language: values
+            .get("site_language")
+            .cloned()
+            .unwrap_or_else(|| "zh-CN".to_string()),
+        custom_css: setting("custom_css"),
+        image: social.image,
+        twitter: social.twitter,
+    };
+    let per_page = setting(keys::POSTS_PER_PAGE)
+        .parse::<usize>()
+        .ok()
+        .filter(|n| *n > 0)
+        .unwrap_or(DEFAULT_PER_PAGE);
+    let by_id = setting(keys::PERMALINK_STRUCTURE).contains("{id}");
+    let schema = StructuredData::from_settings(&values, &site_url);
+    let default_license = license::from_settings(&values);
+
+    theme.set_locale(values.get(keys::DEFAULT_LOCALE).map(String::as_str));
+    register_builtin_templates(theme)?;
+    std::fs::create_dir_all(&options.out)
+        .with_context(|| format!("Failed to create {}", options.out.display()))?;
+    let mut report = ExportReport::default();
+
+    // Load content
+    let article_repo = SqlxArticleRepository::new(pool.clone());
+    let total = article_repo.count_published().await?;
+    let articles = article_repo
+        .list_published(0, total.max(1), ArticleSortBy::default())
+        .await?;
+    let categories = SqlxCategoryRepository::new(pool.clone()).list().await?;
+    let tag_repo = SqlxTagRepository::new(pool.clone());
+    let tags = tag_repo.list().await?;
+    let article_ids: Vec<i64> = articles.iter().map(|a| a.id).collect();
+    let article_tags = tag_repo.get_by_article_ids(&article_ids).await?;
+    let pages = SqlxPageRepository::new(pool.clone())
+        .list_published()
+        .await?;
+    let field_repo = SqlxCustomFieldRepository::new(pool.clone());
+    let mut article_fields = group_fields(
+        field_repo
+            .list_for(ContentKind::Article, &article_ids)
+            .await?,
+    );
+    let page_ids: Vec<i64> = pages.iter().map(|p| p.id).collect();
+    let mut page_fields = group_fields(field_repo.list_for(ContentKind::Page, &page_ids).await?);
+
+    let category_links: HashMap<i64, LinkVars> = categories
+        .iter()
+        .map(|c| (c.id, category_link(c)))
+        .collect();
+    let articles: Vec<ArticleVars> = articles
+        .iter()
+        .map(|article| {
+            article_vars(
+                article,
+                by_id,
+                category_links.get(&article.category_id).cloned(),
+                article_tags
+                    .get(&article.id)
+                    .map(Vec::as_slice)
+                    .unwrap_or(&[]),
+                article_fields.remove(&article.id).unwrap_or_default(),
+                &default_license,
+            )
+        })
+        .collect();
+
+    let nav = NavVars {
+        categories: categories
+            .iter()
+            .filter(|c| c.parent_id.is_none())
+            .map(category_link)
+            .collect(),
+        pages: pages
+            .iter()
+            .map(|p| PageLinkVars {
+                title: p.title.clone(),
+                url: format!("/{}/", p.slug),
+            })
+            .collect(),
+    };
+    let mut base = TeraContext::new();
+    base.insert("site", &site);
+    base.insert("nav", &nav);
+    base.insert("year", &Utc::now().year());
+    base.insert("theme_name", theme.get_current_theme());
+    base.insert("locale", &theme.locale());
+    base.insert("comments_html", &options.comments_html);
+
+    // Articles
+    for article in &articles {
+        let identifier = article.url.trim_matches('/').trim_start_matches("posts/");
+        if !is_safe_segment(identifier) {
+            report.skipped.push(article.url.clone());
+            continue;
+        }
+        let mut context = base.clone();
+        context.insert("article", article);
+        if !site_url.is_empty() {
+            context.insert("json_ld", &article_json_ld(&schema, article));
+        }
+        write_html(
+            &options.out,
+            &article.url,
+            &theme.render("export/article.html", &context)?,
+        )?;
+        report.articles += 1;
+        report.files += 1;
+    }
+
+    // Pages
+    for page in &pages {
+        if !is_safe_segment(&page.slug) {
+            report.skipped.push(page.slug.clone());
+            continue;
+        }
+        let mut context = base.clone();
+        context.insert(
+            "page",
+            &PageVars {
+                page,
+                custom_fields: page_fields.remove(&page.id).unwrap_or_default(),
+            },
+        );
+        if !site_url.is_empty() {
+            let url = schema.absolute_url(&format!("/{}/", page.slug));
+            let graph = vec![
+                schema.web_page(&page.title, "", &url),
+                schema.breadcrumbs(&[(page.title.as_str(), url.clone())]),
+            ];
+            context.insert("json_ld", &structured_data::script_tag(graph));
+        }
+        write_html(
+            &options.out,
+            &format!("/{}/", page.slug),
+            &theme.render("export/page.html", &context)?,
+        )?;
+        report.pages += 1;
+        report.files += 1;
+    }
+
+    // Home, category and tag lists
+    report.files += write_list(theme, &base, &options.out, "/", "", "", &articles, per_page)?;
+    for category in &categories {
+        if !is_safe_segment(&category.slug) {
+            report.skipped.push(category.slug.clone());
+            continue;
+        }
+        let ids = descendant_ids(&categories, category.id);
+        let listed: Vec<ArticleVars> = articles
+            .iter()
+            .filter(|a| ids.contains(&a.category_id))
+            .cloned()
+            .collect();
+        report.files += write_list(
+            theme,
+            &base,
+            &options.out,
+            &format!("/categories/{}/", category.slug),
+            &category.name,
+            category.description.as_deref().unwrap_or(""),
+            &listed,
+            per_page,
+        )?;
+        report.categories += 1;
+    }
+    for tag in &tags {
+        if !is_safe_segment(&tag.slug) {
+            report.skipped.push(tag.slug.clone());
+            continue;
+        }
+        let listed: Vec<ArticleVars> = articles
+            .iter()
+            .filter(|a| a.tag_ids.contains(&tag.id))
+            .cloned()
+            .collect();
+        report.files += write_list(
+            theme,
+            &base,
+            &options.out,
+            &format!("/tags/{}/", tag.slug),
+            &tag.name,
+            "",
+            &listed,
+            per_page,
+        )?;
+        report.tags += 1;
+    }
+
+    // Feeds, sitemap and robots.txt
+    write_file(
+        &options.out.join("feed.xml"),
+        seo::build_feed(pool, &settings, hooks, &site_url)
+            .await
+            .as_bytes(),
+    )?;
+    report.files += 1;
+    let events = EventService::new(SqlxEventRepository::boxed(pool.clone()));
+    write_file(
+        &options.out.join("events.ics"),
+        events.ical_feed(&site.name, &site_url).await?.as_bytes(),
+    )?;
+    report.files += 1;
+    let releases = release::list_releases(pool, &settings, &site_url).await?;
+    if !releases.is_empty() {
+        let document = release::releases_document(&site.name, &site_url, &releases);
+        write_file(
+            &options.out.join("releases.json"),
+            document.to_string().as_bytes(),
+        )?;
+        report.files += 1;
+    }
+    if let Some(sitemap) = seo::build_sitemap(pool, &settings, hooks, &site_url).await {
+        write_file(&options.out.join("sitemap.xml"), sitemap.as_bytes())?;
+        report.files += 1;
+    } else {
+        tracing::warn!("site_url is not set; sitemap.xml skipped (use --base-url)");
+    }
+    write_file(
+        &options.out.join("robots.txt"),
+        seo::robots_body(&settings, &site_url).await.as_bytes(),
+    )?;
+    report.files += 1;
+
+    // Assets
+    if options.uploads.is_dir() {
+        report.files += copy_tree(&options.uploads, &options.out.join("uploads"), &|_| true)?;
+    }
+    let theme_dist = theme.get_theme_path(theme.get_current_theme()).join("dist");
+    if theme_dist.is_dir() {
+        report.files += copy_tree(&theme_dist, &options.out, &is_theme_asset)?;
+    }
+
+    Ok(report)
+}
+
+/// Add the built-in templates the active theme does not override
+fn register_builtin_templates(theme: &mut ThemeEngine) -> Result<()> {
+    let tera = theme.tera_mut();
+    let existing: Vec<String> = tera.get_template_names().map(str::to_string).collect();
+    let missing: Vec<(&str, &str)> = BUILTIN_TEMPLATES
+        .iter()
+        .filter(|(name, _)| !existing.iter().any(|e| e == name))
+        .copied()
+        .collect();
+    tera.add_raw_templates(missing)
+        .context("Failed to load built-in export templates")
+}
+
+fn category_link(category: &Category) -> LinkVars {
+    LinkVars {
+        name: category.name.clone(),
+        url: format!("/categories/{}/", category.slug),
+    }
+}
+
+fn article_vars(
+    article: &Article,
+    by_id: bool,
+    category: Option<LinkVars>,
+    tags: &[Tag],
+    custom_fields: CustomFieldMap,
+    default_license: &ContentLicense,
+) -> ArticleVars {
+    let identifier = if by_id {
+        article.id.to_string()
+    } else {
+        article.slug.clone()
+    };
+    let excerpt = article.excerpt_text();
+    ArticleVars {
+        id: article.id,
+        slug: article.slug.clone(),
+        title: article.title.clone(),
+        url: format!("/posts/{}/", identifier),
+        excerpt,
+        content_html: article.content_html.clone(),
+        thumbnail: article.thumbnail.clone(),
+        published_at: article.published_at.unwrap_or(article.created_at),
+        updated_at: article.updated_at,
+        category,
+        tags: tags
+            .iter()
+            .map(|t| LinkVars {
+                name: t.name.clone(),
+                url: format!("/tags/{}/", t.slug),
+            })
+            .collect(),
+        custom_fields,
+        license: license::resolve(article, default_license),
+        stats: article.stats,
+        category_id: article.category_id,
+        tag_ids: tags.iter().map(|t| t.id).collect(),
+    }
+}
+
+/// Custom field maps by content id
+fn group_fields(fields: Vec<(i64, CustomField)>) -> HashMap<i64, CustomFieldMap> {
+    let mut maps: HashMap<i64, CustomFieldMap> = HashMap::new();
+    for (id, field) in fields {
+        maps.entry(id).or_default().insert(field.key, field.value);
+    }
+    maps
+}
+
+/// `<script>` with the article and its breadcrumb trail
+fn article_json_ld(schema: &StructuredData, article: &ArticleVars) -> String {
+    let url = schema.absolute_url(&article.url);
+    let description: String = article.excerpt.chars().take(160).collect();
+    let keywords: Vec<String> = article.tags.iter().map(|t| t.name.clone()).collect();
+    let mut trail = Vec::new();
+    if let Some(category) = &article.category {
+        trail.push((category.name.as_str(), schema.absolute_url(&category.url)));
+    }
+    trail.push((article.title.as_str(), url.clone()));
+    structured_data::script_tag(vec![
+        schema.article(&ArticleData {
+            headline: &article.title,
+            description: &description,
+            url: &url,
+            image: article.thumbnail.as_deref(),
+            published_at: Some(article.published_at),
+            updated_at: Some(article.updated_at),
+            section: article.category.as_ref().map(|c| c.name.as_str()),
+            keywords: &keywords,
+            license: article.license.url.as_deref(),
+            ..Default::default()
+        }),
+        schema.breadcrumbs(&trail),
+    ])
+}
+
+/// `id` and every category below it
+fn descendant_ids(categories: &[Category], id: i64) -> Vec<i64> {
+    let mut ids = vec![id];
+    let mut i = 0;
+    while i < ids.len() {
+        let parent = ids[i];
+        ids.extend(
+            categories
+                .iter()
+                .filter(|c| c.parent_id == Some(parent))
+                .map(|c| c.id),
+        );
+        i += 1;
+    }
+    ids
+}
+
+/// Render a paginated article list under `path`; returns files written
+#[allow(clippy::too_many_arguments)]
+fn write_list(
+    theme: &ThemeEngine,
+    base: &TeraContext,
+    out: &Path,
+    path: &str,
+    title: &str,
+    description: &str,
+    articles: &[ArticleVars],
+    per_page: usize,
+) -> Result<usize> {
+    let chunks: Vec<&[ArticleVars]> = if articles.is_empty() {
+        vec![&[]]
+    } else {
+        articles.chunks(per_page).collect()
+    };
+    let total_pages = chunks.len();
+    for (index, chunk) in chunks.into_iter().enumerate() {
+        let current = index + 1;
+        let list = ListVars {
+            title: title.to_string(),
+            description: description.to_string(),
+            articles: chunk,
+            current_page: current,
+            total_pages,
+            prev_url: (current > 1).then(|| list_page_url(path, current - 1)),
+            next_url: (current < total_pages).then(|| list_page_url(path, current + 1)),
+        };
+        let mut context = base.clone();
+        context.insert("list", &list);
+        write_html(
+            out,
+            &list_page_url(path, current),
+            &theme.render("export/list.html", &context)?,
+        )?;
+    }
+    Ok(total_pages)
+}
+
+/// URL of page `n` of the list at `path` (`/`, `/tags/rust/`, ...)
+fn list_page_url(path: &str, n: usize) -> String {
+    if n <= 1 {
+        path.to_string()
+    } else {
+        format!("{}page/{}/", path, n)
+    }
+}
+
+/// A slug usable as a single directory name
+fn is_safe_segment(segment: &str) -> bool {
+    !segment.is_empty()
+        && segment != "."
+        && segment != ".."
+        && !segment.contains(['/', '\\', ':', '\0'])
+}
+
+/// Write `html` as the `index.html` of the directory for `url_path`
+fn write_html(out: &Path, url_path: &str, html: &str) -> Result<()> {
+    let mut path = out.to_path_buf();
+    for segment in url_path.split('/').filter(|s| !s.is_empty()) {
+        path.push(segment);
+    }
+    write_file(&path.join("index.html"), html.as_bytes())
+}
+
+fn write_file(path: &Path, contents: &[u8]) -> Result<()> {
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    std::fs::write(path, contents).with_context(|| format!("Failed to write {}", path.display()))
+}
+
+/// Theme files that make sense on a static site: not the SPA shell or the
+/// export templates themselves
+fn is_theme_asset(relative: &Path) -> bool {
+    !relative.starts_with("export") && relative.extension().is_none_or(|ext| ext != "html")
+}
+
+/// Copy files below `from` accepted by `filter` into `to`; returns files copied
+fn copy_tree(from: &Path, to: &Path, filter: &dyn Fn(&Path) -> bool) -> Result<usize> {
+    let mut copied = 0;
+    let mut pending = vec![from.to_path_buf()];
+    while let Some(dir) = pending.pop() {
+        for entry in std::fs::read_dir(&dir)? {
+            let entry = entry?;
+            let path = entry.path();
+            let file_type = entry.file_type()?;
+            if file_type.is_dir() {
+                pending.push(path);
+            } else if file_type.is_file() {
+                let relative = path.strip_prefix(from)?;
+                if filter(relative) {
+                    let dest = to.join(relative);
+                    if let Some(parent) = dest.parent() {
+                        std::fs::create_dir_all(parent)?;
+                    }
+                    std::fs::copy(&path, &dest)
+                        .with_context(|| format!("Failed to copy {}", path.display()))?;
+                    copied += 1;
+                }
+            }
+        }
+    }
+    Ok(copied)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn list_pages_nest_under_their_path() {
+        assert_eq!(list_page_url("/", 1), "/");
+        assert_eq!(list_page_url("/", 2), "/page/2/");
+        assert_eq!(list_page_url("/tags/rust/", 3), "/tags/rust/page/3/");
+    }
+
+    #[test]
+    fn rejects_slugs_that_escape_the_output() {
+        assert!(is_safe_segment("hello-world"));
+        assert!(is_safe_segment("你好"));
+        assert!(!is_safe_segment(""));
+        assert!(!is_safe_segment(".."));
+        assert!(!is_safe_segment("a/b"));
+        assert!(!is_safe_segment("..\\etc"));
+    }
+
+    #[test]
+    fn theme_assets_skip_html_and_templates() {
+        assert!(is_theme_asset(Path::new("assets/index-abc.js")));
+        assert!(is_theme_asset(Path::new("logo.png")));
+        assert!(!is_theme_asset(Path::new("index.html")));
+        assert!(!is_theme_asset(Path::new("export/style.css")));
+    }
+
+    #[test]
+    fn builtin_templates_render() {
+        let mut tera = tera::Tera::default();
+        social_meta::register_functions(&mut tera);
+        crate::theme::custom_field::register_functions(&mut tera);
+        tera.add_raw_templates(BUILTIN_TEMPLATES.to_vec()).unwrap();
+
+        let article = ArticleVars {
+            id: 1,
+            slug: "hello".to_string(),
+            title: "Hello <World>".to_string(),
+            url: "/posts/hello/".to_string(),
+            excerpt: "Hi".to_string(),
+            content_html: "<p>Body</p>".to_string(),
+            thumbnail: None,
+            published_at: Utc::now(),
+            updated_at: Utc::now(),
+            category: None,
+            tags: vec![LinkVars {
+                name: "rust".to_string(),
+                url: "/tags/rust/".to_string(),
+            }],
+            custom_fields: CustomFieldMap::new(),
+            license: ContentLicense::new(crate::models::LicenseKind::CcBy).info(true),
+            stats: ContentStats::default(),
+            category_id: 1,
+            tag_ids: vec![1],
+        };
+        let mut context = TeraContext::new();
+        context.insert(
+            "site",
+            &SiteVars {
+                name: "Blog".to_string(),
+                description: String::new(),
+                subtitle: String::new(),
+                url: String::new(),
+                footer: String::new(),
+                language: "en".to_string(),
+                custom_css: String::new(),
+                image: None,
+                twitter: Some("@blog".to_string()),
+            },
+        );
+        context.insert(
+            "nav",
+            &NavVars {
+                categories: Vec::new(),
+                pages: Vec::new(),
+            },
+        );
+        context.insert("year", &2026);
+        context.insert("comments_html", &Some("<div id=\"giscus\"></div>"));
+        context.insert("article", &article);
+
+        let html = tera.render("export/article.html", &context).unwrap();
+        assert!(html.contains("<title>Hello &lt;World&gt; - Blog</title>"));
+        assert!(html.contains("<p>Body</p>"));
+        assert!(html.contains("#rust</a>"));
+        assert!(html.contains("<div id=\"giscus\"></div>"));
+        assert!(html.contains(r#"<meta property="og:title" content="Hello &lt;World&gt;">"#));
+        assert!(html.contains(r#"<meta name="twitter:site" content="@blog">"#));
+        assert!(html.contains(r#"<link rel="license" href="#));
+        assert!(html.contains(">CC BY 4.0</a>"));
+
+        let articles = [article];
+        context.insert(
+            "list",
+            &ListVars {
+                title: String::new(),
+                description: String::new(),
+                articles: &articles,
+                current_page: 1,
+                total_pages: 2,
+                prev_url: None,
+                next_url: Some("/page/2/".to_string()),
+            },
+        );
+        let html = tera.render("export/list.html", &context).unwrap();
+        assert!(html.contains(">Hello &lt;World&gt;</a>"));
+        assert!(html.contains("&rarr;</a>"));
+    }
+}
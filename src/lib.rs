@@ -4,9 +4,14 @@
 
 pub mod api;
 pub mod cache;
+pub mod cli;
 pub mod config;
+pub mod daemon;
 pub mod db;
+pub mod export;
 pub mod models;
 pub mod plugin;
 pub mod services;
+pub mod telemetry;
 pub mod theme;
+pub mod tls;
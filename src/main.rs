@@ -1,21 +1,33 @@
 //! Noteva - A lightweight modern blog system
 
-use anyhow::Result;
+use anyhow::{Context, Result};
+use axum::serve::ListenerExt;
+use clap::Parser;
 use std::path::Path;
 use std::sync::Arc;
-use tracing_subscriber::{layer::SubscriberExt, util::SubscriberInitExt};
+use std::time::Duration;
 
 use noteva::{
     api::{self, middleware::RequestStats, AppState},
     cache::create_cache,
-    config::Config,
+    cli::{self, Cli, Command},
+    config::{AuthBackend, Config},
     db::{
         self,
         repositories::{
-            SettingsRepository, SqlxArticleRepository, SqlxCategoryRepository,
-            SqlxCommentRepository, SqlxFriendLinkRepository, SqlxNavItemRepository,
-            SqlxPageRepository, SqlxSessionRepository, SqlxSettingsRepository, SqlxTagRepository,
-            SqlxUserRepository,
+            SettingsRepository, SqlxAnalyticsRepository, SqlxArticleAuthorRepository,
+            SqlxArticleRepository, SqlxAttachmentRepository, SqlxCategoryRepository,
+            SqlxCommentRepository, SqlxCustomFieldRepository, SqlxDocRepository,
+            SqlxEmailSuppressionRepository, SqlxEventRepository, SqlxFaqRepository,
+            SqlxFavoriteRepository, SqlxFeaturedRepository, SqlxFriendLinkRepository,
+            SqlxGithubSyncRepository, SqlxInboundWebhookRepository, SqlxJobQueueRepository,
+            SqlxNavItemRepository, SqlxPageRepository, SqlxPollRepository,
+            SqlxPushSubscriptionRepository, SqlxReaderGroupRepository, SqlxReadingProgressRepository,
+            SqlxRedirectRepository,
+            SqlxSeriesRepository, SqlxSessionRepository, SqlxSettingsRepository,
+            SqlxStatsRepository, SqlxSubscriberRepository, SqlxSyncRepository, SqlxTagRepository,
+            SqlxTranslationRepository, SqlxUserPreferencesRepository, SqlxUserRepository,
+            SqlxWebauthnCredentialRepository,
         },
     },
     plugin::{
@@ -23,31 +35,64 @@ use noteva::{
         ShortcodeManager,
     },
     services::{
-        about::AboutService, article::ArticleService, captcha_pow::CaptchaPowStore,
-        category::CategoryService, comment::CommentService, friend_link::FriendLinkService,
-        markdown::MarkdownRenderer, nav_item::NavItemService, page::PageService,
-        settings::SettingsService, tag::TagService, user::UserService,
+        about::AboutService, article::ArticleService, article_author::ArticleAuthorService,
+        attachment::AttachmentService, captcha::CaptchaVerifier, captcha_pow::CaptchaPowStore,
+        category::CategoryService, comment::CommentService, custom_field::CustomFieldService,
+        doc::DocService, event::EventService, faq::FaqService, featured::FeaturedService,
+        friend_link::FriendLinkService, ip_reputation::IpReputationStore, ldap::LdapAuthenticator,
+        markdown::MarkdownRenderer, nav_item::NavItemService, newsletter::NewsletterService,
+        page::PageService, poll::PollService, reader_group::ReaderGroupService,
+        redirect::RedirectService, series::SeriesService,
+        settings::SettingsService, tag::TagService, translation::TranslationService,
+        user::UserService, web_push::WebPushService, webauthn::WebauthnService,
+        webmention::WebmentionService,
     },
     theme::ThemeEngine,
 };
 
 #[tokio::main]
 async fn main() -> Result<()> {
+    let cli = Cli::parse();
+    if let Some(dir) = &cli.workdir {
+        std::env::set_current_dir(dir)
+            .with_context(|| format!("Failed to change directory to {}", dir.display()))?;
+    }
+    // Load configuration (before tracing, which it configures)
+    let config = Config::load_with_env(&cli.config)?;
+
+    match cli.command.unwrap_or(Command::Serve) {
+        Command::Serve => serve(config).await,
+        #[cfg(windows)]
+        Command::RunService { name } => {
+            let service = noteva::daemon::windows::start(&name).await?;
+            let result = serve(config).await;
+            service.stopped(&result);
+            result
+        }
+        command => cli::run(command, &cli.config, &config).await,
+    }
+}
+
+/// Run the web server until a shutdown signal
+async fn serve(config: Config) -> Result<()> {
     // Initialize tracing
-    tracing_subscriber::registry()
-        .with(
-            tracing_subscriber::EnvFilter::try_from_default_env()
-                .unwrap_or_else(|_| "noteva=info,tower_http=warn".into()),
-        )
-        .with(tracing_subscriber::fmt::layer())
-        .init();
+    let _telemetry = noteva::telemetry::init(&config.telemetry, &config.logging)?;
 
     tracing::info!("Starting Noteva blog system...");
-
-    // Load configuration
-    let config = Config::load_with_env(Path::new("config.yml"))?;
     tracing::debug!("Configuration loaded");
 
+    let cors_policy = config.server.cors_policy()?;
+    if cors_policy.is_permissive() {
+        tracing::warn!("cors_permissive is on: any origin may make credentialed requests");
+    }
+    config.server.compression.validate()?;
+    let tls = config
+        .server
+        .tls
+        .as_ref()
+        .map(noteva::tls::setup)
+        .transpose()?;
+
     // Initialize database
     let pool = db::create_pool(&config.database).await?;
     tracing::info!(driver = ?config.database.driver, "database connected");
@@ -75,11 +120,17 @@ async fn main() -> Result<()> {
     tracing::debug!("Plugin system initialized");
 
     // Create markdown renderer with shortcode and hook support
-    let markdown_renderer =
+    let markdown_engine = noteva::services::markdown::engine_from_config(&config.markdown);
+    tracing::debug!(engine = markdown_engine.name(), "Markdown engine selected");
+    let mut markdown_renderer =
         MarkdownRenderer::with_managers(shortcode_manager_arc.clone(), hook_manager.clone());
+    markdown_renderer.set_engine(markdown_engine.clone());
 
     // Create repositories
     let user_repo = SqlxUserRepository::boxed(pool.clone());
+    let preferences_repo = SqlxUserPreferencesRepository::boxed(pool.clone());
+    let reading_progress_repo = SqlxReadingProgressRepository::boxed(pool.clone());
+    let favorite_repo = SqlxFavoriteRepository::boxed(pool.clone());
     let session_repo = Arc::new(SqlxSessionRepository::new(pool.clone()));
     let category_repo = Arc::new(SqlxCategoryRepository::new(pool.clone()));
     let tag_repo = Arc::new(SqlxTagRepository::new(pool.clone()));
@@ -88,9 +139,35 @@ async fn main() -> Result<()> {
     let page_repo = SqlxPageRepository::boxed(pool.clone());
     let nav_repo = SqlxNavItemRepository::boxed(pool.clone());
     let friend_link_repo = SqlxFriendLinkRepository::boxed(pool.clone());
+    let redirect_repo = SqlxRedirectRepository::boxed(pool.clone());
+    let poll_repo = SqlxPollRepository::boxed(pool.clone());
+    let event_repo = SqlxEventRepository::boxed(pool.clone());
+    let doc_repo = SqlxDocRepository::boxed(pool.clone());
+    let faq_repo = SqlxFaqRepository::boxed(pool.clone());
+    let translation_repo = SqlxTranslationRepository::boxed(pool.clone());
+    let series_repo = SqlxSeriesRepository::boxed(pool.clone());
+    let featured_repo = SqlxFeaturedRepository::boxed(pool.clone());
+    let attachment_repo = SqlxAttachmentRepository::boxed(pool.clone());
+    let article_author_repo = SqlxArticleAuthorRepository::boxed(pool.clone());
+    let custom_field_repo = SqlxCustomFieldRepository::boxed(pool.clone());
+    let reader_group_repo = SqlxReaderGroupRepository::boxed(pool.clone());
 
     // Initialize services with hook support
-    let user_service = Arc::new(UserService::new(user_repo.clone(), session_repo));
+    let captcha_verifier = Arc::new(CaptchaVerifier::new(Arc::new(SqlxSettingsRepository::new(
+        pool.clone(),
+    ))));
+    let mut user_service = UserService::new(user_repo.clone(), session_repo)
+        .with_captcha(captcha_verifier.clone())
+        .with_settings(Arc::new(SqlxSettingsRepository::new(pool.clone())));
+    if config.auth.backend == AuthBackend::Ldap {
+        let ldap = LdapAuthenticator::new(config.auth.ldap.clone())?;
+        tracing::info!(url = %config.auth.ldap.url, "LDAP authentication enabled");
+        user_service = user_service.with_directory(
+            Arc::new(ldap),
+            noteva::db::repositories::SqlxExternalIdentityRepository::boxed(pool.clone()),
+        );
+    }
+    let user_service = Arc::new(user_service);
     let category_service = Arc::new(CategoryService::new(
         category_repo,
         cache.clone(),
@@ -98,6 +175,7 @@ async fn main() -> Result<()> {
     ));
     let tag_service = Arc::new(TagService::new(tag_repo.clone(), cache.clone()));
     let settings_service = Arc::new(SettingsService::from_sqlx(settings_repo));
+    let ip_reputation = Arc::new(IpReputationStore::new(settings_service.clone()));
 
     let article_service = Arc::new(ArticleService::with_hooks(
         article_repo,
@@ -106,38 +184,141 @@ async fn main() -> Result<()> {
         markdown_renderer,
         hook_manager.clone(),
     ));
-    let page_service = Arc::new(PageService::with_hooks(
-        page_repo,
-        cache.clone(),
-        hook_manager.clone(),
-    ));
+    let page_service = Arc::new(
+        PageService::with_hooks(page_repo, cache.clone(), hook_manager.clone())
+            .with_markdown_engine(markdown_engine.clone()),
+    );
     let nav_service = Arc::new(NavItemService::new(nav_repo, cache.clone()));
+    let sync_service = Arc::new(noteva::services::SyncService::new(
+        SqlxSyncRepository::boxed(pool.clone()),
+    ));
+    let stats_service = Arc::new(noteva::services::StatsService::new(
+        SqlxStatsRepository::boxed(pool.clone()),
+    ));
+    let analytics = Arc::new(noteva::services::AnalyticsService::new(
+        SqlxAnalyticsRepository::boxed(pool.clone()),
+        config.analytics.clone(),
+    )?);
+    let backup_service = Arc::new(noteva::services::backup::BackupService::new(
+        pool.clone(),
+        noteva::services::backup::BackupSources {
+            uploads: config.upload.path.clone(),
+            themes: config.theme.path.clone(),
+            plugins: Path::new("plugins").to_path_buf(),
+        },
+        config.backup.clone(),
+    ));
     let friend_link_service = Arc::new(FriendLinkService::new(friend_link_repo, cache.clone()));
+    let redirect_service = Arc::new(RedirectService::new(redirect_repo, cache.clone()));
+    let poll_service = Arc::new(PollService::new(poll_repo));
+    let event_service = Arc::new(EventService::new(event_repo));
+    let doc_service =
+        Arc::new(DocService::new(doc_repo).with_markdown_engine(markdown_engine.clone()));
+    let faq_service = Arc::new(FaqService::new(faq_repo).with_markdown_engine(markdown_engine));
+    let translation_service = Arc::new(TranslationService::new(
+        translation_repo,
+        SqlxArticleRepository::boxed(pool.clone()),
+        SqlxPageRepository::boxed(pool.clone()),
+    ));
+    let series_service = Arc::new(SeriesService::new(series_repo));
+    let featured_service = Arc::new(FeaturedService::new(featured_repo));
+    let attachment_service = Arc::new(AttachmentService::new(attachment_repo, &config.upload));
+    let article_author_service = Arc::new(ArticleAuthorService::new(article_author_repo));
+    let custom_field_service = Arc::new(CustomFieldService::new(custom_field_repo));
+    let reader_group_service = Arc::new(ReaderGroupService::new(reader_group_repo));
     let about_service = Arc::new(AboutService::new(settings_service.clone()));
 
     // Create comment service with hooks and settings support
     let comment_repo = Arc::new(SqlxCommentRepository::new(pool.clone()));
     let settings_repo_for_comment = Arc::new(SqlxSettingsRepository::new(pool.clone()));
     let comment_service = Arc::new(
-        CommentService::with_hooks(comment_repo, cache.clone(), hook_manager.clone())
-            .with_settings(settings_repo_for_comment),
+        CommentService::with_hooks(comment_repo.clone(), cache.clone(), hook_manager.clone())
+            .with_settings(settings_repo_for_comment)
+            .with_captcha(captcha_verifier)
+            .with_reputation(ip_reputation.clone()),
     );
+    let views = Arc::new(noteva::services::ViewCounter::new(
+        comment_repo.clone(),
+        cache.clone(),
+        config.views.clone(),
+    ));
+
+    // Webmention sending (on publish) and receiving
+    let webmention_service = Arc::new(WebmentionService::new(
+        comment_repo,
+        Arc::new(SqlxArticleRepository::new(pool.clone())),
+        settings_service.clone(),
+    ));
+    webmention_service.register_hooks(&hook_manager);
+
+    // Inbound webhooks delivered to plugins via the external_webhook hook
+    let inbound_webhooks = Arc::new(noteva::services::InboundWebhookService::new(
+        SqlxInboundWebhookRepository::boxed(pool.clone()),
+        hook_manager.clone(),
+    ));
+
+    // Publish articles from pushes to a GitHub content repository
+    let github_publish = Arc::new(noteva::services::GithubPublishService::new(
+        settings_service.clone(),
+        article_service.clone(),
+        category_service.clone(),
+        tag_service.clone(),
+        user_repo.clone(),
+        SqlxGithubSyncRepository::boxed(pool.clone()),
+    ));
+
+    let maintenance = Arc::new(noteva::services::MaintenanceService::new(
+        settings_service.clone(),
+    ));
+
+    let api_exposure = Arc::new(noteva::services::ApiExposureService::new(
+        settings_service.clone(),
+    ));
+
+    // Passkey (WebAuthn) login for admins
+    let webauthn_service = Arc::new(WebauthnService::new(
+        SqlxWebauthnCredentialRepository::boxed(pool.clone()),
+        user_repo.clone(),
+        settings_service.clone(),
+    ));
+
+    // SAML single sign-on
+    #[cfg(feature = "saml")]
+    let saml_service = if config.saml.enabled {
+        let service = noteva::services::saml::SamlService::new(
+            config.saml.clone(),
+            user_repo.clone(),
+            noteva::db::repositories::SqlxExternalIdentityRepository::boxed(pool.clone()),
+        )?;
+        tracing::info!(idp = %config.saml.idp_entity_id, "SAML single sign-on enabled");
+        Some(Arc::new(service))
+    } else {
+        None
+    };
+    #[cfg(not(feature = "saml"))]
+    if config.saml.enabled {
+        tracing::warn!("saml.enabled is set but this build does not include the `saml` feature");
+    }
 
     // Initialize default navigation items
     nav_service.init_defaults().await?;
     tracing::debug!("Navigation initialized");
 
     // Initialize theme engine - read active theme from database
-    let active_theme = {
-        let settings_repo_for_theme = SqlxSettingsRepository::new(pool.clone());
-        settings_repo_for_theme
-            .get("active_theme")
-            .await
-            .ok()
-            .flatten()
-            .map(|s| s.value)
-            .unwrap_or_else(|| config.theme.active.clone())
-    };
+    let settings_repo_for_theme = SqlxSettingsRepository::new(pool.clone());
+    let active_theme = settings_repo_for_theme
+        .get("active_theme")
+        .await
+        .ok()
+        .flatten()
+        .map(|s| s.value)
+        .unwrap_or_else(|| config.theme.active.clone());
+    let default_locale = settings_repo_for_theme
+        .get(noteva::services::settings::keys::DEFAULT_LOCALE)
+        .await
+        .ok()
+        .flatten()
+        .map(|s| s.value);
     let mut theme_engine = ThemeEngine::new(&config.theme.path, "default")?;
     // If active theme is not default, switch to it
     if active_theme != "default" {
@@ -146,6 +327,7 @@ async fn main() -> Result<()> {
             tracing::warn!(theme = %active_theme, "active theme not available, using default");
         }
     }
+    theme_engine.set_locale(default_locale.as_deref());
     tracing::info!(current = %theme_engine.get_current_theme(), default = "default", "theme engine initialized");
 
     // Initialize WASM plugin runtime
@@ -193,67 +375,302 @@ async fn main() -> Result<()> {
     let request_stats = Arc::new(RequestStats::new());
 
     let rate_limiter = Arc::new(noteva::services::LoginRateLimiter::new());
+    let api_rate_limiter = Arc::new(
+        noteva::services::ApiRateLimiter::from_config(&config.rate_limit, &config.cache).await?,
+    );
+    // Email templates may be overridden by the active theme
+    let email_templates = {
+        let theme_path = theme_engine.get_theme_path(theme_engine.get_current_theme());
+        noteva::services::EmailTemplates::load(&theme_path).unwrap_or_else(|e| {
+            tracing::warn!(error = %e, "invalid theme email templates, using built-in ones");
+            noteva::services::EmailTemplates::builtin()
+        })
+    };
+    let email_service = Arc::new(
+        noteva::services::EmailService::new(Arc::new(SqlxSettingsRepository::new(pool.clone())))
+            .with_templates(Arc::new(email_templates))
+            .with_smtp(config.email.smtp.clone())
+            .with_suppressions(SqlxEmailSuppressionRepository::boxed(pool.clone())),
+    );
     let captcha_pow_store = Arc::new(CaptchaPowStore::new());
+    let jobs = Arc::new(noteva::services::JobMonitor::new());
+    // Live admin events: new comments, finished jobs and plugin logs
+    let admin_events = Arc::new(noteva::services::AdminEvents::default());
+    admin_events.register_hooks(&hook_manager);
+    {
+        let events = admin_events.clone();
+        noteva::plugin::wasm_bridge::set_plugin_log_sink(move |line| {
+            events.publish("plugin_log", serde_json::json!(line));
+        });
+    }
+    let job_queue = Arc::new(
+        noteva::services::JobQueue::new(
+            SqlxJobQueueRepository::boxed(pool.clone()),
+            config.job_queue.clone(),
+        )
+        .with_hooks(hook_manager.clone()),
+    );
+    webmention_service.register_jobs(&job_queue);
+
+    // Newsletter subscriptions, sent on publish through the job queue
+    let newsletter_service = Arc::new(NewsletterService::new(
+        SqlxSubscriberRepository::boxed(pool.clone()),
+        Arc::new(SqlxArticleRepository::new(pool.clone())),
+        settings_service.clone(),
+        email_service.clone(),
+        job_queue.clone(),
+    ));
+    newsletter_service.register_hooks(&hook_manager);
+    newsletter_service.register_jobs(&job_queue);
+
+    // Web Push notifications for new articles; the VAPID key is created on first start
+    let web_push_service = Arc::new(WebPushService::new(
+        SqlxPushSubscriptionRepository::boxed(pool.clone()),
+        Arc::new(SqlxArticleRepository::new(pool.clone())),
+        settings_service.clone(),
+        job_queue.clone(),
+    ));
+    if let Err(e) = web_push_service.init_keys().await {
+        tracing::warn!(error = %e, "failed to load Web Push VAPID key");
+    }
+    web_push_service.register_hooks(&hook_manager);
+    web_push_service.register_jobs(&job_queue);
+
+    let spellcheck = Arc::new(
+        noteva::services::SpellcheckService::load(&config.spellcheck).unwrap_or_else(|e| {
+            tracing::warn!(error = %e, "failed to load spellcheck dictionaries");
+            noteva::services::SpellcheckService::default()
+        }),
+    );
+    let duplicates = Arc::new(noteva::services::DuplicateService::new(
+        SqlxArticleRepository::boxed(pool.clone()),
+    ));
+    let link_suggestions = Arc::new(noteva::services::LinkSuggestionService::new(
+        SqlxArticleRepository::boxed(pool.clone()),
+        SqlxTagRepository::boxed(pool.clone()),
+    ));
     let two_factor_challenges =
         Arc::new(tokio::sync::RwLock::new(std::collections::HashMap::new()));
 
     let state = AppState {
         pool: pool.clone(),
+        cache: cache.clone(),
         user_service,
         user_repo,
-        article_service,
+        preferences_repo,
+        reading_progress_repo,
+        favorite_repo,
+        article_service: article_service.clone(),
         category_service,
         tag_service,
         settings_service,
         comment_service,
         about_service,
         friend_link_service,
+        redirect_service,
+        poll_service,
+        event_service,
+        doc_service,
+        faq_service,
+        series_service,
+        featured_service,
+        attachment_service,
+        article_author_service,
+        custom_field_service,
+        reader_group_service,
+        translation_service,
+        webmention_service,
+        newsletter_service,
+        web_push_service,
+        spellcheck,
+        duplicates: duplicates.clone(),
+        link_suggestions,
+        inbound_webhooks,
+        github_publish,
+        maintenance,
+        api_exposure,
+        webauthn_service,
+        #[cfg(feature = "saml")]
+        saml_service,
         theme_engine: Arc::new(std::sync::RwLock::new(theme_engine)),
         upload_config: Arc::new(config.upload.clone()),
+        native_tls: tls.is_some(),
+        status_page: config.status_page.clone(),
         page_service,
         nav_service,
+        sync_service,
+        stats_service,
+        analytics: analytics.clone(),
+        views: views.clone(),
+        backup_service,
+        jobs: jobs.clone(),
+        job_queue: job_queue.clone(),
         plugin_manager: Arc::new(tokio::sync::RwLock::new(plugin_manager)),
         hook_manager: hook_manager.clone(),
         shortcode_manager: shortcode_manager_arc,
         request_stats,
+        admin_events,
         rate_limiter: rate_limiter.clone(),
+        api_rate_limiter: api_rate_limiter.clone(),
+        email_service,
+        ip_reputation: ip_reputation.clone(),
         captcha_pow_store,
         wasm_runtime: wasm_runtime.clone(),
         wasm_registry: wasm_registry.clone(),
         two_factor_challenges,
     };
 
-    // Start rate limiter cleanup task (runs every 5 minutes)
+    // Start rate limiter and IP reputation cleanup task (runs every 5 minutes)
     {
         let limiter = rate_limiter.clone();
+        let api_limiter = api_rate_limiter.clone();
+        let reputation = ip_reputation.clone();
+        tokio::spawn(jobs.clone().every(
+            "rate_limit_cleanup",
+            Duration::from_secs(300),
+            move || {
+                let (limiter, api_limiter, reputation) =
+                    (limiter.clone(), api_limiter.clone(), reputation.clone());
+                async move {
+                    limiter.cleanup().await;
+                    api_limiter.cleanup().await;
+                    reputation.cleanup().await;
+                    Ok(())
+                }
+            },
+        ));
+    }
+
+    // Write collected page views (analytics.enabled)
+    if config.analytics.enabled {
+        let analytics = analytics.clone();
+        tokio::spawn(jobs.clone().every(
+            "analytics_flush",
+            Duration::from_secs(config.analytics.flush_secs.max(1)),
+            move || {
+                let analytics = analytics.clone();
+                async move { analytics.flush().await }
+            },
+        ));
+    }
+
+    // Write batched article view counts
+    {
+        let (views, article_service) = (views.clone(), article_service.clone());
+        tokio::spawn(jobs.clone().every(
+            "views_flush",
+            Duration::from_secs(config.views.flush_secs.max(1)),
+            move || {
+                let (views, article_service) = (views.clone(), article_service.clone());
+                async move { flush_views(&views, &article_service).await }
+            },
+        ));
+    }
+
+    // Start job queue workers (job_queue.workers > 0), after requeueing
+    // jobs a previous process left running
+    if config.job_queue.workers > 0 {
+        if let Err(e) = job_queue.maintain().await {
+            tracing::warn!(error = %e, "job queue maintenance failed");
+        }
+        for _ in 0..config.job_queue.workers {
+            tokio::spawn(job_queue.clone().run_worker());
+        }
+        let queue = job_queue.clone();
+        tokio::spawn(jobs.clone().every(
+            "job_queue_maintenance",
+            Duration::from_secs(600),
+            move || {
+                let queue = queue.clone();
+                async move { queue.maintain().await }
+            },
+        ));
+    }
+
+    // Measure word counts of articles saved before they were stored
+    {
+        let article_service = state.article_service.clone();
         tokio::spawn(async move {
-            let mut interval = tokio::time::interval(tokio::time::Duration::from_secs(300));
-            loop {
-                interval.tick().await;
-                limiter.cleanup().await;
+            match article_service.measure_unmeasured().await {
+                Ok(0) => {}
+                Ok(count) => tracing::info!(count, "measured article word counts"),
+                Err(e) => tracing::warn!(error = %e, "failed to measure article word counts"),
             }
         });
     }
 
+    // Look for near-duplicate articles once a day
+    tokio::spawn(jobs.clone().every(
+        "duplicate_scan",
+        noteva::services::duplicates::SCAN_INTERVAL,
+        move || {
+            let duplicates = duplicates.clone();
+            async move {
+                let report = duplicates
+                    .scan(noteva::services::duplicates::DEFAULT_MIN_SIMILARITY)
+                    .await?;
+                if !report.pairs.is_empty() {
+                    tracing::info!(pairs = report.pairs.len(), "near-duplicate articles found");
+                }
+                Ok(())
+            }
+        },
+    ));
+
+    // Start scheduled backups (backup.interval_hours > 0)
+    if config.backup.interval_hours > 0 {
+        tracing::info!(
+            every_hours = config.backup.interval_hours,
+            keep = config.backup.keep,
+            "scheduled backups enabled"
+        );
+        tokio::spawn(state.backup_service.clone().run_schedule(jobs.clone()));
+    }
+
+    // Start resource self-monitoring (monitor.interval_secs > 0)
+    if config.monitor.interval_secs > 0 {
+        let mut volumes = vec![("uploads", config.upload.path.clone())];
+        if let Some(data) = config.database.data_dir() {
+            volumes.insert(0, ("data", data));
+        }
+        let monitor = Arc::new(
+            noteva::services::ResourceMonitor::new(
+                config.monitor.clone(),
+                noteva::services::ResourceSources {
+                    pool: pool.clone(),
+                    cache: cache.clone(),
+                    volumes,
+                },
+            )
+            .with_email(state.email_service.clone()),
+        );
+        let stats = state.request_stats.clone();
+        tokio::spawn(jobs.clone().every(
+            "resource_monitor",
+            Duration::from_secs(config.monitor.interval_secs),
+            move || {
+                let (monitor, stats) = (monitor.clone(), stats.clone());
+                async move { monitor.check(stats.in_flight()).await }
+            },
+        ));
+    }
+
     // Start expired session cleanup task (runs every 30 minutes)
     {
         let user_svc = state.user_service.clone();
-        tokio::spawn(async move {
-            let mut interval = tokio::time::interval(tokio::time::Duration::from_secs(1800));
-            interval.tick().await; // skip first immediate tick
-            loop {
-                interval.tick().await;
-                match user_svc.cleanup_expired_sessions().await {
-                    Ok(count) if count > 0 => {
-                        tracing::info!(deleted = count, "cleaned up expired sessions");
-                    }
-                    Err(e) => {
-                        tracing::warn!(error = %e, "failed to cleanup expired sessions");
+        tokio::spawn(
+            jobs.clone()
+                .every("session_cleanup", Duration::from_secs(1800), move || {
+                    let user_svc = user_svc.clone();
+                    async move {
+                        let count = user_svc.cleanup_expired_sessions().await?;
+                        if count > 0 {
+                            tracing::info!(deleted = count, "cleaned up expired sessions");
+                        }
+                        Ok(())
                     }
-                    _ => {}
-                }
-            }
-        });
+                }),
+        );
     }
 
     // Start plugin activation re-verification task
@@ -264,128 +681,141 @@ async fn main() -> Result<()> {
         let ss = state.settings_service.clone();
         let wr = state.wasm_runtime.clone();
         let wreg = state.wasm_registry.clone();
-        tokio::spawn(async move {
-            // Check every 10 minutes; actual per-plugin interval is tracked internally
-            let mut tick = tokio::time::interval(tokio::time::Duration::from_secs(600));
-            // Skip the first immediate tick
-            tick.tick().await;
-            loop {
-                tick.tick().await;
-                // Collect plugins that need re-verification
-                let plugins_to_check: Vec<(String, String, u64)> = {
-                    let mgr = pm.read().await;
-                    mgr.get_enabled()
-                        .iter()
-                        .filter(|p| {
-                            p.metadata.activate.interval_hours > 0
-                                && p.metadata
-                                    .hooks
-                                    .backend
-                                    .contains(&"plugin_activate".to_string())
-                        })
-                        .map(|p| {
-                            (
-                                p.metadata.id.clone(),
-                                p.metadata.version.clone(),
-                                p.metadata.activate.interval_hours,
-                            )
-                        })
-                        .collect()
-                };
+        // Check every 10 minutes; actual per-plugin interval is tracked internally
+        tokio::spawn(jobs.clone().every(
+            "plugin_reverification",
+            Duration::from_secs(600),
+            move || {
+                let (pm, hm, ss, wr, wreg) =
+                    (pm.clone(), hm.clone(), ss.clone(), wr.clone(), wreg.clone());
+                async move {
+                    // Collect plugins that need re-verification
+                    let plugins_to_check: Vec<(String, String, u64)> = {
+                        let mgr = pm.read().await;
+                        mgr.get_enabled()
+                            .iter()
+                            .filter(|p| {
+                                p.metadata.activate.interval_hours > 0
+                                    && p.metadata
+                                        .hooks
+                                        .backend
+                                        .contains(&"plugin_activate".to_string())
+                            })
+                            .map(|p| {
+                                (
+                                    p.metadata.id.clone(),
+                                    p.metadata.version.clone(),
+                                    p.metadata.activate.interval_hours,
+                                )
+                            })
+                            .collect()
+                    };
+
+                    if plugins_to_check.is_empty() {
+                        return Ok(());
+                    }
 
-                if plugins_to_check.is_empty() {
-                    continue;
-                }
+                    let site_url = ss.get("site_url").await.ok().flatten().unwrap_or_default();
+
+                    for (plugin_id, plugin_version, interval_hours) in &plugins_to_check {
+                        // Check last activation time from plugin storage
+                        let last_key = format!("__activate_last_{}", plugin_id);
+                        let last_ts: i64 = ss
+                            .get(&last_key)
+                            .await
+                            .ok()
+                            .flatten()
+                            .and_then(|v| v.parse().ok())
+                            .unwrap_or(0);
+                        let now = chrono::Utc::now().timestamp();
+                        let interval_secs = (*interval_hours as i64) * 3600;
+
+                        if now - last_ts < interval_secs {
+                            continue;
+                        }
 
-                let site_url = ss.get("site_url").await.ok().flatten().unwrap_or_default();
+                        tracing::info!(plugin_id = %plugin_id, interval_hours, "re-verifying plugin activation");
 
-                for (plugin_id, plugin_version, interval_hours) in &plugins_to_check {
-                    // Check last activation time from plugin storage
-                    let last_key = format!("__activate_last_{}", plugin_id);
-                    let last_ts: i64 = ss
-                        .get(&last_key)
-                        .await
-                        .ok()
-                        .flatten()
-                        .and_then(|v| v.parse().ok())
-                        .unwrap_or(0);
-                    let now = chrono::Utc::now().timestamp();
-                    let interval_secs = (*interval_hours as i64) * 3600;
-
-                    if now - last_ts < interval_secs {
-                        continue;
-                    }
+                        let data = serde_json::json!({
+                            "plugin_id": plugin_id,
+                            "plugin_version": plugin_version,
+                            "site_url": site_url,
+                            "timestamp": chrono::Utc::now().to_rfc3339(),
+                        });
+
+                        let result = hm.trigger(noteva::plugin::hook_names::PLUGIN_ACTIVATE, data);
+
+                        // Update last activation timestamp
+                        let _ = ss.set(&last_key, &now.to_string()).await;
 
-                    tracing::info!(plugin_id = %plugin_id, interval_hours, "re-verifying plugin activation");
-
-                    let data = serde_json::json!({
-                        "plugin_id": plugin_id,
-                        "plugin_version": plugin_version,
-                        "site_url": site_url,
-                        "timestamp": chrono::Utc::now().to_rfc3339(),
-                    });
-
-                    let result = hm.trigger(noteva::plugin::hook_names::PLUGIN_ACTIVATE, data);
-
-                    // Update last activation timestamp
-                    let _ = ss.set(&last_key, &now.to_string()).await;
-
-                    if result.get("allow").and_then(|v| v.as_bool()) == Some(false) {
-                        let message = result
-                            .get("message")
-                            .and_then(|v| v.as_str())
-                            .unwrap_or("Re-verification failed");
-                        tracing::warn!(plugin_id = %plugin_id, message = %message, "plugin re-verification failed");
-
-                        // Disable plugin: unload WASM and mark disabled
-                        let _ = noteva::plugin::wasm_bridge::unload_wasm_plugin(
-                            plugin_id, &wr, &hm, &wreg,
-                        )
-                        .await;
-                        {
-                            let mut mgr = pm.write().await;
-                            let _ = mgr.disable(plugin_id).await;
+                        if result.get("allow").and_then(|v| v.as_bool()) == Some(false) {
+                            let message = result
+                                .get("message")
+                                .and_then(|v| v.as_str())
+                                .unwrap_or("Re-verification failed");
+                            tracing::warn!(plugin_id = %plugin_id, message = %message, "plugin re-verification failed");
+
+                            // Disable plugin: unload WASM and mark disabled
+                            let _ = noteva::plugin::wasm_bridge::unload_wasm_plugin(
+                                plugin_id, &wr, &hm, &wreg,
+                            )
+                            .await;
+                            {
+                                let mut mgr = pm.write().await;
+                                let _ = mgr.disable(plugin_id).await;
+                            }
+                            tracing::warn!(plugin_id = %plugin_id, "plugin disabled due to failed re-verification");
                         }
-                        tracing::warn!(plugin_id = %plugin_id, "plugin disabled due to failed re-verification");
                     }
+                    Ok(())
                 }
-            }
-        });
+            },
+        ));
     }
     // Start scheduled publish checker + cron tick (runs every 60 seconds)
     {
         let article_svc = state.article_service.clone();
         let db_pool = pool.clone();
         let cron_hm = hook_manager.clone();
-        tokio::spawn(async move {
-            let mut interval = tokio::time::interval(tokio::time::Duration::from_secs(60));
-            interval.tick().await; // skip first immediate tick
-            loop {
-                interval.tick().await;
-                // Find draft articles scheduled for publication
-                let article_repo =
-                    noteva::db::repositories::SqlxArticleRepository::new(db_pool.clone());
-                use noteva::db::repositories::ArticleRepository;
-                if let Ok(due_articles) = article_repo.list_scheduled_due().await {
-                    for article in due_articles {
-                        tracing::info!(article_id = article.id, title = %article.title, "auto-publishing scheduled article");
-                        let mut update = noteva::models::UpdateArticleInput::new();
-                        update.status = Some(noteva::models::ArticleStatus::Published);
-                        if let Err(e) = article_svc.update(article.id, update, None).await {
-                            tracing::error!(article_id = article.id, error = %e, "failed to auto-publish scheduled article");
+        tokio::spawn(jobs.clone().every(
+            "scheduled_publish",
+            Duration::from_secs(60),
+            move || {
+                let (article_svc, db_pool, cron_hm) =
+                    (article_svc.clone(), db_pool.clone(), cron_hm.clone());
+                async move {
+                    // Find draft articles scheduled for publication
+                    let article_repo =
+                        noteva::db::repositories::SqlxArticleRepository::new(db_pool);
+                    use noteva::db::repositories::ArticleRepository;
+                    let mut failed = 0;
+                    if let Ok(due_articles) = article_repo.list_scheduled_due().await {
+                        for article in due_articles {
+                            tracing::info!(article_id = article.id, title = %article.title, "auto-publishing scheduled article");
+                            let mut update = noteva::models::UpdateArticleInput::new();
+                            update.status = Some(noteva::models::ArticleStatus::Published);
+                            if let Err(e) = article_svc.update(article.id, update, None).await {
+                                tracing::error!(article_id = article.id, error = %e, "failed to auto-publish scheduled article");
+                                failed += 1;
+                            }
                         }
                     }
-                }
 
-                // Hook: cron_tick — fire every 60s for plugins with periodic tasks
-                cron_hm.trigger(
-                    "cron_tick",
-                    serde_json::json!({
-                        "timestamp": chrono::Utc::now().to_rfc3339(),
-                    }),
-                );
-            }
-        });
+                    // Hook: cron_tick — fire every 60s for plugins with periodic tasks
+                    cron_hm.trigger(
+                        "cron_tick",
+                        serde_json::json!({
+                            "timestamp": chrono::Utc::now().to_rfc3339(),
+                        }),
+                    );
+
+                    if failed > 0 {
+                        anyhow::bail!("{} scheduled article(s) failed to publish", failed);
+                    }
+                    Ok(())
+                }
+            },
+        ));
     }
 
     // Trigger system_init hook
@@ -407,25 +837,84 @@ async fn main() -> Result<()> {
     );
 
     // Build router
-    let app = api::build_router(state, &config.server.cors_origin);
+    let app = api::build_router(state, cors_policy, &config.server.compression);
 
     // Start server with graceful shutdown
     let addr = format!("{}:{}", config.server.host, config.server.port);
     let listener = tokio::net::TcpListener::bind(&addr).await?;
-    tracing::info!(addr = %addr, "server listening");
+    let make_service = app.into_make_service_with_connect_info::<std::net::SocketAddr>();
+
+    match (tls, config.server.tls.as_ref()) {
+        (Some(tls), Some(tls_config)) => {
+            let challenges = match &tls.acme {
+                Some(acme) => {
+                    tokio::spawn(acme.clone().run());
+                    acme.challenges()
+                }
+                None => Default::default(),
+            };
+            // ACME validates domains on port 80
+            let http_port = tls_config
+                .redirect_http_port
+                .or(tls.acme.as_ref().map(|_| 80));
+            if let Some(http_port) = http_port {
+                let http_addr = format!("{}:{}", config.server.host, http_port);
+                let http_listener = tokio::net::TcpListener::bind(&http_addr).await?;
+                tracing::info!(addr = %http_addr, "redirecting HTTP to HTTPS");
+                let redirect = noteva::tls::redirect_router(config.server.port, challenges);
+                tokio::spawn(async move {
+                    if let Err(e) = axum::serve(http_listener, redirect)
+                        .with_graceful_shutdown(shutdown_signal())
+                        .await
+                    {
+                        tracing::error!(error = %e, "HTTP redirect listener failed");
+                    }
+                });
+            }
 
-    axum::serve(
-        listener,
-        app.into_make_service_with_connect_info::<std::net::SocketAddr>(),
-    )
-    .with_graceful_shutdown(shutdown_signal())
-    .await?;
+            tracing::info!(addr = %addr, "server listening (HTTPS)");
+            // tap_io lets axum's ConnectInfo<SocketAddr> impl apply to the listener
+            let listener = noteva::tls::TlsListener::new(listener, tls.acceptor)?;
+            axum::serve(listener.tap_io(|_| {}), make_service)
+                .with_graceful_shutdown(shutdown_signal())
+                .await?;
+        }
+        _ => {
+            tracing::info!(addr = %addr, "server listening");
+            axum::serve(listener, make_service)
+                .with_graceful_shutdown(shutdown_signal())
+                .await?;
+        }
+    }
 
+    // Keep the page views counted since the last scheduled flush
+    if let Err(e) = analytics.flush().await {
+        tracing::warn!(error = %e, "failed to write analytics");
+    }
+    if let Err(e) = views.flush().await {
+        tracing::warn!(error = %e, "failed to write article views");
+    }
     tracing::info!("server shut down gracefully");
     Ok(())
 }
 
-/// Wait for shutdown signal (Ctrl+C or SIGTERM)
+/// Write buffered article views and drop the cached copies of the articles
+/// whose `view_count` changed
+async fn flush_views(
+    views: &noteva::services::ViewCounter,
+    article_service: &ArticleService,
+) -> Result<()> {
+    for id in views.flush().await? {
+        if let Ok(Some(article)) = article_service.get_by_id(id).await {
+            let _ = article_service
+                .invalidate_article_cache(article.id, &article.slug)
+                .await;
+        }
+    }
+    Ok(())
+}
+
+/// Wait for shutdown signal (Ctrl+C, SIGTERM or a Windows service stop)
 async fn shutdown_signal() {
     let ctrl_c = async {
         tokio::signal::ctrl_c()
@@ -447,5 +936,6 @@ async fn shutdown_signal() {
     tokio::select! {
         _ = ctrl_c => tracing::info!("received Ctrl+C, shutting down..."),
         _ = terminate => tracing::info!("received SIGTERM, shutting down..."),
+        _ = noteva::daemon::stop_requested() => tracing::info!("service stop requested, shutting down..."),
     }
 }
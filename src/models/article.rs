@@ -4,13 +4,16 @@
 //! - `Article` entity representing a blog article
 //! - `ArticleStatus` enum for publication states
 //! - Input types for creating and updating articles
-//! - Pagination types for list queries
+//! - Pagination types for list queries (offset and keyset/cursor)
 //!
 //! Satisfies requirements:
 //! - 1.1: WHEN 用户提交新文章 THEN Article_Manager SHALL 创建文章记录并生成唯一标识符
 //! - 1.2: WHEN 用户请求文章列表 THEN Article_Manager SHALL 返回分页的文章列表，支持按时间排序
 
+use super::{ArticleReader, AuthorRole, CategoryAccess, ContentLicense, LangFilter};
 use chrono::{DateTime, Utc};
+use once_cell::sync::Lazy;
+use regex::Regex;
 use serde::{Deserialize, Serialize};
 
 /// Article entity
@@ -44,6 +47,9 @@ pub struct Article {
     /// Like count
     #[serde(default)]
     pub like_count: i64,
+    /// Number of readers who saved the article to their favorites
+    #[serde(default)]
+    pub favorite_count: i64,
     /// Comment count
     #[serde(default)]
     pub comment_count: i64,
@@ -62,6 +68,46 @@ pub struct Article {
     /// Scheduled publish timestamp (if set, article will auto-publish at this time)
     #[serde(default)]
     pub scheduled_at: Option<DateTime<Utc>>,
+    /// Markup `content` is written in
+    #[serde(default)]
+    pub input_format: InputFormat,
+    /// Title for search engines and link previews, instead of `title`
+    #[serde(default)]
+    pub meta_title: Option<String>,
+    /// Description for search engines, instead of an excerpt
+    #[serde(default)]
+    pub meta_description: Option<String>,
+    /// Canonical URL, for articles first published elsewhere
+    #[serde(default)]
+    pub canonical_url: Option<String>,
+    /// Ask search engines not to index the article
+    #[serde(default)]
+    pub noindex: bool,
+    /// Excerpt written by the author; when unset one is generated from
+    /// `content_html`, see [`Article::excerpt_text`]
+    #[serde(default)]
+    pub excerpt: Option<String>,
+    /// License chosen for the article; the site default applies when unset
+    #[serde(default)]
+    pub license: Option<ContentLicense>,
+    /// Length and reading time of the rendered content
+    #[serde(default)]
+    pub stats: ContentStats,
+    /// Body withheld from the viewer, see [`Article::redact`]; never stored
+    #[serde(default)]
+    pub redacted: bool,
+}
+
+/// Length of an article's text, measured when it is saved
+///
+/// Chinese and Japanese characters count as one word each.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ContentStats {
+    pub word_count: i64,
+    /// Characters other than whitespace
+    pub char_count: i64,
+    /// Estimated minutes to read
+    pub reading_time: i64,
 }
 
 fn default_meta() -> serde_json::Value {
@@ -100,16 +146,127 @@ impl Article {
             updated_at: now,
             view_count: 0,
             like_count: 0,
+            favorite_count: 0,
             comment_count: 0,
             thumbnail: None,
             is_pinned: false,
             pin_order: 0,
             meta: serde_json::json!({}),
             scheduled_at: None,
+            input_format: InputFormat::Markdown,
+            meta_title: None,
+            meta_description: None,
+            canonical_url: None,
+            noindex: false,
+            excerpt: None,
+            license: None,
+            stats: ContentStats::default(),
+            redacted: false,
+        }
+    }
+
+    /// Clear the body and everything derived from it, leaving the title,
+    /// status and other metadata for listings
+    pub fn redact(&mut self) {
+        self.content.clear();
+        self.content_html.clear();
+        self.excerpt = None;
+        self.meta_description = None;
+        if let Some(meta) = self.meta.as_object_mut() {
+            meta.remove("summary");
+        }
+        self.redacted = true;
+    }
+
+    /// Excerpt written by the author, or the older `summary` kept in `meta`
+    pub fn written_excerpt(&self) -> Option<&str> {
+        [
+            self.excerpt.as_deref(),
+            self.meta.get("summary").and_then(|v| v.as_str()),
+        ]
+        .into_iter()
+        .flatten()
+        .map(str::trim)
+        .find(|v| !v.is_empty())
+    }
+
+    /// The written excerpt, or one generated from the start of the rendered
+    /// content
+    pub fn excerpt_text(&self) -> String {
+        match self.written_excerpt() {
+            Some(excerpt) => excerpt.to_string(),
+            None => generate_excerpt(&self.content_html, EXCERPT_LENGTH),
         }
     }
 }
 
+/// Length in characters of generated excerpts
+pub const EXCERPT_LENGTH: usize = 200;
+
+/// Block elements that end a paragraph of the excerpt
+static BLOCK_END_RE: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"(?i)<br\s*/?>|</(p|li|blockquote|div|dd|dt|td|th)\s*>").expect("valid block regex")
+});
+
+/// Elements whose text does not belong in an excerpt
+static SKIPPED_RE: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(
+        r"(?is)<(h[1-6]|pre|script|style|figure|table|svg)\b.*?</(h[1-6]|pre|script|style|figure|table|svg)\s*>",
+    )
+    .expect("valid skipped element regex")
+});
+
+/// Tags, and a tag cut off at the end of a truncated document
+static TAG_RE: Lazy<Regex> = Lazy::new(|| Regex::new(r"(?s)<[^>]*(>|$)").expect("valid tag regex"));
+
+/// Plain-text excerpt of rendered HTML
+///
+/// Whole paragraphs are taken from the start while they fit in
+/// `max_chars`. When not even the first one fits it is cut at a word and
+/// ends in an ellipsis. Headings, code blocks, tables and figures are
+/// skipped.
+pub fn generate_excerpt(html: &str, max_chars: usize) -> String {
+    let html = SKIPPED_RE.replace_all(html, "\n");
+    let html = BLOCK_END_RE.replace_all(&html, "\n");
+    let mut excerpt = String::new();
+    for line in html.split('\n') {
+        let text = TAG_RE.replace_all(line, "");
+        let text = text
+            .replace("&nbsp;", " ")
+            .replace("&lt;", "<")
+            .replace("&gt;", ">")
+            .replace("&quot;", "\"")
+            .replace("&#39;", "'")
+            .replace("&#x27;", "'")
+            .replace("&amp;", "&");
+        let paragraph = text.split_whitespace().collect::<Vec<_>>().join(" ");
+        if paragraph.is_empty() {
+            continue;
+        }
+        if excerpt.is_empty() {
+            let Some((end, _)) = paragraph.char_indices().nth(max_chars) else {
+                excerpt = paragraph;
+                continue;
+            };
+            let cut = &paragraph[..end];
+            let cut = match cut.rfind(' ') {
+                Some(space) if space > end / 2 => &cut[..space],
+                _ => cut,
+            };
+            return format!(
+                "{}…",
+                cut.trim_end_matches(|c: char| c.is_ascii_punctuation())
+            );
+        }
+        if excerpt.chars().count() + 1 + paragraph.chars().count() > max_chars {
+            break;
+        }
+        excerpt.push(' ');
+        excerpt.push_str(&paragraph);
+    }
+    excerpt
+}
+
 /// Article publication status
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 #[serde(rename_all = "lowercase")]
@@ -155,8 +312,61 @@ impl std::fmt::Display for ArticleStatus {
     }
 }
 
+/// Markup an article's source is written in
+///
+/// The source is stored as written and rendered to HTML on save, so content
+/// migrated from forums and older blogs keeps its original syntax. BBCode and
+/// reStructuredText need the `bbcode` and `rst` cargo features. HTML and
+/// blocks are meant for block editors, which produce markup or a JSON block
+/// document directly.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum InputFormat {
+    #[default]
+    Markdown,
+    Bbcode,
+    /// reStructuredText
+    Rst,
+    /// Raw HTML, sanitized like rendered Markdown
+    Html,
+    /// JSON array of typed blocks, see `services::markup::blocks`
+    Blocks,
+}
+
+impl InputFormat {
+    /// Convert format to database string representation
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            InputFormat::Markdown => "markdown",
+            InputFormat::Bbcode => "bbcode",
+            InputFormat::Rst => "rst",
+            InputFormat::Html => "html",
+            InputFormat::Blocks => "blocks",
+        }
+    }
+
+    /// Parse format from database string representation
+    pub fn parse(s: &str) -> Option<Self> {
+        match s.to_lowercase().as_str() {
+            "markdown" | "md" => Some(InputFormat::Markdown),
+            "bbcode" => Some(InputFormat::Bbcode),
+            "rst" | "restructuredtext" => Some(InputFormat::Rst),
+            "html" => Some(InputFormat::Html),
+            "blocks" => Some(InputFormat::Blocks),
+            _ => None,
+        }
+    }
+}
+
+impl std::fmt::Display for InputFormat {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.as_str())
+    }
+}
+
 /// Sort order for article listings
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
 pub enum ArticleSortBy {
     /// Sort by published_at DESC (default)
     #[default]
@@ -165,6 +375,10 @@ pub enum ArticleSortBy {
     Views,
     /// Sort by comment_count DESC
     Comments,
+    /// Sort by title (A-Z by default)
+    Title,
+    /// Sort by updated_at DESC
+    Updated,
 }
 
 impl ArticleSortBy {
@@ -173,6 +387,8 @@ impl ArticleSortBy {
         match s.to_lowercase().as_str() {
             "views" => Self::Views,
             "comments" => Self::Comments,
+            "title" => Self::Title,
+            "updated" => Self::Updated,
             _ => Self::Date,
         }
     }
@@ -183,6 +399,27 @@ impl ArticleSortBy {
             Self::Date => "published_at DESC, created_at DESC",
             Self::Views => "view_count DESC, published_at DESC",
             Self::Comments => "comment_count DESC, published_at DESC",
+            Self::Title => "title ASC, published_at DESC",
+            Self::Updated => "updated_at DESC, published_at DESC",
+        }
+    }
+
+    /// Primary sort column for filtered listings
+    pub fn column(&self) -> &'static str {
+        match self {
+            Self::Date => "published_at",
+            Self::Views => "view_count",
+            Self::Comments => "comment_count",
+            Self::Title => "title",
+            Self::Updated => "updated_at",
+        }
+    }
+
+    /// Direction used when the client does not request one
+    pub fn default_direction(&self) -> SortDirection {
+        match self {
+            Self::Title => SortDirection::Asc,
+            _ => SortDirection::Desc,
         }
     }
 
@@ -192,10 +429,112 @@ impl ArticleSortBy {
             Self::Date => "date",
             Self::Views => "views",
             Self::Comments => "comments",
+            Self::Title => "title",
+            Self::Updated => "updated",
         }
     }
 }
 
+/// Time window for popular article rankings
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PopularWindow {
+    /// Views since the start of the UTC day N days ago
+    Days(u32),
+    /// Lifetime view count
+    AllTime,
+}
+
+impl PopularWindow {
+    /// Longest window counted from daily views
+    pub const MAX_DAYS: u32 = 365;
+
+    /// Parse `24h`, `7d`, `30d` (up to [`Self::MAX_DAYS`]) or `all`
+    ///
+    /// Views are counted per day, so `24h` is the same as `1d`.
+    pub fn parse(s: &str) -> Option<Self> {
+        let s = s.trim().to_lowercase();
+        if s == "all" {
+            return Some(Self::AllTime);
+        }
+        let days = match (s.strip_suffix('d'), s.strip_suffix('h')) {
+            (Some(days), _) => days.parse().ok()?,
+            (_, Some("24")) => 1,
+            _ => return None,
+        };
+        (1..=Self::MAX_DAYS)
+            .contains(&days)
+            .then_some(Self::Days(days))
+    }
+
+    /// Cache key suffix, also the canonical query value
+    pub fn cache_key(&self) -> String {
+        match self {
+            Self::Days(days) => format!("{}d", days),
+            Self::AllTime => "all".to_string(),
+        }
+    }
+}
+
+/// Sort direction for list queries
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SortDirection {
+    Asc,
+    #[default]
+    Desc,
+}
+
+impl SortDirection {
+    /// Parse from query string value ("asc" / "desc")
+    pub fn parse(s: &str) -> Option<Self> {
+        match s.trim().to_lowercase().as_str() {
+            "asc" => Some(Self::Asc),
+            "desc" => Some(Self::Desc),
+            _ => None,
+        }
+    }
+
+    /// SQL keyword
+    pub fn as_sql(&self) -> &'static str {
+        match self {
+            Self::Asc => "ASC",
+            Self::Desc => "DESC",
+        }
+    }
+}
+
+/// Filters for article listings, applied in SQL
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ArticleFilter {
+    pub status: Option<ArticleStatus>,
+    /// Match articles the user is credited on, primary author or not
+    pub author_id: Option<i64>,
+    /// Only credits with this role
+    #[serde(default)]
+    pub author_role: Option<AuthorRole>,
+    /// Match any of these categories (callers expand subcategories)
+    #[serde(default)]
+    pub category_ids: Vec<i64>,
+    pub tag_id: Option<i64>,
+    /// Inclusive lower bound on the article date (published, else created)
+    pub date_from: Option<DateTime<Utc>>,
+    /// Exclusive upper bound on the article date (published, else created)
+    pub date_to: Option<DateTime<Utc>>,
+    /// Only articles in this language
+    #[serde(default)]
+    pub lang: Option<LangFilter>,
+    /// Reader clearance for private categories. With a published status
+    /// filter, articles in categories above it are left out; unset is an
+    /// anonymous visitor.
+    #[serde(default)]
+    pub clearance: Option<CategoryAccess>,
+    /// Signed-in reader for audience-restricted articles. With a published
+    /// status filter, articles whose audience does not admit them are left
+    /// out; unset is an anonymous visitor.
+    #[serde(default)]
+    pub reader: Option<ArticleReader>,
+}
+
 /// Input for creating a new article
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct CreateArticleInput {
@@ -215,6 +554,22 @@ pub struct CreateArticleInput {
     pub status: Option<ArticleStatus>,
     /// Scheduled publish timestamp
     pub scheduled_at: Option<DateTime<Utc>>,
+    /// Markup of `content` (defaults to Markdown)
+    #[serde(default)]
+    pub input_format: InputFormat,
+    /// Excerpt written by the author (optional, generated when unset)
+    #[serde(default)]
+    pub excerpt: Option<String>,
+    /// License of the article (optional, the site default when unset)
+    #[serde(default)]
+    pub license: Option<ContentLicense>,
+    /// Stats of `content_html`, set together with it
+    #[serde(default)]
+    pub stats: Option<ContentStats>,
+    /// Take the slug although an earlier article used it; its old links
+    /// then lead to this article
+    #[serde(default)]
+    pub reclaim_slug: bool,
 }
 
 impl CreateArticleInput {
@@ -235,6 +590,11 @@ impl CreateArticleInput {
             category_id,
             status: None,
             scheduled_at: None,
+            input_format: InputFormat::Markdown,
+            excerpt: None,
+            license: None,
+            stats: None,
+            reclaim_slug: false,
         }
     }
 
@@ -282,6 +642,25 @@ pub struct UpdateArticleInput {
     /// - Some(Some(dt)): set a scheduled publish time
     /// - Some(None): clear the scheduled publish time
     pub scheduled_at: Option<Option<DateTime<Utc>>>,
+    /// New markup of the content (optional)
+    pub input_format: Option<InputFormat>,
+    /// SEO title patch (None keeps, Some(None) clears)
+    pub meta_title: Option<Option<String>>,
+    /// SEO description patch (None keeps, Some(None) clears)
+    pub meta_description: Option<Option<String>>,
+    /// Canonical URL patch (None keeps, Some(None) clears)
+    pub canonical_url: Option<Option<String>>,
+    /// Whether search engines should skip the article (optional)
+    pub noindex: Option<bool>,
+    /// Excerpt patch (None keeps, Some(None) goes back to a generated one)
+    pub excerpt: Option<Option<String>>,
+    /// License patch (None keeps, Some(None) goes back to the site default)
+    pub license: Option<Option<ContentLicense>>,
+    /// New stats of `content_html` (optional, set together with it)
+    pub stats: Option<ContentStats>,
+    /// Take the new slug although another article used it before
+    #[serde(default)]
+    pub reclaim_slug: bool,
 }
 
 impl UpdateArticleInput {
@@ -344,24 +723,43 @@ impl UpdateArticleInput {
             || self.is_pinned.is_some()
             || self.pin_order.is_some()
             || self.scheduled_at.is_some()
+            || self.input_format.is_some()
     }
 }
 
+/// A slug an article has had, kept for redirects and reservation
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ArticleSlug {
+    pub slug: String,
+    /// May refer to a deleted article
+    pub article_id: i64,
+    pub created_at: DateTime<Utc>,
+}
+
 /// Pagination parameters for list queries
+///
+/// Article listings also carry filters and a sort order, which
+/// `ArticleRepository::list_filtered` turns into SQL.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ListParams {
     /// Page number (1-indexed)
     pub page: u32,
     /// Number of items per page
     pub per_page: u32,
+    #[serde(default)]
+    pub filter: ArticleFilter,
+    #[serde(default)]
+    pub sort_by: ArticleSortBy,
+    #[serde(default)]
+    pub direction: SortDirection,
+    /// Leave `content` and `content_html` empty instead of loading them
+    #[serde(default)]
+    pub skip_content: bool,
 }
 
 impl Default for ListParams {
     fn default() -> Self {
-        Self {
-            page: 1,
-            per_page: 10,
-        }
+        Self::new(1, 10)
     }
 }
 
@@ -371,9 +769,32 @@ impl ListParams {
         Self {
             page: page.max(1),
             per_page: per_page.clamp(1, 100),
+            filter: ArticleFilter::default(),
+            sort_by: ArticleSortBy::default(),
+            direction: SortDirection::default(),
+            skip_content: false,
         }
     }
 
+    /// Set the article filters
+    pub fn with_filter(mut self, filter: ArticleFilter) -> Self {
+        self.filter = filter;
+        self
+    }
+
+    /// Set the sort field and direction
+    pub fn with_sort(mut self, sort_by: ArticleSortBy, direction: SortDirection) -> Self {
+        self.sort_by = sort_by;
+        self.direction = direction;
+        self
+    }
+
+    /// Skip the article bodies, for listings that only show metadata
+    pub fn without_content(mut self) -> Self {
+        self.skip_content = true;
+        self
+    }
+
     /// Calculate the offset for database queries
     pub fn offset(&self) -> i64 {
         ((self.page.saturating_sub(1)) * self.per_page) as i64
@@ -448,3 +869,126 @@ impl<T> Default for PagedResult<T> {
         }
     }
 }
+
+/// Keyset pagination position for article lists
+///
+/// Points at the last article of the previous page. Cursor lists are ordered
+/// by `created_at DESC, id DESC`, so rows inserted while a client is
+/// scrolling never shift later pages. Clients treat the encoded form as an
+/// opaque token.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ArticleCursor {
+    pub created_at: DateTime<Utc>,
+    pub id: i64,
+}
+
+impl ArticleCursor {
+    /// Cursor pointing at `article`
+    pub fn from_article(article: &Article) -> Self {
+        Self {
+            created_at: article.created_at,
+            id: article.id,
+        }
+    }
+
+    /// Encode as an opaque URL-safe token
+    pub fn encode(&self) -> String {
+        let raw = format!(
+            "{}|{}",
+            self.created_at
+                .to_rfc3339_opts(chrono::SecondsFormat::Nanos, true),
+            self.id
+        );
+        data_encoding::BASE64URL_NOPAD.encode(raw.as_bytes())
+    }
+
+    /// Decode a token produced by [`ArticleCursor::encode`]
+    pub fn decode(token: &str) -> Option<Self> {
+        let raw = data_encoding::BASE64URL_NOPAD
+            .decode(token.trim().as_bytes())
+            .ok()?;
+        let raw = String::from_utf8(raw).ok()?;
+        let (created_at, id) = raw.split_once('|')?;
+        Some(Self {
+            created_at: DateTime::parse_from_rfc3339(created_at)
+                .ok()?
+                .with_timezone(&Utc),
+            id: id.parse().ok()?,
+        })
+    }
+}
+
+/// Subset of articles a cursor list walks through
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ArticleListScope {
+    /// All articles regardless of status
+    All,
+    /// Articles with the given status; published ones leave out what the
+    /// viewer may not read, like the other published scopes
+    Status(ArticleStatus),
+    /// Articles in a category (any status)
+    Category(i64),
+    /// Articles with a tag (any status)
+    Tag(i64),
+    /// Published articles in any of the given categories that the viewer
+    /// may read
+    PublishedInCategories(Vec<i64>),
+    /// Published articles with a tag that the viewer may read
+    PublishedWithTag(i64),
+}
+
+/// Result of a cursor list query
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CursorPage<T> {
+    /// Items in the current page
+    pub items: Vec<T>,
+    /// Total number of items in the scope
+    pub total: i64,
+    /// Cursor for the next page, `None` on the last page
+    pub next_cursor: Option<String>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn excerpts_take_whole_paragraphs_that_fit() {
+        let html = "<h2>Intro</h2><p>First &amp; <em>short</em>.</p>\n<pre><code>x = 1</code></pre><ul><li>Second</li><li>one</li></ul>";
+        assert_eq!(generate_excerpt(html, 200), "First & short. Second one");
+        assert_eq!(generate_excerpt(html, 22), "First & short. Second");
+        assert_eq!(generate_excerpt(html, 14), "First & short.");
+        assert_eq!(generate_excerpt("", 200), "");
+    }
+
+    #[test]
+    fn long_paragraphs_are_cut_at_a_word() {
+        let html = "<p>one two three four five six</p>";
+        assert_eq!(generate_excerpt(html, 16), "one two three…");
+        // A tag cut off by a truncated read is dropped
+        assert_eq!(
+            generate_excerpt("<p>Hello</p><p>wor<a href=\"/x", 200),
+            "Hello wor"
+        );
+    }
+
+    #[test]
+    fn author_excerpt_wins_over_generated_one() {
+        let mut article = Article::new(
+            "post".to_string(),
+            "Post".to_string(),
+            String::new(),
+            "<p>Body text</p>".to_string(),
+            1,
+            1,
+            ArticleStatus::Draft,
+        );
+        assert_eq!(article.excerpt_text(), "Body text");
+        article.excerpt = Some("  ".to_string());
+        assert_eq!(article.excerpt_text(), "Body text");
+        article.meta = serde_json::json!({"summary": "Older summary"});
+        assert_eq!(article.excerpt_text(), "Older summary");
+        article.excerpt = Some(" Hand written ".to_string());
+        assert_eq!(article.excerpt_text(), "Hand written");
+    }
+}
@@ -0,0 +1,77 @@
+//! Article author model.
+//!
+//! An article can have several people credited with a role. The article's
+//! own `author_id` stays the primary author: the first one with the
+//! `author` role. Articles without explicit credits have that user as their
+//! only author.
+
+use serde::{Deserialize, Serialize};
+
+use super::PublicUser;
+
+/// Role a user is credited with on an article
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum AuthorRole {
+    /// Wrote the article
+    #[default]
+    Author,
+    /// Edited the article
+    Editor,
+    /// Translated the article
+    Translator,
+}
+
+impl AuthorRole {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Author => "author",
+            Self::Editor => "editor",
+            Self::Translator => "translator",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        match value.trim().to_ascii_lowercase().as_str() {
+            "author" => Some(Self::Author),
+            "editor" => Some(Self::Editor),
+            "translator" => Some(Self::Translator),
+            _ => None,
+        }
+    }
+}
+
+/// A user credited on an article
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ArticleAuthor {
+    pub user_id: i64,
+    pub username: String,
+    pub display_name: Option<String>,
+    pub avatar: Option<String>,
+    pub role: AuthorRole,
+    /// Order among the article's credits, lowest first
+    pub position: i32,
+}
+
+/// A user credited on published articles, for author listings
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PublishedAuthor {
+    #[serde(flatten)]
+    pub user: PublicUser,
+    /// Published articles the user is credited on
+    pub article_count: i64,
+}
+
+/// One credit in the body of the article authors endpoint
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct ArticleAuthorInput {
+    pub user_id: i64,
+    #[serde(default)]
+    pub role: AuthorRole,
+}
+
+/// Body of the article authors endpoint, in display order
+#[derive(Debug, Clone, Deserialize)]
+pub struct ArticleAuthorsInput {
+    pub authors: Vec<ArticleAuthorInput>,
+}
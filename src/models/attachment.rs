@@ -0,0 +1,110 @@
+//! Article attachment model.
+//!
+//! Non-image files (PDFs, archives, documents) attached to an article. They
+//! are listed through the API and rendered as a download list with the
+//! `[attachments]` shortcode. Downloads go through the API so they can be
+//! counted and, per attachment, gated behind a login or an email address.
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+/// Directory under the upload path that attachments are stored in
+pub const ATTACHMENTS_DIR: &str = "attachments";
+
+/// Who may download an attachment
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum AttachmentAccess {
+    /// Anyone
+    #[default]
+    Public,
+    /// Signed-in users
+    Login,
+    /// Visitors who leave an email address
+    Email,
+}
+
+impl AttachmentAccess {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Public => "public",
+            Self::Login => "login",
+            Self::Email => "email",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "public" => Some(Self::Public),
+            "login" => Some(Self::Login),
+            "email" => Some(Self::Email),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ArticleAttachment {
+    pub id: i64,
+    pub article_id: i64,
+    /// Original file name, offered as the download name
+    pub filename: String,
+    /// Name of the file on disk, under `uploads/attachments/`
+    #[serde(skip)]
+    pub stored_name: String,
+    /// Download URL; downloads through it are counted and gated
+    pub url: String,
+    pub content_type: String,
+    /// Size in bytes
+    pub size: i64,
+    /// Order within the article, lowest first
+    pub position: i32,
+    pub access: AttachmentAccess,
+    pub download_count: i64,
+    pub created_at: DateTime<Utc>,
+}
+
+/// Download URL of an attachment
+pub fn attachment_url(id: i64) -> String {
+    format!("/api/v1/attachments/{}/download", id)
+}
+
+/// Body of the attachment reorder endpoint
+#[derive(Debug, Clone, Deserialize)]
+pub struct AttachmentOrderInput {
+    pub attachment_ids: Vec<i64>,
+}
+
+/// Body of the attachment update endpoint
+#[derive(Debug, Clone, Deserialize)]
+pub struct AttachmentUpdateInput {
+    pub access: AttachmentAccess,
+}
+
+/// Downloads of one day
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct DailyDownloads {
+    /// `YYYY-MM-DD`, UTC
+    pub day: String,
+    pub count: i64,
+}
+
+/// An address left to download an email-gated attachment
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct DownloadLead {
+    pub email: String,
+    pub created_at: DateTime<Utc>,
+}
+
+/// Download statistics of one attachment
+#[derive(Debug, Clone, Serialize)]
+pub struct AttachmentDownloadStats {
+    pub attachment: ArticleAttachment,
+    /// Downloads per day over the requested period, oldest first; days
+    /// without downloads are left out
+    pub daily: Vec<DailyDownloads>,
+    /// Downloads by signed-in users over the same period
+    pub signed_in: i64,
+    /// Addresses left for the attachment, newest first
+    pub leads: Vec<DownloadLead>,
+}
@@ -6,8 +6,81 @@
 //! - 2.1: WHEN 用户创建分类 THEN Category_Service SHALL 创建分类记录并支持设置父分类
 //! - 2.3: WHEN 用户请求某分类下的文章 THEN Category_Service SHALL 返回该分类及其子分类下的所有文章
 
+use super::{User, UserRole};
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::str::FromStr;
+
+/// Who may read the articles in a category.
+///
+/// Levels are ordered: a reader cleared for one level can read every level
+/// below it. The setting belongs to the category alone; subcategories keep
+/// their own.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum CategoryAccess {
+    /// Everyone, including anonymous visitors
+    #[default]
+    Public,
+    /// Any signed-in user
+    Members,
+    /// Editors and admins
+    Editors,
+    /// Admins only
+    Admins,
+}
+
+impl CategoryAccess {
+    /// All levels, lowest first
+    pub const ALL: [CategoryAccess; 4] = [
+        CategoryAccess::Public,
+        CategoryAccess::Members,
+        CategoryAccess::Editors,
+        CategoryAccess::Admins,
+    ];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            CategoryAccess::Public => "public",
+            CategoryAccess::Members => "members",
+            CategoryAccess::Editors => "editors",
+            CategoryAccess::Admins => "admins",
+        }
+    }
+
+    /// The highest level `user` may read; `None` is an anonymous visitor
+    pub fn clearance(user: Option<&User>) -> Self {
+        match user.map(|u| u.role) {
+            None => CategoryAccess::Public,
+            Some(UserRole::Author) => CategoryAccess::Members,
+            Some(UserRole::Editor) => CategoryAccess::Editors,
+            Some(UserRole::Admin) => CategoryAccess::Admins,
+        }
+    }
+
+    /// Levels a reader with this clearance may read
+    pub fn readable(self) -> impl Iterator<Item = CategoryAccess> {
+        Self::ALL.into_iter().filter(move |level| *level <= self)
+    }
+}
+
+impl fmt::Display for CategoryAccess {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for CategoryAccess {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::ALL
+            .into_iter()
+            .find(|level| level.as_str() == s)
+            .ok_or_else(|| anyhow::anyhow!("Invalid category access: {}", s))
+    }
+}
 
 /// Category entity representing a hierarchical category in the blog system.
 ///
@@ -27,6 +100,9 @@ pub struct Category {
     pub parent_id: Option<i64>,
     /// Sort order within parent
     pub sort_order: i32,
+    /// Who may read the articles filed here
+    #[serde(default)]
+    pub access: CategoryAccess,
     /// Creation timestamp
     pub created_at: DateTime<Utc>,
 }
@@ -49,10 +125,16 @@ impl Category {
             description,
             parent_id,
             sort_order,
+            access: CategoryAccess::Public,
             created_at: Utc::now(),
         }
     }
 
+    /// Whether a reader with `clearance` may read this category's articles
+    pub fn readable_by(&self, clearance: CategoryAccess) -> bool {
+        self.access <= clearance
+    }
+
     /// Check if this is a root category (no parent)
     pub fn is_root(&self) -> bool {
         self.parent_id.is_none()
@@ -126,6 +208,8 @@ pub struct CreateCategoryInput {
     pub parent_id: Option<i64>,
     /// Sort order within parent
     pub sort_order: Option<i32>,
+    /// Who may read the articles filed here
+    pub access: Option<CategoryAccess>,
 }
 
 /// Input for updating a category
@@ -141,6 +225,8 @@ pub struct UpdateCategoryInput {
     pub parent_id: Option<Option<i64>>,
     /// New sort order (optional)
     pub sort_order: Option<i32>,
+    /// New access level (optional)
+    pub access: Option<CategoryAccess>,
 }
 
 #[cfg(test)]
@@ -189,6 +275,23 @@ mod tests {
         assert!(!other.is_default());
     }
 
+    #[test]
+    fn test_category_access_levels() {
+        let mut category = Category::new("c".to_string(), "C".to_string(), None, None, 0);
+        assert!(category.readable_by(CategoryAccess::clearance(None)));
+
+        category.access = "editors".parse().unwrap();
+        assert!(!category.readable_by(CategoryAccess::Public));
+        assert!(!category.readable_by(CategoryAccess::Members));
+        assert!(category.readable_by(CategoryAccess::Editors));
+        assert!(category.readable_by(CategoryAccess::Admins));
+        assert_eq!(
+            CategoryAccess::Members.readable().collect::<Vec<_>>(),
+            vec![CategoryAccess::Public, CategoryAccess::Members]
+        );
+        assert!("secret".parse::<CategoryAccess>().is_err());
+    }
+
     #[test]
     fn test_category_tree_new() {
         let category = Category::new("test".to_string(), "Test".to_string(), None, None, 0);
@@ -41,6 +41,36 @@ impl std::str::FromStr for CommentStatus {
     }
 }
 
+/// Kind of comment: a reader comment or a received Webmention
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum CommentType {
+    #[default]
+    Comment,
+    Webmention,
+}
+
+impl std::fmt::Display for CommentType {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Comment => write!(f, "comment"),
+            Self::Webmention => write!(f, "webmention"),
+        }
+    }
+}
+
+impl std::str::FromStr for CommentType {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "comment" => Ok(Self::Comment),
+            "webmention" => Ok(Self::Webmention),
+            _ => Err(format!("Invalid comment type: {}", s)),
+        }
+    }
+}
+
 /// Comment entity
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Comment {
@@ -52,6 +82,10 @@ pub struct Comment {
     pub email: Option<String>,
     pub content: String,
     pub status: CommentStatus,
+    #[serde(default)]
+    pub comment_type: CommentType,
+    /// Source page of a Webmention
+    pub source_url: Option<String>,
     pub ip_address: Option<String>,
     pub user_agent: Option<String>,
     pub created_at: DateTime<Utc>,
@@ -71,6 +105,10 @@ pub struct CommentWithMeta {
     pub email: Option<String>,
     pub content: String,
     pub status: CommentStatus,
+    #[serde(default)]
+    pub comment_type: CommentType,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub source_url: Option<String>,
     pub created_at: DateTime<Utc>,
     pub avatar_url: String,
     pub like_count: i64,
@@ -94,6 +132,32 @@ impl CommentWithMeta {
     }
 }
 
+/// Top-level comment without replies or likes, served first when threads
+/// are loaded lazily
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CommentPreview {
+    pub id: i64,
+    pub nickname: Option<String>,
+    pub content: String,
+    #[serde(default)]
+    pub comment_type: CommentType,
+    pub created_at: DateTime<Utc>,
+    pub avatar_url: String,
+    #[serde(default)]
+    pub is_author: bool,
+    /// Approved direct replies
+    pub reply_count: i64,
+}
+
+/// Comment counts of one article; spam is never counted
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CommentCounts {
+    /// Approved and pending
+    pub total: i64,
+    pub approved: i64,
+    pub pending: i64,
+}
+
 /// Input for creating a comment
 #[derive(Debug, Clone, Deserialize)]
 pub struct CreateCommentInput {
@@ -104,6 +168,45 @@ pub struct CreateCommentInput {
     pub content: String,
 }
 
+/// Filters for bulk comment exports
+#[derive(Debug, Clone, Default)]
+pub struct CommentExportFilter {
+    pub status: Option<CommentStatus>,
+    pub article_id: Option<i64>,
+    /// Inclusive lower bound on `created_at`
+    pub from: Option<DateTime<Utc>>,
+    /// Exclusive upper bound on `created_at`
+    pub to: Option<DateTime<Utc>>,
+}
+
+/// Filters for moderator comment search
+#[derive(Debug, Clone, Default)]
+pub struct CommentSearchFilter {
+    /// Full-text keyword matched against content and nickname
+    pub keyword: Option<String>,
+    /// Author email, matched case-insensitively
+    pub email: Option<String>,
+    /// Exact client IP address
+    pub ip_address: Option<String>,
+    pub status: Option<CommentStatus>,
+}
+
+/// Flat comment record produced by bulk exports
+#[derive(Debug, Clone, Serialize)]
+pub struct CommentExportRecord {
+    pub id: i64,
+    pub article_id: i64,
+    pub article_slug: Option<String>,
+    pub parent_id: Option<i64>,
+    pub user_id: Option<i64>,
+    pub nickname: Option<String>,
+    pub email: Option<String>,
+    pub content: String,
+    pub status: CommentStatus,
+    pub ip_address: Option<String>,
+    pub created_at: DateTime<Utc>,
+}
+
 /// Like target type
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 #[serde(rename_all = "lowercase")]
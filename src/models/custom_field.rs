@@ -0,0 +1,170 @@
+//! Custom field model.
+//!
+//! Key/value pairs ("post meta") attached to articles and pages for themes
+//! and plugins. Every field has a type; values are stored as text and come
+//! back as the matching JSON value.
+
+use chrono::{DateTime, NaiveDate};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::BTreeMap;
+
+/// Custom fields of one article or page, by key
+pub type CustomFieldMap = BTreeMap<String, Value>;
+
+/// Type of a custom field value
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum CustomFieldType {
+    /// Any string
+    #[default]
+    Text,
+    /// A JSON number
+    Number,
+    /// `true` or `false`
+    Boolean,
+    /// `YYYY-MM-DD` or an RFC 3339 timestamp
+    Date,
+    /// Any JSON value, e.g. a list or an object
+    Json,
+}
+
+impl CustomFieldType {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Text => "text",
+            Self::Number => "number",
+            Self::Boolean => "boolean",
+            Self::Date => "date",
+            Self::Json => "json",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "text" => Some(Self::Text),
+            "number" => Some(Self::Number),
+            "boolean" => Some(Self::Boolean),
+            "date" => Some(Self::Date),
+            "json" => Some(Self::Json),
+            _ => None,
+        }
+    }
+
+    /// Type of an untyped value: strings are text, numbers and booleans
+    /// keep their type, anything else is JSON
+    pub fn infer(value: &Value) -> Self {
+        match value {
+            Value::String(_) => Self::Text,
+            Value::Number(_) => Self::Number,
+            Value::Bool(_) => Self::Boolean,
+            _ => Self::Json,
+        }
+    }
+
+    /// Text stored for `value`, or why the value does not fit the type
+    pub fn encode(&self, value: &Value) -> Result<String, String> {
+        match (self, value) {
+            (Self::Text, Value::String(s)) => Ok(s.clone()),
+            (Self::Number, Value::Number(n)) => Ok(n.to_string()),
+            (Self::Boolean, Value::Bool(b)) => Ok(b.to_string()),
+            (Self::Date, Value::String(s)) if is_date(s.trim()) => Ok(s.trim().to_string()),
+            (Self::Date, _) => Err("expected YYYY-MM-DD or an RFC 3339 timestamp".to_string()),
+            (Self::Json, value) => Ok(value.to_string()),
+            (field_type, _) => Err(format!("expected a {} value", field_type.as_str())),
+        }
+    }
+
+    /// JSON value of stored text; text that no longer parses comes back as
+    /// a string
+    pub fn decode(&self, stored: &str) -> Value {
+        let parsed = match self {
+            Self::Text | Self::Date => None,
+            Self::Number => stored.parse::<serde_json::Number>().ok().map(Value::Number),
+            Self::Boolean => stored.parse::<bool>().ok().map(Value::Bool),
+            Self::Json => serde_json::from_str(stored).ok(),
+        };
+        parsed.unwrap_or_else(|| Value::String(stored.to_string()))
+    }
+}
+
+fn is_date(s: &str) -> bool {
+    NaiveDate::parse_from_str(s, "%Y-%m-%d").is_ok() || DateTime::parse_from_rfc3339(s).is_ok()
+}
+
+/// One custom field
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CustomField {
+    pub key: String,
+    /// Inferred from `value` when omitted
+    #[serde(rename = "type", default)]
+    pub field_type: Option<CustomFieldType>,
+    pub value: Value,
+}
+
+impl CustomField {
+    /// The field's type, inferred from its value when not given
+    pub fn resolved_type(&self) -> CustomFieldType {
+        self.field_type
+            .unwrap_or_else(|| CustomFieldType::infer(&self.value))
+    }
+}
+
+/// Body of the replace-all custom fields endpoint
+#[derive(Debug, Clone, Deserialize)]
+pub struct CustomFieldsInput {
+    pub fields: Vec<CustomField>,
+}
+
+/// Body of the single custom field endpoint; the key comes from the path
+#[derive(Debug, Clone, Deserialize)]
+pub struct CustomFieldValueInput {
+    #[serde(rename = "type", default)]
+    pub field_type: Option<CustomFieldType>,
+    pub value: Value,
+}
+
+/// Fields as a key/value map, the shape themes and API responses use
+pub fn custom_field_map(fields: Vec<CustomField>) -> CustomFieldMap {
+    fields.into_iter().map(|f| (f.key, f.value)).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn values_round_trip_through_their_type() {
+        for (field_type, value) in [
+            (CustomFieldType::Text, json!("Hello")),
+            (CustomFieldType::Number, json!(4.5)),
+            (CustomFieldType::Boolean, json!(true)),
+            (CustomFieldType::Date, json!("2026-03-01")),
+            (CustomFieldType::Json, json!({"rating": [1, 2]})),
+        ] {
+            let stored = field_type.encode(&value).unwrap();
+            assert_eq!(field_type.decode(&stored), value);
+        }
+    }
+
+    #[test]
+    fn values_must_match_their_type() {
+        assert!(CustomFieldType::Number.encode(&json!("4")).is_err());
+        assert!(CustomFieldType::Boolean.encode(&json!(1)).is_err());
+        assert!(CustomFieldType::Date.encode(&json!("next week")).is_err());
+        assert!(CustomFieldType::Date
+            .encode(&json!("2026-03-01T10:00:00+08:00"))
+            .is_ok());
+    }
+
+    #[test]
+    fn type_is_inferred_from_the_value() {
+        let field: CustomField =
+            serde_json::from_value(json!({"key": "tags", "value": ["a", "b"]})).unwrap();
+        assert_eq!(field.resolved_type(), CustomFieldType::Json);
+        let field: CustomField =
+            serde_json::from_value(json!({"key": "draft", "value": false})).unwrap();
+        assert_eq!(field.resolved_type(), CustomFieldType::Boolean);
+    }
+}
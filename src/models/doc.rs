@@ -0,0 +1,197 @@
+//! Documentation model.
+//!
+//! Docs live apart from pages, in versioned trees: each [`DocVersion`]
+//! (e.g. "v1", "v2") holds [`DocPage`]s nested through `parent_id`. One
+//! version is marked latest; older versions point search engines at it
+//! through their canonical links. Pages are served at
+//! `/docs/{version}/{slug}`, and `latest` may stand in for the version.
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+/// Version slug that always resolves to the latest version
+pub const LATEST_DOC_VERSION: &str = "latest";
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DocVersion {
+    pub id: i64,
+    /// URL segment, e.g. "v2"
+    pub slug: String,
+    /// Shown in the version switcher, e.g. "2.x"
+    pub title: String,
+    /// Switcher order, lowest first
+    pub position: i32,
+    pub is_latest: bool,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DocPage {
+    pub id: i64,
+    pub version_id: i64,
+    /// Page this one is listed under in the sidebar
+    pub parent_id: Option<i64>,
+    /// Unique within the version; the same slug across versions is the
+    /// same document
+    pub slug: String,
+    pub title: String,
+    /// Markdown source
+    pub content: String,
+    pub content_html: String,
+    /// Order among siblings, lowest first
+    pub position: i32,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl DocPage {
+    pub fn path(&self, version_slug: &str) -> String {
+        format!("/docs/{}/{}", version_slug, self.slug)
+    }
+}
+
+/// Body of both version create and update
+#[derive(Debug, Clone, Deserialize)]
+pub struct DocVersionInput {
+    pub slug: String,
+    pub title: String,
+    #[serde(default)]
+    pub position: i32,
+    /// Marking a version latest unmarks the previous one
+    #[serde(default)]
+    pub is_latest: bool,
+    /// On create, start from a copy of this version's pages
+    #[serde(default)]
+    pub copy_from: Option<i64>,
+}
+
+/// Body of both page create and update
+#[derive(Debug, Clone, Deserialize)]
+pub struct DocPageInput {
+    pub version_id: i64,
+    pub parent_id: Option<i64>,
+    pub slug: String,
+    pub title: String,
+    #[serde(default)]
+    pub content: String,
+    #[serde(default)]
+    pub position: i32,
+}
+
+/// A sidebar entry
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct DocNode {
+    pub id: i64,
+    pub slug: String,
+    pub title: String,
+    pub url: String,
+    pub children: Vec<DocNode>,
+}
+
+impl DocNode {
+    /// Entries in reading order: each page, then its children
+    pub fn flatten(nodes: &[DocNode]) -> Vec<&DocNode> {
+        let mut out = Vec::new();
+        for node in nodes {
+            out.push(node);
+            out.extend(Self::flatten(&node.children));
+        }
+        out
+    }
+}
+
+/// Sidebar tree of a version's pages, siblings by position then title.
+/// Pages whose parent is missing are listed at the top level.
+pub fn build_doc_sidebar(version_slug: &str, pages: &[DocPage]) -> Vec<DocNode> {
+    let ids: Vec<i64> = pages.iter().map(|p| p.id).collect();
+    let is_root = |page: &DocPage| {
+        page.parent_id
+            .is_none_or(|parent| parent == page.id || !ids.contains(&parent))
+    };
+    let mut visited = Vec::new();
+    let roots: Vec<&DocPage> = pages.iter().filter(|p| is_root(p)).collect();
+    children_of(version_slug, pages, roots, &mut visited)
+}
+
+fn children_of(
+    version_slug: &str,
+    pages: &[DocPage],
+    mut level: Vec<&DocPage>,
+    visited: &mut Vec<i64>,
+) -> Vec<DocNode> {
+    level.sort_by(|a, b| {
+        a.position
+            .cmp(&b.position)
+            .then_with(|| a.title.cmp(&b.title))
+    });
+    let mut nodes = Vec::new();
+    for page in level {
+        // A parent loop would otherwise recurse forever
+        if visited.contains(&page.id) {
+            continue;
+        }
+        visited.push(page.id);
+        let children: Vec<&DocPage> = pages
+            .iter()
+            .filter(|p| p.parent_id == Some(page.id) && p.id != page.id)
+            .collect();
+        nodes.push(DocNode {
+            id: page.id,
+            slug: page.slug.clone(),
+            title: page.title.clone(),
+            url: page.path(version_slug),
+            children: children_of(version_slug, pages, children, visited),
+        });
+    }
+    nodes
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn page(id: i64, parent_id: Option<i64>, slug: &str, position: i32) -> DocPage {
+        let now = Utc::now();
+        DocPage {
+            id,
+            version_id: 1,
+            parent_id,
+            slug: slug.to_string(),
+            title: slug.to_string(),
+            content: String::new(),
+            content_html: String::new(),
+            position,
+            created_at: now,
+            updated_at: now,
+        }
+    }
+
+    #[test]
+    fn sidebar_nests_and_orders_pages() {
+        let pages = [
+            page(1, None, "install", 1),
+            page(2, None, "intro", 0),
+            page(3, Some(1), "docker", 1),
+            page(4, Some(1), "binary", 0),
+            page(5, Some(99), "orphan", 5),
+        ];
+        let sidebar = build_doc_sidebar("v2", &pages);
+        let top: Vec<&str> = sidebar.iter().map(|n| n.slug.as_str()).collect();
+        assert_eq!(top, ["intro", "install", "orphan"]);
+        assert_eq!(sidebar[1].children[0].url, "/docs/v2/binary");
+
+        let order: Vec<&str> = DocNode::flatten(&sidebar)
+            .iter()
+            .map(|n| n.slug.as_str())
+            .collect();
+        assert_eq!(order, ["intro", "install", "binary", "docker", "orphan"]);
+    }
+
+    #[test]
+    fn parent_loops_do_not_hang() {
+        let pages = [page(1, Some(2), "a", 0), page(2, Some(1), "b", 0)];
+        // Neither page is a root, so the loop is simply not shown
+        assert!(build_doc_sidebar("v1", &pages).is_empty());
+    }
+}
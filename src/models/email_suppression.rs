@@ -0,0 +1,64 @@
+//! Email suppression list model
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::str::FromStr;
+
+/// Why an address is suppressed
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SuppressionReason {
+    /// The receiving server permanently rejected the address
+    HardBounce,
+    /// The recipient marked a message as spam
+    Complaint,
+    /// Added by an administrator
+    Manual,
+}
+
+impl SuppressionReason {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::HardBounce => "hard_bounce",
+            Self::Complaint => "complaint",
+            Self::Manual => "manual",
+        }
+    }
+}
+
+impl fmt::Display for SuppressionReason {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for SuppressionReason {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "hard_bounce" => Ok(Self::HardBounce),
+            "complaint" => Ok(Self::Complaint),
+            "manual" => Ok(Self::Manual),
+            other => Err(format!("Invalid suppression reason: {}", other)),
+        }
+    }
+}
+
+/// An address that outgoing email is no longer sent to
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EmailSuppression {
+    pub id: i64,
+    /// Lower-cased address
+    pub email: String,
+    pub reason: SuppressionReason,
+    /// Bounce message, provider name or admin note
+    pub detail: Option<String>,
+    pub created_at: DateTime<Utc>,
+}
+
+/// Normalize an address for suppression lookups
+pub fn normalize_email(email: &str) -> String {
+    email.trim().to_lowercase()
+}
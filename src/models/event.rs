@@ -0,0 +1,295 @@
+//! Event model.
+//!
+//! Events are dated entries such as meetups or release parties, listed by
+//! `GET /api/v1/events/upcoming` and published at `/events.ics`. A subset
+//! of iCalendar recurrence is supported: every N days, weeks, months or
+//! years, optionally until a date. Times are stored and repeated in UTC.
+
+use chrono::{DateTime, Datelike, Duration, NaiveDate, Utc};
+use serde::{Deserialize, Serialize};
+
+/// Skipped occurrences (e.g. the 31st in short months) before giving up
+const MAX_SKIPPED_OCCURRENCES: usize = 1000;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum RepeatFrequency {
+    Daily,
+    Weekly,
+    Monthly,
+    Yearly,
+}
+
+impl RepeatFrequency {
+    /// Convert frequency to database string representation
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            RepeatFrequency::Daily => "daily",
+            RepeatFrequency::Weekly => "weekly",
+            RepeatFrequency::Monthly => "monthly",
+            RepeatFrequency::Yearly => "yearly",
+        }
+    }
+
+    /// Parse frequency from database string representation
+    pub fn parse(s: &str) -> Option<Self> {
+        match s {
+            "daily" => Some(RepeatFrequency::Daily),
+            "weekly" => Some(RepeatFrequency::Weekly),
+            "monthly" => Some(RepeatFrequency::Monthly),
+            "yearly" => Some(RepeatFrequency::Yearly),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct EventRepeat {
+    pub frequency: RepeatFrequency,
+    /// Repeat every `interval` days, weeks, months or years
+    #[serde(default = "default_interval")]
+    pub interval: u32,
+    /// No occurrence starts after this time
+    pub until: Option<DateTime<Utc>>,
+}
+
+fn default_interval() -> u32 {
+    1
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Event {
+    pub id: i64,
+    pub title: String,
+    /// Plain text
+    pub description: String,
+    pub location: Option<String>,
+    /// Page with details or tickets
+    pub url: Option<String>,
+    /// Start of the first occurrence; midnight UTC for all-day events
+    pub starts_at: DateTime<Utc>,
+    /// End of the first occurrence; for all-day events, the last day
+    pub ends_at: Option<DateTime<Utc>>,
+    pub all_day: bool,
+    pub repeat: Option<EventRepeat>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+/// One occurrence of an event
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct EventOccurrence {
+    pub event_id: i64,
+    pub title: String,
+    pub description: String,
+    pub location: Option<String>,
+    pub url: Option<String>,
+    pub starts_at: DateTime<Utc>,
+    pub ends_at: DateTime<Utc>,
+    pub all_day: bool,
+    pub recurring: bool,
+}
+
+impl Event {
+    /// How long each occurrence lasts; all-day events cover whole days
+    pub fn span(&self) -> Duration {
+        if self.all_day {
+            let last_day = self.ends_at.unwrap_or(self.starts_at);
+            return last_day - self.starts_at + Duration::days(1);
+        }
+        self.ends_at
+            .map(|ends_at| ends_at - self.starts_at)
+            .unwrap_or_else(Duration::zero)
+    }
+
+    /// Occurrences still running or yet to start at `from`, earliest first
+    pub fn occurrences(&self, from: DateTime<Utc>, limit: usize) -> Vec<EventOccurrence> {
+        let span = self.span();
+        let Some(repeat) = &self.repeat else {
+            return if self.starts_at + span >= from && limit > 0 {
+                vec![self.occurrence(self.starts_at, span)]
+            } else {
+                Vec::new()
+            };
+        };
+
+        let mut occurrences = Vec::new();
+        let mut skipped = 0;
+        let mut n = first_candidate(self.starts_at, from - span, repeat);
+        while occurrences.len() < limit && skipped < MAX_SKIPPED_OCCURRENCES {
+            let Some(start) = nth_start(self.starts_at, repeat, n) else {
+                skipped += 1;
+                n += 1;
+                continue;
+            };
+            if repeat.until.is_some_and(|until| start > until) {
+                break;
+            }
+            if start + span >= from {
+                occurrences.push(self.occurrence(start, span));
+            } else {
+                skipped += 1;
+            }
+            n += 1;
+        }
+        occurrences
+    }
+
+    fn occurrence(&self, starts_at: DateTime<Utc>, span: Duration) -> EventOccurrence {
+        EventOccurrence {
+            event_id: self.id,
+            title: self.title.clone(),
+            description: self.description.clone(),
+            location: self.location.clone(),
+            url: self.url.clone(),
+            starts_at,
+            ends_at: starts_at + span,
+            all_day: self.all_day,
+            recurring: self.repeat.is_some(),
+        }
+    }
+}
+
+/// Index of the last occurrence starting before `from`, so expansion does
+/// not walk through years of past repeats
+fn first_candidate(start: DateTime<Utc>, from: DateTime<Utc>, repeat: &EventRepeat) -> i64 {
+    if from <= start {
+        return 0;
+    }
+    let interval = i64::from(repeat.interval.max(1));
+    let steps = match repeat.frequency {
+        RepeatFrequency::Daily => (from - start).num_days() / interval,
+        RepeatFrequency::Weekly => (from - start).num_weeks() / interval,
+        RepeatFrequency::Monthly | RepeatFrequency::Yearly => {
+            let months = i64::from(from.year() - start.year()) * 12 + i64::from(from.month())
+                - i64::from(start.month());
+            months / months_per_step(repeat)
+        }
+    };
+    (steps - 1).max(0)
+}
+
+fn months_per_step(repeat: &EventRepeat) -> i64 {
+    let interval = i64::from(repeat.interval.max(1));
+    match repeat.frequency {
+        RepeatFrequency::Yearly => interval * 12,
+        _ => interval,
+    }
+}
+
+/// Start of the `n`-th repeat, or `None` when that month lacks the day
+fn nth_start(start: DateTime<Utc>, repeat: &EventRepeat, n: i64) -> Option<DateTime<Utc>> {
+    let interval = i64::from(repeat.interval.max(1));
+    match repeat.frequency {
+        RepeatFrequency::Daily => Some(start + Duration::days(n * interval)),
+        RepeatFrequency::Weekly => Some(start + Duration::weeks(n * interval)),
+        RepeatFrequency::Monthly | RepeatFrequency::Yearly => {
+            let month0 = i64::from(start.month0()) + n * months_per_step(repeat);
+            let year = i32::try_from(i64::from(start.year()) + month0.div_euclid(12)).ok()?;
+            let month = u32::try_from(month0.rem_euclid(12)).ok()? + 1;
+            let date = NaiveDate::from_ymd_opt(year, month, start.day())?;
+            Some(date.and_time(start.time()).and_utc())
+        }
+    }
+}
+
+/// Body of both create and update: events are replaced as a whole, so a
+/// field is cleared by sending `null`
+#[derive(Debug, Clone, Deserialize)]
+pub struct EventInput {
+    pub title: String,
+    #[serde(default)]
+    pub description: String,
+    pub location: Option<String>,
+    pub url: Option<String>,
+    pub starts_at: DateTime<Utc>,
+    pub ends_at: Option<DateTime<Utc>>,
+    #[serde(default)]
+    pub all_day: bool,
+    pub repeat: Option<EventRepeat>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    fn event(starts_at: DateTime<Utc>, repeat: Option<EventRepeat>) -> Event {
+        Event {
+            id: 1,
+            title: "Meetup".to_string(),
+            description: String::new(),
+            location: None,
+            url: None,
+            starts_at,
+            ends_at: Some(starts_at + Duration::hours(2)),
+            all_day: false,
+            repeat,
+            created_at: starts_at,
+            updated_at: starts_at,
+        }
+    }
+
+    fn at(y: i32, m: u32, d: u32, h: u32) -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(y, m, d, h, 0, 0).unwrap()
+    }
+
+    #[test]
+    fn single_event_is_upcoming_until_it_ends() {
+        let meetup = event(at(2026, 3, 1, 18), None);
+        assert_eq!(meetup.occurrences(at(2026, 3, 1, 19), 10).len(), 1);
+        assert!(meetup.occurrences(at(2026, 3, 1, 21), 10).is_empty());
+    }
+
+    #[test]
+    fn weekly_repeats_skip_ahead_and_stop_at_until() {
+        let repeat = EventRepeat {
+            frequency: RepeatFrequency::Weekly,
+            interval: 2,
+            until: Some(at(2026, 2, 1, 0)),
+        };
+        let meetup = event(at(2025, 1, 6, 18), Some(repeat));
+        let starts: Vec<_> = meetup
+            .occurrences(at(2026, 1, 1, 0), 10)
+            .into_iter()
+            .map(|o| o.starts_at)
+            .collect();
+        assert_eq!(
+            starts,
+            [at(2026, 1, 5, 18), at(2026, 1, 19, 18)],
+            "every other Monday in January"
+        );
+    }
+
+    #[test]
+    fn monthly_repeats_skip_months_without_the_day() {
+        let repeat = EventRepeat {
+            frequency: RepeatFrequency::Monthly,
+            interval: 1,
+            until: None,
+        };
+        let meetup = event(at(2026, 1, 31, 18), Some(repeat));
+        let starts: Vec<_> = meetup
+            .occurrences(at(2026, 1, 1, 0), 3)
+            .into_iter()
+            .map(|o| o.starts_at)
+            .collect();
+        assert_eq!(
+            starts,
+            [
+                at(2026, 1, 31, 18),
+                at(2026, 3, 31, 18),
+                at(2026, 5, 31, 18)
+            ]
+        );
+    }
+
+    #[test]
+    fn all_day_events_cover_their_last_day() {
+        let mut festival = event(at(2026, 7, 1, 0), None);
+        festival.all_day = true;
+        festival.ends_at = Some(at(2026, 7, 3, 0));
+        assert_eq!(festival.span(), Duration::days(3));
+        assert_eq!(festival.occurrences(at(2026, 7, 3, 23), 1).len(), 1);
+    }
+}
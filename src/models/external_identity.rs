@@ -0,0 +1,49 @@
+//! External identity model
+//!
+//! Binds an account at a single sign-on identity provider or directory to a
+//! local user. Sign-ins through SAML or LDAP resolve the local account through
+//! this binding only, never by matching usernames.
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+/// Provider name for SAML identity providers
+pub const PROVIDER_SAML: &str = "saml";
+
+/// Provider name for the LDAP / Active Directory backend
+pub const PROVIDER_LDAP: &str = "ldap";
+
+/// A local user's account at an external identity provider
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ExternalIdentity {
+    /// `saml` or `ldap`
+    pub provider: String,
+    /// IdP entity ID for SAML; empty for LDAP
+    pub issuer: String,
+    /// NameID for SAML; objectGUID, entryUUID or DN for LDAP
+    pub subject: String,
+    pub user_id: i64,
+    /// Whether the provider's role is applied on every sign-in. Only set for
+    /// accounts the provider created; adopted local accounts keep their role.
+    pub manages_role: bool,
+    pub created_at: DateTime<Utc>,
+}
+
+impl ExternalIdentity {
+    pub fn new(
+        provider: &str,
+        issuer: impl Into<String>,
+        subject: impl Into<String>,
+        user_id: i64,
+        manages_role: bool,
+    ) -> Self {
+        Self {
+            provider: provider.to_string(),
+            issuer: issuer.into(),
+            subject: subject.into(),
+            user_id,
+            manages_role,
+            created_at: Utc::now(),
+        }
+    }
+}
@@ -0,0 +1,64 @@
+//! FAQ model.
+//!
+//! Question/answer pairs grouped into topics (e.g. "Billing", "Accounts").
+//! A topic is shown on its own through the API or embedded in articles with
+//! `[faq topic="billing"]`.
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FaqTopic {
+    pub id: i64,
+    /// Referenced by the `[faq]` shortcode and `/api/v1/faq/{slug}`
+    pub slug: String,
+    pub title: String,
+    pub description: String,
+    /// Order among topics, lowest first
+    pub position: i32,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FaqItem {
+    pub id: i64,
+    pub topic_id: i64,
+    pub question: String,
+    /// Markdown source
+    pub answer: String,
+    pub answer_html: String,
+    /// Order within the topic, lowest first
+    pub position: i32,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+/// A topic with its questions in order
+#[derive(Debug, Clone, Serialize)]
+pub struct FaqTopicWithItems {
+    #[serde(flatten)]
+    pub topic: FaqTopic,
+    pub items: Vec<FaqItem>,
+}
+
+/// Body of both topic create and update
+#[derive(Debug, Clone, Deserialize)]
+pub struct FaqTopicInput {
+    pub slug: String,
+    pub title: String,
+    #[serde(default)]
+    pub description: String,
+    #[serde(default)]
+    pub position: i32,
+}
+
+/// Body of both question create and update
+#[derive(Debug, Clone, Deserialize)]
+pub struct FaqItemInput {
+    pub topic_id: i64,
+    pub question: String,
+    pub answer: String,
+    #[serde(default)]
+    pub position: i32,
+}
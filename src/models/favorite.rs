@@ -0,0 +1,21 @@
+//! Reader favorites model
+//!
+//! Each user has one list of saved ("read later") articles, private unless
+//! the user makes it public.
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+/// A published article in a user's favorites
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FavoriteArticle {
+    pub article_id: i64,
+    pub slug: String,
+    pub title: String,
+    pub thumbnail: Option<String>,
+    pub published_at: Option<DateTime<Utc>>,
+    /// Readers who saved the article, this user included
+    pub favorite_count: i64,
+    /// When the user saved the article
+    pub favorited_at: DateTime<Utc>,
+}
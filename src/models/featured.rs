@@ -0,0 +1,44 @@
+//! Featured article model.
+//!
+//! The featured list is a hand-picked, hand-ordered run of articles for
+//! hero sections, independent of pinning and publish dates. Each entry can
+//! expire, after which it drops off the public list but stays visible to
+//! admins until removed.
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+/// An entry of the featured list, with the article it points to
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FeaturedArticle {
+    pub article_id: i64,
+    pub slug: String,
+    pub title: String,
+    pub status: String,
+    /// Order on the list, lowest first
+    pub position: i32,
+    /// When the entry leaves the public list; `None` keeps it until removed
+    pub expires_at: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>,
+}
+
+impl FeaturedArticle {
+    /// Whether the entry has passed its expiry at `now`
+    pub fn is_expired(&self, now: DateTime<Utc>) -> bool {
+        self.expires_at.is_some_and(|at| at <= now)
+    }
+}
+
+/// One entry of a new featured list
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct FeaturedItemInput {
+    pub article_id: i64,
+    #[serde(default)]
+    pub expires_at: Option<DateTime<Utc>>,
+}
+
+/// New featured list, in display order
+#[derive(Debug, Clone, Deserialize)]
+pub struct FeaturedInput {
+    pub items: Vec<FeaturedItemInput>,
+}
@@ -0,0 +1,73 @@
+//! Inbound webhook model
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::str::FromStr;
+
+/// How requests to an inbound webhook are authenticated besides the token
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SignatureScheme {
+    /// The URL token alone (Zapier, IFTTT)
+    #[default]
+    None,
+    /// GitHub's `X-Hub-Signature-256: sha256=<hex>`
+    Github,
+    /// HMAC-SHA256 of the body in `X-Signature`, hex encoded
+    HmacSha256,
+}
+
+impl SignatureScheme {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::None => "none",
+            Self::Github => "github",
+            Self::HmacSha256 => "hmac_sha256",
+        }
+    }
+}
+
+impl fmt::Display for SignatureScheme {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for SignatureScheme {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "none" => Ok(Self::None),
+            "github" => Ok(Self::Github),
+            "hmac_sha256" => Ok(Self::HmacSha256),
+            other => Err(format!("Invalid signature scheme: {}", other)),
+        }
+    }
+}
+
+/// An endpoint third parties can post events to
+#[derive(Debug, Clone, Serialize)]
+pub struct InboundWebhook {
+    pub id: i64,
+    /// Name passed to plugins so they can pick their events
+    pub name: String,
+    /// Secret path segment of `/api/v1/hooks/in/{token}`
+    pub token: String,
+    pub signature: SignatureScheme,
+    /// Signing secret; never returned by the API
+    #[serde(skip)]
+    pub secret: Option<String>,
+    pub last_received_at: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>,
+}
+
+/// Input for creating an inbound webhook
+#[derive(Debug, Clone, Deserialize)]
+pub struct CreateInboundWebhookInput {
+    pub name: String,
+    #[serde(default)]
+    pub signature: SignatureScheme,
+    pub secret: Option<String>,
+}
@@ -0,0 +1,240 @@
+//! Content license model.
+//!
+//! The terms an article may be reused under: one of the Creative Commons
+//! 4.0 licenses, CC0, all rights reserved, or a custom license with its own
+//! name and link. Articles without a license of their own use the site
+//! default.
+
+use serde::{Deserialize, Serialize};
+
+/// Kind of license an article is published under
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum LicenseKind {
+    /// Attribution
+    CcBy,
+    /// Attribution-ShareAlike
+    CcBySa,
+    /// Attribution-NoDerivatives
+    CcByNd,
+    /// Attribution-NonCommercial
+    CcByNc,
+    /// Attribution-NonCommercial-ShareAlike
+    CcByNcSa,
+    /// Attribution-NonCommercial-NoDerivatives
+    CcByNcNd,
+    /// Public domain dedication
+    Cc0,
+    #[default]
+    AllRightsReserved,
+    /// Named by the author, optionally with a link to its terms
+    Custom,
+}
+
+impl LicenseKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::CcBy => "cc-by",
+            Self::CcBySa => "cc-by-sa",
+            Self::CcByNd => "cc-by-nd",
+            Self::CcByNc => "cc-by-nc",
+            Self::CcByNcSa => "cc-by-nc-sa",
+            Self::CcByNcNd => "cc-by-nc-nd",
+            Self::Cc0 => "cc0",
+            Self::AllRightsReserved => "all-rights-reserved",
+            Self::Custom => "custom",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "cc-by" => Some(Self::CcBy),
+            "cc-by-sa" => Some(Self::CcBySa),
+            "cc-by-nd" => Some(Self::CcByNd),
+            "cc-by-nc" => Some(Self::CcByNc),
+            "cc-by-nc-sa" => Some(Self::CcByNcSa),
+            "cc-by-nc-nd" => Some(Self::CcByNcNd),
+            "cc0" => Some(Self::Cc0),
+            "all-rights-reserved" => Some(Self::AllRightsReserved),
+            "custom" => Some(Self::Custom),
+            _ => None,
+        }
+    }
+
+    /// Short name readers know the license by; empty for custom licenses
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::CcBy => "CC BY 4.0",
+            Self::CcBySa => "CC BY-SA 4.0",
+            Self::CcByNd => "CC BY-ND 4.0",
+            Self::CcByNc => "CC BY-NC 4.0",
+            Self::CcByNcSa => "CC BY-NC-SA 4.0",
+            Self::CcByNcNd => "CC BY-NC-ND 4.0",
+            Self::Cc0 => "CC0 1.0",
+            Self::AllRightsReserved => "All rights reserved",
+            Self::Custom => "",
+        }
+    }
+
+    /// Legal code of the license, for `rel="license"` links
+    pub fn url(&self) -> Option<&'static str> {
+        match self {
+            Self::CcBy => Some("https://creativecommons.org/licenses/by/4.0/"),
+            Self::CcBySa => Some("https://creativecommons.org/licenses/by-sa/4.0/"),
+            Self::CcByNd => Some("https://creativecommons.org/licenses/by-nd/4.0/"),
+            Self::CcByNc => Some("https://creativecommons.org/licenses/by-nc/4.0/"),
+            Self::CcByNcSa => Some("https://creativecommons.org/licenses/by-nc-sa/4.0/"),
+            Self::CcByNcNd => Some("https://creativecommons.org/licenses/by-nc-nd/4.0/"),
+            Self::Cc0 => Some("https://creativecommons.org/publicdomain/zero/1.0/"),
+            Self::AllRightsReserved | Self::Custom => None,
+        }
+    }
+}
+
+/// A license as chosen by the author or set as the site default
+///
+/// `name` and `url` are only read for custom licenses; the others have
+/// fixed ones.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ContentLicense {
+    pub kind: LicenseKind,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub url: Option<String>,
+}
+
+impl ContentLicense {
+    pub fn new(kind: LicenseKind) -> Self {
+        Self {
+            kind,
+            name: None,
+            url: None,
+        }
+    }
+
+    /// Trim the custom name and link and drop them from other kinds, or
+    /// tell why the license cannot be used
+    pub fn normalize(self) -> Result<Self, String> {
+        if self.kind != LicenseKind::Custom {
+            return Ok(Self::new(self.kind));
+        }
+        let trimmed = |value: Option<String>| {
+            value
+                .map(|v| v.trim().to_string())
+                .filter(|v| !v.is_empty())
+        };
+        let name = trimmed(self.name).ok_or("A custom license needs a name")?;
+        if name.chars().count() > 100 {
+            return Err("License name cannot exceed 100 characters".to_string());
+        }
+        let url = trimmed(self.url);
+        if let Some(url) = &url {
+            let valid = url.starts_with('/') && !url.starts_with("//")
+                || url.starts_with("https://")
+                || url.starts_with("http://");
+            if !valid || url.chars().count() > 500 {
+                return Err("License URL must be a site path or an http(s) URL".to_string());
+            }
+        }
+        Ok(Self {
+            kind: LicenseKind::Custom,
+            name: Some(name),
+            url,
+        })
+    }
+
+    /// Display name
+    pub fn name(&self) -> &str {
+        match self.kind {
+            LicenseKind::Custom => self.name.as_deref().unwrap_or_default(),
+            kind => kind.name(),
+        }
+    }
+
+    /// Link to the license terms, if there is one
+    pub fn url(&self) -> Option<&str> {
+        match self.kind {
+            LicenseKind::Custom => self.url.as_deref(),
+            kind => kind.url(),
+        }
+    }
+
+    /// The license as returned by the API
+    pub fn info(&self, inherited: bool) -> LicenseInfo {
+        LicenseInfo {
+            kind: self.kind,
+            name: self.name().to_string(),
+            url: self.url().map(str::to_string),
+            inherited,
+        }
+    }
+}
+
+/// License of an article as returned by the API
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LicenseInfo {
+    pub kind: LicenseKind,
+    pub name: String,
+    pub url: Option<String>,
+    /// Whether the article uses the site default rather than its own
+    pub inherited: bool,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn kinds_round_trip_through_their_names() {
+        for kind in [
+            LicenseKind::CcBy,
+            LicenseKind::CcByNcSa,
+            LicenseKind::Cc0,
+            LicenseKind::AllRightsReserved,
+            LicenseKind::Custom,
+        ] {
+            assert_eq!(LicenseKind::parse(kind.as_str()), Some(kind));
+            assert_eq!(
+                serde_json::to_value(kind).unwrap(),
+                serde_json::json!(kind.as_str())
+            );
+        }
+        assert_eq!(LicenseKind::parse("gpl"), None);
+    }
+
+    #[test]
+    fn custom_licenses_need_a_name() {
+        let custom = |name: &str, url: Option<&str>| ContentLicense {
+            kind: LicenseKind::Custom,
+            name: Some(name.to_string()),
+            url: url.map(str::to_string),
+        };
+        let license = custom(" Reuse with credit ", Some(" /reuse "))
+            .normalize()
+            .unwrap();
+        assert_eq!(license.name(), "Reuse with credit");
+        assert_eq!(license.url(), Some("/reuse"));
+        assert!(custom("  ", None).normalize().is_err());
+        assert!(custom("Mine", Some("javascript:alert(1)"))
+            .normalize()
+            .is_err());
+
+        // Other kinds keep their own name and link
+        let cc = ContentLicense {
+            kind: LicenseKind::CcBySa,
+            name: Some("Mine".to_string()),
+            url: None,
+        }
+        .normalize()
+        .unwrap();
+        assert_eq!(cc, ContentLicense::new(LicenseKind::CcBySa));
+        let info = cc.info(true);
+        assert_eq!(info.name, "CC BY-SA 4.0");
+        assert_eq!(
+            info.url.as_deref(),
+            Some("https://creativecommons.org/licenses/by-sa/4.0/")
+        );
+        assert!(info.inherited);
+    }
+}
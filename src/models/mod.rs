@@ -2,39 +2,116 @@
 //!
 //! This module contains all data structures used throughout the Noteva blog system.
 //! Models represent:
-//! - Database entities (Article, Category, Tag, User, Session, Comment, Page, NavItem)
+//! - Database entities (Article, Category, Tag, User, Session, Comment, Page, NavItem, WebauthnCredential,
+//!   EmailSuppression, InboundWebhook, QueuedJob, Subscriber, PushSubscription, ReadingProgress,
+//!   FavoriteArticle, Redirect, Poll, Event, DocVersion, DocPage, FaqTopic, FaqItem,
+//!   ContentTranslation, Series, ArticleAttachment, ArticleAuthor, CustomField, FeaturedArticle,
+//!   ReaderGroup)
 //! - API request/response types
 //! - Internal data transfer objects
 
 mod about;
 mod article;
+mod article_author;
+mod attachment;
 mod category;
 mod comment;
+mod custom_field;
+mod doc;
+mod email_suppression;
+mod event;
+mod external_identity;
+mod faq;
+mod favorite;
+mod featured;
 mod friend_link;
+mod inbound_webhook;
+mod license;
 mod nav_item;
 mod page;
+mod poll;
+mod push_subscription;
+mod queued_job;
+mod reader_group;
+mod reading_progress;
+mod redirect;
+mod series;
 mod session;
+mod subscriber;
 mod tag;
+mod translation;
 mod user;
+mod user_preferences;
+mod webauthn;
 
 pub use about::{AboutProfile, AboutSocialLink, AboutTimelineItem};
 pub use article::{
-    Article, ArticleSortBy, ArticleStatus, CreateArticleInput, ListParams, PagedResult,
-    UpdateArticleInput,
+    Article, ArticleCursor, ArticleFilter, ArticleListScope, ArticleSlug, ArticleSortBy,
+    ArticleStatus, ContentStats, CreateArticleInput, CursorPage, InputFormat, ListParams,
+    PagedResult, PopularWindow, SortDirection, UpdateArticleInput,
+};
+pub use article_author::{
+    ArticleAuthor, ArticleAuthorInput, ArticleAuthorsInput, AuthorRole, PublishedAuthor,
+};
+pub use attachment::{
+    attachment_url, ArticleAttachment, AttachmentAccess, AttachmentDownloadStats,
+    AttachmentOrderInput, AttachmentUpdateInput, DailyDownloads, DownloadLead, ATTACHMENTS_DIR,
+};
+pub use category::{
+    Category, CategoryAccess, CategoryTree, CreateCategoryInput, UpdateCategoryInput,
 };
-pub use category::{Category, CategoryTree, CreateCategoryInput, UpdateCategoryInput};
 pub use comment::{
-    Comment, CommentStatus, CommentWithMeta, CreateCommentInput, Like, LikeTargetType,
+    Comment, CommentCounts, CommentExportFilter, CommentExportRecord, CommentPreview,
+    CommentSearchFilter, CommentStatus, CommentType, CommentWithMeta, CreateCommentInput, Like,
+    LikeTargetType,
+};
+pub use custom_field::{
+    custom_field_map, CustomField, CustomFieldMap, CustomFieldType, CustomFieldValueInput,
+    CustomFieldsInput,
+};
+pub use doc::{
+    build_doc_sidebar, DocNode, DocPage, DocPageInput, DocVersion, DocVersionInput,
+    LATEST_DOC_VERSION,
 };
+pub use email_suppression::{normalize_email, EmailSuppression, SuppressionReason};
+pub use event::{Event, EventInput, EventOccurrence, EventRepeat, RepeatFrequency};
+pub use external_identity::{ExternalIdentity, PROVIDER_LDAP, PROVIDER_SAML};
+pub use faq::{FaqItem, FaqItemInput, FaqTopic, FaqTopicInput, FaqTopicWithItems};
+pub use favorite::FavoriteArticle;
+pub use featured::{FeaturedArticle, FeaturedInput, FeaturedItemInput};
 pub use friend_link::{
     CreateFriendLinkInput, FriendLink, FriendLinkOrderItem, FriendLinkStatus,
     UpdateFriendLinkInput, UpdateFriendLinkOrderInput,
 };
+pub use inbound_webhook::{CreateInboundWebhookInput, InboundWebhook, SignatureScheme};
+pub use license::{ContentLicense, LicenseInfo, LicenseKind};
 pub use nav_item::{
     CreateNavItemInput, NavItem, NavItemTree, NavItemType, NavOrderItem, UpdateNavItemInput,
     UpdateNavOrderInput,
 };
 pub use page::{CreatePageInput, Page, PageStatus, UpdatePageInput};
+pub use poll::{CreatePollInput, Poll, PollOption, UpdatePollInput};
+pub use push_subscription::PushSubscription;
+pub use queued_job::{QueuedJob, QueuedJobStatus};
+pub use reader_group::{
+    ArticleAudience, ArticleReader, ReaderGroup, ReaderGroupInput, ReaderGroupMembersInput,
+};
+pub use reading_progress::ReadingProgress;
+pub use redirect::{
+    normalize_redirect_path, CreateRedirectInput, Redirect, UpdateRedirectInput,
+    DEFAULT_REDIRECT_STATUS, REDIRECT_STATUS_CODES,
+};
+pub use series::{
+    Series, SeriesArticle, SeriesArticlesInput, SeriesInput, SeriesNavigation, SeriesWithArticles,
+};
 pub use session::Session;
+pub use subscriber::{NewsletterIssue, Subscriber, SubscriberStatus};
 pub use tag::{Tag, TagWithCount};
-pub use user::{CreateUserInput, UpdateUserInput, User, UserRole, UserStatus};
+pub use translation::{
+    normalize_lang, ContentAlternate, ContentKind, ContentTranslation, LangFilter, TranslationInput,
+};
+pub use user::{CreateUserInput, PublicUser, UpdateUserInput, User, UserRole, UserStatus};
+pub use user_preferences::{
+    EditorPreferences, ListDensity, UserPreferences, MAX_PREFERENCES_BYTES,
+};
+pub use webauthn::{CreateWebauthnCredentialInput, WebauthnCredential, MAX_PASSKEY_NAME_LEN};
@@ -0,0 +1,72 @@
+//! Poll model.
+//!
+//! Polls are created in the admin panel and placed in articles with the
+//! `[poll id=N]` shortcode. Each reader votes once per poll; readers are
+//! told apart by account, or by IP and user agent when logged out.
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Deserializer, Serialize};
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Poll {
+    pub id: i64,
+    pub question: String,
+    /// Whether a reader may pick more than one option
+    pub multiple: bool,
+    /// No votes are taken after this time
+    pub closes_at: Option<DateTime<Utc>>,
+    pub options: Vec<PollOption>,
+    /// Number of readers who voted
+    pub total_voters: i64,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PollOption {
+    pub id: i64,
+    pub label: String,
+    pub votes: i64,
+}
+
+impl Poll {
+    pub fn is_closed(&self, now: DateTime<Utc>) -> bool {
+        self.closes_at.is_some_and(|closes_at| closes_at <= now)
+    }
+
+    /// Share of voters who picked an option, rounded to whole percent
+    pub fn percent(&self, option: &PollOption) -> u32 {
+        if self.total_voters <= 0 {
+            return 0;
+        }
+        ((option.votes as f64 / self.total_voters as f64) * 100.0).round() as u32
+    }
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct CreatePollInput {
+    pub question: String,
+    pub options: Vec<String>,
+    #[serde(default)]
+    pub multiple: bool,
+    pub closes_at: Option<DateTime<Utc>>,
+}
+
+/// Options are fixed once created so existing votes keep their meaning
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct UpdatePollInput {
+    pub question: Option<String>,
+    pub multiple: Option<bool>,
+    /// `null` reopens a closed poll
+    #[serde(default, deserialize_with = "deserialize_patch")]
+    pub closes_at: Option<Option<DateTime<Utc>>>,
+}
+
+/// Tell a `null` field (`Some(None)`) apart from a missing one (`None`)
+fn deserialize_patch<'de, D, T>(deserializer: D) -> Result<Option<Option<T>>, D::Error>
+where
+    D: Deserializer<'de>,
+    T: Deserialize<'de>,
+{
+    Option::<T>::deserialize(deserializer).map(Some)
+}
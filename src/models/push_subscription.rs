@@ -0,0 +1,19 @@
+//! Web Push subscription model
+
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+
+/// A browser's push subscription (the `PushSubscription` of the Push API)
+#[derive(Debug, Clone, Serialize)]
+pub struct PushSubscription {
+    pub id: i64,
+    /// Push service URL notifications are posted to
+    pub endpoint: String,
+    /// Browser's P-256 public key, base64url
+    #[serde(skip_serializing)]
+    pub p256dh: String,
+    /// Authentication secret, base64url
+    #[serde(skip_serializing)]
+    pub auth: String,
+    pub created_at: DateTime<Utc>,
+}
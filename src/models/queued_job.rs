@@ -0,0 +1,75 @@
+//! Persistent job queue model
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::str::FromStr;
+
+/// Lifecycle of a queued job
+///
+/// A failed attempt puts the job back to `pending` with a later `run_at`
+/// until its attempts run out; then it is `dead` and waits for a manual retry.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum QueuedJobStatus {
+    /// Waiting for `run_at`
+    Pending,
+    /// Claimed by a worker
+    Running,
+    Succeeded,
+    /// Every attempt failed (dead letter)
+    Dead,
+}
+
+impl QueuedJobStatus {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Pending => "pending",
+            Self::Running => "running",
+            Self::Succeeded => "succeeded",
+            Self::Dead => "dead",
+        }
+    }
+}
+
+impl fmt::Display for QueuedJobStatus {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for QueuedJobStatus {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "pending" => Ok(Self::Pending),
+            "running" => Ok(Self::Running),
+            "succeeded" => Ok(Self::Succeeded),
+            "dead" => Ok(Self::Dead),
+            other => Err(format!("Invalid job status: {}", other)),
+        }
+    }
+}
+
+/// A job stored in the `job_queue` table
+#[derive(Debug, Clone, Serialize)]
+pub struct QueuedJob {
+    pub id: i64,
+    /// Handler that runs the job, e.g. `webmention_verify`
+    pub kind: String,
+    /// Input passed to the handler
+    pub payload: serde_json::Value,
+    pub status: QueuedJobStatus,
+    /// Attempts started so far
+    pub attempts: u32,
+    pub max_attempts: u32,
+    /// Earliest time of the next attempt
+    pub run_at: DateTime<Utc>,
+    /// When a worker claimed the job, while running
+    pub locked_at: Option<DateTime<Utc>>,
+    /// Error of the last failed attempt
+    pub last_error: Option<String>,
+    pub created_at: DateTime<Utc>,
+    pub finished_at: Option<DateTime<Utc>>,
+}
@@ -0,0 +1,147 @@
+//! Reader group and article audience model.
+//!
+//! Reader groups are named sets of users (e.g. "Staff", "Beta readers").
+//! An article's audience lists the roles and groups allowed to read it;
+//! an article without an audience is open to everyone who can read its
+//! category.
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use super::{User, UserRole};
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ReaderGroup {
+    pub id: i64,
+    pub name: String,
+    pub slug: String,
+    pub description: Option<String>,
+    /// Users in the group
+    #[serde(default)]
+    pub member_count: i64,
+    pub created_at: DateTime<Utc>,
+}
+
+/// Body of both group create and update
+#[derive(Debug, Clone, Deserialize)]
+pub struct ReaderGroupInput {
+    pub name: String,
+    /// Generated from the name when missing
+    #[serde(default)]
+    pub slug: Option<String>,
+    #[serde(default)]
+    pub description: Option<String>,
+}
+
+/// Body of the group members endpoint
+#[derive(Debug, Clone, Deserialize)]
+pub struct ReaderGroupMembersInput {
+    pub user_ids: Vec<i64>,
+}
+
+/// Who may read an article, also the body of the article audience
+/// endpoint. Readers matching any role or group are admitted; both empty
+/// means everyone.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ArticleAudience {
+    /// Users with one of these roles or a higher one
+    #[serde(default)]
+    pub roles: Vec<UserRole>,
+    /// Members of these reader groups
+    #[serde(default)]
+    pub group_ids: Vec<i64>,
+}
+
+impl ArticleAudience {
+    /// Whether the article is limited to some readers
+    pub fn is_restricted(&self) -> bool {
+        !self.roles.is_empty() || !self.group_ids.is_empty()
+    }
+
+    /// Whether `reader`, in the groups `reader_groups`, may read the
+    /// article; `None` is an anonymous visitor
+    pub fn admits(&self, reader: Option<&ArticleReader>, reader_groups: &[i64]) -> bool {
+        if !self.is_restricted() {
+            return true;
+        }
+        let Some(reader) = reader else {
+            return false;
+        };
+        reader.role == UserRole::Admin
+            || self.roles.iter().any(|role| reader.has_role(*role))
+            || self.group_ids.iter().any(|id| reader_groups.contains(id))
+    }
+}
+
+/// A signed-in reader, as far as article audiences are concerned
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ArticleReader {
+    pub user_id: i64,
+    pub role: UserRole,
+}
+
+impl ArticleReader {
+    /// The reader behind an optional signed-in user
+    pub fn of(user: Option<&User>) -> Option<Self> {
+        user.map(|u| Self {
+            user_id: u.id,
+            role: u.role,
+        })
+    }
+
+    /// Whether the reader has `role` or a higher one
+    pub fn has_role(&self, role: UserRole) -> bool {
+        role_rank(self.role) >= role_rank(role)
+    }
+
+    /// Roles whose audiences admit the reader, lowest first
+    pub fn admitted_roles(&self) -> impl Iterator<Item = UserRole> + '_ {
+        [UserRole::Author, UserRole::Editor, UserRole::Admin]
+            .into_iter()
+            .filter(|role| self.has_role(*role))
+    }
+}
+
+fn role_rank(role: UserRole) -> u8 {
+    match role {
+        UserRole::Author => 0,
+        UserRole::Editor => 1,
+        UserRole::Admin => 2,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn reader(role: UserRole) -> ArticleReader {
+        ArticleReader { user_id: 7, role }
+    }
+
+    #[test]
+    fn open_audience_admits_everyone() {
+        let audience = ArticleAudience::default();
+        assert!(audience.admits(None, &[]));
+        assert!(audience.admits(Some(&reader(UserRole::Author)), &[]));
+    }
+
+    #[test]
+    fn roles_admit_higher_roles_and_groups_admit_members() {
+        let audience = ArticleAudience {
+            roles: vec![UserRole::Editor],
+            group_ids: vec![3],
+        };
+        assert!(!audience.admits(None, &[]));
+        assert!(!audience.admits(Some(&reader(UserRole::Author)), &[1]));
+        assert!(audience.admits(Some(&reader(UserRole::Author)), &[1, 3]));
+        assert!(audience.admits(Some(&reader(UserRole::Editor)), &[]));
+        assert!(audience.admits(Some(&reader(UserRole::Admin)), &[]));
+
+        let groups_only = ArticleAudience {
+            roles: Vec::new(),
+            group_ids: vec![3],
+        };
+        assert!(groups_only.admits(Some(&reader(UserRole::Admin)), &[]));
+        assert!(!groups_only.admits(Some(&reader(UserRole::Editor)), &[]));
+    }
+}
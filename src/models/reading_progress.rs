@@ -0,0 +1,25 @@
+//! Reading progress model
+//!
+//! Where a logged-in reader stopped in an article, for "continue reading"
+//! lists in themes.
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+/// Reading progress of one user in one article
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ReadingProgress {
+    pub article_id: i64,
+    pub slug: String,
+    pub title: String,
+    /// Scroll position as a fraction of the article, from 0.0 to 1.0
+    pub position: f64,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl ReadingProgress {
+    /// Whether `position` is a valid scroll fraction
+    pub fn is_valid_position(position: f64) -> bool {
+        (0.0..=1.0).contains(&position)
+    }
+}
@@ -0,0 +1,64 @@
+//! Redirect model.
+//!
+//! Manual redirects map a site path to another path or an external URL.
+//! They are checked by the static file fallback before the theme renders
+//! its not-found page, so they never shadow API or admin routes.
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+/// Status codes a redirect may answer with
+pub const REDIRECT_STATUS_CODES: [u16; 4] = [301, 302, 307, 308];
+
+/// Default status code: permanent, so search engines move their index
+pub const DEFAULT_REDIRECT_STATUS: u16 = 301;
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Redirect {
+    pub id: i64,
+    /// Request path, without query string or trailing slash
+    pub source: String,
+    /// Site path or absolute http(s) URL
+    pub target: String,
+    pub status_code: u16,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl Redirect {
+    pub fn new(source: String, target: String) -> Self {
+        let now = Utc::now();
+        Self {
+            id: 0,
+            source,
+            target,
+            status_code: DEFAULT_REDIRECT_STATUS,
+            created_at: now,
+            updated_at: now,
+        }
+    }
+}
+
+/// Normalize a request path for matching: no trailing slash except the root
+pub fn normalize_redirect_path(path: &str) -> &str {
+    let trimmed = path.trim_end_matches('/');
+    if trimmed.is_empty() {
+        "/"
+    } else {
+        trimmed
+    }
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct CreateRedirectInput {
+    pub source: String,
+    pub target: String,
+    pub status_code: Option<u16>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct UpdateRedirectInput {
+    pub source: Option<String>,
+    pub target: Option<String>,
+    pub status_code: Option<u16>,
+}
@@ -0,0 +1,69 @@
+//! Article series model.
+//!
+//! A series is an ordered run of articles ("Rust from scratch, part 1..n").
+//! An article belongs to at most one series.
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Series {
+    pub id: i64,
+    /// Used by `/api/v1/series/{slug}`
+    pub slug: String,
+    pub title: String,
+    pub description: String,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+/// An article of a series, in reading order
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SeriesArticle {
+    pub id: i64,
+    pub slug: String,
+    pub title: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub thumbnail: Option<String>,
+    pub status: String,
+    pub published_at: Option<DateTime<Utc>>,
+    /// Order within the series, lowest first
+    pub position: i32,
+}
+
+/// A series with its articles in order
+#[derive(Debug, Clone, Serialize)]
+pub struct SeriesWithArticles {
+    #[serde(flatten)]
+    pub series: Series,
+    pub articles: Vec<SeriesArticle>,
+}
+
+/// Where an article sits in its series, for previous/next navigation
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SeriesNavigation {
+    pub id: i64,
+    pub slug: String,
+    pub title: String,
+    /// 1-based index of the article among the published ones
+    pub index: usize,
+    /// Number of published articles in the series
+    pub total: usize,
+    pub prev: Option<SeriesArticle>,
+    pub next: Option<SeriesArticle>,
+}
+
+/// Body of both series create and update
+#[derive(Debug, Clone, Deserialize)]
+pub struct SeriesInput {
+    pub slug: String,
+    pub title: String,
+    #[serde(default)]
+    pub description: String,
+}
+
+/// New article list of a series, in reading order
+#[derive(Debug, Clone, Deserialize)]
+pub struct SeriesArticlesInput {
+    pub article_ids: Vec<i64>,
+}
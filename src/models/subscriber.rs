@@ -0,0 +1,70 @@
+//! Newsletter subscriber model
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::str::FromStr;
+
+/// Lifecycle of a subscription (double opt-in)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SubscriberStatus {
+    /// Signed up, confirmation link not followed yet
+    Pending,
+    /// Confirmed; receives newsletters
+    Active,
+    Unsubscribed,
+}
+
+impl SubscriberStatus {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Pending => "pending",
+            Self::Active => "active",
+            Self::Unsubscribed => "unsubscribed",
+        }
+    }
+}
+
+impl fmt::Display for SubscriberStatus {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for SubscriberStatus {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "pending" => Ok(Self::Pending),
+            "active" => Ok(Self::Active),
+            "unsubscribed" => Ok(Self::Unsubscribed),
+            other => Err(format!("Invalid subscriber status: {}", other)),
+        }
+    }
+}
+
+/// A newsletter subscriber
+#[derive(Debug, Clone, Serialize)]
+pub struct Subscriber {
+    pub id: i64,
+    /// Lower-cased address
+    pub email: String,
+    pub status: SubscriberStatus,
+    /// Secret in the confirmation and unsubscribe links
+    #[serde(skip_serializing)]
+    pub token: String,
+    pub created_at: DateTime<Utc>,
+    pub confirmed_at: Option<DateTime<Utc>>,
+    pub unsubscribed_at: Option<DateTime<Utc>>,
+}
+
+/// An article sent as a newsletter
+#[derive(Debug, Clone, Serialize)]
+pub struct NewsletterIssue {
+    pub article_id: i64,
+    /// Subscribers a message was queued for
+    pub recipients: i64,
+    pub sent_at: DateTime<Utc>,
+}
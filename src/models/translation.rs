@@ -0,0 +1,122 @@
+//! Content translation model.
+//!
+//! Articles and pages can exist in several languages. Variants of the same
+//! piece of content share a translation group; content that was never given
+//! a language is in the site language (the `site_language` setting).
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+/// Kind of content that can be translated
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ContentKind {
+    Article,
+    Page,
+}
+
+impl ContentKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Article => "article",
+            Self::Page => "page",
+        }
+    }
+
+    /// Accepts both the singular and the plural used in API paths
+    pub fn parse(s: &str) -> Option<Self> {
+        match s {
+            "article" | "articles" => Some(Self::Article),
+            "page" | "pages" => Some(Self::Page),
+            _ => None,
+        }
+    }
+}
+
+/// Language of one article or page and the group of its variants
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ContentTranslation {
+    pub content_type: ContentKind,
+    pub content_id: i64,
+    /// BCP 47 language tag, e.g. `en` or `zh-Hant-TW`
+    pub lang: String,
+    pub translation_group: String,
+    pub created_at: DateTime<Utc>,
+}
+
+/// Body of setting the language of an article or page
+#[derive(Debug, Clone, Deserialize)]
+pub struct TranslationInput {
+    pub lang: String,
+    /// Join the translation group of this article or page (same kind)
+    #[serde(default)]
+    pub translation_of: Option<i64>,
+}
+
+/// A published language variant, for `hreflang` links
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ContentAlternate {
+    pub lang: String,
+    pub id: i64,
+    pub slug: String,
+    pub title: String,
+    /// Site-relative URL
+    pub url: String,
+}
+
+/// Language filter for listings
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LangFilter {
+    pub lang: String,
+    /// Content without a language counts as this one
+    pub site_lang: String,
+}
+
+/// Normalize a BCP 47 language tag, or `None` when it is not one.
+///
+/// The language is lower-cased, a script title-cased and a region
+/// upper-cased, so `ZH-hant-tw` becomes `zh-Hant-TW`.
+pub fn normalize_lang(tag: &str) -> Option<String> {
+    let tag = tag.trim().replace('_', "-");
+    let mut parts = tag.split('-');
+    let language = parts.next()?;
+    if !(2..=8).contains(&language.len()) || !language.bytes().all(|b| b.is_ascii_alphabetic()) {
+        return None;
+    }
+    let mut normalized = language.to_ascii_lowercase();
+    for part in parts {
+        if part.is_empty() || part.len() > 8 || !part.bytes().all(|b| b.is_ascii_alphanumeric()) {
+            return None;
+        }
+        normalized.push('-');
+        match part.len() {
+            2 if part.bytes().all(|b| b.is_ascii_alphabetic()) => {
+                normalized.push_str(&part.to_ascii_uppercase())
+            }
+            4 if part.bytes().all(|b| b.is_ascii_alphabetic()) => {
+                normalized.push_str(&part[..1].to_ascii_uppercase());
+                normalized.push_str(&part[1..].to_ascii_lowercase());
+            }
+            _ => normalized.push_str(&part.to_ascii_lowercase()),
+        }
+    }
+    Some(normalized)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn normalizes_language_tags() {
+        assert_eq!(normalize_lang("en").as_deref(), Some("en"));
+        assert_eq!(normalize_lang(" EN-us ").as_deref(), Some("en-US"));
+        assert_eq!(normalize_lang("zh_hant_tw").as_deref(), Some("zh-Hant-TW"));
+        assert_eq!(normalize_lang("es-419").as_deref(), Some("es-419"));
+        assert_eq!(normalize_lang(""), None);
+        assert_eq!(normalize_lang("e"), None);
+        assert_eq!(normalize_lang("en-"), None);
+        assert_eq!(normalize_lang("en us"), None);
+        assert_eq!(normalize_lang("../x"), None);
+    }
+}
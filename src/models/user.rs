@@ -39,6 +39,8 @@ pub struct User {
     pub totp_secret: Option<String>,
     /// Whether TOTP 2FA is enabled
     pub totp_enabled: bool,
+    /// Whether an admin requires the user to change their password
+    pub password_reset_required: bool,
     /// Creation timestamp
     pub created_at: DateTime<Utc>,
     /// Last update timestamp
@@ -63,6 +65,7 @@ impl User {
             avatar: None,
             totp_secret: None,
             totp_enabled: false,
+            password_reset_required: false,
             created_at: now,
             updated_at: now,
         }
@@ -102,6 +105,29 @@ impl User {
     }
 }
 
+/// What readers may see of a user: no email, role or account state.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PublicUser {
+    pub id: i64,
+    pub username: String,
+    pub display_name: Option<String>,
+    pub avatar: Option<String>,
+    /// When the account was created
+    pub created_at: DateTime<Utc>,
+}
+
+impl From<&User> for PublicUser {
+    fn from(user: &User) -> Self {
+        Self {
+            id: user.id,
+            username: user.username.clone(),
+            display_name: user.display_name.clone(),
+            avatar: user.avatar.clone(),
+            created_at: user.created_at,
+        }
+    }
+}
+
 /// User role for authorization.
 ///
 /// Roles determine what actions a user can perform:
@@ -341,4 +367,22 @@ mod tests {
     fn test_user_role_default() {
         assert_eq!(UserRole::default(), UserRole::Author);
     }
+
+    #[test]
+    fn test_public_user_hides_private_fields() {
+        let mut user = User::new(
+            "writer".to_string(),
+            "writer@test.com".to_string(),
+            "hash".to_string(),
+            UserRole::Admin,
+        );
+        user.display_name = Some("Writer".to_string());
+
+        let json = serde_json::to_value(PublicUser::from(&user)).unwrap();
+        assert_eq!(json["username"], "writer");
+        assert_eq!(json["display_name"], "Writer");
+        for field in ["email", "role", "status", "totp_enabled", "password_hash"] {
+            assert!(json.get(field).is_none(), "{} leaked", field);
+        }
+    }
 }
@@ -0,0 +1,125 @@
+//! Per-user admin UI preferences model.
+
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+/// Maximum serialized size of a preferences document
+pub const MAX_PREFERENCES_BYTES: usize = 16 * 1024;
+
+const MAX_LIST_DENSITIES: usize = 64;
+const MAX_DISMISSED_TIPS: usize = 256;
+const MAX_KEY_LEN: usize = 64;
+
+/// Admin UI preferences stored per user.
+///
+/// Unknown fields are rejected so the stored document always matches this
+/// schema; new preferences need to be added here first.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct UserPreferences {
+    #[serde(default)]
+    pub editor: EditorPreferences,
+    /// List view density keyed by list name (e.g. "articles", "comments")
+    #[serde(default)]
+    pub list_density: BTreeMap<String, ListDensity>,
+    /// Identifiers of tips the user has dismissed
+    #[serde(default)]
+    pub dismissed_tips: Vec<String>,
+}
+
+/// Markdown editor settings
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct EditorPreferences {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub font_size: Option<u8>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub line_numbers: Option<bool>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub word_wrap: Option<bool>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub live_preview: Option<bool>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub spell_check: Option<bool>,
+}
+
+/// Table/list row density
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ListDensity {
+    Compact,
+    Comfortable,
+    Spacious,
+}
+
+impl UserPreferences {
+    /// Check value ranges that serde cannot express.
+    pub fn validate(&self) -> Result<(), String> {
+        if let Some(size) = self.editor.font_size {
+            if !(10..=32).contains(&size) {
+                return Err("editor.font_size must be between 10 and 32".to_string());
+            }
+        }
+        if self.list_density.len() > MAX_LIST_DENSITIES {
+            return Err(format!(
+                "list_density supports at most {} entries",
+                MAX_LIST_DENSITIES
+            ));
+        }
+        if self.dismissed_tips.len() > MAX_DISMISSED_TIPS {
+            return Err(format!(
+                "dismissed_tips supports at most {} entries",
+                MAX_DISMISSED_TIPS
+            ));
+        }
+        for key in self.list_density.keys().chain(self.dismissed_tips.iter()) {
+            if key.is_empty() || key.len() > MAX_KEY_LEN || !is_identifier(key) {
+                return Err(format!("Invalid preference key: {:?}", key));
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Keys are short identifiers: ASCII letters, digits, `-`, `_`, `.` and `:`.
+fn is_identifier(key: &str) -> bool {
+    key.chars()
+        .all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.' | ':'))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn empty_document_uses_defaults() {
+        let prefs: UserPreferences = serde_json::from_str("{}").unwrap();
+        assert_eq!(prefs, UserPreferences::default());
+        assert!(prefs.validate().is_ok());
+    }
+
+    #[test]
+    fn unknown_fields_are_rejected() {
+        assert!(serde_json::from_str::<UserPreferences>(r#"{"theme":"dark"}"#).is_err());
+        assert!(
+            serde_json::from_str::<UserPreferences>(r#"{"editor":{"vim_mode":true}}"#).is_err()
+        );
+    }
+
+    #[test]
+    fn values_are_range_checked() {
+        let prefs: UserPreferences =
+            serde_json::from_str(r#"{"editor":{"font_size":64}}"#).unwrap();
+        assert!(prefs.validate().is_err());
+
+        let prefs: UserPreferences =
+            serde_json::from_str(r#"{"dismissed_tips":["has space"]}"#).unwrap();
+        assert!(prefs.validate().is_err());
+
+        let prefs: UserPreferences = serde_json::from_str(
+            r#"{"editor":{"font_size":14},"list_density":{"articles":"compact"},"dismissed_tips":["welcome"]}"#,
+        )
+        .unwrap();
+        assert!(prefs.validate().is_ok());
+    }
+}
@@ -0,0 +1,36 @@
+//! Passkey (WebAuthn) credential model
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+/// Maximum length of a passkey display name
+pub const MAX_PASSKEY_NAME_LEN: usize = 100;
+
+/// A registered passkey
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WebauthnCredential {
+    pub id: i64,
+    pub user_id: i64,
+    /// Credential ID (base64url)
+    pub credential_id: String,
+    /// Credential state from webauthn-rs (a serialized `Passkey`), which
+    /// holds the public key and signature counter
+    #[serde(skip_serializing)]
+    pub public_key: String,
+    /// Signature counter reported at the last sign-in
+    pub sign_count: i64,
+    /// User-chosen display name
+    pub name: String,
+    pub created_at: DateTime<Utc>,
+    pub last_used_at: Option<DateTime<Utc>>,
+}
+
+/// Input for storing a newly registered passkey
+#[derive(Debug, Clone)]
+pub struct CreateWebauthnCredentialInput {
+    pub user_id: i64,
+    pub credential_id: String,
+    pub public_key: String,
+    pub sign_count: i64,
+    pub name: String,
+}
@@ -233,8 +233,11 @@ pub mod hook_names {
     // Taxonomy hooks - triggered in src/api/admin/taxonomy.rs
     pub const CATEGORY_AFTER_CREATE: &str = "category_after_create";
     pub const CATEGORY_AFTER_DELETE: &str = "category_after_delete";
+    pub const CATEGORY_AFTER_MOVE: &str = "category_after_move";
     pub const TAG_AFTER_CREATE: &str = "tag_after_create";
     pub const TAG_AFTER_DELETE: &str = "tag_after_delete";
+    pub const TAG_AFTER_UPDATE: &str = "tag_after_update";
+    pub const TAG_AFTER_MERGE: &str = "tag_after_merge";
 
     // Comment moderation hooks - triggered in src/services/comment.rs
     pub const COMMENT_APPROVE: &str = "comment_approve";
@@ -243,6 +246,7 @@ pub mod hook_names {
     // User behavior hooks - triggered in src/services/user.rs and src/api/auth.rs
     pub const USER_PROFILE_UPDATE: &str = "user_profile_update";
     pub const USER_PASSWORD_CHANGE: &str = "user_password_change";
+    pub const USER_ROLE_CHANGE: &str = "user_role_change";
 
     // Settings hooks - triggered in src/api/admin/settings.rs
     pub const SETTINGS_BEFORE_SAVE: &str = "settings_before_save";
@@ -270,6 +274,12 @@ pub mod hook_names {
     // Cron hooks - triggered in src/main.rs
     pub const CRON_REGISTER: &str = "cron_register"; // src/main.rs (system_init)
     pub const CRON_TICK: &str = "cron_tick"; // src/main.rs (every 60s)
+
+    // Job queue hooks - triggered in src/services/job_queue.rs
+    pub const JOB_COMPLETE: &str = "job_complete"; // src/services/job_queue.rs
+
+    // Integration hooks - triggered in src/services/inbound_webhook.rs
+    pub const EXTERNAL_WEBHOOK: &str = "external_webhook"; // src/services/inbound_webhook.rs
 }
 
 #[cfg(test)]
@@ -486,6 +486,13 @@ impl PluginRuntime {
         func_name: &str,
         input: &[u8],
     ) -> Result<ExecutionResult, PluginError> {
+        let _span = tracing::info_span!(
+            "wasm.execute",
+            otel.name = %format!("wasm {}", func_name),
+            plugin.handle = handle.0,
+            wasm.function = %func_name,
+        )
+        .entered();
         let plugin = self
             .plugins
             .get_mut(&handle.0)
@@ -3,7 +3,7 @@
 //! Parses and renders shortcodes like [name attr="value"]content[/name]
 
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use tracing::debug;
 
 /// Parsed shortcode
@@ -39,6 +39,8 @@ pub struct ShortcodeContext {
 pub struct ShortcodeManager {
     /// Registered handlers (name -> handler)
     handlers: HashMap<String, ShortcodeHandler>,
+    /// Shortcodes that never take content, so `[name]` needs no closing tag
+    void_names: HashSet<String>,
 }
 
 impl Default for ShortcodeManager {
@@ -52,6 +54,7 @@ impl ShortcodeManager {
     pub fn new() -> Self {
         Self {
             handlers: HashMap::new(),
+            void_names: HashSet::new(),
         }
     }
 
@@ -64,9 +67,20 @@ impl ShortcodeManager {
         self.handlers.insert(name.to_string(), Box::new(handler));
     }
 
+    /// Register a handler for a shortcode without content, written as
+    /// `[name attr=value]` with no closing tag
+    pub fn register_void<F>(&mut self, name: &str, handler: F)
+    where
+        F: Fn(&Shortcode, &ShortcodeContext) -> String + Send + Sync + 'static,
+    {
+        self.register(name, handler);
+        self.void_names.insert(name.to_string());
+    }
+
     /// Unregister a shortcode handler
     pub fn unregister(&mut self, name: &str) {
         self.handlers.remove(name);
+        self.void_names.remove(name);
     }
 
     /// Check if a shortcode is registered
@@ -162,7 +176,9 @@ impl ShortcodeManager {
             let attr_name: String = chars[attr_name_start..i].iter().collect();
 
             // Skip whitespace and =
+            let mut has_value = false;
             while i < len && (chars[i].is_whitespace() || chars[i] == '=') {
+                has_value |= chars[i] == '=';
                 i += 1;
             }
 
@@ -179,6 +195,18 @@ impl ShortcodeManager {
                 if i < len {
                     i += 1; // Skip closing quote
                 }
+            } else if has_value && i < len && chars[i] != ']' {
+                // Unquoted value, ends at whitespace, `]` or `/]`
+                let value_start = i;
+                while i < len
+                    && !chars[i].is_whitespace()
+                    && chars[i] != ']'
+                    && !(chars[i] == '/' && chars.get(i + 1) == Some(&']'))
+                {
+                    i += 1;
+                }
+                let attr_value: String = chars[value_start..i].iter().collect();
+                attrs.insert(attr_name, attr_value);
             }
         }
 
@@ -190,7 +218,7 @@ impl ShortcodeManager {
 
         let opening_tag_end = i;
 
-        if is_self_closing {
+        if is_self_closing || self.void_names.contains(&name) {
             let original: String = chars[start..opening_tag_end].iter().collect();
             return Some((
                 Shortcode {
@@ -402,6 +430,35 @@ pub mod builtins {
             )
         });
 
+        // [poll id=3] - Poll placeholder, replaced with the form or results
+        // whenever the article is served
+        manager.register_void("poll", |shortcode, _ctx| {
+            match shortcode
+                .attrs
+                .get("id")
+                .and_then(|id| id.trim().parse::<i64>().ok())
+            {
+                Some(id) => crate::services::poll::placeholder(id),
+                None => shortcode.original.clone(),
+            }
+        });
+
+        // [faq topic="billing"] - FAQ topic placeholder, replaced with the
+        // topic's questions whenever the article is served
+        manager.register_void("faq", |shortcode, _ctx| {
+            shortcode
+                .attrs
+                .get("topic")
+                .and_then(|slug| crate::services::faq::placeholder(slug))
+                .unwrap_or_else(|| shortcode.original.clone())
+        });
+
+        // [attachments] - Placeholder for the article's attached files,
+        // replaced with a download list whenever the article is served
+        manager.register_void("attachments", |_shortcode, _ctx| {
+            crate::services::attachment::PLACEHOLDER.to_string()
+        });
+
         // [collapse title="Click to expand"]content[/collapse] - Collapsible section
         manager.register("collapse", |shortcode, _ctx| {
             let title = shortcode
@@ -678,6 +735,62 @@ mod tests {
         assert!(result.contains("aria-expanded=\"false\""));
     }
 
+    #[test]
+    fn test_parse_unquoted_attributes() {
+        let manager = ShortcodeManager::new();
+        let shortcodes = manager.parse(r#"[video url=https://example.com/a.mp4 muted /]"#);
+
+        assert_eq!(shortcodes.len(), 1);
+        assert_eq!(
+            shortcodes[0].attrs.get("url"),
+            Some(&"https://example.com/a.mp4".to_string())
+        );
+        assert!(!shortcodes[0].attrs.contains_key("muted"));
+    }
+
+    #[test]
+    fn test_render_void_shortcode() {
+        let mut manager = ShortcodeManager::new();
+        manager.register_void("hr", |sc, _| format!("<hr data-id=\"{}\">", sc.attrs["id"]));
+
+        let result = manager.render("a [hr id=1] b [hr id=2] c", &ShortcodeContext::default());
+
+        assert_eq!(result, "a <hr data-id=\"1\"> b <hr data-id=\"2\"> c");
+    }
+
+    #[test]
+    fn test_render_builtin_poll_placeholder() {
+        let mut manager = ShortcodeManager::new();
+        builtins::register_builtins(&mut manager);
+
+        let result = manager.render("[poll id=3]", &ShortcodeContext::default());
+        assert_eq!(result, crate::services::poll::placeholder(3));
+
+        let result = manager.render("[poll id=x]", &ShortcodeContext::default());
+        assert_eq!(result, "[poll id=x]");
+    }
+
+    #[test]
+    fn test_render_builtin_faq_placeholder() {
+        let mut manager = ShortcodeManager::new();
+        builtins::register_builtins(&mut manager);
+
+        let result = manager.render(r#"[faq topic="billing"]"#, &ShortcodeContext::default());
+        assert_eq!(Some(result), crate::services::faq::placeholder("billing"));
+
+        let result = manager.render("[faq]", &ShortcodeContext::default());
+        assert_eq!(result, "[faq]");
+    }
+
+    #[test]
+    fn test_render_builtin_attachments_placeholder() {
+        let mut manager = ShortcodeManager::new();
+        builtins::register_builtins(&mut manager);
+
+        let result = manager.render("[attachments]", &ShortcodeContext::default());
+        assert_eq!(result, crate::services::attachment::PLACEHOLDER);
+    }
+
     #[test]
     fn test_render_builtin_article_card() {
         let mut manager = ShortcodeManager::new();
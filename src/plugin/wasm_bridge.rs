@@ -8,6 +8,8 @@
 //! pooled and reused across requests, with compiled WASM modules cached in
 //! each worker for fast subsequent invocations.
 
+use once_cell::sync::OnceCell;
+use serde::Serialize;
 use serde_json::Value;
 use std::future::Future;
 use std::sync::Arc;
@@ -54,6 +56,37 @@ impl WasmPluginRegistry {
     }
 }
 
+/// A line a plugin logged with `host_log`, after sanitizing
+#[derive(Debug, Clone, Serialize)]
+pub struct PluginLogLine {
+    pub plugin_id: String,
+    pub level: String,
+    pub message: String,
+}
+
+type PluginLogSink = Box<dyn Fn(PluginLogLine) + Send + Sync>;
+
+static PLUGIN_LOG_SINK: OnceCell<PluginLogSink> = OnceCell::new();
+
+/// Also pass plugin log lines to `sink`, e.g. the admin event stream.
+///
+/// This is not a hook on purpose: a plugin handling it could log from its
+/// handler and loop. Only the first sink set is used.
+pub fn set_plugin_log_sink(sink: impl Fn(PluginLogLine) + Send + Sync + 'static) {
+    let _ = PLUGIN_LOG_SINK.set(Box::new(sink));
+}
+
+/// Split a worker stderr line of the form `[wasm:plugin_id][level] message`
+fn parse_plugin_log(line: &str) -> Option<PluginLogLine> {
+    let (plugin_id, rest) = line.strip_prefix("[wasm:")?.split_once(']')?;
+    let (level, message) = rest.strip_prefix('[')?.split_once(']')?;
+    Some(PluginLogLine {
+        plugin_id: plugin_id.to_string(),
+        level: level.to_string(),
+        message: message.trim_start().to_string(),
+    })
+}
+
 /// Sanitize plugin log output to prevent leaking sensitive information.
 ///
 /// Masks:
@@ -186,6 +219,11 @@ impl Worker {
                         } else {
                             tracing::debug!("{}", sanitized);
                         }
+                        if let Some(sink) = PLUGIN_LOG_SINK.get() {
+                            if let Some(log) = parse_plugin_log(&sanitized) {
+                                sink(log);
+                            }
+                        }
                     }
                 }
             });
@@ -617,6 +655,13 @@ fn execute_wasm_subprocess(
     comments: Option<&Value>,
     pool: &DynDatabasePool,
 ) -> SubprocessResult {
+    let _span = tracing::info_span!(
+        "wasm.execute",
+        otel.name = %format!("wasm {}", func_name),
+        plugin.id = %plugin_id,
+        wasm.function = %func_name,
+    )
+    .entered();
     let input_b64 = base64_encode(input_bytes);
 
     let pd_json: serde_json::Map<String, Value> = plugin_data
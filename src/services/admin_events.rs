@@ -0,0 +1,110 @@
+//! Live events for the admin UI
+//!
+//! Hook handlers and the plugin log sink publish into a broadcast channel;
+//! each `GET /admin/events` connection subscribes and streams the events as
+//! Server-Sent Events. Publishing never blocks: events are dropped when
+//! nobody listens, and slow listeners skip what they missed.
+
+use serde::Serialize;
+use serde_json::{json, Value};
+use std::sync::Arc;
+use tokio::sync::broadcast;
+
+use crate::plugin::{hook_names, HookManager};
+
+/// Events kept for each listener before it starts skipping
+pub const DEFAULT_CAPACITY: usize = 256;
+
+/// One event for the admin UI; `kind` becomes the SSE event name
+#[derive(Debug, Clone, Serialize)]
+pub struct AdminEvent {
+    pub kind: &'static str,
+    pub data: Value,
+}
+
+/// Broadcast channel of [`AdminEvent`]s
+pub struct AdminEvents {
+    sender: broadcast::Sender<AdminEvent>,
+}
+
+impl AdminEvents {
+    pub fn new(capacity: usize) -> Self {
+        let (sender, _) = broadcast::channel(capacity);
+        Self { sender }
+    }
+
+    /// Send an event to everyone currently listening
+    pub fn publish(&self, kind: &'static str, data: Value) {
+        // Err only means there are no listeners
+        let _ = self.sender.send(AdminEvent { kind, data });
+    }
+
+    pub fn subscribe(&self) -> broadcast::Receiver<AdminEvent> {
+        self.sender.subscribe()
+    }
+
+    /// Publish new comments and finished queue jobs
+    pub fn register_hooks(self: &Arc<Self>, hook_manager: &HookManager) {
+        let events = self.clone();
+        hook_manager.register(
+            hook_names::COMMENT_AFTER_CREATE,
+            move |data: &mut Value| {
+                events.publish(
+                    "comment",
+                    json!({
+                        "id": data.get("id"),
+                        "article_id": data.get("article_id"),
+                        "nickname": data.get("nickname"),
+                        "content": data.get("content"),
+                        "status": data.get("status"),
+                        "created_at": data.get("created_at"),
+                    }),
+                );
+                None
+            },
+            100,
+            None,
+        );
+
+        let events = self.clone();
+        hook_manager.register(
+            hook_names::JOB_COMPLETE,
+            move |data: &mut Value| {
+                events.publish("job", data.clone());
+                None
+            },
+            100,
+            None,
+        );
+    }
+}
+
+impl Default for AdminEvents {
+    fn default() -> Self {
+        Self::new(DEFAULT_CAPACITY)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn comment_hook_publishes_without_private_fields() {
+        let hooks = HookManager::default();
+        let events = Arc::new(AdminEvents::default());
+        events.register_hooks(&hooks);
+        let mut rx = events.subscribe();
+
+        hooks.trigger_action(
+            hook_names::COMMENT_AFTER_CREATE,
+            json!({"id": 7, "article_id": 2, "nickname": "a", "email": "a@example.com", "ip": "1.2.3.4"}),
+        );
+
+        let event = rx.recv().await.unwrap();
+        assert_eq!(event.kind, "comment");
+        assert_eq!(event.data["id"], 7);
+        assert!(event.data.get("email").is_none());
+        assert!(event.data.get("ip").is_none());
+    }
+}
@@ -0,0 +1,371 @@
+//! Built-in page view analytics
+//!
+//! Page views of the public site are counted in memory and written out as
+//! daily aggregates (site totals, pages, referring hosts, countries) every
+//! `analytics.flush_secs`. Nothing per-visitor is stored: a visitor is the
+//! hash of IP and user agent with a random salt that is replaced at midnight
+//! UTC and never persisted, so it only serves to count unique visitors per
+//! day. A restart starts a new salt, so visitors seen before and after it
+//! count twice for that day.
+
+use anyhow::Result;
+use chrono::{NaiveDate, Utc};
+use sha2::{Digest, Sha256};
+use std::collections::hash_map::DefaultHasher;
+use std::collections::{BTreeMap, HashSet};
+use std::hash::{Hash, Hasher};
+use std::sync::{Arc, Mutex};
+
+use crate::config::AnalyticsConfig;
+use crate::db::repositories::{
+    AnalyticsRepository, DailyVisits, DayCounts, RankedTraffic, TrafficDimension,
+};
+use crate::services::stats::{day, fill_days};
+
+/// Longest path kept, longer ones are cut
+const MAX_PATH_CHARS: usize = 255;
+
+/// Substrings of user agents that are not counted
+const BOT_MARKERS: &[&str] = &[
+    "bot",
+    "crawl",
+    "spider",
+    "slurp",
+    "headless",
+    "lighthouse",
+    "preview",
+    "curl",
+    "wget",
+    "python-requests",
+    "go-http-client",
+];
+
+/// One page request of the public site
+#[derive(Debug, Clone, Default)]
+pub struct PageHit<'a> {
+    pub path: &'a str,
+    /// `Host` of the request, to tell internal navigation from referrals
+    pub host: Option<&'a str>,
+    pub referrer: Option<&'a str>,
+    pub client_ip: &'a str,
+    pub user_agent: &'a str,
+    /// Country code from `analytics.country_header`
+    pub country: Option<&'a str>,
+}
+
+/// Counts collected since the last flush
+struct Collector {
+    day: String,
+    salt: [u8; 16],
+    /// Hashes of (visitor, dimension, key) seen today
+    seen: HashSet<u64>,
+    pending: BTreeMap<String, DayCounts>,
+}
+
+impl Collector {
+    fn new() -> Self {
+        Self {
+            day: String::new(),
+            salt: [0; 16],
+            seen: HashSet::new(),
+            pending: BTreeMap::new(),
+        }
+    }
+
+    /// Start a new day: fresh salt, nobody seen yet
+    fn rotate(&mut self, today: String) {
+        getrandom::fill(&mut self.salt).expect("Failed to generate random bytes for analytics");
+        self.seen.clear();
+        self.day = today;
+    }
+
+    /// Whether `visitor` is new for `key` today
+    fn first_visit(&mut self, visitor: u64, dimension: u8, key: &str) -> bool {
+        let mut hasher = DefaultHasher::new();
+        (visitor, dimension, key).hash(&mut hasher);
+        self.seen.insert(hasher.finish())
+    }
+}
+
+/// Records page views and queries the daily aggregates
+pub struct AnalyticsService {
+    repo: Arc<dyn AnalyticsRepository>,
+    config: AnalyticsConfig,
+    #[cfg(feature = "geoip")]
+    geoip: Option<maxminddb::Reader<Vec<u8>>>,
+    collector: Mutex<Collector>,
+}
+
+impl AnalyticsService {
+    /// Create the service, opening the GeoIP database when configured
+    pub fn new(repo: Arc<dyn AnalyticsRepository>, config: AnalyticsConfig) -> Result<Self> {
+        #[cfg(feature = "geoip")]
+        let geoip = config.geoip_db.as_deref().map(open_geoip).transpose()?;
+        #[cfg(not(feature = "geoip"))]
+        if config.geoip_db.is_some() {
+            tracing::warn!("analytics.geoip_db is set but this build has no `geoip` feature");
+        }
+        Ok(Self {
+            repo,
+            config,
+            #[cfg(feature = "geoip")]
+            geoip,
+            collector: Mutex::new(Collector::new()),
+        })
+    }
+
+    pub fn config(&self) -> &AnalyticsConfig {
+        &self.config
+    }
+
+    /// Count a page view; bots are ignored
+    pub fn record(&self, hit: &PageHit<'_>) {
+        if !self.config.enabled || is_bot(hit.user_agent) {
+            return;
+        }
+        let path = normalize_path(hit.path);
+        let referrer = hit
+            .referrer
+            .and_then(referrer_host)
+            .filter(|r| !is_same_host(r, hit.host));
+        let country = hit
+            .country
+            .and_then(country_code)
+            .or_else(|| self.lookup_country(hit.client_ip));
+
+        let today = day(Utc::now().date_naive());
+        let mut collector = self.collector.lock().unwrap();
+        if collector.day != today {
+            collector.rotate(today.clone());
+        }
+        let visitor = visitor_hash(&collector.salt, hit.client_ip, hit.user_agent);
+        let new_visitor = collector.first_visit(visitor, 0, "");
+        let new_on_page = collector.first_visit(visitor, 1, &path);
+        let new_from_referrer = referrer
+            .as_deref()
+            .is_some_and(|r| collector.first_visit(visitor, 2, r));
+        let new_from_country = country
+            .as_deref()
+            .is_some_and(|c| collector.first_visit(visitor, 3, c));
+
+        let counts = collector.pending.entry(today).or_default();
+        counts.total.views += 1;
+        counts.total.visitors += new_visitor as i64;
+        let page = counts.pages.entry(path).or_default();
+        page.views += 1;
+        page.visitors += new_on_page as i64;
+        if let Some(referrer) = referrer {
+            let entry = counts.referrers.entry(referrer).or_default();
+            entry.views += 1;
+            entry.visitors += new_from_referrer as i64;
+        }
+        if let Some(country) = country {
+            let entry = counts.countries.entry(country).or_default();
+            entry.views += 1;
+            entry.visitors += new_from_country as i64;
+        }
+    }
+
+    /// Write the collected counts; they are kept for the next flush when
+    /// writing fails
+    pub async fn flush(&self) -> Result<()> {
+        let pending = std::mem::take(&mut self.collector.lock().unwrap().pending);
+        let mut days = pending.into_iter();
+        while let Some((day, counts)) = days.next() {
+            if let Err(e) = self.repo.add_counts(&day, &counts).await {
+                let mut collector = self.collector.lock().unwrap();
+                for (day, counts) in std::iter::once((day, counts)).chain(days) {
+                    collector.pending.entry(day).or_default().merge(counts);
+                }
+                return Err(e);
+            }
+        }
+        Ok(())
+    }
+
+    /// Views and visitors for every day in `[from, to)`, including days
+    /// without any
+    pub async fn visits(&self, from: NaiveDate, to: NaiveDate) -> Result<Vec<DailyVisits>> {
+        let rows = self.repo.visits_by_day(&day(from), &day(to)).await?;
+        Ok(fill_days(
+            from,
+            to,
+            rows,
+            |d| &d.day,
+            |day| DailyVisits {
+                day,
+                views: 0,
+                visitors: 0,
+            },
+        ))
+    }
+
+    /// Most viewed pages, referrers or countries in `[from, to)`
+    pub async fn top(
+        &self,
+        dimension: TrafficDimension,
+        from: NaiveDate,
+        to: NaiveDate,
+        limit: i64,
+    ) -> Result<Vec<RankedTraffic>> {
+        self.repo.top(dimension, &day(from), &day(to), limit).await
+    }
+
+    #[cfg(feature = "geoip")]
+    fn lookup_country(&self, client_ip: &str) -> Option<String> {
+        let ip = client_ip.parse().ok()?;
+        let record: maxminddb::geoip2::Country = self.geoip.as_ref()?.lookup(ip).ok()?;
+        record.country?.iso_code.and_then(country_code)
+    }
+
+    #[cfg(not(feature = "geoip"))]
+    fn lookup_country(&self, _client_ip: &str) -> Option<String> {
+        None
+    }
+}
+
+#[cfg(feature = "geoip")]
+fn open_geoip(path: &str) -> Result<maxminddb::Reader<Vec<u8>>> {
+    maxminddb::Reader::open_readfile(path)
+        .map_err(|e| anyhow::anyhow!("Failed to open GeoIP database {}: {}", path, e))
+}
+
+/// Whether a user agent is empty or looks like a crawler or script
+pub(crate) fn is_bot(user_agent: &str) -> bool {
+    let ua = user_agent.to_ascii_lowercase();
+    ua.trim().is_empty() || BOT_MARKERS.iter().any(|marker| ua.contains(marker))
+}
+
+fn visitor_hash(salt: &[u8], client_ip: &str, user_agent: &str) -> u64 {
+    let mut hasher = Sha256::new();
+    hasher.update(salt);
+    hasher.update(client_ip.as_bytes());
+    hasher.update([0]);
+    hasher.update(user_agent.as_bytes());
+    let digest = hasher.finalize();
+    u64::from_be_bytes(digest[..8].try_into().unwrap())
+}
+
+/// Path without a trailing slash, cut to the column size
+fn normalize_path(path: &str) -> String {
+    let path = match path.trim_end_matches('/') {
+        "" => "/",
+        trimmed => trimmed,
+    };
+    path.chars().take(MAX_PATH_CHARS).collect()
+}
+
+/// Lowercase host of an http(s) referrer
+fn referrer_host(referrer: &str) -> Option<String> {
+    let rest = referrer
+        .strip_prefix("https://")
+        .or_else(|| referrer.strip_prefix("http://"))?;
+    let authority = rest.split(['/', '?', '#']).next()?;
+    let host = authority.rsplit('@').next()?;
+    let host = if host.starts_with('[') {
+        host.split_inclusive(']').next()?
+    } else {
+        host.split(':').next()?
+    };
+    (!host.is_empty()).then(|| host.to_ascii_lowercase())
+}
+
+fn is_same_host(referrer: &str, host: Option<&str>) -> bool {
+    host.and_then(|h| referrer_host(&format!("http://{}", h)))
+        .is_some_and(|h| h == referrer)
+}
+
+/// Uppercase ISO 3166-1 alpha-2 code; unknown (`XX`) and Tor (`T1`) are dropped
+fn country_code(value: &str) -> Option<String> {
+    let code = value.trim().to_ascii_uppercase();
+    let valid = code.len() == 2 && code.bytes().all(|b| b.is_ascii_uppercase());
+    (valid && code != "XX").then_some(code)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::repositories::SqlxAnalyticsRepository;
+    use crate::db::{create_test_pool, migrations};
+    use chrono::Duration;
+
+    fn hit<'a>(path: &'a str, ip: &'a str) -> PageHit<'a> {
+        PageHit {
+            path,
+            host: Some("blog.example.com"),
+            client_ip: ip,
+            user_agent: "Mozilla/5.0 (X11; Linux x86_64) Firefox/128.0",
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn parses_referrer_hosts() {
+        assert_eq!(
+            referrer_host("https://user@News.Example.com:8443/a?b#c").as_deref(),
+            Some("news.example.com")
+        );
+        assert_eq!(referrer_host("http://[::1]:80/").as_deref(), Some("[::1]"));
+        assert_eq!(referrer_host("android-app://com.example"), None);
+        assert!(is_same_host(
+            "blog.example.com",
+            Some("blog.example.com:443")
+        ));
+        assert_eq!(country_code(" de ").as_deref(), Some("DE"));
+        assert_eq!(country_code("T1"), None);
+        assert_eq!(country_code("XX"), None);
+        assert_eq!(normalize_path("/posts/a/"), "/posts/a");
+        assert_eq!(normalize_path("/"), "/");
+    }
+
+    #[tokio::test]
+    async fn counts_unique_visitors_per_day_and_key() {
+        let pool = create_test_pool().await.unwrap();
+        migrations::run_migrations(&pool).await.unwrap();
+        let service = AnalyticsService::new(
+            SqlxAnalyticsRepository::boxed(pool),
+            AnalyticsConfig::default(),
+        )
+        .unwrap();
+
+        service.record(&hit("/", "203.0.113.1"));
+        service.record(&PageHit {
+            referrer: Some("https://news.example.com/item"),
+            country: Some("de"),
+            ..hit("/posts/a/", "203.0.113.1")
+        });
+        service.record(&PageHit {
+            referrer: Some("https://blog.example.com/"),
+            ..hit("/posts/a", "203.0.113.2")
+        });
+        service.record(&PageHit {
+            user_agent: "Googlebot/2.1",
+            ..hit("/posts/a", "203.0.113.3")
+        });
+        service.flush().await.unwrap();
+        // Nothing left to write twice
+        service.flush().await.unwrap();
+
+        let today = Utc::now().date_naive();
+        let tomorrow = today + Duration::days(1);
+        let days = service.visits(today, tomorrow).await.unwrap();
+        assert_eq!((days[0].views, days[0].visitors), (3, 2));
+
+        let pages = service
+            .top(TrafficDimension::Page, today, tomorrow, 10)
+            .await
+            .unwrap();
+        let ranked: Vec<(&str, i64, i64)> = pages
+            .iter()
+            .map(|p| (p.key.as_str(), p.views, p.visitors))
+            .collect();
+        assert_eq!(ranked, vec![("/posts/a", 2, 2), ("/", 1, 1)]);
+
+        let referrers = service
+            .top(TrafficDimension::Referrer, today, tomorrow, 10)
+            .await
+            .unwrap();
+        assert_eq!(referrers.len(), 1);
+        assert_eq!(referrers[0].key, "news.example.com");
+    }
+}
@@ -0,0 +1,251 @@
+//! Site-wide announcement banners
+//!
+//! Announcements are stored as a JSON list in the `announcements` setting.
+//! Each has a plain-text message, a style, an optional start/end window and
+//! a flag telling themes whether visitors may dismiss it. Only the ones
+//! inside their window are published through `/api/v1/site/info` and
+//! `window.__SITE_CONFIG__`, together with banners announcing upcoming
+//! maintenance windows.
+
+use chrono::{DateTime, Utc};
+use data_encoding::HEXLOWER;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+use crate::services::maintenance::{self, MaintenanceWindow};
+use crate::services::settings::SettingsService;
+
+/// Setting holding the JSON list
+pub const ANNOUNCEMENTS_KEY: &str = "announcements";
+
+const MAX_ANNOUNCEMENTS: usize = 20;
+const MAX_MESSAGE_LEN: usize = 1000;
+const MAX_ID_LEN: usize = 64;
+
+/// Errors returned when saving announcements
+#[derive(Debug, thiserror::Error)]
+pub enum AnnouncementError {
+    /// Invalid message, window or id
+    #[error("{0}")]
+    Validation(String),
+
+    /// Internal error
+    #[error("Internal error: {0}")]
+    Internal(String),
+}
+
+/// How themes should color the banner
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum AnnouncementStyle {
+    #[default]
+    Info,
+    Success,
+    Warning,
+    Danger,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Announcement {
+    /// Stable id themes remember dismissals by; derived from the message
+    /// when empty
+    #[serde(default)]
+    pub id: String,
+    /// Plain text, escaped by themes
+    pub message: String,
+    #[serde(default)]
+    pub style: AnnouncementStyle,
+    /// Shown from this time on; immediately when unset
+    #[serde(default)]
+    pub starts_at: Option<DateTime<Utc>>,
+    /// Hidden from this time on; never when unset
+    #[serde(default)]
+    pub ends_at: Option<DateTime<Utc>>,
+    #[serde(default = "default_dismissible")]
+    pub dismissible: bool,
+}
+
+fn default_dismissible() -> bool {
+    true
+}
+
+impl Announcement {
+    /// Whether the banner is shown at `now`
+    pub fn is_active(&self, now: DateTime<Utc>) -> bool {
+        self.starts_at.is_none_or(|start| start <= now) && self.ends_at.is_none_or(|end| now < end)
+    }
+}
+
+/// Trim and check a list of announcements, filling in missing ids
+pub fn validate(
+    mut announcements: Vec<Announcement>,
+) -> Result<Vec<Announcement>, AnnouncementError> {
+    if announcements.len() > MAX_ANNOUNCEMENTS {
+        return Err(AnnouncementError::Validation(format!(
+            "At most {} announcements are allowed",
+            MAX_ANNOUNCEMENTS
+        )));
+    }
+    let mut ids: Vec<String> = Vec::new();
+    for announcement in &mut announcements {
+        announcement.message = announcement.message.trim().to_string();
+        if announcement.message.is_empty() {
+            return Err(AnnouncementError::Validation(
+                "Announcement message is required".to_string(),
+            ));
+        }
+        if announcement.message.chars().count() > MAX_MESSAGE_LEN {
+            return Err(AnnouncementError::Validation(format!(
+                "Announcement messages must be at most {} characters",
+                MAX_MESSAGE_LEN
+            )));
+        }
+        if let (Some(start), Some(end)) = (announcement.starts_at, announcement.ends_at) {
+            if end <= start {
+                return Err(AnnouncementError::Validation(
+                    "An announcement must end after it starts".to_string(),
+                ));
+            }
+        }
+
+        announcement.id = announcement.id.trim().to_string();
+        if announcement.id.is_empty() {
+            // Derived rather than random so lists written through the generic
+            // settings endpoint keep their ids across loads
+            let digest = Sha256::digest(announcement.message.as_bytes());
+            announcement.id = HEXLOWER.encode(&digest[..8]);
+        }
+        let valid_id = announcement.id.len() <= MAX_ID_LEN
+            && announcement
+                .id
+                .bytes()
+                .all(|b| b.is_ascii_alphanumeric() || b == b'-' || b == b'_');
+        if !valid_id {
+            return Err(AnnouncementError::Validation(format!(
+                "Invalid announcement id: {:?}",
+                announcement.id
+            )));
+        }
+        if ids.contains(&announcement.id) {
+            return Err(AnnouncementError::Validation(format!(
+                "Duplicate announcement id: {}",
+                announcement.id
+            )));
+        }
+        ids.push(announcement.id.clone());
+    }
+    Ok(announcements)
+}
+
+/// The stored announcements; empty when unset or unreadable
+pub async fn load(settings: &SettingsService) -> Vec<Announcement> {
+    match settings.get(ANNOUNCEMENTS_KEY).await {
+        Ok(Some(json)) if !json.trim().is_empty() => {
+            // Also written by the generic settings endpoint, so check it again
+            serde_json::from_str::<Vec<Announcement>>(&json)
+                .map_err(|e| e.to_string())
+                .and_then(|list| validate(list).map_err(|e| e.to_string()))
+                .unwrap_or_else(|e| {
+                    tracing::warn!("Ignoring invalid {}: {}", ANNOUNCEMENTS_KEY, e);
+                    Vec::new()
+                })
+        }
+        Ok(_) => Vec::new(),
+        Err(e) => {
+            tracing::warn!("Failed to read {}: {}", ANNOUNCEMENTS_KEY, e);
+            Vec::new()
+        }
+    }
+}
+
+/// The announcements shown right now, in stored order, followed by those
+/// of upcoming maintenance windows
+pub async fn active(settings: &SettingsService) -> Vec<Announcement> {
+    let now = Utc::now();
+    let windows = maintenance::load_windows(settings).await;
+    load(settings)
+        .await
+        .into_iter()
+        .chain(windows.iter().filter_map(MaintenanceWindow::announcement))
+        .filter(|a| a.is_active(now))
+        .collect()
+}
+
+/// Validate and store a new list
+pub async fn save(
+    settings: &SettingsService,
+    announcements: Vec<Announcement>,
+) -> Result<Vec<Announcement>, AnnouncementError> {
+    let announcements = validate(announcements)?;
+    let json = serde_json::to_string(&announcements)
+        .map_err(|e| AnnouncementError::Internal(e.to_string()))?;
+    settings
+        .set_setting(ANNOUNCEMENTS_KEY, &json)
+        .await
+        .map_err(|e| AnnouncementError::Internal(e.to_string()))?;
+    Ok(announcements)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Duration;
+
+    fn announcement(message: &str) -> Announcement {
+        Announcement {
+            id: String::new(),
+            message: message.to_string(),
+            style: AnnouncementStyle::Info,
+            starts_at: None,
+            ends_at: None,
+            dismissible: true,
+        }
+    }
+
+    #[test]
+    fn active_only_inside_the_window() {
+        let now = Utc::now();
+        let mut a = announcement("Maintenance tonight");
+        assert!(a.is_active(now));
+
+        a.starts_at = Some(now + Duration::hours(1));
+        assert!(!a.is_active(now));
+
+        a.starts_at = Some(now - Duration::hours(1));
+        a.ends_at = Some(now);
+        assert!(!a.is_active(now));
+        assert!(a.is_active(now - Duration::minutes(1)));
+    }
+
+    #[test]
+    fn defaults_when_fields_are_missing() {
+        let json = r#"[{"message": "Hello", "style": "warning"}]"#;
+        let parse = || validate(serde_json::from_str(json).unwrap()).unwrap();
+        let list = parse();
+        assert_eq!(list[0].style, AnnouncementStyle::Warning);
+        assert!(list[0].dismissible);
+        assert_eq!(list[0].id.len(), 16);
+        // Generated ids survive reloading the same setting
+        assert_eq!(parse()[0].id, list[0].id);
+    }
+
+    #[test]
+    fn rejects_empty_messages_bad_windows_and_duplicate_ids() {
+        assert!(validate(vec![announcement("  ")]).is_err());
+
+        let now = Utc::now();
+        let mut backwards = announcement("x");
+        backwards.starts_at = Some(now);
+        backwards.ends_at = Some(now - Duration::hours(1));
+        assert!(validate(vec![backwards]).is_err());
+
+        let mut first = announcement("a");
+        first.id = "notice".to_string();
+        let mut second = announcement("b");
+        second.id = " notice ".to_string();
+        assert!(validate(vec![first.clone(), second]).is_err());
+
+        first.id = "not an id".to_string();
+        assert!(validate(vec![first]).is_err());
+    }
+}
@@ -0,0 +1,397 @@
+//! Public endpoint exposure
+//!
+//! Privacy-minded or minimal deployments can switch off groups of public
+//! endpoints (search, comments, likes, feeds, ...) with `api_expose_{group}`
+//! settings set to "false", and make the public API read-only with
+//! `api_read_only`. Disabled endpoints answer 404 as if they did not exist;
+//! visitor writes in read-only mode answer 403. Admin routes are never
+//! affected.
+
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use axum::http::Method;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use tokio::sync::RwLock;
+
+use crate::services::settings::SettingsService;
+
+/// Setting that blocks visitor writes
+pub const API_READ_ONLY_KEY: &str = "api_read_only";
+
+/// Prefix of the per-group settings, e.g. `api_expose_search`
+pub const API_EXPOSE_KEY_PREFIX: &str = "api_expose_";
+
+/// How long the settings are reused before being read again
+const STATE_TTL: Duration = Duration::from_secs(5);
+
+/// Errors returned when changing exposure settings
+#[derive(Debug, thiserror::Error)]
+pub enum ApiExposureError {
+    /// Unknown endpoint group
+    #[error("{0}")]
+    Validation(String),
+
+    /// Internal error
+    #[error("Internal error: {0}")]
+    Internal(String),
+}
+
+/// A group of public endpoints switched together
+#[derive(Debug)]
+pub struct EndpointGroup {
+    pub id: &'static str,
+    pub description: &'static str,
+    /// Paths in the group; each also covers everything below it
+    paths: &'static [&'static str],
+    /// Only requests carrying this non-empty query parameter belong to the group
+    query_param: Option<&'static str>,
+}
+
+/// All switchable endpoint groups
+pub const ENDPOINT_GROUPS: &[EndpointGroup] = &[
+    EndpointGroup {
+        id: "search",
+        description: "Article keyword search",
+        paths: &["/api/v1/articles"],
+        query_param: Some("keyword"),
+    },
+    EndpointGroup {
+        id: "archives",
+        description: "Article archives by month",
+        paths: &["/api/v1/articles/archives"],
+        query_param: None,
+    },
+    EndpointGroup {
+        id: "comments",
+        description: "Reading and posting comments, including the embed widget",
+        paths: &["/api/v1/comments", "/api/v1/embed"],
+        query_param: None,
+    },
+    EndpointGroup {
+        id: "likes",
+        description: "Likes on articles and comments",
+        paths: &["/api/v1/like"],
+        query_param: None,
+    },
+    EndpointGroup {
+        id: "views",
+        description: "View counting",
+        paths: &["/api/v1/view"],
+        query_param: None,
+    },
+    EndpointGroup {
+        id: "registration",
+        description: "Visitor account registration",
+        paths: &["/api/v1/auth/register"],
+        query_param: None,
+    },
+    EndpointGroup {
+        id: "friend_links",
+        description: "Public friend link list",
+        paths: &["/api/v1/friend-links"],
+        query_param: None,
+    },
+    EndpointGroup {
+        id: "feeds",
+        description: "RSS/Atom feeds",
+        paths: &["/feed.xml", "/rss.xml", "/feed"],
+        query_param: None,
+    },
+    EndpointGroup {
+        id: "events",
+        description: "Upcoming events and the iCal feed",
+        paths: &["/api/v1/events", "/events.ics"],
+        query_param: None,
+    },
+    EndpointGroup {
+        id: "releases",
+        description: "Release notes and releases.json",
+        paths: &["/api/v1/releases", "/releases.json"],
+        query_param: None,
+    },
+    EndpointGroup {
+        id: "docs",
+        description: "Versioned documentation pages",
+        paths: &["/api/v1/docs"],
+        query_param: None,
+    },
+    EndpointGroup {
+        id: "faq",
+        description: "FAQ topics and questions",
+        paths: &["/api/v1/faq"],
+        query_param: None,
+    },
+    EndpointGroup {
+        id: "series",
+        description: "Article series",
+        paths: &["/api/v1/series"],
+        query_param: None,
+    },
+    EndpointGroup {
+        id: "translations",
+        description: "Language variants of articles and pages",
+        paths: &["/api/v1/translations"],
+        query_param: None,
+    },
+    EndpointGroup {
+        id: "sitemap",
+        description: "XML sitemap",
+        paths: &["/sitemap.xml"],
+        query_param: None,
+    },
+    EndpointGroup {
+        id: "plugin_proxy",
+        description: "Plugin HTTP proxy",
+        paths: &["/api/v1/plugins/proxy"],
+        query_param: None,
+    },
+];
+
+/// Public endpoints that change data on behalf of visitors
+const PUBLIC_WRITE_PATHS: &[&str] = &[
+    "/api/v1/comments",
+    "/api/v1/embed",
+    "/api/v1/like",
+    "/api/v1/view",
+    "/api/v1/polls",
+    "/api/v1/auth/register",
+    "/api/v1/captcha",
+    "/api/v1/plugins/proxy",
+    "/api/v1/plugins/",
+    "/webmention",
+    "/api/v1/newsletter/subscribe",
+    "/api/v1/push/subscribe",
+];
+
+/// Whether `path` is `base` or below it
+fn path_matches(path: &str, base: &str) -> bool {
+    if base.ends_with('/') {
+        return path.starts_with(base);
+    }
+    path.strip_prefix(base)
+        .is_some_and(|rest| rest.is_empty() || rest.starts_with('/'))
+}
+
+fn has_query_param(query: Option<&str>, name: &str) -> bool {
+    query.is_some_and(|query| {
+        query.split('&').any(|pair| {
+            let (key, value) = pair.split_once('=').unwrap_or((pair, ""));
+            key == name && !value.is_empty()
+        })
+    })
+}
+
+impl EndpointGroup {
+    fn matches(&self, path: &str, query: Option<&str>) -> bool {
+        let in_paths = match self.query_param {
+            // Query-selected groups cover exactly their paths
+            Some(_) => self.paths.contains(&path),
+            None => self.paths.iter().any(|base| path_matches(path, base)),
+        };
+        in_paths
+            && self
+                .query_param
+                .is_none_or(|name| has_query_param(query, name))
+    }
+}
+
+/// Why a request was refused
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Refusal {
+    /// The endpoint group is switched off
+    Disabled,
+    /// Visitor writes are blocked
+    ReadOnly,
+}
+
+/// Exposure settings
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ApiExposure {
+    pub read_only: bool,
+    /// Group id to enabled flag, for every group
+    pub endpoints: HashMap<String, bool>,
+}
+
+impl ApiExposure {
+    /// Decide whether a request may proceed
+    pub fn check(&self, method: &Method, path: &str, query: Option<&str>) -> Option<Refusal> {
+        let disabled = ENDPOINT_GROUPS.iter().any(|group| {
+            !self.endpoints.get(group.id).copied().unwrap_or(true) && group.matches(path, query)
+        });
+        if disabled {
+            return Some(Refusal::Disabled);
+        }
+
+        let is_write = !matches!(*method, Method::GET | Method::HEAD | Method::OPTIONS);
+        if self.read_only
+            && is_write
+            && PUBLIC_WRITE_PATHS
+                .iter()
+                .any(|base| path_matches(path, base))
+        {
+            return Some(Refusal::ReadOnly);
+        }
+        None
+    }
+}
+
+/// Input for changing exposure; omitted fields and groups are kept
+#[derive(Debug, Clone, Deserialize)]
+pub struct UpdateApiExposureInput {
+    pub read_only: Option<bool>,
+    #[serde(default)]
+    pub endpoints: HashMap<String, bool>,
+}
+
+/// Reads and changes endpoint exposure
+pub struct ApiExposureService {
+    settings: Arc<SettingsService>,
+    cached: RwLock<Option<(Instant, Arc<ApiExposure>)>>,
+}
+
+impl ApiExposureService {
+    pub fn new(settings: Arc<SettingsService>) -> Self {
+        Self {
+            settings,
+            cached: RwLock::new(None),
+        }
+    }
+
+    /// Current settings
+    pub async fn current(&self) -> Arc<ApiExposure> {
+        if let Some((at, exposure)) = self.cached.read().await.as_ref() {
+            if at.elapsed() < STATE_TTL {
+                return exposure.clone();
+            }
+        }
+
+        let all = self.settings.get_all_settings().await.unwrap_or_default();
+        let exposure = Arc::new(ApiExposure {
+            read_only: all.get(API_READ_ONLY_KEY).map(String::as_str) == Some("true"),
+            endpoints: ENDPOINT_GROUPS
+                .iter()
+                .map(|group| {
+                    let key = format!("{}{}", API_EXPOSE_KEY_PREFIX, group.id);
+                    (
+                        group.id.to_string(),
+                        all.get(&key).map(String::as_str) != Some("false"),
+                    )
+                })
+                .collect(),
+        });
+        *self.cached.write().await = Some((Instant::now(), exposure.clone()));
+        exposure
+    }
+
+    /// Change exposure settings
+    pub async fn update(
+        &self,
+        input: UpdateApiExposureInput,
+    ) -> Result<Arc<ApiExposure>, ApiExposureError> {
+        if let Some(unknown) = input
+            .endpoints
+            .keys()
+            .find(|id| !ENDPOINT_GROUPS.iter().any(|group| group.id == id.as_str()))
+        {
+            return Err(ApiExposureError::Validation(format!(
+                "Unknown endpoint group: {}",
+                unknown
+            )));
+        }
+
+        let mut values: HashMap<String, String> = input
+            .endpoints
+            .iter()
+            .map(|(id, enabled)| {
+                (
+                    format!("{}{}", API_EXPOSE_KEY_PREFIX, id),
+                    enabled.to_string(),
+                )
+            })
+            .collect();
+        if let Some(read_only) = input.read_only {
+            values.insert(API_READ_ONLY_KEY.to_string(), read_only.to_string());
+        }
+        for (key, value) in &values {
+            self.settings
+                .set(key, value)
+                .await
+                .map_err(|e| ApiExposureError::Internal(e.to_string()))?;
+        }
+
+        *self.cached.write().await = None;
+        Ok(self.current().await)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn exposure(read_only: bool, disabled: &[&str]) -> ApiExposure {
+        ApiExposure {
+            read_only,
+            endpoints: ENDPOINT_GROUPS
+                .iter()
+                .map(|g| (g.id.to_string(), !disabled.contains(&g.id)))
+                .collect(),
+        }
+    }
+
+    #[test]
+    fn disabled_groups_are_refused() {
+        let get = Method::GET;
+        let exposure = exposure(false, &["search", "comments", "feeds"]);
+        assert_eq!(
+            exposure.check(&get, "/api/v1/articles", Some("keyword=rust&page=1")),
+            Some(Refusal::Disabled)
+        );
+        assert_eq!(
+            exposure.check(&get, "/api/v1/articles", Some("page=1&keyword=")),
+            None
+        );
+        assert_eq!(exposure.check(&get, "/api/v1/articles/hello", None), None);
+        assert_eq!(
+            exposure.check(&get, "/api/v1/comments/recent", None),
+            Some(Refusal::Disabled)
+        );
+        assert_eq!(
+            exposure.check(&get, "/api/v1/embed/comments/a", None),
+            Some(Refusal::Disabled)
+        );
+        assert_eq!(exposure.check(&get, "/feed", None), Some(Refusal::Disabled));
+        assert_eq!(exposure.check(&get, "/feedback", None), None);
+        assert_eq!(exposure.check(&get, "/api/v1/like/check", None), None);
+    }
+
+    #[test]
+    fn read_only_blocks_visitor_writes() {
+        let exposure = exposure(true, &[]);
+        assert_eq!(
+            exposure.check(&Method::POST, "/api/v1/comments", None),
+            Some(Refusal::ReadOnly)
+        );
+        assert_eq!(
+            exposure.check(&Method::POST, "/api/v1/auth/register", None),
+            Some(Refusal::ReadOnly)
+        );
+        assert_eq!(
+            exposure.check(&Method::GET, "/api/v1/comments/1", None),
+            None
+        );
+        assert_eq!(
+            exposure.check(&Method::POST, "/api/v1/auth/login", None),
+            None
+        );
+        assert_eq!(
+            exposure.check(&Method::POST, "/api/v1/articles", None),
+            None
+        );
+        assert_eq!(
+            exposure.check(&Method::PUT, "/api/v1/admin/settings", None),
+            None
+        );
+    }
+}
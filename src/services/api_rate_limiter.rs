@@ -0,0 +1,357 @@
+//! Token bucket rate limiting for the API
+//!
+//! Requests are grouped into classes (public API, comment submissions,
+//! uploads), each with its own bucket per client. Buckets live in memory by
+//! default; the Redis store shares them between instances.
+
+use async_trait::async_trait;
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tokio::sync::Mutex;
+
+use crate::config::{CacheConfig, CacheDriver, RateLimitConfig, RateLimitRule};
+
+/// Class of request sharing one set of limits
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RateLimitClass {
+    Api,
+    Comments,
+    Uploads,
+}
+
+impl RateLimitClass {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Api => "api",
+            Self::Comments => "comments",
+            Self::Uploads => "uploads",
+        }
+    }
+}
+
+/// Outcome of taking a token
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RateDecision {
+    Allowed,
+    /// The bucket is empty; retry after this many seconds
+    Limited {
+        retry_after_secs: u64,
+    },
+}
+
+/// Storage for token buckets
+#[async_trait]
+pub trait RateLimitStore: Send + Sync {
+    /// Take one token from the bucket `key`
+    async fn take(&self, key: &str, rule: RateLimitRule) -> anyhow::Result<RateDecision>;
+
+    /// Drop state that no longer affects decisions
+    async fn cleanup(&self) {}
+}
+
+/// Tokens refilled per second
+fn refill_rate(rule: RateLimitRule) -> f64 {
+    f64::from(rule.per_minute) / 60.0
+}
+
+fn bucket_size(rule: RateLimitRule) -> f64 {
+    f64::from(rule.burst.max(1))
+}
+
+#[derive(Debug, Clone)]
+struct Bucket {
+    tokens: f64,
+    updated: Instant,
+    /// When the bucket will be full again, after which it can be dropped
+    full_at: Instant,
+}
+
+impl Bucket {
+    fn new(rule: RateLimitRule, now: Instant) -> Self {
+        Self {
+            tokens: bucket_size(rule),
+            updated: now,
+            full_at: now,
+        }
+    }
+
+    fn take(&mut self, rule: RateLimitRule, now: Instant) -> RateDecision {
+        let rate = refill_rate(rule);
+        let size = bucket_size(rule);
+        let elapsed = now.saturating_duration_since(self.updated).as_secs_f64();
+        self.tokens = (self.tokens + elapsed * rate).min(size);
+        self.updated = now;
+
+        let decision = if self.tokens >= 1.0 {
+            self.tokens -= 1.0;
+            RateDecision::Allowed
+        } else {
+            RateDecision::Limited {
+                retry_after_secs: ((1.0 - self.tokens) / rate).ceil().max(1.0) as u64,
+            }
+        };
+        self.full_at = now + Duration::from_secs_f64((size - self.tokens) / rate);
+        decision
+    }
+}
+
+/// In-process bucket store for single-instance deployments
+#[derive(Default)]
+pub struct MemoryRateLimitStore {
+    buckets: Mutex<HashMap<String, Bucket>>,
+}
+
+impl MemoryRateLimitStore {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+#[async_trait]
+impl RateLimitStore for MemoryRateLimitStore {
+    async fn take(&self, key: &str, rule: RateLimitRule) -> anyhow::Result<RateDecision> {
+        let now = Instant::now();
+        let mut buckets = self.buckets.lock().await;
+        let bucket = buckets
+            .entry(key.to_string())
+            .or_insert_with(|| Bucket::new(rule, now));
+        Ok(bucket.take(rule, now))
+    }
+
+    async fn cleanup(&self) {
+        let now = Instant::now();
+        self.buckets
+            .lock()
+            .await
+            .retain(|_, bucket| bucket.full_at > now);
+    }
+}
+
+/// Redis-backed bucket store shared by all instances
+#[cfg(feature = "redis-cache")]
+pub struct RedisRateLimitStore {
+    connection: redis::aio::MultiplexedConnection,
+    script: redis::Script,
+}
+
+/// Atomically refill and take from a bucket using the Redis clock.
+/// Returns 0 when allowed, else the wait in milliseconds.
+#[cfg(feature = "redis-cache")]
+const TAKE_TOKEN_SCRIPT: &str = r#"
+local rate = tonumber(ARGV[1])
+local size = tonumber(ARGV[2])
+local time = redis.call('TIME')
+local now = tonumber(time[1]) * 1000 + math.floor(tonumber(time[2]) / 1000)
+local state = redis.call('HMGET', KEYS[1], 'tokens', 'ts')
+local tokens = tonumber(state[1]) or size
+local ts = tonumber(state[2]) or now
+tokens = math.min(size, tokens + math.max(0, now - ts) * rate)
+local wait = 0
+if tokens >= 1 then
+  tokens = tokens - 1
+else
+  wait = math.ceil((1 - tokens) / rate)
+end
+redis.call('HSET', KEYS[1], 'tokens', tostring(tokens), 'ts', now)
+redis.call('PEXPIRE', KEYS[1], math.ceil((size - tokens) / rate) + 1000)
+return wait
+"#;
+
+#[cfg(feature = "redis-cache")]
+impl RedisRateLimitStore {
+    /// Connect to Redis
+    pub async fn new(redis_url: &str) -> anyhow::Result<Self> {
+        use anyhow::Context;
+
+        let client = redis::Client::open(redis_url).context("Failed to create Redis client")?;
+        let connection = client
+            .get_multiplexed_async_connection()
+            .await
+            .context("Failed to connect to Redis")?;
+        Ok(Self {
+            connection,
+            script: redis::Script::new(TAKE_TOKEN_SCRIPT),
+        })
+    }
+}
+
+#[cfg(feature = "redis-cache")]
+#[async_trait]
+impl RateLimitStore for RedisRateLimitStore {
+    async fn take(&self, key: &str, rule: RateLimitRule) -> anyhow::Result<RateDecision> {
+        let mut connection = self.connection.clone();
+        // Tokens per millisecond
+        let rate = refill_rate(rule) / 1000.0;
+        let wait_ms: u64 = self
+            .script
+            .key(format!("noteva:ratelimit:{}", key))
+            .arg(rate)
+            .arg(bucket_size(rule))
+            .invoke_async(&mut connection)
+            .await?;
+        Ok(if wait_ms == 0 {
+            RateDecision::Allowed
+        } else {
+            RateDecision::Limited {
+                retry_after_secs: wait_ms.div_ceil(1000),
+            }
+        })
+    }
+}
+
+/// Rate limiter applied by the API middleware
+pub struct ApiRateLimiter {
+    config: RateLimitConfig,
+    store: Arc<dyn RateLimitStore>,
+}
+
+impl ApiRateLimiter {
+    pub fn new(config: RateLimitConfig, store: Arc<dyn RateLimitStore>) -> Self {
+        Self { config, store }
+    }
+
+    /// Create a limiter with the store selected in `config`.
+    ///
+    /// The Redis store uses `rate_limit.redis_url`, falling back to
+    /// `cache.redis_url`, and requires the `redis-cache` feature.
+    pub async fn from_config(
+        config: &RateLimitConfig,
+        cache: &CacheConfig,
+    ) -> anyhow::Result<Self> {
+        let store: Arc<dyn RateLimitStore> = match config.driver {
+            CacheDriver::Memory => Arc::new(MemoryRateLimitStore::new()),
+            CacheDriver::Redis => {
+                #[cfg(feature = "redis-cache")]
+                {
+                    let redis_url = config
+                        .redis_url
+                        .as_ref()
+                        .or(cache.redis_url.as_ref())
+                        .ok_or_else(|| {
+                            anyhow::anyhow!(
+                                "Redis rate limiting requires rate_limit.redis_url or cache.redis_url"
+                            )
+                        })?;
+                    Arc::new(RedisRateLimitStore::new(redis_url).await?)
+                }
+                #[cfg(not(feature = "redis-cache"))]
+                {
+                    let _ = cache;
+                    anyhow::bail!(
+                        "Redis rate limiting is configured but the 'redis-cache' feature is not enabled"
+                    );
+                }
+            }
+        };
+        Ok(Self::new(config.clone(), store))
+    }
+
+    fn rule(&self, class: RateLimitClass) -> RateLimitRule {
+        match class {
+            RateLimitClass::Api => self.config.api,
+            RateLimitClass::Comments => self.config.comments,
+            RateLimitClass::Uploads => self.config.uploads,
+        }
+    }
+
+    /// Take a token for `client` (an IP address or session key).
+    ///
+    /// Store errors are logged and the request is let through.
+    pub async fn check(&self, class: RateLimitClass, client: &str) -> RateDecision {
+        let rule = self.rule(class);
+        if !self.config.enabled || rule.per_minute == 0 {
+            return RateDecision::Allowed;
+        }
+        let key = format!("{}:{}", class.as_str(), client);
+        match self.store.take(&key, rule).await {
+            Ok(decision) => decision,
+            Err(e) => {
+                tracing::warn!(error = %e, "rate limit store unavailable");
+                RateDecision::Allowed
+            }
+        }
+    }
+
+    /// Drop idle buckets (should be called periodically)
+    pub async fn cleanup(&self) {
+        self.store.cleanup().await;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const RULE: RateLimitRule = RateLimitRule {
+        per_minute: 60,
+        burst: 2,
+    };
+
+    #[test]
+    fn bucket_allows_burst_then_refills() {
+        let start = Instant::now();
+        let mut bucket = Bucket::new(RULE, start);
+
+        assert_eq!(bucket.take(RULE, start), RateDecision::Allowed);
+        assert_eq!(bucket.take(RULE, start), RateDecision::Allowed);
+        assert_eq!(
+            bucket.take(RULE, start),
+            RateDecision::Limited {
+                retry_after_secs: 1
+            }
+        );
+
+        // One token per second at 60 per minute
+        let later = start + Duration::from_secs(1);
+        assert_eq!(bucket.take(RULE, later), RateDecision::Allowed);
+        assert!(matches!(
+            bucket.take(RULE, later),
+            RateDecision::Limited { .. }
+        ));
+    }
+
+    #[tokio::test]
+    async fn limiter_keeps_clients_and_classes_apart() {
+        let config = RateLimitConfig {
+            comments: RULE,
+            ..Default::default()
+        };
+        let limiter = ApiRateLimiter::new(config, Arc::new(MemoryRateLimitStore::new()));
+
+        for _ in 0..2 {
+            assert_eq!(
+                limiter.check(RateLimitClass::Comments, "ip:1.2.3.4").await,
+                RateDecision::Allowed
+            );
+        }
+        assert!(matches!(
+            limiter.check(RateLimitClass::Comments, "ip:1.2.3.4").await,
+            RateDecision::Limited { .. }
+        ));
+        assert_eq!(
+            limiter.check(RateLimitClass::Comments, "ip:5.6.7.8").await,
+            RateDecision::Allowed
+        );
+        assert_eq!(
+            limiter.check(RateLimitClass::Api, "ip:1.2.3.4").await,
+            RateDecision::Allowed
+        );
+    }
+
+    #[tokio::test]
+    async fn disabled_limiter_allows_everything() {
+        let config = RateLimitConfig {
+            enabled: false,
+            comments: RULE,
+            ..Default::default()
+        };
+        let limiter = ApiRateLimiter::new(config, Arc::new(MemoryRateLimitStore::new()));
+        for _ in 0..5 {
+            assert_eq!(
+                limiter.check(RateLimitClass::Comments, "ip:1.2.3.4").await,
+                RateDecision::Allowed
+            );
+        }
+    }
+}
@@ -17,11 +17,13 @@
 use crate::cache::{Cache, CacheLayer};
 use crate::db::repositories::{ArticleRepository, TagRepository};
 use crate::models::{
-    Article, ArticleSortBy, ArticleStatus, CreateArticleInput, ListParams, PagedResult,
-    UpdateArticleInput,
+    Article, ArticleCursor, ArticleFilter, ArticleListScope, ArticleReader, ArticleSlug,
+    ArticleSortBy, ArticleStatus, CategoryAccess, ContentLicense, CreateArticleInput, CursorPage,
+    InputFormat, ListParams, PagedResult, PopularWindow, UpdateArticleInput, UserRole,
 };
 use crate::plugin::{hook_names, HookManager};
 use crate::services::markdown::MarkdownRenderer;
+use crate::services::markup;
 use anyhow::Context;
 use serde_json::json;
 use std::sync::Arc;
@@ -38,6 +40,14 @@ const CACHE_KEY_ARTICLE_BY_ID: &str = "article:id:";
 const CACHE_KEY_ARTICLE_BY_SLUG: &str = "article:slug:";
 const CACHE_KEY_ARTICLE_LIST: &str = "articles:list";
 
+/// Length limits of the SEO overrides, matching their columns
+const MAX_META_TITLE_LEN: usize = 200;
+const MAX_META_DESCRIPTION_LEN: usize = 500;
+const MAX_CANONICAL_URL_LEN: usize = 1000;
+
+/// Longest excerpt an author may write
+const MAX_EXCERPT_LEN: usize = 1000;
+
 /// Error types for article service operations
 #[derive(Debug, thiserror::Error)]
 pub enum ArticleServiceError {
@@ -53,6 +63,11 @@ pub enum ArticleServiceError {
     #[error("Article slug already exists: {0}")]
     DuplicateSlug(String),
 
+    /// Slug an earlier (possibly deleted) article had; its old links would
+    /// lead to the new article
+    #[error("Article slug was used by another article: {0}")]
+    ReservedSlug(String),
+
     /// Internal error
     #[error("Internal error: {0}")]
     InternalError(#[from] anyhow::Error),
@@ -196,6 +211,7 @@ impl ArticleService {
 
         // Validate input (Requirement 1.7)
         self.validate_create_input(&input)?;
+        input.license = input.license.map(normalize_license).transpose()?;
 
         // Generate slug if empty
         if input.slug.trim().is_empty() {
@@ -212,33 +228,21 @@ impl ArticleService {
         {
             return Err(ArticleServiceError::DuplicateSlug(input.slug));
         }
+        if !input.reclaim_slug && self.slug_owner(&input.slug).await?.is_some() {
+            return Err(ArticleServiceError::ReservedSlug(input.slug));
+        }
 
         if let Some(ids) = tag_ids.as_deref() {
             self.validate_tag_ids(ids).await?;
         }
 
         // Render markdown to HTML with shortcode processing
-        let _content_html = self
-            .markdown_renderer
-            .render_article(&input.content, 0, None);
-
-        // Trigger article_content_filter hook
-        let filter_data = self.trigger_hook(
-            hook_names::ARTICLE_CONTENT_FILTER,
-            json!({
-                "content": &input.content,
-                "article_id": 0,
-            }),
-        );
+        let _content_html =
+            self.markdown_renderer
+                .render_source(input.input_format, &input.content, 0, None);
 
-        let filtered_content = filter_data
-            .get("content")
-            .and_then(|v| v.as_str())
-            .unwrap_or(&input.content);
-
-        let final_content_html = self
-            .markdown_renderer
-            .render_article(filtered_content, 0, None);
+        let final_content_html = self.render_filtered(input.input_format, &input.content, 0);
+        input.stats = Some(MarkdownRenderer::content_stats(&final_content_html));
         input.content_html = Some(final_content_html);
 
         // Create article
@@ -347,6 +351,8 @@ impl ArticleService {
     ///
     /// # Arguments
     /// * `params` - Pagination parameters
+    /// * `viewer` - Who is listing; unpublished articles they may not read
+    ///   come back redacted
     ///
     /// # Returns
     /// Paginated result of articles
@@ -356,11 +362,12 @@ impl ArticleService {
         &self,
         params: &ListParams,
         sort_by: ArticleSortBy,
+        viewer: Option<&ArticleReader>,
     ) -> Result<PagedResult<Article>, ArticleServiceError> {
         let offset = params.offset();
         let limit = params.limit();
 
-        let articles = self
+        let mut articles = self
             .repo
             .list(offset, limit, sort_by)
             .await
@@ -372,6 +379,7 @@ impl ArticleService {
             .await
             .context("Failed to count articles")?;
 
+        redact_unpublished(viewer, &mut articles);
         Ok(PagedResult::new(articles, total, params))
     }
 
@@ -437,6 +445,7 @@ impl ArticleService {
         status: ArticleStatus,
         params: &ListParams,
         sort_by: ArticleSortBy,
+        viewer: Option<&ArticleReader>,
     ) -> Result<PagedResult<Article>, ArticleServiceError> {
         if status == ArticleStatus::Published {
             return self.list_published(params, sort_by).await;
@@ -452,7 +461,8 @@ impl ArticleService {
             limit,
             sort_by.cache_key()
         );
-        if let Ok(Some(cached)) = self.cache.get::<PagedResult<Article>>(&cache_key).await {
+        if let Ok(Some(mut cached)) = self.cache.get::<PagedResult<Article>>(&cache_key).await {
+            redact_unpublished(viewer, &mut cached.items);
             return Ok(cached);
         }
 
@@ -468,7 +478,7 @@ impl ArticleService {
             .await
             .context("Failed to count articles by status")?;
 
-        let result = PagedResult::new(articles, total, params);
+        let mut result = PagedResult::new(articles, total, params);
         let _ = self
             .cache
             .set(
@@ -478,6 +488,7 @@ impl ArticleService {
             )
             .await;
 
+        redact_unpublished(viewer, &mut result.items);
         Ok(result)
     }
 
@@ -494,11 +505,12 @@ impl ArticleService {
         category_id: i64,
         params: &ListParams,
         sort_by: ArticleSortBy,
+        viewer: Option<&ArticleReader>,
     ) -> Result<PagedResult<Article>, ArticleServiceError> {
         let offset = params.offset();
         let limit = params.limit();
 
-        let articles = self
+        let mut articles = self
             .repo
             .list_by_category(category_id, offset, limit, sort_by)
             .await
@@ -510,6 +522,7 @@ impl ArticleService {
             .await
             .context("Failed to count articles by category")?;
 
+        redact_unpublished(viewer, &mut articles);
         Ok(PagedResult::new(articles, total, params))
     }
 
@@ -551,11 +564,12 @@ impl ArticleService {
         tag_id: i64,
         params: &ListParams,
         sort_by: ArticleSortBy,
+        viewer: Option<&ArticleReader>,
     ) -> Result<PagedResult<Article>, ArticleServiceError> {
         let offset = params.offset();
         let limit = params.limit();
 
-        let articles = self
+        let mut articles = self
             .repo
             .list_by_tag(tag_id, offset, limit, sort_by)
             .await
@@ -567,6 +581,7 @@ impl ArticleService {
             .await
             .context("Failed to count articles by tag")?;
 
+        redact_unpublished(viewer, &mut articles);
         Ok(PagedResult::new(articles, total, params))
     }
 
@@ -595,6 +610,104 @@ impl ArticleService {
         Ok(PagedResult::new(articles, total, params))
     }
 
+    /// List articles matching the filters and sort order in `params`
+    ///
+    /// Filtering, sorting and pagination all happen in SQL.
+    pub async fn list_filtered(
+        &self,
+        params: &ListParams,
+        viewer: Option<&ArticleReader>,
+    ) -> Result<PagedResult<Article>, ArticleServiceError> {
+        let mut articles = self
+            .repo
+            .list_filtered(params)
+            .await
+            .context("Failed to list filtered articles")?;
+
+        let total = self
+            .repo
+            .count_filtered(&params.filter)
+            .await
+            .context("Failed to count filtered articles")?;
+
+        redact_unpublished(viewer, &mut articles);
+        Ok(PagedResult::new(articles, total, params))
+    }
+
+    /// List articles with keyset (cursor) pagination
+    ///
+    /// Articles are ordered by `created_at DESC, id DESC`; pinned articles
+    /// are not moved to the top. Pass the returned `next_cursor` to get the
+    /// following page. Published scopes leave out categories above
+    /// `clearance` and articles whose audience does not admit `viewer`.
+    pub async fn list_by_cursor(
+        &self,
+        scope: &ArticleListScope,
+        cursor: Option<&ArticleCursor>,
+        limit: i64,
+        clearance: CategoryAccess,
+        viewer: Option<&ArticleReader>,
+    ) -> Result<CursorPage<Article>, ArticleServiceError> {
+        let limit = limit.clamp(1, 100);
+
+        // Fetch one extra row to know whether another page exists
+        let mut articles = self
+            .repo
+            .list_after_cursor(scope, cursor, limit + 1, clearance, viewer)
+            .await
+            .context("Failed to list articles after cursor")?;
+        let next_cursor = if articles.len() as i64 > limit {
+            articles.truncate(limit as usize);
+            articles
+                .last()
+                .map(|article| ArticleCursor::from_article(article).encode())
+        } else {
+            None
+        };
+
+        // Published totals go through the same access filter as the rows
+        let published = |category_ids: Vec<i64>, tag_id: Option<i64>| ArticleFilter {
+            status: Some(ArticleStatus::Published),
+            category_ids,
+            tag_id,
+            clearance: Some(clearance),
+            reader: viewer.copied(),
+            ..Default::default()
+        };
+        let total = match scope {
+            ArticleListScope::All => self.repo.count().await,
+            ArticleListScope::Status(ArticleStatus::Published) => {
+                self.repo.count_filtered(&published(Vec::new(), None)).await
+            }
+            ArticleListScope::Status(status) => self.repo.count_by_status(*status).await,
+            ArticleListScope::Category(category_id) => {
+                self.repo.count_by_category(*category_id).await
+            }
+            ArticleListScope::Tag(tag_id) => self.repo.count_by_tag(*tag_id).await,
+            ArticleListScope::PublishedInCategories(category_ids) if category_ids.is_empty() => {
+                Ok(0)
+            }
+            ArticleListScope::PublishedInCategories(category_ids) => {
+                self.repo
+                    .count_filtered(&published(category_ids.clone(), None))
+                    .await
+            }
+            ArticleListScope::PublishedWithTag(tag_id) => {
+                self.repo
+                    .count_filtered(&published(Vec::new(), Some(*tag_id)))
+                    .await
+            }
+        }
+        .context("Failed to count articles")?;
+
+        redact_unpublished(viewer, &mut articles);
+        Ok(CursorPage {
+            items: articles,
+            total,
+            next_cursor,
+        })
+    }
+
     /// Search articles by keyword
     ///
     /// Searches in article title and content.
@@ -612,11 +725,12 @@ impl ArticleService {
         params: &ListParams,
         published_only: bool,
         sort_by: ArticleSortBy,
+        viewer: Option<&ArticleReader>,
     ) -> Result<PagedResult<Article>, ArticleServiceError> {
         let offset = params.offset();
         let limit = params.limit();
 
-        let articles = self
+        let mut articles = self
             .repo
             .search(keyword, offset, limit, published_only, sort_by)
             .await
@@ -628,6 +742,7 @@ impl ArticleService {
             .await
             .context("Failed to count search results")?;
 
+        redact_unpublished(viewer, &mut articles);
         Ok(PagedResult::new(articles, total, params))
     }
 
@@ -700,6 +815,10 @@ impl ArticleService {
 
         // Validate input (Requirement 1.7)
         self.validate_update_input(&input, &existing)?;
+        input.license = input
+            .license
+            .map(|license| license.map(normalize_license).transpose())
+            .transpose()?;
 
         // Check slug uniqueness if slug is being changed
         if let Some(ref new_slug) = input.slug {
@@ -712,6 +831,10 @@ impl ArticleService {
                 {
                     return Err(ArticleServiceError::DuplicateSlug(new_slug.clone()));
                 }
+                let owner = self.slug_owner(new_slug).await?;
+                if !input.reclaim_slug && owner.is_some_and(|owner| owner != id) {
+                    return Err(ArticleServiceError::ReservedSlug(new_slug.clone()));
+                }
             }
         }
 
@@ -719,25 +842,12 @@ impl ArticleService {
             self.validate_tag_ids(new_tag_ids).await?;
         }
 
-        // Re-render markdown if content is being updated (with shortcode processing)
-        if let Some(ref content) = input.content {
-            // Trigger article_content_filter hook
-            let filter_data = self.trigger_hook(
-                hook_names::ARTICLE_CONTENT_FILTER,
-                json!({
-                    "content": content,
-                    "article_id": id,
-                }),
-            );
-
-            let filtered_content = filter_data
-                .get("content")
-                .and_then(|v| v.as_str())
-                .unwrap_or(content);
-
-            let content_html = self
-                .markdown_renderer
-                .render_article(filtered_content, id, None);
+        // Re-render if content or input format is being updated (with shortcode processing)
+        if input.content.is_some() || input.input_format.is_some() {
+            let content = input.content.as_deref().unwrap_or(&existing.content);
+            let input_format = input.input_format.unwrap_or(existing.input_format);
+            let content_html = self.render_filtered(input_format, content, id);
+            input.stats = Some(MarkdownRenderer::content_stats(&content_html));
             input.content_html = Some(content_html);
         }
 
@@ -861,6 +971,40 @@ impl ArticleService {
         Ok(())
     }
 
+    async fn slug_owner(&self, slug: &str) -> Result<Option<i64>, ArticleServiceError> {
+        self.repo
+            .get_slug_owner(slug)
+            .await
+            .context("Failed to check slug history")
+            .map_err(Into::into)
+    }
+
+    /// Find the article that used to have `slug`, for redirecting old links
+    ///
+    /// Returns None when no live article had it, or when it is still the
+    /// article's current slug.
+    pub async fn get_by_previous_slug(
+        &self,
+        slug: &str,
+    ) -> Result<Option<Article>, ArticleServiceError> {
+        let Some(owner) = self.slug_owner(slug).await? else {
+            return Ok(None);
+        };
+        Ok(self.get_by_id(owner).await?.filter(|a| a.slug != slug))
+    }
+
+    /// Every slug an article has had, oldest first
+    pub async fn list_slug_history(
+        &self,
+        article_id: i64,
+    ) -> Result<Vec<ArticleSlug>, ArticleServiceError> {
+        self.repo
+            .list_slugs(article_id)
+            .await
+            .context("Failed to list slug history")
+            .map_err(Into::into)
+    }
+
     /// Render markdown content to HTML
     ///
     /// # Arguments
@@ -879,6 +1023,63 @@ impl ArticleService {
         self.markdown_renderer.extract_toc(content)
     }
 
+    /// Extract table of contents from an article source; BBCode and
+    /// reStructuredText have none.
+    pub fn extract_source_toc(
+        &self,
+        format: InputFormat,
+        content: &str,
+    ) -> Vec<crate::services::markdown::TocEntry> {
+        match format {
+            InputFormat::Markdown => self.markdown_renderer.extract_toc(content),
+            InputFormat::Html => crate::services::markup::html::anchor_headings(content).1,
+            InputFormat::Blocks => match markup::blocks::parse(content) {
+                Ok(blocks) => {
+                    let html = markup::blocks::to_html(&blocks, &|_, _| String::new());
+                    markup::html::anchor_headings(&html).1
+                }
+                Err(_) => Vec::new(),
+            },
+            _ => Vec::new(),
+        }
+    }
+
+    /// Render an article source in its input format with shortcode processing
+    pub fn render_source(
+        &self,
+        format: InputFormat,
+        content: &str,
+        article_id: Option<i64>,
+        user_id: Option<i64>,
+    ) -> String {
+        self.markdown_renderer
+            .render_source(format, content, article_id.unwrap_or(0), user_id)
+    }
+
+    /// Render an article source the way it is stored on save: the
+    /// `article_content_filter` hook runs first, then shortcodes, the
+    /// Markdown engine, `markdown_after_parse` and sanitizing.
+    ///
+    /// `article_id` is 0 for articles that do not exist yet.
+    pub fn render_filtered(&self, format: InputFormat, content: &str, article_id: i64) -> String {
+        // Trigger article_content_filter hook
+        let filter_data = self.trigger_hook(
+            hook_names::ARTICLE_CONTENT_FILTER,
+            json!({
+                "content": content,
+                "article_id": article_id,
+            }),
+        );
+
+        let filtered_content = filter_data
+            .get("content")
+            .and_then(|v| v.as_str())
+            .unwrap_or(content);
+
+        self.markdown_renderer
+            .render_source(format, filtered_content, article_id, None)
+    }
+
     /// Render markdown content to HTML with shortcode processing
     ///
     /// # Arguments
@@ -960,6 +1161,50 @@ impl ArticleService {
             .map_err(Into::into)
     }
 
+    /// Published articles with the most views in `window`, each with its
+    /// views in the window. Cached like other lists, so rankings lag behind
+    /// new views by up to ten minutes.
+    pub async fn list_popular(
+        &self,
+        window: PopularWindow,
+        limit: i64,
+    ) -> Result<Vec<(Article, i64)>, ArticleServiceError> {
+        let cache_key = format!(
+            "{}:popular:{}:{}",
+            CACHE_KEY_ARTICLE_LIST,
+            window.cache_key(),
+            limit
+        );
+        if let Ok(Some(cached)) = self.cache.get::<Vec<(Article, i64)>>(&cache_key).await {
+            return Ok(cached);
+        }
+
+        let since = match window {
+            PopularWindow::Days(days) => Some(
+                (chrono::Utc::now().date_naive() - chrono::Duration::days(days as i64))
+                    .format("%Y-%m-%d")
+                    .to_string(),
+            ),
+            PopularWindow::AllTime => None,
+        };
+        let popular = self
+            .repo
+            .list_popular(since.as_deref(), limit)
+            .await
+            .context("Failed to list popular articles")?;
+
+        let _ = self
+            .cache
+            .set(
+                &cache_key,
+                &popular,
+                Duration::from_secs(ARTICLE_LIST_CACHE_TTL_SECS),
+            )
+            .await;
+
+        Ok(popular)
+    }
+
     // ========================================================================
     // Private helper methods
     // ========================================================================
@@ -982,6 +1227,9 @@ impl ArticleService {
             ));
         }
 
+        validate_source(input.input_format, &input.content)?;
+        validate_excerpt(input.excerpt.as_deref())?;
+
         Ok(())
     }
 
@@ -1027,6 +1275,18 @@ impl ArticleService {
             ));
         }
 
+        if input.input_format.is_some() || input.content.is_some() {
+            validate_source(
+                input.input_format.unwrap_or(existing.input_format),
+                final_content,
+            )?;
+        }
+
+        validate_seo_fields(input)?;
+        if let Some(excerpt) = &input.excerpt {
+            validate_excerpt(excerpt.as_deref())?;
+        }
+
         Ok(())
     }
 
@@ -1114,10 +1374,46 @@ impl ArticleService {
         Ok(())
     }
 
+    /// Measure articles saved before content stats were stored
+    ///
+    /// Returns how many were measured.
+    pub async fn measure_unmeasured(&self) -> Result<usize, ArticleServiceError> {
+        let mut measured = 0;
+        loop {
+            let batch = self
+                .repo
+                .list_unmeasured(200)
+                .await
+                .context("Failed to list unmeasured articles")?;
+            if batch.is_empty() {
+                break;
+            }
+            for (id, content_html) in batch {
+                self.repo
+                    .set_stats(id, &MarkdownRenderer::content_stats(&content_html))
+                    .await
+                    .with_context(|| format!("Failed to store stats of article {}", id))?;
+                measured += 1;
+            }
+        }
+        if measured > 0 {
+            let _ = self
+                .cache
+                .delete_pattern(&format!("{}*", CACHE_KEY_ARTICLE_BY_ID))
+                .await;
+            let _ = self
+                .cache
+                .delete_pattern(&format!("{}*", CACHE_KEY_ARTICLE_BY_SLUG))
+                .await;
+            self.invalidate_list_cache().await?;
+        }
+        Ok(measured)
+    }
+
     /// Invalidate all article list caches
     ///
     /// Satisfies requirement 1.5: WHEN 文章被创建或更新 THEN Article_Manager SHALL 使相关缓存失�?
-    async fn invalidate_list_cache(&self) -> Result<(), ArticleServiceError> {
+    pub async fn invalidate_list_cache(&self) -> Result<(), ArticleServiceError> {
         let _ = self
             .cache
             .delete_pattern(&format!("{}*", CACHE_KEY_ARTICLE_LIST))
@@ -1126,6 +1422,24 @@ impl ArticleService {
     }
 }
 
+/// Withhold the body of unpublished articles `viewer` did not write
+///
+/// Editors and admins see every article in full; authors see published
+/// articles and their own drafts, and only the metadata of everyone else's.
+/// Without a viewer every unpublished article is redacted.
+fn redact_unpublished(viewer: Option<&ArticleReader>, articles: &mut [Article]) {
+    if viewer.is_some_and(|v| v.has_role(UserRole::Editor)) {
+        return;
+    }
+    for article in articles.iter_mut() {
+        if article.status != ArticleStatus::Published
+            && viewer.is_none_or(|v| article.author_id != v.user_id)
+        {
+            article.redact();
+        }
+    }
+}
+
 /// Generate a URL-friendly slug from a title
 ///
 /// Converts the title to lowercase, replaces spaces and special characters
@@ -1200,5 +1514,80 @@ fn validate_article_slug(slug: &str) -> Result<(), ArticleServiceError> {
     Ok(())
 }
 
+/// Check the SEO overrides of an update against their column limits
+fn validate_seo_fields(input: &UpdateArticleInput) -> Result<(), ArticleServiceError> {
+    let too_long = |value: &Option<Option<String>>, max: usize| {
+        value
+            .as_ref()
+            .and_then(Option::as_deref)
+            .is_some_and(|v| v.chars().count() > max)
+    };
+    if too_long(&input.meta_title, MAX_META_TITLE_LEN) {
+        return Err(ArticleServiceError::ValidationError(format!(
+            "SEO title cannot exceed {} characters",
+            MAX_META_TITLE_LEN
+        )));
+    }
+    if too_long(&input.meta_description, MAX_META_DESCRIPTION_LEN) {
+        return Err(ArticleServiceError::ValidationError(format!(
+            "SEO description cannot exceed {} characters",
+            MAX_META_DESCRIPTION_LEN
+        )));
+    }
+    if let Some(Some(url)) = &input.canonical_url {
+        let valid = if url.starts_with('/') {
+            !url.starts_with("//")
+        } else {
+            reqwest::Url::parse(url).is_ok_and(|parsed| {
+                matches!(parsed.scheme(), "http" | "https") && parsed.host_str().is_some()
+            })
+        };
+        if !valid || url.chars().count() > MAX_CANONICAL_URL_LEN {
+            return Err(ArticleServiceError::ValidationError(
+                "Canonical URL must be a site path or an http(s) URL".to_string(),
+            ));
+        }
+    }
+    Ok(())
+}
+
+/// Check an author-written excerpt against its length limit
+fn validate_excerpt(excerpt: Option<&str>) -> Result<(), ArticleServiceError> {
+    if excerpt.is_some_and(|v| v.chars().count() > MAX_EXCERPT_LEN) {
+        return Err(ArticleServiceError::ValidationError(format!(
+            "Excerpt cannot exceed {} characters",
+            MAX_EXCERPT_LEN
+        )));
+    }
+    Ok(())
+}
+
+/// Tidy a chosen license, refusing custom ones without a name
+fn normalize_license(license: ContentLicense) -> Result<ContentLicense, ArticleServiceError> {
+    license
+        .normalize()
+        .map_err(ArticleServiceError::ValidationError)
+}
+
+/// Check that `content` can be rendered as `format`
+///
+/// Block documents are parsed up front so a malformed one is rejected on save
+/// instead of rendering as escaped JSON.
+fn validate_source(format: InputFormat, content: &str) -> Result<(), ArticleServiceError> {
+    if !markup::is_available(format) {
+        return Err(ArticleServiceError::ValidationError(
+            markup::MarkupError::Unavailable(format).to_string(),
+        ));
+    }
+    if format == InputFormat::Blocks {
+        markup::blocks::parse(content).map_err(|message| {
+            ArticleServiceError::ValidationError(
+                markup::MarkupError::Parse { format, message }.to_string(),
+            )
+        })?;
+    }
+    Ok(())
+}
+
 #[cfg(test)]
 mod tests;
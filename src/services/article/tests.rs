@@ -3,7 +3,7 @@ use crate::cache::create_cache;
 use crate::config::CacheConfig;
 use crate::db::repositories::{SqlxArticleRepository, SqlxTagRepository};
 use crate::db::{create_test_pool, migrations, DynDatabasePool};
-use crate::models::{ArticleSortBy, ArticleStatus};
+use crate::models::{ArticleSortBy, ArticleStatus, LicenseKind};
 
 async fn setup_test_service() -> (DynDatabasePool, ArticleService) {
     let pool = create_test_pool()
@@ -236,6 +236,38 @@ async fn test_create_article_success() {
     assert_eq!(article.status, ArticleStatus::Draft);
 }
 
+#[tokio::test]
+async fn test_create_article_in_bbcode() {
+    let (pool, service) = setup_test_service().await;
+    let sqlite_pool = pool.as_sqlite().unwrap();
+    let author_id = create_test_user(sqlite_pool).await;
+
+    let mut input = CreateArticleInput::new(
+        "forum-post".to_string(),
+        "Forum Post".to_string(),
+        "[b]Hello[/b] from the forum".to_string(),
+        author_id,
+        1,
+    );
+    input.input_format = InputFormat::Bbcode;
+
+    let result = service.create(input, None).await;
+    if !cfg!(feature = "bbcode") {
+        assert!(matches!(
+            result,
+            Err(ArticleServiceError::ValidationError(_))
+        ));
+        return;
+    }
+    let article = result.expect("Failed to create article");
+    assert_eq!(article.input_format, InputFormat::Bbcode);
+    assert_eq!(article.content, "[b]Hello[/b] from the forum");
+    assert!(article.content_html.contains("<strong>Hello</strong>"));
+
+    let stored = service.get_by_id(article.id).await.unwrap().unwrap();
+    assert_eq!(stored.input_format, InputFormat::Bbcode);
+}
+
 #[tokio::test]
 async fn test_create_article_generates_slug_from_title() {
     let (pool, service) = setup_test_service().await;
@@ -422,7 +454,7 @@ async fn test_list_articles() {
 
     let params = ListParams::new(1, 10);
     let result = service
-        .list(&params, ArticleSortBy::default())
+        .list(&params, ArticleSortBy::default(), None)
         .await
         .expect("Failed to list articles");
 
@@ -454,7 +486,7 @@ async fn test_list_articles_pagination() {
     // Get first page
     let params = ListParams::new(1, 3);
     let result = service
-        .list(&params, ArticleSortBy::default())
+        .list(&params, ArticleSortBy::default(), None)
         .await
         .expect("Failed to list articles");
 
@@ -548,11 +580,21 @@ async fn test_list_articles_by_status() {
 
     let params = ListParams::new(1, 10);
     let drafts = service
-        .list_by_status(ArticleStatus::Draft, &params, ArticleSortBy::default())
+        .list_by_status(
+            ArticleStatus::Draft,
+            &params,
+            ArticleSortBy::default(),
+            None,
+        )
         .await
         .expect("Failed to list drafts");
     let archived = service
-        .list_by_status(ArticleStatus::Archived, &params, ArticleSortBy::default())
+        .list_by_status(
+            ArticleStatus::Archived,
+            &params,
+            ArticleSortBy::default(),
+            None,
+        )
         .await
         .expect("Failed to list archived articles");
 
@@ -566,6 +608,92 @@ async fn test_list_articles_by_status() {
     assert_eq!(archived.items[0].status, ArticleStatus::Archived);
 }
 
+#[tokio::test]
+async fn test_listings_redact_other_authors_drafts() {
+    let (pool, service) = setup_test_service().await;
+    let sqlite_pool = pool.as_sqlite().unwrap();
+    let author_id = create_test_user(sqlite_pool).await;
+    let other_id =
+        sqlx::query("INSERT INTO users (username, email, password_hash, role) VALUES (?, ?, ?, ?)")
+            .bind("otheruser")
+            .bind("other@example.com")
+            .bind("hash123")
+            .bind("author")
+            .execute(sqlite_pool)
+            .await
+            .expect("Failed to create second user")
+            .last_insert_rowid();
+
+    for (slug, owner) in [("own-draft", author_id), ("other-draft", other_id)] {
+        let mut input = CreateArticleInput::new(
+            slug.to_string(),
+            "Title".to_string(),
+            "Secret content".to_string(),
+            owner,
+            1,
+        );
+        input.excerpt = Some("Secret excerpt".to_string());
+        service
+            .create(input, None)
+            .await
+            .expect("Failed to create draft");
+    }
+
+    let params = ListParams::new(1, 10);
+    let by_slug = |result: &PagedResult<Article>, slug: &str| {
+        result
+            .items
+            .iter()
+            .find(|article| article.slug == slug)
+            .cloned()
+            .expect("Article missing from listing")
+    };
+
+    let author = ArticleReader {
+        user_id: author_id,
+        role: UserRole::Author,
+    };
+    // Drafts are cached unredacted; a second listing must still redact
+    for _ in 0..2 {
+        let seen = service
+            .list_by_status(
+                ArticleStatus::Draft,
+                &params,
+                ArticleSortBy::default(),
+                Some(&author),
+            )
+            .await
+            .expect("Failed to list drafts");
+        let own = by_slug(&seen, "own-draft");
+        assert!(!own.redacted);
+        assert_eq!(own.content, "Secret content");
+        let other = by_slug(&seen, "other-draft");
+        assert!(other.redacted);
+        assert!(other.content.is_empty() && other.content_html.is_empty());
+        assert_eq!(other.excerpt, None);
+        assert_eq!(other.title, "Title");
+    }
+
+    let anonymous = service
+        .list(&params, ArticleSortBy::default(), None)
+        .await
+        .expect("Failed to list articles");
+    assert!(anonymous.items.iter().all(|article| article.redacted));
+
+    let editor = ArticleReader {
+        user_id: other_id + 1,
+        role: UserRole::Editor,
+    };
+    let seen = service
+        .list(&params, ArticleSortBy::default(), Some(&editor))
+        .await
+        .expect("Failed to list articles");
+    assert!(seen
+        .items
+        .iter()
+        .all(|article| !article.redacted && article.content == "Secret content"));
+}
+
 // ========================================================================
 // Update article tests (Requirement 1.3)
 // ========================================================================
@@ -638,6 +766,214 @@ async fn test_update_article_empty_title_fails() {
     ));
 }
 
+#[tokio::test]
+async fn test_update_article_seo_fields() {
+    let (pool, service) = setup_test_service().await;
+    let sqlite_pool = pool.as_sqlite().unwrap();
+    let author_id = create_test_user(sqlite_pool).await;
+
+    let input = CreateArticleInput::new(
+        "seo-test".to_string(),
+        "SEO Title".to_string(),
+        "Content".to_string(),
+        author_id,
+        1,
+    );
+    let created = service
+        .create(input, None)
+        .await
+        .expect("Failed to create article");
+    assert_eq!(created.meta_title, None);
+    assert!(!created.noindex);
+
+    let update_input = UpdateArticleInput {
+        meta_title: Some(Some("Search title".to_string())),
+        meta_description: Some(Some("Search description".to_string())),
+        canonical_url: Some(Some("https://example.com/original".to_string())),
+        noindex: Some(true),
+        ..Default::default()
+    };
+    let updated = service
+        .update(created.id, update_input, None)
+        .await
+        .expect("Failed to update article");
+    assert_eq!(updated.meta_title.as_deref(), Some("Search title"));
+    assert_eq!(
+        updated.meta_description.as_deref(),
+        Some("Search description")
+    );
+    assert_eq!(
+        updated.canonical_url.as_deref(),
+        Some("https://example.com/original")
+    );
+    assert!(updated.noindex);
+
+    // Untouched fields keep their values, cleared ones go back to None
+    let update_input = UpdateArticleInput {
+        meta_title: Some(None),
+        ..Default::default()
+    };
+    let updated = service
+        .update(created.id, update_input, None)
+        .await
+        .expect("Failed to update article");
+    assert_eq!(updated.meta_title, None);
+    assert_eq!(
+        updated.meta_description.as_deref(),
+        Some("Search description")
+    );
+    assert!(updated.noindex);
+
+    let invalid = UpdateArticleInput {
+        canonical_url: Some(Some("javascript:alert(1)".to_string())),
+        ..Default::default()
+    };
+    let result = service.update(created.id, invalid, None).await;
+    assert!(matches!(
+        result,
+        Err(ArticleServiceError::ValidationError(_))
+    ));
+}
+
+#[tokio::test]
+async fn test_article_excerpt_is_generated_until_written() {
+    let (pool, service) = setup_test_service().await;
+    let sqlite_pool = pool.as_sqlite().unwrap();
+    let author_id = create_test_user(sqlite_pool).await;
+
+    let input = CreateArticleInput::new(
+        "excerpt-test".to_string(),
+        "Excerpt".to_string(),
+        "# Heading\n\nFirst paragraph.\n\nSecond paragraph.".to_string(),
+        author_id,
+        1,
+    );
+    let created = service
+        .create(input, None)
+        .await
+        .expect("Failed to create article");
+    assert_eq!(created.excerpt, None);
+    assert_eq!(created.excerpt_text(), "First paragraph. Second paragraph.");
+
+    let update_input = UpdateArticleInput {
+        excerpt: Some(Some("Written by hand".to_string())),
+        ..Default::default()
+    };
+    let updated = service
+        .update(created.id, update_input, None)
+        .await
+        .expect("Failed to update article");
+    assert_eq!(updated.excerpt_text(), "Written by hand");
+
+    let update_input = UpdateArticleInput {
+        excerpt: Some(None),
+        ..Default::default()
+    };
+    let updated = service
+        .update(created.id, update_input, None)
+        .await
+        .expect("Failed to update article");
+    assert_eq!(updated.excerpt, None);
+
+    let too_long = UpdateArticleInput {
+        excerpt: Some(Some("x".repeat(1001))),
+        ..Default::default()
+    };
+    let result = service.update(created.id, too_long, None).await;
+    assert!(matches!(
+        result,
+        Err(ArticleServiceError::ValidationError(_))
+    ));
+}
+
+#[tokio::test]
+async fn test_article_stats_are_measured_on_save() {
+    let (pool, service) = setup_test_service().await;
+    let sqlite_pool = pool.as_sqlite().unwrap();
+    let author_id = create_test_user(sqlite_pool).await;
+
+    let input = CreateArticleInput::new(
+        "stats-test".to_string(),
+        "Stats".to_string(),
+        "Hello **world**".to_string(),
+        author_id,
+        1,
+    );
+    let created = service
+        .create(input, None)
+        .await
+        .expect("Failed to create article");
+    assert_eq!(created.stats.word_count, 2);
+    assert_eq!(created.stats.char_count, 10);
+    assert_eq!(created.stats.reading_time, 1);
+
+    let update_input = UpdateArticleInput {
+        content: Some("你好，世界".to_string()),
+        ..Default::default()
+    };
+    let updated = service
+        .update(created.id, update_input, None)
+        .await
+        .expect("Failed to update article");
+    assert_eq!(updated.stats.word_count, 4);
+    assert_eq!(updated.stats.char_count, 5);
+
+    // Articles saved before stats existed are measured once
+    sqlx::query("UPDATE articles SET word_count = NULL")
+        .execute(sqlite_pool)
+        .await
+        .unwrap();
+    assert_eq!(service.measure_unmeasured().await.unwrap(), 1);
+    assert_eq!(service.measure_unmeasured().await.unwrap(), 0);
+    let article = service.get_by_id(created.id).await.unwrap().unwrap();
+    assert_eq!(article.stats.word_count, 4);
+}
+
+#[tokio::test]
+async fn test_article_license_is_stored_and_cleared() {
+    let (pool, service) = setup_test_service().await;
+    let sqlite_pool = pool.as_sqlite().unwrap();
+    let author_id = create_test_user(sqlite_pool).await;
+
+    let mut input = CreateArticleInput::new(
+        "license-test".to_string(),
+        "License".to_string(),
+        "Content".to_string(),
+        author_id,
+        1,
+    );
+    input.license = Some(ContentLicense::new(LicenseKind::CcBySa));
+    let created = service
+        .create(input, None)
+        .await
+        .expect("Failed to create article");
+    let stored = service.get_by_id(created.id).await.unwrap().unwrap();
+    assert_eq!(
+        stored.license,
+        Some(ContentLicense::new(LicenseKind::CcBySa))
+    );
+
+    let nameless = UpdateArticleInput {
+        license: Some(Some(ContentLicense::new(LicenseKind::Custom))),
+        ..Default::default()
+    };
+    let result = service.update(created.id, nameless, None).await;
+    assert!(matches!(
+        result,
+        Err(ArticleServiceError::ValidationError(_))
+    ));
+
+    let update_input = UpdateArticleInput {
+        license: Some(None),
+        ..Default::default()
+    };
+    let updated = service
+        .update(created.id, update_input, None)
+        .await
+        .expect("Failed to update article");
+    assert_eq!(updated.license, None);
+}
+
 #[tokio::test]
 async fn test_update_article_invalid_slug_fails() {
     let (pool, service) = setup_test_service().await;
@@ -785,6 +1121,94 @@ async fn test_create_article_renders_markdown() {
     assert!(article.content_html.contains("<li>"));
 }
 
+#[tokio::test]
+async fn test_previous_slugs_redirect_and_stay_reserved() {
+    let (pool, service) = setup_test_service().await;
+    let sqlite_pool = pool.as_sqlite().unwrap();
+    let author_id = create_test_user(sqlite_pool).await;
+    let new_input = |slug: &str| {
+        CreateArticleInput::new(
+            slug.to_string(),
+            "Title".to_string(),
+            "Content".to_string(),
+            author_id,
+            1,
+        )
+    };
+
+    let first = service.create(new_input("old-slug"), None).await.unwrap();
+    service
+        .update(
+            first.id,
+            UpdateArticleInput::new().with_slug("new-slug".to_string()),
+            None,
+        )
+        .await
+        .unwrap();
+
+    let redirected = service.get_by_previous_slug("old-slug").await.unwrap();
+    assert_eq!(redirected.map(|a| a.id), Some(first.id));
+    assert!(service
+        .get_by_previous_slug("new-slug")
+        .await
+        .unwrap()
+        .is_none());
+    let history: Vec<_> = service
+        .list_slug_history(first.id)
+        .await
+        .unwrap()
+        .into_iter()
+        .map(|s| s.slug)
+        .collect();
+    assert_eq!(history.len(), 2);
+    assert!(history.contains(&"old-slug".to_string()));
+
+    // Deleting the article keeps its slugs reserved
+    service.delete(first.id).await.unwrap();
+    assert!(matches!(
+        service.create(new_input("new-slug"), None).await,
+        Err(ArticleServiceError::ReservedSlug(_))
+    ));
+
+    let mut input = new_input("new-slug");
+    input.reclaim_slug = true;
+    let second = service.create(input, None).await.unwrap();
+    assert_eq!(second.slug, "new-slug");
+    let history = service.list_slug_history(second.id).await.unwrap();
+    assert_eq!(history.len(), 1);
+}
+
+#[tokio::test]
+async fn test_render_filtered_runs_content_filter() {
+    let pool = create_test_pool()
+        .await
+        .expect("Failed to create test pool");
+    let hooks = Arc::new(HookManager::default());
+    hooks.register(
+        hook_names::ARTICLE_CONTENT_FILTER,
+        |data: &mut serde_json::Value| {
+            let content = data["content"]
+                .as_str()
+                .unwrap_or("")
+                .replace("draft", "**final**");
+            data["content"] = json!(content);
+            None
+        },
+        10,
+        None,
+    );
+    let service = ArticleService::with_hooks(
+        SqlxArticleRepository::boxed(pool.clone()),
+        SqlxTagRepository::boxed(pool),
+        create_cache(&CacheConfig::default()).await.unwrap(),
+        MarkdownRenderer::new(),
+        hooks,
+    );
+
+    let html = service.render_filtered(InputFormat::Markdown, "A draft", 0);
+    assert!(html.contains("<strong>final</strong>"), "{}", html);
+}
+
 // ========================================================================
 // Property-Based Tests
 // ========================================================================
@@ -976,7 +1400,7 @@ proptest! {
 
             // List articles with pagination
             let params = ListParams::new(page, per_page);
-            let result = service.list(&params, ArticleSortBy::default()).await
+            let result = service.list(&params, ArticleSortBy::default(), None).await
                 .expect("list should succeed");
 
             // Property: Total count should match the number of articles created
@@ -0,0 +1,141 @@
+//! Article author credits service.
+//!
+//! Validates co-author lists and keeps the article's `author_id` pointing
+//! at its primary author, the first credit with the `author` role.
+
+use crate::db::repositories::ArticleAuthorRepository;
+use crate::models::{
+    ArticleAuthor, ArticleAuthorInput, ArticleAuthorsInput, AuthorRole, PublishedAuthor,
+};
+use std::collections::HashMap;
+use std::sync::Arc;
+
+const MAX_AUTHORS: usize = 20;
+
+/// Errors returned by the article author service
+#[derive(Debug, thiserror::Error)]
+pub enum ArticleAuthorError {
+    #[error("{0}")]
+    Validation(String),
+
+    #[error("Internal error: {0}")]
+    Internal(#[from] anyhow::Error),
+}
+
+pub struct ArticleAuthorService {
+    repo: Arc<dyn ArticleAuthorRepository>,
+}
+
+impl ArticleAuthorService {
+    pub fn new(repo: Arc<dyn ArticleAuthorRepository>) -> Self {
+        Self { repo }
+    }
+
+    /// Credits of an article in display order
+    pub async fn list(&self, article_id: i64) -> Result<Vec<ArticleAuthor>, ArticleAuthorError> {
+        Ok(self
+            .repo
+            .list_for_articles(&[article_id])
+            .await?
+            .into_iter()
+            .map(|(_, author)| author)
+            .collect())
+    }
+
+    /// Credits of several articles, keyed by article id
+    pub async fn by_articles(
+        &self,
+        article_ids: &[i64],
+    ) -> Result<HashMap<i64, Vec<ArticleAuthor>>, ArticleAuthorError> {
+        let mut map: HashMap<i64, Vec<ArticleAuthor>> = HashMap::new();
+        for (article_id, author) in self.repo.list_for_articles(article_ids).await? {
+            map.entry(article_id).or_default().push(author);
+        }
+        Ok(map)
+    }
+
+    /// Replace the credits of an article
+    pub async fn set(
+        &self,
+        article_id: i64,
+        input: ArticleAuthorsInput,
+    ) -> Result<Vec<ArticleAuthor>, ArticleAuthorError> {
+        let primary_id = primary_author(&input.authors)?;
+        let ids: Vec<i64> = input.authors.iter().map(|a| a.user_id).collect();
+        let existing = self.repo.existing_user_ids(&ids).await?;
+        if let Some(missing) = ids.iter().find(|id| !existing.contains(id)) {
+            return Err(ArticleAuthorError::Validation(format!(
+                "User not found: {}",
+                missing
+            )));
+        }
+        self.repo
+            .set(article_id, primary_id, &input.authors)
+            .await?;
+        self.list(article_id).await
+    }
+
+    /// Authors of public published articles with their article counts
+    pub async fn published_authors(&self) -> Result<Vec<PublishedAuthor>, ArticleAuthorError> {
+        Ok(self.repo.list_published_authors().await?)
+    }
+
+    /// Drop the credits of a deleted article
+    pub async fn remove_article(&self, article_id: i64) -> Result<(), ArticleAuthorError> {
+        Ok(self.repo.remove_article(article_id).await?)
+    }
+}
+
+/// Check a credit list and pick its primary author
+pub fn primary_author(authors: &[ArticleAuthorInput]) -> Result<i64, ArticleAuthorError> {
+    if authors.len() > MAX_AUTHORS {
+        return Err(ArticleAuthorError::Validation(format!(
+            "An article can credit at most {} people",
+            MAX_AUTHORS
+        )));
+    }
+    for (i, author) in authors.iter().enumerate() {
+        if authors[..i].iter().any(|a| a.user_id == author.user_id) {
+            return Err(ArticleAuthorError::Validation(format!(
+                "User {} is credited more than once",
+                author.user_id
+            )));
+        }
+    }
+    authors
+        .iter()
+        .find(|a| a.role == AuthorRole::Author)
+        .map(|a| a.user_id)
+        .ok_or_else(|| {
+            ArticleAuthorError::Validation("At least one credit needs the author role".to_string())
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn credit(user_id: i64, role: AuthorRole) -> ArticleAuthorInput {
+        ArticleAuthorInput { user_id, role }
+    }
+
+    #[test]
+    fn primary_author_is_the_first_author_credit() {
+        let authors = [
+            credit(3, AuthorRole::Editor),
+            credit(5, AuthorRole::Author),
+            credit(7, AuthorRole::Author),
+        ];
+        assert_eq!(primary_author(&authors).unwrap(), 5);
+    }
+
+    #[test]
+    fn rejects_lists_without_author_or_with_duplicates() {
+        assert!(primary_author(&[]).is_err());
+        assert!(primary_author(&[credit(1, AuthorRole::Translator)]).is_err());
+        assert!(
+            primary_author(&[credit(1, AuthorRole::Author), credit(1, AuthorRole::Editor)])
+                .is_err()
+        );
+    }
+}
@@ -0,0 +1,541 @@
+//! Article attachment service.
+//!
+//! Stores non-image files under `uploads/attachments/` and keeps the list of
+//! files attached to each article. The `[attachments]` shortcode leaves a
+//! placeholder that is filled with the article's download list whenever the
+//! article is served, so uploads and removals show up without re-saving it.
+//!
+//! Files are downloaded through the API rather than from `/uploads/`, so
+//! every download is counted and an attachment can require a signed-in user
+//! or an email address first.
+
+use crate::config::UploadConfig;
+use crate::db::repositories::AttachmentRepository;
+use crate::models::{
+    normalize_email, ArticleAttachment, AttachmentAccess, AttachmentDownloadStats, ATTACHMENTS_DIR,
+};
+use anyhow::Context;
+use chrono::{Duration, Utc};
+use std::path::PathBuf;
+use std::sync::Arc;
+use uuid::Uuid;
+
+const MAX_FILENAME_LEN: usize = 255;
+const MAX_CONTENT_TYPE_LEN: usize = 100;
+/// Addresses returned with an attachment's download stats
+const MAX_LEADS: i64 = 500;
+
+/// Placeholder the `[attachments]` shortcode leaves in rendered content
+pub const PLACEHOLDER: &str = r#"<div class="noteva-attachments" data-noteva-attachments></div>"#;
+
+/// Errors returned by the attachment service
+#[derive(Debug, thiserror::Error)]
+pub enum AttachmentError {
+    #[error("{0} not found")]
+    NotFound(&'static str),
+
+    #[error("{0}")]
+    Validation(String),
+
+    /// The attachment's access gate was not passed
+    #[error("{}", gate_message(*.0))]
+    Gated(AttachmentAccess),
+
+    #[error("Internal error: {0}")]
+    Internal(#[from] anyhow::Error),
+}
+
+fn gate_message(access: AttachmentAccess) -> &'static str {
+    match access {
+        AttachmentAccess::Login => "Sign in to download this file",
+        AttachmentAccess::Email => "Enter your email address to download this file",
+        AttachmentAccess::Public => "Download not allowed",
+    }
+}
+
+pub struct AttachmentService {
+    repo: Arc<dyn AttachmentRepository>,
+    dir: PathBuf,
+    extensions: Vec<String>,
+    max_size: u64,
+}
+
+impl AttachmentService {
+    pub fn new(repo: Arc<dyn AttachmentRepository>, config: &UploadConfig) -> Self {
+        Self {
+            repo,
+            dir: config.path.join(ATTACHMENTS_DIR),
+            extensions: config.attachment_extensions.clone(),
+            max_size: config.max_attachment_size,
+        }
+    }
+
+    /// Attachments of an article in display order
+    pub async fn list(&self, article_id: i64) -> Result<Vec<ArticleAttachment>, AttachmentError> {
+        Ok(self.repo.list(article_id).await?)
+    }
+
+    pub async fn get(&self, id: i64) -> Result<ArticleAttachment, AttachmentError> {
+        self.repo
+            .get(id)
+            .await?
+            .ok_or(AttachmentError::NotFound("Attachment"))
+    }
+
+    /// Store a file and attach it after the article's existing attachments
+    pub async fn add(
+        &self,
+        article_id: i64,
+        filename: &str,
+        content_type: &str,
+        access: AttachmentAccess,
+        data: &[u8],
+    ) -> Result<ArticleAttachment, AttachmentError> {
+        let filename = clean_filename(filename)?;
+        let ext = self.allowed_extension(&filename)?;
+        if data.is_empty() {
+            return Err(AttachmentError::Validation("File is empty".to_string()));
+        }
+        if data.len() as u64 > self.max_size {
+            return Err(AttachmentError::Validation(format!(
+                "File too large. Maximum size: {} MB",
+                self.max_size / 1024 / 1024
+            )));
+        }
+        let content_type = if content_type.is_empty() || content_type.len() > MAX_CONTENT_TYPE_LEN {
+            "application/octet-stream"
+        } else {
+            content_type
+        };
+
+        let stored_name = format!("{}.{}", Uuid::new_v4(), ext);
+        tokio::fs::create_dir_all(&self.dir)
+            .await
+            .context("Failed to create attachments directory")?;
+        tokio::fs::write(self.dir.join(&stored_name), data)
+            .await
+            .context("Failed to save attachment")?;
+
+        let attachment = ArticleAttachment {
+            id: 0,
+            article_id,
+            filename,
+            stored_name,
+            url: String::new(),
+            content_type: content_type.to_string(),
+            size: data.len() as i64,
+            position: 0,
+            access,
+            download_count: 0,
+            created_at: Utc::now(),
+        };
+        match self.repo.create(&attachment).await {
+            Ok(created) => Ok(created),
+            Err(e) => {
+                self.remove_file(&attachment).await;
+                Err(e.into())
+            }
+        }
+    }
+
+    /// Detach a file from an article and delete it from disk
+    pub async fn delete(&self, article_id: i64, id: i64) -> Result<(), AttachmentError> {
+        let attachment = self
+            .repo
+            .get(id)
+            .await?
+            .filter(|a| a.article_id == article_id)
+            .ok_or(AttachmentError::NotFound("Attachment"))?;
+        self.repo.delete(id).await?;
+        self.remove_file(&attachment).await;
+        Ok(())
+    }
+
+    /// Change who may download an attachment
+    pub async fn set_access(
+        &self,
+        article_id: i64,
+        id: i64,
+        access: AttachmentAccess,
+    ) -> Result<ArticleAttachment, AttachmentError> {
+        let attachment = self.get(id).await?;
+        if attachment.article_id != article_id {
+            return Err(AttachmentError::NotFound("Attachment"));
+        }
+        self.repo.set_access(id, access).await?;
+        Ok(ArticleAttachment {
+            access,
+            ..attachment
+        })
+    }
+
+    /// Pass the attachment's gate and count the download; returns the path
+    /// of the file to send
+    pub async fn download(
+        &self,
+        attachment: &ArticleAttachment,
+        user_id: Option<i64>,
+        email: Option<&str>,
+    ) -> Result<PathBuf, AttachmentError> {
+        let email = check_access(attachment.access, user_id.is_some(), email)?;
+        let path = self.dir.join(&attachment.stored_name);
+        if !tokio::fs::try_exists(&path).await.unwrap_or(false) {
+            return Err(AttachmentError::NotFound("File"));
+        }
+        self.repo
+            .record_download(attachment.id, user_id, email.as_deref())
+            .await?;
+        Ok(path)
+    }
+
+    /// Downloads of an attachment over the last `days` days and the
+    /// addresses left for it
+    pub async fn download_stats(
+        &self,
+        article_id: i64,
+        id: i64,
+        days: u32,
+    ) -> Result<AttachmentDownloadStats, AttachmentError> {
+        let attachment = self.get(id).await?;
+        if attachment.article_id != article_id {
+            return Err(AttachmentError::NotFound("Attachment"));
+        }
+        let since = (Utc::now() - Duration::days(i64::from(days.max(1)) - 1))
+            .format("%Y-%m-%d")
+            .to_string();
+        Ok(AttachmentDownloadStats {
+            daily: self.repo.daily_downloads(id, &since).await?,
+            signed_in: self.repo.signed_in_downloads(id, &since).await?,
+            leads: self.repo.leads(id, MAX_LEADS).await?,
+            attachment,
+        })
+    }
+
+    /// Most downloaded attachments across the site
+    pub async fn top(&self, limit: i64) -> Result<Vec<ArticleAttachment>, AttachmentError> {
+        Ok(self.repo.top(limit.clamp(1, 100)).await?)
+    }
+
+    /// Put an article's attachments in the given order; every attachment of
+    /// the article must be listed exactly once
+    pub async fn reorder(
+        &self,
+        article_id: i64,
+        attachment_ids: &[i64],
+    ) -> Result<Vec<ArticleAttachment>, AttachmentError> {
+        let current = self.repo.list(article_id).await?;
+        let mut expected: Vec<i64> = current.iter().map(|a| a.id).collect();
+        let mut given = attachment_ids.to_vec();
+        expected.sort_unstable();
+        given.sort_unstable();
+        if expected != given {
+            return Err(AttachmentError::Validation(
+                "attachment_ids must list every attachment of the article once".to_string(),
+            ));
+        }
+        self.repo.set_order(article_id, attachment_ids).await?;
+        Ok(self.repo.list(article_id).await?)
+    }
+
+    /// Delete every attachment of an article, e.g. when the article is deleted
+    pub async fn remove_article(&self, article_id: i64) -> Result<(), AttachmentError> {
+        let attachments = self.repo.list(article_id).await?;
+        self.repo.delete_for_article(article_id).await?;
+        self.remove_files(&attachments).await;
+        Ok(())
+    }
+
+    /// Delete the files of attachments whose rows are already gone
+    pub async fn remove_files(&self, attachments: &[ArticleAttachment]) {
+        for attachment in attachments {
+            self.remove_file(attachment).await;
+        }
+    }
+
+    /// Replace `[attachments]` placeholders in an article's rendered HTML
+    /// with its download list
+    pub async fn render_embeds(&self, html: &str, article_id: i64) -> String {
+        if !html.contains(PLACEHOLDER) {
+            return html.to_string();
+        }
+        let attachments = match self.repo.list(article_id).await {
+            Ok(attachments) => attachments,
+            Err(e) => {
+                tracing::warn!(
+                    "Failed to load attachments of article {}: {}",
+                    article_id,
+                    e
+                );
+                Vec::new()
+            }
+        };
+        fill_embeds(html, &attachments)
+    }
+
+    fn allowed_extension(&self, filename: &str) -> Result<String, AttachmentError> {
+        let ext = filename
+            .rsplit_once('.')
+            .map(|(_, ext)| ext.to_ascii_lowercase())
+            .unwrap_or_default();
+        let allowed = !ext.is_empty()
+            && self
+                .extensions
+                .iter()
+                .any(|allowed| allowed.trim_start_matches('.').eq_ignore_ascii_case(&ext));
+        if !allowed {
+            return Err(AttachmentError::Validation(format!(
+                "File type not allowed. Allowed extensions: {}",
+                self.extensions.join(", ")
+            )));
+        }
+        Ok(ext)
+    }
+
+    async fn remove_file(&self, attachment: &ArticleAttachment) {
+        let path = self.dir.join(&attachment.stored_name);
+        if let Err(e) = tokio::fs::remove_file(&path).await {
+            if e.kind() != std::io::ErrorKind::NotFound {
+                tracing::warn!("Failed to delete attachment {}: {}", path.display(), e);
+            }
+        }
+    }
+}
+
+/// Whether a download may go ahead; returns the address to log, which an
+/// email-gated attachment requires unless the visitor is signed in
+fn check_access(
+    access: AttachmentAccess,
+    signed_in: bool,
+    email: Option<&str>,
+) -> Result<Option<String>, AttachmentError> {
+    let email = email.map(normalize_email).filter(|email| !email.is_empty());
+    if let Some(email) = &email {
+        if email.len() > 254 || email.parse::<lettre::Address>().is_err() {
+            return Err(AttachmentError::Validation(
+                "Invalid email address".to_string(),
+            ));
+        }
+    }
+    match access {
+        AttachmentAccess::Login if !signed_in => Err(AttachmentError::Gated(access)),
+        AttachmentAccess::Email if !signed_in && email.is_none() => {
+            Err(AttachmentError::Gated(access))
+        }
+        _ => Ok(email),
+    }
+}
+
+/// Keep only the final path component of an uploaded file name
+fn clean_filename(filename: &str) -> Result<String, AttachmentError> {
+    let name = filename
+        .rsplit(['/', '\\'])
+        .next()
+        .unwrap_or("")
+        .trim()
+        .chars()
+        .filter(|c| !c.is_control())
+        .collect::<String>();
+    if name.is_empty() || name == "." || name == ".." {
+        return Err(AttachmentError::Validation(
+            "File name is required".to_string(),
+        ));
+    }
+    if name.chars().count() > MAX_FILENAME_LEN {
+        return Err(AttachmentError::Validation(format!(
+            "File name must be at most {} characters",
+            MAX_FILENAME_LEN
+        )));
+    }
+    Ok(name)
+}
+
+/// Swap placeholders for the download list; an article without attachments
+/// drops them
+pub fn fill_embeds(html: &str, attachments: &[ArticleAttachment]) -> String {
+    let list = if attachments.is_empty() {
+        String::new()
+    } else {
+        render_list(attachments)
+    };
+    html.replace(PLACEHOLDER, &list)
+}
+
+/// Attachments as a download list with icons and sizes. Email-gated files
+/// get a small form that posts the address to the download URL.
+pub fn render_list(attachments: &[ArticleAttachment]) -> String {
+    let mut html = String::from(r#"<ul class="noteva-attachments">"#);
+    for attachment in attachments {
+        let kind = file_kind(&attachment.filename);
+        let url = html_escape(&attachment.url);
+        let name = html_escape(&attachment.filename);
+        let gate = if attachment.access == AttachmentAccess::Email {
+            format!(
+                r#"<form class="noteva-attachment-gate" method="post" action="{url}"><input type="email" name="email" required placeholder="you@example.com" aria-label="Email"><button type="submit">↓</button></form>"#,
+                url = url,
+            )
+        } else {
+            String::new()
+        };
+        html.push_str(&format!(
+            r#"<li class="noteva-attachment noteva-attachment-{kind}" data-access="{access}"><span class="noteva-attachment-icon" aria-hidden="true">{icon}</span><a class="noteva-attachment-link" href="{url}" download="{name}">{name}</a><span class="noteva-attachment-size">{size}</span>{gate}</li>"#,
+            kind = kind,
+            access = attachment.access.as_str(),
+            icon = file_icon(kind),
+            url = url,
+            name = name,
+            size = format_size(attachment.size.max(0) as u64),
+            gate = gate,
+        ));
+    }
+    html.push_str("</ul>");
+    html
+}
+
+/// Broad kind of a file by extension, used for the icon and a CSS class
+pub fn file_kind(filename: &str) -> &'static str {
+    let ext = filename
+        .rsplit_once('.')
+        .map(|(_, ext)| ext.to_ascii_lowercase())
+        .unwrap_or_default();
+    match ext.as_str() {
+        "pdf" => "pdf",
+        "zip" | "7z" | "rar" | "tar" | "gz" | "tgz" | "bz2" | "xz" => "archive",
+        "doc" | "docx" | "odt" | "rtf" | "epub" => "document",
+        "xls" | "xlsx" | "ods" | "csv" => "spreadsheet",
+        "ppt" | "pptx" | "odp" | "key" => "presentation",
+        "mp3" | "wav" | "ogg" | "flac" | "m4a" => "audio",
+        "mp4" | "webm" | "mov" | "mkv" | "avi" => "video",
+        "txt" | "md" | "log" | "json" | "yml" | "yaml" => "text",
+        _ => "file",
+    }
+}
+
+fn file_icon(kind: &str) -> &'static str {
+    match kind {
+        "pdf" => "📕",
+        "archive" => "🗜️",
+        "document" => "📄",
+        "spreadsheet" => "📊",
+        "presentation" => "📽️",
+        "audio" => "🎵",
+        "video" => "🎬",
+        "text" => "📝",
+        _ => "📎",
+    }
+}
+
+/// Human-readable size, e.g. `1.5 MB`
+pub fn format_size(bytes: u64) -> String {
+    const UNITS: [&str; 4] = ["KB", "MB", "GB", "TB"];
+    if bytes < 1024 {
+        return format!("{} B", bytes);
+    }
+    let mut size = bytes as f64 / 1024.0;
+    let mut unit = 0;
+    while size >= 1024.0 && unit < UNITS.len() - 1 {
+        size /= 1024.0;
+        unit += 1;
+    }
+    format!("{:.1} {}", size, UNITS[unit])
+}
+
+fn html_escape(s: &str) -> String {
+    s.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn attachment(filename: &str, size: i64) -> ArticleAttachment {
+        ArticleAttachment {
+            id: 1,
+            article_id: 1,
+            filename: filename.to_string(),
+            stored_name: "x.pdf".to_string(),
+            url: crate::models::attachment_url(1),
+            content_type: "application/pdf".to_string(),
+            size,
+            position: 0,
+            access: AttachmentAccess::Public,
+            download_count: 0,
+            created_at: Utc::now(),
+        }
+    }
+
+    #[test]
+    fn formats_sizes() {
+        assert_eq!(format_size(512), "512 B");
+        assert_eq!(format_size(1536), "1.5 KB");
+        assert_eq!(format_size(5 * 1024 * 1024), "5.0 MB");
+    }
+
+    #[test]
+    fn strips_directories_from_file_names() {
+        assert_eq!(
+            clean_filename("C:\\docs\\report.pdf").unwrap(),
+            "report.pdf"
+        );
+        assert_eq!(clean_filename("../../etc/notes.txt").unwrap(), "notes.txt");
+        assert!(clean_filename("dir/").is_err());
+    }
+
+    #[test]
+    fn fills_placeholder_with_download_list() {
+        let html = format!("<p>Files:</p>{}", PLACEHOLDER);
+        let filled = fill_embeds(&html, &[attachment("Q&A <draft>.pdf", 2048)]);
+        assert!(filled.starts_with("<p>Files:</p><ul class=\"noteva-attachments\">"));
+        assert!(filled.contains("noteva-attachment-pdf"));
+        assert!(filled.contains(r#"href="/api/v1/attachments/1/download""#));
+        assert!(filled.contains(r#"download="Q&amp;A &lt;draft&gt;.pdf""#));
+        assert!(filled.contains(r#"<span class="noteva-attachment-size">2.0 KB</span>"#));
+
+        assert_eq!(fill_embeds(&html, &[]), "<p>Files:</p>");
+    }
+
+    #[test]
+    fn email_gated_files_get_an_address_form() {
+        let mut gated = attachment("guide.pdf", 10);
+        gated.access = AttachmentAccess::Email;
+        let html = render_list(&[gated, attachment("open.pdf", 10)]);
+        assert_eq!(html.matches("noteva-attachment-gate").count(), 1);
+        assert!(html.contains(r#"data-access="email""#));
+        assert!(html.contains(r#"<form class="noteva-attachment-gate" method="post" action="/api/v1/attachments/1/download">"#));
+    }
+
+    #[test]
+    fn gates_require_a_login_or_an_address() {
+        assert!(check_access(AttachmentAccess::Public, false, None).is_ok());
+        assert!(matches!(
+            check_access(AttachmentAccess::Login, false, Some("a@example.com")),
+            Err(AttachmentError::Gated(AttachmentAccess::Login))
+        ));
+        assert!(check_access(AttachmentAccess::Login, true, None).is_ok());
+        assert!(matches!(
+            check_access(AttachmentAccess::Email, false, None),
+            Err(AttachmentError::Gated(AttachmentAccess::Email))
+        ));
+        assert_eq!(
+            check_access(AttachmentAccess::Email, false, Some(" Reader@Example.com "))
+                .unwrap()
+                .as_deref(),
+            Some("reader@example.com")
+        );
+        assert!(check_access(AttachmentAccess::Email, true, None).is_ok());
+        assert!(matches!(
+            check_access(AttachmentAccess::Email, false, Some("not-an-address")),
+            Err(AttachmentError::Validation(_))
+        ));
+    }
+
+    #[test]
+    fn classifies_files_by_extension() {
+        assert_eq!(file_kind("a.PDF"), "pdf");
+        assert_eq!(file_kind("a.tar.gz"), "archive");
+        assert_eq!(file_kind("a.xlsx"), "spreadsheet");
+        assert_eq!(file_kind("README"), "file");
+    }
+}
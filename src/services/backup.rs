@@ -1,18 +1,29 @@
 //! Data backup & restore service
 //!
-//! Provides backup (JSON+ZIP), restore (ZIP upload), and Markdown export.
+//! Provides backup (JSON+ZIP), restore (ZIP upload), Markdown export and the
+//! portable content bundle (see [`export_bundle`]).
+//!
+//! Archives are written to `backup.path` as
+//! `noteva-backup-YYYYMMDD-HHMMSS.zip` and contain:
+//! - `data/*.json` - the content tables, used for restore
+//! - `database/noteva.db` (SQLite snapshot) or `database/dump.sql` (MySQL),
+//!   for manual recovery; restore does not read them
+//! - `uploads/`, `themes/` and `plugins/`
+//! - `manifest.json`
 
 use anyhow::{Context, Result};
-use chrono::Utc;
+use chrono::{DateTime, NaiveDate, NaiveDateTime, Utc};
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::io::{Read, Seek, Write};
-use std::path::Path;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
 use tracing::{info, warn};
 use zip::write::SimpleFileOptions;
 
-use crate::config::DatabaseDriver;
+use crate::config::{BackupConfig, DatabaseDriver};
 use crate::db::DynDatabasePool;
+use crate::services::jobs::JobMonitor;
 
 /// Tables to back up, in dependency order (parents first)
 /// SAFETY: These names are used in `format!()` SQL (e.g. `DELETE FROM {table}`).
@@ -52,6 +63,12 @@ const MAX_BACKUP_ZIP_ENTRIES: usize = 4096;
 const MAX_BACKUP_ENTRY_BYTES: u64 = 50 * 1024 * 1024;
 const MAX_BACKUP_UNPACKED_BYTES: u64 = 200 * 1024 * 1024;
 
+/// Archive entry holding the SQLite snapshot
+const SQLITE_SNAPSHOT_ENTRY: &str = "database/noteva.db";
+/// Archive entry holding the MySQL dump
+const MYSQL_DUMP_ENTRY: &str = "database/dump.sql";
+const ARCHIVE_PREFIX: &str = "noteva-backup-";
+
 /// Backup manifest
 #[derive(Debug, Serialize, Deserialize)]
 pub struct BackupManifest {
@@ -59,52 +76,375 @@ pub struct BackupManifest {
     pub created_at: String,
     pub db_driver: String,
     pub tables: HashMap<String, usize>,
+    /// Archive entry with the full database snapshot or dump
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub database: Option<String>,
 }
 
-/// Create a full backup as ZIP bytes
-pub async fn create_backup(pool: &DynDatabasePool, upload_dir: &Path) -> Result<Vec<u8>> {
-    let mut zip = zip::ZipWriter::new(std::io::Cursor::new(Vec::new()));
-    let options = SimpleFileOptions::default().compression_method(zip::CompressionMethod::Deflated);
+/// Directories copied into archives and written back on restore
+#[derive(Debug, Clone)]
+pub struct BackupSources {
+    pub uploads: PathBuf,
+    pub themes: PathBuf,
+    pub plugins: PathBuf,
+}
+
+impl BackupSources {
+    /// Archive prefix and local directory of each source
+    fn dirs(&self) -> [(&'static str, &Path); 3] {
+        [
+            ("uploads", &self.uploads),
+            ("themes", &self.themes),
+            ("plugins", &self.plugins),
+        ]
+    }
+}
+
+/// A stored backup archive
+#[derive(Debug, Clone, Serialize)]
+pub struct BackupArchive {
+    pub name: String,
+    pub size: u64,
+    pub created_at: DateTime<Utc>,
+}
 
+/// Creates, stores, prunes and restores backup archives
+pub struct BackupService {
+    pool: DynDatabasePool,
+    sources: BackupSources,
+    config: BackupConfig,
+    /// One backup or restore at a time
+    lock: tokio::sync::Mutex<()>,
+}
+
+impl BackupService {
+    pub fn new(pool: DynDatabasePool, sources: BackupSources, config: BackupConfig) -> Self {
+        Self {
+            pool,
+            sources,
+            config,
+            lock: tokio::sync::Mutex::new(()),
+        }
+    }
+
+    /// Write a new archive to the backup directory, then prune old ones
+    pub async fn create(&self) -> Result<BackupArchive> {
+        let _guard = self.lock.lock().await;
+        std::fs::create_dir_all(&self.config.path).with_context(|| {
+            format!(
+                "Failed to create backup directory {}",
+                self.config.path.display()
+            )
+        })?;
+
+        let now = Utc::now();
+        let stamp = now.format("%Y%m%d-%H%M%S").to_string();
+        let mut name = format!("{}{}.zip", ARCHIVE_PREFIX, stamp);
+        let mut n = 1;
+        while self.config.path.join(&name).exists() {
+            name = format!("{}{}-{}.zip", ARCHIVE_PREFIX, stamp, n);
+            n += 1;
+        }
+        let dest = self.config.path.join(&name);
+        write_archive(&self.pool, &self.sources, &dest).await?;
+        let size = std::fs::metadata(&dest)?.len();
+        info!(archive = %name, size, "backup created");
+
+        if let Err(e) = self.prune() {
+            warn!(error = %e, "failed to prune old backups");
+        }
+        Ok(BackupArchive {
+            name,
+            size,
+            created_at: now,
+        })
+    }
+
+    /// Stored archives, newest first
+    pub fn list(&self) -> Result<Vec<BackupArchive>> {
+        let entries = match std::fs::read_dir(&self.config.path) {
+            Ok(entries) => entries,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+            Err(e) => return Err(e.into()),
+        };
+        let mut archives = Vec::new();
+        for entry in entries {
+            let entry = entry?;
+            let name = entry.file_name().to_string_lossy().to_string();
+            if !is_archive_name(&name) {
+                continue;
+            }
+            let meta = entry.metadata()?;
+            archives.push(BackupArchive {
+                name,
+                size: meta.len(),
+                created_at: meta
+                    .modified()
+                    .map(DateTime::<Utc>::from)
+                    .unwrap_or_default(),
+            });
+        }
+        archives.sort_by(|a, b| archive_order(&b.name).cmp(&archive_order(&a.name)));
+        Ok(archives)
+    }
+
+    /// Path of a stored archive, or `None` if the name is invalid or missing
+    pub fn archive_path(&self, name: &str) -> Option<PathBuf> {
+        if !is_archive_name(name) {
+            return None;
+        }
+        let path = self.config.path.join(name);
+        path.is_file().then_some(path)
+    }
+
+    /// Delete a stored archive; returns whether it existed
+    pub fn delete(&self, name: &str) -> Result<bool> {
+        match self.archive_path(name) {
+            Some(path) => {
+                std::fs::remove_file(path)?;
+                Ok(true)
+            }
+            None => Ok(false),
+        }
+    }
+
+    /// Restore a stored archive
+    pub async fn restore_archive(&self, name: &str) -> Result<BackupManifest> {
+        let path = self
+            .archive_path(name)
+            .with_context(|| format!("Backup not found: {}", name))?;
+        let data = tokio::fs::read(&path).await?;
+        self.restore(&data).await
+    }
+
+    /// Restore an uploaded archive
+    pub async fn restore(&self, zip_data: &[u8]) -> Result<BackupManifest> {
+        let _guard = self.lock.lock().await;
+        restore_backup(&self.pool, &self.sources, zip_data).await
+    }
+
+    /// Create backups every `interval_hours` (no-op when 0)
+    pub async fn run_schedule(self: Arc<Self>, jobs: Arc<JobMonitor>) {
+        if self.config.interval_hours == 0 {
+            return;
+        }
+        let period = std::time::Duration::from_secs(self.config.interval_hours * 3600);
+        jobs.every("backup", period, || async {
+            self.create().await.map(|_| ())
+        })
+        .await;
+    }
+
+    /// Delete the oldest archives beyond `keep`
+    fn prune(&self) -> Result<usize> {
+        if self.config.keep == 0 {
+            return Ok(0);
+        }
+        let archives = self.list()?;
+        let mut removed = 0;
+        for archive in archives.iter().skip(self.config.keep) {
+            std::fs::remove_file(self.config.path.join(&archive.name))?;
+            removed += 1;
+        }
+        Ok(removed)
+    }
+}
+
+/// Names produced by [`BackupService::create`]
+fn is_archive_name(name: &str) -> bool {
+    name.starts_with(ARCHIVE_PREFIX)
+        && name.ends_with(".zip")
+        && name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '.')
+        && !name.contains("..")
+}
+
+/// Sort key of an archive name: timestamp, then collision suffix
+fn archive_order(name: &str) -> (&str, u32) {
+    let stem = name
+        .trim_start_matches(ARCHIVE_PREFIX)
+        .trim_end_matches(".zip");
+    match stem.get(15..).and_then(|rest| rest.strip_prefix('-')) {
+        Some(n) => (&stem[..15], n.parse().unwrap_or(0)),
+        None => (stem, 0),
+    }
+}
+
+/// Write a full backup archive to `dest`
+async fn write_archive(pool: &DynDatabasePool, sources: &BackupSources, dest: &Path) -> Result<()> {
     let driver_name = match pool.driver() {
         DatabaseDriver::Sqlite => "sqlite",
         DatabaseDriver::Mysql => "mysql",
     };
 
     let mut table_counts = HashMap::new();
-
+    let mut table_json = Vec::new();
     for table in BACKUP_TABLES {
         let rows = export_table(pool, table).await?;
         table_counts.insert(table.to_string(), rows.len());
-        let json = serde_json::to_string_pretty(&rows)?;
-        zip.start_file(format!("data/{}.json", table), options)?;
-        zip.write_all(json.as_bytes())?;
+        table_json.push((table, serde_json::to_string_pretty(&rows)?));
         info!(table = table, rows = rows.len(), "backed up table");
     }
 
-    // Write manifest
+    // Full database: a consistent SQLite copy, or a SQL dump for MySQL
+    let snapshot_path = dest.with_extension("db.tmp");
+    let database = match pool.driver() {
+        DatabaseDriver::Sqlite => {
+            let _ = std::fs::remove_file(&snapshot_path);
+            sqlx::query("VACUUM INTO ?")
+                .bind(snapshot_path.to_string_lossy().to_string())
+                .execute(pool.as_sqlite_or_err()?)
+                .await
+                .context("Failed to snapshot SQLite database")?;
+            // In-memory databases are vacuumed into memory, leaving no file
+            if snapshot_path.is_file() {
+                Some((SQLITE_SNAPSHOT_ENTRY, None))
+            } else {
+                warn!("SQLite snapshot not written, archive has table data only");
+                None
+            }
+        }
+        DatabaseDriver::Mysql => Some((
+            MYSQL_DUMP_ENTRY,
+            Some(mysql_dump(pool.as_mysql_or_err()?).await?),
+        )),
+    };
+
     let manifest = BackupManifest {
         version: env!("CARGO_PKG_VERSION").to_string(),
         created_at: Utc::now().to_rfc3339(),
         db_driver: driver_name.to_string(),
         tables: table_counts,
+        database: database.as_ref().map(|(entry, _)| entry.to_string()),
     };
-    zip.start_file("manifest.json", options)?;
-    zip.write_all(serde_json::to_string_pretty(&manifest)?.as_bytes())?;
 
-    // Add uploads directory
-    if upload_dir.exists() && upload_dir.is_dir() {
-        add_dir_to_zip(&mut zip, upload_dir, "uploads", options)?;
+    let sources = sources.clone();
+    let dest = dest.to_path_buf();
+    let partial = dest.with_extension("zip.partial");
+    let partial_cleanup = partial.clone();
+    let snapshot = snapshot_path.clone();
+    let result = tokio::task::spawn_blocking(move || -> Result<()> {
+        let file = std::fs::File::create(&partial)
+            .with_context(|| format!("Failed to create {}", partial.display()))?;
+        let mut zip = zip::ZipWriter::new(std::io::BufWriter::new(file));
+        let options =
+            SimpleFileOptions::default().compression_method(zip::CompressionMethod::Deflated);
+
+        for (table, json) in &table_json {
+            zip.start_file(format!("data/{}.json", table), options)?;
+            zip.write_all(json.as_bytes())?;
+        }
+        zip.start_file("manifest.json", options)?;
+        zip.write_all(serde_json::to_string_pretty(&manifest)?.as_bytes())?;
+
+        if let Some((entry, dump)) = database {
+            zip.start_file(entry, options.large_file(true))?;
+            match dump {
+                Some(dump) => zip.write_all(dump.as_bytes())?,
+                None => {
+                    std::io::copy(&mut std::fs::File::open(&snapshot)?, &mut zip)?;
+                }
+            }
+        }
+
+        for (prefix, dir) in sources.dirs() {
+            if dir.is_dir() {
+                add_dir_to_zip(&mut zip, dir, prefix, options)?;
+            }
+        }
+
+        zip.finish()?.flush()?;
+        std::fs::rename(&partial, &dest)?;
+        Ok(())
+    })
+    .await
+    .context("Backup task panicked")?;
+
+    let _ = std::fs::remove_file(&snapshot_path);
+    if result.is_err() {
+        let _ = std::fs::remove_file(&partial_cleanup);
     }
+    result
+}
 
-    let cursor = zip.finish()?;
-    Ok(cursor.into_inner())
+/// Dump every table of a MySQL database as SQL statements
+async fn mysql_dump(pool: &sqlx::MySqlPool) -> Result<String> {
+    use sqlx::Row;
+
+    let mut out = format!(
+        "-- Noteva {} MySQL dump, {}\nSET FOREIGN_KEY_CHECKS = 0;\n",
+        env!("CARGO_PKG_VERSION"),
+        Utc::now().to_rfc3339()
+    );
+    let tables: Vec<String> = sqlx::query("SHOW TABLES")
+        .fetch_all(pool)
+        .await?
+        .iter()
+        .map(|row| row.try_get::<String, _>(0))
+        .collect::<Result<_, _>>()?;
+    for table in tables {
+        let ident = format!("`{}`", table.replace('`', "``"));
+        let create: String = sqlx::query(&format!("SHOW CREATE TABLE {}", ident))
+            .fetch_one(pool)
+            .await?
+            .try_get(1)?;
+        out.push_str(&format!("\nDROP TABLE IF EXISTS {};\n{};\n", ident, create));
+
+        let rows = sqlx::query(&format!("SELECT * FROM {}", ident))
+            .fetch_all(pool)
+            .await?;
+        for row in &rows {
+            let serde_json::Value::Object(map) = mysql_row_to_json(row) else {
+                continue;
+            };
+            let columns = map
+                .keys()
+                .map(|c| format!("`{}`", c.replace('`', "``")))
+                .collect::<Vec<_>>()
+                .join(", ");
+            let values = map.values().map(sql_literal).collect::<Vec<_>>().join(", ");
+            out.push_str(&format!(
+                "INSERT INTO {} ({}) VALUES ({});\n",
+                ident, columns, values
+            ));
+        }
+    }
+    out.push_str("\nSET FOREIGN_KEY_CHECKS = 1;\n");
+    Ok(out)
+}
+
+/// Format a JSON value as a MySQL literal
+fn sql_literal(value: &serde_json::Value) -> String {
+    match value {
+        serde_json::Value::Null => "NULL".to_string(),
+        serde_json::Value::Bool(b) => if *b { "1" } else { "0" }.to_string(),
+        serde_json::Value::Number(n) => n.to_string(),
+        serde_json::Value::String(s) => {
+            let mut quoted = String::with_capacity(s.len() + 2);
+            quoted.push('\'');
+            for c in s.chars() {
+                match c {
+                    '\'' => quoted.push_str("\\'"),
+                    '\\' => quoted.push_str("\\\\"),
+                    '\0' => quoted.push_str("\\0"),
+                    '\n' => quoted.push_str("\\n"),
+                    '\r' => quoted.push_str("\\r"),
+                    '\x1a' => quoted.push_str("\\Z"),
+                    c => quoted.push(c),
+                }
+            }
+            quoted.push('\'');
+            quoted
+        }
+        other => sql_literal(&serde_json::Value::String(other.to_string())),
+    }
 }
 
 /// Restore from ZIP bytes
 pub async fn restore_backup(
     pool: &DynDatabasePool,
-    upload_dir: &Path,
+    sources: &BackupSources,
     zip_data: &[u8],
 ) -> Result<BackupManifest> {
     let reader = std::io::Cursor::new(zip_data);
@@ -330,19 +670,26 @@ pub async fn restore_backup(
         }
     }
 
-    // Restore uploads directory
+    // Restore uploads, themes and plugins directories
     for i in 0..archive.len() {
         let mut file = archive.by_index(i)?;
         let Some(enclosed_name) = file.enclosed_name().map(|p| p.to_owned()) else {
             warn!(name = file.name(), "skipping unsafe backup entry path");
             continue;
         };
-        if enclosed_name.starts_with("uploads") && !file.is_dir() {
-            let rel = match enclosed_name.strip_prefix("uploads") {
-                Ok(rel) if !rel.as_os_str().is_empty() => rel,
-                _ => continue,
-            };
-            let dest = upload_dir.join(rel);
+        if file.is_dir() {
+            continue;
+        }
+        let Some((rel, dir)) = sources.dirs().into_iter().find_map(|(prefix, dir)| {
+            match enclosed_name.strip_prefix(prefix) {
+                Ok(rel) if !rel.as_os_str().is_empty() => Some((rel.to_path_buf(), dir)),
+                _ => None,
+            }
+        }) else {
+            continue;
+        };
+        {
+            let dest = dir.join(rel);
             if let Some(parent) = dest.parent() {
                 std::fs::create_dir_all(parent)?;
             }
@@ -419,6 +766,181 @@ pub async fn export_markdown(pool: &DynDatabasePool) -> Result<Vec<u8>> {
     Ok(cursor.into_inner())
 }
 
+/// Front matter of a file in the content bundle, in the form the Markdown
+/// importer reads
+#[derive(Debug, Serialize)]
+struct BundleFrontMatter<'a> {
+    title: &'a str,
+    slug: &'a str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    layout: Option<&'static str>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    date: Option<&'a str>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    updated: Option<&'a str>,
+    draft: bool,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    categories: Vec<&'a str>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    tags: Vec<&'a str>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    thumbnail: Option<&'a str>,
+}
+
+/// Export all content as a portable ZIP bundle
+///
+/// - `articles/<slug>.md` and `pages/<slug>.md`: Markdown with YAML front
+///   matter, importable with the Markdown importer
+/// - `categories.json`, `comments.json`, `navigation.json`, `settings.json`:
+///   the rows of those tables
+///
+/// Settings holding passwords, secrets, tokens or keys are left out.
+pub async fn export_bundle(pool: &DynDatabasePool) -> Result<Vec<u8>> {
+    let articles = export_table(pool, "articles").await?;
+    let pages = export_table(pool, "pages").await?;
+    let categories = export_table(pool, "categories").await?;
+    let tags = export_table(pool, "tags").await?;
+    let article_tags = export_table(pool, "article_tags").await?;
+    let comments = export_table(pool, "comments").await?;
+    let nav_items = export_table(pool, "nav_items").await?;
+    let settings: Vec<serde_json::Value> = export_table(pool, "settings")
+        .await?
+        .into_iter()
+        .filter(|row| {
+            row.get("key")
+                .and_then(|k| k.as_str())
+                .is_some_and(|key| !is_secret_setting(key))
+        })
+        .collect();
+
+    let category_names: HashMap<i64, &str> = categories
+        .iter()
+        .filter_map(|c| Some((c.get("id")?.as_i64()?, c.get("name")?.as_str()?)))
+        .collect();
+    let tag_names: HashMap<i64, &str> = tags
+        .iter()
+        .filter_map(|t| Some((t.get("id")?.as_i64()?, t.get("name")?.as_str()?)))
+        .collect();
+    let mut article_tag_names: HashMap<i64, Vec<&str>> = HashMap::new();
+    for row in &article_tags {
+        let (Some(article_id), Some(name)) = (
+            row.get("article_id").and_then(|v| v.as_i64()),
+            row.get("tag_id")
+                .and_then(|v| v.as_i64())
+                .and_then(|id| tag_names.get(&id).copied()),
+        ) else {
+            continue;
+        };
+        article_tag_names.entry(article_id).or_default().push(name);
+    }
+
+    let mut zip = zip::ZipWriter::new(std::io::Cursor::new(Vec::new()));
+    let options = SimpleFileOptions::default().compression_method(zip::CompressionMethod::Deflated);
+
+    for article in &articles {
+        let id = article.get("id").and_then(|v| v.as_i64()).unwrap_or(0);
+        let category = article
+            .get("category_id")
+            .and_then(|v| v.as_i64())
+            .and_then(|id| category_names.get(&id).copied());
+        let tags = article_tag_names.remove(&id).unwrap_or_default();
+        let (name, text) = bundle_document("articles", article, category, tags)?;
+        zip.start_file(name, options)?;
+        zip.write_all(text.as_bytes())?;
+    }
+    for page in &pages {
+        let (name, text) = bundle_document("pages", page, None, Vec::new())?;
+        zip.start_file(name, options)?;
+        zip.write_all(text.as_bytes())?;
+    }
+    for (name, rows) in [
+        ("categories.json", &categories),
+        ("comments.json", &comments),
+        ("navigation.json", &nav_items),
+        ("settings.json", &settings),
+    ] {
+        zip.start_file(name, options)?;
+        zip.write_all(&serde_json::to_vec_pretty(rows)?)?;
+    }
+
+    let cursor = zip.finish()?;
+    Ok(cursor.into_inner())
+}
+
+/// Settings that must not leave the site in a content export
+fn is_secret_setting(key: &str) -> bool {
+    let key = key.to_ascii_lowercase();
+    ["password", "secret", "token"]
+        .iter()
+        .any(|word| key.contains(word))
+        || key.ends_with("_key")
+}
+
+/// File name and contents of an article or page row in the content bundle
+fn bundle_document(
+    dir: &str,
+    row: &serde_json::Value,
+    category: Option<&str>,
+    tags: Vec<&str>,
+) -> Result<(String, String)> {
+    let text = |key: &str| {
+        row.get(key)
+            .and_then(|v| v.as_str())
+            .filter(|s| !s.is_empty())
+    };
+    let id = row.get("id").and_then(|v| v.as_i64()).unwrap_or(0);
+    let slug = text("slug").unwrap_or_default();
+    let status = text("status").unwrap_or("draft");
+    let scheduled = text("scheduled_at").filter(|_| status == "draft");
+
+    let front = BundleFrontMatter {
+        title: text("title").unwrap_or("Untitled"),
+        slug,
+        layout: (dir == "pages").then_some("page"),
+        // A scheduled draft keeps its publish time, so it is scheduled again on import
+        date: scheduled
+            .or_else(|| text("published_at"))
+            .or_else(|| text("created_at")),
+        updated: text("updated_at"),
+        draft: status != "published" && scheduled.is_none(),
+        categories: category.into_iter().collect(),
+        tags,
+        thumbnail: text("thumbnail"),
+    };
+    let yaml = serde_yaml::to_string(&front)?;
+    let mut content = text("content").unwrap_or_default().to_string();
+    let mut input_format = text("input_format");
+    // Block documents are exported as Markdown, the portable format
+    if input_format == Some("blocks") {
+        if let Ok(blocks) = crate::services::markup::blocks::parse(&content) {
+            content = crate::services::markup::blocks::to_markdown(&blocks);
+            input_format = None;
+        }
+    }
+
+    // Slugs never contain path separators, but the file name must not escape `dir`
+    let stem: String = slug
+        .chars()
+        .map(|c| if matches!(c, '/' | '\\') { '-' } else { c })
+        .collect();
+    let stem = match stem.trim_matches('.') {
+        "" => format!("{}-{}", dir.trim_end_matches('s'), id),
+        _ => stem,
+    };
+    // Articles keep the syntax they were written in
+    let extension = match input_format {
+        Some("bbcode") => "bbcode",
+        Some("rst") => "rst",
+        Some("html") => "html",
+        Some("blocks") => "json",
+        _ => "md",
+    };
+    Ok((
+        format!("{}/{}.{}", dir, stem, extension),
+        format!("---\n{}---\n\n{}\n", yaml, content.trim_end()),
+    ))
+}
+
 /// Import result summary
 #[derive(Debug, Serialize)]
 pub struct ImportResult {
@@ -800,7 +1322,8 @@ fn validate_zip_archive_limits<R: Read + Seek>(archive: &mut zip::ZipArchive<R>)
         if file.enclosed_name().is_none() {
             anyhow::bail!("ZIP contains unsafe entry path: {}", file.name());
         }
-        if file.is_dir() {
+        // Database snapshots are kept for manual recovery and never unpacked
+        if file.is_dir() || file.name().starts_with("database/") {
             continue;
         }
 
@@ -881,6 +1404,21 @@ fn mysql_row_to_json(row: &sqlx::mysql::MySqlRow) -> serde_json::Value {
             map.insert(name.to_string(), serde_json::Value::String(v));
         } else if let Ok(v) = row.try_get::<bool, _>(name) {
             map.insert(name.to_string(), serde_json::Value::Bool(v));
+        } else if let Ok(v) = row.try_get::<NaiveDateTime, _>(name) {
+            map.insert(
+                name.to_string(),
+                serde_json::Value::String(v.format("%Y-%m-%d %H:%M:%S").to_string()),
+            );
+        } else if let Ok(v) = row.try_get::<DateTime<Utc>, _>(name) {
+            map.insert(
+                name.to_string(),
+                serde_json::Value::String(v.format("%Y-%m-%d %H:%M:%S").to_string()),
+            );
+        } else if let Ok(v) = row.try_get::<NaiveDate, _>(name) {
+            map.insert(
+                name.to_string(),
+                serde_json::Value::String(v.format("%Y-%m-%d").to_string()),
+            );
         } else {
             map.insert(name.to_string(), serde_json::Value::Null);
         }
@@ -953,3 +1491,156 @@ fn add_dir_to_zip<W: Write + std::io::Seek>(
     }
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::DatabaseConfig;
+    use crate::db::{create_pool, migrations};
+
+    #[test]
+    fn archive_names() {
+        assert!(is_archive_name("noteva-backup-20240501-120000.zip"));
+        assert!(is_archive_name("noteva-backup-20240501-120000-2.zip"));
+        assert!(!is_archive_name("../noteva-backup-20240501-120000.zip"));
+        assert!(!is_archive_name("noteva-backup-..zip"));
+        assert!(!is_archive_name("noteva-backup-1.zip.partial"));
+        assert!(!is_archive_name("other.zip"));
+
+        let mut names = vec![
+            "noteva-backup-20240501-120000-10.zip",
+            "noteva-backup-20240501-120000.zip",
+            "noteva-backup-20240502-080000.zip",
+            "noteva-backup-20240501-120000-2.zip",
+        ];
+        names.sort_by(|a, b| archive_order(b).cmp(&archive_order(a)));
+        assert_eq!(
+            names,
+            vec![
+                "noteva-backup-20240502-080000.zip",
+                "noteva-backup-20240501-120000-10.zip",
+                "noteva-backup-20240501-120000-2.zip",
+                "noteva-backup-20240501-120000.zip",
+            ]
+        );
+    }
+
+    #[test]
+    fn sql_literals_are_escaped() {
+        use serde_json::json;
+        assert_eq!(sql_literal(&json!(null)), "NULL");
+        assert_eq!(sql_literal(&json!(true)), "1");
+        assert_eq!(sql_literal(&json!(42)), "42");
+        assert_eq!(
+            sql_literal(&json!("it's a \\ path\n")),
+            "'it\\'s a \\\\ path\\n'"
+        );
+    }
+
+    #[test]
+    fn bundle_documents() {
+        use serde_json::json;
+        let article = json!({
+            "id": 7,
+            "slug": "hello/world",
+            "title": "Hello",
+            "content": "Body\n\n",
+            "status": "draft",
+            "created_at": "2024-05-01T12:00:00Z",
+            "scheduled_at": "2024-06-01T08:00:00Z",
+            "thumbnail": "",
+        });
+        let (name, text) =
+            bundle_document("articles", &article, Some("News"), vec!["rust"]).unwrap();
+        assert_eq!(name, "articles/hello-world.md");
+        let (yaml, body) = text
+            .strip_prefix("---\n")
+            .and_then(|rest| rest.split_once("---\n"))
+            .unwrap();
+        assert_eq!(body, "\nBody\n");
+        let front: serde_json::Value = serde_yaml::from_str(yaml).unwrap();
+        assert_eq!(front["date"], "2024-06-01T08:00:00Z");
+        assert_eq!(front["draft"], false);
+        assert_eq!(front["categories"], json!(["News"]));
+        assert_eq!(front["tags"], json!(["rust"]));
+        assert!(front.get("thumbnail").is_none());
+
+        let page = json!({ "id": 3, "slug": "..", "title": "About", "status": "draft" });
+        let (name, text) = bundle_document("pages", &page, None, Vec::new()).unwrap();
+        assert_eq!(name, "pages/page-3.md");
+        assert!(text.contains("layout: page\n") && text.contains("draft: true\n"));
+
+        let blocks = json!({
+            "id": 8,
+            "slug": "blocks",
+            "title": "Blocks",
+            "content": r#"[{"type": "heading", "level": 2, "text": "Intro"}]"#,
+            "input_format": "blocks",
+            "status": "published",
+        });
+        let (name, text) = bundle_document("articles", &blocks, None, Vec::new()).unwrap();
+        assert_eq!(name, "articles/blocks.md");
+        assert!(text.ends_with("---\n\n## Intro\n"));
+
+        assert!(is_secret_setting("smtp_password"));
+        assert!(is_secret_setting("api_key"));
+        assert!(!is_secret_setting("site_name"));
+        assert!(!is_secret_setting("keywords"));
+    }
+
+    #[tokio::test]
+    async fn test_create_list_prune_restore() {
+        // A file database, since in-memory ones cannot be snapshotted
+        let root = tempfile::tempdir().unwrap();
+        let pool = create_pool(&DatabaseConfig {
+            driver: DatabaseDriver::Sqlite,
+            url: root.path().join("noteva.db").to_string_lossy().to_string(),
+        })
+        .await
+        .unwrap();
+        migrations::run_migrations(&pool).await.unwrap();
+        let sources = BackupSources {
+            uploads: root.path().join("uploads"),
+            themes: root.path().join("themes"),
+            plugins: root.path().join("plugins"),
+        };
+        std::fs::create_dir_all(sources.themes.join("default")).unwrap();
+        std::fs::write(sources.themes.join("default/theme.json"), "{}").unwrap();
+        let service = BackupService::new(
+            pool.clone(),
+            sources.clone(),
+            BackupConfig {
+                path: root.path().join("backups"),
+                interval_hours: 0,
+                keep: 2,
+            },
+        );
+
+        let first = service.create().await.unwrap();
+        service.create().await.unwrap();
+        let last = service.create().await.unwrap();
+        let names: Vec<String> = service
+            .list()
+            .unwrap()
+            .into_iter()
+            .map(|a| a.name)
+            .collect();
+        assert_eq!(names.len(), 2);
+        assert_eq!(names[0], last.name);
+        assert!(service.archive_path(&first.name).is_none());
+
+        let path = service.archive_path(&last.name).unwrap();
+        let mut archive = zip::ZipArchive::new(std::fs::File::open(&path).unwrap()).unwrap();
+        assert!(archive.by_name(SQLITE_SNAPSHOT_ENTRY).unwrap().size() > 0);
+        assert!(archive.by_name("themes/default/theme.json").is_ok());
+
+        std::fs::remove_dir_all(&sources.themes).unwrap();
+        let manifest = service.restore_archive(&last.name).await.unwrap();
+        assert_eq!(manifest.database.as_deref(), Some(SQLITE_SNAPSHOT_ENTRY));
+        assert!(sources.themes.join("default/theme.json").is_file());
+
+        assert!(service.delete(&last.name).unwrap());
+        assert!(!service.delete(&last.name).unwrap());
+        assert!(!service.delete("../config.yml").unwrap());
+    }
+}
@@ -0,0 +1,206 @@
+//! Server-side verification for third-party captcha providers.
+//!
+//! Cloudflare Turnstile and hCaptcha are configured through the
+//! `captcha_provider`, `captcha_site_key` and `captcha_secret_key` settings.
+//! Tokens are checked against the provider's `siteverify` endpoint when a
+//! comment is created or a user registers. The built-in proof-of-work and Cap
+//! providers are verified by the API layer instead.
+
+use crate::db::repositories::SettingsRepository;
+use anyhow::Context;
+use serde::Deserialize;
+use std::sync::Arc;
+use std::time::Duration;
+
+/// Setting keys used for captcha configuration
+pub mod keys {
+    pub const CAPTCHA_PROVIDER: &str = "captcha_provider";
+    pub const CAPTCHA_SITE_KEY: &str = "captcha_site_key";
+    pub const CAPTCHA_SECRET_KEY: &str = "captcha_secret_key";
+}
+
+/// Timeout for calls to the provider's verification endpoint
+const VERIFY_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Third-party captcha providers verified by [`CaptchaVerifier`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CaptchaProvider {
+    Turnstile,
+    HCaptcha,
+}
+
+impl CaptchaProvider {
+    /// Parse the `captcha_provider` setting; other providers yield `None`
+    pub fn from_setting(value: &str) -> Option<Self> {
+        match value.trim().to_ascii_lowercase().as_str() {
+            "turnstile" => Some(Self::Turnstile),
+            "hcaptcha" => Some(Self::HCaptcha),
+            _ => None,
+        }
+    }
+
+    /// Token verification endpoint
+    pub fn verify_url(self) -> &'static str {
+        match self {
+            Self::Turnstile => "https://challenges.cloudflare.com/turnstile/v0/siteverify",
+            Self::HCaptcha => "https://hcaptcha.com/siteverify",
+        }
+    }
+}
+
+/// Captcha verification errors
+#[derive(Debug, thiserror::Error)]
+pub enum CaptchaError {
+    /// No token was submitted
+    #[error("Captcha token is required")]
+    Required,
+
+    /// The provider rejected the token
+    #[error("Captcha verification failed")]
+    Failed,
+
+    /// The provider could not be reached or answered unexpectedly
+    #[error("Captcha verification error: {0}")]
+    Internal(#[from] anyhow::Error),
+}
+
+#[derive(Debug, Deserialize)]
+struct SiteVerifyResponse {
+    success: bool,
+}
+
+/// Verifies Turnstile / hCaptcha tokens using keys stored in settings
+pub struct CaptchaVerifier {
+    settings_repo: Arc<dyn SettingsRepository>,
+    client: reqwest::Client,
+}
+
+impl CaptchaVerifier {
+    pub fn new(settings_repo: Arc<dyn SettingsRepository>) -> Self {
+        let client = reqwest::Client::builder()
+            .timeout(VERIFY_TIMEOUT)
+            .build()
+            .unwrap_or_default();
+        Self {
+            settings_repo,
+            client,
+        }
+    }
+
+    /// The active provider and its secret key, if Turnstile or hCaptcha is
+    /// selected and both keys are configured.
+    pub async fn active_provider(&self) -> Option<(CaptchaProvider, String)> {
+        let provider = CaptchaProvider::from_setting(&self.setting(keys::CAPTCHA_PROVIDER).await)?;
+        let site_key = self.setting(keys::CAPTCHA_SITE_KEY).await;
+        let secret_key = self.setting(keys::CAPTCHA_SECRET_KEY).await;
+        if site_key.trim().is_empty() || secret_key.trim().is_empty() {
+            return None;
+        }
+        Some((provider, secret_key.trim().to_string()))
+    }
+
+    /// Verify a submitted token. Succeeds without a request when no
+    /// third-party provider is configured.
+    pub async fn verify(
+        &self,
+        token: Option<&str>,
+        remote_ip: Option<&str>,
+    ) -> Result<(), CaptchaError> {
+        let Some((provider, secret)) = self.active_provider().await else {
+            return Ok(());
+        };
+        let token = token
+            .map(str::trim)
+            .filter(|value| !value.is_empty())
+            .ok_or(CaptchaError::Required)?;
+
+        let mut form = vec![("secret", secret.as_str()), ("response", token)];
+        if let Some(ip) = remote_ip.filter(|value| !value.trim().is_empty()) {
+            form.push(("remoteip", ip));
+        }
+
+        let result = self
+            .client
+            .post(provider.verify_url())
+            .form(&form)
+            .send()
+            .await
+            .context("Failed to reach captcha provider")?
+            .json::<SiteVerifyResponse>()
+            .await
+            .context("Invalid captcha provider response")?;
+
+        if result.success {
+            Ok(())
+        } else {
+            Err(CaptchaError::Failed)
+        }
+    }
+
+    async fn setting(&self, key: &str) -> String {
+        match self.settings_repo.get(key).await {
+            Ok(Some(setting)) => setting.value,
+            _ => String::new(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::repositories::SqlxSettingsRepository;
+    use crate::db::{create_test_pool, migrations};
+
+    async fn verifier() -> (Arc<SqlxSettingsRepository>, CaptchaVerifier) {
+        let pool = create_test_pool()
+            .await
+            .expect("Failed to create test pool");
+        migrations::run_migrations(&pool)
+            .await
+            .expect("Failed to run migrations");
+        let repo = Arc::new(SqlxSettingsRepository::new(pool));
+        (repo.clone(), CaptchaVerifier::new(repo))
+    }
+
+    #[test]
+    fn only_third_party_providers_are_handled() {
+        assert_eq!(
+            CaptchaProvider::from_setting(" Turnstile "),
+            Some(CaptchaProvider::Turnstile)
+        );
+        assert_eq!(
+            CaptchaProvider::from_setting("hcaptcha"),
+            Some(CaptchaProvider::HCaptcha)
+        );
+        assert_eq!(CaptchaProvider::from_setting("cap"), None);
+        assert_eq!(CaptchaProvider::from_setting("noteva_pow"), None);
+    }
+
+    #[tokio::test]
+    async fn unconfigured_provider_skips_verification() {
+        let (repo, verifier) = verifier().await;
+        assert!(verifier.verify(None, None).await.is_ok());
+
+        // Provider selected but keys missing
+        repo.set(keys::CAPTCHA_PROVIDER, "turnstile").await.unwrap();
+        assert!(verifier.active_provider().await.is_none());
+        assert!(verifier.verify(None, None).await.is_ok());
+    }
+
+    #[tokio::test]
+    async fn configured_provider_requires_token() {
+        let (repo, verifier) = verifier().await;
+        repo.set(keys::CAPTCHA_PROVIDER, "hcaptcha").await.unwrap();
+        repo.set(keys::CAPTCHA_SITE_KEY, "site").await.unwrap();
+        repo.set(keys::CAPTCHA_SECRET_KEY, "secret").await.unwrap();
+
+        assert_eq!(
+            verifier.active_provider().await,
+            Some((CaptchaProvider::HCaptcha, "secret".to_string()))
+        );
+        assert!(matches!(
+            verifier.verify(Some("  "), None).await,
+            Err(CaptchaError::Required)
+        ));
+    }
+}
@@ -12,14 +12,15 @@
 //! - 2.1: WHEN 用户创建分类 THEN Category_Service SHALL 创建分类记录并支持设置父分类
 //! - 2.2: WHEN 用户为文章指定分�?THEN Category_Service SHALL 建立文章与分类的关联
 //! - 2.3: WHEN 用户请求某分类下的文�?THEN Category_Service SHALL 返回该分类及其子分类下的所有文�?
-//! - 2.4: WHEN 用户删除分类 THEN Category_Service SHALL 将该分类下的文章移至默认分类
+//! - 2.4: WHEN 用户删除分类 THEN Category_Service SHALL 将直接属于该分类的文章移至默认分类，子分类上移一级并保留各自的文章
 //! - 2.5: IF 分类名称已存�?THEN Category_Service SHALL 返回重复错误
 
 use crate::cache::{Cache, CacheLayer};
 use crate::db::repositories::CategoryRepository;
 use crate::db::DynDatabasePool;
-use crate::models::{Category, CategoryTree};
+use crate::models::{Category, CategoryAccess, CategoryTree};
 use anyhow::{Context, Result};
+use std::collections::HashMap;
 use std::sync::Arc;
 use std::time::Duration;
 
@@ -32,6 +33,9 @@ const CACHE_KEY_CATEGORY_BY_SLUG: &str = "category:slug:";
 const CACHE_KEY_CATEGORY_TREE: &str = "category:tree";
 const CACHE_KEY_CATEGORY_LIST: &str = "category:list";
 
+/// Maximum nesting depth of the category tree (root categories are level 1)
+pub const MAX_CATEGORY_DEPTH: usize = 5;
+
 /// Error types for category service operations
 #[derive(Debug, thiserror::Error)]
 pub enum CategoryServiceError {
@@ -63,6 +67,10 @@ pub enum CategoryServiceError {
     #[error("Circular reference detected: category cannot be its own ancestor")]
     CircularReference,
 
+    /// The move would nest categories deeper than allowed
+    #[error("Categories cannot be nested more than {0} levels deep")]
+    TooDeep(usize),
+
     /// Internal error
     #[error("Internal error: {0}")]
     InternalError(#[from] anyhow::Error),
@@ -163,16 +171,18 @@ impl CategoryService {
             {
                 return Err(CategoryServiceError::ParentNotFound(parent_id));
             }
+            self.check_depth(None, Some(parent_id)).await?;
         }
 
         // Create category
-        let category = Category::new(
+        let mut category = Category::new(
             slug,
             input.name,
             input.description,
             input.parent_id,
             input.sort_order.unwrap_or(0),
         );
+        category.access = input.access.unwrap_or_default();
 
         let created = self
             .repo
@@ -402,6 +412,9 @@ impl CategoryService {
                 if self.would_create_cycle(id, parent_id).await? {
                     return Err(CategoryServiceError::CircularReference);
                 }
+                if category.parent_id != Some(parent_id) {
+                    self.check_depth(Some(id), Some(parent_id)).await?;
+                }
             }
             category.parent_id = new_parent_id;
         }
@@ -411,6 +424,10 @@ impl CategoryService {
             category.sort_order = new_sort_order;
         }
 
+        if let Some(access) = input.access {
+            category.access = access;
+        }
+
         // Save changes
         let updated = self
             .repo
@@ -426,8 +443,9 @@ impl CategoryService {
 
     /// Delete a category
     ///
-    /// When a category is deleted, all articles in that category are moved
-    /// to the default "uncategorized" category.
+    /// The articles filed directly under the category move to the default
+    /// "uncategorized" category. Child categories move up to the deleted
+    /// category's parent and keep their own articles.
     ///
     /// # Arguments
     /// * `id` - Category ID to delete
@@ -436,9 +454,94 @@ impl CategoryService {
     /// - `NotFound` if the category doesn't exist
     /// - `CannotDeleteDefault` if trying to delete the default category
     ///
-    /// Satisfies requirement 2.4: WHEN 用户删除分类 THEN Category_Service SHALL 将该分类下的文章移至默认分类
+    /// Satisfies requirement 2.4: WHEN 用户删除分类 THEN Category_Service SHALL 将直接属于该分类的文章移至默认分类，子分类上移一级并保留各自的文章
     pub async fn delete(&self, id: i64) -> Result<(), CategoryServiceError> {
-        // Get the category to delete
+        self.delete_into(id, None).await
+    }
+
+    /// Delete a category, moving its articles to `move_to`
+    ///
+    /// Falls back to the default category when `move_to` is `None`. Child
+    /// categories are reparented to the deleted category's parent and keep
+    /// their own articles. Everything happens in one transaction.
+    ///
+    /// # Errors
+    /// - `NotFound` if the category or the target doesn't exist
+    /// - `CannotDeleteDefault` if trying to delete the default category
+    /// - `ValidationError` if the target is the category being deleted
+    pub async fn delete_into(
+        &self,
+        id: i64,
+        move_to: Option<i64>,
+    ) -> Result<(), CategoryServiceError> {
+        let category = self.get_deletable(id).await?;
+
+        // Resolve the target, defaulting to the "uncategorized" category
+        let target = match move_to {
+            Some(target_id) if target_id == id => {
+                return Err(CategoryServiceError::ValidationError(
+                    "Cannot move articles into the category being deleted".to_string(),
+                ));
+            }
+            Some(target_id) => self
+                .repo
+                .get_by_id(target_id)
+                .await
+                .context("Failed to get target category")?
+                .ok_or_else(|| {
+                    CategoryServiceError::NotFound(format!(
+                        "Category with ID {} not found",
+                        target_id
+                    ))
+                })?,
+            None => self
+                .repo
+                .get_default()
+                .await
+                .context("Failed to get default category")?
+                .ok_or_else(|| {
+                    CategoryServiceError::NotFound("Default category not found".to_string())
+                })?,
+        };
+
+        self.repo
+            .delete_cascading(id, category.parent_id, Some(target.id))
+            .await
+            .context("Failed to delete category")?;
+
+        self.invalidate_cache().await?;
+
+        Ok(())
+    }
+
+    /// Delete a category together with the articles filed directly under it
+    ///
+    /// Child categories are reparented as in `delete_into`. The category and
+    /// its articles go in one transaction; callers clean up whatever else
+    /// the returned articles owned.
+    ///
+    /// # Returns
+    /// IDs of the deleted articles
+    ///
+    /// # Errors
+    /// - `NotFound` if the category doesn't exist
+    /// - `CannotDeleteDefault` if trying to delete the default category
+    pub async fn delete_with_articles(&self, id: i64) -> Result<Vec<i64>, CategoryServiceError> {
+        let category = self.get_deletable(id).await?;
+
+        let article_ids = self
+            .repo
+            .delete_cascading(id, category.parent_id, None)
+            .await
+            .context("Failed to delete category")?;
+
+        self.invalidate_cache().await?;
+
+        Ok(article_ids)
+    }
+
+    /// Look up a category that is about to be deleted
+    async fn get_deletable(&self, id: i64) -> Result<Category, CategoryServiceError> {
         let category = self
             .repo
             .get_by_id(id)
@@ -453,52 +556,141 @@ impl CategoryService {
             return Err(CategoryServiceError::CannotDeleteDefault);
         }
 
-        // Get the default category
-        let default_category = self
+        Ok(category)
+    }
+
+    /// Move a category under a new parent at the given position
+    ///
+    /// `position` is the index among the new siblings; `None` or an index
+    /// past the end appends. Sibling sort orders are renumbered.
+    ///
+    /// # Returns
+    /// The recomputed category tree
+    ///
+    /// # Errors
+    /// - `NotFound` if the category doesn't exist
+    /// - `ParentNotFound` if the new parent doesn't exist
+    /// - `CircularReference` if the parent is the category or one of its descendants
+    /// - `TooDeep` if the move would exceed `MAX_CATEGORY_DEPTH`
+    pub async fn move_to(
+        &self,
+        id: i64,
+        parent_id: Option<i64>,
+        position: Option<usize>,
+    ) -> Result<Vec<CategoryTree>, CategoryServiceError> {
+        let categories = self
             .repo
-            .get_default()
+            .list()
             .await
-            .context("Failed to get default category")?
-            .ok_or_else(|| {
-                CategoryServiceError::NotFound("Default category not found".to_string())
-            })?;
+            .context("Failed to list categories")?;
 
-        // Get all descendant category IDs (including this one)
-        let descendant_ids = self
-            .repo
-            .get_all_descendants(id)
+        let category = categories.iter().find(|c| c.id == id).ok_or_else(|| {
+            CategoryServiceError::NotFound(format!("Category with ID {} not found", id))
+        })?;
+
+        if let Some(parent_id) = parent_id {
+            if !categories.iter().any(|c| c.id == parent_id) {
+                return Err(CategoryServiceError::ParentNotFound(parent_id));
+            }
+            if self.would_create_cycle(id, parent_id).await? {
+                return Err(CategoryServiceError::CircularReference);
+            }
+            if category.parent_id != Some(parent_id) {
+                check_depth_in(&categories, Some(id), Some(parent_id))?;
+            }
+        }
+
+        let mut siblings: Vec<&Category> = categories
+            .iter()
+            .filter(|c| c.parent_id == parent_id && c.id != id)
+            .collect();
+        siblings.sort_by_key(|c| (c.sort_order, c.id));
+
+        let mut ids: Vec<i64> = siblings.iter().map(|c| c.id).collect();
+        let position = position.unwrap_or(ids.len()).min(ids.len());
+        ids.insert(position, id);
+
+        self.repo
+            .set_order(parent_id, &ids)
             .await
-            .context("Failed to get descendant categories")?;
+            .context("Failed to move category")?;
 
-        // Move articles from this category and all descendants to default category (Requirement 2.4)
-        self.move_articles_to_category(&descendant_ids, default_category.id)
-            .await?;
+        self.invalidate_cache().await?;
+        self.list_tree().await
+    }
 
-        // Update children to have no parent (or move to default parent)
-        // First, get direct children and update their parent to this category's parent
-        let children = self
+    /// Reorder the direct children of `parent_id`
+    ///
+    /// `ids` must list exactly the current children of the parent; their
+    /// sort orders become their index in the list.
+    ///
+    /// # Returns
+    /// The recomputed category tree
+    ///
+    /// # Errors
+    /// - `ParentNotFound` if the parent doesn't exist
+    /// - `ValidationError` if `ids` is not a permutation of the children
+    pub async fn reorder(
+        &self,
+        parent_id: Option<i64>,
+        ids: &[i64],
+    ) -> Result<Vec<CategoryTree>, CategoryServiceError> {
+        let categories = self
             .repo
-            .get_children(id)
+            .list()
             .await
-            .context("Failed to get children")?;
-        for mut child in children {
-            child.parent_id = category.parent_id;
-            self.repo
-                .update(&child)
-                .await
-                .context("Failed to update child category")?;
+            .context("Failed to list categories")?;
+
+        if let Some(parent_id) = parent_id {
+            if !categories.iter().any(|c| c.id == parent_id) {
+                return Err(CategoryServiceError::ParentNotFound(parent_id));
+            }
+        }
+
+        let mut current: Vec<i64> = categories
+            .iter()
+            .filter(|c| c.parent_id == parent_id)
+            .map(|c| c.id)
+            .collect();
+        let mut requested = ids.to_vec();
+        current.sort_unstable();
+        requested.sort_unstable();
+        if current != requested {
+            return Err(CategoryServiceError::ValidationError(
+                "Category IDs must list every child of the parent exactly once".to_string(),
+            ));
         }
 
-        // Delete the category
         self.repo
-            .delete(id)
+            .set_order(parent_id, ids)
             .await
-            .context("Failed to delete category")?;
+            .context("Failed to reorder categories")?;
 
-        // Invalidate cache
         self.invalidate_cache().await?;
+        self.list_tree().await
+    }
 
-        Ok(())
+    /// IDs of the articles filed directly under a category
+    pub async fn article_ids(&self, category_id: i64) -> Result<Vec<i64>, CategoryServiceError> {
+        use crate::config::DatabaseDriver;
+
+        let ids = match self.pool.driver() {
+            DatabaseDriver::Sqlite => {
+                sqlx::query_scalar("SELECT id FROM articles WHERE category_id = ?")
+                    .bind(category_id)
+                    .fetch_all(self.pool.as_sqlite_or_err()?)
+                    .await
+            }
+            DatabaseDriver::Mysql => {
+                sqlx::query_scalar("SELECT id FROM articles WHERE category_id = ?")
+                    .bind(category_id)
+                    .fetch_all(self.pool.as_mysql_or_err()?)
+                    .await
+            }
+        }
+        .context("Failed to list category articles")?;
+
+        Ok(ids)
     }
 
     /// Get the default category (uncategorized)
@@ -577,44 +769,19 @@ impl CategoryService {
         Ok(descendants.contains(&new_parent_id))
     }
 
-    /// Move articles from specified categories to a target category
-    ///
-    /// This is called when deleting a category to move its articles to the default category.
-    ///
-    /// Satisfies requirement 2.4: WHEN 用户删除分类 THEN Category_Service SHALL 将该分类下的文章移至默认分类
-    async fn move_articles_to_category(
+    /// Check that placing `category_id` (or a new leaf when `None`) under
+    /// `parent_id` stays within `MAX_CATEGORY_DEPTH`
+    async fn check_depth(
         &self,
-        from_category_ids: &[i64],
-        to_category_id: i64,
+        category_id: Option<i64>,
+        parent_id: Option<i64>,
     ) -> Result<(), CategoryServiceError> {
-        use crate::config::DatabaseDriver;
-
-        match self.pool.driver() {
-            DatabaseDriver::Sqlite => {
-                let pool = self.pool.as_sqlite_or_err()?;
-                for category_id in from_category_ids {
-                    sqlx::query("UPDATE articles SET category_id = ? WHERE category_id = ?")
-                        .bind(to_category_id)
-                        .bind(category_id)
-                        .execute(pool)
-                        .await
-                        .context("Failed to move articles to default category")?;
-                }
-            }
-            DatabaseDriver::Mysql => {
-                let pool = self.pool.as_mysql_or_err()?;
-                for category_id in from_category_ids {
-                    sqlx::query("UPDATE articles SET category_id = ? WHERE category_id = ?")
-                        .bind(to_category_id)
-                        .bind(category_id)
-                        .execute(pool)
-                        .await
-                        .context("Failed to move articles to default category")?;
-                }
-            }
-        }
-
-        Ok(())
+        let categories = self
+            .repo
+            .list()
+            .await
+            .context("Failed to list categories")?;
+        check_depth_in(&categories, category_id, parent_id)
     }
 
     /// Invalidate all category-related cache entries
@@ -635,6 +802,50 @@ impl CategoryService {
     }
 }
 
+/// Depth check over a flat category list
+///
+/// The parent's level (counted from the root) plus the height of the moved
+/// subtree must not exceed `MAX_CATEGORY_DEPTH`.
+fn check_depth_in(
+    categories: &[Category],
+    category_id: Option<i64>,
+    parent_id: Option<i64>,
+) -> Result<(), CategoryServiceError> {
+    let parents: HashMap<i64, Option<i64>> =
+        categories.iter().map(|c| (c.id, c.parent_id)).collect();
+
+    // Level of the parent: walk up the ancestor chain, guarding against loops
+    let mut level = 0;
+    let mut current = parent_id;
+    while let Some(id) = current {
+        level += 1;
+        if level > categories.len() {
+            return Err(CategoryServiceError::CircularReference);
+        }
+        current = parents.get(&id).copied().flatten();
+    }
+
+    // Height of the subtree rooted at the moved category (1 for a leaf)
+    fn height(id: i64, categories: &[Category], budget: usize) -> usize {
+        if budget == 0 {
+            return 1;
+        }
+        1 + categories
+            .iter()
+            .filter(|c| c.parent_id == Some(id))
+            .map(|c| height(c.id, categories, budget - 1))
+            .max()
+            .unwrap_or(0)
+    }
+    let subtree = category_id.map_or(1, |id| height(id, categories, MAX_CATEGORY_DEPTH));
+
+    if level + subtree > MAX_CATEGORY_DEPTH {
+        return Err(CategoryServiceError::TooDeep(MAX_CATEGORY_DEPTH));
+    }
+
+    Ok(())
+}
+
 /// Input for creating a new category
 #[derive(Debug, Clone)]
 pub struct CreateCategoryInput {
@@ -648,6 +859,8 @@ pub struct CreateCategoryInput {
     pub parent_id: Option<i64>,
     /// Sort order within parent (optional, defaults to 0)
     pub sort_order: Option<i32>,
+    /// Who may read the articles (optional, defaults to public)
+    pub access: Option<CategoryAccess>,
 }
 
 impl CreateCategoryInput {
@@ -659,6 +872,7 @@ impl CreateCategoryInput {
             description: None,
             parent_id: None,
             sort_order: None,
+            access: None,
         }
     }
 
@@ -685,6 +899,12 @@ impl CreateCategoryInput {
         self.sort_order = Some(sort_order);
         self
     }
+
+    /// Set who may read the articles
+    pub fn with_access(mut self, access: CategoryAccess) -> Self {
+        self.access = Some(access);
+        self
+    }
 }
 
 /// Input for updating a category
@@ -700,6 +920,8 @@ pub struct UpdateCategoryInput {
     pub parent_id: Option<Option<i64>>,
     /// New sort order (optional)
     pub sort_order: Option<i32>,
+    /// New access level (optional)
+    pub access: Option<CategoryAccess>,
 }
 
 impl UpdateCategoryInput {
@@ -737,6 +959,12 @@ impl UpdateCategoryInput {
         self.sort_order = Some(sort_order);
         self
     }
+
+    /// Set who may read the articles
+    pub fn with_access(mut self, access: CategoryAccess) -> Self {
+        self.access = Some(access);
+        self
+    }
 }
 
 /// Generate a URL-friendly slug from a name
@@ -1312,6 +1540,305 @@ mod tests {
         assert_eq!(updated_child.parent_id, Some(grandparent.id));
     }
 
+    /// Insert an article filed under `category_id`
+    async fn create_test_article(pool: &DynDatabasePool, category_id: i64, slug: &str) -> i64 {
+        let pool = pool.as_sqlite().expect("Expected SQLite pool");
+        let author_id = sqlx::query(
+            "INSERT INTO users (username, email, password_hash, role) VALUES (?, ?, ?, 'author')",
+        )
+        .bind(slug)
+        .bind(format!("{}@example.com", slug))
+        .bind("hash")
+        .execute(pool)
+        .await
+        .expect("Failed to create user")
+        .last_insert_rowid();
+        sqlx::query(
+            r#"INSERT INTO articles (slug, title, content, content_html, author_id, category_id, status)
+               VALUES (?, ?, '', '', ?, ?, 'published')"#,
+        )
+        .bind(slug)
+        .bind(slug)
+        .bind(author_id)
+        .bind(category_id)
+        .execute(pool)
+        .await
+        .expect("Failed to create article")
+        .last_insert_rowid()
+    }
+
+    #[tokio::test]
+    async fn test_delete_into_moves_only_own_articles() {
+        let (pool, service) = setup_test_service().await;
+
+        let parent = service
+            .create(CreateCategoryInput::new("Parent"))
+            .await
+            .expect("Failed to create parent");
+        let child = service
+            .create(CreateCategoryInput::new("Child").with_parent(parent.id))
+            .await
+            .expect("Failed to create child");
+        let target = service
+            .create(CreateCategoryInput::new("Target"))
+            .await
+            .expect("Failed to create target");
+        let own = create_test_article(&pool, parent.id, "own").await;
+        let nested = create_test_article(&pool, child.id, "nested").await;
+
+        service
+            .delete_into(parent.id, Some(target.id))
+            .await
+            .expect("Failed to delete parent");
+
+        assert_eq!(service.article_ids(target.id).await.unwrap(), vec![own]);
+        assert_eq!(service.article_ids(child.id).await.unwrap(), vec![nested]);
+    }
+
+    #[tokio::test]
+    async fn test_delete_into_self_fails() {
+        let (_pool, service) = setup_test_service().await;
+
+        let category = service
+            .create(CreateCategoryInput::new("Self"))
+            .await
+            .expect("Failed to create category");
+
+        let result = service.delete_into(category.id, Some(category.id)).await;
+        assert!(matches!(
+            result,
+            Err(CategoryServiceError::ValidationError(_))
+        ));
+        let result = service.delete_into(category.id, Some(99999)).await;
+        assert!(matches!(result, Err(CategoryServiceError::NotFound(_))));
+    }
+
+    #[tokio::test]
+    async fn test_delete_moves_own_articles_to_default() {
+        let (pool, service) = setup_test_service().await;
+
+        let parent = service
+            .create(CreateCategoryInput::new("Parent"))
+            .await
+            .expect("Failed to create parent");
+        let child = service
+            .create(CreateCategoryInput::new("Child").with_parent(parent.id))
+            .await
+            .expect("Failed to create child");
+        let own = create_test_article(&pool, parent.id, "own").await;
+        let nested = create_test_article(&pool, child.id, "nested").await;
+
+        service
+            .delete(parent.id)
+            .await
+            .expect("Failed to delete parent");
+
+        let default = service
+            .get_default()
+            .await
+            .expect("Failed to get default")
+            .expect("Default category not found");
+        assert_eq!(service.article_ids(default.id).await.unwrap(), vec![own]);
+        assert_eq!(service.article_ids(child.id).await.unwrap(), vec![nested]);
+        let child = service
+            .get_by_id(child.id)
+            .await
+            .expect("Failed to get child")
+            .expect("Child not found");
+        assert_eq!(child.parent_id, None);
+    }
+
+    #[tokio::test]
+    async fn test_delete_with_articles() {
+        let (pool, service) = setup_test_service().await;
+
+        let parent = service
+            .create(CreateCategoryInput::new("Parent"))
+            .await
+            .expect("Failed to create parent");
+        let child = service
+            .create(CreateCategoryInput::new("Child").with_parent(parent.id))
+            .await
+            .expect("Failed to create child");
+        let own = create_test_article(&pool, parent.id, "own").await;
+        let nested = create_test_article(&pool, child.id, "nested").await;
+
+        let deleted = service
+            .delete_with_articles(parent.id)
+            .await
+            .expect("Failed to delete parent");
+
+        assert_eq!(deleted, vec![own]);
+        assert!(service.get_by_id(parent.id).await.unwrap().is_none());
+        assert_eq!(service.article_ids(child.id).await.unwrap(), vec![nested]);
+        let tombstones: i64 = sqlx::query_scalar(
+            "SELECT COUNT(*) FROM sync_tombstones WHERE entity_type = 'article' AND entity_id = ?",
+        )
+        .bind(own)
+        .fetch_one(pool.as_sqlite().unwrap())
+        .await
+        .expect("Failed to count tombstones");
+        assert_eq!(tombstones, 1);
+
+        let default = service
+            .get_default()
+            .await
+            .expect("Failed to get default")
+            .expect("Default category not found");
+        let result = service.delete_with_articles(default.id).await;
+        assert!(matches!(
+            result,
+            Err(CategoryServiceError::CannotDeleteDefault)
+        ));
+    }
+
+    // ========================================================================
+    // Move and reorder tests
+    // ========================================================================
+
+    #[tokio::test]
+    async fn test_move_to_position() {
+        let (_pool, service) = setup_test_service().await;
+
+        let parent = service
+            .create(CreateCategoryInput::new("Parent"))
+            .await
+            .expect("Failed to create parent");
+        let first = service
+            .create(CreateCategoryInput::new("First").with_parent(parent.id))
+            .await
+            .expect("Failed to create first");
+        let second = service
+            .create(CreateCategoryInput::new("Second").with_parent(parent.id))
+            .await
+            .expect("Failed to create second");
+        let moved = service
+            .create(CreateCategoryInput::new("Moved"))
+            .await
+            .expect("Failed to create moved");
+
+        let tree = service
+            .move_to(moved.id, Some(parent.id), Some(1))
+            .await
+            .expect("Failed to move category");
+
+        let parent_tree = tree
+            .iter()
+            .find(|t| t.category.id == parent.id)
+            .expect("Parent not found");
+        let ids: Vec<i64> = parent_tree.children.iter().map(|t| t.category.id).collect();
+        assert_eq!(ids, vec![first.id, moved.id, second.id]);
+        assert!(!tree.iter().any(|t| t.category.id == moved.id));
+    }
+
+    #[tokio::test]
+    async fn test_move_to_rejects_cycle() {
+        let (_pool, service) = setup_test_service().await;
+
+        let parent = service
+            .create(CreateCategoryInput::new("Parent"))
+            .await
+            .expect("Failed to create parent");
+        let child = service
+            .create(CreateCategoryInput::new("Child").with_parent(parent.id))
+            .await
+            .expect("Failed to create child");
+
+        let result = service.move_to(parent.id, Some(child.id), None).await;
+        assert!(matches!(
+            result,
+            Err(CategoryServiceError::CircularReference)
+        ));
+        let result = service.move_to(parent.id, Some(parent.id), None).await;
+        assert!(matches!(
+            result,
+            Err(CategoryServiceError::CircularReference)
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_move_to_rejects_excessive_depth() {
+        let (_pool, service) = setup_test_service().await;
+
+        // A chain exactly MAX_CATEGORY_DEPTH levels deep
+        let mut chain = Vec::new();
+        let mut parent_id = None;
+        for level in 0..MAX_CATEGORY_DEPTH {
+            let mut input = CreateCategoryInput::new(format!("Level {}", level));
+            if let Some(parent_id) = parent_id {
+                input = input.with_parent(parent_id);
+            }
+            let category = service.create(input).await.expect("Failed to create");
+            parent_id = Some(category.id);
+            chain.push(category.id);
+        }
+
+        // One more level is rejected on create
+        let result = service
+            .create(CreateCategoryInput::new("Too deep").with_parent(chain[MAX_CATEGORY_DEPTH - 1]))
+            .await;
+        assert!(matches!(result, Err(CategoryServiceError::TooDeep(_))));
+
+        // Moving a two-level subtree under the second level would need six levels
+        let branch = service
+            .create(CreateCategoryInput::new("Branch"))
+            .await
+            .expect("Failed to create branch");
+        service
+            .create(CreateCategoryInput::new("Leaf").with_parent(branch.id))
+            .await
+            .expect("Failed to create leaf");
+        let result = service.move_to(branch.id, Some(chain[3]), None).await;
+        assert!(matches!(result, Err(CategoryServiceError::TooDeep(_))));
+        service
+            .move_to(branch.id, Some(chain[2]), None)
+            .await
+            .expect("Five levels should be allowed");
+    }
+
+    #[tokio::test]
+    async fn test_reorder_children() {
+        let (_pool, service) = setup_test_service().await;
+
+        let parent = service
+            .create(CreateCategoryInput::new("Parent"))
+            .await
+            .expect("Failed to create parent");
+        let a = service
+            .create(CreateCategoryInput::new("A").with_parent(parent.id))
+            .await
+            .expect("Failed to create a");
+        let b = service
+            .create(CreateCategoryInput::new("B").with_parent(parent.id))
+            .await
+            .expect("Failed to create b");
+
+        let tree = service
+            .reorder(Some(parent.id), &[b.id, a.id])
+            .await
+            .expect("Failed to reorder");
+        let parent_tree = tree
+            .iter()
+            .find(|t| t.category.id == parent.id)
+            .expect("Parent not found");
+        let ids: Vec<i64> = parent_tree.children.iter().map(|t| t.category.id).collect();
+        assert_eq!(ids, vec![b.id, a.id]);
+
+        // Missing or foreign IDs are rejected
+        let result = service.reorder(Some(parent.id), &[b.id]).await;
+        assert!(matches!(
+            result,
+            Err(CategoryServiceError::ValidationError(_))
+        ));
+        let result = service
+            .reorder(Some(parent.id), &[a.id, b.id, parent.id])
+            .await;
+        assert!(matches!(
+            result,
+            Err(CategoryServiceError::ValidationError(_))
+        ));
+    }
+
     // ========================================================================
     // Cache tests
     // ========================================================================
@@ -2,8 +2,13 @@
 
 use crate::cache::{Cache, CacheLayer};
 use crate::db::repositories::{CommentRepository, SettingsRepository};
-use crate::models::{CommentStatus, CommentWithMeta, CreateCommentInput, LikeTargetType};
+use crate::models::{
+    CommentCounts, CommentExportFilter, CommentExportRecord, CommentPreview, CommentSearchFilter,
+    CommentStatus, CommentWithMeta, CreateCommentInput, LikeTargetType,
+};
 use crate::plugin::{hook_names, HookManager};
+use crate::services::captcha::{CaptchaError, CaptchaVerifier};
+use crate::services::ip_reputation::{AbuseSignal, IpReputationStore};
 use anyhow::Result;
 use serde_json::json;
 use std::sync::Arc;
@@ -17,11 +22,21 @@ const MAX_COMMENT_LENGTH: usize = 10_000;
 
 /// Cache key prefixes
 const CACHE_KEY_COMMENT_BY_ARTICLE: &str = "comment:article:";
+const CACHE_KEY_LOAD_TOKEN: &str = "comment:load:";
+
+/// Lifetime of a lazy loading token (10 minutes)
+pub const LOAD_TOKEN_TTL_SECS: u64 = 600;
+
+/// Origins of external sites allowed to embed comment threads, one per line
+/// or comma separated. Embedding is off while empty.
+pub const COMMENT_EMBED_ORIGINS_KEY: &str = "comment_embed_origins";
 
 /// Comment service
 pub struct CommentService {
     repo: Arc<dyn CommentRepository>,
     settings_repo: Option<Arc<dyn SettingsRepository>>,
+    captcha: Option<Arc<CaptchaVerifier>>,
+    reputation: Option<Arc<IpReputationStore>>,
     hook_manager: Option<Arc<HookManager>>,
     cache: Arc<Cache>,
     cache_ttl: Duration,
@@ -32,6 +47,8 @@ impl CommentService {
         Self {
             repo,
             settings_repo: None,
+            captcha: None,
+            reputation: None,
             hook_manager: None,
             cache,
             cache_ttl: Duration::from_secs(COMMENT_CACHE_TTL_SECS),
@@ -46,6 +63,8 @@ impl CommentService {
         Self {
             repo,
             settings_repo: None,
+            captcha: None,
+            reputation: None,
             hook_manager: Some(hook_manager),
             cache,
             cache_ttl: Duration::from_secs(COMMENT_CACHE_TTL_SECS),
@@ -57,6 +76,25 @@ impl CommentService {
         self
     }
 
+    /// Require a Turnstile / hCaptcha token from guest commenters when configured
+    pub fn with_captcha(mut self, captcha: Arc<CaptchaVerifier>) -> Self {
+        self.captcha = Some(captcha);
+        self
+    }
+
+    /// Report spam and failed captchas to the shared IP reputation store
+    pub fn with_reputation(mut self, reputation: Arc<IpReputationStore>) -> Self {
+        self.reputation = Some(reputation);
+        self
+    }
+
+    /// Report an abuse signal for `ip` if a reputation store is attached
+    async fn report_abuse(&self, ip: Option<&str>, signal: AbuseSignal) {
+        if let (Some(reputation), Some(ip)) = (&self.reputation, ip) {
+            reputation.record_str(ip, signal).await;
+        }
+    }
+
     /// Check if login is required to comment
     pub async fn check_require_login(&self) -> Result<bool> {
         if let Some(ref settings_repo) = self.settings_repo {
@@ -78,6 +116,9 @@ impl CommentService {
 
     /// Create a comment
     ///
+    /// Guest comments must carry a valid `captcha_token` when Turnstile or
+    /// hCaptcha is configured (see [`Self::with_captcha`]).
+    ///
     /// # Hooks
     /// - `comment_before_create` - Triggered before creating, can modify input
     /// - `comment_after_create` - Triggered after creating, receives created comment
@@ -87,9 +128,20 @@ impl CommentService {
         user_id: Option<i64>,
         ip: Option<String>,
         user_agent: Option<String>,
+        captcha_token: Option<&str>,
     ) -> Result<crate::models::Comment> {
         validate_comment_content(&input.content)?;
 
+        // Authenticated users bypass the captcha
+        if let (Some(captcha), None) = (&self.captcha, user_id) {
+            let result = captcha.verify(captcha_token, ip.as_deref()).await;
+            if matches!(result, Err(CaptchaError::Required | CaptchaError::Failed)) {
+                self.report_abuse(ip.as_deref(), AbuseSignal::CaptchaFailed)
+                    .await;
+            }
+            result?;
+        }
+
         // Trigger comment_before_create hook
         let hook_data = self.trigger_hook(
             hook_names::COMMENT_BEFORE_CREATE,
@@ -152,6 +204,10 @@ impl CommentService {
             if fs == "pending" || fs == "spam" {
                 status = CommentStatus::Pending;
             }
+            if fs == "spam" {
+                self.report_abuse(ip.as_deref(), AbuseSignal::SpamComment)
+                    .await;
+            }
         }
 
         let comment = self
@@ -168,6 +224,7 @@ impl CommentService {
         // Invalidate cache - CRITICAL: must clear comment cache for this article
         let cache_key = format!("{}{}", CACHE_KEY_COMMENT_BY_ARTICLE, input.article_id);
         let _ = self.cache.delete(&cache_key).await;
+        let _ = self.cache.delete(&counts_cache_key(input.article_id)).await;
 
         // Trigger comment_after_create hook
         self.trigger_hook(
@@ -247,6 +304,56 @@ impl CommentService {
         Ok(comments)
     }
 
+    /// Approved and pending comment counts of an article
+    pub async fn count_by_article(&self, article_id: i64) -> Result<CommentCounts> {
+        let cache_key = counts_cache_key(article_id);
+        if let Ok(Some(counts)) = self.cache.get::<CommentCounts>(&cache_key).await {
+            return Ok(counts);
+        }
+
+        let counts = self.repo.count_by_article(article_id).await?;
+        let _ = self.cache.set(&cache_key, &counts, self.cache_ttl).await;
+
+        Ok(counts)
+    }
+
+    /// First `limit` threads of an article without replies or likes
+    pub async fn list_thread_previews(
+        &self,
+        article_id: i64,
+        limit: i64,
+    ) -> Result<Vec<CommentPreview>> {
+        self.repo.list_thread_previews(article_id, limit).await
+    }
+
+    /// Issue a token for loading the full threads of an article later
+    pub async fn issue_load_token(&self, article_id: i64) -> Result<String> {
+        let mut bytes = [0u8; 16];
+        getrandom::fill(&mut bytes)
+            .map_err(|e| anyhow::anyhow!("Failed to generate load token: {}", e))?;
+        let token = data_encoding::HEXLOWER.encode(&bytes);
+        self.cache
+            .set(
+                &format!("{}{}", CACHE_KEY_LOAD_TOKEN, token),
+                &article_id,
+                Duration::from_secs(LOAD_TOKEN_TTL_SECS),
+            )
+            .await?;
+        Ok(token)
+    }
+
+    /// Whether `token` was issued for `article_id` and has not expired
+    pub async fn check_load_token(&self, token: &str, article_id: i64) -> bool {
+        let valid_format = token.len() == 32 && token.bytes().all(|b| b.is_ascii_hexdigit());
+        valid_format
+            && matches!(
+                self.cache
+                    .get::<i64>(&format!("{}{}", CACHE_KEY_LOAD_TOKEN, token))
+                    .await,
+                Ok(Some(id)) if id == article_id
+            )
+    }
+
     /// Get a comment by ID.
     pub async fn get_by_id(&self, id: i64) -> Result<Option<crate::models::Comment>> {
         self.repo.get_by_id(id).await
@@ -271,6 +378,16 @@ impl CommentService {
         self.repo.list_all(status, page, per_page).await
     }
 
+    /// Search comments by keyword, author email, IP and status (for moderators)
+    pub async fn search(
+        &self,
+        filter: &CommentSearchFilter,
+        page: i64,
+        per_page: i64,
+    ) -> Result<(Vec<crate::models::Comment>, i64)> {
+        self.repo.search(filter, page, per_page).await
+    }
+
     /// Approve a comment
     pub async fn approve(&self, id: i64) -> Result<bool> {
         let result = self.repo.update_status(id, CommentStatus::Approved).await?;
@@ -294,6 +411,14 @@ impl CommentService {
     pub async fn reject(&self, id: i64) -> Result<bool> {
         let result = self.repo.update_status(id, CommentStatus::Spam).await?;
 
+        // A moderator marking spam counts against the commenter's address
+        if result && self.reputation.is_some() {
+            if let Some(comment) = self.repo.get_by_id(id).await? {
+                self.report_abuse(comment.ip_address.as_deref(), AbuseSignal::SpamComment)
+                    .await;
+            }
+        }
+
         // Invalidate all comment caches
         let _ = self
             .cache
@@ -335,6 +460,24 @@ impl CommentService {
         Ok(result)
     }
 
+    /// Fetch a batch of comments for a bulk export (keyset paginated by ID)
+    pub async fn list_for_export(
+        &self,
+        filter: &CommentExportFilter,
+        after_id: i64,
+        limit: i64,
+    ) -> Result<Vec<CommentExportRecord>> {
+        self.repo.list_for_export(filter, after_id, limit).await
+    }
+
+    /// Drop all cached comment lists, e.g. after a bulk import
+    pub async fn invalidate_cache(&self) {
+        let _ = self
+            .cache
+            .delete_pattern(&format!("{}*", CACHE_KEY_COMMENT_BY_ARTICLE))
+            .await;
+    }
+
     /// Like an article or comment
     pub async fn like(
         &self,
@@ -409,6 +552,25 @@ fn validate_comment_content(content: &str) -> Result<()> {
     Ok(())
 }
 
+/// Under the article prefix so the moderation actions drop it with the lists
+fn counts_cache_key(article_id: i64) -> String {
+    format!("{}{}:counts", CACHE_KEY_COMMENT_BY_ARTICLE, article_id)
+}
+
+/// Normalize an origin for comparison: lowercase, no trailing slash
+pub fn normalize_origin(origin: &str) -> String {
+    origin.trim().trim_end_matches('/').to_ascii_lowercase()
+}
+
+/// Parse the embed origin allowlist setting
+pub fn parse_embed_origins(value: &str) -> Vec<String> {
+    value
+        .split([',', '\n'])
+        .map(normalize_origin)
+        .filter(|origin| !origin.is_empty())
+        .collect()
+}
+
 /// Generate fingerprint from IP and User-Agent
 pub fn generate_fingerprint(ip: &str, user_agent: &str) -> String {
     let data = format!("{}:{}", ip, user_agent);
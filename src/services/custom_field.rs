@@ -0,0 +1,206 @@
+//! Custom fields service.
+//!
+//! Validates keys and typed values of the key/value fields attached to
+//! articles and pages, and hands them out as maps for API responses and
+//! theme templates.
+
+use crate::db::repositories::custom_field::StoredField;
+use crate::db::repositories::CustomFieldRepository;
+use crate::models::{
+    custom_field_map, ContentKind, CustomField, CustomFieldMap, CustomFieldType,
+    CustomFieldValueInput, CustomFieldsInput,
+};
+use std::collections::HashMap;
+use std::sync::Arc;
+
+const MAX_FIELDS: usize = 50;
+const MAX_KEY_LEN: usize = 64;
+const MAX_VALUE_LEN: usize = 65_535;
+
+/// Errors returned by the custom field service
+#[derive(Debug, thiserror::Error)]
+pub enum CustomFieldError {
+    #[error("Custom field not found")]
+    NotFound,
+
+    #[error("{0}")]
+    Validation(String),
+
+    #[error("Internal error: {0}")]
+    Internal(#[from] anyhow::Error),
+}
+
+pub struct CustomFieldService {
+    repo: Arc<dyn CustomFieldRepository>,
+}
+
+impl CustomFieldService {
+    pub fn new(repo: Arc<dyn CustomFieldRepository>) -> Self {
+        Self { repo }
+    }
+
+    /// Fields of an article or page with their types, ordered by key
+    pub async fn list(
+        &self,
+        kind: ContentKind,
+        id: i64,
+    ) -> Result<Vec<CustomField>, CustomFieldError> {
+        Ok(self
+            .repo
+            .list_for(kind, &[id])
+            .await?
+            .into_iter()
+            .map(|(_, field)| field)
+            .collect())
+    }
+
+    /// Fields of an article or page as a key/value map
+    pub async fn map(
+        &self,
+        kind: ContentKind,
+        id: i64,
+    ) -> Result<CustomFieldMap, CustomFieldError> {
+        Ok(custom_field_map(self.list(kind, id).await?))
+    }
+
+    /// Field maps of several articles or pages, keyed by id; ids without
+    /// fields are left out
+    pub async fn maps(
+        &self,
+        kind: ContentKind,
+        ids: &[i64],
+    ) -> Result<HashMap<i64, CustomFieldMap>, CustomFieldError> {
+        let mut maps: HashMap<i64, CustomFieldMap> = HashMap::new();
+        for (id, field) in self.repo.list_for(kind, ids).await? {
+            maps.entry(id).or_default().insert(field.key, field.value);
+        }
+        Ok(maps)
+    }
+
+    /// Replace all fields of an article or page
+    pub async fn replace(
+        &self,
+        kind: ContentKind,
+        id: i64,
+        input: CustomFieldsInput,
+    ) -> Result<Vec<CustomField>, CustomFieldError> {
+        if input.fields.len() > MAX_FIELDS {
+            return Err(CustomFieldError::Validation(format!(
+                "At most {} custom fields are allowed",
+                MAX_FIELDS
+            )));
+        }
+        let mut stored: Vec<StoredField> = Vec::with_capacity(input.fields.len());
+        for field in &input.fields {
+            let entry = encode_field(&field.key, field.resolved_type(), &field.value)?;
+            if stored.iter().any(|(key, _, _)| *key == entry.0) {
+                return Err(CustomFieldError::Validation(format!(
+                    "Duplicate custom field: {}",
+                    entry.0
+                )));
+            }
+            stored.push(entry);
+        }
+        self.repo.replace(kind, id, &stored).await?;
+        self.list(kind, id).await
+    }
+
+    /// Add or overwrite one field
+    pub async fn set(
+        &self,
+        kind: ContentKind,
+        id: i64,
+        key: &str,
+        input: CustomFieldValueInput,
+    ) -> Result<Vec<CustomField>, CustomFieldError> {
+        let field_type = input
+            .field_type
+            .unwrap_or_else(|| CustomFieldType::infer(&input.value));
+        let entry = encode_field(key, field_type, &input.value)?;
+        let existing = self.list(kind, id).await?;
+        if existing.len() >= MAX_FIELDS && !existing.iter().any(|f| f.key == entry.0) {
+            return Err(CustomFieldError::Validation(format!(
+                "At most {} custom fields are allowed",
+                MAX_FIELDS
+            )));
+        }
+        self.repo.upsert(kind, id, &entry).await?;
+        self.list(kind, id).await
+    }
+
+    /// Remove one field
+    pub async fn delete(
+        &self,
+        kind: ContentKind,
+        id: i64,
+        key: &str,
+    ) -> Result<(), CustomFieldError> {
+        if self.repo.delete(kind, id, key).await? {
+            Ok(())
+        } else {
+            Err(CustomFieldError::NotFound)
+        }
+    }
+
+    /// Drop the fields of a deleted article or page
+    pub async fn remove_content(&self, kind: ContentKind, id: i64) -> Result<(), CustomFieldError> {
+        Ok(self.repo.remove_content(kind, id).await?)
+    }
+}
+
+/// Check a key and encode its value for storage
+fn encode_field(
+    key: &str,
+    field_type: CustomFieldType,
+    value: &serde_json::Value,
+) -> Result<StoredField, CustomFieldError> {
+    let key = key.trim();
+    if key.is_empty() || key.len() > MAX_KEY_LEN {
+        return Err(CustomFieldError::Validation(format!(
+            "Custom field keys must be 1-{} characters",
+            MAX_KEY_LEN
+        )));
+    }
+    if !key
+        .chars()
+        .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '_' || c == '-')
+    {
+        return Err(CustomFieldError::Validation(format!(
+            "Invalid custom field key '{}': use lowercase letters, digits, '_' and '-'",
+            key
+        )));
+    }
+    let encoded = field_type
+        .encode(value)
+        .map_err(|e| CustomFieldError::Validation(format!("Custom field '{}': {}", key, e)))?;
+    if encoded.len() > MAX_VALUE_LEN {
+        return Err(CustomFieldError::Validation(format!(
+            "Custom field '{}' is too long",
+            key
+        )));
+    }
+    Ok((key.to_string(), field_type, encoded))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn keys_are_slug_like() {
+        assert!(encode_field("reading_time", CustomFieldType::Number, &json!(5)).is_ok());
+        assert!(encode_field("hero-image", CustomFieldType::Text, &json!("a.png")).is_ok());
+        assert!(encode_field("Hero Image", CustomFieldType::Text, &json!("a.png")).is_err());
+        assert!(encode_field("", CustomFieldType::Text, &json!("")).is_err());
+        assert!(encode_field(&"k".repeat(65), CustomFieldType::Text, &json!("")).is_err());
+    }
+
+    #[test]
+    fn values_are_checked_against_their_type() {
+        let (_, _, stored) = encode_field("rating", CustomFieldType::Number, &json!(4)).unwrap();
+        assert_eq!(stored, "4");
+        let err = encode_field("rating", CustomFieldType::Number, &json!("four")).unwrap_err();
+        assert!(err.to_string().contains("rating"));
+    }
+}
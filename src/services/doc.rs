@@ -0,0 +1,510 @@
+//! Documentation service.
+//!
+//! Manages doc versions and their page trees, and assembles what a docs
+//! page needs to render: the sidebar, previous/next links, the version
+//! switcher and the canonical URL. Pages of older versions that still
+//! exist in the latest one are canonicalized to `/docs/latest/{slug}`, so
+//! search engines index the current text while old versions stay readable.
+
+use crate::db::repositories::DocRepository;
+use crate::models::{
+    build_doc_sidebar, DocNode, DocPage, DocPageInput, DocVersion, DocVersionInput,
+    LATEST_DOC_VERSION,
+};
+use crate::services::markdown::MarkdownEngine;
+use crate::services::MarkdownRenderer;
+use chrono::Utc;
+use serde::Serialize;
+use std::collections::HashMap;
+use std::sync::Arc;
+
+const MAX_VERSION_SLUG_LEN: usize = 50;
+const MAX_VERSION_TITLE_LEN: usize = 100;
+const MAX_PAGE_SLUG_LEN: usize = 100;
+const MAX_PAGE_TITLE_LEN: usize = 200;
+
+/// Errors returned by the doc service
+#[derive(Debug, thiserror::Error)]
+pub enum DocError {
+    #[error("{0} not found")]
+    NotFound(&'static str),
+
+    #[error("{0}")]
+    Validation(String),
+
+    #[error("Internal error: {0}")]
+    Internal(#[from] anyhow::Error),
+}
+
+/// An entry of the version switcher
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct DocVersionLink {
+    pub slug: String,
+    pub title: String,
+    pub is_latest: bool,
+    /// The version being viewed
+    pub current: bool,
+    /// The same page in that version, or the version's first page
+    pub url: String,
+}
+
+/// A previous/next link
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct DocLink {
+    pub slug: String,
+    pub title: String,
+    pub url: String,
+}
+
+impl From<&DocNode> for DocLink {
+    fn from(node: &DocNode) -> Self {
+        Self {
+            slug: node.slug.clone(),
+            title: node.title.clone(),
+            url: node.url.clone(),
+        }
+    }
+}
+
+/// Everything a docs page renders
+#[derive(Debug, Clone, Serialize)]
+pub struct DocView {
+    pub version: DocVersion,
+    pub versions: Vec<DocVersionLink>,
+    pub sidebar: Vec<DocNode>,
+    pub page: DocPage,
+    pub prev: Option<DocLink>,
+    pub next: Option<DocLink>,
+    /// Site path search engines should index
+    pub canonical: String,
+}
+
+pub struct DocService {
+    repo: Arc<dyn DocRepository>,
+    markdown: MarkdownRenderer,
+}
+
+impl DocService {
+    pub fn new(repo: Arc<dyn DocRepository>) -> Self {
+        Self {
+            repo,
+            markdown: MarkdownRenderer::new(),
+        }
+    }
+
+    /// Parse doc content with `engine` instead of pulldown-cmark
+    pub fn with_markdown_engine(mut self, engine: Arc<dyn MarkdownEngine>) -> Self {
+        self.markdown.set_engine(engine);
+        self
+    }
+
+    pub async fn list_versions(&self) -> Result<Vec<DocVersion>, DocError> {
+        Ok(self.repo.list_versions().await?)
+    }
+
+    pub async fn get_version(&self, id: i64) -> Result<DocVersion, DocError> {
+        self.repo
+            .get_version(id)
+            .await?
+            .ok_or(DocError::NotFound("Doc version"))
+    }
+
+    /// Create a version, optionally copying another version's pages
+    pub async fn create_version(&self, input: DocVersionInput) -> Result<DocVersion, DocError> {
+        let (slug, title) = normalize_version(&input)?;
+        if self.repo.get_version_by_slug(&slug).await?.is_some() {
+            return Err(DocError::Validation(format!(
+                "Version '{}' already exists",
+                slug
+            )));
+        }
+        let source = match input.copy_from {
+            Some(id) => Some(self.get_version(id).await?),
+            None => None,
+        };
+
+        let now = Utc::now();
+        let version = self
+            .repo
+            .create_version(&DocVersion {
+                id: 0,
+                slug,
+                title,
+                position: input.position,
+                is_latest: input.is_latest,
+                created_at: now,
+                updated_at: now,
+            })
+            .await?;
+
+        if let Some(source) = source {
+            let pages = self.repo.list_pages(source.id).await?;
+            // Parents come before their children in reading order
+            let order = DocNode::flatten(&build_doc_sidebar(&source.slug, &pages))
+                .iter()
+                .map(|node| node.id)
+                .collect::<Vec<_>>();
+            let mut new_ids: HashMap<i64, i64> = HashMap::new();
+            for id in order {
+                let Some(page) = pages.iter().find(|p| p.id == id) else {
+                    continue;
+                };
+                let copy = self
+                    .repo
+                    .create_page(&DocPage {
+                        version_id: version.id,
+                        parent_id: page.parent_id.and_then(|p| new_ids.get(&p).copied()),
+                        ..page.clone()
+                    })
+                    .await?;
+                new_ids.insert(page.id, copy.id);
+            }
+        }
+        Ok(version)
+    }
+
+    /// Replace a version's slug, title, position and latest flag
+    pub async fn update_version(
+        &self,
+        id: i64,
+        input: DocVersionInput,
+    ) -> Result<DocVersion, DocError> {
+        let mut version = self.get_version(id).await?;
+        let (slug, title) = normalize_version(&input)?;
+        if slug != version.slug && self.repo.get_version_by_slug(&slug).await?.is_some() {
+            return Err(DocError::Validation(format!(
+                "Version '{}' already exists",
+                slug
+            )));
+        }
+        version.slug = slug;
+        version.title = title;
+        version.position = input.position;
+        version.is_latest = input.is_latest;
+        Ok(self.repo.update_version(&version).await?)
+    }
+
+    /// Delete a version and all its pages
+    pub async fn delete_version(&self, id: i64) -> Result<(), DocError> {
+        if !self.repo.delete_version(id).await? {
+            return Err(DocError::NotFound("Doc version"));
+        }
+        Ok(())
+    }
+
+    /// A version's pages, by position
+    pub async fn list_pages(&self, version_id: i64) -> Result<Vec<DocPage>, DocError> {
+        self.get_version(version_id).await?;
+        Ok(self.repo.list_pages(version_id).await?)
+    }
+
+    pub async fn get_page(&self, id: i64) -> Result<DocPage, DocError> {
+        self.repo
+            .get_page(id)
+            .await?
+            .ok_or(DocError::NotFound("Doc page"))
+    }
+
+    pub async fn create_page(&self, input: DocPageInput) -> Result<DocPage, DocError> {
+        self.get_version(input.version_id).await?;
+        let page = self.page_from_input(0, input).await?;
+        Ok(self.repo.create_page(&page).await?)
+    }
+
+    /// Replace a page; pages stay in the version they were created in
+    pub async fn update_page(&self, id: i64, input: DocPageInput) -> Result<DocPage, DocError> {
+        let existing = self.get_page(id).await?;
+        if input.version_id != existing.version_id {
+            return Err(DocError::Validation(
+                "Pages cannot move between versions".to_string(),
+            ));
+        }
+        let page = self.page_from_input(id, input).await?;
+        Ok(self
+            .repo
+            .update_page(&DocPage {
+                created_at: existing.created_at,
+                ..page
+            })
+            .await?)
+    }
+
+    /// Delete a page; its children move up a level
+    pub async fn delete_page(&self, id: i64) -> Result<(), DocError> {
+        if !self.repo.delete_page(id).await? {
+            return Err(DocError::NotFound("Doc page"));
+        }
+        Ok(())
+    }
+
+    /// A page with its navigation. `version_slug` may be `latest`; without
+    /// `page_slug` the version's first page is shown.
+    pub async fn view(
+        &self,
+        version_slug: &str,
+        page_slug: Option<&str>,
+    ) -> Result<DocView, DocError> {
+        let versions = self.repo.list_versions().await?;
+        let version = resolve_version(&versions, version_slug)
+            .ok_or(DocError::NotFound("Doc version"))?
+            .clone();
+        let pages = self.repo.list_pages(version.id).await?;
+        let sidebar = build_doc_sidebar(version_slug, &pages);
+        let reading_order = DocNode::flatten(&sidebar);
+
+        let page = match page_slug {
+            Some(slug) => pages.iter().find(|p| p.slug == slug),
+            None => reading_order
+                .first()
+                .and_then(|node| pages.iter().find(|p| p.id == node.id)),
+        }
+        .cloned()
+        .ok_or(DocError::NotFound("Doc page"))?;
+        let position = reading_order.iter().position(|node| node.id == page.id);
+        let prev = position
+            .and_then(|i| i.checked_sub(1))
+            .map(|i| DocLink::from(reading_order[i]));
+        let next = position
+            .and_then(|i| reading_order.get(i + 1))
+            .map(|node| DocLink::from(*node));
+
+        let with_page = self.repo.versions_with_page(&page.slug).await?;
+        Ok(DocView {
+            versions: version_links(&versions, version.id, &page.slug, &with_page),
+            canonical: canonical_path(&versions, &version, &page.slug, &with_page),
+            version,
+            prev,
+            next,
+            sidebar,
+            page,
+        })
+    }
+
+    async fn page_from_input(&self, id: i64, input: DocPageInput) -> Result<DocPage, DocError> {
+        let slug = normalize_slug(&input.slug, MAX_PAGE_SLUG_LEN, "Page")?;
+        let title = normalize_title(&input.title, MAX_PAGE_TITLE_LEN, "Page")?;
+        if let Some(other) = self.repo.get_page_by_slug(input.version_id, &slug).await? {
+            if other.id != id {
+                return Err(DocError::Validation(format!(
+                    "Page '{}' already exists in this version",
+                    slug
+                )));
+            }
+        }
+
+        if let Some(parent_id) = input.parent_id {
+            let pages = self.repo.list_pages(input.version_id).await?;
+            if !pages.iter().any(|p| p.id == parent_id) {
+                return Err(DocError::Validation(
+                    "Parent page must be in the same version".to_string(),
+                ));
+            }
+            // Walk up from the new parent; meeting this page means a loop
+            let mut ancestor = Some(parent_id);
+            while let Some(current) = ancestor {
+                if current == id {
+                    return Err(DocError::Validation(
+                        "A page cannot be nested under itself".to_string(),
+                    ));
+                }
+                ancestor = pages
+                    .iter()
+                    .find(|p| p.id == current)
+                    .and_then(|p| p.parent_id);
+            }
+        }
+
+        let now = Utc::now();
+        Ok(DocPage {
+            id,
+            version_id: input.version_id,
+            parent_id: input.parent_id,
+            slug,
+            title,
+            content_html: self.markdown.render(&input.content),
+            content: input.content,
+            position: input.position,
+            created_at: now,
+            updated_at: now,
+        })
+    }
+}
+
+fn normalize_version(input: &DocVersionInput) -> Result<(String, String), DocError> {
+    let slug = normalize_slug(&input.slug, MAX_VERSION_SLUG_LEN, "Version")?;
+    if slug == LATEST_DOC_VERSION {
+        return Err(DocError::Validation(format!(
+            "'{}' is reserved for the latest version",
+            LATEST_DOC_VERSION
+        )));
+    }
+    let title = normalize_title(&input.title, MAX_VERSION_TITLE_LEN, "Version")?;
+    Ok((slug, title))
+}
+
+fn normalize_slug(slug: &str, max_len: usize, what: &str) -> Result<String, DocError> {
+    let slug = slug.trim().to_lowercase();
+    if slug.is_empty() {
+        return Err(DocError::Validation(format!(
+            "{} slug cannot be empty",
+            what
+        )));
+    }
+    if slug.len() > max_len {
+        return Err(DocError::Validation(format!(
+            "{} slug cannot exceed {} characters",
+            what, max_len
+        )));
+    }
+    let valid = slug
+        .chars()
+        .all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.'))
+        && !slug.starts_with('.');
+    if !valid {
+        return Err(DocError::Validation(format!(
+            "{} slug may only contain letters, digits, '-', '_' and '.'",
+            what
+        )));
+    }
+    Ok(slug)
+}
+
+fn normalize_title(title: &str, max_len: usize, what: &str) -> Result<String, DocError> {
+    let title = title.trim();
+    if title.is_empty() {
+        return Err(DocError::Validation(format!(
+            "{} title cannot be empty",
+            what
+        )));
+    }
+    if title.chars().count() > max_len {
+        return Err(DocError::Validation(format!(
+            "{} title cannot exceed {} characters",
+            what, max_len
+        )));
+    }
+    Ok(title.to_string())
+}
+
+/// The version a URL segment names; `latest` falls back to the last
+/// version when none is marked
+pub fn resolve_version<'a>(versions: &'a [DocVersion], slug: &str) -> Option<&'a DocVersion> {
+    if slug == LATEST_DOC_VERSION {
+        versions
+            .iter()
+            .find(|v| v.is_latest)
+            .or_else(|| versions.last())
+    } else {
+        versions.iter().find(|v| v.slug == slug)
+    }
+}
+
+/// Version switcher entries for a page; `with_page` lists the versions
+/// that have a page with the same slug
+pub fn version_links(
+    versions: &[DocVersion],
+    current_id: i64,
+    page_slug: &str,
+    with_page: &[i64],
+) -> Vec<DocVersionLink> {
+    versions
+        .iter()
+        .map(|version| DocVersionLink {
+            slug: version.slug.clone(),
+            title: version.title.clone(),
+            is_latest: version.is_latest,
+            current: version.id == current_id,
+            url: if with_page.contains(&version.id) {
+                format!("/docs/{}/{}", version.slug, page_slug)
+            } else {
+                format!("/docs/{}", version.slug)
+            },
+        })
+        .collect()
+}
+
+/// `/docs/latest/{slug}` while the latest version has the page, otherwise
+/// the page's own path
+pub fn canonical_path(
+    versions: &[DocVersion],
+    version: &DocVersion,
+    page_slug: &str,
+    with_page: &[i64],
+) -> String {
+    match resolve_version(versions, LATEST_DOC_VERSION) {
+        Some(latest) if with_page.contains(&latest.id) => {
+            format!("/docs/{}/{}", LATEST_DOC_VERSION, page_slug)
+        }
+        _ => format!("/docs/{}/{}", version.slug, page_slug),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn version(id: i64, slug: &str, is_latest: bool) -> DocVersion {
+        let now = Utc::now();
+        DocVersion {
+            id,
+            slug: slug.to_string(),
+            title: slug.to_uppercase(),
+            position: id as i32,
+            is_latest,
+            created_at: now,
+            updated_at: now,
+        }
+    }
+
+    #[test]
+    fn latest_resolves_to_the_marked_version() {
+        let versions = [
+            version(1, "v1", false),
+            version(2, "v2", true),
+            version(3, "v3-beta", false),
+        ];
+        assert_eq!(resolve_version(&versions, "latest").unwrap().id, 2);
+        assert_eq!(resolve_version(&versions, "v3-beta").unwrap().id, 3);
+        assert!(resolve_version(&versions, "v4").is_none());
+
+        let unmarked = [version(1, "v1", false), version(2, "v2", false)];
+        assert_eq!(resolve_version(&unmarked, "latest").unwrap().id, 2);
+    }
+
+    #[test]
+    fn switcher_and_canonical_follow_the_page_across_versions() {
+        let versions = [version(1, "v1", false), version(2, "v2", true)];
+
+        // "install" exists in both versions
+        let links = version_links(&versions, 1, "install", &[1, 2]);
+        assert_eq!(links[1].url, "/docs/v2/install");
+        assert!(links[0].current && !links[1].current);
+        assert_eq!(
+            canonical_path(&versions, &versions[0], "install", &[1, 2]),
+            "/docs/latest/install"
+        );
+
+        // "legacy-api" was dropped in v2
+        let links = version_links(&versions, 1, "legacy-api", &[1]);
+        assert_eq!(links[1].url, "/docs/v2");
+        assert_eq!(
+            canonical_path(&versions, &versions[0], "legacy-api", &[1]),
+            "/docs/v1/legacy-api"
+        );
+    }
+
+    #[test]
+    fn rejects_reserved_and_unsafe_slugs() {
+        let input = |slug: &str| DocVersionInput {
+            slug: slug.to_string(),
+            title: "Docs".to_string(),
+            position: 0,
+            is_latest: false,
+            copy_from: None,
+        };
+        assert_eq!(normalize_version(&input(" V2.1 ")).unwrap().0, "v2.1");
+        assert!(normalize_version(&input("latest")).is_err());
+        assert!(normalize_version(&input("../v1")).is_err());
+        assert!(normalize_version(&input("v 1")).is_err());
+    }
+}
@@ -0,0 +1,299 @@
+//! Near-duplicate article detection
+//!
+//! Every article (drafts included) is cut into shingles of
+//! [`SHINGLE_TOKENS`] consecutive words, or characters for Chinese and
+//! Japanese text, and summarised by a MinHash signature. Articles whose
+//! signatures agree on enough positions are reported as a pair with their
+//! estimated Jaccard similarity. Locality-sensitive hashing over bands of
+//! the signature keeps the scan from comparing every pair of articles.
+//!
+//! The scan runs as a background job, on demand from
+//! `/api/v1/admin/duplicates/scan` and once a day. Only the latest report
+//! is kept, in memory.
+
+use std::collections::hash_map::DefaultHasher;
+use std::collections::{HashMap, HashSet};
+use std::hash::{Hash, Hasher};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use chrono::{DateTime, Utc};
+use once_cell::sync::Lazy;
+use regex::Regex;
+use serde::Serialize;
+
+use crate::db::repositories::ArticleRepository;
+use crate::models::{Article, ArticleSortBy, ArticleStatus};
+
+/// Tokens per shingle
+const SHINGLE_TOKENS: usize = 5;
+
+/// Hash functions in a signature
+const SIGNATURE_LEN: usize = 128;
+
+/// Signature positions per LSH band; 32 bands of 4 find pairs above 0.5
+/// similarity with high probability
+const BAND_ROWS: usize = 4;
+
+/// Articles loaded per query while scanning
+const SCAN_BATCH: i64 = 200;
+
+/// Pairs kept in a report, most similar first
+const MAX_PAIRS: usize = 500;
+
+/// Similarity a pair needs to be reported when the scan does not say
+pub const DEFAULT_MIN_SIMILARITY: f64 = 0.7;
+
+/// Lowest similarity a scan may ask for; below it LSH misses too many pairs
+pub const MIN_SIMILARITY_FLOOR: f64 = 0.5;
+
+/// How often the scheduled scan runs
+pub const SCAN_INTERVAL: Duration = Duration::from_secs(24 * 3600);
+
+static TAG_RE: Lazy<Regex> = Lazy::new(|| Regex::new(r"(?s)<[^>]*>").unwrap());
+
+/// A Chinese or Japanese character, or a run of other letters and digits
+static TOKEN_RE: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(
+        r"[\p{Han}\p{Hiragana}\p{Katakana}]|[[\p{L}\p{N}]&&[^\p{Han}\p{Hiragana}\p{Katakana}]]+",
+    )
+    .unwrap()
+});
+
+/// An article in a duplicate pair
+#[derive(Debug, Clone, Serialize)]
+pub struct DuplicateArticle {
+    pub id: i64,
+    pub slug: String,
+    pub title: String,
+    pub author_id: i64,
+    pub status: ArticleStatus,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl From<&Article> for DuplicateArticle {
+    fn from(article: &Article) -> Self {
+        Self {
+            id: article.id,
+            slug: article.slug.clone(),
+            title: article.title.clone(),
+            author_id: article.author_id,
+            status: article.status,
+            updated_at: article.updated_at,
+        }
+    }
+}
+
+/// Two articles with much of their text in common
+#[derive(Debug, Clone, Serialize)]
+pub struct DuplicatePair {
+    /// The older of the two
+    pub first: DuplicateArticle,
+    pub second: DuplicateArticle,
+    /// Estimated share of shingles the two have in common, 0 to 1
+    pub similarity: f64,
+}
+
+/// Result of a duplicate scan
+#[derive(Debug, Clone, Serialize)]
+pub struct DuplicateReport {
+    pub generated_at: DateTime<Utc>,
+    pub articles_scanned: usize,
+    /// Articles too short to compare
+    pub articles_skipped: usize,
+    pub min_similarity: f64,
+    /// Most similar first
+    pub pairs: Vec<DuplicatePair>,
+}
+
+/// MinHash signature of an article's shingles
+type Signature = [u64; SIGNATURE_LEN];
+
+/// Finds near-duplicate articles and keeps the latest report
+pub struct DuplicateService {
+    repo: Arc<dyn ArticleRepository>,
+    report: Mutex<Option<DuplicateReport>>,
+}
+
+impl DuplicateService {
+    pub fn new(repo: Arc<dyn ArticleRepository>) -> Self {
+        Self {
+            repo,
+            report: Mutex::new(None),
+        }
+    }
+
+    /// The latest report, if a scan has finished since startup
+    pub fn report(&self) -> Option<DuplicateReport> {
+        self.report.lock().unwrap().clone()
+    }
+
+    /// Compare all articles and keep the report
+    pub async fn scan(&self, min_similarity: f64) -> anyhow::Result<DuplicateReport> {
+        let mut articles = Vec::new();
+        let mut signatures = Vec::new();
+        let mut skipped = 0;
+        let mut offset = 0;
+        loop {
+            let batch = self
+                .repo
+                .list(offset, SCAN_BATCH, ArticleSortBy::default())
+                .await?;
+            let done = (batch.len() as i64) < SCAN_BATCH;
+            offset += batch.len() as i64;
+            for article in &batch {
+                match signature(&shingles(&article.content_html)) {
+                    Some(sig) => {
+                        articles.push(DuplicateArticle::from(article));
+                        signatures.push(sig);
+                    }
+                    None => skipped += 1,
+                }
+            }
+            if done {
+                break;
+            }
+        }
+
+        let mut pairs: Vec<DuplicatePair> = similar_pairs(&signatures, min_similarity)
+            .into_iter()
+            .map(|(a, b, similarity)| {
+                let (first, second) = if articles[a].id < articles[b].id {
+                    (a, b)
+                } else {
+                    (b, a)
+                };
+                DuplicatePair {
+                    first: articles[first].clone(),
+                    second: articles[second].clone(),
+                    similarity,
+                }
+            })
+            .collect();
+        pairs.sort_by(|a, b| {
+            b.similarity
+                .total_cmp(&a.similarity)
+                .then(a.first.id.cmp(&b.first.id))
+                .then(a.second.id.cmp(&b.second.id))
+        });
+        pairs.truncate(MAX_PAIRS);
+
+        let report = DuplicateReport {
+            generated_at: Utc::now(),
+            articles_scanned: articles.len() + skipped,
+            articles_skipped: skipped,
+            min_similarity,
+            pairs,
+        };
+        *self.report.lock().unwrap() = Some(report.clone());
+        Ok(report)
+    }
+}
+
+/// Hashed shingles of the text of `html`
+fn shingles(html: &str) -> HashSet<u64> {
+    let text = TAG_RE.replace_all(html, " ").to_lowercase();
+    let tokens: Vec<&str> = TOKEN_RE.find_iter(&text).map(|m| m.as_str()).collect();
+    tokens
+        .windows(SHINGLE_TOKENS)
+        .map(|window| {
+            let mut hasher = DefaultHasher::new();
+            window.hash(&mut hasher);
+            hasher.finish()
+        })
+        .collect()
+}
+
+/// SplitMix64 finaliser, used to derive the hash functions
+fn mix(mut x: u64) -> u64 {
+    x = x.wrapping_add(0x9e37_79b9_7f4a_7c15);
+    x = (x ^ (x >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+    x = (x ^ (x >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+    x ^ (x >> 31)
+}
+
+/// MinHash signature; `None` for text shorter than one shingle
+fn signature(shingles: &HashSet<u64>) -> Option<Signature> {
+    if shingles.is_empty() {
+        return None;
+    }
+    let mut sig = [u64::MAX; SIGNATURE_LEN];
+    for &shingle in shingles {
+        for (i, min) in sig.iter_mut().enumerate() {
+            *min = (*min).min(mix(shingle ^ mix(i as u64)));
+        }
+    }
+    Some(sig)
+}
+
+/// Share of positions where two signatures agree
+fn estimate_similarity(a: &Signature, b: &Signature) -> f64 {
+    let same = a.iter().zip(b).filter(|(x, y)| x == y).count();
+    same as f64 / SIGNATURE_LEN as f64
+}
+
+/// Index pairs with an estimated similarity of at least `min_similarity`
+fn similar_pairs(signatures: &[Signature], min_similarity: f64) -> Vec<(usize, usize, f64)> {
+    let mut candidates = HashSet::new();
+    for start in (0..SIGNATURE_LEN).step_by(BAND_ROWS) {
+        let mut buckets: HashMap<&[u64], Vec<usize>> = HashMap::new();
+        for (i, sig) in signatures.iter().enumerate() {
+            buckets
+                .entry(&sig[start..start + BAND_ROWS])
+                .or_default()
+                .push(i);
+        }
+        for bucket in buckets.values().filter(|b| b.len() > 1) {
+            for (n, &a) in bucket.iter().enumerate() {
+                for &b in &bucket[n + 1..] {
+                    candidates.insert((a, b));
+                }
+            }
+        }
+    }
+    candidates
+        .into_iter()
+        .map(|(a, b)| (a, b, estimate_similarity(&signatures[a], &signatures[b])))
+        .filter(|(_, _, similarity)| *similarity >= min_similarity)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sig(html: &str) -> Signature {
+        signature(&shingles(html)).unwrap()
+    }
+
+    const ORIGINAL: &str = "<p>The quick brown fox jumps over the lazy dog while the farmer \
+        watches from the porch and the cat sleeps in the warm afternoon sun near the old barn \
+        at the end of the long gravel road.</p>";
+
+    #[test]
+    fn near_copies_are_similar_and_unrelated_text_is_not() {
+        let copy = ORIGINAL.replace("<p>", "<p><strong>Updated:</strong> ");
+        let other = "<p>Rust makes systems programming approachable with ownership, borrowing \
+            and lifetimes checked at compile time so memory bugs are caught before the program \
+            ever runs on a real machine.</p>";
+        let pairs = similar_pairs(&[sig(ORIGINAL), sig(other), sig(&copy)], 0.7);
+        assert_eq!(pairs.len(), 1);
+        let (a, b, similarity) = pairs[0];
+        assert_eq!((a.min(b), a.max(b)), (0, 2));
+        assert!(similarity > 0.8, "{}", similarity);
+    }
+
+    #[test]
+    fn chinese_text_is_shingled_by_character() {
+        let text = "<p>今天天气很好，我们去公园散步，看到很多人在放风筝。</p>";
+        assert!(!shingles(text).is_empty());
+        let edited = "<p>今天天气很好，我们去公园散步，看到很多孩子在放风筝。</p>";
+        assert!(estimate_similarity(&sig(text), &sig(edited)) > 0.5);
+    }
+
+    #[test]
+    fn short_articles_have_no_signature() {
+        assert!(signature(&shingles("<p>Too short</p>")).is_none());
+        assert!(signature(&shingles("")).is_none());
+    }
+}
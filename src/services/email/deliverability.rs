@@ -0,0 +1,293 @@
+//! Email deliverability checks
+//!
+//! Looks up the SPF, DMARC and DKIM records of the sending domain so admins
+//! can see why mail from their instance lands in spam.
+
+use hickory_resolver::error::ResolveErrorKind;
+use hickory_resolver::TokioAsyncResolver;
+use serde::Serialize;
+
+/// Outcome of a single DNS check
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CheckStatus {
+    Pass,
+    /// Present but weaker than recommended
+    Warning,
+    Missing,
+    Invalid,
+    /// The lookup itself failed
+    Error,
+    Skipped,
+}
+
+/// Result of looking up one record
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct DnsCheck {
+    /// Name that was queried
+    pub name: String,
+    pub status: CheckStatus,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub record: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub message: Option<String>,
+}
+
+impl DnsCheck {
+    fn new(name: &str, status: CheckStatus, record: Option<&str>, message: Option<&str>) -> Self {
+        Self {
+            name: name.to_string(),
+            status,
+            record: record.map(str::to_string),
+            message: message.map(str::to_string),
+        }
+    }
+}
+
+/// SPF, DMARC and DKIM checks for a sending domain
+#[derive(Debug, Clone, Serialize)]
+pub struct DomainReport {
+    pub domain: String,
+    pub spf: DnsCheck,
+    pub dmarc: DnsCheck,
+    pub dkim: DnsCheck,
+}
+
+/// Domain part of an email address
+pub fn sending_domain(address: &str) -> Option<&str> {
+    address
+        .rsplit_once('@')
+        .map(|(_, domain)| domain.trim().trim_end_matches('>'))
+        .filter(|domain| !domain.is_empty())
+}
+
+/// TXT records at `name`; a name without records yields an empty list
+async fn txt_records(resolver: &TokioAsyncResolver, name: &str) -> Result<Vec<String>, String> {
+    match resolver.txt_lookup(name).await {
+        Ok(lookup) => Ok(lookup
+            .iter()
+            .map(|txt| {
+                txt.iter()
+                    .map(|chunk| String::from_utf8_lossy(chunk))
+                    .collect::<String>()
+            })
+            .collect()),
+        Err(e) if matches!(e.kind(), ResolveErrorKind::NoRecordsFound { .. }) => Ok(Vec::new()),
+        Err(e) => Err(e.to_string()),
+    }
+}
+
+/// Records starting with `prefix` (case-insensitive)
+fn with_prefix<'a>(records: &'a [String], prefix: &str) -> Vec<&'a str> {
+    records
+        .iter()
+        .map(|r| r.trim())
+        .filter(|r| {
+            r.get(..prefix.len())
+                .is_some_and(|p| p.eq_ignore_ascii_case(prefix))
+        })
+        .collect()
+}
+
+/// Value of a `tag=value` entry in a DMARC or DKIM record
+fn tag_value<'a>(record: &'a str, tag: &str) -> Option<&'a str> {
+    record.split(';').find_map(|part| {
+        let (key, value) = part.split_once('=')?;
+        key.trim().eq_ignore_ascii_case(tag).then_some(value.trim())
+    })
+}
+
+pub fn evaluate_spf(name: &str, records: &[String]) -> DnsCheck {
+    match with_prefix(records, "v=spf1").as_slice() {
+        [] => DnsCheck::new(
+            name,
+            CheckStatus::Missing,
+            None,
+            Some("No SPF record found"),
+        ),
+        [record] if record.ends_with("+all") => DnsCheck::new(
+            name,
+            CheckStatus::Warning,
+            Some(record),
+            Some("+all allows any server to send for this domain"),
+        ),
+        [record] => DnsCheck::new(name, CheckStatus::Pass, Some(record), None),
+        [first, ..] => DnsCheck::new(
+            name,
+            CheckStatus::Invalid,
+            Some(first),
+            Some("Multiple SPF records; receivers treat this as an error"),
+        ),
+    }
+}
+
+pub fn evaluate_dmarc(name: &str, records: &[String]) -> DnsCheck {
+    let records = with_prefix(records, "v=DMARC1");
+    let Some(record) = records.first() else {
+        return DnsCheck::new(
+            name,
+            CheckStatus::Missing,
+            None,
+            Some("No DMARC record found"),
+        );
+    };
+    match tag_value(record, "p")
+        .map(str::to_ascii_lowercase)
+        .as_deref()
+    {
+        Some("quarantine" | "reject") => DnsCheck::new(name, CheckStatus::Pass, Some(record), None),
+        Some("none") => DnsCheck::new(
+            name,
+            CheckStatus::Warning,
+            Some(record),
+            Some("Policy is p=none; failing mail is only reported"),
+        ),
+        _ => DnsCheck::new(
+            name,
+            CheckStatus::Invalid,
+            Some(record),
+            Some("Missing or unknown p= policy"),
+        ),
+    }
+}
+
+pub fn evaluate_dkim(name: &str, records: &[String]) -> DnsCheck {
+    let Some(record) = records
+        .iter()
+        .map(|r| r.trim())
+        .find(|r| tag_value(r, "p").is_some())
+    else {
+        return DnsCheck::new(
+            name,
+            CheckStatus::Missing,
+            None,
+            Some("No DKIM key found for this selector"),
+        );
+    };
+    match tag_value(record, "p") {
+        Some("") => DnsCheck::new(
+            name,
+            CheckStatus::Invalid,
+            Some(record),
+            Some("The key has been revoked (empty p=)"),
+        ),
+        _ => DnsCheck::new(name, CheckStatus::Pass, Some(record), None),
+    }
+}
+
+/// Look up SPF, DMARC and (when a selector is known) DKIM for `domain`
+pub async fn check_domain(domain: &str, dkim_selector: Option<&str>) -> DomainReport {
+    let dmarc_name = format!("_dmarc.{}", domain);
+    let dkim_name = dkim_selector.map(|s| format!("{}._domainkey.{}", s, domain));
+
+    let resolver = match TokioAsyncResolver::tokio_from_system_conf() {
+        Ok(resolver) => resolver,
+        Err(e) => {
+            let message = format!("DNS resolver unavailable: {}", e);
+            let failed =
+                |name: &str| DnsCheck::new(name, CheckStatus::Error, None, Some(message.as_str()));
+            return DomainReport {
+                domain: domain.to_string(),
+                spf: failed(domain),
+                dmarc: failed(&dmarc_name),
+                dkim: failed(dkim_name.as_deref().unwrap_or_default()),
+            };
+        }
+    };
+
+    let lookup = |name: String, evaluate: fn(&str, &[String]) -> DnsCheck| {
+        let resolver = &resolver;
+        async move {
+            match txt_records(resolver, &name).await {
+                Ok(records) => evaluate(&name, &records),
+                Err(e) => DnsCheck::new(&name, CheckStatus::Error, None, Some(&e)),
+            }
+        }
+    };
+    let dkim = async {
+        match dkim_name {
+            Some(name) => lookup(name, evaluate_dkim).await,
+            None => DnsCheck::new(
+                "",
+                CheckStatus::Skipped,
+                None,
+                Some("No DKIM selector configured"),
+            ),
+        }
+    };
+    let (spf, dmarc, dkim) = tokio::join!(
+        lookup(domain.to_string(), evaluate_spf),
+        lookup(dmarc_name.clone(), evaluate_dmarc),
+        dkim
+    );
+
+    DomainReport {
+        domain: domain.to_string(),
+        spf,
+        dmarc,
+        dkim,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn records(values: &[&str]) -> Vec<String> {
+        values.iter().map(|v| v.to_string()).collect()
+    }
+
+    #[test]
+    fn extracts_sending_domain() {
+        assert_eq!(sending_domain("blog@example.com"), Some("example.com"));
+        assert_eq!(
+            sending_domain("Blog <blog@example.com>"),
+            Some("example.com")
+        );
+        assert_eq!(sending_domain("blog"), None);
+    }
+
+    #[test]
+    fn evaluates_spf_records() {
+        let check = |values: &[&str]| evaluate_spf("example.com", &records(values)).status;
+        assert_eq!(
+            check(&["google-site-verification=x", "v=spf1 mx -all"]),
+            CheckStatus::Pass
+        );
+        assert_eq!(check(&["v=spf1 +all"]), CheckStatus::Warning);
+        assert_eq!(
+            check(&["v=spf1 mx -all", "v=spf1 a ~all"]),
+            CheckStatus::Invalid
+        );
+        assert_eq!(check(&[]), CheckStatus::Missing);
+    }
+
+    #[test]
+    fn evaluates_dmarc_policy() {
+        let check = |values: &[&str]| evaluate_dmarc("_dmarc.example.com", &records(values)).status;
+        assert_eq!(
+            check(&["v=DMARC1; p=reject; rua=mailto:x@example.com"]),
+            CheckStatus::Pass
+        );
+        assert_eq!(check(&["v=DMARC1; p=none"]), CheckStatus::Warning);
+        assert_eq!(
+            check(&["v=DMARC1; rua=mailto:x@example.com"]),
+            CheckStatus::Invalid
+        );
+        assert_eq!(check(&[]), CheckStatus::Missing);
+    }
+
+    #[test]
+    fn evaluates_dkim_keys() {
+        let name = "mail._domainkey.example.com";
+        assert_eq!(
+            evaluate_dkim(name, &records(&["v=DKIM1; k=rsa; p=MIGfMA0GCSq"])).status,
+            CheckStatus::Pass
+        );
+        assert_eq!(
+            evaluate_dkim(name, &records(&["v=DKIM1; p="])).status,
+            CheckStatus::Invalid
+        );
+        assert_eq!(evaluate_dkim(name, &[]).status, CheckStatus::Missing);
+    }
+}
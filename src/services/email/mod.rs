@@ -0,0 +1,601 @@
+//! Email service for sending verification codes, notifications and newsletters
+//!
+//! Emails are sent as HTML with a plain text alternative, branded from the
+//! `email_logo_url`, `email_primary_color`, `email_background_color` and
+//! `email_footer_text` settings, and DKIM signed when a valid key is
+//! configured (see [`DkimSettings`]). Addresses on the suppression list are
+//! never mailed; hard bounces seen over SMTP and provider webhooks add to it.
+//!
+//! Mail goes to the `email.smtp` server of the config file when one is
+//! configured, otherwise to the `smtp_*` settings from the admin panel.
+
+pub mod deliverability;
+mod dkim;
+pub mod suppression;
+mod templates;
+
+pub use deliverability::{CheckStatus, DnsCheck, DomainReport};
+pub use dkim::DkimSettings;
+pub use suppression::SuppressionEvent;
+pub use templates::{EmailBranding, EmailTemplates, RenderedEmail};
+
+use crate::config::{SmtpConfig, SmtpTls};
+use crate::db::repositories::{EmailSuppressionRepository, SettingsRepository};
+use crate::models::SuppressionReason;
+use anyhow::{anyhow, Result};
+use lettre::{
+    message::{
+        dkim::DkimConfig,
+        header::{HeaderName, HeaderValue},
+        MultiPart,
+    },
+    transport::smtp::authentication::Credentials,
+    AsyncSmtpTransport, AsyncTransport, Message, Tokio1Executor,
+};
+use serde::Serialize;
+use serde_json::json;
+use std::collections::VecDeque;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+/// Number of delivery failures kept for diagnostics
+const MAX_RECENT_FAILURES: usize = 20;
+
+/// Timeout of SMTP servers configured in the admin panel
+const SETTINGS_SMTP_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// SMTP server from `email.smtp` in the config file or the settings table
+struct SmtpSettings {
+    host: String,
+    port: u16,
+    tls: SmtpTls,
+    username: String,
+    password: String,
+    from: String,
+    from_name: String,
+    timeout: Duration,
+    /// `config` or `settings`
+    source: &'static str,
+}
+
+impl SmtpSettings {
+    fn from_config(config: &SmtpConfig) -> Self {
+        Self {
+            host: config.host.clone(),
+            port: config.port,
+            tls: config.tls,
+            username: config.username.clone().unwrap_or_default(),
+            password: config.password.clone().unwrap_or_default(),
+            from: config.from.clone(),
+            from_name: config
+                .from_name
+                .clone()
+                .unwrap_or_else(|| "Noteva".to_string()),
+            timeout: Duration::from_secs(config.timeout_secs.max(1)),
+            source: "config",
+        }
+    }
+
+    fn transport(&self) -> Result<AsyncSmtpTransport<Tokio1Executor>> {
+        let builder = match self.tls {
+            SmtpTls::Tls => AsyncSmtpTransport::<Tokio1Executor>::relay(&self.host),
+            SmtpTls::Starttls => AsyncSmtpTransport::<Tokio1Executor>::starttls_relay(&self.host),
+            SmtpTls::None => Ok(AsyncSmtpTransport::<Tokio1Executor>::builder_dangerous(
+                &self.host,
+            )),
+        }
+        .map_err(|e| anyhow!("Failed to create SMTP transport: {}", e))?
+        .port(self.port)
+        .timeout(Some(self.timeout));
+        let builder = if self.username.is_empty() {
+            builder
+        } else {
+            builder.credentials(Credentials::new(
+                self.username.clone(),
+                self.password.clone(),
+            ))
+        };
+        Ok(builder.build())
+    }
+}
+
+/// A message that could not be delivered
+#[derive(Debug, Clone, Serialize)]
+pub struct SendFailure {
+    pub to: String,
+    pub subject: String,
+    pub error: String,
+    pub failed_at: chrono::DateTime<chrono::Utc>,
+}
+
+/// Result of connecting to the SMTP server
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct SmtpCheck {
+    pub configured: bool,
+    /// `config` when set in the config file, `settings` when from the admin panel
+    pub source: Option<String>,
+    pub host: Option<String>,
+    pub port: Option<u16>,
+    pub tls: Option<SmtpTls>,
+    pub from: Option<String>,
+    pub connected: bool,
+    pub latency_ms: Option<u64>,
+    pub error: Option<String>,
+}
+
+/// Whether outgoing mail is DKIM signed
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct DkimCheck {
+    pub enabled: bool,
+    pub settings: Option<DkimSettings>,
+    /// Why the configured key cannot be used
+    pub error: Option<String>,
+}
+
+/// SMTP and DNS diagnostics for the configured sender
+#[derive(Debug, Clone, Serialize)]
+pub struct EmailDiagnostics {
+    pub smtp: SmtpCheck,
+    pub dkim: DkimCheck,
+    /// Missing when the from address has no domain
+    pub domain: Option<DomainReport>,
+}
+
+/// Email service for sending emails
+pub struct EmailService {
+    settings_repo: Arc<dyn SettingsRepository>,
+    templates: Arc<EmailTemplates>,
+    suppressions: Option<Arc<dyn EmailSuppressionRepository>>,
+    /// Server from the config file, used instead of the settings table
+    smtp: Option<SmtpConfig>,
+    recent_failures: Mutex<VecDeque<SendFailure>>,
+}
+
+impl EmailService {
+    pub fn new(settings_repo: Arc<dyn SettingsRepository>) -> Self {
+        Self {
+            settings_repo,
+            templates: Arc::new(EmailTemplates::builtin()),
+            suppressions: None,
+            smtp: None,
+            recent_failures: Mutex::new(VecDeque::new()),
+        }
+    }
+
+    /// Use a theme's email templates instead of the built-in ones
+    pub fn with_templates(mut self, templates: Arc<EmailTemplates>) -> Self {
+        self.templates = templates;
+        self
+    }
+
+    /// Send through the `email.smtp` server of the config file instead of
+    /// the SMTP settings from the admin panel
+    pub fn with_smtp(mut self, smtp: Option<SmtpConfig>) -> Self {
+        self.smtp = smtp;
+        self
+    }
+
+    /// Consult and maintain a suppression list when sending
+    pub fn with_suppressions(mut self, suppressions: Arc<dyn EmailSuppressionRepository>) -> Self {
+        self.suppressions = Some(suppressions);
+        self
+    }
+
+    /// Branding from site settings
+    ///
+    /// The logo falls back to the site logo; relative logo paths are made
+    /// absolute with `site_url` since mail clients need full URLs.
+    pub async fn branding(&self) -> EmailBranding {
+        let logo = match self.get_setting("email_logo_url").await {
+            Ok(url) if !url.trim().is_empty() => Some(url),
+            _ => self.get_setting("site_logo").await.ok(),
+        };
+        let logo = match (logo, self.get_setting("site_url").await.ok()) {
+            (Some(path), Some(site_url)) if path.starts_with('/') => {
+                Some(format!("{}{}", site_url.trim_end_matches('/'), path))
+            }
+            (logo, _) => logo,
+        };
+        EmailBranding::from_settings(
+            self.get_setting("site_name").await.ok(),
+            logo,
+            self.get_setting("email_primary_color").await.ok(),
+            self.get_setting("email_background_color").await.ok(),
+            self.get_setting("email_footer_text").await.ok(),
+        )
+    }
+
+    /// Check if email verification is enabled
+    pub async fn is_verification_enabled(&self) -> bool {
+        if let Ok(Some(setting)) = self.settings_repo.get("email_verification_enabled").await {
+            return setting.value == "true";
+        }
+        false
+    }
+
+    /// Send verification code email
+    pub async fn send_verification_code(&self, to_email: &str, code: &str) -> Result<()> {
+        let branding = self.branding().await;
+        let subject = format!("[{}] 邮箱验证码", branding.site_name);
+        self.send_templated(
+            to_email,
+            &subject,
+            "verification",
+            &branding,
+            &json!({ "code": code }),
+            None,
+        )
+        .await
+    }
+
+    /// Send a branded notification with a title, paragraphs and an optional
+    /// call-to-action link
+    pub async fn send_notification(
+        &self,
+        to_email: &str,
+        title: &str,
+        paragraphs: &[String],
+        action: Option<(&str, &str)>,
+    ) -> Result<()> {
+        let branding = self.branding().await;
+        let subject = format!("[{}] {}", branding.site_name, title);
+        let vars = json!({
+            "title": title,
+            "paragraphs": paragraphs,
+            "action_label": action.map(|(label, _)| label),
+            "action_url": action.map(|(_, url)| url),
+        });
+        self.send_templated(to_email, &subject, "notification", &branding, &vars, None)
+            .await
+    }
+
+    /// Send an article to a newsletter subscriber
+    ///
+    /// `content_html` is the sanitized article HTML and is inserted as is.
+    /// The message carries `List-Unsubscribe` headers pointing at
+    /// `unsubscribe_url` so mail clients can offer one-click unsubscribing.
+    pub async fn send_newsletter(
+        &self,
+        to_email: &str,
+        title: &str,
+        content_html: &str,
+        article_url: &str,
+        unsubscribe_url: &str,
+    ) -> Result<()> {
+        let branding = self.branding().await;
+        let subject = format!("[{}] {}", branding.site_name, title);
+        let vars = json!({
+            "title": title,
+            "content_html": content_html,
+            "article_url": article_url,
+            "unsubscribe_url": unsubscribe_url,
+        });
+        self.send_templated(
+            to_email,
+            &subject,
+            "newsletter",
+            &branding,
+            &vars,
+            Some(unsubscribe_url),
+        )
+        .await
+    }
+
+    /// Render a template pair and send it over the configured SMTP server.
+    /// Failures are kept for the deliverability diagnostics.
+    async fn send_templated(
+        &self,
+        to_email: &str,
+        subject: &str,
+        template: &str,
+        branding: &EmailBranding,
+        vars: &serde_json::Value,
+        unsubscribe_url: Option<&str>,
+    ) -> Result<()> {
+        if self.is_suppressed(to_email).await? {
+            tracing::info!(to = %to_email, template = %template, "skipping email to suppressed address");
+            return Err(anyhow!("Recipient {} is on the suppression list", to_email));
+        }
+
+        let result = self
+            .deliver(to_email, subject, template, branding, vars, unsubscribe_url)
+            .await;
+        if let Err(e) = &result {
+            tracing::warn!(to = %to_email, template = %template, error = %e, "email delivery failed");
+            self.record_failure(to_email, subject, e);
+        }
+        result
+    }
+
+    async fn deliver(
+        &self,
+        to_email: &str,
+        subject: &str,
+        template: &str,
+        branding: &EmailBranding,
+        vars: &serde_json::Value,
+        unsubscribe_url: Option<&str>,
+    ) -> Result<()> {
+        let smtp = self.smtp_settings().await?;
+
+        // Build email
+        let from = format!("{} <{}>", smtp.from_name, smtp.from);
+        let body = self.templates.render(template, subject, branding, vars)?;
+
+        let mut builder = Message::builder();
+        if let Some(url) = unsubscribe_url {
+            // RFC 8058 one-click unsubscribe
+            builder = builder
+                .raw_header(HeaderValue::new(
+                    HeaderName::new_from_ascii_str("List-Unsubscribe"),
+                    format!("<{}>", url),
+                ))
+                .raw_header(HeaderValue::new(
+                    HeaderName::new_from_ascii_str("List-Unsubscribe-Post"),
+                    "List-Unsubscribe=One-Click".to_string(),
+                ));
+        }
+        let mut email = builder
+            .from(
+                from.parse()
+                    .map_err(|e| anyhow!("Invalid from address: {}", e))?,
+            )
+            .to(to_email
+                .parse()
+                .map_err(|e| anyhow!("Invalid to address: {}", e))?)
+            .subject(subject.to_string())
+            .multipart(MultiPart::alternative_plain_html(body.text, body.html))
+            .map_err(|e| anyhow!("Failed to build email: {}", e))?;
+        if let Some(dkim) = self.dkim_config(&smtp.from).await {
+            email.sign(&dkim);
+        }
+
+        // Send email
+        if let Err(e) = smtp.transport()?.send(email).await {
+            let code = e.status().map(|code| code.to_string());
+            if code
+                .as_deref()
+                .is_some_and(suppression::is_recipient_rejection)
+            {
+                self.suppress(
+                    to_email,
+                    SuppressionReason::HardBounce,
+                    Some(&format!("smtp: {}", e)),
+                )
+                .await;
+            }
+            return Err(anyhow!("Failed to send email: {}", e));
+        }
+
+        Ok(())
+    }
+
+    /// Whether sending to an address is blocked
+    pub async fn is_suppressed(&self, email: &str) -> Result<bool> {
+        match &self.suppressions {
+            Some(repo) => Ok(repo.get(email).await?.is_some()),
+            None => Ok(false),
+        }
+    }
+
+    /// Add an address to the suppression list, logging instead of failing
+    async fn suppress(&self, email: &str, reason: SuppressionReason, detail: Option<&str>) {
+        let Some(repo) = &self.suppressions else {
+            return;
+        };
+        match repo.upsert(email, reason, detail).await {
+            Ok(_) => tracing::info!(email = %email, reason = %reason, "address suppressed"),
+            Err(e) => tracing::warn!(email = %email, error = %e, "failed to suppress address"),
+        }
+    }
+
+    /// Apply bounce and complaint events from a provider webhook
+    pub async fn apply_suppression_events(&self, events: &[SuppressionEvent]) {
+        for event in events {
+            self.suppress(&event.email, event.reason, Some(&event.detail))
+                .await;
+        }
+    }
+
+    async fn smtp_settings(&self) -> Result<SmtpSettings> {
+        if let Some(config) = &self.smtp {
+            return Ok(SmtpSettings::from_config(config));
+        }
+
+        let host = self.get_setting("smtp_host").await.map_err(|_| {
+            anyhow!("SMTP host not configured. Please configure SMTP settings first.")
+        })?;
+
+        if host.is_empty() {
+            return Err(anyhow!(
+                "SMTP host not configured. Please configure SMTP settings first."
+            ));
+        }
+
+        let port: u16 = self
+            .get_setting("smtp_port")
+            .await
+            .unwrap_or_else(|_| "587".to_string())
+            .parse()
+            .unwrap_or(587);
+        let username = self
+            .get_setting("smtp_username")
+            .await
+            .map_err(|_| anyhow!("SMTP username not configured"))?;
+        let password = self
+            .get_setting("smtp_password")
+            .await
+            .map_err(|_| anyhow!("SMTP password not configured"))?;
+        let from = self
+            .get_setting("smtp_from")
+            .await
+            .map_err(|_| anyhow!("SMTP from address not configured"))?;
+        let from_name = self
+            .get_setting("smtp_from_name")
+            .await
+            .unwrap_or_else(|_| "Noteva".to_string());
+        let tls = self
+            .get_setting("smtp_tls")
+            .await
+            .ok()
+            .and_then(|tls| SmtpTls::parse(&tls))
+            .unwrap_or_else(|| SmtpTls::for_port(port));
+
+        Ok(SmtpSettings {
+            host,
+            port,
+            tls,
+            username,
+            password,
+            from,
+            from_name,
+            timeout: SETTINGS_SMTP_TIMEOUT,
+            source: "settings",
+        })
+    }
+
+    /// DKIM signing settings, when configured
+    pub async fn dkim_settings(&self, from_address: &str) -> Option<DkimSettings> {
+        DkimSettings::from_settings(
+            self.get_setting("dkim_selector").await.ok(),
+            self.get_setting("dkim_domain").await.ok(),
+            self.get_setting("dkim_algorithm").await.ok(),
+            self.get_setting("dkim_private_key").await.ok(),
+            from_address,
+        )
+    }
+
+    /// Validated DKIM signing configuration
+    ///
+    /// An unusable key disables signing (the admin email diagnostics report
+    /// why) instead of failing every send.
+    async fn dkim_config(&self, from_address: &str) -> Option<DkimConfig> {
+        let dkim = self.dkim_settings(from_address).await?;
+        match dkim.config() {
+            Ok(config) => Some(config),
+            Err(e) => {
+                tracing::warn!(selector = %dkim.selector, error = %e, "DKIM signing disabled");
+                None
+            }
+        }
+    }
+
+    fn record_failure(&self, to_email: &str, subject: &str, error: &anyhow::Error) {
+        let mut failures = self
+            .recent_failures
+            .lock()
+            .unwrap_or_else(|e| e.into_inner());
+        if failures.len() == MAX_RECENT_FAILURES {
+            failures.pop_front();
+        }
+        failures.push_back(SendFailure {
+            to: to_email.to_string(),
+            subject: subject.to_string(),
+            error: error.to_string(),
+            failed_at: chrono::Utc::now(),
+        });
+    }
+
+    /// Most recent delivery failures, newest first
+    pub fn recent_failures(&self) -> Vec<SendFailure> {
+        self.recent_failures
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .iter()
+            .rev()
+            .cloned()
+            .collect()
+    }
+
+    /// Check the SMTP connection and the DNS records of the sending domain
+    ///
+    /// `dkim_selector` falls back to the `dkim_selector` setting.
+    pub async fn diagnose(&self, dkim_selector: Option<&str>) -> EmailDiagnostics {
+        let smtp = match self.smtp_settings().await {
+            Ok(smtp) => smtp,
+            Err(e) => {
+                return EmailDiagnostics {
+                    smtp: SmtpCheck {
+                        error: Some(e.to_string()),
+                        ..Default::default()
+                    },
+                    dkim: DkimCheck::default(),
+                    domain: None,
+                }
+            }
+        };
+
+        let started = std::time::Instant::now();
+        let connection = match smtp.transport() {
+            Ok(transport) => transport.test_connection().await.map_err(|e| e.to_string()),
+            Err(e) => Err(e.to_string()),
+        };
+        let smtp_check = SmtpCheck {
+            configured: true,
+            source: Some(smtp.source.to_string()),
+            host: Some(smtp.host.clone()),
+            port: Some(smtp.port),
+            tls: Some(smtp.tls),
+            from: Some(smtp.from.clone()),
+            connected: connection == Ok(true),
+            latency_ms: Some(started.elapsed().as_millis() as u64),
+            error: match connection {
+                Ok(true) => None,
+                Ok(false) => Some("The server did not accept the connection".to_string()),
+                Err(e) => Some(e),
+            },
+        };
+
+        let dkim = self.dkim_settings(&smtp.from).await;
+        let error = dkim
+            .as_ref()
+            .and_then(|dkim| dkim.config().err())
+            .map(|e| e.to_string());
+        let dkim_check = DkimCheck {
+            enabled: dkim.is_some() && error.is_none(),
+            error,
+            settings: dkim.clone(),
+        };
+
+        let selector = dkim_selector
+            .map(str::trim)
+            .filter(|s| !s.is_empty())
+            .map(str::to_string)
+            .or_else(|| dkim.as_ref().map(|d| d.selector.clone()));
+        let domain = match deliverability::sending_domain(&smtp.from) {
+            Some(domain) => Some(deliverability::check_domain(domain, selector.as_deref()).await),
+            None => None,
+        };
+
+        EmailDiagnostics {
+            smtp: smtp_check,
+            dkim: dkim_check,
+            domain,
+        }
+    }
+
+    /// Send test email
+    pub async fn send_test_email(&self, to_email: &str) -> Result<()> {
+        let paragraphs = ["这是一封测试邮件，收到说明邮件发送配置正确。".to_string()];
+        self.send_notification(to_email, "测试邮件", &paragraphs, None)
+            .await
+    }
+
+    async fn get_setting(&self, key: &str) -> Result<String> {
+        self.settings_repo
+            .get(key)
+            .await?
+            .map(|s| s.value)
+            .ok_or_else(|| anyhow!("Setting '{}' not configured", key))
+    }
+}
+
+/// Generate a random 6-digit verification code
+pub fn generate_verification_code() -> String {
+    use std::time::{SystemTime, UNIX_EPOCH};
+    let seed = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap()
+        .as_nanos();
+    format!("{:06}", (seed % 1000000) as u32)
+}
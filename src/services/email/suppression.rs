@@ -0,0 +1,273 @@
+//! Bounce and complaint events from email provider webhooks
+//!
+//! Only permanent failures and spam complaints are returned; soft bounces
+//! and delivery notices are ignored since the address may still work.
+
+use anyhow::{bail, Context, Result};
+use serde_json::Value;
+
+use crate::models::SuppressionReason;
+
+/// Providers whose webhook payloads are understood
+pub const PROVIDERS: &[&str] = &["postmark", "sendgrid", "mailgun", "ses"];
+
+/// An address to suppress, reported by a provider
+#[derive(Debug, Clone, PartialEq)]
+pub struct SuppressionEvent {
+    pub email: String,
+    pub reason: SuppressionReason,
+    pub detail: String,
+}
+
+impl SuppressionEvent {
+    fn new(email: &str, reason: SuppressionReason, provider: &str, detail: Option<&str>) -> Self {
+        Self {
+            email: email.to_string(),
+            reason,
+            detail: match detail.map(str::trim).filter(|d| !d.is_empty()) {
+                Some(detail) => format!("{}: {}", provider, detail),
+                None => provider.to_string(),
+            },
+        }
+    }
+}
+
+/// Extract suppression events from a provider's webhook body
+pub fn parse_webhook(provider: &str, body: &Value) -> Result<Vec<SuppressionEvent>> {
+    match provider {
+        "postmark" => Ok(parse_postmark(body).into_iter().collect()),
+        "sendgrid" => Ok(parse_sendgrid(body)),
+        "mailgun" => Ok(parse_mailgun(body).into_iter().collect()),
+        "ses" => parse_ses(body),
+        other => bail!("Unsupported email provider: {}", other),
+    }
+}
+
+/// Whether an SMTP reply code means the recipient address itself is bad
+///
+/// 550 (mailbox unavailable), 551 (user not local) and 553 (mailbox name
+/// not allowed). Other permanent errors such as 535 (authentication) or
+/// 554 (rejected content) say nothing about the address.
+pub fn is_recipient_rejection(code: &str) -> bool {
+    matches!(code, "550" | "551" | "553")
+}
+
+fn str_field<'a>(value: &'a Value, key: &str) -> Option<&'a str> {
+    value.get(key).and_then(Value::as_str)
+}
+
+/// Postmark bounce and spam complaint webhooks
+fn parse_postmark(body: &Value) -> Option<SuppressionEvent> {
+    let email = str_field(body, "Email")?;
+    let reason = match (str_field(body, "RecordType")?, str_field(body, "Type")) {
+        ("Bounce", Some("HardBounce")) => SuppressionReason::HardBounce,
+        ("SpamComplaint", _) => SuppressionReason::Complaint,
+        _ => return None,
+    };
+    Some(SuppressionEvent::new(
+        email,
+        reason,
+        "postmark",
+        str_field(body, "Description"),
+    ))
+}
+
+/// SendGrid event webhook (an array of events)
+fn parse_sendgrid(body: &Value) -> Vec<SuppressionEvent> {
+    let Some(events) = body.as_array() else {
+        return Vec::new();
+    };
+    events
+        .iter()
+        .filter_map(|event| {
+            let email = str_field(event, "email")?;
+            let reason = match str_field(event, "event")? {
+                // SendGrid reports soft bounces as "blocked" or "deferred"
+                "bounce" | "dropped" => SuppressionReason::HardBounce,
+                "spamreport" => SuppressionReason::Complaint,
+                _ => return None,
+            };
+            Some(SuppressionEvent::new(
+                email,
+                reason,
+                "sendgrid",
+                str_field(event, "reason"),
+            ))
+        })
+        .collect()
+}
+
+/// Mailgun webhooks (`event-data` envelope)
+fn parse_mailgun(body: &Value) -> Option<SuppressionEvent> {
+    let data = body.get("event-data")?;
+    let email = str_field(data, "recipient")?;
+    let reason = match str_field(data, "event")? {
+        "failed" if str_field(data, "severity") == Some("permanent") => {
+            SuppressionReason::HardBounce
+        }
+        "complained" => SuppressionReason::Complaint,
+        _ => return None,
+    };
+    let detail = data.get("delivery-status").and_then(|status| {
+        str_field(status, "message").or_else(|| str_field(status, "description"))
+    });
+    Some(SuppressionEvent::new(email, reason, "mailgun", detail))
+}
+
+/// Amazon SES notifications delivered through SNS
+///
+/// The SES notification is a JSON string in the SNS `Message` field;
+/// a bare notification is accepted as well.
+fn parse_ses(body: &Value) -> Result<Vec<SuppressionEvent>> {
+    let message = match str_field(body, "Message") {
+        Some(message) => {
+            serde_json::from_str(message).context("Invalid SES notification in SNS message")?
+        }
+        None => body.clone(),
+    };
+
+    let events = match str_field(&message, "notificationType")
+        .or_else(|| str_field(&message, "eventType"))
+    {
+        Some("Bounce") => {
+            let bounce = &message["bounce"];
+            if str_field(bounce, "bounceType") != Some("Permanent") {
+                return Ok(Vec::new());
+            }
+            recipients(bounce, "bouncedRecipients")
+                .filter_map(|recipient| {
+                    Some(SuppressionEvent::new(
+                        str_field(recipient, "emailAddress")?,
+                        SuppressionReason::HardBounce,
+                        "ses",
+                        str_field(recipient, "diagnosticCode"),
+                    ))
+                })
+                .collect()
+        }
+        Some("Complaint") => {
+            let complaint = &message["complaint"];
+            recipients(complaint, "complainedRecipients")
+                .filter_map(|recipient| {
+                    Some(SuppressionEvent::new(
+                        str_field(recipient, "emailAddress")?,
+                        SuppressionReason::Complaint,
+                        "ses",
+                        str_field(complaint, "complaintFeedbackType"),
+                    ))
+                })
+                .collect()
+        }
+        _ => Vec::new(),
+    };
+    Ok(events)
+}
+
+fn recipients<'a>(value: &'a Value, key: &str) -> impl Iterator<Item = &'a Value> {
+    value
+        .get(key)
+        .and_then(Value::as_array)
+        .into_iter()
+        .flatten()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn parses_postmark_hard_bounces_and_complaints() {
+        let bounce = json!({
+            "RecordType": "Bounce",
+            "Type": "HardBounce",
+            "Email": "gone@example.com",
+            "Description": "The server was unable to deliver your message",
+        });
+        let events = parse_webhook("postmark", &bounce).unwrap();
+        assert_eq!(events.len(), 1);
+        assert_eq!(events[0].email, "gone@example.com");
+        assert_eq!(events[0].reason, SuppressionReason::HardBounce);
+        assert!(events[0].detail.starts_with("postmark: The server"));
+
+        let soft =
+            json!({ "RecordType": "Bounce", "Type": "SoftBounce", "Email": "a@example.com" });
+        assert!(parse_webhook("postmark", &soft).unwrap().is_empty());
+
+        let complaint = json!({ "RecordType": "SpamComplaint", "Email": "angry@example.com" });
+        let events = parse_webhook("postmark", &complaint).unwrap();
+        assert_eq!(events[0].reason, SuppressionReason::Complaint);
+        assert_eq!(events[0].detail, "postmark");
+    }
+
+    #[test]
+    fn parses_sendgrid_and_mailgun_events() {
+        let sendgrid = json!([
+            { "event": "delivered", "email": "ok@example.com" },
+            { "event": "bounce", "email": "gone@example.com", "reason": "550 5.1.1 unknown" },
+            { "event": "spamreport", "email": "angry@example.com" },
+        ]);
+        let events = parse_webhook("sendgrid", &sendgrid).unwrap();
+        assert_eq!(events.len(), 2);
+        assert_eq!(events[0].detail, "sendgrid: 550 5.1.1 unknown");
+        assert_eq!(events[1].reason, SuppressionReason::Complaint);
+
+        let temporary = json!({ "event-data": {
+            "event": "failed", "severity": "temporary", "recipient": "a@example.com",
+        }});
+        assert!(parse_webhook("mailgun", &temporary).unwrap().is_empty());
+
+        let permanent = json!({ "event-data": {
+            "event": "failed",
+            "severity": "permanent",
+            "recipient": "gone@example.com",
+            "delivery-status": { "message": "No such user" },
+        }});
+        let events = parse_webhook("mailgun", &permanent).unwrap();
+        assert_eq!(events[0].email, "gone@example.com");
+        assert_eq!(events[0].detail, "mailgun: No such user");
+    }
+
+    #[test]
+    fn parses_ses_notifications_wrapped_in_sns() {
+        let notification = json!({
+            "notificationType": "Bounce",
+            "bounce": {
+                "bounceType": "Permanent",
+                "bouncedRecipients": [
+                    { "emailAddress": "gone@example.com", "diagnosticCode": "smtp; 550 unknown" },
+                    { "emailAddress": "also-gone@example.com" },
+                ],
+            },
+        });
+        let sns = json!({ "Type": "Notification", "Message": notification.to_string() });
+        let events = parse_webhook("ses", &sns).unwrap();
+        assert_eq!(events.len(), 2);
+        assert_eq!(events[0].detail, "ses: smtp; 550 unknown");
+
+        let transient = json!({
+            "notificationType": "Bounce",
+            "bounce": { "bounceType": "Transient", "bouncedRecipients": [{ "emailAddress": "a@example.com" }] },
+        });
+        assert!(parse_webhook("ses", &transient).unwrap().is_empty());
+
+        let complaint = json!({
+            "notificationType": "Complaint",
+            "complaint": { "complainedRecipients": [{ "emailAddress": "angry@example.com" }] },
+        });
+        let events = parse_webhook("ses", &complaint).unwrap();
+        assert_eq!(events[0].reason, SuppressionReason::Complaint);
+
+        assert!(parse_webhook("ses", &json!({ "Message": "not json" })).is_err());
+        assert!(parse_webhook("unknown", &json!({})).is_err());
+    }
+
+    #[test]
+    fn only_mailbox_errors_count_as_hard_bounces() {
+        assert!(is_recipient_rejection("550"));
+        assert!(is_recipient_rejection("553"));
+        assert!(!is_recipient_rejection("535"));
+        assert!(!is_recipient_rejection("554"));
+        assert!(!is_recipient_rejection("450"));
+    }
+}
@@ -0,0 +1,277 @@
+//! Branded email templates
+//!
+//! Emails are rendered with Tera from built-in templates. A theme can
+//! replace any of them by shipping a file with the same name in its
+//! `email/` directory (e.g. `themes/my-theme/email/layout.html`). Every
+//! template receives a `branding` object built from site settings.
+
+use anyhow::{Context, Result};
+use serde::Serialize;
+use std::path::Path;
+use tera::{Context as TeraContext, Tera};
+
+const BUILTIN_TEMPLATES: &[(&str, &str)] = &[
+    ("layout.html", include_str!("templates/layout.html")),
+    (
+        "verification.html",
+        include_str!("templates/verification.html"),
+    ),
+    (
+        "verification.txt",
+        include_str!("templates/verification.txt"),
+    ),
+    (
+        "notification.html",
+        include_str!("templates/notification.html"),
+    ),
+    (
+        "notification.txt",
+        include_str!("templates/notification.txt"),
+    ),
+    ("newsletter.html", include_str!("templates/newsletter.html")),
+    ("newsletter.txt", include_str!("templates/newsletter.txt")),
+];
+
+const DEFAULT_PRIMARY_COLOR: &str = "#2563eb";
+const DEFAULT_BACKGROUND_COLOR: &str = "#f4f4f5";
+
+/// Site branding applied to system emails
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct EmailBranding {
+    pub site_name: String,
+    /// Absolute URL of the logo shown in the header
+    pub logo_url: Option<String>,
+    pub primary_color: String,
+    pub background_color: String,
+    pub footer_text: Option<String>,
+}
+
+impl Default for EmailBranding {
+    fn default() -> Self {
+        Self {
+            site_name: "Noteva".to_string(),
+            logo_url: None,
+            primary_color: DEFAULT_PRIMARY_COLOR.to_string(),
+            background_color: DEFAULT_BACKGROUND_COLOR.to_string(),
+            footer_text: None,
+        }
+    }
+}
+
+impl EmailBranding {
+    /// Build branding from setting values, ignoring invalid colors and
+    /// logos that are not absolute http(s) URLs
+    pub fn from_settings(
+        site_name: Option<String>,
+        logo_url: Option<String>,
+        primary_color: Option<String>,
+        background_color: Option<String>,
+        footer_text: Option<String>,
+    ) -> Self {
+        let non_empty = |value: Option<String>| {
+            value
+                .map(|v| v.trim().to_string())
+                .filter(|v| !v.is_empty())
+        };
+        let color = |value: Option<String>, default: &str| {
+            non_empty(value)
+                .filter(|c| is_hex_color(c))
+                .unwrap_or_else(|| default.to_string())
+        };
+        Self {
+            site_name: non_empty(site_name).unwrap_or_else(|| "Noteva".to_string()),
+            logo_url: non_empty(logo_url)
+                .filter(|url| url.starts_with("https://") || url.starts_with("http://")),
+            primary_color: color(primary_color, DEFAULT_PRIMARY_COLOR),
+            background_color: color(background_color, DEFAULT_BACKGROUND_COLOR),
+            footer_text: non_empty(footer_text),
+        }
+    }
+}
+
+/// `#rgb` or `#rrggbb`; anything else could break out of the inline styles
+fn is_hex_color(value: &str) -> bool {
+    value
+        .strip_prefix('#')
+        .is_some_and(|hex| matches!(hex.len(), 3 | 6) && hex.chars().all(|c| c.is_ascii_hexdigit()))
+}
+
+/// HTML and plain text bodies of a rendered email
+#[derive(Debug, Clone)]
+pub struct RenderedEmail {
+    pub html: String,
+    pub text: String,
+}
+
+/// Email template set
+pub struct EmailTemplates {
+    tera: Tera,
+}
+
+impl EmailTemplates {
+    /// The built-in templates
+    pub fn builtin() -> Self {
+        Self::with_overrides(&[]).expect("built-in email templates are valid")
+    }
+
+    /// Built-in templates overridden by the `email/` directory of a theme.
+    /// A missing directory leaves the built-in templates in place.
+    pub fn load(theme_path: &Path) -> Result<Self> {
+        let dir = theme_path.join("email");
+        let mut overrides = Vec::new();
+        if dir.is_dir() {
+            for (name, _) in BUILTIN_TEMPLATES {
+                let path = dir.join(name);
+                if path.is_file() {
+                    let content = std::fs::read_to_string(&path)
+                        .with_context(|| format!("Failed to read {}", path.display()))?;
+                    overrides.push((name.to_string(), content));
+                }
+            }
+        }
+        Self::with_overrides(&overrides)
+    }
+
+    fn with_overrides(overrides: &[(String, String)]) -> Result<Self> {
+        let mut tera = Tera::default();
+        let templates = BUILTIN_TEMPLATES.iter().map(|(name, builtin)| {
+            let content = overrides
+                .iter()
+                .find(|(n, _)| n == name)
+                .map_or(*builtin, |(_, content)| content.as_str());
+            (*name, content)
+        });
+        tera.add_raw_templates(templates)
+            .context("Failed to parse email templates")?;
+        Ok(Self { tera })
+    }
+
+    /// Render `<name>.html` and `<name>.txt` with `vars` and the branding
+    pub fn render(
+        &self,
+        name: &str,
+        subject: &str,
+        branding: &EmailBranding,
+        vars: &serde_json::Value,
+    ) -> Result<RenderedEmail> {
+        let mut context = TeraContext::from_value(vars.clone())
+            .context("Email template variables must be an object")?;
+        context.insert("subject", subject);
+        context.insert("branding", branding);
+        let render = |template: String| {
+            self.tera
+                .render(&template, &context)
+                .with_context(|| format!("Failed to render email template {}", template))
+        };
+        Ok(RenderedEmail {
+            html: render(format!("{}.html", name))?,
+            text: render(format!("{}.txt", name))?,
+        })
+    }
+}
+
+impl Default for EmailTemplates {
+    fn default() -> Self {
+        Self::builtin()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn branding_rejects_unsafe_values() {
+        let branding = EmailBranding::from_settings(
+            Some("My Blog".to_string()),
+            Some("javascript:alert(1)".to_string()),
+            Some("red;background:url(x)".to_string()),
+            Some("#FFF".to_string()),
+            Some("  ".to_string()),
+        );
+        assert_eq!(branding.site_name, "My Blog");
+        assert_eq!(branding.logo_url, None);
+        assert_eq!(branding.primary_color, DEFAULT_PRIMARY_COLOR);
+        assert_eq!(branding.background_color, "#FFF");
+        assert_eq!(branding.footer_text, None);
+    }
+
+    #[test]
+    fn renders_branded_verification_email() {
+        let branding = EmailBranding {
+            site_name: "My <Blog>".to_string(),
+            logo_url: Some("https://example.com/logo.png".to_string()),
+            primary_color: "#ff6600".to_string(),
+            footer_text: Some("Sent by My Blog".to_string()),
+            ..Default::default()
+        };
+        let email = EmailTemplates::builtin()
+            .render(
+                "verification",
+                "Code",
+                &branding,
+                &json!({ "code": "123456" }),
+            )
+            .unwrap();
+
+        assert!(email.html.contains("123456"));
+        assert!(email.html.contains("#ff6600"));
+        // Tera escapes `/` in attributes, which mail clients decode
+        assert!(email
+            .html
+            .contains("<img src=\"https:&#x2F;&#x2F;example.com&#x2F;logo.png\""));
+        assert!(email.html.contains("My &lt;Blog&gt;"));
+        assert!(email.text.contains("123456"));
+        assert!(email.text.contains("Sent by My Blog"));
+    }
+
+    #[test]
+    fn newsletter_keeps_article_html() {
+        let email = EmailTemplates::builtin()
+            .render(
+                "newsletter",
+                "Hello",
+                &EmailBranding::default(),
+                &json!({
+                    "title": "Hello <world>",
+                    "content_html": "<p>First <strong>post</strong></p>",
+                    "article_url": "https://example.com/posts/hello",
+                    "unsubscribe_url": "https://example.com/unsubscribe?token=abc",
+                }),
+            )
+            .unwrap();
+
+        assert!(email.html.contains("<p>First <strong>post</strong></p>"));
+        assert!(email.html.contains("Hello &lt;world&gt;"));
+        assert!(email
+            .text
+            .contains("https://example.com/unsubscribe?token=abc"));
+        assert!(!email.text.contains("<strong>"));
+    }
+
+    #[test]
+    fn theme_templates_override_builtin_ones() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::create_dir(dir.path().join("email")).unwrap();
+        std::fs::write(
+            dir.path().join("email/verification.txt"),
+            "{{ branding.site_name }} code: {{ code }}",
+        )
+        .unwrap();
+
+        let templates = EmailTemplates::load(dir.path()).unwrap();
+        let email = templates
+            .render(
+                "verification",
+                "Code",
+                &EmailBranding::default(),
+                &json!({ "code": "654321" }),
+            )
+            .unwrap();
+        assert_eq!(email.text, "Noteva code: 654321");
+        assert!(email.html.contains("654321"));
+
+        assert!(EmailTemplates::load(&dir.path().join("missing")).is_ok());
+    }
+}
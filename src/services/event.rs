@@ -0,0 +1,372 @@
+//! Event service.
+//!
+//! Besides CRUD this expands repeating events into upcoming occurrences and
+//! writes the iCalendar feed. The feed carries each event once with its
+//! `RRULE`, so calendar apps keep following the series.
+
+use crate::db::repositories::EventRepository;
+use crate::models::{Event, EventInput, EventOccurrence, RepeatFrequency};
+use anyhow::{Context, Result};
+use chrono::{DateTime, Duration, NaiveTime, Utc};
+use std::sync::Arc;
+
+const MAX_TITLE_LEN: usize = 200;
+const MAX_DESCRIPTION_LEN: usize = 10_000;
+const MAX_LOCATION_LEN: usize = 300;
+const MAX_URL_LEN: usize = 1000;
+const MAX_REPEAT_INTERVAL: u32 = 365;
+
+/// Events that ended longer ago than this are left out of the feed
+const FEED_HISTORY_DAYS: i64 = 90;
+
+pub struct EventService {
+    repo: Arc<dyn EventRepository>,
+}
+
+impl EventService {
+    pub fn new(repo: Arc<dyn EventRepository>) -> Self {
+        Self { repo }
+    }
+
+    pub async fn create(&self, input: EventInput) -> Result<Event> {
+        let event = event_from_input(0, input)?;
+        self.repo
+            .create(&event)
+            .await
+            .context("Failed to create event")
+    }
+
+    pub async fn get_by_id(&self, id: i64) -> Result<Option<Event>> {
+        self.repo.get_by_id(id).await
+    }
+
+    pub async fn list(&self) -> Result<Vec<Event>> {
+        self.repo.list().await
+    }
+
+    /// Replace an event
+    pub async fn update(&self, id: i64, input: EventInput) -> Result<Event> {
+        self.repo
+            .get_by_id(id)
+            .await?
+            .ok_or_else(|| anyhow::anyhow!("Event not found"))?;
+        self.repo.update(&event_from_input(id, input)?).await
+    }
+
+    /// Delete an event; false when it did not exist
+    pub async fn delete(&self, id: i64) -> Result<bool> {
+        self.repo.delete(id).await
+    }
+
+    /// The next occurrences across all events, including ones in progress
+    pub async fn upcoming(&self, now: DateTime<Utc>, limit: usize) -> Result<Vec<EventOccurrence>> {
+        let events = self.repo.list_active(now - Duration::days(1)).await?;
+        let mut occurrences: Vec<EventOccurrence> = events
+            .iter()
+            .flat_map(|event| event.occurrences(now, limit))
+            .collect();
+        occurrences.sort_by(|a, b| {
+            a.starts_at
+                .cmp(&b.starts_at)
+                .then(a.event_id.cmp(&b.event_id))
+        });
+        occurrences.truncate(limit);
+        Ok(occurrences)
+    }
+
+    /// iCalendar feed of current events and those of the last few months
+    pub async fn ical_feed(&self, calendar_name: &str, site_url: &str) -> Result<String> {
+        let now = Utc::now();
+        let events = self
+            .repo
+            .list_active(now - Duration::days(FEED_HISTORY_DAYS))
+            .await?;
+        Ok(render_ical(&events, calendar_name, site_url, now))
+    }
+}
+
+fn event_from_input(id: i64, input: EventInput) -> Result<Event> {
+    let title = input.title.trim();
+    if title.is_empty() {
+        anyhow::bail!("Title cannot be empty");
+    }
+    if title.chars().count() > MAX_TITLE_LEN {
+        anyhow::bail!("Title cannot exceed {} characters", MAX_TITLE_LEN);
+    }
+    let description = input.description.trim();
+    if description.chars().count() > MAX_DESCRIPTION_LEN {
+        anyhow::bail!(
+            "Description cannot exceed {} characters",
+            MAX_DESCRIPTION_LEN
+        );
+    }
+    let location = non_empty(input.location);
+    if location
+        .as_ref()
+        .is_some_and(|l| l.chars().count() > MAX_LOCATION_LEN)
+    {
+        anyhow::bail!("Location cannot exceed {} characters", MAX_LOCATION_LEN);
+    }
+    let url = non_empty(input.url);
+    if let Some(url) = &url {
+        validate_url(url)?;
+    }
+
+    // All-day events are whole UTC days; the end is the last day
+    let (starts_at, ends_at) = if input.all_day {
+        (
+            start_of_day(input.starts_at),
+            input.ends_at.map(start_of_day),
+        )
+    } else {
+        (input.starts_at, input.ends_at)
+    };
+    if ends_at.is_some_and(|ends_at| ends_at < starts_at) {
+        anyhow::bail!("An event cannot end before it starts");
+    }
+
+    let mut repeat = input.repeat;
+    if let Some(repeat) = &mut repeat {
+        if repeat.interval == 0 || repeat.interval > MAX_REPEAT_INTERVAL {
+            anyhow::bail!(
+                "Repeat interval must be between 1 and {}",
+                MAX_REPEAT_INTERVAL
+            );
+        }
+        if repeat.until.is_some_and(|until| until < starts_at) {
+            anyhow::bail!("Repeats cannot end before the event starts");
+        }
+    }
+
+    let now = Utc::now();
+    Ok(Event {
+        id,
+        title: title.to_string(),
+        description: description.to_string(),
+        location,
+        url,
+        starts_at,
+        ends_at,
+        all_day: input.all_day,
+        repeat,
+        created_at: now,
+        updated_at: now,
+    })
+}
+
+fn non_empty(value: Option<String>) -> Option<String> {
+    value
+        .map(|v| v.trim().to_string())
+        .filter(|v| !v.is_empty())
+}
+
+fn validate_url(value: &str) -> Result<()> {
+    if value.chars().count() > MAX_URL_LEN {
+        anyhow::bail!("URL cannot exceed {} characters", MAX_URL_LEN);
+    }
+    let parsed = reqwest::Url::parse(value).context("URL must be a valid URL")?;
+    if !matches!(parsed.scheme(), "http" | "https") || parsed.host_str().is_none() {
+        anyhow::bail!("URL must start with http:// or https://");
+    }
+    Ok(())
+}
+
+fn start_of_day(value: DateTime<Utc>) -> DateTime<Utc> {
+    value.date_naive().and_time(NaiveTime::MIN).and_utc()
+}
+
+/// Write events as an iCalendar (RFC 5545) document
+pub fn render_ical(
+    events: &[Event],
+    calendar_name: &str,
+    site_url: &str,
+    now: DateTime<Utc>,
+) -> String {
+    let host = reqwest::Url::parse(site_url)
+        .ok()
+        .and_then(|url| url.host_str().map(str::to_string))
+        .unwrap_or_else(|| "noteva".to_string());
+
+    let mut lines = vec![
+        "BEGIN:VCALENDAR".to_string(),
+        "VERSION:2.0".to_string(),
+        "PRODID:-//Noteva//Events//EN".to_string(),
+        "CALSCALE:GREGORIAN".to_string(),
+        "METHOD:PUBLISH".to_string(),
+        format!("X-WR-CALNAME:{}", ical_escape(calendar_name)),
+    ];
+    for event in events {
+        lines.push("BEGIN:VEVENT".to_string());
+        lines.push(format!("UID:event-{}@{}", event.id, host));
+        lines.push(format!("DTSTAMP:{}", ical_datetime(now)));
+        lines.push(format!("LAST-MODIFIED:{}", ical_datetime(event.updated_at)));
+        if event.all_day {
+            let end = event.starts_at + event.span();
+            lines.push(format!("DTSTART;VALUE=DATE:{}", ical_date(event.starts_at)));
+            lines.push(format!("DTEND;VALUE=DATE:{}", ical_date(end)));
+        } else {
+            lines.push(format!("DTSTART:{}", ical_datetime(event.starts_at)));
+            if let Some(ends_at) = event.ends_at {
+                lines.push(format!("DTEND:{}", ical_datetime(ends_at)));
+            }
+        }
+        if let Some(repeat) = &event.repeat {
+            let mut rule = format!(
+                "RRULE:FREQ={};INTERVAL={}",
+                rrule_frequency(repeat.frequency),
+                repeat.interval
+            );
+            if let Some(until) = repeat.until {
+                let until = if event.all_day {
+                    ical_date(until)
+                } else {
+                    ical_datetime(until)
+                };
+                rule.push_str(&format!(";UNTIL={}", until));
+            }
+            lines.push(rule);
+        }
+        lines.push(format!("SUMMARY:{}", ical_escape(&event.title)));
+        if !event.description.is_empty() {
+            lines.push(format!("DESCRIPTION:{}", ical_escape(&event.description)));
+        }
+        if let Some(location) = &event.location {
+            lines.push(format!("LOCATION:{}", ical_escape(location)));
+        }
+        if let Some(url) = &event.url {
+            lines.push(format!("URL:{}", url));
+        }
+        lines.push("END:VEVENT".to_string());
+    }
+    lines.push("END:VCALENDAR".to_string());
+
+    let mut ical = String::new();
+    for line in lines {
+        ical.push_str(&fold_line(&line));
+        ical.push_str("\r\n");
+    }
+    ical
+}
+
+fn rrule_frequency(frequency: RepeatFrequency) -> &'static str {
+    match frequency {
+        RepeatFrequency::Daily => "DAILY",
+        RepeatFrequency::Weekly => "WEEKLY",
+        RepeatFrequency::Monthly => "MONTHLY",
+        RepeatFrequency::Yearly => "YEARLY",
+    }
+}
+
+fn ical_datetime(value: DateTime<Utc>) -> String {
+    value.format("%Y%m%dT%H%M%SZ").to_string()
+}
+
+fn ical_date(value: DateTime<Utc>) -> String {
+    value.format("%Y%m%d").to_string()
+}
+
+/// Escape a TEXT value
+fn ical_escape(s: &str) -> String {
+    s.replace('\\', "\\\\")
+        .replace(';', "\\;")
+        .replace(',', "\\,")
+        .replace("\r\n", "\\n")
+        .replace('\n', "\\n")
+}
+
+/// Fold a content line at 75 octets without splitting a character
+fn fold_line(line: &str) -> String {
+    let mut folded = String::with_capacity(line.len() + line.len() / 74 * 3);
+    let mut width = 0;
+    for c in line.chars() {
+        // Continuation lines start with a space, which counts toward 75
+        if width + c.len_utf8() > 75 {
+            folded.push_str("\r\n ");
+            width = 1;
+        }
+        folded.push(c);
+        width += c.len_utf8();
+    }
+    folded
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::EventRepeat;
+    use chrono::TimeZone;
+
+    fn input(title: &str) -> EventInput {
+        EventInput {
+            title: title.to_string(),
+            description: String::new(),
+            location: Some("  ".to_string()),
+            url: None,
+            starts_at: Utc.with_ymd_and_hms(2026, 5, 2, 18, 30, 0).unwrap(),
+            ends_at: None,
+            all_day: false,
+            repeat: None,
+        }
+    }
+
+    #[test]
+    fn input_is_validated() {
+        let event = event_from_input(0, input(" Meetup ")).unwrap();
+        assert_eq!(event.title, "Meetup");
+        assert_eq!(event.location, None);
+        assert!(event_from_input(0, input(" ")).is_err());
+
+        let mut bad_url = input("Meetup");
+        bad_url.url = Some("javascript:alert(1)".to_string());
+        assert!(event_from_input(0, bad_url).is_err());
+
+        let mut backwards = input("Meetup");
+        backwards.ends_at = Some(backwards.starts_at - Duration::hours(1));
+        assert!(event_from_input(0, backwards).is_err());
+
+        let mut zero_interval = input("Meetup");
+        zero_interval.repeat = Some(EventRepeat {
+            frequency: RepeatFrequency::Daily,
+            interval: 0,
+            until: None,
+        });
+        assert!(event_from_input(0, zero_interval).is_err());
+    }
+
+    #[test]
+    fn all_day_events_start_at_midnight() {
+        let mut festival = input("Festival");
+        festival.all_day = true;
+        festival.ends_at = Some(Utc.with_ymd_and_hms(2026, 5, 4, 9, 0, 0).unwrap());
+        let event = event_from_input(0, festival).unwrap();
+        assert_eq!(
+            event.starts_at,
+            Utc.with_ymd_and_hms(2026, 5, 2, 0, 0, 0).unwrap()
+        );
+
+        let ical = render_ical(&[event], "Blog", "https://blog.example", Utc::now());
+        assert!(ical.contains("DTSTART;VALUE=DATE:20260502\r\n"));
+        assert!(ical.contains("DTEND;VALUE=DATE:20260505\r\n"));
+        assert!(ical.contains("UID:event-0@blog.example\r\n"));
+    }
+
+    #[test]
+    fn ical_lines_are_escaped_and_folded() {
+        let mut event = event_from_input(1, input("Talks; lightning, short")).unwrap();
+        event.description = "Line one\nLine two ".repeat(10).trim().to_string();
+        event.repeat = Some(EventRepeat {
+            frequency: RepeatFrequency::Weekly,
+            interval: 2,
+            until: Some(Utc.with_ymd_and_hms(2026, 12, 31, 0, 0, 0).unwrap()),
+        });
+        let ical = render_ical(&[event], "Blog", "", Utc::now());
+
+        assert!(ical.starts_with("BEGIN:VCALENDAR\r\n"));
+        assert!(ical.ends_with("END:VCALENDAR\r\n"));
+        assert!(ical.contains("SUMMARY:Talks\\; lightning\\, short\r\n"));
+        assert!(ical.contains("RRULE:FREQ=WEEKLY;INTERVAL=2;UNTIL=20261231T000000Z\r\n"));
+        assert!(ical.contains("DTSTART:20260502T183000Z\r\n"));
+        assert!(ical.split("\r\n").all(|line| line.len() <= 75));
+        assert!(ical.contains("\r\n "));
+    }
+}
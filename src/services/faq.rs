@@ -0,0 +1,416 @@
+//! FAQ service.
+//!
+//! Besides CRUD this expands `[faq topic="slug"]` placeholders in rendered
+//! articles. Like polls, the shortcode only leaves a placeholder so edits
+//! to a topic show up in every article that embeds it.
+
+use crate::db::repositories::FaqRepository;
+use crate::models::{FaqItem, FaqItemInput, FaqTopic, FaqTopicInput, FaqTopicWithItems};
+use crate::services::markdown::MarkdownEngine;
+use crate::services::MarkdownRenderer;
+use chrono::Utc;
+use once_cell::sync::Lazy;
+use regex::Regex;
+use std::sync::Arc;
+
+const MAX_SLUG_LEN: usize = 100;
+const MAX_TITLE_LEN: usize = 200;
+const MAX_DESCRIPTION_LEN: usize = 2000;
+const MAX_QUESTION_LEN: usize = 500;
+const MAX_ANSWER_LEN: usize = 20_000;
+
+static PLACEHOLDER_RE: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r#"<div class="noteva-faq" data-noteva-faq="([a-z0-9_-]+)"></div>"#).unwrap()
+});
+
+/// Errors returned by the FAQ service
+#[derive(Debug, thiserror::Error)]
+pub enum FaqError {
+    #[error("{0} not found")]
+    NotFound(&'static str),
+
+    #[error("{0}")]
+    Validation(String),
+
+    #[error("Internal error: {0}")]
+    Internal(#[from] anyhow::Error),
+}
+
+/// Placeholder the `[faq]` shortcode leaves in rendered content; `None`
+/// when `slug` cannot be a topic slug
+pub fn placeholder(slug: &str) -> Option<String> {
+    let slug = normalize_slug(slug).ok()?;
+    Some(format!(
+        r#"<div class="noteva-faq" data-noteva-faq="{}"></div>"#,
+        slug
+    ))
+}
+
+pub struct FaqService {
+    repo: Arc<dyn FaqRepository>,
+    markdown: MarkdownRenderer,
+}
+
+impl FaqService {
+    pub fn new(repo: Arc<dyn FaqRepository>) -> Self {
+        Self {
+            repo,
+            markdown: MarkdownRenderer::new(),
+        }
+    }
+
+    /// Parse answers with `engine` instead of pulldown-cmark
+    pub fn with_markdown_engine(mut self, engine: Arc<dyn MarkdownEngine>) -> Self {
+        self.markdown.set_engine(engine);
+        self
+    }
+
+    pub async fn list_topics(&self) -> Result<Vec<FaqTopic>, FaqError> {
+        Ok(self.repo.list_topics().await?)
+    }
+
+    pub async fn get_topic(&self, id: i64) -> Result<FaqTopic, FaqError> {
+        self.repo
+            .get_topic(id)
+            .await?
+            .ok_or(FaqError::NotFound("FAQ topic"))
+    }
+
+    pub async fn create_topic(&self, input: FaqTopicInput) -> Result<FaqTopic, FaqError> {
+        let topic = topic_from_input(0, input)?;
+        if self.repo.get_topic_by_slug(&topic.slug).await?.is_some() {
+            return Err(FaqError::Validation(format!(
+                "Topic '{}' already exists",
+                topic.slug
+            )));
+        }
+        Ok(self.repo.create_topic(&topic).await?)
+    }
+
+    /// Replace a topic's slug, title, description and position
+    pub async fn update_topic(&self, id: i64, input: FaqTopicInput) -> Result<FaqTopic, FaqError> {
+        let existing = self.get_topic(id).await?;
+        let topic = topic_from_input(id, input)?;
+        if topic.slug != existing.slug && self.repo.get_topic_by_slug(&topic.slug).await?.is_some()
+        {
+            return Err(FaqError::Validation(format!(
+                "Topic '{}' already exists",
+                topic.slug
+            )));
+        }
+        Ok(self
+            .repo
+            .update_topic(&FaqTopic {
+                created_at: existing.created_at,
+                ..topic
+            })
+            .await?)
+    }
+
+    /// Delete a topic and its questions
+    pub async fn delete_topic(&self, id: i64) -> Result<(), FaqError> {
+        if !self.repo.delete_topic(id).await? {
+            return Err(FaqError::NotFound("FAQ topic"));
+        }
+        Ok(())
+    }
+
+    /// A topic's questions, by position
+    pub async fn list_items(&self, topic_id: i64) -> Result<Vec<FaqItem>, FaqError> {
+        self.get_topic(topic_id).await?;
+        Ok(self.repo.list_items(&[topic_id]).await?)
+    }
+
+    pub async fn get_item(&self, id: i64) -> Result<FaqItem, FaqError> {
+        self.repo
+            .get_item(id)
+            .await?
+            .ok_or(FaqError::NotFound("FAQ question"))
+    }
+
+    pub async fn create_item(&self, input: FaqItemInput) -> Result<FaqItem, FaqError> {
+        self.get_topic(input.topic_id).await?;
+        let item = self.item_from_input(0, input)?;
+        Ok(self.repo.create_item(&item).await?)
+    }
+
+    /// Replace a question; it may move to another topic
+    pub async fn update_item(&self, id: i64, input: FaqItemInput) -> Result<FaqItem, FaqError> {
+        let existing = self.get_item(id).await?;
+        if input.topic_id != existing.topic_id {
+            self.get_topic(input.topic_id).await?;
+        }
+        let item = self.item_from_input(id, input)?;
+        Ok(self
+            .repo
+            .update_item(&FaqItem {
+                created_at: existing.created_at,
+                ..item
+            })
+            .await?)
+    }
+
+    pub async fn delete_item(&self, id: i64) -> Result<(), FaqError> {
+        if !self.repo.delete_item(id).await? {
+            return Err(FaqError::NotFound("FAQ question"));
+        }
+        Ok(())
+    }
+
+    /// Every topic with its questions
+    pub async fn list_with_items(&self) -> Result<Vec<FaqTopicWithItems>, FaqError> {
+        let topics = self.repo.list_topics().await?;
+        self.attach_items(topics).await
+    }
+
+    /// One topic with its questions
+    pub async fn get_with_items(&self, slug: &str) -> Result<FaqTopicWithItems, FaqError> {
+        let topic = self
+            .repo
+            .get_topic_by_slug(slug)
+            .await?
+            .ok_or(FaqError::NotFound("FAQ topic"))?;
+        let mut topics = self.attach_items(vec![topic]).await?;
+        Ok(topics.remove(0))
+    }
+
+    /// Topics embedded in rendered HTML, in order of first appearance;
+    /// placeholders of deleted topics are skipped
+    pub async fn embedded_topics(&self, html: &str) -> Vec<FaqTopicWithItems> {
+        let mut topics = Vec::new();
+        let mut seen: Vec<&str> = Vec::new();
+        for caps in PLACEHOLDER_RE.captures_iter(html) {
+            let slug = caps.get(1).map_or("", |m| m.as_str());
+            if seen.contains(&slug) {
+                continue;
+            }
+            seen.push(slug);
+            match self.get_with_items(slug).await {
+                Ok(topic) => topics.push(topic),
+                Err(FaqError::NotFound(_)) => {}
+                Err(e) => tracing::warn!("Failed to load FAQ topic {}: {}", slug, e),
+            }
+        }
+        topics
+    }
+
+    /// Replace FAQ placeholders in rendered HTML with the topics' questions
+    pub async fn render_embeds(&self, html: &str) -> String {
+        if !PLACEHOLDER_RE.is_match(html) {
+            return html.to_string();
+        }
+        let topics = self.embedded_topics(html).await;
+        fill_embeds(html, &topics)
+    }
+
+    async fn attach_items(
+        &self,
+        topics: Vec<FaqTopic>,
+    ) -> Result<Vec<FaqTopicWithItems>, FaqError> {
+        let ids: Vec<i64> = topics.iter().map(|t| t.id).collect();
+        let items = self.repo.list_items(&ids).await?;
+        Ok(topics
+            .into_iter()
+            .map(|topic| FaqTopicWithItems {
+                items: items
+                    .iter()
+                    .filter(|i| i.topic_id == topic.id)
+                    .cloned()
+                    .collect(),
+                topic,
+            })
+            .collect())
+    }
+
+    fn item_from_input(&self, id: i64, input: FaqItemInput) -> Result<FaqItem, FaqError> {
+        let question = input.question.trim();
+        if question.is_empty() {
+            return Err(FaqError::Validation("Question cannot be empty".to_string()));
+        }
+        if question.chars().count() > MAX_QUESTION_LEN {
+            return Err(FaqError::Validation(format!(
+                "Question cannot exceed {} characters",
+                MAX_QUESTION_LEN
+            )));
+        }
+        let answer = input.answer.trim();
+        if answer.is_empty() {
+            return Err(FaqError::Validation("Answer cannot be empty".to_string()));
+        }
+        if answer.chars().count() > MAX_ANSWER_LEN {
+            return Err(FaqError::Validation(format!(
+                "Answer cannot exceed {} characters",
+                MAX_ANSWER_LEN
+            )));
+        }
+        let now = Utc::now();
+        Ok(FaqItem {
+            id,
+            topic_id: input.topic_id,
+            question: question.to_string(),
+            answer_html: self.markdown.render(answer),
+            answer: answer.to_string(),
+            position: input.position,
+            created_at: now,
+            updated_at: now,
+        })
+    }
+}
+
+fn topic_from_input(id: i64, input: FaqTopicInput) -> Result<FaqTopic, FaqError> {
+    let slug = normalize_slug(&input.slug)?;
+    let title = input.title.trim();
+    if title.is_empty() {
+        return Err(FaqError::Validation("Title cannot be empty".to_string()));
+    }
+    if title.chars().count() > MAX_TITLE_LEN {
+        return Err(FaqError::Validation(format!(
+            "Title cannot exceed {} characters",
+            MAX_TITLE_LEN
+        )));
+    }
+    let description = input.description.trim();
+    if description.chars().count() > MAX_DESCRIPTION_LEN {
+        return Err(FaqError::Validation(format!(
+            "Description cannot exceed {} characters",
+            MAX_DESCRIPTION_LEN
+        )));
+    }
+    let now = Utc::now();
+    Ok(FaqTopic {
+        id,
+        slug,
+        title: title.to_string(),
+        description: description.to_string(),
+        position: input.position,
+        created_at: now,
+        updated_at: now,
+    })
+}
+
+fn normalize_slug(slug: &str) -> Result<String, FaqError> {
+    let slug = slug.trim().to_lowercase();
+    if slug.is_empty() {
+        return Err(FaqError::Validation("Slug cannot be empty".to_string()));
+    }
+    if slug.len() > MAX_SLUG_LEN {
+        return Err(FaqError::Validation(format!(
+            "Slug cannot exceed {} characters",
+            MAX_SLUG_LEN
+        )));
+    }
+    if !slug
+        .chars()
+        .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+    {
+        return Err(FaqError::Validation(
+            "Slug may only contain letters, digits, '-' and '_'".to_string(),
+        ));
+    }
+    Ok(slug)
+}
+
+/// Swap placeholders for the matching topics; unknown ones are dropped
+pub fn fill_embeds(html: &str, topics: &[FaqTopicWithItems]) -> String {
+    PLACEHOLDER_RE
+        .replace_all(html, |caps: &regex::Captures| {
+            topics
+                .iter()
+                .find(|t| t.topic.slug == caps[1])
+                .map(render_topic)
+                .unwrap_or_default()
+        })
+        .into_owned()
+}
+
+/// A topic as collapsible questions
+pub fn render_topic(topic: &FaqTopicWithItems) -> String {
+    let mut html = format!(
+        r#"<section class="noteva-faq" data-faq-topic="{}"><h3 class="noteva-faq-title">{}</h3>"#,
+        topic.topic.slug,
+        html_escape(&topic.topic.title)
+    );
+    if !topic.topic.description.is_empty() {
+        html.push_str(&format!(
+            r#"<p class="noteva-faq-description">{}</p>"#,
+            html_escape(&topic.topic.description)
+        ));
+    }
+    for item in &topic.items {
+        html.push_str(&format!(
+            r#"<details class="noteva-faq-item"><summary class="noteva-faq-question">{}</summary><div class="noteva-faq-answer">{}</div></details>"#,
+            html_escape(&item.question),
+            item.answer_html
+        ));
+    }
+    html.push_str("</section>");
+    html
+}
+
+fn html_escape(s: &str) -> String {
+    s.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn topic(slug: &str, questions: &[&str]) -> FaqTopicWithItems {
+        let now = Utc::now();
+        FaqTopicWithItems {
+            topic: FaqTopic {
+                id: 1,
+                slug: slug.to_string(),
+                title: "Billing & plans".to_string(),
+                description: String::new(),
+                position: 0,
+                created_at: now,
+                updated_at: now,
+            },
+            items: questions
+                .iter()
+                .enumerate()
+                .map(|(i, question)| FaqItem {
+                    id: i as i64 + 1,
+                    topic_id: 1,
+                    question: question.to_string(),
+                    answer: "Yes".to_string(),
+                    answer_html: "<p>Yes</p>".to_string(),
+                    position: i as i32,
+                    created_at: now,
+                    updated_at: now,
+                })
+                .collect(),
+        }
+    }
+
+    #[test]
+    fn placeholder_only_accepts_slugs() {
+        assert_eq!(
+            placeholder(" Billing ").as_deref(),
+            Some(r#"<div class="noteva-faq" data-noteva-faq="billing"></div>"#)
+        );
+        assert!(placeholder("bill\"ing").is_none());
+        assert!(placeholder("").is_none());
+    }
+
+    #[test]
+    fn fills_known_topics_and_drops_unknown_ones() {
+        let html = format!(
+            "<p>Intro</p>{}{}",
+            placeholder("billing").unwrap(),
+            placeholder("gone").unwrap()
+        );
+        let filled = fill_embeds(&html, &[topic("billing", &["Can I pay <yearly>?"])]);
+        assert!(filled.starts_with(
+            r#"<p>Intro</p><section class="noteva-faq" data-faq-topic="billing"><h3 class="noteva-faq-title">Billing &amp; plans</h3>"#
+        ));
+        assert!(filled.contains(
+            r#"<summary class="noteva-faq-question">Can I pay &lt;yearly&gt;?</summary><div class="noteva-faq-answer"><p>Yes</p></div>"#
+        ));
+        assert!(!filled.contains("noteva-faq\" data-noteva-faq"));
+    }
+}
@@ -0,0 +1,86 @@
+//! Featured article service.
+//!
+//! Admins keep one ordered list of hand-picked articles; visitors see the
+//! published entries that have not expired, for themes to build hero
+//! sections with.
+
+use crate::db::repositories::FeaturedRepository;
+use crate::models::{FeaturedArticle, FeaturedInput};
+use chrono::Utc;
+use std::sync::Arc;
+
+/// Longest featured list
+pub const MAX_FEATURED: usize = 50;
+
+/// Errors returned by the featured service
+#[derive(Debug, thiserror::Error)]
+pub enum FeaturedError {
+    #[error("{0} not found")]
+    NotFound(&'static str),
+
+    #[error("{0}")]
+    Validation(String),
+
+    #[error("Internal error: {0}")]
+    Internal(#[from] anyhow::Error),
+}
+
+pub struct FeaturedService {
+    repo: Arc<dyn FeaturedRepository>,
+}
+
+impl FeaturedService {
+    pub fn new(repo: Arc<dyn FeaturedRepository>) -> Self {
+        Self { repo }
+    }
+
+    /// The whole list, drafts and expired entries included
+    pub async fn list(&self) -> Result<Vec<FeaturedArticle>, FeaturedError> {
+        Ok(self.repo.list().await?)
+    }
+
+    /// Replace the list with `input.items`, in that order
+    pub async fn set(&self, input: FeaturedInput) -> Result<Vec<FeaturedArticle>, FeaturedError> {
+        let items = input.items;
+        if items.len() > MAX_FEATURED {
+            return Err(FeaturedError::Validation(format!(
+                "The featured list cannot have more than {} articles",
+                MAX_FEATURED
+            )));
+        }
+        let ids: Vec<i64> = items.iter().map(|item| item.article_id).collect();
+        for (i, article_id) in ids.iter().enumerate() {
+            if ids[..i].contains(article_id) {
+                return Err(FeaturedError::Validation(format!(
+                    "Article {} is listed twice",
+                    article_id
+                )));
+            }
+        }
+        let existing = self.repo.existing_article_ids(&ids).await?;
+        if let Some(missing) = ids.iter().find(|id| !existing.contains(id)) {
+            return Err(FeaturedError::Validation(format!(
+                "Article {} does not exist",
+                missing
+            )));
+        }
+        self.repo.set(&items).await?;
+        Ok(self.repo.list().await?)
+    }
+
+    /// Take an article off the list
+    pub async fn remove(&self, article_id: i64) -> Result<(), FeaturedError> {
+        if !self.repo.remove(article_id).await? {
+            return Err(FeaturedError::NotFound("Featured article"));
+        }
+        Ok(())
+    }
+
+    /// Ids of the published, unexpired articles on the list, in order
+    pub async fn active_ids(&self, limit: usize) -> Result<Vec<i64>, FeaturedError> {
+        Ok(self
+            .repo
+            .active_article_ids(Utc::now(), limit.min(MAX_FEATURED) as i64)
+            .await?)
+    }
+}
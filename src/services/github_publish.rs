@@ -0,0 +1,555 @@
+//! Publish articles from a GitHub content repository
+//!
+//! A GitHub push webhook pointed at `/api/v1/integrations/github/push`
+//! makes Noteva fetch the Markdown files the push added or changed under
+//! `github_publish_path` and create or update the matching articles.
+//! Front matter supplies `title`, `slug`, `status`, `category` (slug) and
+//! `tags`; files deleted from the repository turn their article into a
+//! draft. Each file remembers the commit it was last published from, so
+//! redelivered pushes do nothing.
+//!
+//! Settings: `github_publish_repo` (`owner/name`), `github_publish_branch`
+//! (default `main`), `github_publish_path`, `github_publish_token` (for
+//! private repositories), `github_publish_author` (username, default the
+//! first user) and `github_webhook_secret`.
+
+use anyhow::{anyhow, Context, Result};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::sync::Arc;
+use std::time::Duration;
+
+use crate::db::repositories::{GithubSyncRepository, UserRepository};
+use crate::models::{ArticleStatus, CreateArticleInput, UpdateArticleInput};
+use crate::services::article::ArticleService;
+use crate::services::category::CategoryService;
+use crate::services::inbound_webhook::verify_hmac_sha256;
+use crate::services::settings::SettingsService;
+use crate::services::tag::TagService;
+
+pub const REPO_KEY: &str = "github_publish_repo";
+pub const BRANCH_KEY: &str = "github_publish_branch";
+pub const PATH_KEY: &str = "github_publish_path";
+pub const TOKEN_KEY: &str = "github_publish_token";
+pub const AUTHOR_KEY: &str = "github_publish_author";
+pub const SECRET_KEY: &str = "github_webhook_secret";
+
+/// Timeout for fetching a file from the GitHub API
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(15);
+
+/// Errors returned when accepting a push
+#[derive(Debug, thiserror::Error)]
+pub enum GithubPublishError {
+    /// Repository or webhook secret not set
+    #[error("GitHub publishing is not configured")]
+    NotConfigured,
+
+    /// Missing or wrong `X-Hub-Signature-256`
+    #[error("Invalid webhook signature")]
+    InvalidSignature,
+
+    /// Malformed payload
+    #[error("{0}")]
+    InvalidRequest(String),
+
+    /// Internal error
+    #[error("Internal error: {0}")]
+    Internal(#[from] anyhow::Error),
+}
+
+/// The parts of a GitHub push event that matter for publishing
+#[derive(Debug, Clone, Deserialize)]
+pub struct PushEvent {
+    #[serde(rename = "ref")]
+    pub git_ref: String,
+    /// Head commit after the push
+    pub after: String,
+    #[serde(default)]
+    pub commits: Vec<PushCommit>,
+    pub repository: PushRepository,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct PushCommit {
+    #[serde(default)]
+    pub added: Vec<String>,
+    #[serde(default)]
+    pub modified: Vec<String>,
+    #[serde(default)]
+    pub removed: Vec<String>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct PushRepository {
+    pub full_name: String,
+}
+
+/// Markdown files touched by a push
+#[derive(Debug, Default, PartialEq, Serialize)]
+pub struct ChangedFiles {
+    pub updated: Vec<String>,
+    pub removed: Vec<String>,
+}
+
+impl ChangedFiles {
+    pub fn is_empty(&self) -> bool {
+        self.updated.is_empty() && self.removed.is_empty()
+    }
+}
+
+/// Outcome of publishing one push
+#[derive(Debug, Default, Serialize)]
+pub struct PublishReport {
+    pub created: usize,
+    pub updated: usize,
+    pub unpublished: usize,
+    pub unchanged: usize,
+    pub errors: Vec<String>,
+}
+
+/// Where to publish from
+#[derive(Debug, Clone)]
+struct PublishSettings {
+    repo: String,
+    branch: String,
+    path: String,
+    token: Option<String>,
+}
+
+/// An article parsed from a Markdown file
+#[derive(Debug, Clone, PartialEq)]
+pub struct MarkdownDocument {
+    pub title: String,
+    pub slug: String,
+    pub status: ArticleStatus,
+    pub category: Option<String>,
+    pub tags: Vec<String>,
+    pub body: String,
+}
+
+#[derive(Debug, Default, Deserialize)]
+struct FrontMatter {
+    title: Option<String>,
+    slug: Option<String>,
+    status: Option<String>,
+    #[serde(default)]
+    draft: bool,
+    category: Option<String>,
+    #[serde(default)]
+    tags: Vec<String>,
+}
+
+/// Whether `path` is a Markdown file under the publish directory
+fn is_publishable(path: &str, prefix: &str) -> bool {
+    let prefix = prefix.trim_matches('/');
+    let in_dir = prefix.is_empty()
+        || path
+            .strip_prefix(prefix)
+            .is_some_and(|rest| rest.starts_with('/'));
+    in_dir && (path.ends_with(".md") || path.ends_with(".markdown"))
+}
+
+/// Collect the publishable files a push changed; later commits win
+pub fn changed_files(event: &PushEvent, prefix: &str) -> ChangedFiles {
+    let mut state: BTreeMap<&str, bool> = BTreeMap::new();
+    for commit in &event.commits {
+        for path in commit.added.iter().chain(&commit.modified) {
+            state.insert(path, true);
+        }
+        for path in &commit.removed {
+            state.insert(path, false);
+        }
+    }
+
+    let mut files = ChangedFiles::default();
+    for (path, present) in state {
+        if !is_publishable(path, prefix) {
+            continue;
+        }
+        if present {
+            files.updated.push(path.to_string());
+        } else {
+            files.removed.push(path.to_string());
+        }
+    }
+    files
+}
+
+/// Split front matter from a Markdown file
+///
+/// Files without front matter are published with the first `# ` heading
+/// as title; the slug defaults to the file name.
+pub fn parse_document(content: &str, path: &str) -> Result<MarkdownDocument> {
+    let stem = path.rsplit('/').next().unwrap_or(path);
+    let stem = stem
+        .strip_suffix(".markdown")
+        .or_else(|| stem.strip_suffix(".md"))
+        .unwrap_or(stem);
+
+    let content = content.trim_start_matches('\u{feff}');
+    let (front, body) = match content
+        .strip_prefix("---")
+        .and_then(|rest| rest.find("\n---").map(|end| (rest, end)))
+    {
+        Some((rest, end)) => {
+            let front: FrontMatter = serde_yaml::from_str::<Option<FrontMatter>>(&rest[..end])
+                .with_context(|| format!("Invalid front matter in {}", path))?
+                .unwrap_or_default();
+            let body = rest[end + 4..].trim_start_matches(['\r', '\n']);
+            (front, body)
+        }
+        None => (FrontMatter::default(), content),
+    };
+
+    let title = front
+        .title
+        .filter(|t| !t.trim().is_empty())
+        .or_else(|| {
+            body.lines()
+                .find_map(|line| line.strip_prefix("# "))
+                .map(|t| t.trim().to_string())
+        })
+        .unwrap_or_else(|| stem.to_string());
+    let status = match front.status.as_deref() {
+        Some(status) => ArticleStatus::from_str(status)
+            .ok_or_else(|| anyhow!("Invalid status '{}' in {}", status, path))?,
+        None if front.draft => ArticleStatus::Draft,
+        None => ArticleStatus::Published,
+    };
+
+    Ok(MarkdownDocument {
+        title,
+        slug: front
+            .slug
+            .filter(|s| !s.trim().is_empty())
+            .unwrap_or_else(|| stem.to_string()),
+        status,
+        category: front.category.filter(|c| !c.trim().is_empty()),
+        tags: front.tags,
+        body: body.to_string(),
+    })
+}
+
+/// Publishes articles from pushes to the configured repository
+pub struct GithubPublishService {
+    settings: Arc<SettingsService>,
+    article_service: Arc<ArticleService>,
+    category_service: Arc<CategoryService>,
+    tag_service: Arc<TagService>,
+    user_repo: Arc<dyn UserRepository>,
+    sync_repo: Arc<dyn GithubSyncRepository>,
+    client: reqwest::Client,
+}
+
+impl GithubPublishService {
+    pub fn new(
+        settings: Arc<SettingsService>,
+        article_service: Arc<ArticleService>,
+        category_service: Arc<CategoryService>,
+        tag_service: Arc<TagService>,
+        user_repo: Arc<dyn UserRepository>,
+        sync_repo: Arc<dyn GithubSyncRepository>,
+    ) -> Self {
+        let client = reqwest::Client::builder()
+            .timeout(REQUEST_TIMEOUT)
+            .user_agent(concat!("Noteva/", env!("CARGO_PKG_VERSION")))
+            .build()
+            .expect("Failed to build HTTP client");
+        Self {
+            settings,
+            article_service,
+            category_service,
+            tag_service,
+            user_repo,
+            sync_repo,
+            client,
+        }
+    }
+
+    async fn setting(&self, key: &str) -> Option<String> {
+        self.settings
+            .get(key)
+            .await
+            .ok()
+            .flatten()
+            .map(|v| v.trim().to_string())
+            .filter(|v| !v.is_empty())
+    }
+
+    async fn publish_settings(&self) -> Result<PublishSettings, GithubPublishError> {
+        let repo = self
+            .setting(REPO_KEY)
+            .await
+            .ok_or(GithubPublishError::NotConfigured)?;
+        Ok(PublishSettings {
+            repo,
+            branch: self
+                .setting(BRANCH_KEY)
+                .await
+                .unwrap_or_else(|| "main".to_string()),
+            path: self.setting(PATH_KEY).await.unwrap_or_default(),
+            token: self.setting(TOKEN_KEY).await,
+        })
+    }
+
+    /// Check the `X-Hub-Signature-256` header against the webhook secret
+    pub async fn verify_signature(
+        &self,
+        signature: Option<&str>,
+        body: &[u8],
+    ) -> Result<(), GithubPublishError> {
+        let secret = self
+            .setting(SECRET_KEY)
+            .await
+            .ok_or(GithubPublishError::NotConfigured)?;
+        match signature {
+            Some(signature) if verify_hmac_sha256(&secret, signature, body) => Ok(()),
+            _ => Err(GithubPublishError::InvalidSignature),
+        }
+    }
+
+    /// Files a push would publish, or `None` when the push is for another
+    /// repository or branch
+    pub async fn files_to_publish(
+        &self,
+        event: &PushEvent,
+    ) -> Result<Option<ChangedFiles>, GithubPublishError> {
+        let settings = self.publish_settings().await?;
+        let branch_ref = format!("refs/heads/{}", settings.branch);
+        if !event
+            .repository
+            .full_name
+            .eq_ignore_ascii_case(&settings.repo)
+            || event.git_ref != branch_ref
+        {
+            return Ok(None);
+        }
+        Ok(Some(changed_files(event, &settings.path)))
+    }
+
+    /// Publish the files changed by a push
+    pub async fn publish(&self, event: &PushEvent, files: &ChangedFiles) -> PublishReport {
+        let mut report = PublishReport::default();
+        let settings = match self.publish_settings().await {
+            Ok(settings) => settings,
+            Err(e) => {
+                report.errors.push(e.to_string());
+                return report;
+            }
+        };
+
+        for path in &files.updated {
+            match self.publish_file(&settings, path, &event.after).await {
+                Ok(Some(true)) => report.created += 1,
+                Ok(Some(false)) => report.updated += 1,
+                Ok(None) => report.unchanged += 1,
+                Err(e) => report.errors.push(format!("{}: {:#}", path, e)),
+            }
+        }
+        for path in &files.removed {
+            match self.unpublish_file(path, &event.after).await {
+                Ok(true) => report.unpublished += 1,
+                Ok(false) => report.unchanged += 1,
+                Err(e) => report.errors.push(format!("{}: {:#}", path, e)),
+            }
+        }
+        report
+    }
+
+    /// Create or update the article for a file. Returns `Some(true)` when
+    /// created, `Some(false)` when updated and `None` when already current.
+    async fn publish_file(
+        &self,
+        settings: &PublishSettings,
+        path: &str,
+        commit: &str,
+    ) -> Result<Option<bool>> {
+        let synced = self.sync_repo.get(path).await?;
+        if synced.as_ref().is_some_and(|f| f.commit_sha == commit) {
+            return Ok(None);
+        }
+
+        let content = self.fetch_file(settings, path, commit).await?;
+        let doc = parse_document(&content, path)?;
+
+        let category_id = match &doc.category {
+            Some(slug) => self.category_service.get_by_slug(slug).await?,
+            None => None,
+        };
+        let category_id = match category_id {
+            Some(category) => category.id,
+            None => {
+                self.category_service
+                    .get_default()
+                    .await?
+                    .ok_or_else(|| anyhow!("Default category not found"))?
+                    .id
+            }
+        };
+        let mut tag_ids = Vec::with_capacity(doc.tags.len());
+        for name in &doc.tags {
+            tag_ids.push(self.tag_service.create_or_get(name).await?.id);
+        }
+
+        // A file is tied to its article once published; before that an
+        // article with the same slug is adopted
+        let existing = match &synced {
+            Some(file) => self.article_service.get_by_id(file.article_id).await?,
+            None => self.article_service.get_by_slug(&doc.slug).await?,
+        };
+
+        let (article, created) = match existing {
+            Some(article) => {
+                let mut update = UpdateArticleInput::new();
+                update.slug = Some(doc.slug);
+                update.title = Some(doc.title);
+                update.content = Some(doc.body);
+                update.category_id = Some(category_id);
+                update.status = Some(doc.status);
+                let article = self
+                    .article_service
+                    .update(article.id, update, Some(tag_ids))
+                    .await?;
+                (article, false)
+            }
+            None => {
+                let input = CreateArticleInput::new(
+                    doc.slug,
+                    doc.title,
+                    doc.body,
+                    self.author_id().await?,
+                    category_id,
+                )
+                .with_status(doc.status);
+                let article = self.article_service.create(input, Some(tag_ids)).await?;
+                (article, true)
+            }
+        };
+
+        self.sync_repo.upsert(path, article.id, commit).await?;
+        tracing::info!(path = %path, article_id = article.id, commit = %commit, "published article from GitHub");
+        Ok(Some(created))
+    }
+
+    /// Turn the article of a deleted file into a draft
+    async fn unpublish_file(&self, path: &str, commit: &str) -> Result<bool> {
+        let Some(file) = self.sync_repo.get(path).await? else {
+            return Ok(false);
+        };
+        if file.commit_sha == commit {
+            return Ok(false);
+        }
+        let mut update = UpdateArticleInput::new();
+        update.status = Some(ArticleStatus::Draft);
+        self.article_service
+            .update(file.article_id, update, None)
+            .await?;
+        self.sync_repo.upsert(path, file.article_id, commit).await?;
+        Ok(true)
+    }
+
+    /// Author for new articles
+    async fn author_id(&self) -> Result<i64> {
+        if let Some(username) = self.setting(AUTHOR_KEY).await {
+            return self
+                .user_repo
+                .get_by_username(&username)
+                .await?
+                .map(|user| user.id)
+                .ok_or_else(|| anyhow!("{} user '{}' not found", AUTHOR_KEY, username));
+        }
+        let (users, _) = self.user_repo.list(1, 1).await?;
+        users
+            .first()
+            .map(|user| user.id)
+            .ok_or_else(|| anyhow!("No user to publish articles as"))
+    }
+
+    /// Raw file content at a commit, via the contents API
+    async fn fetch_file(
+        &self,
+        settings: &PublishSettings,
+        path: &str,
+        commit: &str,
+    ) -> Result<String> {
+        let encoded_path = path
+            .split('/')
+            .map(|segment| urlencoding::encode(segment).into_owned())
+            .collect::<Vec<_>>()
+            .join("/");
+        let url = format!(
+            "https://api.github.com/repos/{}/contents/{}?ref={}",
+            settings.repo, encoded_path, commit
+        );
+        let mut request = self
+            .client
+            .get(&url)
+            .header("Accept", "application/vnd.github.raw+json")
+            .header("X-GitHub-Api-Version", "2022-11-28");
+        if let Some(token) = &settings.token {
+            request = request.bearer_auth(token);
+        }
+        let response = request.send().await.context("GitHub request failed")?;
+        if !response.status().is_success() {
+            return Err(anyhow!("GitHub returned {}", response.status()));
+        }
+        response.text().await.context("Failed to read file")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn push(commits: serde_json::Value) -> PushEvent {
+        serde_json::from_value(serde_json::json!({
+            "ref": "refs/heads/main",
+            "after": "abc",
+            "repository": { "full_name": "me/blog" },
+            "commits": commits,
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn collects_markdown_changes_under_the_publish_path() {
+        let event = push(serde_json::json!([
+            { "added": ["posts/a.md", "posts/b.md", "README.md"], "modified": [], "removed": [] },
+            { "added": [], "modified": ["posts/img.png", "postscript/c.md"], "removed": ["posts/b.md"] },
+            { "added": [], "modified": [], "removed": ["posts/old.markdown"] },
+        ]));
+        let files = changed_files(&event, "/posts/");
+        assert_eq!(files.updated, vec!["posts/a.md"]);
+        assert_eq!(files.removed, vec!["posts/b.md", "posts/old.markdown"]);
+
+        let all = changed_files(&event, "");
+        assert!(all.updated.contains(&"README.md".to_string()));
+    }
+
+    #[test]
+    fn parses_front_matter() {
+        let doc = parse_document(
+            "---\ntitle: \"Hello: World\"\nslug: hello\ncategory: notes\ntags: [rust, blog]\n---\n\nBody text\n",
+            "posts/2024-01-01-hello.md",
+        )
+        .unwrap();
+        assert_eq!(doc.title, "Hello: World");
+        assert_eq!(doc.slug, "hello");
+        assert_eq!(doc.status, ArticleStatus::Published);
+        assert_eq!(doc.category.as_deref(), Some("notes"));
+        assert_eq!(doc.tags, vec!["rust", "blog"]);
+        assert_eq!(doc.body, "Body text\n");
+
+        let draft = parse_document("---\ndraft: true\n---\n# Heading\n", "posts/x.md").unwrap();
+        assert_eq!(draft.status, ArticleStatus::Draft);
+        assert_eq!(draft.title, "Heading");
+        assert_eq!(draft.slug, "x");
+
+        let plain = parse_document("No front matter", "notes/plain.markdown").unwrap();
+        assert_eq!(plain.title, "plain");
+        assert_eq!(plain.body, "No front matter");
+
+        assert!(parse_document("---\nstatus: maybe\n---\n", "a.md").is_err());
+        assert!(parse_document("---\ntags: [unclosed\n---\n", "a.md").is_err());
+    }
+}
@@ -0,0 +1,448 @@
+//! Comment import from Disqus and WordPress
+//!
+//! Supported formats:
+//! - Disqus XML export (`<disqus>` root with `<thread>` and `<post>` elements)
+//! - WordPress WXR export (`<wp:comment>` blocks inside each `<item>`)
+//!
+//! Threads are matched to existing articles by slug: the WordPress post name,
+//! the last path segment of the thread link, or the Disqus thread identifier.
+//! Original authors, timestamps and reply nesting are preserved.
+
+use anyhow::{Context, Result};
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use std::collections::HashMap;
+
+use super::{find_elements, html_to_text, parse_timestamp, slug_from_url, xml_attr, xml_text};
+use crate::db::repositories::{ArticleRepository, CommentRepository};
+use crate::models::{CommentStatus, CreateCommentInput};
+
+/// Supported comment export formats
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum CommentExportFormat {
+    Disqus,
+    Wordpress,
+}
+
+/// Comment import result summary
+#[derive(Debug, Serialize)]
+pub struct CommentImportResult {
+    pub format: CommentExportFormat,
+    pub imported: usize,
+    pub skipped: usize,
+    pub errors: Vec<String>,
+    /// Articles that received comments, as `(id, slug)`
+    #[serde(skip)]
+    pub articles: Vec<(i64, String)>,
+}
+
+/// A discussion thread parsed from an export, with the keys used to find
+/// the matching article.
+#[derive(Debug, Default)]
+struct ParsedThread {
+    title: String,
+    slug_candidates: Vec<String>,
+    comments: Vec<ParsedComment>,
+}
+
+/// A single comment parsed from an export
+#[derive(Debug)]
+struct ParsedComment {
+    source_id: String,
+    parent_source_id: Option<String>,
+    author_name: Option<String>,
+    author_email: Option<String>,
+    content: String,
+    created_at: DateTime<Utc>,
+    status: CommentStatus,
+}
+
+/// Detect the export format from the document content
+fn detect_format(xml: &str) -> Option<CommentExportFormat> {
+    if xml.contains("<disqus") {
+        Some(CommentExportFormat::Disqus)
+    } else if xml.contains("<wp:comment>") || xml.contains("<rss") {
+        Some(CommentExportFormat::Wordpress)
+    } else {
+        None
+    }
+}
+
+/// Import comments from a Disqus or WordPress export.
+///
+/// Comments whose thread cannot be matched to an existing article are
+/// skipped and reported in `errors`.
+pub async fn import_comments(
+    articles: &dyn ArticleRepository,
+    comments: &dyn CommentRepository,
+    data: &[u8],
+) -> Result<CommentImportResult> {
+    let xml = std::str::from_utf8(data).context("Invalid UTF-8 in XML file")?;
+    let format = detect_format(xml).context(
+        "Unsupported file format. Expected a Disqus XML export or a WordPress WXR export.",
+    )?;
+    let threads = match format {
+        CommentExportFormat::Disqus => parse_disqus(xml),
+        CommentExportFormat::Wordpress => parse_wordpress(xml),
+    };
+
+    let mut result = CommentImportResult {
+        format,
+        imported: 0,
+        skipped: 0,
+        errors: Vec::new(),
+        articles: Vec::new(),
+    };
+
+    for mut thread in threads {
+        if thread.comments.is_empty() {
+            continue;
+        }
+
+        let mut article = None;
+        for slug in &thread.slug_candidates {
+            if let Some(found) = articles
+                .get_by_slug(slug)
+                .await
+                .context("Failed to look up article")?
+            {
+                article = Some(found);
+                break;
+            }
+        }
+        let Some(article) = article else {
+            result.skipped += thread.comments.len();
+            result.errors.push(format!(
+                "Skipped {} comment(s) on '{}': no matching article",
+                thread.comments.len(),
+                thread.title
+            ));
+            continue;
+        };
+
+        // Oldest first so parents are inserted before their replies
+        thread.comments.sort_by_key(|c| c.created_at);
+        let mut id_map: HashMap<String, i64> = HashMap::new();
+        let mut imported_here = 0;
+
+        for comment in thread.comments {
+            let parent_id = comment
+                .parent_source_id
+                .as_ref()
+                .and_then(|p| id_map.get(p).copied());
+            let input = CreateCommentInput {
+                article_id: article.id,
+                parent_id,
+                nickname: comment.author_name,
+                email: comment.author_email,
+                content: comment.content,
+            };
+            match comments
+                .create_imported(input, comment.status, comment.created_at)
+                .await
+            {
+                Ok(id) => {
+                    id_map.insert(comment.source_id, id);
+                    imported_here += 1;
+                }
+                Err(e) => {
+                    result.skipped += 1;
+                    result.errors.push(format!(
+                        "Failed to import comment {} on '{}': {}",
+                        comment.source_id, article.slug, e
+                    ));
+                }
+            }
+        }
+
+        if imported_here > 0 {
+            comments
+                .sync_comment_count(article.id)
+                .await
+                .context("Failed to update article comment count")?;
+            result.imported += imported_here;
+            result.articles.push((article.id, article.slug));
+        }
+    }
+
+    Ok(result)
+}
+
+/// Parse a Disqus XML export.
+///
+/// Threads and posts are siblings in the document; posts reference their
+/// thread and parent through `dsq:id` attributes.
+fn parse_disqus(xml: &str) -> Vec<ParsedThread> {
+    let mut order = Vec::new();
+    let mut threads: HashMap<String, ParsedThread> = HashMap::new();
+    // Posts reference their thread with a self-closing `<thread dsq:id="..."/>`;
+    // only full elements carrying a link are thread definitions.
+    for element in find_elements(xml, "thread") {
+        let (Some(id), Some(inner)) = (xml_attr(element.attrs, "dsq:id"), element.inner) else {
+            continue;
+        };
+        if threads.contains_key(&id) || !inner.contains("<link") {
+            continue;
+        }
+        let mut thread = ParsedThread {
+            title: xml_text(inner, "title").unwrap_or_else(|| id.clone()),
+            ..Default::default()
+        };
+        if let Some(slug) = xml_text(inner, "link").and_then(|l| slug_from_url(&l)) {
+            thread.slug_candidates.push(slug);
+        }
+        if let Some(identifier) = xml_text(inner, "id") {
+            let slug = slug_from_url(&identifier).unwrap_or(identifier);
+            if !thread.slug_candidates.contains(&slug) {
+                thread.slug_candidates.push(slug);
+            }
+        }
+        order.push(id.clone());
+        threads.insert(id, thread);
+    }
+
+    for post in find_elements(xml, "post") {
+        let Some(inner) = post.inner else { continue };
+        let Some(source_id) = xml_attr(post.attrs, "dsq:id") else {
+            continue;
+        };
+        if xml_text(inner, "isDeleted").as_deref() == Some("true") {
+            continue;
+        }
+        let Some(thread_id) = find_elements(inner, "thread")
+            .first()
+            .and_then(|t| xml_attr(t.attrs, "dsq:id"))
+        else {
+            continue;
+        };
+        let Some(thread) = threads.get_mut(&thread_id) else {
+            continue;
+        };
+        let content = html_to_text(&xml_text(inner, "message").unwrap_or_default());
+        if content.is_empty() {
+            continue;
+        }
+
+        let author = find_elements(inner, "author")
+            .into_iter()
+            .next()
+            .and_then(|a| a.inner)
+            .unwrap_or("");
+        let status = if xml_text(inner, "isSpam").as_deref() == Some("true") {
+            CommentStatus::Spam
+        } else {
+            CommentStatus::Approved
+        };
+
+        thread.comments.push(ParsedComment {
+            source_id,
+            parent_source_id: find_elements(inner, "parent")
+                .first()
+                .and_then(|p| xml_attr(p.attrs, "dsq:id")),
+            author_name: xml_text(author, "name").or_else(|| xml_text(author, "username")),
+            author_email: xml_text(author, "email"),
+            content,
+            created_at: xml_text(inner, "createdAt")
+                .and_then(|d| parse_timestamp(&d))
+                .unwrap_or_else(Utc::now),
+            status,
+        });
+    }
+
+    order
+        .into_iter()
+        .filter_map(|id| threads.remove(&id))
+        .collect()
+}
+
+/// Parse the comments of a WordPress WXR export.
+fn parse_wordpress(xml: &str) -> Vec<ParsedThread> {
+    let mut threads = Vec::new();
+
+    for item in find_elements(xml, "item") {
+        let Some(item) = item.inner else { continue };
+
+        let mut thread = ParsedThread {
+            title: xml_text(item, "title").unwrap_or_else(|| "Untitled".to_string()),
+            ..Default::default()
+        };
+        if let Some(slug) = xml_text(item, "wp:post_name") {
+            thread.slug_candidates.push(slug);
+        }
+        if let Some(slug) = xml_text(item, "link").and_then(|l| slug_from_url(&l)) {
+            if !thread.slug_candidates.contains(&slug) {
+                thread.slug_candidates.push(slug);
+            }
+        }
+
+        for comment in find_elements(item, "wp:comment") {
+            let Some(comment) = comment.inner else {
+                continue;
+            };
+            // Pingbacks and trackbacks are not reader comments
+            if matches!(
+                xml_text(comment, "wp:comment_type").as_deref(),
+                Some("pingback" | "trackback")
+            ) {
+                continue;
+            }
+            let status = match xml_text(comment, "wp:comment_approved").as_deref() {
+                Some("1") => CommentStatus::Approved,
+                Some("spam") => CommentStatus::Spam,
+                Some("trash") => continue,
+                _ => CommentStatus::Pending,
+            };
+            let Some(source_id) = xml_text(comment, "wp:comment_id") else {
+                continue;
+            };
+            let content =
+                html_to_text(&xml_text(comment, "wp:comment_content").unwrap_or_default());
+            if content.is_empty() {
+                continue;
+            }
+
+            thread.comments.push(ParsedComment {
+                source_id,
+                parent_source_id: xml_text(comment, "wp:comment_parent").filter(|p| p != "0"),
+                author_name: xml_text(comment, "wp:comment_author"),
+                author_email: xml_text(comment, "wp:comment_author_email"),
+                content,
+                created_at: xml_text(comment, "wp:comment_date_gmt")
+                    .and_then(|d| parse_timestamp(&d))
+                    .or_else(|| {
+                        xml_text(comment, "wp:comment_date").and_then(|d| parse_timestamp(&d))
+                    })
+                    .unwrap_or_else(Utc::now),
+                status,
+            });
+        }
+
+        threads.push(thread);
+    }
+
+    threads
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const DISQUS_SAMPLE: &str = r#"<?xml version="1.0" encoding="utf-8"?>
+<disqus xmlns="http://disqus.com" xmlns:dsq="http://disqus.com/disqus-internals">
+  <thread dsq:id="100">
+    <id>legacy-id</id>
+    <link>https://old.example.com/posts/hello-world/</link>
+    <title>Hello World</title>
+  </thread>
+  <post dsq:id="1">
+    <message><![CDATA[<p>First!</p>]]></message>
+    <createdAt>2015-03-01T10:00:00Z</createdAt>
+    <isDeleted>false</isDeleted>
+    <isSpam>false</isSpam>
+    <author><email>alice@example.com</email><name>Alice</name></author>
+    <thread dsq:id="100" />
+  </post>
+  <post dsq:id="2">
+    <message><![CDATA[<p>Reply</p>]]></message>
+    <createdAt>2015-03-01T11:00:00Z</createdAt>
+    <isDeleted>false</isDeleted>
+    <isSpam>true</isSpam>
+    <author><name>Bob</name></author>
+    <thread dsq:id="100" />
+    <parent dsq:id="1" />
+  </post>
+  <post dsq:id="3">
+    <message><![CDATA[gone]]></message>
+    <isDeleted>true</isDeleted>
+    <thread dsq:id="100" />
+  </post>
+</disqus>"#;
+
+    const WXR_SAMPLE: &str = r#"<?xml version="1.0" encoding="UTF-8" ?>
+<rss version="2.0" xmlns:wp="http://wordpress.org/export/1.2/">
+<channel>
+  <item>
+    <title>Hello World</title>
+    <link>https://old.example.com/2015/03/hello-world/</link>
+    <wp:post_name><![CDATA[hello-world]]></wp:post_name>
+    <wp:comment>
+      <wp:comment_id>7</wp:comment_id>
+      <wp:comment_author><![CDATA[Alice]]></wp:comment_author>
+      <wp:comment_author_email><![CDATA[alice@example.com]]></wp:comment_author_email>
+      <wp:comment_date_gmt><![CDATA[2015-03-01 10:00:00]]></wp:comment_date_gmt>
+      <wp:comment_content><![CDATA[Nice post]]></wp:comment_content>
+      <wp:comment_approved><![CDATA[1]]></wp:comment_approved>
+      <wp:comment_type><![CDATA[comment]]></wp:comment_type>
+      <wp:comment_parent>0</wp:comment_parent>
+    </wp:comment>
+    <wp:comment>
+      <wp:comment_id>8</wp:comment_id>
+      <wp:comment_author><![CDATA[Bob]]></wp:comment_author>
+      <wp:comment_date_gmt><![CDATA[2015-03-02 10:00:00]]></wp:comment_date_gmt>
+      <wp:comment_content><![CDATA[Thanks]]></wp:comment_content>
+      <wp:comment_approved><![CDATA[0]]></wp:comment_approved>
+      <wp:comment_parent>7</wp:comment_parent>
+    </wp:comment>
+    <wp:comment>
+      <wp:comment_id>9</wp:comment_id>
+      <wp:comment_content><![CDATA[Linked]]></wp:comment_content>
+      <wp:comment_approved><![CDATA[1]]></wp:comment_approved>
+      <wp:comment_type><![CDATA[pingback]]></wp:comment_type>
+    </wp:comment>
+  </item>
+</channel>
+</rss>"#;
+
+    #[test]
+    fn detects_export_format() {
+        assert_eq!(
+            detect_format(DISQUS_SAMPLE),
+            Some(CommentExportFormat::Disqus)
+        );
+        assert_eq!(
+            detect_format(WXR_SAMPLE),
+            Some(CommentExportFormat::Wordpress)
+        );
+        assert_eq!(detect_format("<html></html>"), None);
+    }
+
+    #[test]
+    fn parses_disqus_threads_and_posts() {
+        let threads = parse_disqus(DISQUS_SAMPLE);
+        assert_eq!(threads.len(), 1);
+        let thread = &threads[0];
+        assert_eq!(thread.title, "Hello World");
+        assert_eq!(thread.slug_candidates, vec!["hello-world", "legacy-id"]);
+        assert_eq!(thread.comments.len(), 2);
+
+        let first = &thread.comments[0];
+        assert_eq!(first.content, "First!");
+        assert_eq!(first.author_name.as_deref(), Some("Alice"));
+        assert_eq!(first.author_email.as_deref(), Some("alice@example.com"));
+        assert_eq!(first.status, CommentStatus::Approved);
+        assert_eq!(first.created_at.to_rfc3339(), "2015-03-01T10:00:00+00:00");
+
+        let reply = &thread.comments[1];
+        assert_eq!(reply.parent_source_id.as_deref(), Some("1"));
+        assert_eq!(reply.status, CommentStatus::Spam);
+    }
+
+    #[test]
+    fn parses_wordpress_comments() {
+        let threads = parse_wordpress(WXR_SAMPLE);
+        assert_eq!(threads.len(), 1);
+        let thread = &threads[0];
+        assert_eq!(thread.slug_candidates, vec!["hello-world"]);
+        // Pingback is dropped
+        assert_eq!(thread.comments.len(), 2);
+        assert_eq!(thread.comments[0].parent_source_id, None);
+        assert_eq!(thread.comments[0].status, CommentStatus::Approved);
+        assert_eq!(thread.comments[1].parent_source_id.as_deref(), Some("7"));
+        assert_eq!(thread.comments[1].status, CommentStatus::Pending);
+        assert_eq!(
+            thread.comments[1].created_at.to_rfc3339(),
+            "2015-03-02T10:00:00+00:00"
+        );
+    }
+}
@@ -0,0 +1,1020 @@
+//! Markdown importer for Hugo, Jekyll and Ghost sites
+//!
+//! Accepts one of:
+//! - a ZIP of Markdown files with YAML (`---`) or TOML (`+++`) front matter,
+//!   such as a zipped Hugo `content/` directory or Jekyll site
+//! - a single Markdown file with front matter
+//! - a Ghost JSON export (Settings → Advanced → Export content)
+//!
+//! Front matter `title`, `slug`, `date`, `tags` and `categories` (or
+//! `category`) are kept. Without a `slug` the file name is used: Jekyll's
+//! `YYYY-MM-DD-` prefix also supplies the date, and the `index.md` of a Hugo
+//! page bundle takes the directory name. The first category becomes the
+//! article category, created if needed; posts without one use the default.
+//!
+//! Anything unpublished becomes a draft: `draft: true` (Hugo), `published:
+//! false` or the `_drafts/` directory (Jekyll) and Ghost drafts. Posts dated
+//! in the future are scheduled. Files with `layout: page` or `type: page` and
+//! Ghost pages are imported as pages.
+//!
+//! Ghost posts keep their Markdown when the export has it; otherwise the
+//! rendered HTML is stored, as in the WordPress importer. Existing slugs are
+//! skipped, so an upload can be imported again after fixing errors.
+
+use anyhow::{bail, Context, Result};
+use chrono::{DateTime, NaiveDate, NaiveDateTime, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use std::collections::{HashMap, HashSet};
+use std::io::{Cursor, Read};
+
+use super::parse_timestamp;
+use crate::db::repositories::ArticleRepository;
+use crate::models::{ArticleStatus, CreateArticleInput, UpdateArticleInput};
+use crate::services::article::{ArticleService, ArticleServiceError};
+use crate::services::category::{generate_slug, CategoryService, CreateCategoryInput};
+use crate::services::page::PageService;
+use crate::services::tag::TagService;
+
+/// Most entries read from a ZIP
+const MAX_ZIP_ENTRIES: usize = 10_000;
+
+/// Largest Markdown file read from a ZIP
+const MAX_FILE_BYTES: u64 = 8 * 1024 * 1024;
+
+/// Largest total size of the Markdown files in a ZIP
+const MAX_TOTAL_BYTES: u64 = 256 * 1024 * 1024;
+
+/// Supported upload formats
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum MarkdownExportFormat {
+    #[default]
+    Markdown,
+    Ghost,
+}
+
+/// Markdown import result summary
+#[derive(Debug, Default, Serialize)]
+pub struct MarkdownImportResult {
+    pub format: MarkdownExportFormat,
+    pub categories: usize,
+    pub tags: usize,
+    pub posts: usize,
+    pub pages: usize,
+    /// Posts and pages imported as drafts
+    pub drafts: usize,
+    pub skipped: usize,
+    pub errors: Vec<String>,
+}
+
+/// Services used to import Markdown files or a Ghost export
+pub struct MarkdownImporter<'a> {
+    pub articles: &'a ArticleService,
+    pub article_repo: &'a dyn ArticleRepository,
+    pub categories: &'a CategoryService,
+    pub tags: &'a TagService,
+    pub pages: &'a PageService,
+    /// Owner of the imported posts
+    pub author_id: i64,
+}
+
+/// Tag name, with its slug when the source defines one
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Term {
+    name: String,
+    slug: Option<String>,
+}
+
+/// A post or page read from a Markdown file or a Ghost export
+#[derive(Debug, Default)]
+struct Document {
+    /// File name or Ghost post ID, for messages
+    source: String,
+    title: String,
+    slug: Option<String>,
+    content: String,
+    date: Option<DateTime<Utc>>,
+    tags: Vec<Term>,
+    categories: Vec<String>,
+    draft: bool,
+    page: bool,
+    thumbnail: Option<String>,
+}
+
+/// Documents parsed from an upload and the files that were skipped
+#[derive(Debug, Default)]
+struct ParsedUpload {
+    format: MarkdownExportFormat,
+    documents: Vec<Document>,
+    errors: Vec<String>,
+}
+
+impl MarkdownImporter<'_> {
+    /// Import a ZIP of Markdown files, a single Markdown file or a Ghost export
+    pub async fn import(&self, data: &[u8]) -> Result<MarkdownImportResult> {
+        let parsed = parse_upload(data)?;
+        let mut result = MarkdownImportResult {
+            format: parsed.format,
+            skipped: parsed.errors.len(),
+            errors: parsed.errors,
+            ..MarkdownImportResult::default()
+        };
+
+        let default_category = self
+            .categories
+            .get_default()
+            .await
+            .context("Failed to load default category")?
+            .map_or(1, |category| category.id);
+        let mut categories: HashMap<String, Option<i64>> = HashMap::new();
+        for category in self
+            .categories
+            .list()
+            .await
+            .context("Failed to load categories")?
+        {
+            categories.insert(category.slug, Some(category.id));
+            categories.insert(category.name, Some(category.id));
+        }
+        let mut tags = HashMap::new();
+        let now = Utc::now();
+
+        for doc in parsed.documents {
+            if doc.page {
+                self.import_page(doc, now, &mut result).await;
+                continue;
+            }
+            let category_id = match doc.categories.first() {
+                Some(name) => self
+                    .category_id(name, &mut categories, &mut result)
+                    .await
+                    .unwrap_or(default_category),
+                None => default_category,
+            };
+            let mut tag_ids = Vec::new();
+            for term in &doc.tags {
+                if let Some(id) = self.tag_id(term, &mut tags, &mut result).await {
+                    tag_ids.push(id);
+                }
+            }
+            self.import_post(doc, category_id, tag_ids, now, &mut result)
+                .await;
+        }
+
+        Ok(result)
+    }
+
+    /// Category ID for a name, creating the category on first use
+    async fn category_id(
+        &self,
+        name: &str,
+        ids: &mut HashMap<String, Option<i64>>,
+        result: &mut MarkdownImportResult,
+    ) -> Option<i64> {
+        let slug = generate_slug(name);
+        if let Some(id) = ids.get(name).or_else(|| ids.get(&slug)) {
+            return *id;
+        }
+        let id = match self
+            .categories
+            .create(CreateCategoryInput::new(name).with_slug(slug.clone()))
+            .await
+        {
+            Ok(created) => {
+                result.categories += 1;
+                Some(created.id)
+            }
+            Err(e) => {
+                result
+                    .errors
+                    .push(format!("Failed to import category '{}': {}", name, e));
+                None
+            }
+        };
+        ids.insert(name.to_string(), id);
+        ids.insert(slug, id);
+        id
+    }
+
+    /// Tag ID for a term, creating the tag on first use
+    async fn tag_id(
+        &self,
+        term: &Term,
+        ids: &mut HashMap<String, Option<i64>>,
+        result: &mut MarkdownImportResult,
+    ) -> Option<i64> {
+        if let Some(id) = ids.get(&term.name) {
+            return *id;
+        }
+        let existed = match &term.slug {
+            Some(slug) => matches!(self.tags.get_by_slug(slug).await, Ok(Some(_))),
+            None => false,
+        } || matches!(self.tags.exists_by_name(&term.name).await, Ok(true));
+        let tag = match &term.slug {
+            Some(slug) => self.tags.get_or_create_with_slug(slug, &term.name).await,
+            None => self.tags.create_or_get(&term.name).await,
+        };
+        let id = match tag {
+            Ok(tag) => {
+                if !existed {
+                    result.tags += 1;
+                }
+                Some(tag.id)
+            }
+            Err(e) => {
+                result
+                    .errors
+                    .push(format!("Failed to import tag '{}': {}", term.name, e));
+                None
+            }
+        };
+        ids.insert(term.name.clone(), id);
+        id
+    }
+
+    async fn import_page(
+        &self,
+        doc: Document,
+        now: DateTime<Utc>,
+        result: &mut MarkdownImportResult,
+    ) {
+        let slug = doc
+            .slug
+            .clone()
+            .unwrap_or_else(|| generate_slug(&doc.title));
+        // Pages cannot be scheduled, so future pages wait as drafts
+        let draft = doc.draft || doc.date.is_some_and(|date| date > now);
+        match self.pages.get_by_slug(&slug).await {
+            Ok(Some(_)) => {
+                result.skipped += 1;
+                result.errors.push(format!(
+                    "Skipped page '{}': slug '{}' already exists",
+                    doc.title, slug
+                ));
+                return;
+            }
+            Ok(None) => {}
+            Err(e) => {
+                result
+                    .errors
+                    .push(format!("Failed to import page '{}': {}", doc.title, e));
+                return;
+            }
+        }
+        let status = if draft { "draft" } else { "published" };
+        match self
+            .pages
+            .create(
+                slug,
+                doc.title.clone(),
+                doc.content,
+                Some(status.to_string()),
+            )
+            .await
+        {
+            Ok(_) => {
+                result.pages += 1;
+                if draft {
+                    result.drafts += 1;
+                }
+            }
+            Err(e) => result
+                .errors
+                .push(format!("Failed to import page '{}': {}", doc.title, e)),
+        }
+    }
+
+    async fn import_post(
+        &self,
+        doc: Document,
+        category_id: i64,
+        tag_ids: Vec<i64>,
+        now: DateTime<Utc>,
+        result: &mut MarkdownImportResult,
+    ) {
+        let (status, scheduled) = post_status(doc.draft, doc.date, now);
+        let mut input = CreateArticleInput::new(
+            doc.slug.clone().unwrap_or_default(),
+            doc.title.clone(),
+            doc.content,
+            self.author_id,
+            category_id,
+        )
+        .with_status(status);
+        input.scheduled_at = scheduled;
+
+        let article = match self.articles.create(input, Some(tag_ids)).await {
+            Ok(article) => article,
+            Err(ArticleServiceError::DuplicateSlug(slug)) => {
+                result.skipped += 1;
+                result.errors.push(format!(
+                    "Skipped '{}': slug '{}' already exists",
+                    doc.title, slug
+                ));
+                return;
+            }
+            Err(ArticleServiceError::ReservedSlug(slug)) => {
+                result.skipped += 1;
+                result.errors.push(format!(
+                    "Skipped '{}': slug '{}' was used by another article",
+                    doc.title, slug
+                ));
+                return;
+            }
+            Err(e) => {
+                result.errors.push(format!(
+                    "Failed to import '{}' ({}): {}",
+                    doc.title, doc.source, e
+                ));
+                return;
+            }
+        };
+        result.posts += 1;
+        if status == ArticleStatus::Draft {
+            result.drafts += 1;
+        }
+
+        if let Some(thumbnail) = doc.thumbnail {
+            let update = UpdateArticleInput {
+                thumbnail: Some(Some(thumbnail)),
+                ..UpdateArticleInput::default()
+            };
+            if let Err(e) = self.article_repo.update(article.id, &update).await {
+                result.errors.push(format!(
+                    "Failed to set the featured image of '{}': {}",
+                    doc.title, e
+                ));
+            }
+        }
+        if let Some(date) = doc.date.filter(|date| *date <= now) {
+            let published_at = (status == ArticleStatus::Published).then_some(date);
+            if let Err(e) = self
+                .article_repo
+                .set_imported_dates(article.id, date, published_at)
+                .await
+            {
+                result
+                    .errors
+                    .push(format!("Failed to set the date of '{}': {}", doc.title, e));
+            }
+        }
+        let _ = self
+            .articles
+            .invalidate_article_cache(article.id, &article.slug)
+            .await;
+    }
+}
+
+/// Article status for a document; future posts become scheduled drafts
+fn post_status(
+    draft: bool,
+    date: Option<DateTime<Utc>>,
+    now: DateTime<Utc>,
+) -> (ArticleStatus, Option<DateTime<Utc>>) {
+    match date {
+        _ if draft => (ArticleStatus::Draft, None),
+        Some(date) if date > now => (ArticleStatus::Draft, Some(date)),
+        _ => (ArticleStatus::Published, None),
+    }
+}
+
+/// Detect the upload format and parse it
+fn parse_upload(data: &[u8]) -> Result<ParsedUpload> {
+    if data.starts_with(b"PK\x03\x04") {
+        return parse_zip(data);
+    }
+    let text = std::str::from_utf8(data).context("Invalid UTF-8 in uploaded file")?;
+    let text = text.trim_start_matches('\u{feff}');
+    if text.trim_start().starts_with('{') {
+        return Ok(ParsedUpload {
+            format: MarkdownExportFormat::Ghost,
+            documents: parse_ghost(text)?,
+            errors: Vec::new(),
+        });
+    }
+    match parse_markdown("", text)? {
+        Some(doc) => Ok(ParsedUpload {
+            documents: vec![doc],
+            ..ParsedUpload::default()
+        }),
+        None => bail!(
+            "Unsupported file format. Expected a ZIP of Markdown files, a Markdown file with front matter or a Ghost JSON export."
+        ),
+    }
+}
+
+/// Parse every Markdown file in a ZIP; unreadable files are reported
+fn parse_zip(data: &[u8]) -> Result<ParsedUpload> {
+    let mut archive = zip::ZipArchive::new(Cursor::new(data)).context("Invalid ZIP file")?;
+    if archive.len() > MAX_ZIP_ENTRIES {
+        bail!("ZIP contains too many entries");
+    }
+
+    let mut parsed = ParsedUpload::default();
+    let mut total = 0u64;
+    for i in 0..archive.len() {
+        let file = archive.by_index(i)?;
+        let Some(path) = file.enclosed_name() else {
+            continue;
+        };
+        let name = path.to_string_lossy().replace('\\', "/");
+        let file_name = name.rsplit('/').next().unwrap_or_default();
+        // Hugo section lists (`_index.md`) have no article of their own
+        if file.is_dir()
+            || name.starts_with("__MACOSX/")
+            || file_name == "_index.md"
+            || !(name.ends_with(".md") || name.ends_with(".markdown"))
+        {
+            continue;
+        }
+
+        let mut bytes = Vec::new();
+        file.take(MAX_FILE_BYTES + 1).read_to_end(&mut bytes)?;
+        if bytes.len() as u64 > MAX_FILE_BYTES {
+            parsed
+                .errors
+                .push(format!("Skipped {}: file is too large", name));
+            continue;
+        }
+        total += bytes.len() as u64;
+        if total > MAX_TOTAL_BYTES {
+            bail!("ZIP uncompressed size is too large");
+        }
+        let Ok(text) = String::from_utf8(bytes) else {
+            parsed
+                .errors
+                .push(format!("Skipped {}: invalid UTF-8", name));
+            continue;
+        };
+        match parse_markdown(&name, &text) {
+            Ok(Some(doc)) => parsed.documents.push(doc),
+            Ok(None) => parsed
+                .errors
+                .push(format!("Skipped {}: no front matter", name)),
+            Err(e) => parsed.errors.push(format!("Skipped {}: {:#}", name, e)),
+        }
+    }
+
+    if parsed.documents.is_empty() && parsed.errors.is_empty() {
+        bail!("ZIP contains no Markdown files");
+    }
+    Ok(parsed)
+}
+
+/// Parse a Markdown file; `None` if it has no front matter
+fn parse_markdown(path: &str, text: &str) -> Result<Option<Document>> {
+    let Some((front, body)) = split_front_matter(text)? else {
+        return Ok(None);
+    };
+    let (path_slug, path_date, path_draft) = path_hints(path);
+
+    let slug = text_field(&front, &["slug"]).or(path_slug);
+    let date = text_field(&front, &["date", "publishDate", "pubDate"])
+        .and_then(|value| parse_date(&value))
+        .or(path_date);
+    let draft = path_draft
+        || bool_field(&front, "draft") == Some(true)
+        || bool_field(&front, "published") == Some(false)
+        || text_field(&front, &["status"]).is_some_and(|s| s.eq_ignore_ascii_case("draft"));
+    let page = ["layout", "type"]
+        .iter()
+        .any(|key| front.get(*key).and_then(Value::as_str) == Some("page"));
+    // Hugo themes often nest the image as `cover.image`
+    let thumbnail = [
+        "thumbnail",
+        "featured_image",
+        "feature_image",
+        "image",
+        "cover",
+    ]
+    .iter()
+    .filter_map(|key| front.get(*key))
+    .find_map(|value| value.as_str().or_else(|| value.get("image")?.as_str()))
+    .filter(|url| !url.trim().is_empty())
+    .map(|url| url.trim().to_string());
+
+    Ok(Some(Document {
+        source: path.to_string(),
+        title: text_field(&front, &["title"])
+            .or_else(|| slug.clone())
+            .unwrap_or_else(|| "Untitled".to_string()),
+        slug,
+        content: body.trim().to_string(),
+        date,
+        tags: list_field(&front, &["tags"])
+            .into_iter()
+            .map(|name| Term { name, slug: None })
+            .collect(),
+        categories: list_field(&front, &["categories", "category"]),
+        draft,
+        page,
+        thumbnail,
+    }))
+}
+
+/// Split YAML (`---`) or TOML (`+++`) front matter from the body
+fn split_front_matter(text: &str) -> Result<Option<(Map<String, Value>, &str)>> {
+    let text = text.trim_start_matches('\u{feff}');
+    let (first, rest) = text.split_once('\n').unwrap_or((text, ""));
+    let delimiter = first.trim_end();
+    if delimiter != "---" && delimiter != "+++" {
+        return Ok(None);
+    }
+
+    let mut offset = 0;
+    for line in rest.split_inclusive('\n') {
+        let trimmed = line.trim_end();
+        if trimmed == delimiter || (delimiter == "---" && trimmed == "...") {
+            let front = &rest[..offset];
+            let body = &rest[offset + line.len()..];
+            let value = if delimiter == "+++" {
+                let table: toml::Table =
+                    toml::from_str(front).context("Invalid TOML front matter")?;
+                toml_to_json(toml::Value::Table(table))
+            } else {
+                serde_yaml::from_str(front).context("Invalid YAML front matter")?
+            };
+            let map = match value {
+                Value::Object(map) => map,
+                Value::Null => Map::new(),
+                _ => bail!("Front matter is not a mapping"),
+            };
+            return Ok(Some((map, body)));
+        }
+        offset += line.len();
+    }
+    Ok(None)
+}
+
+fn toml_to_json(value: toml::Value) -> Value {
+    match value {
+        toml::Value::String(s) => Value::String(s),
+        toml::Value::Integer(i) => i.into(),
+        toml::Value::Float(f) => f.into(),
+        toml::Value::Boolean(b) => Value::Bool(b),
+        toml::Value::Datetime(dt) => Value::String(dt.to_string()),
+        toml::Value::Array(items) => items.into_iter().map(toml_to_json).collect(),
+        toml::Value::Table(table) => Value::Object(
+            table
+                .into_iter()
+                .map(|(key, value)| (key, toml_to_json(value)))
+                .collect(),
+        ),
+    }
+}
+
+/// Slug, date and draft flag implied by a file's path in the ZIP
+fn path_hints(path: &str) -> (Option<String>, Option<DateTime<Utc>>, bool) {
+    let parts: Vec<&str> = path.split('/').filter(|part| !part.is_empty()).collect();
+    let draft = parts.contains(&"_drafts");
+    let Some((file, dirs)) = parts.split_last() else {
+        return (None, None, draft);
+    };
+    let stem = file.rsplit_once('.').map_or(*file, |(stem, _)| stem);
+    // Hugo page bundles are named after their directory
+    let stem = match (stem, dirs.last()) {
+        ("index", Some(dir)) => *dir,
+        ("index", None) => return (None, None, draft),
+        _ => stem,
+    };
+    // Jekyll posts are named `YYYY-MM-DD-slug`
+    if let (Some(prefix), Some("-"), Some(slug)) =
+        (stem.get(..10), stem.get(10..11), stem.get(11..))
+    {
+        if let Ok(date) = NaiveDate::parse_from_str(prefix, "%Y-%m-%d") {
+            if !slug.is_empty() {
+                let date = date.and_hms_opt(0, 0, 0).map(|dt| dt.and_utc());
+                return (Some(slug.to_string()), date, draft);
+            }
+        }
+    }
+    (Some(stem.to_string()), None, draft)
+}
+
+/// First non-empty string (or number) under one of `keys`
+fn text_field(front: &Map<String, Value>, keys: &[&str]) -> Option<String> {
+    keys.iter()
+        .filter_map(|key| front.get(*key))
+        .find_map(|value| match value {
+            Value::String(s) if !s.trim().is_empty() => Some(s.trim().to_string()),
+            Value::Number(n) => Some(n.to_string()),
+            _ => None,
+        })
+}
+
+fn bool_field(front: &Map<String, Value>, key: &str) -> Option<bool> {
+    match front.get(key)? {
+        Value::Bool(b) => Some(*b),
+        Value::String(s) => s.trim().parse().ok(),
+        _ => None,
+    }
+}
+
+/// Strings from lists under `keys`; Jekyll also allows a space separated
+/// string, and comma separated strings are common elsewhere
+fn list_field(front: &Map<String, Value>, keys: &[&str]) -> Vec<String> {
+    let mut items = Vec::new();
+    for value in keys.iter().filter_map(|key| front.get(*key)) {
+        match value {
+            Value::String(s) if s.contains(',') => {
+                items.extend(s.split(',').map(|item| item.trim().to_string()))
+            }
+            Value::String(s) => items.extend(s.split_whitespace().map(str::to_string)),
+            Value::Array(list) => items.extend(list.iter().filter_map(|item| match item {
+                Value::String(s) => Some(s.trim().to_string()),
+                Value::Number(n) => Some(n.to_string()),
+                _ => None,
+            })),
+            _ => {}
+        }
+    }
+    let mut seen = HashSet::new();
+    items.retain(|item| !item.is_empty() && seen.insert(item.clone()));
+    items
+}
+
+/// Parse the date formats used by Hugo, Jekyll and Ghost; dates without a
+/// time zone are taken as UTC
+fn parse_date(value: &str) -> Option<DateTime<Utc>> {
+    let value = value.trim();
+    if let Some(date) = parse_timestamp(value) {
+        return Some(date);
+    }
+    if let Some(date) = ["%Y-%m-%d %H:%M:%S %z", "%Y-%m-%d %H:%M %z"]
+        .iter()
+        .find_map(|format| DateTime::parse_from_str(value, format).ok())
+    {
+        return Some(date.with_timezone(&Utc));
+    }
+    if let Some(date) = [
+        "%Y-%m-%dT%H:%M:%S%.f",
+        "%Y-%m-%d %H:%M:%S%.f",
+        "%Y-%m-%d %H:%M",
+    ]
+    .iter()
+    .find_map(|format| NaiveDateTime::parse_from_str(value, format).ok())
+    {
+        return Some(date.and_utc());
+    }
+    let date = NaiveDate::parse_from_str(value, "%Y-%m-%d").ok()?;
+    date.and_hms_opt(0, 0, 0).map(|dt| dt.and_utc())
+}
+
+/// Ghost exports are either `{"db": [{"data": …}]}` or a bare `{"data": …}`
+#[derive(Debug, Deserialize)]
+#[serde(untagged)]
+enum GhostFile {
+    Wrapped { db: Vec<GhostDatabase> },
+    Bare(GhostDatabase),
+}
+
+#[derive(Debug, Deserialize)]
+struct GhostDatabase {
+    data: GhostData,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
+struct GhostData {
+    posts: Vec<GhostPost>,
+    tags: Vec<GhostTag>,
+    posts_tags: Vec<GhostPostTag>,
+}
+
+/// IDs are strings in current exports and numbers in Ghost 0.x
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
+struct GhostPost {
+    id: Value,
+    title: Option<String>,
+    slug: Option<String>,
+    /// Ghost 0.x Markdown source
+    markdown: Option<String>,
+    mobiledoc: Option<String>,
+    html: Option<String>,
+    status: Option<String>,
+    #[serde(rename = "type")]
+    kind: Option<String>,
+    /// Ghost before 2.0 marks pages with `page: true` (or `1`)
+    page: Value,
+    published_at: Value,
+    created_at: Value,
+    feature_image: Option<String>,
+    /// Ghost 0.x name of `feature_image`
+    image: Option<String>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
+struct GhostTag {
+    id: Value,
+    name: String,
+    slug: Option<String>,
+    visibility: Option<String>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
+struct GhostPostTag {
+    post_id: Value,
+    tag_id: Value,
+    sort_order: i64,
+}
+
+/// Parse the posts and pages of a Ghost export with their tags
+fn parse_ghost(json: &str) -> Result<Vec<Document>> {
+    let file: GhostFile = serde_json::from_str(json).context("Invalid Ghost export")?;
+    let databases = match file {
+        GhostFile::Wrapped { db } => db,
+        GhostFile::Bare(database) => vec![database],
+    };
+
+    let mut documents = Vec::new();
+    for data in databases.into_iter().map(|database| database.data) {
+        // Internal tags (`#name`) only drive theme features
+        let tags: HashMap<String, &GhostTag> = data
+            .tags
+            .iter()
+            .filter(|tag| {
+                !tag.name.starts_with('#') && tag.visibility.as_deref() != Some("internal")
+            })
+            .map(|tag| (value_key(&tag.id), tag))
+            .collect();
+        let mut post_tags: HashMap<String, Vec<(i64, Term)>> = HashMap::new();
+        for link in &data.posts_tags {
+            if let Some(tag) = tags.get(&value_key(&link.tag_id)) {
+                post_tags
+                    .entry(value_key(&link.post_id))
+                    .or_default()
+                    .push((
+                        link.sort_order,
+                        Term {
+                            name: tag.name.clone(),
+                            slug: tag.slug.clone(),
+                        },
+                    ));
+            }
+        }
+
+        for post in data.posts {
+            let id = value_key(&post.id);
+            let mut terms = post_tags.remove(&id).unwrap_or_default();
+            terms.sort_by_key(|(order, _)| *order);
+            let content = ghost_content(&post).replace("__GHOST_URL__", "");
+            let status = post.status.as_deref().unwrap_or("draft");
+            documents.push(Document {
+                source: format!("Ghost post {}", id),
+                title: post
+                    .title
+                    .filter(|title| !title.trim().is_empty())
+                    .unwrap_or_else(|| "Untitled".to_string()),
+                slug: post.slug.filter(|slug| !slug.is_empty()),
+                content,
+                date: ghost_date(&post.published_at).or_else(|| ghost_date(&post.created_at)),
+                tags: terms.into_iter().map(|(_, term)| term).collect(),
+                categories: Vec::new(),
+                draft: !matches!(status, "published" | "scheduled"),
+                page: post.kind.as_deref() == Some("page")
+                    || post.page == Value::Bool(true)
+                    || post.page.as_i64() == Some(1),
+                thumbnail: post
+                    .feature_image
+                    .or(post.image)
+                    .map(|url| url.replace("__GHOST_URL__", "")),
+            });
+        }
+    }
+    Ok(documents)
+}
+
+/// Post content: the Ghost 0.x Markdown, the Markdown cards of a Mobiledoc
+/// document made only of them, or else the rendered HTML
+fn ghost_content(post: &GhostPost) -> String {
+    post.markdown
+        .clone()
+        .filter(|markdown| !markdown.trim().is_empty())
+        .or_else(|| post.mobiledoc.as_deref().and_then(mobiledoc_markdown))
+        .or_else(|| post.html.clone())
+        .unwrap_or_default()
+}
+
+fn mobiledoc_markdown(mobiledoc: &str) -> Option<String> {
+    let doc: Value = serde_json::from_str(mobiledoc).ok()?;
+    let cards = doc.get("cards")?.as_array()?;
+    let mut parts = Vec::new();
+    for section in doc.get("sections")?.as_array()? {
+        // Card sections are `[10, <card index>]`
+        let section = section.as_array()?;
+        if section.first()?.as_i64()? != 10 {
+            return None;
+        }
+        let card = cards.get(section.get(1)?.as_u64()? as usize)?.as_array()?;
+        if !matches!(card.first()?.as_str()?, "markdown" | "card-markdown") {
+            return None;
+        }
+        parts.push(card.get(1)?.get("markdown")?.as_str()?.trim().to_string());
+    }
+    (!parts.is_empty()).then(|| parts.join("\n\n"))
+}
+
+/// Ghost dates are ISO strings, or milliseconds in Ghost 0.x
+fn ghost_date(value: &Value) -> Option<DateTime<Utc>> {
+    match value {
+        Value::String(s) => parse_date(s),
+        Value::Number(n) => DateTime::from_timestamp_millis(n.as_i64()?),
+        _ => None,
+    }
+}
+
+fn value_key(value: &Value) -> String {
+    match value {
+        Value::String(s) => s.clone(),
+        other => other.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+    use std::io::Write;
+
+    fn utc(y: i32, m: u32, d: u32, h: u32, min: u32) -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(y, m, d, h, min, 0).unwrap()
+    }
+
+    #[test]
+    fn parses_front_matter_and_file_names() {
+        let jekyll = "---\ntitle: Hello World\ntags: rust web\ncategories:\n  - Notes\n---\n\nBody **text**\n";
+        let doc = parse_markdown("_posts/2021-03-04-hello-world.md", jekyll)
+            .unwrap()
+            .unwrap();
+        assert_eq!(doc.title, "Hello World");
+        assert_eq!(doc.slug.as_deref(), Some("hello-world"));
+        assert_eq!(doc.date, Some(utc(2021, 3, 4, 0, 0)));
+        assert_eq!(doc.content, "Body **text**");
+        let tags: Vec<&str> = doc.tags.iter().map(|t| t.name.as_str()).collect();
+        assert_eq!(tags, ["rust", "web"]);
+        assert_eq!(doc.categories, ["Notes"]);
+        assert!(!doc.draft && !doc.page);
+
+        let hugo = "+++\ntitle = \"Bundle\"\ndate = 2022-05-06T07:08:00+02:00\ndraft = true\ntags = [\"Go\", \"Go\"]\n[cover]\nimage = \"cover.png\"\n+++\nText\n";
+        let doc = parse_markdown("content/posts/my-bundle/index.md", hugo)
+            .unwrap()
+            .unwrap();
+        assert_eq!(doc.slug.as_deref(), Some("my-bundle"));
+        assert_eq!(doc.date, Some(utc(2022, 5, 6, 5, 8)));
+        assert!(doc.draft);
+        assert_eq!(doc.tags.len(), 1);
+        assert_eq!(doc.thumbnail.as_deref(), Some("cover.png"));
+
+        let doc = parse_markdown("_drafts/idea.md", "---\ntitle: Idea\n---\n")
+            .unwrap()
+            .unwrap();
+        assert!(doc.draft);
+        let doc = parse_markdown(
+            "about.md",
+            "---\nlayout: page\nslug: about-me\ndate: 2020-01-02 10:00:00 +0100\ncategory: a, b\n---\nMe",
+        )
+        .unwrap()
+        .unwrap();
+        assert!(doc.page);
+        assert_eq!(doc.slug.as_deref(), Some("about-me"));
+        assert_eq!(doc.date, Some(utc(2020, 1, 2, 9, 0)));
+        assert_eq!(doc.categories, ["a", "b"]);
+
+        assert!(parse_markdown("README.md", "# Readme").unwrap().is_none());
+        assert!(parse_markdown("bad.md", "---\n: [\n---\n").is_err());
+    }
+
+    #[test]
+    fn parses_zip_of_markdown_files() {
+        let mut zip = zip::ZipWriter::new(Cursor::new(Vec::new()));
+        let options = zip::write::SimpleFileOptions::default();
+        for (name, content) in [
+            ("site/content/posts/first.md", "---\ntitle: First\n---\nOne"),
+            ("site/content/posts/_index.md", "---\ntitle: Posts\n---\n"),
+            ("site/README.md", "# Site"),
+            ("site/static/logo.png", "png"),
+        ] {
+            zip.start_file(name, options).unwrap();
+            zip.write_all(content.as_bytes()).unwrap();
+        }
+        let data = zip.finish().unwrap().into_inner();
+
+        let parsed = parse_upload(&data).unwrap();
+        assert_eq!(parsed.format, MarkdownExportFormat::Markdown);
+        assert_eq!(parsed.documents.len(), 1);
+        assert_eq!(parsed.documents[0].slug.as_deref(), Some("first"));
+        assert_eq!(parsed.errors, ["Skipped site/README.md: no front matter"]);
+
+        let single = parse_upload(b"---\ntitle: Single\n---\nText").unwrap();
+        assert_eq!(single.documents[0].title, "Single");
+        assert_eq!(single.documents[0].slug, None);
+        assert!(parse_upload(b"just text").is_err());
+    }
+
+    #[test]
+    fn parses_ghost_export() {
+        let mobiledoc = serde_json::json!({
+            "version": "0.3.1",
+            "cards": [["markdown", {"markdown": "# Hi\n\n![](__GHOST_URL__/content/images/a.png)"}]],
+            "sections": [[10, 0]]
+        });
+        let export = serde_json::json!({
+            "db": [{
+                "meta": {"version": "4.0.0"},
+                "data": {
+                    "posts": [
+                        {"id": "p1", "title": "Hello", "slug": "hello", "mobiledoc": mobiledoc.to_string(),
+                         "html": "<h1>Hi</h1>", "status": "published", "type": "post",
+                         "published_at": "2021-01-02T03:04:05.000Z", "feature_image": "https://cdn.example.com/a.png"},
+                        {"id": "p2", "title": "Soon", "slug": "soon", "html": "<p>Soon</p>",
+                         "status": "scheduled", "type": "post", "published_at": "2999-01-01T00:00:00.000Z"},
+                        {"id": 3, "title": "About", "slug": "about", "markdown": "About me",
+                         "status": "draft", "page": 1, "created_at": 1600000000000i64}
+                    ],
+                    "tags": [
+                        {"id": "t1", "name": "News", "slug": "news"},
+                        {"id": "t2", "name": "#hash", "slug": "hash-hash"},
+                        {"id": "t3", "name": "Rust", "slug": "rust-lang"}
+                    ],
+                    "posts_tags": [
+                        {"post_id": "p1", "tag_id": "t3", "sort_order": 1},
+                        {"post_id": "p1", "tag_id": "t1", "sort_order": 0},
+                        {"post_id": "p1", "tag_id": "t2", "sort_order": 2}
+                    ]
+                }
+            }]
+        });
+        let parsed = parse_upload(export.to_string().as_bytes()).unwrap();
+        assert_eq!(parsed.format, MarkdownExportFormat::Ghost);
+        let [hello, soon, about] = parsed.documents.as_slice() else {
+            panic!("expected three documents");
+        };
+
+        assert_eq!(hello.content, "# Hi\n\n![](/content/images/a.png)");
+        assert_eq!(
+            hello.date,
+            Some(Utc.with_ymd_and_hms(2021, 1, 2, 3, 4, 5).unwrap())
+        );
+        let tags: Vec<(&str, Option<&str>)> = hello
+            .tags
+            .iter()
+            .map(|t| (t.name.as_str(), t.slug.as_deref()))
+            .collect();
+        assert_eq!(tags, [("News", Some("news")), ("Rust", Some("rust-lang"))]);
+        assert_eq!(
+            hello.thumbnail.as_deref(),
+            Some("https://cdn.example.com/a.png")
+        );
+        assert!(!hello.draft && !hello.page);
+
+        assert_eq!(soon.content, "<p>Soon</p>");
+        assert!(!soon.draft);
+        assert_eq!(
+            post_status(soon.draft, soon.date, utc(2024, 1, 1, 0, 0)),
+            (ArticleStatus::Draft, soon.date)
+        );
+
+        assert!(about.page && about.draft);
+        assert_eq!(about.content, "About me");
+        assert_eq!(about.date, DateTime::from_timestamp_millis(1600000000000));
+    }
+
+    #[test]
+    fn dates_and_statuses() {
+        assert_eq!(parse_date("2020-01-02"), Some(utc(2020, 1, 2, 0, 0)));
+        assert_eq!(
+            parse_date("2020-01-02 10:30"),
+            Some(utc(2020, 1, 2, 10, 30))
+        );
+        assert_eq!(
+            parse_date("2020-01-02 10:30:00 -0500"),
+            Some(utc(2020, 1, 2, 15, 30))
+        );
+        assert_eq!(
+            parse_date("2020-01-02T10:30:00Z"),
+            Some(utc(2020, 1, 2, 10, 30))
+        );
+        assert_eq!(parse_date("yesterday"), None);
+
+        let now = utc(2024, 6, 1, 0, 0);
+        let past = Some(utc(2024, 1, 1, 0, 0));
+        let future = Some(utc(2025, 1, 1, 0, 0));
+        assert_eq!(
+            post_status(false, past, now),
+            (ArticleStatus::Published, None)
+        );
+        assert_eq!(
+            post_status(false, None, now),
+            (ArticleStatus::Published, None)
+        );
+        assert_eq!(
+            post_status(false, future, now),
+            (ArticleStatus::Draft, future)
+        );
+        assert_eq!(post_status(true, future, now), (ArticleStatus::Draft, None));
+    }
+}
@@ -0,0 +1,273 @@
+//! Importers for content exported from other blogging platforms
+//!
+//! - `comments`: Disqus XML and WordPress WXR comment exports
+//! - `markdown`: Hugo / Jekyll Markdown files and Ghost JSON exports
+//! - `wordpress`: posts, pages, taxonomies, authors and media from a WordPress WXR export
+//!
+//! Exports are parsed with small string-based helpers instead of a full XML
+//! parser; the formats involved are machine-generated and predictable.
+
+use chrono::{DateTime, NaiveDateTime, Utc};
+
+pub mod comments;
+pub mod markdown;
+pub mod wordpress;
+
+pub use comments::{import_comments, CommentImportResult};
+pub use markdown::{MarkdownImportResult, MarkdownImporter};
+pub use wordpress::{WordpressImportResult, WordpressImporter};
+
+/// A single XML element found by [`find_elements`].
+struct XmlElement<'a> {
+    /// Raw attribute text of the opening tag
+    attrs: &'a str,
+    /// Inner content, `None` for self-closing elements
+    inner: Option<&'a str>,
+}
+
+/// Find all (non-nested) occurrences of `<tag ...>...</tag>` in `xml`.
+///
+/// Self-closing elements (`<tag ... />`) are returned with no inner content.
+fn find_elements<'a>(xml: &'a str, tag: &str) -> Vec<XmlElement<'a>> {
+    let open = format!("<{}", tag);
+    let close = format!("</{}>", tag);
+    let mut elements = Vec::new();
+    let mut pos = 0;
+
+    while let Some(found) = xml[pos..].find(&open) {
+        let start = pos + found + open.len();
+        // Make sure we matched the whole tag name, not a prefix of another tag
+        match xml[start..].chars().next() {
+            Some(c) if c.is_whitespace() || c == '>' || c == '/' => {}
+            _ => {
+                pos = start;
+                continue;
+            }
+        }
+        let Some(tag_end) = xml[start..].find('>') else {
+            break;
+        };
+        let tag_end = start + tag_end;
+        let attrs = xml[start..tag_end].trim();
+
+        if let Some(attrs) = attrs.strip_suffix('/') {
+            elements.push(XmlElement {
+                attrs: attrs.trim(),
+                inner: None,
+            });
+            pos = tag_end + 1;
+            continue;
+        }
+
+        let body_start = tag_end + 1;
+        let Some(body_len) = xml[body_start..].find(&close) else {
+            break;
+        };
+        elements.push(XmlElement {
+            attrs,
+            inner: Some(&xml[body_start..body_start + body_len]),
+        });
+        pos = body_start + body_len + close.len();
+    }
+
+    elements
+}
+
+/// Read an attribute value from the raw attribute text of an opening tag.
+fn xml_attr(attrs: &str, name: &str) -> Option<String> {
+    let needle = format!("{}=\"", name);
+    let mut pos = 0;
+    while let Some(found) = attrs[pos..].find(&needle) {
+        let start = pos + found;
+        // Reject matches in the middle of another attribute name
+        let boundary = attrs[..start]
+            .chars()
+            .next_back()
+            .is_none_or(char::is_whitespace);
+        let value_start = start + needle.len();
+        if boundary {
+            let end = attrs[value_start..].find('"')? + value_start;
+            return Some(decode_entities(&attrs[value_start..end]));
+        }
+        pos = value_start;
+    }
+    None
+}
+
+/// Text content of the first `<tag>` element, with CDATA unwrapped and
+/// entities decoded. Empty elements yield `None`.
+fn xml_text(xml: &str, tag: &str) -> Option<String> {
+    let element = find_elements(xml, tag).into_iter().next()?;
+    inner_text(element.inner?)
+}
+
+/// Element content with CDATA unwrapped and entities decoded; `None` if empty.
+fn inner_text(inner: &str) -> Option<String> {
+    let inner = inner.trim();
+    let text = match inner.strip_prefix("<![CDATA[") {
+        Some(stripped) => stripped.strip_suffix("]]>").unwrap_or(stripped).to_string(),
+        None => decode_entities(inner),
+    };
+    let text = text.trim().to_string();
+    if text.is_empty() {
+        None
+    } else {
+        Some(text)
+    }
+}
+
+/// Decode the predefined XML entities plus numeric character references.
+fn decode_entities(text: &str) -> String {
+    if !text.contains('&') {
+        return text.to_string();
+    }
+
+    let mut out = String::with_capacity(text.len());
+    let mut rest = text;
+    while let Some(amp) = rest.find('&') {
+        out.push_str(&rest[..amp]);
+        rest = &rest[amp..];
+        let Some(semi) = rest.find(';').filter(|&i| i <= 10) else {
+            out.push('&');
+            rest = &rest[1..];
+            continue;
+        };
+        let entity = &rest[1..semi];
+        let decoded = match entity {
+            "amp" => Some('&'),
+            "lt" => Some('<'),
+            "gt" => Some('>'),
+            "quot" => Some('"'),
+            "apos" => Some('\''),
+            _ => entity
+                .strip_prefix("#x")
+                .or_else(|| entity.strip_prefix("#X"))
+                .and_then(|hex| u32::from_str_radix(hex, 16).ok())
+                .or_else(|| entity.strip_prefix('#').and_then(|d| d.parse().ok()))
+                .and_then(char::from_u32),
+        };
+        match decoded {
+            Some(c) => {
+                out.push(c);
+                rest = &rest[semi + 1..];
+            }
+            None => {
+                out.push('&');
+                rest = &rest[1..];
+            }
+        }
+    }
+    out.push_str(rest);
+    out
+}
+
+/// Reduce HTML markup to plain text, keeping paragraph and line breaks.
+fn html_to_text(html: &str) -> String {
+    let mut out = String::with_capacity(html.len());
+    let mut rest = html;
+    while let Some(lt) = rest.find('<') {
+        out.push_str(&rest[..lt]);
+        let Some(gt) = rest[lt..].find('>') else {
+            out.push_str(&rest[lt..]);
+            rest = "";
+            break;
+        };
+        let tag = rest[lt + 1..lt + gt].trim().to_ascii_lowercase();
+        let name = tag
+            .trim_start_matches('/')
+            .split(|c: char| c.is_whitespace() || c == '/')
+            .next()
+            .unwrap_or("");
+        match name {
+            "br" => out.push('\n'),
+            "p" | "div" | "blockquote" | "pre" if tag.starts_with('/') => out.push_str("\n\n"),
+            _ => {}
+        }
+        rest = &rest[lt + gt + 1..];
+    }
+    out.push_str(rest);
+
+    let text = decode_entities(&out);
+    let mut lines: Vec<&str> = text.lines().map(str::trim_end).collect();
+    // Collapse runs of blank lines left behind by block elements
+    lines.dedup_by(|a, b| a.is_empty() && b.is_empty());
+    lines.join("\n").trim().to_string()
+}
+
+/// Parse RFC 3339 (Disqus) or `YYYY-MM-DD HH:MM:SS` (WordPress, UTC) timestamps
+fn parse_timestamp(value: &str) -> Option<DateTime<Utc>> {
+    if let Ok(dt) = DateTime::parse_from_rfc3339(value) {
+        return Some(dt.with_timezone(&Utc));
+    }
+    NaiveDateTime::parse_from_str(value, "%Y-%m-%d %H:%M:%S")
+        .or_else(|_| NaiveDateTime::parse_from_str(value, "%Y-%m-%dT%H:%M:%S"))
+        .ok()
+        .map(|dt| dt.and_utc())
+}
+
+/// Candidate article slug derived from a permalink: its last path segment.
+fn slug_from_url(url: &str) -> Option<String> {
+    let path = url.split(['?', '#']).next().unwrap_or("");
+    let path = path.split_once("://").map_or(path, |(_, rest)| {
+        rest.split_once('/').map_or("", |(_, p)| p)
+    });
+    path.split('/')
+        .rev()
+        .find(|segment| !segment.is_empty())
+        .map(|segment| {
+            segment
+                .trim_end_matches(".html")
+                .trim_end_matches(".htm")
+                .to_string()
+        })
+        .filter(|segment| !segment.is_empty())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn find_elements_distinguishes_self_closing() {
+        let xml = r#"<a x="1">one</a><ab>skip</ab><a x="2" />"#;
+        let elements = find_elements(xml, "a");
+        assert_eq!(elements.len(), 2);
+        assert_eq!(elements[0].inner, Some("one"));
+        assert_eq!(xml_attr(elements[1].attrs, "x").as_deref(), Some("2"));
+        assert!(elements[1].inner.is_none());
+    }
+
+    #[test]
+    fn xml_text_unwraps_cdata_and_entities() {
+        assert_eq!(
+            xml_text("<t><![CDATA[a & b]]></t>", "t").as_deref(),
+            Some("a & b")
+        );
+        assert_eq!(
+            xml_text("<t>a &amp; &#233;</t>", "t").as_deref(),
+            Some("a & é")
+        );
+        assert_eq!(xml_text("<t></t>", "t"), None);
+    }
+
+    #[test]
+    fn html_is_reduced_to_text() {
+        assert_eq!(
+            html_to_text("<p>Hello <b>there</b></p><p>Line<br/>two &lt;3</p>"),
+            "Hello there\n\nLine\ntwo <3"
+        );
+    }
+
+    #[test]
+    fn slug_is_taken_from_last_path_segment() {
+        assert_eq!(
+            slug_from_url("https://blog.example.com/posts/hello-world/?utm=x").as_deref(),
+            Some("hello-world")
+        );
+        assert_eq!(
+            slug_from_url("https://example.com/2020/01/post.html#c1").as_deref(),
+            Some("post")
+        );
+        assert_eq!(slug_from_url("https://example.com/"), None);
+    }
+}
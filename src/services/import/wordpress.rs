@@ -0,0 +1,946 @@
+//! WordPress importer
+//!
+//! Ingests a WXR export (Tools → Export → All content):
+//! - Authors are matched to existing users by login or email; unknown authors
+//!   get an account with an unusable password (a password reset or SSO
+//!   sign-in activates it). Authors without an email fall back to the admin
+//!   running the import.
+//! - Categories (with their hierarchy) and tags keep their slugs.
+//! - Posts and pages keep their slugs, status and dates. The first category
+//!   of a post becomes its category; posts without one use the default.
+//! - Media under `wp-content/uploads/` on the exported site is downloaded to
+//!   `uploads/wordpress/` and links in the content are rewritten. Featured
+//!   images become article thumbnails.
+//!
+//! Post HTML is stored as the article Markdown: Markdown passes block-level
+//! HTML through, so the rendered article matches the original markup. Only
+//! the Gutenberg block comments are removed.
+//!
+//! Existing slugs are skipped, so an export can be imported again after
+//! fixing errors. Comments are imported separately with `import_comments`.
+
+use anyhow::{bail, Context, Result};
+use chrono::{DateTime, Utc};
+use futures::stream::{self, StreamExt};
+use once_cell::sync::Lazy;
+use regex::Regex;
+use serde::Serialize;
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::path::Path;
+use std::time::Duration;
+
+use super::{find_elements, inner_text, parse_timestamp, xml_attr, xml_text};
+use crate::db::repositories::ArticleRepository;
+use crate::models::{ArticleStatus, CreateArticleInput, UpdateArticleInput, UserRole};
+use crate::services::article::{ArticleService, ArticleServiceError};
+use crate::services::category::{CategoryService, CreateCategoryInput};
+use crate::services::outbound::{ensure_public_url, public_client_builder};
+use crate::services::page::PageService;
+use crate::services::tag::TagService;
+use crate::services::user::{ProvisionOutcome, ProvisionUserInput, UserService};
+
+/// Timeout for downloading a single media file
+const MEDIA_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Media files downloaded at the same time
+const MEDIA_CONCURRENCY: usize = 4;
+
+/// Upload subdirectory holding imported media
+const MEDIA_DIR: &str = "wordpress";
+
+static UPLOAD_URL_RE: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(
+        r#"(?i)(?:https?:)?//[^\s"'<>()/]+(?:/[^\s"'<>()]*?)?/wp-content/uploads/[^\s"'<>()?#]+"#,
+    )
+    .expect("valid upload URL regex")
+});
+static BLOCK_COMMENT_RE: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"(?s)<!-- /?wp:.*?-->\n?").expect("valid block comment regex"));
+
+/// WordPress import result summary
+#[derive(Debug, Default, Serialize)]
+pub struct WordpressImportResult {
+    /// User accounts created for authors
+    pub authors: usize,
+    pub categories: usize,
+    pub tags: usize,
+    pub posts: usize,
+    pub pages: usize,
+    /// Media files downloaded
+    pub media: usize,
+    pub skipped: usize,
+    pub errors: Vec<String>,
+}
+
+/// Services and settings used to import a WordPress export
+pub struct WordpressImporter<'a> {
+    pub articles: &'a ArticleService,
+    pub article_repo: &'a dyn ArticleRepository,
+    pub categories: &'a CategoryService,
+    pub tags: &'a TagService,
+    pub pages: &'a PageService,
+    pub users: &'a UserService,
+    /// Admin running the import; owns posts of authors without an email
+    pub actor_id: i64,
+    /// Upload directory; media goes to its `wordpress/` subdirectory
+    pub uploads: &'a Path,
+    /// Largest media file downloaded, in bytes
+    pub max_file_size: u64,
+    /// Download media; otherwise links keep pointing at the old site
+    pub download_media: bool,
+}
+
+/// Author from `<wp:author>`
+#[derive(Debug)]
+struct WxrAuthor {
+    login: String,
+    email: Option<String>,
+    display_name: Option<String>,
+}
+
+/// Category or tag; categories may name a parent slug
+#[derive(Debug, Clone)]
+struct WxrTerm {
+    slug: String,
+    name: String,
+    parent: Option<String>,
+    description: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ItemKind {
+    Post,
+    Page,
+    Attachment,
+}
+
+/// A post, page or attachment `<item>`
+#[derive(Debug)]
+struct WxrItem {
+    kind: ItemKind,
+    id: String,
+    title: String,
+    slug: Option<String>,
+    creator: Option<String>,
+    content: String,
+    status: String,
+    date: Option<DateTime<Utc>>,
+    categories: Vec<String>,
+    tags: Vec<String>,
+    attachment_url: Option<String>,
+    thumbnail_id: Option<String>,
+}
+
+#[derive(Debug, Default)]
+struct WxrExport {
+    /// Hosts of the exported site, used to recognise its media URLs
+    hosts: HashSet<String>,
+    authors: Vec<WxrAuthor>,
+    categories: Vec<WxrTerm>,
+    tags: Vec<WxrTerm>,
+    items: Vec<WxrItem>,
+}
+
+impl WordpressImporter<'_> {
+    /// Import everything in a WXR export
+    pub async fn import(&self, data: &[u8]) -> Result<WordpressImportResult> {
+        let xml = std::str::from_utf8(data).context("Invalid UTF-8 in XML file")?;
+        if !xml.contains("<rss") || !xml.contains("wordpress.org/export") {
+            bail!("Unsupported file format. Expected a WordPress WXR export.");
+        }
+        let export = parse_wxr(xml);
+        let mut result = WordpressImportResult::default();
+
+        let authors = self.import_authors(&export.authors, &mut result).await;
+        let categories = self
+            .import_categories(&export.categories, &mut result)
+            .await;
+        let tags = self.import_tags(&export.tags, &mut result).await;
+        let media = if self.download_media {
+            self.download_all_media(&export, &mut result).await
+        } else {
+            HashMap::new()
+        };
+        let attachments: HashMap<&str, &str> = export
+            .items
+            .iter()
+            .filter(|item| item.kind == ItemKind::Attachment)
+            .filter_map(|item| Some((item.id.as_str(), item.attachment_url.as_deref()?)))
+            .collect();
+        let default_category = self
+            .categories
+            .get_default()
+            .await
+            .context("Failed to load default category")?
+            .map_or(1, |category| category.id);
+
+        for item in &export.items {
+            let author_id = item
+                .creator
+                .as_ref()
+                .and_then(|login| authors.get(login))
+                .copied()
+                .unwrap_or(self.actor_id);
+            let content = rewrite_content(&item.content, &media);
+            match item.kind {
+                ItemKind::Attachment => {}
+                ItemKind::Page => self.import_page(item, content, &mut result).await,
+                ItemKind::Post => {
+                    let category_id = item
+                        .categories
+                        .iter()
+                        .find_map(|slug| categories.get(slug))
+                        .copied()
+                        .unwrap_or(default_category);
+                    let tag_ids = item
+                        .tags
+                        .iter()
+                        .filter_map(|slug| tags.get(slug))
+                        .copied()
+                        .collect();
+                    let thumbnail = item
+                        .thumbnail_id
+                        .as_deref()
+                        .and_then(|id| attachments.get(id))
+                        .map(|url| media.get(*url).cloned().unwrap_or_else(|| url.to_string()));
+                    let post = ImportedPost {
+                        author_id,
+                        category_id,
+                        tag_ids,
+                        content,
+                        thumbnail,
+                    };
+                    self.import_post(item, post, &mut result).await
+                }
+            }
+        }
+
+        Ok(result)
+    }
+
+    /// Map author logins to user IDs, creating accounts as needed
+    async fn import_authors(
+        &self,
+        authors: &[WxrAuthor],
+        result: &mut WordpressImportResult,
+    ) -> HashMap<String, i64> {
+        let mut ids = HashMap::new();
+        for author in authors {
+            match self.users.get_by_username(&author.login).await {
+                Ok(Some(user)) => {
+                    ids.insert(author.login.clone(), user.id);
+                    continue;
+                }
+                Ok(None) => {}
+                Err(e) => {
+                    result.errors.push(format!(
+                        "Failed to look up author '{}': {}",
+                        author.login, e
+                    ));
+                    continue;
+                }
+            }
+            let Some(email) = &author.email else {
+                result.errors.push(format!(
+                    "Author '{}' has no email; their posts are assigned to you",
+                    author.login
+                ));
+                continue;
+            };
+            let input = ProvisionUserInput {
+                email: email.clone(),
+                username: Some(author.login.clone()),
+                display_name: author.display_name.clone(),
+                role: Some(UserRole::Author),
+                active: true,
+            };
+            match self.users.provision_user(self.actor_id, input).await {
+                Ok((outcome, Some(user))) => {
+                    if outcome == ProvisionOutcome::Created {
+                        result.authors += 1;
+                    }
+                    ids.insert(author.login.clone(), user.id);
+                }
+                Ok((_, None)) => {}
+                Err(e) => result
+                    .errors
+                    .push(format!("Failed to import author '{}': {}", author.login, e)),
+            }
+        }
+        ids
+    }
+
+    /// Map category slugs to IDs, creating parents before their children
+    async fn import_categories(
+        &self,
+        terms: &[WxrTerm],
+        result: &mut WordpressImportResult,
+    ) -> HashMap<String, i64> {
+        let mut ids = HashMap::new();
+        let mut pending: Vec<&WxrTerm> = terms.iter().collect();
+        while !pending.is_empty() {
+            // Terms whose parent is part of the export but not imported yet
+            let (waiting, ready): (Vec<&WxrTerm>, Vec<&WxrTerm>) =
+                pending.iter().copied().partition(|term| {
+                    term.parent.as_ref().is_some_and(|parent| {
+                        !ids.contains_key(parent) && pending.iter().any(|t| &t.slug == parent)
+                    })
+                });
+            // A cycle leaves nothing ready; import what is left as roots
+            let (ready, waiting) = if ready.is_empty() {
+                (waiting, Vec::new())
+            } else {
+                (ready, waiting)
+            };
+            for term in ready {
+                let parent_id = term.parent.as_ref().and_then(|p| ids.get(p)).copied();
+                match self.category_id(term, parent_id, result).await {
+                    Ok(id) => {
+                        ids.insert(term.slug.clone(), id);
+                    }
+                    Err(e) => result
+                        .errors
+                        .push(format!("Failed to import category '{}': {}", term.name, e)),
+                }
+            }
+            pending = waiting;
+        }
+        ids
+    }
+
+    async fn category_id(
+        &self,
+        term: &WxrTerm,
+        parent_id: Option<i64>,
+        result: &mut WordpressImportResult,
+    ) -> Result<i64> {
+        if let Some(existing) = self.categories.get_by_slug(&term.slug).await? {
+            return Ok(existing.id);
+        }
+        let mut input = CreateCategoryInput::new(term.name.clone());
+        input.slug = Some(term.slug.clone());
+        input.description = term.description.clone();
+        input.parent_id = parent_id;
+        let created = self.categories.create(input).await?;
+        result.categories += 1;
+        Ok(created.id)
+    }
+
+    /// Map tag slugs to IDs
+    async fn import_tags(
+        &self,
+        terms: &[WxrTerm],
+        result: &mut WordpressImportResult,
+    ) -> HashMap<String, i64> {
+        let mut ids = HashMap::new();
+        for term in terms {
+            let existed = matches!(self.tags.get_by_slug(&term.slug).await, Ok(Some(_)));
+            match self
+                .tags
+                .get_or_create_with_slug(&term.slug, &term.name)
+                .await
+            {
+                Ok(tag) => {
+                    if !existed && tag.slug == term.slug {
+                        result.tags += 1;
+                    }
+                    ids.insert(term.slug.clone(), tag.id);
+                }
+                Err(e) => result
+                    .errors
+                    .push(format!("Failed to import tag '{}': {}", term.name, e)),
+            }
+        }
+        ids
+    }
+
+    /// Download media of the exported site, returning original → local URLs
+    async fn download_all_media(
+        &self,
+        export: &WxrExport,
+        result: &mut WordpressImportResult,
+    ) -> HashMap<String, String> {
+        let mut urls = BTreeMap::new();
+        for item in &export.items {
+            let found = item
+                .attachment_url
+                .iter()
+                .map(String::as_str)
+                .chain(UPLOAD_URL_RE.find_iter(&item.content).map(|m| m.as_str()));
+            for url in found {
+                if let Some(local) = media_path(url, &export.hosts) {
+                    urls.insert(url.to_string(), local);
+                }
+            }
+        }
+
+        let client = match public_client_builder().timeout(MEDIA_TIMEOUT).build() {
+            Ok(client) => client,
+            Err(e) => {
+                result
+                    .errors
+                    .push(format!("Failed to create HTTP client: {}", e));
+                return HashMap::new();
+            }
+        };
+
+        let downloads = stream::iter(urls)
+            .map(|(url, local)| {
+                let client = &client;
+                async move {
+                    let outcome = self.download(client, &url, &local).await;
+                    (url, local, outcome)
+                }
+            })
+            .buffer_unordered(MEDIA_CONCURRENCY)
+            .collect::<Vec<_>>()
+            .await;
+
+        let mut media = HashMap::new();
+        for (url, local, outcome) in downloads {
+            match outcome {
+                Ok(downloaded) => {
+                    if downloaded {
+                        result.media += 1;
+                    }
+                    media.insert(url, format!("/uploads/{}", local));
+                }
+                Err(e) => result
+                    .errors
+                    .push(format!("Failed to download {}: {:#}", url, e)),
+            }
+        }
+        media
+    }
+
+    /// Fetch `url` into the upload directory; `false` if it was already there
+    async fn download(&self, client: &reqwest::Client, url: &str, local: &str) -> Result<bool> {
+        let dest = self.uploads.join(local);
+        if tokio::fs::try_exists(&dest).await.unwrap_or(false) {
+            return Ok(false);
+        }
+        let absolute = match url.strip_prefix("//") {
+            Some(rest) => format!("https://{}", rest),
+            None => url.to_string(),
+        };
+        let target = ensure_public_url(&absolute).await?;
+        let mut response = client.get(target).send().await?.error_for_status()?;
+        if response
+            .content_length()
+            .is_some_and(|len| len > self.max_file_size)
+        {
+            bail!("File is larger than {} bytes", self.max_file_size);
+        }
+        let mut data = Vec::new();
+        while let Some(chunk) = response.chunk().await? {
+            if (data.len() + chunk.len()) as u64 > self.max_file_size {
+                bail!("File is larger than {} bytes", self.max_file_size);
+            }
+            data.extend_from_slice(&chunk);
+        }
+        if let Some(dir) = dest.parent() {
+            tokio::fs::create_dir_all(dir).await?;
+        }
+        tokio::fs::write(&dest, &data)
+            .await
+            .with_context(|| format!("Failed to save {}", dest.display()))?;
+        Ok(true)
+    }
+
+    async fn import_page(
+        &self,
+        item: &WxrItem,
+        content: String,
+        result: &mut WordpressImportResult,
+    ) {
+        let Some(status) = page_status(&item.status) else {
+            return;
+        };
+        let slug = item
+            .slug
+            .clone()
+            .unwrap_or_else(|| format!("page-{}", item.id));
+        match self.pages.get_by_slug(&slug).await {
+            Ok(Some(_)) => {
+                result.skipped += 1;
+                result.errors.push(format!(
+                    "Skipped page '{}': slug '{}' already exists",
+                    item.title, slug
+                ));
+                return;
+            }
+            Ok(None) => {}
+            Err(e) => {
+                result
+                    .errors
+                    .push(format!("Failed to import page '{}': {}", item.title, e));
+                return;
+            }
+        }
+        match self
+            .pages
+            .create(slug, item.title.clone(), content, Some(status.to_string()))
+            .await
+        {
+            Ok(_) => result.pages += 1,
+            Err(e) => result
+                .errors
+                .push(format!("Failed to import page '{}': {}", item.title, e)),
+        }
+    }
+
+    async fn import_post(
+        &self,
+        item: &WxrItem,
+        post: ImportedPost,
+        result: &mut WordpressImportResult,
+    ) {
+        let Some((status, scheduled)) = post_status(&item.status, item.date) else {
+            return;
+        };
+        let mut input = CreateArticleInput::new(
+            item.slug.clone().unwrap_or_default(),
+            item.title.clone(),
+            post.content,
+            post.author_id,
+            post.category_id,
+        )
+        .with_status(status);
+        input.scheduled_at = scheduled;
+
+        let article = match self.articles.create(input, Some(post.tag_ids)).await {
+            Ok(article) => article,
+            Err(ArticleServiceError::DuplicateSlug(slug)) => {
+                result.skipped += 1;
+                result.errors.push(format!(
+                    "Skipped '{}': slug '{}' already exists",
+                    item.title, slug
+                ));
+                return;
+            }
+            Err(ArticleServiceError::ReservedSlug(slug)) => {
+                result.skipped += 1;
+                result.errors.push(format!(
+                    "Skipped '{}': slug '{}' was used by another article",
+                    item.title, slug
+                ));
+                return;
+            }
+            Err(e) => {
+                result
+                    .errors
+                    .push(format!("Failed to import '{}': {}", item.title, e));
+                return;
+            }
+        };
+        result.posts += 1;
+
+        if let Some(thumbnail) = post.thumbnail {
+            let update = UpdateArticleInput {
+                thumbnail: Some(Some(thumbnail)),
+                ..UpdateArticleInput::default()
+            };
+            if let Err(e) = self.article_repo.update(article.id, &update).await {
+                result.errors.push(format!(
+                    "Failed to set the featured image of '{}': {}",
+                    item.title, e
+                ));
+            }
+        }
+        if let Some(date) = item.date {
+            let published_at = (status == ArticleStatus::Published).then_some(date);
+            if let Err(e) = self
+                .article_repo
+                .set_imported_dates(article.id, date, published_at)
+                .await
+            {
+                result
+                    .errors
+                    .push(format!("Failed to set the date of '{}': {}", item.title, e));
+            }
+        }
+        let _ = self
+            .articles
+            .invalidate_article_cache(article.id, &article.slug)
+            .await;
+    }
+}
+
+/// Per-post values resolved against the imported authors, terms and media
+struct ImportedPost {
+    author_id: i64,
+    category_id: i64,
+    tag_ids: Vec<i64>,
+    content: String,
+    thumbnail: Option<String>,
+}
+
+/// Article status for a WordPress post status; `None` skips the post.
+/// Future posts become drafts scheduled for their date.
+fn post_status(
+    status: &str,
+    date: Option<DateTime<Utc>>,
+) -> Option<(ArticleStatus, Option<DateTime<Utc>>)> {
+    match status {
+        "publish" => Some((ArticleStatus::Published, None)),
+        "future" => Some((ArticleStatus::Draft, date)),
+        "draft" | "pending" | "private" => Some((ArticleStatus::Draft, None)),
+        _ => None,
+    }
+}
+
+/// Page status for a WordPress post status; `None` skips the page
+fn page_status(status: &str) -> Option<&'static str> {
+    match status {
+        "publish" => Some("published"),
+        "future" | "draft" | "pending" | "private" => Some("draft"),
+        _ => None,
+    }
+}
+
+/// Parse the parts of a WXR export this importer uses
+fn parse_wxr(xml: &str) -> WxrExport {
+    let mut export = WxrExport::default();
+    let channel = xml.split("<item>").next().unwrap_or(xml);
+
+    for tag in ["wp:base_site_url", "wp:base_blog_url", "link"] {
+        if let Some(host) = xml_text(channel, tag).as_deref().and_then(url_host) {
+            export.hosts.insert(host);
+        }
+    }
+
+    for author in find_elements(channel, "wp:author") {
+        let Some(inner) = author.inner else { continue };
+        let Some(login) = xml_text(inner, "wp:author_login") else {
+            continue;
+        };
+        export.authors.push(WxrAuthor {
+            login,
+            email: xml_text(inner, "wp:author_email"),
+            display_name: xml_text(inner, "wp:author_display_name"),
+        });
+    }
+
+    for category in find_elements(channel, "wp:category") {
+        let Some(inner) = category.inner else {
+            continue;
+        };
+        let Some(slug) = xml_text(inner, "wp:category_nicename").map(|s| decode_slug(&s)) else {
+            continue;
+        };
+        export.categories.push(WxrTerm {
+            name: xml_text(inner, "wp:cat_name").unwrap_or_else(|| slug.clone()),
+            parent: xml_text(inner, "wp:category_parent").map(|s| decode_slug(&s)),
+            description: xml_text(inner, "wp:category_description"),
+            slug,
+        });
+    }
+
+    for tag in find_elements(channel, "wp:tag") {
+        let Some(inner) = tag.inner else { continue };
+        let Some(slug) = xml_text(inner, "wp:tag_slug").map(|s| decode_slug(&s)) else {
+            continue;
+        };
+        export.tags.push(WxrTerm {
+            name: xml_text(inner, "wp:tag_name").unwrap_or_else(|| slug.clone()),
+            slug,
+            parent: None,
+            description: None,
+        });
+    }
+
+    for item in find_elements(xml, "item") {
+        let Some(inner) = item.inner else { continue };
+        let kind = match xml_text(inner, "wp:post_type").as_deref() {
+            Some("post") => ItemKind::Post,
+            Some("page") => ItemKind::Page,
+            Some("attachment") => ItemKind::Attachment,
+            _ => continue,
+        };
+        let Some(id) = xml_text(inner, "wp:post_id") else {
+            continue;
+        };
+
+        let mut categories = Vec::new();
+        let mut tags = Vec::new();
+        for term in find_elements(inner, "category") {
+            let (Some(slug), Some(name)) = (
+                xml_attr(term.attrs, "nicename").map(|s| decode_slug(&s)),
+                term.inner.and_then(inner_text),
+            ) else {
+                continue;
+            };
+            let (list, known) = match xml_attr(term.attrs, "domain").as_deref() {
+                Some("category") => (&mut categories, &mut export.categories),
+                Some("post_tag") => (&mut tags, &mut export.tags),
+                _ => continue,
+            };
+            // Terms only referenced by items are still imported
+            if !known.iter().any(|t| t.slug == slug) {
+                known.push(WxrTerm {
+                    slug: slug.clone(),
+                    name,
+                    parent: None,
+                    description: None,
+                });
+            }
+            list.push(slug);
+        }
+
+        let thumbnail_id = find_elements(inner, "wp:postmeta")
+            .into_iter()
+            .filter_map(|meta| meta.inner)
+            .find(|meta| xml_text(meta, "wp:meta_key").as_deref() == Some("_thumbnail_id"))
+            .and_then(|meta| xml_text(meta, "wp:meta_value"));
+
+        export.items.push(WxrItem {
+            kind,
+            title: xml_text(inner, "title").unwrap_or_else(|| "Untitled".to_string()),
+            slug: xml_text(inner, "wp:post_name").map(|s| decode_slug(&s)),
+            creator: xml_text(inner, "dc:creator"),
+            content: xml_text(inner, "content:encoded").unwrap_or_default(),
+            status: xml_text(inner, "wp:status").unwrap_or_default(),
+            date: xml_text(inner, "wp:post_date_gmt")
+                .and_then(|d| parse_timestamp(&d))
+                .or_else(|| xml_text(inner, "wp:post_date").and_then(|d| parse_timestamp(&d))),
+            categories,
+            tags,
+            attachment_url: xml_text(inner, "wp:attachment_url"),
+            thumbnail_id,
+            id,
+        });
+    }
+
+    for url in export
+        .items
+        .iter()
+        .filter_map(|item| item.attachment_url.as_deref())
+    {
+        if let Some(host) = url_host(url) {
+            export.hosts.insert(host);
+        }
+    }
+
+    export
+}
+
+/// WordPress stores non-ASCII slugs percent-encoded
+fn decode_slug(slug: &str) -> String {
+    urlencoding::decode(slug)
+        .map(|decoded| decoded.into_owned())
+        .unwrap_or_else(|_| slug.to_string())
+}
+
+fn url_host(url: &str) -> Option<String> {
+    let rest = url.split_once("//")?.1;
+    let host = rest.split(['/', '?', '#']).next()?;
+    let host = host.rsplit('@').next()?.split(':').next()?;
+    (!host.is_empty()).then(|| host.to_ascii_lowercase())
+}
+
+/// Path below the upload directory for a media URL of the exported site.
+///
+/// `https://blog.example.com/wp-content/uploads/2020/01/a.jpg` becomes
+/// `wordpress/2020/01/a.jpg`. Other hosts, unsafe names and active content
+/// are rejected.
+fn media_path(url: &str, hosts: &HashSet<String>) -> Option<String> {
+    if !hosts.contains(&url_host(url)?) {
+        return None;
+    }
+    let (_, rest) = url.split_once("/wp-content/uploads/")?;
+    let rest = rest.split(['?', '#']).next()?;
+    let segments: Vec<&str> = rest.split('/').collect();
+    let safe = segments.iter().all(|segment| {
+        !segment.is_empty()
+            && !segment.starts_with('.')
+            && segment
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.'))
+    });
+    let ext = rest.rsplit_once('.')?.1.to_ascii_lowercase();
+    let active = matches!(
+        ext.as_str(),
+        "html" | "htm" | "xhtml" | "js" | "mjs" | "svg" | "xml"
+    );
+    (safe && !active).then(|| format!("{}/{}", MEDIA_DIR, segments.join("/")))
+}
+
+/// Drop Gutenberg block comments and point media links at the local copies
+fn rewrite_content(html: &str, media: &HashMap<String, String>) -> String {
+    let html = BLOCK_COMMENT_RE.replace_all(html, "");
+    if media.is_empty() {
+        return html.trim().to_string();
+    }
+    UPLOAD_URL_RE
+        .replace_all(&html, |caps: &regex::Captures| {
+            let url = &caps[0];
+            media.get(url).cloned().unwrap_or_else(|| url.to_string())
+        })
+        .trim()
+        .to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const WXR_SAMPLE: &str = r#"<?xml version="1.0" encoding="UTF-8" ?>
+<rss version="2.0"
+  xmlns:content="http://purl.org/rss/1.0/modules/content/"
+  xmlns:dc="http://purl.org/dc/elements/1.1/"
+  xmlns:wp="http://wordpress.org/export/1.2/">
+<channel>
+  <title>Old Blog</title>
+  <link>https://old.example.com</link>
+  <wp:base_site_url>https://old.example.com</wp:base_site_url>
+  <wp:author>
+    <wp:author_id>1</wp:author_id>
+    <wp:author_login><![CDATA[alice]]></wp:author_login>
+    <wp:author_email><![CDATA[alice@example.com]]></wp:author_email>
+    <wp:author_display_name><![CDATA[Alice]]></wp:author_display_name>
+  </wp:author>
+  <wp:category>
+    <wp:term_id>3</wp:term_id>
+    <wp:category_nicename><![CDATA[rust]]></wp:category_nicename>
+    <wp:category_parent><![CDATA[programming]]></wp:category_parent>
+    <wp:cat_name><![CDATA[Rust]]></wp:cat_name>
+  </wp:category>
+  <wp:category>
+    <wp:term_id>2</wp:term_id>
+    <wp:category_nicename><![CDATA[programming]]></wp:category_nicename>
+    <wp:category_parent><![CDATA[]]></wp:category_parent>
+    <wp:cat_name><![CDATA[Programming]]></wp:cat_name>
+  </wp:category>
+  <wp:tag>
+    <wp:term_id>5</wp:term_id>
+    <wp:tag_slug><![CDATA[async]]></wp:tag_slug>
+    <wp:tag_name><![CDATA[Async]]></wp:tag_name>
+  </wp:tag>
+  <item>
+    <title>Hello &amp; welcome</title>
+    <dc:creator><![CDATA[alice]]></dc:creator>
+    <content:encoded><![CDATA[<!-- wp:paragraph -->
+<p>Hi <img src="https://old.example.com/wp-content/uploads/2020/01/cat-300x200.jpg" /></p>
+<!-- /wp:paragraph -->]]></content:encoded>
+    <wp:post_id>10</wp:post_id>
+    <wp:post_date_gmt><![CDATA[2020-01-02 03:04:05]]></wp:post_date_gmt>
+    <wp:post_name><![CDATA[%e4%bd%a0%e5%a5%bd]]></wp:post_name>
+    <wp:status><![CDATA[publish]]></wp:status>
+    <wp:post_type><![CDATA[post]]></wp:post_type>
+    <category domain="category" nicename="rust"><![CDATA[Rust]]></category>
+    <category domain="post_tag" nicename="async"><![CDATA[Async]]></category>
+    <category domain="post_tag" nicename="tokio"><![CDATA[Tokio]]></category>
+    <wp:postmeta>
+      <wp:meta_key><![CDATA[_thumbnail_id]]></wp:meta_key>
+      <wp:meta_value><![CDATA[11]]></wp:meta_value>
+    </wp:postmeta>
+  </item>
+  <item>
+    <title>Cat</title>
+    <wp:post_id>11</wp:post_id>
+    <wp:post_name><![CDATA[cat]]></wp:post_name>
+    <wp:status><![CDATA[inherit]]></wp:status>
+    <wp:post_type><![CDATA[attachment]]></wp:post_type>
+    <wp:attachment_url><![CDATA[https://old.example.com/wp-content/uploads/2020/01/cat.jpg]]></wp:attachment_url>
+  </item>
+  <item>
+    <title>About</title>
+    <wp:post_id>12</wp:post_id>
+    <wp:post_name><![CDATA[about]]></wp:post_name>
+    <wp:status><![CDATA[draft]]></wp:status>
+    <wp:post_type><![CDATA[page]]></wp:post_type>
+  </item>
+  <item>
+    <title>Menu</title>
+    <wp:post_id>13</wp:post_id>
+    <wp:post_type><![CDATA[nav_menu_item]]></wp:post_type>
+  </item>
+</channel>
+</rss>"#;
+
+    #[test]
+    fn parses_wxr_export() {
+        let export = parse_wxr(WXR_SAMPLE);
+        assert!(export.hosts.contains("old.example.com"));
+        assert_eq!(export.authors.len(), 1);
+        assert_eq!(export.authors[0].login, "alice");
+        assert_eq!(
+            export.authors[0].email.as_deref(),
+            Some("alice@example.com")
+        );
+
+        assert_eq!(export.categories.len(), 2);
+        assert_eq!(export.categories[0].parent.as_deref(), Some("programming"));
+        assert_eq!(export.categories[1].parent, None);
+        // "tokio" is only referenced by the post
+        let tags: Vec<&str> = export.tags.iter().map(|t| t.slug.as_str()).collect();
+        assert_eq!(tags, ["async", "tokio"]);
+
+        assert_eq!(export.items.len(), 3);
+        let post = &export.items[0];
+        assert_eq!(post.kind, ItemKind::Post);
+        assert_eq!(post.title, "Hello & welcome");
+        assert_eq!(post.slug.as_deref(), Some("你好"));
+        assert_eq!(post.creator.as_deref(), Some("alice"));
+        assert_eq!(post.categories, ["rust"]);
+        assert_eq!(post.tags, ["async", "tokio"]);
+        assert_eq!(post.thumbnail_id.as_deref(), Some("11"));
+        assert_eq!(post.date.unwrap().to_rfc3339(), "2020-01-02T03:04:05+00:00");
+        assert_eq!(export.items[1].kind, ItemKind::Attachment);
+        assert_eq!(export.items[2].kind, ItemKind::Page);
+    }
+
+    #[test]
+    fn media_paths_stay_inside_the_upload_directory() {
+        let hosts = HashSet::from(["old.example.com".to_string()]);
+        assert_eq!(
+            media_path(
+                "https://old.example.com/wp-content/uploads/2020/01/cat.jpg?ver=2",
+                &hosts
+            )
+            .as_deref(),
+            Some("wordpress/2020/01/cat.jpg")
+        );
+        assert_eq!(
+            media_path("//old.example.com/blog/wp-content/uploads/a.png", &hosts).as_deref(),
+            Some("wordpress/a.png")
+        );
+        assert!(media_path("https://cdn.other.com/wp-content/uploads/a.png", &hosts).is_none());
+        assert!(media_path(
+            "https://old.example.com/wp-content/uploads/../a.png",
+            &hosts
+        )
+        .is_none());
+        assert!(media_path("https://old.example.com/wp-content/uploads/x.svg", &hosts).is_none());
+    }
+
+    #[test]
+    fn content_is_cleaned_and_media_rewritten() {
+        let export = parse_wxr(WXR_SAMPLE);
+        let url = "https://old.example.com/wp-content/uploads/2020/01/cat-300x200.jpg";
+        let media = HashMap::from([(
+            url.to_string(),
+            "/uploads/wordpress/2020/01/cat-300x200.jpg".to_string(),
+        )]);
+        assert_eq!(
+            rewrite_content(&export.items[0].content, &media),
+            "<p>Hi <img src=\"/uploads/wordpress/2020/01/cat-300x200.jpg\" /></p>"
+        );
+    }
+
+    #[test]
+    fn statuses_map_to_article_and_page_statuses() {
+        let date = Utc::now();
+        assert_eq!(
+            post_status("publish", Some(date)),
+            Some((ArticleStatus::Published, None))
+        );
+        assert_eq!(
+            post_status("future", Some(date)),
+            Some((ArticleStatus::Draft, Some(date)))
+        );
+        assert_eq!(post_status("trash", None), None);
+        assert_eq!(page_status("private"), Some("draft"));
+        assert_eq!(page_status("auto-draft"), None);
+    }
+}
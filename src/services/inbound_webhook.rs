@@ -0,0 +1,286 @@
+//! Inbound webhooks for third-party integrations
+//!
+//! Admins create endpoints at `/api/v1/hooks/in/{token}`. Accepted requests
+//! are queued and handed to plugins through the `external_webhook` action
+//! hook by a background worker, so senders get a quick `202` no matter how
+//! long plugins take. The queue is in memory; events still queued when the
+//! process stops are lost.
+
+use anyhow::Result;
+use axum::http::HeaderMap;
+use data_encoding::{BASE64URL_NOPAD, HEXLOWER_PERMISSIVE};
+use hmac::{Hmac, Mac};
+use serde_json::{json, Map, Value};
+use sha2::Sha256;
+use std::sync::Arc;
+use tokio::sync::mpsc;
+
+use crate::db::repositories::InboundWebhookRepository;
+use crate::models::{CreateInboundWebhookInput, InboundWebhook, SignatureScheme};
+use crate::plugin::{hook_names, HookManager};
+
+/// Events waiting for plugins before new ones are refused
+const QUEUE_CAPACITY: usize = 256;
+
+/// Longest accepted endpoint name
+const MAX_NAME_LEN: usize = 100;
+
+/// Headers never passed on to plugins
+const REDACTED_HEADERS: &[&str] = &["authorization", "cookie", "x-csrf-token"];
+
+/// Errors returned by the inbound webhook service
+#[derive(Debug, thiserror::Error)]
+pub enum InboundWebhookError {
+    /// Unknown token
+    #[error("Webhook not found")]
+    NotFound,
+
+    /// Missing or wrong signature
+    #[error("Invalid webhook signature")]
+    InvalidSignature,
+
+    /// Plugins are not keeping up
+    #[error("Webhook queue is full")]
+    QueueFull,
+
+    /// Invalid endpoint settings
+    #[error("{0}")]
+    Validation(String),
+
+    /// Internal error
+    #[error("Internal error: {0}")]
+    Internal(#[from] anyhow::Error),
+}
+
+/// Inbound webhook endpoints and the queue feeding plugins
+pub struct InboundWebhookService {
+    repo: Arc<dyn InboundWebhookRepository>,
+    queue: mpsc::Sender<Value>,
+}
+
+impl InboundWebhookService {
+    /// Create the service and start the worker delivering queued events
+    pub fn new(repo: Arc<dyn InboundWebhookRepository>, hook_manager: Arc<HookManager>) -> Self {
+        let (queue, mut events) = mpsc::channel::<Value>(QUEUE_CAPACITY);
+        tokio::spawn(async move {
+            while let Some(event) = events.recv().await {
+                // WASM hooks run in a subprocess and block
+                let hm = hook_manager.clone();
+                let result = tokio::task::spawn_blocking(move || {
+                    hm.trigger(hook_names::EXTERNAL_WEBHOOK, event);
+                })
+                .await;
+                if let Err(e) = result {
+                    tracing::error!(error = %e, "external_webhook hook panicked");
+                }
+            }
+        });
+        Self { repo, queue }
+    }
+
+    /// Create an endpoint with a random token
+    pub async fn create(
+        &self,
+        input: CreateInboundWebhookInput,
+    ) -> Result<InboundWebhook, InboundWebhookError> {
+        let name = input.name.trim();
+        if name.is_empty() || name.chars().count() > MAX_NAME_LEN {
+            return Err(InboundWebhookError::Validation(format!(
+                "Name must be 1-{} characters",
+                MAX_NAME_LEN
+            )));
+        }
+        let secret = input
+            .secret
+            .as_deref()
+            .map(str::trim)
+            .filter(|s| !s.is_empty());
+        if input.signature != SignatureScheme::None && secret.is_none() {
+            return Err(InboundWebhookError::Validation(
+                "A secret is required for signed webhooks".to_string(),
+            ));
+        }
+
+        Ok(self
+            .repo
+            .create(name, &generate_token(), input.signature, secret)
+            .await?)
+    }
+
+    /// All endpoints
+    pub async fn list(&self) -> Result<Vec<InboundWebhook>, InboundWebhookError> {
+        Ok(self.repo.list().await?)
+    }
+
+    /// Delete an endpoint
+    pub async fn delete(&self, id: i64) -> Result<(), InboundWebhookError> {
+        if self.repo.delete(id).await? {
+            Ok(())
+        } else {
+            Err(InboundWebhookError::NotFound)
+        }
+    }
+
+    /// Verify a request and queue it for plugins
+    pub async fn receive(
+        &self,
+        token: &str,
+        headers: &HeaderMap,
+        body: &[u8],
+    ) -> Result<(), InboundWebhookError> {
+        let webhook = self
+            .repo
+            .get_by_token(token)
+            .await?
+            .ok_or(InboundWebhookError::NotFound)?;
+        if !verify_signature(&webhook, headers, body) {
+            return Err(InboundWebhookError::InvalidSignature);
+        }
+
+        let received_at = chrono::Utc::now();
+        self.queue
+            .try_send(event_payload(&webhook, headers, body, received_at))
+            .map_err(|_| InboundWebhookError::QueueFull)?;
+        if let Err(e) = self.repo.touch(webhook.id, received_at).await {
+            tracing::warn!(webhook_id = webhook.id, error = %e, "failed to record webhook delivery");
+        }
+        Ok(())
+    }
+}
+
+fn generate_token() -> String {
+    let mut buf = [0u8; 24];
+    getrandom::fill(&mut buf).expect("Failed to generate random bytes for webhook token");
+    BASE64URL_NOPAD.encode(&buf)
+}
+
+/// Check the request signature required by the endpoint
+fn verify_signature(webhook: &InboundWebhook, headers: &HeaderMap, body: &[u8]) -> bool {
+    let header = match webhook.signature {
+        SignatureScheme::None => return true,
+        SignatureScheme::Github => "x-hub-signature-256",
+        SignatureScheme::HmacSha256 => "x-signature",
+    };
+    let (Some(secret), Some(signature)) = (
+        webhook.secret.as_deref(),
+        headers.get(header).and_then(|v| v.to_str().ok()),
+    ) else {
+        return false;
+    };
+    verify_hmac_sha256(secret, signature, body)
+}
+
+/// Check a hex HMAC-SHA256 signature of `body`, with or without the
+/// `sha256=` prefix GitHub uses. The comparison is constant-time.
+pub(crate) fn verify_hmac_sha256(secret: &str, signature: &str, body: &[u8]) -> bool {
+    let signature = signature.trim();
+    let hex = signature.strip_prefix("sha256=").unwrap_or(signature);
+    let Ok(expected) = HEXLOWER_PERMISSIVE.decode(hex.as_bytes()) else {
+        return false;
+    };
+
+    let Ok(mut mac) = Hmac::<Sha256>::new_from_slice(secret.as_bytes()) else {
+        return false;
+    };
+    mac.update(body);
+    mac.verify_slice(&expected).is_ok()
+}
+
+/// Hook data for an accepted request
+///
+/// JSON bodies are passed parsed, anything else as text.
+fn event_payload(
+    webhook: &InboundWebhook,
+    headers: &HeaderMap,
+    body: &[u8],
+    received_at: chrono::DateTime<chrono::Utc>,
+) -> Value {
+    let header_map: Map<String, Value> = headers
+        .iter()
+        .filter(|(name, _)| !REDACTED_HEADERS.contains(&name.as_str()))
+        .filter_map(|(name, value)| {
+            Some((
+                name.to_string(),
+                Value::String(value.to_str().ok()?.to_string()),
+            ))
+        })
+        .collect();
+    let body = serde_json::from_slice(body)
+        .unwrap_or_else(|_| Value::String(String::from_utf8_lossy(body).into_owned()));
+
+    json!({
+        "webhook_id": webhook.id,
+        "name": webhook.name,
+        "headers": header_map,
+        "body": body,
+        "received_at": received_at.to_rfc3339(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn webhook(signature: SignatureScheme) -> InboundWebhook {
+        InboundWebhook {
+            id: 1,
+            name: "github".to_string(),
+            token: "token".to_string(),
+            signature,
+            secret: Some("It's a Secret to Everybody".to_string()),
+            last_received_at: None,
+            created_at: chrono::Utc::now(),
+        }
+    }
+
+    #[test]
+    fn verifies_github_signatures() {
+        // Example from GitHub's webhook validation docs
+        let body = b"Hello, World!";
+        let mut headers = HeaderMap::new();
+        headers.insert(
+            "x-hub-signature-256",
+            "sha256=757107ea0eb2509fc211221cce984b8a37570b6d7586c22c46f4379c8b043e17"
+                .parse()
+                .unwrap(),
+        );
+        let github = webhook(SignatureScheme::Github);
+        assert!(verify_signature(&github, &headers, body));
+        assert!(!verify_signature(&github, &headers, b"Hello, World?"));
+        assert!(!verify_signature(&github, &HeaderMap::new(), body));
+
+        // The generic scheme reads another header and accepts bare hex
+        let generic = webhook(SignatureScheme::HmacSha256);
+        assert!(!verify_signature(&generic, &headers, body));
+        headers.insert(
+            "x-signature",
+            "757107EA0EB2509FC211221CCE984B8A37570B6D7586C22C46F4379C8B043E17"
+                .parse()
+                .unwrap(),
+        );
+        assert!(verify_signature(&generic, &headers, body));
+
+        assert!(verify_signature(
+            &webhook(SignatureScheme::None),
+            &HeaderMap::new(),
+            body
+        ));
+    }
+
+    #[test]
+    fn payload_parses_json_and_drops_credentials() {
+        let mut headers = HeaderMap::new();
+        headers.insert("x-github-event", "push".parse().unwrap());
+        headers.insert("cookie", "session=abc".parse().unwrap());
+        let hook = webhook(SignatureScheme::None);
+
+        let payload = event_payload(&hook, &headers, br#"{"ref":"main"}"#, chrono::Utc::now());
+        assert_eq!(payload["name"], "github");
+        assert_eq!(payload["body"]["ref"], "main");
+        assert_eq!(payload["headers"]["x-github-event"], "push");
+        assert!(payload["headers"].get("cookie").is_none());
+
+        let payload = event_payload(&hook, &headers, b"a=1&b=2", chrono::Utc::now());
+        assert_eq!(payload["body"], "a=1&b=2");
+    }
+}
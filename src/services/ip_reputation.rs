@@ -0,0 +1,312 @@
+//! Shared IP reputation store
+//!
+//! Collects abuse signals from different parts of the system (login rate
+//! limits, failed logins, spam comments, failed captchas) into a single
+//! per-IP score. The score decays exponentially over time, so an address
+//! recovers on its own once the abuse stops.
+//!
+//! Registration, comment posting and likes consult the store and refuse
+//! requests from blocked addresses. Admins can pin addresses with the
+//! `ip_allowlist` / `ip_blocklist` settings (comma or newline separated),
+//! which take precedence over the computed score.
+
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use std::collections::HashMap;
+use std::net::IpAddr;
+use std::sync::Arc;
+use tokio::sync::RwLock;
+
+use crate::services::settings::SettingsService;
+
+/// Setting keys for admin overrides
+pub mod keys {
+    /// Addresses that are never blocked
+    pub const IP_ALLOWLIST: &str = "ip_allowlist";
+    /// Addresses that are always blocked
+    pub const IP_BLOCKLIST: &str = "ip_blocklist";
+}
+
+/// Time for a score to halve without new signals (seconds)
+const HALF_LIFE_SECS: f64 = 6.0 * 3600.0;
+
+/// Score at which an address is considered suspicious
+const SUSPICIOUS_THRESHOLD: f64 = 10.0;
+
+/// Score at which an address is blocked
+const BLOCK_THRESHOLD: f64 = 20.0;
+
+/// Entries below this score are dropped during cleanup
+const FORGET_THRESHOLD: f64 = 0.5;
+
+/// Kinds of abuse reported to the store
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AbuseSignal {
+    /// A login attempt with wrong credentials
+    FailedLogin,
+    /// The login rate limiter rejected a request
+    LoginRateLimited,
+    /// A comment was flagged as spam by a filter or a moderator
+    SpamComment,
+    /// A captcha token was missing or rejected
+    CaptchaFailed,
+}
+
+impl AbuseSignal {
+    fn weight(self) -> f64 {
+        match self {
+            Self::FailedLogin => 1.0,
+            Self::LoginRateLimited => 4.0,
+            Self::SpamComment => 5.0,
+            Self::CaptchaFailed => 1.0,
+        }
+    }
+}
+
+/// Reputation classification of an address
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ReputationLevel {
+    /// On the admin allowlist
+    Allowed,
+    Good,
+    Suspicious,
+    Blocked,
+}
+
+/// Snapshot of a tracked address
+#[derive(Debug, Clone, Serialize)]
+pub struct IpReputation {
+    pub ip: IpAddr,
+    pub score: f64,
+    pub level: ReputationLevel,
+    pub last_signal_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Copy)]
+struct ReputationEntry {
+    score: f64,
+    updated_at: DateTime<Utc>,
+}
+
+impl ReputationEntry {
+    /// Score decayed to `now`
+    fn score_at(&self, now: DateTime<Utc>) -> f64 {
+        let elapsed = (now - self.updated_at).num_milliseconds().max(0) as f64 / 1000.0;
+        self.score * 0.5f64.powf(elapsed / HALF_LIFE_SECS)
+    }
+}
+
+fn level_for_score(score: f64) -> ReputationLevel {
+    if score >= BLOCK_THRESHOLD {
+        ReputationLevel::Blocked
+    } else if score >= SUSPICIOUS_THRESHOLD {
+        ReputationLevel::Suspicious
+    } else {
+        ReputationLevel::Good
+    }
+}
+
+/// Parse an override list setting into addresses, ignoring invalid entries
+pub fn parse_ip_list(value: &str) -> Vec<IpAddr> {
+    value
+        .split([',', '\n'])
+        .filter_map(|s| s.trim().parse().ok())
+        .collect()
+}
+
+/// In-memory IP reputation store shared by the rate limiter and content services
+pub struct IpReputationStore {
+    entries: RwLock<HashMap<IpAddr, ReputationEntry>>,
+    settings: Arc<SettingsService>,
+}
+
+impl IpReputationStore {
+    pub fn new(settings: Arc<SettingsService>) -> Self {
+        Self {
+            entries: RwLock::new(HashMap::new()),
+            settings,
+        }
+    }
+
+    /// Report an abuse signal for `ip`
+    pub async fn record(&self, ip: IpAddr, signal: AbuseSignal) {
+        let now = Utc::now();
+        let mut entries = self.entries.write().await;
+        let entry = entries.entry(ip).or_insert(ReputationEntry {
+            score: 0.0,
+            updated_at: now,
+        });
+        entry.score = entry.score_at(now) + signal.weight();
+        entry.updated_at = now;
+
+        if entry.score >= BLOCK_THRESHOLD && entry.score - signal.weight() < BLOCK_THRESHOLD {
+            tracing::warn!(%ip, score = entry.score, "IP reached the reputation block threshold");
+        }
+    }
+
+    /// Report an abuse signal for an address given as a string; invalid
+    /// addresses are ignored.
+    pub async fn record_str(&self, ip: &str, signal: AbuseSignal) {
+        if let Ok(ip) = ip.parse() {
+            self.record(ip, signal).await;
+        }
+    }
+
+    /// Current decayed score of `ip` (0 when unknown)
+    pub async fn score(&self, ip: IpAddr) -> f64 {
+        self.entries
+            .read()
+            .await
+            .get(&ip)
+            .map(|e| e.score_at(Utc::now()))
+            .unwrap_or(0.0)
+    }
+
+    /// Classify `ip`, applying admin overrides first
+    pub async fn level(&self, ip: IpAddr) -> ReputationLevel {
+        if self.override_list(keys::IP_ALLOWLIST).await.contains(&ip) {
+            return ReputationLevel::Allowed;
+        }
+        if self.override_list(keys::IP_BLOCKLIST).await.contains(&ip) {
+            return ReputationLevel::Blocked;
+        }
+        level_for_score(self.score(ip).await)
+    }
+
+    /// Whether requests from `ip` should be refused. Unparseable addresses
+    /// are never blocked.
+    pub async fn is_blocked(&self, ip: &str) -> bool {
+        match ip.parse() {
+            Ok(ip) => self.level(ip).await == ReputationLevel::Blocked,
+            Err(_) => false,
+        }
+    }
+
+    /// All tracked addresses, worst first
+    pub async fn list(&self) -> Vec<IpReputation> {
+        let now = Utc::now();
+        let allowlist = self.override_list(keys::IP_ALLOWLIST).await;
+        let blocklist = self.override_list(keys::IP_BLOCKLIST).await;
+        let mut list: Vec<IpReputation> = self
+            .entries
+            .read()
+            .await
+            .iter()
+            .map(|(ip, entry)| {
+                let score = entry.score_at(now);
+                let level = if allowlist.contains(ip) {
+                    ReputationLevel::Allowed
+                } else if blocklist.contains(ip) {
+                    ReputationLevel::Blocked
+                } else {
+                    level_for_score(score)
+                };
+                IpReputation {
+                    ip: *ip,
+                    score,
+                    level,
+                    last_signal_at: entry.updated_at,
+                }
+            })
+            .collect();
+        list.sort_by(|a, b| b.score.total_cmp(&a.score));
+        list
+    }
+
+    /// Forget the score of `ip`. Returns whether it was tracked.
+    pub async fn reset(&self, ip: IpAddr) -> bool {
+        self.entries.write().await.remove(&ip).is_some()
+    }
+
+    /// Drop entries that have decayed to insignificance (called periodically)
+    pub async fn cleanup(&self) {
+        let now = Utc::now();
+        self.entries
+            .write()
+            .await
+            .retain(|_, entry| entry.score_at(now) >= FORGET_THRESHOLD);
+    }
+
+    async fn override_list(&self, key: &str) -> Vec<IpAddr> {
+        match self.settings.get(key).await {
+            Ok(Some(value)) => parse_ip_list(&value),
+            _ => Vec::new(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::repositories::SqlxSettingsRepository;
+    use crate::db::{create_test_pool, migrations};
+
+    async fn store() -> (Arc<SettingsService>, IpReputationStore) {
+        let pool = create_test_pool()
+            .await
+            .expect("Failed to create test pool");
+        migrations::run_migrations(&pool)
+            .await
+            .expect("Failed to run migrations");
+        let settings = Arc::new(SettingsService::from_sqlx(SqlxSettingsRepository::new(
+            pool,
+        )));
+        (settings.clone(), IpReputationStore::new(settings))
+    }
+
+    #[test]
+    fn score_halves_after_half_life() {
+        let now = Utc::now();
+        let entry = ReputationEntry {
+            score: 20.0,
+            updated_at: now - chrono::Duration::hours(6),
+        };
+        assert!((entry.score_at(now) - 10.0).abs() < 0.01);
+    }
+
+    #[test]
+    fn ip_lists_ignore_invalid_entries() {
+        let ips = parse_ip_list("10.0.0.1, nope\n::1,");
+        assert_eq!(ips.len(), 2);
+    }
+
+    #[tokio::test]
+    async fn signals_accumulate_until_blocked() {
+        let (_, store) = store().await;
+        let ip: IpAddr = "203.0.113.7".parse().unwrap();
+
+        for _ in 0..3 {
+            store.record(ip, AbuseSignal::SpamComment).await;
+        }
+        assert_eq!(store.level(ip).await, ReputationLevel::Suspicious);
+        assert!(!store.is_blocked("203.0.113.7").await);
+
+        store.record(ip, AbuseSignal::LoginRateLimited).await;
+        store.record(ip, AbuseSignal::SpamComment).await;
+        assert!(store.is_blocked("203.0.113.7").await);
+
+        assert!(store.reset(ip).await);
+        assert_eq!(store.level(ip).await, ReputationLevel::Good);
+    }
+
+    #[tokio::test]
+    async fn admin_overrides_take_precedence() {
+        let (settings, store) = store().await;
+        let ip: IpAddr = "198.51.100.4".parse().unwrap();
+        for _ in 0..5 {
+            store.record(ip, AbuseSignal::SpamComment).await;
+        }
+
+        settings
+            .set(keys::IP_ALLOWLIST, "198.51.100.4")
+            .await
+            .unwrap();
+        assert_eq!(store.level(ip).await, ReputationLevel::Allowed);
+        assert!(!store.is_blocked("198.51.100.4").await);
+
+        settings.set(keys::IP_ALLOWLIST, "").await.unwrap();
+        settings.set(keys::IP_BLOCKLIST, "192.0.2.1").await.unwrap();
+        assert!(store.is_blocked("192.0.2.1").await);
+    }
+}
@@ -0,0 +1,357 @@
+//! Persistent background job queue
+//!
+//! Work that must survive a restart (fetching Webmention sources, and later
+//! webhooks, emails, image processing and imports) is stored in the
+//! `job_queue` table with [`JobQueue::enqueue`] and picked up by worker tasks
+//! started in `main.rs`. Each job names a handler registered with
+//! [`JobQueue::register`] and carries a JSON payload.
+//!
+//! A failed attempt is retried after `job_queue.retry_base_secs`, doubling up
+//! to `retry_max_secs`. After `max_attempts` the job is dead (the dead letter
+//! status) until an admin retries it through `/api/v1/admin/jobs/queue`.
+//!
+//! Unlike [`JobMonitor`](super::JobMonitor), which tracks in-process tasks,
+//! handlers here must be able to run again from the payload alone.
+//!
+//! With [`JobQueue::with_hooks`], the `job_complete` hook is triggered when a
+//! job succeeds or dies.
+
+use std::collections::{BTreeMap, HashMap};
+use std::future::Future;
+use std::sync::{Arc, RwLock};
+use std::time::Duration;
+
+use chrono::Utc;
+use futures::future::BoxFuture;
+use serde::Serialize;
+use tokio::sync::Notify;
+
+use crate::config::JobQueueConfig;
+use crate::db::repositories::JobQueueRepository;
+use crate::models::{QueuedJob, QueuedJobStatus};
+use crate::plugin::{hook_names, HookManager};
+
+/// How often idle workers look for jobs that became due
+const POLL_INTERVAL: Duration = Duration::from_secs(5);
+
+/// Jobs listed when no limit is given
+pub const DEFAULT_LIST_LIMIT: i64 = 100;
+
+/// Errors returned by queue actions
+#[derive(Debug, thiserror::Error)]
+pub enum JobQueueError {
+    /// Unknown job id
+    #[error("Job not found")]
+    NotFound,
+
+    /// The action does not apply to the job's current status
+    #[error("{0}")]
+    InvalidState(String),
+
+    #[error(transparent)]
+    Internal(#[from] anyhow::Error),
+}
+
+type JobHandler =
+    Arc<dyn Fn(serde_json::Value) -> BoxFuture<'static, anyhow::Result<()>> + Send + Sync>;
+
+/// Job counts per status plus the listed jobs
+#[derive(Debug, Clone, Serialize)]
+pub struct JobQueueOverview {
+    pub counts: BTreeMap<QueuedJobStatus, i64>,
+    pub jobs: Vec<QueuedJob>,
+}
+
+/// Stores jobs in the database and runs them with retries
+pub struct JobQueue {
+    repo: Arc<dyn JobQueueRepository>,
+    config: JobQueueConfig,
+    handlers: RwLock<HashMap<String, JobHandler>>,
+    /// Wakes idle workers when a job is enqueued
+    wake: Notify,
+    hook_manager: Option<Arc<HookManager>>,
+}
+
+impl JobQueue {
+    pub fn new(repo: Arc<dyn JobQueueRepository>, config: JobQueueConfig) -> Self {
+        Self {
+            repo,
+            config,
+            handlers: RwLock::new(HashMap::new()),
+            wake: Notify::new(),
+            hook_manager: None,
+        }
+    }
+
+    /// Trigger `job_complete` for finished jobs
+    pub fn with_hooks(mut self, hook_manager: Arc<HookManager>) -> Self {
+        self.hook_manager = Some(hook_manager);
+        self
+    }
+
+    /// Run jobs of `kind` with `handler`, which receives the job's payload
+    pub fn register<F, Fut>(&self, kind: &str, handler: F)
+    where
+        F: Fn(serde_json::Value) -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = anyhow::Result<()>> + Send + 'static,
+    {
+        let handler: JobHandler =
+            Arc::new(move |payload| -> BoxFuture<'static, _> { Box::pin(handler(payload)) });
+        self.handlers
+            .write()
+            .unwrap()
+            .insert(kind.to_string(), handler);
+    }
+
+    /// Store a job to be run as soon as a worker is free
+    pub async fn enqueue(
+        &self,
+        kind: &str,
+        payload: &impl Serialize,
+    ) -> Result<QueuedJob, JobQueueError> {
+        let payload = serde_json::to_value(payload).map_err(anyhow::Error::from)?;
+        let job = self
+            .repo
+            .enqueue(kind, &payload, self.config.max_attempts.max(1), Utc::now())
+            .await?;
+        self.wake.notify_one();
+        Ok(job)
+    }
+
+    /// Counts per status and the newest jobs, optionally only those with `status`
+    pub async fn overview(
+        &self,
+        status: Option<QueuedJobStatus>,
+        limit: i64,
+    ) -> Result<JobQueueOverview, JobQueueError> {
+        let counts = self.repo.counts().await?.into_iter().collect();
+        let jobs = self.repo.list(status, limit).await?;
+        Ok(JobQueueOverview { counts, jobs })
+    }
+
+    /// A single job
+    pub async fn job(&self, id: i64) -> Result<QueuedJob, JobQueueError> {
+        self.repo.get(id).await?.ok_or(JobQueueError::NotFound)
+    }
+
+    /// Give a dead job a fresh set of attempts
+    pub async fn retry(&self, id: i64) -> Result<QueuedJob, JobQueueError> {
+        let job = self.job(id).await?;
+        if job.status != QueuedJobStatus::Dead || !self.repo.retry(id, Utc::now()).await? {
+            return Err(JobQueueError::InvalidState(
+                "Only dead jobs can be retried".to_string(),
+            ));
+        }
+        self.wake.notify_one();
+        self.job(id).await
+    }
+
+    /// Delete a job that is not running
+    pub async fn delete(&self, id: i64) -> Result<(), JobQueueError> {
+        let job = self.job(id).await?;
+        if job.status == QueuedJobStatus::Running || !self.repo.delete(id).await? {
+            return Err(JobQueueError::InvalidState(
+                "Running jobs cannot be deleted".to_string(),
+            ));
+        }
+        Ok(())
+    }
+
+    /// Return jobs whose attempt outlived the timeout to the queue, e.g.
+    /// after a crash, and delete old succeeded jobs
+    pub async fn maintain(&self) -> anyhow::Result<()> {
+        let now = Utc::now();
+        let timeout = chrono::Duration::seconds(self.config.timeout_secs as i64);
+        let released = self.repo.release_stale(now - timeout * 2).await?;
+        if released > 0 {
+            tracing::warn!(count = released, "requeued jobs left running");
+            self.wake.notify_waiters();
+        }
+        let keep = chrono::Duration::days(self.config.keep_days as i64);
+        let pruned = self.repo.prune_succeeded(now - keep).await?;
+        if pruned > 0 {
+            tracing::debug!(count = pruned, "deleted old succeeded jobs");
+        }
+        Ok(())
+    }
+
+    /// Claim and run due jobs until the process exits
+    pub async fn run_worker(self: Arc<Self>) {
+        loop {
+            // Register before claiming so an enqueue in between is not missed
+            let woken = self.wake.notified();
+            tokio::pin!(woken);
+            woken.as_mut().enable();
+            match self.run_next().await {
+                Ok(true) => continue,
+                Ok(false) => {}
+                Err(e) => tracing::error!(error = %e, "job queue worker failed"),
+            }
+            let _ = tokio::time::timeout(POLL_INTERVAL, woken).await;
+        }
+    }
+
+    /// Run the next due job, if any; returns whether one was run
+    pub async fn run_next(&self) -> anyhow::Result<bool> {
+        let Some(job) = self.repo.claim(Utc::now()).await? else {
+            return Ok(false);
+        };
+        let handler = self.handlers.read().unwrap().get(&job.kind).cloned();
+        let result = match handler {
+            Some(handler) => {
+                let timeout = Duration::from_secs(self.config.timeout_secs.max(1));
+                match tokio::time::timeout(timeout, handler(job.payload.clone())).await {
+                    Ok(result) => result,
+                    Err(_) => Err(anyhow::anyhow!(
+                        "Timed out after {} seconds",
+                        timeout.as_secs()
+                    )),
+                }
+            }
+            None => Err(anyhow::anyhow!("No handler for job kind '{}'", job.kind)),
+        };
+
+        let now = Utc::now();
+        match result {
+            Ok(()) => {
+                self.repo.complete(job.id, now).await?;
+                self.trigger_complete(&job, QueuedJobStatus::Succeeded, None);
+            }
+            Err(e) => {
+                let retry_at = (job.attempts < job.max_attempts).then(|| {
+                    now + chrono::Duration::from_std(backoff(&self.config, job.attempts))
+                        .unwrap_or_default()
+                });
+                if retry_at.is_some() {
+                    tracing::warn!(job_id = job.id, kind = %job.kind, attempt = job.attempts, error = %e, "queued job failed, will retry");
+                } else {
+                    tracing::error!(job_id = job.id, kind = %job.kind, error = %e, "queued job failed permanently");
+                }
+                self.repo
+                    .fail(job.id, &e.to_string(), retry_at, now)
+                    .await?;
+                if retry_at.is_none() {
+                    self.trigger_complete(&job, QueuedJobStatus::Dead, Some(e.to_string()));
+                }
+            }
+        }
+        Ok(true)
+    }
+
+    fn trigger_complete(&self, job: &QueuedJob, status: QueuedJobStatus, error: Option<String>) {
+        if let Some(hook_manager) = &self.hook_manager {
+            hook_manager.trigger_action(
+                hook_names::JOB_COMPLETE,
+                serde_json::json!({
+                    "id": job.id,
+                    "kind": job.kind,
+                    "status": status,
+                    "attempts": job.attempts,
+                    "error": error,
+                }),
+            );
+        }
+    }
+}
+
+/// Delay before retrying after the `attempts`-th failed attempt
+pub fn backoff(config: &JobQueueConfig, attempts: u32) -> Duration {
+    let factor = 1u64 << attempts.saturating_sub(1).min(32);
+    Duration::from_secs(
+        config
+            .retry_base_secs
+            .saturating_mul(factor)
+            .min(config.retry_max_secs),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::repositories::SqlxJobQueueRepository;
+    use crate::db::{create_test_pool, migrations};
+    use std::sync::atomic::{AtomicU32, Ordering};
+
+    #[test]
+    fn backoff_doubles_up_to_the_maximum() {
+        let config = JobQueueConfig {
+            retry_base_secs: 30,
+            retry_max_secs: 200,
+            ..JobQueueConfig::default()
+        };
+        let delays: Vec<u64> = (1..=5).map(|n| backoff(&config, n).as_secs()).collect();
+        assert_eq!(delays, [30, 60, 120, 200, 200]);
+        assert_eq!(backoff(&config, 100).as_secs(), 200);
+    }
+
+    #[tokio::test]
+    async fn failing_jobs_are_retried_then_dead_lettered() {
+        let pool = create_test_pool()
+            .await
+            .expect("Failed to create test pool");
+        migrations::run_migrations(&pool)
+            .await
+            .expect("Failed to run migrations");
+        let queue = JobQueue::new(
+            SqlxJobQueueRepository::boxed(pool),
+            JobQueueConfig {
+                max_attempts: 2,
+                retry_base_secs: 0,
+                ..JobQueueConfig::default()
+            },
+        );
+        let calls = Arc::new(AtomicU32::new(0));
+        let counter = calls.clone();
+        queue.register("test", move |payload| {
+            counter.fetch_add(1, Ordering::SeqCst);
+            async move {
+                if payload["fail"] == true {
+                    anyhow::bail!("asked to fail");
+                }
+                Ok(())
+            }
+        });
+
+        let ok = queue.enqueue("test", &serde_json::json!({})).await.unwrap();
+        assert!(queue.run_next().await.unwrap());
+        assert_eq!(
+            queue.job(ok.id).await.unwrap().status,
+            QueuedJobStatus::Succeeded
+        );
+
+        let bad = queue
+            .enqueue("test", &serde_json::json!({ "fail": true }))
+            .await
+            .unwrap();
+        assert!(queue.run_next().await.unwrap());
+        let job = queue.job(bad.id).await.unwrap();
+        assert_eq!(job.status, QueuedJobStatus::Pending);
+        assert_eq!(job.last_error.as_deref(), Some("asked to fail"));
+        assert!(queue.run_next().await.unwrap());
+        assert_eq!(
+            queue.job(bad.id).await.unwrap().status,
+            QueuedJobStatus::Dead
+        );
+        assert!(!queue.run_next().await.unwrap());
+        assert_eq!(calls.load(Ordering::SeqCst), 3);
+
+        let overview = queue.overview(None, DEFAULT_LIST_LIMIT).await.unwrap();
+        assert_eq!(overview.counts.get(&QueuedJobStatus::Dead), Some(&1));
+        assert_eq!(overview.jobs.len(), 2);
+
+        assert!(matches!(
+            queue.retry(ok.id).await,
+            Err(JobQueueError::InvalidState(_))
+        ));
+        let retried = queue.retry(bad.id).await.unwrap();
+        assert_eq!(retried.status, QueuedJobStatus::Pending);
+        assert_eq!(retried.attempts, 0);
+
+        let orphan = queue.enqueue("unknown", &()).await.unwrap();
+        queue.delete(orphan.id).await.unwrap();
+        assert!(matches!(
+            queue.job(orphan.id).await,
+            Err(JobQueueError::NotFound)
+        ));
+    }
+}
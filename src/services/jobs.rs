@@ -0,0 +1,456 @@
+//! Background job and schedule monitoring
+//!
+//! One-off background work (publishing GitHub pushes, verifying
+//! Webmentions) is started through [`JobMonitor::submit`], and periodic
+//! tasks run through [`JobMonitor::every`]. Both record their state so
+//! `/api/v1/admin/jobs` and `/api/v1/admin/schedules` can show what is
+//! queued, running or failing. Failed and cancelled jobs can be retried,
+//! queued and running ones cancelled.
+//!
+//! Everything is kept in memory: history is lost on restart and only the
+//! most recent finished jobs are kept.
+
+use std::collections::BTreeMap;
+use std::future::Future;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use chrono::{DateTime, Utc};
+use futures::future::BoxFuture;
+use serde::{Deserialize, Serialize};
+use tokio::sync::Semaphore;
+use tokio::task::AbortHandle;
+
+/// Jobs running at the same time; the rest wait as queued
+const MAX_CONCURRENT_JOBS: usize = 4;
+
+/// Finished jobs kept for inspection
+const MAX_FINISHED_JOBS: usize = 200;
+
+/// Errors returned by job actions
+#[derive(Debug, thiserror::Error)]
+pub enum JobError {
+    /// Unknown job id
+    #[error("Job not found")]
+    NotFound,
+
+    /// The action does not apply to the job's current status
+    #[error("{0}")]
+    InvalidState(String),
+}
+
+/// Lifecycle of a background job
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum JobStatus {
+    /// Waiting for a free slot
+    Queued,
+    Running,
+    Succeeded,
+    Failed,
+    Cancelled,
+}
+
+impl JobStatus {
+    /// The job is not queued or running
+    pub fn is_finished(&self) -> bool {
+        matches!(self, Self::Succeeded | Self::Failed | Self::Cancelled)
+    }
+}
+
+/// A background job as reported by the admin API
+#[derive(Debug, Clone, Serialize)]
+pub struct JobInfo {
+    pub id: u64,
+    /// What the job does, e.g. `github_publish`
+    pub kind: String,
+    /// What it works on, e.g. a commit or URL
+    pub label: String,
+    pub status: JobStatus,
+    /// Runs started so far, including retries
+    pub attempts: u32,
+    pub created_at: DateTime<Utc>,
+    pub started_at: Option<DateTime<Utc>>,
+    pub finished_at: Option<DateTime<Utc>>,
+    pub duration_ms: Option<u64>,
+    pub error: Option<String>,
+}
+
+/// A periodic task as reported by the admin API
+#[derive(Debug, Clone, Serialize)]
+pub struct ScheduleInfo {
+    pub name: String,
+    pub interval_secs: u64,
+    /// A run is in progress
+    pub running: bool,
+    pub next_run_at: DateTime<Utc>,
+    pub last_started_at: Option<DateTime<Utc>>,
+    pub last_duration_ms: Option<u64>,
+    /// Error of the last run, if it failed
+    pub last_error: Option<String>,
+    pub runs: u64,
+    pub failures: u64,
+}
+
+type JobTask = Arc<dyn Fn() -> BoxFuture<'static, anyhow::Result<()>> + Send + Sync>;
+
+struct JobEntry {
+    info: JobInfo,
+    task: JobTask,
+    abort: Option<AbortHandle>,
+}
+
+/// Tracks background jobs and periodic tasks
+pub struct JobMonitor {
+    jobs: Mutex<BTreeMap<u64, JobEntry>>,
+    schedules: Mutex<BTreeMap<String, ScheduleInfo>>,
+    next_id: AtomicU64,
+    slots: Arc<Semaphore>,
+}
+
+impl Default for JobMonitor {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl JobMonitor {
+    pub fn new() -> Self {
+        Self {
+            jobs: Mutex::new(BTreeMap::new()),
+            schedules: Mutex::new(BTreeMap::new()),
+            next_id: AtomicU64::new(1),
+            slots: Arc::new(Semaphore::new(MAX_CONCURRENT_JOBS)),
+        }
+    }
+
+    /// Run `task` in the background as a tracked job and return its id
+    ///
+    /// `task` is called again for every retry, so it must be able to
+    /// repeat the work from scratch.
+    pub fn submit<F, Fut>(self: &Arc<Self>, kind: &str, label: impl Into<String>, task: F) -> u64
+    where
+        F: Fn() -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = anyhow::Result<()>> + Send + 'static,
+    {
+        let id = self.next_id.fetch_add(1, Ordering::Relaxed);
+        let task: JobTask = Arc::new(move || -> BoxFuture<'static, _> { Box::pin(task()) });
+        let info = JobInfo {
+            id,
+            kind: kind.to_string(),
+            label: label.into(),
+            status: JobStatus::Queued,
+            attempts: 0,
+            created_at: Utc::now(),
+            started_at: None,
+            finished_at: None,
+            duration_ms: None,
+            error: None,
+        };
+        {
+            let mut jobs = self.jobs.lock().unwrap();
+            jobs.insert(
+                id,
+                JobEntry {
+                    info,
+                    task,
+                    abort: None,
+                },
+            );
+            prune_finished(&mut jobs);
+        }
+        self.start(id);
+        id
+    }
+
+    /// Jobs, newest first, optionally only those with `status`
+    pub fn jobs(&self, status: Option<JobStatus>) -> Vec<JobInfo> {
+        let jobs = self.jobs.lock().unwrap();
+        jobs.values()
+            .rev()
+            .filter(|entry| status.is_none_or(|s| entry.info.status == s))
+            .map(|entry| entry.info.clone())
+            .collect()
+    }
+
+    /// A single job
+    pub fn job(&self, id: u64) -> Option<JobInfo> {
+        let jobs = self.jobs.lock().unwrap();
+        jobs.get(&id).map(|entry| entry.info.clone())
+    }
+
+    /// Run a failed or cancelled job again
+    pub fn retry(self: &Arc<Self>, id: u64) -> Result<JobInfo, JobError> {
+        let info = {
+            let mut jobs = self.jobs.lock().unwrap();
+            let entry = jobs.get_mut(&id).ok_or(JobError::NotFound)?;
+            if !matches!(entry.info.status, JobStatus::Failed | JobStatus::Cancelled) {
+                return Err(JobError::InvalidState(
+                    "Only failed or cancelled jobs can be retried".to_string(),
+                ));
+            }
+            entry.info.status = JobStatus::Queued;
+            entry.info.started_at = None;
+            entry.info.finished_at = None;
+            entry.info.duration_ms = None;
+            entry.info.error = None;
+            entry.info.clone()
+        };
+        self.start(id);
+        Ok(info)
+    }
+
+    /// Stop a queued or running job
+    pub fn cancel(&self, id: u64) -> Result<JobInfo, JobError> {
+        let mut jobs = self.jobs.lock().unwrap();
+        let entry = jobs.get_mut(&id).ok_or(JobError::NotFound)?;
+        if entry.info.status.is_finished() {
+            return Err(JobError::InvalidState(
+                "Only queued or running jobs can be cancelled".to_string(),
+            ));
+        }
+        if let Some(abort) = entry.abort.take() {
+            abort.abort();
+        }
+        let now = Utc::now();
+        entry.info.duration_ms = entry
+            .info
+            .started_at
+            .map(|started| (now - started).num_milliseconds().max(0) as u64);
+        entry.info.status = JobStatus::Cancelled;
+        entry.info.finished_at = Some(now);
+        Ok(entry.info.clone())
+    }
+
+    /// Spawn the task of a queued job
+    fn start(self: &Arc<Self>, id: u64) {
+        let monitor = self.clone();
+        let mut jobs = self.jobs.lock().unwrap();
+        let Some(entry) = jobs.get_mut(&id) else {
+            return;
+        };
+        let task = entry.task.clone();
+        // Holding the lock keeps the job from finishing before its
+        // abort handle is stored
+        let handle = tokio::spawn(async move {
+            let _permit = monitor.slots.clone().acquire_owned().await;
+            if !monitor.mark_running(id) {
+                return;
+            }
+            let started = Instant::now();
+            let result = task().await;
+            monitor.finish(id, started.elapsed(), result);
+        });
+        entry.abort = Some(handle.abort_handle());
+    }
+
+    fn mark_running(&self, id: u64) -> bool {
+        let mut jobs = self.jobs.lock().unwrap();
+        match jobs.get_mut(&id) {
+            Some(entry) if entry.info.status == JobStatus::Queued => {
+                entry.info.status = JobStatus::Running;
+                entry.info.attempts += 1;
+                entry.info.started_at = Some(Utc::now());
+                true
+            }
+            _ => false,
+        }
+    }
+
+    fn finish(&self, id: u64, elapsed: Duration, result: anyhow::Result<()>) {
+        let mut jobs = self.jobs.lock().unwrap();
+        let Some(entry) = jobs.get_mut(&id) else {
+            return;
+        };
+        if entry.info.status != JobStatus::Running {
+            return;
+        }
+        entry.abort = None;
+        entry.info.finished_at = Some(Utc::now());
+        entry.info.duration_ms = Some(elapsed.as_millis() as u64);
+        match result {
+            Ok(()) => entry.info.status = JobStatus::Succeeded,
+            Err(e) => {
+                tracing::warn!(job_id = id, kind = %entry.info.kind, error = %e, "background job failed");
+                entry.info.status = JobStatus::Failed;
+                entry.info.error = Some(e.to_string());
+            }
+        }
+    }
+
+    /// Periodic tasks, by name
+    pub fn schedules(&self) -> Vec<ScheduleInfo> {
+        self.schedules.lock().unwrap().values().cloned().collect()
+    }
+
+    /// Run `task` every `period` until the process exits
+    ///
+    /// The first run happens one period after the call. Each run is
+    /// recorded under `name`; errors are logged and do not stop the loop.
+    pub async fn every<F, Fut>(self: Arc<Self>, name: &str, period: Duration, task: F)
+    where
+        F: Fn() -> Fut,
+        Fut: Future<Output = anyhow::Result<()>>,
+    {
+        self.register_schedule(name, period);
+        let mut interval = tokio::time::interval(period);
+        interval.tick().await; // skip first immediate tick
+        loop {
+            interval.tick().await;
+            self.update_schedule(name, |s| {
+                s.running = true;
+                s.last_started_at = Some(Utc::now());
+            });
+            let started = Instant::now();
+            let result = task().await;
+            let elapsed = started.elapsed();
+            if let Err(e) = &result {
+                tracing::error!(schedule = name, error = %e, "scheduled task failed");
+            }
+            self.update_schedule(name, |s| {
+                s.running = false;
+                s.runs += 1;
+                s.last_duration_ms = Some(elapsed.as_millis() as u64);
+                s.next_run_at = Utc::now() + period.saturating_sub(elapsed);
+                s.last_error = result.err().map(|e| e.to_string());
+                if s.last_error.is_some() {
+                    s.failures += 1;
+                }
+            });
+        }
+    }
+
+    fn register_schedule(&self, name: &str, period: Duration) {
+        self.schedules.lock().unwrap().insert(
+            name.to_string(),
+            ScheduleInfo {
+                name: name.to_string(),
+                interval_secs: period.as_secs(),
+                running: false,
+                next_run_at: Utc::now() + period,
+                last_started_at: None,
+                last_duration_ms: None,
+                last_error: None,
+                runs: 0,
+                failures: 0,
+            },
+        );
+    }
+
+    fn update_schedule(&self, name: &str, update: impl FnOnce(&mut ScheduleInfo)) {
+        if let Some(schedule) = self.schedules.lock().unwrap().get_mut(name) {
+            update(schedule);
+        }
+    }
+}
+
+/// Drop the oldest finished jobs beyond [`MAX_FINISHED_JOBS`]
+fn prune_finished(jobs: &mut BTreeMap<u64, JobEntry>) {
+    let finished: Vec<u64> = jobs
+        .iter()
+        .filter(|(_, entry)| entry.info.status.is_finished())
+        .map(|(id, _)| *id)
+        .collect();
+    let excess = finished.len().saturating_sub(MAX_FINISHED_JOBS);
+    for id in finished.into_iter().take(excess) {
+        jobs.remove(&id);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::AtomicU32;
+
+    async fn wait_for(monitor: &JobMonitor, id: u64, status: JobStatus) -> JobInfo {
+        for _ in 0..100 {
+            let job = monitor.job(id).unwrap();
+            if job.status == status {
+                return job;
+            }
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        }
+        panic!("job {} never reached {:?}", id, status);
+    }
+
+    #[tokio::test]
+    async fn failed_jobs_record_the_error_and_can_be_retried() {
+        let monitor = Arc::new(JobMonitor::new());
+        let calls = Arc::new(AtomicU32::new(0));
+        let counter = calls.clone();
+        let id = monitor.submit("test", "flaky", move || {
+            let n = counter.fetch_add(1, Ordering::SeqCst);
+            async move {
+                if n == 0 {
+                    anyhow::bail!("first attempt fails");
+                }
+                Ok(())
+            }
+        });
+
+        let job = wait_for(&monitor, id, JobStatus::Failed).await;
+        assert_eq!(job.error.as_deref(), Some("first attempt fails"));
+        assert_eq!(job.attempts, 1);
+        assert!(job.duration_ms.is_some());
+        assert!(matches!(monitor.cancel(id), Err(JobError::InvalidState(_))));
+
+        monitor.retry(id).unwrap();
+        let job = wait_for(&monitor, id, JobStatus::Succeeded).await;
+        assert_eq!(job.attempts, 2);
+        assert!(job.error.is_none());
+        assert!(matches!(monitor.retry(id), Err(JobError::InvalidState(_))));
+        assert_eq!(monitor.jobs(Some(JobStatus::Failed)).len(), 0);
+        assert_eq!(monitor.jobs(None).len(), 1);
+    }
+
+    #[tokio::test]
+    async fn running_jobs_can_be_cancelled() {
+        let monitor = Arc::new(JobMonitor::new());
+        let id = monitor.submit("test", "slow", || async {
+            tokio::time::sleep(Duration::from_secs(60)).await;
+            Ok(())
+        });
+        wait_for(&monitor, id, JobStatus::Running).await;
+
+        let job = monitor.cancel(id).unwrap();
+        assert_eq!(job.status, JobStatus::Cancelled);
+        assert!(job.finished_at.is_some());
+        assert!(matches!(monitor.cancel(999), Err(JobError::NotFound)));
+    }
+
+    #[test]
+    fn prunes_only_finished_jobs() {
+        let mut jobs = BTreeMap::new();
+        for id in 0..(MAX_FINISHED_JOBS as u64 + 10) {
+            let status = if id < 5 {
+                JobStatus::Queued
+            } else {
+                JobStatus::Succeeded
+            };
+            jobs.insert(
+                id,
+                JobEntry {
+                    info: JobInfo {
+                        id,
+                        kind: "test".to_string(),
+                        label: String::new(),
+                        status,
+                        attempts: 0,
+                        created_at: Utc::now(),
+                        started_at: None,
+                        finished_at: None,
+                        duration_ms: None,
+                        error: None,
+                    },
+                    task: Arc::new(|| -> BoxFuture<'static, _> { Box::pin(async { Ok(()) }) }),
+                    abort: None,
+                },
+            );
+        }
+        prune_finished(&mut jobs);
+        assert_eq!(jobs.len(), MAX_FINISHED_JOBS + 5);
+        assert!(jobs.contains_key(&0));
+        assert!(!jobs.contains_key(&5));
+    }
+}
@@ -0,0 +1,424 @@
+//! LDAP / Active Directory authentication backend
+//!
+//! Selected with `auth.backend: ldap` in `config.yml`. A login looks the user
+//! up with the (optional) service account, then binds as the user's DN with
+//! the submitted password. The user's groups are mapped to a role; accounts
+//! are provisioned on first login, bound to the directory entry's objectGUID
+//! (or entryUUID, or DN) and their role is re-applied on every login.
+
+use async_trait::async_trait;
+use ldap3::{ldap_escape, LdapConnAsync, LdapConnSettings, Scope, SearchEntry};
+use std::collections::HashMap;
+use std::str::FromStr;
+use std::time::Duration;
+
+use crate::config::LdapConfig;
+use crate::models::UserRole;
+
+/// LDAP result code for invalid credentials
+const LDAP_INVALID_CREDENTIALS: u32 = 49;
+
+/// Stable identifier of an Active Directory entry
+const AD_OBJECT_GUID: &str = "objectGUID";
+
+/// Stable identifier of an OpenLDAP / 389 DS entry
+const ENTRY_UUID: &str = "entryUUID";
+
+/// Errors raised while talking to the directory
+#[derive(Debug, thiserror::Error)]
+pub enum DirectoryError {
+    /// The directory could not be reached or returned an error
+    #[error("Directory unavailable: {0}")]
+    Unavailable(String),
+
+    /// The user authenticated but may not sign in
+    #[error("{0}")]
+    Forbidden(String),
+}
+
+/// A user verified by an external directory
+#[derive(Debug, Clone, PartialEq)]
+pub struct DirectoryUser {
+    /// Stable ID of the directory entry: hex objectGUID, entryUUID or DN
+    pub id: String,
+    pub username: String,
+    pub email: String,
+    pub display_name: Option<String>,
+    pub role: UserRole,
+}
+
+/// Password verification against an external directory
+#[async_trait]
+pub trait DirectoryAuthenticator: Send + Sync {
+    /// Verify a login. Returns `Ok(None)` when the directory does not know
+    /// the user or the password is wrong.
+    async fn authenticate(
+        &self,
+        username: &str,
+        password: &str,
+    ) -> Result<Option<DirectoryUser>, DirectoryError>;
+
+    /// Whether local accounts may sign in when the directory rejects a login
+    fn allow_local_fallback(&self) -> bool;
+
+    /// Whether an unbound local account with the directory user's email is
+    /// adopted on first login
+    fn link_by_email(&self) -> bool;
+}
+
+fn role_rank(role: UserRole) -> u8 {
+    match role {
+        UserRole::Admin => 3,
+        UserRole::Editor => 2,
+        UserRole::Author => 1,
+    }
+}
+
+/// Value of the first RDN of a DN, e.g. `blog-admins` for
+/// `CN=blog-admins,OU=Groups,DC=example,DC=com`
+fn first_rdn_value(dn: &str) -> Option<&str> {
+    dn.split(',').next()?.split_once('=').map(|(_, v)| v.trim())
+}
+
+/// LDAP implementation of [`DirectoryAuthenticator`]
+pub struct LdapAuthenticator {
+    config: LdapConfig,
+    /// Lower-cased group DN or CN to role
+    role_mapping: HashMap<String, UserRole>,
+    default_role: Option<UserRole>,
+}
+
+impl LdapAuthenticator {
+    /// Create the authenticator, validating the configuration
+    pub fn new(config: LdapConfig) -> anyhow::Result<Self> {
+        if !(config.url.starts_with("ldap://") || config.url.starts_with("ldaps://")) {
+            anyhow::bail!("auth.ldap.url must start with ldap:// or ldaps://");
+        }
+        if config.base_dn.trim().is_empty() {
+            anyhow::bail!("auth.ldap.base_dn is required");
+        }
+        if !config.user_filter.contains("{username}") {
+            anyhow::bail!("auth.ldap.user_filter must contain {{username}}");
+        }
+
+        let mut role_mapping = HashMap::new();
+        for (group, role) in &config.role_mapping {
+            let role = UserRole::from_str(role)
+                .map_err(|_| anyhow::anyhow!("Invalid role in auth.ldap.role_mapping: {}", role))?;
+            role_mapping.insert(group.to_lowercase(), role);
+        }
+        let default_role = config
+            .default_role
+            .as_deref()
+            .map(UserRole::from_str)
+            .transpose()?;
+
+        Ok(Self {
+            config,
+            role_mapping,
+            default_role,
+        })
+    }
+
+    /// Search filter for a login name, with LDAP metacharacters escaped
+    fn user_filter(&self, username: &str) -> String {
+        self.config
+            .user_filter
+            .replace("{username}", &ldap_escape(username))
+    }
+
+    /// Highest role mapped from the user's groups, else the default role
+    fn resolve_role(&self, groups: &[String]) -> Option<UserRole> {
+        groups
+            .iter()
+            .filter_map(|dn| {
+                self.role_mapping.get(&dn.to_lowercase()).or_else(|| {
+                    first_rdn_value(dn).and_then(|cn| self.role_mapping.get(&cn.to_lowercase()))
+                })
+            })
+            .copied()
+            .max_by_key(|role| role_rank(*role))
+            .or(self.default_role)
+    }
+
+    fn to_directory_user(
+        &self,
+        login: &str,
+        entry: &SearchEntry,
+    ) -> Result<DirectoryUser, DirectoryError> {
+        let first = |name: &str| {
+            entry
+                .attrs
+                .iter()
+                .find(|(k, _)| k.eq_ignore_ascii_case(name))
+                .and_then(|(_, v)| v.first())
+                .map(|v| v.trim().to_string())
+                .filter(|v| !v.is_empty())
+        };
+        let groups = entry
+            .attrs
+            .iter()
+            .find(|(k, _)| k.eq_ignore_ascii_case(&self.config.group_attribute))
+            .map(|(_, v)| v.clone())
+            .unwrap_or_default();
+
+        let role = self.resolve_role(&groups).ok_or_else(|| {
+            DirectoryError::Forbidden("Your account is not allowed to sign in to this site".into())
+        })?;
+        let email = first(&self.config.email_attribute).ok_or_else(|| {
+            DirectoryError::Forbidden("Your directory account has no email address".into())
+        })?;
+        let username = self
+            .config
+            .username_attribute
+            .as_deref()
+            .and_then(first)
+            .unwrap_or_else(|| login.to_string());
+        // objectGUID is binary, but ldap3 keeps values that happen to be
+        // valid UTF-8 in `attrs`
+        let guid = entry
+            .bin_attrs
+            .iter()
+            .find(|(k, _)| k.eq_ignore_ascii_case(AD_OBJECT_GUID))
+            .and_then(|(_, v)| v.first().cloned())
+            .or_else(|| {
+                entry
+                    .attrs
+                    .iter()
+                    .find(|(k, _)| k.eq_ignore_ascii_case(AD_OBJECT_GUID))
+                    .and_then(|(_, v)| v.first())
+                    .map(|v| v.as_bytes().to_vec())
+            })
+            .filter(|v| !v.is_empty())
+            .map(|v| v.iter().map(|b| format!("{:02x}", b)).collect::<String>());
+        let id = guid
+            .or_else(|| first(ENTRY_UUID))
+            .unwrap_or_else(|| entry.dn.to_lowercase());
+
+        Ok(DirectoryUser {
+            id,
+            username,
+            email,
+            display_name: first(&self.config.display_name_attribute),
+            role,
+        })
+    }
+
+    async fn lookup_and_bind(
+        &self,
+        username: &str,
+        password: &str,
+    ) -> Result<Option<SearchEntry>, ldap3::LdapError> {
+        let timeout = Duration::from_secs(self.config.timeout_seconds);
+        let settings = LdapConnSettings::new()
+            .set_conn_timeout(timeout)
+            .set_starttls(self.config.starttls)
+            .set_no_tls_verify(self.config.tls_insecure);
+        let (conn, mut ldap) = LdapConnAsync::with_settings(settings, &self.config.url).await?;
+        ldap3::drive!(conn);
+
+        if let Some(bind_dn) = &self.config.bind_dn {
+            ldap.with_timeout(timeout)
+                .simple_bind(bind_dn, self.config.bind_password.as_deref().unwrap_or(""))
+                .await?
+                .success()?;
+        }
+
+        let mut attributes = vec![
+            AD_OBJECT_GUID,
+            ENTRY_UUID,
+            self.config.email_attribute.as_str(),
+            self.config.display_name_attribute.as_str(),
+            self.config.group_attribute.as_str(),
+        ];
+        if let Some(attr) = &self.config.username_attribute {
+            attributes.push(attr);
+        }
+        let (entries, _) = ldap
+            .with_timeout(timeout)
+            .search(
+                &self.config.base_dn,
+                Scope::Subtree,
+                &self.user_filter(username),
+                attributes,
+            )
+            .await?
+            .success()?;
+        // Unknown and ambiguous logins are rejected alike
+        if entries.len() != 1 {
+            let _ = ldap.unbind().await;
+            return Ok(None);
+        }
+        let entry = SearchEntry::construct(entries.into_iter().next().expect("one entry"));
+
+        let bind = ldap
+            .with_timeout(timeout)
+            .simple_bind(&entry.dn, password)
+            .await?;
+        let _ = ldap.unbind().await;
+        if bind.rc == LDAP_INVALID_CREDENTIALS {
+            return Ok(None);
+        }
+        bind.success()?;
+        Ok(Some(entry))
+    }
+}
+
+#[async_trait]
+impl DirectoryAuthenticator for LdapAuthenticator {
+    async fn authenticate(
+        &self,
+        username: &str,
+        password: &str,
+    ) -> Result<Option<DirectoryUser>, DirectoryError> {
+        // An empty password makes an unauthenticated bind, which servers accept
+        if username.trim().is_empty() || password.is_empty() {
+            return Ok(None);
+        }
+        let entry = self
+            .lookup_and_bind(username, password)
+            .await
+            .map_err(|e| DirectoryError::Unavailable(e.to_string()))?;
+        entry
+            .map(|entry| self.to_directory_user(username, &entry))
+            .transpose()
+    }
+
+    fn allow_local_fallback(&self) -> bool {
+        self.config.allow_local_fallback
+    }
+
+    fn link_by_email(&self) -> bool {
+        self.config.link_by_email
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn authenticator() -> LdapAuthenticator {
+        LdapAuthenticator::new(LdapConfig {
+            url: "ldaps://ad.example.com".to_string(),
+            base_dn: "DC=example,DC=com".to_string(),
+            user_filter: "(&(objectClass=user)(sAMAccountName={username}))".to_string(),
+            role_mapping: [
+                ("blog-admins".to_string(), "admin".to_string()),
+                (
+                    "CN=Blog Editors,OU=Groups,DC=example,DC=com".to_string(),
+                    "editor".to_string(),
+                ),
+            ]
+            .into_iter()
+            .collect(),
+            ..Default::default()
+        })
+        .unwrap()
+    }
+
+    #[test]
+    fn user_filter_escapes_the_login_name() {
+        assert_eq!(
+            authenticator().user_filter("alice*)(uid=*"),
+            r"(&(objectClass=user)(sAMAccountName=alice\2a\29\28uid=\2a))"
+        );
+    }
+
+    #[test]
+    fn roles_are_mapped_by_dn_or_cn() {
+        let ldap = authenticator();
+        let groups = |dns: &[&str]| dns.iter().map(|s| s.to_string()).collect::<Vec<_>>();
+
+        assert_eq!(
+            ldap.resolve_role(&groups(&["cn=blog editors,ou=groups,dc=example,dc=com"])),
+            Some(UserRole::Editor)
+        );
+        assert_eq!(
+            ldap.resolve_role(&groups(&[
+                "CN=Blog Editors,OU=Groups,DC=example,DC=com",
+                "CN=blog-admins,OU=Other,DC=example,DC=com",
+            ])),
+            Some(UserRole::Admin)
+        );
+        assert_eq!(
+            ldap.resolve_role(&groups(&["CN=staff,DC=example,DC=com"])),
+            None
+        );
+    }
+
+    #[test]
+    fn builds_directory_user_from_entry() {
+        let ldap = authenticator();
+        let entry = SearchEntry {
+            dn: "CN=Alice,OU=People,DC=example,DC=com".to_string(),
+            attrs: [
+                ("mail".to_string(), vec!["alice@example.com".to_string()]),
+                ("displayName".to_string(), vec!["Alice".to_string()]),
+                (
+                    "memberOf".to_string(),
+                    vec!["CN=blog-admins,OU=Groups,DC=example,DC=com".to_string()],
+                ),
+            ]
+            .into_iter()
+            .collect(),
+            bin_attrs: HashMap::new(),
+        };
+
+        let user = ldap.to_directory_user("alice", &entry).unwrap();
+        assert_eq!(user.id, "cn=alice,ou=people,dc=example,dc=com");
+        assert_eq!(user.username, "alice");
+        assert_eq!(user.email, "alice@example.com");
+        assert_eq!(user.display_name.as_deref(), Some("Alice"));
+        assert_eq!(user.role, UserRole::Admin);
+
+        let mut outsider = entry.clone();
+        outsider.attrs.remove("memberOf");
+        assert!(matches!(
+            ldap.to_directory_user("alice", &outsider),
+            Err(DirectoryError::Forbidden(_))
+        ));
+    }
+
+    #[test]
+    fn prefers_object_guid_over_entry_uuid_and_dn() {
+        let ldap = authenticator();
+        let mut entry = SearchEntry {
+            dn: "CN=Alice,OU=People,DC=example,DC=com".to_string(),
+            attrs: [
+                ("mail".to_string(), vec!["alice@example.com".to_string()]),
+                ("memberOf".to_string(), vec!["CN=blog-admins".to_string()]),
+                (
+                    "entryUUID".to_string(),
+                    vec!["6f1c8e52-3a0d-4c5e-9b1a-2f7d8e9c0a1b".to_string()],
+                ),
+            ]
+            .into_iter()
+            .collect(),
+            bin_attrs: HashMap::new(),
+        };
+        assert_eq!(
+            ldap.to_directory_user("alice", &entry).unwrap().id,
+            "6f1c8e52-3a0d-4c5e-9b1a-2f7d8e9c0a1b"
+        );
+
+        entry
+            .bin_attrs
+            .insert("objectGUID".to_string(), vec![vec![0x00, 0xff, 0x10, 0xab]]);
+        assert_eq!(
+            ldap.to_directory_user("alice", &entry).unwrap().id,
+            "00ff10ab"
+        );
+    }
+
+    #[test]
+    fn rejects_invalid_configuration() {
+        assert!(LdapAuthenticator::new(LdapConfig::default()).is_err());
+        assert!(LdapAuthenticator::new(LdapConfig {
+            url: "ldap://localhost".to_string(),
+            base_dn: "dc=example,dc=com".to_string(),
+            default_role: Some("owner".to_string()),
+            ..Default::default()
+        })
+        .is_err());
+    }
+}
@@ -0,0 +1,130 @@
+//! Content licenses
+//!
+//! The `default_license` setting holds the license of articles that do not
+//! choose their own, as JSON like `{"kind": "cc-by-sa"}` or
+//! `{"kind": "custom", "name": "Free to quote", "url": "/reuse"}`. Without
+//! it articles are all rights reserved.
+
+use std::collections::HashMap;
+
+use crate::models::{Article, ContentLicense, LicenseInfo};
+use crate::services::settings::SettingsService;
+
+/// Setting holding the site default license as JSON
+pub const DEFAULT_LICENSE_KEY: &str = "default_license";
+
+/// Errors returned when saving the site default
+#[derive(Debug, thiserror::Error)]
+pub enum LicenseError {
+    /// Invalid license
+    #[error("{0}")]
+    Validation(String),
+
+    /// Internal error
+    #[error("Internal error: {0}")]
+    Internal(String),
+}
+
+/// Parse the stored setting; empty or invalid values give the default
+fn parse_default(value: Option<&str>) -> ContentLicense {
+    let Some(json) = value.map(str::trim).filter(|v| !v.is_empty()) else {
+        return ContentLicense::default();
+    };
+    // Also written by the generic settings endpoint, so check it again
+    serde_json::from_str::<ContentLicense>(json)
+        .map_err(|e| e.to_string())
+        .and_then(ContentLicense::normalize)
+        .unwrap_or_else(|e| {
+            tracing::warn!("Ignoring invalid {}: {}", DEFAULT_LICENSE_KEY, e);
+            ContentLicense::default()
+        })
+}
+
+/// The site default from a settings map
+pub fn from_settings(settings: &HashMap<String, String>) -> ContentLicense {
+    parse_default(settings.get(DEFAULT_LICENSE_KEY).map(String::as_str))
+}
+
+/// The stored site default, or all rights reserved when unset or unreadable
+pub async fn load(settings: &SettingsService) -> ContentLicense {
+    match settings.get(DEFAULT_LICENSE_KEY).await {
+        Ok(value) => parse_default(value.as_deref()),
+        Err(e) => {
+            tracing::warn!("Failed to read {}: {}", DEFAULT_LICENSE_KEY, e);
+            ContentLicense::default()
+        }
+    }
+}
+
+/// Validate and store a new site default
+pub async fn save(
+    settings: &SettingsService,
+    license: ContentLicense,
+) -> Result<ContentLicense, LicenseError> {
+    let license = license.normalize().map_err(LicenseError::Validation)?;
+    let json =
+        serde_json::to_string(&license).map_err(|e| LicenseError::Internal(e.to_string()))?;
+    settings
+        .set_setting(DEFAULT_LICENSE_KEY, &json)
+        .await
+        .map_err(|e| LicenseError::Internal(e.to_string()))?;
+    Ok(license)
+}
+
+/// License `article` is published under
+pub fn resolve(article: &Article, default: &ContentLicense) -> LicenseInfo {
+    match &article.license {
+        Some(license) => license.info(false),
+        None => default.info(true),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::{ArticleStatus, LicenseKind};
+
+    #[test]
+    fn default_comes_from_settings() {
+        let mut settings = HashMap::new();
+        assert_eq!(from_settings(&settings), ContentLicense::default());
+
+        settings.insert(
+            DEFAULT_LICENSE_KEY.to_string(),
+            r#"{"kind": "cc-by-nc"}"#.to_string(),
+        );
+        assert_eq!(
+            from_settings(&settings),
+            ContentLicense::new(LicenseKind::CcByNc)
+        );
+
+        // A custom license without a name is ignored
+        settings.insert(
+            DEFAULT_LICENSE_KEY.to_string(),
+            r#"{"kind": "custom"}"#.to_string(),
+        );
+        assert_eq!(from_settings(&settings), ContentLicense::default());
+    }
+
+    #[test]
+    fn articles_inherit_the_default_until_they_choose() {
+        let mut article = Article::new(
+            "post".to_string(),
+            "Post".to_string(),
+            String::new(),
+            String::new(),
+            1,
+            1,
+            ArticleStatus::Published,
+        );
+        let default = ContentLicense::new(LicenseKind::CcBy);
+        let info = resolve(&article, &default);
+        assert_eq!(info.kind, LicenseKind::CcBy);
+        assert!(info.inherited);
+
+        article.license = Some(ContentLicense::new(LicenseKind::Cc0));
+        let info = resolve(&article, &default);
+        assert_eq!(info.name, "CC0 1.0");
+        assert!(!info.inherited);
+    }
+}
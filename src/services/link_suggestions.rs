@@ -0,0 +1,439 @@
+//! Internal link suggestions for drafts
+//!
+//! The admin editor posts a draft and gets back published articles worth
+//! linking to. Keywords are taken from the draft's text (words of three or
+//! more letters, or pairs of adjacent characters for Chinese and Japanese)
+//! and weighted by how rare they are across published articles, so a shared
+//! "database" counts for more than a shared "things". Shared tags count on
+//! top of that. Articles the draft already links to are left out.
+//!
+//! Each suggestion carries an anchor: the article's title, a shared tag or a
+//! shared keyword as it appears in the draft, ready to be turned into a link.
+
+use std::collections::{HashMap, HashSet};
+use std::sync::Arc;
+
+use once_cell::sync::Lazy;
+use regex::Regex;
+use serde::Serialize;
+
+use crate::db::repositories::{ArticleRepository, TagRepository};
+use crate::models::{Article, ArticleSortBy, Tag};
+use crate::services::settings::generate_article_url;
+
+/// Articles loaded per query while building the candidate list
+const SCAN_BATCH: i64 = 200;
+
+/// Draft keywords compared against each article, by weight
+const DRAFT_KEYWORDS: usize = 40;
+
+/// Shared keywords an article needs when it shares no tag with the draft
+const MIN_SHARED_KEYWORDS: usize = 2;
+
+/// Score of a shared tag
+const TAG_WEIGHT: f64 = 3.0;
+
+/// Multiplier for a shared keyword that is also in the article's title
+const TITLE_WEIGHT: f64 = 2.0;
+
+/// Suggestions returned when the caller does not say
+pub const DEFAULT_LIMIT: usize = 10;
+
+/// Most suggestions a caller may ask for
+pub const MAX_LIMIT: usize = 50;
+
+/// Code, HTML tags, Markdown link targets and URLs, none of them prose
+static SKIPPED: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(
+        r"(?ms)^(```|~~~).*?^(```|~~~)|<pre\b.*?</pre>|`[^`\n]+`|<[^>]*>|\]\([^)\n]*\)|[a-zA-Z][a-zA-Z0-9+.-]*://\S+",
+    )
+    .unwrap()
+});
+
+/// A run of Chinese or Japanese characters, or of other letters and digits
+static TOKEN_RE: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(
+        r"[\p{Han}\p{Hiragana}\p{Katakana}]+|[[\p{L}\p{N}]&&[^\p{Han}\p{Hiragana}\p{Katakana}]]+",
+    )
+    .unwrap()
+});
+
+static STOPWORDS: Lazy<HashSet<&'static str>> = Lazy::new(|| {
+    [
+        "about", "after", "again", "all", "also", "and", "any", "are", "because", "been", "before",
+        "being", "but", "can", "could", "did", "does", "doing", "down", "each", "few", "for",
+        "from", "further", "had", "has", "have", "having", "her", "here", "hers", "him", "his",
+        "how", "into", "its", "just", "more", "most", "not", "now", "off", "once", "only", "other",
+        "our", "ours", "out", "over", "own", "same", "she", "should", "some", "such", "than",
+        "that", "the", "their", "them", "then", "there", "these", "they", "this", "those",
+        "through", "too", "under", "until", "very", "was", "were", "what", "when", "where",
+        "which", "while", "who", "whom", "why", "will", "with", "would", "you", "your", "yours",
+    ]
+    .into_iter()
+    .collect()
+});
+
+/// A draft to find links for
+#[derive(Debug, Clone, Default)]
+pub struct Draft {
+    pub title: String,
+    /// Markdown or HTML
+    pub content: String,
+    /// Tag names
+    pub tags: Vec<String>,
+    /// The article being edited, never suggested; 0 for a new article
+    pub article_id: i64,
+}
+
+/// A published article the draft could link to
+#[derive(Debug, Clone, Serialize)]
+pub struct LinkSuggestion {
+    pub article_id: i64,
+    pub slug: String,
+    pub title: String,
+    pub url: String,
+    /// Relative relevance, only meaningful against other suggestions
+    pub score: f64,
+    pub shared_tags: Vec<String>,
+    /// Most significant first
+    pub shared_keywords: Vec<String>,
+    /// Text in the draft to link from, if any fits
+    pub anchor: Option<String>,
+}
+
+/// Keywords of a published article
+struct Candidate {
+    article: Article,
+    tags: Vec<Tag>,
+    keywords: HashSet<String>,
+    title_keywords: HashSet<String>,
+}
+
+/// A candidate that scored against the draft
+struct Match {
+    index: usize,
+    score: f64,
+    shared_tags: Vec<String>,
+    shared_keywords: Vec<String>,
+}
+
+/// Suggests published articles to link to from a draft
+pub struct LinkSuggestionService {
+    articles: Arc<dyn ArticleRepository>,
+    tags: Arc<dyn TagRepository>,
+}
+
+impl LinkSuggestionService {
+    pub fn new(articles: Arc<dyn ArticleRepository>, tags: Arc<dyn TagRepository>) -> Self {
+        Self { articles, tags }
+    }
+
+    /// Up to `limit` articles to link to from `draft`, best first
+    pub async fn suggest(
+        &self,
+        draft: &Draft,
+        permalink_structure: &str,
+        limit: usize,
+    ) -> anyhow::Result<Vec<LinkSuggestion>> {
+        let mut candidates = Vec::new();
+        let mut offset = 0;
+        loop {
+            let batch = self
+                .articles
+                .list_published(offset, SCAN_BATCH, ArticleSortBy::default())
+                .await?;
+            let done = (batch.len() as i64) < SCAN_BATCH;
+            offset += batch.len() as i64;
+            let ids: Vec<i64> = batch.iter().map(|a| a.id).collect();
+            let mut tags = self.tags.get_by_article_ids(&ids).await?;
+            for article in batch {
+                if article.id == draft.article_id {
+                    continue;
+                }
+                candidates.push(Candidate {
+                    tags: tags.remove(&article.id).unwrap_or_default(),
+                    keywords: keywords(&article.content_html)
+                        .into_keys()
+                        .chain(keywords(&article.title).into_keys())
+                        .collect(),
+                    title_keywords: keywords(&article.title).into_keys().collect(),
+                    article,
+                });
+            }
+            if done {
+                break;
+            }
+        }
+
+        let text = prose(&draft.content);
+        let suggestions = rank(draft, &text, &candidates)
+            .into_iter()
+            .filter_map(|m| {
+                let article = &candidates[m.index].article;
+                let url = generate_article_url(
+                    permalink_structure,
+                    article.id,
+                    &article.slug,
+                    article.published_at.as_ref(),
+                );
+                if draft.content.contains(&url) {
+                    return None;
+                }
+                let anchor = std::iter::once(article.title.as_str())
+                    .chain(m.shared_tags.iter().map(String::as_str))
+                    .chain(m.shared_keywords.iter().map(String::as_str))
+                    .find_map(|phrase| find_phrase(&text, phrase));
+                Some(LinkSuggestion {
+                    article_id: article.id,
+                    slug: article.slug.clone(),
+                    title: article.title.clone(),
+                    url,
+                    score: (m.score * 100.0).round() / 100.0,
+                    shared_tags: m.shared_tags,
+                    shared_keywords: m.shared_keywords,
+                    anchor,
+                })
+            })
+            .take(limit)
+            .collect();
+        Ok(suggestions)
+    }
+}
+
+/// Candidates related to the draft, best first
+fn rank(draft: &Draft, text: &str, candidates: &[Candidate]) -> Vec<Match> {
+    let mut document_frequency: HashMap<&str, usize> = HashMap::new();
+    for candidate in candidates {
+        for keyword in &candidate.keywords {
+            *document_frequency.entry(keyword.as_str()).or_default() += 1;
+        }
+    }
+    let idf = |keyword: &str| {
+        let df = document_frequency.get(keyword).copied().unwrap_or(0);
+        ((candidates.len() as f64 + 1.0) / (df as f64 + 1.0)).ln() + 1.0
+    };
+
+    let mut counts = keywords(text);
+    for (keyword, count) in keywords(&draft.title) {
+        *counts.entry(keyword).or_default() += count;
+    }
+    let mut draft_keywords: Vec<(String, f64)> = counts
+        .into_iter()
+        .map(|(keyword, count)| {
+            let weight = (1.0 + (count as f64).ln()) * idf(&keyword);
+            (keyword, weight)
+        })
+        .collect();
+    draft_keywords.sort_by(|a, b| b.1.total_cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+    draft_keywords.truncate(DRAFT_KEYWORDS);
+
+    let draft_tags: HashSet<String> = draft.tags.iter().map(|t| t.trim().to_lowercase()).collect();
+
+    let mut ranked: Vec<_> = candidates
+        .iter()
+        .enumerate()
+        .filter_map(|(index, candidate)| {
+            let shared_tags: Vec<String> = candidate
+                .tags
+                .iter()
+                .filter(|tag| {
+                    draft_tags.contains(&tag.name.to_lowercase())
+                        || draft_tags.contains(&tag.slug.to_lowercase())
+                })
+                .map(|tag| tag.name.clone())
+                .collect();
+            let mut score = TAG_WEIGHT * shared_tags.len() as f64;
+            let mut shared_keywords = Vec::new();
+            for (keyword, _) in &draft_keywords {
+                if !candidate.keywords.contains(keyword) {
+                    continue;
+                }
+                score += if candidate.title_keywords.contains(keyword) {
+                    idf(keyword) * TITLE_WEIGHT
+                } else {
+                    idf(keyword)
+                };
+                shared_keywords.push(keyword.clone());
+            }
+            if shared_tags.is_empty() && shared_keywords.len() < MIN_SHARED_KEYWORDS {
+                return None;
+            }
+            Some(Match {
+                index,
+                score,
+                shared_tags,
+                shared_keywords,
+            })
+        })
+        .collect();
+    ranked.sort_by(|a, b| {
+        b.score.total_cmp(&a.score).then_with(|| {
+            candidates[b.index]
+                .article
+                .id
+                .cmp(&candidates[a.index].article.id)
+        })
+    });
+    ranked
+}
+
+/// The prose of Markdown or HTML, with code, tags and link targets blanked
+fn prose(content: &str) -> String {
+    SKIPPED.replace_all(content, " ").into_owned()
+}
+
+/// Lowercased keywords of `content` and how often each occurs
+fn keywords(content: &str) -> HashMap<String, usize> {
+    let text = prose(content).to_lowercase();
+    let mut counts = HashMap::new();
+    for token in TOKEN_RE.find_iter(&text).map(|m| m.as_str()) {
+        if is_cjk(token.chars().next().unwrap_or_default()) {
+            let chars: Vec<char> = token.chars().collect();
+            for pair in chars.windows(2) {
+                *counts.entry(pair.iter().collect()).or_default() += 1;
+            }
+        } else if token.chars().count() >= 3
+            && !token.chars().all(|c| c.is_ascii_digit())
+            && !STOPWORDS.contains(token)
+        {
+            *counts.entry(token.to_string()).or_default() += 1;
+        }
+    }
+    counts
+}
+
+fn is_cjk(ch: char) -> bool {
+    matches!(ch,
+        '\u{3040}'..='\u{30ff}'
+        | '\u{3400}'..='\u{4dbf}'
+        | '\u{4e00}'..='\u{9fff}'
+        | '\u{f900}'..='\u{faff}'
+        | '\u{20000}'..='\u{2fa1f}')
+}
+
+/// `phrase` as written in `text`, matched without regard to case; Latin
+/// words only match whole
+fn find_phrase(text: &str, phrase: &str) -> Option<String> {
+    let phrase = phrase.trim();
+    if phrase.is_empty() {
+        return None;
+    }
+    let escaped = regex::escape(phrase);
+    let pattern = if phrase.chars().any(is_cjk) {
+        format!("(?i){}", escaped)
+    } else {
+        format!(r"(?i)\b{}\b", escaped)
+    };
+    Regex::new(&pattern)
+        .ok()?
+        .find(text)
+        .map(|m| m.as_str().to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::ArticleStatus;
+
+    fn candidate(id: i64, title: &str, content: &str, tags: &[&str]) -> Candidate {
+        let mut article = Article::new(
+            id.to_string(),
+            title.to_string(),
+            content.to_string(),
+            content.to_string(),
+            1,
+            1,
+            ArticleStatus::Published,
+        );
+        article.id = id;
+        Candidate {
+            tags: tags
+                .iter()
+                .map(|name| Tag::new(name.to_lowercase(), name.to_string()))
+                .collect(),
+            keywords: keywords(content)
+                .into_keys()
+                .chain(keywords(title).into_keys())
+                .collect(),
+            title_keywords: keywords(title).into_keys().collect(),
+            article,
+        }
+    }
+
+    #[test]
+    fn articles_sharing_rare_keywords_rank_first() {
+        let candidates = vec![
+            candidate(
+                1,
+                "Tuning PostgreSQL",
+                "<p>Vacuum and index bloat in PostgreSQL.</p>",
+                &[],
+            ),
+            candidate(
+                2,
+                "Weekend notes",
+                "<p>Some notes about the garden and a walk.</p>",
+                &[],
+            ),
+            candidate(
+                3,
+                "Backups",
+                "<p>Dumping a PostgreSQL database nightly.</p>",
+                &["Ops"],
+            ),
+        ];
+        let draft = Draft {
+            title: "Why my PostgreSQL index grew".to_string(),
+            content: "Our index bloat came back after every bulk load into PostgreSQL.".to_string(),
+            ..Default::default()
+        };
+        let text = prose(&draft.content);
+        let ranked = rank(&draft, &text, &candidates);
+        let ids: Vec<i64> = ranked
+            .iter()
+            .map(|m| candidates[m.index].article.id)
+            .collect();
+        assert_eq!(ids, vec![1]);
+        assert!(ranked[0].shared_keywords.contains(&"bloat".to_string()));
+
+        let draft = Draft {
+            tags: vec!["ops".to_string()],
+            ..draft
+        };
+        let ranked = rank(&draft, &text, &candidates);
+        let ids: Vec<i64> = ranked
+            .iter()
+            .map(|m| candidates[m.index].article.id)
+            .collect();
+        assert_eq!(ids, vec![1, 3]);
+        assert_eq!(ranked[1].shared_tags, vec!["Ops".to_string()]);
+    }
+
+    #[test]
+    fn keywords_skip_code_links_and_stopwords() {
+        let words = keywords(
+            "The [cache](https://example.com/redis) layer\n```\nfn hidden() {}\n```\nuses `inline` 缓存策略",
+        );
+        assert!(words.contains_key("cache"));
+        assert!(words.contains_key("layer"));
+        assert!(words.contains_key("缓存"));
+        assert!(!words.contains_key("the"));
+        assert!(!words.contains_key("redis"));
+        assert!(!words.contains_key("hidden"));
+        assert!(!words.contains_key("inline"));
+    }
+
+    #[test]
+    fn anchors_match_whole_words_as_written() {
+        let text = "Setting up Rust Analyzer for rustaceans";
+        assert_eq!(
+            find_phrase(text, "rust analyzer").as_deref(),
+            Some("Rust Analyzer")
+        );
+        assert_eq!(find_phrase(text, "rustacean"), None);
+        assert_eq!(
+            find_phrase("我们的缓存策略", "缓存").as_deref(),
+            Some("缓存")
+        );
+    }
+}
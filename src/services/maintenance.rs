@@ -0,0 +1,513 @@
+//! Maintenance mode
+//!
+//! While `maintenance_enabled` is "true", public routes answer with a 503
+//! maintenance page. Admin routes, logged-in admins and addresses in
+//! `maintenance_allowed_ips` (single IPs or CIDR ranges, one per line or
+//! comma separated) keep full access.
+//!
+//! Maintenance windows scheduled in `maintenance_windows` switch the mode on
+//! for their duration without touching `maintenance_enabled`, and are
+//! announced with a banner ahead of time.
+
+use std::net::IpAddr;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use tokio::sync::RwLock;
+
+use crate::services::announcement::{Announcement, AnnouncementStyle};
+use crate::services::settings::SettingsService;
+
+/// Setting that turns maintenance mode on
+pub const MAINTENANCE_ENABLED_KEY: &str = "maintenance_enabled";
+/// Message shown on the maintenance page
+pub const MAINTENANCE_MESSAGE_KEY: &str = "maintenance_message";
+/// Addresses that bypass maintenance mode
+pub const MAINTENANCE_ALLOWED_IPS_KEY: &str = "maintenance_allowed_ips";
+/// Scheduled maintenance windows, a JSON list
+pub const MAINTENANCE_WINDOWS_KEY: &str = "maintenance_windows";
+
+const MAX_WINDOWS: usize = 50;
+const MAX_WINDOW_MESSAGE_LEN: usize = 1000;
+/// Banners can go up at most 30 days ahead
+const MAX_ANNOUNCE_HOURS: u32 = 720;
+/// Finished windows stay listed on the status page this long
+const WINDOW_HISTORY_DAYS: i64 = 30;
+
+/// How long the settings are reused before being read again; the admin
+/// endpoint refreshes them immediately
+const STATE_TTL: Duration = Duration::from_secs(5);
+
+/// Errors returned by the maintenance service
+#[derive(Debug, thiserror::Error)]
+pub enum MaintenanceError {
+    /// Invalid allowlist entry or window
+    #[error("{0}")]
+    Validation(String),
+
+    /// Internal error
+    #[error("Internal error: {0}")]
+    Internal(String),
+}
+
+/// A scheduled period of maintenance
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct MaintenanceWindow {
+    pub starts_at: DateTime<Utc>,
+    pub ends_at: DateTime<Utc>,
+    /// Shown during the window; the maintenance message when empty
+    #[serde(default)]
+    pub message: String,
+    /// Hours before the start a banner announces the window; 0 for none
+    #[serde(default = "default_announce_hours")]
+    pub announce_hours: u32,
+}
+
+fn default_announce_hours() -> u32 {
+    24
+}
+
+impl MaintenanceWindow {
+    pub fn is_active(&self, now: DateTime<Utc>) -> bool {
+        self.starts_at <= now && now < self.ends_at
+    }
+
+    /// "scheduled", "in_progress" or "completed"
+    pub fn phase(&self, now: DateTime<Utc>) -> &'static str {
+        if now < self.starts_at {
+            "scheduled"
+        } else if now < self.ends_at {
+            "in_progress"
+        } else {
+            "completed"
+        }
+    }
+
+    /// Banner shown from `announce_hours` before the window until it starts
+    pub fn announcement(&self) -> Option<Announcement> {
+        if self.announce_hours == 0 {
+            return None;
+        }
+        let period = format!(
+            "{} – {} UTC",
+            self.starts_at.format("%Y-%m-%d %H:%M"),
+            self.ends_at.format("%Y-%m-%d %H:%M")
+        );
+        Some(Announcement {
+            id: format!("maintenance-{}", self.starts_at.timestamp()),
+            message: if self.message.is_empty() {
+                format!("Scheduled maintenance: {}", period)
+            } else {
+                format!("{} ({})", self.message, period)
+            },
+            style: AnnouncementStyle::Warning,
+            starts_at: Some(self.starts_at - chrono::Duration::hours(self.announce_hours as i64)),
+            ends_at: Some(self.starts_at),
+            dismissible: true,
+        })
+    }
+}
+
+/// Check windows and sort them by start, dropping those that ended more
+/// than [`WINDOW_HISTORY_DAYS`] before `now`
+pub fn validate_windows(
+    mut windows: Vec<MaintenanceWindow>,
+    now: DateTime<Utc>,
+) -> Result<Vec<MaintenanceWindow>, MaintenanceError> {
+    windows.retain(|w| w.ends_at > now - chrono::Duration::days(WINDOW_HISTORY_DAYS));
+    if windows.len() > MAX_WINDOWS {
+        return Err(MaintenanceError::Validation(format!(
+            "At most {} maintenance windows are allowed",
+            MAX_WINDOWS
+        )));
+    }
+    windows.sort_by_key(|w| w.starts_at);
+    for window in &mut windows {
+        window.message = window.message.trim().to_string();
+        if window.ends_at <= window.starts_at {
+            return Err(MaintenanceError::Validation(
+                "A maintenance window must end after it starts".to_string(),
+            ));
+        }
+        if window.message.chars().count() > MAX_WINDOW_MESSAGE_LEN {
+            return Err(MaintenanceError::Validation(format!(
+                "Maintenance window messages must be at most {} characters",
+                MAX_WINDOW_MESSAGE_LEN
+            )));
+        }
+        if window.announce_hours > MAX_ANNOUNCE_HOURS {
+            return Err(MaintenanceError::Validation(format!(
+                "Maintenance windows can be announced at most {} hours ahead",
+                MAX_ANNOUNCE_HOURS
+            )));
+        }
+    }
+    if windows.windows(2).any(|w| w[1].starts_at < w[0].ends_at) {
+        return Err(MaintenanceError::Validation(
+            "Maintenance windows must not overlap".to_string(),
+        ));
+    }
+    Ok(windows)
+}
+
+/// The stored windows; empty when unset or unreadable
+pub async fn load_windows(settings: &SettingsService) -> Vec<MaintenanceWindow> {
+    match settings.get(MAINTENANCE_WINDOWS_KEY).await {
+        Ok(Some(json)) => parse_windows(&json),
+        Ok(None) => Vec::new(),
+        Err(e) => {
+            tracing::warn!("Failed to read {}: {}", MAINTENANCE_WINDOWS_KEY, e);
+            Vec::new()
+        }
+    }
+}
+
+fn parse_windows(json: &str) -> Vec<MaintenanceWindow> {
+    if json.trim().is_empty() {
+        return Vec::new();
+    }
+    // Also written by the generic settings endpoint, so check it again
+    serde_json::from_str::<Vec<MaintenanceWindow>>(json)
+        .map_err(|e| e.to_string())
+        .and_then(|windows| validate_windows(windows, Utc::now()).map_err(|e| e.to_string()))
+        .unwrap_or_else(|e| {
+            tracing::warn!("Ignoring invalid {}: {}", MAINTENANCE_WINDOWS_KEY, e);
+            Vec::new()
+        })
+}
+
+/// Current maintenance mode settings
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct MaintenanceState {
+    /// Turned on by hand, independent of the windows
+    pub enabled: bool,
+    pub message: String,
+    pub allowed_ips: Vec<String>,
+    pub windows: Vec<MaintenanceWindow>,
+}
+
+impl MaintenanceState {
+    /// The window in progress at `now`
+    pub fn active_window(&self, now: DateTime<Utc>) -> Option<&MaintenanceWindow> {
+        self.windows.iter().find(|w| w.is_active(now))
+    }
+
+    /// Whether public requests are blocked at `now`, by hand or by a window
+    pub fn in_effect(&self, now: DateTime<Utc>) -> bool {
+        self.enabled || self.active_window(now).is_some()
+    }
+
+    /// Message for the maintenance page at `now`
+    pub fn message_at(&self, now: DateTime<Utc>) -> String {
+        self.active_window(now)
+            .map(|w| w.message.clone())
+            .filter(|m| !m.is_empty())
+            .unwrap_or_else(|| self.message.clone())
+    }
+}
+
+/// Input for changing maintenance mode; omitted fields are kept
+#[derive(Debug, Clone, Deserialize)]
+pub struct UpdateMaintenanceInput {
+    #[serde(default)]
+    pub enabled: Option<bool>,
+    pub message: Option<String>,
+    pub allowed_ips: Option<Vec<String>>,
+    /// Replaces the scheduled windows
+    #[serde(default)]
+    pub windows: Option<Vec<MaintenanceWindow>>,
+}
+
+/// An allowlisted address or network
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct IpRule {
+    network: IpAddr,
+    prefix: u8,
+}
+
+impl IpRule {
+    fn parse(s: &str) -> Option<Self> {
+        let (addr, prefix) = match s.split_once('/') {
+            Some((addr, prefix)) => (addr, Some(prefix.parse::<u8>().ok()?)),
+            None => (s, None),
+        };
+        let network: IpAddr = addr.trim().parse().ok()?;
+        let max = if network.is_ipv4() { 32 } else { 128 };
+        let prefix = prefix.unwrap_or(max);
+        (prefix <= max).then_some(Self { network, prefix })
+    }
+
+    fn contains(&self, ip: IpAddr) -> bool {
+        // Compare IPv4-mapped IPv6 peers against IPv4 rules
+        let ip = match ip {
+            IpAddr::V6(v6) => v6.to_ipv4_mapped().map(IpAddr::V4).unwrap_or(ip),
+            v4 => v4,
+        };
+        match (self.network, ip) {
+            (IpAddr::V4(net), IpAddr::V4(ip)) => {
+                let mask = u32::MAX.checked_shl(32 - self.prefix as u32).unwrap_or(0);
+                u32::from(net) & mask == u32::from(ip) & mask
+            }
+            (IpAddr::V6(net), IpAddr::V6(ip)) => {
+                let mask = u128::MAX.checked_shl(128 - self.prefix as u32).unwrap_or(0);
+                u128::from(net) & mask == u128::from(ip) & mask
+            }
+            _ => false,
+        }
+    }
+}
+
+/// Split the stored allowlist into entries
+fn split_allowlist(value: &str) -> Vec<String> {
+    value
+        .split([',', '\n'])
+        .map(str::trim)
+        .filter(|s| !s.is_empty())
+        .map(str::to_string)
+        .collect()
+}
+
+#[derive(Debug)]
+struct Loaded {
+    state: MaintenanceState,
+    rules: Vec<IpRule>,
+}
+
+/// Reads and changes maintenance mode
+pub struct MaintenanceService {
+    settings: Arc<SettingsService>,
+    cached: RwLock<Option<(Instant, Arc<Loaded>)>>,
+}
+
+impl MaintenanceService {
+    pub fn new(settings: Arc<SettingsService>) -> Self {
+        Self {
+            settings,
+            cached: RwLock::new(None),
+        }
+    }
+
+    async fn loaded(&self) -> Arc<Loaded> {
+        if let Some((at, loaded)) = self.cached.read().await.as_ref() {
+            if at.elapsed() < STATE_TTL {
+                return loaded.clone();
+            }
+        }
+
+        let get = |key: &'static str| async move {
+            self.settings
+                .get(key)
+                .await
+                .ok()
+                .flatten()
+                .unwrap_or_default()
+        };
+        let allowed_ips = split_allowlist(&get(MAINTENANCE_ALLOWED_IPS_KEY).await);
+        let rules = allowed_ips
+            .iter()
+            .filter_map(|entry| {
+                let rule = IpRule::parse(entry);
+                if rule.is_none() {
+                    tracing::warn!(entry = %entry, "ignoring invalid maintenance allowlist entry");
+                }
+                rule
+            })
+            .collect();
+        let loaded = Arc::new(Loaded {
+            state: MaintenanceState {
+                enabled: get(MAINTENANCE_ENABLED_KEY).await == "true",
+                message: get(MAINTENANCE_MESSAGE_KEY).await,
+                allowed_ips,
+                windows: parse_windows(&get(MAINTENANCE_WINDOWS_KEY).await),
+            },
+            rules,
+        });
+        *self.cached.write().await = Some((Instant::now(), loaded.clone()));
+        loaded
+    }
+
+    /// Current settings
+    pub async fn state(&self) -> MaintenanceState {
+        self.loaded().await.state.clone()
+    }
+
+    /// Whether public requests from `client_ip` get the maintenance page.
+    /// Returns the message to show.
+    pub async fn blocks(&self, client_ip: Option<IpAddr>) -> Option<String> {
+        let loaded = self.loaded().await;
+        // Checked on every request so windows start and end on time
+        let now = Utc::now();
+        if !loaded.state.in_effect(now) {
+            return None;
+        }
+        if client_ip.is_some_and(|ip| loaded.rules.iter().any(|rule| rule.contains(ip))) {
+            return None;
+        }
+        Some(loaded.state.message_at(now))
+    }
+
+    /// Change maintenance mode
+    pub async fn update(
+        &self,
+        input: UpdateMaintenanceInput,
+    ) -> Result<MaintenanceState, MaintenanceError> {
+        if let Some(allowed_ips) = &input.allowed_ips {
+            let entries: Vec<&str> = allowed_ips
+                .iter()
+                .map(|s| s.trim())
+                .filter(|s| !s.is_empty())
+                .collect();
+            if let Some(bad) = entries.iter().find(|e| IpRule::parse(e).is_none()) {
+                return Err(MaintenanceError::Validation(format!(
+                    "Invalid IP address or CIDR range: {}",
+                    bad
+                )));
+            }
+            self.set(MAINTENANCE_ALLOWED_IPS_KEY, &entries.join("\n"))
+                .await?;
+        }
+        if let Some(windows) = input.windows {
+            let windows = validate_windows(windows, Utc::now())?;
+            let json = serde_json::to_string(&windows)
+                .map_err(|e| MaintenanceError::Internal(e.to_string()))?;
+            self.set(MAINTENANCE_WINDOWS_KEY, &json).await?;
+        }
+        if let Some(message) = &input.message {
+            self.set(MAINTENANCE_MESSAGE_KEY, message.trim()).await?;
+        }
+        if let Some(enabled) = input.enabled {
+            self.set(
+                MAINTENANCE_ENABLED_KEY,
+                if enabled { "true" } else { "false" },
+            )
+            .await?;
+        }
+
+        *self.cached.write().await = None;
+        Ok(self.state().await)
+    }
+
+    async fn set(&self, key: &str, value: &str) -> Result<(), MaintenanceError> {
+        self.settings
+            .set(key, value)
+            .await
+            .map_err(|e| MaintenanceError::Internal(e.to_string()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Duration as ChronoDuration;
+
+    fn window(start_hours: i64, end_hours: i64) -> MaintenanceWindow {
+        let base = DateTime::parse_from_rfc3339("2026-03-01T12:00:00Z")
+            .unwrap()
+            .with_timezone(&Utc);
+        MaintenanceWindow {
+            starts_at: base + ChronoDuration::hours(start_hours),
+            ends_at: base + ChronoDuration::hours(end_hours),
+            message: String::new(),
+            announce_hours: 24,
+        }
+    }
+
+    fn ip(s: &str) -> IpAddr {
+        s.parse().unwrap()
+    }
+
+    #[test]
+    fn allowlist_matches_addresses_and_ranges() {
+        let single = IpRule::parse("203.0.113.7").unwrap();
+        assert!(single.contains(ip("203.0.113.7")));
+        assert!(!single.contains(ip("203.0.113.8")));
+        assert!(single.contains(ip("::ffff:203.0.113.7")));
+
+        let range = IpRule::parse("10.0.0.0/8").unwrap();
+        assert!(range.contains(ip("10.20.30.40")));
+        assert!(!range.contains(ip("11.0.0.1")));
+        assert!(IpRule::parse("0.0.0.0/0").unwrap().contains(ip("1.2.3.4")));
+
+        let v6 = IpRule::parse("2001:db8::/32").unwrap();
+        assert!(v6.contains(ip("2001:db8:1::1")));
+        assert!(!v6.contains(ip("2001:db9::1")));
+        assert!(!v6.contains(ip("10.0.0.1")));
+
+        assert!(IpRule::parse("10.0.0.0/33").is_none());
+        assert!(IpRule::parse("example.com").is_none());
+        assert_eq!(
+            split_allowlist("1.2.3.4, 10.0.0.0/8\n\n::1"),
+            vec!["1.2.3.4", "10.0.0.0/8", "::1"]
+        );
+    }
+
+    #[test]
+    fn windows_switch_maintenance_on_for_their_duration() {
+        let state = MaintenanceState {
+            message: "Back soon".to_string(),
+            windows: vec![MaintenanceWindow {
+                message: "Database upgrade".to_string(),
+                ..window(0, 2)
+            }],
+            ..Default::default()
+        };
+        let start = state.windows[0].starts_at;
+        assert!(!state.in_effect(start - ChronoDuration::minutes(1)));
+        assert!(state.in_effect(start));
+        assert_eq!(state.message_at(start), "Database upgrade");
+        assert!(!state.in_effect(state.windows[0].ends_at));
+        assert_eq!(state.windows[0].phase(start), "in_progress");
+        assert_eq!(
+            state.windows[0].phase(state.windows[0].ends_at),
+            "completed"
+        );
+
+        let manual = MaintenanceState {
+            enabled: true,
+            ..state
+        };
+        assert!(manual.in_effect(start - ChronoDuration::days(1)));
+        assert_eq!(
+            manual.message_at(start - ChronoDuration::days(1)),
+            "Back soon"
+        );
+    }
+
+    #[test]
+    fn windows_are_announced_ahead_of_time() {
+        let banner = window(0, 2).announcement().unwrap();
+        assert_eq!(
+            banner.id,
+            format!("maintenance-{}", window(0, 2).starts_at.timestamp())
+        );
+        assert_eq!(banner.style, AnnouncementStyle::Warning);
+        assert_eq!(banner.ends_at, Some(window(0, 2).starts_at));
+        assert!(banner.is_active(window(-23, 0).starts_at));
+        assert!(!banner.is_active(window(-25, 0).starts_at));
+        assert!(banner
+            .message
+            .contains("2026-03-01 12:00 – 2026-03-01 14:00 UTC"));
+
+        let silent = MaintenanceWindow {
+            announce_hours: 0,
+            ..window(0, 2)
+        };
+        assert!(silent.announcement().is_none());
+    }
+
+    #[test]
+    fn rejects_overlapping_and_backwards_windows() {
+        let now = window(0, 0).starts_at;
+        let sorted = validate_windows(vec![window(5, 6), window(1, 2)], now).unwrap();
+        assert_eq!(sorted, vec![window(1, 2), window(5, 6)]);
+
+        assert!(validate_windows(vec![window(1, 3), window(2, 4)], now).is_err());
+        assert!(validate_windows(vec![window(2, 1)], now).is_err());
+
+        // Long finished windows are dropped rather than rejected
+        let old = window(-24 * 40, -24 * 40 + 1);
+        assert!(validate_windows(vec![old], now).unwrap().is_empty());
+    }
+}
@@ -0,0 +1,424 @@
+//! Markdown engines
+//!
+//! A [`MarkdownEngine`] turns Markdown into HTML. [`MarkdownRenderer`]
+//! handles everything around it (shortcodes, math, image grids, hooks and
+//! sanitizing), so those behave the same whichever engine `markdown.engine`
+//! selects. Engines give headings their anchor ids and leave code blocks to
+//! the renderer, which highlights them.
+//!
+//! [`MarkdownRenderer`]: super::MarkdownRenderer
+
+use comrak::nodes::{AstNode, NodeHtmlBlock, NodeValue};
+use comrak::{format_html, parse_document, Arena};
+use pulldown_cmark::{html, CodeBlockKind, Event, Options, Parser, Tag, TagEnd};
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use super::html_escape;
+use crate::config::{ComrakOptions, MarkdownConfig, MarkdownEngineKind, PulldownOptions};
+
+/// Renders a code block from its info string (if any) and contents
+pub type CodeBlockRenderer<'a> = dyn Fn(Option<&str>, &str) -> String + 'a;
+
+/// Markdown to HTML conversion
+pub trait MarkdownEngine: Send + Sync {
+    /// Name used in `markdown.engine`
+    fn name(&self) -> &'static str;
+
+    /// Render `markdown` to HTML, emitting code blocks through `code_block`
+    /// and headings with ids from [`HeadingIds`]
+    fn render(&self, markdown: &str, code_block: &CodeBlockRenderer<'_>) -> String;
+
+    /// Level and plain text of every heading, in document order
+    fn headings(&self, markdown: &str) -> Vec<(u32, String)>;
+}
+
+/// Build the engine selected in the configuration
+pub fn engine_from_config(config: &MarkdownConfig) -> Arc<dyn MarkdownEngine> {
+    match config.engine {
+        MarkdownEngineKind::Pulldown => {
+            Arc::new(PulldownEngine::new(&config.pulldown, config.hard_breaks))
+        }
+        MarkdownEngineKind::Comrak => {
+            Arc::new(ComrakEngine::new(&config.comrak, config.hard_breaks))
+        }
+    }
+}
+
+/// Assigns unique anchor ids to headings in document order
+#[derive(Debug, Default)]
+pub struct HeadingIds {
+    used: HashMap<String, u32>,
+}
+
+impl HeadingIds {
+    /// Id for the next heading; repeated headings get `-1`, `-2`, ... appended
+    pub fn next(&mut self, text: &str) -> String {
+        let base_id = heading_to_id(text);
+        let count = self.used.entry(base_id.clone()).or_insert(0);
+        let id = if *count == 0 {
+            base_id
+        } else {
+            format!("{}-{}", base_id, count)
+        };
+        *count += 1;
+        id
+    }
+}
+
+/// Convert heading text to a URL-friendly anchor id.
+fn heading_to_id(text: &str) -> String {
+    text.chars()
+        .map(|c| {
+            if c.is_alphanumeric() {
+                c.to_ascii_lowercase()
+            } else if c == ' ' || c == '-' || c == '_' {
+                '-'
+            } else if c > '\x7f' {
+                c
+            }
+            // keep CJK characters
+            else {
+                '-'
+            }
+        })
+        .collect::<String>()
+        .split('-')
+        .filter(|s| !s.is_empty())
+        .collect::<Vec<_>>()
+        .join("-")
+}
+
+/// pulldown-cmark, the default engine
+pub struct PulldownEngine {
+    options: Options,
+    hard_breaks: bool,
+}
+
+impl Default for PulldownEngine {
+    fn default() -> Self {
+        Self::new(&PulldownOptions::default(), true)
+    }
+}
+
+impl PulldownEngine {
+    pub fn new(options: &PulldownOptions, hard_breaks: bool) -> Self {
+        let mut parser_options = Options::empty();
+        parser_options.set(Options::ENABLE_TABLES, options.tables);
+        parser_options.set(Options::ENABLE_STRIKETHROUGH, options.strikethrough);
+        parser_options.set(Options::ENABLE_TASKLISTS, options.tasklists);
+        parser_options.set(Options::ENABLE_FOOTNOTES, options.footnotes);
+        parser_options.set(Options::ENABLE_SMART_PUNCTUATION, options.smart_punctuation);
+        Self {
+            options: parser_options,
+            hard_breaks,
+        }
+    }
+
+    /// Replaces code blocks with the rendered HTML and gives headings ids.
+    fn process_events<'a>(
+        &self,
+        parser: Parser<'a>,
+        code_block: &CodeBlockRenderer<'_>,
+    ) -> Vec<Event<'a>> {
+        let mut events = Vec::new();
+        let mut in_code_block = false;
+        let mut code_lang: Option<String> = None;
+        let mut code_content = String::new();
+        // Heading ID injection state
+        let mut in_heading = false;
+        let mut heading_level: u32 = 0;
+        let mut heading_text = String::new();
+        let mut heading_events: Vec<Event<'a>> = Vec::new();
+        let mut heading_ids = HeadingIds::default();
+
+        for event in parser {
+            match event {
+                Event::Start(Tag::Heading { level, .. }) => {
+                    in_heading = true;
+                    heading_level = level as u32;
+                    heading_text.clear();
+                    heading_events.clear();
+                    // Keep collecting events but don't push yet
+                }
+                Event::End(TagEnd::Heading(_)) if in_heading => {
+                    in_heading = false;
+                    let id = heading_ids.next(heading_text.trim());
+                    // Emit heading with id attribute
+                    let tag = format!("h{}", heading_level);
+                    events.push(Event::Html(
+                        format!("<{} id=\"{}\">", tag, html_escape(&id)).into(),
+                    ));
+                    for ev in heading_events.drain(..) {
+                        events.push(ev);
+                    }
+                    events.push(Event::Html(format!("</{}>", tag).into()));
+                }
+                Event::Text(ref text) if in_heading => {
+                    heading_text.push_str(text);
+                    heading_events.push(event);
+                }
+                Event::Code(ref code) if in_heading => {
+                    heading_text.push_str(code);
+                    heading_events.push(event);
+                }
+                _ if in_heading => {
+                    heading_events.push(event);
+                }
+                Event::Start(Tag::CodeBlock(kind)) => {
+                    in_code_block = true;
+                    code_content.clear();
+                    code_lang = match kind {
+                        CodeBlockKind::Fenced(lang) if !lang.is_empty() => Some(lang.to_string()),
+                        _ => None,
+                    };
+                }
+                Event::End(TagEnd::CodeBlock) => {
+                    in_code_block = false;
+                    let html = code_block(code_lang.as_deref(), &code_content);
+                    events.push(Event::Html(html.into()));
+                    code_lang = None;
+                }
+                Event::Text(text) if in_code_block => {
+                    code_content.push_str(&text);
+                }
+                // Convert soft breaks (single newline) to hard breaks (<br>)
+                // so users don't need double-newline for line breaks
+                Event::SoftBreak if self.hard_breaks => {
+                    events.push(Event::HardBreak);
+                }
+                _ => {
+                    events.push(event);
+                }
+            }
+        }
+
+        events
+    }
+}
+
+impl MarkdownEngine for PulldownEngine {
+    fn name(&self) -> &'static str {
+        "pulldown"
+    }
+
+    fn render(&self, markdown: &str, code_block: &CodeBlockRenderer<'_>) -> String {
+        let parser = Parser::new_ext(markdown, self.options);
+        let events = self.process_events(parser, code_block);
+
+        let mut html_output = String::new();
+        html::push_html(&mut html_output, events.into_iter());
+        html_output
+    }
+
+    fn headings(&self, markdown: &str) -> Vec<(u32, String)> {
+        let mut headings = Vec::new();
+        let mut current: Option<(u32, String)> = None;
+
+        for event in Parser::new_ext(markdown, self.options) {
+            match event {
+                Event::Start(Tag::Heading { level, .. }) => {
+                    current = Some((level as u32, String::new()));
+                }
+                Event::Text(text) | Event::Code(text) => {
+                    if let Some((_, heading_text)) = &mut current {
+                        heading_text.push_str(&text);
+                    }
+                }
+                Event::End(TagEnd::Heading(_)) => {
+                    headings.extend(current.take());
+                }
+                _ => {}
+            }
+        }
+
+        headings
+    }
+}
+
+/// comrak, for GitHub Flavored Markdown and its extensions
+pub struct ComrakEngine {
+    options: ComrakOptions,
+    hard_breaks: bool,
+}
+
+impl ComrakEngine {
+    pub fn new(options: &ComrakOptions, hard_breaks: bool) -> Self {
+        Self {
+            options: options.clone(),
+            hard_breaks,
+        }
+    }
+
+    fn comrak_options(&self) -> comrak::Options<'_> {
+        let mut options = comrak::Options::default();
+        options.extension.table = self.options.tables;
+        options.extension.strikethrough = self.options.strikethrough;
+        options.extension.tasklist = self.options.tasklists;
+        options.extension.footnotes = self.options.footnotes;
+        options.extension.autolink = self.options.autolink;
+        options.extension.superscript = self.options.superscript;
+        options.extension.description_lists = self.options.description_lists;
+        options.parse.smart = self.options.smart_punctuation;
+        options.render.hardbreaks = self.hard_breaks;
+        // Raw HTML passes through as with pulldown-cmark; the renderer
+        // sanitizes the result
+        options.render.unsafe_ = true;
+        options
+    }
+}
+
+/// Plain text of a node's text and inline code descendants
+fn comrak_text<'a>(node: &'a AstNode<'a>) -> String {
+    node.descendants()
+        .filter_map(|descendant| match &descendant.data.borrow().value {
+            NodeValue::Text(text) => Some(text.clone()),
+            NodeValue::Code(code) => Some(code.literal.clone()),
+            _ => None,
+        })
+        .collect()
+}
+
+impl MarkdownEngine for ComrakEngine {
+    fn name(&self) -> &'static str {
+        "comrak"
+    }
+
+    fn render(&self, markdown: &str, code_block: &CodeBlockRenderer<'_>) -> String {
+        let arena = Arena::new();
+        let options = self.comrak_options();
+        let root = parse_document(&arena, markdown, &options);
+        let mut heading_ids = HeadingIds::default();
+
+        // Code blocks and headings become raw HTML blocks with the
+        // highlighted code and the heading carrying its id
+        let nodes: Vec<_> = root.descendants().collect();
+        for node in nodes {
+            let html = match &node.data.borrow().value {
+                NodeValue::CodeBlock(block) => {
+                    let lang = Some(block.info.as_str()).filter(|info| !info.is_empty());
+                    code_block(lang, &block.literal)
+                }
+                NodeValue::Heading(heading) => {
+                    let id = heading_ids.next(comrak_text(node).trim());
+                    let mut rendered = Vec::new();
+                    if format_html(node, &options, &mut rendered).is_err() {
+                        continue;
+                    }
+                    String::from_utf8_lossy(&rendered).replacen(
+                        &format!("<h{}>", heading.level),
+                        &format!("<h{} id=\"{}\">", heading.level, html_escape(&id)),
+                        1,
+                    )
+                }
+                _ => continue,
+            };
+
+            for child in node.children().collect::<Vec<_>>() {
+                child.detach();
+            }
+            node.data.borrow_mut().value = NodeValue::HtmlBlock(NodeHtmlBlock {
+                block_type: 0,
+                literal: html,
+            });
+        }
+
+        let mut output = Vec::new();
+        if let Err(e) = format_html(root, &options, &mut output) {
+            tracing::warn!(error = %e, "comrak failed to render markdown");
+        }
+        String::from_utf8_lossy(&output).into_owned()
+    }
+
+    fn headings(&self, markdown: &str) -> Vec<(u32, String)> {
+        let arena = Arena::new();
+        let options = self.comrak_options();
+        let root = parse_document(&arena, markdown, &options);
+
+        root.descendants()
+            .filter_map(|node| match &node.data.borrow().value {
+                NodeValue::Heading(heading) => Some((heading.level as u32, comrak_text(node))),
+                _ => None,
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn render_code(lang: Option<&str>, code: &str) -> String {
+        format!("<pre data-lang=\"{}\">{}</pre>", lang.unwrap_or(""), code)
+    }
+
+    #[test]
+    fn heading_ids_are_unique() {
+        let mut ids = HeadingIds::default();
+        assert_eq!(ids.next("Getting Started"), "getting-started");
+        assert_eq!(ids.next("Getting Started"), "getting-started-1");
+        assert_eq!(ids.next("Getting  started!"), "getting-started-2");
+        assert_eq!(ids.next("简介"), "简介");
+    }
+
+    #[test]
+    fn engines_render_the_same_basics() {
+        let markdown = "# Intro\n\nline one\nline two\n\n```rust\nfn main() {}\n```\n\n## Intro\n";
+        let engines: [Arc<dyn MarkdownEngine>; 2] = [
+            Arc::new(PulldownEngine::default()),
+            Arc::new(ComrakEngine::new(&ComrakOptions::default(), true)),
+        ];
+        for engine in engines {
+            let html = engine.render(markdown, &render_code);
+            assert!(
+                html.contains("<h1 id=\"intro\">Intro</h1>"),
+                "{}",
+                engine.name()
+            );
+            assert!(
+                html.contains("<h2 id=\"intro-1\">Intro</h2>"),
+                "{}",
+                engine.name()
+            );
+            assert!(html.contains("line one<br />"), "{}", engine.name());
+            assert!(
+                html.contains("<pre data-lang=\"rust\">fn main() {}\n</pre>"),
+                "{}",
+                engine.name()
+            );
+            assert_eq!(
+                engine.headings(markdown),
+                [(1, "Intro".to_string()), (2, "Intro".to_string())]
+            );
+        }
+    }
+
+    #[test]
+    fn options_select_extensions() {
+        let strict = PulldownOptions {
+            tables: false,
+            strikethrough: false,
+            tasklists: false,
+            footnotes: false,
+            smart_punctuation: false,
+        };
+        let html = PulldownEngine::new(&strict, false).render("~~no~~\na", &render_code);
+        assert!(!html.contains("<del>"));
+        assert!(!html.contains("<br"));
+
+        let gfm = ComrakEngine::new(&ComrakOptions::default(), true);
+        assert!(gfm
+            .render("see https://example.com", &render_code)
+            .contains("<a href=\"https://example.com\">"));
+        let plain = ComrakEngine::new(
+            &ComrakOptions {
+                autolink: false,
+                ..ComrakOptions::default()
+            },
+            true,
+        );
+        assert!(!plain
+            .render("see https://example.com", &render_code)
+            .contains("<a "));
+    }
+}
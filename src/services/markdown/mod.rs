@@ -1,8 +1,8 @@
 //! Markdown rendering service
 //!
 //! This module provides Markdown to HTML conversion with syntax highlighting
-//! for code blocks. It uses pulldown-cmark for Markdown parsing and syntect
-//! for syntax highlighting.
+//! for code blocks. Parsing is done by a [`MarkdownEngine`] (pulldown-cmark by
+//! default, or comrak; see [`engine`]) and highlighting by syntect.
 //!
 //! # Example
 //!
@@ -15,15 +15,35 @@
 //! assert!(html.contains("<strong>"));
 //! ```
 
-use pulldown_cmark::{html, CodeBlockKind, Event, Options, Parser, Tag, TagEnd};
+use once_cell::sync::Lazy;
+use pulldown_cmark::{Event, Options, Parser, Tag, TagEnd};
 use regex::Regex;
 use std::sync::Arc;
 use syntect::highlighting::ThemeSet;
 use syntect::html::highlighted_html_for_string;
 use syntect::parsing::SyntaxSet;
 
+use crate::models::{ContentStats, InputFormat};
 use crate::plugin::{hook_names, HookManager, ShortcodeContext, ShortcodeManager};
-use crate::services::emoji;
+use crate::services::{emoji, markup};
+
+pub mod engine;
+
+pub use engine::{engine_from_config, MarkdownEngine};
+use engine::{HeadingIds, PulldownEngine};
+
+/// Words read per minute in text written with spaces between words
+const WORDS_PER_MINUTE: f64 = 275.0;
+
+/// Characters read per minute in Chinese and Japanese text
+const CJK_CHARS_PER_MINUTE: f64 = 400.0;
+
+/// Elements whose text is not read
+static UNREAD_RE: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"(?is)<(script|style)\b.*?</(script|style)>").unwrap());
+
+static TAG_OR_ENTITY_RE: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"(?s)<[^>]*>|&(#?[a-zA-Z0-9]+);").unwrap());
 
 /// Options for rendering markdown with shortcodes
 #[derive(Debug, Clone, Default)]
@@ -70,6 +90,7 @@ struct ImageGridItem {
 /// - Hook integration for plugins
 #[derive(Clone)]
 pub struct MarkdownRenderer {
+    engine: Arc<dyn MarkdownEngine>,
     syntax_set: SyntaxSet,
     theme_set: Arc<ThemeSet>,
     theme_name: String,
@@ -109,6 +130,7 @@ impl MarkdownRenderer {
         };
 
         Self {
+            engine: Arc::new(PulldownEngine::default()),
             syntax_set,
             theme_set: Arc::new(theme_set),
             theme_name: validated_theme,
@@ -149,6 +171,11 @@ impl MarkdownRenderer {
         self.hook_manager = Some(hook_manager);
     }
 
+    /// Set the engine that parses Markdown (pulldown-cmark by default).
+    pub fn set_engine(&mut self, engine: Arc<dyn MarkdownEngine>) {
+        self.engine = engine;
+    }
+
     /// Trigger a hook if hook manager is available
     fn trigger_hook(&self, name: &str, data: serde_json::Value) -> serde_json::Value {
         if let Some(ref manager) = self.hook_manager {
@@ -190,14 +217,6 @@ impl MarkdownRenderer {
             .and_then(|v| v.as_str())
             .unwrap_or(markdown);
 
-        // Configure parser options
-        let mut options = Options::empty();
-        options.insert(Options::ENABLE_TABLES);
-        options.insert(Options::ENABLE_STRIKETHROUGH);
-        options.insert(Options::ENABLE_TASKLISTS);
-        options.insert(Options::ENABLE_SMART_PUNCTUATION);
-        options.insert(Options::ENABLE_FOOTNOTES);
-
         // Pre-process: extract [grid] image blocks before generic Markdown
         // handling so Markdown image syntax inside the grid still works.
         let (content, image_grids) = Self::extract_image_grids(content);
@@ -212,14 +231,10 @@ impl MarkdownRenderer {
         // Pre-process: protect math expressions from markdown parsing
         let (content, math_placeholders) = Self::extract_math_expressions(&content);
 
-        let parser = Parser::new_ext(&content, options);
-
-        // Process events, handling code blocks specially for syntax highlighting
-        let events = self.process_events(parser);
-
-        // Render to HTML
-        let mut html_output = String::new();
-        html::push_html(&mut html_output, events.into_iter());
+        // Render to HTML, handling code blocks specially for syntax highlighting
+        let html_output = self
+            .engine
+            .render(&content, &|lang, code| self.render_code_block(lang, code));
 
         // Post-process: restore math expressions as KaTeX-ready elements
         let html_output = Self::restore_math_expressions(&html_output, &math_placeholders);
@@ -227,6 +242,11 @@ impl MarkdownRenderer {
         // Post-process: restore image grids before plugin hooks and sanitizing.
         let html_output = Self::restore_image_grids(&html_output, &image_grids);
 
+        self.finish_html(&html_output)
+    }
+
+    /// Runs the `markdown_after_parse` hook on generated HTML and sanitizes it
+    fn finish_html(&self, html_output: &str) -> String {
         // Trigger markdown_after_parse hook
         let hook_data = self.trigger_hook(
             hook_names::MARKDOWN_AFTER_PARSE,
@@ -237,7 +257,7 @@ impl MarkdownRenderer {
         let html_after_hook = hook_data
             .get("html")
             .and_then(|v| v.as_str())
-            .unwrap_or(&html_output)
+            .unwrap_or(html_output)
             .to_string();
 
         let safe_html = Self::sanitize_rendered_html(&html_after_hook);
@@ -252,82 +272,19 @@ impl MarkdownRenderer {
     /// Parses the markdown and collects all headings with their level, text,
     /// and a generated anchor id. This can be used to build a TOC sidebar.
     pub fn extract_toc(&self, markdown: &str) -> Vec<TocEntry> {
-        let mut options = Options::empty();
-        options.insert(Options::ENABLE_TABLES);
-        options.insert(Options::ENABLE_STRIKETHROUGH);
-        options.insert(Options::ENABLE_TASKLISTS);
-        options.insert(Options::ENABLE_FOOTNOTES);
-
-        let parser = Parser::new_ext(markdown, options);
-
-        let mut toc = Vec::new();
-        let mut in_heading = false;
-        let mut heading_level: u32 = 0;
-        let mut heading_text = String::new();
-        let mut used_ids: std::collections::HashMap<String, u32> = std::collections::HashMap::new();
-
-        for event in parser {
-            match event {
-                Event::Start(Tag::Heading { level, .. }) => {
-                    in_heading = true;
-                    heading_level = level as u32;
-                    heading_text.clear();
-                }
-                Event::Text(text) if in_heading => {
-                    heading_text.push_str(&text);
-                }
-                Event::Code(code) if in_heading => {
-                    heading_text.push_str(&code);
-                }
-                Event::End(TagEnd::Heading(_)) => {
-                    in_heading = false;
-                    let text = heading_text.trim().to_string();
-                    if !text.is_empty() {
-                        let base_id = Self::heading_to_id(&text);
-                        let count = used_ids.entry(base_id.clone()).or_insert(0);
-                        let id = if *count == 0 {
-                            *count += 1;
-                            base_id
-                        } else {
-                            let suffixed = format!("{}-{}", base_id, count);
-                            *count += 1;
-                            suffixed
-                        };
-                        toc.push(TocEntry {
-                            level: heading_level,
-                            text,
-                            id,
-                        });
-                    }
-                }
-                _ => {}
-            }
-        }
-
-        toc
-    }
-
-    /// Convert heading text to a URL-friendly anchor id.
-    fn heading_to_id(text: &str) -> String {
-        text.chars()
-            .map(|c| {
-                if c.is_alphanumeric() {
-                    c.to_ascii_lowercase()
-                } else if c == ' ' || c == '-' || c == '_' {
-                    '-'
-                } else if c > '\x7f' {
-                    c
-                }
-                // keep CJK characters
-                else {
-                    '-'
+        let mut heading_ids = HeadingIds::default();
+        self.engine
+            .headings(markdown)
+            .into_iter()
+            .filter_map(|(level, text)| {
+                let text = text.trim().to_string();
+                if text.is_empty() {
+                    return None;
                 }
+                let id = heading_ids.next(&text);
+                Some(TocEntry { level, text, id })
             })
-            .collect::<String>()
-            .split('-')
-            .filter(|s| !s.is_empty())
-            .collect::<Vec<_>>()
-            .join("-")
+            .collect()
     }
 
     /// Renders Markdown text to HTML with shortcode processing.
@@ -387,18 +344,82 @@ impl MarkdownRenderer {
     /// * `article_id` - The ID of the article being rendered.
     /// * `user_id` - Optional user ID if the viewer is logged in.
     pub fn render_article(&self, markdown: &str, article_id: i64, user_id: Option<i64>) -> String {
-        self.render_with_options(
-            markdown,
-            &RenderOptions {
-                shortcode_context: ShortcodeContext {
-                    article_id: Some(article_id),
-                    user_id,
-                    is_preview: false,
-                    ..Default::default()
-                },
-                process_shortcodes: true,
+        self.render_with_options(markdown, &Self::article_options(article_id, user_id))
+    }
+
+    fn article_options(article_id: i64, user_id: Option<i64>) -> RenderOptions {
+        RenderOptions {
+            shortcode_context: ShortcodeContext {
+                article_id: Some(article_id),
+                user_id,
+                is_preview: false,
+                ..Default::default()
             },
-        )
+            process_shortcodes: true,
+        }
+    }
+
+    /// Renders an article source in its input format.
+    ///
+    /// Markdown goes through [`render_article`](Self::render_article) and
+    /// HTML and block documents through [`render_html`](Self::render_html);
+    /// BBCode and reStructuredText are converted by [`markup`] and
+    /// sanitized the same way. A source that cannot be rendered is shown escaped in a `<pre>`
+    /// block so the article stays readable.
+    pub fn render_source(
+        &self,
+        format: InputFormat,
+        source: &str,
+        article_id: i64,
+        user_id: Option<i64>,
+    ) -> String {
+        match format {
+            InputFormat::Markdown => return self.render_article(source, article_id, user_id),
+            InputFormat::Html => {
+                return self.render_html(source, &Self::article_options(article_id, user_id))
+            }
+            InputFormat::Blocks => match markup::blocks::parse(source) {
+                Ok(blocks) => {
+                    let html = markup::blocks::to_html(&blocks, &|lang, code| {
+                        self.render_code_block(lang, code)
+                    });
+                    return self.render_html(&html, &Self::article_options(article_id, user_id));
+                }
+                Err(e) => {
+                    tracing::warn!(article_id, error = %e, "failed to render article blocks");
+                    return format!("<pre>{}</pre>", html_escape(source));
+                }
+            },
+            _ => {}
+        }
+        match markup::to_html(format, source) {
+            Ok(html) => Self::sanitize_rendered_html(&html),
+            Err(e) => {
+                tracing::warn!(article_id, error = %e, "failed to render article source");
+                format!("<pre>{}</pre>", html_escape(source))
+            }
+        }
+    }
+
+    /// Renders an HTML source, e.g. from a block editor, without Markdown
+    /// parsing.
+    ///
+    /// Shortcodes outside `<pre>` and `<code>` are expanded, headings get
+    /// anchor ids (see [`markup::html::anchor_headings`]), and the result
+    /// goes through the `markdown_after_parse` hook and the same sanitizing
+    /// as rendered Markdown.
+    pub fn render_html(&self, html: &str, options: &RenderOptions) -> String {
+        let content = match (&self.shortcode_manager, options.process_shortcodes) {
+            (Some(manager), true) => {
+                let (protected, code_segments) =
+                    Self::protect_html_code_segments(html, "SHORTCODE");
+                let rendered = manager.render(&protected, &options.shortcode_context);
+                Self::restore_protected_segments(&rendered, &code_segments)
+            }
+            _ => html.to_string(),
+        };
+        let (content, _) = markup::html::anchor_headings(&content);
+        self.finish_html(&content)
     }
 
     /// Renders Markdown text to HTML in preview mode.
@@ -418,111 +439,64 @@ impl MarkdownRenderer {
         )
     }
 
-    /// Processes parser events, applying syntax highlighting to code blocks.
-    fn process_events<'a>(&self, parser: Parser<'a>) -> Vec<Event<'a>> {
-        let mut events = Vec::new();
-        let mut in_code_block = false;
-        let mut code_lang: Option<String> = None;
-        let mut code_content = String::new();
-        // Heading ID injection state
-        let mut in_heading = false;
-        let mut heading_level: u32 = 0;
-        let mut heading_text = String::new();
-        let mut heading_events: Vec<Event<'a>> = Vec::new();
-        let mut used_ids: std::collections::HashMap<String, u32> = std::collections::HashMap::new();
-
-        for event in parser {
-            match event {
-                Event::Start(Tag::Heading { level, .. }) => {
-                    in_heading = true;
-                    heading_level = level as u32;
-                    heading_text.clear();
-                    heading_events.clear();
-                    // Keep collecting events but don't push yet
-                }
-                Event::End(TagEnd::Heading(_)) if in_heading => {
-                    in_heading = false;
-                    let text = heading_text.trim().to_string();
-                    let base_id = Self::heading_to_id(&text);
-                    // Deduplicate: append -1, -2, etc. for repeated headings
-                    let count = used_ids.entry(base_id.clone()).or_insert(0);
-                    let id = if *count == 0 {
-                        *count += 1;
-                        base_id
-                    } else {
-                        let suffixed = format!("{}-{}", base_id, count);
-                        *count += 1;
-                        suffixed
-                    };
-                    // Emit heading with id attribute
-                    let tag = format!("h{}", heading_level);
-                    events.push(Event::Html(
-                        format!("<{} id=\"{}\">", tag, html_escape(&id)).into(),
-                    ));
-                    for ev in heading_events.drain(..) {
-                        events.push(ev);
-                    }
-                    events.push(Event::Html(format!("</{}>", tag).into()));
-                }
-                Event::Text(ref text) if in_heading => {
-                    heading_text.push_str(text);
-                    heading_events.push(event);
-                }
-                Event::Code(ref code) if in_heading => {
-                    heading_text.push_str(code);
-                    heading_events.push(event);
-                }
-                _ if in_heading => {
-                    heading_events.push(event);
-                }
-                Event::Start(Tag::CodeBlock(kind)) => {
-                    in_code_block = true;
-                    code_content.clear();
-                    code_lang = match kind {
-                        CodeBlockKind::Fenced(lang) => {
-                            let lang_str = lang.to_string();
-                            if lang_str.is_empty() {
-                                None
-                            } else {
-                                Some(lang_str)
-                            }
-                        }
-                        CodeBlockKind::Indented => None,
-                    };
-                }
-                Event::End(TagEnd::CodeBlock) => {
-                    in_code_block = false;
-
-                    // Mermaid: output as <div class="mermaid"> instead of code block
-                    let highlighted = if code_lang.as_deref() == Some("mermaid") {
-                        format!(
-                            "<div class=\"mermaid\">{}</div>",
-                            html_escape(&code_content)
-                        )
-                    } else if let Some(ref lang) = code_lang {
-                        self.highlight_code(&code_content, lang)
-                    } else {
-                        self.plain_code_block(&code_content)
-                    };
+    /// Measures the text of rendered HTML.
+    ///
+    /// Chinese and Japanese characters count as one word each and are read
+    /// at [`CJK_CHARS_PER_MINUTE`]; other words are runs of letters and
+    /// digits read at [`WORDS_PER_MINUTE`]. Text with any words takes at
+    /// least a minute.
+    pub fn content_stats(html: &str) -> ContentStats {
+        let text = UNREAD_RE.replace_all(html, " ");
+        let text = TAG_OR_ENTITY_RE.replace_all(&text, |caps: &regex::Captures| {
+            match caps.get(1).map(|entity| entity.as_str()) {
+                Some("nbsp") | None => " ",
+                Some(_) => "&",
+            }
+        });
 
-                    events.push(Event::Html(highlighted.into()));
-                    code_lang = None;
-                }
-                Event::Text(text) if in_code_block => {
-                    code_content.push_str(&text);
-                }
-                // Convert soft breaks (single newline) to hard breaks (<br>)
-                // so users don't need double-newline for line breaks
-                Event::SoftBreak => {
-                    events.push(Event::HardBreak);
-                }
-                _ => {
-                    events.push(event);
+        let (mut words, mut cjk_chars, mut char_count) = (0u64, 0u64, 0u64);
+        let mut in_word = false;
+        for ch in text.chars() {
+            if ch.is_whitespace() {
+                in_word = false;
+                continue;
+            }
+            char_count += 1;
+            if is_cjk(ch) {
+                cjk_chars += 1;
+                in_word = false;
+            } else if ch.is_alphanumeric() {
+                if !in_word {
+                    words += 1;
                 }
+                in_word = true;
+            } else {
+                in_word = false;
             }
         }
 
-        events
+        let minutes = words as f64 / WORDS_PER_MINUTE + cjk_chars as f64 / CJK_CHARS_PER_MINUTE;
+        let word_count = words + cjk_chars;
+        ContentStats {
+            word_count: word_count as i64,
+            char_count: char_count as i64,
+            reading_time: if word_count == 0 {
+                0
+            } else {
+                (minutes.ceil() as i64).max(1)
+            },
+        }
+    }
+
+    /// Renders a code block, applying syntax highlighting when the language
+    /// is known.
+    fn render_code_block(&self, lang: Option<&str>, code: &str) -> String {
+        match lang {
+            // Mermaid: output as <div class="mermaid"> instead of code block
+            Some("mermaid") => format!("<div class=\"mermaid\">{}</div>", html_escape(code)),
+            Some(lang) => self.highlight_code(code, lang),
+            None => self.plain_code_block(code),
+        }
     }
 
     /// Applies syntax highlighting to a code block.
@@ -655,8 +629,10 @@ impl MarkdownRenderer {
                 "MATH_INLINE"
             };
             let placeholder = format!("\x00{}_{}\x00", tag, idx);
-            // The placeholder may have been HTML-escaped by pulldown-cmark
+            // The placeholder may have been HTML-escaped by pulldown-cmark, or
+            // had its NULs replaced by comrak as CommonMark requires
             let escaped_placeholder = placeholder.replace('\x00', "&#0;");
+            let replaced_placeholder = placeholder.replace('\x00', "\u{FFFD}");
             let replacement = if *is_block {
                 format!("<div class=\"math-block\">{}</div>", html_escape(expr))
             } else {
@@ -664,6 +640,7 @@ impl MarkdownRenderer {
             };
             result = result.replace(&placeholder, &replacement);
             result = result.replace(&escaped_placeholder, &replacement);
+            result = result.replace(&replaced_placeholder, &replacement);
         }
         result
     }
@@ -819,6 +796,20 @@ impl MarkdownRenderer {
         (protected, segments)
     }
 
+    fn protect_html_code_segments(content: &str, prefix: &str) -> (String, Vec<(String, String)>) {
+        let code_re = Regex::new(r"(?is)<pre\b.*?</pre\s*>|<code\b.*?</code\s*>").unwrap();
+        let mut output = String::with_capacity(content.len());
+        let mut segments = Vec::new();
+        let mut last = 0;
+        for m in code_re.find_iter(content) {
+            output.push_str(&content[last..m.start()]);
+            Self::push_protected_segment(&mut output, &mut segments, prefix, m.as_str());
+            last = m.end();
+        }
+        output.push_str(&content[last..]);
+        (output, segments)
+    }
+
     fn restore_protected_segments(content: &str, segments: &[(String, String)]) -> String {
         let mut result = content.to_string();
         for (marker, original) in segments.iter().rev() {
@@ -1092,7 +1083,7 @@ impl MarkdownRenderer {
         )
     }
 
-    fn render_link_card(url: &str) -> String {
+    pub(crate) fn render_link_card(url: &str) -> String {
         let label = url
             .trim_start_matches("https://")
             .trim_start_matches("http://")
@@ -1304,10 +1295,41 @@ fn html_escape(s: &str) -> String {
         .replace('\'', "&#x27;")
 }
 
+/// Chinese characters, and Japanese kana, which are read one by one
+fn is_cjk(ch: char) -> bool {
+    matches!(ch,
+        '\u{3040}'..='\u{30FF}' // Hiragana and Katakana
+        | '\u{3400}'..='\u{4DBF}'
+        | '\u{4E00}'..='\u{9FFF}'
+        | '\u{F900}'..='\u{FAFF}'
+        | '\u{20000}'..='\u{2FA1F}')
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_content_stats_count_cjk_characters_as_words() {
+        let stats = MarkdownRenderer::content_stats(
+            "<h1>Hello world</h1><p>Rust&nbsp;is fun. 你好，世界！</p><script>var x = 1;</script>",
+        );
+        // 5 words and 4 characters
+        assert_eq!(stats.word_count, 9);
+        assert_eq!(stats.char_count, 26);
+        assert_eq!(stats.reading_time, 1);
+
+        let long = format!("<p>{}</p>", "字".repeat(1000));
+        assert_eq!(MarkdownRenderer::content_stats(&long).reading_time, 3);
+        let long = format!("<p>{}</p>", "word ".repeat(600));
+        assert_eq!(MarkdownRenderer::content_stats(&long).reading_time, 3);
+
+        assert_eq!(
+            MarkdownRenderer::content_stats("<p><img src=\"a.png\"></p>"),
+            ContentStats::default()
+        );
+    }
+
     #[test]
     fn test_new_renderer() {
         let renderer = MarkdownRenderer::new();
@@ -1730,6 +1752,29 @@ Some **bold** text."#;
         );
     }
 
+    #[test]
+    fn test_render_html_source() {
+        use crate::plugin::shortcode::builtins;
+
+        let mut shortcode_manager = ShortcodeManager::new();
+        builtins::register_builtins(&mut shortcode_manager);
+
+        let renderer = MarkdownRenderer::with_shortcode_manager(Arc::new(shortcode_manager));
+        let source = r#"<h2>Intro</h2>
+<p onclick="steal()">Some *not markdown* text</p>
+<script>alert(1)</script>
+[note type="info"]Expanded[/note]
+<pre><code>[spoiler]kept[/spoiler]</code></pre>"#;
+
+        let html = renderer.render_source(InputFormat::Html, source, 1, None);
+
+        assert!(html.contains("<h2 id=\"intro\">Intro</h2>"));
+        assert!(html.contains("<p>Some *not markdown* text</p>"));
+        assert!(!html.contains("<script"));
+        assert!(html.contains("shortcode-note-info"));
+        assert!(html.contains("[spoiler]kept[/spoiler]"));
+    }
+
     #[test]
     fn test_shortcodes_are_ignored_in_unclosed_code_fence() {
         use crate::plugin::shortcode::builtins;
@@ -1853,4 +1898,23 @@ Some **bold** text."#;
         // Inside code block, $ should not be treated as math
         assert!(!html.contains("math-inline"));
     }
+
+    #[test]
+    fn test_render_with_comrak_engine() {
+        let mut renderer = MarkdownRenderer::new();
+        renderer.set_engine(engine_from_config(&crate::config::MarkdownConfig {
+            engine: crate::config::MarkdownEngineKind::Comrak,
+            ..Default::default()
+        }));
+        let html = renderer.render(
+            "## Setup\n\nSee https://example.com and $x^2$\n\n```rust\nfn main() {}\n```\n\n<script>alert(1)</script>",
+        );
+
+        assert!(html.contains("<h2 id=\"setup\">Setup</h2>"));
+        assert!(html.contains("href=\"https://example.com\""));
+        assert!(html.contains("math-inline"));
+        assert!(html.contains("style="));
+        assert!(!html.to_ascii_lowercase().contains("<script"));
+        assert_eq!(renderer.extract_toc("## Setup")[0].id, "setup");
+    }
 }
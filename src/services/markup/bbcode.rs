@@ -0,0 +1,278 @@
+//! BBCode to HTML
+//!
+//! Covers the tags phpBB, vBulletin and Discuz exports commonly contain:
+//! `b`, `i`, `u`, `s`, `color`, `size`, `center`, `quote`, `code`, `url`,
+//! `email`, `img` and `list` with `[*]` items. Unknown or unbalanced tags are
+//! kept as text, tags left open are closed at the end, and newlines become
+//! `<br />`.
+
+use super::html_escape;
+
+/// Longest tag, including its argument, that is recognised
+const MAX_TAG_LEN: usize = 512;
+
+/// A tag that stays open until its closing tag
+struct OpenTag {
+    name: String,
+    close: &'static str,
+}
+
+/// A parsed `[name=arg]` or `[/name]`
+struct Tag<'a> {
+    name: String,
+    arg: Option<&'a str>,
+    closing: bool,
+    /// Length of the tag in the source, brackets included
+    len: usize,
+}
+
+fn parse_tag(input: &str) -> Option<Tag<'_>> {
+    let end = input[..input.len().min(MAX_TAG_LEN)].find(']')?;
+    let inner = &input[1..end];
+    let (closing, inner) = match inner.strip_prefix('/') {
+        Some(rest) => (true, rest),
+        None => (false, inner),
+    };
+    let (name, arg) = match inner.split_once('=') {
+        Some((name, arg)) => (name, Some(arg.trim_matches(|c| c == '"' || c == '\''))),
+        None => (inner, None),
+    };
+    let name = name.trim().to_ascii_lowercase();
+    if name.is_empty() || !name.chars().all(|c| c.is_ascii_alphanumeric() || c == '*') {
+        return None;
+    }
+    Some(Tag {
+        name,
+        arg,
+        closing,
+        len: end + 1,
+    })
+}
+
+/// Font size of `[size=n]`: 1-7 are the classic HTML sizes, larger values percent
+fn font_size(arg: &str) -> Option<String> {
+    const CLASSIC: [&str; 7] = ["0.63em", "0.82em", "1em", "1.13em", "1.5em", "2em", "3em"];
+    let value: u32 = arg.trim().trim_end_matches('%').parse().ok()?;
+    match value {
+        1..=7 => Some(CLASSIC[value as usize - 1].to_string()),
+        8.. => Some(format!("{}%", value.clamp(50, 300))),
+        0 => None,
+    }
+}
+
+fn is_color(arg: &str) -> bool {
+    let color = arg.strip_prefix('#').unwrap_or(arg);
+    !color.is_empty() && color.len() <= 20 && color.chars().all(|c| c.is_ascii_alphanumeric())
+}
+
+/// Opening HTML and closing HTML of a tag that wraps content
+fn wrapping_tag(name: &str, arg: Option<&str>) -> Option<(String, &'static str)> {
+    let tag = match (name, arg) {
+        ("b", None) => ("<strong>".to_string(), "</strong>"),
+        ("i", None) => ("<em>".to_string(), "</em>"),
+        ("u", None) => ("<u>".to_string(), "</u>"),
+        ("s", None) => ("<del>".to_string(), "</del>"),
+        ("center", None) => ("<div style=\"text-align: center\">".to_string(), "</div>"),
+        ("quote", None) => ("<blockquote>".to_string(), "</blockquote>"),
+        ("quote", Some(author)) => (
+            format!("<blockquote><cite>{}</cite><br />", html_escape(author)),
+            "</blockquote>",
+        ),
+        ("color", Some(color)) if is_color(color) => {
+            (format!("<span style=\"color: {}\">", color), "</span>")
+        }
+        ("size", Some(size)) => (
+            format!("<span style=\"font-size: {}\">", font_size(size)?),
+            "</span>",
+        ),
+        ("url", Some(href)) => (format!("<a href=\"{}\">", html_escape(href.trim())), "</a>"),
+        ("list", None) => ("<ul>".to_string(), "</ul>"),
+        ("list", Some("1")) => ("<ol>".to_string(), "</ol>"),
+        ("list", Some("a")) => ("<ol type=\"a\">".to_string(), "</ol>"),
+        _ => return None,
+    };
+    Some(tag)
+}
+
+/// Whether the tag's content is taken verbatim, e.g. `[img]url[/img]`
+fn is_verbatim(name: &str, arg: Option<&str>) -> bool {
+    matches!((name, arg), ("code" | "img" | "email", _) | ("url", None))
+}
+
+/// HTML of a verbatim tag
+fn verbatim_tag(name: &str, content: &str) -> String {
+    match name {
+        "code" => format!(
+            "<pre><code>{}</code></pre>",
+            html_escape(content.trim_matches('\n'))
+        ),
+        "img" => format!("<img src=\"{}\" alt=\"\" />", html_escape(content.trim())),
+        "email" => format!(
+            "<a href=\"mailto:{0}\">{0}</a>",
+            html_escape(content.trim())
+        ),
+        _ => format!("<a href=\"{0}\">{0}</a>", html_escape(content.trim())),
+    }
+}
+
+/// Byte offset of `needle` in `haystack`, ignoring ASCII case
+fn find_ignore_case(haystack: &str, needle: &str) -> Option<usize> {
+    haystack
+        .as_bytes()
+        .windows(needle.len())
+        .position(|window| window.eq_ignore_ascii_case(needle.as_bytes()))
+}
+
+struct Converter {
+    html: String,
+    open: Vec<OpenTag>,
+}
+
+impl Converter {
+    fn text(&mut self, text: &str) {
+        // Line breaks around list items are layout, not content
+        let text = match self.open.last().map(|tag| tag.name.as_str()) {
+            Some("list") => text.trim(),
+            Some("*") => text.trim_matches('\n'),
+            _ => text,
+        };
+        self.html
+            .push_str(&html_escape(text).replace('\n', "<br />\n"));
+    }
+
+    /// Close open tags down to and including the innermost `name`
+    fn close(&mut self, name: &str) -> bool {
+        let Some(index) = self.open.iter().rposition(|tag| tag.name == name) else {
+            return false;
+        };
+        for tag in self.open.drain(index..).rev() {
+            self.html.push_str(tag.close);
+        }
+        true
+    }
+
+    fn push(&mut self, name: &str, open: &str, close: &'static str) {
+        self.html.push_str(open);
+        self.open.push(OpenTag {
+            name: name.to_string(),
+            close,
+        });
+    }
+}
+
+/// Convert BBCode to HTML
+pub fn to_html(source: &str) -> String {
+    let source = source.replace("\r\n", "\n");
+    let mut converter = Converter {
+        html: String::new(),
+        open: Vec::new(),
+    };
+    let mut rest = source.as_str();
+
+    while let Some(start) = rest.find('[') {
+        converter.text(&rest[..start]);
+        rest = &rest[start..];
+
+        let Some(tag) = parse_tag(rest) else {
+            converter.text("[");
+            rest = &rest[1..];
+            continue;
+        };
+        let raw = &rest[..tag.len];
+        let after = &rest[tag.len..];
+
+        if tag.closing {
+            if !converter.close(&tag.name) {
+                converter.text(raw);
+            }
+            rest = after;
+            continue;
+        }
+
+        if tag.name == "*" {
+            if converter.open.iter().any(|open| open.name == "list") {
+                if converter.open.last().is_some_and(|open| open.name == "*") {
+                    converter.close("*");
+                }
+                converter.push("*", "<li>", "</li>");
+            } else {
+                converter.text(raw);
+            }
+            rest = after;
+            continue;
+        }
+
+        if is_verbatim(&tag.name, tag.arg) {
+            let closing_tag = format!("[/{}]", tag.name);
+            if let Some(end) = find_ignore_case(after, &closing_tag) {
+                converter
+                    .html
+                    .push_str(&verbatim_tag(&tag.name, &after[..end]));
+                rest = &after[end + closing_tag.len()..];
+                continue;
+            }
+        }
+
+        match wrapping_tag(&tag.name, tag.arg) {
+            Some((open, close)) => converter.push(&tag.name, &open, close),
+            None => converter.text(raw),
+        }
+        rest = after;
+    }
+    converter.text(rest);
+
+    for tag in converter.open.drain(..).rev() {
+        converter.html.push_str(tag.close);
+    }
+    converter.html
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn converts_common_tags() {
+        assert_eq!(
+            to_html("[b]bold[/b] and [I]italic[/I]\nnext"),
+            "<strong>bold</strong> and <em>italic</em><br />\nnext"
+        );
+        assert_eq!(
+            to_html("[url=https://example.com]site[/url] [url]https://a.b[/url]"),
+            "<a href=\"https://example.com\">site</a> <a href=\"https://a.b\">https://a.b</a>"
+        );
+        assert_eq!(
+            to_html("[quote=\"bob\"]hi[/quote]"),
+            "<blockquote><cite>bob</cite><br />hi</blockquote>"
+        );
+        assert_eq!(
+            to_html("[list]\n[*]one [b]1[/b]\n[*]two\n[/list]"),
+            "<ul><li>one <strong>1</strong></li><li>two</li></ul>"
+        );
+        assert_eq!(
+            to_html("[size=5][color=#f00]big[/color][/size]"),
+            "<span style=\"font-size: 1.5em\"><span style=\"color: #f00\">big</span></span>"
+        );
+    }
+
+    #[test]
+    fn code_is_verbatim_and_text_is_escaped() {
+        assert_eq!(
+            to_html("[code]\n[b]<x>[/b]\n[/code]"),
+            "<pre><code>[b]&lt;x&gt;[/b]</code></pre>"
+        );
+        assert_eq!(to_html("<script>"), "&lt;script&gt;");
+    }
+
+    #[test]
+    fn unbalanced_tags_are_repaired() {
+        assert_eq!(to_html("[b]open"), "<strong>open</strong>");
+        assert_eq!(to_html("stray[/i] [foo]"), "stray[/i] [foo]");
+        assert_eq!(to_html("[b][i]x[/b]"), "<strong><em>x</em></strong>");
+        assert_eq!(
+            to_html("[color=red;x:y]z[/color]"),
+            "[color=red;x:y]z[/color]"
+        );
+        assert_eq!(to_html("a [ b"), "a [ b");
+    }
+}
@@ -0,0 +1,515 @@
+//! Block-based article content
+//!
+//! Block editors store an article as a JSON array of typed blocks:
+//!
+//! ```json
+//! [
+//!   { "type": "heading", "level": 2, "text": "Intro" },
+//!   { "type": "paragraph", "text": "Hello <strong>world</strong>" },
+//!   { "type": "image", "url": "/uploads/a.png", "alt": "A", "caption": "Figure 1" },
+//!   { "type": "embed", "url": "https://www.youtube.com/watch?v=abc" },
+//!   { "type": "code", "language": "rust", "code": "fn main() {}" }
+//! ]
+//! ```
+//!
+//! Text fields hold inline HTML, which is sanitized with the rest of the
+//! rendered article. Markdown stays the portable format: documents convert
+//! from Markdown with [`from_markdown`] and back with [`to_markdown`].
+
+use once_cell::sync::Lazy;
+use pulldown_cmark::{html::push_html, CodeBlockKind, Event, Options, Parser, Tag, TagEnd};
+use regex::{Captures, Regex};
+use serde::{Deserialize, Serialize};
+
+use super::html_escape;
+use crate::services::markdown::MarkdownRenderer;
+
+static STRONG_RE: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"(?is)<(strong|b)>(.*?)</(?:strong|b)>").expect("valid strong regex"));
+static EM_RE: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"(?is)<(em|i)>(.*?)</(?:em|i)>").expect("valid em regex"));
+static CODE_RE: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"(?is)<code>(.*?)</code>").expect("valid code regex"));
+static LINK_RE: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r#"(?is)<a\s+href\s*=\s*"([^"]*)"\s*>(.*?)</a>"#).expect("valid link regex")
+});
+
+/// One block of an article
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum Block {
+    Paragraph {
+        text: String,
+    },
+    Heading {
+        level: u8,
+        text: String,
+    },
+    List {
+        #[serde(default)]
+        ordered: bool,
+        items: Vec<String>,
+    },
+    Quote {
+        text: String,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        cite: Option<String>,
+    },
+    Image {
+        url: String,
+        #[serde(default)]
+        alt: String,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        caption: Option<String>,
+    },
+    /// External content; videos become players, other URLs link cards
+    Embed {
+        url: String,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        caption: Option<String>,
+    },
+    Code {
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        language: Option<String>,
+        code: String,
+    },
+    /// Markup without a block type of its own, e.g. tables from Markdown
+    Html {
+        html: String,
+    },
+}
+
+/// Parse a stored block document
+pub fn parse(source: &str) -> Result<Vec<Block>, String> {
+    serde_json::from_str(source).map_err(|e| e.to_string())
+}
+
+/// Render blocks to unsanitized HTML
+///
+/// Code blocks are rendered by `code_block` (language, code) so they can be
+/// highlighted like fenced Markdown code. Video embeds are emitted as
+/// `[video]` shortcodes, which the caller expands.
+pub fn to_html(blocks: &[Block], code_block: &dyn Fn(Option<&str>, &str) -> String) -> String {
+    let mut html = String::new();
+    for block in blocks {
+        match block {
+            Block::Paragraph { text } => html.push_str(&format!("<p>{}</p>", text)),
+            Block::Heading { level, text } => {
+                let level = (*level).clamp(1, 6);
+                html.push_str(&format!("<h{}>{}</h{}>", level, text, level));
+            }
+            Block::List { ordered, items } => {
+                let tag = if *ordered { "ol" } else { "ul" };
+                html.push_str(&format!("<{}>", tag));
+                for item in items {
+                    html.push_str(&format!("<li>{}</li>", item));
+                }
+                html.push_str(&format!("</{}>", tag));
+            }
+            Block::Quote { text, cite } => {
+                html.push_str(&format!("<blockquote><p>{}</p>", text));
+                if let Some(cite) = cite.as_deref().filter(|c| !c.is_empty()) {
+                    html.push_str(&format!("<footer>{}</footer>", html_escape(cite)));
+                }
+                html.push_str("</blockquote>");
+            }
+            Block::Image { url, alt, caption } => {
+                html.push_str(&format!(
+                    "<figure><img src=\"{}\" alt=\"{}\">",
+                    html_escape(url),
+                    html_escape(alt)
+                ));
+                push_caption(&mut html, caption);
+                html.push_str("</figure>");
+            }
+            Block::Embed { url, caption } => {
+                html.push_str("<figure class=\"noteva-embed\">");
+                if is_video(url) {
+                    html.push_str(&format!("[video url=\"{}\" /]", html_escape(url)));
+                } else {
+                    html.push_str(&MarkdownRenderer::render_link_card(url));
+                }
+                push_caption(&mut html, caption);
+                html.push_str("</figure>");
+            }
+            Block::Code { language, code } => {
+                html.push_str(&code_block(language.as_deref(), code));
+            }
+            Block::Html { html: raw } => html.push_str(raw),
+        }
+        html.push('\n');
+    }
+    html
+}
+
+fn push_caption(html: &mut String, caption: &Option<String>) {
+    if let Some(caption) = caption.as_deref().filter(|c| !c.is_empty()) {
+        html.push_str(&format!(
+            "<figcaption>{}</figcaption>",
+            html_escape(caption)
+        ));
+    }
+}
+
+/// URLs the `[video]` shortcode can play
+fn is_video(url: &str) -> bool {
+    let path = url.split(['?', '#']).next().unwrap_or(url).to_lowercase();
+    url.contains("youtube.com/watch")
+        || url.contains("youtu.be/")
+        || url.contains("bilibili.com/video/")
+        || [".mp4", ".webm", ".m3u8"]
+            .iter()
+            .any(|ext| path.ends_with(ext))
+}
+
+/// Convert blocks to Markdown
+///
+/// Inline bold, italic, code and links become Markdown syntax; other inline
+/// HTML is kept, which Markdown allows.
+pub fn to_markdown(blocks: &[Block]) -> String {
+    let parts: Vec<String> = blocks
+        .iter()
+        .map(|block| match block {
+            Block::Paragraph { text } => inline_to_markdown(text),
+            Block::Heading { level, text } => format!(
+                "{} {}",
+                "#".repeat((*level).clamp(1, 6) as usize),
+                inline_to_markdown(text)
+            ),
+            Block::List { ordered, items } => items
+                .iter()
+                .enumerate()
+                .map(|(i, item)| {
+                    let marker = if *ordered {
+                        format!("{}.", i + 1)
+                    } else {
+                        "-".to_string()
+                    };
+                    format!("{} {}", marker, inline_to_markdown(item))
+                })
+                .collect::<Vec<_>>()
+                .join("\n"),
+            Block::Quote { text, cite } => {
+                let mut quote = inline_to_markdown(text)
+                    .lines()
+                    .map(|line| format!("> {}", line))
+                    .collect::<Vec<_>>()
+                    .join("\n");
+                if let Some(cite) = cite.as_deref().filter(|c| !c.is_empty()) {
+                    quote.push_str(&format!("\n>\n> — {}", cite));
+                }
+                quote
+            }
+            Block::Image { url, alt, caption } => match caption.as_deref() {
+                Some(caption) if !caption.is_empty() => {
+                    format!("![{}]({} \"{}\")", alt, url, caption.replace('"', "\\\""))
+                }
+                _ => format!("![{}]({})", alt, url),
+            },
+            // A URL on its own line renders as a link card
+            Block::Embed { url, .. } => url.clone(),
+            Block::Code { language, code } => {
+                let longest_run = code.split(|c| c != '`').map(str::len).max().unwrap_or(0);
+                let fence = "`".repeat((longest_run + 1).max(3));
+                format!(
+                    "{}{}\n{}\n{}",
+                    fence,
+                    language.as_deref().unwrap_or_default(),
+                    code.trim_end_matches('\n'),
+                    fence
+                )
+            }
+            Block::Html { html } => html.trim().to_string(),
+        })
+        .collect();
+    let mut markdown = parts.join("\n\n");
+    markdown.push('\n');
+    markdown
+}
+
+fn inline_to_markdown(html: &str) -> String {
+    let text = CODE_RE.replace_all(html, |caps: &Captures| {
+        let code = caps[1]
+            .replace("&lt;", "<")
+            .replace("&gt;", ">")
+            .replace("&quot;", "\"")
+            .replace("&amp;", "&");
+        format!("`{}`", code)
+    });
+    let text = STRONG_RE.replace_all(&text, "**$2**");
+    let text = EM_RE.replace_all(&text, "*$2*");
+    LINK_RE.replace_all(&text, "[$2]($1)").into_owned()
+}
+
+/// Convert a Markdown document to blocks
+///
+/// Paragraphs holding only an image or a bare URL become image and embed
+/// blocks; tables, rules and other constructs are kept as HTML blocks.
+pub fn from_markdown(markdown: &str) -> Vec<Block> {
+    let mut options = Options::empty();
+    options.insert(Options::ENABLE_TABLES);
+    options.insert(Options::ENABLE_STRIKETHROUGH);
+    options.insert(Options::ENABLE_TASKLISTS);
+
+    let mut blocks = Vec::new();
+    let mut group: Vec<Event> = Vec::new();
+    let mut depth = 0usize;
+    for event in Parser::new_ext(markdown, options) {
+        match &event {
+            Event::Start(_) => depth += 1,
+            Event::End(_) => depth = depth.saturating_sub(1),
+            _ => {}
+        }
+        group.push(event);
+        if depth == 0 {
+            blocks.push(group_to_block(std::mem::take(&mut group)));
+        }
+    }
+    blocks
+}
+
+/// Convert the events of one top-level Markdown block
+fn group_to_block(group: Vec<Event>) -> Block {
+    let inner = if group.len() >= 2 {
+        &group[1..group.len() - 1]
+    } else {
+        &[][..]
+    };
+    match group.first() {
+        Some(Event::Start(Tag::Paragraph)) => paragraph_block(inner),
+        Some(Event::Start(Tag::Heading { level, .. })) => Block::Heading {
+            level: *level as u8,
+            text: events_to_html(inner),
+        },
+        Some(Event::Start(Tag::CodeBlock(kind))) => {
+            let language = match kind {
+                CodeBlockKind::Fenced(info) => info.split_whitespace().next().map(str::to_string),
+                CodeBlockKind::Indented => None,
+            };
+            let code: String = inner
+                .iter()
+                .filter_map(|e| match e {
+                    Event::Text(text) => Some(text.as_ref()),
+                    _ => None,
+                })
+                .collect();
+            Block::Code {
+                language,
+                code: code.strip_suffix('\n').unwrap_or(&code).to_string(),
+            }
+        }
+        Some(Event::Start(Tag::List(start))) => Block::List {
+            ordered: start.is_some(),
+            items: split_top_level(inner)
+                .into_iter()
+                .map(|item| unwrap_paragraph(events_to_html(item).trim()))
+                .collect(),
+        },
+        Some(Event::Start(Tag::BlockQuote)) => {
+            let parts = split_top_level(inner);
+            let only_paragraphs = parts
+                .iter()
+                .all(|part| matches!(part.first(), Some(Event::Start(Tag::Paragraph))));
+            if only_paragraphs && !parts.is_empty() {
+                let paragraphs: Vec<String> = parts
+                    .iter()
+                    .map(|part| events_to_html(&part[1..part.len() - 1]))
+                    .collect();
+                Block::Quote {
+                    text: paragraphs.join("<br><br>"),
+                    cite: None,
+                }
+            } else {
+                Block::Html {
+                    html: events_to_html(&group),
+                }
+            }
+        }
+        _ => Block::Html {
+            html: events_to_html(&group).trim().to_string(),
+        },
+    }
+}
+
+fn paragraph_block(inner: &[Event]) -> Block {
+    if let (
+        Some(Event::Start(Tag::Image {
+            dest_url, title, ..
+        })),
+        Some(Event::End(TagEnd::Image)),
+    ) = (inner.first(), inner.last())
+    {
+        let alt_events = &inner[1..inner.len() - 1];
+        if !alt_events
+            .iter()
+            .any(|e| matches!(e, Event::Start(Tag::Image { .. })))
+        {
+            return Block::Image {
+                url: dest_url.to_string(),
+                alt: alt_events
+                    .iter()
+                    .filter_map(|e| match e {
+                        Event::Text(text) | Event::Code(text) => Some(text.as_ref()),
+                        _ => None,
+                    })
+                    .collect(),
+                caption: Some(title.to_string()).filter(|t| !t.is_empty()),
+            };
+        }
+    }
+    if let [Event::Text(text)] = inner {
+        let url = text.trim();
+        if (url.starts_with("https://") || url.starts_with("http://"))
+            && !url.contains(char::is_whitespace)
+        {
+            return Block::Embed {
+                url: url.to_string(),
+                caption: None,
+            };
+        }
+    }
+    Block::Paragraph {
+        text: events_to_html(inner),
+    }
+}
+
+/// Split events into their top-level start..end groups
+fn split_top_level<'a, 'e>(events: &'a [Event<'e>]) -> Vec<&'a [Event<'e>]> {
+    let mut parts = Vec::new();
+    let mut depth = 0usize;
+    let mut start = 0;
+    for (i, event) in events.iter().enumerate() {
+        match event {
+            Event::Start(_) => {
+                if depth == 0 {
+                    start = i;
+                }
+                depth += 1;
+            }
+            Event::End(_) => {
+                depth = depth.saturating_sub(1);
+                if depth == 0 {
+                    parts.push(&events[start..=i]);
+                }
+            }
+            _ => {}
+        }
+    }
+    parts
+        .into_iter()
+        .map(|part| {
+            // List items: drop the item tags themselves
+            match (part.first(), part.last()) {
+                (Some(Event::Start(Tag::Item)), Some(Event::End(TagEnd::Item))) => {
+                    &part[1..part.len() - 1]
+                }
+                _ => part,
+            }
+        })
+        .collect()
+}
+
+fn unwrap_paragraph(html: &str) -> String {
+    match html
+        .strip_prefix("<p>")
+        .and_then(|rest| rest.strip_suffix("</p>"))
+    {
+        Some(inner) if !inner.contains("<p>") => inner.to_string(),
+        _ => html.to_string(),
+    }
+}
+
+fn events_to_html(events: &[Event]) -> String {
+    let mut html = String::new();
+    push_html(&mut html, events.iter().cloned());
+    html
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn renders_blocks() {
+        let blocks = parse(
+            r#"[
+                {"type": "heading", "level": 2, "text": "Intro"},
+                {"type": "paragraph", "text": "Hello <strong>world</strong>"},
+                {"type": "list", "ordered": true, "items": ["one", "two"]},
+                {"type": "image", "url": "/a.png", "alt": "A \"quoted\"", "caption": "Fig <1>"},
+                {"type": "embed", "url": "https://www.youtube.com/watch?v=abc"},
+                {"type": "embed", "url": "https://example.com/post"},
+                {"type": "code", "language": "rust", "code": "fn main() {}"}
+            ]"#,
+        )
+        .unwrap();
+        let html = to_html(&blocks, &|lang, code| {
+            format!("<pre data-lang=\"{}\">{}</pre>", lang.unwrap_or(""), code)
+        });
+
+        assert!(html.contains("<h2>Intro</h2>"));
+        assert!(html.contains("<p>Hello <strong>world</strong></p>"));
+        assert!(html.contains("<ol><li>one</li><li>two</li></ol>"));
+        assert!(html.contains("<img src=\"/a.png\" alt=\"A &quot;quoted&quot;\">"));
+        assert!(html.contains("<figcaption>Fig &lt;1&gt;</figcaption>"));
+        assert!(html.contains("[video url=\"https://www.youtube.com/watch?v=abc\" /]"));
+        assert!(html.contains("noteva-link-card"));
+        assert!(html.contains("<pre data-lang=\"rust\">fn main() {}</pre>"));
+    }
+
+    #[test]
+    fn rejects_unknown_block_types() {
+        assert!(parse(r#"[{"type": "carousel"}]"#).is_err());
+        assert!(parse("# Markdown").is_err());
+    }
+
+    #[test]
+    fn markdown_round_trips() {
+        let markdown = "## Intro\n\n\
+                        Hello **world** and `a<b`, see [docs](https://example.com/docs).\n\n\
+                        - one\n- two\n\n\
+                        > Quoted\n\n\
+                        ![Alt](/a.png \"Caption\")\n\n\
+                        https://example.com\n\n\
+                        ```rust\nfn main() {}\n```\n\n\
+                        | a | b |\n|---|---|\n| 1 | 2 |\n";
+        let blocks = from_markdown(markdown);
+
+        assert_eq!(
+            blocks[..4],
+            [
+                Block::Heading {
+                    level: 2,
+                    text: "Intro".to_string()
+                },
+                Block::Paragraph {
+                    text: "Hello <strong>world</strong> and <code>a&lt;b</code>, see \
+                           <a href=\"https://example.com/docs\">docs</a>."
+                        .to_string()
+                },
+                Block::List {
+                    ordered: false,
+                    items: vec!["one".to_string(), "two".to_string()]
+                },
+                Block::Quote {
+                    text: "Quoted".to_string(),
+                    cite: None
+                },
+            ]
+        );
+        assert_eq!(
+            blocks[4],
+            Block::Image {
+                url: "/a.png".to_string(),
+                alt: "Alt".to_string(),
+                caption: Some("Caption".to_string())
+            }
+        );
+        assert!(matches!(&blocks[5], Block::Embed { url, .. } if url == "https://example.com"));
+        assert!(matches!(&blocks[6], Block::Code { language: Some(l), .. } if l == "rust"));
+        assert!(matches!(&blocks[7], Block::Html { html } if html.starts_with("<table>")));
+
+        let exported = to_markdown(&blocks[..7]);
+        assert_eq!(exported, markdown[..markdown.find("| a |").unwrap() - 1]);
+    }
+}
@@ -0,0 +1,113 @@
+//! HTML article sources
+//!
+//! HTML from block editors is kept as written; headings only get anchor ids
+//! so the table of contents works like for Markdown articles.
+
+use once_cell::sync::Lazy;
+use regex::{Captures, Regex};
+
+use crate::services::markdown::engine::HeadingIds;
+use crate::services::markdown::TocEntry;
+
+static HEADING_RE: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"(?is)<h([1-6])\b([^>]*)>(.*?)(</h[1-6]\s*>)").expect("valid heading regex")
+});
+static ID_ATTR_RE: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r#"(?is)(?:^|\s)id\s*=\s*(?:"([^"]*)"|'([^']*)')"#).expect("valid id regex")
+});
+static TAG_RE: Lazy<Regex> = Lazy::new(|| Regex::new(r"(?s)<[^>]*>").expect("valid tag regex"));
+
+/// Give headings without an `id` one and collect the table of contents
+///
+/// Ids come from [`HeadingIds`], the same as for rendered Markdown; ids
+/// already set by the editor are kept.
+pub fn anchor_headings(html: &str) -> (String, Vec<TocEntry>) {
+    let mut ids = HeadingIds::default();
+    let mut toc = Vec::new();
+    let html = HEADING_RE
+        .replace_all(html, |caps: &Captures| {
+            let text = heading_text(&caps[3]);
+            if text.is_empty() {
+                return caps[0].to_string();
+            }
+            let level = caps[1].parse().unwrap_or(1);
+            let attrs = &caps[2];
+            match ID_ATTR_RE.captures(attrs) {
+                Some(existing) => {
+                    let id = existing
+                        .get(1)
+                        .or(existing.get(2))
+                        .map_or("", |m| m.as_str());
+                    toc.push(TocEntry {
+                        level,
+                        text,
+                        id: id.to_string(),
+                    });
+                    caps[0].to_string()
+                }
+                None => {
+                    let id = ids.next(&text);
+                    let anchored = format!(
+                        "<h{}{} id=\"{}\">{}{}",
+                        level, attrs, id, &caps[3], &caps[4]
+                    );
+                    toc.push(TocEntry { level, text, id });
+                    anchored
+                }
+            }
+        })
+        .into_owned();
+    (html, toc)
+}
+
+/// Plain text of a heading's inner HTML
+fn heading_text(inner: &str) -> String {
+    let text = TAG_RE.replace_all(inner, "");
+    let text = text
+        .replace("&nbsp;", " ")
+        .replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&quot;", "\"")
+        .replace("&#39;", "'")
+        .replace("&amp;", "&");
+    text.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn headings_get_unique_ids() {
+        let (html, toc) = anchor_headings(
+            "<h2>Getting <em>started</em></h2><p>x</p><h3 class=\"sub\">Setup</h3><h2>Getting started</h2>",
+        );
+        assert_eq!(
+            html,
+            "<h2 id=\"getting-started\">Getting <em>started</em></h2><p>x</p>\
+             <h3 class=\"sub\" id=\"setup\">Setup</h3><h2 id=\"getting-started-1\">Getting started</h2>"
+        );
+        let entries: Vec<_> = toc
+            .iter()
+            .map(|e| (e.level, e.text.as_str(), e.id.as_str()))
+            .collect();
+        assert_eq!(
+            entries,
+            [
+                (2, "Getting started", "getting-started"),
+                (3, "Setup", "setup"),
+                (2, "Getting started", "getting-started-1"),
+            ]
+        );
+    }
+
+    #[test]
+    fn editor_ids_and_empty_headings_are_kept() {
+        let source = "<h2 data-block='1' id='intro'>Intro &amp; goals</h2><h3><br></h3>";
+        let (html, toc) = anchor_headings(source);
+        assert_eq!(html, source);
+        assert_eq!(toc.len(), 1);
+        assert_eq!(toc[0].id, "intro");
+        assert_eq!(toc[0].text, "Intro & goals");
+    }
+}
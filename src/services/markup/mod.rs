@@ -0,0 +1,80 @@
+//! Renderers for article input formats other than Markdown
+//!
+//! Articles imported from forums and older blogs keep their BBCode or
+//! reStructuredText source; it is turned into HTML here and then sanitized
+//! like rendered Markdown. Each renderer is behind a cargo feature (`bbcode`,
+//! `rst`), and [`is_available`] tells whether the running build has it.
+//! HTML sources from block editors need no conversion; see [`html`]. Block
+//! documents are rendered by [`blocks`].
+
+#[cfg(feature = "bbcode")]
+mod bbcode;
+pub mod blocks;
+pub mod html;
+#[cfg(feature = "rst")]
+mod rst;
+
+use crate::models::InputFormat;
+
+/// Errors from rendering non-Markdown sources
+#[derive(Debug, thiserror::Error)]
+pub enum MarkupError {
+    /// The build was compiled without the format's feature
+    #[error("Input format '{0}' is not available in this build")]
+    Unavailable(InputFormat),
+
+    #[error("Invalid {format} source: {message}")]
+    Parse {
+        format: InputFormat,
+        message: String,
+    },
+}
+
+/// Whether articles in `format` can be rendered by this build
+pub fn is_available(format: InputFormat) -> bool {
+    match format {
+        InputFormat::Markdown | InputFormat::Html | InputFormat::Blocks => true,
+        InputFormat::Bbcode => cfg!(feature = "bbcode"),
+        InputFormat::Rst => cfg!(feature = "rst"),
+    }
+}
+
+/// Render a non-Markdown source to unsanitized HTML
+///
+/// Markdown goes through [`MarkdownRenderer`](super::MarkdownRenderer) and
+/// is reported as unavailable here.
+pub fn to_html(format: InputFormat, source: &str) -> Result<String, MarkupError> {
+    match format {
+        InputFormat::Html => Ok(source.to_string()),
+        InputFormat::Blocks => {
+            let blocks =
+                blocks::parse(source).map_err(|message| MarkupError::Parse { format, message })?;
+            Ok(blocks::to_html(&blocks, &|lang, code| match lang {
+                Some(lang) => format!(
+                    "<pre><code class=\"language-{}\">{}</code></pre>",
+                    html_escape(lang),
+                    html_escape(code)
+                ),
+                None => format!("<pre><code>{}</code></pre>", html_escape(code)),
+            }))
+        }
+        #[cfg(feature = "bbcode")]
+        InputFormat::Bbcode => Ok(bbcode::to_html(source)),
+        #[cfg(feature = "rst")]
+        InputFormat::Rst => {
+            rst::to_html(source).map_err(|message| MarkupError::Parse { format, message })
+        }
+        _ => {
+            let _ = source;
+            Err(MarkupError::Unavailable(format))
+        }
+    }
+}
+
+fn html_escape(s: &str) -> String {
+    s.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+        .replace('\'', "&#x27;")
+}
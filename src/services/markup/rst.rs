@@ -0,0 +1,22 @@
+//! reStructuredText to HTML
+
+/// Convert a reStructuredText document to an HTML fragment
+pub fn to_html(source: &str) -> Result<String, String> {
+    let document = rst_parser::parse(source).map_err(|e| e.to_string())?;
+    let mut html = Vec::new();
+    rst_renderer::render_html(&document, &mut html, false).map_err(|e| e.to_string())?;
+    String::from_utf8(html).map_err(|e| e.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn renders_inline_markup_and_lists() {
+        let html = to_html("Some *emphasis* and **strong** text.\n\n- one\n- two\n").unwrap();
+        assert!(html.contains("<em>emphasis</em>"));
+        assert!(html.contains("<strong>strong</strong>"));
+        assert!(html.contains("<li>"));
+    }
+}
@@ -7,38 +7,124 @@
 //! - Handling validation and error cases
 
 pub mod about;
+pub mod admin_events;
+pub mod analytics;
+pub mod announcement;
+pub mod api_exposure;
+pub mod api_rate_limiter;
 pub mod article;
+pub mod article_author;
+pub mod attachment;
 pub mod backup;
+pub mod captcha;
 pub mod captcha_pow;
 pub mod category;
 pub mod comment;
+pub mod custom_field;
+pub mod doc;
+pub mod duplicates;
 pub mod email;
 pub mod emoji;
+pub mod event;
+pub mod faq;
+pub mod featured;
 pub mod friend_link;
+pub mod github_publish;
+pub mod import;
+pub mod inbound_webhook;
+pub mod ip_reputation;
+pub mod job_queue;
+pub mod jobs;
+pub mod ldap;
+pub mod license;
+pub mod link_suggestions;
+pub mod maintenance;
 pub mod markdown;
+pub mod markup;
+pub mod monitor;
 pub mod nav_item;
+pub mod newsletter;
+pub mod outbound;
 pub mod page;
 pub mod password;
+pub mod password_policy;
+pub mod poll;
+pub mod publish_checklist;
 pub mod rate_limiter;
+pub mod reader_group;
+pub mod redirect;
+pub mod release;
+pub mod robots;
+#[cfg(feature = "saml")]
+pub mod saml;
+pub mod series;
 pub mod settings;
+pub mod spellcheck;
+pub mod stats;
+pub mod sync;
 pub mod tag;
+pub mod translation;
 pub mod user;
+pub mod views;
+pub mod web_push;
+pub mod webauthn;
+pub mod webmention;
 
 pub use about::AboutService;
+pub use admin_events::{AdminEvent, AdminEvents};
+pub use analytics::{AnalyticsService, PageHit};
+pub use api_exposure::ApiExposureService;
+pub use api_rate_limiter::{ApiRateLimiter, RateDecision, RateLimitClass};
 pub use article::{generate_slug as generate_article_slug, ArticleService, ArticleServiceError};
+pub use article_author::{ArticleAuthorError, ArticleAuthorService};
+pub use attachment::{AttachmentError, AttachmentService};
+pub use captcha::{CaptchaError, CaptchaVerifier};
 pub use captcha_pow::{CaptchaPowDifficulty, CaptchaPowStore};
 pub use category::{
     generate_slug, CategoryService, CategoryServiceError, CreateCategoryInput, UpdateCategoryInput,
 };
 pub use comment::{generate_fingerprint, CommentService};
-pub use email::{generate_verification_code, EmailService};
+pub use custom_field::{CustomFieldError, CustomFieldService};
+pub use doc::{DocError, DocService, DocView};
+pub use duplicates::DuplicateService;
+pub use email::{generate_verification_code, EmailService, EmailTemplates};
 pub use emoji::{process_all_emoji, process_shortcodes, process_unicode_emoji};
+pub use event::EventService;
+pub use faq::{FaqError, FaqService};
+pub use featured::{FeaturedError, FeaturedService};
 pub use friend_link::FriendLinkService;
+pub use github_publish::{GithubPublishError, GithubPublishService};
+pub use inbound_webhook::{InboundWebhookError, InboundWebhookService};
+pub use ip_reputation::{AbuseSignal, IpReputationStore};
+pub use job_queue::{JobQueue, JobQueueError, JobQueueOverview};
+pub use jobs::{JobError, JobInfo, JobMonitor, JobStatus, ScheduleInfo};
+pub use ldap::{DirectoryAuthenticator, LdapAuthenticator};
+pub use license::LicenseError;
+pub use link_suggestions::LinkSuggestionService;
+pub use maintenance::MaintenanceService;
 pub use markdown::{MarkdownRenderer, TocEntry};
+pub use monitor::{ResourceMonitor, ResourceSources};
 pub use nav_item::NavItemService;
+pub use newsletter::{NewsletterError, NewsletterService};
 pub use page::PageService;
 pub use password::{hash_password, verify_password};
+pub use poll::{PollError, PollService, PollView};
 pub use rate_limiter::LoginRateLimiter;
+pub use reader_group::{ReaderGroupError, ReaderGroupService};
+pub use redirect::RedirectService;
+#[cfg(feature = "saml")]
+pub use saml::{SamlError, SamlService};
+pub use series::{SeriesError, SeriesService};
 pub use settings::{SettingsService, SettingsServiceError, SiteSettings};
+pub use spellcheck::SpellcheckService;
+pub use stats::StatsService;
+pub use sync::SyncService;
 pub use tag::{generate_tag_slug, TagService, TagServiceError};
-pub use user::{LoginInput, RegisterInput, UserService, UserServiceError};
+pub use translation::{TranslationError, TranslationService};
+pub use user::{
+    LoginInput, ProvisionOutcome, ProvisionUserInput, RegisterInput, UserService, UserServiceError,
+};
+pub use views::ViewCounter;
+pub use web_push::{WebPushError, WebPushService};
+pub use webauthn::{WebauthnError, WebauthnService};
+pub use webmention::{WebmentionError, WebmentionService};
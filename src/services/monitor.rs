@@ -0,0 +1,374 @@
+//! Resource self-monitor
+//!
+//! Every `monitor.interval_secs` the process samples its resident memory,
+//! open connections (requests being handled plus database connections),
+//! in-memory cache size and the free space on the database and upload
+//! volumes, and compares them with the configured limits.
+//!
+//! A limit being crossed is logged as a warning and reported once to
+//! `monitor.notify_emails` and `monitor.webhook_url`; when it recovers a
+//! second notification follows. Limits that stay crossed are not repeated.
+
+use anyhow::{anyhow, Result};
+use serde::Serialize;
+use std::collections::BTreeSet;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use sysinfo::{Disks, Pid, ProcessesToUpdate, System};
+
+use crate::cache::Cache;
+use crate::config::MonitorConfig;
+use crate::db::DynDatabasePool;
+use crate::services::EmailService;
+
+/// Timeout for delivering an alert to the webhook
+const WEBHOOK_TIMEOUT: Duration = Duration::from_secs(10);
+
+const MIB: u64 = 1024 * 1024;
+
+/// What the monitor reads its measurements from
+pub struct ResourceSources {
+    pub pool: DynDatabasePool,
+    pub cache: Arc<Cache>,
+    /// Volumes checked for free space, by label
+    pub volumes: Vec<(&'static str, PathBuf)>,
+}
+
+/// One set of measurements
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct ResourceSample {
+    /// Resident memory of the process; `None` if it could not be read
+    pub rss_bytes: Option<u64>,
+    /// Requests being handled plus open database connections
+    pub connections: u64,
+    /// In-memory cache entries; `None` for Redis
+    pub cache_entries: Option<u64>,
+    pub volumes: Vec<VolumeSample>,
+}
+
+/// Free space on the volume holding a watched directory
+#[derive(Debug, Clone, Serialize)]
+pub struct VolumeSample {
+    pub label: &'static str,
+    pub path: PathBuf,
+    pub free_bytes: u64,
+}
+
+/// A limit that is currently crossed
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct ResourceAlert {
+    /// Stable name of the check, e.g. `memory` or `disk:uploads`
+    pub check: String,
+    pub message: String,
+    pub value: u64,
+    pub limit: u64,
+}
+
+/// Periodically compares resource usage with the `monitor` limits
+pub struct ResourceMonitor {
+    config: MonitorConfig,
+    sources: ResourceSources,
+    email: Option<Arc<EmailService>>,
+    system: Mutex<System>,
+    /// Checks alerted on and not yet recovered
+    active: Mutex<BTreeSet<String>>,
+}
+
+impl ResourceMonitor {
+    pub fn new(config: MonitorConfig, sources: ResourceSources) -> Self {
+        Self {
+            config,
+            sources,
+            email: None,
+            system: Mutex::new(System::new()),
+            active: Mutex::new(BTreeSet::new()),
+        }
+    }
+
+    /// Email `monitor.notify_emails` through this service
+    pub fn with_email(mut self, email: Arc<EmailService>) -> Self {
+        self.email = Some(email);
+        self
+    }
+
+    /// Take a sample, log crossed and recovered limits and send notifications
+    ///
+    /// `in_flight` is the number of requests currently being handled.
+    /// Fails if a notification could not be delivered.
+    pub async fn check(&self, in_flight: u64) -> Result<()> {
+        let sample = self.sample(in_flight);
+        let alerts = evaluate(&self.config, &sample);
+        let (raised, recovered) = self.transitions(&alerts);
+
+        for alert in &raised {
+            tracing::warn!(
+                check = %alert.check,
+                value = alert.value,
+                limit = alert.limit,
+                "resource limit crossed: {}",
+                alert.message
+            );
+        }
+        for check in &recovered {
+            tracing::info!(check = %check, "resource back within limits");
+        }
+        if raised.is_empty() && recovered.is_empty() {
+            return Ok(());
+        }
+        self.notify(&raised, &recovered, &sample).await
+    }
+
+    /// Measure current resource usage
+    pub fn sample(&self, in_flight: u64) -> ResourceSample {
+        let pid = Pid::from_u32(std::process::id());
+        let rss_bytes = {
+            let mut system = self.system.lock().unwrap();
+            system.refresh_processes(ProcessesToUpdate::Some(&[pid]));
+            system.process(pid).map(|process| process.memory())
+        };
+
+        let disks = Disks::new_with_refreshed_list();
+        let mounts: Vec<(&Path, u64)> = disks
+            .list()
+            .iter()
+            .map(|disk| (disk.mount_point(), disk.available_space()))
+            .collect();
+        let volumes = self
+            .sources
+            .volumes
+            .iter()
+            .filter_map(|(label, path)| {
+                Some(VolumeSample {
+                    label,
+                    path: path.clone(),
+                    free_bytes: free_space(&mounts, path)?,
+                })
+            })
+            .collect();
+
+        ResourceSample {
+            rss_bytes,
+            connections: in_flight + u64::from(self.sources.pool.connections()),
+            cache_entries: self.sources.cache.entry_count(),
+            volumes,
+        }
+    }
+
+    /// Split the current alerts into newly raised ones and recovered checks
+    fn transitions(&self, alerts: &[ResourceAlert]) -> (Vec<ResourceAlert>, Vec<String>) {
+        let mut active = self.active.lock().unwrap();
+        let current: BTreeSet<String> = alerts.iter().map(|a| a.check.clone()).collect();
+        let raised = alerts
+            .iter()
+            .filter(|alert| !active.contains(&alert.check))
+            .cloned()
+            .collect();
+        let recovered = active.difference(&current).cloned().collect();
+        *active = current;
+        (raised, recovered)
+    }
+
+    async fn notify(
+        &self,
+        raised: &[ResourceAlert],
+        recovered: &[String],
+        sample: &ResourceSample,
+    ) -> Result<()> {
+        let mut failures = Vec::new();
+
+        if let Some(email) = self
+            .email
+            .as_ref()
+            .filter(|_| !self.config.notify_emails.is_empty())
+        {
+            let title = if raised.is_empty() {
+                "Resource usage back to normal"
+            } else {
+                "Resource limit crossed"
+            };
+            let mut paragraphs: Vec<String> = raised.iter().map(|a| a.message.clone()).collect();
+            if !recovered.is_empty() {
+                paragraphs.push(format!("Back within limits: {}", recovered.join(", ")));
+            }
+            for to in &self.config.notify_emails {
+                if let Err(e) = email.send_notification(to, title, &paragraphs, None).await {
+                    failures.push(format!("email to {}: {}", to, e));
+                }
+            }
+        }
+
+        if let Some(url) = &self.config.webhook_url {
+            let payload = serde_json::json!({
+                "event": "resource_alert",
+                "alerts": raised,
+                "recovered": recovered,
+                "sample": sample,
+            });
+            let sent = async {
+                reqwest::Client::builder()
+                    .timeout(WEBHOOK_TIMEOUT)
+                    .build()?
+                    .post(url)
+                    .json(&payload)
+                    .send()
+                    .await?
+                    .error_for_status()?;
+                Ok::<_, reqwest::Error>(())
+            };
+            if let Err(e) = sent.await {
+                failures.push(format!("webhook: {}", e));
+            }
+        }
+
+        if failures.is_empty() {
+            Ok(())
+        } else {
+            Err(anyhow!(
+                "Failed to deliver resource alert: {}",
+                failures.join("; ")
+            ))
+        }
+    }
+}
+
+/// Limits crossed by a sample; a limit of 0 is not checked
+pub fn evaluate(config: &MonitorConfig, sample: &ResourceSample) -> Vec<ResourceAlert> {
+    let mut alerts = Vec::new();
+    let mut above = |check: String, value: u64, limit: u64, message: String| {
+        if limit > 0 && value > limit {
+            alerts.push(ResourceAlert {
+                check,
+                message,
+                value,
+                limit,
+            });
+        }
+    };
+
+    if let Some(rss) = sample.rss_bytes {
+        above(
+            "memory".to_string(),
+            rss,
+            config.max_rss_mb.saturating_mul(MIB),
+            format!(
+                "Memory usage is {} MiB (limit {} MiB)",
+                rss / MIB,
+                config.max_rss_mb
+            ),
+        );
+    }
+    above(
+        "connections".to_string(),
+        sample.connections,
+        config.max_connections,
+        format!(
+            "{} open connections (limit {})",
+            sample.connections, config.max_connections
+        ),
+    );
+    if let Some(entries) = sample.cache_entries {
+        above(
+            "cache".to_string(),
+            entries,
+            config.max_cache_entries,
+            format!(
+                "Cache holds {} entries (limit {})",
+                entries, config.max_cache_entries
+            ),
+        );
+    }
+
+    let min_free = config.min_disk_free_mb.saturating_mul(MIB);
+    for volume in &sample.volumes {
+        if min_free > 0 && volume.free_bytes < min_free {
+            alerts.push(ResourceAlert {
+                check: format!("disk:{}", volume.label),
+                message: format!(
+                    "Only {} MiB free on the {} volume ({}; minimum {} MiB)",
+                    volume.free_bytes / MIB,
+                    volume.label,
+                    volume.path.display(),
+                    config.min_disk_free_mb
+                ),
+                value: volume.free_bytes,
+                limit: min_free,
+            });
+        }
+    }
+    alerts
+}
+
+/// Free space of the mount holding `path`: the longest mount point it is under
+fn free_space(mounts: &[(&Path, u64)], path: &Path) -> Option<u64> {
+    // Resolve symlinks; a directory that does not exist yet uses its parent
+    let resolved = path
+        .ancestors()
+        .find_map(|dir| dir.canonicalize().ok())
+        .or_else(|| std::env::current_dir().ok())?;
+    mounts
+        .iter()
+        .filter(|(mount, _)| resolved.starts_with(mount))
+        .max_by_key(|(mount, _)| mount.as_os_str().len())
+        .map(|(_, free)| *free)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample() -> ResourceSample {
+        ResourceSample {
+            rss_bytes: Some(300 * MIB),
+            connections: 12,
+            cache_entries: Some(5_000),
+            volumes: vec![
+                VolumeSample {
+                    label: "data",
+                    path: PathBuf::from("data"),
+                    free_bytes: 10 * 1024 * MIB,
+                },
+                VolumeSample {
+                    label: "uploads",
+                    path: PathBuf::from("uploads"),
+                    free_bytes: 100 * MIB,
+                },
+            ],
+        }
+    }
+
+    #[test]
+    fn evaluate_reports_crossed_limits() {
+        let config = MonitorConfig {
+            max_rss_mb: 256,
+            max_connections: 20,
+            max_cache_entries: 1_000,
+            min_disk_free_mb: 512,
+            ..MonitorConfig::default()
+        };
+        let checks: Vec<String> = evaluate(&config, &sample())
+            .into_iter()
+            .map(|alert| alert.check)
+            .collect();
+        assert_eq!(checks, ["memory", "cache", "disk:uploads"]);
+
+        let off = MonitorConfig {
+            max_rss_mb: 0,
+            max_connections: 0,
+            max_cache_entries: 0,
+            min_disk_free_mb: 0,
+            ..MonitorConfig::default()
+        };
+        assert!(evaluate(&off, &sample()).is_empty());
+    }
+
+    #[test]
+    fn free_space_uses_the_deepest_mount() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path().canonicalize().unwrap();
+        let mounts = [(Path::new("/"), 1), (root.as_path(), 2)];
+        assert_eq!(free_space(&mounts, &root.join("not/created/yet")), Some(2));
+        assert_eq!(free_space(&mounts, Path::new("/")), Some(1));
+        assert_eq!(free_space(&[], &root), None);
+    }
+}
@@ -0,0 +1,588 @@
+//! Newsletter service
+//!
+//! Readers subscribe with `POST /newsletter/subscribe` and are mailed a
+//! confirmation link (double opt-in); only confirmed subscribers receive
+//! newsletters. Every message links to an unsubscribe page and carries a
+//! one-click `List-Unsubscribe` header.
+//!
+//! When the `newsletter_enabled` setting is "true", publishing an article
+//! sends it to all subscribers: one [`SEND_JOB`] is queued per recipient, so
+//! failed deliveries are retried by the job queue. Each article is sent at
+//! most once unless an admin explicitly sends it again.
+
+use anyhow::{Context, Result};
+use chrono::{DateTime, Utc};
+use data_encoding::BASE64URL_NOPAD;
+use once_cell::sync::Lazy;
+use regex::{Captures, Regex};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::sync::Arc;
+
+use crate::db::repositories::{ArticleRepository, SubscriberRepository};
+use crate::models::{
+    normalize_email, ArticleStatus, NewsletterIssue, Subscriber, SubscriberStatus,
+};
+use crate::plugin::{hook_names, HookManager};
+use crate::services::settings::{keys, SettingsService};
+use crate::services::{EmailService, JobQueue};
+
+/// Setting that enables subscriptions and sending on publish
+pub const NEWSLETTER_ENABLED_KEY: &str = "newsletter_enabled";
+
+/// Job queue kind that mails a confirmation link
+pub const CONFIRM_JOB: &str = "newsletter_confirm";
+
+/// Job queue kind that mails an article to one subscriber
+pub const SEND_JOB: &str = "newsletter_send";
+
+/// Root-relative `src` and `href` attributes
+static RELATIVE_URL_RE: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r#"(?i)(\s(?:src|href)\s*=\s*["'])/([^/])"#).expect("valid relative url regex")
+});
+
+/// Errors returned by the newsletter service
+#[derive(Debug, thiserror::Error)]
+pub enum NewsletterError {
+    /// Subscriptions are disabled
+    #[error("Newsletter is not enabled")]
+    Disabled,
+
+    /// `site_url` is needed for the links in newsletter emails
+    #[error("The site URL must be configured to send newsletters")]
+    SiteUrlMissing,
+
+    #[error("Invalid email address")]
+    InvalidEmail,
+
+    /// Unknown or outdated confirmation / unsubscribe link
+    #[error("Invalid or expired link")]
+    InvalidToken,
+
+    #[error("Article not found")]
+    ArticleNotFound,
+
+    #[error("Only published articles can be sent")]
+    NotPublished,
+
+    /// Subscribers are anonymous; private articles never go out
+    #[error("Articles that anonymous visitors cannot read are not sent")]
+    NotPublic,
+
+    /// The article went out before and `resend` was not requested
+    #[error("Article was already sent on {0}")]
+    AlreadySent(DateTime<Utc>),
+
+    #[error("Internal error: {0}")]
+    Internal(#[from] anyhow::Error),
+}
+
+/// Payload of a [`CONFIRM_JOB`]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ConfirmJob {
+    pub subscriber_id: i64,
+}
+
+/// Payload of a [`SEND_JOB`]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SendJob {
+    pub article_id: i64,
+    pub subscriber_id: i64,
+}
+
+/// Subscriber management and article delivery
+pub struct NewsletterService {
+    subscriber_repo: Arc<dyn SubscriberRepository>,
+    article_repo: Arc<dyn ArticleRepository>,
+    settings: Arc<SettingsService>,
+    email: Arc<EmailService>,
+    queue: Arc<JobQueue>,
+}
+
+impl NewsletterService {
+    pub fn new(
+        subscriber_repo: Arc<dyn SubscriberRepository>,
+        article_repo: Arc<dyn ArticleRepository>,
+        settings: Arc<SettingsService>,
+        email: Arc<EmailService>,
+        queue: Arc<JobQueue>,
+    ) -> Self {
+        Self {
+            subscriber_repo,
+            article_repo,
+            settings,
+            email,
+            queue,
+        }
+    }
+
+    /// Register hooks that send articles when they get published
+    pub fn register_hooks(self: &Arc<Self>, hook_manager: &HookManager) {
+        let service = self.clone();
+        hook_manager.register(
+            hook_names::ARTICLE_STATUS_CHANGE,
+            move |data: &mut Value| {
+                if data.get("new_status").and_then(Value::as_str) == Some("Published") {
+                    service.spawn_send(data.get("id").and_then(Value::as_i64));
+                }
+                None
+            },
+            100,
+            None,
+        );
+
+        let service = self.clone();
+        hook_manager.register(
+            hook_names::ARTICLE_AFTER_CREATE,
+            move |data: &mut Value| {
+                if data.get("status").and_then(Value::as_str) == Some("Published") {
+                    service.spawn_send(data.get("id").and_then(Value::as_i64));
+                }
+                None
+            },
+            100,
+            None,
+        );
+    }
+
+    /// Register the handlers for queued [`CONFIRM_JOB`]s and [`SEND_JOB`]s
+    pub fn register_jobs(self: &Arc<Self>, queue: &JobQueue) {
+        let service = self.clone();
+        queue.register(CONFIRM_JOB, move |payload| {
+            let service = service.clone();
+            async move {
+                let job: ConfirmJob =
+                    serde_json::from_value(payload).context("Invalid newsletter confirm job")?;
+                service.deliver_confirmation(job.subscriber_id).await
+            }
+        });
+
+        let service = self.clone();
+        queue.register(SEND_JOB, move |payload| {
+            let service = service.clone();
+            async move {
+                let job: SendJob =
+                    serde_json::from_value(payload).context("Invalid newsletter send job")?;
+                service
+                    .deliver_article(job.article_id, job.subscriber_id)
+                    .await
+            }
+        });
+    }
+
+    fn spawn_send(self: &Arc<Self>, article_id: Option<i64>) {
+        let (Some(article_id), Ok(handle)) = (article_id, tokio::runtime::Handle::try_current())
+        else {
+            return;
+        };
+        let service = self.clone();
+        handle.spawn(async move {
+            if !service.is_enabled().await {
+                return;
+            }
+            match service.send_article(article_id, false).await {
+                Ok(issue) => {
+                    tracing::info!(
+                        article_id,
+                        recipients = issue.recipients,
+                        "newsletter queued"
+                    );
+                }
+                // Republishing an article does not mail it again
+                Err(NewsletterError::AlreadySent(_)) => {}
+                Err(NewsletterError::NotPublic) => {
+                    tracing::debug!(article_id, "private article not sent to subscribers");
+                }
+                Err(e) => {
+                    tracing::warn!(article_id, error = %e, "failed to queue newsletter");
+                }
+            }
+        });
+    }
+
+    pub async fn is_enabled(&self) -> bool {
+        matches!(
+            self.settings.get(NEWSLETTER_ENABLED_KEY).await,
+            Ok(Some(ref v)) if v == "true"
+        )
+    }
+
+    /// Configured public site URL without trailing slash
+    async fn site_url(&self) -> Result<String, NewsletterError> {
+        self.settings
+            .get(keys::SITE_URL)
+            .await
+            .ok()
+            .flatten()
+            .map(|s| s.trim().trim_end_matches('/').to_string())
+            .filter(|s| !s.is_empty())
+            .ok_or(NewsletterError::SiteUrlMissing)
+    }
+
+    /// Start a subscription and queue the confirmation email.
+    ///
+    /// Addresses that are already subscribed succeed without an email, so
+    /// the response does not reveal who is on the list. Pending addresses
+    /// get the confirmation again; unsubscribed ones start over.
+    pub async fn subscribe(&self, email: &str) -> Result<(), NewsletterError> {
+        if !self.is_enabled().await {
+            return Err(NewsletterError::Disabled);
+        }
+        self.site_url().await?;
+        let email = normalize_email(email);
+        if email.len() > 254 || email.parse::<lettre::Address>().is_err() {
+            return Err(NewsletterError::InvalidEmail);
+        }
+
+        let subscriber_id = match self.subscriber_repo.get_by_email(&email).await? {
+            None => {
+                self.subscriber_repo
+                    .create(&email, &generate_token())
+                    .await?
+                    .id
+            }
+            Some(subscriber) => match subscriber.status {
+                SubscriberStatus::Active => return Ok(()),
+                SubscriberStatus::Pending => subscriber.id,
+                SubscriberStatus::Unsubscribed => {
+                    self.subscriber_repo
+                        .resubscribe(subscriber.id, &generate_token())
+                        .await?;
+                    subscriber.id
+                }
+            },
+        };
+        self.queue
+            .enqueue(CONFIRM_JOB, &ConfirmJob { subscriber_id })
+            .await
+            .map_err(anyhow::Error::from)?;
+        Ok(())
+    }
+
+    /// Activate the subscription of a confirmation link
+    pub async fn confirm(&self, token: &str) -> Result<Subscriber, NewsletterError> {
+        let mut subscriber = self.find_by_token(token).await?;
+        match subscriber.status {
+            SubscriberStatus::Active => {}
+            SubscriberStatus::Pending => {
+                let now = Utc::now();
+                self.subscriber_repo
+                    .set_status(subscriber.id, SubscriberStatus::Active, now)
+                    .await?;
+                subscriber.status = SubscriberStatus::Active;
+                subscriber.confirmed_at = Some(now);
+            }
+            // The old confirmation link must not undo an unsubscribe
+            SubscriberStatus::Unsubscribed => return Err(NewsletterError::InvalidToken),
+        }
+        Ok(subscriber)
+    }
+
+    /// End the subscription of an unsubscribe link
+    ///
+    /// Works even when the newsletter has been disabled since.
+    pub async fn unsubscribe(&self, token: &str) -> Result<Subscriber, NewsletterError> {
+        let mut subscriber = self.find_by_token(token).await?;
+        if subscriber.status != SubscriberStatus::Unsubscribed {
+            let now = Utc::now();
+            self.subscriber_repo
+                .set_status(subscriber.id, SubscriberStatus::Unsubscribed, now)
+                .await?;
+            subscriber.status = SubscriberStatus::Unsubscribed;
+            subscriber.unsubscribed_at = Some(now);
+        }
+        Ok(subscriber)
+    }
+
+    async fn find_by_token(&self, token: &str) -> Result<Subscriber, NewsletterError> {
+        let token = token.trim();
+        if token.is_empty() {
+            return Err(NewsletterError::InvalidToken);
+        }
+        self.subscriber_repo
+            .get_by_token(token)
+            .await?
+            .ok_or(NewsletterError::InvalidToken)
+    }
+
+    /// List subscribers for the admin panel
+    pub async fn list(
+        &self,
+        status: Option<SubscriberStatus>,
+        page: i64,
+        per_page: i64,
+    ) -> Result<(Vec<Subscriber>, i64)> {
+        self.subscriber_repo.list(status, page, per_page).await
+    }
+
+    /// Number of subscribers per status
+    pub async fn counts(&self) -> Result<Vec<(SubscriberStatus, i64)>> {
+        self.subscriber_repo.counts().await
+    }
+
+    /// Remove a subscriber. Returns whether it existed.
+    pub async fn delete(&self, id: i64) -> Result<bool> {
+        self.subscriber_repo.delete(id).await
+    }
+
+    /// When an article was sent, if it was
+    pub async fn issue(&self, article_id: i64) -> Result<Option<NewsletterIssue>> {
+        self.subscriber_repo.get_issue(article_id).await
+    }
+
+    /// Queue a published article for every active subscriber.
+    ///
+    /// An article that was sent before is rejected with
+    /// [`NewsletterError::AlreadySent`] unless `resend` is set.
+    pub async fn send_article(
+        &self,
+        article_id: i64,
+        resend: bool,
+    ) -> Result<NewsletterIssue, NewsletterError> {
+        let article = self
+            .article_repo
+            .get_by_id(article_id)
+            .await?
+            .ok_or(NewsletterError::ArticleNotFound)?;
+        if article.status != ArticleStatus::Published {
+            return Err(NewsletterError::NotPublished);
+        }
+        if !self.article_repo.is_public(article_id).await? {
+            return Err(NewsletterError::NotPublic);
+        }
+        self.site_url().await?;
+        if !resend {
+            if let Some(issue) = self.subscriber_repo.get_issue(article_id).await? {
+                return Err(NewsletterError::AlreadySent(issue.sent_at));
+            }
+        }
+
+        let subscriber_ids = self.subscriber_repo.active_ids().await?;
+        // Recorded first so a concurrent publish hook sees the issue
+        let issue = self
+            .subscriber_repo
+            .record_issue(article_id, subscriber_ids.len() as i64, Utc::now())
+            .await?;
+        for subscriber_id in subscriber_ids {
+            self.queue
+                .enqueue(
+                    SEND_JOB,
+                    &SendJob {
+                        article_id,
+                        subscriber_id,
+                    },
+                )
+                .await
+                .map_err(anyhow::Error::from)?;
+        }
+        Ok(issue)
+    }
+
+    /// Mail the confirmation link of a pending subscriber
+    async fn deliver_confirmation(&self, subscriber_id: i64) -> Result<()> {
+        let Some(subscriber) = self.subscriber_repo.get(subscriber_id).await? else {
+            return Ok(());
+        };
+        if subscriber.status != SubscriberStatus::Pending {
+            return Ok(());
+        }
+        let site_url = self.site_url().await?;
+        let confirm_url = format!(
+            "{}/api/v1/newsletter/confirm?token={}",
+            site_url, subscriber.token
+        );
+        let paragraphs = [
+            "感谢订阅！请点击下面的按钮确认你的邮箱地址，确认后你将收到新文章的邮件通知。"
+                .to_string(),
+            "如果这不是你本人的操作，请忽略这封邮件。".to_string(),
+        ];
+        self.email
+            .send_notification(
+                &subscriber.email,
+                "确认订阅",
+                &paragraphs,
+                Some(("确认订阅", confirm_url.as_str())),
+            )
+            .await
+    }
+
+    /// Mail an article to one subscriber if both are still eligible
+    async fn deliver_article(&self, article_id: i64, subscriber_id: i64) -> Result<()> {
+        let Some(subscriber) = self.subscriber_repo.get(subscriber_id).await? else {
+            return Ok(());
+        };
+        if subscriber.status != SubscriberStatus::Active
+            || self.email.is_suppressed(&subscriber.email).await?
+        {
+            return Ok(());
+        }
+        let Some(article) = self.article_repo.get_by_id(article_id).await? else {
+            return Ok(());
+        };
+        // Moved to a private category (or unpublished) since it was queued
+        if article.status != ArticleStatus::Published
+            || !self.article_repo.is_public(article_id).await?
+        {
+            return Ok(());
+        }
+
+        let site_url = self.site_url().await?;
+        let permalink_structure = self
+            .settings
+            .get(keys::PERMALINK_STRUCTURE)
+            .await
+            .ok()
+            .flatten()
+            .unwrap_or_else(|| "/posts/{slug}".to_string());
+        let identifier = if permalink_structure.contains("{id}") {
+            article.id.to_string()
+        } else {
+            article.slug.clone()
+        };
+        let article_url = format!("{}/posts/{}", site_url, identifier);
+        let unsubscribe_url = format!(
+            "{}/api/v1/newsletter/unsubscribe?token={}",
+            site_url, subscriber.token
+        );
+
+        self.email
+            .send_newsletter(
+                &subscriber.email,
+                &article.title,
+                &absolute_urls(&article.content_html, &site_url),
+                &article_url,
+                &unsubscribe_url,
+            )
+            .await
+    }
+}
+
+/// Random secret for confirmation and unsubscribe links
+fn generate_token() -> String {
+    let mut buf = [0u8; 24];
+    getrandom::fill(&mut buf).expect("Failed to generate random bytes for subscriber token");
+    BASE64URL_NOPAD.encode(&buf)
+}
+
+/// Prefix root-relative links and images with the site URL, since mail
+/// clients have no page to resolve them against
+fn absolute_urls(html: &str, site_url: &str) -> String {
+    RELATIVE_URL_RE
+        .replace_all(html, |caps: &Captures| {
+            format!("{}{}/{}", &caps[1], site_url, &caps[2])
+        })
+        .into_owned()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::JobQueueConfig;
+    use crate::db::repositories::{
+        ReaderGroupRepository, SqlxArticleRepository, SqlxJobQueueRepository,
+        SqlxReaderGroupRepository, SqlxSettingsRepository, SqlxSubscriberRepository,
+    };
+    use crate::db::{create_test_pool, migrations};
+    use crate::models::{ArticleAudience, CreateArticleInput, UserRole};
+
+    #[test]
+    fn relative_urls_are_made_absolute() {
+        let html = r#"<p><img src="/uploads/a.png"> <a href='/posts/b'>b</a> <a href="//cdn.example.com/x">x</a> <a href="https://other.org/">o</a></p>"#;
+        assert_eq!(
+            absolute_urls(html, "https://blog.example.com"),
+            r#"<p><img src="https://blog.example.com/uploads/a.png"> <a href='https://blog.example.com/posts/b'>b</a> <a href="//cdn.example.com/x">x</a> <a href="https://other.org/">o</a></p>"#
+        );
+    }
+
+    #[test]
+    fn tokens_are_unique_and_url_safe() {
+        let (a, b) = (generate_token(), generate_token());
+        assert_ne!(a, b);
+        assert_eq!(a.len(), 32);
+        assert!(a
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_'));
+    }
+
+    #[tokio::test]
+    async fn articles_with_an_audience_are_not_sent() {
+        let pool = create_test_pool()
+            .await
+            .expect("Failed to create test pool");
+        migrations::run_migrations(&pool)
+            .await
+            .expect("Failed to run migrations");
+        let sqlite = pool.as_sqlite().unwrap();
+        let author_id = sqlx::query(
+            "INSERT INTO users (username, email, password_hash, role) VALUES ('author', 'author@example.com', 'hash', 'author')",
+        )
+        .execute(sqlite)
+        .await
+        .unwrap()
+        .last_insert_rowid();
+
+        let article_repo = Arc::new(SqlxArticleRepository::new(pool.clone()));
+        let mut input = CreateArticleInput::new(
+            "members-only".to_string(),
+            "Members only".to_string(),
+            "Hello".to_string(),
+            author_id,
+            1,
+        );
+        input.status = Some(ArticleStatus::Published);
+        let article = article_repo.create(&input).await.unwrap();
+        let audiences = SqlxReaderGroupRepository::new(pool.clone());
+        audiences
+            .set_audience(
+                article.id,
+                &ArticleAudience {
+                    roles: vec![UserRole::Author],
+                    group_ids: Vec::new(),
+                },
+            )
+            .await
+            .unwrap();
+
+        let settings_repo = Arc::new(SqlxSettingsRepository::new(pool.clone()));
+        let settings = Arc::new(SettingsService::new(settings_repo.clone()));
+        settings
+            .set(keys::SITE_URL, "https://blog.example.com")
+            .await
+            .unwrap();
+        let subscribers = SqlxSubscriberRepository::boxed(pool.clone());
+        let subscriber = subscribers
+            .create("reader@example.org", &generate_token())
+            .await
+            .unwrap();
+        subscribers
+            .set_status(subscriber.id, SubscriberStatus::Active, Utc::now())
+            .await
+            .unwrap();
+        let queue = Arc::new(JobQueue::new(
+            SqlxJobQueueRepository::boxed(pool.clone()),
+            JobQueueConfig::default(),
+        ));
+        let service = NewsletterService::new(
+            subscribers,
+            article_repo,
+            settings,
+            Arc::new(EmailService::new(settings_repo)),
+            queue.clone(),
+        );
+
+        assert!(matches!(
+            service.send_article(article.id, false).await,
+            Err(NewsletterError::NotPublic)
+        ));
+        assert!(service.issue(article.id).await.unwrap().is_none());
+        assert!(queue.overview(None, 10).await.unwrap().jobs.is_empty());
+
+        audiences
+            .set_audience(article.id, &ArticleAudience::default())
+            .await
+            .unwrap();
+        let issue = service.send_article(article.id, false).await.unwrap();
+        assert_eq!(issue.recipients, 1);
+        assert_eq!(queue.overview(None, 10).await.unwrap().jobs.len(), 1);
+    }
+}
@@ -0,0 +1,204 @@
+//! Outbound HTTP safety helpers
+//!
+//! Server-side requests to user-supplied URLs (Webmention sources and
+//! endpoints, etc.) must not reach loopback, private or link-local
+//! addresses. These helpers resolve the host and reject such targets, and
+//! [`public_client_builder`] makes the HTTP client re-check every address it
+//! connects to, so a DNS answer that changes after the check (DNS rebinding)
+//! cannot steer the request to an internal host.
+
+use anyhow::{bail, Context, Result};
+use reqwest::dns::{Addrs, Name, Resolve, Resolving};
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr};
+use std::sync::Arc;
+
+/// Maximum response body size read from remote servers
+pub const MAX_REMOTE_BODY_BYTES: usize = 1024 * 1024;
+
+/// Parse `url` and make sure it is an http(s) URL resolving only to public addresses.
+pub async fn ensure_public_url(url: &str) -> Result<reqwest::Url> {
+    let parsed = reqwest::Url::parse(url).with_context(|| format!("Invalid URL: {}", url))?;
+    if !matches!(parsed.scheme(), "http" | "https") {
+        bail!("Only http and https URLs are allowed");
+    }
+    let host = parsed.host_str().context("URL has no host")?;
+    if is_blocked_host_name(host) {
+        bail!("Host is not allowed: {}", host);
+    }
+
+    let port = parsed.port_or_known_default().unwrap_or(80);
+    resolve_public(host, port).await?;
+    Ok(parsed)
+}
+
+/// Client builder for requests to user-supplied URLs.
+///
+/// Host names are resolved through [`PublicResolver`], redirects are not
+/// followed (each hop would need its own check) and proxies are ignored
+/// because a proxy would resolve the host itself.
+pub fn public_client_builder() -> reqwest::ClientBuilder {
+    reqwest::Client::builder()
+        .dns_resolver(Arc::new(PublicResolver))
+        .redirect(reqwest::redirect::Policy::none())
+        .no_proxy()
+}
+
+/// DNS resolver that fails unless every address of a host is public
+pub struct PublicResolver;
+
+impl Resolve for PublicResolver {
+    fn resolve(&self, name: Name) -> Resolving {
+        let host = name.as_str().to_string();
+        Box::pin(async move {
+            let addrs = resolve_public(&host, 0).await?;
+            Ok(Box::new(addrs.into_iter()) as Addrs)
+        })
+    }
+}
+
+/// Resolve `host` and make sure all of its addresses are public.
+async fn resolve_public(host: &str, port: u16) -> Result<Vec<std::net::SocketAddr>> {
+    let addrs: Vec<_> = tokio::net::lookup_host((host, port))
+        .await
+        .with_context(|| format!("Failed to resolve host: {}", host))?
+        .collect();
+    if addrs.is_empty() {
+        bail!("Host did not resolve: {}", host);
+    }
+    if let Some(addr) = addrs.iter().find(|a| !is_public_ip(a.ip())) {
+        bail!("Host resolves to a non-public address: {}", addr.ip());
+    }
+    Ok(addrs)
+}
+
+/// Read a response body, giving up once it exceeds [`MAX_REMOTE_BODY_BYTES`].
+pub async fn read_limited_body(mut response: reqwest::Response) -> Result<String> {
+    let mut body = Vec::new();
+    while let Some(chunk) = response.chunk().await? {
+        if body.len() + chunk.len() > MAX_REMOTE_BODY_BYTES {
+            bail!("Response body too large");
+        }
+        body.extend_from_slice(&chunk);
+    }
+    Ok(String::from_utf8_lossy(&body).into_owned())
+}
+
+fn is_blocked_host_name(host: &str) -> bool {
+    host == "localhost"
+        || host.ends_with(".localhost")
+        || host.ends_with(".local")
+        || host.ends_with(".internal")
+}
+
+/// Whether `ip` is a globally routable address
+pub fn is_public_ip(ip: IpAddr) -> bool {
+    match ip {
+        IpAddr::V4(ip) => is_public_ipv4(ip),
+        IpAddr::V6(ip) => is_public_ipv6(ip),
+    }
+}
+
+fn is_public_ipv4(ip: Ipv4Addr) -> bool {
+    let octets = ip.octets();
+    !(ip.is_private()
+        // "This network" (0.0.0.0/8)
+        || octets[0] == 0
+        || ip.is_loopback()
+        || ip.is_link_local()
+        || ip.is_unspecified()
+        || ip.is_broadcast()
+        || ip.is_multicast()
+        || ip.is_documentation()
+        // Carrier-grade NAT (100.64.0.0/10)
+        || (octets[0] == 100 && (64..=127).contains(&octets[1])))
+}
+
+fn is_public_ipv6(ip: Ipv6Addr) -> bool {
+    if let Some(mapped) = ip.to_ipv4_mapped() {
+        return is_public_ipv4(mapped);
+    }
+    let segments = ip.segments();
+    // NAT64 well-known prefix (64:ff9b::/96) embeds the IPv4 destination
+    if segments[..6] == [0x64, 0xff9b, 0, 0, 0, 0] {
+        let [.., a, b, c, d] = ip.octets();
+        return is_public_ipv4(Ipv4Addr::new(a, b, c, d));
+    }
+    let first = segments[0];
+    !(ip.is_loopback()
+        || ip.is_unspecified()
+        || ip.is_multicast()
+        // Local-use NAT64 (64:ff9b:1::/48)
+        || segments[..3] == [0x64, 0xff9b, 1]
+        // Unique local (fc00::/7) and link-local (fe80::/10)
+        || (first & 0xfe00) == 0xfc00
+        || (first & 0xffc0) == 0xfe80)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn private_addresses_are_not_public() {
+        for ip in [
+            "127.0.0.1",
+            "10.1.2.3",
+            "192.168.0.1",
+            "169.254.169.254",
+            "100.64.0.1",
+            "0.0.0.0",
+            "0.1.2.3",
+        ] {
+            assert!(!is_public_ip(ip.parse().unwrap()), "{}", ip);
+        }
+        for ip in ["::1", "fd00::1", "fe80::1", "::ffff:127.0.0.1"] {
+            assert!(!is_public_ip(ip.parse().unwrap()), "{}", ip);
+        }
+        assert!(is_public_ip("93.184.216.34".parse().unwrap()));
+        assert!(is_public_ip("2606:4700::1111".parse().unwrap()));
+    }
+
+    #[test]
+    fn nat64_addresses_are_judged_by_the_embedded_ipv4() {
+        for ip in ["64:ff9b::7f00:1", "64:ff9b::a9fe:a9fe", "64:ff9b::10.0.0.1"] {
+            assert!(!is_public_ip(ip.parse().unwrap()), "{}", ip);
+        }
+        assert!(!is_public_ip("64:ff9b:1::5db8:d822".parse().unwrap()));
+        assert!(is_public_ip("64:ff9b::93.184.216.34".parse().unwrap()));
+    }
+
+    #[tokio::test]
+    async fn resolver_refuses_hosts_with_private_addresses() {
+        assert!(PublicResolver
+            .resolve("localhost".parse().unwrap())
+            .await
+            .is_err());
+        let addrs: Vec<_> = PublicResolver
+            .resolve("93.184.216.34".parse().unwrap())
+            .await
+            .unwrap()
+            .collect();
+        assert_eq!(addrs[0].ip(), "93.184.216.34".parse::<IpAddr>().unwrap());
+    }
+
+    #[tokio::test]
+    async fn clients_do_not_connect_to_private_addresses() {
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let port = listener.local_addr().unwrap().port();
+        let client = public_client_builder().build().unwrap();
+        // Stands in for a name whose DNS answer turned private after the check
+        let err = client
+            .get(format!("http://localhost:{}/", port))
+            .send()
+            .await
+            .unwrap_err();
+        assert!(err.is_connect(), "{:?}", err);
+    }
+
+    #[tokio::test]
+    async fn local_urls_are_rejected() {
+        assert!(ensure_public_url("http://127.0.0.1/").await.is_err());
+        assert!(ensure_public_url("http://localhost:8080/").await.is_err());
+        assert!(ensure_public_url("ftp://example.com/").await.is_err());
+    }
+}
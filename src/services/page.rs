@@ -4,6 +4,7 @@ use crate::cache::{Cache, CacheLayer};
 use crate::db::repositories::PageRepository;
 use crate::models::{Page, PageStatus};
 use crate::plugin::HookManager;
+use crate::services::markdown::MarkdownEngine;
 use crate::services::MarkdownRenderer;
 use anyhow::{Context, Result};
 use serde_json::json;
@@ -52,6 +53,12 @@ impl PageService {
         }
     }
 
+    /// Parse page content with `engine` instead of pulldown-cmark
+    pub fn with_markdown_engine(mut self, engine: Arc<dyn MarkdownEngine>) -> Self {
+        self.markdown.set_engine(engine);
+        self
+    }
+
     fn trigger_hook(&self, name: &str, data: serde_json::Value) -> serde_json::Value {
         if let Some(ref manager) = self.hook_manager {
             manager.trigger(name, data.clone())
@@ -0,0 +1,246 @@
+//! Password policy
+//!
+//! Minimum length and character class requirements are read from settings.
+//! Optionally, new passwords are checked against the Have I Been Pwned
+//! "Pwned Passwords" range API using k-anonymity: only the first five hex
+//! characters of the password's SHA-1 hash leave the server.
+
+use anyhow::{Context, Result};
+use sha1::{Digest, Sha1};
+use std::collections::HashMap;
+use std::time::Duration;
+
+/// Setting keys for the password policy
+pub mod keys {
+    pub const PASSWORD_MIN_LENGTH: &str = "password_min_length";
+    pub const PASSWORD_REQUIRE_UPPERCASE: &str = "password_require_uppercase";
+    pub const PASSWORD_REQUIRE_LOWERCASE: &str = "password_require_lowercase";
+    pub const PASSWORD_REQUIRE_DIGIT: &str = "password_require_digit";
+    pub const PASSWORD_REQUIRE_SYMBOL: &str = "password_require_symbol";
+    pub const PASSWORD_CHECK_BREACHED: &str = "password_check_breached";
+
+    pub const ALL: &[&str] = &[
+        PASSWORD_MIN_LENGTH,
+        PASSWORD_REQUIRE_UPPERCASE,
+        PASSWORD_REQUIRE_LOWERCASE,
+        PASSWORD_REQUIRE_DIGIT,
+        PASSWORD_REQUIRE_SYMBOL,
+        PASSWORD_CHECK_BREACHED,
+    ];
+}
+
+/// Minimum password length when none is configured
+pub const DEFAULT_MIN_LENGTH: usize = 8;
+
+/// Upper bound for the configurable minimum length
+const MAX_MIN_LENGTH: usize = 128;
+
+/// Pwned Passwords range endpoint
+const HIBP_RANGE_URL: &str = "https://api.pwnedpasswords.com/range/";
+
+/// Timeout for breach lookups
+const HIBP_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Password requirements configured by the admin
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PasswordPolicy {
+    pub min_length: usize,
+    pub require_uppercase: bool,
+    pub require_lowercase: bool,
+    pub require_digit: bool,
+    pub require_symbol: bool,
+    /// Reject passwords found in known data breaches
+    pub check_breached: bool,
+}
+
+impl Default for PasswordPolicy {
+    fn default() -> Self {
+        Self {
+            min_length: DEFAULT_MIN_LENGTH,
+            require_uppercase: false,
+            require_lowercase: false,
+            require_digit: false,
+            require_symbol: false,
+            check_breached: false,
+        }
+    }
+}
+
+impl PasswordPolicy {
+    /// Build the policy from settings values; missing or invalid values fall
+    /// back to the defaults.
+    pub fn from_settings(settings: &HashMap<String, String>) -> Self {
+        let flag = |key: &str| settings.get(key).is_some_and(|v| v.trim() == "true");
+        let min_length = settings
+            .get(keys::PASSWORD_MIN_LENGTH)
+            .and_then(|v| v.trim().parse::<usize>().ok())
+            .map(|n| n.clamp(DEFAULT_MIN_LENGTH, MAX_MIN_LENGTH))
+            .unwrap_or(DEFAULT_MIN_LENGTH);
+
+        Self {
+            min_length,
+            require_uppercase: flag(keys::PASSWORD_REQUIRE_UPPERCASE),
+            require_lowercase: flag(keys::PASSWORD_REQUIRE_LOWERCASE),
+            require_digit: flag(keys::PASSWORD_REQUIRE_DIGIT),
+            require_symbol: flag(keys::PASSWORD_REQUIRE_SYMBOL),
+            check_breached: flag(keys::PASSWORD_CHECK_BREACHED),
+        }
+    }
+
+    /// Check length and character class requirements, returning the first
+    /// violated rule as a user-facing message.
+    pub fn validate(&self, password: &str) -> Result<(), String> {
+        if password.chars().count() < self.min_length {
+            return Err(format!(
+                "Password must be at least {} characters",
+                self.min_length
+            ));
+        }
+        let has = |matches: fn(char) -> bool| password.chars().any(matches);
+        if self.require_uppercase && !has(char::is_uppercase) {
+            return Err("Password must contain an uppercase letter".to_string());
+        }
+        if self.require_lowercase && !has(char::is_lowercase) {
+            return Err("Password must contain a lowercase letter".to_string());
+        }
+        if self.require_digit && !has(|c| c.is_ascii_digit()) {
+            return Err("Password must contain a digit".to_string());
+        }
+        if self.require_symbol && !has(|c| !c.is_alphanumeric() && !c.is_whitespace()) {
+            return Err("Password must contain a symbol".to_string());
+        }
+        Ok(())
+    }
+}
+
+/// Uppercase hex SHA-1 of `password`, split into the 5-character range
+/// prefix and the remaining suffix.
+fn sha1_prefix_suffix(password: &str) -> (String, String) {
+    let digest = Sha1::digest(password.as_bytes());
+    let hex: String = digest.iter().map(|b| format!("{:02X}", b)).collect();
+    let (prefix, suffix) = hex.split_at(5);
+    (prefix.to_string(), suffix.to_string())
+}
+
+/// Whether `suffix` appears with a non-zero count in a range API response.
+/// Padding entries (count 0) are ignored.
+fn range_contains(body: &str, suffix: &str) -> bool {
+    body.lines().any(|line| {
+        let mut parts = line.trim().splitn(2, ':');
+        let entry = parts.next().unwrap_or("");
+        let count = parts
+            .next()
+            .and_then(|c| c.trim().parse::<u64>().ok())
+            .unwrap_or(0);
+        count > 0 && entry.eq_ignore_ascii_case(suffix)
+    })
+}
+
+/// Client for the Pwned Passwords range API
+pub struct BreachChecker {
+    client: reqwest::Client,
+}
+
+impl BreachChecker {
+    pub fn new() -> Self {
+        let client = reqwest::Client::builder()
+            .timeout(HIBP_TIMEOUT)
+            .user_agent(concat!("Noteva/", env!("CARGO_PKG_VERSION")))
+            .build()
+            .unwrap_or_default();
+        Self { client }
+    }
+
+    /// Whether `password` appears in a known breach
+    pub async fn is_breached(&self, password: &str) -> Result<bool> {
+        let (prefix, suffix) = sha1_prefix_suffix(password);
+        let body = self
+            .client
+            .get(format!("{}{}", HIBP_RANGE_URL, prefix))
+            .header("Add-Padding", "true")
+            .send()
+            .await
+            .context("Failed to reach breached password service")?
+            .error_for_status()
+            .context("Breached password service returned an error")?
+            .text()
+            .await
+            .context("Failed to read breached password response")?;
+        Ok(range_contains(&body, &suffix))
+    }
+}
+
+impl Default for BreachChecker {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn settings(pairs: &[(&str, &str)]) -> HashMap<String, String> {
+        pairs
+            .iter()
+            .map(|(k, v)| (k.to_string(), v.to_string()))
+            .collect()
+    }
+
+    #[test]
+    fn default_policy_only_checks_length() {
+        let policy = PasswordPolicy::from_settings(&HashMap::new());
+        assert_eq!(policy, PasswordPolicy::default());
+        assert!(policy.validate("short").is_err());
+        assert!(policy.validate("longenough").is_ok());
+    }
+
+    #[test]
+    fn minimum_length_cannot_go_below_default() {
+        let policy = PasswordPolicy::from_settings(&settings(&[(keys::PASSWORD_MIN_LENGTH, "4")]));
+        assert_eq!(policy.min_length, DEFAULT_MIN_LENGTH);
+        let policy = PasswordPolicy::from_settings(&settings(&[(keys::PASSWORD_MIN_LENGTH, "12")]));
+        assert_eq!(
+            policy.validate("elevenchars").unwrap_err(),
+            "Password must be at least 12 characters"
+        );
+    }
+
+    #[test]
+    fn complexity_rules_report_first_missing_class() {
+        let policy = PasswordPolicy::from_settings(&settings(&[
+            (keys::PASSWORD_REQUIRE_UPPERCASE, "true"),
+            (keys::PASSWORD_REQUIRE_DIGIT, "true"),
+            (keys::PASSWORD_REQUIRE_SYMBOL, "true"),
+        ]));
+        assert_eq!(
+            policy.validate("lowercase1!").unwrap_err(),
+            "Password must contain an uppercase letter"
+        );
+        assert_eq!(
+            policy.validate("Uppercase!!").unwrap_err(),
+            "Password must contain a digit"
+        );
+        assert_eq!(
+            policy.validate("Uppercase11").unwrap_err(),
+            "Password must contain a symbol"
+        );
+        assert!(policy.validate("Upper-case11").is_ok());
+    }
+
+    #[test]
+    fn range_lookup_uses_sha1_suffix() {
+        let (prefix, suffix) = sha1_prefix_suffix("password");
+        assert_eq!(prefix, "5BAA6");
+        assert_eq!(suffix, "1E4C9B93F3F0682250B6CF8331B7EE68FD8");
+
+        let body = format!(
+            "0018A45C4D1DEF81644B54AB7F969B88D65:1\r\n{}:3861493\r\n",
+            suffix
+        );
+        assert!(range_contains(&body, &suffix));
+        // Padding entries have a zero count
+        let padded = format!("{}:0\r\n", suffix);
+        assert!(!range_contains(&padded, &suffix));
+    }
+}
@@ -0,0 +1,375 @@
+//! Poll service.
+//!
+//! The `[poll id=N]` shortcode only leaves a placeholder in the rendered
+//! article, because the stored HTML would otherwise freeze the counts.
+//! [`PollService::render_embeds`] swaps placeholders for the form (or the
+//! results once the poll closed) each time an article is served; the SDK
+//! then asks `GET /api/v1/polls/{id}` for the reader's own view.
+
+use crate::db::repositories::PollRepository;
+use crate::models::{CreatePollInput, Poll, UpdatePollInput};
+use chrono::{DateTime, Utc};
+use once_cell::sync::Lazy;
+use regex::Regex;
+use std::collections::HashSet;
+use std::sync::Arc;
+
+const MAX_QUESTION_LEN: usize = 500;
+const MAX_OPTION_LEN: usize = 200;
+const MIN_OPTIONS: usize = 2;
+const MAX_OPTIONS: usize = 20;
+
+static PLACEHOLDER_RE: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r#"<div class="noteva-poll" data-noteva-poll="(\d+)"></div>"#).unwrap()
+});
+
+/// Errors returned by the poll service
+#[derive(Debug, thiserror::Error)]
+pub enum PollError {
+    #[error("Poll not found")]
+    NotFound,
+
+    #[error("This poll is closed")]
+    Closed,
+
+    #[error("{0}")]
+    Validation(String),
+
+    #[error("Internal error: {0}")]
+    Internal(#[from] anyhow::Error),
+}
+
+/// A poll as seen by one reader
+#[derive(Debug, Clone)]
+pub struct PollView {
+    pub poll: Poll,
+    /// Options the reader picked, empty until they vote
+    pub voted: Vec<i64>,
+}
+
+impl PollView {
+    /// Form or results, whichever the reader should see
+    pub fn html(&self) -> String {
+        render_poll(&self.poll, &self.voted, Utc::now())
+    }
+}
+
+/// Placeholder the `[poll]` shortcode leaves in rendered content
+pub fn placeholder(id: i64) -> String {
+    format!(
+        r#"<div class="noteva-poll" data-noteva-poll="{}"></div>"#,
+        id
+    )
+}
+
+pub struct PollService {
+    repo: Arc<dyn PollRepository>,
+}
+
+impl PollService {
+    pub fn new(repo: Arc<dyn PollRepository>) -> Self {
+        Self { repo }
+    }
+
+    pub async fn create(&self, input: CreatePollInput) -> Result<Poll, PollError> {
+        let input = CreatePollInput {
+            question: normalize_question(&input.question)?,
+            options: normalize_options(&input.options)?,
+            multiple: input.multiple,
+            closes_at: input.closes_at,
+        };
+        Ok(self.repo.create(&input).await?)
+    }
+
+    pub async fn get_by_id(&self, id: i64) -> Result<Poll, PollError> {
+        self.repo.get_by_id(id).await?.ok_or(PollError::NotFound)
+    }
+
+    pub async fn list(&self) -> Result<Vec<Poll>, PollError> {
+        Ok(self.repo.list().await?)
+    }
+
+    pub async fn update(&self, id: i64, input: UpdatePollInput) -> Result<Poll, PollError> {
+        let mut poll = self.get_by_id(id).await?;
+        if let Some(question) = input.question {
+            poll.question = normalize_question(&question)?;
+        }
+        if let Some(multiple) = input.multiple {
+            poll.multiple = multiple;
+        }
+        if let Some(closes_at) = input.closes_at {
+            poll.closes_at = closes_at;
+        }
+        Ok(self.repo.update(&poll).await?)
+    }
+
+    /// Delete a poll with its votes; false when it did not exist
+    pub async fn delete(&self, id: i64) -> Result<bool, PollError> {
+        Ok(self.repo.delete(id).await?)
+    }
+
+    /// The poll with the reader's choices
+    pub async fn view(&self, id: i64, voter_key: &str) -> Result<PollView, PollError> {
+        let poll = self.get_by_id(id).await?;
+        let voted = self.repo.voted_options(id, voter_key).await?;
+        Ok(PollView { poll, voted })
+    }
+
+    /// Vote on a poll
+    ///
+    /// A reader who already voted keeps their first choice and gets the
+    /// current results back.
+    pub async fn vote(
+        &self,
+        id: i64,
+        voter_key: &str,
+        option_ids: &[i64],
+    ) -> Result<PollView, PollError> {
+        let poll = self.get_by_id(id).await?;
+        if poll.is_closed(Utc::now()) {
+            return Err(PollError::Closed);
+        }
+        let choices = validate_choices(&poll, option_ids)?;
+        self.repo.vote(id, voter_key, &choices).await?;
+        self.view(id, voter_key).await
+    }
+
+    /// Replace poll placeholders in rendered HTML with each poll's form or
+    /// results; placeholders of deleted polls are dropped
+    pub async fn render_embeds(&self, html: &str) -> String {
+        let ids: HashSet<i64> = PLACEHOLDER_RE
+            .captures_iter(html)
+            .filter_map(|caps| caps[1].parse().ok())
+            .collect();
+        if ids.is_empty() {
+            return html.to_string();
+        }
+
+        let mut rendered = html.to_string();
+        let now = Utc::now();
+        for id in ids {
+            let replacement = match self.repo.get_by_id(id).await {
+                Ok(Some(poll)) => render_poll(&poll, &[], now),
+                Ok(None) => String::new(),
+                Err(e) => {
+                    tracing::warn!("Failed to load poll {}: {}", id, e);
+                    continue;
+                }
+            };
+            rendered = rendered.replace(&placeholder(id), &replacement);
+        }
+        rendered
+    }
+}
+
+fn normalize_question(question: &str) -> Result<String, PollError> {
+    let question = question.trim();
+    if question.is_empty() {
+        return Err(PollError::Validation(
+            "Question cannot be empty".to_string(),
+        ));
+    }
+    if question.chars().count() > MAX_QUESTION_LEN {
+        return Err(PollError::Validation(format!(
+            "Question cannot exceed {} characters",
+            MAX_QUESTION_LEN
+        )));
+    }
+    Ok(question.to_string())
+}
+
+fn normalize_options(options: &[String]) -> Result<Vec<String>, PollError> {
+    let options: Vec<String> = options
+        .iter()
+        .map(|option| option.trim().to_string())
+        .filter(|option| !option.is_empty())
+        .collect();
+    if !(MIN_OPTIONS..=MAX_OPTIONS).contains(&options.len()) {
+        return Err(PollError::Validation(format!(
+            "A poll needs between {} and {} options",
+            MIN_OPTIONS, MAX_OPTIONS
+        )));
+    }
+    if options.iter().any(|o| o.chars().count() > MAX_OPTION_LEN) {
+        return Err(PollError::Validation(format!(
+            "Options cannot exceed {} characters",
+            MAX_OPTION_LEN
+        )));
+    }
+    let unique: HashSet<&str> = options.iter().map(String::as_str).collect();
+    if unique.len() != options.len() {
+        return Err(PollError::Validation(
+            "Options must be different".to_string(),
+        ));
+    }
+    Ok(options)
+}
+
+/// Deduplicated choices, all belonging to the poll
+fn validate_choices(poll: &Poll, option_ids: &[i64]) -> Result<Vec<i64>, PollError> {
+    let mut choices: Vec<i64> = option_ids.to_vec();
+    choices.sort_unstable();
+    choices.dedup();
+    if choices.is_empty() {
+        return Err(PollError::Validation("Pick an option".to_string()));
+    }
+    if !poll.multiple && choices.len() > 1 {
+        return Err(PollError::Validation(
+            "This poll takes a single option".to_string(),
+        ));
+    }
+    if !choices
+        .iter()
+        .all(|id| poll.options.iter().any(|option| option.id == *id))
+    {
+        return Err(PollError::Validation(
+            "Unknown option for this poll".to_string(),
+        ));
+    }
+    Ok(choices)
+}
+
+/// The voting form while the poll is open and the reader has not voted,
+/// the results otherwise
+pub fn render_poll(poll: &Poll, voted: &[i64], now: DateTime<Utc>) -> String {
+    let closed = poll.is_closed(now);
+    let mut html = String::new();
+    if !closed && voted.is_empty() {
+        let input_type = if poll.multiple { "checkbox" } else { "radio" };
+        html.push_str(&format!(
+            r#"<form class="noteva-poll" data-noteva-poll="{}" data-multiple="{}"><p class="noteva-poll-question">{}</p>"#,
+            poll.id,
+            poll.multiple,
+            html_escape(&poll.question)
+        ));
+        for option in &poll.options {
+            html.push_str(&format!(
+                r#"<label class="noteva-poll-option"><input type="{}" name="poll-{}" value="{}"> {}</label>"#,
+                input_type,
+                poll.id,
+                option.id,
+                html_escape(&option.label)
+            ));
+        }
+        html.push_str(r#"<button type="submit" class="noteva-poll-submit">Vote</button></form>"#);
+        return html;
+    }
+
+    html.push_str(&format!(
+        r#"<div class="noteva-poll noteva-poll-results" data-noteva-poll="{}" data-closed="{}"><p class="noteva-poll-question">{}</p><ul>"#,
+        poll.id,
+        closed,
+        html_escape(&poll.question)
+    ));
+    for option in &poll.options {
+        let percent = poll.percent(option);
+        let chosen = if voted.contains(&option.id) {
+            " is-chosen"
+        } else {
+            ""
+        };
+        html.push_str(&format!(
+            r#"<li class="noteva-poll-result{}"><span class="noteva-poll-label">{}</span><progress max="100" value="{}"></progress><span class="noteva-poll-percent">{}%</span><span class="noteva-poll-votes">{}</span></li>"#,
+            chosen,
+            html_escape(&option.label),
+            percent,
+            percent,
+            option.votes
+        ));
+    }
+    html.push_str(&format!(
+        r#"</ul><p class="noteva-poll-total" data-voters="{}">{} voters</p></div>"#,
+        poll.total_voters, poll.total_voters
+    ));
+    html
+}
+
+fn html_escape(s: &str) -> String {
+    s.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+        .replace('\'', "&#39;")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::PollOption;
+
+    fn poll(multiple: bool, closes_at: Option<DateTime<Utc>>) -> Poll {
+        let now = Utc::now();
+        Poll {
+            id: 7,
+            question: "Best <editor>?".to_string(),
+            multiple,
+            closes_at,
+            options: vec![
+                PollOption {
+                    id: 1,
+                    label: "Vim".to_string(),
+                    votes: 3,
+                },
+                PollOption {
+                    id: 2,
+                    label: "Emacs".to_string(),
+                    votes: 1,
+                },
+            ],
+            total_voters: 4,
+            created_at: now,
+            updated_at: now,
+        }
+    }
+
+    #[test]
+    fn options_are_trimmed_and_checked() {
+        let options = vec![" Yes ".to_string(), "".to_string(), "No".to_string()];
+        assert_eq!(normalize_options(&options).unwrap(), ["Yes", "No"]);
+        assert!(normalize_options(&["Only".to_string()]).is_err());
+        assert!(normalize_options(&["Same".to_string(), " Same".to_string()]).is_err());
+        assert!(normalize_question("  ").is_err());
+    }
+
+    #[test]
+    fn choices_must_fit_the_poll() {
+        let single = poll(false, None);
+        assert_eq!(validate_choices(&single, &[2, 2]).unwrap(), [2]);
+        assert!(validate_choices(&single, &[1, 2]).is_err());
+        assert!(validate_choices(&single, &[9]).is_err());
+        assert!(validate_choices(&single, &[]).is_err());
+        assert_eq!(
+            validate_choices(&poll(true, None), &[2, 1]).unwrap(),
+            [1, 2]
+        );
+    }
+
+    #[test]
+    fn form_until_voted_or_closed() {
+        let now = Utc::now();
+        let open = poll(false, None);
+        let form = render_poll(&open, &[], now);
+        assert!(form.starts_with("<form"));
+        assert!(form.contains(r#"type="radio" name="poll-7" value="2""#));
+        assert!(form.contains("Best &lt;editor&gt;?"));
+
+        let results = render_poll(&open, &[1], now);
+        assert!(results.contains(r#"<li class="noteva-poll-result is-chosen"><span class="noteva-poll-label">Vim</span><progress max="100" value="75">"#));
+        assert!(results.contains("4 voters"));
+
+        let closed = poll(true, Some(now - chrono::Duration::hours(1)));
+        let results = render_poll(&closed, &[], now);
+        assert!(results.contains(r#"data-closed="true""#));
+        assert!(!results.contains("<form"));
+    }
+
+    #[test]
+    fn placeholders_are_found() {
+        let html = format!("<p>Intro</p>{}<p>Outro</p>", placeholder(12));
+        let ids: Vec<&str> = PLACEHOLDER_RE
+            .captures_iter(&html)
+            .map(|caps| caps.get(1).unwrap().as_str())
+            .collect();
+        assert_eq!(ids, ["12"]);
+    }
+}
@@ -0,0 +1,232 @@
+//! Publish checklist
+//!
+//! The `publish_checklist` setting lists what an article needs before it
+//! goes live: a thumbnail, a category other than the default one, an
+//! excerpt, and a search engine title and description. Publishing an
+//! article that misses items is refused in `block` mode and goes through
+//! with the missing items reported in `warn` mode.
+
+use serde::{Deserialize, Serialize};
+
+use crate::models::Article;
+use crate::services::settings::SettingsService;
+
+/// Setting holding the JSON configuration
+pub const PUBLISH_CHECKLIST_KEY: &str = "publish_checklist";
+
+/// Errors returned when saving the configuration
+#[derive(Debug, thiserror::Error)]
+pub enum PublishChecklistError {
+    /// Invalid configuration
+    #[error("{0}")]
+    Validation(String),
+
+    /// Internal error
+    #[error("Internal error: {0}")]
+    Internal(String),
+}
+
+/// Something an article must have to be published
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ChecklistItem {
+    /// A thumbnail image
+    Thumbnail,
+    /// A category other than the default one
+    Category,
+    /// An excerpt written by the author, to show in lists
+    Excerpt,
+    /// Both the search engine title and description
+    Seo,
+}
+
+impl ChecklistItem {
+    pub const ALL: [ChecklistItem; 4] = [
+        ChecklistItem::Thumbnail,
+        ChecklistItem::Category,
+        ChecklistItem::Excerpt,
+        ChecklistItem::Seo,
+    ];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ChecklistItem::Thumbnail => "thumbnail",
+            ChecklistItem::Category => "category",
+            ChecklistItem::Excerpt => "excerpt",
+            ChecklistItem::Seo => "seo",
+        }
+    }
+
+    /// Whether `article` satisfies the item
+    fn is_met(&self, article: &Article, default_category_id: Option<i64>) -> bool {
+        let filled = |value: Option<&str>| value.is_some_and(|v| !v.trim().is_empty());
+        match self {
+            ChecklistItem::Thumbnail => filled(article.thumbnail.as_deref()),
+            ChecklistItem::Category => default_category_id != Some(article.category_id),
+            ChecklistItem::Excerpt => article.written_excerpt().is_some(),
+            ChecklistItem::Seo => {
+                filled(article.meta_title.as_deref()) && filled(article.meta_description.as_deref())
+            }
+        }
+    }
+}
+
+/// What happens when an article misses checklist items
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ChecklistMode {
+    /// The checklist is not checked
+    #[default]
+    Off,
+    /// Publish anyway and report the missing items
+    Warn,
+    /// Refuse to publish
+    Block,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct PublishChecklist {
+    pub mode: ChecklistMode,
+    pub items: Vec<ChecklistItem>,
+}
+
+impl Default for PublishChecklist {
+    fn default() -> Self {
+        Self {
+            mode: ChecklistMode::Off,
+            items: ChecklistItem::ALL.to_vec(),
+        }
+    }
+}
+
+impl PublishChecklist {
+    /// Drop repeated items, keeping the first
+    pub fn validate(mut self) -> Result<Self, PublishChecklistError> {
+        let mut items: Vec<ChecklistItem> = Vec::new();
+        for item in self.items {
+            if !items.contains(&item) {
+                items.push(item);
+            }
+        }
+        if self.mode != ChecklistMode::Off && items.is_empty() {
+            return Err(PublishChecklistError::Validation(
+                "Choose at least one checklist item or turn the checklist off".to_string(),
+            ));
+        }
+        self.items = items;
+        Ok(self)
+    }
+
+    /// Items `article` misses; empty when the checklist is off
+    pub fn unmet(&self, article: &Article, default_category_id: Option<i64>) -> Vec<ChecklistItem> {
+        if self.mode == ChecklistMode::Off {
+            return Vec::new();
+        }
+        self.items
+            .iter()
+            .copied()
+            .filter(|item| !item.is_met(article, default_category_id))
+            .collect()
+    }
+}
+
+/// The stored configuration, or the default when unset or unreadable
+pub async fn load(settings: &SettingsService) -> PublishChecklist {
+    match settings.get(PUBLISH_CHECKLIST_KEY).await {
+        Ok(Some(json)) if !json.trim().is_empty() => {
+            // Also written by the generic settings endpoint, so check it again
+            serde_json::from_str::<PublishChecklist>(&json)
+                .map_err(|e| e.to_string())
+                .and_then(|config| config.validate().map_err(|e| e.to_string()))
+                .unwrap_or_else(|e| {
+                    tracing::warn!("Ignoring invalid {}: {}", PUBLISH_CHECKLIST_KEY, e);
+                    PublishChecklist::default()
+                })
+        }
+        Ok(_) => PublishChecklist::default(),
+        Err(e) => {
+            tracing::warn!("Failed to read {}: {}", PUBLISH_CHECKLIST_KEY, e);
+            PublishChecklist::default()
+        }
+    }
+}
+
+/// Validate and store a new configuration
+pub async fn save(
+    settings: &SettingsService,
+    config: PublishChecklist,
+) -> Result<PublishChecklist, PublishChecklistError> {
+    let config = config.validate()?;
+    let json = serde_json::to_string(&config)
+        .map_err(|e| PublishChecklistError::Internal(e.to_string()))?;
+    settings
+        .set_setting(PUBLISH_CHECKLIST_KEY, &json)
+        .await
+        .map_err(|e| PublishChecklistError::Internal(e.to_string()))?;
+    Ok(config)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::ArticleStatus;
+
+    fn article() -> Article {
+        Article::new(
+            "post".to_string(),
+            "Post".to_string(),
+            String::new(),
+            String::new(),
+            1,
+            1,
+            ArticleStatus::Draft,
+        )
+    }
+
+    #[test]
+    fn reports_missing_items_in_configured_order() {
+        let checklist = PublishChecklist {
+            mode: ChecklistMode::Block,
+            ..Default::default()
+        };
+        let mut article = article();
+        assert_eq!(checklist.unmet(&article, Some(1)), ChecklistItem::ALL);
+
+        article.thumbnail = Some("/uploads/cover.png".to_string());
+        article.category_id = 2;
+        article.meta = serde_json::json!({"summary": "  "});
+        article.meta_title = Some("Post".to_string());
+        assert_eq!(
+            checklist.unmet(&article, Some(1)),
+            [ChecklistItem::Excerpt, ChecklistItem::Seo]
+        );
+
+        article.meta = serde_json::json!({"summary": "About the post"});
+        article.meta_description = Some("About the post".to_string());
+        assert!(checklist.unmet(&article, Some(1)).is_empty());
+    }
+
+    #[test]
+    fn off_checks_nothing() {
+        assert!(PublishChecklist::default()
+            .unmet(&article(), Some(1))
+            .is_empty());
+    }
+
+    #[test]
+    fn parses_and_dedupes_items() {
+        let config: PublishChecklist =
+            serde_json::from_str(r#"{"mode": "warn", "items": ["seo", "thumbnail", "seo"]}"#)
+                .unwrap();
+        let config = config.validate().unwrap();
+        assert_eq!(config.items, [ChecklistItem::Seo, ChecklistItem::Thumbnail]);
+        assert!(serde_json::from_str::<PublishChecklist>(r#"{"items": ["tags"]}"#).is_err());
+        assert!(PublishChecklist {
+            mode: ChecklistMode::Block,
+            items: Vec::new(),
+        }
+        .validate()
+        .is_err());
+    }
+}
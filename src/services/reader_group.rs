@@ -0,0 +1,291 @@
+//! Reader group service.
+//!
+//! Manages reader groups and the audiences that limit an article to some
+//! roles or groups. Listings filter restricted articles in SQL (see
+//! `readable_audience_sql`); single-article lookups ask [`can_read`].
+//!
+//! [`can_read`]: ReaderGroupService::can_read
+
+use crate::db::repositories::ReaderGroupRepository;
+use crate::models::{
+    ArticleAudience, ArticleReader, PublicUser, ReaderGroup, ReaderGroupInput,
+    ReaderGroupMembersInput,
+};
+use crate::services::category::generate_slug;
+use crate::services::settings::SettingsService;
+use chrono::Utc;
+use std::sync::Arc;
+
+/// Setting choosing the status readers get for an article they may not
+/// read: `404` (default) hides that it exists, `403` admits it does
+pub const RESTRICTED_ARTICLE_STATUS_KEY: &str = "restricted_article_status";
+
+const MAX_NAME_LEN: usize = 100;
+const MAX_SLUG_LEN: usize = 100;
+const MAX_DESCRIPTION_LEN: usize = 2000;
+const MAX_AUDIENCE_GROUPS: usize = 50;
+
+/// Errors returned by the reader group service
+#[derive(Debug, thiserror::Error)]
+pub enum ReaderGroupError {
+    #[error("{0} not found")]
+    NotFound(&'static str),
+
+    #[error("{0}")]
+    Validation(String),
+
+    #[error("Internal error: {0}")]
+    Internal(#[from] anyhow::Error),
+}
+
+/// Whether articles a reader may not read answer 403 rather than 404
+pub async fn forbid_restricted(settings: &SettingsService) -> bool {
+    match settings.get(RESTRICTED_ARTICLE_STATUS_KEY).await {
+        Ok(value) => value.as_deref().map(str::trim) == Some("403"),
+        Err(e) => {
+            tracing::warn!("Failed to read {}: {}", RESTRICTED_ARTICLE_STATUS_KEY, e);
+            false
+        }
+    }
+}
+
+pub struct ReaderGroupService {
+    repo: Arc<dyn ReaderGroupRepository>,
+}
+
+impl ReaderGroupService {
+    pub fn new(repo: Arc<dyn ReaderGroupRepository>) -> Self {
+        Self { repo }
+    }
+
+    pub async fn list_groups(&self) -> Result<Vec<ReaderGroup>, ReaderGroupError> {
+        Ok(self.repo.list_groups().await?)
+    }
+
+    pub async fn get_group(&self, id: i64) -> Result<ReaderGroup, ReaderGroupError> {
+        self.repo
+            .get_group(id)
+            .await?
+            .ok_or(ReaderGroupError::NotFound("Reader group"))
+    }
+
+    pub async fn create_group(
+        &self,
+        input: ReaderGroupInput,
+    ) -> Result<ReaderGroup, ReaderGroupError> {
+        let group = group_from_input(0, input)?;
+        self.ensure_slug_free(&group.slug, None).await?;
+        Ok(self.repo.create_group(&group).await?)
+    }
+
+    /// Replace a group's name, slug and description
+    pub async fn update_group(
+        &self,
+        id: i64,
+        input: ReaderGroupInput,
+    ) -> Result<ReaderGroup, ReaderGroupError> {
+        let existing = self.get_group(id).await?;
+        let group = group_from_input(id, input)?;
+        self.ensure_slug_free(&group.slug, Some(id)).await?;
+        let group = ReaderGroup {
+            member_count: existing.member_count,
+            created_at: existing.created_at,
+            ..group
+        };
+        self.repo.update_group(&group).await?;
+        Ok(group)
+    }
+
+    /// Delete a group; articles shown only to it are left to admins
+    pub async fn delete_group(&self, id: i64) -> Result<(), ReaderGroupError> {
+        if !self.repo.delete_group(id).await? {
+            return Err(ReaderGroupError::NotFound("Reader group"));
+        }
+        Ok(())
+    }
+
+    pub async fn list_members(&self, group_id: i64) -> Result<Vec<PublicUser>, ReaderGroupError> {
+        self.get_group(group_id).await?;
+        Ok(self.repo.list_members(group_id).await?)
+    }
+
+    /// Replace the members of a group
+    pub async fn set_members(
+        &self,
+        group_id: i64,
+        input: ReaderGroupMembersInput,
+    ) -> Result<Vec<PublicUser>, ReaderGroupError> {
+        self.get_group(group_id).await?;
+        let mut user_ids = input.user_ids;
+        dedup(&mut user_ids);
+        let existing = self.repo.existing_user_ids(&user_ids).await?;
+        if let Some(missing) = user_ids.iter().find(|id| !existing.contains(id)) {
+            return Err(ReaderGroupError::Validation(format!(
+                "User not found: {}",
+                missing
+            )));
+        }
+        self.repo.set_members(group_id, &user_ids).await?;
+        Ok(self.repo.list_members(group_id).await?)
+    }
+
+    /// Who may read an article; empty when it is open to everyone
+    pub async fn audience(&self, article_id: i64) -> Result<ArticleAudience, ReaderGroupError> {
+        Ok(self.repo.get_audience(article_id).await?)
+    }
+
+    /// Replace the audience of an article; an empty one opens it to all
+    pub async fn set_audience(
+        &self,
+        article_id: i64,
+        mut audience: ArticleAudience,
+    ) -> Result<ArticleAudience, ReaderGroupError> {
+        dedup(&mut audience.roles);
+        dedup(&mut audience.group_ids);
+        if audience.group_ids.len() > MAX_AUDIENCE_GROUPS {
+            return Err(ReaderGroupError::Validation(format!(
+                "An article can be limited to at most {} groups",
+                MAX_AUDIENCE_GROUPS
+            )));
+        }
+        let existing = self.repo.existing_group_ids(&audience.group_ids).await?;
+        if let Some(missing) = audience.group_ids.iter().find(|id| !existing.contains(id)) {
+            return Err(ReaderGroupError::Validation(format!(
+                "Reader group not found: {}",
+                missing
+            )));
+        }
+        self.repo.set_audience(article_id, &audience).await?;
+        Ok(audience)
+    }
+
+    /// Whether `reader` may read the article; `None` is an anonymous
+    /// visitor
+    pub async fn can_read(
+        &self,
+        article_id: i64,
+        reader: Option<&ArticleReader>,
+    ) -> Result<bool, ReaderGroupError> {
+        let audience = self.repo.get_audience(article_id).await?;
+        if !audience.is_restricted() {
+            return Ok(true);
+        }
+        let groups = match reader {
+            Some(reader) if !audience.group_ids.is_empty() => {
+                self.repo.group_ids_of_user(reader.user_id).await?
+            }
+            _ => Vec::new(),
+        };
+        Ok(audience.admits(reader, &groups))
+    }
+
+    /// Drop the audience of a deleted article
+    pub async fn remove_article(&self, article_id: i64) -> Result<(), ReaderGroupError> {
+        Ok(self.repo.remove_article(article_id).await?)
+    }
+
+    async fn ensure_slug_free(&self, slug: &str, id: Option<i64>) -> Result<(), ReaderGroupError> {
+        match self.repo.get_group_by_slug(slug).await? {
+            Some(other) if Some(other.id) != id => Err(ReaderGroupError::Validation(format!(
+                "Reader group '{}' already exists",
+                slug
+            ))),
+            _ => Ok(()),
+        }
+    }
+}
+
+/// Remove repeated values, keeping the first of each
+fn dedup<T: PartialEq + Copy>(values: &mut Vec<T>) {
+    let mut seen = Vec::with_capacity(values.len());
+    values.retain(|value| {
+        let first = !seen.contains(value);
+        seen.push(*value);
+        first
+    });
+}
+
+fn group_from_input(id: i64, input: ReaderGroupInput) -> Result<ReaderGroup, ReaderGroupError> {
+    let name = input.name.trim().to_string();
+    if name.is_empty() {
+        return Err(ReaderGroupError::Validation(
+            "Name cannot be empty".to_string(),
+        ));
+    }
+    if name.chars().count() > MAX_NAME_LEN {
+        return Err(ReaderGroupError::Validation(format!(
+            "Name cannot exceed {} characters",
+            MAX_NAME_LEN
+        )));
+    }
+    let slug = generate_slug(input.slug.as_deref().unwrap_or(&name));
+    if slug.is_empty() {
+        return Err(ReaderGroupError::Validation(
+            "Slug cannot be empty".to_string(),
+        ));
+    }
+    if slug.chars().count() > MAX_SLUG_LEN {
+        return Err(ReaderGroupError::Validation(format!(
+            "Slug cannot exceed {} characters",
+            MAX_SLUG_LEN
+        )));
+    }
+    let description = input
+        .description
+        .map(|d| d.trim().to_string())
+        .filter(|d| !d.is_empty());
+    if description
+        .as_ref()
+        .is_some_and(|d| d.chars().count() > MAX_DESCRIPTION_LEN)
+    {
+        return Err(ReaderGroupError::Validation(format!(
+            "Description cannot exceed {} characters",
+            MAX_DESCRIPTION_LEN
+        )));
+    }
+    Ok(ReaderGroup {
+        id,
+        name,
+        slug,
+        description,
+        member_count: 0,
+        created_at: Utc::now(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn input(name: &str, slug: Option<&str>) -> ReaderGroupInput {
+        ReaderGroupInput {
+            name: name.to_string(),
+            slug: slug.map(str::to_string),
+            description: Some("  ".to_string()),
+        }
+    }
+
+    #[test]
+    fn group_slug_defaults_to_the_name() {
+        let group = group_from_input(0, input(" Beta Readers ", None)).unwrap();
+        assert_eq!(group.name, "Beta Readers");
+        assert_eq!(group.slug, "beta-readers");
+        assert_eq!(group.description, None);
+
+        let group = group_from_input(0, input("Staff", Some("Team"))).unwrap();
+        assert_eq!(group.slug, "team");
+    }
+
+    #[test]
+    fn rejects_empty_names_and_slugs() {
+        assert!(group_from_input(0, input("  ", None)).is_err());
+        assert!(group_from_input(0, input("Staff", Some("--"))).is_err());
+    }
+
+    #[test]
+    fn dedup_keeps_first_occurrences_in_order() {
+        let mut ids = vec![3, 1, 3, 2, 1];
+        dedup(&mut ids);
+        assert_eq!(ids, [3, 1, 2]);
+    }
+}
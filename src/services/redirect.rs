@@ -0,0 +1,221 @@
+//! Redirect service.
+//!
+//! Redirects are looked up on every request that falls through to the
+//! theme, so the whole table is kept in the cache and matched in memory.
+
+use crate::cache::{Cache, CacheLayer};
+use crate::db::repositories::RedirectRepository;
+use crate::models::{
+    normalize_redirect_path, CreateRedirectInput, Redirect, UpdateRedirectInput,
+    DEFAULT_REDIRECT_STATUS, REDIRECT_STATUS_CODES,
+};
+use anyhow::{Context, Result};
+use std::sync::Arc;
+use std::time::Duration;
+
+const REDIRECT_CACHE_TTL_SECS: u64 = 3600;
+const CACHE_KEY_REDIRECT_LIST: &str = "redirects:list";
+const MAX_SOURCE_LEN: usize = 500;
+const MAX_TARGET_LEN: usize = 1000;
+
+/// Paths served before the fallback, so a redirect on them would never fire
+const RESERVED_PREFIXES: [&str; 4] = ["/api/", "/manage", "/uploads/", "/themes/"];
+
+pub struct RedirectService {
+    repo: Arc<dyn RedirectRepository>,
+    cache: Arc<Cache>,
+    cache_ttl: Duration,
+}
+
+impl RedirectService {
+    pub fn new(repo: Arc<dyn RedirectRepository>, cache: Arc<Cache>) -> Self {
+        Self {
+            repo,
+            cache,
+            cache_ttl: Duration::from_secs(REDIRECT_CACHE_TTL_SECS),
+        }
+    }
+
+    pub async fn create(&self, input: CreateRedirectInput) -> Result<Redirect> {
+        let mut redirect = Redirect::new(
+            normalize_source(&input.source)?,
+            normalize_target(&input.target)?,
+        );
+        redirect.status_code = parse_status_code(input.status_code)?;
+        validate_not_loop(&redirect)?;
+        self.ensure_unique(&redirect.source, None).await?;
+
+        let created = self
+            .repo
+            .create(&redirect)
+            .await
+            .context("Failed to create redirect")?;
+        self.invalidate_cache().await;
+        Ok(created)
+    }
+
+    pub async fn get_by_id(&self, id: i64) -> Result<Option<Redirect>> {
+        self.repo.get_by_id(id).await
+    }
+
+    pub async fn list(&self) -> Result<Vec<Redirect>> {
+        if let Ok(Some(redirects)) = self
+            .cache
+            .get::<Vec<Redirect>>(CACHE_KEY_REDIRECT_LIST)
+            .await
+        {
+            return Ok(redirects);
+        }
+
+        let redirects = self.repo.list().await?;
+        let _ = self
+            .cache
+            .set(CACHE_KEY_REDIRECT_LIST, &redirects, self.cache_ttl)
+            .await;
+        Ok(redirects)
+    }
+
+    /// The redirect for a request path, ignoring a trailing slash
+    pub async fn find(&self, path: &str) -> Result<Option<Redirect>> {
+        let path = normalize_redirect_path(path);
+        Ok(self.list().await?.into_iter().find(|r| r.source == path))
+    }
+
+    pub async fn update(&self, id: i64, input: UpdateRedirectInput) -> Result<Redirect> {
+        let mut redirect = self
+            .repo
+            .get_by_id(id)
+            .await?
+            .ok_or_else(|| anyhow::anyhow!("Redirect not found"))?;
+
+        if let Some(source) = input.source {
+            redirect.source = normalize_source(&source)?;
+        }
+        if let Some(target) = input.target {
+            redirect.target = normalize_target(&target)?;
+        }
+        if let Some(status_code) = input.status_code {
+            redirect.status_code = parse_status_code(Some(status_code))?;
+        }
+        validate_not_loop(&redirect)?;
+        self.ensure_unique(&redirect.source, Some(id)).await?;
+
+        let updated = self.repo.update(&redirect).await?;
+        self.invalidate_cache().await;
+        Ok(updated)
+    }
+
+    /// Delete a redirect; false when it did not exist
+    pub async fn delete(&self, id: i64) -> Result<bool> {
+        let deleted = self.repo.delete(id).await?;
+        self.invalidate_cache().await;
+        Ok(deleted)
+    }
+
+    async fn ensure_unique(&self, source: &str, except: Option<i64>) -> Result<()> {
+        let taken = self
+            .repo
+            .list()
+            .await?
+            .iter()
+            .any(|r| r.source == source && Some(r.id) != except);
+        if taken {
+            anyhow::bail!("A redirect for {} already exists", source);
+        }
+        Ok(())
+    }
+
+    async fn invalidate_cache(&self) {
+        let _ = self.cache.delete(CACHE_KEY_REDIRECT_LIST).await;
+    }
+}
+
+fn normalize_source(value: &str) -> Result<String> {
+    let value = value.trim();
+    if !value.starts_with('/') || value.starts_with("//") {
+        anyhow::bail!("Source must be a path starting with /");
+    }
+    if value.contains(['?', '#']) || value.chars().any(char::is_whitespace) {
+        anyhow::bail!("Source must be a plain path without query string or spaces");
+    }
+    if value.chars().count() > MAX_SOURCE_LEN {
+        anyhow::bail!("Source cannot exceed {} characters", MAX_SOURCE_LEN);
+    }
+    let path = normalize_redirect_path(value);
+    if let Some(prefix) = RESERVED_PREFIXES
+        .iter()
+        .find(|prefix| path.starts_with(*prefix) || path == prefix.trim_end_matches('/'))
+    {
+        anyhow::bail!("Paths under {} cannot be redirected", prefix);
+    }
+    Ok(path.to_string())
+}
+
+fn normalize_target(value: &str) -> Result<String> {
+    let value = value.trim();
+    if value.is_empty() {
+        anyhow::bail!("Target cannot be empty");
+    }
+    if value.chars().count() > MAX_TARGET_LEN {
+        anyhow::bail!("Target cannot exceed {} characters", MAX_TARGET_LEN);
+    }
+    if value.starts_with('/') && !value.starts_with("//") {
+        return Ok(value.to_string());
+    }
+    let parsed = reqwest::Url::parse(value).context("Target must be a path or a valid URL")?;
+    if !matches!(parsed.scheme(), "http" | "https") || parsed.host_str().is_none() {
+        anyhow::bail!("Target must be a path or start with http:// or https://");
+    }
+    Ok(value.to_string())
+}
+
+fn parse_status_code(value: Option<u16>) -> Result<u16> {
+    let code = value.unwrap_or(DEFAULT_REDIRECT_STATUS);
+    if !REDIRECT_STATUS_CODES.contains(&code) {
+        anyhow::bail!("Status code must be one of 301, 302, 307 or 308");
+    }
+    Ok(code)
+}
+
+fn validate_not_loop(redirect: &Redirect) -> Result<()> {
+    let target_path = redirect.target.split(['?', '#']).next().unwrap_or_default();
+    if redirect.target.starts_with('/') && normalize_redirect_path(target_path) == redirect.source {
+        anyhow::bail!("A redirect cannot point to itself");
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sources_are_normalized_and_reserved_paths_rejected() {
+        assert_eq!(normalize_source(" /old-post/ ").unwrap(), "/old-post");
+        assert_eq!(normalize_source("/").unwrap(), "/");
+        assert!(normalize_source("old-post").is_err());
+        assert!(normalize_source("//evil.example").is_err());
+        assert!(normalize_source("/search?q=1").is_err());
+        assert!(normalize_source("/api/v1/articles").is_err());
+        assert!(normalize_source("/manage").is_err());
+        assert!(normalize_source("/uploads/a.png").is_err());
+    }
+
+    #[test]
+    fn targets_are_paths_or_http_urls() {
+        assert!(normalize_target("/new-post?ref=old").is_ok());
+        assert!(normalize_target("https://example.com/post").is_ok());
+        assert!(normalize_target("//example.com").is_err());
+        assert!(normalize_target("javascript:alert(1)").is_err());
+        assert!(parse_status_code(Some(302)).is_ok());
+        assert!(parse_status_code(Some(200)).is_err());
+    }
+
+    #[test]
+    fn self_redirects_are_rejected() {
+        let redirect = Redirect::new("/loop".to_string(), "/loop/?x=1".to_string());
+        assert!(validate_not_loop(&redirect).is_err());
+        let redirect = Redirect::new("/loop".to_string(), "https://example.com/loop".to_string());
+        assert!(validate_not_loop(&redirect).is_ok());
+    }
+}
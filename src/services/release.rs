@@ -0,0 +1,319 @@
+//! Release notes
+//!
+//! Sites used as a product blog tag their release announcements with the
+//! releases tag (the `releases_tag` setting, `release` by default). Tagged
+//! articles whose title or slug carries a semantic version such as
+//! `v1.4.0` or `2.0.0-beta.1` are listed by `GET /api/v1/releases`, grouped
+//! by minor series, and published at `/releases.json` for update checkers.
+
+use std::cmp::Ordering;
+use std::fmt;
+
+use anyhow::Result;
+use chrono::{DateTime, Utc};
+use once_cell::sync::Lazy;
+use regex::Regex;
+use serde::{Serialize, Serializer};
+
+use crate::db::repositories::{
+    ArticleRepository, SqlxArticleRepository, SqlxTagRepository, TagRepository,
+};
+use crate::db::DynDatabasePool;
+use crate::models::ArticleSortBy;
+use crate::services::settings::{keys, SettingsService};
+
+/// Slug of the tag that marks release notes
+pub const RELEASES_TAG_KEY: &str = "releases_tag";
+pub const DEFAULT_RELEASES_TAG: &str = "release";
+
+static VERSION_RE: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"(?i)\bv?(\d+)\.(\d+)(?:\.(\d+))?(?:-([0-9a-z]+(?:\.[0-9a-z]+)*))?\b")
+        .expect("valid version regex")
+});
+
+/// A semantic version; a missing patch number reads as 0
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Version {
+    pub major: u64,
+    pub minor: u64,
+    pub patch: u64,
+    /// Pre-release identifiers, e.g. `["beta", "1"]`
+    pub pre: Vec<String>,
+}
+
+impl Version {
+    /// The first version number in `text`, e.g. "Noteva v1.2 released"
+    pub fn find(text: &str) -> Option<Self> {
+        let caps = VERSION_RE.captures(text)?;
+        let number = |i: usize| caps.get(i).map_or(Some(0), |m| m.as_str().parse().ok());
+        Some(Self {
+            major: number(1)?,
+            minor: number(2)?,
+            patch: number(3)?,
+            pre: caps
+                .get(4)
+                .map(|m| m.as_str().split('.').map(str::to_string).collect())
+                .unwrap_or_default(),
+        })
+    }
+
+    pub fn is_prerelease(&self) -> bool {
+        !self.pre.is_empty()
+    }
+
+    /// Minor series the version belongs to, e.g. "1.2"
+    pub fn series(&self) -> String {
+        format!("{}.{}", self.major, self.minor)
+    }
+}
+
+impl fmt::Display for Version {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}.{}.{}", self.major, self.minor, self.patch)?;
+        if self.is_prerelease() {
+            write!(f, "-{}", self.pre.join("."))?;
+        }
+        Ok(())
+    }
+}
+
+impl Ord for Version {
+    /// Semantic version precedence: a pre-release sorts before its release,
+    /// numeric identifiers compare as numbers and before alphanumeric ones
+    fn cmp(&self, other: &Self) -> Ordering {
+        (self.major, self.minor, self.patch)
+            .cmp(&(other.major, other.minor, other.patch))
+            .then_with(|| match (self.pre.is_empty(), other.pre.is_empty()) {
+                (true, true) => Ordering::Equal,
+                (true, false) => Ordering::Greater,
+                (false, true) => Ordering::Less,
+                (false, false) => {
+                    for (a, b) in self.pre.iter().zip(&other.pre) {
+                        let order = match (a.parse::<u64>(), b.parse::<u64>()) {
+                            (Ok(a), Ok(b)) => a.cmp(&b),
+                            (Ok(_), Err(_)) => Ordering::Less,
+                            (Err(_), Ok(_)) => Ordering::Greater,
+                            (Err(_), Err(_)) => a.cmp(b),
+                        };
+                        if order != Ordering::Equal {
+                            return order;
+                        }
+                    }
+                    self.pre.len().cmp(&other.pre.len())
+                }
+            })
+    }
+}
+
+impl PartialOrd for Version {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Serialize for Version {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+/// A release note article
+#[derive(Debug, Clone, Serialize)]
+pub struct Release {
+    pub version: Version,
+    pub prerelease: bool,
+    pub article_id: i64,
+    pub title: String,
+    pub slug: String,
+    /// Absolute when `site_url` is set, otherwise a site path
+    pub url: String,
+    pub published_at: DateTime<Utc>,
+}
+
+/// Releases of one minor series, newest first
+#[derive(Debug, Clone, Serialize)]
+pub struct ReleaseGroup {
+    /// e.g. "1.2"
+    pub series: String,
+    pub latest: Version,
+    pub releases: Vec<Release>,
+}
+
+/// Published release notes, newest version first
+pub async fn list_releases(
+    pool: &DynDatabasePool,
+    settings: &SettingsService,
+    site_url: &str,
+) -> Result<Vec<Release>> {
+    let tag_slug = settings
+        .get(RELEASES_TAG_KEY)
+        .await?
+        .filter(|slug| !slug.trim().is_empty())
+        .unwrap_or_else(|| DEFAULT_RELEASES_TAG.to_string());
+    let Some(tag) = SqlxTagRepository::new(pool.clone())
+        .get_by_slug(tag_slug.trim())
+        .await?
+    else {
+        return Ok(Vec::new());
+    };
+    let by_id = settings
+        .get(keys::PERMALINK_STRUCTURE)
+        .await?
+        .is_some_and(|structure| structure.contains("{id}"));
+
+    let repo = SqlxArticleRepository::new(pool.clone());
+    let total = repo.count_published_by_tag(tag.id).await?;
+    let articles = repo
+        .list_published_by_tag(tag.id, 0, total.max(1), ArticleSortBy::default())
+        .await?;
+
+    let base = site_url.trim_end_matches('/');
+    let mut releases: Vec<Release> = articles
+        .into_iter()
+        .filter_map(|article| {
+            let version = Version::find(&article.title).or_else(|| Version::find(&article.slug))?;
+            let identifier = if by_id {
+                article.id.to_string()
+            } else {
+                article.slug.clone()
+            };
+            Some(Release {
+                prerelease: version.is_prerelease(),
+                version,
+                article_id: article.id,
+                url: format!("{}/posts/{}", base, identifier),
+                title: article.title,
+                slug: article.slug,
+                published_at: article.published_at.unwrap_or(article.created_at),
+            })
+        })
+        .collect();
+    sort_releases(&mut releases);
+    Ok(releases)
+}
+
+/// Newest version first; equal versions by newest article
+pub fn sort_releases(releases: &mut [Release]) {
+    releases.sort_by(|a, b| {
+        b.version
+            .cmp(&a.version)
+            .then(b.published_at.cmp(&a.published_at))
+    });
+}
+
+/// Group sorted releases by minor series
+pub fn group_releases(releases: Vec<Release>) -> Vec<ReleaseGroup> {
+    let mut groups: Vec<ReleaseGroup> = Vec::new();
+    for release in releases {
+        match groups.last_mut() {
+            Some(group) if group.series == release.version.series() => group.releases.push(release),
+            _ => groups.push(ReleaseGroup {
+                series: release.version.series(),
+                latest: release.version.clone(),
+                releases: vec![release],
+            }),
+        }
+    }
+    groups
+}
+
+/// Body of `/releases.json`
+pub fn releases_document(
+    site_name: &str,
+    site_url: &str,
+    releases: &[Release],
+) -> serde_json::Value {
+    let latest = releases
+        .iter()
+        .find(|r| !r.prerelease)
+        .map(|r| r.version.to_string());
+    serde_json::json!({
+        "name": site_name,
+        "url": site_url.trim_end_matches('/'),
+        "latest": latest,
+        "releases": releases,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn v(text: &str) -> Version {
+        Version::find(text).unwrap()
+    }
+
+    fn release(title: &str, day: i64) -> Release {
+        let version = v(title);
+        Release {
+            prerelease: version.is_prerelease(),
+            version,
+            article_id: day,
+            title: title.to_string(),
+            slug: String::new(),
+            url: String::new(),
+            published_at: DateTime::from_timestamp(day * 86400, 0).unwrap(),
+        }
+    }
+
+    #[test]
+    fn finds_versions_in_titles() {
+        assert_eq!(v("Noteva v1.2 released").to_string(), "1.2.0");
+        assert_eq!(v("2.0.0-beta.1: new editor").to_string(), "2.0.0-beta.1");
+        assert_eq!(v("V10.4.12").series(), "10.4");
+        assert!(Version::find("What's new this week").is_none());
+        assert!(Version::find("Released in 2026").is_none());
+    }
+
+    #[test]
+    fn orders_by_semver_precedence() {
+        let mut versions = [
+            v("1.0.0"),
+            v("1.0.0-rc.1"),
+            v("1.0.0-beta.11"),
+            v("1.0.0-beta.2"),
+            v("1.0.0-beta"),
+            v("1.0.0-alpha.1"),
+            v("0.10.0"),
+            v("0.9.3"),
+        ];
+        versions.sort();
+        let ordered: Vec<String> = versions.iter().map(Version::to_string).collect();
+        assert_eq!(
+            ordered,
+            [
+                "0.9.3",
+                "0.10.0",
+                "1.0.0-alpha.1",
+                "1.0.0-beta",
+                "1.0.0-beta.2",
+                "1.0.0-beta.11",
+                "1.0.0-rc.1",
+                "1.0.0"
+            ]
+        );
+    }
+
+    #[test]
+    fn groups_by_minor_series_and_picks_latest_stable() {
+        let mut releases = vec![
+            release("v1.1.0", 1),
+            release("v1.2.0", 2),
+            release("v1.1.1", 3),
+            release("v1.3.0-beta.1", 4),
+        ];
+        sort_releases(&mut releases);
+
+        let document = releases_document("Noteva", "https://example.com/", &releases);
+        assert_eq!(document["latest"], "1.2.0");
+        assert_eq!(document["releases"][0]["version"], "1.3.0-beta.1");
+
+        let groups = group_releases(releases);
+        let series: Vec<(&str, usize)> = groups
+            .iter()
+            .map(|g| (g.series.as_str(), g.releases.len()))
+            .collect();
+        assert_eq!(series, [("1.3", 1), ("1.2", 1), ("1.1", 2)]);
+        assert_eq!(groups[2].latest.to_string(), "1.1.1");
+    }
+}
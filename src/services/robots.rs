@@ -0,0 +1,313 @@
+//! robots.txt configuration
+//!
+//! `/robots.txt` is generated from the `robots_config` setting: crawler
+//! groups with allow/disallow paths, a list of AI crawlers to shut out
+//! entirely, free-form extra lines and the sitemap link. Without the
+//! setting the site is open except for the admin UI and the API.
+
+use serde::{Deserialize, Serialize};
+
+use crate::services::settings::SettingsService;
+
+/// Setting holding the JSON configuration
+pub const ROBOTS_CONFIG_KEY: &str = "robots_config";
+
+const MAX_EXTRA_LEN: usize = 5000;
+const MAX_RULES: usize = 50;
+
+/// An AI crawler that can be blocked by name
+#[derive(Debug, Clone, Copy, Serialize)]
+pub struct AiCrawler {
+    pub user_agent: &'static str,
+    pub operator: &'static str,
+}
+
+/// Known AI training and answer-engine crawlers
+pub const AI_CRAWLERS: &[AiCrawler] = &[
+    AiCrawler {
+        user_agent: "GPTBot",
+        operator: "OpenAI",
+    },
+    AiCrawler {
+        user_agent: "ChatGPT-User",
+        operator: "OpenAI",
+    },
+    AiCrawler {
+        user_agent: "OAI-SearchBot",
+        operator: "OpenAI",
+    },
+    AiCrawler {
+        user_agent: "ClaudeBot",
+        operator: "Anthropic",
+    },
+    AiCrawler {
+        user_agent: "anthropic-ai",
+        operator: "Anthropic",
+    },
+    AiCrawler {
+        user_agent: "Google-Extended",
+        operator: "Google",
+    },
+    AiCrawler {
+        user_agent: "Applebot-Extended",
+        operator: "Apple",
+    },
+    AiCrawler {
+        user_agent: "CCBot",
+        operator: "Common Crawl",
+    },
+    AiCrawler {
+        user_agent: "PerplexityBot",
+        operator: "Perplexity",
+    },
+    AiCrawler {
+        user_agent: "Bytespider",
+        operator: "ByteDance",
+    },
+    AiCrawler {
+        user_agent: "meta-externalagent",
+        operator: "Meta",
+    },
+    AiCrawler {
+        user_agent: "Amazonbot",
+        operator: "Amazon",
+    },
+    AiCrawler {
+        user_agent: "cohere-ai",
+        operator: "Cohere",
+    },
+];
+
+/// Errors returned when saving the configuration
+#[derive(Debug, thiserror::Error)]
+pub enum RobotsError {
+    /// Invalid rule, path or crawler name
+    #[error("{0}")]
+    Validation(String),
+
+    /// Internal error
+    #[error("Internal error: {0}")]
+    Internal(String),
+}
+
+/// One `User-agent` group
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RobotsRule {
+    pub user_agent: String,
+    #[serde(default)]
+    pub allow: Vec<String>,
+    #[serde(default)]
+    pub disallow: Vec<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct RobotsConfig {
+    pub rules: Vec<RobotsRule>,
+    /// User agents from [`AI_CRAWLERS`] disallowed from the whole site
+    pub blocked_ai_crawlers: Vec<String>,
+    /// Link `/sitemap.xml` when `site_url` is set
+    pub include_sitemap: bool,
+    /// Lines appended as written
+    pub extra: String,
+}
+
+impl Default for RobotsConfig {
+    fn default() -> Self {
+        Self {
+            rules: vec![RobotsRule {
+                user_agent: "*".to_string(),
+                allow: vec!["/".to_string()],
+                disallow: vec!["/manage/".to_string(), "/api/".to_string()],
+            }],
+            blocked_ai_crawlers: Vec::new(),
+            include_sitemap: true,
+            extra: String::new(),
+        }
+    }
+}
+
+impl RobotsConfig {
+    /// Trim and check the configuration; crawler names take their canonical case
+    pub fn validate(mut self) -> Result<Self, RobotsError> {
+        if self.rules.len() > MAX_RULES {
+            return Err(RobotsError::Validation(format!(
+                "At most {} user-agent groups are allowed",
+                MAX_RULES
+            )));
+        }
+        for rule in &mut self.rules {
+            rule.user_agent = rule.user_agent.trim().to_string();
+            if rule.user_agent.is_empty() || rule.user_agent.contains(char::is_whitespace) {
+                return Err(RobotsError::Validation(format!(
+                    "Invalid user agent: {:?}",
+                    rule.user_agent
+                )));
+            }
+            for path in rule.allow.iter_mut().chain(rule.disallow.iter_mut()) {
+                *path = path.trim().to_string();
+                let valid = (path.starts_with('/') || path.starts_with('*'))
+                    && !path.contains(char::is_whitespace);
+                if !valid {
+                    return Err(RobotsError::Validation(format!(
+                        "Invalid path {:?}: paths start with / or *",
+                        path
+                    )));
+                }
+            }
+        }
+
+        let mut blocked: Vec<String> = Vec::new();
+        for name in &self.blocked_ai_crawlers {
+            let crawler = AI_CRAWLERS
+                .iter()
+                .find(|c| c.user_agent.eq_ignore_ascii_case(name.trim()))
+                .ok_or_else(|| RobotsError::Validation(format!("Unknown AI crawler: {}", name)))?;
+            if !blocked.iter().any(|b| b == crawler.user_agent) {
+                blocked.push(crawler.user_agent.to_string());
+            }
+        }
+        self.blocked_ai_crawlers = blocked;
+
+        self.extra = self.extra.trim().to_string();
+        if self.extra.len() > MAX_EXTRA_LEN {
+            return Err(RobotsError::Validation(format!(
+                "Extra lines must be at most {} bytes",
+                MAX_EXTRA_LEN
+            )));
+        }
+        Ok(self)
+    }
+
+    /// The robots.txt body
+    pub fn render(&self, site_url: &str) -> String {
+        let mut groups: Vec<String> = Vec::new();
+        for rule in &self.rules {
+            let mut group = format!("User-agent: {}\n", rule.user_agent);
+            for path in &rule.allow {
+                group.push_str(&format!("Allow: {}\n", path));
+            }
+            for path in &rule.disallow {
+                group.push_str(&format!("Disallow: {}\n", path));
+            }
+            if rule.allow.is_empty() && rule.disallow.is_empty() {
+                // An empty Disallow allows everything
+                group.push_str("Disallow:\n");
+            }
+            groups.push(group);
+        }
+        if !self.blocked_ai_crawlers.is_empty() {
+            let mut group: String = self
+                .blocked_ai_crawlers
+                .iter()
+                .map(|agent| format!("User-agent: {}\n", agent))
+                .collect();
+            group.push_str("Disallow: /\n");
+            groups.push(group);
+        }
+        if !self.extra.is_empty() {
+            groups.push(format!("{}\n", self.extra));
+        }
+        let site_url = site_url.trim_end_matches('/');
+        if self.include_sitemap && !site_url.is_empty() {
+            groups.push(format!("Sitemap: {}/sitemap.xml\n", site_url));
+        }
+        groups.join("\n")
+    }
+}
+
+/// The stored configuration, or the default when unset or unreadable
+pub async fn load(settings: &SettingsService) -> RobotsConfig {
+    match settings.get(ROBOTS_CONFIG_KEY).await {
+        Ok(Some(json)) if !json.trim().is_empty() => {
+            // Also written by the generic settings endpoint, so check it again
+            serde_json::from_str::<RobotsConfig>(&json)
+                .map_err(|e| e.to_string())
+                .and_then(|config| config.validate().map_err(|e| e.to_string()))
+                .unwrap_or_else(|e| {
+                    tracing::warn!("Ignoring invalid {}: {}", ROBOTS_CONFIG_KEY, e);
+                    RobotsConfig::default()
+                })
+        }
+        Ok(_) => RobotsConfig::default(),
+        Err(e) => {
+            tracing::warn!("Failed to read {}: {}", ROBOTS_CONFIG_KEY, e);
+            RobotsConfig::default()
+        }
+    }
+}
+
+/// Validate and store a new configuration
+pub async fn save(
+    settings: &SettingsService,
+    config: RobotsConfig,
+) -> Result<RobotsConfig, RobotsError> {
+    let config = config.validate()?;
+    let json = serde_json::to_string(&config).map_err(|e| RobotsError::Internal(e.to_string()))?;
+    settings
+        .set_setting(ROBOTS_CONFIG_KEY, &json)
+        .await
+        .map_err(|e| RobotsError::Internal(e.to_string()))?;
+    Ok(config)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn default_keeps_admin_and_api_out() {
+        assert_eq!(
+            RobotsConfig::default().render("https://blog.example/"),
+            "User-agent: *\nAllow: /\nDisallow: /manage/\nDisallow: /api/\n\n\
+             Sitemap: https://blog.example/sitemap.xml\n"
+        );
+        assert!(!RobotsConfig::default().render("").contains("Sitemap"));
+    }
+
+    #[test]
+    fn blocked_ai_crawlers_share_one_group() {
+        let config = RobotsConfig {
+            rules: vec![RobotsRule {
+                user_agent: " * ".to_string(),
+                allow: Vec::new(),
+                disallow: Vec::new(),
+            }],
+            blocked_ai_crawlers: vec![
+                "gptbot".to_string(),
+                "CCBot".to_string(),
+                "GPTBot".to_string(),
+            ],
+            include_sitemap: false,
+            extra: "Crawl-delay: 5\n".to_string(),
+        }
+        .validate()
+        .unwrap();
+        assert_eq!(config.blocked_ai_crawlers, ["GPTBot", "CCBot"]);
+        assert_eq!(
+            config.render("https://blog.example"),
+            "User-agent: *\nDisallow:\n\n\
+             User-agent: GPTBot\nUser-agent: CCBot\nDisallow: /\n\n\
+             Crawl-delay: 5\n"
+        );
+    }
+
+    #[test]
+    fn rejects_unknown_crawlers_and_line_breaks() {
+        let mut config = RobotsConfig {
+            blocked_ai_crawlers: vec!["NotABot".to_string()],
+            ..Default::default()
+        };
+        assert!(config.clone().validate().is_err());
+
+        config.blocked_ai_crawlers.clear();
+        config.rules[0]
+            .disallow
+            .push("/private\nUser-agent: x".to_string());
+        assert!(config.clone().validate().is_err());
+
+        config.rules[0].disallow.pop();
+        config.rules[0].allow.push("private".to_string());
+        assert!(config.validate().is_err());
+    }
+}
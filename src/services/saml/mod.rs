@@ -0,0 +1,471 @@
+//! SAML 2.0 single sign-on (service provider side)
+//!
+//! Built only with the `saml` feature. Sign-in starts with an AuthnRequest
+//! sent to the IdP over the HTTP-Redirect binding; the IdP posts a signed
+//! response back to the assertion consumer service (HTTP-POST binding).
+//!
+//! Users are provisioned on their first sign-in and bound to their IdP
+//! account by (issuer, NameID); later sign-ins resolve the local user through
+//! that binding only. The role of a provisioned user comes from the
+//! configured role attribute and mapping, and is re-applied on every sign-in
+//! so changes made in the IdP take effect.
+//!
+//! Existing local accounts are never matched by username. With
+//! `link_by_email` an account with the asserted email address is adopted on
+//! its first SAML sign-in and keeps its local role; otherwise a username or
+//! email collision refuses the sign-in.
+//!
+//! Configured in the `saml` section of `config.yml` (see [`SamlConfig`]).
+
+mod response;
+mod xmldsig;
+
+pub use response::SamlAssertion;
+
+use anyhow::Context;
+use chrono::{DateTime, Duration, SecondsFormat, Utc};
+use data_encoding::{BASE64, HEXLOWER};
+use flate2::{write::DeflateEncoder, Compression};
+use reqwest::Url;
+use std::collections::HashMap;
+use std::io::Write;
+use std::str::FromStr;
+use std::sync::Arc;
+use tokio::sync::RwLock;
+
+use crate::config::SamlConfig;
+use crate::db::repositories::{ExternalIdentityRepository, UserRepository};
+use crate::models::{ExternalIdentity, User, UserRole, PROVIDER_SAML};
+use crate::services::password::hash_password;
+
+/// How long an AuthnRequest may stay unanswered
+const REQUEST_TTL_SECS: i64 = 10 * 60;
+
+/// Upper bound on a base64 `SAMLResponse`
+const MAX_RESPONSE_LEN: usize = 256 * 1024;
+
+/// Default attribute holding the email address
+const DEFAULT_EMAIL_ATTRIBUTE: &str = "email";
+
+/// Default post-login redirect
+const DEFAULT_LOGIN_REDIRECT: &str = "/manage";
+
+/// Errors returned by the SAML service
+#[derive(Debug, thiserror::Error)]
+pub enum SamlError {
+    /// The response failed parsing or validation
+    #[error("Invalid SAML response: {0}")]
+    InvalidResponse(String),
+
+    /// The authenticated user may not sign in
+    #[error("{0}")]
+    Forbidden(String),
+
+    /// Internal error
+    #[error("Internal error: {0}")]
+    Internal(#[from] anyhow::Error),
+}
+
+fn random_bytes<const N: usize>() -> Result<[u8; N], SamlError> {
+    let mut buf = [0u8; N];
+    getrandom::fill(&mut buf)
+        .map_err(|e| anyhow::anyhow!("Failed to generate random bytes: {}", e))?;
+    Ok(buf)
+}
+
+fn random_id() -> Result<String, SamlError> {
+    // IDs must be valid NCNames, which cannot start with a digit
+    Ok(format!("_{}", HEXLOWER.encode(&random_bytes::<20>()?)))
+}
+
+fn escape_xml(value: &str) -> String {
+    value
+        .replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+fn role_rank(role: UserRole) -> u8 {
+    match role {
+        UserRole::Admin => 3,
+        UserRole::Editor => 2,
+        UserRole::Author => 1,
+    }
+}
+
+/// SAML service provider
+pub struct SamlService {
+    config: SamlConfig,
+    idp_sso_url: Url,
+    idp_public_key: Vec<u8>,
+    role_mapping: HashMap<String, UserRole>,
+    default_role: Option<UserRole>,
+    user_repo: Arc<dyn UserRepository>,
+    identity_repo: Arc<dyn ExternalIdentityRepository>,
+    /// Outstanding AuthnRequest IDs and their expiry
+    pending_requests: RwLock<HashMap<String, DateTime<Utc>>>,
+    /// Consumed assertion IDs and their expiry
+    used_assertions: RwLock<HashMap<String, DateTime<Utc>>>,
+}
+
+impl SamlService {
+    /// Create the service, validating the configuration
+    pub fn new(
+        config: SamlConfig,
+        user_repo: Arc<dyn UserRepository>,
+        identity_repo: Arc<dyn ExternalIdentityRepository>,
+    ) -> anyhow::Result<Self> {
+        for (name, value) in [
+            ("sp_entity_id", &config.sp_entity_id),
+            ("acs_url", &config.acs_url),
+            ("idp_entity_id", &config.idp_entity_id),
+            ("idp_sso_url", &config.idp_sso_url),
+            ("idp_certificate", &config.idp_certificate),
+        ] {
+            if value.trim().is_empty() {
+                anyhow::bail!("saml.{} is required", name);
+            }
+        }
+        let idp_sso_url = Url::parse(&config.idp_sso_url).context("Invalid saml.idp_sso_url")?;
+        let idp_public_key = xmldsig::rsa_public_key_from_pem(&config.idp_certificate)
+            .map_err(|e| anyhow::anyhow!("Invalid saml.idp_certificate: {}", e))?;
+
+        let role_mapping = config
+            .role_mapping
+            .iter()
+            .map(|(value, role)| {
+                UserRole::from_str(role)
+                    .map(|role| (value.clone(), role))
+                    .context("Invalid role in saml.role_mapping")
+            })
+            .collect::<anyhow::Result<_>>()?;
+        let default_role = config
+            .default_role
+            .as_deref()
+            .map(UserRole::from_str)
+            .transpose()
+            .context("Invalid saml.default_role")?;
+
+        Ok(Self {
+            config,
+            idp_sso_url,
+            idp_public_key,
+            role_mapping,
+            default_role,
+            user_repo,
+            identity_repo,
+            pending_requests: RwLock::new(HashMap::new()),
+            used_assertions: RwLock::new(HashMap::new()),
+        })
+    }
+
+    /// Where to send the browser after signing in
+    pub fn login_redirect(&self) -> &str {
+        self.config
+            .login_redirect
+            .as_deref()
+            .unwrap_or(DEFAULT_LOGIN_REDIRECT)
+    }
+
+    /// Service provider metadata for registering with the IdP
+    pub fn metadata_xml(&self) -> String {
+        format!(
+            concat!(
+                r#"<?xml version="1.0" encoding="UTF-8"?>"#,
+                r#"<md:EntityDescriptor xmlns:md="urn:oasis:names:tc:SAML:2.0:metadata" entityID="{}">"#,
+                r#"<md:SPSSODescriptor AuthnRequestsSigned="false" WantAssertionsSigned="true" protocolSupportEnumeration="{}">"#,
+                r#"<md:AssertionConsumerService Binding="urn:oasis:names:tc:SAML:2.0:bindings:HTTP-POST" Location="{}" index="0" isDefault="true"/>"#,
+                r#"</md:SPSSODescriptor></md:EntityDescriptor>"#
+            ),
+            escape_xml(&self.config.sp_entity_id),
+            response::PROTOCOL_NS,
+            escape_xml(&self.config.acs_url),
+        )
+    }
+
+    fn authn_request_xml(&self, id: &str, issue_instant: DateTime<Utc>) -> String {
+        format!(
+            concat!(
+                r#"<samlp:AuthnRequest xmlns:samlp="{}" xmlns:saml="{}" ID="{}" Version="2.0" IssueInstant="{}" "#,
+                r#"Destination="{}" AssertionConsumerServiceURL="{}" ProtocolBinding="urn:oasis:names:tc:SAML:2.0:bindings:HTTP-POST">"#,
+                r#"<saml:Issuer>{}</saml:Issuer>"#,
+                r#"<samlp:NameIDPolicy Format="urn:oasis:names:tc:SAML:1.1:nameid-format:unspecified" AllowCreate="true"/>"#,
+                r#"</samlp:AuthnRequest>"#
+            ),
+            response::PROTOCOL_NS,
+            response::ASSERTION_NS,
+            id,
+            issue_instant.to_rfc3339_opts(SecondsFormat::Secs, true),
+            escape_xml(&self.config.idp_sso_url),
+            escape_xml(&self.config.acs_url),
+            escape_xml(&self.config.sp_entity_id),
+        )
+    }
+
+    /// Build the IdP redirect URL for a new AuthnRequest
+    pub async fn login_url(&self, relay_state: Option<&str>) -> Result<String, SamlError> {
+        let id = random_id()?;
+        let now = Utc::now();
+
+        let mut encoder = DeflateEncoder::new(Vec::new(), Compression::default());
+        encoder
+            .write_all(self.authn_request_xml(&id, now).as_bytes())
+            .and_then(|_| encoder.try_finish())
+            .context("Failed to encode AuthnRequest")?;
+        let request = BASE64.encode(encoder.get_ref());
+
+        let mut url = self.idp_sso_url.clone();
+        url.query_pairs_mut().append_pair("SAMLRequest", &request);
+        if let Some(relay_state) = relay_state {
+            url.query_pairs_mut().append_pair("RelayState", relay_state);
+        }
+
+        let mut pending = self.pending_requests.write().await;
+        pending.retain(|_, expires_at| *expires_at > now);
+        pending.insert(id, now + Duration::seconds(REQUEST_TTL_SECS));
+
+        Ok(url.into())
+    }
+
+    /// Verify a posted `SAMLResponse` (base64) and return its assertion
+    ///
+    /// Solicited responses must answer an outstanding request; unsolicited
+    /// ones are only accepted with `allow_idp_initiated`. Each assertion can
+    /// be used once.
+    pub async fn process_response(&self, saml_response: &str) -> Result<SamlAssertion, SamlError> {
+        self.process_response_at(saml_response, Utc::now()).await
+    }
+
+    async fn process_response_at(
+        &self,
+        saml_response: &str,
+        now: DateTime<Utc>,
+    ) -> Result<SamlAssertion, SamlError> {
+        if saml_response.len() > MAX_RESPONSE_LEN {
+            return Err(SamlError::InvalidResponse("Response is too large".into()));
+        }
+        let compact: String = saml_response
+            .chars()
+            .filter(|c| !c.is_whitespace())
+            .collect();
+        let xml = BASE64
+            .decode(compact.as_bytes())
+            .ok()
+            .and_then(|bytes| String::from_utf8(bytes).ok())
+            .ok_or_else(|| SamlError::InvalidResponse("Response is not base64 XML".into()))?;
+
+        let assertion = response::parse_response(
+            &xml,
+            &response::Expectations {
+                idp_entity_id: &self.config.idp_entity_id,
+                sp_entity_id: &self.config.sp_entity_id,
+                acs_url: &self.config.acs_url,
+                idp_public_key: &self.idp_public_key,
+            },
+            now,
+        )
+        .map_err(SamlError::InvalidResponse)?;
+
+        match &assertion.in_response_to {
+            Some(request_id) => {
+                let known = self
+                    .pending_requests
+                    .write()
+                    .await
+                    .remove(request_id)
+                    .is_some_and(|expires_at| expires_at > now);
+                if !known {
+                    return Err(SamlError::InvalidResponse(
+                        "Response does not answer a pending request".into(),
+                    ));
+                }
+            }
+            None if !self.config.allow_idp_initiated => {
+                return Err(SamlError::InvalidResponse(
+                    "Unsolicited responses are not accepted".into(),
+                ));
+            }
+            None => {}
+        }
+
+        let mut used = self.used_assertions.write().await;
+        used.retain(|_, expires_at| *expires_at > now);
+        if used.contains_key(&assertion.id) {
+            return Err(SamlError::InvalidResponse(
+                "Assertion has already been used".into(),
+            ));
+        }
+        used.insert(assertion.id.clone(), assertion.not_on_or_after);
+
+        Ok(assertion)
+    }
+
+    /// Role for an assertion: the highest mapped role, else the default role
+    fn resolve_role(&self, assertion: &SamlAssertion) -> Option<UserRole> {
+        self.config
+            .role_attribute
+            .as_ref()
+            .and_then(|name| assertion.attributes.get(name))
+            .into_iter()
+            .flatten()
+            .filter_map(|value| self.role_mapping.get(value).copied())
+            .max_by_key(|role| role_rank(*role))
+            .or(self.default_role)
+    }
+
+    /// Find or provision the local user for a verified assertion
+    ///
+    /// The user is resolved through the stored (issuer, NameID) binding. An
+    /// unbound NameID provisions a new account, or adopts the account with
+    /// the asserted email when `link_by_email` is set; any other collision
+    /// with a local account is refused.
+    pub async fn sign_in(&self, assertion: &SamlAssertion) -> Result<User, SamlError> {
+        let role = self.resolve_role(assertion).ok_or_else(|| {
+            SamlError::Forbidden("Your account is not allowed to sign in to this site".into())
+        })?;
+
+        let name_id = assertion.name_id.trim();
+        if name_id.is_empty() {
+            return Err(SamlError::InvalidResponse("Assertion has no NameID".into()));
+        }
+        let issuer = self.config.idp_entity_id.as_str();
+
+        if let Some(identity) = self
+            .identity_repo
+            .get(PROVIDER_SAML, issuer, name_id)
+            .await
+            .context("Failed to look up SAML identity")?
+        {
+            let user = self
+                .user_repo
+                .get_by_id(identity.user_id)
+                .await
+                .context("Failed to look up user")?
+                .ok_or_else(|| anyhow::anyhow!("Linked user {} not found", identity.user_id))?;
+            if user.is_banned() {
+                return Err(SamlError::Forbidden("This account has been banned".into()));
+            }
+            if !identity.manages_role || user.role == role {
+                return Ok(user);
+            }
+            // Also signs the user out of existing sessions
+            self.user_repo
+                .revoke_access(user.id, Some(role), false)
+                .await
+                .context("Failed to update user role")?;
+            let user = self
+                .user_repo
+                .get_by_id(user.id)
+                .await
+                .context("Failed to reload user")?
+                .ok_or_else(|| anyhow::anyhow!("User disappeared during sign-in"))?;
+            return Ok(user);
+        }
+
+        let username = self
+            .config
+            .username_attribute
+            .as_deref()
+            .and_then(|name| assertion.attribute(name))
+            .unwrap_or(name_id)
+            .trim()
+            .to_string();
+        let email_attribute = self
+            .config
+            .email_attribute
+            .as_deref()
+            .unwrap_or(DEFAULT_EMAIL_ATTRIBUTE);
+        let email = assertion
+            .attribute(email_attribute)
+            .or_else(|| Some(name_id).filter(|n| n.contains('@')))
+            .map(|e| e.trim().to_string())
+            .ok_or_else(|| SamlError::InvalidResponse("Assertion has no email address".into()))?;
+        if username.is_empty() {
+            return Err(SamlError::InvalidResponse(
+                "Assertion has no username".into(),
+            ));
+        }
+
+        let by_email = self
+            .user_repo
+            .get_by_email(&email)
+            .await
+            .context("Failed to look up user")?;
+        if let Some(user) = by_email.as_ref().filter(|_| self.config.link_by_email) {
+            if user.is_banned() {
+                return Err(SamlError::Forbidden("This account has been banned".into()));
+            }
+            if self
+                .identity_repo
+                .exists_for_user(PROVIDER_SAML, user.id)
+                .await
+                .context("Failed to look up SAML identity")?
+            {
+                return Err(SamlError::Forbidden(
+                    "This account is linked to another single sign-on identity".into(),
+                ));
+            }
+            // Adopted accounts keep the role they were given locally
+            self.identity_repo
+                .create(&ExternalIdentity::new(
+                    PROVIDER_SAML,
+                    issuer,
+                    name_id,
+                    user.id,
+                    false,
+                ))
+                .await
+                .context("Failed to link SAML identity")?;
+            tracing::info!(
+                user_id = user.id,
+                "linked SAML identity to existing account"
+            );
+            return Ok(user.clone());
+        }
+
+        let username_taken = self
+            .user_repo
+            .get_by_username(&username)
+            .await
+            .context("Failed to look up user")?
+            .is_some();
+        if username_taken || by_email.is_some() {
+            return Err(SamlError::Forbidden(
+                "An account with this username or email address already exists; ask an administrator to link it".into(),
+            ));
+        }
+
+        // Provisioned users sign in through the IdP only
+        let password_hash = hash_password(&HEXLOWER.encode(&random_bytes::<32>()?))
+            .context("Failed to hash password")?;
+        let mut user = User::new(username, email, password_hash, role);
+        user.display_name = self
+            .config
+            .display_name_attribute
+            .as_deref()
+            .and_then(|name| assertion.attribute(name))
+            .map(String::from);
+        let user = self
+            .user_repo
+            .create(&user)
+            .await
+            .context("Failed to provision user")?;
+        self.identity_repo
+            .create(&ExternalIdentity::new(
+                PROVIDER_SAML,
+                issuer,
+                name_id,
+                user.id,
+                true,
+            ))
+            .await
+            .context("Failed to bind SAML identity")?;
+        tracing::info!(user_id = user.id, role = %user.role, "provisioned SAML user");
+
+        Ok(user)
+    }
+}
+
+#[cfg(test)]
+mod tests;
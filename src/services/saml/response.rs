@@ -0,0 +1,256 @@
+//! SAML response parsing and validation
+
+use chrono::{DateTime, Duration, Utc};
+use roxmltree::{Document, Node, ParsingOptions};
+use std::collections::HashMap;
+
+use super::xmldsig;
+
+pub(super) const PROTOCOL_NS: &str = "urn:oasis:names:tc:SAML:2.0:protocol";
+pub(super) const ASSERTION_NS: &str = "urn:oasis:names:tc:SAML:2.0:assertion";
+
+const STATUS_SUCCESS: &str = "urn:oasis:names:tc:SAML:2.0:status:Success";
+const BEARER: &str = "urn:oasis:names:tc:SAML:2.0:cm:bearer";
+
+/// Tolerated clock difference between the IdP and this server
+const CLOCK_SKEW_SECS: i64 = 120;
+
+/// Upper bound on nodes in a response, to cap memory use
+const MAX_NODES: u32 = 10_000;
+
+/// What a response must match to be accepted
+pub(super) struct Expectations<'a> {
+    pub idp_entity_id: &'a str,
+    pub sp_entity_id: &'a str,
+    pub acs_url: &'a str,
+    /// PKCS#1 RSA public key of the IdP
+    pub idp_public_key: &'a [u8],
+}
+
+/// A verified assertion
+#[derive(Debug, Clone)]
+pub struct SamlAssertion {
+    /// Assertion ID, used for replay protection
+    pub id: String,
+    /// Request ID this assertion answers (absent for IdP-initiated logins)
+    pub in_response_to: Option<String>,
+    /// Subject NameID
+    pub name_id: String,
+    /// Attribute name to values
+    pub attributes: HashMap<String, Vec<String>>,
+    /// Time after which the assertion must not be accepted
+    pub not_on_or_after: DateTime<Utc>,
+}
+
+impl SamlAssertion {
+    /// First value of an attribute
+    pub fn attribute(&self, name: &str) -> Option<&str> {
+        self.attributes
+            .get(name)
+            .and_then(|values| values.first())
+            .map(String::as_str)
+    }
+}
+
+fn saml_child<'a, 'input>(
+    node: Node<'a, 'input>,
+    ns: &str,
+    name: &str,
+) -> Option<Node<'a, 'input>> {
+    node.children().find(|c| {
+        c.is_element() && c.tag_name().namespace() == Some(ns) && c.tag_name().name() == name
+    })
+}
+
+fn saml_children<'a, 'input>(
+    node: Node<'a, 'input>,
+    ns: &'a str,
+    name: &'a str,
+) -> impl Iterator<Item = Node<'a, 'input>> + 'a {
+    node.children().filter(move |c| {
+        c.is_element() && c.tag_name().namespace() == Some(ns) && c.tag_name().name() == name
+    })
+}
+
+fn text_of(node: Option<Node>) -> Option<String> {
+    node.map(|n| {
+        n.descendants()
+            .filter(|d| d.is_text())
+            .filter_map(|d| d.text())
+            .collect::<String>()
+            .trim()
+            .to_string()
+    })
+}
+
+fn parse_time(value: Option<&str>) -> Result<Option<DateTime<Utc>>, String> {
+    value
+        .map(|v| {
+            DateTime::parse_from_rfc3339(v)
+                .map(|t| t.with_timezone(&Utc))
+                .map_err(|_| format!("Invalid timestamp: {}", v))
+        })
+        .transpose()
+}
+
+/// Parse a decoded `SAMLResponse` and validate it against `expected`
+///
+/// Exactly one unencrypted assertion is accepted. Either the assertion or the
+/// whole response must carry a valid signature; the issuer, audience,
+/// recipient and validity window are all checked.
+pub(super) fn parse_response(
+    xml: &str,
+    expected: &Expectations,
+    now: DateTime<Utc>,
+) -> Result<SamlAssertion, String> {
+    let doc = Document::parse_with_options(
+        xml,
+        ParsingOptions {
+            allow_dtd: false,
+            nodes_limit: MAX_NODES,
+        },
+    )
+    .map_err(|e| format!("Malformed XML: {}", e))?;
+    let response = doc.root_element();
+    if !response.has_tag_name((PROTOCOL_NS, "Response")) {
+        return Err("Not a SAML response".into());
+    }
+
+    if let Some(destination) = response.attribute("Destination") {
+        if destination != expected.acs_url {
+            return Err("Response destination does not match".into());
+        }
+    }
+    if let Some(issuer) = text_of(saml_child(response, ASSERTION_NS, "Issuer")) {
+        if issuer != expected.idp_entity_id {
+            return Err("Unexpected response issuer".into());
+        }
+    }
+
+    let status = saml_child(response, PROTOCOL_NS, "Status")
+        .and_then(|s| saml_child(s, PROTOCOL_NS, "StatusCode"))
+        .and_then(|c| c.attribute("Value"));
+    if status != Some(STATUS_SUCCESS) {
+        return Err(format!(
+            "Identity provider returned status {}",
+            status.unwrap_or("(none)")
+        ));
+    }
+
+    if doc
+        .descendants()
+        .any(|n| n.has_tag_name((ASSERTION_NS, "EncryptedAssertion")))
+    {
+        return Err("Encrypted assertions are not supported".into());
+    }
+    // A single assertion anywhere in the document rules out wrapping attacks
+    let mut assertions = doc
+        .descendants()
+        .filter(|n| n.has_tag_name((ASSERTION_NS, "Assertion")));
+    let assertion = assertions.next().ok_or("Response has no assertion")?;
+    if assertions.next().is_some() {
+        return Err("Response has more than one assertion".into());
+    }
+    if assertion.parent() != Some(response) {
+        return Err("Assertion is not a direct child of the response".into());
+    }
+
+    if xmldsig::is_signed(assertion) {
+        xmldsig::verify_enveloped(assertion, expected.idp_public_key)?;
+    } else if xmldsig::is_signed(response) {
+        xmldsig::verify_enveloped(response, expected.idp_public_key)?;
+    } else {
+        return Err("Neither the response nor the assertion is signed".into());
+    }
+
+    let id = assertion
+        .attribute("ID")
+        .ok_or("Assertion has no ID")?
+        .to_string();
+    if text_of(saml_child(assertion, ASSERTION_NS, "Issuer")).as_deref()
+        != Some(expected.idp_entity_id)
+    {
+        return Err("Unexpected assertion issuer".into());
+    }
+
+    let skew = Duration::seconds(CLOCK_SKEW_SECS);
+    let subject =
+        saml_child(assertion, ASSERTION_NS, "Subject").ok_or("Assertion has no subject")?;
+    let name_id = text_of(saml_child(subject, ASSERTION_NS, "NameID"))
+        .filter(|n| !n.is_empty())
+        .ok_or("Assertion has no NameID")?;
+
+    // At least one bearer confirmation addressed to us and still valid
+    let mut confirmation_data = None;
+    for confirmation in saml_children(subject, ASSERTION_NS, "SubjectConfirmation") {
+        if confirmation.attribute("Method") != Some(BEARER) {
+            continue;
+        }
+        let Some(data) = saml_child(confirmation, ASSERTION_NS, "SubjectConfirmationData") else {
+            continue;
+        };
+        let recipient_ok = data.attribute("Recipient") == Some(expected.acs_url);
+        let expiry = parse_time(data.attribute("NotOnOrAfter"))?;
+        if recipient_ok && expiry.is_some_and(|t| now < t + skew) {
+            confirmation_data = Some((data, expiry.unwrap_or(now)));
+            break;
+        }
+    }
+    let (confirmation_data, mut not_on_or_after) =
+        confirmation_data.ok_or("No valid bearer subject confirmation")?;
+
+    let in_response_to = confirmation_data
+        .attribute("InResponseTo")
+        .or_else(|| response.attribute("InResponseTo"))
+        .filter(|id| !id.is_empty())
+        .map(String::from);
+
+    let conditions =
+        saml_child(assertion, ASSERTION_NS, "Conditions").ok_or("Assertion has no conditions")?;
+    if let Some(not_before) = parse_time(conditions.attribute("NotBefore"))? {
+        if now + skew < not_before {
+            return Err("Assertion is not yet valid".into());
+        }
+    }
+    if let Some(expiry) = parse_time(conditions.attribute("NotOnOrAfter"))? {
+        if now >= expiry + skew {
+            return Err("Assertion has expired".into());
+        }
+        not_on_or_after = not_on_or_after.min(expiry);
+    }
+    let mut restrictions =
+        saml_children(conditions, ASSERTION_NS, "AudienceRestriction").peekable();
+    if restrictions.peek().is_none() {
+        return Err("Assertion has no audience restriction".into());
+    }
+    for restriction in restrictions {
+        let allowed = saml_children(restriction, ASSERTION_NS, "Audience")
+            .any(|a| text_of(Some(a)).as_deref() == Some(expected.sp_entity_id));
+        if !allowed {
+            return Err("Assertion is not addressed to this service provider".into());
+        }
+    }
+
+    let mut attributes: HashMap<String, Vec<String>> = HashMap::new();
+    for statement in saml_children(assertion, ASSERTION_NS, "AttributeStatement") {
+        for attribute in saml_children(statement, ASSERTION_NS, "Attribute") {
+            let Some(name) = attribute.attribute("Name") else {
+                continue;
+            };
+            let values = saml_children(attribute, ASSERTION_NS, "AttributeValue")
+                .filter_map(|v| text_of(Some(v)));
+            attributes
+                .entry(name.to_string())
+                .or_default()
+                .extend(values);
+        }
+    }
+
+    Ok(SamlAssertion {
+        id,
+        in_response_to,
+        name_id,
+        attributes,
+        not_on_or_after,
+    })
+}
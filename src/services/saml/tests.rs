@@ -0,0 +1,446 @@
+use super::*;
+use crate::db::repositories::{SqlxExternalIdentityRepository, SqlxUserRepository};
+use crate::db::{create_test_pool, migrations};
+use data_encoding::BASE64;
+use ring::{digest, rand::SystemRandom, signature::RsaKeyPair};
+use std::io::Read;
+
+const IDP_ENTITY_ID: &str = "https://idp.example.com/metadata";
+const SP_ENTITY_ID: &str = "https://blog.example.com/saml";
+const ACS_URL: &str = "https://blog.example.com/api/v1/auth/saml/acs";
+
+/// Self-signed test IdP certificate (RSA 2048)
+const IDP_CERTIFICATE: &str = "-----BEGIN CERTIFICATE-----
+MIIDFzCCAf+gAwIBAgIUAu5fuwAjfaQ6/qkC9NKNrWLKpCIwDQYJKoZIhvcNAQEL
+BQAwGjEYMBYGA1UEAwwPTm90ZXZhIFRlc3QgSWRQMCAXDTI2MTAxNjE2MTc1N1oY
+DzIxMjYwOTIyMTYxNzU3WjAaMRgwFgYDVQQDDA9Ob3RldmEgVGVzdCBJZFAwggEi
+MA0GCSqGSIb3DQEBAQUAA4IBDwAwggEKAoIBAQC4Rr2Cs1xuyt51PFx3F4WyD+Yf
+MBLOMi4ZLe6wl+V6qecGAyjbe2KSjZH8Q06MFC+2WC3vL1XxLXcLS4CkysAIJdkC
+EhrqqDeAa3bpKbNU93cBr0oCqbmEVGFzdTiiz+9hsIgxVVKWdfpA8I9wmQolnJ6E
+qLrDuU5sgdeJjhy67/6M3MBSWO+nXG8ygeqZRw/WywFcWYzx7FHHc5zOtXpE3kQ7
+acnWuPLgtMgaFxhX4v4a9xQF3cD7fZ92EoIaHM/EP2lz2ntvbO6XAuMCG5FINhMf
+mmQMZM29QWr6dSwulBLg6sTaBDbzVcU6hiGlISd95PTFsgmg00PEdPwFLqEBAgMB
+AAGjUzBRMB0GA1UdDgQWBBQPFOutoepmTcp/fkMjBb+X9SKPLjAfBgNVHSMEGDAW
+gBQPFOutoepmTcp/fkMjBb+X9SKPLjAPBgNVHRMBAf8EBTADAQH/MA0GCSqGSIb3
+DQEBCwUAA4IBAQB4Wg3tMPkE5WN2KfT3OtCZmX4gBX8XarHC26u7wEqAzO7jcUnf
+13IFgVGTeqR+BdVA/iGEoryTOENpPYoyCr+ILGrIh0jQwaPbq3YWQdAEdQDpNpn2
+syH8ypjVXUNxxUYzdvlyKcULYz5jeYGEbnrOzRQXdvVLF1vkJMdmrpuzaoKqhxJL
+ccuoSKLlrOR6k42rPxtxM8iSCuOkRvDN6vwR1Api1PdJRs9hL1Zp3u1xQgHktAGi
+3AuI8OKJ6gxsY96r3BvXlaZThjqR5ePJO1C4b7z8GGa//MWws+gwGtBL+2eCO8Jd
+EuVUCcKD35iDb9WBHGs6cr87mhHWc9w2a7Td
+-----END CERTIFICATE-----";
+
+/// PKCS#8 private key of the test IdP
+const IDP_PRIVATE_KEY: &str =
+    "MIIEvAIBADANBgkqhkiG9w0BAQEFAASCBKYwggSiAgEAAoIBAQC4Rr2Cs1xuyt51PFx3F4WyD+Yf
+MBLOMi4ZLe6wl+V6qecGAyjbe2KSjZH8Q06MFC+2WC3vL1XxLXcLS4CkysAIJdkCEhrqqDeAa3bp
+KbNU93cBr0oCqbmEVGFzdTiiz+9hsIgxVVKWdfpA8I9wmQolnJ6EqLrDuU5sgdeJjhy67/6M3MBS
+WO+nXG8ygeqZRw/WywFcWYzx7FHHc5zOtXpE3kQ7acnWuPLgtMgaFxhX4v4a9xQF3cD7fZ92EoIa
+HM/EP2lz2ntvbO6XAuMCG5FINhMfmmQMZM29QWr6dSwulBLg6sTaBDbzVcU6hiGlISd95PTFsgmg
+00PEdPwFLqEBAgMBAAECggEAHS28W8n9UKDIZU1jwcrEdXu9f0I8NBCvinaitS00Hb5x8ZqF+Wz5
+4IVQsJ5SvW0D2g5v5Y/7/oapsyznJ5DgI9okWMbXosD0mt3JVCWxevp9SlPAdxf7Y2f+D0toJqWg
+vLn4csFVJLR1JhvBRr3A5NA+LHjQK+Y3dAiCQcpyI6pQCGAYv6PmxVzbLrRg1iyyNRPHqgrPIWFl
+TJGA6GZcB+l7o4l7PLrsWSwWwn1/wGMcQJWYyELlK8+v1wHMC/p2lxS8pJOFMZA8s7jLpPcXU66y
+uNgwgUmOBg7eRQ7Kz2WFRDlN81StpXkB9OwTfAgeMGIR+e5RAz7lYlxAd7bevQKBgQDz0oLd9H0m
+tH1CIw9URuOmeyICzp1Av/9CF5CB0as3urguqw73AJgvmVd2blFWGjbNtnaXW33NxDyRCjKspu2M
+2/6mf5SOMi1PfxHi32IiPWyGSFI7wxmmRRFL0ogKV6FRLsmXZ1nO2G5jsz1lx9Ck3lLjfVZRaOu0
+oyLwoKD9LQKBgQDBeuE97V/76LkvIY2sUcVSKYBh9P70xLIXR6n78DMCXJ8DGsPu0yOXeEXnQGrd
+X7ODLATcHV9tcPJoHtpvoGc9lwPRCfDvJJS0pj2NhYaD4xy5qG5ICUzAAOItTj+kX3Ks7jdZ2XKD
+PNbBbRWfCrjZmFecXLvuc9D4mpK5wa8fpQKBgDr7m+jxsizg0MRlEtpCY+PsOagQL35wc+GbLbwZ
+6p+Kre+sNeyO2kH5iGz/9vErWkKdd0baPS6/4B7+JpAZubGvroS8rEVR4iAvDzag7251CYHakPog
+El5kqvB6t6o41K988AuOVu1w8hNwUtQCaHjZx5d8xJrhFNQX+uXXFdrpAoGAbREoCBDYGtmGCtuP
+UaSG51od1RqW3sRC4Hy1hiBRoPM1cDqWWPsNBUX3ofTeSQy51SfrVWn5cWhKPgeme2agzEdlD2Hb
+P/QPIAbWjADJY2+TsdUQ1eroKRRWa9PAbJihvHpNGYlK1FBnf4JOvej9ayxFtw9PoYwIMRxxueVF
+I0ECgYAy3zkJYuTsTxQI3R9IosCMTr2DybuhZAW/Pe4wpQbsuJ8CG97kIoslR2Fyw3LcQ8gx0zrX
+ORgrtBame+rNGA4Cne6GRudgLHViFWvjfZ4oES+ihS/oVzDwp1cGhZ1rJJNDvvIQCqsPm74uWy06
+GManP9JHUzT5jlOaLSQcqjYn7w==";
+
+fn test_config() -> SamlConfig {
+    SamlConfig {
+        enabled: true,
+        sp_entity_id: SP_ENTITY_ID.to_string(),
+        acs_url: ACS_URL.to_string(),
+        idp_entity_id: IDP_ENTITY_ID.to_string(),
+        idp_sso_url: "https://idp.example.com/sso".to_string(),
+        idp_certificate: IDP_CERTIFICATE.to_string(),
+        display_name_attribute: Some("displayName".to_string()),
+        role_attribute: Some("groups".to_string()),
+        role_mapping: [
+            ("blog-admins".to_string(), "admin".to_string()),
+            ("blog-editors".to_string(), "editor".to_string()),
+        ]
+        .into_iter()
+        .collect(),
+        ..Default::default()
+    }
+}
+
+async fn test_service(config: SamlConfig) -> SamlService {
+    let pool = create_test_pool()
+        .await
+        .expect("Failed to create test pool");
+    migrations::run_migrations(&pool)
+        .await
+        .expect("Failed to run migrations");
+    SamlService::new(
+        config,
+        SqlxUserRepository::boxed(pool.clone()),
+        SqlxExternalIdentityRepository::boxed(pool),
+    )
+    .unwrap()
+}
+
+fn time(t: DateTime<Utc>) -> String {
+    t.to_rfc3339_opts(SecondsFormat::Secs, true)
+}
+
+/// Assertion XML with a `{SIG}` marker where the signature goes
+fn assertion_xml(id: &str, in_response_to: &str, groups: &[&str], now: DateTime<Utc>) -> String {
+    let groups: String = groups
+        .iter()
+        .map(|g| format!("<saml:AttributeValue>{}</saml:AttributeValue>", g))
+        .collect();
+    format!(
+        concat!(
+            r#"<saml:Assertion xmlns:saml="urn:oasis:names:tc:SAML:2.0:assertion" ID="{id}" Version="2.0" IssueInstant="{now}">"#,
+            r#"<saml:Issuer>{idp}</saml:Issuer>{{SIG}}"#,
+            r#"<saml:Subject><saml:NameID>alice</saml:NameID>"#,
+            r#"<saml:SubjectConfirmation Method="urn:oasis:names:tc:SAML:2.0:cm:bearer">"#,
+            r#"<saml:SubjectConfirmationData InResponseTo="{irt}" Recipient="{acs}" NotOnOrAfter="{until}"/>"#,
+            r#"</saml:SubjectConfirmation></saml:Subject>"#,
+            r#"<saml:Conditions NotBefore="{before}" NotOnOrAfter="{until}">"#,
+            r#"<saml:AudienceRestriction><saml:Audience>{sp}</saml:Audience></saml:AudienceRestriction>"#,
+            r#"</saml:Conditions>"#,
+            r#"<saml:AttributeStatement>"#,
+            r#"<saml:Attribute Name="email"><saml:AttributeValue>alice@example.com</saml:AttributeValue></saml:Attribute>"#,
+            r#"<saml:Attribute Name="displayName"><saml:AttributeValue>Alice</saml:AttributeValue></saml:Attribute>"#,
+            r#"<saml:Attribute Name="groups">{groups}</saml:Attribute>"#,
+            r#"</saml:AttributeStatement></saml:Assertion>"#
+        ),
+        id = id,
+        now = time(now),
+        idp = IDP_ENTITY_ID,
+        irt = in_response_to,
+        acs = ACS_URL,
+        until = time(now + Duration::minutes(5)),
+        before = time(now - Duration::minutes(1)),
+        sp = SP_ENTITY_ID,
+        groups = groups,
+    )
+}
+
+/// Response XML around `assertion`, with a `{SIG}` marker after the issuer
+fn response_xml(in_response_to: &str, assertion: &str) -> String {
+    format!(
+        concat!(
+            r#"<samlp:Response xmlns:samlp="urn:oasis:names:tc:SAML:2.0:protocol" xmlns:saml="urn:oasis:names:tc:SAML:2.0:assertion" "#,
+            r#"ID="_resp1" Version="2.0" InResponseTo="{}" Destination="{}">"#,
+            r#"<saml:Issuer>{}</saml:Issuer>{{SIG}}"#,
+            r#"<samlp:Status><samlp:StatusCode Value="urn:oasis:names:tc:SAML:2.0:status:Success"/></samlp:Status>"#,
+            r#"{}</samlp:Response>"#
+        ),
+        in_response_to, ACS_URL, IDP_ENTITY_ID, assertion,
+    )
+}
+
+/// Sign the element with `id` at its `{SIG}` marker; other markers are dropped
+fn sign(xml: &str, id: &str) -> String {
+    let marker = format!("ID=\"{}\"", id);
+    let at = xml.find(&marker).unwrap();
+    let sig_at = at + xml[at..].find("{SIG}").unwrap();
+    let unsigned = xml.replace("{SIG}", "");
+
+    let doc = roxmltree::Document::parse(&unsigned).unwrap();
+    let element = doc
+        .descendants()
+        .find(|n| n.attribute("ID") == Some(id))
+        .unwrap();
+    let canonical = xmldsig::canonicalize(element, None, &[]);
+    let digest_value =
+        BASE64.encode(digest::digest(&digest::SHA256, canonical.as_bytes()).as_ref());
+
+    let signature = format!(
+        concat!(
+            r#"<ds:Signature xmlns:ds="http://www.w3.org/2000/09/xmldsig#"><ds:SignedInfo>"#,
+            r#"<ds:CanonicalizationMethod Algorithm="http://www.w3.org/2001/10/xml-exc-c14n#"/>"#,
+            r#"<ds:SignatureMethod Algorithm="http://www.w3.org/2001/04/xmldsig-more#rsa-sha256"/>"#,
+            r##"<ds:Reference URI="#{}"><ds:Transforms>"##,
+            r#"<ds:Transform Algorithm="http://www.w3.org/2000/09/xmldsig#enveloped-signature"/>"#,
+            r#"<ds:Transform Algorithm="http://www.w3.org/2001/10/xml-exc-c14n#"/></ds:Transforms>"#,
+            r#"<ds:DigestMethod Algorithm="http://www.w3.org/2001/04/xmlenc#sha256"/>"#,
+            r#"<ds:DigestValue>{}</ds:DigestValue></ds:Reference></ds:SignedInfo>"#,
+            r#"<ds:SignatureValue>{{VALUE}}</ds:SignatureValue></ds:Signature>"#
+        ),
+        id, digest_value,
+    );
+    let with_signature =
+        format!("{}{}{}", &xml[..sig_at], signature, &xml[sig_at + 5..]).replace("{SIG}", "");
+
+    let doc = roxmltree::Document::parse(&with_signature).unwrap();
+    let signed_info = doc
+        .descendants()
+        .find(|n| n.has_tag_name((xmldsig::DSIG_NS, "SignedInfo")))
+        .unwrap();
+    let canonical = xmldsig::canonicalize(signed_info, None, &[]);
+    let key_der = BASE64
+        .decode(IDP_PRIVATE_KEY.replace('\n', "").as_bytes())
+        .unwrap();
+    let key = RsaKeyPair::from_pkcs8(&key_der).unwrap();
+    let mut value = vec![0u8; key.public().modulus_len()];
+    key.sign(
+        &ring::signature::RSA_PKCS1_SHA256,
+        &SystemRandom::new(),
+        canonical.as_bytes(),
+        &mut value,
+    )
+    .unwrap();
+    with_signature.replace("{VALUE}", &BASE64.encode(&value))
+}
+
+fn encode(xml: &str) -> String {
+    BASE64.encode(xml.as_bytes())
+}
+
+async fn expect_request(service: &SamlService, id: &str) {
+    service
+        .pending_requests
+        .write()
+        .await
+        .insert(id.to_string(), Utc::now() + Duration::minutes(5));
+}
+
+fn signed_response(groups: &[&str], now: DateTime<Utc>) -> String {
+    let assertion = sign(&assertion_xml("_a1", "_req1", groups, now), "_a1");
+    response_xml("_req1", &assertion)
+}
+
+#[test]
+fn extracts_rsa_key_from_certificate() {
+    let key = xmldsig::rsa_public_key_from_pem(IDP_CERTIFICATE).unwrap();
+    // SEQUENCE { modulus, exponent } for a 2048-bit key
+    assert_eq!(key[0], 0x30);
+    assert!(key.len() > 256);
+    assert!(xmldsig::rsa_public_key_from_pem("not a certificate").is_err());
+}
+
+#[tokio::test]
+async fn accepts_signed_assertion() {
+    let service = test_service(test_config()).await;
+    expect_request(&service, "_req1").await;
+    let xml = signed_response(&["blog-editors"], Utc::now());
+
+    let assertion = service.process_response(&encode(&xml)).await.unwrap();
+    assert_eq!(assertion.name_id, "alice");
+    assert_eq!(assertion.attribute("email"), Some("alice@example.com"));
+    assert_eq!(assertion.in_response_to.as_deref(), Some("_req1"));
+
+    // The same assertion cannot be used twice
+    expect_request(&service, "_req1").await;
+    assert!(matches!(
+        service.process_response(&encode(&xml)).await,
+        Err(SamlError::InvalidResponse(_))
+    ));
+}
+
+#[tokio::test]
+async fn accepts_signed_response() {
+    let service = test_service(test_config()).await;
+    expect_request(&service, "_req1").await;
+    let assertion = assertion_xml("_a1", "_req1", &[], Utc::now()).replace("{SIG}", "");
+    let xml = sign(&response_xml("_req1", &assertion), "_resp1");
+
+    assert!(service.process_response(&encode(&xml)).await.is_ok());
+}
+
+#[tokio::test]
+async fn rejects_tampered_assertion() {
+    let service = test_service(test_config()).await;
+    expect_request(&service, "_req1").await;
+    let xml = signed_response(&["blog-editors"], Utc::now()).replace("blog-editors", "blog-admins");
+
+    let err = service.process_response(&encode(&xml)).await.unwrap_err();
+    assert!(err.to_string().contains("Digest mismatch"), "{}", err);
+}
+
+#[tokio::test]
+async fn rejects_wrapped_assertion() {
+    let service = test_service(test_config()).await;
+    expect_request(&service, "_req1").await;
+    let now = Utc::now();
+    let signed = sign(&assertion_xml("_a1", "_req1", &[], now), "_a1");
+    let forged = assertion_xml("_a1", "_req1", &["blog-admins"], now).replace("{SIG}", "");
+    let xml = response_xml("_req1", &format!("{}{}", forged, signed));
+
+    assert!(service.process_response(&encode(&xml)).await.is_err());
+}
+
+#[tokio::test]
+async fn rejects_unsigned_and_misaddressed_responses() {
+    let service = test_service(test_config()).await;
+    let now = Utc::now();
+
+    expect_request(&service, "_req1").await;
+    let unsigned =
+        response_xml("_req1", &assertion_xml("_a1", "_req1", &[], now)).replace("{SIG}", "");
+    assert!(service.process_response(&encode(&unsigned)).await.is_err());
+
+    expect_request(&service, "_req1").await;
+    let other_audience = sign(
+        &assertion_xml("_a2", "_req1", &[], now).replace(SP_ENTITY_ID, "https://other.example.com"),
+        "_a2",
+    );
+    let xml = response_xml("_req1", &other_audience);
+    let err = service.process_response(&encode(&xml)).await.unwrap_err();
+    assert!(err.to_string().contains("not addressed"), "{}", err);
+
+    // Expired by the time it arrives
+    expect_request(&service, "_req1").await;
+    let xml = signed_response(&[], now);
+    assert!(service
+        .process_response_at(&encode(&xml), now + Duration::hours(1))
+        .await
+        .is_err());
+}
+
+#[tokio::test]
+async fn requires_a_pending_request_unless_idp_initiated_is_allowed() {
+    let xml = signed_response(&[], Utc::now());
+
+    let service = test_service(test_config()).await;
+    let err = service.process_response(&encode(&xml)).await.unwrap_err();
+    assert!(err.to_string().contains("pending request"), "{}", err);
+
+    let service = test_service(SamlConfig {
+        allow_idp_initiated: true,
+        ..test_config()
+    })
+    .await;
+    let assertion = assertion_xml("_a1", "", &[], Utc::now()).replace(r#" InResponseTo="""#, "");
+    let unsolicited = response_xml("", &sign(&assertion, "_a1")).replace(r#" InResponseTo="""#, "");
+    assert!(service
+        .process_response(&encode(&unsolicited))
+        .await
+        .is_ok());
+}
+
+#[tokio::test]
+async fn sign_in_provisions_users_and_syncs_roles() {
+    let service = test_service(test_config()).await;
+    let now = Utc::now();
+
+    expect_request(&service, "_req1").await;
+    let xml = signed_response(&["blog-editors", "staff"], now);
+    let assertion = service.process_response(&encode(&xml)).await.unwrap();
+    let user = service.sign_in(&assertion).await.unwrap();
+    assert_eq!(user.username, "alice");
+    assert_eq!(user.email, "alice@example.com");
+    assert_eq!(user.display_name.as_deref(), Some("Alice"));
+    assert_eq!(user.role, UserRole::Editor);
+
+    // The highest mapped role wins and is applied to the existing account
+    let mut promoted = assertion.clone();
+    promoted.attributes.insert(
+        "groups".to_string(),
+        vec!["blog-editors".to_string(), "blog-admins".to_string()],
+    );
+    let again = service.sign_in(&promoted).await.unwrap();
+    assert_eq!(again.id, user.id);
+    assert_eq!(again.role, UserRole::Admin);
+
+    // Without a mapped group or default role the user is refused
+    let mut outsider = assertion.clone();
+    outsider.attributes.insert("groups".to_string(), vec![]);
+    assert!(matches!(
+        service.sign_in(&outsider).await,
+        Err(SamlError::Forbidden(_))
+    ));
+}
+
+async fn create_local_user(service: &SamlService, username: &str, email: &str) -> User {
+    service
+        .user_repo
+        .create(&User::new(
+            username.to_string(),
+            email.to_string(),
+            "x".to_string(),
+            UserRole::Admin,
+        ))
+        .await
+        .unwrap()
+}
+
+#[tokio::test]
+async fn sign_in_never_takes_over_local_accounts() {
+    let service = test_service(test_config()).await;
+    expect_request(&service, "_req1").await;
+    let xml = signed_response(&["blog-editors"], Utc::now());
+    let assertion = service.process_response(&encode(&xml)).await.unwrap();
+
+    // A local admin named like the NameID is neither signed in nor demoted
+    let admin = create_local_user(&service, "alice", "admin@example.com").await;
+    assert!(matches!(
+        service.sign_in(&assertion).await,
+        Err(SamlError::Forbidden(_))
+    ));
+    let admin = service
+        .user_repo
+        .get_by_id(admin.id)
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(admin.role, UserRole::Admin);
+
+    // Nor is a local account with the asserted email, unless opted in
+    let service = test_service(test_config()).await;
+    create_local_user(&service, "owner", "alice@example.com").await;
+    assert!(matches!(
+        service.sign_in(&assertion).await,
+        Err(SamlError::Forbidden(_))
+    ));
+}
+
+#[tokio::test]
+async fn link_by_email_adopts_the_account_and_keeps_its_role() {
+    let service = test_service(SamlConfig {
+        link_by_email: true,
+        ..test_config()
+    })
+    .await;
+    expect_request(&service, "_req1").await;
+    let xml = signed_response(&["blog-editors"], Utc::now());
+    let assertion = service.process_response(&encode(&xml)).await.unwrap();
+    let owner = create_local_user(&service, "owner", "alice@example.com").await;
+
+    let user = service.sign_in(&assertion).await.unwrap();
+    assert_eq!(user.id, owner.id);
+    assert_eq!(user.role, UserRole::Admin);
+
+    // Later sign-ins go through the binding, still without a role change
+    let mut renamed = assertion.clone();
+    renamed
+        .attributes
+        .insert("email".to_string(), vec!["new@example.com".to_string()]);
+    let again = service.sign_in(&renamed).await.unwrap();
+    assert_eq!(again.id, owner.id);
+    assert_eq!(again.role, UserRole::Admin);
+}
+
+#[tokio::test]
+async fn login_url_carries_deflated_request() {
+    let service = test_service(test_config()).await;
+    let url = Url::parse(&service.login_url(Some("/manage/articles")).await.unwrap()).unwrap();
+    let params: HashMap<String, String> = url.query_pairs().into_owned().collect();
+    assert_eq!(params["RelayState"], "/manage/articles");
+
+    let deflated = BASE64.decode(params["SAMLRequest"].as_bytes()).unwrap();
+    let mut xml = String::new();
+    flate2::read::DeflateDecoder::new(deflated.as_slice())
+        .read_to_string(&mut xml)
+        .unwrap();
+    let doc = roxmltree::Document::parse(&xml).unwrap();
+    let id = doc.root_element().attribute("ID").unwrap();
+    assert!(service.pending_requests.read().await.contains_key(id));
+    assert!(xml.contains(SP_ENTITY_ID));
+
+    assert!(service.metadata_xml().contains(ACS_URL));
+}
@@ -0,0 +1,384 @@
+//! Enveloped XML signature verification
+//!
+//! Implements the subset of XML-DSig that SAML identity providers use:
+//! a single same-document reference to the signed element, the
+//! enveloped-signature and exclusive canonicalization transforms, SHA-256 or
+//! SHA-512 digests and RSA PKCS#1 v1.5 signatures.
+
+use data_encoding::BASE64;
+use ring::{digest, signature};
+use roxmltree::{Node, NodeId};
+
+pub(super) const DSIG_NS: &str = "http://www.w3.org/2000/09/xmldsig#";
+
+const EXC_C14N: &str = "http://www.w3.org/2001/10/xml-exc-c14n#";
+const EXC_C14N_WITH_COMMENTS: &str = "http://www.w3.org/2001/10/xml-exc-c14n#WithComments";
+const ENVELOPED_SIGNATURE: &str = "http://www.w3.org/2000/09/xmldsig#enveloped-signature";
+const RSA_SHA256: &str = "http://www.w3.org/2001/04/xmldsig-more#rsa-sha256";
+const RSA_SHA512: &str = "http://www.w3.org/2001/04/xmldsig-more#rsa-sha512";
+const SHA256: &str = "http://www.w3.org/2001/04/xmlenc#sha256";
+const SHA512: &str = "http://www.w3.org/2001/04/xmlenc#sha512";
+
+/// `rsaEncryption` algorithm identifier (1.2.840.113549.1.1.1)
+const RSA_ENCRYPTION_OID: &[u8] = &[0x2A, 0x86, 0x48, 0x86, 0xF7, 0x0D, 0x01, 0x01, 0x01];
+
+/// Extract the PKCS#1 RSA public key from a PEM-encoded X.509 certificate
+pub(super) fn rsa_public_key_from_pem(pem: &str) -> Result<Vec<u8>, String> {
+    let body: String = pem
+        .lines()
+        .map(str::trim)
+        .filter(|l| !l.is_empty() && !l.starts_with("-----"))
+        .collect();
+    let der = BASE64
+        .decode(body.as_bytes())
+        .map_err(|_| "Certificate is not valid base64".to_string())?;
+    rsa_public_key_from_der(&der).ok_or_else(|| "Certificate does not hold an RSA key".to_string())
+}
+
+/// Read one DER element, returning its tag, contents and the remaining input
+fn der_next(input: &[u8]) -> Option<(u8, &[u8], &[u8])> {
+    let (&tag, rest) = input.split_first()?;
+    let (&first, rest) = rest.split_first()?;
+    let (len, rest) = if first < 0x80 {
+        (first as usize, rest)
+    } else {
+        let count = (first & 0x7F) as usize;
+        if count == 0 || count > 4 || rest.len() < count {
+            return None;
+        }
+        let len = rest[..count]
+            .iter()
+            .fold(0usize, |acc, &b| (acc << 8) | b as usize);
+        (len, &rest[count..])
+    };
+    if rest.len() < len {
+        return None;
+    }
+    Some((tag, &rest[..len], &rest[len..]))
+}
+
+fn rsa_public_key_from_der(cert: &[u8]) -> Option<Vec<u8>> {
+    let (_, certificate, _) = der_next(cert)?;
+    let (_, tbs, _) = der_next(certificate)?;
+    let mut fields = std::iter::successors(der_next(tbs), |(_, _, rest)| der_next(rest));
+    // Skip the optional explicit version, serial, signature, issuer,
+    // validity and subject
+    let (first_tag, _, _) = fields.next()?;
+    let skip = if first_tag == 0xA0 { 5 } else { 4 };
+    let (_, spki, _) = fields.nth(skip)?;
+    let (_, algorithm, key) = der_next(spki)?;
+    let (_, oid, _) = der_next(algorithm)?;
+    if oid != RSA_ENCRYPTION_OID {
+        return None;
+    }
+    let (tag, bits, _) = der_next(key)?;
+    // BIT STRING with no unused bits
+    if tag != 0x03 || bits.first() != Some(&0) {
+        return None;
+    }
+    Some(bits[1..].to_vec())
+}
+
+fn dsig_child<'a, 'input>(node: Node<'a, 'input>, name: &str) -> Option<Node<'a, 'input>> {
+    node.children().find(|c| {
+        c.is_element() && c.tag_name().namespace() == Some(DSIG_NS) && c.tag_name().name() == name
+    })
+}
+
+fn dsig_children<'a, 'input>(
+    node: Node<'a, 'input>,
+    name: &'a str,
+) -> impl Iterator<Item = Node<'a, 'input>> + 'a {
+    node.children().filter(move |c| {
+        c.is_element() && c.tag_name().namespace() == Some(DSIG_NS) && c.tag_name().name() == name
+    })
+}
+
+/// Prefixes listed in an `InclusiveNamespaces` child of a c14n method
+fn inclusive_prefixes(method: Node) -> Vec<String> {
+    method
+        .children()
+        .find(|c| c.is_element() && c.tag_name().name() == "InclusiveNamespaces")
+        .and_then(|n| n.attribute("PrefixList"))
+        .map(|list| list.split_whitespace().map(String::from).collect())
+        .unwrap_or_default()
+}
+
+/// Whether `element` carries a direct `ds:Signature` child
+pub(super) fn is_signed(element: Node) -> bool {
+    dsig_child(element, "Signature").is_some()
+}
+
+/// Verify the enveloped signature of `element` against an RSA public key
+///
+/// The reference must point at `element` by its `ID` attribute, and that ID
+/// must be unique in the document so a wrapped copy cannot be verified in
+/// place of the element the caller reads.
+pub(super) fn verify_enveloped(element: Node, public_key: &[u8]) -> Result<(), String> {
+    let signature = dsig_child(element, "Signature").ok_or("Element is not signed")?;
+    let signed_info = dsig_child(signature, "SignedInfo").ok_or("Missing SignedInfo")?;
+
+    let c14n_method = dsig_child(signed_info, "CanonicalizationMethod")
+        .ok_or("Missing CanonicalizationMethod")?;
+    match c14n_method.attribute("Algorithm") {
+        Some(EXC_C14N) => {}
+        other => return Err(format!("Unsupported canonicalization: {:?}", other)),
+    }
+    let verification_alg: &signature::RsaParameters =
+        match dsig_child(signed_info, "SignatureMethod").and_then(|m| m.attribute("Algorithm")) {
+            Some(RSA_SHA256) => &signature::RSA_PKCS1_2048_8192_SHA256,
+            Some(RSA_SHA512) => &signature::RSA_PKCS1_2048_8192_SHA512,
+            other => return Err(format!("Unsupported signature method: {:?}", other)),
+        };
+
+    let mut references = dsig_children(signed_info, "Reference");
+    let reference = references.next().ok_or("Missing Reference")?;
+    if references.next().is_some() {
+        return Err("Multiple references are not supported".into());
+    }
+
+    let id = element.attribute("ID").ok_or("Signed element has no ID")?;
+    if reference.attribute("URI").and_then(|u| u.strip_prefix('#')) != Some(id) {
+        return Err("Reference does not point at the signed element".into());
+    }
+    let same_id = element
+        .document()
+        .descendants()
+        .filter(|n| n.attribute("ID") == Some(id))
+        .count();
+    if same_id != 1 {
+        return Err("Duplicate ID in document".into());
+    }
+
+    // Only the transforms IdPs actually emit are accepted
+    let mut prefixes = Vec::new();
+    if let Some(transforms) = dsig_child(reference, "Transforms") {
+        for transform in dsig_children(transforms, "Transform") {
+            match transform.attribute("Algorithm") {
+                Some(ENVELOPED_SIGNATURE) => {}
+                // Comments are dropped for same-document references either way
+                Some(EXC_C14N) | Some(EXC_C14N_WITH_COMMENTS) => {
+                    prefixes = inclusive_prefixes(transform);
+                }
+                other => return Err(format!("Unsupported transform: {:?}", other)),
+            }
+        }
+    }
+
+    let digest_alg =
+        match dsig_child(reference, "DigestMethod").and_then(|m| m.attribute("Algorithm")) {
+            Some(SHA256) => &digest::SHA256,
+            Some(SHA512) => &digest::SHA512,
+            other => return Err(format!("Unsupported digest method: {:?}", other)),
+        };
+    let expected_digest = decode_base64_text(dsig_child(reference, "DigestValue"))?;
+    let canonical = canonicalize(element, Some(signature.id()), &prefixes);
+    if digest::digest(digest_alg, canonical.as_bytes()).as_ref() != expected_digest.as_slice() {
+        return Err("Digest mismatch".into());
+    }
+
+    let signature_value = decode_base64_text(dsig_child(signature, "SignatureValue"))?;
+    let canonical_signed_info = canonicalize(signed_info, None, &inclusive_prefixes(c14n_method));
+    signature::UnparsedPublicKey::new(verification_alg, public_key)
+        .verify(canonical_signed_info.as_bytes(), &signature_value)
+        .map_err(|_| "Signature mismatch".to_string())
+}
+
+fn decode_base64_text(node: Option<Node>) -> Result<Vec<u8>, String> {
+    let text: String = node
+        .and_then(|n| n.text())
+        .unwrap_or_default()
+        .chars()
+        .filter(|c| !c.is_whitespace())
+        .collect();
+    BASE64
+        .decode(text.as_bytes())
+        .map_err(|_| "Invalid base64 in signature".to_string())
+}
+
+/// Exclusive XML canonicalization (without comments) of a subtree
+///
+/// `exclude` drops one descendant element, which is how the
+/// enveloped-signature transform is applied.
+pub(super) fn canonicalize(node: Node, exclude: Option<NodeId>, inclusive: &[String]) -> String {
+    let mut out = String::new();
+    write_element(node, exclude, inclusive, &[], &mut out);
+    out
+}
+
+/// Qualified name of an element as written in the source document
+fn element_qname<'input>(node: Node<'_, 'input>) -> &'input str {
+    let input = node.document().input_text();
+    let start = node.range().start + 1;
+    let len = input[start..]
+        .find(|c: char| c.is_whitespace() || c == '>' || c == '/')
+        .unwrap_or(input.len() - start);
+    &input[start..start + len]
+}
+
+fn prefix_of(qname: &str) -> &str {
+    qname.split_once(':').map(|(p, _)| p).unwrap_or("")
+}
+
+fn write_element(
+    node: Node,
+    exclude: Option<NodeId>,
+    inclusive: &[String],
+    rendered: &[(String, String)],
+    out: &mut String,
+) {
+    let input = node.document().input_text();
+    let name = element_qname(node);
+
+    // Namespaces visibly utilized by the element and its attributes, plus the
+    // InclusiveNamespaces prefix list
+    let mut utilized: Vec<&str> = vec![prefix_of(name)];
+    for attr in node.attributes() {
+        let qname = &input[attr.range_qname()];
+        if qname.contains(':') {
+            utilized.push(prefix_of(qname));
+        }
+    }
+    utilized.extend(
+        inclusive
+            .iter()
+            .map(|p| if p == "#default" { "" } else { p.as_str() }),
+    );
+    utilized.retain(|p| *p != "xml");
+    utilized.sort_unstable();
+    utilized.dedup();
+
+    let mut in_output = rendered.to_vec();
+    let mut declarations = Vec::new();
+    for prefix in utilized {
+        let uri = if prefix.is_empty() {
+            node.default_namespace().unwrap_or("")
+        } else {
+            match node.lookup_namespace_uri(Some(prefix)) {
+                Some(uri) => uri,
+                None => continue,
+            }
+        };
+        let current = in_output
+            .iter()
+            .find(|(p, _)| p == prefix)
+            .map(|(_, u)| u.as_str());
+        let needed = match current {
+            Some(current) => current != uri,
+            None => !(prefix.is_empty() && uri.is_empty()),
+        };
+        if needed {
+            in_output.retain(|(p, _)| p != prefix);
+            in_output.push((prefix.to_string(), uri.to_string()));
+            declarations.push((prefix, uri));
+        }
+    }
+
+    out.push('<');
+    out.push_str(name);
+    for (prefix, uri) in declarations {
+        if prefix.is_empty() {
+            out.push_str(" xmlns=\"");
+        } else {
+            out.push_str(" xmlns:");
+            out.push_str(prefix);
+            out.push_str("=\"");
+        }
+        escape_attribute(uri, out);
+        out.push('"');
+    }
+
+    let mut attributes: Vec<_> = node.attributes().collect();
+    attributes.sort_by(|a, b| {
+        (a.namespace().unwrap_or(""), a.name()).cmp(&(b.namespace().unwrap_or(""), b.name()))
+    });
+    for attr in attributes {
+        out.push(' ');
+        out.push_str(&input[attr.range_qname()]);
+        out.push_str("=\"");
+        escape_attribute(attr.value(), out);
+        out.push('"');
+    }
+    out.push('>');
+
+    for child in node.children() {
+        if child.is_element() {
+            if Some(child.id()) != exclude {
+                write_element(child, exclude, inclusive, &in_output, out);
+            }
+        } else if child.is_text() {
+            escape_text(child.text().unwrap_or(""), out);
+        } else if child.is_pi() {
+            if let Some(pi) = child.pi() {
+                out.push_str("<?");
+                out.push_str(pi.target);
+                if let Some(value) = pi.value {
+                    out.push(' ');
+                    out.push_str(value);
+                }
+                out.push_str("?>");
+            }
+        }
+    }
+
+    out.push_str("</");
+    out.push_str(name);
+    out.push('>');
+}
+
+fn escape_text(text: &str, out: &mut String) {
+    for c in text.chars() {
+        match c {
+            '&' => out.push_str("&amp;"),
+            '<' => out.push_str("&lt;"),
+            '>' => out.push_str("&gt;"),
+            '\r' => out.push_str("&#xD;"),
+            c => out.push(c),
+        }
+    }
+}
+
+fn escape_attribute(value: &str, out: &mut String) {
+    for c in value.chars() {
+        match c {
+            '&' => out.push_str("&amp;"),
+            '<' => out.push_str("&lt;"),
+            '"' => out.push_str("&quot;"),
+            '\t' => out.push_str("&#x9;"),
+            '\n' => out.push_str("&#xA;"),
+            '\r' => out.push_str("&#xD;"),
+            c => out.push(c),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn canonicalization_renders_only_utilized_namespaces() {
+        let xml = r#"<r:Root xmlns:r="urn:r" xmlns:u="urn:unused" xmlns:a="urn:a"><r:Child b="2" a:x='1 &amp; "q"' a="1"><Plain>t&lt;</Plain><a:Leaf/></r:Child></r:Root>"#;
+        let doc = roxmltree::Document::parse(xml).unwrap();
+        let child = doc.root_element().first_child().unwrap();
+
+        assert_eq!(
+            canonicalize(child, None, &[]),
+            r#"<r:Child xmlns:a="urn:a" xmlns:r="urn:r" a="1" b="2" a:x="1 &amp; &quot;q&quot;"><Plain>t&lt;</Plain><a:Leaf></a:Leaf></r:Child>"#
+        );
+        // Inclusive prefixes are rendered even when unused
+        assert!(canonicalize(child, None, &["u".to_string()]).contains(r#"xmlns:u="urn:unused""#));
+    }
+
+    #[test]
+    fn canonicalization_skips_excluded_element() {
+        let xml = r#"<A xmlns="urn:d" ID="1"><B/><S/></A>"#;
+        let doc = roxmltree::Document::parse(xml).unwrap();
+        let s = doc.descendants().find(|n| n.has_tag_name("S")).unwrap();
+
+        assert_eq!(
+            canonicalize(doc.root_element(), Some(s.id()), &[]),
+            r#"<A xmlns="urn:d" ID="1"><B></B></A>"#
+        );
+    }
+}
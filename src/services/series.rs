@@ -0,0 +1,284 @@
+//! Article series service.
+//!
+//! Visitors only see the published articles of a series; previous/next
+//! links skip drafts so navigation never leads to a missing page.
+
+use crate::db::repositories::SeriesRepository;
+use crate::models::{
+    Series, SeriesArticle, SeriesArticlesInput, SeriesInput, SeriesNavigation, SeriesWithArticles,
+};
+use chrono::Utc;
+use std::sync::Arc;
+
+const MAX_SLUG_LEN: usize = 100;
+const MAX_TITLE_LEN: usize = 200;
+const MAX_DESCRIPTION_LEN: usize = 2000;
+const MAX_ARTICLES: usize = 500;
+
+/// Errors returned by the series service
+#[derive(Debug, thiserror::Error)]
+pub enum SeriesError {
+    #[error("{0} not found")]
+    NotFound(&'static str),
+
+    #[error("{0}")]
+    Validation(String),
+
+    #[error("Internal error: {0}")]
+    Internal(#[from] anyhow::Error),
+}
+
+pub struct SeriesService {
+    repo: Arc<dyn SeriesRepository>,
+}
+
+impl SeriesService {
+    pub fn new(repo: Arc<dyn SeriesRepository>) -> Self {
+        Self { repo }
+    }
+
+    pub async fn list(&self) -> Result<Vec<Series>, SeriesError> {
+        Ok(self.repo.list().await?)
+    }
+
+    pub async fn get(&self, id: i64) -> Result<Series, SeriesError> {
+        self.repo
+            .get(id)
+            .await?
+            .ok_or(SeriesError::NotFound("Series"))
+    }
+
+    pub async fn create(&self, input: SeriesInput) -> Result<Series, SeriesError> {
+        let series = series_from_input(0, input)?;
+        if self.repo.get_by_slug(&series.slug).await?.is_some() {
+            return Err(SeriesError::Validation(format!(
+                "Series '{}' already exists",
+                series.slug
+            )));
+        }
+        Ok(self.repo.create(&series).await?)
+    }
+
+    /// Replace a series' slug, title and description
+    pub async fn update(&self, id: i64, input: SeriesInput) -> Result<Series, SeriesError> {
+        let existing = self.get(id).await?;
+        let series = series_from_input(id, input)?;
+        if series.slug != existing.slug && self.repo.get_by_slug(&series.slug).await?.is_some() {
+            return Err(SeriesError::Validation(format!(
+                "Series '{}' already exists",
+                series.slug
+            )));
+        }
+        Ok(self
+            .repo
+            .update(&Series {
+                created_at: existing.created_at,
+                ..series
+            })
+            .await?)
+    }
+
+    /// Delete a series; its articles stay published on their own
+    pub async fn delete(&self, id: i64) -> Result<(), SeriesError> {
+        if !self.repo.delete(id).await? {
+            return Err(SeriesError::NotFound("Series"));
+        }
+        Ok(())
+    }
+
+    /// All articles of a series, drafts included
+    pub async fn articles(&self, id: i64) -> Result<Vec<SeriesArticle>, SeriesError> {
+        self.get(id).await?;
+        Ok(self.repo.list_articles(id, false).await?)
+    }
+
+    /// Replace the articles of a series with `input.article_ids`, in that
+    /// order; articles already in another series move to this one
+    pub async fn set_articles(
+        &self,
+        id: i64,
+        input: SeriesArticlesInput,
+    ) -> Result<Vec<SeriesArticle>, SeriesError> {
+        self.get(id).await?;
+        let ids = input.article_ids;
+        if ids.len() > MAX_ARTICLES {
+            return Err(SeriesError::Validation(format!(
+                "A series cannot have more than {} articles",
+                MAX_ARTICLES
+            )));
+        }
+        for (i, article_id) in ids.iter().enumerate() {
+            if ids[..i].contains(article_id) {
+                return Err(SeriesError::Validation(format!(
+                    "Article {} is listed twice",
+                    article_id
+                )));
+            }
+        }
+        let existing = self.repo.existing_article_ids(&ids).await?;
+        if let Some(missing) = ids.iter().find(|id| !existing.contains(id)) {
+            return Err(SeriesError::Validation(format!(
+                "Article {} does not exist",
+                missing
+            )));
+        }
+        self.repo.set_articles(id, &ids).await?;
+        Ok(self.repo.list_articles(id, false).await?)
+    }
+
+    /// A series with its published articles
+    pub async fn get_with_articles(&self, slug: &str) -> Result<SeriesWithArticles, SeriesError> {
+        let series = self
+            .repo
+            .get_by_slug(slug)
+            .await?
+            .ok_or(SeriesError::NotFound("Series"))?;
+        let articles = self.repo.list_articles(series.id, true).await?;
+        Ok(SeriesWithArticles { series, articles })
+    }
+
+    /// The series of a published article with its neighbours; `None` when
+    /// the article is in no series
+    pub async fn navigation(
+        &self,
+        article_id: i64,
+    ) -> Result<Option<SeriesNavigation>, SeriesError> {
+        let Some(series_id) = self.repo.series_of_article(article_id).await? else {
+            return Ok(None);
+        };
+        let Some(series) = self.repo.get(series_id).await? else {
+            return Ok(None);
+        };
+        let articles = self.repo.list_articles(series_id, true).await?;
+        Ok(navigation(&series, &articles, article_id))
+    }
+
+    /// Take a deleted article out of its series
+    pub async fn remove_article(&self, article_id: i64) -> Result<(), SeriesError> {
+        Ok(self.repo.remove_article(article_id).await?)
+    }
+}
+
+/// Position of `article_id` among `articles` with its neighbours
+pub fn navigation(
+    series: &Series,
+    articles: &[SeriesArticle],
+    article_id: i64,
+) -> Option<SeriesNavigation> {
+    let index = articles.iter().position(|a| a.id == article_id)?;
+    Some(SeriesNavigation {
+        id: series.id,
+        slug: series.slug.clone(),
+        title: series.title.clone(),
+        index: index + 1,
+        total: articles.len(),
+        prev: index.checked_sub(1).map(|i| articles[i].clone()),
+        next: articles.get(index + 1).cloned(),
+    })
+}
+
+fn series_from_input(id: i64, input: SeriesInput) -> Result<Series, SeriesError> {
+    let slug = input.slug.trim().to_lowercase();
+    if slug.is_empty() {
+        return Err(SeriesError::Validation("Slug cannot be empty".to_string()));
+    }
+    if slug.len() > MAX_SLUG_LEN {
+        return Err(SeriesError::Validation(format!(
+            "Slug cannot exceed {} characters",
+            MAX_SLUG_LEN
+        )));
+    }
+    if !slug
+        .chars()
+        .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+    {
+        return Err(SeriesError::Validation(
+            "Slug may only contain letters, digits, '-' and '_'".to_string(),
+        ));
+    }
+    let title = input.title.trim();
+    if title.is_empty() {
+        return Err(SeriesError::Validation("Title cannot be empty".to_string()));
+    }
+    if title.chars().count() > MAX_TITLE_LEN {
+        return Err(SeriesError::Validation(format!(
+            "Title cannot exceed {} characters",
+            MAX_TITLE_LEN
+        )));
+    }
+    let description = input.description.trim();
+    if description.chars().count() > MAX_DESCRIPTION_LEN {
+        return Err(SeriesError::Validation(format!(
+            "Description cannot exceed {} characters",
+            MAX_DESCRIPTION_LEN
+        )));
+    }
+    let now = Utc::now();
+    Ok(Series {
+        id,
+        slug,
+        title: title.to_string(),
+        description: description.to_string(),
+        created_at: now,
+        updated_at: now,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn series() -> Series {
+        let now = Utc::now();
+        Series {
+            id: 7,
+            slug: "rust".to_string(),
+            title: "Rust".to_string(),
+            description: String::new(),
+            created_at: now,
+            updated_at: now,
+        }
+    }
+
+    fn article(id: i64) -> SeriesArticle {
+        SeriesArticle {
+            id,
+            slug: format!("part-{}", id),
+            title: format!("Part {}", id),
+            thumbnail: None,
+            status: "published".to_string(),
+            published_at: None,
+            position: id as i32,
+        }
+    }
+
+    #[test]
+    fn navigation_links_neighbours() {
+        let articles = [article(1), article(2), article(3)];
+        let nav = navigation(&series(), &articles, 2).unwrap();
+        assert_eq!((nav.index, nav.total), (2, 3));
+        assert_eq!(nav.prev.map(|a| a.id), Some(1));
+        assert_eq!(nav.next.map(|a| a.id), Some(3));
+
+        let first = navigation(&series(), &articles, 1).unwrap();
+        assert!(first.prev.is_none());
+        assert!(navigation(&series(), &articles, 4).is_none());
+    }
+
+    #[test]
+    fn rejects_bad_slugs_and_titles() {
+        let input = |slug: &str, title: &str| SeriesInput {
+            slug: slug.to_string(),
+            title: title.to_string(),
+            description: String::new(),
+        };
+        assert_eq!(
+            series_from_input(0, input(" Rust-101 ", "Rust"))
+                .unwrap()
+                .slug,
+            "rust-101"
+        );
+        assert!(series_from_input(0, input("rust 101", "Rust")).is_err());
+        assert!(series_from_input(0, input("rust", "  ")).is_err());
+    }
+}
@@ -28,6 +28,8 @@ pub mod keys {
     pub const SITE_URL: &str = "site_url";
     pub const ABOUT_PROFILE: &str = "about_profile";
     pub const ABOUT_NAV_ENABLED: &str = "about_nav_enabled";
+    /// Locale of theme strings rendered with `t()`
+    pub const DEFAULT_LOCALE: &str = "default_locale";
 }
 
 /// Permalink structure presets
@@ -275,6 +277,32 @@ pub fn generate_article_url(
         .replace("{day}", &date.format("%d").to_string())
 }
 
+/// Extract the slug from a request path that follows the permalink structure
+///
+/// Returns None when the structure has no `{slug}` or the path does not match
+/// it; date placeholders only need to be numeric.
+pub fn match_article_slug<'a>(structure: &str, path: &'a str) -> Option<&'a str> {
+    let pattern: Vec<&str> = structure.trim_matches('/').split('/').collect();
+    let segments: Vec<&str> = path.trim_matches('/').split('/').collect();
+    if pattern.len() != segments.len() {
+        return None;
+    }
+    let mut slug = None;
+    for (part, segment) in pattern.iter().zip(&segments) {
+        match *part {
+            "{slug}" if !segment.is_empty() => slug = Some(*segment),
+            "{id}" | "{year}" | "{month}" | "{day}" => {
+                if segment.is_empty() || !segment.bytes().all(|b| b.is_ascii_digit()) {
+                    return None;
+                }
+            }
+            literal if literal == *segment => {}
+            _ => return None,
+        }
+    }
+    slug
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -292,6 +320,27 @@ mod tests {
         assert_eq!(url, "/posts/42");
     }
 
+    #[test]
+    fn test_match_article_slug() {
+        assert_eq!(
+            match_article_slug("/posts/{slug}", "/posts/hello-world/"),
+            Some("hello-world")
+        );
+        assert_eq!(
+            match_article_slug("/{year}/{month}/{slug}", "/2024/03/hello-world"),
+            Some("hello-world")
+        );
+        assert_eq!(
+            match_article_slug("/{year}/{month}/{slug}", "/about/team/x"),
+            None
+        );
+        assert_eq!(
+            match_article_slug("/posts/{slug}", "/pages/hello-world"),
+            None
+        );
+        assert_eq!(match_article_slug("/posts/{id}", "/posts/42"), None);
+    }
+
     #[test]
     fn test_generate_article_url_date_based() {
         let date = chrono::Utc.with_ymd_and_hms(2024, 3, 15, 12, 0, 0).unwrap();
@@ -0,0 +1,516 @@
+//! Hunspell dictionary reader
+//!
+//! Reads a `.aff`/`.dic` pair and checks words against it. Covers what
+//! spelling lookups in common dictionaries need: `SET` (UTF-8 or
+//! ISO8859-1), `FLAG` (char, `long`, `num`, `UTF-8`), `AF` flag aliases,
+//! `PFX`/`SFX` rules with conditions and cross products, `NEEDAFFIX`,
+//! `FORBIDDENWORD`, `TRY` and `REP`. Compounding and twofold suffixes are
+//! not supported; such words are reported as misspelled.
+
+use anyhow::{bail, Context, Result};
+use std::collections::{HashMap, HashSet};
+use std::path::Path;
+
+type Flag = u32;
+
+/// How flags are written in the dictionary
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum FlagMode {
+    Char,
+    Long,
+    Num,
+}
+
+/// One position of an affix condition
+#[derive(Debug, Clone)]
+enum CondPart {
+    Any,
+    Set { chars: Vec<char>, negated: bool },
+}
+
+impl CondPart {
+    fn matches(&self, c: char) -> bool {
+        match self {
+            CondPart::Any => true,
+            CondPart::Set { chars, negated } => chars.contains(&c) != *negated,
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+struct Affix {
+    flag: Flag,
+    cross_product: bool,
+    strip: String,
+    add: String,
+    condition: Vec<CondPart>,
+}
+
+impl Affix {
+    /// Undo this suffix on `word`, giving the stem it applies to
+    fn strip_suffix(&self, word: &str) -> Option<String> {
+        let base = word.strip_suffix(self.add.as_str())?;
+        let stem = format!("{}{}", base, self.strip);
+        if stem.is_empty() || base.is_empty() {
+            return None;
+        }
+        let chars: Vec<char> = stem.chars().collect();
+        if chars.len() < self.condition.len() {
+            return None;
+        }
+        let tail = &chars[chars.len() - self.condition.len()..];
+        self.condition
+            .iter()
+            .zip(tail)
+            .all(|(part, &c)| part.matches(c))
+            .then_some(stem)
+    }
+
+    /// Undo this prefix on `word`, giving the stem it applies to
+    fn strip_prefix(&self, word: &str) -> Option<String> {
+        let base = word.strip_prefix(self.add.as_str())?;
+        let stem = format!("{}{}", self.strip, base);
+        if stem.is_empty() || base.is_empty() {
+            return None;
+        }
+        let mut chars = stem.chars();
+        self.condition
+            .iter()
+            .all(|part| chars.next().is_some_and(|c| part.matches(c)))
+            .then_some(stem)
+    }
+}
+
+/// A loaded Hunspell dictionary
+#[derive(Debug, Default)]
+pub struct Dictionary {
+    words: HashMap<String, Vec<Vec<Flag>>>,
+    prefixes: Vec<Affix>,
+    suffixes: Vec<Affix>,
+    need_affix: Option<Flag>,
+    forbidden: Option<Flag>,
+    try_chars: Vec<char>,
+    replacements: Vec<(String, String)>,
+}
+
+impl Dictionary {
+    /// Load `<base>.aff` and `<base>.dic`
+    pub fn load(base: &Path) -> Result<Self> {
+        let aff_path = base.with_extension("aff");
+        let dic_path = base.with_extension("dic");
+        let aff = std::fs::read(&aff_path)
+            .with_context(|| format!("Failed to read {}", aff_path.display()))?;
+        let dic = std::fs::read(&dic_path)
+            .with_context(|| format!("Failed to read {}", dic_path.display()))?;
+        Self::parse(&aff, &dic)
+    }
+
+    /// Parse the contents of an affix file and a word list
+    pub fn parse(aff: &[u8], dic: &[u8]) -> Result<Self> {
+        // SET must be known before the rest of the file can be decoded
+        let latin1 = match declared_encoding(aff).as_deref() {
+            None | Some("UTF-8") => false,
+            Some("ISO8859-1") | Some("ISO-8859-1") => true,
+            Some(other) => bail!("Unsupported dictionary encoding {}", other),
+        };
+        let decode = |bytes: &[u8]| -> Result<String> {
+            if latin1 {
+                Ok(bytes.iter().map(|&b| b as char).collect())
+            } else {
+                String::from_utf8(bytes.to_vec()).context("Dictionary is not valid UTF-8")
+            }
+        };
+        let aff = decode(aff)?;
+        let dic = decode(dic)?;
+
+        let mut dict = Dictionary::default();
+        let mut mode = FlagMode::Char;
+        let mut aliases: Vec<Vec<Flag>> = Vec::new();
+        let mut aliases_started = false;
+        // Affix headers seen so far: flag -> cross product
+        let mut headers: HashMap<(bool, Flag), bool> = HashMap::new();
+
+        for line in aff.lines() {
+            let fields: Vec<&str> = line.split_whitespace().collect();
+            let Some(&keyword) = fields.first() else {
+                continue;
+            };
+            match keyword {
+                "FLAG" => {
+                    mode = match fields.get(1).copied() {
+                        Some("long") => FlagMode::Long,
+                        Some("num") => FlagMode::Num,
+                        _ => FlagMode::Char,
+                    }
+                }
+                // The first AF line is the count
+                "AF" if !aliases_started => aliases_started = true,
+                "AF" => aliases.push(parse_flags(fields.get(1).unwrap_or(&""), mode)),
+                "NEEDAFFIX" | "PSEUDOROOT" => {
+                    dict.need_affix = fields.get(1).and_then(|f| parse_flag(f, mode))
+                }
+                "FORBIDDENWORD" => dict.forbidden = fields.get(1).and_then(|f| parse_flag(f, mode)),
+                "TRY" => {
+                    dict.try_chars = fields
+                        .get(1)
+                        .map(|s| s.chars().collect())
+                        .unwrap_or_default()
+                }
+                "REP" if fields.len() >= 3 => dict
+                    .replacements
+                    .push((fields[1].replace('_', " "), fields[2].replace('_', " "))),
+                "PFX" | "SFX" if fields.len() >= 4 => {
+                    let prefix = keyword == "PFX";
+                    let Some(flag) = parse_flag(fields[1], mode) else {
+                        continue;
+                    };
+                    match headers.get(&(prefix, flag)) {
+                        // Header line: PFX flag cross_product count
+                        None => {
+                            headers.insert((prefix, flag), fields[2] == "Y");
+                        }
+                        // Rule line: PFX flag strip add condition
+                        Some(&cross_product) => {
+                            let affix = Affix {
+                                flag,
+                                cross_product,
+                                strip: zero_as_empty(fields[2]).to_string(),
+                                add: zero_as_empty(fields[3].split('/').next().unwrap_or(""))
+                                    .to_string(),
+                                condition: parse_condition(fields.get(4).copied().unwrap_or(".")),
+                            };
+                            if prefix {
+                                dict.prefixes.push(affix);
+                            } else {
+                                dict.suffixes.push(affix);
+                            }
+                        }
+                    }
+                }
+                _ => {}
+            }
+        }
+
+        let mut lines = dic.lines();
+        // The first line is the approximate word count
+        lines.next();
+        for line in lines {
+            let entry = line.split(['\t', ' ']).next().unwrap_or("").trim();
+            if entry.is_empty() {
+                continue;
+            }
+            let (word, flags) = split_entry(entry);
+            let flags = match flags {
+                None => Vec::new(),
+                Some(f) if !aliases.is_empty() => f
+                    .parse::<usize>()
+                    .ok()
+                    .and_then(|i| aliases.get(i.wrapping_sub(1)))
+                    .cloned()
+                    .unwrap_or_default(),
+                Some(f) => parse_flags(f, mode),
+            };
+            dict.words.entry(word.to_string()).or_default().push(flags);
+        }
+        if dict.words.is_empty() {
+            bail!("Dictionary has no words");
+        }
+        Ok(dict)
+    }
+
+    /// Whether `word` is spelled correctly
+    pub fn check(&self, word: &str) -> bool {
+        if word.is_empty() {
+            return true;
+        }
+        if self.check_exact(word) {
+            return true;
+        }
+        let lower = word.to_lowercase();
+        let mut chars = word.chars();
+        let first_upper = chars.next().is_some_and(char::is_uppercase);
+        let all_upper = word.chars().all(|c| !c.is_lowercase());
+        if all_upper {
+            // "PARIS" is fine when "Paris" or "paris" is
+            if self.check_exact(&capitalize(&lower)) || self.check_exact(&lower) {
+                return true;
+            }
+        } else if first_upper && chars.all(|c| !c.is_uppercase()) {
+            // Sentence-initial capital
+            return self.check_exact(&lower);
+        }
+        false
+    }
+
+    fn check_exact(&self, word: &str) -> bool {
+        if self.is_forbidden(word) {
+            return false;
+        }
+        if self.has_root(word, |flags| !self.flag_set(flags, self.need_affix)) {
+            return true;
+        }
+        for sfx in &self.suffixes {
+            if let Some(stem) = sfx.strip_suffix(word) {
+                if self.has_root(&stem, |flags| flags.contains(&sfx.flag)) {
+                    return true;
+                }
+            }
+        }
+        for pfx in &self.prefixes {
+            let Some(stem) = pfx.strip_prefix(word) else {
+                continue;
+            };
+            if self.has_root(&stem, |flags| flags.contains(&pfx.flag)) {
+                return true;
+            }
+            if !pfx.cross_product {
+                continue;
+            }
+            for sfx in self.suffixes.iter().filter(|s| s.cross_product) {
+                if let Some(root) = sfx.strip_suffix(&stem) {
+                    if self.has_root(&root, |flags| {
+                        flags.contains(&pfx.flag) && flags.contains(&sfx.flag)
+                    }) {
+                        return true;
+                    }
+                }
+            }
+        }
+        false
+    }
+
+    fn has_root(&self, word: &str, accept: impl Fn(&[Flag]) -> bool) -> bool {
+        self.words.get(word).is_some_and(|homonyms| {
+            homonyms
+                .iter()
+                .any(|flags| !self.flag_set(flags, self.forbidden) && accept(flags))
+        })
+    }
+
+    fn is_forbidden(&self, word: &str) -> bool {
+        self.forbidden.is_some()
+            && self.words.get(word).is_some_and(|homonyms| {
+                homonyms
+                    .iter()
+                    .all(|flags| self.flag_set(flags, self.forbidden))
+            })
+    }
+
+    fn flag_set(&self, flags: &[Flag], flag: Option<Flag>) -> bool {
+        flag.is_some_and(|f| flags.contains(&f))
+    }
+
+    /// Up to `limit` correctly spelled words close to `word`
+    pub fn suggest(&self, word: &str, limit: usize) -> Vec<String> {
+        let mut found = Vec::new();
+        let mut seen = HashSet::new();
+        let mut consider = |candidate: String, found: &mut Vec<String>| {
+            if found.len() < limit
+                && candidate != word
+                && seen.insert(candidate.clone())
+                && candidate.split(' ').all(|part| self.check(part))
+            {
+                found.push(candidate);
+            }
+        };
+
+        // Known misspellings first
+        for (from, to) in &self.replacements {
+            for (i, _) in word.match_indices(from.as_str()) {
+                let candidate = format!("{}{}{}", &word[..i], to, &word[i + from.len()..]);
+                consider(candidate, &mut found);
+            }
+        }
+
+        let chars: Vec<char> = word.chars().collect();
+        let alphabet: Vec<char> = if self.try_chars.is_empty() {
+            let mut letters: Vec<char> = word.to_lowercase().chars().collect();
+            letters.dedup();
+            letters
+        } else {
+            self.try_chars.clone()
+        };
+        let join = |parts: &[char]| parts.iter().collect::<String>();
+
+        // Swapped neighbours
+        for i in 0..chars.len().saturating_sub(1) {
+            let mut swapped = chars.clone();
+            swapped.swap(i, i + 1);
+            consider(join(&swapped), &mut found);
+        }
+        // Wrong letter
+        for i in 0..chars.len() {
+            for &c in &alphabet {
+                if c != chars[i] {
+                    let mut replaced = chars.clone();
+                    replaced[i] = c;
+                    consider(join(&replaced), &mut found);
+                }
+            }
+        }
+        // Extra letter
+        for i in 0..chars.len() {
+            let mut removed = chars.clone();
+            removed.remove(i);
+            consider(join(&removed), &mut found);
+        }
+        // Missing letter
+        for i in 0..=chars.len() {
+            for &c in &alphabet {
+                let mut inserted = chars.clone();
+                inserted.insert(i, c);
+                consider(join(&inserted), &mut found);
+            }
+        }
+        // Missing space
+        for i in 1..chars.len() {
+            consider(
+                format!("{} {}", join(&chars[..i]), join(&chars[i..])),
+                &mut found,
+            );
+        }
+        found
+    }
+}
+
+/// The `SET` value of an affix file, read before decoding it
+fn declared_encoding(aff: &[u8]) -> Option<String> {
+    aff.split(|&b| b == b'\n').find_map(|line| {
+        let line = std::str::from_utf8(line).ok()?.trim();
+        line.strip_prefix("SET ")
+            .map(|enc| enc.trim().to_ascii_uppercase())
+    })
+}
+
+fn zero_as_empty(s: &str) -> &str {
+    if s == "0" {
+        ""
+    } else {
+        s
+    }
+}
+
+/// Split a `.dic` entry into word and flags, honouring `\/` escapes
+fn split_entry(entry: &str) -> (String, Option<&str>) {
+    let bytes = entry.as_bytes();
+    let mut i = 0;
+    while i < bytes.len() {
+        match bytes[i] {
+            b'\\' => i += 2,
+            b'/' if i > 0 => {
+                return (entry[..i].replace("\\/", "/"), Some(&entry[i + 1..]));
+            }
+            _ => i += 1,
+        }
+    }
+    (entry.replace("\\/", "/"), None)
+}
+
+fn parse_flag(s: &str, mode: FlagMode) -> Option<Flag> {
+    parse_flags(s, mode).into_iter().next()
+}
+
+fn parse_flags(s: &str, mode: FlagMode) -> Vec<Flag> {
+    match mode {
+        FlagMode::Char => s.chars().map(|c| c as Flag).collect(),
+        FlagMode::Long => s
+            .chars()
+            .collect::<Vec<_>>()
+            .chunks(2)
+            .map(|pair| pair.iter().fold(0, |acc, &c| (acc << 16) | c as Flag))
+            .collect(),
+        FlagMode::Num => s.split(',').filter_map(|n| n.trim().parse().ok()).collect(),
+    }
+}
+
+fn parse_condition(s: &str) -> Vec<CondPart> {
+    if s == "." {
+        return Vec::new();
+    }
+    let mut parts = Vec::new();
+    let mut chars = s.chars();
+    while let Some(c) = chars.next() {
+        match c {
+            '.' => parts.push(CondPart::Any),
+            '[' => {
+                let mut set = Vec::new();
+                let mut negated = false;
+                for (i, c) in chars.by_ref().enumerate() {
+                    match c {
+                        ']' => break,
+                        '^' if i == 0 => negated = true,
+                        c => set.push(c),
+                    }
+                }
+                parts.push(CondPart::Set {
+                    chars: set,
+                    negated,
+                });
+            }
+            c => parts.push(CondPart::Set {
+                chars: vec![c],
+                negated: false,
+            }),
+        }
+    }
+    parts
+}
+
+fn capitalize(word: &str) -> String {
+    let mut chars = word.chars();
+    match chars.next() {
+        Some(first) => first.to_uppercase().chain(chars).collect(),
+        None => String::new(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const AFF: &str = "SET UTF-8\nTRY esianrtolcdugmphbyfvkwz\nREP 1\nREP f ph\n\
+        PFX A Y 1\nPFX A 0 re .\n\
+        SFX D Y 2\nSFX D 0 d e\nSFX D y ied [^aeiou]y\n\
+        SFX S Y 1\nSFX S 0 s .\n";
+    const DIC: &str = "4\ncreate/ADS\ncopy/AD\nParis\nphone/S\n";
+
+    fn dict() -> Dictionary {
+        Dictionary::parse(AFF.as_bytes(), DIC.as_bytes()).unwrap()
+    }
+
+    #[test]
+    fn affixes_and_conditions() {
+        let d = dict();
+        for word in [
+            "create",
+            "created",
+            "recreated",
+            "creates",
+            "copied",
+            "recopied",
+        ] {
+            assert!(d.check(word), "{}", word);
+        }
+        // `y -> ied` needs a consonant before the y; no `d` on `copy` itself
+        assert!(!d.check("copyd"));
+        assert!(!d.check("copys"));
+        assert!(!d.check("crate"));
+    }
+
+    #[test]
+    fn capitalization() {
+        let d = dict();
+        assert!(d.check("Created"));
+        assert!(d.check("CREATED"));
+        assert!(d.check("PARIS"));
+        assert!(!d.check("paris"));
+    }
+
+    #[test]
+    fn suggestions() {
+        let d = dict();
+        assert_eq!(d.suggest("fones", 3), vec!["phones"]);
+        assert_eq!(d.suggest("craete", 3), vec!["create"]);
+        assert!(d.suggest("creted", 5).contains(&"created".to_string()));
+    }
+}
@@ -0,0 +1,310 @@
+//! Language detection, spelling and grammar hints for drafts
+//!
+//! The admin editor posts the draft text and gets back annotated ranges to
+//! highlight. The language is detected (or given by the editor) and picks
+//! the Hunspell dictionary from `spellcheck.dictionaries`; a few simple
+//! grammar checks (repeated words, doubled spaces, space before a comma or
+//! period) run for every language.
+//!
+//! Code, URLs, HTML tags and Markdown link targets are skipped. Offsets are
+//! UTF-16 code units, the way JavaScript indexes strings.
+
+pub mod hunspell;
+
+use anyhow::{anyhow, Result};
+use once_cell::sync::Lazy;
+use regex::Regex;
+use serde::Serialize;
+use std::collections::HashMap;
+use std::ops::Range;
+use std::path::Path;
+use whatlang::Lang;
+
+use crate::config::SpellcheckConfig;
+use hunspell::Dictionary;
+
+/// Parts of a draft that are not prose
+static SKIPPED: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(
+        r"(?ms)^(```|~~~).*?^(```|~~~)|`[^`\n]+`|<[^>\n]+>|\]\([^)\n]*\)|[a-zA-Z][a-zA-Z0-9+.-]*://\S+|\S+@\S+\.\w+",
+    )
+    .unwrap()
+});
+
+static WORD: Lazy<Regex> = Lazy::new(|| Regex::new(r"\p{L}[\p{L}\p{M}'’]*").unwrap());
+
+static DOUBLE_SPACE: Lazy<Regex> = Lazy::new(|| Regex::new(r"\S( {2,})").unwrap());
+
+static SPACE_BEFORE_PUNCT: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"\p{L}( +)[,.](?:\s|$)").unwrap());
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum IssueKind {
+    Spelling,
+    RepeatedWord,
+    DoubleSpace,
+    SpaceBeforePunctuation,
+}
+
+/// A range of the draft to highlight
+#[derive(Debug, Clone, Serialize)]
+pub struct Issue {
+    pub kind: IssueKind,
+    /// UTF-16 offset of the first unit
+    pub start: usize,
+    /// UTF-16 offset after the last unit
+    pub end: usize,
+    pub text: String,
+    pub suggestions: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct DetectedLanguage {
+    /// ISO 639-3 code
+    pub code: &'static str,
+    pub name: &'static str,
+    /// 0 when given by the caller
+    pub confidence: f64,
+    pub reliable: bool,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct SpellcheckReport {
+    pub language: Option<DetectedLanguage>,
+    /// Whether a dictionary was available for spelling
+    pub spelling_checked: bool,
+    pub issues: Vec<Issue>,
+}
+
+/// Spell checker holding the configured dictionaries
+#[derive(Default)]
+pub struct SpellcheckService {
+    dictionaries: HashMap<Lang, Dictionary>,
+    max_suggestions: usize,
+}
+
+impl SpellcheckService {
+    /// Load every configured dictionary
+    pub fn load(config: &SpellcheckConfig) -> Result<Self> {
+        let mut dictionaries = HashMap::new();
+        for (code, path) in &config.dictionaries {
+            let lang = Lang::from_code(code.as_str())
+                .ok_or_else(|| anyhow!("Unknown spellcheck language code {}", code))?;
+            let dictionary = Dictionary::load(Path::new(path))?;
+            dictionaries.insert(lang, dictionary);
+        }
+        Ok(Self {
+            dictionaries,
+            max_suggestions: config.max_suggestions,
+        })
+    }
+
+    /// Languages with a dictionary, as ISO 639-3 codes
+    pub fn languages(&self) -> Vec<&'static str> {
+        let mut codes: Vec<_> = self.dictionaries.keys().map(|l| l.code()).collect();
+        codes.sort_unstable();
+        codes
+    }
+
+    /// Check `text`, in `language` (ISO 639-3) or the detected one
+    pub fn check(&self, text: &str, language: Option<&str>) -> Result<SpellcheckReport> {
+        let skipped: Vec<Range<usize>> = SKIPPED.find_iter(text).map(|m| m.range()).collect();
+        let in_skipped = |range: &Range<usize>| {
+            skipped
+                .iter()
+                .any(|s| range.start < s.end && s.start < range.end)
+        };
+
+        let language = match language {
+            Some(code) => {
+                let lang = Lang::from_code(code)
+                    .ok_or_else(|| anyhow!("Unknown language code {}", code))?;
+                Some(DetectedLanguage {
+                    code: lang.code(),
+                    name: lang.eng_name(),
+                    confidence: 0.0,
+                    reliable: true,
+                })
+            }
+            None => {
+                let prose = SKIPPED.replace_all(text, " ");
+                whatlang::detect(&prose).map(|info| DetectedLanguage {
+                    code: info.lang().code(),
+                    name: info.lang().eng_name(),
+                    confidence: info.confidence(),
+                    reliable: info.is_reliable(),
+                })
+            }
+        };
+        let dictionary = language
+            .as_ref()
+            .and_then(|l| Lang::from_code(l.code))
+            .and_then(|lang| self.dictionaries.get(&lang));
+
+        let mut found: Vec<(IssueKind, Range<usize>, Vec<String>)> = Vec::new();
+        let mut previous: Option<(Range<usize>, String)> = None;
+        for m in WORD.find_iter(text) {
+            let word = m.as_str().trim_end_matches(['\'', '’']);
+            let range = m.start()..m.start() + word.len();
+            if in_skipped(&range) {
+                previous = None;
+                continue;
+            }
+            let lower = word.to_lowercase();
+            if let Some((prev, prev_lower)) = &previous {
+                // Only whitespace, and no line break, between the two
+                let gap = &text[prev.end..range.start];
+                if *prev_lower == lower
+                    && gap.chars().all(char::is_whitespace)
+                    && !gap.contains('\n')
+                {
+                    found.push((
+                        IssueKind::RepeatedWord,
+                        prev.start..range.end,
+                        vec![text[prev.start..prev.end].to_string()],
+                    ));
+                }
+            }
+            if let Some(dict) = dictionary {
+                if !dict.check(word) {
+                    found.push((
+                        IssueKind::Spelling,
+                        range.clone(),
+                        dict.suggest(word, self.max_suggestions),
+                    ));
+                }
+            }
+            previous = Some((range, lower));
+        }
+
+        for caps in DOUBLE_SPACE.captures_iter(text) {
+            let spaces = caps.get(1).unwrap().range();
+            // Trailing spaces are a Markdown line break
+            let mid_line = text[spaces.end..]
+                .chars()
+                .next()
+                .is_some_and(|c| !c.is_whitespace());
+            if mid_line && !in_skipped(&spaces) {
+                found.push((IssueKind::DoubleSpace, spaces, vec![" ".to_string()]));
+            }
+        }
+        for caps in SPACE_BEFORE_PUNCT.captures_iter(text) {
+            let spaces = caps.get(1).unwrap().range();
+            if !in_skipped(&spaces) {
+                found.push((
+                    IssueKind::SpaceBeforePunctuation,
+                    spaces,
+                    vec![String::new()],
+                ));
+            }
+        }
+
+        found.sort_by_key(|(_, range, _)| range.start);
+        let mut offsets = Utf16Offsets::new(text);
+        let issues = found
+            .into_iter()
+            .map(|(kind, range, suggestions)| {
+                let start = offsets.at(range.start);
+                Issue {
+                    kind,
+                    start,
+                    end: start + text[range.clone()].encode_utf16().count(),
+                    text: text[range].to_string(),
+                    suggestions,
+                }
+            })
+            .collect();
+
+        Ok(SpellcheckReport {
+            language,
+            spelling_checked: dictionary.is_some(),
+            issues,
+        })
+    }
+}
+
+/// Converts increasing byte offsets to UTF-16 offsets in one pass
+struct Utf16Offsets<'a> {
+    text: &'a str,
+    byte: usize,
+    utf16: usize,
+}
+
+impl<'a> Utf16Offsets<'a> {
+    fn new(text: &'a str) -> Self {
+        Self {
+            text,
+            byte: 0,
+            utf16: 0,
+        }
+    }
+
+    fn at(&mut self, byte: usize) -> usize {
+        self.utf16 += self.text[self.byte..byte].encode_utf16().count();
+        self.byte = byte;
+        self.utf16
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn service() -> SpellcheckService {
+        let aff = "SET UTF-8\nTRY etaoinshrdlucmfwypvbgkjqxz\nSFX S Y 1\nSFX S 0 s .\n";
+        let dic = "8\nthe\ncat/S\nsat\non\nmat\na\nsee\nhere\n";
+        let mut dictionaries = HashMap::new();
+        dictionaries.insert(
+            Lang::Eng,
+            Dictionary::parse(aff.as_bytes(), dic.as_bytes()).unwrap(),
+        );
+        SpellcheckService {
+            dictionaries,
+            max_suggestions: 3,
+        }
+    }
+
+    #[test]
+    fn reports_spelling_and_grammar_ranges() {
+        let text = "The the cat sat on teh mat ,  see `cdoe` here";
+        let report = service().check(text, Some("eng")).unwrap();
+        assert!(report.spelling_checked);
+        let kinds: Vec<_> = report
+            .issues
+            .iter()
+            .map(|i| (i.kind, i.text.as_str()))
+            .collect();
+        assert_eq!(
+            kinds,
+            vec![
+                (IssueKind::RepeatedWord, "The the"),
+                (IssueKind::Spelling, "teh"),
+                (IssueKind::SpaceBeforePunctuation, " "),
+                (IssueKind::DoubleSpace, "  "),
+            ]
+        );
+        assert_eq!(report.issues[1].suggestions, vec!["the"]);
+        assert_eq!((report.issues[1].start, report.issues[1].end), (19, 22));
+    }
+
+    #[test]
+    fn offsets_are_utf16() {
+        let report = service().check("😀 teh cats", Some("eng")).unwrap();
+        assert_eq!(report.issues.len(), 1);
+        // The emoji is two UTF-16 units
+        assert_eq!((report.issues[0].start, report.issues[0].end), (3, 6));
+    }
+
+    #[test]
+    fn detects_language_without_dictionary() {
+        let report = service()
+            .check(
+                "Der schnelle braune Fuchs springt über den faulen Hund und läuft davon.",
+                None,
+            )
+            .unwrap();
+        assert_eq!(report.language.unwrap().code, "deu");
+        assert!(!report.spelling_checked);
+    }
+}
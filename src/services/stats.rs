@@ -0,0 +1,129 @@
+//! Traffic and engagement statistics
+//!
+//! Views are counted per article per UTC day as they are recorded by
+//! `POST /view/{article_id}`; comment metrics come from the comments table.
+
+use anyhow::Result;
+use chrono::{Duration, NaiveDate};
+use std::sync::Arc;
+
+use crate::db::repositories::{DailyComments, DailyTraffic, StatsRepository, TopContent};
+
+/// Longest range a single query may cover
+pub const MAX_RANGE_DAYS: i64 = 3660;
+
+/// Statistics queries over a date range
+pub struct StatsService {
+    repo: Arc<dyn StatsRepository>,
+}
+
+impl StatsService {
+    pub fn new(repo: Arc<dyn StatsRepository>) -> Self {
+        Self { repo }
+    }
+
+    /// Views for every day in `[from, to)`, including days without any
+    pub async fn traffic(&self, from: NaiveDate, to: NaiveDate) -> Result<Vec<DailyTraffic>> {
+        let rows = self.repo.traffic_by_day(&day(from), &day(to)).await?;
+        Ok(fill_days(
+            from,
+            to,
+            rows,
+            |d| &d.day,
+            |day| DailyTraffic {
+                day,
+                views: 0,
+                articles: 0,
+            },
+        ))
+    }
+
+    /// Comments created on every day in `[from, to)`, including days without any
+    pub async fn comments(&self, from: NaiveDate, to: NaiveDate) -> Result<Vec<DailyComments>> {
+        let rows = self.repo.comments_by_day(&day(from), &day(to)).await?;
+        Ok(fill_days(
+            from,
+            to,
+            rows,
+            |d| &d.day,
+            |day| DailyComments {
+                day,
+                approved: 0,
+                pending: 0,
+                spam: 0,
+            },
+        ))
+    }
+
+    /// One page of articles ranked by views in `[from, to)`
+    pub async fn top_content(
+        &self,
+        from: NaiveDate,
+        to: NaiveDate,
+        offset: i64,
+        limit: i64,
+    ) -> Result<Vec<TopContent>> {
+        self.repo
+            .top_content(&day(from), &day(to), offset, limit)
+            .await
+    }
+}
+
+pub(crate) fn day(date: NaiveDate) -> String {
+    date.format("%Y-%m-%d").to_string()
+}
+
+/// Merge sorted per-day rows into a continuous series, inserting `empty`
+/// rows for the missing days
+pub(crate) fn fill_days<T>(
+    from: NaiveDate,
+    to: NaiveDate,
+    rows: Vec<T>,
+    day_of: impl Fn(&T) -> &String,
+    empty: impl Fn(String) -> T,
+) -> Vec<T> {
+    let mut rows = rows.into_iter().peekable();
+    let mut series = Vec::new();
+    let mut date = from;
+    while date < to {
+        let key = day(date);
+        match rows.next_if(|row| *day_of(row) == key) {
+            Some(row) => series.push(row),
+            None => series.push(empty(key)),
+        }
+        date += Duration::days(1);
+    }
+    series
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn fills_missing_days() {
+        let from = NaiveDate::from_ymd_opt(2024, 2, 28).unwrap();
+        let to = NaiveDate::from_ymd_opt(2024, 3, 2).unwrap();
+        let rows = vec![DailyTraffic {
+            day: "2024-02-29".to_string(),
+            views: 7,
+            articles: 2,
+        }];
+        let series = fill_days(
+            from,
+            to,
+            rows,
+            |d| &d.day,
+            |day| DailyTraffic {
+                day,
+                views: 0,
+                articles: 0,
+            },
+        );
+        let views: Vec<(&str, i64)> = series.iter().map(|d| (d.day.as_str(), d.views)).collect();
+        assert_eq!(
+            views,
+            vec![("2024-02-28", 0), ("2024-02-29", 7), ("2024-03-01", 0)]
+        );
+    }
+}
@@ -0,0 +1,299 @@
+//! Delta sync for offline clients
+//!
+//! `GET /api/v1/sync?since=<checkpoint>` lists published articles and pages
+//! created or updated after the checkpoint, and the ids of those deleted or
+//! unpublished since. Articles in private categories or restricted to an
+//! audience are never listed; restricting one reports it as deleted. Clients store the returned `checkpoint` and pass it
+//! back next time. Deletions are remembered for [`TOMBSTONE_RETENTION_DAYS`];
+//! an older checkpoint answers with `reset: true` and the full list, and the
+//! client should drop anything it has that is not in it.
+
+use anyhow::Result;
+use chrono::{DateTime, Duration, Utc};
+use serde::Serialize;
+use std::sync::Arc;
+
+use crate::db::repositories::{SyncArticle, SyncPage, SyncRepository, Tombstone};
+
+/// How long deletions are kept for sync
+pub const TOMBSTONE_RETENTION_DAYS: i64 = 90;
+
+/// An item to remove from the client's copy
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct DeletedItem {
+    pub id: i64,
+    pub deleted_at: DateTime<Utc>,
+}
+
+/// Changes to one kind of content
+#[derive(Debug, Serialize)]
+pub struct SyncChanges<T> {
+    pub created: Vec<T>,
+    pub updated: Vec<T>,
+    pub deleted: Vec<DeletedItem>,
+}
+
+impl<T> Default for SyncChanges<T> {
+    fn default() -> Self {
+        Self {
+            created: Vec::new(),
+            updated: Vec::new(),
+            deleted: Vec::new(),
+        }
+    }
+}
+
+/// Everything that changed since a checkpoint
+#[derive(Debug, Serialize)]
+pub struct SyncResult {
+    /// Pass as `since` on the next sync
+    pub checkpoint: DateTime<Utc>,
+    /// The checkpoint was missing or too old; this is a full listing
+    pub reset: bool,
+    pub articles: SyncChanges<SyncArticle>,
+    pub pages: SyncChanges<SyncPage>,
+}
+
+/// Computes deltas from the sync repository
+pub struct SyncService {
+    repo: Arc<dyn SyncRepository>,
+}
+
+impl SyncService {
+    pub fn new(repo: Arc<dyn SyncRepository>) -> Self {
+        Self { repo }
+    }
+
+    /// Changes after `since`; `None` asks for a full listing
+    pub async fn changes_since(&self, since: Option<DateTime<Utc>>) -> Result<SyncResult> {
+        // Taken before reading, so changes made meanwhile show up next time
+        let checkpoint = Utc::now();
+        let horizon = checkpoint - Duration::days(TOMBSTONE_RETENTION_DAYS);
+        if let Err(e) = self.repo.prune_tombstones(horizon).await {
+            tracing::warn!(error = %e, "failed to prune sync tombstones");
+        }
+
+        let (since, reset) = match since {
+            Some(since) if since >= horizon => (since, false),
+            _ => (DateTime::<Utc>::UNIX_EPOCH, true),
+        };
+        let articles = self.repo.articles_changed_since(since).await?;
+        let pages = self.repo.pages_changed_since(since).await?;
+        let tombstones = if reset {
+            Vec::new()
+        } else {
+            self.repo.tombstones_since(since).await?
+        };
+
+        Ok(SyncResult {
+            checkpoint,
+            reset,
+            articles: classify(
+                articles,
+                reset,
+                tombstones_of(&tombstones, "article"),
+                |a| (a.id, is_listed(a), created_after(a, since)),
+                |a| a.updated_at,
+            ),
+            pages: classify(
+                pages,
+                reset,
+                tombstones_of(&tombstones, "page"),
+                |p| (p.id, p.status == "published", p.created_at > since),
+                |p| p.updated_at,
+            ),
+        })
+    }
+}
+
+/// Published where anonymous clients may read it
+fn is_listed(article: &SyncArticle) -> bool {
+    article.status == "published" && article.public
+}
+
+/// New to a client that synced at `since`: created or first published since
+fn created_after(article: &SyncArticle, since: DateTime<Utc>) -> bool {
+    article.created_at > since || article.published_at.is_some_and(|at| at > since)
+}
+
+fn tombstones_of(tombstones: &[Tombstone], entity_type: &str) -> Vec<DeletedItem> {
+    tombstones
+        .iter()
+        .filter(|t| t.entity_type == entity_type)
+        .map(|t| DeletedItem {
+            id: t.entity_id,
+            deleted_at: t.deleted_at,
+        })
+        .collect()
+}
+
+/// Sort changed items into created/updated, and report items that are no
+/// longer listed as deleted
+///
+/// `key` gives an item's id, whether clients may see it, and whether it is
+/// new since the checkpoint.
+fn classify<T>(
+    items: Vec<T>,
+    reset: bool,
+    mut deleted: Vec<DeletedItem>,
+    key: impl Fn(&T) -> (i64, bool, bool),
+    updated_at: impl Fn(&T) -> DateTime<Utc>,
+) -> SyncChanges<T> {
+    let mut changes = SyncChanges::default();
+    for item in items {
+        let (id, listed, is_new) = key(&item);
+        if !listed {
+            // Drafts and private articles never reach clients; items that
+            // stopped being listed must be dropped
+            if !reset {
+                deleted.push(DeletedItem {
+                    id,
+                    deleted_at: updated_at(&item),
+                });
+            }
+        } else if reset || is_new {
+            changes.created.push(item);
+        } else {
+            changes.updated.push(item);
+        }
+    }
+    deleted.sort_by_key(|d| d.deleted_at);
+    changes.deleted = deleted;
+    changes
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::repositories::{
+        ArticleRepository, ReaderGroupRepository, SqlxArticleRepository, SqlxReaderGroupRepository,
+        SqlxSyncRepository,
+    };
+    use crate::db::{create_test_pool, migrations};
+    use crate::models::{ArticleAudience, ArticleStatus, CreateArticleInput, UserRole};
+    use chrono::TimeZone;
+
+    fn at(hour: u32) -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(2024, 5, 1, hour, 0, 0).unwrap()
+    }
+
+    fn article(id: i64, status: &str, created: u32, published: Option<u32>) -> SyncArticle {
+        SyncArticle {
+            id,
+            slug: format!("a{}", id),
+            title: format!("A{}", id),
+            category_id: 1,
+            thumbnail: None,
+            status: status.to_string(),
+            public: true,
+            published_at: published.map(at),
+            created_at: at(created),
+            updated_at: at(12),
+        }
+    }
+
+    fn ids(items: &[SyncArticle]) -> Vec<i64> {
+        items.iter().map(|a| a.id).collect()
+    }
+
+    #[test]
+    fn classifies_changes_since_checkpoint() {
+        let since = at(6);
+        let items = vec![
+            article(1, "published", 8, Some(8)),
+            article(2, "published", 1, Some(2)),
+            article(3, "published", 1, Some(9)),
+            article(4, "draft", 1, Some(2)),
+            article(5, "archived", 1, None),
+            SyncArticle {
+                public: false,
+                ..article(6, "published", 8, Some(8))
+            },
+        ];
+        let tombstones = vec![DeletedItem {
+            id: 9,
+            deleted_at: at(7),
+        }];
+        let changes = classify(
+            items,
+            false,
+            tombstones,
+            |a| (a.id, is_listed(a), created_after(a, since)),
+            |a| a.updated_at,
+        );
+        assert_eq!(ids(&changes.created), vec![1, 3]);
+        assert_eq!(ids(&changes.updated), vec![2]);
+        let deleted: Vec<i64> = changes.deleted.iter().map(|d| d.id).collect();
+        assert_eq!(deleted, vec![9, 4, 5, 6]);
+    }
+
+    #[test]
+    fn reset_lists_published_items_only() {
+        let since = DateTime::<Utc>::UNIX_EPOCH;
+        let changes = classify(
+            vec![
+                article(1, "published", 1, Some(1)),
+                article(2, "draft", 1, None),
+            ],
+            true,
+            Vec::new(),
+            |a| (a.id, is_listed(a), created_after(a, since)),
+            |a| a.updated_at,
+        );
+        assert_eq!(ids(&changes.created), vec![1]);
+        assert!(changes.updated.is_empty() && changes.deleted.is_empty());
+    }
+
+    #[tokio::test]
+    async fn articles_with_an_audience_are_not_synced() {
+        let pool = create_test_pool()
+            .await
+            .expect("Failed to create test pool");
+        migrations::run_migrations(&pool)
+            .await
+            .expect("Failed to run migrations");
+        let author_id = sqlx::query(
+            "INSERT INTO users (username, email, password_hash, role) VALUES ('author', 'author@example.com', 'hash', 'author')",
+        )
+        .execute(pool.as_sqlite().unwrap())
+        .await
+        .unwrap()
+        .last_insert_rowid();
+        let since = Utc::now() - Duration::seconds(1);
+
+        let articles = SqlxArticleRepository::new(pool.clone());
+        let mut article_ids = Vec::new();
+        for slug in ["open", "members-only"] {
+            let mut input = CreateArticleInput::new(
+                slug.to_string(),
+                slug.to_string(),
+                "Hello".to_string(),
+                author_id,
+                1,
+            );
+            input.status = Some(ArticleStatus::Published);
+            article_ids.push(articles.create(&input).await.unwrap().id);
+        }
+        SqlxReaderGroupRepository::new(pool.clone())
+            .set_audience(
+                article_ids[1],
+                &ArticleAudience {
+                    roles: vec![UserRole::Author],
+                    group_ids: Vec::new(),
+                },
+            )
+            .await
+            .unwrap();
+        let service = SyncService::new(SqlxSyncRepository::boxed(pool));
+
+        let full = service.changes_since(None).await.unwrap();
+        assert_eq!(ids(&full.articles.created), vec![article_ids[0]]);
+        assert!(full.articles.deleted.is_empty());
+
+        let delta = service.changes_since(Some(since)).await.unwrap();
+        assert_eq!(ids(&delta.articles.created), vec![article_ids[0]]);
+        assert!(delta.articles.updated.is_empty());
+        let deleted: Vec<i64> = delta.articles.deleted.iter().map(|d| d.id).collect();
+        assert_eq!(deleted, vec![article_ids[1]]);
+    }
+}
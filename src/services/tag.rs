@@ -34,6 +34,14 @@ pub enum TagServiceError {
     #[error("Tag not found: {0}")]
     NotFound(String),
 
+    /// Another tag already has the name
+    #[error("Tag name already exists: {0}")]
+    DuplicateName(String),
+
+    /// Another tag already has the slug
+    #[error("Tag slug already exists: {0}")]
+    DuplicateSlug(String),
+
     /// Validation error
     #[error("Validation error: {0}")]
     ValidationError(String),
@@ -120,6 +128,52 @@ impl TagService {
         Ok(created)
     }
 
+    /// Find a tag by slug or name, or create it with the given slug
+    ///
+    /// Unlike [`Self::create_or_get`] the slug is not derived from the name,
+    /// so importers can keep the tag URLs of the source blog.
+    ///
+    /// # Errors
+    /// - `ValidationError` if the slug or name is empty
+    pub async fn get_or_create_with_slug(
+        &self,
+        slug: &str,
+        name: &str,
+    ) -> Result<Tag, TagServiceError> {
+        let (slug, name) = (slug.trim(), name.trim());
+        if slug.is_empty() || name.is_empty() {
+            return Err(TagServiceError::ValidationError(
+                "Tag slug and name cannot be empty".to_string(),
+            ));
+        }
+
+        if let Some(existing) = self
+            .repo
+            .get_by_slug(slug)
+            .await
+            .context("Failed to check existing tag")?
+        {
+            return Ok(existing);
+        }
+        if let Some(existing) = self
+            .repo
+            .get_by_name(name)
+            .await
+            .context("Failed to check existing tag")?
+        {
+            return Ok(existing);
+        }
+
+        let created = self
+            .repo
+            .create(&Tag::new(slug.to_string(), name.to_string()))
+            .await
+            .context("Failed to create tag")?;
+        self.invalidate_cache().await?;
+
+        Ok(created)
+    }
+
     /// Get tag by slug
     ///
     /// # Arguments
@@ -268,6 +322,136 @@ impl TagService {
         Ok(())
     }
 
+    /// Rename a tag
+    ///
+    /// The slug is generated from the name when not given. The old slug
+    /// keeps pointing at the tag, see [`Self::get_by_previous_slug`].
+    ///
+    /// # Errors
+    /// - `NotFound` if the tag doesn't exist
+    /// - `ValidationError` if the name or slug is empty
+    /// - `DuplicateName` / `DuplicateSlug` if another tag uses them
+    pub async fn rename(
+        &self,
+        id: i64,
+        name: &str,
+        slug: Option<&str>,
+    ) -> Result<Tag, TagServiceError> {
+        let name = name.trim();
+        if name.is_empty() {
+            return Err(TagServiceError::ValidationError(
+                "Tag name cannot be empty".to_string(),
+            ));
+        }
+        let slug = generate_tag_slug(slug.unwrap_or(name));
+        if slug.is_empty() {
+            return Err(TagServiceError::ValidationError(
+                "Tag slug cannot be empty".to_string(),
+            ));
+        }
+
+        let tag = self
+            .repo
+            .get_by_id(id)
+            .await
+            .context("Failed to get tag")?
+            .ok_or_else(|| TagServiceError::NotFound(format!("Tag with ID {} not found", id)))?;
+        if let Some(other) = self
+            .repo
+            .get_by_name(name)
+            .await
+            .context("Failed to check existing tag")?
+            .filter(|other| other.id != tag.id)
+        {
+            return Err(TagServiceError::DuplicateName(other.name));
+        }
+        if self
+            .repo
+            .get_by_slug(&slug)
+            .await
+            .context("Failed to check existing tag")?
+            .is_some_and(|other| other.id != tag.id)
+        {
+            return Err(TagServiceError::DuplicateSlug(slug));
+        }
+
+        self.repo
+            .rename(tag.id, name, &slug)
+            .await
+            .context("Failed to rename tag")?;
+        self.invalidate_cache().await?;
+
+        Ok(Tag {
+            name: name.to_string(),
+            slug,
+            ..tag
+        })
+    }
+
+    /// Merge tags into `target_id`
+    ///
+    /// Articles tagged with any of `source_ids` get the target tag instead,
+    /// the source tags are deleted and their slugs point at the target.
+    ///
+    /// # Errors
+    /// - `NotFound` if the target or a source tag doesn't exist
+    /// - `ValidationError` if there are no sources or the target is one
+    pub async fn merge(&self, target_id: i64, source_ids: &[i64]) -> Result<Tag, TagServiceError> {
+        let mut source_ids = source_ids.to_vec();
+        source_ids.sort_unstable();
+        source_ids.dedup();
+        if source_ids.is_empty() {
+            return Err(TagServiceError::ValidationError(
+                "No tags to merge".to_string(),
+            ));
+        }
+        if source_ids.contains(&target_id) {
+            return Err(TagServiceError::ValidationError(
+                "A tag cannot be merged into itself".to_string(),
+            ));
+        }
+
+        let target = self
+            .repo
+            .get_by_id(target_id)
+            .await
+            .context("Failed to get tag")?
+            .ok_or_else(|| {
+                TagServiceError::NotFound(format!("Tag with ID {} not found", target_id))
+            })?;
+        for id in &source_ids {
+            if self
+                .repo
+                .get_by_id(*id)
+                .await
+                .context("Failed to get tag")?
+                .is_none()
+            {
+                return Err(TagServiceError::NotFound(format!(
+                    "Tag with ID {} not found",
+                    id
+                )));
+            }
+        }
+
+        self.repo
+            .merge(target.id, &source_ids)
+            .await
+            .context("Failed to merge tags")?;
+        self.invalidate_cache().await?;
+
+        Ok(target)
+    }
+
+    /// Get the tag a former slug of a renamed or merged tag points to
+    pub async fn get_by_previous_slug(&self, slug: &str) -> Result<Option<Tag>, TagServiceError> {
+        self.repo
+            .get_by_previous_slug(slug)
+            .await
+            .context("Failed to look up previous tag slug")
+            .map_err(Into::into)
+    }
+
     /// Add a tag to an article
     ///
     /// Creates an association between a tag and an article.
@@ -528,6 +712,29 @@ mod tests {
         assert_eq!(tag1.slug, tag2.slug);
     }
 
+    #[tokio::test]
+    async fn test_get_or_create_with_slug_keeps_slug() {
+        let (_pool, service) = setup_test_service().await;
+
+        let tag = service
+            .get_or_create_with_slug("rust-lang", "Rust")
+            .await
+            .expect("Failed to create tag");
+        assert_eq!(tag.slug, "rust-lang");
+
+        // Matched by slug or by name
+        let by_slug = service
+            .get_or_create_with_slug("rust-lang", "Other")
+            .await
+            .unwrap();
+        let by_name = service
+            .get_or_create_with_slug("rust", "Rust")
+            .await
+            .unwrap();
+        assert_eq!(by_slug.id, tag.id);
+        assert_eq!(by_name.id, tag.id);
+    }
+
     #[tokio::test]
     async fn test_create_or_get_empty_name_fails() {
         let (_pool, service) = setup_test_service().await;
@@ -716,6 +923,59 @@ mod tests {
         assert!(matches!(result, Err(TagServiceError::NotFound(_))));
     }
 
+    // ========================================================================
+    // Rename and merge tests
+    // ========================================================================
+
+    #[tokio::test]
+    async fn test_rename_updates_cache_and_rejects_duplicates() {
+        let (_pool, service) = setup_test_service().await;
+        let tag = service.create_or_get("JS").await.unwrap();
+        let other = service.create_or_get("TypeScript").await.unwrap();
+        // Warm the slug cache
+        assert!(service.get_by_slug("js").await.unwrap().is_some());
+
+        let renamed = service
+            .rename(tag.id, " JavaScript ", None)
+            .await
+            .expect("Failed to rename tag");
+        assert_eq!(renamed.name, "JavaScript");
+        assert_eq!(renamed.slug, "javascript");
+        assert!(service.get_by_slug("js").await.unwrap().is_none());
+        let previous = service.get_by_previous_slug("js").await.unwrap();
+        assert_eq!(previous.map(|t| t.id), Some(tag.id));
+
+        let result = service.rename(tag.id, "TypeScript", Some("ts")).await;
+        assert!(matches!(result, Err(TagServiceError::DuplicateName(_))));
+        let result = service.rename(tag.id, "ECMAScript", Some(&other.slug)).await;
+        assert!(matches!(result, Err(TagServiceError::DuplicateSlug(_))));
+        let result = service.rename(99999, "Anything", None).await;
+        assert!(matches!(result, Err(TagServiceError::NotFound(_))));
+    }
+
+    #[tokio::test]
+    async fn test_merge_validates_tags() {
+        let (_pool, service) = setup_test_service().await;
+        let target = service.create_or_get("Rust").await.unwrap();
+        let source = service.create_or_get("Rustlang").await.unwrap();
+
+        let result = service.merge(target.id, &[]).await;
+        assert!(matches!(result, Err(TagServiceError::ValidationError(_))));
+        let result = service.merge(target.id, &[source.id, target.id]).await;
+        assert!(matches!(result, Err(TagServiceError::ValidationError(_))));
+        let result = service.merge(target.id, &[source.id, 99999]).await;
+        assert!(matches!(result, Err(TagServiceError::NotFound(_))));
+        assert!(service.get_by_id(source.id).await.unwrap().is_some());
+
+        service
+            .merge(target.id, &[source.id, source.id])
+            .await
+            .expect("Failed to merge tags");
+        assert!(service.get_by_id(source.id).await.unwrap().is_none());
+        let tags = service.list().await.unwrap();
+        assert_eq!(tags.iter().map(|t| t.id).collect::<Vec<_>>(), vec![target.id]);
+    }
+
     // ========================================================================
     // Tag-article association tests
     // ========================================================================
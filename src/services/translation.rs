@@ -0,0 +1,239 @@
+//! Content translation service.
+//!
+//! Links articles and pages into translation groups, one variant per
+//! language, and lists the published variants themes need for `hreflang`
+//! links and language switchers.
+
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use thiserror::Error;
+use uuid::Uuid;
+
+use crate::db::repositories::{ArticleRepository, PageRepository, TranslationRepository};
+use crate::models::{
+    normalize_lang, ArticleStatus, ContentAlternate, ContentKind, ContentTranslation, PageStatus,
+    TranslationInput,
+};
+use crate::services::settings::generate_article_url;
+
+/// Setting holding the language of content that has none
+pub const SITE_LANGUAGE_KEY: &str = "site_language";
+pub const DEFAULT_SITE_LANGUAGE: &str = "zh-CN";
+
+#[derive(Debug, Error)]
+pub enum TranslationError {
+    #[error("{0} not found")]
+    NotFound(&'static str),
+    #[error("{0}")]
+    Validation(String),
+    #[error(transparent)]
+    Internal(#[from] anyhow::Error),
+}
+
+pub struct TranslationService {
+    repo: Arc<dyn TranslationRepository>,
+    articles: Arc<dyn ArticleRepository>,
+    pages: Arc<dyn PageRepository>,
+}
+
+impl TranslationService {
+    pub fn new(
+        repo: Arc<dyn TranslationRepository>,
+        articles: Arc<dyn ArticleRepository>,
+        pages: Arc<dyn PageRepository>,
+    ) -> Self {
+        Self {
+            repo,
+            articles,
+            pages,
+        }
+    }
+
+    /// Language and group of an article or page, if it has one
+    pub async fn get(
+        &self,
+        kind: ContentKind,
+        id: i64,
+    ) -> Result<Option<ContentTranslation>, TranslationError> {
+        Ok(self.repo.get(kind, id).await?)
+    }
+
+    /// Every variant in the content's group, itself included
+    pub async fn variants(
+        &self,
+        kind: ContentKind,
+        id: i64,
+    ) -> Result<Vec<ContentTranslation>, TranslationError> {
+        match self.repo.get(kind, id).await? {
+            Some(own) => Ok(self.repo.list_group(kind, &own.translation_group).await?),
+            None => Ok(Vec::new()),
+        }
+    }
+
+    /// Set the language of an article or page.
+    ///
+    /// With `translation_of` it joins that content's group, which must
+    /// already have a language; otherwise it keeps its group or starts one.
+    /// A group holds one variant per language. Returns the whole group.
+    pub async fn set(
+        &self,
+        kind: ContentKind,
+        id: i64,
+        input: TranslationInput,
+    ) -> Result<Vec<ContentTranslation>, TranslationError> {
+        let lang = normalize_lang(&input.lang).ok_or_else(|| {
+            TranslationError::Validation(format!("Invalid language tag: {}", input.lang))
+        })?;
+        self.ensure_exists(kind, id).await?;
+
+        let group = match input.translation_of {
+            Some(source) if source == id => {
+                return Err(TranslationError::Validation(
+                    "Content cannot be a translation of itself".to_string(),
+                ))
+            }
+            Some(source) => {
+                self.ensure_exists(kind, source).await?;
+                self.repo
+                    .get(kind, source)
+                    .await?
+                    .ok_or_else(|| {
+                        TranslationError::Validation(
+                            "Set the language of the original first".to_string(),
+                        )
+                    })?
+                    .translation_group
+            }
+            None => match self.repo.get(kind, id).await? {
+                Some(own) => own.translation_group,
+                None => Uuid::new_v4().simple().to_string(),
+            },
+        };
+
+        let members = self.repo.list_group(kind, &group).await?;
+        if members.iter().any(|m| m.lang == lang && m.content_id != id) {
+            return Err(TranslationError::Validation(format!(
+                "A {} translation already exists",
+                lang
+            )));
+        }
+
+        self.repo
+            .set(&ContentTranslation {
+                content_type: kind,
+                content_id: id,
+                lang,
+                translation_group: group.clone(),
+                created_at: chrono::Utc::now(),
+            })
+            .await?;
+        Ok(self.repo.list_group(kind, &group).await?)
+    }
+
+    /// Drop the language, leaving the group; the content is back in the
+    /// site language
+    pub async fn remove(&self, kind: ContentKind, id: i64) -> Result<(), TranslationError> {
+        self.repo.delete(kind, id).await?;
+        Ok(())
+    }
+
+    /// Language of every article or page that has one
+    pub async fn languages(
+        &self,
+        kind: ContentKind,
+    ) -> Result<HashMap<i64, String>, TranslationError> {
+        Ok(self
+            .repo
+            .list(kind)
+            .await?
+            .into_iter()
+            .map(|t| (t.content_id, t.lang))
+            .collect())
+    }
+
+    /// Published variants of the content, itself included, for `hreflang`.
+    ///
+    /// Empty unless at least two variants are published. Article URLs
+    /// follow `permalink_structure`.
+    pub async fn alternates(
+        &self,
+        kind: ContentKind,
+        id: i64,
+        permalink_structure: &str,
+    ) -> Result<Vec<ContentAlternate>, TranslationError> {
+        let mut alternates = Vec::new();
+        for variant in self.variants(kind, id).await? {
+            let alternate = match kind {
+                ContentKind::Article => self
+                    .articles
+                    .get_by_id(variant.content_id)
+                    .await?
+                    .filter(|a| a.status == ArticleStatus::Published)
+                    .map(|a| ContentAlternate {
+                        url: generate_article_url(
+                            permalink_structure,
+                            a.id,
+                            &a.slug,
+                            a.published_at.as_ref(),
+                        ),
+                        lang: variant.lang,
+                        id: a.id,
+                        slug: a.slug,
+                        title: a.title,
+                    }),
+                ContentKind::Page => self
+                    .pages
+                    .get_by_id(variant.content_id)
+                    .await?
+                    .filter(|p| p.status == PageStatus::Published)
+                    .map(|p| ContentAlternate {
+                        url: format!("/{}", p.slug),
+                        lang: variant.lang,
+                        id: p.id,
+                        slug: p.slug,
+                        title: p.title,
+                    }),
+            };
+            alternates.extend(alternate);
+        }
+        if alternates.len() < 2 {
+            alternates.clear();
+        }
+        Ok(alternates)
+    }
+
+    async fn ensure_exists(&self, kind: ContentKind, id: i64) -> Result<(), TranslationError> {
+        let exists = match kind {
+            ContentKind::Article => self.articles.get_by_id(id).await?.is_some(),
+            ContentKind::Page => self.pages.get_by_id(id).await?.is_some(),
+        };
+        if exists {
+            Ok(())
+        } else {
+            Err(TranslationError::NotFound(match kind {
+                ContentKind::Article => "Article",
+                ContentKind::Page => "Page",
+            }))
+        }
+    }
+}
+
+/// Whether content with `lang` (or none) belongs in a listing for `wanted`
+pub fn matches_lang(lang: Option<&str>, wanted: &str, site_lang: &str) -> bool {
+    lang.unwrap_or(site_lang).eq_ignore_ascii_case(wanted)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn content_without_language_is_in_the_site_language() {
+        assert!(matches_lang(None, "en", "en"));
+        assert!(!matches_lang(None, "de", "en"));
+        assert!(matches_lang(Some("de"), "de", "en"));
+        assert!(matches_lang(Some("zh-CN"), "zh-cn", "en"));
+        assert!(!matches_lang(Some("de"), "en", "en"));
+    }
+}
@@ -5,6 +5,7 @@
 //! - Login/logout - Requirements 4.3, 4.5
 //! - Session management - Requirements 4.4, 4.7
 //! - Password hashing - Requirement 4.6
+//! - Provisioning from identity-management tooling
 //!
 //! Satisfies requirements:
 //! - 4.1: WHEN 第一个用户注册 THEN User_Service SHALL 自动将其设置为管理员角色
@@ -13,10 +14,15 @@
 //! - 4.5: IF 登录凭据无效 THEN User_Service SHALL 返回认证错误
 //! - 4.7: WHILE 用户已登录 THEN User_Service SHALL 维护用户会话状态
 
-use crate::db::repositories::{SessionRepository, UserRepository};
-use crate::models::{Session, User, UserRole};
+use crate::db::repositories::{
+    ExternalIdentityRepository, SessionRepository, SettingsRepository, UserRepository,
+};
+use crate::models::{ExternalIdentity, Session, User, UserRole, UserStatus, PROVIDER_LDAP};
 use crate::plugin::{hook_names, HookManager};
+use crate::services::captcha::{CaptchaError, CaptchaVerifier};
+use crate::services::ldap::{DirectoryAuthenticator, DirectoryError, DirectoryUser};
 use crate::services::password::{hash_password, verify_password};
+use crate::services::password_policy::{self, BreachChecker, PasswordPolicy};
 use anyhow::{Context, Result};
 use chrono::{Duration, Utc};
 use serde_json::json;
@@ -49,6 +55,10 @@ pub enum UserServiceError {
     #[error("Session not found")]
     SessionNotFound,
 
+    /// User not found
+    #[error("User not found")]
+    UserNotFound,
+
     /// Internal error
     #[error("Internal error: {0}")]
     InternalError(#[from] anyhow::Error),
@@ -60,6 +70,11 @@ pub struct UserService {
     session_repo: Arc<dyn SessionRepository>,
     session_expiration_days: i64,
     hook_manager: Option<Arc<HookManager>>,
+    captcha: Option<Arc<CaptchaVerifier>>,
+    settings_repo: Option<Arc<dyn SettingsRepository>>,
+    breach_checker: BreachChecker,
+    directory: Option<Arc<dyn DirectoryAuthenticator>>,
+    identity_repo: Option<Arc<dyn ExternalIdentityRepository>>,
 }
 
 impl UserService {
@@ -73,6 +88,11 @@ impl UserService {
             session_repo,
             session_expiration_days: DEFAULT_SESSION_EXPIRATION_DAYS,
             hook_manager: None,
+            captcha: None,
+            settings_repo: None,
+            breach_checker: BreachChecker::new(),
+            directory: None,
+            identity_repo: None,
         }
     }
 
@@ -87,6 +107,11 @@ impl UserService {
             session_repo,
             session_expiration_days,
             hook_manager: None,
+            captcha: None,
+            settings_repo: None,
+            breach_checker: BreachChecker::new(),
+            directory: None,
+            identity_repo: None,
         }
     }
 
@@ -101,9 +126,39 @@ impl UserService {
             session_repo,
             session_expiration_days: DEFAULT_SESSION_EXPIRATION_DAYS,
             hook_manager: Some(hook_manager),
+            captcha: None,
+            settings_repo: None,
+            breach_checker: BreachChecker::new(),
+            directory: None,
+            identity_repo: None,
         }
     }
 
+    /// Require a Turnstile / hCaptcha token on registration when configured
+    pub fn with_captcha(mut self, captcha: Arc<CaptchaVerifier>) -> Self {
+        self.captcha = Some(captcha);
+        self
+    }
+
+    /// Read the password policy from settings instead of using the defaults
+    pub fn with_settings(mut self, settings_repo: Arc<dyn SettingsRepository>) -> Self {
+        self.settings_repo = Some(settings_repo);
+        self
+    }
+
+    /// Check passwords against an external directory (LDAP) first
+    ///
+    /// Directory users are bound to local accounts through `identity_repo`.
+    pub fn with_directory(
+        mut self,
+        directory: Arc<dyn DirectoryAuthenticator>,
+        identity_repo: Arc<dyn ExternalIdentityRepository>,
+    ) -> Self {
+        self.directory = Some(directory);
+        self.identity_repo = Some(identity_repo);
+        self
+    }
+
     /// Trigger a hook if hook manager is available
     fn trigger_hook(&self, name: &str, data: serde_json::Value) -> serde_json::Value {
         if let Some(ref manager) = self.hook_manager {
@@ -162,34 +217,19 @@ impl UserService {
 
         // Validate input
         self.validate_register_input(&input)?;
-
-        // Check if username already exists
-        if self
-            .user_repo
-            .get_by_username(&input.username)
-            .await
-            .context("Failed to check username")?
-            .is_some()
-        {
-            return Err(UserServiceError::UserExists(format!(
-                "Username '{}' is already taken",
-                input.username
-            )));
+        self.validate_new_password(&input.password).await?;
+
+        if let Some(ref captcha) = self.captcha {
+            captcha
+                .verify(input.captcha_token.as_deref(), input.ip.as_deref())
+                .await
+                .map_err(|e| match e {
+                    CaptchaError::Internal(e) => UserServiceError::InternalError(e),
+                    e => UserServiceError::ValidationError(e.to_string()),
+                })?;
         }
 
-        // Check if email already exists
-        if self
-            .user_repo
-            .get_by_email(&input.email)
-            .await
-            .context("Failed to check email")?
-            .is_some()
-        {
-            return Err(UserServiceError::UserExists(format!(
-                "Email '{}' is already registered",
-                input.email
-            )));
-        }
+        self.ensure_available(&input.username, &input.email).await?;
 
         // Determine role: first user becomes admin (Requirement 4.1)
         let is_first = self.is_first_user().await?;
@@ -291,6 +331,37 @@ impl UserService {
             return Err(UserServiceError::AuthenticationError(reason.to_string()));
         }
 
+        let user = match self.authenticate_with_directory(input, &ip).await? {
+            Some(user) => user,
+            None => self.authenticate_local(input, &ip).await?,
+        };
+
+        // Check if user is banned
+        if user.is_banned() {
+            // Trigger user_login_failed hook
+            self.trigger_hook(
+                hook_names::USER_LOGIN_FAILED,
+                json!({
+                    "username_or_email": input.username_or_email.clone(),
+                    "user_id": user.id,
+                    "reason": "user_banned",
+                    "ip": ip,
+                }),
+            );
+            return Err(UserServiceError::AuthenticationError(
+                "Your account has been banned. Please contact the administrator.".to_string(),
+            ));
+        }
+
+        Ok(user)
+    }
+
+    /// Verify a login against the local password hash
+    async fn authenticate_local(
+        &self,
+        input: &LoginInput,
+        ip: &Option<String>,
+    ) -> Result<User, UserServiceError> {
         // Find user by username or email
         let user = self
             .find_user_by_username_or_email(&input.username_or_email)
@@ -328,24 +399,309 @@ impl UserService {
             ));
         }
 
-        // Check if user is banned
+        Ok(user)
+    }
+
+    /// Verify a login against the configured directory
+    ///
+    /// Returns `None` when no directory is configured, or when the directory
+    /// rejects the login (or is unreachable) and local accounts may still
+    /// sign in.
+    async fn authenticate_with_directory(
+        &self,
+        input: &LoginInput,
+        ip: &Option<String>,
+    ) -> Result<Option<User>, UserServiceError> {
+        let Some(directory) = self.directory.as_ref() else {
+            return Ok(None);
+        };
+
+        match directory
+            .authenticate(&input.username_or_email, &input.password)
+            .await
+        {
+            Ok(Some(identity)) => self
+                .sync_directory_user(directory.as_ref(), identity)
+                .await
+                .map(Some),
+            Ok(None) if directory.allow_local_fallback() => Ok(None),
+            Ok(None) => {
+                self.trigger_hook(
+                    hook_names::USER_LOGIN_FAILED,
+                    json!({
+                        "username_or_email": input.username_or_email.clone(),
+                        "reason": "invalid_credentials",
+                        "ip": ip,
+                    }),
+                );
+                Err(UserServiceError::AuthenticationError(
+                    "Invalid username or password".to_string(),
+                ))
+            }
+            Err(DirectoryError::Forbidden(msg)) => Err(UserServiceError::AuthenticationError(msg)),
+            Err(e) if directory.allow_local_fallback() => {
+                tracing::warn!(error = %e, "directory login failed, trying local accounts");
+                Ok(None)
+            }
+            Err(e) => Err(UserServiceError::InternalError(anyhow::anyhow!(e))),
+        }
+    }
+
+    /// Find or provision the local account of a directory user
+    ///
+    /// Accounts are resolved through their binding to the directory entry.
+    /// The directory's role is applied on every login to accounts it
+    /// provisioned; changing it revokes the user's other sessions. An unbound
+    /// local account with the same username or email is never taken over,
+    /// unless the directory opts in to adopting it by email.
+    async fn sync_directory_user(
+        &self,
+        directory: &dyn DirectoryAuthenticator,
+        identity: DirectoryUser,
+    ) -> Result<User, UserServiceError> {
+        let identity_repo = self
+            .identity_repo
+            .as_ref()
+            .context("Directory identities are not configured")?;
+
+        if let Some(binding) = identity_repo
+            .get(PROVIDER_LDAP, "", &identity.id)
+            .await
+            .context("Failed to look up directory identity")?
+        {
+            let user = self
+                .user_repo
+                .get_by_id(binding.user_id)
+                .await
+                .context("Failed to look up user")?
+                .ok_or(UserServiceError::UserNotFound)?;
+            if !binding.manages_role || user.role == identity.role || user.is_banned() {
+                return Ok(user);
+            }
+            self.user_repo
+                .revoke_access(user.id, Some(identity.role), false)
+                .await
+                .context("Failed to update user role")?;
+            return self
+                .user_repo
+                .get_by_id(user.id)
+                .await
+                .context("Failed to reload user")?
+                .ok_or(UserServiceError::UserNotFound);
+        }
+
+        let by_email = self
+            .user_repo
+            .get_by_email(&identity.email)
+            .await
+            .context("Failed to look up user")?;
+        if let Some(user) = by_email.as_ref().filter(|_| directory.link_by_email()) {
+            if identity_repo
+                .exists_for_user(PROVIDER_LDAP, user.id)
+                .await
+                .context("Failed to look up directory identity")?
+            {
+                return Err(UserServiceError::AuthenticationError(
+                    "This account is linked to another directory entry".to_string(),
+                ));
+            }
+            // Adopted accounts keep the role they were given locally
+            identity_repo
+                .create(&ExternalIdentity::new(
+                    PROVIDER_LDAP,
+                    "",
+                    &identity.id,
+                    user.id,
+                    false,
+                ))
+                .await
+                .context("Failed to link directory identity")?;
+            tracing::info!(
+                user_id = user.id,
+                "linked directory identity to existing account"
+            );
+            return Ok(user.clone());
+        }
+
+        let username_taken = self
+            .user_repo
+            .get_by_username(&identity.username)
+            .await
+            .context("Failed to look up user")?
+            .is_some();
+        if username_taken || by_email.is_some() {
+            return Err(UserServiceError::AuthenticationError(
+                "An account with this username or email address already exists; ask an administrator to link it".to_string(),
+            ));
+        }
+
+        // Directory users never sign in with a local password
+        let password_hash = unusable_password_hash()?;
+        let mut user = User::new(
+            identity.username,
+            identity.email,
+            password_hash,
+            identity.role,
+        );
+        user.display_name = identity.display_name;
+        let user = self
+            .user_repo
+            .create(&user)
+            .await
+            .context("Failed to provision directory user")?;
+        identity_repo
+            .create(&ExternalIdentity::new(
+                PROVIDER_LDAP,
+                "",
+                identity.id,
+                user.id,
+                true,
+            ))
+            .await
+            .context("Failed to bind directory identity")?;
+        tracing::info!(user_id = user.id, role = %user.role, "provisioned directory user");
+        Ok(user)
+    }
+
+    /// Create, update or deactivate a user for identity-management tooling
+    ///
+    /// Users are matched by email, so pushing the same record again is a
+    /// no-op. New users get an unusable password and sign in through SSO or
+    /// a password reset. Deactivated users are banned and signed out; an
+    /// active record re-enables them. Role changes revoke all sessions.
+    ///
+    /// # Errors
+    ///
+    /// - `ValidationError` for an invalid email, or when `actor_id` would
+    ///   deactivate or change the role of their own account
+    /// - `InternalError` for database errors
+    pub async fn provision_user(
+        &self,
+        actor_id: i64,
+        input: ProvisionUserInput,
+    ) -> Result<(ProvisionOutcome, Option<User>), UserServiceError> {
+        let email = input.email.trim();
+        if !email.contains('@') {
+            return Err(UserServiceError::ValidationError(format!(
+                "Invalid email: {}",
+                email
+            )));
+        }
+
+        let existing = self
+            .user_repo
+            .get_by_email(email)
+            .await
+            .context("Failed to look up user")?;
+        let Some(mut user) = existing else {
+            if !input.active {
+                return Ok((ProvisionOutcome::Unchanged, None));
+            }
+            let username = self
+                .available_username(input.username.as_deref(), email)
+                .await?;
+            let role = input.role.unwrap_or(UserRole::Author);
+            let mut user = User::new(username, email.to_string(), unusable_password_hash()?, role);
+            user.display_name = input.display_name;
+            let user = self
+                .user_repo
+                .create(&user)
+                .await
+                .context("Failed to provision user")?;
+            tracing::info!(user_id = user.id, role = %user.role, "provisioned user");
+            return Ok((ProvisionOutcome::Created, Some(user)));
+        };
+
+        let role = input.role.unwrap_or(user.role);
+        if user.id == actor_id && (!input.active || role != user.role) {
+            return Err(UserServiceError::ValidationError(
+                "You cannot deactivate or change the role of your own account".to_string(),
+            ));
+        }
+
+        if !input.active {
+            if user.is_banned() {
+                return Ok((ProvisionOutcome::Unchanged, Some(user)));
+            }
+            user.status = UserStatus::Banned;
+            self.user_repo
+                .update(&user)
+                .await
+                .context("Failed to deactivate user")?;
+            self.user_repo
+                .revoke_access(user.id, None, false)
+                .await
+                .context("Failed to revoke sessions")?;
+            tracing::info!(user_id = user.id, "deprovisioned user");
+            let user = self.require_user(user.id).await?;
+            return Ok((ProvisionOutcome::Deactivated, Some(user)));
+        }
+
+        let mut changed = false;
         if user.is_banned() {
-            // Trigger user_login_failed hook
+            user.status = UserStatus::Active;
+            changed = true;
+        }
+        if input.display_name.is_some() && input.display_name != user.display_name {
+            user.display_name = input.display_name;
+            changed = true;
+        }
+        if changed {
+            self.user_repo
+                .update(&user)
+                .await
+                .context("Failed to update user")?;
+        }
+        if user.role != role {
+            self.user_repo
+                .revoke_access(user.id, Some(role), false)
+                .await
+                .context("Failed to change user role")?;
             self.trigger_hook(
-                hook_names::USER_LOGIN_FAILED,
+                hook_names::USER_ROLE_CHANGE,
                 json!({
-                    "username_or_email": input.username_or_email.clone(),
-                    "user_id": user.id,
-                    "reason": "user_banned",
-                    "ip": ip,
+                    "id": user.id,
+                    "old_role": user.role.to_string(),
+                    "new_role": role.to_string(),
                 }),
             );
-            return Err(UserServiceError::AuthenticationError(
-                "Your account has been banned. Please contact the administrator.".to_string(),
-            ));
+            changed = true;
         }
 
-        Ok(user)
+        let outcome = if changed {
+            ProvisionOutcome::Updated
+        } else {
+            ProvisionOutcome::Unchanged
+        };
+        let user = self.require_user(user.id).await?;
+        Ok((outcome, Some(user)))
+    }
+
+    /// The requested username, else the local part of the email, with a
+    /// numeric suffix added when it is taken
+    async fn available_username(
+        &self,
+        requested: Option<&str>,
+        email: &str,
+    ) -> Result<String, UserServiceError> {
+        let base = requested
+            .map(str::trim)
+            .filter(|name| !name.is_empty())
+            .unwrap_or_else(|| email.split('@').next().unwrap_or(email));
+        let mut candidate = base.to_string();
+        for suffix in 2.. {
+            let taken = self
+                .user_repo
+                .get_by_username(&candidate)
+                .await
+                .context("Failed to look up user")?
+                .is_some();
+            if !taken {
+                break;
+            }
+            candidate = format!("{}{}", base, suffix);
+        }
+        Ok(candidate)
     }
 
     /// Create a login session after credentials and any required second factor are complete.
@@ -463,6 +819,209 @@ impl UserService {
         Ok(updated)
     }
 
+    /// Change a user's password after verifying the current one
+    ///
+    /// The new password must satisfy the configured password policy.
+    ///
+    /// # Errors
+    ///
+    /// - `ValidationError` if the current password is wrong or the new one
+    ///   violates the policy
+    /// - `InternalError` for database or hashing errors
+    pub async fn change_password(
+        &self,
+        mut user: User,
+        current_password: &str,
+        new_password: &str,
+    ) -> Result<User, UserServiceError> {
+        let is_valid = verify_password(current_password, &user.password_hash)
+            .context("Failed to verify password")?;
+        if !is_valid {
+            return Err(UserServiceError::ValidationError(
+                "Current password is incorrect".to_string(),
+            ));
+        }
+
+        self.validate_new_password(new_password).await?;
+
+        user.password_hash = hash_password(new_password).context("Failed to hash password")?;
+        user.password_reset_required = false;
+        self.update_user(user).await
+    }
+
+    /// Change a user's role, revoking all of their sessions
+    ///
+    /// The role update and session revocation happen in one transaction so
+    /// the user cannot keep acting with the old privileges.
+    ///
+    /// # Errors
+    ///
+    /// - `UserNotFound` if the user doesn't exist
+    /// - `ValidationError` if an admin tries to change their own role
+    /// - `InternalError` for database errors
+    pub async fn change_role(
+        &self,
+        actor_id: i64,
+        user_id: i64,
+        role: UserRole,
+    ) -> Result<User, UserServiceError> {
+        if actor_id == user_id {
+            return Err(UserServiceError::ValidationError(
+                "You cannot change your own role".to_string(),
+            ));
+        }
+        let user = self.require_user(user_id).await?;
+        if user.role == role {
+            return Ok(user);
+        }
+
+        self.user_repo
+            .revoke_access(user_id, Some(role), false)
+            .await
+            .context("Failed to change user role")?;
+
+        self.trigger_hook(
+            hook_names::USER_ROLE_CHANGE,
+            json!({
+                "id": user_id,
+                "old_role": user.role.to_string(),
+                "new_role": role.to_string(),
+            }),
+        );
+
+        self.require_user(user_id).await
+    }
+
+    /// Require a user to choose a new password, revoking all of their sessions
+    ///
+    /// After logging in again, the user can only change their password until
+    /// the flag is cleared.
+    ///
+    /// # Errors
+    ///
+    /// - `UserNotFound` if the user doesn't exist
+    /// - `InternalError` for database errors
+    pub async fn force_password_reset(&self, user_id: i64) -> Result<User, UserServiceError> {
+        self.require_user(user_id).await?;
+        self.user_repo
+            .revoke_access(user_id, None, true)
+            .await
+            .context("Failed to force password reset")?;
+        self.require_user(user_id).await
+    }
+
+    /// Create a user with the given role, without captcha or plugin hooks
+    ///
+    /// Used by the `noteva user create` command to bootstrap accounts.
+    ///
+    /// # Errors
+    ///
+    /// - `ValidationError` if the input or password is invalid
+    /// - `UserExists` if username or email is already taken
+    /// - `InternalError` for database errors
+    pub async fn create_user(
+        &self,
+        input: RegisterInput,
+        role: UserRole,
+    ) -> Result<User, UserServiceError> {
+        self.validate_register_input(&input)?;
+        self.validate_new_password(&input.password).await?;
+        self.ensure_available(&input.username, &input.email).await?;
+
+        let password_hash = hash_password(&input.password).context("Failed to hash password")?;
+        let user = User::new(input.username, input.email, password_hash, role);
+        Ok(self
+            .user_repo
+            .create(&user)
+            .await
+            .context("Failed to create user")?)
+    }
+
+    /// Set a new password without the current one, revoking all sessions
+    ///
+    /// Used by the `noteva user passwd` command. Clears a pending forced reset.
+    ///
+    /// # Errors
+    ///
+    /// - `UserNotFound` if the user doesn't exist
+    /// - `ValidationError` if the password violates the policy
+    /// - `InternalError` for database or hashing errors
+    pub async fn reset_password(
+        &self,
+        user_id: i64,
+        new_password: &str,
+    ) -> Result<User, UserServiceError> {
+        let mut user = self.require_user(user_id).await?;
+        self.validate_new_password(new_password).await?;
+
+        user.password_hash = hash_password(new_password).context("Failed to hash password")?;
+        user.password_reset_required = false;
+        self.user_repo
+            .update(&user)
+            .await
+            .context("Failed to update password")?;
+        self.revoke_sessions(user_id).await?;
+        self.require_user(user_id).await
+    }
+
+    /// Revoke all sessions of a user
+    ///
+    /// # Errors
+    ///
+    /// - `UserNotFound` if the user doesn't exist
+    /// - `InternalError` for database errors
+    pub async fn revoke_sessions(&self, user_id: i64) -> Result<(), UserServiceError> {
+        self.require_user(user_id).await?;
+        self.user_repo
+            .revoke_access(user_id, None, false)
+            .await
+            .context("Failed to revoke sessions")?;
+        Ok(())
+    }
+
+    /// Load the password policy from settings (defaults when not configured)
+    pub async fn password_policy(&self) -> Result<PasswordPolicy, UserServiceError> {
+        let Some(ref settings_repo) = self.settings_repo else {
+            return Ok(PasswordPolicy::default());
+        };
+        let settings = settings_repo
+            .get_many(password_policy::keys::ALL)
+            .await
+            .context("Failed to load password policy")?;
+        Ok(PasswordPolicy::from_settings(&settings))
+    }
+
+    /// Check a new password against the password policy
+    ///
+    /// When the breached-password check is enabled, the password is looked up
+    /// in the Pwned Passwords range API. If the lookup fails the password is
+    /// accepted, so an outage does not block registrations.
+    ///
+    /// # Errors
+    ///
+    /// - `ValidationError` describing the first violated rule
+    pub async fn validate_new_password(&self, password: &str) -> Result<(), UserServiceError> {
+        let policy = self.password_policy().await?;
+        policy
+            .validate(password)
+            .map_err(UserServiceError::ValidationError)?;
+
+        if policy.check_breached {
+            match self.breach_checker.is_breached(password).await {
+                Ok(true) => {
+                    return Err(UserServiceError::ValidationError(
+                        "This password has appeared in a data breach; please choose a different one"
+                            .to_string(),
+                    ));
+                }
+                Ok(false) => {}
+                Err(e) => tracing::warn!("Breached password check skipped: {:#}", e),
+            }
+        }
+
+        Ok(())
+    }
+
     /// Validate session token and return the associated user
     ///
     /// Checks if the session exists and is not expired. If valid, returns
@@ -574,6 +1133,44 @@ impl UserService {
     // Private helper methods
     // ========================================================================
 
+    /// Load a user or fail with `UserNotFound`
+    async fn require_user(&self, user_id: i64) -> Result<User, UserServiceError> {
+        self.get_by_id(user_id)
+            .await?
+            .ok_or(UserServiceError::UserNotFound)
+    }
+
+    /// Fail with `UserExists` if the username or email is taken
+    async fn ensure_available(&self, username: &str, email: &str) -> Result<(), UserServiceError> {
+        if self
+            .user_repo
+            .get_by_username(username)
+            .await
+            .context("Failed to check username")?
+            .is_some()
+        {
+            return Err(UserServiceError::UserExists(format!(
+                "Username '{}' is already taken",
+                username
+            )));
+        }
+
+        if self
+            .user_repo
+            .get_by_email(email)
+            .await
+            .context("Failed to check email")?
+            .is_some()
+        {
+            return Err(UserServiceError::UserExists(format!(
+                "Email '{}' is already registered",
+                email
+            )));
+        }
+
+        Ok(())
+    }
+
     /// Validate registration input
     fn validate_register_input(&self, input: &RegisterInput) -> Result<(), UserServiceError> {
         if input.username.trim().is_empty() {
@@ -588,12 +1185,6 @@ impl UserService {
             ));
         }
 
-        if input.password.len() < 8 {
-            return Err(UserServiceError::ValidationError(
-                "Password must be at least 8 characters".to_string(),
-            ));
-        }
-
         // Basic email format validation
         if !input.email.contains('@') {
             return Err(UserServiceError::ValidationError(
@@ -649,12 +1240,47 @@ impl UserService {
     }
 }
 
+/// Password hash nobody can sign in with, for externally managed accounts
+fn unusable_password_hash() -> Result<String, UserServiceError> {
+    let mut secret = [0u8; 32];
+    getrandom::fill(&mut secret).expect("Failed to generate random bytes for password");
+    Ok(hash_password(&data_encoding::HEXLOWER.encode(&secret))
+        .context("Failed to hash password")?)
+}
+
+/// A user record pushed by identity-management tooling
+#[derive(Debug, Clone)]
+pub struct ProvisionUserInput {
+    /// Email address, the key records are matched on
+    pub email: String,
+    /// Username for new users (defaults to the local part of the email)
+    pub username: Option<String>,
+    pub display_name: Option<String>,
+    /// Role to apply (new users default to author)
+    pub role: Option<UserRole>,
+    /// `false` deactivates the user
+    pub active: bool,
+}
+
+/// What [`UserService::provision_user`] did with a record
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ProvisionOutcome {
+    Created,
+    Updated,
+    Deactivated,
+    Unchanged,
+}
+
 /// Input for user registration
 #[derive(Debug, Clone)]
 pub struct RegisterInput {
     pub username: String,
     pub email: String,
     pub password: String,
+    /// Turnstile / hCaptcha response token
+    pub captcha_token: Option<String>,
+    /// Client IP forwarded to the captcha provider
+    pub ip: Option<String>,
 }
 
 impl RegisterInput {
@@ -668,8 +1294,17 @@ impl RegisterInput {
             username: username.into(),
             email: email.into(),
             password: password.into(),
+            captcha_token: None,
+            ip: None,
         }
     }
+
+    /// Attach the captcha token and client IP submitted with the form
+    pub fn with_captcha(mut self, token: Option<String>, ip: Option<String>) -> Self {
+        self.captcha_token = token;
+        self.ip = ip;
+        self
+    }
 }
 
 /// Input for user login
@@ -692,7 +1327,10 @@ impl LoginInput {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::db::repositories::{SqlxSessionRepository, SqlxUserRepository};
+    use crate::db::repositories::{
+        SqlxExternalIdentityRepository, SqlxSessionRepository, SqlxSettingsRepository,
+        SqlxUserRepository,
+    };
     use crate::db::{create_test_pool, migrations, DynDatabasePool};
 
     async fn setup_test_service() -> (DynDatabasePool, UserService) {
@@ -710,6 +1348,295 @@ mod tests {
         (pool, service)
     }
 
+    /// Directory that knows one user with a fixed password
+    struct FakeDirectory {
+        role: std::sync::Mutex<UserRole>,
+        fallback: bool,
+        link_by_email: bool,
+    }
+
+    #[async_trait::async_trait]
+    impl DirectoryAuthenticator for FakeDirectory {
+        async fn authenticate(
+            &self,
+            username: &str,
+            password: &str,
+        ) -> Result<Option<DirectoryUser>, DirectoryError> {
+            if username != "carol" || password != "directory-pass" {
+                return Ok(None);
+            }
+            Ok(Some(DirectoryUser {
+                id: "cn=carol,ou=people,dc=example,dc=com".to_string(),
+                username: "carol".to_string(),
+                email: "carol@example.com".to_string(),
+                display_name: Some("Carol".to_string()),
+                role: *self.role.lock().unwrap(),
+            }))
+        }
+
+        fn allow_local_fallback(&self) -> bool {
+            self.fallback
+        }
+
+        fn link_by_email(&self) -> bool {
+            self.link_by_email
+        }
+    }
+
+    async fn setup_linking_directory_service(
+        fallback: bool,
+        link_by_email: bool,
+    ) -> (Arc<FakeDirectory>, UserService) {
+        let (pool, service) = setup_test_service().await;
+        let directory = Arc::new(FakeDirectory {
+            role: std::sync::Mutex::new(UserRole::Editor),
+            fallback,
+            link_by_email,
+        });
+        let service = service.with_directory(
+            directory.clone(),
+            SqlxExternalIdentityRepository::boxed(pool),
+        );
+        (directory, service)
+    }
+
+    async fn setup_directory_service(fallback: bool) -> (Arc<FakeDirectory>, UserService) {
+        setup_linking_directory_service(fallback, false).await
+    }
+
+    #[tokio::test]
+    async fn test_directory_login_provisions_and_syncs_role() {
+        let (directory, service) = setup_directory_service(true).await;
+
+        let user = service
+            .authenticate_login(&LoginInput::new("carol", "directory-pass"), None)
+            .await
+            .expect("Directory login should succeed");
+        assert_eq!(user.role, UserRole::Editor);
+        assert_eq!(user.display_name.as_deref(), Some("Carol"));
+
+        *directory.role.lock().unwrap() = UserRole::Admin;
+        let again = service
+            .authenticate_login(&LoginInput::new("carol", "directory-pass"), None)
+            .await
+            .unwrap();
+        assert_eq!(again.id, user.id);
+        assert_eq!(again.role, UserRole::Admin);
+
+        // The provisioned account has no usable local password
+        assert!(service
+            .authenticate_login(&LoginInput::new("carol", "wrong"), None)
+            .await
+            .is_err());
+    }
+
+    #[tokio::test]
+    async fn test_directory_login_never_takes_over_local_accounts() {
+        let (_, service) = setup_directory_service(false).await;
+        // First registered user becomes admin
+        let local = service
+            .register(RegisterInput::new(
+                "carol",
+                "someone-else@example.com",
+                "password123",
+            ))
+            .await
+            .unwrap();
+
+        assert!(matches!(
+            service
+                .authenticate_login(&LoginInput::new("carol", "directory-pass"), None)
+                .await,
+            Err(UserServiceError::AuthenticationError(_))
+        ));
+        let local = service.get_by_id(local.id).await.unwrap().unwrap();
+        assert_eq!(local.role, UserRole::Admin);
+    }
+
+    #[tokio::test]
+    async fn test_directory_link_by_email_keeps_local_role() {
+        let (directory, service) = setup_linking_directory_service(false, true).await;
+        let local = service
+            .register(RegisterInput::new(
+                "caroline",
+                "carol@example.com",
+                "password123",
+            ))
+            .await
+            .unwrap();
+        assert_eq!(local.role, UserRole::Admin);
+
+        let user = service
+            .authenticate_login(&LoginInput::new("carol", "directory-pass"), None)
+            .await
+            .expect("Directory login should adopt the account");
+        assert_eq!(user.id, local.id);
+        assert_eq!(user.role, UserRole::Admin);
+
+        // Adopted accounts are not demoted by later directory role changes
+        *directory.role.lock().unwrap() = UserRole::Author;
+        let again = service
+            .authenticate_login(&LoginInput::new("carol", "directory-pass"), None)
+            .await
+            .unwrap();
+        assert_eq!(again.id, local.id);
+        assert_eq!(again.role, UserRole::Admin);
+    }
+
+    #[tokio::test]
+    async fn test_directory_local_fallback() {
+        let (_, service) = setup_directory_service(true).await;
+        service
+            .register(RegisterInput::new(
+                "admin",
+                "admin@example.com",
+                "password123",
+            ))
+            .await
+            .unwrap();
+        assert!(service
+            .authenticate_login(&LoginInput::new("admin", "password123"), None)
+            .await
+            .is_ok());
+
+        let (_, service) = setup_directory_service(false).await;
+        service
+            .register(RegisterInput::new(
+                "admin",
+                "admin@example.com",
+                "password123",
+            ))
+            .await
+            .unwrap();
+        assert!(matches!(
+            service
+                .authenticate_login(&LoginInput::new("admin", "password123"), None)
+                .await,
+            Err(UserServiceError::AuthenticationError(_))
+        ));
+    }
+
+    fn provision(email: &str, role: UserRole, active: bool) -> ProvisionUserInput {
+        ProvisionUserInput {
+            email: email.to_string(),
+            username: None,
+            display_name: None,
+            role: Some(role),
+            active,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_provision_user_is_idempotent_by_email() {
+        let (_pool, service) = setup_test_service().await;
+        let admin = service
+            .register(RegisterInput::new(
+                "admin",
+                "admin@example.com",
+                "password123",
+            ))
+            .await
+            .unwrap();
+        // Username taken, so a suffix is added
+        service
+            .register(RegisterInput::new(
+                "dave",
+                "other@example.com",
+                "password123",
+            ))
+            .await
+            .unwrap();
+
+        let (outcome, user) = service
+            .provision_user(
+                admin.id,
+                provision("dave@example.com", UserRole::Author, true),
+            )
+            .await
+            .unwrap();
+        let user = user.unwrap();
+        assert_eq!(outcome, ProvisionOutcome::Created);
+        assert_eq!(user.username, "dave2");
+
+        let (outcome, _) = service
+            .provision_user(
+                admin.id,
+                provision("dave@example.com", UserRole::Author, true),
+            )
+            .await
+            .unwrap();
+        assert_eq!(outcome, ProvisionOutcome::Unchanged);
+
+        let (outcome, synced) = service
+            .provision_user(
+                admin.id,
+                provision("dave@example.com", UserRole::Editor, true),
+            )
+            .await
+            .unwrap();
+        assert_eq!(outcome, ProvisionOutcome::Updated);
+        assert_eq!(synced.unwrap().role, UserRole::Editor);
+
+        let (outcome, deactivated) = service
+            .provision_user(
+                admin.id,
+                provision("dave@example.com", UserRole::Editor, false),
+            )
+            .await
+            .unwrap();
+        assert_eq!(outcome, ProvisionOutcome::Deactivated);
+        assert!(deactivated.unwrap().is_banned());
+
+        let (outcome, reactivated) = service
+            .provision_user(
+                admin.id,
+                provision("dave@example.com", UserRole::Editor, true),
+            )
+            .await
+            .unwrap();
+        assert_eq!(outcome, ProvisionOutcome::Updated);
+        assert_eq!(reactivated.unwrap().id, user.id);
+
+        let (outcome, missing) = service
+            .provision_user(
+                admin.id,
+                provision("ghost@example.com", UserRole::Author, false),
+            )
+            .await
+            .unwrap();
+        assert_eq!(outcome, ProvisionOutcome::Unchanged);
+        assert!(missing.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_provision_user_protects_own_account() {
+        let (_pool, service) = setup_test_service().await;
+        let admin = service
+            .register(RegisterInput::new(
+                "admin",
+                "admin@example.com",
+                "password123",
+            ))
+            .await
+            .unwrap();
+
+        assert!(matches!(
+            service
+                .provision_user(
+                    admin.id,
+                    provision("admin@example.com", UserRole::Admin, false)
+                )
+                .await,
+            Err(UserServiceError::ValidationError(_))
+        ));
+        assert!(matches!(
+            service
+                .provision_user(admin.id, provision("not-an-email", UserRole::Author, true))
+                .await,
+            Err(UserServiceError::ValidationError(_))
+        ));
+    }
+
     // ========================================================================
     // Registration tests
     // ========================================================================
@@ -810,6 +1737,51 @@ mod tests {
         assert!(matches!(result, Err(UserServiceError::ValidationError(_))));
     }
 
+    #[tokio::test]
+    async fn test_password_policy_from_settings() {
+        let (pool, service) = setup_test_service().await;
+        let settings_repo = Arc::new(SqlxSettingsRepository::new(pool));
+        settings_repo
+            .set(password_policy::keys::PASSWORD_MIN_LENGTH, "10")
+            .await
+            .unwrap();
+        settings_repo
+            .set(password_policy::keys::PASSWORD_REQUIRE_DIGIT, "true")
+            .await
+            .unwrap();
+        let service = service.with_settings(settings_repo);
+
+        let input = RegisterInput::new("testuser", "test@example.com", "password1");
+        let result = service.register(input).await;
+        assert!(
+            matches!(result, Err(UserServiceError::ValidationError(msg)) if msg.contains("10"))
+        );
+
+        let user = service
+            .register(RegisterInput::new(
+                "testuser",
+                "test@example.com",
+                "password123",
+            ))
+            .await
+            .unwrap();
+
+        let result = service
+            .change_password(user.clone(), "password123", "passwordabc")
+            .await;
+        assert!(matches!(result, Err(UserServiceError::ValidationError(_))));
+        let result = service
+            .change_password(user.clone(), "wrong-password", "password456")
+            .await;
+        assert!(matches!(result, Err(UserServiceError::ValidationError(_))));
+
+        let updated = service
+            .change_password(user, "password123", "password456")
+            .await
+            .unwrap();
+        assert!(verify_password("password456", &updated.password_hash).unwrap());
+    }
+
     #[tokio::test]
     async fn test_register_invalid_email_fails() {
         let (_pool, service) = setup_test_service().await;
@@ -1030,6 +2002,135 @@ mod tests {
         assert!(result.is_ok());
     }
 
+    // ========================================================================
+    // Access revocation tests
+    // ========================================================================
+
+    #[tokio::test]
+    async fn test_change_role_revokes_sessions() {
+        let (_pool, service) = setup_test_service().await;
+        let admin = service
+            .register(RegisterInput::new(
+                "admin",
+                "admin@example.com",
+                "password123",
+            ))
+            .await
+            .unwrap();
+        let author = service
+            .register(RegisterInput::new(
+                "author",
+                "author@example.com",
+                "password123",
+            ))
+            .await
+            .unwrap();
+        let session = service
+            .login(LoginInput::new("author", "password123"), None, None)
+            .await
+            .unwrap();
+
+        let result = service
+            .change_role(admin.id, admin.id, UserRole::Author)
+            .await;
+        assert!(matches!(result, Err(UserServiceError::ValidationError(_))));
+        let result = service.change_role(admin.id, 9999, UserRole::Editor).await;
+        assert!(matches!(result, Err(UserServiceError::UserNotFound)));
+
+        let updated = service
+            .change_role(admin.id, author.id, UserRole::Editor)
+            .await
+            .unwrap();
+        assert_eq!(updated.role, UserRole::Editor);
+        assert!(service
+            .validate_session(&session.id)
+            .await
+            .unwrap()
+            .is_none());
+    }
+
+    #[tokio::test]
+    async fn test_force_password_reset_until_changed() {
+        let (_pool, service) = setup_test_service().await;
+        let user = service
+            .register(RegisterInput::new(
+                "testuser",
+                "test@example.com",
+                "password123",
+            ))
+            .await
+            .unwrap();
+        let session = service
+            .login(LoginInput::new("testuser", "password123"), None, None)
+            .await
+            .unwrap();
+
+        let flagged = service.force_password_reset(user.id).await.unwrap();
+        assert!(flagged.password_reset_required);
+        assert!(service
+            .validate_session(&session.id)
+            .await
+            .unwrap()
+            .is_none());
+
+        let updated = service
+            .change_password(flagged, "password123", "password456")
+            .await
+            .unwrap();
+        assert!(!updated.password_reset_required);
+    }
+
+    #[tokio::test]
+    async fn test_create_user_and_reset_password() {
+        let (_pool, service) = setup_test_service().await;
+        service
+            .register(RegisterInput::new(
+                "first",
+                "first@example.com",
+                "password123",
+            ))
+            .await
+            .unwrap();
+
+        let admin = service
+            .create_user(
+                RegisterInput::new("ops", "ops@example.com", "password123"),
+                UserRole::Admin,
+            )
+            .await
+            .unwrap();
+        assert_eq!(admin.role, UserRole::Admin);
+        assert!(matches!(
+            service
+                .create_user(
+                    RegisterInput::new("ops", "other@example.com", "password123"),
+                    UserRole::Author,
+                )
+                .await,
+            Err(UserServiceError::UserExists(_))
+        ));
+
+        let session = service
+            .login(LoginInput::new("ops", "password123"), None, None)
+            .await
+            .unwrap();
+        service.force_password_reset(admin.id).await.unwrap();
+        let updated = service
+            .reset_password(admin.id, "password456")
+            .await
+            .unwrap();
+        assert!(!updated.password_reset_required);
+        assert!(service
+            .validate_session(&session.id)
+            .await
+            .unwrap()
+            .is_none());
+        assert!(service
+            .login(LoginInput::new("ops", "password456"), None, None)
+            .await
+            .is_ok());
+    }
+
     // ========================================================================
     // Other tests
     // ========================================================================
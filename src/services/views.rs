@@ -0,0 +1,155 @@
+//! Deduplicated article view counting
+//!
+//! `POST /view/{article_id}` is public, so reloading a page or calling the
+//! endpoint in a loop would inflate counts. A reader (IP and user agent) is
+//! counted once per article within `views.dedup_secs`; the marker lives in
+//! the cache layer, so it is shared between instances when Redis is used.
+//! Bots are skipped, and increments are buffered in memory and written every
+//! `views.flush_secs` in one transaction.
+
+use anyhow::Result;
+use chrono::Utc;
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use crate::cache::{Cache, CacheLayer};
+use crate::config::ViewsConfig;
+use crate::db::repositories::CommentRepository;
+use crate::services::analytics::is_bot;
+use crate::services::comment::generate_fingerprint;
+use crate::services::stats::day;
+
+const CACHE_KEY_SEEN: &str = "view:seen:";
+
+/// Counts article views and writes them in batches
+pub struct ViewCounter {
+    repo: Arc<dyn CommentRepository>,
+    cache: Arc<Cache>,
+    config: ViewsConfig,
+    /// Increments by day and article since the last flush
+    pending: Mutex<BTreeMap<String, HashMap<i64, i64>>>,
+}
+
+impl ViewCounter {
+    pub fn new(repo: Arc<dyn CommentRepository>, cache: Arc<Cache>, config: ViewsConfig) -> Self {
+        Self {
+            repo,
+            cache,
+            config,
+            pending: Mutex::new(BTreeMap::new()),
+        }
+    }
+
+    pub fn config(&self) -> &ViewsConfig {
+        &self.config
+    }
+
+    /// Count a view of `article_id`; false for bots and repeated views
+    pub async fn record(&self, article_id: i64, client_ip: &str, user_agent: &str) -> bool {
+        if !self.config.count_bots && is_bot(user_agent) {
+            return false;
+        }
+        if self.config.dedup_secs > 0 {
+            let key = format!(
+                "{}{}:{}",
+                CACHE_KEY_SEEN,
+                article_id,
+                generate_fingerprint(client_ip, user_agent)
+            );
+            if let Ok(Some(true)) = self.cache.get::<bool>(&key).await {
+                return false;
+            }
+            let _ = self
+                .cache
+                .set(&key, &true, Duration::from_secs(self.config.dedup_secs))
+                .await;
+        }
+
+        let today = day(Utc::now().date_naive());
+        *self
+            .pending
+            .lock()
+            .unwrap()
+            .entry(today)
+            .or_default()
+            .entry(article_id)
+            .or_default() += 1;
+        true
+    }
+
+    /// Write the buffered increments and return the articles that got new
+    /// views; increments are kept for the next flush when writing fails
+    pub async fn flush(&self) -> Result<Vec<i64>> {
+        let pending = std::mem::take(&mut *self.pending.lock().unwrap());
+        let mut updated = BTreeSet::new();
+        let mut days = pending.into_iter();
+        while let Some((day, views)) = days.next() {
+            let batch: Vec<(i64, i64)> = views.iter().map(|(&id, &count)| (id, count)).collect();
+            if let Err(e) = self.repo.add_views(&day, &batch).await {
+                let mut pending = self.pending.lock().unwrap();
+                for (day, views) in std::iter::once((day, views)).chain(days) {
+                    let entry = pending.entry(day).or_default();
+                    for (id, count) in views {
+                        *entry.entry(id).or_default() += count;
+                    }
+                }
+                return Err(e);
+            }
+            updated.extend(views.into_keys());
+        }
+        Ok(updated.into_iter().collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cache::MemoryCache;
+    use crate::db::repositories::SqlxCommentRepository;
+    use crate::db::{create_test_pool, migrations};
+
+    const BROWSER: &str = "Mozilla/5.0 (X11; Linux x86_64) Firefox/128.0";
+
+    #[tokio::test]
+    async fn repeated_views_and_bots_are_not_counted() {
+        let pool = create_test_pool().await.unwrap();
+        migrations::run_migrations(&pool).await.unwrap();
+        let sqlite = pool.as_sqlite().unwrap();
+        sqlx::query("INSERT INTO users (id, username, email, password_hash, role) VALUES (1, 'u', 'u@example.com', 'x', 'admin')")
+            .execute(sqlite)
+            .await
+            .unwrap();
+        let article_id = sqlx::query(
+            "INSERT INTO articles (slug, title, content, content_html, author_id, category_id, status) VALUES ('post', 'Post', 'c', 'c', 1, 1, 'published')",
+        )
+        .execute(sqlite)
+        .await
+        .unwrap()
+        .last_insert_rowid();
+        let counter = ViewCounter::new(
+            Arc::new(SqlxCommentRepository::new(pool.clone())),
+            Arc::new(Cache::Memory(MemoryCache::new())),
+            ViewsConfig::default(),
+        );
+
+        assert!(counter.record(article_id, "203.0.113.7", BROWSER).await);
+        assert!(!counter.record(article_id, "203.0.113.7", BROWSER).await);
+        assert!(counter.record(article_id, "203.0.113.8", BROWSER).await);
+        assert!(
+            !counter
+                .record(article_id, "203.0.113.9", "Googlebot/2.1")
+                .await
+        );
+        assert!(!counter.record(article_id, "203.0.113.9", "").await);
+
+        assert_eq!(counter.flush().await.unwrap(), vec![article_id]);
+        assert!(counter.flush().await.unwrap().is_empty());
+        let views: i64 = sqlx::query_scalar("SELECT view_count FROM articles WHERE id = ?")
+            .bind(article_id)
+            .fetch_one(sqlite)
+            .await
+            .unwrap();
+        assert_eq!(views, 2);
+    }
+}
@@ -0,0 +1,710 @@
+//! Web Push notifications for new articles
+//!
+//! Browsers register a push subscription with `POST /push/subscribe`, using
+//! the VAPID public key from `GET /push/public-key` as their
+//! `applicationServerKey`. The key pair is generated on first start and kept
+//! in the `web_push_vapid_private_key` setting.
+//!
+//! When the `web_push_enabled` setting is "true", publishing an article
+//! queues one [`NOTIFY_JOB`] that sends every subscriber a notification.
+//! Payloads are encrypted per RFC 8291 (`aes128gcm`) and requests carry a
+//! VAPID (RFC 8292) authorization. Subscriptions the push service reports
+//! as gone are removed. The payload is JSON for the site's service worker:
+//! `{"title", "body", "url", "icon", "tag"}`.
+
+use anyhow::{anyhow, Context, Result};
+use chrono::Utc;
+use data_encoding::{BASE64, BASE64URL_NOPAD};
+use once_cell::sync::{Lazy, OnceCell};
+use regex::Regex;
+use ring::rand::{SecureRandom, SystemRandom};
+use ring::signature::{EcdsaKeyPair, KeyPair, ECDSA_P256_SHA256_FIXED_SIGNING};
+use ring::{aead, agreement, hmac};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::sync::Arc;
+use std::time::Duration;
+
+use crate::db::repositories::{ArticleRepository, PushSubscriptionRepository};
+use crate::models::{ArticleStatus, PushSubscription};
+use crate::plugin::{hook_names, HookManager};
+use crate::services::outbound::{ensure_public_url, public_client_builder};
+use crate::services::settings::{keys, SettingsService};
+use crate::services::JobQueue;
+
+/// Setting that enables subscriptions and notifications on publish
+pub const WEB_PUSH_ENABLED_KEY: &str = "web_push_enabled";
+
+/// Setting holding the VAPID private key (PKCS#8, base64)
+pub const VAPID_PRIVATE_KEY: &str = "web_push_vapid_private_key";
+
+/// Job queue kind that notifies all subscribers of an article
+pub const NOTIFY_JOB: &str = "web_push_notify";
+
+/// How long push services keep undelivered notifications
+const TTL_SECONDS: u32 = 24 * 60 * 60;
+
+/// Longest accepted endpoint URL
+const MAX_ENDPOINT_LEN: usize = 512;
+
+/// Record size of the encrypted payload (a single record)
+const RECORD_SIZE: u32 = 4096;
+
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(10);
+
+static TAG_RE: Lazy<Regex> = Lazy::new(|| Regex::new(r"(?s)<[^>]*>").expect("valid tag regex"));
+
+/// Errors returned by the Web Push service
+#[derive(Debug, thiserror::Error)]
+pub enum WebPushError {
+    /// Notifications are disabled
+    #[error("Web Push is not enabled")]
+    Disabled,
+
+    #[error("Invalid push subscription: {0}")]
+    InvalidSubscription(String),
+
+    #[error("Internal error: {0}")]
+    Internal(#[from] anyhow::Error),
+}
+
+/// Payload of a [`NOTIFY_JOB`]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NotifyJob {
+    pub article_id: i64,
+}
+
+/// Result of posting a notification to a push service
+enum Delivery {
+    Sent,
+    /// The subscription expired or was revoked
+    Gone,
+}
+
+/// VAPID key pair of this server
+struct VapidKey {
+    key_pair: EcdsaKeyPair,
+    /// Uncompressed public key, base64url
+    public_key: String,
+}
+
+/// Push subscriptions and notification delivery
+pub struct WebPushService {
+    repo: Arc<dyn PushSubscriptionRepository>,
+    article_repo: Arc<dyn ArticleRepository>,
+    settings: Arc<SettingsService>,
+    queue: Arc<JobQueue>,
+    client: reqwest::Client,
+    vapid: OnceCell<VapidKey>,
+}
+
+impl WebPushService {
+    pub fn new(
+        repo: Arc<dyn PushSubscriptionRepository>,
+        article_repo: Arc<dyn ArticleRepository>,
+        settings: Arc<SettingsService>,
+        queue: Arc<JobQueue>,
+    ) -> Self {
+        let client = public_client_builder()
+            .timeout(REQUEST_TIMEOUT)
+            .user_agent(concat!("Noteva/", env!("CARGO_PKG_VERSION"), " WebPush"))
+            .build()
+            .expect("Failed to build Web Push HTTP client");
+        Self {
+            repo,
+            article_repo,
+            settings,
+            queue,
+            client,
+            vapid: OnceCell::new(),
+        }
+    }
+
+    /// Load the VAPID key pair, generating and storing one on first start
+    pub async fn init_keys(&self) -> Result<()> {
+        if self.vapid.get().is_some() {
+            return Ok(());
+        }
+        let rng = SystemRandom::new();
+        let stored = self
+            .settings
+            .get(VAPID_PRIVATE_KEY)
+            .await
+            .map_err(|e| anyhow!(e.to_string()))?
+            .filter(|s| !s.is_empty());
+        let pkcs8 = match stored {
+            Some(encoded) => BASE64
+                .decode(encoded.as_bytes())
+                .context("Invalid stored VAPID key")?,
+            None => {
+                let document = EcdsaKeyPair::generate_pkcs8(&ECDSA_P256_SHA256_FIXED_SIGNING, &rng)
+                    .map_err(|_| anyhow!("Failed to generate VAPID key"))?;
+                self.settings
+                    .set(VAPID_PRIVATE_KEY, &BASE64.encode(document.as_ref()))
+                    .await
+                    .map_err(|e| anyhow!(e.to_string()))?;
+                tracing::info!("generated Web Push VAPID key");
+                document.as_ref().to_vec()
+            }
+        };
+        let key_pair = EcdsaKeyPair::from_pkcs8(&ECDSA_P256_SHA256_FIXED_SIGNING, &pkcs8, &rng)
+            .map_err(|e| anyhow!("Invalid stored VAPID key: {}", e))?;
+        let public_key = BASE64URL_NOPAD.encode(key_pair.public_key().as_ref());
+        // Another caller may have loaded it meanwhile; both hold the same key
+        let _ = self.vapid.set(VapidKey {
+            key_pair,
+            public_key,
+        });
+        Ok(())
+    }
+
+    async fn vapid(&self) -> Result<&VapidKey> {
+        self.init_keys().await?;
+        self.vapid.get().context("VAPID key not loaded")
+    }
+
+    /// Register hooks that notify subscribers when articles get published
+    pub fn register_hooks(self: &Arc<Self>, hook_manager: &HookManager) {
+        let service = self.clone();
+        hook_manager.register(
+            hook_names::ARTICLE_STATUS_CHANGE,
+            move |data: &mut Value| {
+                if data.get("new_status").and_then(Value::as_str) == Some("Published") {
+                    service.spawn_notify(data.get("id").and_then(Value::as_i64));
+                }
+                None
+            },
+            100,
+            None,
+        );
+
+        let service = self.clone();
+        hook_manager.register(
+            hook_names::ARTICLE_AFTER_CREATE,
+            move |data: &mut Value| {
+                if data.get("status").and_then(Value::as_str) == Some("Published") {
+                    service.spawn_notify(data.get("id").and_then(Value::as_i64));
+                }
+                None
+            },
+            100,
+            None,
+        );
+    }
+
+    /// Register the handler for queued [`NOTIFY_JOB`]s
+    pub fn register_jobs(self: &Arc<Self>, queue: &JobQueue) {
+        let service = self.clone();
+        queue.register(NOTIFY_JOB, move |payload| {
+            let service = service.clone();
+            async move {
+                let job: NotifyJob =
+                    serde_json::from_value(payload).context("Invalid web push job")?;
+                service.notify_article(job.article_id).await
+            }
+        });
+    }
+
+    fn spawn_notify(self: &Arc<Self>, article_id: Option<i64>) {
+        let (Some(article_id), Ok(handle)) = (article_id, tokio::runtime::Handle::try_current())
+        else {
+            return;
+        };
+        let service = self.clone();
+        handle.spawn(async move {
+            if !service.is_enabled().await {
+                return;
+            }
+            if let Err(e) = service.queue_article(article_id).await {
+                tracing::warn!(article_id, error = %e, "failed to queue push notifications");
+            }
+        });
+    }
+
+    pub async fn is_enabled(&self) -> bool {
+        matches!(
+            self.settings.get(WEB_PUSH_ENABLED_KEY).await,
+            Ok(Some(ref v)) if v == "true"
+        )
+    }
+
+    /// VAPID public key for `PushManager.subscribe()`, base64url
+    pub async fn public_key(&self) -> Result<String, WebPushError> {
+        if !self.is_enabled().await {
+            return Err(WebPushError::Disabled);
+        }
+        Ok(self.vapid().await?.public_key.clone())
+    }
+
+    /// Store a browser's push subscription
+    ///
+    /// `p256dh` and `auth` are the base64url keys of
+    /// `PushSubscription.toJSON()`. Subscribing again with a known endpoint
+    /// replaces its keys.
+    pub async fn subscribe(
+        &self,
+        endpoint: &str,
+        p256dh: &str,
+        auth: &str,
+    ) -> Result<PushSubscription, WebPushError> {
+        if !self.is_enabled().await {
+            return Err(WebPushError::Disabled);
+        }
+        let invalid = |message: &str| WebPushError::InvalidSubscription(message.to_string());
+        let endpoint = endpoint.trim();
+        if endpoint.len() > MAX_ENDPOINT_LEN || !endpoint.starts_with("https://") {
+            return Err(invalid("endpoint must be an https URL"));
+        }
+        ensure_public_url(endpoint)
+            .await
+            .map_err(|e| WebPushError::InvalidSubscription(e.to_string()))?;
+        let p256dh = p256dh.trim().trim_end_matches('=');
+        let auth = auth.trim().trim_end_matches('=');
+        match decode_key(p256dh) {
+            Some(key) if key.len() == 65 && key[0] == 0x04 => {}
+            _ => return Err(invalid("p256dh must be an uncompressed P-256 key")),
+        }
+        if decode_key(auth).map(|a| a.len()) != Some(16) {
+            return Err(invalid("auth must be 16 bytes"));
+        }
+        Ok(self.repo.upsert(endpoint, p256dh, auth).await?)
+    }
+
+    /// Remove a push subscription. Returns whether it existed.
+    ///
+    /// Works even when Web Push has been disabled since.
+    pub async fn unsubscribe(&self, endpoint: &str) -> Result<bool> {
+        self.repo.delete_by_endpoint(endpoint.trim()).await
+    }
+
+    /// Number of stored subscriptions
+    pub async fn count(&self) -> Result<i64> {
+        self.repo.count().await
+    }
+
+    /// Queue notifications for an article unless they went out before
+    async fn queue_article(&self, article_id: i64) -> Result<()> {
+        if self.repo.count().await? == 0 {
+            return Ok(());
+        }
+        // Subscribers are anonymous; private articles are never announced
+        if !self.article_repo.is_public(article_id).await? {
+            return Ok(());
+        }
+        if !self.repo.mark_notified(article_id, Utc::now()).await? {
+            // Republishing an article does not notify again
+            return Ok(());
+        }
+        self.queue
+            .enqueue(NOTIFY_JOB, &NotifyJob { article_id })
+            .await
+            .map_err(anyhow::Error::from)?;
+        Ok(())
+    }
+
+    /// Send a published article to every subscriber
+    async fn notify_article(&self, article_id: i64) -> Result<()> {
+        let Some(article) = self.article_repo.get_by_id(article_id).await? else {
+            return Ok(());
+        };
+        if article.status != ArticleStatus::Published
+            || !self.article_repo.is_public(article_id).await?
+        {
+            return Ok(());
+        }
+        let site_url = self
+            .settings
+            .get(keys::SITE_URL)
+            .await
+            .ok()
+            .flatten()
+            .map(|s| s.trim().trim_end_matches('/').to_string())
+            .filter(|s| !s.is_empty())
+            .context("The site URL must be configured to send push notifications")?;
+        let permalink_structure = self
+            .settings
+            .get(keys::PERMALINK_STRUCTURE)
+            .await
+            .ok()
+            .flatten()
+            .unwrap_or_else(|| "/posts/{slug}".to_string());
+        let identifier = if permalink_structure.contains("{id}") {
+            article.id.to_string()
+        } else {
+            article.slug.clone()
+        };
+        let icon = article
+            .thumbnail
+            .as_deref()
+            .filter(|t| !t.is_empty())
+            .map(|t| {
+                if t.starts_with('/') {
+                    format!("{}{}", site_url, t)
+                } else {
+                    t.to_string()
+                }
+            });
+        let payload = json!({
+            "title": article.title,
+            "body": excerpt(&article.content_html, 120),
+            "url": format!("{}/posts/{}", site_url, identifier),
+            "icon": icon,
+            "tag": format!("article-{}", article.id),
+        })
+        .to_string();
+
+        let vapid = self.vapid().await?;
+        let (mut sent, mut removed) = (0, 0);
+        for subscription in self.repo.list().await? {
+            match self.send(vapid, &site_url, &subscription, &payload).await {
+                Ok(Delivery::Sent) => sent += 1,
+                Ok(Delivery::Gone) => {
+                    self.repo.delete_by_endpoint(&subscription.endpoint).await?;
+                    removed += 1;
+                }
+                Err(e) => {
+                    tracing::warn!(
+                        subscription_id = subscription.id,
+                        error = %e,
+                        "push notification failed"
+                    );
+                }
+            }
+        }
+        tracing::info!(article_id, sent, removed, "push notifications sent");
+        Ok(())
+    }
+
+    /// Post an encrypted notification to a subscription's push service
+    async fn send(
+        &self,
+        vapid: &VapidKey,
+        site_url: &str,
+        subscription: &PushSubscription,
+        payload: &str,
+    ) -> Result<Delivery> {
+        let endpoint = ensure_public_url(&subscription.endpoint).await?;
+        let ua_public = decode_key(&subscription.p256dh).context("Invalid p256dh key")?;
+        let auth = decode_key(&subscription.auth).context("Invalid auth secret")?;
+        let body = encrypt(&ua_public, &auth, payload.as_bytes())?;
+
+        let audience = endpoint.origin().ascii_serialization();
+        let token = vapid_token(&vapid.key_pair, &audience, site_url)?;
+        let response = self
+            .client
+            .post(endpoint)
+            .header(
+                reqwest::header::AUTHORIZATION,
+                format!("vapid t={}, k={}", token, vapid.public_key),
+            )
+            .header(reqwest::header::CONTENT_ENCODING, "aes128gcm")
+            .header(reqwest::header::CONTENT_TYPE, "application/octet-stream")
+            .header("TTL", TTL_SECONDS.to_string())
+            .body(body)
+            .send()
+            .await
+            .context("Failed to reach push service")?;
+
+        match response.status() {
+            status if status.is_success() => Ok(Delivery::Sent),
+            reqwest::StatusCode::NOT_FOUND | reqwest::StatusCode::GONE => Ok(Delivery::Gone),
+            status => Err(anyhow!("Push service returned HTTP {}", status)),
+        }
+    }
+}
+
+/// Decode a base64url key, with or without padding
+fn decode_key(value: &str) -> Option<Vec<u8>> {
+    BASE64URL_NOPAD
+        .decode(value.trim_end_matches('=').as_bytes())
+        .ok()
+}
+
+/// Plain-text start of an article for the notification body
+fn excerpt(html: &str, max_chars: usize) -> String {
+    let text = TAG_RE.replace_all(html, " ");
+    let text = text
+        .replace("&nbsp;", " ")
+        .replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&quot;", "\"")
+        .replace("&#39;", "'")
+        .replace("&amp;", "&");
+    let text = text.split_whitespace().collect::<Vec<_>>().join(" ");
+    match text.char_indices().nth(max_chars) {
+        Some((end, _)) => format!("{}…", text[..end].trim_end()),
+        None => text,
+    }
+}
+
+/// Signed VAPID JWT for requests to `audience` (RFC 8292)
+fn vapid_token(key_pair: &EcdsaKeyPair, audience: &str, subject: &str) -> Result<String> {
+    let header = BASE64URL_NOPAD.encode(br#"{"typ":"JWT","alg":"ES256"}"#);
+    let claims = json!({
+        "aud": audience,
+        "exp": Utc::now().timestamp() + 12 * 60 * 60,
+        "sub": subject,
+    });
+    let claims = BASE64URL_NOPAD.encode(claims.to_string().as_bytes());
+    let signing_input = format!("{}.{}", header, claims);
+    let signature = key_pair
+        .sign(&SystemRandom::new(), signing_input.as_bytes())
+        .map_err(|_| anyhow!("Failed to sign VAPID token"))?;
+    Ok(format!(
+        "{}.{}",
+        signing_input,
+        BASE64URL_NOPAD.encode(signature.as_ref())
+    ))
+}
+
+/// Encrypt a payload for a subscription (RFC 8291, `aes128gcm`)
+///
+/// Returns the request body: the RFC 8188 header with a fresh salt and
+/// ephemeral public key, followed by the single encrypted record.
+fn encrypt(ua_public: &[u8], auth_secret: &[u8], payload: &[u8]) -> Result<Vec<u8>> {
+    let rng = SystemRandom::new();
+    let as_private = agreement::EphemeralPrivateKey::generate(&agreement::ECDH_P256, &rng)
+        .map_err(|_| anyhow!("Failed to generate ephemeral key"))?;
+    let as_public = as_private
+        .compute_public_key()
+        .map_err(|_| anyhow!("Failed to compute ephemeral public key"))?;
+    let ecdh_secret = agreement::agree_ephemeral(
+        as_private,
+        &agreement::UnparsedPublicKey::new(&agreement::ECDH_P256, ua_public),
+        |secret| secret.to_vec(),
+    )
+    .map_err(|_| anyhow!("Invalid subscription public key"))?;
+    let mut salt = [0u8; 16];
+    rng.fill(&mut salt)
+        .map_err(|_| anyhow!("Failed to generate salt"))?;
+
+    let (cek, nonce) = derive_key_and_nonce(
+        &ecdh_secret,
+        auth_secret,
+        ua_public,
+        as_public.as_ref(),
+        &salt,
+    );
+    encrypt_record(&cek, &nonce, &salt, as_public.as_ref(), payload)
+}
+
+/// Content encryption key and nonce (RFC 8291 section 3.4)
+fn derive_key_and_nonce(
+    ecdh_secret: &[u8],
+    auth_secret: &[u8],
+    ua_public: &[u8],
+    as_public: &[u8],
+    salt: &[u8],
+) -> ([u8; 16], [u8; 12]) {
+    let mut key_info = b"WebPush: info\0".to_vec();
+    key_info.extend_from_slice(ua_public);
+    key_info.extend_from_slice(as_public);
+    let prk_key = hkdf_extract(auth_secret, ecdh_secret);
+    let ikm = hkdf_expand(&prk_key, &key_info);
+
+    let prk = hkdf_extract(salt, &ikm);
+    let mut cek = [0u8; 16];
+    cek.copy_from_slice(&hkdf_expand(&prk, b"Content-Encoding: aes128gcm\0")[..16]);
+    let mut nonce = [0u8; 12];
+    nonce.copy_from_slice(&hkdf_expand(&prk, b"Content-Encoding: nonce\0")[..12]);
+    (cek, nonce)
+}
+
+fn hkdf_extract(salt: &[u8], ikm: &[u8]) -> hmac::Tag {
+    hmac::sign(&hmac::Key::new(hmac::HMAC_SHA256, salt), ikm)
+}
+
+/// First output block of HKDF-Expand, enough for every key used here
+fn hkdf_expand(prk: &hmac::Tag, info: &[u8]) -> [u8; 32] {
+    let mut input = info.to_vec();
+    input.push(0x01);
+    let tag = hmac::sign(&hmac::Key::new(hmac::HMAC_SHA256, prk.as_ref()), &input);
+    let mut output = [0u8; 32];
+    output.copy_from_slice(tag.as_ref());
+    output
+}
+
+/// Encrypt the payload as the last (and only) record, with its header
+fn encrypt_record(
+    cek: &[u8; 16],
+    nonce: &[u8; 12],
+    salt: &[u8; 16],
+    as_public: &[u8],
+    payload: &[u8],
+) -> Result<Vec<u8>> {
+    // Delimiter, tag and header must fit in the record
+    if payload.len() + 1 + 16 > RECORD_SIZE as usize {
+        anyhow::bail!("Push payload is too large");
+    }
+    let key = aead::LessSafeKey::new(
+        aead::UnboundKey::new(&aead::AES_128_GCM, cek).map_err(|_| anyhow!("Invalid key"))?,
+    );
+    let mut record = payload.to_vec();
+    record.push(0x02);
+    key.seal_in_place_append_tag(
+        aead::Nonce::assume_unique_for_key(*nonce),
+        aead::Aad::empty(),
+        &mut record,
+    )
+    .map_err(|_| anyhow!("Failed to encrypt push payload"))?;
+
+    let mut body = Vec::with_capacity(16 + 4 + 1 + as_public.len() + record.len());
+    body.extend_from_slice(salt);
+    body.extend_from_slice(&RECORD_SIZE.to_be_bytes());
+    body.push(as_public.len() as u8);
+    body.extend_from_slice(as_public);
+    body.extend_from_slice(&record);
+    Ok(body)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::JobQueueConfig;
+    use crate::db::repositories::{
+        ReaderGroupRepository, SqlxArticleRepository, SqlxJobQueueRepository,
+        SqlxPushSubscriptionRepository, SqlxReaderGroupRepository, SqlxSettingsRepository,
+    };
+    use crate::db::{create_test_pool, migrations};
+    use crate::models::{ArticleAudience, CreateArticleInput, UserRole};
+
+    fn b64(value: &str) -> Vec<u8> {
+        decode_key(value).unwrap()
+    }
+
+    /// Test vector from RFC 8291 appendix A
+    #[test]
+    fn encrypts_rfc8291_example() {
+        let ua_public = b64("BCVxsr7N_eNgVRqvHtD0zTZsEc6-VV-JvLexhqUzORcxaOzi6-AYWXvTBHm4bjyPjs7Vd8pZGH6SRpkNtoIAiw4");
+        let as_public = b64("BP4z9KsN6nGRTbVYI_c7VJSPQTBtkgcy27mlmlMoZIIgDll6e3vCYLocInmYWAmS6TlzAC8wEqKK6PBru3jl7A8");
+        let auth_secret = b64("BTBZMqHH6r4Tts7J_aSIgg");
+        let ecdh_secret = b64("kyrL1jIIOHEzg3sM2ZWRHDRB62YACZhhSlknJ672kSs");
+        let salt: [u8; 16] = b64("DGv6ra1nlYgDCS1FRnbzlw").try_into().unwrap();
+
+        let (cek, nonce) =
+            derive_key_and_nonce(&ecdh_secret, &auth_secret, &ua_public, &as_public, &salt);
+        assert_eq!(BASE64URL_NOPAD.encode(&cek), "oIhVW04MRdy2XN9CiKLxTg");
+        assert_eq!(BASE64URL_NOPAD.encode(&nonce), "4h_95klXJ5E_qnoN");
+
+        let body = encrypt_record(
+            &cek,
+            &nonce,
+            &salt,
+            &as_public,
+            b"When I grow up, I want to be a watermelon",
+        )
+        .unwrap();
+        assert_eq!(
+            BASE64URL_NOPAD.encode(&body),
+            "DGv6ra1nlYgDCS1FRnbzlwAAEABBBP4z9KsN6nGRTbVYI_c7VJSPQTBtkgcy27mlmlMoZIIgDll6e3vCYLocInmYWAmS6TlzAC8wEqKK6PBru3jl7A_yl95bQpu6cVPTpK4Mqgkf1CXztLVBSt2Ks3oZwbuwXPXLWyouBWLVWGNWQexSgSxsj_Qulcy4a-fN"
+        );
+    }
+
+    #[test]
+    fn vapid_tokens_verify_with_the_public_key() {
+        let rng = SystemRandom::new();
+        let pkcs8 = EcdsaKeyPair::generate_pkcs8(&ECDSA_P256_SHA256_FIXED_SIGNING, &rng).unwrap();
+        let key_pair =
+            EcdsaKeyPair::from_pkcs8(&ECDSA_P256_SHA256_FIXED_SIGNING, pkcs8.as_ref(), &rng)
+                .unwrap();
+        let token = vapid_token(
+            &key_pair,
+            "https://push.example.net",
+            "https://blog.example.com",
+        )
+        .unwrap();
+
+        let (signing_input, signature) = token.rsplit_once('.').unwrap();
+        ring::signature::UnparsedPublicKey::new(
+            &ring::signature::ECDSA_P256_SHA256_FIXED,
+            key_pair.public_key().as_ref(),
+        )
+        .verify(signing_input.as_bytes(), &b64(signature))
+        .unwrap();
+        let claims: Value =
+            serde_json::from_slice(&b64(signing_input.split_once('.').unwrap().1)).unwrap();
+        assert_eq!(claims["aud"], "https://push.example.net");
+        assert_eq!(claims["sub"], "https://blog.example.com");
+    }
+
+    #[test]
+    fn excerpts_are_plain_text() {
+        assert_eq!(
+            excerpt(
+                "<p>Hello &amp; <em>welcome</em></p>\n<p>to the blog</p>",
+                100
+            ),
+            "Hello & welcome to the blog"
+        );
+        assert_eq!(excerpt("<p>abcdef</p>", 3), "abc…");
+    }
+
+    #[tokio::test]
+    async fn articles_with_an_audience_are_not_announced() {
+        let pool = create_test_pool()
+            .await
+            .expect("Failed to create test pool");
+        migrations::run_migrations(&pool)
+            .await
+            .expect("Failed to run migrations");
+        let author_id = sqlx::query(
+            "INSERT INTO users (username, email, password_hash, role) VALUES ('author', 'author@example.com', 'hash', 'author')",
+        )
+        .execute(pool.as_sqlite().unwrap())
+        .await
+        .unwrap()
+        .last_insert_rowid();
+
+        let article_repo = Arc::new(SqlxArticleRepository::new(pool.clone()));
+        let mut input = CreateArticleInput::new(
+            "members-only".to_string(),
+            "Members only".to_string(),
+            "Hello".to_string(),
+            author_id,
+            1,
+        );
+        input.status = Some(ArticleStatus::Published);
+        let article = article_repo.create(&input).await.unwrap();
+        let audiences = SqlxReaderGroupRepository::new(pool.clone());
+        audiences
+            .set_audience(
+                article.id,
+                &ArticleAudience {
+                    roles: vec![UserRole::Author],
+                    group_ids: Vec::new(),
+                },
+            )
+            .await
+            .unwrap();
+
+        let subscriptions = SqlxPushSubscriptionRepository::boxed(pool.clone());
+        subscriptions
+            .upsert("https://push.example.net/1", "p256dh", "auth")
+            .await
+            .unwrap();
+        let queue = Arc::new(JobQueue::new(
+            SqlxJobQueueRepository::boxed(pool.clone()),
+            JobQueueConfig::default(),
+        ));
+        let service = WebPushService::new(
+            subscriptions,
+            article_repo,
+            Arc::new(SettingsService::new(Arc::new(SqlxSettingsRepository::new(
+                pool.clone(),
+            )))),
+            queue.clone(),
+        );
+
+        service.queue_article(article.id).await.unwrap();
+        assert!(queue.overview(None, 10).await.unwrap().jobs.is_empty());
+
+        // Lifting the restriction later still announces the article once
+        audiences
+            .set_audience(article.id, &ArticleAudience::default())
+            .await
+            .unwrap();
+        service.queue_article(article.id).await.unwrap();
+        service.queue_article(article.id).await.unwrap();
+        assert_eq!(queue.overview(None, 10).await.unwrap().jobs.len(), 1);
+    }
+}
@@ -0,0 +1,917 @@
+//! Passkey (WebAuthn) service
+//!
+//! Runs the relying party side of the WebAuthn registration and
+//! authentication ceremonies with `webauthn-rs`, so admins can sign in with
+//! a passkey instead of a password. Options and credentials use the
+//! standard WebAuthn JSON encoding (binary values as base64url strings); the
+//! admin UI converts them for `navigator.credentials.create()` / `.get()`.
+//!
+//! Passkeys always require user verification (PIN or biometrics), and each
+//! credential is stored as the serialized `webauthn_rs::prelude::Passkey`.
+//!
+//! Settings:
+//! - `webauthn_enabled`: "true" to enable passkeys
+//! - `webauthn_rp_id`: relying party ID, defaults to the host of `site_url`
+//! - `webauthn_origin`: expected origin, defaults to the origin of `site_url`
+//! - `webauthn_allow_password_fallback`: "false" to stop admins who have a
+//!   passkey from signing in with their password
+
+use anyhow::Context;
+use chrono::{DateTime, Duration, Utc};
+use data_encoding::BASE64URL_NOPAD;
+use serde::Deserialize;
+use serde_json::Value;
+use std::collections::HashMap;
+use std::sync::Arc;
+use tokio::sync::RwLock;
+use webauthn_rs::prelude::{
+    CredentialID, DiscoverableAuthentication, DiscoverableKey, Passkey, PasskeyAuthentication,
+    PasskeyRegistration, Url, Uuid, Webauthn, WebauthnBuilder,
+};
+
+use crate::db::repositories::{UserRepository, WebauthnCredentialRepository};
+use crate::models::{
+    CreateWebauthnCredentialInput, User, UserRole, WebauthnCredential, MAX_PASSKEY_NAME_LEN,
+};
+use crate::services::settings::{self, SettingsService};
+
+pub use webauthn_rs::prelude::{
+    PublicKeyCredential as AuthenticationCredential,
+    RegisterPublicKeyCredential as RegistrationCredential,
+};
+
+/// Setting keys for passkeys
+pub mod keys {
+    pub const WEBAUTHN_ENABLED: &str = "webauthn_enabled";
+    pub const WEBAUTHN_RP_ID: &str = "webauthn_rp_id";
+    pub const WEBAUTHN_ORIGIN: &str = "webauthn_origin";
+    pub const WEBAUTHN_ALLOW_PASSWORD_FALLBACK: &str = "webauthn_allow_password_fallback";
+}
+
+/// How long a ceremony challenge stays valid
+const CHALLENGE_TTL_SECS: i64 = 5 * 60;
+
+/// Upper bound on outstanding challenges. Login challenges are issued to
+/// anonymous clients, so the map must not grow without limit.
+const MAX_PENDING_CHALLENGES: usize = 10_000;
+
+/// Maximum number of passkeys per user
+const MAX_CREDENTIALS_PER_USER: i64 = 10;
+
+/// Errors returned by the passkey service
+#[derive(Debug, thiserror::Error)]
+pub enum WebauthnError {
+    /// Passkeys are disabled in settings
+    #[error("Passkeys are not enabled")]
+    Disabled,
+
+    /// Relying party ID or origin cannot be determined
+    #[error("Passkeys are not configured: {0}")]
+    NotConfigured(String),
+
+    /// The user may not use passkeys
+    #[error("Passkeys are only available to administrators")]
+    Forbidden,
+
+    /// Unknown, expired or already used challenge
+    #[error("Invalid or expired challenge")]
+    InvalidChallenge,
+
+    /// Too many ceremonies are in flight
+    #[error("Too many pending passkey challenges, try again later")]
+    TooManyChallenges,
+
+    /// The authenticator response did not verify
+    #[error("Passkey verification failed: {0}")]
+    VerificationFailed(String),
+
+    /// Invalid input
+    #[error("Validation error: {0}")]
+    ValidationError(String),
+
+    /// Credential not found
+    #[error("Passkey not found")]
+    NotFound,
+
+    /// Internal error
+    #[error("Internal error: {0}")]
+    Internal(#[from] anyhow::Error),
+}
+
+fn verification_failed(msg: &str) -> WebauthnError {
+    WebauthnError::VerificationFailed(msg.to_string())
+}
+
+/// Map a `webauthn-rs` ceremony error
+fn rejected(e: webauthn_rs::prelude::WebauthnError) -> WebauthnError {
+    WebauthnError::VerificationFailed(e.to_string())
+}
+
+/// Relying party configuration resolved from settings
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct RelyingParty {
+    id: String,
+    origin: String,
+    name: String,
+}
+
+impl RelyingParty {
+    fn from_settings(values: &HashMap<String, String>) -> Result<Self, WebauthnError> {
+        let get = |key: &str| {
+            values
+                .get(key)
+                .map(|v| v.trim().to_string())
+                .filter(|v| !v.is_empty())
+        };
+        let site_url = get(settings::keys::SITE_URL).and_then(|u| Url::parse(&u).ok());
+
+        let origin = match get(keys::WEBAUTHN_ORIGIN) {
+            Some(origin) => origin.trim_end_matches('/').to_string(),
+            None => site_url
+                .as_ref()
+                .map(|u| u.origin().ascii_serialization())
+                .filter(|o| o != "null")
+                .ok_or_else(|| {
+                    WebauthnError::NotConfigured("set site_url or webauthn_origin".to_string())
+                })?,
+        };
+        let id = match get(keys::WEBAUTHN_RP_ID) {
+            Some(id) => id,
+            None => Url::parse(&origin)
+                .ok()
+                .and_then(|u| u.host_str().map(str::to_string))
+                .ok_or_else(|| WebauthnError::NotConfigured("set webauthn_rp_id".to_string()))?,
+        };
+
+        Ok(Self {
+            id,
+            origin,
+            name: get(settings::keys::SITE_NAME).unwrap_or_else(|| "Noteva".to_string()),
+        })
+    }
+
+    /// Build the `webauthn-rs` relying party
+    fn webauthn(&self) -> Result<Webauthn, WebauthnError> {
+        let origin = Url::parse(&self.origin)
+            .map_err(|_| WebauthnError::NotConfigured("invalid webauthn_origin".to_string()))?;
+        WebauthnBuilder::new(&self.id, &origin)
+            .and_then(|builder| {
+                builder
+                    .rp_name(&self.name)
+                    .timeout(std::time::Duration::from_secs(CHALLENGE_TTL_SECS as u64))
+                    .build()
+            })
+            .map_err(|e| WebauthnError::NotConfigured(e.to_string()))
+    }
+}
+
+/// What a pending challenge was issued for, with the `webauthn-rs` state
+/// needed to finish the ceremony
+enum Ceremony {
+    Registration {
+        user_id: i64,
+        state: PasskeyRegistration,
+    },
+    /// Login offering the passkeys of a named user
+    Authentication {
+        user_id: Option<i64>,
+        state: PasskeyAuthentication,
+    },
+    /// Login with a discoverable credential
+    Discoverable { state: DiscoverableAuthentication },
+}
+
+struct PendingChallenge {
+    ceremony: Ceremony,
+    expires_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Deserialize)]
+struct ClientData {
+    challenge: String,
+}
+
+/// Decode base64url, tolerating padding
+fn decode_b64url(value: &str) -> Result<Vec<u8>, WebauthnError> {
+    BASE64URL_NOPAD
+        .decode(value.trim().trim_end_matches('=').as_bytes())
+        .map_err(|_| WebauthnError::ValidationError("Invalid base64url data".to_string()))
+}
+
+/// Opaque WebAuthn user handle for a user
+fn user_handle(user_id: i64) -> Uuid {
+    Uuid::from_u64_pair(0, user_id as u64)
+}
+
+/// Load the `webauthn-rs` state of a stored credential
+fn load_passkey(stored: &WebauthnCredential) -> Result<Passkey, WebauthnError> {
+    Ok(serde_json::from_str(&stored.public_key).context("Stored passkey is corrupt")?)
+}
+
+/// Passkey registration, authentication and credential management
+pub struct WebauthnService {
+    credential_repo: Arc<dyn WebauthnCredentialRepository>,
+    user_repo: Arc<dyn UserRepository>,
+    settings: Arc<SettingsService>,
+    challenges: RwLock<HashMap<String, PendingChallenge>>,
+}
+
+impl WebauthnService {
+    pub fn new(
+        credential_repo: Arc<dyn WebauthnCredentialRepository>,
+        user_repo: Arc<dyn UserRepository>,
+        settings: Arc<SettingsService>,
+    ) -> Self {
+        Self {
+            credential_repo,
+            user_repo,
+            settings,
+            challenges: RwLock::new(HashMap::new()),
+        }
+    }
+
+    async fn setting_is(&self, key: &str, expected: &str) -> bool {
+        matches!(self.settings.get(key).await, Ok(Some(ref v)) if v.trim() == expected)
+    }
+
+    /// Whether passkeys are enabled in settings
+    pub async fn is_enabled(&self) -> bool {
+        self.setting_is(keys::WEBAUTHN_ENABLED, "true").await
+    }
+
+    async fn relying_party(&self) -> Result<Webauthn, WebauthnError> {
+        if !self.is_enabled().await {
+            return Err(WebauthnError::Disabled);
+        }
+        let mut values = HashMap::new();
+        for key in [
+            settings::keys::SITE_URL,
+            settings::keys::SITE_NAME,
+            keys::WEBAUTHN_RP_ID,
+            keys::WEBAUTHN_ORIGIN,
+        ] {
+            if let Some(value) = self
+                .settings
+                .get(key)
+                .await
+                .context("Failed to read settings")?
+            {
+                values.insert(key.to_string(), value);
+            }
+        }
+        RelyingParty::from_settings(&values)?.webauthn()
+    }
+
+    async fn issue_challenge(
+        &self,
+        challenge: &[u8],
+        ceremony: Ceremony,
+    ) -> Result<(), WebauthnError> {
+        let now = Utc::now();
+        let mut challenges = self.challenges.write().await;
+        challenges.retain(|_, pending| pending.expires_at > now);
+        if challenges.len() >= MAX_PENDING_CHALLENGES {
+            return Err(WebauthnError::TooManyChallenges);
+        }
+        challenges.insert(
+            BASE64URL_NOPAD.encode(challenge),
+            PendingChallenge {
+                ceremony,
+                expires_at: now + Duration::seconds(CHALLENGE_TTL_SECS),
+            },
+        );
+        Ok(())
+    }
+
+    /// Consume the challenge answered by `client_data_json`. Its type and
+    /// origin are checked by `webauthn-rs` when the ceremony finishes.
+    async fn take_challenge(&self, client_data_json: &[u8]) -> Result<Ceremony, WebauthnError> {
+        let client_data: ClientData = serde_json::from_slice(client_data_json)
+            .map_err(|_| verification_failed("Malformed client data"))?;
+        let challenge = BASE64URL_NOPAD.encode(&decode_b64url(&client_data.challenge)?);
+        let pending = self
+            .challenges
+            .write()
+            .await
+            .remove(&challenge)
+            .filter(|p| p.expires_at > Utc::now())
+            .ok_or(WebauthnError::InvalidChallenge)?;
+        Ok(pending.ceremony)
+    }
+
+    /// Begin registering a passkey for `user`. Returns the
+    /// `PublicKeyCredentialCreationOptions` for the browser.
+    pub async fn start_registration(&self, user: &User) -> Result<Value, WebauthnError> {
+        let webauthn = self.relying_party().await?;
+        if user.role != UserRole::Admin {
+            return Err(WebauthnError::Forbidden);
+        }
+        let existing = self.credential_repo.list_by_user(user.id).await?;
+        if existing.len() as i64 >= MAX_CREDENTIALS_PER_USER {
+            return Err(WebauthnError::ValidationError(format!(
+                "At most {} passkeys can be registered",
+                MAX_CREDENTIALS_PER_USER
+            )));
+        }
+        let exclude = existing
+            .iter()
+            .map(|c| decode_b64url(&c.credential_id).map(CredentialID::from))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let (options, state) = webauthn
+            .start_passkey_registration(
+                user_handle(user.id),
+                &user.username,
+                user.display_name
+                    .as_deref()
+                    .filter(|name| !name.trim().is_empty())
+                    .unwrap_or(&user.username),
+                Some(exclude),
+            )
+            .map_err(|e| anyhow::anyhow!("Failed to start passkey registration: {}", e))?;
+        self.issue_challenge(
+            &options.public_key.challenge,
+            Ceremony::Registration {
+                user_id: user.id,
+                state,
+            },
+        )
+        .await?;
+        Ok(serde_json::to_value(&options).context("Failed to encode creation options")?)
+    }
+
+    /// Verify a registration response and store the new passkey
+    pub async fn finish_registration(
+        &self,
+        user: &User,
+        name: &str,
+        credential: &RegistrationCredential,
+    ) -> Result<WebauthnCredential, WebauthnError> {
+        let name = name.trim();
+        if name.is_empty() || name.chars().count() > MAX_PASSKEY_NAME_LEN {
+            return Err(WebauthnError::ValidationError(format!(
+                "Passkey name must be 1-{} characters",
+                MAX_PASSKEY_NAME_LEN
+            )));
+        }
+
+        let webauthn = self.relying_party().await?;
+        let state = match self
+            .take_challenge(&credential.response.client_data_json)
+            .await?
+        {
+            Ceremony::Registration { user_id, state } if user_id == user.id => state,
+            _ => return Err(WebauthnError::InvalidChallenge),
+        };
+        let passkey = webauthn
+            .finish_passkey_registration(credential, &state)
+            .map_err(rejected)?;
+
+        let credential_id = BASE64URL_NOPAD.encode(passkey.cred_id());
+        if self
+            .credential_repo
+            .get_by_credential_id(&credential_id)
+            .await?
+            .is_some()
+        {
+            return Err(WebauthnError::ValidationError(
+                "This passkey is already registered".to_string(),
+            ));
+        }
+
+        let stored = self
+            .credential_repo
+            .create(&CreateWebauthnCredentialInput {
+                user_id: user.id,
+                credential_id,
+                public_key: serde_json::to_string(&passkey).context("Failed to encode passkey")?,
+                sign_count: 0,
+                name: name.to_string(),
+            })
+            .await?;
+        Ok(stored)
+    }
+
+    /// Begin a passkey login. With a username, the browser is told which
+    /// credentials to offer; without one, discoverable credentials are used.
+    pub async fn start_authentication(
+        &self,
+        username: Option<&str>,
+    ) -> Result<Value, WebauthnError> {
+        let webauthn = self.relying_party().await?;
+        let failed = |e| anyhow::anyhow!("Failed to start passkey login: {}", e);
+
+        let (options, ceremony) = match username.map(str::trim).filter(|u| !u.is_empty()) {
+            Some(username) => {
+                // Unknown users get an empty list, so the response does not
+                // reveal which accounts exist
+                let mut user_id = None;
+                let mut passkeys = Vec::new();
+                if let Some(user) = self.user_repo.get_by_username(username).await? {
+                    for stored in self.credential_repo.list_by_user(user.id).await? {
+                        passkeys.push(load_passkey(&stored)?);
+                    }
+                    user_id = Some(user.id);
+                }
+                let (options, state) = webauthn
+                    .start_passkey_authentication(&passkeys)
+                    .map_err(failed)?;
+                (options, Ceremony::Authentication { user_id, state })
+            }
+            None => {
+                let (mut options, state) = webauthn
+                    .start_discoverable_authentication()
+                    .map_err(failed)?;
+                // Leave the choice of modal or autofill UI to the admin UI
+                options.mediation = None;
+                (options, Ceremony::Discoverable { state })
+            }
+        };
+        self.issue_challenge(&options.public_key.challenge, ceremony)
+            .await?;
+        Ok(serde_json::to_value(&options).context("Failed to encode request options")?)
+    }
+
+    /// Verify an authentication response and return the signed-in user
+    pub async fn finish_authentication(
+        &self,
+        credential: &AuthenticationCredential,
+    ) -> Result<User, WebauthnError> {
+        let webauthn = self.relying_party().await?;
+        let ceremony = self
+            .take_challenge(&credential.response.client_data_json)
+            .await?;
+
+        let credential_id = BASE64URL_NOPAD.encode(credential.get_credential_id());
+        let stored = self
+            .credential_repo
+            .get_by_credential_id(&credential_id)
+            .await?
+            .ok_or_else(|| verification_failed("Unknown passkey"))?;
+        if let Some(handle) = credential.get_user_unique_id() {
+            if !handle.is_empty() && handle != user_handle(stored.user_id).as_bytes() {
+                return Err(verification_failed("User handle mismatch"));
+            }
+        }
+        let mut passkey = load_passkey(&stored)?;
+
+        // webauthn-rs checks the challenge, origin, RP ID, user verification,
+        // signature and that the signature counter increased
+        let result = match ceremony {
+            Ceremony::Authentication { user_id, state } => {
+                if user_id.is_some_and(|id| id != stored.user_id) {
+                    return Err(verification_failed("Passkey belongs to another user"));
+                }
+                webauthn.finish_passkey_authentication(credential, &state)
+            }
+            Ceremony::Discoverable { state } => webauthn.finish_discoverable_authentication(
+                credential,
+                state,
+                &[DiscoverableKey::from(&passkey)],
+            ),
+            Ceremony::Registration { .. } => return Err(WebauthnError::InvalidChallenge),
+        };
+        let result = result.map_err(|e| {
+            tracing::warn!(
+                user_id = stored.user_id,
+                credential = stored.id,
+                "passkey assertion rejected: {}",
+                e
+            );
+            rejected(e)
+        })?;
+
+        passkey.update_credential(&result);
+        self.credential_repo
+            .update_usage(
+                stored.id,
+                &serde_json::to_string(&passkey).context("Failed to encode passkey")?,
+                result.counter() as i64,
+            )
+            .await?;
+
+        let user = self
+            .user_repo
+            .get_by_id(stored.user_id)
+            .await?
+            .ok_or_else(|| verification_failed("Unknown passkey"))?;
+        if user.role != UserRole::Admin {
+            return Err(WebauthnError::Forbidden);
+        }
+        if user.is_banned() {
+            return Err(verification_failed(
+                "Your account has been banned. Please contact the administrator.",
+            ));
+        }
+        Ok(user)
+    }
+
+    /// Whether `user` may sign in with a password. Admins who registered a
+    /// passkey are refused when `webauthn_allow_password_fallback` is "false".
+    pub async fn password_login_allowed(&self, user: &User) -> Result<bool, WebauthnError> {
+        if user.role != UserRole::Admin
+            || !self.is_enabled().await
+            || !self
+                .setting_is(keys::WEBAUTHN_ALLOW_PASSWORD_FALLBACK, "false")
+                .await
+        {
+            return Ok(true);
+        }
+        Ok(self.credential_repo.count_by_user(user.id).await? == 0)
+    }
+
+    /// List the passkeys of a user
+    pub async fn list(&self, user_id: i64) -> Result<Vec<WebauthnCredential>, WebauthnError> {
+        Ok(self.credential_repo.list_by_user(user_id).await?)
+    }
+
+    /// Rename a passkey of a user
+    pub async fn rename(&self, user_id: i64, id: i64, name: &str) -> Result<(), WebauthnError> {
+        let name = name.trim();
+        if name.is_empty() || name.chars().count() > MAX_PASSKEY_NAME_LEN {
+            return Err(WebauthnError::ValidationError(format!(
+                "Passkey name must be 1-{} characters",
+                MAX_PASSKEY_NAME_LEN
+            )));
+        }
+        if !self.credential_repo.rename(user_id, id, name).await? {
+            return Err(WebauthnError::NotFound);
+        }
+        Ok(())
+    }
+
+    /// Delete a passkey of a user
+    pub async fn delete(&self, user_id: i64, id: i64) -> Result<(), WebauthnError> {
+        if !self.credential_repo.delete(user_id, id).await? {
+            return Err(WebauthnError::NotFound);
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::repositories::{
+        SqlxSettingsRepository, SqlxUserRepository, SqlxWebauthnCredentialRepository,
+    };
+    use crate::db::{create_test_pool, migrations};
+    use ciborium::Value as Cbor;
+    use ring::rand::SystemRandom;
+    use ring::signature::{EcdsaKeyPair, KeyPair, ECDSA_P256_SHA256_ASN1_SIGNING};
+    use serde_json::json;
+    use sha2::{Digest, Sha256};
+
+    const ORIGIN: &str = "https://blog.example.com";
+
+    /// Authenticator data flags
+    const FLAG_USER_PRESENT: u8 = 0x01;
+    const FLAG_USER_VERIFIED: u8 = 0x04;
+    const FLAG_ATTESTED_CREDENTIAL: u8 = 0x40;
+
+    fn settings(pairs: &[(&str, &str)]) -> HashMap<String, String> {
+        pairs
+            .iter()
+            .map(|(k, v)| (k.to_string(), v.to_string()))
+            .collect()
+    }
+
+    /// Software authenticator holding a single ES256 key
+    struct TestAuthenticator {
+        key: EcdsaKeyPair,
+        credential_id: Vec<u8>,
+        counter: u32,
+        flags: u8,
+        origin: &'static str,
+    }
+
+    impl TestAuthenticator {
+        fn new() -> Self {
+            let rng = SystemRandom::new();
+            let pkcs8 =
+                EcdsaKeyPair::generate_pkcs8(&ECDSA_P256_SHA256_ASN1_SIGNING, &rng).unwrap();
+            let key =
+                EcdsaKeyPair::from_pkcs8(&ECDSA_P256_SHA256_ASN1_SIGNING, pkcs8.as_ref(), &rng)
+                    .unwrap();
+            Self {
+                key,
+                credential_id: vec![7; 16],
+                counter: 0,
+                flags: FLAG_USER_PRESENT | FLAG_USER_VERIFIED,
+                origin: ORIGIN,
+            }
+        }
+
+        fn cose_key(&self) -> Vec<u8> {
+            let point = self.key.public_key().as_ref();
+            let key = Cbor::Map(vec![
+                (Cbor::from(1), Cbor::from(2)),
+                (Cbor::from(3), Cbor::from(-7)),
+                (Cbor::from(-1), Cbor::from(1)),
+                (Cbor::from(-2), Cbor::Bytes(point[1..33].to_vec())),
+                (Cbor::from(-3), Cbor::Bytes(point[33..].to_vec())),
+            ]);
+            let mut out = Vec::new();
+            ciborium::into_writer(&key, &mut out).unwrap();
+            out
+        }
+
+        fn auth_data(&mut self, attested: bool) -> Vec<u8> {
+            self.counter += 1;
+            let mut data = Sha256::digest(b"blog.example.com").to_vec();
+            let mut flags = self.flags;
+            if attested {
+                flags |= FLAG_ATTESTED_CREDENTIAL;
+            }
+            data.push(flags);
+            data.extend_from_slice(&self.counter.to_be_bytes());
+            if attested {
+                data.extend_from_slice(&[0u8; 16]);
+                data.extend_from_slice(&(self.credential_id.len() as u16).to_be_bytes());
+                data.extend_from_slice(&self.credential_id);
+                data.extend_from_slice(&self.cose_key());
+            }
+            data
+        }
+
+        fn client_data(&self, kind: &str, options: &Value) -> Vec<u8> {
+            serde_json::to_vec(&json!({
+                "type": kind,
+                "challenge": options["publicKey"]["challenge"],
+                "origin": self.origin,
+            }))
+            .unwrap()
+        }
+
+        fn create(&mut self, options: &Value) -> RegistrationCredential {
+            let attestation = Cbor::Map(vec![
+                (Cbor::from("fmt"), Cbor::from("none")),
+                (Cbor::from("attStmt"), Cbor::Map(vec![])),
+                (Cbor::from("authData"), Cbor::Bytes(self.auth_data(true))),
+            ]);
+            let mut attestation_object = Vec::new();
+            ciborium::into_writer(&attestation, &mut attestation_object).unwrap();
+            let id = BASE64URL_NOPAD.encode(&self.credential_id);
+            serde_json::from_value(json!({
+                "id": id,
+                "rawId": id,
+                "type": "public-key",
+                "response": {
+                    "clientDataJSON": BASE64URL_NOPAD
+                        .encode(&self.client_data("webauthn.create", options)),
+                    "attestationObject": BASE64URL_NOPAD.encode(&attestation_object),
+                },
+            }))
+            .unwrap()
+        }
+
+        fn get(&mut self, options: &Value) -> AuthenticationCredential {
+            let client_data = self.client_data("webauthn.get", options);
+            let auth_data = self.auth_data(false);
+            let mut message = auth_data.clone();
+            message.extend_from_slice(&Sha256::digest(&client_data));
+            let sig = self.key.sign(&SystemRandom::new(), &message).unwrap();
+            let id = BASE64URL_NOPAD.encode(&self.credential_id);
+            serde_json::from_value(json!({
+                "id": id,
+                "rawId": id,
+                "type": "public-key",
+                "response": {
+                    "clientDataJSON": BASE64URL_NOPAD.encode(&client_data),
+                    "authenticatorData": BASE64URL_NOPAD.encode(&auth_data),
+                    "signature": BASE64URL_NOPAD.encode(sig.as_ref()),
+                    "userHandle": BASE64URL_NOPAD.encode(user_handle(1).as_bytes()),
+                },
+            }))
+            .unwrap()
+        }
+    }
+
+    async fn service() -> (
+        Arc<SettingsService>,
+        Arc<dyn UserRepository>,
+        WebauthnService,
+    ) {
+        let pool = create_test_pool()
+            .await
+            .expect("Failed to create test pool");
+        migrations::run_migrations(&pool)
+            .await
+            .expect("Failed to run migrations");
+        let settings = Arc::new(SettingsService::from_sqlx(SqlxSettingsRepository::new(
+            pool.clone(),
+        )));
+        settings.set(keys::WEBAUTHN_ENABLED, "true").await.unwrap();
+        settings
+            .set(settings::keys::SITE_URL, "https://blog.example.com/")
+            .await
+            .unwrap();
+        let user_repo = SqlxUserRepository::boxed(pool.clone());
+        user_repo
+            .create(&User::new(
+                "admin".to_string(),
+                "admin@example.com".to_string(),
+                "x".to_string(),
+                UserRole::Admin,
+            ))
+            .await
+            .unwrap();
+        let service = WebauthnService::new(
+            SqlxWebauthnCredentialRepository::boxed(pool),
+            user_repo.clone(),
+            settings.clone(),
+        );
+        (settings, user_repo, service)
+    }
+
+    /// Register `authenticator` as a passkey of the admin
+    async fn register(
+        service: &WebauthnService,
+        admin: &User,
+        authenticator: &mut TestAuthenticator,
+    ) -> WebauthnCredential {
+        let options = service.start_registration(admin).await.unwrap();
+        service
+            .finish_registration(admin, "Laptop", &authenticator.create(&options))
+            .await
+            .unwrap()
+    }
+
+    #[test]
+    fn relying_party_defaults_to_site_url() {
+        let rp = RelyingParty::from_settings(&settings(&[(
+            settings::keys::SITE_URL,
+            "https://blog.example.com:8443/sub/",
+        )]))
+        .unwrap();
+        assert_eq!(rp.origin, "https://blog.example.com:8443");
+        assert_eq!(rp.id, "blog.example.com");
+        assert!(rp.webauthn().is_ok());
+
+        let rp = RelyingParty::from_settings(&settings(&[
+            (settings::keys::SITE_URL, "https://blog.example.com"),
+            (keys::WEBAUTHN_RP_ID, "example.com"),
+        ]))
+        .unwrap();
+        assert_eq!(rp.id, "example.com");
+        assert!(rp.webauthn().is_ok());
+
+        // The RP ID must be a registrable suffix of the origin's host
+        let rp = RelyingParty::from_settings(&settings(&[
+            (settings::keys::SITE_URL, "https://blog.example.com"),
+            (keys::WEBAUTHN_RP_ID, "other.org"),
+        ]))
+        .unwrap();
+        assert!(matches!(
+            rp.webauthn(),
+            Err(WebauthnError::NotConfigured(_))
+        ));
+
+        assert!(matches!(
+            RelyingParty::from_settings(&HashMap::new()),
+            Err(WebauthnError::NotConfigured(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn register_then_login_with_passkey() {
+        let (_, user_repo, service) = service().await;
+        let admin = user_repo.get_by_id(1).await.unwrap().unwrap();
+        let mut authenticator = TestAuthenticator::new();
+
+        let options = service.start_registration(&admin).await.unwrap();
+        assert_eq!(options["publicKey"]["rp"]["id"], "blog.example.com");
+        assert_eq!(
+            options["publicKey"]["authenticatorSelection"]["userVerification"],
+            "required"
+        );
+        let stored = service
+            .finish_registration(&admin, "Laptop", &authenticator.create(&options))
+            .await
+            .unwrap();
+        assert_eq!(stored.credential_id, BASE64URL_NOPAD.encode(&[7; 16]));
+
+        let options = service.start_authentication(Some("admin")).await.unwrap();
+        assert_eq!(
+            options["publicKey"]["allowCredentials"][0]["id"],
+            stored.credential_id
+        );
+        let response = authenticator.get(&options);
+        let user = service.finish_authentication(&response).await.unwrap();
+        assert_eq!(user.id, admin.id);
+        assert_eq!(service.list(admin.id).await.unwrap()[0].sign_count, 2);
+
+        // Challenges are single use
+        assert!(matches!(
+            service.finish_authentication(&response).await,
+            Err(WebauthnError::InvalidChallenge)
+        ));
+
+        // Discoverable login without a username
+        let options = service.start_authentication(None).await.unwrap();
+        assert!(options.get("mediation").is_none());
+        let user = service
+            .finish_authentication(&authenticator.get(&options))
+            .await
+            .unwrap();
+        assert_eq!(user.id, admin.id);
+
+        // A replayed counter is rejected
+        let options = service.start_authentication(None).await.unwrap();
+        authenticator.counter -= 1;
+        assert!(matches!(
+            service
+                .finish_authentication(&authenticator.get(&options))
+                .await,
+            Err(WebauthnError::VerificationFailed(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn assertions_from_other_origins_or_without_verification_fail() {
+        let (_, user_repo, service) = service().await;
+        let admin = user_repo.get_by_id(1).await.unwrap().unwrap();
+        let mut authenticator = TestAuthenticator::new();
+        register(&service, &admin, &mut authenticator).await;
+
+        authenticator.origin = "https://evil.example.net";
+        let options = service.start_authentication(Some("admin")).await.unwrap();
+        assert!(matches!(
+            service
+                .finish_authentication(&authenticator.get(&options))
+                .await,
+            Err(WebauthnError::VerificationFailed(_))
+        ));
+
+        authenticator.origin = ORIGIN;
+        authenticator.flags = FLAG_USER_PRESENT;
+        let options = service.start_authentication(Some("admin")).await.unwrap();
+        assert!(matches!(
+            service
+                .finish_authentication(&authenticator.get(&options))
+                .await,
+            Err(WebauthnError::VerificationFailed(_))
+        ));
+
+        // A tampered signature does not verify
+        authenticator.flags = FLAG_USER_PRESENT | FLAG_USER_VERIFIED;
+        let options = service.start_authentication(Some("admin")).await.unwrap();
+        let mut response = authenticator.get(&options);
+        response.response.signature = vec![0u8; 70].into();
+        assert!(matches!(
+            service.finish_authentication(&response).await,
+            Err(WebauthnError::VerificationFailed(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn pending_challenges_are_capped_and_expire() {
+        let (_, _, service) = service().await;
+        let webauthn = service.relying_party().await.unwrap();
+        {
+            let mut challenges = service.challenges.write().await;
+            for i in 0..MAX_PENDING_CHALLENGES {
+                let (_, state) = webauthn.start_passkey_authentication(&[]).unwrap();
+                challenges.insert(
+                    i.to_string(),
+                    PendingChallenge {
+                        ceremony: Ceremony::Authentication {
+                            user_id: None,
+                            state,
+                        },
+                        expires_at: Utc::now() + Duration::seconds(CHALLENGE_TTL_SECS),
+                    },
+                );
+            }
+        }
+        assert!(matches!(
+            service.start_authentication(None).await,
+            Err(WebauthnError::TooManyChallenges)
+        ));
+
+        // Expired entries are swept before the cap is checked
+        for pending in service.challenges.write().await.values_mut() {
+            pending.expires_at = Utc::now() - Duration::seconds(1);
+        }
+        service.start_authentication(None).await.unwrap();
+        assert_eq!(service.challenges.read().await.len(), 1);
+    }
+
+    #[tokio::test]
+    async fn password_fallback_policy() {
+        let (settings, user_repo, service) = service().await;
+        let admin = user_repo.get_by_id(1).await.unwrap().unwrap();
+        register(&service, &admin, &mut TestAuthenticator::new()).await;
+
+        assert!(service.password_login_allowed(&admin).await.unwrap());
+        settings
+            .set(keys::WEBAUTHN_ALLOW_PASSWORD_FALLBACK, "false")
+            .await
+            .unwrap();
+        assert!(!service.password_login_allowed(&admin).await.unwrap());
+
+        let id = service.list(admin.id).await.unwrap()[0].id;
+        service.delete(admin.id, id).await.unwrap();
+        assert!(service.password_login_allowed(&admin).await.unwrap());
+    }
+}
@@ -0,0 +1,474 @@
+//! Webmention service
+//!
+//! Implements the W3C Webmention protocol in both directions:
+//! - Receiving: `POST /webmention` with `source` and `target`; the source page
+//!   is fetched by a queued [`VERIFY_JOB`] and, if it links to the target
+//!   article, stored as a comment of type `webmention` (always held for
+//!   moderation).
+//! - Sending: when an article is published, links in its HTML are checked for
+//!   a Webmention endpoint and notified.
+//!
+//! Both directions are disabled unless the `webmention_enabled` setting is "true".
+
+use anyhow::{Context, Result};
+use once_cell::sync::Lazy;
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::HashSet;
+use std::sync::Arc;
+use std::time::Duration;
+
+use crate::db::repositories::{ArticleRepository, CommentRepository};
+use crate::models::{Article, ArticleStatus, CommentStatus};
+use crate::plugin::{hook_names, HookManager};
+use crate::services::outbound::{ensure_public_url, public_client_builder, read_limited_body};
+use crate::services::settings::{keys, SettingsService};
+use crate::services::JobQueue;
+
+/// Setting that enables sending and receiving Webmentions
+pub const WEBMENTION_ENABLED_KEY: &str = "webmention_enabled";
+
+/// Job queue kind that verifies a received Webmention
+pub const VERIFY_JOB: &str = "webmention_verify";
+
+/// Timeout for fetching sources and notifying endpoints
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Maximum number of links notified per published article
+const MAX_LINKS_PER_ARTICLE: usize = 20;
+
+static HREF_RE: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r#"(?is)<a\s[^>]*?href\s*=\s*["']([^"']+)["']"#).expect("valid href regex")
+});
+static LINK_TAG_RE: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r#"(?is)<(?:link|a)\s[^>]*>"#).expect("valid link tag regex"));
+static ATTR_RE: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r#"(?is)([a-z-]+)\s*=\s*["']([^"']*)["']"#).expect("valid attribute regex")
+});
+static TITLE_RE: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"(?is)<title[^>]*>(.*?)</title>").expect("valid title regex"));
+
+/// Errors returned when accepting a Webmention
+#[derive(Debug, thiserror::Error)]
+pub enum WebmentionError {
+    /// Receiving is disabled
+    #[error("Webmention is not enabled")]
+    Disabled,
+
+    /// Malformed or unsupported request
+    #[error("{0}")]
+    InvalidRequest(String),
+
+    /// Internal error
+    #[error("Internal error: {0}")]
+    Internal(#[from] anyhow::Error),
+}
+
+/// Payload of a [`VERIFY_JOB`]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct VerifyJob {
+    pub source: String,
+    pub target: String,
+    pub article_id: i64,
+}
+
+/// Webmention sender and receiver
+pub struct WebmentionService {
+    comment_repo: Arc<dyn CommentRepository>,
+    article_repo: Arc<dyn ArticleRepository>,
+    settings: Arc<SettingsService>,
+    client: reqwest::Client,
+}
+
+impl WebmentionService {
+    pub fn new(
+        comment_repo: Arc<dyn CommentRepository>,
+        article_repo: Arc<dyn ArticleRepository>,
+        settings: Arc<SettingsService>,
+    ) -> Self {
+        let client = public_client_builder()
+            .timeout(REQUEST_TIMEOUT)
+            .user_agent(concat!("Noteva/", env!("CARGO_PKG_VERSION"), " Webmention"))
+            .build()
+            .expect("Failed to build Webmention HTTP client");
+        Self {
+            comment_repo,
+            article_repo,
+            settings,
+            client,
+        }
+    }
+
+    /// Register hooks that send Webmentions when articles get published
+    pub fn register_hooks(self: &Arc<Self>, hook_manager: &HookManager) {
+        let service = self.clone();
+        hook_manager.register(
+            hook_names::ARTICLE_STATUS_CHANGE,
+            move |data: &mut Value| {
+                if data.get("new_status").and_then(Value::as_str) == Some("Published") {
+                    service.spawn_send(data.get("id").and_then(Value::as_i64));
+                }
+                None
+            },
+            100,
+            None,
+        );
+
+        let service = self.clone();
+        hook_manager.register(
+            hook_names::ARTICLE_AFTER_CREATE,
+            move |data: &mut Value| {
+                if data.get("status").and_then(Value::as_str) == Some("Published") {
+                    service.spawn_send(data.get("id").and_then(Value::as_i64));
+                }
+                None
+            },
+            100,
+            None,
+        );
+    }
+
+    /// Register the handler for queued [`VERIFY_JOB`]s
+    pub fn register_jobs(self: &Arc<Self>, queue: &JobQueue) {
+        let service = self.clone();
+        queue.register(VERIFY_JOB, move |payload| {
+            let service = service.clone();
+            async move {
+                let job: VerifyJob =
+                    serde_json::from_value(payload).context("Invalid webmention job")?;
+                service
+                    .verify_and_store(&job.source, &job.target, job.article_id)
+                    .await
+            }
+        });
+    }
+
+    fn spawn_send(self: &Arc<Self>, article_id: Option<i64>) {
+        let (Some(article_id), Ok(handle)) = (article_id, tokio::runtime::Handle::try_current())
+        else {
+            return;
+        };
+        let service = self.clone();
+        handle.spawn(async move {
+            match service.send_for_article(article_id).await {
+                Ok(sent) if sent > 0 => {
+                    tracing::info!(article_id, sent, "webmentions sent");
+                }
+                Ok(_) => {}
+                Err(e) => {
+                    tracing::warn!(article_id, error = %e, "failed to send webmentions");
+                }
+            }
+        });
+    }
+
+    async fn is_enabled(&self) -> bool {
+        matches!(
+            self.settings.get(WEBMENTION_ENABLED_KEY).await,
+            Ok(Some(ref v)) if v == "true"
+        )
+    }
+
+    /// Configured public site URL without trailing slash, if any
+    async fn site_url(&self) -> Option<String> {
+        self.settings
+            .get(keys::SITE_URL)
+            .await
+            .ok()
+            .flatten()
+            .map(|s| s.trim().trim_end_matches('/').to_string())
+            .filter(|s| !s.is_empty())
+    }
+
+    /// Validate an incoming Webmention and resolve its target article.
+    ///
+    /// Source verification happens later in [`Self::verify_and_store`] so the
+    /// endpoint can answer `202 Accepted` right away, as the spec recommends.
+    pub async fn accept(&self, source: &str, target: &str) -> Result<Article, WebmentionError> {
+        if !self.is_enabled().await {
+            return Err(WebmentionError::Disabled);
+        }
+
+        let invalid = |msg: &str| WebmentionError::InvalidRequest(msg.to_string());
+        let source_url = reqwest::Url::parse(source).map_err(|_| invalid("Invalid source URL"))?;
+        let target_url = reqwest::Url::parse(target).map_err(|_| invalid("Invalid target URL"))?;
+        if !matches!(source_url.scheme(), "http" | "https")
+            || !matches!(target_url.scheme(), "http" | "https")
+        {
+            return Err(invalid("Source and target must be http(s) URLs"));
+        }
+        if source_url == target_url {
+            return Err(invalid("Source and target must differ"));
+        }
+
+        if let Some(site_url) = self.site_url().await {
+            let site_host = reqwest::Url::parse(&site_url)
+                .ok()
+                .and_then(|u| u.host_str().map(str::to_string));
+            if site_host.is_some() && target_url.host_str() != site_host.as_deref() {
+                return Err(invalid("Target is not on this site"));
+            }
+        }
+
+        let identifier =
+            article_identifier(&target_url).ok_or_else(|| invalid("Target is not an article"))?;
+        let article = match self.article_repo.get_by_slug(&identifier).await? {
+            Some(article) => Some(article),
+            None => match identifier.parse::<i64>() {
+                Ok(id) => self.article_repo.get_by_id(id).await?,
+                Err(_) => None,
+            },
+        };
+        match article {
+            Some(article) if article.status == ArticleStatus::Published => Ok(article),
+            _ => Err(invalid("Target article not found")),
+        }
+    }
+
+    /// Fetch the source and store, refresh or remove the mention accordingly.
+    pub async fn verify_and_store(
+        &self,
+        source: &str,
+        target: &str,
+        article_id: i64,
+    ) -> Result<()> {
+        let source_url = ensure_public_url(source).await?;
+        let response = self
+            .client
+            .get(source_url.clone())
+            .header(reqwest::header::ACCEPT, "text/html")
+            .send()
+            .await
+            .context("Failed to fetch webmention source")?;
+
+        // A deleted source removes the mention (spec section 3.2.2)
+        if response.status() == reqwest::StatusCode::GONE {
+            self.comment_repo
+                .delete_webmention(article_id, source)
+                .await?;
+            return Ok(());
+        }
+        if !response.status().is_success() {
+            anyhow::bail!("Source returned HTTP {}", response.status());
+        }
+
+        let html = read_limited_body(response).await?;
+        if !links_to(&html, target) {
+            self.comment_repo
+                .delete_webmention(article_id, source)
+                .await?;
+            tracing::debug!(source, target, "webmention source does not link to target");
+            return Ok(());
+        }
+
+        let author = source_url.host_str().map(str::to_string);
+        let content = extract_title(&html).unwrap_or_else(|| source.to_string());
+        self.comment_repo
+            .upsert_webmention(article_id, source, author, &content, CommentStatus::Pending)
+            .await?;
+        tracing::info!(article_id, source, "webmention received");
+        Ok(())
+    }
+
+    /// Notify every Webmention-capable page linked from a published article.
+    ///
+    /// Returns the number of endpoints that accepted the notification.
+    pub async fn send_for_article(&self, article_id: i64) -> Result<usize> {
+        if !self.is_enabled().await {
+            return Ok(0);
+        }
+        let Some(site_url) = self.site_url().await else {
+            tracing::debug!("site_url not configured, skipping webmentions");
+            return Ok(0);
+        };
+        let Some(article) = self.article_repo.get_by_id(article_id).await? else {
+            return Ok(0);
+        };
+        if article.status != ArticleStatus::Published {
+            return Ok(0);
+        }
+
+        let source = format!("{}/posts/{}", site_url, article.slug);
+        let site_host = reqwest::Url::parse(&site_url)
+            .ok()
+            .and_then(|u| u.host_str().map(str::to_string));
+
+        let mut sent = 0;
+        for target in extract_links(&article.content_html) {
+            let Ok(target_url) = reqwest::Url::parse(&target) else {
+                continue;
+            };
+            if target_url.host_str() == site_host.as_deref() {
+                continue;
+            }
+            match self.send_one(&source, target_url).await {
+                Ok(true) => sent += 1,
+                Ok(false) => {}
+                Err(e) => tracing::debug!(target = %target, error = %e, "webmention not sent"),
+            }
+        }
+        Ok(sent)
+    }
+
+    /// Discover the endpoint of `target` and notify it. Returns `false` when
+    /// the target does not advertise an endpoint.
+    async fn send_one(&self, source: &str, target: reqwest::Url) -> Result<bool> {
+        let target = ensure_public_url(target.as_str()).await?;
+        let response = self.client.get(target.clone()).send().await?;
+        let link_header = response
+            .headers()
+            .get_all(reqwest::header::LINK)
+            .iter()
+            .filter_map(|v| v.to_str().ok())
+            .collect::<Vec<_>>()
+            .join(", ");
+        let is_html = response
+            .headers()
+            .get(reqwest::header::CONTENT_TYPE)
+            .and_then(|v| v.to_str().ok())
+            .is_some_and(|ct| ct.contains("html"));
+        let html = if is_html {
+            read_limited_body(response).await.unwrap_or_default()
+        } else {
+            String::new()
+        };
+
+        let Some(endpoint) = discover_endpoint(&link_header, &html) else {
+            return Ok(false);
+        };
+        let endpoint = target.join(&endpoint).context("Invalid endpoint URL")?;
+        let endpoint = ensure_public_url(endpoint.as_str()).await?;
+
+        let response = self
+            .client
+            .post(endpoint)
+            .form(&[("source", source), ("target", target.as_str())])
+            .send()
+            .await?;
+        Ok(response.status().is_success())
+    }
+}
+
+/// Article slug or ID from a `/posts/{identifier}` URL
+fn article_identifier(url: &reqwest::Url) -> Option<String> {
+    let mut segments = url.path_segments()?.filter(|s| !s.is_empty());
+    match (segments.next(), segments.next(), segments.next()) {
+        (Some("posts"), Some(identifier), None) => {
+            urlencoding::decode(identifier).ok().map(|s| s.into_owned())
+        }
+        _ => None,
+    }
+}
+
+/// Unique absolute http(s) links found in `<a href>` attributes
+fn extract_links(html: &str) -> Vec<String> {
+    let mut seen = HashSet::new();
+    HREF_RE
+        .captures_iter(html)
+        .map(|c| c[1].trim().replace("&amp;", "&"))
+        .filter(|href| href.starts_with("http://") || href.starts_with("https://"))
+        .filter(|href| seen.insert(href.clone()))
+        .take(MAX_LINKS_PER_ARTICLE)
+        .collect()
+}
+
+/// Whether the page links to `target` (ignoring a trailing slash)
+fn links_to(html: &str, target: &str) -> bool {
+    let target = target.trim_end_matches('/');
+    HREF_RE
+        .captures_iter(html)
+        .any(|c| c[1].trim().replace("&amp;", "&").trim_end_matches('/') == target)
+}
+
+/// Find the Webmention endpoint from a `Link` header or `<link>`/`<a>` tags.
+fn discover_endpoint(link_header: &str, html: &str) -> Option<String> {
+    for part in link_header.split(',') {
+        let mut pieces = part.split(';');
+        let Some(url) = pieces.next() else { continue };
+        let is_webmention = pieces.any(|p| {
+            let p = p.trim();
+            p.strip_prefix("rel=").is_some_and(|rel| {
+                rel.trim_matches('"')
+                    .split_whitespace()
+                    .any(|r| r.eq_ignore_ascii_case("webmention"))
+            })
+        });
+        if is_webmention {
+            let url = url.trim().trim_start_matches('<').trim_end_matches('>');
+            return Some(url.to_string());
+        }
+    }
+
+    for tag in LINK_TAG_RE.find_iter(html) {
+        let mut rel = None;
+        let mut href = None;
+        for attr in ATTR_RE.captures_iter(tag.as_str()) {
+            match attr[1].to_ascii_lowercase().as_str() {
+                "rel" => rel = Some(attr[2].to_string()),
+                "href" => href = Some(attr[2].to_string()),
+                _ => {}
+            }
+        }
+        if let (Some(rel), Some(href)) = (rel, href) {
+            if rel
+                .split_whitespace()
+                .any(|r| r.eq_ignore_ascii_case("webmention"))
+            {
+                return Some(href.replace("&amp;", "&"));
+            }
+        }
+    }
+    None
+}
+
+fn extract_title(html: &str) -> Option<String> {
+    TITLE_RE
+        .captures(html)
+        .map(|c| c[1].split_whitespace().collect::<Vec<_>>().join(" "))
+        .filter(|t| !t.is_empty())
+        .map(|t| t.chars().take(200).collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn endpoint_is_discovered_from_link_header_first() {
+        let header = r#"<https://example.com/other>; rel="other", <https://example.com/wm>; rel="webmention""#;
+        assert_eq!(
+            discover_endpoint(header, r#"<link rel="webmention" href="/ignored">"#).as_deref(),
+            Some("https://example.com/wm")
+        );
+        assert_eq!(
+            discover_endpoint(
+                "",
+                r#"<head><link href="/webmention" rel="webmention"></head>"#
+            )
+            .as_deref(),
+            Some("/webmention")
+        );
+        assert_eq!(discover_endpoint("", "<p>nothing</p>"), None);
+    }
+
+    #[test]
+    fn links_are_extracted_and_matched() {
+        let html = r#"<p><a href="https://a.example/x">a</a> <a href='https://a.example/x'>dup</a>
+            <a href="/relative">r</a> <a class="u" href="https://b.example/post/">b</a></p>"#;
+        assert_eq!(
+            extract_links(html),
+            vec!["https://a.example/x", "https://b.example/post/"]
+        );
+        assert!(links_to(html, "https://b.example/post"));
+        assert!(!links_to(html, "https://c.example/"));
+    }
+
+    #[test]
+    fn article_identifier_requires_posts_path() {
+        let url = reqwest::Url::parse("https://blog.example/posts/hello-world/").unwrap();
+        assert_eq!(article_identifier(&url).as_deref(), Some("hello-world"));
+        let url = reqwest::Url::parse("https://blog.example/about").unwrap();
+        assert_eq!(article_identifier(&url), None);
+    }
+}
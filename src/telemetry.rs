@@ -0,0 +1,191 @@
+//! Logging and distributed tracing setup
+//!
+//! Logs go to stdout and/or a rotating file (see `logging` in the config).
+//! With the `otel` feature and
+//! `telemetry.enabled`, spans are also exported over OTLP/HTTP and every
+//! request span records its `trace_id`, which then shows up in the log
+//! lines written while handling that request.
+
+use anyhow::Context;
+use axum::extract::{MatchedPath, Request};
+use std::sync::Mutex;
+use tracing::Span;
+use tracing_subscriber::{layer::SubscriberExt, util::SubscriberInitExt};
+
+use crate::config::{LoggingConfig, TelemetryConfig};
+
+mod log_file;
+
+pub use log_file::RotatingFile;
+
+/// Flushes pending spans when dropped; keep it alive for the whole process
+#[must_use = "spans are only flushed while the guard is alive"]
+pub struct TelemetryGuard {
+    #[cfg(feature = "otel")]
+    provider: Option<opentelemetry_sdk::trace::SdkTracerProvider>,
+}
+
+impl Drop for TelemetryGuard {
+    fn drop(&mut self) {
+        #[cfg(feature = "otel")]
+        if let Some(provider) = self.provider.take() {
+            if let Err(e) = provider.shutdown() {
+                eprintln!("failed to flush traces: {}", e);
+            }
+        }
+    }
+}
+
+fn env_filter() -> tracing_subscriber::EnvFilter {
+    tracing_subscriber::EnvFilter::try_from_default_env()
+        .unwrap_or_else(|_| "noteva=info,tower_http=warn".into())
+}
+
+/// Install the global subscriber
+pub fn init(config: &TelemetryConfig, logging: &LoggingConfig) -> anyhow::Result<TelemetryGuard> {
+    let stdout = logging.stdout.then(tracing_subscriber::fmt::layer);
+    let file = match &logging.file {
+        Some(path) => {
+            let file = RotatingFile::open(path, logging)
+                .with_context(|| format!("Failed to open log file {}", path.display()))?;
+            Some(
+                tracing_subscriber::fmt::layer()
+                    .with_ansi(false)
+                    .with_writer(Mutex::new(file)),
+            )
+        }
+        None => None,
+    };
+
+    #[cfg(feature = "otel")]
+    {
+        let provider = config.enabled.then(|| otel::provider(config)).transpose()?;
+        let layer = provider.as_ref().map(otel::layer);
+        tracing_subscriber::registry()
+            .with(env_filter())
+            .with(stdout)
+            .with(file)
+            .with(layer)
+            .init();
+        if provider.is_some() {
+            tracing::info!(endpoint = %config.endpoint, "OpenTelemetry trace export enabled");
+        }
+        Ok(TelemetryGuard { provider })
+    }
+
+    #[cfg(not(feature = "otel"))]
+    {
+        tracing_subscriber::registry()
+            .with(env_filter())
+            .with(stdout)
+            .with(file)
+            .init();
+        if config.enabled {
+            tracing::warn!(
+                "telemetry.enabled is set but this build does not include the `otel` feature"
+            );
+        }
+        Ok(TelemetryGuard {})
+    }
+}
+
+/// Span for an HTTP request, continuing the caller's trace when the request
+/// carries a W3C `traceparent` header
+pub fn http_request_span(request: &Request) -> Span {
+    let method = request.method();
+    // Raw paths would make span names unbounded, so only matched routes
+    // are used for the name
+    let route = request
+        .extensions()
+        .get::<MatchedPath>()
+        .map(MatchedPath::as_str);
+    let span = tracing::info_span!(
+        "http_request",
+        otel.name = %match route {
+            Some(route) => format!("{} {}", method, route),
+            None => format!("HTTP {}", method),
+        },
+        http.request.method = %method,
+        http.route = route,
+        url.path = %request.uri().path(),
+        http.response.status_code = tracing::field::Empty,
+        trace_id = tracing::field::Empty,
+    );
+
+    #[cfg(feature = "otel")]
+    otel::link_request(&span, request.headers());
+
+    span
+}
+
+/// Record the response status on the request span
+pub fn record_response_status(status: axum::http::StatusCode, span: &Span) {
+    span.record("http.response.status_code", status.as_u16());
+}
+
+#[cfg(feature = "otel")]
+mod otel {
+    use opentelemetry::propagation::{Extractor, TextMapPropagator};
+    use opentelemetry::trace::{TraceContextExt, TracerProvider as _};
+    use opentelemetry_otlp::WithExportConfig;
+    use opentelemetry_sdk::propagation::TraceContextPropagator;
+    use opentelemetry_sdk::trace::{Sampler, SdkTracerProvider};
+    use opentelemetry_sdk::Resource;
+    use tracing::Span;
+    use tracing_opentelemetry::OpenTelemetrySpanExt;
+
+    use crate::config::TelemetryConfig;
+
+    pub(super) fn provider(config: &TelemetryConfig) -> anyhow::Result<SdkTracerProvider> {
+        let exporter = opentelemetry_otlp::SpanExporter::builder()
+            .with_http()
+            .with_endpoint(&config.endpoint)
+            .with_timeout(std::time::Duration::from_secs(10))
+            .build()?;
+        let sampler = Sampler::ParentBased(Box::new(Sampler::TraceIdRatioBased(
+            config.sample_ratio.clamp(0.0, 1.0),
+        )));
+        Ok(SdkTracerProvider::builder()
+            .with_batch_exporter(exporter)
+            .with_sampler(sampler)
+            .with_resource(
+                Resource::builder()
+                    .with_service_name(config.service_name.clone())
+                    .build(),
+            )
+            .build())
+    }
+
+    pub(super) fn layer<S>(
+        provider: &SdkTracerProvider,
+    ) -> tracing_opentelemetry::OpenTelemetryLayer<S, opentelemetry_sdk::trace::Tracer>
+    where
+        S: tracing::Subscriber + for<'span> tracing_subscriber::registry::LookupSpan<'span>,
+    {
+        tracing_opentelemetry::layer().with_tracer(provider.tracer("noteva"))
+    }
+
+    struct HeaderExtractor<'a>(&'a axum::http::HeaderMap);
+
+    impl Extractor for HeaderExtractor<'_> {
+        fn get(&self, key: &str) -> Option<&str> {
+            self.0.get(key).and_then(|value| value.to_str().ok())
+        }
+
+        fn keys(&self) -> Vec<&str> {
+            self.0.keys().map(|key| key.as_str()).collect()
+        }
+    }
+
+    /// Parent the span on an incoming `traceparent` and record its trace ID
+    pub(super) fn link_request(span: &Span, headers: &axum::http::HeaderMap) {
+        let parent = TraceContextPropagator::new().extract(&HeaderExtractor(headers));
+        if parent.span().span_context().is_valid() {
+            let _ = span.set_parent(parent);
+        }
+        let trace_id = span.context().span().span_context().trace_id();
+        if trace_id != opentelemetry::trace::TraceId::INVALID {
+            span.record("trace_id", trace_id.to_string());
+        }
+    }
+}
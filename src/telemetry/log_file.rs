@@ -0,0 +1,213 @@
+//! Log file with size and time based rotation
+//!
+//! The active file is renamed to `<name>.<YYYYmmdd-HHMMSS>` when the current
+//! hour or day ends, or when the next line would push it past the size limit.
+//! Only the newest `keep` rotated files are kept.
+
+use chrono::{DateTime, Utc};
+use std::fs::{self, File, OpenOptions};
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+
+use crate::config::{LogRotation, LoggingConfig};
+
+/// Append-only log file that rotates itself before writing
+pub struct RotatingFile {
+    path: PathBuf,
+    file: File,
+    size: u64,
+    /// Rotation period the active file belongs to
+    period: Option<String>,
+    rotation: LogRotation,
+    max_size: u64,
+    keep: usize,
+}
+
+impl RotatingFile {
+    /// Open (or create) the log file for appending
+    pub fn open(path: &Path, config: &LoggingConfig) -> io::Result<Self> {
+        if let Some(dir) = path.parent().filter(|dir| !dir.as_os_str().is_empty()) {
+            fs::create_dir_all(dir)?;
+        }
+        let file = open_append(path)?;
+        let metadata = file.metadata()?;
+        // A file left over from an earlier period is rotated on the first write
+        let modified = metadata
+            .modified()
+            .map(DateTime::<Utc>::from)
+            .unwrap_or_else(|_| Utc::now());
+        Ok(Self {
+            path: path.to_path_buf(),
+            file,
+            size: metadata.len(),
+            period: period_key(config.rotation, modified),
+            rotation: config.rotation,
+            max_size: config.max_size_mb.saturating_mul(1024 * 1024),
+            keep: config.keep,
+        })
+    }
+
+    fn needs_rotation(&self, len: usize, now: DateTime<Utc>) -> bool {
+        let too_big = self.max_size > 0 && self.size > 0 && self.size + len as u64 > self.max_size;
+        too_big || period_key(self.rotation, now) != self.period
+    }
+
+    fn rotate(&mut self, now: DateTime<Utc>) -> io::Result<()> {
+        self.period = period_key(self.rotation, now);
+        fs::rename(&self.path, self.rotated_path(now)?)?;
+        self.file = open_append(&self.path)?;
+        self.size = 0;
+        self.prune()
+    }
+
+    fn file_name(&self) -> String {
+        self.path
+            .file_name()
+            .map(|name| name.to_string_lossy().into_owned())
+            .unwrap_or_default()
+    }
+
+    fn dir(&self) -> &Path {
+        match self.path.parent() {
+            Some(dir) if !dir.as_os_str().is_empty() => dir,
+            _ => Path::new("."),
+        }
+    }
+
+    /// `<name>.<timestamp>`, with a counter after the newest file already
+    /// rotated within the same second so names keep sorting by age
+    fn rotated_path(&self, now: DateTime<Utc>) -> io::Result<PathBuf> {
+        let base = format!("{}.{}", self.file_name(), now.format("%Y%m%d-%H%M%S"));
+        let mut last = None;
+        for entry in fs::read_dir(self.dir())? {
+            let name = entry?.file_name().to_string_lossy().into_owned();
+            let Some(rest) = name.strip_prefix(base.as_str()) else {
+                continue;
+            };
+            let n = match rest.strip_prefix('.') {
+                None if rest.is_empty() => 0,
+                Some(counter) => match counter.parse::<u32>() {
+                    Ok(n) => n,
+                    Err(_) => continue,
+                },
+                None => continue,
+            };
+            last = last.max(Some(n));
+        }
+        let name = match last {
+            None => base,
+            Some(n) => format!("{}.{:03}", base, n + 1),
+        };
+        Ok(self.path.with_file_name(name))
+    }
+
+    /// Delete the oldest rotated files beyond `keep`
+    fn prune(&self) -> io::Result<()> {
+        if self.keep == 0 {
+            return Ok(());
+        }
+        let prefix = format!("{}.", self.file_name());
+        let mut rotated: Vec<PathBuf> = fs::read_dir(self.dir())?
+            .filter_map(|entry| entry.ok())
+            .filter(|entry| entry.file_name().to_string_lossy().starts_with(&prefix))
+            .map(|entry| entry.path())
+            .collect();
+        rotated.sort();
+        let excess = rotated.len().saturating_sub(self.keep);
+        for path in &rotated[..excess] {
+            fs::remove_file(path)?;
+        }
+        Ok(())
+    }
+}
+
+impl Write for RotatingFile {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        let now = Utc::now();
+        if self.needs_rotation(buf.len(), now) {
+            if let Err(e) = self.rotate(now) {
+                // Keep logging to the current file and retry after another
+                // max_size bytes or the next period
+                self.size = 0;
+                eprintln!("failed to rotate {}: {}", self.path.display(), e);
+            }
+        }
+        let written = self.file.write(buf)?;
+        self.size += written as u64;
+        Ok(written)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.file.flush()
+    }
+}
+
+fn open_append(path: &Path) -> io::Result<File> {
+    OpenOptions::new().create(true).append(true).open(path)
+}
+
+fn period_key(rotation: LogRotation, at: DateTime<Utc>) -> Option<String> {
+    match rotation {
+        LogRotation::Never => None,
+        LogRotation::Hourly => Some(at.format("%Y-%m-%d %H").to_string()),
+        LogRotation::Daily => Some(at.format("%Y-%m-%d").to_string()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rotated_files(dir: &Path) -> Vec<String> {
+        let mut names: Vec<String> = fs::read_dir(dir)
+            .unwrap()
+            .map(|entry| entry.unwrap().file_name().to_string_lossy().into_owned())
+            .filter(|name| name != "noteva.log")
+            .collect();
+        names.sort();
+        names
+    }
+
+    #[test]
+    fn rotates_at_max_size_and_keeps_the_newest() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("logs/noteva.log");
+        let config = LoggingConfig {
+            file: Some(path.clone()),
+            rotation: LogRotation::Never,
+            keep: 2,
+            ..LoggingConfig::default()
+        };
+        let mut file = RotatingFile::open(&path, &config).unwrap();
+        file.max_size = 10;
+
+        for line in ["first\n", "second\n", "third\n", "fourth\n"] {
+            file.write_all(line.as_bytes()).unwrap();
+        }
+
+        assert_eq!(fs::read_to_string(&path).unwrap(), "fourth\n");
+        let rotated = rotated_files(path.parent().unwrap());
+        assert_eq!(rotated.len(), 2);
+        assert!(rotated.iter().all(|name| name.starts_with("noteva.log.")));
+        let contents: Vec<String> = rotated
+            .iter()
+            .map(|name| fs::read_to_string(path.with_file_name(name)).unwrap())
+            .collect();
+        assert_eq!(contents, ["second\n", "third\n"]);
+    }
+
+    #[test]
+    fn rotates_when_the_period_changes() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("noteva.log");
+        let mut file = RotatingFile::open(&path, &LoggingConfig::default()).unwrap();
+        file.write_all(b"today\n").unwrap();
+        assert!(rotated_files(dir.path()).is_empty());
+
+        file.period = Some("2000-01-01".to_string());
+        file.write_all(b"tomorrow\n").unwrap();
+
+        assert_eq!(fs::read_to_string(&path).unwrap(), "tomorrow\n");
+        assert_eq!(rotated_files(dir.path()).len(), 1);
+    }
+}
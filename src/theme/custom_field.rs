@@ -0,0 +1,89 @@
+//! `custom_field()` template function
+//!
+//! Reads a custom field of an article or page in Tera templates:
+//!
+//! ```text
+//! {{ custom_field(item=article, key="rating", default=0) }}
+//! {% if custom_field(item=page, key="hide_title") %}...{% endif %}
+//! ```
+//!
+//! `item` is anything with a `custom_fields` map; `fields` takes the map
+//! itself. Missing fields give `default`, or an empty string.
+
+use serde_json::Value;
+use std::collections::HashMap;
+use tera::Tera;
+
+/// Register `custom_field()` on a Tera instance
+pub fn register_functions(tera: &mut Tera) {
+    tera.register_function("custom_field", custom_field_function);
+}
+
+fn custom_field_function(args: &HashMap<String, Value>) -> tera::Result<Value> {
+    let key = args
+        .get("key")
+        .and_then(Value::as_str)
+        .ok_or_else(|| tera::Error::msg("custom_field: `key` is required"))?;
+    let fields = match (args.get("item"), args.get("fields")) {
+        (Some(item), _) => item.get("custom_fields"),
+        (None, Some(fields)) => Some(fields),
+        (None, None) => return Err(tera::Error::msg("custom_field: pass `item` or `fields`")),
+    };
+    Ok(fields
+        .and_then(|fields| fields.get(key))
+        .filter(|value| !value.is_null())
+        .or_else(|| args.get("default"))
+        .cloned()
+        .unwrap_or_else(|| Value::String(String::new())))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn render(template: &str, context: Value) -> String {
+        let mut tera = Tera::default();
+        register_functions(&mut tera);
+        tera.add_raw_template("t.html", template).unwrap();
+        tera.render("t.html", &tera::Context::from_value(context).unwrap())
+            .unwrap()
+    }
+
+    #[test]
+    fn reads_fields_of_an_item() {
+        let context = json!({
+            "article": {"title": "A", "custom_fields": {"rating": 4.5, "featured": true}}
+        });
+        assert_eq!(
+            render(
+                r#"{{ custom_field(item=article, key="rating") }}"#,
+                context.clone()
+            ),
+            "4.5"
+        );
+        assert_eq!(
+            render(
+                r#"{% if custom_field(item=article, key="featured") %}yes{% endif %}"#,
+                context
+            ),
+            "yes"
+        );
+    }
+
+    #[test]
+    fn missing_fields_fall_back_to_the_default() {
+        let context = json!({"page": {"title": "About"}, "fields": {"mood": "calm"}});
+        assert_eq!(
+            render(
+                r#"[{{ custom_field(item=page, key="mood") }}|{{ custom_field(item=page, key="mood", default="none") }}]"#,
+                context.clone()
+            ),
+            "[|none]"
+        );
+        assert_eq!(
+            render(r#"{{ custom_field(fields=fields, key="mood") }}"#, context),
+            "calm"
+        );
+    }
+}
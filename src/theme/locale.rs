@@ -0,0 +1,306 @@
+//! Theme string localization
+//!
+//! Themes ship translated UI strings as `locales/<locale>.json` files next
+//! to `theme.json`, e.g. `locales/en.json`:
+//!
+//! ```json
+//! { "nav": { "home": "Home" }, "comments": { "one": "{count} comment", "other": "{count} comments" } }
+//! ```
+//!
+//! Nested objects are flattened into dotted keys. Templates look strings up
+//! with the `t()` Tera function:
+//!
+//! ```text
+//! {{ t(key="nav.home") }}
+//! {{ t(key="comments", count=article.comment_count) }}
+//! {{ t(key="greeting", name=current_user.username, locale="de") }}
+//! ```
+//!
+//! Without `locale` the site's `default_locale` setting is used, then the
+//! theme's `i18n.default`. A missing string falls back to the base language
+//! (`pt` for `pt-BR`), then the theme default, then the key itself. With
+//! `count`, `<key>.one` is used for 1 and `<key>.other` otherwise. Other
+//! arguments fill `{name}` placeholders.
+
+use anyhow::{Context, Result};
+use serde_json::Value;
+use std::collections::HashMap;
+use std::fs;
+use std::path::Path;
+use std::sync::Arc;
+use tera::Tera;
+
+/// Directory of a theme holding the locale files
+pub const LOCALES_DIR: &str = "locales";
+/// Locale used when a theme declares no default
+pub const FALLBACK_LOCALE: &str = "en";
+
+/// Translated strings of one theme, by locale then dotted key
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ThemeLocales {
+    messages: HashMap<String, HashMap<String, String>>,
+    /// The theme's default locale
+    fallback: String,
+}
+
+impl ThemeLocales {
+    /// No strings yet, falling back to `fallback`
+    pub fn new(fallback: &str) -> Self {
+        Self {
+            messages: HashMap::new(),
+            fallback: fallback.to_string(),
+        }
+    }
+
+    /// Load every `*.json` file of `dir`; a missing directory means no strings
+    pub fn load(dir: &Path, fallback: &str) -> Result<Self> {
+        let mut locales = Self::new(fallback);
+        if !dir.is_dir() {
+            return Ok(locales);
+        }
+        for entry in fs::read_dir(dir)? {
+            let path = entry?.path();
+            if path.extension().and_then(|ext| ext.to_str()) != Some("json") {
+                continue;
+            }
+            let Some(locale) = path.file_stem().and_then(|s| s.to_str()) else {
+                continue;
+            };
+            if !is_valid_locale(locale) {
+                tracing::warn!("Skipping locale file with invalid name: {:?}", path);
+                continue;
+            }
+            let content = fs::read_to_string(&path)
+                .with_context(|| format!("Failed to read locale file: {:?}", path))?;
+            let value: Value = serde_json::from_str(&content)
+                .with_context(|| format!("Invalid locale file: {:?}", path))?;
+            locales.insert(locale, &value);
+        }
+        Ok(locales)
+    }
+
+    /// Add the strings of a parsed locale file, flattening nested objects
+    pub fn insert(&mut self, locale: &str, value: &Value) {
+        let messages = self.messages.entry(locale.to_string()).or_default();
+        flatten("", value, messages);
+    }
+
+    /// Locales that have at least one string, sorted
+    pub fn locales(&self) -> Vec<&str> {
+        let mut locales: Vec<&str> = self.messages.keys().map(String::as_str).collect();
+        locales.sort_unstable();
+        locales
+    }
+
+    /// Look up `key` for `locale` and fill its placeholders
+    pub fn translate(&self, key: &str, locale: &str, args: &HashMap<String, Value>) -> String {
+        let count = args.get("count").and_then(Value::as_f64);
+        let keys: Vec<String> = match count {
+            Some(n) => {
+                let form = if n == 1.0 { "one" } else { "other" };
+                vec![format!("{}.{}", key, form), key.to_string()]
+            }
+            None => vec![key.to_string()],
+        };
+
+        let template = self
+            .chain(locale)
+            .iter()
+            .filter_map(|l| self.messages.get(l))
+            .find_map(|messages| keys.iter().find_map(|k| messages.get(k)))
+            .map(String::as_str)
+            .unwrap_or(key);
+        interpolate(template, args)
+    }
+
+    /// Locales to try, most specific first
+    fn chain(&self, locale: &str) -> Vec<String> {
+        let mut chain: Vec<String> = Vec::new();
+        for candidate in [locale, self.fallback.as_str()] {
+            let candidate = candidate.replace('_', "-");
+            let base = candidate.split('-').next().unwrap_or_default().to_string();
+            for l in [candidate, base] {
+                if !l.is_empty() && !chain.contains(&l) {
+                    chain.push(l);
+                }
+            }
+        }
+        chain
+    }
+}
+
+/// Register the `t()` function, with `locale` used when a call names none
+pub fn register_functions(tera: &mut Tera, locales: Arc<ThemeLocales>, locale: String) {
+    tera.register_function("t", move |args: &HashMap<String, Value>| {
+        let key = args
+            .get("key")
+            .and_then(Value::as_str)
+            .ok_or_else(|| tera::Error::msg("t() requires a string `key` argument"))?;
+        let locale = args
+            .get("locale")
+            .and_then(Value::as_str)
+            .filter(|l| !l.trim().is_empty())
+            .unwrap_or(&locale);
+        Ok(Value::String(locales.translate(key, locale.trim(), args)))
+    });
+}
+
+fn flatten(prefix: &str, value: &Value, out: &mut HashMap<String, String>) {
+    match value {
+        Value::Object(map) => {
+            for (key, value) in map {
+                let key = if prefix.is_empty() {
+                    key.clone()
+                } else {
+                    format!("{}.{}", prefix, key)
+                };
+                flatten(&key, value, out);
+            }
+        }
+        Value::String(s) if !prefix.is_empty() => {
+            out.insert(prefix.to_string(), s.clone());
+        }
+        Value::Number(n) if !prefix.is_empty() => {
+            out.insert(prefix.to_string(), n.to_string());
+        }
+        _ => {}
+    }
+}
+
+/// Replace `{name}` with the `name` argument; unknown placeholders stay
+fn interpolate(template: &str, args: &HashMap<String, Value>) -> String {
+    let mut out = String::with_capacity(template.len());
+    let mut rest = template;
+    while let Some(start) = rest.find('{') {
+        out.push_str(&rest[..start]);
+        let after = &rest[start + 1..];
+        let placeholder = after
+            .find('}')
+            .filter(|&end| {
+                end > 0
+                    && after[..end]
+                        .bytes()
+                        .all(|b| b.is_ascii_alphanumeric() || b == b'_')
+            })
+            .map(|end| (end, args.get(&after[..end])));
+        match placeholder {
+            Some((end, Some(value))) => {
+                match value {
+                    Value::String(s) => out.push_str(s),
+                    Value::Null => {}
+                    other => out.push_str(&other.to_string()),
+                }
+                rest = &after[end + 1..];
+            }
+            _ => {
+                out.push('{');
+                rest = after;
+            }
+        }
+    }
+    out.push_str(rest);
+    out
+}
+
+fn is_valid_locale(locale: &str) -> bool {
+    !locale.is_empty()
+        && locale.len() <= 20
+        && locale
+            .chars()
+            .all(|ch| ch.is_ascii_alphanumeric() || ch == '-' || ch == '_')
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn locales() -> ThemeLocales {
+        let mut locales = ThemeLocales::new("en");
+        locales.insert(
+            "en",
+            &json!({
+                "nav": { "home": "Home", "archive": "Archive" },
+                "comments": { "one": "{count} comment", "other": "{count} comments" },
+                "greeting": "Hello, {name}!"
+            }),
+        );
+        locales.insert("pt", &json!({ "nav": { "home": "Início" } }));
+        locales.insert("pt-BR", &json!({ "nav": { "archive": "Arquivo" } }));
+        locales
+    }
+
+    fn args(pairs: &[(&str, Value)]) -> HashMap<String, Value> {
+        pairs
+            .iter()
+            .map(|(k, v)| (k.to_string(), v.clone()))
+            .collect()
+    }
+
+    #[test]
+    fn falls_back_to_base_language_then_theme_default_then_key() {
+        let locales = locales();
+        let none = HashMap::new();
+        assert_eq!(locales.translate("nav.archive", "pt-BR", &none), "Arquivo");
+        assert_eq!(locales.translate("nav.home", "pt_BR", &none), "Início");
+        assert_eq!(
+            locales.translate("greeting", "pt-BR", &none),
+            "Hello, {name}!"
+        );
+        assert_eq!(locales.translate("nav.missing", "de", &none), "nav.missing");
+        assert_eq!(locales.locales(), ["en", "pt", "pt-BR"]);
+    }
+
+    #[test]
+    fn fills_placeholders_and_plural_forms() {
+        let locales = locales();
+        assert_eq!(
+            locales.translate("greeting", "en", &args(&[("name", json!("Ada"))])),
+            "Hello, Ada!"
+        );
+        assert_eq!(
+            locales.translate("comments", "en", &args(&[("count", json!(1))])),
+            "1 comment"
+        );
+        assert_eq!(
+            locales.translate("comments", "en", &args(&[("count", json!(3))])),
+            "3 comments"
+        );
+        assert_eq!(
+            interpolate("{a} {b} {} {", &args(&[("a", json!(1))])),
+            "1 {b} {} {"
+        );
+    }
+
+    #[test]
+    fn tera_function_uses_the_default_locale() {
+        let mut tera = Tera::default();
+        register_functions(&mut tera, Arc::new(locales()), "pt-BR".to_string());
+        tera.add_raw_template(
+            "nav.html",
+            r#"{{ t(key="nav.home") }}|{{ t(key="nav.home", locale="en") }}|{{ t(key="greeting", name="<b>") }}"#,
+        )
+        .unwrap();
+        let html = tera.render("nav.html", &tera::Context::new()).unwrap();
+        assert_eq!(html, "Início|Home|Hello, &lt;b&gt;!");
+    }
+
+    #[test]
+    fn loads_locale_files_from_a_directory() {
+        let dir = tempfile::tempdir().unwrap();
+        fs::write(dir.path().join("de.json"), r#"{"nav": {"home": "Start"}}"#).unwrap();
+        fs::write(dir.path().join("notes.txt"), "ignored").unwrap();
+        let locales = ThemeLocales::load(dir.path(), "de").unwrap();
+        assert_eq!(
+            locales.translate("nav.home", "fr", &HashMap::new()),
+            "Start"
+        );
+
+        fs::write(dir.path().join("en.json"), "{ not json").unwrap();
+        assert!(ThemeLocales::load(dir.path(), "de").is_err());
+        assert!(ThemeLocales::load(&dir.path().join("missing"), "en")
+            .unwrap()
+            .locales()
+            .is_empty());
+    }
+}
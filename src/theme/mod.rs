@@ -20,10 +20,15 @@ use tracing;
 use crate::plugin::loader::{check_version_requirement, NOTEVA_VERSION};
 use crate::plugin::HookManager;
 
+pub mod custom_field;
 mod error;
+pub mod locale;
+pub mod social_meta;
+pub mod structured_data;
 pub mod validation;
 
 pub use error::ThemeError;
+pub use locale::ThemeLocales;
 
 /// Theme engine for rendering templates
 pub struct ThemeEngine {
@@ -39,6 +44,10 @@ pub struct ThemeEngine {
     theme_cache: HashMap<String, ThemeInfo>,
     /// Hook manager for triggering theme_switch hook
     hook_manager: Option<Arc<HookManager>>,
+    /// Translated strings of the current theme
+    locales: Arc<ThemeLocales>,
+    /// Site-level default locale (`default_locale` setting)
+    site_locale: Option<String>,
 }
 
 /// Result of a theme switch operation with fallback support
@@ -94,7 +103,16 @@ impl ThemeEngine {
             default_theme: default_theme.to_string(),
             theme_cache: HashMap::new(),
             hook_manager: None,
+            locales: Arc::new(ThemeLocales::default()),
+            site_locale: None,
         };
+        social_meta::register_functions(&mut engine.tera);
+        custom_field::register_functions(&mut engine.tera);
+        locale::register_functions(
+            &mut engine.tera,
+            engine.locales.clone(),
+            locale::FALLBACK_LOCALE.to_string(),
+        );
 
         // Cache theme metadata FIRST so dir_name resolution works
         engine.refresh_theme_cache()?;
@@ -157,8 +175,22 @@ impl ThemeEngine {
             theme_path.clone()
         };
 
+        let theme_default_locale = self.theme_default_locale(theme_name);
+        let locales_path = [&theme_path, &template_path]
+            .iter()
+            .map(|dir| dir.join(locale::LOCALES_DIR))
+            .find(|dir| dir.is_dir())
+            .unwrap_or_else(|| theme_path.join(locale::LOCALES_DIR));
+        let locales =
+            ThemeLocales::load(&locales_path, &theme_default_locale).unwrap_or_else(|e| {
+                tracing::warn!("Failed to load locales of theme '{}': {}", theme_name, e);
+                ThemeLocales::new(&theme_default_locale)
+            });
+
         // Create a new Tera instance
         let mut tera = Tera::default();
+        social_meta::register_functions(&mut tera);
+        custom_field::register_functions(&mut tera);
 
         // Collect all templates first
         let mut templates: Vec<(String, String)> = Vec::new();
@@ -184,9 +216,54 @@ impl ThemeEngine {
         })?;
 
         self.tera = tera;
+        self.locales = Arc::new(locales);
+        self.register_locale_function(&theme_default_locale);
         Ok(())
     }
 
+    /// (Re)register `t()` with the current theme's strings and locale
+    fn register_locale_function(&mut self, theme_default_locale: &str) {
+        let locale = self
+            .site_locale
+            .clone()
+            .unwrap_or_else(|| theme_default_locale.to_string());
+        locale::register_functions(&mut self.tera, self.locales.clone(), locale);
+    }
+
+    /// Set the site-level default locale used by `t()`; `None` or an empty
+    /// value means the theme's default
+    pub fn set_locale(&mut self, locale: Option<&str>) {
+        self.site_locale = locale
+            .map(str::trim)
+            .filter(|l| !l.is_empty())
+            .map(str::to_string);
+        let theme_default_locale = self.theme_default_locale(&self.current_theme);
+        self.register_locale_function(&theme_default_locale);
+    }
+
+    /// Locale `t()` uses when a template names none
+    pub fn locale(&self) -> String {
+        self.site_locale
+            .clone()
+            .unwrap_or_else(|| self.theme_default_locale(&self.current_theme))
+    }
+
+    /// Locales the current theme ships strings for
+    pub fn theme_locales(&self) -> Vec<&str> {
+        self.locales.locales()
+    }
+
+    /// `i18n.default` of a theme's theme.json
+    fn theme_default_locale(&self, theme_name: &str) -> String {
+        self.theme_cache
+            .get(theme_name)
+            .and_then(|info| info.i18n.as_ref())
+            .map(|i18n| i18n.default.trim())
+            .filter(|l| !l.is_empty())
+            .unwrap_or(locale::FALLBACK_LOCALE)
+            .to_string()
+    }
+
     /// Collect templates from a directory
     fn collect_templates_from_dir(
         &self,
@@ -405,11 +482,15 @@ impl ThemeEngine {
         full_context.insert("site_description", &standard_vars.site_description);
         full_context.insert("request_path", &standard_vars.request_path);
         full_context.insert("theme_name", &self.current_theme);
+        full_context.insert("locale", &self.locale());
         full_context.insert("year", &standard_vars.year);
 
         if let Some(ref user) = standard_vars.current_user {
             full_context.insert("current_user", user);
         }
+        if let Some(ref seo) = standard_vars.seo {
+            full_context.insert("seo", seo);
+        }
 
         self.render(template, &full_context)
     }
@@ -876,6 +957,46 @@ pub struct StandardTemplateVars {
     pub request_path: String,
     /// Current year (for copyright)
     pub year: i32,
+    /// Search engine metadata of the article being rendered
+    pub seo: Option<SeoMeta>,
+}
+
+/// Per-article SEO metadata for templates
+///
+/// Unset fields mean the theme should fall back to the article title,
+/// excerpt and permalink.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct SeoMeta {
+    /// Replacement for the page title
+    pub title: Option<String>,
+    /// Replacement for the excerpt in the description meta tag
+    pub description: Option<String>,
+    /// Site path or absolute URL to use as the canonical link
+    pub canonical_url: Option<String>,
+    /// Ask search engines not to index the page
+    pub noindex: bool,
+}
+
+impl SeoMeta {
+    /// Take the SEO fields of an article
+    pub fn from_article(article: &crate::models::Article) -> Self {
+        Self {
+            title: article.meta_title.clone(),
+            description: article.meta_description.clone(),
+            canonical_url: article.canonical_url.clone(),
+            noindex: article.noindex,
+        }
+    }
+
+    /// Canonical link as an absolute URL, joining site paths to `base_url`
+    pub fn absolute_canonical_url(&self, base_url: &str) -> Option<String> {
+        let url = self.canonical_url.as_deref()?;
+        if url.starts_with('/') {
+            Some(format!("{}{}", base_url.trim_end_matches('/'), url))
+        } else {
+            Some(url.to_string())
+        }
+    }
 }
 
 /// Current user information for templates
@@ -902,6 +1023,7 @@ impl StandardTemplateVars {
             current_user: None,
             request_path: request_path.into(),
             year: chrono::Utc::now().year(),
+            seo: None,
         }
     }
 
@@ -910,6 +1032,12 @@ impl StandardTemplateVars {
         self.current_user = Some(user);
         self
     }
+
+    /// Set the SEO metadata of the article being rendered
+    pub fn with_seo(mut self, seo: SeoMeta) -> Self {
+        self.seo = Some(seo);
+        self
+    }
 }
 
 // Import chrono for year calculation
@@ -0,0 +1,268 @@
+//! Open Graph and Twitter Card meta tags
+//!
+//! [`SiteSocial::meta_tags`] builds the `og:*`, `article:*` and `twitter:*`
+//! tags for a page, falling back to the site name, description and default
+//! image where the page has none. Templates get the same through the
+//! `social_meta()` Tera function:
+//!
+//! ```text
+//! {{ social_meta(site=site, type="article", title=article.title,
+//!                description=article.excerpt, url=article.url,
+//!                image=article.thumbnail, author=article.author) | safe }}
+//! ```
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::HashMap;
+use tera::Tera;
+
+/// Image shared when a page has none, defaults to the site logo
+pub const DEFAULT_IMAGE_KEY: &str = "seo_default_image";
+/// Twitter account of the site, e.g. "@noteva"
+pub const TWITTER_SITE_KEY: &str = "seo_twitter_site";
+
+/// Site-level fallbacks; field names match the export `site` variable
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SiteSocial {
+    pub name: String,
+    pub description: String,
+    /// Absolute site URL without trailing slash; may be empty
+    pub url: String,
+    /// Default share image, path or URL
+    pub image: Option<String>,
+    /// Twitter handle with the leading `@`
+    pub twitter: Option<String>,
+}
+
+/// What a page contributes to its meta tags
+#[derive(Debug, Clone, Default)]
+pub struct SocialPage<'a> {
+    /// `og:type` "article" rather than "website"
+    pub article: bool,
+    pub title: &'a str,
+    pub description: &'a str,
+    /// Path or absolute URL of the page
+    pub url: &'a str,
+    pub image: Option<&'a str>,
+    pub author: Option<&'a str>,
+    /// RFC 3339 timestamps, articles only
+    pub published_time: Option<&'a str>,
+    pub modified_time: Option<&'a str>,
+}
+
+impl SiteSocial {
+    /// Read the site and `seo_*` sharing settings from a settings map
+    pub fn from_settings(settings: &HashMap<String, String>, base_url: &str) -> Self {
+        let get = |key: &str| {
+            settings
+                .get(key)
+                .map(|v| v.trim())
+                .filter(|v| !v.is_empty())
+        };
+        Self {
+            name: get("site_name").unwrap_or("Noteva").to_string(),
+            description: get("site_description").unwrap_or_default().to_string(),
+            url: base_url.trim_end_matches('/').to_string(),
+            image: get(DEFAULT_IMAGE_KEY)
+                .or_else(|| get("site_logo"))
+                .map(str::to_string),
+            twitter: get(TWITTER_SITE_KEY).map(|handle| {
+                if handle.starts_with('@') {
+                    handle.to_string()
+                } else {
+                    format!("@{}", handle)
+                }
+            }),
+        }
+    }
+
+    /// Join a site path to the site URL; absolute URLs pass through
+    fn absolute_url(&self, path: &str) -> String {
+        if path.is_empty() || path.starts_with("http://") || path.starts_with("https://") {
+            path.to_string()
+        } else if path.starts_with('/') {
+            format!("{}{}", self.url, path)
+        } else {
+            format!("{}/{}", self.url, path)
+        }
+    }
+
+    /// `<meta>` tags for a page, one per line
+    pub fn meta_tags(&self, page: &SocialPage) -> String {
+        let title = non_empty(page.title).unwrap_or(&self.name);
+        let description = non_empty(page.description).unwrap_or(&self.description);
+        let own_image = page.image.and_then(non_empty);
+        let image = own_image
+            .or(self.image.as_deref())
+            .map(|image| self.absolute_url(image));
+
+        let mut tags = Vec::new();
+        let mut property = |name: &str, content: &str| {
+            tags.push(format!(
+                r#"<meta property="{}" content="{}">"#,
+                name,
+                escape(content)
+            ));
+        };
+        property("og:title", title);
+        property("og:description", description);
+        property("og:type", if page.article { "article" } else { "website" });
+        property("og:site_name", &self.name);
+        if let Some(url) = non_empty(page.url) {
+            property("og:url", &self.absolute_url(url));
+        }
+        if let Some(image) = &image {
+            property("og:image", image);
+        }
+        if page.article {
+            if let Some(time) = page.published_time.and_then(non_empty) {
+                property("article:published_time", time);
+            }
+            if let Some(time) = page.modified_time.and_then(non_empty) {
+                property("article:modified_time", time);
+            }
+            if let Some(author) = page.author.and_then(non_empty) {
+                property("article:author", author);
+            }
+        }
+
+        let mut meta_name = |name: &str, content: &str| {
+            tags.push(format!(
+                r#"<meta name="{}" content="{}">"#,
+                name,
+                escape(content)
+            ));
+        };
+        // A large card only for the page's own image, not the site logo
+        meta_name(
+            "twitter:card",
+            if own_image.is_some() {
+                "summary_large_image"
+            } else {
+                "summary"
+            },
+        );
+        meta_name("twitter:title", title);
+        meta_name("twitter:description", description);
+        if let Some(image) = &image {
+            meta_name("twitter:image", image);
+        }
+        if let Some(handle) = &self.twitter {
+            meta_name("twitter:site", handle);
+        }
+        tags.join("\n")
+    }
+}
+
+fn non_empty(s: &str) -> Option<&str> {
+    let s = s.trim();
+    (!s.is_empty()).then_some(s)
+}
+
+fn escape(s: &str) -> String {
+    s.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+/// Register `social_meta()` on a Tera instance
+pub fn register_functions(tera: &mut Tera) {
+    tera.register_function("social_meta", social_meta_function);
+}
+
+fn social_meta_function(args: &HashMap<String, Value>) -> tera::Result<Value> {
+    let site: SiteSocial = match args.get("site") {
+        Some(site) => serde_json::from_value(site.clone())
+            .map_err(|e| tera::Error::msg(format!("social_meta: invalid `site`: {}", e)))?,
+        None => SiteSocial::default(),
+    };
+    let text = |key: &str| args.get(key).and_then(Value::as_str);
+    let page = SocialPage {
+        article: text("type") == Some("article"),
+        title: text("title").unwrap_or_default(),
+        description: text("description").unwrap_or_default(),
+        url: text("url").unwrap_or_default(),
+        image: text("image"),
+        author: text("author"),
+        published_time: text("published_time"),
+        modified_time: text("modified_time"),
+    };
+    Ok(Value::String(site.meta_tags(&page)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn site() -> SiteSocial {
+        SiteSocial::from_settings(
+            &[
+                ("site_name", "My Blog"),
+                ("site_description", "Notes"),
+                ("site_logo", "/logo.png"),
+                (TWITTER_SITE_KEY, "myblog"),
+            ]
+            .iter()
+            .map(|(k, v)| (k.to_string(), v.to_string()))
+            .collect(),
+            "https://blog.example/",
+        )
+    }
+
+    #[test]
+    fn article_tags_use_the_page_image_for_a_large_card() {
+        let tags = site().meta_tags(&SocialPage {
+            article: true,
+            title: "Hello \"World\"",
+            url: "/posts/hello",
+            image: Some("/uploads/cover.png"),
+            author: Some("Ada"),
+            published_time: Some("2026-01-01T00:00:00+00:00"),
+            ..Default::default()
+        });
+        assert!(tags.contains(r#"<meta property="og:title" content="Hello &quot;World&quot;">"#));
+        assert!(tags.contains(r#"<meta property="og:description" content="Notes">"#));
+        assert!(
+            tags.contains(r#"<meta property="og:url" content="https://blog.example/posts/hello">"#)
+        );
+        assert!(tags.contains(r#"content="https://blog.example/uploads/cover.png""#));
+        assert!(tags.contains(r#"<meta property="article:author" content="Ada">"#));
+        assert!(tags.contains(r#"<meta name="twitter:card" content="summary_large_image">"#));
+        assert!(tags.contains(r#"<meta name="twitter:site" content="@myblog">"#));
+        assert!(!tags.contains("article:modified_time"));
+    }
+
+    #[test]
+    fn pages_without_an_image_fall_back_to_the_site() {
+        let tags = site().meta_tags(&SocialPage::default());
+        assert!(tags.contains(r#"<meta property="og:type" content="website">"#));
+        assert!(tags.contains(r#"<meta property="og:title" content="My Blog">"#));
+        assert!(
+            tags.contains(r#"<meta property="og:image" content="https://blog.example/logo.png">"#)
+        );
+        assert!(tags.contains(r#"<meta name="twitter:card" content="summary">"#));
+        assert!(!tags.contains("og:url"));
+        assert!(!tags.contains("article:"));
+    }
+
+    #[test]
+    fn tera_function_reads_the_site_variable() {
+        let mut tera = Tera::default();
+        register_functions(&mut tera);
+        tera.add_raw_template(
+            "head.html",
+            r#"{{ social_meta(site=site, type="article", title=title, image=image) | safe }}"#,
+        )
+        .unwrap();
+        let mut context = tera::Context::new();
+        context.insert("site", &site());
+        context.insert("title", "Post");
+        context.insert("image", &Option::<String>::None);
+        let html = tera.render("head.html", &context).unwrap();
+        assert!(html.contains(r#"<meta property="og:title" content="Post">"#));
+        assert!(html.contains(r#"<meta property="og:type" content="article">"#));
+        assert!(html.contains(r#"<meta name="twitter:card" content="summary">"#));
+    }
+}
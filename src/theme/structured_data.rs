@@ -0,0 +1,332 @@
+//! schema.org structured data (JSON-LD)
+//!
+//! Builds the `BlogPosting`, `WebPage`, `WebSite`, `BreadcrumbList` and
+//! `FAQPage` objects that search engines read from article, page and home
+//! page HTML.
+//! The publisher defaults to the site name and logo; the `seo_publisher_*`
+//! settings override it, e.g. to publish as a person rather than an
+//! organization.
+
+use chrono::{DateTime, Utc};
+use serde_json::{json, Map, Value};
+use std::collections::HashMap;
+
+/// "Organization" (default) or "Person"
+pub const PUBLISHER_TYPE_KEY: &str = "seo_publisher_type";
+/// Publisher name, defaults to the site name
+pub const PUBLISHER_NAME_KEY: &str = "seo_publisher_name";
+/// Publisher logo path or URL, defaults to the site logo
+pub const PUBLISHER_LOGO_KEY: &str = "seo_publisher_logo";
+/// "BlogPosting" (default), "Article" or "NewsArticle"
+pub const ARTICLE_TYPE_KEY: &str = "seo_article_type";
+
+/// Settings read by [`StructuredData::from_settings`], besides the site ones
+pub const SETTING_KEYS: [&str; 4] = [
+    PUBLISHER_TYPE_KEY,
+    PUBLISHER_NAME_KEY,
+    PUBLISHER_LOGO_KEY,
+    ARTICLE_TYPE_KEY,
+];
+
+const ARTICLE_TYPES: [&str; 3] = ["BlogPosting", "Article", "NewsArticle"];
+
+/// Site-wide inputs for structured data
+#[derive(Debug, Clone, PartialEq)]
+pub struct StructuredData {
+    site_name: String,
+    site_description: String,
+    /// Absolute site URL without trailing slash; may be empty
+    base_url: String,
+    publisher_type: &'static str,
+    publisher_name: String,
+    publisher_logo: Option<String>,
+    article_type: &'static str,
+}
+
+/// What an article contributes to its JSON-LD
+#[derive(Debug, Clone, Default)]
+pub struct ArticleData<'a> {
+    pub headline: &'a str,
+    pub description: &'a str,
+    /// Absolute URL of the article
+    pub url: &'a str,
+    pub image: Option<&'a str>,
+    pub published_at: Option<DateTime<Utc>>,
+    pub updated_at: Option<DateTime<Utc>>,
+    pub author: Option<&'a str>,
+    /// Category name, used as `articleSection`
+    pub section: Option<&'a str>,
+    pub keywords: &'a [String],
+    /// Link to the terms the article may be reused under
+    pub license: Option<&'a str>,
+}
+
+impl StructuredData {
+    /// Read the site and `seo_publisher_*` settings from a settings map
+    pub fn from_settings(settings: &HashMap<String, String>, base_url: &str) -> Self {
+        let get = |key: &str| {
+            settings
+                .get(key)
+                .map(|v| v.trim())
+                .filter(|v| !v.is_empty())
+        };
+        let site_name = get("site_name").unwrap_or("Noteva").to_string();
+        let publisher_type = match get(PUBLISHER_TYPE_KEY) {
+            Some(t) if t.eq_ignore_ascii_case("person") => "Person",
+            _ => "Organization",
+        };
+        let article_type = get(ARTICLE_TYPE_KEY)
+            .and_then(|t| {
+                ARTICLE_TYPES
+                    .iter()
+                    .find(|known| known.eq_ignore_ascii_case(t))
+            })
+            .copied()
+            .unwrap_or("BlogPosting");
+        Self {
+            publisher_name: get(PUBLISHER_NAME_KEY).unwrap_or(&site_name).to_string(),
+            site_description: get("site_description").unwrap_or_default().to_string(),
+            site_name,
+            base_url: base_url.trim_end_matches('/').to_string(),
+            publisher_type,
+            publisher_logo: get(PUBLISHER_LOGO_KEY)
+                .or_else(|| get("site_logo"))
+                .map(str::to_string),
+            article_type,
+        }
+    }
+
+    /// Join a site path to the base URL; absolute URLs pass through
+    pub fn absolute_url(&self, path: &str) -> String {
+        if path.starts_with("http://") || path.starts_with("https://") {
+            path.to_string()
+        } else {
+            format!("{}/{}", self.base_url, path.trim_start_matches('/'))
+        }
+    }
+
+    fn publisher(&self) -> Value {
+        let mut publisher = Map::new();
+        publisher.insert("@type".into(), json!(self.publisher_type));
+        publisher.insert("name".into(), json!(self.publisher_name));
+        if !self.base_url.is_empty() {
+            publisher.insert("url".into(), json!(format!("{}/", self.base_url)));
+        }
+        if let Some(logo) = &self.publisher_logo {
+            let key = if self.publisher_type == "Person" {
+                "image"
+            } else {
+                "logo"
+            };
+            publisher.insert(
+                key.into(),
+                json!({ "@type": "ImageObject", "url": self.absolute_url(logo) }),
+            );
+        }
+        Value::Object(publisher)
+    }
+
+    /// The site itself, for the home page
+    pub fn website(&self) -> Value {
+        let mut website = json!({
+            "@type": "WebSite",
+            "name": self.site_name,
+            "publisher": self.publisher(),
+        });
+        if !self.site_description.is_empty() {
+            website["description"] = json!(self.site_description);
+        }
+        if !self.base_url.is_empty() {
+            website["url"] = json!(format!("{}/", self.base_url));
+        }
+        website
+    }
+
+    pub fn article(&self, article: &ArticleData) -> Value {
+        let mut value = json!({
+            "@type": self.article_type,
+            "headline": article.headline,
+            "description": article.description,
+            "url": article.url,
+            "mainEntityOfPage": { "@type": "WebPage", "@id": article.url },
+            "publisher": self.publisher(),
+        });
+        if let Some(image) = article.image {
+            value["image"] = json!(self.absolute_url(image));
+        }
+        if let Some(published_at) = article.published_at {
+            value["datePublished"] = json!(published_at.to_rfc3339());
+        }
+        if let Some(updated_at) = article.updated_at {
+            value["dateModified"] = json!(updated_at.to_rfc3339());
+        }
+        value["author"] = match article.author {
+            Some(author) => json!({ "@type": "Person", "name": author }),
+            None => self.publisher(),
+        };
+        if let Some(section) = article.section {
+            value["articleSection"] = json!(section);
+        }
+        if !article.keywords.is_empty() {
+            value["keywords"] = json!(article.keywords.join(", "));
+        }
+        if let Some(license) = article.license {
+            value["license"] = json!(self.absolute_url(license));
+        }
+        value
+    }
+
+    /// A standalone page such as /about
+    pub fn web_page(&self, title: &str, description: &str, url: &str) -> Value {
+        let mut page = json!({
+            "@type": "WebPage",
+            "name": title,
+            "url": url,
+            "isPartOf": { "@type": "WebSite", "name": self.site_name },
+        });
+        if !description.is_empty() {
+            page["description"] = json!(description);
+        }
+        page
+    }
+
+    /// Trail of `(name, absolute URL)` from the home page down
+    pub fn breadcrumbs(&self, trail: &[(&str, String)]) -> Value {
+        let mut items = vec![json!({
+            "@type": "ListItem",
+            "position": 1,
+            "name": self.site_name,
+            "item": format!("{}/", self.base_url),
+        })];
+        for (i, (name, url)) in trail.iter().enumerate() {
+            items.push(json!({
+                "@type": "ListItem",
+                "position": i + 2,
+                "name": name,
+                "item": url,
+            }));
+        }
+        json!({ "@type": "BreadcrumbList", "itemListElement": items })
+    }
+}
+
+/// Questions and answers for FAQ rich results; answers are HTML
+pub fn faq_page(questions: &[(&str, &str)]) -> Value {
+    let entities: Vec<Value> = questions
+        .iter()
+        .map(|(question, answer)| {
+            json!({
+                "@type": "Question",
+                "name": question,
+                "acceptedAnswer": { "@type": "Answer", "text": answer },
+            })
+        })
+        .collect();
+    json!({ "@type": "FAQPage", "mainEntity": entities })
+}
+
+/// A `<script type="application/ld+json">` holding the objects as a graph
+pub fn script_tag(graph: Vec<Value>) -> String {
+    let document = json!({ "@context": "https://schema.org", "@graph": graph });
+    // `</script>` or `<!--` in a title must not end the script element
+    let json = serde_json::to_string(&document)
+        .unwrap_or_else(|_| "{}".to_string())
+        .replace('<', "\\u003c")
+        .replace('>', "\\u003e")
+        .replace('&', "\\u0026");
+    format!(r#"<script type="application/ld+json">{}</script>"#, json)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn settings(pairs: &[(&str, &str)]) -> HashMap<String, String> {
+        pairs
+            .iter()
+            .map(|(k, v)| (k.to_string(), v.to_string()))
+            .collect()
+    }
+
+    #[test]
+    fn publisher_falls_back_to_site_settings() {
+        let data = StructuredData::from_settings(
+            &settings(&[("site_name", "My Blog"), ("site_logo", "/logo.png")]),
+            "https://blog.example/",
+        );
+        let website = data.website();
+        assert_eq!(website["url"], "https://blog.example/");
+        assert_eq!(website["publisher"]["@type"], "Organization");
+        assert_eq!(website["publisher"]["name"], "My Blog");
+        assert_eq!(
+            website["publisher"]["logo"]["url"],
+            "https://blog.example/logo.png"
+        );
+
+        let data = StructuredData::from_settings(
+            &settings(&[
+                ("site_name", "My Blog"),
+                (PUBLISHER_TYPE_KEY, "person"),
+                (PUBLISHER_NAME_KEY, "Ada"),
+                (ARTICLE_TYPE_KEY, "article"),
+            ]),
+            "https://blog.example",
+        );
+        assert_eq!(data.publisher()["@type"], "Person");
+        assert_eq!(data.publisher()["name"], "Ada");
+        assert_eq!(data.article_type, "Article");
+    }
+
+    #[test]
+    fn article_includes_dates_author_and_breadcrumbs() {
+        let data =
+            StructuredData::from_settings(&settings(&[("site_name", "Blog")]), "https://b.example");
+        let keywords = vec!["rust".to_string(), "web".to_string()];
+        let article = data.article(&ArticleData {
+            headline: "Hello",
+            description: "First post",
+            url: "https://b.example/posts/hello",
+            image: Some("/uploads/cover.png"),
+            published_at: Some(DateTime::from_timestamp(0, 0).unwrap()),
+            author: Some("Ada"),
+            section: Some("Notes"),
+            keywords: &keywords,
+            license: Some("/reuse"),
+            ..Default::default()
+        });
+        assert_eq!(article["@type"], "BlogPosting");
+        assert_eq!(article["image"], "https://b.example/uploads/cover.png");
+        assert_eq!(article["datePublished"], "1970-01-01T00:00:00+00:00");
+        assert!(article.get("dateModified").is_none());
+        assert_eq!(article["author"]["name"], "Ada");
+        assert_eq!(article["keywords"], "rust, web");
+        assert_eq!(article["license"], "https://b.example/reuse");
+
+        let crumbs = data.breadcrumbs(&[("Hello", "https://b.example/posts/hello".to_string())]);
+        assert_eq!(crumbs["itemListElement"][0]["item"], "https://b.example/");
+        assert_eq!(crumbs["itemListElement"][1]["position"], 2);
+    }
+
+    #[test]
+    fn faq_page_lists_questions_with_answers() {
+        let faq = faq_page(&[
+            ("Is it free?", "<p>Yes.</p>"),
+            ("Can I export?", "<p>Any time.</p>"),
+        ]);
+        assert_eq!(faq["@type"], "FAQPage");
+        assert_eq!(faq["mainEntity"][1]["name"], "Can I export?");
+        assert_eq!(
+            faq["mainEntity"][0]["acceptedAnswer"]["text"],
+            "<p>Yes.</p>"
+        );
+    }
+
+    #[test]
+    fn script_tag_cannot_be_closed_by_content() {
+        let tag = script_tag(vec![json!({ "headline": "</script><script>alert(1)" })]);
+        assert!(tag
+            .starts_with(r#"<script type="application/ld+json">{"@context":"https://schema.org""#));
+        assert_eq!(tag.matches("</script>").count(), 1);
+        assert!(tag.contains("\\u003c/script\\u003e"));
+    }
+}
@@ -126,6 +126,57 @@ fn test_render_with_standard_vars() {
     assert!(result.contains("A great blog"));
 }
 
+#[test]
+fn test_render_with_seo_vars() {
+    let temp_dir = TempDir::new().unwrap();
+    let themes_path = temp_dir.path().join("themes");
+    create_test_theme(&themes_path, "default");
+    fs::write(
+        themes_path.join("default").join("dist").join("seo.html"),
+        r#"{% if seo %}{{ seo.title }}|{{ seo.noindex }}{% else %}none{% endif %}"#,
+    )
+    .unwrap();
+
+    let engine = ThemeEngine::new(&themes_path, "default").unwrap();
+    let context = TeraContext::new();
+
+    let plain = StandardTemplateVars::new("My Blog", "A great blog", "/");
+    let result = engine
+        .render_with_standard_vars("seo.html", &context, &plain)
+        .unwrap();
+    assert_eq!(result, "none");
+
+    let with_seo = plain.with_seo(SeoMeta {
+        title: Some("Custom Title".to_string()),
+        noindex: true,
+        ..Default::default()
+    });
+    let result = engine
+        .render_with_standard_vars("seo.html", &context, &with_seo)
+        .unwrap();
+    assert_eq!(result, "Custom Title|true");
+}
+
+#[test]
+fn test_seo_meta_absolute_canonical_url() {
+    let mut seo = SeoMeta::default();
+    assert_eq!(seo.absolute_canonical_url("https://blog.example"), None);
+
+    seo.canonical_url = Some("/posts/original".to_string());
+    assert_eq!(
+        seo.absolute_canonical_url("https://blog.example/")
+            .as_deref(),
+        Some("https://blog.example/posts/original")
+    );
+
+    seo.canonical_url = Some("https://elsewhere.example/a".to_string());
+    assert_eq!(
+        seo.absolute_canonical_url("https://blog.example")
+            .as_deref(),
+        Some("https://elsewhere.example/a")
+    );
+}
+
 #[test]
 fn test_set_theme() {
     let temp_dir = TempDir::new().unwrap();
@@ -1311,3 +1362,39 @@ mod property_tests {
         }
     }
 }
+
+#[test]
+fn test_locale_strings_follow_the_site_locale() {
+    let temp_dir = TempDir::new().unwrap();
+    let themes_path = temp_dir.path().join("themes");
+    let theme_path = create_test_theme(&themes_path, "default");
+    let locales_path = theme_path.join("locales");
+    fs::create_dir_all(&locales_path).unwrap();
+    fs::write(locales_path.join("en.json"), r#"{"nav": {"home": "Home"}}"#).unwrap();
+    fs::write(locales_path.join("de.json"), r#"{"nav": {"home": "Startseite"}}"#).unwrap();
+    fs::write(
+        theme_path.join("dist").join("nav.html"),
+        r#"<html lang="{{ locale }}">{{ t(key="nav.home") }}</html>"#,
+    )
+    .unwrap();
+
+    let mut engine = ThemeEngine::new(&themes_path, "default").unwrap();
+    assert_eq!(engine.theme_locales(), ["de", "en"]);
+    let vars = StandardTemplateVars::new("My Blog", "", "/");
+    let render = |engine: &ThemeEngine| {
+        engine
+            .render_with_standard_vars("nav.html", &TeraContext::new(), &vars)
+            .unwrap()
+    };
+    assert_eq!(render(&engine), r#"<html lang="en">Home</html>"#);
+
+    engine.set_locale(Some("de-AT"));
+    assert_eq!(render(&engine), r#"<html lang="de-AT">Startseite</html>"#);
+
+    // The locale survives reloading templates
+    engine.reload_templates().unwrap();
+    assert_eq!(render(&engine), r#"<html lang="de-AT">Startseite</html>"#);
+
+    engine.set_locale(Some(" "));
+    assert_eq!(engine.locale(), "en");
+}
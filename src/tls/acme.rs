@@ -0,0 +1,739 @@
+//! Automatic certificates over ACME (RFC 8555)
+//!
+//! With `server.tls.acme` set, a certificate for the configured domains is
+//! ordered from the CA (Let's Encrypt by default) and renewed 30 days before
+//! it expires. Domains are validated with HTTP-01 challenges answered by the
+//! plain HTTP listener. The account key, certificate and key live in
+//! `state_dir` so restarts reuse them instead of ordering again.
+
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, RwLock};
+use std::time::Duration;
+
+use data_encoding::{BASE64, BASE64URL_NOPAD};
+use ring::rand::SystemRandom;
+use ring::signature::{
+    EcdsaKeyPair, KeyPair, ECDSA_P256_SHA256_ASN1_SIGNING, ECDSA_P256_SHA256_FIXED_SIGNING,
+};
+use rustls_pki_types::{pem::PemObject, CertificateDer, PrivateKeyDer, PrivatePkcs8KeyDer};
+use serde::Deserialize;
+use serde_json::{json, Value};
+use sha2::{Digest, Sha256};
+use tokio_rustls::rustls::{
+    crypto::ring::sign::any_supported_type,
+    server::{ClientHello, ResolvesServerCert},
+    sign::CertifiedKey,
+};
+
+use crate::config::AcmeConfig;
+
+/// Renew once the certificate has less than this left
+const RENEW_BEFORE_SECS: i64 = 30 * 24 * 3600;
+/// How often the certificate is checked
+const CHECK_INTERVAL: Duration = Duration::from_secs(12 * 3600);
+/// Wait after a failed order; Let's Encrypt limits failed validations
+const RETRY_INTERVAL: Duration = Duration::from_secs(3600);
+/// Polling of authorizations and orders
+const POLL_INTERVAL: Duration = Duration::from_secs(2);
+const POLL_ATTEMPTS: usize = 30;
+
+const ACCOUNT_KEY_FILE: &str = "account.key";
+const CERT_FILE: &str = "cert.pem";
+const KEY_FILE: &str = "key.pem";
+
+/// Errors while ordering certificates
+#[derive(Debug, thiserror::Error)]
+pub enum AcmeError {
+    /// Invalid `server.tls.acme` settings
+    #[error("{0}")]
+    Config(String),
+
+    /// The CA could not be reached
+    #[error("ACME request failed: {0}")]
+    Http(#[from] reqwest::Error),
+
+    /// The CA refused a request or the order failed
+    #[error("ACME error: {0}")]
+    Protocol(String),
+
+    /// Reading or writing `state_dir`
+    #[error("ACME state error: {0}")]
+    Io(#[from] std::io::Error),
+
+    /// Key generation or signing failed
+    #[error("ACME key error: {0}")]
+    Key(String),
+}
+
+/// Pending HTTP-01 challenges, token to key authorization
+#[derive(Debug, Clone, Default)]
+pub struct AcmeChallenges(Arc<RwLock<HashMap<String, String>>>);
+
+impl AcmeChallenges {
+    /// Key authorization to serve for `token`
+    pub fn get(&self, token: &str) -> Option<String> {
+        self.0.read().ok()?.get(token).cloned()
+    }
+
+    fn insert(&self, token: &str, key_authorization: String) {
+        if let Ok(mut pending) = self.0.write() {
+            pending.insert(token.to_string(), key_authorization);
+        }
+    }
+
+    fn remove(&self, token: &str) {
+        if let Ok(mut pending) = self.0.write() {
+            pending.remove(token);
+        }
+    }
+}
+
+/// Hands the current certificate to rustls; renewals swap it in place
+#[derive(Debug, Default)]
+pub struct AcmeCertResolver(RwLock<Option<Arc<CertifiedKey>>>);
+
+impl AcmeCertResolver {
+    fn set(&self, key: Arc<CertifiedKey>) {
+        if let Ok(mut current) = self.0.write() {
+            *current = Some(key);
+        }
+    }
+}
+
+impl ResolvesServerCert for AcmeCertResolver {
+    fn resolve(&self, _client_hello: ClientHello<'_>) -> Option<Arc<CertifiedKey>> {
+        // Handshakes fail until the first certificate has been issued
+        self.0.read().ok()?.clone()
+    }
+}
+
+/// Orders, stores and renews the certificate
+pub struct AcmeManager {
+    config: AcmeConfig,
+    http: reqwest::Client,
+    challenges: AcmeChallenges,
+    resolver: Arc<AcmeCertResolver>,
+}
+
+impl AcmeManager {
+    /// Validate the settings and load a previously issued certificate
+    pub fn new(config: AcmeConfig) -> Result<Self, AcmeError> {
+        if config.domains.is_empty() {
+            return Err(AcmeError::Config(
+                "server.tls.acme.domains must list at least one domain".to_string(),
+            ));
+        }
+        if let Some(bad) = config.domains.iter().find(|d| !is_valid_domain(d)) {
+            return Err(AcmeError::Config(format!(
+                "server.tls.acme.domains: '{}' is not a domain name (wildcards need DNS-01, which is not supported)",
+                bad
+            )));
+        }
+        std::fs::create_dir_all(&config.state_dir)?;
+
+        let http = reqwest::Client::builder()
+            .timeout(Duration::from_secs(30))
+            .user_agent(concat!("noteva/", env!("CARGO_PKG_VERSION")))
+            .build()?;
+        let manager = Self {
+            config,
+            http,
+            challenges: AcmeChallenges::default(),
+            resolver: Arc::new(AcmeCertResolver::default()),
+        };
+        match manager.load_stored() {
+            Ok(Some(key)) => manager.resolver.set(key),
+            Ok(None) => {}
+            Err(e) => tracing::warn!(error = %e, "ignoring stored ACME certificate"),
+        }
+        Ok(manager)
+    }
+
+    pub fn challenges(&self) -> AcmeChallenges {
+        self.challenges.clone()
+    }
+
+    pub fn resolver(&self) -> Arc<AcmeCertResolver> {
+        self.resolver.clone()
+    }
+
+    /// Keep the certificate current; runs until the process exits
+    pub async fn run(self: Arc<Self>) {
+        loop {
+            let wait = match self.renew_if_due().await {
+                Ok(()) => CHECK_INTERVAL,
+                Err(e) => {
+                    tracing::error!(error = %e, "ACME certificate order failed");
+                    RETRY_INTERVAL
+                }
+            };
+            tokio::time::sleep(wait).await;
+        }
+    }
+
+    fn path(&self, file: &str) -> PathBuf {
+        self.config.state_dir.join(file)
+    }
+
+    /// Stored certificate and key, if they cover the configured domains
+    fn load_stored(&self) -> Result<Option<Arc<CertifiedKey>>, AcmeError> {
+        let (cert_path, key_path) = (self.path(CERT_FILE), self.path(KEY_FILE));
+        if !cert_path.exists() || !key_path.exists() {
+            return Ok(None);
+        }
+        let chain = std::fs::read(&cert_path)?;
+        if stored_expiry(&chain, &self.config.domains).is_none() {
+            return Ok(None);
+        }
+        let key = std::fs::read(&key_path)?;
+        certified_key(&chain, &key).map(|key| Some(Arc::new(key)))
+    }
+
+    async fn renew_if_due(&self) -> Result<(), AcmeError> {
+        let expiry = std::fs::read(self.path(CERT_FILE))
+            .ok()
+            .and_then(|chain| stored_expiry(&chain, &self.config.domains));
+        if let Some(not_after) = expiry {
+            if not_after - chrono::Utc::now().timestamp() > RENEW_BEFORE_SECS {
+                return Ok(());
+            }
+        }
+
+        tracing::info!(domains = ?self.config.domains, "ordering ACME certificate");
+        let (chain, key) = self.order().await?;
+        let certified = certified_key(chain.as_bytes(), key.as_bytes())?;
+        write_private(&self.path(KEY_FILE), key.as_bytes())?;
+        std::fs::write(self.path(CERT_FILE), chain.as_bytes())?;
+        self.resolver.set(Arc::new(certified));
+        tracing::info!(domains = ?self.config.domains, "ACME certificate installed");
+        Ok(())
+    }
+
+    /// Run an order to completion; returns the PEM chain and PEM key
+    async fn order(&self) -> Result<(String, String), AcmeError> {
+        let account_key = self.account_key()?;
+        let mut session = Session::new(&self.http, &self.config.directory_url, account_key).await?;
+
+        let contact: Vec<String> = self
+            .config
+            .email
+            .iter()
+            .map(|email| format!("mailto:{}", email))
+            .collect();
+        let new_account = session.directory.new_account.clone();
+        let (headers, _) = session
+            .post(
+                &new_account,
+                Some(&json!({ "termsOfServiceAgreed": true, "contact": contact })),
+            )
+            .await?;
+        session.kid = Some(location(&headers)?);
+
+        let identifiers: Vec<Value> = self
+            .config
+            .domains
+            .iter()
+            .map(|domain| json!({ "type": "dns", "value": domain }))
+            .collect();
+        let new_order = session.directory.new_order.clone();
+        let (headers, body) = session
+            .post(&new_order, Some(&json!({ "identifiers": identifiers })))
+            .await?;
+        let order_url = location(&headers)?;
+        let order: Order = parse(&body)?;
+
+        for authorization_url in &order.authorizations {
+            self.authorize(&mut session, authorization_url).await?;
+        }
+
+        // Certificate key and CSR
+        let rng = SystemRandom::new();
+        let pkcs8 = EcdsaKeyPair::generate_pkcs8(&ECDSA_P256_SHA256_ASN1_SIGNING, &rng)
+            .map_err(|e| AcmeError::Key(e.to_string()))?;
+        let cert_key =
+            EcdsaKeyPair::from_pkcs8(&ECDSA_P256_SHA256_ASN1_SIGNING, pkcs8.as_ref(), &rng)
+                .map_err(|e| AcmeError::Key(e.to_string()))?;
+        let csr = csr_der(&self.config.domains, &cert_key, &rng)?;
+        session
+            .post(
+                &order.finalize,
+                Some(&json!({ "csr": BASE64URL_NOPAD.encode(&csr) })),
+            )
+            .await?;
+
+        let mut certificate_url = None;
+        for _ in 0..POLL_ATTEMPTS {
+            let (_, body) = session.post(&order_url, None).await?;
+            let order: Order = parse(&body)?;
+            match order.status.as_str() {
+                "valid" => {
+                    certificate_url = order.certificate;
+                    break;
+                }
+                "invalid" => return Err(AcmeError::Protocol("order became invalid".to_string())),
+                _ => tokio::time::sleep(POLL_INTERVAL).await,
+            }
+        }
+        let certificate_url = certificate_url
+            .ok_or_else(|| AcmeError::Protocol("timed out waiting for the certificate".into()))?;
+        let (_, chain) = session.post(&certificate_url, None).await?;
+
+        Ok((chain, pem("PRIVATE KEY", pkcs8.as_ref())))
+    }
+
+    async fn authorize(&self, session: &mut Session<'_>, url: &str) -> Result<(), AcmeError> {
+        let (_, body) = session.post(url, None).await?;
+        let authorization: Authorization = parse(&body)?;
+        if authorization.status == "valid" {
+            return Ok(());
+        }
+        let domain = authorization.identifier.value;
+        let challenge = authorization
+            .challenges
+            .into_iter()
+            .find(|c| c.kind == "http-01")
+            .ok_or_else(|| {
+                AcmeError::Protocol(format!("no http-01 challenge offered for {}", domain))
+            })?;
+
+        let key_authorization = format!("{}.{}", challenge.token, session.thumbprint);
+        self.challenges.insert(&challenge.token, key_authorization);
+        let result = async {
+            session.post(&challenge.url, Some(&json!({}))).await?;
+            for _ in 0..POLL_ATTEMPTS {
+                tokio::time::sleep(POLL_INTERVAL).await;
+                let (_, body) = session.post(url, None).await?;
+                let authorization: Authorization = parse(&body)?;
+                match authorization.status.as_str() {
+                    "valid" => return Ok(()),
+                    "pending" | "processing" => {}
+                    status => {
+                        let detail = authorization
+                            .challenges
+                            .iter()
+                            .find_map(|c| c.error.as_ref())
+                            .map(|e| e.to_string())
+                            .unwrap_or_default();
+                        return Err(AcmeError::Protocol(format!(
+                            "validation of {} is {}: {}",
+                            domain, status, detail
+                        )));
+                    }
+                }
+            }
+            Err(AcmeError::Protocol(format!(
+                "timed out validating {}",
+                domain
+            )))
+        }
+        .await;
+        self.challenges.remove(&challenge.token);
+        result
+    }
+
+    /// Load the account key, creating one on first use
+    fn account_key(&self) -> Result<Vec<u8>, AcmeError> {
+        let path = self.path(ACCOUNT_KEY_FILE);
+        if path.exists() {
+            let key = PrivatePkcs8KeyDer::from_pem_file(&path)
+                .map_err(|e| AcmeError::Key(format!("{}: {}", path.display(), e)))?;
+            return Ok(key.secret_pkcs8_der().to_vec());
+        }
+        let pkcs8 =
+            EcdsaKeyPair::generate_pkcs8(&ECDSA_P256_SHA256_FIXED_SIGNING, &SystemRandom::new())
+                .map_err(|e| AcmeError::Key(e.to_string()))?;
+        write_private(&path, pem("PRIVATE KEY", pkcs8.as_ref()).as_bytes())?;
+        Ok(pkcs8.as_ref().to_vec())
+    }
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct Directory {
+    new_nonce: String,
+    new_account: String,
+    new_order: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct Order {
+    status: String,
+    #[serde(default)]
+    authorizations: Vec<String>,
+    finalize: String,
+    certificate: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct Identifier {
+    value: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct Authorization {
+    status: String,
+    identifier: Identifier,
+    #[serde(default)]
+    challenges: Vec<Challenge>,
+}
+
+#[derive(Debug, Deserialize)]
+struct Challenge {
+    #[serde(rename = "type")]
+    kind: String,
+    url: String,
+    #[serde(default)]
+    token: String,
+    error: Option<Value>,
+}
+
+fn parse<T: serde::de::DeserializeOwned>(body: &str) -> Result<T, AcmeError> {
+    serde_json::from_str(body)
+        .map_err(|e| AcmeError::Protocol(format!("unexpected response: {}", e)))
+}
+
+fn location(headers: &reqwest::header::HeaderMap) -> Result<String, AcmeError> {
+    headers
+        .get(reqwest::header::LOCATION)
+        .and_then(|v| v.to_str().ok())
+        .map(str::to_string)
+        .ok_or_else(|| AcmeError::Protocol("response without Location".to_string()))
+}
+
+/// Signed requests with one account key
+struct Session<'a> {
+    http: &'a reqwest::Client,
+    directory: Directory,
+    key: EcdsaKeyPair,
+    rng: SystemRandom,
+    jwk: Value,
+    thumbprint: String,
+    kid: Option<String>,
+    nonce: Option<String>,
+}
+
+impl<'a> Session<'a> {
+    async fn new(
+        http: &'a reqwest::Client,
+        directory_url: &str,
+        account_pkcs8: Vec<u8>,
+    ) -> Result<Session<'a>, AcmeError> {
+        let directory: Directory = http
+            .get(directory_url)
+            .send()
+            .await?
+            .error_for_status()?
+            .json()
+            .await?;
+        let rng = SystemRandom::new();
+        let key = EcdsaKeyPair::from_pkcs8(&ECDSA_P256_SHA256_FIXED_SIGNING, &account_pkcs8, &rng)
+            .map_err(|e| AcmeError::Key(e.to_string()))?;
+        let (jwk, thumbprint) = jwk(key.public_key().as_ref());
+        Ok(Self {
+            http,
+            directory,
+            key,
+            rng,
+            jwk,
+            thumbprint,
+            kid: None,
+            nonce: None,
+        })
+    }
+
+    async fn nonce(&mut self) -> Result<String, AcmeError> {
+        if let Some(nonce) = self.nonce.take() {
+            return Ok(nonce);
+        }
+        let response = self.http.head(&self.directory.new_nonce).send().await?;
+        replay_nonce(response.headers())
+            .ok_or_else(|| AcmeError::Protocol("no Replay-Nonce from newNonce".to_string()))
+    }
+
+    /// POST a JWS; `None` sends a POST-as-GET
+    async fn post(
+        &mut self,
+        url: &str,
+        payload: Option<&Value>,
+    ) -> Result<(reqwest::header::HeaderMap, String), AcmeError> {
+        let mut retried = false;
+        loop {
+            let mut protected = json!({
+                "alg": "ES256",
+                "nonce": self.nonce().await?,
+                "url": url,
+            });
+            match &self.kid {
+                Some(kid) => protected["kid"] = json!(kid),
+                None => protected["jwk"] = self.jwk.clone(),
+            }
+            let protected = BASE64URL_NOPAD.encode(protected.to_string().as_bytes());
+            let payload = payload
+                .map(|p| BASE64URL_NOPAD.encode(p.to_string().as_bytes()))
+                .unwrap_or_default();
+            let signature = self
+                .key
+                .sign(&self.rng, format!("{}.{}", protected, payload).as_bytes())
+                .map_err(|e| AcmeError::Key(e.to_string()))?;
+            let body = json!({
+                "protected": protected,
+                "payload": payload,
+                "signature": BASE64URL_NOPAD.encode(signature.as_ref()),
+            });
+
+            let response = self
+                .http
+                .post(url)
+                .header(reqwest::header::CONTENT_TYPE, "application/jose+json")
+                .body(body.to_string())
+                .send()
+                .await?;
+            self.nonce = replay_nonce(response.headers());
+            let status = response.status();
+            let headers = response.headers().clone();
+            let text = response.text().await?;
+            if status.is_success() {
+                return Ok((headers, text));
+            }
+
+            let problem: Value = serde_json::from_str(&text).unwrap_or_default();
+            let kind = problem["type"].as_str().unwrap_or_default();
+            if kind.ends_with(":badNonce") && !retried {
+                retried = true;
+                continue;
+            }
+            return Err(AcmeError::Protocol(format!(
+                "{} ({}): {}",
+                url,
+                status,
+                problem["detail"].as_str().unwrap_or(&text)
+            )));
+        }
+    }
+}
+
+fn replay_nonce(headers: &reqwest::header::HeaderMap) -> Option<String> {
+    headers
+        .get("replay-nonce")
+        .and_then(|v| v.to_str().ok())
+        .map(str::to_string)
+}
+
+/// JWK of an uncompressed P-256 public key and its RFC 7638 thumbprint
+fn jwk(public_key: &[u8]) -> (Value, String) {
+    let x = BASE64URL_NOPAD.encode(&public_key[1..33]);
+    let y = BASE64URL_NOPAD.encode(&public_key[33..65]);
+    // Members in lexicographic order, no whitespace
+    let canonical = format!(r#"{{"crv":"P-256","kty":"EC","x":"{}","y":"{}"}}"#, x, y);
+    let thumbprint = BASE64URL_NOPAD.encode(&Sha256::digest(canonical.as_bytes()));
+    (
+        json!({ "crv": "P-256", "kty": "EC", "x": x, "y": y }),
+        thumbprint,
+    )
+}
+
+/// Hostnames HTTP-01 can validate
+fn is_valid_domain(domain: &str) -> bool {
+    domain.len() <= 253
+        && domain.contains('.')
+        && domain.split('.').all(|label| {
+            !label.is_empty()
+                && label.len() <= 63
+                && !label.starts_with('-')
+                && !label.ends_with('-')
+                && label
+                    .bytes()
+                    .all(|b| b.is_ascii_alphanumeric() || b == b'-')
+        })
+}
+
+/// Expiry of a stored chain, if its leaf covers every domain
+fn stored_expiry(chain_pem: &[u8], domains: &[String]) -> Option<i64> {
+    let leaf = CertificateDer::pem_slice_iter(chain_pem).next()?.ok()?;
+    let (_, cert) = x509_parser::parse_x509_certificate(leaf.as_ref()).ok()?;
+    let names: Vec<String> = cert
+        .subject_alternative_name()
+        .ok()??
+        .value
+        .general_names
+        .iter()
+        .filter_map(|name| match name {
+            x509_parser::extensions::GeneralName::DNSName(dns) => Some(dns.to_ascii_lowercase()),
+            _ => None,
+        })
+        .collect();
+    domains
+        .iter()
+        .all(|d| names.contains(&d.to_ascii_lowercase()))
+        .then(|| cert.validity().not_after.timestamp())
+}
+
+fn certified_key(chain_pem: &[u8], key_pem: &[u8]) -> Result<CertifiedKey, AcmeError> {
+    let chain = CertificateDer::pem_slice_iter(chain_pem)
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|e| AcmeError::Key(e.to_string()))?;
+    let key = PrivateKeyDer::from_pem_slice(key_pem).map_err(|e| AcmeError::Key(e.to_string()))?;
+    let signing_key = any_supported_type(&key).map_err(|e| AcmeError::Key(e.to_string()))?;
+    Ok(CertifiedKey::new(chain, signing_key))
+}
+
+fn pem(label: &str, der: &[u8]) -> String {
+    let encoded = BASE64.encode(der);
+    let mut out = format!("-----BEGIN {}-----\n", label);
+    for line in encoded.as_bytes().chunks(64) {
+        out.push_str(std::str::from_utf8(line).unwrap_or_default());
+        out.push('\n');
+    }
+    out.push_str(&format!("-----END {}-----\n", label));
+    out
+}
+
+/// Write a key readable only by the owner
+fn write_private(path: &Path, contents: &[u8]) -> std::io::Result<()> {
+    std::fs::write(path, contents)?;
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        std::fs::set_permissions(path, std::fs::Permissions::from_mode(0o600))?;
+    }
+    Ok(())
+}
+
+// DER encoding for the certificate signing request
+
+const OID_COMMON_NAME: &[u8] = &[0x06, 0x03, 0x55, 0x04, 0x03];
+const OID_EC_PUBLIC_KEY: &[u8] = &[0x06, 0x07, 0x2a, 0x86, 0x48, 0xce, 0x3d, 0x02, 0x01];
+const OID_PRIME256V1: &[u8] = &[0x06, 0x08, 0x2a, 0x86, 0x48, 0xce, 0x3d, 0x03, 0x01, 0x07];
+const OID_EXTENSION_REQUEST: &[u8] = &[
+    0x06, 0x09, 0x2a, 0x86, 0x48, 0x86, 0xf7, 0x0d, 0x01, 0x09, 0x0e,
+];
+const OID_SUBJECT_ALT_NAME: &[u8] = &[0x06, 0x03, 0x55, 0x1d, 0x11];
+const OID_ECDSA_WITH_SHA256: &[u8] = &[0x06, 0x08, 0x2a, 0x86, 0x48, 0xce, 0x3d, 0x04, 0x03, 0x02];
+
+fn der(tag: u8, content: &[u8]) -> Vec<u8> {
+    let mut out = vec![tag];
+    let len = content.len();
+    if len < 0x80 {
+        out.push(len as u8);
+    } else {
+        let bytes: Vec<u8> = len
+            .to_be_bytes()
+            .into_iter()
+            .skip_while(|b| *b == 0)
+            .collect();
+        out.push(0x80 | bytes.len() as u8);
+        out.extend(bytes);
+    }
+    out.extend_from_slice(content);
+    out
+}
+
+fn sequence(parts: &[&[u8]]) -> Vec<u8> {
+    der(0x30, &parts.concat())
+}
+
+fn bit_string(bytes: &[u8]) -> Vec<u8> {
+    der(0x03, &[&[0u8][..], bytes].concat())
+}
+
+/// PKCS#10 request for `domains` signed with `key`
+fn csr_der(
+    domains: &[String],
+    key: &EcdsaKeyPair,
+    rng: &SystemRandom,
+) -> Result<Vec<u8>, AcmeError> {
+    let common_name = sequence(&[OID_COMMON_NAME, &der(0x0c, domains[0].as_bytes())]);
+    let subject = sequence(&[&der(0x31, &common_name)]);
+    let public_key_info = sequence(&[
+        &sequence(&[OID_EC_PUBLIC_KEY, OID_PRIME256V1]),
+        &bit_string(key.public_key().as_ref()),
+    ]);
+    let names: Vec<u8> = domains
+        .iter()
+        .flat_map(|domain| der(0x82, domain.as_bytes()))
+        .collect();
+    let extensions = sequence(&[&sequence(&[
+        OID_SUBJECT_ALT_NAME,
+        &der(0x04, &der(0x30, &names)),
+    ])]);
+    let attributes = der(
+        0xa0,
+        &sequence(&[OID_EXTENSION_REQUEST, &der(0x31, &extensions)]),
+    );
+    let info = sequence(&[&der(0x02, &[0]), &subject, &public_key_info, &attributes]);
+    let signature = key
+        .sign(rng, &info)
+        .map_err(|e| AcmeError::Key(e.to_string()))?;
+    Ok(sequence(&[
+        &info,
+        &sequence(&[OID_ECDSA_WITH_SHA256]),
+        &bit_string(signature.as_ref()),
+    ]))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use x509_parser::prelude::{FromDer, ParsedExtension, X509CertificationRequest};
+
+    #[test]
+    fn csr_carries_all_domains() {
+        let rng = SystemRandom::new();
+        let pkcs8 = EcdsaKeyPair::generate_pkcs8(&ECDSA_P256_SHA256_ASN1_SIGNING, &rng).unwrap();
+        let key = EcdsaKeyPair::from_pkcs8(&ECDSA_P256_SHA256_ASN1_SIGNING, pkcs8.as_ref(), &rng)
+            .unwrap();
+        let domains = vec!["example.com".to_string(), "www.example.com".to_string()];
+        let der = csr_der(&domains, &key, &rng).unwrap();
+
+        let (rest, csr) = X509CertificationRequest::from_der(&der).unwrap();
+        assert!(rest.is_empty());
+        assert_eq!(
+            csr.certification_request_info.subject.to_string(),
+            "CN=example.com"
+        );
+        let names: Vec<String> = csr
+            .requested_extensions()
+            .unwrap()
+            .filter_map(|ext| match ext {
+                ParsedExtension::SubjectAlternativeName(san) => Some(
+                    san.general_names
+                        .iter()
+                        .map(|n| n.to_string())
+                        .collect::<Vec<_>>(),
+                ),
+                _ => None,
+            })
+            .flatten()
+            .collect();
+        assert_eq!(
+            names,
+            vec!["DNSName(example.com)", "DNSName(www.example.com)"]
+        );
+    }
+
+    #[test]
+    fn jwk_thumbprint_is_stable() {
+        let rng = SystemRandom::new();
+        let pkcs8 = EcdsaKeyPair::generate_pkcs8(&ECDSA_P256_SHA256_FIXED_SIGNING, &rng).unwrap();
+        let key = EcdsaKeyPair::from_pkcs8(&ECDSA_P256_SHA256_FIXED_SIGNING, pkcs8.as_ref(), &rng)
+            .unwrap();
+        let (jwk, thumbprint) = jwk(key.public_key().as_ref());
+        assert_eq!(jwk["kty"], "EC");
+        assert_eq!(jwk["x"].as_str().unwrap().len(), 43);
+        assert_eq!(thumbprint.len(), 43);
+        assert_eq!(thumbprint, super::jwk(key.public_key().as_ref()).1);
+    }
+
+    #[test]
+    fn der_lengths_and_domains() {
+        assert_eq!(der(0x04, &[1, 2]), vec![0x04, 2, 1, 2]);
+        let long = der(0x04, &[0; 300]);
+        assert_eq!(&long[..4], &[0x04, 0x82, 0x01, 0x2c]);
+
+        assert!(is_valid_domain("blog.example.com"));
+        assert!(!is_valid_domain("*.example.com"));
+        assert!(!is_valid_domain("localhost"));
+        assert!(!is_valid_domain("-a.example.com"));
+    }
+}
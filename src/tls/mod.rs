@@ -0,0 +1,261 @@
+//! Native TLS termination
+//!
+//! With `server.tls` configured the server speaks HTTPS itself through
+//! rustls, for deployments without a reverse proxy. The certificate comes
+//! from files or from an ACME CA (see [`acme`]). An optional second listener
+//! answers plain HTTP with permanent redirects to HTTPS, and ACME HTTP-01
+//! challenges.
+
+pub mod acme;
+
+use std::io;
+use std::net::SocketAddr;
+use std::sync::Arc;
+use std::time::Duration;
+
+use axum::{
+    extract::{Path, State},
+    http::{header, HeaderMap, StatusCode, Uri},
+    response::{IntoResponse, Redirect, Response},
+    Router,
+};
+use rustls_pki_types::{pem::PemObject, CertificateDer, PrivateKeyDer};
+use tokio::net::{TcpListener, TcpStream};
+use tokio::sync::mpsc;
+use tokio_rustls::{rustls, server::TlsStream, TlsAcceptor};
+
+use crate::config::{ConfigError, TlsConfig};
+use acme::{AcmeChallenges, AcmeManager};
+
+/// Clients that have not finished the handshake by then are dropped
+const HANDSHAKE_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Completed handshakes waiting for the HTTP server to pick them up
+const ACCEPT_BACKLOG: usize = 128;
+
+/// TLS acceptor plus the certificate manager when ACME is used
+pub struct TlsSetup {
+    pub acceptor: TlsAcceptor,
+    pub acme: Option<Arc<AcmeManager>>,
+}
+
+/// Load the certificate, or prepare ACME
+pub fn setup(config: &TlsConfig) -> Result<TlsSetup, ConfigError> {
+    let builder = rustls::ServerConfig::builder().with_no_client_auth();
+    let (mut server_config, acme) = match (&config.acme, &config.cert_path, &config.key_path) {
+        (Some(acme), None, None) => {
+            let manager = AcmeManager::new(acme.clone())
+                .map_err(|e| ConfigError::ValidationError(e.to_string()))?;
+            let resolver = manager.resolver();
+            (
+                builder.with_cert_resolver(resolver),
+                Some(Arc::new(manager)),
+            )
+        }
+        (None, Some(cert_path), Some(key_path)) => {
+            let (certs, key) = load_pem(cert_path, key_path)?;
+            let server_config = builder
+                .with_single_cert(certs, key)
+                .map_err(|e| invalid("key_path", key_path, &e))?;
+            (server_config, None)
+        }
+        _ => {
+            return Err(ConfigError::ValidationError(
+                "server.tls needs either cert_path and key_path, or acme".to_string(),
+            ))
+        }
+    };
+    server_config.alpn_protocols = vec![b"http/1.1".to_vec()];
+    Ok(TlsSetup {
+        acceptor: TlsAcceptor::from(Arc::new(server_config)),
+        acme,
+    })
+}
+
+fn invalid(what: &str, path: &std::path::Path, e: &dyn std::fmt::Display) -> ConfigError {
+    ConfigError::ValidationError(format!(
+        "server.tls.{}: cannot load '{}': {}",
+        what,
+        path.display(),
+        e
+    ))
+}
+
+/// Read the certificate chain and key files
+fn load_pem(
+    cert_path: &std::path::Path,
+    key_path: &std::path::Path,
+) -> Result<(Vec<CertificateDer<'static>>, PrivateKeyDer<'static>), ConfigError> {
+    let certs = CertificateDer::pem_file_iter(cert_path)
+        .and_then(|iter| iter.collect::<Result<Vec<_>, _>>())
+        .map_err(|e| invalid("cert_path", cert_path, &e))?;
+    if certs.is_empty() {
+        return Err(invalid("cert_path", cert_path, &"no certificates found"));
+    }
+    let key =
+        PrivateKeyDer::from_pem_file(key_path).map_err(|e| invalid("key_path", key_path, &e))?;
+    Ok((certs, key))
+}
+
+/// A listener that hands out connections after their TLS handshake
+///
+/// Handshakes run in their own tasks so a slow client cannot hold up
+/// accepting others.
+pub struct TlsListener {
+    incoming: mpsc::Receiver<(TlsStream<TcpStream>, SocketAddr)>,
+    local_addr: SocketAddr,
+}
+
+impl TlsListener {
+    pub fn new(tcp: TcpListener, acceptor: TlsAcceptor) -> io::Result<Self> {
+        let local_addr = tcp.local_addr()?;
+        let (tx, incoming) = mpsc::channel(ACCEPT_BACKLOG);
+        tokio::spawn(async move {
+            loop {
+                let (stream, peer) = tokio::select! {
+                    // The server has shut down
+                    _ = tx.closed() => break,
+                    accepted = tcp.accept() => match accepted {
+                        Ok(accepted) => accepted,
+                        Err(e) => {
+                            tracing::warn!(error = %e, "accept error");
+                            tokio::time::sleep(Duration::from_secs(1)).await;
+                            continue;
+                        }
+                    },
+                };
+                let acceptor = acceptor.clone();
+                let tx = tx.clone();
+                tokio::spawn(async move {
+                    match tokio::time::timeout(HANDSHAKE_TIMEOUT, acceptor.accept(stream)).await {
+                        Ok(Ok(tls)) => {
+                            let _ = tx.send((tls, peer)).await;
+                        }
+                        Ok(Err(e)) => {
+                            tracing::debug!(peer = %peer, error = %e, "TLS handshake failed")
+                        }
+                        Err(_) => tracing::debug!(peer = %peer, "TLS handshake timed out"),
+                    }
+                });
+            }
+        });
+        Ok(Self {
+            incoming,
+            local_addr,
+        })
+    }
+}
+
+impl axum::serve::Listener for TlsListener {
+    type Io = TlsStream<TcpStream>;
+    type Addr = SocketAddr;
+
+    async fn accept(&mut self) -> (Self::Io, Self::Addr) {
+        match self.incoming.recv().await {
+            Some(connection) => connection,
+            // The accept task only stops once this listener is dropped
+            None => std::future::pending().await,
+        }
+    }
+
+    fn local_addr(&self) -> io::Result<Self::Addr> {
+        Ok(self.local_addr)
+    }
+}
+
+/// HTTPS URL for a plain HTTP request to `host`
+fn https_location(host: &str, path_and_query: &str, https_port: u16) -> Option<String> {
+    let host = host.trim();
+    // Drop the HTTP port, keeping IPv6 literals intact
+    let hostname = match host.rsplit_once(':') {
+        Some((name, port))
+            if !port.contains(']') && (!name.contains(':') || name.ends_with(']')) =>
+        {
+            name
+        }
+        _ => host,
+    };
+    if hostname.is_empty()
+        || !hostname
+            .bytes()
+            .all(|b| b.is_ascii_alphanumeric() || b"-.[]:".contains(&b))
+    {
+        return None;
+    }
+    Some(if https_port == 443 {
+        format!("https://{}{}", hostname, path_and_query)
+    } else {
+        format!("https://{}:{}{}", hostname, https_port, path_and_query)
+    })
+}
+
+async fn redirect_to_https(
+    State(state): State<RedirectState>,
+    headers: HeaderMap,
+    uri: Uri,
+) -> Response {
+    let path_and_query = uri.path_and_query().map(|pq| pq.as_str()).unwrap_or("/");
+    match headers
+        .get(header::HOST)
+        .and_then(|h| h.to_str().ok())
+        .and_then(|host| https_location(host, path_and_query, state.https_port))
+    {
+        Some(location) => Redirect::permanent(&location).into_response(),
+        None => (StatusCode::BAD_REQUEST, "Missing or invalid Host header").into_response(),
+    }
+}
+
+/// GET /.well-known/acme-challenge/{token} - HTTP-01 key authorization
+async fn acme_challenge(State(state): State<RedirectState>, Path(token): Path<String>) -> Response {
+    match state.challenges.get(&token) {
+        Some(key_authorization) => key_authorization.into_response(),
+        None => StatusCode::NOT_FOUND.into_response(),
+    }
+}
+
+#[derive(Clone)]
+struct RedirectState {
+    https_port: u16,
+    challenges: AcmeChallenges,
+}
+
+/// Router for the plain HTTP listener
+pub fn redirect_router(https_port: u16, challenges: AcmeChallenges) -> Router {
+    Router::new()
+        .route(
+            "/.well-known/acme-challenge/{token}",
+            axum::routing::get(acme_challenge),
+        )
+        .fallback(redirect_to_https)
+        .with_state(RedirectState {
+            https_port,
+            challenges,
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn redirects_keep_host_path_and_query() {
+        assert_eq!(
+            https_location("blog.example.com", "/posts?page=2", 443).as_deref(),
+            Some("https://blog.example.com/posts?page=2")
+        );
+        assert_eq!(
+            https_location("blog.example.com:80", "/", 8443).as_deref(),
+            Some("https://blog.example.com:8443/")
+        );
+        assert_eq!(
+            https_location("[::1]:8080", "/a", 443).as_deref(),
+            Some("https://[::1]/a")
+        );
+        assert_eq!(
+            https_location("[::1]", "/a", 443).as_deref(),
+            Some("https://[::1]/a")
+        );
+        assert_eq!(https_location("evil.com/x", "/", 443), None);
+        assert_eq!(https_location("", "/", 443), None);
+    }
+}
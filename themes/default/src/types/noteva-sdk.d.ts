@@ -20,6 +20,8 @@ interface NotevaCategory {
   name: string;
   description?: string;
   parentId?: number | null;
+  /** Who may read the category's articles; private ones are only listed for readers cleared for them */
+  access?: 'public' | 'members' | 'editors' | 'admins';
   articleCount: number;
   createdAt?: string;
   updatedAt?: string;
@@ -41,6 +43,7 @@ interface NotevaArticle {
   content: string;
   html: string;
   summary?: string | null;
+  /** Excerpt written by the author, or plain text generated from the start of the content */
   excerpt: string;
   thumbnail: string | null;
   coverImage: string | null;
@@ -48,6 +51,8 @@ interface NotevaArticle {
   categoryId?: number | null;
   status: string;
   author?: NotevaUser | null;
+  /** Everyone credited on the article, in order; the first `author` is `authorId` */
+  authors: NotevaArticleAuthor[];
   category?: NotevaCategory | null;
   tags: NotevaTag[];
   createdAt: string;
@@ -56,8 +61,13 @@ interface NotevaArticle {
   scheduledAt?: string | null;
   viewCount: number;
   likeCount: number;
+  favoriteCount: number;
   commentCount: number;
+  /** Words in the rendered content; each Chinese or Japanese character counts as one */
   wordCount: number;
+  /** Characters in the rendered text, whitespace excluded */
+  charCount: number;
+  /** Estimated minutes to read */
   readingTime: number;
   isPinned: boolean;
   pinOrder: number;
@@ -67,6 +77,92 @@ interface NotevaArticle {
   toc: Array<{ id: string; text: string; level: number }>;
   meta?: unknown;
   canonicalUrl?: string | null;
+  seo: NotevaArticleSeo;
+  /** Published language variants, this article included; empty when there are none */
+  translations: NotevaTranslation[];
+  /** The series this article is part of; null when it is in none */
+  series: NotevaArticleSeries | null;
+  /** Files attached to the article, in display order */
+  attachments: NotevaAttachment[];
+  /** Custom fields by key; values keep their type (number, boolean, JSON, ...) */
+  customFields: NotevaCustomFields;
+  /** Terms the article may be reused under; null when the response left it out */
+  license: NotevaLicense | null;
+}
+
+/** License of an article */
+interface NotevaLicense {
+  kind:
+    | "cc-by"
+    | "cc-by-sa"
+    | "cc-by-nd"
+    | "cc-by-nc"
+    | "cc-by-nc-sa"
+    | "cc-by-nc-nd"
+    | "cc0"
+    | "all-rights-reserved"
+    | "custom";
+  /** Display name such as "CC BY-SA 4.0", or the author's own for custom licenses */
+  name: string;
+  /** Link to the license terms; null for all rights reserved and unlinked custom licenses */
+  url: string | null;
+  /** Whether the article uses the site default rather than its own */
+  inherited: boolean;
+}
+
+/** Custom fields set by the author, by key; empty when there are none */
+type NotevaCustomFields = Record<string, unknown>;
+
+/** A user credited on an article */
+interface NotevaArticleAuthor {
+  userId: number;
+  username: string;
+  displayName: string;
+  avatar: string;
+  role: "author" | "editor" | "translator";
+}
+
+/** A file attached to an article */
+interface NotevaAttachment {
+  id: number;
+  /** Original file name */
+  filename: string;
+  url: string;
+  contentType: string;
+  /** Size in bytes */
+  size: number;
+  /** Who may download it; `email` needs an address posted to `url` */
+  access: "public" | "login" | "email";
+  downloadCount: number;
+}
+
+/** Position of an article in its series, counting published parts only */
+interface NotevaArticleSeries {
+  id: number;
+  slug: string;
+  title: string;
+  /** 1-based */
+  index: number;
+  total: number;
+  prev: NotevaArticleLink | null;
+  next: NotevaArticleLink | null;
+}
+
+/** A language variant, for `hreflang` links and language switchers */
+interface NotevaTranslation {
+  lang: string;
+  id: number;
+  slug: string;
+  title: string;
+  url: string;
+}
+
+/** SEO overrides set by the author; null fields fall back to title, excerpt and permalink */
+interface NotevaArticleSeo {
+  metaTitle: string | null;
+  metaDescription: string | null;
+  canonicalUrl: string | null;
+  noindex: boolean;
 }
 
 interface NotevaComment {
@@ -89,6 +185,166 @@ interface NotevaComment {
   replies: NotevaComment[];
 }
 
+interface NotevaReadingProgress {
+  articleId: number;
+  slug: string;
+  title: string;
+  /** Scroll position as a fraction of the article, 0 to 1 */
+  position: number;
+  updatedAt: string;
+}
+
+interface NotevaFavorite {
+  articleId: number;
+  slug: string;
+  title: string;
+  thumbnail: string | null;
+  publishedAt: string | null;
+  favoriteCount: number;
+  favoritedAt: string;
+}
+
+interface NotevaFavoriteList {
+  favorites: NotevaFavorite[];
+  total: number;
+  page: number;
+  pageSize: number;
+  totalPages: number;
+  hasMore: boolean;
+}
+
+interface NotevaFavoriteStatus {
+  favorited: boolean;
+  favoriteCount: number;
+}
+
+interface NotevaPublicUser {
+  id: number;
+  username: string;
+  displayName: string | null;
+  avatar: string | null;
+  createdAt: string | null;
+}
+
+interface NotevaAuthor extends NotevaPublicUser {
+  /** Published articles the author is credited on */
+  articleCount: number;
+}
+
+interface NotevaPoll {
+  id: number;
+  question: string;
+  /** Whether readers may pick more than one option */
+  multiple: boolean;
+  closesAt: string | null;
+  options: { id: number; label: string; votes: number }[];
+  totalVoters: number;
+}
+
+interface NotevaPollView {
+  poll: NotevaPoll;
+  /** Option ids the reader picked, empty until they vote */
+  voted: number[];
+  /** Server-rendered form, or results once voted or closed */
+  html: string;
+}
+
+interface NotevaEventOccurrence {
+  eventId: number;
+  title: string;
+  description: string;
+  location: string | null;
+  url: string | null;
+  startsAt: string;
+  /** For all-day events, midnight after the last day */
+  endsAt: string;
+  allDay: boolean;
+  recurring: boolean;
+}
+
+interface NotevaRelease {
+  /** Normalized semantic version, e.g. "1.4.0" or "2.0.0-beta.1" */
+  version: string;
+  prerelease: boolean;
+  articleId: number;
+  title: string;
+  slug: string;
+  url: string;
+  publishedAt: string;
+}
+
+interface NotevaReleaseGroup {
+  /** Minor series, e.g. "1.4" */
+  series: string;
+  latest: string;
+  releases: NotevaRelease[];
+}
+
+interface NotevaDocVersion {
+  id: number;
+  slug: string;
+  title: string;
+  isLatest: boolean;
+}
+
+interface NotevaDocNode {
+  id: number;
+  slug: string;
+  title: string;
+  url: string;
+  children: NotevaDocNode[];
+}
+
+interface NotevaDocLink {
+  slug: string;
+  title: string;
+  url: string;
+}
+
+interface NotevaDocView {
+  version: NotevaDocVersion;
+  /** Version switcher; `url` is the same page when that version has it */
+  versions: Array<NotevaDocLink & { isLatest: boolean; current: boolean }>;
+  sidebar: NotevaDocNode[];
+  page: {
+    id: number;
+    parentId: number | null;
+    slug: string;
+    title: string;
+    html: string;
+    updatedAt: string;
+  };
+  prev: NotevaDocLink | null;
+  next: NotevaDocLink | null;
+  /** `/docs/latest/{slug}` while the latest version has the page */
+  canonical: string;
+}
+
+interface NotevaFaqTopic {
+  id: number;
+  slug: string;
+  title: string;
+  description: string;
+  /** `answer` is rendered HTML */
+  items: Array<{ id: number; question: string; answer: string }>;
+}
+
+interface NotevaSeries {
+  id: number;
+  slug: string;
+  title: string;
+  description: string;
+  /** Published articles in reading order; empty in `series.list()` */
+  articles: NotevaArticleLink[];
+}
+
+interface NotevaCommentCounts {
+  /** approved + pending; spam is never counted */
+  total: number;
+  approved: number;
+  pending: number;
+}
+
 interface NotevaUser {
   id: number;
   username: string;
@@ -102,6 +358,16 @@ interface NotevaUser {
   updatedAt?: string;
 }
 
+interface NotevaAnnouncement {
+  id: string;
+  /** Plain text; escape before rendering */
+  message: string;
+  style: "info" | "success" | "warning" | "danger";
+  startsAt: string | null;
+  endsAt: string | null;
+  dismissible: boolean;
+}
+
 interface NotevaSiteInfo {
   version: string;
   name: string;
@@ -121,6 +387,8 @@ interface NotevaSiteInfo {
   showComments: boolean;
   friendLinksNavEnabled: boolean;
   aboutNavEnabled: boolean;
+  /** Banners currently inside their start/end window */
+  announcements: NotevaAnnouncement[];
   stats: {
     totalArticles: number;
     totalCategories: number;
@@ -210,6 +478,18 @@ interface NotevaArchiveEntry {
   count: number;
 }
 
+interface NotevaArchiveTree {
+  /** Published articles in all years */
+  total: number;
+  /** Newest first */
+  years: Array<{
+    year: number;
+    count: number;
+    /** Newest first; `month` is 1 to 12 */
+    months: Array<{ month: number; count: number }>;
+  }>;
+}
+
 interface NotevaRuntimeError extends Error {
   status: number;
   code: string | null;
@@ -326,6 +606,9 @@ interface NotevaSDK {
     getInfo(): Promise<NotevaSiteInfo>;
     getNav(): Promise<NotevaNavItem[]>;
     refresh(): Promise<NotevaSiteInfo>;
+    /** Active announcements the visitor has not dismissed */
+    announcements(): Promise<NotevaAnnouncement[]>;
+    dismissAnnouncement(id: string): void;
   };
 
   theme: {
@@ -345,6 +628,11 @@ interface NotevaSDK {
       tag?: string;
       keyword?: string;
       sort?: "date" | "views" | "comments" | "latest" | string;
+      /** BCP 47 tag; articles without a language are in the site language */
+      lang?: string;
+      /** User ID or username; matches co-authors too */
+      author?: string | number;
+      authorRole?: "author" | "editor" | "translator";
     }): Promise<NotevaArticleListResult>;
     popular(params?: {
       limit?: number;
@@ -355,12 +643,25 @@ interface NotevaSDK {
     }): Promise<NotevaArticle[]>;
     get(slug: string): Promise<NotevaArticle>;
     related(slug: string, params?: { limit?: number }): Promise<NotevaArticleLink[]>;
+    popular(params?: {
+      window?: "24h" | "7d" | "30d" | "all" | string;
+      limit?: number;
+    }): Promise<Array<NotevaArticleLink & { publishedAt: string | null; views: number }>>;
+    /** Hand-picked articles in curated order, for hero sections */
+    featured(params?: { limit?: number }): Promise<NotevaArticle[]>;
     archives(): Promise<NotevaArchiveEntry[]>;
+    archiveTree(): Promise<NotevaArchiveTree>;
+    /** Published articles of a month, oldest first */
+    archiveMonth(
+      year: number,
+      month: number,
+      params?: { page?: number; pageSize?: number }
+    ): Promise<NotevaArticleListResult>;
     incrementView(articleId: number): Promise<void>;
   };
 
   pages: {
-    list(): Promise<NotevaPage[]>;
+    list(params?: { lang?: string }): Promise<NotevaPage[]>;
     get(slug: string): Promise<NotevaPage>;
   };
 
@@ -383,7 +684,32 @@ interface NotevaSDK {
   };
 
   comments: {
-    list(articleId: number): Promise<NotevaComment[]>;
+    list(articleId: number, params?: { page?: number; perPage?: number }): Promise<NotevaComment[]>;
+    paginate(
+      articleId: number,
+      params?: { page?: number; perPage?: number }
+    ): Promise<
+      {
+        comments: NotevaComment[];
+        page: number;
+        perPage: number;
+        totalPages: number;
+        threads: number;
+      } & NotevaCommentCounts
+    >;
+    count(articleId: number): Promise<NotevaCommentCounts>;
+    lazy(
+      articleId: number,
+      params?: { limit?: number }
+    ): Promise<
+      {
+        comments: NotevaComment[];
+        hasMore: boolean;
+        token: string;
+        expiresAt: string;
+      } & NotevaCommentCounts
+    >;
+    loadRest(articleId: number, token: string): Promise<NotevaComment[]>;
     create(data: {
       articleId: number;
       content: string;
@@ -476,6 +802,74 @@ interface NotevaSDK {
     hasPermission(permission: string): boolean;
   };
 
+  readingProgress: {
+    list(params?: { limit?: number }): Promise<NotevaReadingProgress[]>;
+    get(articleId: number): Promise<NotevaReadingProgress | null>;
+    save(articleId: number, position: number): Promise<NotevaReadingProgress>;
+    remove(articleId: number): Promise<void>;
+  };
+
+  favorites: {
+    list(params?: { page?: number; pageSize?: number }): Promise<NotevaFavoriteList>;
+    check(articleId: number): Promise<NotevaFavoriteStatus>;
+    add(articleId: number): Promise<NotevaFavoriteStatus>;
+    remove(articleId: number): Promise<NotevaFavoriteStatus>;
+    settings(): Promise<{ public: boolean }>;
+    setPublic(isPublic: boolean): Promise<{ public: boolean }>;
+    /** Returns null when the user does not share their favorites */
+    ofUser(
+      username: string,
+      params?: { page?: number; pageSize?: number }
+    ): Promise<(NotevaFavoriteList & { user: { username: string; displayName: string | null; avatar: string | null } }) | null>;
+  };
+
+  authors: {
+    /** Authors of published articles, most articles first */
+    list(): Promise<NotevaAuthor[]>;
+    /** Returns null when the author does not exist or has no published articles */
+    get(
+      username: string,
+      params?: { page?: number; pageSize?: number; sort?: string }
+    ): Promise<(NotevaArticleListResult & { author: NotevaPublicUser }) | null>;
+  };
+
+  polls: {
+    get(pollId: number | string): Promise<NotevaPollView>;
+    /** Voting twice keeps the first choice and returns the current results */
+    vote(pollId: number | string, optionIds: Array<number | string>): Promise<NotevaPollView>;
+  };
+
+  calendar: {
+    /** Upcoming and ongoing occurrences, repeating events expanded */
+    upcoming(params?: { limit?: number }): Promise<NotevaEventOccurrence[]>;
+    /** iCalendar feed to subscribe to */
+    feedUrl(): string;
+  };
+
+  releases: {
+    /** Release notes grouped by minor series, newest version first */
+    list(): Promise<NotevaReleaseGroup[]>;
+    /** Machine-readable release list */
+    feedUrl(): string;
+  };
+
+  docs: {
+    versions(): Promise<NotevaDocVersion[]>;
+    /** `version` may be "latest"; without `slug` the version's first page */
+    get(version?: string, slug?: string): Promise<NotevaDocView>;
+  };
+
+  faq: {
+    /** `jsonLd` is a schema.org FAQPage object */
+    list(): Promise<{ topics: NotevaFaqTopic[]; jsonLd: object | null }>;
+    get(slug: string): Promise<{ topic: NotevaFaqTopic; jsonLd: object | null }>;
+  };
+
+  series: {
+    list(): Promise<NotevaSeries[]>;
+    get(slug: string): Promise<NotevaSeries | null>;
+  };
+
   urls: {
     article(article: { id: number | string; slug?: string }): string;
     category(category: string | { slug?: string }): string;
@@ -642,6 +1036,10 @@ interface NotevaPage {
   source?: string;
   createdAt: string;
   updatedAt: string;
+  /** Set by `pages.get` */
+  translations?: NotevaTranslation[];
+  /** Custom fields by key; filled by `pages.get` */
+  customFields: NotevaCustomFields;
 }
 
 declare global {
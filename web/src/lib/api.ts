@@ -255,7 +255,22 @@ export const categoriesApi = {
   update: (id: number, data: UpdateCategoryInput) =>
     api.put<Category>(`/admin/categories/${id}`, data),
 
-  delete: (id: number) => api.delete(`/admin/categories/${id}`),
+  delete: (
+    id: number,
+    options?: { articles?: "move"; move_to?: number } | { articles: "delete" }
+  ) => api.delete(`/admin/categories/${id}`, { params: options }),
+
+  move: (id: number, parentId: number | null, position?: number) =>
+    api.put<{ categories: CategoryTree[] }>(`/admin/categories/${id}/move`, {
+      parent_id: parentId,
+      position,
+    }),
+
+  reorder: (parentId: number | null, categoryIds: number[]) =>
+    api.put<{ categories: CategoryTree[] }>("/admin/categories/order", {
+      parent_id: parentId,
+      category_ids: categoryIds,
+    }),
 };
 
 // Tags API
@@ -268,6 +283,14 @@ export const tagsApi = {
   create: (name: string) =>
     api.post<Tag>("/admin/tags", { name }),
 
+  /** Rename a tag; its old slug keeps redirecting to it */
+  rename: (id: number, name: string, slug?: string) =>
+    api.put<Tag>(`/admin/tags/${id}`, { name, slug }),
+
+  /** Move the articles of `tagIds` to tag `id` and delete them */
+  merge: (id: number, tagIds: number[]) =>
+    api.post<Tag>(`/admin/tags/${id}/merge`, { tag_ids: tagIds }),
+
   delete: (id: number) => api.delete(`/admin/tags/${id}`),
 };
 
@@ -494,6 +517,8 @@ export interface Article {
   word_count?: number;
   reading_time?: number;
   scheduled_at?: string | null;
+  /** Someone else's draft listed for an author: content, excerpt and summary are withheld */
+  redacted?: boolean;
 }
 
 export interface CreateArticleInput {
@@ -528,9 +553,13 @@ export interface Category {
   description: string | null;
   parent_id: number | null;
   sort_order: number;
+  access: CategoryAccess;
   created_at: string;
 }
 
+/** Who may read a category's articles */
+export type CategoryAccess = "public" | "members" | "editors" | "admins";
+
 export interface FriendLink {
   id: number;
   name: string;
@@ -609,6 +638,7 @@ export interface CreateCategoryInput {
   slug?: string;
   description?: string;
   parent_id?: number | null;
+  access?: CategoryAccess;
 }
 
 export interface UpdateCategoryInput {
@@ -616,6 +646,7 @@ export interface UpdateCategoryInput {
   slug?: string;
   description?: string;
   parent_id?: number | null;
+  access?: CategoryAccess;
 }
 
 export interface Tag {
@@ -1,7 +1,31 @@
-import { defineConfig } from 'vite'
+import { defineConfig, type Plugin } from 'vite'
 import react from '@vitejs/plugin-react'
 import tailwindcss from '@tailwindcss/vite'
 import path from 'path'
+import { readFileSync } from 'fs'
+
+const packageVersion: string = JSON.parse(
+  readFileSync(path.resolve(__dirname, 'package.json'), 'utf-8'),
+).version
+
+// Record the version in dist/build-info.json; the server reports it at
+// /api/v1/site/frontend so a frontend from another release is detectable
+function buildInfo(): Plugin {
+  return {
+    name: 'noteva-build-info',
+    apply: 'build',
+    generateBundle() {
+      this.emitFile({
+        type: 'asset',
+        fileName: 'build-info.json',
+        source: JSON.stringify({
+          version: packageVersion,
+          builtAt: new Date().toISOString(),
+        }),
+      })
+    },
+  }
+}
 
 const enableReactCompiler = process.env.REACT_COMPILER === '1'
 
@@ -20,6 +44,7 @@ export default defineConfig({
       },
     }),
     tailwindcss(),
+    buildInfo(),
   ],
   base: '/manage/',
   resolve: {